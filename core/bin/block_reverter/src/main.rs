@@ -1,9 +1,14 @@
+use std::path::PathBuf;
+
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
 use tokio::io::{self, AsyncReadExt};
 use zksync_config::{ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig};
-use zksync_core::block_reverter::{
-    BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
+use zksync_core::{
+    block_reverter::{
+        BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
+    },
+    state_archive::StateArchiver,
 };
 use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
@@ -59,11 +64,37 @@ enum Command {
         /// Flag that allows to revert already executed blocks, it's ultra dangerous and required only for fixing external nodes
         #[arg(long)]
         allow_executed_block_reversion: bool,
+        /// Flag that specifies if the data to be rolled back should only be reported, without modifying any state.
+        #[arg(long)]
+        dry_run: bool,
+        /// Displays the dry run report as a JSON object, so that it is machine-readable.
+        #[arg(long, requires = "dry_run")]
+        json: bool,
     },
 
     /// Clears failed L1 transactions.
     #[command(name = "clear-failed-transactions")]
     ClearFailedL1Transactions,
+
+    /// Exports RocksDB with state keeper cache and RocksDB with tree to an archive directory,
+    /// aligning them at the same L1 batch. The node must be stopped.
+    #[command(name = "export-rocksdb-state")]
+    ExportRocksdbState {
+        /// Path to the archive directory. Must not exist.
+        #[arg(long)]
+        archive_path: PathBuf,
+    },
+    /// Imports RocksDB with state keeper cache and RocksDB with tree from an archive directory
+    /// created by `export-rocksdb-state`. The node must be stopped, and the target DB paths must not exist.
+    #[command(name = "import-rocksdb-state")]
+    ImportRocksdbState {
+        /// Path to the archive directory.
+        #[arg(long)]
+        archive_path: PathBuf,
+        /// Flag that specifies if the consistency of the imported tree should be checked. This may take a long time.
+        #[arg(long)]
+        verify_tree_consistency: bool,
+    },
 }
 
 #[tokio::main]
@@ -84,15 +115,9 @@ async fn main() -> anyhow::Result<()> {
     }
     let _guard = builder.build();
 
-    let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig::from_env()")?;
+    let command = Cli::parse().command;
     let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
-    let eth_client = ETHClientConfig::from_env().context("ETHClientConfig::from_env()")?;
-    let default_priority_fee_per_gas =
-        U256::from(eth_sender.gas_adjuster.default_priority_fee_per_gas);
-    let contracts = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
-    let config = BlockReverterEthConfig::new(eth_sender, contracts, eth_client.web3_url.clone());
-
     let connection_pool = ConnectionPool::builder(
         postgres_config.master_url()?,
         postgres_config.max_connections()?,
@@ -100,6 +125,49 @@ async fn main() -> anyhow::Result<()> {
     .build()
     .await
     .context("failed to build a connection pool")?;
+
+    // Exporting and importing state doesn't interact with L1, so Ethereum configs are not required.
+    match &command {
+        Command::ExportRocksdbState { archive_path } => {
+            let archiver = StateArchiver::new(
+                &db_config.state_keeper_db_path,
+                &db_config.merkle_tree.path,
+                connection_pool,
+            );
+            let manifest = archiver.export(archive_path).await?;
+            println!(
+                "Exported state at L1 batch #{} with root hash {:?}",
+                manifest.l1_batch_number, manifest.root_hash
+            );
+            return Ok(());
+        }
+        Command::ImportRocksdbState {
+            archive_path,
+            verify_tree_consistency,
+        } => {
+            let archiver = StateArchiver::new(
+                &db_config.state_keeper_db_path,
+                &db_config.merkle_tree.path,
+                connection_pool,
+            );
+            let manifest = archiver
+                .import(archive_path, *verify_tree_consistency)
+                .await?;
+            println!(
+                "Imported state at L1 batch #{} with root hash {:?}",
+                manifest.l1_batch_number, manifest.root_hash
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig::from_env()")?;
+    let eth_client = ETHClientConfig::from_env().context("ETHClientConfig::from_env()")?;
+    let default_priority_fee_per_gas =
+        U256::from(eth_sender.gas_adjuster.default_priority_fee_per_gas);
+    let contracts = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+    let config = BlockReverterEthConfig::new(eth_sender, contracts, eth_client.web3_url.clone());
     let mut block_reverter = BlockReverter::new(
         db_config.state_keeper_db_path,
         db_config.merkle_tree.path,
@@ -108,7 +176,7 @@ async fn main() -> anyhow::Result<()> {
         L1ExecutedBatchesRevert::Disallowed,
     );
 
-    match Cli::parse().command {
+    match command {
         Command::Display { json } => {
             let suggested_values = block_reverter.suggested_values().await;
             if json {
@@ -138,7 +206,38 @@ async fn main() -> anyhow::Result<()> {
             rollback_tree,
             rollback_sk_cache,
             allow_executed_block_reversion,
+            dry_run,
+            json,
         } => {
+            let mut flags = BlockReverterFlags::empty();
+            if rollback_postgres {
+                flags |= BlockReverterFlags::POSTGRES;
+            }
+            if rollback_tree {
+                flags |= BlockReverterFlags::TREE;
+            }
+            if rollback_sk_cache {
+                flags |= BlockReverterFlags::SK_CACHE;
+            }
+
+            if dry_run {
+                let report = block_reverter
+                    .dry_run(L1BatchNumber(l1_batch_number), flags)
+                    .await?;
+                if json {
+                    println!("{}", serde_json::to_string(&report).unwrap());
+                } else {
+                    println!("Data to be rolled back: {report:#?}");
+                }
+                if !json && report.reverts_executed_l1_batches && !allow_executed_block_reversion {
+                    println!(
+                        "Rollback reverts already executed L1 batches and would fail \
+                         without `--allow-executed-block-reversion`"
+                    );
+                }
+                return Ok(());
+            }
+
             if !rollback_tree && rollback_postgres {
                 println!("You want to rollback Postgres DB without rolling back tree.");
                 println!(
@@ -169,21 +268,14 @@ async fn main() -> anyhow::Result<()> {
                 );
             }
 
-            let mut flags = BlockReverterFlags::empty();
-            if rollback_postgres {
-                flags |= BlockReverterFlags::POSTGRES;
-            }
-            if rollback_tree {
-                flags |= BlockReverterFlags::TREE;
-            }
-            if rollback_sk_cache {
-                flags |= BlockReverterFlags::SK_CACHE;
-            }
             block_reverter
                 .rollback_db(L1BatchNumber(l1_batch_number), flags)
                 .await
         }
         Command::ClearFailedL1Transactions => block_reverter.clear_failed_l1_transactions().await,
+        Command::ExportRocksdbState { .. } | Command::ImportRocksdbState { .. } => {
+            unreachable!("handled above")
+        }
     }
     Ok(())
 }
@@ -0,0 +1,146 @@
+//! Registry of compiler binaries available to the contract verifier.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+
+use crate::error::ContractVerifierError;
+
+/// Compiler supported by the contract verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compiler {
+    ZkSolc,
+    Solc,
+    ZkVyper,
+    Vyper,
+}
+
+impl Compiler {
+    /// Returns the name of the compiler binary.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ZkSolc => "zksolc",
+            Self::Solc => "solc",
+            Self::ZkVyper => "zkvyper",
+            Self::Vyper => "vyper",
+        }
+    }
+}
+
+/// Registry of compiler binaries stored on the local filesystem. Each compiler version is stored as
+/// `{root}/etc/{compiler}-bin/{version}/{compiler}`; versions of zk compilers and the corresponding base compilers
+/// are independent, so any pair of them present in the registry can be used for verification.
+#[derive(Debug, Clone)]
+pub struct CompilerRegistry {
+    root: PathBuf,
+}
+
+impl CompilerRegistry {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Creates a registry rooted at `$ZKSYNC_HOME`, or at the current directory if the variable is not set.
+    pub fn from_env() -> Self {
+        Self::new(env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into()))
+    }
+
+    fn compiler_dir(&self, compiler: Compiler) -> PathBuf {
+        self.root
+            .join("etc")
+            .join(format!("{}-bin", compiler.name()))
+    }
+
+    /// Lists all versions of the specified compiler present in the registry.
+    pub fn versions(&self, compiler: Compiler) -> anyhow::Result<Vec<String>> {
+        let dir = self.compiler_dir(compiler);
+        let entries = fs::read_dir(&dir).with_context(|| {
+            format!("failed reading `{}` compiler dir {dir:?}", compiler.name())
+        })?;
+
+        let mut versions = vec![];
+        for entry in entries {
+            let entry = entry.with_context(|| format!("failed reading entry in {dir:?}"))?;
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if let Ok(version) = entry.file_name().into_string() {
+                    versions.push(version);
+                }
+            }
+        }
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// Resolves the path to the binary of the specified compiler version. Versions with and without
+    /// the `v` prefix (e.g., `v1.3.14` and `1.3.14`) are considered equivalent.
+    pub fn resolve(
+        &self,
+        compiler: Compiler,
+        version: &str,
+    ) -> Result<PathBuf, ContractVerifierError> {
+        let dir = self.compiler_dir(compiler);
+        let unprefixed_version = version.strip_prefix('v').unwrap_or(version);
+        let candidates = [
+            version.to_owned(),
+            unprefixed_version.to_owned(),
+            format!("v{unprefixed_version}"),
+        ];
+        candidates
+            .iter()
+            .map(|version| dir.join(version).join(compiler.name()))
+            .find(|path| Path::exists(path))
+            .ok_or_else(|| {
+                ContractVerifierError::UnknownCompilerVersion(
+                    compiler.name().to_owned(),
+                    version.to_owned(),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolving_compiler_versions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let registry = CompilerRegistry::new(temp_dir.path());
+        for (compiler, version) in [
+            (Compiler::ZkSolc, "v1.3.14"),
+            (Compiler::Solc, "0.8.20"),
+            (Compiler::Solc, "0.8.21"),
+        ] {
+            let version_dir = registry.compiler_dir(compiler).join(version);
+            fs::create_dir_all(&version_dir).unwrap();
+            fs::write(version_dir.join(compiler.name()), "").unwrap();
+        }
+
+        assert_eq!(registry.versions(Compiler::ZkSolc).unwrap(), ["v1.3.14"]);
+        assert_eq!(
+            registry.versions(Compiler::Solc).unwrap(),
+            ["0.8.20", "0.8.21"]
+        );
+        assert!(registry.versions(Compiler::Vyper).is_err());
+
+        let expected_path = temp_dir.path().join("etc/zksolc-bin/v1.3.14/zksolc");
+        for version in ["v1.3.14", "1.3.14"] {
+            let path = registry.resolve(Compiler::ZkSolc, version).unwrap();
+            assert_eq!(path, expected_path);
+        }
+        let path = registry.resolve(Compiler::Solc, "v0.8.21").unwrap();
+        assert_eq!(path, temp_dir.path().join("etc/solc-bin/0.8.21/solc"));
+
+        let err = registry.resolve(Compiler::Solc, "0.8.22").unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::UnknownCompilerVersion(..)),
+            "{err:?}"
+        );
+    }
+}
@@ -16,6 +16,8 @@ pub enum ContractVerifierError {
     UnknownCompilerVersion(String, String),
     #[error("Contract with {0} name is missing in sources")]
     MissingContract(String),
+    #[error("Contract with {0} name is defined in multiple source files; specify the file name")]
+    AmbiguousContractName(String),
     #[error("There is no {0} source file")]
     MissingSource(String),
     #[error("Contract with {0} name is an abstract and thus is not verifiable")]
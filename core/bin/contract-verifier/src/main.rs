@@ -10,98 +10,46 @@ use zksync_env_config::FromEnv;
 use zksync_queued_job_processor::JobProcessor;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::verifier::ContractVerifier;
+use crate::{
+    compiler_registry::{Compiler, CompilerRegistry},
+    verifier::ContractVerifier,
+};
 
+pub mod compiler_registry;
 pub mod error;
 pub mod verifier;
 pub mod zksolc_utils;
 pub mod zkvyper_utils;
 
-async fn update_compiler_versions(connection_pool: &ConnectionPool) {
+async fn update_compiler_versions(
+    connection_pool: &ConnectionPool,
+    registry: &CompilerRegistry,
+) -> anyhow::Result<()> {
     let mut storage = connection_pool.access_storage().await.unwrap();
     let mut transaction = storage.start_transaction().await.unwrap();
 
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-
-    let zksolc_path = format!("{}/etc/zksolc-bin/", zksync_home);
-    let zksolc_versions: Vec<String> = std::fs::read_dir(zksolc_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
+    let zksolc_versions = registry.versions(Compiler::ZkSolc)?;
     transaction
         .contract_verification_dal()
         .set_zksolc_versions(zksolc_versions)
         .await
         .unwrap();
 
-    let solc_path = format!("{}/etc/solc-bin/", zksync_home);
-    let solc_versions: Vec<String> = std::fs::read_dir(solc_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
+    let solc_versions = registry.versions(Compiler::Solc)?;
     transaction
         .contract_verification_dal()
         .set_solc_versions(solc_versions)
         .await
         .unwrap();
 
-    let zkvyper_path = format!("{}/etc/zkvyper-bin/", zksync_home);
-    let zkvyper_versions: Vec<String> = std::fs::read_dir(zkvyper_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
+    let zkvyper_versions = registry.versions(Compiler::ZkVyper)?;
     transaction
         .contract_verification_dal()
         .set_zkvyper_versions(zkvyper_versions)
         .await
         .unwrap();
 
-    let vyper_path = format!("{}/etc/vyper-bin/", zksync_home);
-    let vyper_versions: Vec<String> = std::fs::read_dir(vyper_path)
-        .unwrap()
-        .filter_map(|file| {
-            let file = file.unwrap();
-            let Ok(file_type) = file.file_type() else {
-                return None;
-            };
-            if file_type.is_dir() {
-                file.file_name().into_string().ok()
-            } else {
-                None
-            }
-        })
-        .collect();
-
+    let vyper_versions = registry.versions(Compiler::Vyper)?;
     transaction
         .contract_verification_dal()
         .set_vyper_versions(vyper_versions)
@@ -109,6 +57,7 @@ async fn update_compiler_versions(connection_pool: &ConnectionPool) {
         .unwrap();
 
     transaction.commit().await.unwrap();
+    Ok(())
 }
 
 use structopt::StructOpt;
@@ -174,9 +123,12 @@ async fn main() -> anyhow::Result<()> {
         .expect("Error setting Ctrl+C handler");
     }
 
-    update_compiler_versions(&pool).await;
+    let registry = CompilerRegistry::from_env();
+    update_compiler_versions(&pool, &registry)
+        .await
+        .context("failed updating compiler versions")?;
 
-    let contract_verifier = ContractVerifier::new(verifier_config, pool);
+    let contract_verifier = ContractVerifier::new(verifier_config, pool, registry);
     let tasks = vec![
         // todo PLA-335: Leftovers after the prover DB split.
         // The prover connection pool is not used by the contract verifier, but we need to pass it
@@ -1,6 +1,5 @@
 use std::{
     collections::HashMap,
-    env,
     path::Path,
     time::{Duration, Instant},
 };
@@ -24,6 +23,7 @@ use zksync_types::{
 };
 
 use crate::{
+    compiler_registry::{Compiler, CompilerRegistry},
     error::ContractVerifierError,
     zksolc_utils::{Optimizer, Settings, Source, StandardJson, ZkSolc, ZkSolcInput, ZkSolcOutput},
     zkvyper_utils::{ZkVyper, ZkVyperInput},
@@ -33,6 +33,39 @@ lazy_static! {
     static ref DEPLOYER_CONTRACT: Contract = zksync_contracts::deployer_contract();
 }
 
+/// Size of a bytecode word.
+const BYTECODE_WORD_LEN: usize = 32;
+
+/// Returns the length of the trailing part of the bytecode that differs between two compilations
+/// of the same contract, rounded up to whole words. For Vyper contracts, this is the metadata hash:
+/// Vyper metadata depends on the compilation environment (e.g., absolute source paths), so it may differ
+/// even if the contract code is identical. Returns `None` if compilations have different lengths.
+fn environment_dependent_suffix_len(first: &[u8], second: &[u8]) -> Option<usize> {
+    if first.len() != second.len() {
+        return None;
+    }
+    let first_diff_idx = first.iter().zip(second).position(|(x, y)| x != y);
+    Some(first_diff_idx.map_or(0, |idx| {
+        first.len() - idx / BYTECODE_WORD_LEN * BYTECODE_WORD_LEN
+    }))
+}
+
+/// Compares the compiled bytecode with the deployed one, ignoring the trailing `ignored_suffix_len` bytes
+/// that depend on the compilation environment.
+fn bytecodes_match(compiled: &[u8], deployed: &[u8], ignored_suffix_len: usize) -> bool {
+    compiled.len() == deployed.len()
+        && ignored_suffix_len <= compiled.len()
+        && compiled[..compiled.len() - ignored_suffix_len]
+            == deployed[..deployed.len() - ignored_suffix_len]
+}
+
+/// Compilation artifacts of a contract together with the length of the environment-dependent bytecode suffix.
+#[derive(Debug)]
+struct CompilationOutput {
+    artifacts: CompilationArtifacts,
+    ignored_suffix_len: usize,
+}
+
 #[derive(Debug)]
 enum ConstructorArgs {
     Check(Vec<u8>),
@@ -43,13 +76,19 @@ enum ConstructorArgs {
 pub struct ContractVerifier {
     config: ContractVerifierConfig,
     connection_pool: ConnectionPool,
+    registry: CompilerRegistry,
 }
 
 impl ContractVerifier {
-    pub fn new(config: ContractVerifierConfig, connection_pool: ConnectionPool) -> Self {
+    pub fn new(
+        config: ContractVerifierConfig,
+        connection_pool: ConnectionPool,
+        registry: CompilerRegistry,
+    ) -> Self {
         Self {
             config,
             connection_pool,
+            registry,
         }
     }
 
@@ -57,8 +96,12 @@ impl ContractVerifier {
         storage: &mut StorageProcessor<'_>,
         mut request: VerificationRequest,
         config: ContractVerifierConfig,
+        registry: &CompilerRegistry,
     ) -> Result<VerificationInfo, ContractVerifierError> {
-        let artifacts = Self::compile(request.clone(), config).await?;
+        let CompilationOutput {
+            artifacts,
+            ignored_suffix_len,
+        } = Self::compile(request.clone(), config, registry).await?;
 
         // Bytecode should be present because it is checked when accepting request.
         let (deployed_bytecode, creation_tx_calldata) = storage
@@ -74,7 +117,7 @@ impl ContractVerifier {
             request.req.contract_address,
         );
 
-        if artifacts.bytecode != deployed_bytecode {
+        if !bytecodes_match(&artifacts.bytecode, &deployed_bytecode, ignored_suffix_len) {
             return Err(ContractVerifierError::BytecodeMismatch);
         }
 
@@ -99,45 +142,35 @@ impl ContractVerifier {
     async fn compile_zksolc(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        registry: &CompilerRegistry,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
         let (file_name, contract_name) =
             if let Some((file_name, contract_name)) = request.req.contract_name.rsplit_once(':') {
-                (file_name.to_string(), contract_name.to_string())
+                (Some(file_name.to_string()), contract_name.to_string())
             } else {
-                (
-                    format!("{}.sol", request.req.contract_name),
-                    request.req.contract_name.clone(),
-                )
+                (None, request.req.contract_name.clone())
             };
-        let input = Self::build_zksolc_input(request.clone(), file_name.clone())?;
-
-        let zksync_home = env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-        let zksolc_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("zksolc-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zksolc");
-        if !zksolc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zksolc".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let solc_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("solc-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("solc");
-        if !solc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "solc".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
-
+        // For standard JSON input with multiple sources, the file can be looked up in the compilation output
+        // by the contract name.
+        let is_standard_json = matches!(
+            request.req.source_code_data,
+            SourceCodeData::StandardJsonInput(_)
+        );
+        let input_file_name = file_name
+            .clone()
+            .unwrap_or_else(|| format!("{contract_name}.sol"));
+        let input = Self::build_zksolc_input(request.clone(), input_file_name.clone())?;
+
+        let zksolc_path = registry.resolve(
+            Compiler::ZkSolc,
+            &request.req.compiler_versions.zk_compiler_version(),
+        )?;
+        let solc_path = registry.resolve(
+            Compiler::Solc,
+            &request.req.compiler_versions.compiler_version(),
+        )?;
         let zksolc = ZkSolc::new(zksolc_path, solc_path);
 
         let output = time::timeout(config.compilation_timeout(), zksolc.async_compile(input))
@@ -162,6 +195,14 @@ impl ContractVerifier {
                     }
                 }
 
+                let file_name = match file_name {
+                    Some(file_name) => file_name,
+                    None if is_standard_json => {
+                        Self::find_source_file(&output["contracts"], &contract_name)?
+                            .unwrap_or(input_file_name)
+                    }
+                    None => input_file_name,
+                };
                 let contracts = output["contracts"]
                     .get(file_name.as_str())
                     .cloned()
@@ -198,80 +239,138 @@ impl ContractVerifier {
         }
     }
 
+    /// Finds the source file defining a contract with the specified name in the `contracts` section
+    /// of the standard JSON compiler output. Returns `None` if there is no such file, and an error
+    /// if the contract name is ambiguous.
+    fn find_source_file(
+        contracts: &serde_json::Value,
+        contract_name: &str,
+    ) -> Result<Option<String>, ContractVerifierError> {
+        let Some(contracts) = contracts.as_object() else {
+            return Ok(None);
+        };
+        let mut matching_files = contracts
+            .iter()
+            .filter(|(_, file_contracts)| file_contracts.get(contract_name).is_some())
+            .map(|(file_name, _)| file_name);
+        let file_name = matching_files.next();
+        if matching_files.next().is_some() {
+            return Err(ContractVerifierError::AmbiguousContractName(
+                contract_name.to_owned(),
+            ));
+        }
+        Ok(file_name.cloned())
+    }
+
     async fn compile_zkvyper(
         request: VerificationRequest,
         config: ContractVerifierConfig,
-    ) -> Result<CompilationArtifacts, ContractVerifierError> {
+        registry: &CompilerRegistry,
+    ) -> Result<CompilationOutput, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
-        let contract_name =
-            if let Some((_file_name, contract_name)) = request.req.contract_name.rsplit_once(':') {
-                contract_name.to_string()
+        let (file_name, contract_name) =
+            if let Some((file_name, contract_name)) = request.req.contract_name.rsplit_once(':') {
+                (file_name.to_string(), contract_name.to_string())
             } else {
-                request.req.contract_name.clone()
+                (
+                    request.req.contract_name.clone(),
+                    request.req.contract_name.clone(),
+                )
             };
-        let input = Self::build_zkvyper_input(request.clone())?;
-
-        let zksync_home = env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-        let zkvyper_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("zkvyper-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zkvyper");
-        if !zkvyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zkvyper".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let vyper_path = Path::new(&zksync_home)
-            .join("etc")
-            .join("vyper-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("vyper");
-        if !vyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "vyper".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
-
+        let file_name = if file_name.ends_with(".vy") {
+            file_name
+        } else {
+            format!("{file_name}.vy")
+        };
+        let zkvyper_path = registry.resolve(
+            Compiler::ZkVyper,
+            &request.req.compiler_versions.zk_compiler_version(),
+        )?;
+        let vyper_path = registry.resolve(
+            Compiler::Vyper,
+            &request.req.compiler_versions.compiler_version(),
+        )?;
         let zkvyper = ZkVyper::new(zkvyper_path, vyper_path);
 
-        let output = time::timeout(config.compilation_timeout(), zkvyper.async_compile(input))
-            .await
-            .map_err(|_| ContractVerifierError::CompilationTimeout)??;
+        // The contract is compiled twice (each time in a separate temporary directory) to find out
+        // which part of the bytecode depends on the compilation environment.
+        let input = Self::build_zkvyper_input(request.clone())?;
+        let other_input = Self::build_zkvyper_input(request)?;
+        let (output, other_output) = time::timeout(config.compilation_timeout(), async {
+            tokio::try_join!(
+                zkvyper.async_compile(input),
+                zkvyper.async_compile(other_input)
+            )
+        })
+        .await
+        .map_err(|_| ContractVerifierError::CompilationTimeout)??;
+
+        let artifacts = Self::find_zkvyper_artifacts(output, &file_name, &contract_name)?;
+        let other_artifacts =
+            Self::find_zkvyper_artifacts(other_output, &file_name, &contract_name)?;
+        let ignored_suffix_len =
+            environment_dependent_suffix_len(&artifacts.bytecode, &other_artifacts.bytecode)
+                .ok_or_else(|| {
+                    tracing::warn!(
+                        "zkvyper produced bytecodes of different lengths for the same input"
+                    );
+                    ContractVerifierError::InternalError
+                })?;
+        Ok(CompilationOutput {
+            artifacts,
+            ignored_suffix_len,
+        })
+    }
 
-        let file_name = format!("{contract_name}.vy");
+    fn find_zkvyper_artifacts(
+        output: serde_json::Value,
+        file_name: &str,
+        contract_name: &str,
+    ) -> Result<CompilationArtifacts, ContractVerifierError> {
         let object = output
             .as_object()
             .cloned()
             .ok_or(ContractVerifierError::InternalError)?;
-        for (path, artifact) in object {
-            let path = Path::new(&path);
-            if path.file_name().unwrap().to_str().unwrap() == file_name {
-                let bytecode_str = artifact["bytecode"]
-                    .as_str()
-                    .ok_or(ContractVerifierError::InternalError)?;
-                let bytecode = hex::decode(bytecode_str).unwrap();
-                return Ok(CompilationArtifacts {
-                    abi: artifact["abi"].clone(),
-                    bytecode,
-                });
-            }
+        // Source paths in the output are absolute, so they are matched by the trailing path components.
+        let mut artifacts = object
+            .into_iter()
+            .filter(|(path, _)| Path::new(path).ends_with(file_name));
+        let (_, artifact) = artifacts
+            .next()
+            .ok_or_else(|| ContractVerifierError::MissingContract(contract_name.to_owned()))?;
+        if artifacts.next().is_some() {
+            return Err(ContractVerifierError::AmbiguousContractName(
+                contract_name.to_owned(),
+            ));
         }
 
-        Err(ContractVerifierError::MissingContract(contract_name))
+        let bytecode_str = artifact["bytecode"]
+            .as_str()
+            .ok_or(ContractVerifierError::InternalError)?;
+        let bytecode_str = bytecode_str.strip_prefix("0x").unwrap_or(bytecode_str);
+        let bytecode =
+            hex::decode(bytecode_str).map_err(|_| ContractVerifierError::InternalError)?;
+        Ok(CompilationArtifacts {
+            abi: artifact["abi"].clone(),
+            bytecode,
+        })
     }
 
     async fn compile(
         request: VerificationRequest,
         config: ContractVerifierConfig,
-    ) -> Result<CompilationArtifacts, ContractVerifierError> {
+        registry: &CompilerRegistry,
+    ) -> Result<CompilationOutput, ContractVerifierError> {
         match request.req.source_code_data.compiler_type() {
-            CompilerType::Solc => Self::compile_zksolc(request, config).await,
-            CompilerType::Vyper => Self::compile_zkvyper(request, config).await,
+            CompilerType::Solc => {
+                let artifacts = Self::compile_zksolc(request, config, registry).await?;
+                Ok(CompilationOutput {
+                    artifacts,
+                    ignored_suffix_len: 0,
+                })
+            }
+            CompilerType::Vyper => Self::compile_zkvyper(request, config, registry).await,
         }
     }
 
@@ -299,6 +398,8 @@ impl ContractVerifier {
 
                 let settings = Settings {
                     libraries: None,
+                    remappings: None,
+                    evm_version: None,
                     output_selection: Some(default_output_selection),
                     optimizer,
                     is_system: request.req.is_system,
@@ -411,6 +512,9 @@ impl ContractVerifier {
                     _ => ConstructorArgs::Ignore,
                 }
             }
+            DeployContractCalldata::FactoryDeploy(constructor_args) => {
+                ConstructorArgs::Check(constructor_args)
+            }
             DeployContractCalldata::Ignore => ConstructorArgs::Ignore,
         }
     }
@@ -497,6 +601,7 @@ impl JobProcessor for ContractVerifier {
         started_at: Instant,
     ) -> tokio::task::JoinHandle<anyhow::Result<()>> {
         let connection_pool = self.connection_pool.clone();
+        let registry = self.registry.clone();
         tokio::task::spawn(async move {
             tracing::info!("Started to process request with id = {}", job.id);
 
@@ -505,7 +610,7 @@ impl JobProcessor for ContractVerifier {
             let mut connection = connection_pool.access_storage().await.unwrap();
 
             let job_id = job.id;
-            let verification_result = Self::verify(&mut connection, job, config).await;
+            let verification_result = Self::verify(&mut connection, job, config, &registry).await;
             Self::process_result(&mut connection, job_id, verification_result).await;
 
             metrics::histogram!(
@@ -534,3 +639,52 @@ impl JobProcessor for ContractVerifier {
         Ok(1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finding_environment_dependent_suffix() {
+        let code = vec![1_u8; 96];
+        assert_eq!(environment_dependent_suffix_len(&code, &code), Some(0));
+
+        let mut code_with_other_metadata = code.clone();
+        code_with_other_metadata[95] = 2;
+        assert_eq!(
+            environment_dependent_suffix_len(&code, &code_with_other_metadata),
+            Some(32)
+        );
+        code_with_other_metadata[64] = 2;
+        assert_eq!(
+            environment_dependent_suffix_len(&code, &code_with_other_metadata),
+            Some(32)
+        );
+        code_with_other_metadata[63] = 2;
+        assert_eq!(
+            environment_dependent_suffix_len(&code, &code_with_other_metadata),
+            Some(64)
+        );
+
+        assert_eq!(environment_dependent_suffix_len(&code, &code[..64]), None);
+    }
+
+    #[test]
+    fn comparing_bytecodes() {
+        let code = vec![1_u8; 64];
+        let mut code_with_other_metadata = code.clone();
+        code_with_other_metadata[63] = 2;
+        let mut other_code = code.clone();
+        other_code[0] = 2;
+
+        assert!(bytecodes_match(&code, &code, 0));
+        assert!(!bytecodes_match(&code, &code_with_other_metadata, 0));
+        assert!(!bytecodes_match(&code, &other_code, 0));
+
+        assert!(bytecodes_match(&code, &code, 32));
+        assert!(bytecodes_match(&code, &code_with_other_metadata, 32));
+        assert!(!bytecodes_match(&code, &other_code, 32));
+        assert!(!bytecodes_match(&code, &code[..32], 32));
+        assert!(!bytecodes_match(&code, &code, 96));
+    }
+}
@@ -55,6 +55,12 @@ pub struct Settings {
     /// The linker library addresses.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub libraries: Option<HashMap<String, HashMap<String, String>>>,
+    /// The import remappings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remappings: Option<Vec<String>>,
+    /// The target EVM version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evm_version: Option<String>,
     /// The output selection filters.
     pub output_selection: Option<serde_json::Value>,
     /// The optimizer settings.
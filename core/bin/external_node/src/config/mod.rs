@@ -1,14 +1,24 @@
-use std::{env, time::Duration};
+use std::{env, path::PathBuf, time::Duration};
 
 use anyhow::Context;
 use serde::Deserialize;
 use url::Url;
 use zksync_basic_types::{Address, L1ChainId, L2ChainId, MiniblockNumber};
-use zksync_core::api_server::{
-    tx_sender::TxSenderConfig,
-    web3::{state::InternalApiConfig, Namespace},
+use zksync_config::{
+    configs::{api::SubscriptionBackpressurePolicy, DADispatcherConfig},
+    ObjectStoreConfig, SnapshotsCreatorConfig,
+};
+use zksync_core::{
+    api_server::{
+        tx_sender::TxSenderConfig,
+        web3::{state::InternalApiConfig, Namespace},
+    },
+    sync_layer::GossipConfig,
+};
+use zksync_types::{
+    api::{BridgeAddresses, CallTraceLimits},
+    commitment::L1BatchCommitmentMode,
 };
-use zksync_types::api::BridgeAddresses;
 use zksync_web3_decl::{
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
     namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
@@ -32,6 +42,8 @@ pub struct RemoteENConfig {
     pub l1_chain_id: L1ChainId,
 
     pub fair_l2_gas_price: u64,
+    /// Address of the main node operator (aka the fee account).
+    pub operator_address: Address,
 }
 
 impl RemoteENConfig {
@@ -83,6 +95,7 @@ impl RemoteENConfig {
             l2_chain_id,
             l1_chain_id,
             fair_l2_gas_price: block_header.l2_fair_gas_price,
+            operator_address: block_header.operator_address,
         })
     }
 }
@@ -99,6 +112,16 @@ pub struct OptionalENConfig {
     /// Max possible limit of subscriptions to be in the API state at once.
     #[serde(default = "OptionalENConfig::default_subscriptions_limit")]
     pub subscriptions_limit: usize,
+    /// Max number of subscriptions a single WebSocket connection can hold.
+    #[serde(default = "OptionalENConfig::default_max_subscriptions_per_connection")]
+    pub max_subscriptions_per_connection: u32,
+    /// Max number of notification batches buffered for each subscription type before slow subscribers
+    /// start lagging behind.
+    #[serde(default = "OptionalENConfig::default_max_buffered_notifications")]
+    pub max_buffered_notifications: usize,
+    /// What to do with a subscriber that lags behind by more than `max_buffered_notifications`.
+    #[serde(default)]
+    pub subscription_backpressure_policy: SubscriptionBackpressurePolicy,
     /// Max possible limit of entities to be requested via API at once.
     #[serde(default = "OptionalENConfig::default_req_entities_limit")]
     pub req_entities_limit: usize,
@@ -119,6 +142,13 @@ pub struct OptionalENConfig {
     /// Maximum response body size in MiBs. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
+    /// Maximum nesting depth of calls returned by `debug_trace*` methods. Default is 1024.
+    #[serde(default = "OptionalENConfig::default_trace_max_depth")]
+    pub trace_max_depth: usize,
+    /// Maximum number of nested calls returned by `debug_trace*` methods for a single transaction.
+    /// Default is 10000.
+    #[serde(default = "OptionalENConfig::default_trace_max_calls")]
+    pub trace_max_calls: usize,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
@@ -134,7 +164,7 @@ pub struct OptionalENConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
     pub vm_concurrency_limit: usize,
-    /// Smart contract bytecode cache size for the API server. Default value is 128 MiB.
+    /// Smart contract bytecode cache size shared by the API server and the state keeper. Default value is 128 MiB.
     #[serde(default = "OptionalENConfig::default_factory_deps_cache_size_mb")]
     factory_deps_cache_size_mb: usize,
     /// Initial writes cache size for the API server. Default value is 32 MiB.
@@ -168,6 +198,9 @@ pub struct OptionalENConfig {
         default = "OptionalENConfig::default_max_l1_batches_per_tree_iter"
     )]
     pub max_l1_batches_per_tree_iter: usize,
+    /// Maximum number of L1 batches for which commitments are computed concurrently by the Merkle tree component.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_commitment_generation_concurrency")]
+    pub merkle_tree_commitment_generation_concurrency: usize,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     #[serde(default = "OptionalENConfig::default_merkle_tree_multi_get_chunk_size")]
@@ -195,6 +228,66 @@ pub struct OptionalENConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
+    /// Mode in which L1 batches are committed by the main node. Must match the main node configuration;
+    /// otherwise, the consistency checker will fail.
+    #[serde(default)]
+    pub l1_batch_commitment_mode: L1BatchCommitmentMode,
+    /// Number of L1 confirmations a batch commit transaction must have before the consistency checker
+    /// checks it. If not set, commit transactions are checked as soon as they are included into an L1 block.
+    /// Commit transactions reverted by an L1 reorg are rechecked once they are included into L1 again.
+    pub l1_finality_depth: Option<u64>,
+    /// Whether the consistency checker should fetch pubdata blobs referenced by validium commitments from
+    /// the external DA layer and compare them with the locally computed pubdata. Requires the validium
+    /// commitment mode and the DA client config in [`ExternalNodeConfig`].
+    #[serde(default)]
+    pub check_da_blobs: bool,
+
+    // Pruning config
+    /// If set, the node runs in the pruning mode, retaining data only for the specified number of latest L1 batches.
+    /// For older L1 batches executed on L1, transactions, events, call traces, overwritten storage logs and
+    /// Merkle tree versions are removed; such historical data is no longer available via the API.
+    /// Block headers and the latest value of each storage slot are always retained.
+    pub pruning_retained_l1_batches: Option<u32>,
+
+    // Main node failover config
+    /// Comma-separated list of fallback main node URLs. If the main node specified by `main_node_url` is unreachable,
+    /// the external node fails over to these URLs in order.
+    /// Intentionally private: use getter method as it manages the missing port.
+    fallback_main_node_urls: Option<Vec<String>>,
+    /// Interval in milliseconds between checks whether the main node specified by `main_node_url` is reachable again
+    /// after the external node has failed over to a fallback main node.
+    #[serde(default = "OptionalENConfig::default_main_node_health_probe_interval_ms")]
+    main_node_health_probe_interval_ms: u64,
+
+    // Treeless mode config
+    /// URL of a remote Merkle tree API server. If set, the node runs in the treeless mode: it doesn't maintain
+    /// the Merkle tree locally, and fetches tree data for L1 batches and Merkle proofs from the remote server instead.
+    /// The consistency checker is disabled in this mode.
+    pub tree_api_remote_url: Option<String>,
+    /// Port on which the Merkle tree API server is listening. If set, the node serves its local Merkle tree
+    /// to other nodes (e.g., external nodes running in the treeless mode). Cannot be set in the treeless mode.
+    pub tree_api_port: Option<u16>,
+
+    // Consensus config
+    /// Path to the JSON file with the consensus executor config, which specifies gossip network params
+    /// and the validator set. If set together with `consensus_node_key_path`, L2 blocks are fetched via the gossip
+    /// network instead of JSON-RPC requests to the main node.
+    pub consensus_config_path: Option<PathBuf>,
+    /// Path to the file with the secret key of the node in the gossip network.
+    pub consensus_node_key_path: Option<PathBuf>,
+
+    // Snapshots config
+    /// Interval between attempts to create a snapshot of the node state. If set, the node periodically creates
+    /// snapshots (at most one per L1 batch) in the object store configured in [`SnapshotsENConfig`].
+    snapshots_creation_interval_sec: Option<u64>,
+    /// Port on which the HTTP server serving snapshot files to other nodes is listening.
+    pub snapshots_server_port: Option<u16>,
+    /// Token required from clients of the snapshots server (passed as `Authorization: Bearer <token>`). If not set,
+    /// the server is unauthenticated and is only bound to localhost (e.g., to be exposed via a reverse proxy).
+    pub snapshots_server_auth_token: Option<String>,
+    /// Base URL under which the snapshot files are publicly available, e.g. the URL of the snapshots server.
+    /// If set, created snapshots reference their files by URLs under this base rather than by object store paths.
+    pub snapshots_public_url: Option<String>,
 }
 
 impl OptionalENConfig {
@@ -206,6 +299,14 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_max_subscriptions_per_connection() -> u32 {
+        1_024
+    }
+
+    const fn default_max_buffered_notifications() -> usize {
+        1_024
+    }
+
     const fn default_req_entities_limit() -> usize {
         1_024
     }
@@ -242,6 +343,10 @@ impl OptionalENConfig {
         20
     }
 
+    const fn default_merkle_tree_commitment_generation_concurrency() -> usize {
+        4
+    }
+
     const fn default_vm_concurrency_limit() -> usize {
         // The default limit is large so that it does not create a bottleneck on its own.
         // VM execution can still be limited by Tokio runtime parallelism and/or the number
@@ -281,6 +386,14 @@ impl OptionalENConfig {
         1_024
     }
 
+    const fn default_trace_max_depth() -> usize {
+        1_024
+    }
+
+    const fn default_trace_max_calls() -> usize {
+        10_000
+    }
+
     const fn default_max_batch_request_size() -> usize {
         500 // The default limit is chosen to be reasonably permissive.
     }
@@ -297,6 +410,10 @@ impl OptionalENConfig {
         10
     }
 
+    const fn default_main_node_health_probe_interval_ms() -> u64 {
+        10_000
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
@@ -344,6 +461,43 @@ impl OptionalENConfig {
     pub fn max_response_body_size(&self) -> usize {
         self.max_response_body_size_mb * BYTES_IN_MEGABYTE
     }
+
+    pub fn fallback_main_node_urls(&self) -> anyhow::Result<Vec<String>> {
+        let urls = self.fallback_main_node_urls.as_deref().unwrap_or_default();
+        urls.iter()
+            .map(|url| {
+                RequiredENConfig::get_url(url)
+                    .with_context(|| format!("Could not parse fallback main node URL `{url}`"))
+            })
+            .collect()
+    }
+
+    pub fn main_node_health_probe_interval(&self) -> Duration {
+        Duration::from_millis(self.main_node_health_probe_interval_ms)
+    }
+
+    pub fn snapshots_creation_interval(&self) -> Option<Duration> {
+        self.snapshots_creation_interval_sec
+            .map(Duration::from_secs)
+    }
+
+    /// Checks whether snapshots are created or served by the node.
+    fn snapshots_enabled(&self) -> bool {
+        self.snapshots_creation_interval_sec.is_some() || self.snapshots_server_port.is_some()
+    }
+
+    /// Reads the gossip fetcher config if it is specified.
+    pub fn gossip_config(&self) -> anyhow::Result<Option<GossipConfig>> {
+        match (&self.consensus_config_path, &self.consensus_node_key_path) {
+            (Some(config_path), Some(node_key_path)) => {
+                GossipConfig::read(config_path, node_key_path).map(Some)
+            }
+            (None, None) => Ok(None),
+            _ => {
+                anyhow::bail!("Consensus config path and node key path must be specified together")
+            }
+        }
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -407,6 +561,29 @@ impl PostgresConfig {
     }
 }
 
+/// Configuration for creating and serving snapshots of the node state.
+/// Only loaded if snapshot creation or serving is enabled in [`OptionalENConfig`].
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SnapshotsENConfig {
+    /// Object store for snapshot files, configured with `EN_SNAPSHOTS_OBJECT_STORE_`-prefixed env variables.
+    pub object_store: ObjectStoreConfig,
+    /// Snapshot creator params, configured with `EN_SNAPSHOTS_CREATOR_`-prefixed env variables.
+    pub creator: SnapshotsCreatorConfig,
+}
+
+impl SnapshotsENConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            object_store: envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
+                .from_env()
+                .context("could not load snapshots object store config")?,
+            creator: envy::prefixed("EN_SNAPSHOTS_CREATOR_")
+                .from_env()
+                .context("could not load snapshots creator config")?,
+        })
+    }
+}
+
 /// External Node Config contains all the configuration required for the EN operation.
 /// It is split into three parts: required, optional and remote for easier navigation.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -415,6 +592,10 @@ pub struct ExternalNodeConfig {
     pub postgres: PostgresConfig,
     pub optional: OptionalENConfig,
     pub remote: RemoteENConfig,
+    pub snapshots: Option<SnapshotsENConfig>,
+    /// DA layer client params, configured with `EN_DA_`-prefixed env variables. Only loaded if DA blobs
+    /// are checked by the consistency checker; params specific to the DA dispatcher are ignored.
+    pub da_client: Option<DADispatcherConfig>,
 }
 
 impl ExternalNodeConfig {
@@ -473,13 +654,40 @@ impl ExternalNodeConfig {
             );
         }
 
+        if optional.tree_api_port.is_some() && optional.tree_api_remote_url.is_some() {
+            anyhow::bail!(
+                "Merkle tree API server cannot be run in the treeless mode; unset either `EN_TREE_API_PORT` \
+                 or `EN_TREE_API_REMOTE_URL`"
+            );
+        }
+
         let postgres = PostgresConfig::from_env()?;
+        let snapshots = if optional.snapshots_enabled() {
+            Some(SnapshotsENConfig::from_env()?)
+        } else {
+            None
+        };
+        let da_client = if optional.check_da_blobs {
+            anyhow::ensure!(
+                optional.l1_batch_commitment_mode == L1BatchCommitmentMode::Validium,
+                "DA blobs can only be checked in the validium commitment mode; unset `EN_CHECK_DA_BLOBS` \
+                 or set `EN_L1_BATCH_COMMITMENT_MODE` to `Validium`"
+            );
+            let config = envy::prefixed("EN_DA_")
+                .from_env()
+                .context("could not load DA client config")?;
+            Some(config)
+        } else {
+            None
+        };
 
         Ok(Self {
             remote,
             postgres,
             required,
             optional,
+            snapshots,
+            da_client,
         })
     }
 }
@@ -515,6 +723,10 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
+            trace_limits: CallTraceLimits {
+                max_depth: config.optional.trace_max_depth,
+                max_calls: config.optional.trace_max_calls,
+            },
         }
     }
 }
@@ -528,7 +740,13 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
                 .parse()
                 .unwrap(),
             gas_price_scale_factor: config.optional.gas_price_scale_factor,
+            // Batch capacity isn't checked on the external node since it doesn't have the state keeper config.
+            batch_boundary_gas_price_scale_factor: 1.0,
             max_nonce_ahead: config.optional.max_nonce_ahead,
+            // Transactions are replaced on the main node, which enforces its own fee bump.
+            tx_replacement_fee_bump_percent: 0,
+            // Deadlines are enforced on the main node as well; this only guards against out-of-range values.
+            max_tx_deadline: Duration::from_secs(24 * 60 * 60),
             fair_l2_gas_price: config.remote.fair_l2_gas_price,
             vm_execution_cache_misses_limit: config.optional.vm_execution_cache_misses_limit,
             // We set these values to the maximum since we don't know the actual values
@@ -7,6 +7,11 @@ fn parsing_optional_config_from_empty_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter([]).unwrap();
     assert_eq!(config.filters_limit, 10_000);
     assert_eq!(config.subscriptions_limit, 10_000);
+    assert_eq!(config.max_subscriptions_per_connection, 1_024);
+    assert_eq!(
+        config.subscription_backpressure_policy,
+        SubscriptionBackpressurePolicy::Disconnect
+    );
     assert_eq!(config.fee_history_limit, 1_024);
     assert_eq!(config.polling_interval(), Duration::from_millis(200));
     assert_eq!(config.max_tx_size, 1_000_000);
@@ -25,6 +30,19 @@ fn parsing_optional_config_from_empty_env() {
         128 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.trace_max_depth, 1_024);
+    assert_eq!(config.trace_max_calls, 10_000);
+    assert_eq!(config.pruning_retained_l1_batches, None);
+    assert!(config.fallback_main_node_urls().unwrap().is_empty());
+    assert_eq!(
+        config.main_node_health_probe_interval(),
+        Duration::from_secs(10)
+    );
+    assert_eq!(config.tree_api_remote_url, None);
+    assert_eq!(config.tree_api_port, None);
+    assert!(config.gossip_config().unwrap().is_none());
+    assert_eq!(config.snapshots_creation_interval(), None);
+    assert!(!config.snapshots_enabled());
 }
 
 #[test]
@@ -32,6 +50,8 @@ fn parsing_optional_config_from_env() {
     let env_vars = [
         ("EN_FILTERS_LIMIT", "5000"),
         ("EN_SUBSCRIPTIONS_LIMIT", "20000"),
+        ("EN_MAX_SUBSCRIPTIONS_PER_CONNECTION", "32"),
+        ("EN_SUBSCRIPTION_BACKPRESSURE_POLICY", "drop_oldest"),
         ("EN_FEE_HISTORY_LIMIT", "1000"),
         ("EN_PUBSUB_POLLING_INTERVAL", "500"),
         ("EN_MAX_TX_SIZE", "1048576"),
@@ -44,6 +64,18 @@ fn parsing_optional_config_from_env() {
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_TRACE_MAX_DEPTH", "64"),
+        ("EN_TRACE_MAX_CALLS", "5000"),
+        ("EN_PRUNING_RETAINED_L1_BATCHES", "1000"),
+        (
+            "EN_FALLBACK_MAIN_NODE_URLS",
+            "http://127.0.0.1:3050,http://127.0.0.1:3051",
+        ),
+        ("EN_MAIN_NODE_HEALTH_PROBE_INTERVAL_MS", "5000"),
+        ("EN_TREE_API_REMOTE_URL", "http://127.0.0.1:3072"),
+        ("EN_SNAPSHOTS_CREATION_INTERVAL_SEC", "3600"),
+        ("EN_SNAPSHOTS_SERVER_PORT", "3070"),
+        ("EN_SNAPSHOTS_SERVER_AUTH_TOKEN", "secret"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -52,6 +84,11 @@ fn parsing_optional_config_from_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
     assert_eq!(config.filters_limit, 5_000);
     assert_eq!(config.subscriptions_limit, 20_000);
+    assert_eq!(config.max_subscriptions_per_connection, 32);
+    assert_eq!(
+        config.subscription_backpressure_policy,
+        SubscriptionBackpressurePolicy::DropOldest
+    );
     assert_eq!(config.fee_history_limit, 1_000);
     assert_eq!(config.polling_interval(), Duration::from_millis(500));
     assert_eq!(config.max_tx_size, BYTES_IN_MEGABYTE);
@@ -70,4 +107,41 @@ fn parsing_optional_config_from_env() {
         32 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    assert_eq!(config.trace_max_depth, 64);
+    assert_eq!(config.trace_max_calls, 5_000);
+    assert_eq!(config.pruning_retained_l1_batches, Some(1_000));
+    assert_eq!(
+        config.fallback_main_node_urls().unwrap(),
+        ["http://127.0.0.1:3050/", "http://127.0.0.1:3051/"]
+    );
+    assert_eq!(
+        config.main_node_health_probe_interval(),
+        Duration::from_secs(5)
+    );
+    assert_eq!(
+        config.tree_api_remote_url.as_deref(),
+        Some("http://127.0.0.1:3072")
+    );
+    assert_eq!(
+        config.snapshots_creation_interval(),
+        Some(Duration::from_secs(3_600))
+    );
+    assert_eq!(config.snapshots_server_port, Some(3_070));
+    assert_eq!(
+        config.snapshots_server_auth_token.as_deref(),
+        Some("secret")
+    );
+    assert!(config.snapshots_enabled());
+}
+
+#[test]
+fn gossip_config_requires_both_paths() {
+    let env_vars = [("EN_CONSENSUS_CONFIG_PATH", "/etc/consensus/config.json")];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config.gossip_config().unwrap_err().to_string();
+    assert!(err.contains("must be specified together"), "{err}");
 }
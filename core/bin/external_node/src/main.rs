@@ -1,12 +1,20 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
-use futures::{future::FusedFuture, FutureExt as _};
+use futures::{
+    future::{self, FusedFuture},
+    FutureExt as _,
+};
 use metrics::EN_METRICS;
 use prometheus_exporter::PrometheusExporterConfig;
-use tokio::{sync::watch, task, time::sleep};
+use snapshots_creator::{SnapshotCreator, SnapshotsServer};
+use tokio::{
+    sync::watch,
+    task::{self, JoinError},
+};
 use zksync_basic_types::{Address, L2ChainId};
+use zksync_config::configs::database::PruningConfig;
 use zksync_core::{
     api_server::{
         execution_sandbox::VmConcurrencyLimiter,
@@ -16,6 +24,9 @@ use zksync_core::{
     },
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
     consistency_checker::ConsistencyChecker,
+    da_dispatcher::create_da_client,
+    db_pruner::DbPruner,
+    genesis::GenesisStateDump,
     l1_gas_price::MainNodeGasPriceFetcher,
     metadata_calculator::{
         MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
@@ -28,14 +39,16 @@ use zksync_core::{
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO, fetcher::FetcherCursor,
-        genesis::perform_genesis_if_needed, ActionQueue, MainNodeClient, SyncState,
+        genesis::perform_genesis_if_needed, run_gossip_fetcher, tree_data_fetcher::TreeDataFetcher,
+        ActionQueue, FailoverMainNodeClient, SyncState,
     },
 };
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_health_check::CheckHealth;
-use zksync_state::PostgresStorageCaches;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_state::{BytecodeCache, PostgresStorageCaches};
 use zksync_storage::RocksDB;
-use zksync_utils::wait_for_tasks::wait_for_tasks;
+use zksync_utils::panic_extractor::try_extract_panic_message;
 
 mod config;
 mod metrics;
@@ -43,7 +56,7 @@ mod metrics;
 const RELEASE_MANIFEST: &str =
     std::include_str!("../../../../.github/release-please/manifest.json");
 
-use crate::config::ExternalNodeConfig;
+use crate::config::{ExternalNodeConfig, OptionalENConfig};
 
 /// Creates the state keeper configured to work in the external node mode.
 #[allow(clippy::too_many_arguments)]
@@ -55,8 +68,10 @@ async fn build_state_keeper(
     sync_state: SyncState,
     l2_erc20_bridge_addr: Address,
     miniblock_sealer_handle: MiniblockSealerHandle,
+    bytecode_cache: BytecodeCache,
     stop_receiver: watch::Receiver<bool>,
     chain_id: L2ChainId,
+    main_node_client: FailoverMainNodeClient,
 ) -> ZkSyncStateKeeper {
     // These config values are used on the main node, and depending on these values certain transactions can
     // be *rejected* (that is, not included into the block). However, external node only mirrors what the main
@@ -67,19 +82,18 @@ async fn build_state_keeper(
     // We only need call traces on the external node if the `debug_` namespace is enabled.
     let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
 
-    let batch_executor_base: Box<dyn L1BatchExecutorBuilder> =
-        Box::new(MainBatchExecutorBuilder::new(
+    let batch_executor_base: Box<dyn L1BatchExecutorBuilder> = Box::new(
+        MainBatchExecutorBuilder::new(
             state_keeper_db_path,
             connection_pool.clone(),
             max_allowed_l2_tx_gas_limit,
             save_call_traces,
             false,
             config.optional.enum_index_migration_chunk_size,
-        ));
+        )
+        .with_bytecode_cache(bytecode_cache),
+    );
 
-    let main_node_url = config.required.main_node_url().unwrap();
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .expect("Failed creating JSON-RPC client for main node");
     let io = ExternalIO::new(
         miniblock_sealer_handle,
         connection_pool,
@@ -98,6 +112,7 @@ async fn build_state_keeper(
 async fn init_tasks(
     config: ExternalNodeConfig,
     connection_pool: ConnectionPool,
+    main_node_client: FailoverMainNodeClient,
 ) -> anyhow::Result<(
     Vec<task::JoinHandle<anyhow::Result<()>>>,
     watch::Sender<bool>,
@@ -112,14 +127,10 @@ async fn init_tasks(
 
     let version = semver::Version::parse(release_manifest_version)
         .expect("version in manifest is a correct semver format; qed");
-    let main_node_url = config
-        .required
-        .main_node_url()
-        .expect("Main node URL is incorrect");
     let (stop_sender, stop_receiver) = watch::channel(false);
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
     // Create components.
-    let gas_adjuster = Arc::new(MainNodeGasPriceFetcher::new(&main_node_url));
+    let gas_adjuster = Arc::new(MainNodeGasPriceFetcher::new(main_node_client.clone()));
 
     let sync_state = SyncState::new();
     let (action_queue_sender, action_queue) = ActionQueue::new();
@@ -131,8 +142,10 @@ async fn init_tasks(
     );
     task_handles.push(tokio::spawn(miniblock_sealer.run()));
     let pool = connection_pool.clone();
+    let mut version_stop_receiver = stop_receiver.clone();
+    // This task must terminate on the stop signal, since node components are restarted after a reorg rollback.
     task_handles.push(tokio::spawn(async move {
-        loop {
+        while !*version_stop_receiver.borrow_and_update() {
             let protocol_version = pool
                 .access_storage()
                 .await
@@ -144,10 +157,18 @@ async fn init_tasks(
 
             EN_METRICS.version[&(format!("{}", version), protocol_version)].set(1);
 
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            if tokio::time::timeout(Duration::from_secs(10), version_stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
         }
+        Ok(())
     }));
 
+    // Bytecode cache is shared by the state keeper and the API server.
+    let bytecode_cache = BytecodeCache::new(config.optional.factory_deps_cache_size() as u64);
     let state_keeper = build_state_keeper(
         action_queue,
         config.required.state_cache_path.clone(),
@@ -156,47 +177,54 @@ async fn init_tasks(
         sync_state.clone(),
         config.remote.l2_erc20_bridge_addr,
         miniblock_sealer_handle,
+        bytecode_cache.clone(),
         stop_receiver.clone(),
         config.remote.l2_chain_id,
+        main_node_client.clone(),
     )
     .await;
 
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .context("Failed creating JSON-RPC client for main node")?;
+    task_handles.push(tokio::spawn(main_node_client.clone().run_health_probe(
+        config.optional.main_node_health_probe_interval(),
+        stop_receiver.clone(),
+    )));
     let singleton_pool_builder = ConnectionPool::singleton(&config.postgres.database_url);
-    let fetcher_cursor = {
-        let pool = singleton_pool_builder
-            .build()
-            .await
-            .context("failed to build a connection pool for `MainNodeFetcher`")?;
-        let mut storage = pool.access_storage_tagged("sync_layer").await?;
-        FetcherCursor::new(&mut storage)
-            .await
-            .context("failed to load `MainNodeFetcher` cursor from Postgres")?
+    let gossip_config = config
+        .optional
+        .gossip_config()
+        .context("failed reading gossip fetcher config")?;
+    let fetcher_handle = if let Some(gossip_config) = gossip_config {
+        tracing::info!("Fetching L2 blocks via the gossip network with fallback to JSON-RPC");
+        tokio::spawn(run_gossip_fetcher(
+            connection_pool.clone(),
+            action_queue_sender,
+            gossip_config,
+            Box::new(main_node_client.clone()),
+            sync_state.clone(),
+            stop_receiver.clone(),
+            config.remote.operator_address,
+        ))
+    } else {
+        let fetcher_cursor = {
+            let pool = singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a connection pool for `MainNodeFetcher`")?;
+            let mut storage = pool.access_storage_tagged("sync_layer").await?;
+            FetcherCursor::new(&mut storage)
+                .await
+                .context("failed to load `MainNodeFetcher` cursor from Postgres")?
+        };
+        let fetcher = fetcher_cursor.into_fetcher(
+            Box::new(main_node_client.clone()),
+            action_queue_sender,
+            sync_state.clone(),
+            stop_receiver.clone(),
+        );
+        tokio::spawn(fetcher.run())
     };
-    let fetcher = fetcher_cursor.into_fetcher(
-        Box::new(main_node_client),
-        action_queue_sender,
-        sync_state.clone(),
-        stop_receiver.clone(),
-    );
-
-    let metadata_calculator = MetadataCalculator::new(&MetadataCalculatorConfig {
-        db_path: &config.required.merkle_tree_path,
-        mode: MetadataCalculatorModeConfig::Full {
-            store_factory: None,
-        },
-        delay_interval: config.optional.metadata_calculator_delay(),
-        max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
-        multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
-        block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
-        memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
-        stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
-    })
-    .await;
-    healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
 
-    let consistency_checker = ConsistencyChecker::new(
+    let mut consistency_checker = ConsistencyChecker::new(
         &config
             .required
             .eth_client_url()
@@ -206,10 +234,16 @@ async fn init_tasks(
             .build()
             .await
             .context("failed to build connection pool for ConsistencyChecker")?,
+        config.optional.l1_batch_commitment_mode,
+        config.optional.l1_finality_depth,
     );
+    if let Some(da_config) = &config.da_client {
+        let da_client = create_da_client(da_config).context("failed creating DA client")?;
+        consistency_checker = consistency_checker.with_da_client(da_client);
+    }
 
     let batch_status_updater = BatchStatusUpdater::new(
-        &main_node_url,
+        main_node_client.clone(),
         singleton_pool_builder
             .build()
             .await
@@ -218,25 +252,85 @@ async fn init_tasks(
     .await;
 
     // Run the components.
-    let tree_stop_receiver = stop_receiver.clone();
     let tree_pool = singleton_pool_builder
         .build()
         .await
         .context("failed to build a tree_pool")?;
-    let tree_handle = task::spawn(metadata_calculator.run(tree_pool, tree_stop_receiver));
+    let tree_handle = if let Some(tree_api_url) = &config.optional.tree_api_remote_url {
+        tracing::info!(
+            "Running in the treeless mode, fetching Merkle tree data from the remote tree API at {tree_api_url}"
+        );
+        let tree_data_fetcher = TreeDataFetcher::new(tree_api_url, tree_pool);
+        task::spawn(tree_data_fetcher.run(stop_receiver.clone()))
+    } else {
+        let metadata_calculator = MetadataCalculator::new(&MetadataCalculatorConfig {
+            db_path: &config.required.merkle_tree_path,
+            mode: MetadataCalculatorModeConfig::Full {
+                store_factory: None,
+            },
+            delay_interval: config.optional.metadata_calculator_delay(),
+            max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
+            commitment_generation_concurrency: config
+                .optional
+                .merkle_tree_commitment_generation_concurrency,
+            multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
+            block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
+            memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
+            stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+            pruning_past_versions_to_keep: config
+                .optional
+                .pruning_retained_l1_batches
+                .map(u64::from),
+            commitment_mode: config.optional.l1_batch_commitment_mode,
+        })
+        .await;
+        healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
+        if let Some(port) = config.optional.tree_api_port {
+            let address = ([0, 0, 0, 0], port).into();
+            let tree_reader = metadata_calculator.tree_reader();
+            let stop_receiver = stop_receiver.clone();
+            task_handles.push(tokio::spawn(async move {
+                tree_reader
+                    .await
+                    .run_api_server(address, stop_receiver)
+                    .await
+            }));
+        }
+        task::spawn(metadata_calculator.run(tree_pool, stop_receiver.clone()))
+    };
 
-    let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
+    // The consistency checker requires L1 batch commitments, which are only computed by the local Merkle tree.
+    if config.optional.tree_api_remote_url.is_none() {
+        task_handles.push(tokio::spawn(consistency_checker.run(stop_receiver.clone())));
+    }
+
+    if let Some(retained_l1_batches) = config.optional.pruning_retained_l1_batches {
+        tracing::info!(
+            "Running in the pruning mode, retaining {retained_l1_batches} latest L1 batches"
+        );
+        let pruning_config = PruningConfig {
+            // Retention is determined only by the number of L1 batches.
+            data_retention_sec: 0,
+            retained_l1_batches: Some(retained_l1_batches),
+            ..PruningConfig::default()
+        };
+        let pruner_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a connection pool for DbPruner")?;
+        let db_pruner = DbPruner::new(pruning_config, pruner_pool);
+        task_handles.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
+    }
 
     let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
     let sk_handle = task::spawn(state_keeper.run());
-    let fetcher_handle = tokio::spawn(fetcher.run());
     let gas_adjuster_handle = tokio::spawn(gas_adjuster.clone().run(stop_receiver.clone()));
 
     let (tx_sender, vm_barrier, cache_update_handle) = {
         let mut tx_sender_builder =
             TxSenderBuilder::new(config.clone().into(), connection_pool.clone())
                 .with_main_connection_pool(connection_pool.clone())
-                .with_tx_proxy(&main_node_url);
+                .with_tx_proxy(main_node_client);
 
         // Add rate limiter if enabled.
         if let Some(tps_limit) = config.optional.transactions_per_sec_limit {
@@ -245,8 +339,8 @@ async fn init_tasks(
 
         let max_concurrency = config.optional.vm_concurrency_limit;
         let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
-        let mut storage_caches = PostgresStorageCaches::new(
-            config.optional.factory_deps_cache_size() as u64,
+        let mut storage_caches = PostgresStorageCaches::with_bytecode_cache(
+            bytecode_cache,
             config.optional.initial_writes_cache_size() as u64,
         );
         let latest_values_cache_size = config.optional.latest_values_cache_size() as u64;
@@ -278,6 +372,7 @@ async fn init_tasks(
             .with_threads(config.required.threads_per_server)
             .with_tx_sender(tx_sender.clone(), vm_barrier.clone())
             .with_sync_state(sync_state.clone())
+            .with_tree_api(config.optional.tree_api_remote_url.clone())
             .enable_api_namespaces(config.optional.api_namespaces())
             .build(stop_receiver.clone())
             .await
@@ -288,12 +383,16 @@ async fn init_tasks(
             .ws(config.required.ws_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_subscriptions_limit(config.optional.subscriptions_limit)
+            .with_max_subscriptions_per_connection(config.optional.max_subscriptions_per_connection)
+            .with_max_buffered_notifications(config.optional.max_buffered_notifications)
+            .with_subscription_backpressure_policy(config.optional.subscription_backpressure_policy)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
             .with_polling_interval(config.optional.polling_interval())
             .with_threads(config.required.threads_per_server)
             .with_tx_sender(tx_sender, vm_barrier)
             .with_sync_state(sync_state)
+            .with_tree_api(config.optional.tree_api_remote_url.clone())
             .enable_api_namespaces(config.optional.api_namespaces())
             .build(stop_receiver.clone())
             .await
@@ -311,6 +410,53 @@ async fn init_tasks(
         let prometheus_task = PrometheusExporterConfig::pull(port).run(stop_receiver.clone());
         task_handles.push(tokio::spawn(prometheus_task));
     }
+    if let Some(snapshots_config) = &config.snapshots {
+        let blob_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::new(snapshots_config.object_store.clone())
+                .create_store()
+                .await
+                .into();
+
+        if let Some(interval) = config.optional.snapshots_creation_interval() {
+            let replica_pool = ConnectionPool::builder(
+                &config.postgres.database_url,
+                snapshots_config.creator.concurrent_queries_count,
+            )
+            .build()
+            .await
+            .context("failed to build a connection pool for snapshot creator")?;
+            let master_pool = singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a connection pool for snapshot creator")?;
+            let mut creator = SnapshotCreator::new(
+                blob_store.clone(),
+                replica_pool,
+                master_pool,
+                snapshots_config.creator.clone(),
+            );
+            if let Some(public_url) = &config.optional.snapshots_public_url {
+                creator = creator.with_public_url(public_url.clone());
+            }
+            let creator_task = creator.run_periodically(interval, stop_receiver.clone());
+            task_handles.push(tokio::spawn(creator_task));
+        }
+
+        if let Some(port) = config.optional.snapshots_server_port {
+            let mut server = SnapshotsServer::new(blob_store);
+            let bind_address = if let Some(token) = &config.optional.snapshots_server_auth_token {
+                server = server.with_auth_token(token.clone());
+                ([0, 0, 0, 0], port).into()
+            } else {
+                tracing::info!(
+                    "Snapshots server auth token is not set; binding the server to localhost"
+                );
+                ([127, 0, 0, 1], port).into()
+            };
+            let server_task = server.run(bind_address, stop_receiver.clone());
+            task_handles.push(tokio::spawn(server_task));
+        }
+    }
     task_handles.extend(http_server_handles.tasks);
     task_handles.extend(ws_server_handles.tasks);
     task_handles.extend(cache_update_handle);
@@ -321,21 +467,76 @@ async fn init_tasks(
         tree_handle,
         gas_adjuster_handle,
     ]);
-    task_handles.push(consistency_checker_handle);
 
     Ok((task_handles, stop_sender, healthcheck_handle, stop_receiver))
 }
 
+/// Returns flags for reverting the node state. The Merkle tree is not reverted in the treeless mode since it's not
+/// maintained locally.
+fn block_reverter_flags(config: &OptionalENConfig) -> BlockReverterFlags {
+    if config.tree_api_remote_url.is_some() {
+        BlockReverterFlags::POSTGRES | BlockReverterFlags::SK_CACHE
+    } else {
+        BlockReverterFlags::all()
+    }
+}
+
+/// Maximum time to wait for node components to stop after the stop signal is sent. Components that don't stop
+/// in time are aborted.
+const COMPONENTS_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn log_component_termination(result: Result<anyhow::Result<()>, JoinError>) {
+    let err = match result {
+        Ok(Ok(())) => {
+            "One of the actors finished its run, while it wasn't expected to do it".to_owned()
+        }
+        Ok(Err(err)) => {
+            format!("One of the tokio actors unexpectedly finished with error: {err:#}")
+        }
+        Err(err) => format!(
+            "One of the tokio actors panicked: {}",
+            try_extract_panic_message(err)
+        ),
+    };
+    tracing::error!("{err}");
+    vlog::capture_message(&err, vlog::AlertLevel::Warning);
+}
+
+/// Stops node components and waits until they terminate.
+/// `task_handles` must not contain already awaited handles.
 async fn shutdown_components(
     stop_sender: watch::Sender<bool>,
     healthcheck_handle: HealthCheckHandle,
+    task_handles: Vec<task::JoinHandle<anyhow::Result<()>>>,
 ) {
     stop_sender.send(true).ok();
+    // Components must be fully stopped before the node state is rolled back or components are restarted.
+    let deadline = tokio::time::Instant::now() + COMPONENTS_SHUTDOWN_TIMEOUT;
+    for mut handle in task_handles {
+        let result = match tokio::time::timeout_at(deadline, &mut handle).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(
+                    "Node component didn't stop in {COMPONENTS_SHUTDOWN_TIMEOUT:?} after the stop signal; \
+                     aborting it"
+                );
+                handle.abort();
+                handle.await
+            }
+        };
+        match result {
+            Ok(Ok(())) => { /* component has stopped gracefully */ }
+            Ok(Err(err)) => tracing::warn!("Node component failed while stopping: {err:#}"),
+            Err(err) if err.is_cancelled() => { /* component was aborted above */ }
+            Err(err) => tracing::error!(
+                "Node component panicked while stopping: {}",
+                try_extract_panic_message(err)
+            ),
+        }
+    }
     task::spawn_blocking(RocksDB::await_rocksdb_termination)
         .await
         .unwrap();
-    // Sleep for some time to let components gracefully stop.
-    sleep(Duration::from_secs(10)).await;
     healthcheck_handle.stop().await;
 }
 
@@ -344,6 +545,10 @@ async fn shutdown_components(
 struct Cli {
     #[arg(long)]
     revert_pending_l1_batch: bool,
+    /// Path to a JSON state dump applied on top of the standard genesis state. Must be the same dump
+    /// that the main node genesis was performed with, if any. Only used when genesis is performed.
+    #[arg(long, value_name = "PATH")]
+    genesis_state_dump: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -357,6 +562,8 @@ async fn main() -> anyhow::Result<()> {
     let sentry_url = vlog::sentry_url_from_env();
     #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
     let environment = vlog::environment_from_env();
+    #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
+    let otlp_url = vlog::otlp_url_from_env();
 
     let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
     if let Some(sentry_url) = &sentry_url {
@@ -365,6 +572,9 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(environment);
     }
+    if let Some(otlp_url) = &otlp_url {
+        builder = builder.with_opentelemetry(otlp_url.clone(), "zksync_external_node".to_owned());
+    }
     let _guard = builder.build();
 
     // Report whether sentry is running after the logging subsystem was initialized.
@@ -373,6 +583,9 @@ async fn main() -> anyhow::Result<()> {
     } else {
         tracing::info!("No sentry URL was provided");
     }
+    if let Some(otlp_url) = otlp_url {
+        tracing::info!("OpenTelemetry spans are exported to {otlp_url}");
+    }
 
     let config = ExternalNodeConfig::collect()
         .await
@@ -410,7 +623,10 @@ async fn main() -> anyhow::Result<()> {
 
         tracing::info!("Rolling back to l1 batch number {sealed_l1_batch_number}");
         reverter
-            .rollback_db(sealed_l1_batch_number, BlockReverterFlags::all())
+            .rollback_db(
+                sealed_l1_batch_number,
+                block_reverter_flags(&config.optional),
+            )
             .await;
         tracing::info!(
             "Rollback successfully completed, the node has to restart to continue working"
@@ -418,84 +634,118 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let sigint_receiver = setup_sigint_handler();
+    let mut sigint_receiver = setup_sigint_handler();
 
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
 
     tracing::info!("Started the external node");
     tracing::info!("Main node URL is: {}", main_node_url);
 
+    // The same client is shared by all node components, so that they fail over to fallback main nodes together.
+    let main_node_urls: Vec<_> = [main_node_url.clone()]
+        .into_iter()
+        .chain(config.optional.fallback_main_node_urls()?)
+        .collect();
+    let main_node_client = FailoverMainNodeClient::new(&main_node_urls)?;
+
     // Make sure that genesis is performed.
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .context("Failed creating JSON-RPC client for main node")?;
+    let state_dump = opt
+        .genesis_state_dump
+        .as_deref()
+        .map(GenesisStateDump::load)
+        .transpose()
+        .context("failed loading genesis state dump")?;
     perform_genesis_if_needed(
         &mut connection_pool.access_storage().await.unwrap(),
         config.remote.l2_chain_id,
         &main_node_client,
+        state_dump,
     )
     .await
     .context("Performing genesis failed")?;
 
-    let (task_handles, stop_sender, health_check_handle, stop_receiver) =
-        init_tasks(config.clone(), connection_pool.clone())
-            .await
-            .context("init_tasks")?;
-
-    let reorg_detector = ReorgDetector::new(&main_node_url, connection_pool.clone(), stop_receiver);
-    let mut reorg_detector_handle = tokio::spawn(reorg_detector.run()).fuse();
-    let mut reorg_detector_result = None;
-
-    let particular_crypto_alerts = None;
-    let graceful_shutdown = None::<futures::future::Ready<()>>;
-    let tasks_allowed_to_finish = false;
-
-    tokio::select! {
-        _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
-        _ = sigint_receiver => {
-            tracing::info!("Stop signal received, shutting down");
-        },
-        result = &mut reorg_detector_handle => {
-            tracing::info!("Reorg detector terminated, shutting down");
-            reorg_detector_result = Some(result);
-        }
-    };
+    // Node components are restarted in-process after each rollback caused by a reorg on the main node.
+    loop {
+        let (mut task_handles, stop_sender, health_check_handle, stop_receiver) = init_tasks(
+            config.clone(),
+            connection_pool.clone(),
+            main_node_client.clone(),
+        )
+        .await
+        .context("init_tasks")?;
 
-    // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
-    // Broadcast the stop signal to all actors and exit.
-    shutdown_components(stop_sender, health_check_handle).await;
+        let reorg_detector = ReorgDetector::new(
+            main_node_client.clone(),
+            connection_pool.clone(),
+            stop_receiver,
+        );
+        let mut reorg_detector_handle = tokio::spawn(reorg_detector.run()).fuse();
+        let mut reorg_detector_result = None;
+        let mut sigint_received = false;
+
+        let mut terminated_task_idx = None;
+
+        tokio::select! {
+            (result, idx, _) = future::select_all(task_handles.iter_mut()) => {
+                log_component_termination(result);
+                terminated_task_idx = Some(idx);
+            },
+            _ = &mut sigint_receiver => {
+                tracing::info!("Stop signal received, shutting down");
+                sigint_received = true;
+            },
+            result = &mut reorg_detector_handle => {
+                tracing::info!("Reorg detector terminated, shutting down");
+                reorg_detector_result = Some(result);
+            }
+        };
 
-    if !reorg_detector_handle.is_terminated() {
-        reorg_detector_result = Some(reorg_detector_handle.await);
-    }
-    let reorg_detector_last_correct_batch = reorg_detector_result.and_then(|result| match result {
-        Ok(Ok(last_correct_batch)) => last_correct_batch,
-        Ok(Err(err)) => {
-            tracing::error!("Reorg detector failed: {err}");
-            None
+        // Reaching this point means that either some actor exited unexpectedly, a reorg was detected,
+        // or we received a stop signal. Broadcast the stop signal to all actors and wait until they stop.
+        if let Some(idx) = terminated_task_idx {
+            // The handle of the terminated task is already awaited and must not be polled again.
+            drop(task_handles.swap_remove(idx));
         }
-        Err(err) => {
-            tracing::error!("Reorg detector panicked: {err}");
-            None
-        }
-    });
+        shutdown_components(stop_sender, health_check_handle, task_handles).await;
 
-    if let Some(last_correct_batch) = reorg_detector_last_correct_batch {
+        if !reorg_detector_handle.is_terminated() {
+            reorg_detector_result = Some(reorg_detector_handle.await);
+        }
+        let reorg_detector_last_correct_batch =
+            reorg_detector_result.and_then(|result| match result {
+                Ok(Ok(last_correct_batch)) => last_correct_batch,
+                Ok(Err(err)) => {
+                    tracing::error!("Reorg detector failed: {err}");
+                    None
+                }
+                Err(err) => {
+                    tracing::error!("Reorg detector panicked: {err}");
+                    None
+                }
+            });
+
+        let Some(last_correct_batch) = reorg_detector_last_correct_batch else {
+            return Ok(());
+        };
         tracing::info!("Performing rollback to L1 batch #{last_correct_batch}");
-
         let reverter = BlockReverter::new(
-            config.required.state_cache_path,
-            config.required.merkle_tree_path,
+            config.required.state_cache_path.clone(),
+            config.required.merkle_tree_path.clone(),
             None,
-            connection_pool,
+            connection_pool.clone(),
             L1ExecutedBatchesRevert::Allowed,
         );
         reverter
-            .rollback_db(last_correct_batch, BlockReverterFlags::all())
+            .rollback_db(last_correct_batch, block_reverter_flags(&config.optional))
             .await;
-        tracing::info!(
-            "Rollback successfully completed, the node has to restart to continue working"
-        );
-    }
+        EN_METRICS.reorg_rollbacks.inc();
 
-    Ok(())
+        if sigint_received {
+            tracing::info!(
+                "Rollback successfully completed; not restarting since stop signal was received"
+            );
+            return Ok(());
+        }
+        tracing::info!("Rollback successfully completed, restarting node components");
+    }
 }
@@ -0,0 +1,449 @@
+//! Snapshot creation logic shared by the snapshot creator utility and the external node.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::{watch, Semaphore};
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_types::{
+    snapshots::{
+        snapshot_file_checksum, IncrementalSnapshotManifest, SnapshotFactoryDependencies,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+    },
+    L1BatchNumber, MiniblockNumber, H256,
+};
+use zksync_utils::ceil_div;
+
+pub use crate::server::SnapshotsServer;
+use crate::{
+    chunking::get_chunk_hashed_keys_range,
+    metrics::{FactoryDepsStage, StorageChunkStage, METRICS},
+};
+
+mod chunking;
+mod metrics;
+mod server;
+#[cfg(test)]
+mod tests;
+
+/// Minimum number of storage log chunks to produce.
+const MIN_CHUNK_COUNT: u64 = 10;
+
+/// Stores `value` in the object store similarly to `ObjectStore::put()`. Returns the object key together with
+/// the Keccak-256 checksum of the stored bytes, which allows consumers of the snapshot to verify downloaded files.
+async fn put_with_checksum<V: StoredObject>(
+    blob_store: &dyn ObjectStore,
+    key: V::Key<'_>,
+    value: &V,
+) -> Result<(String, H256), ObjectStoreError> {
+    let key = V::encode_key(key);
+    let bytes = value.serialize().map_err(ObjectStoreError::Serialization)?;
+    let checksum = snapshot_file_checksum(&bytes);
+    blob_store.put_raw(V::BUCKET, &key, bytes).await?;
+    Ok((key, checksum))
+}
+
+/// Creates snapshots of the node state and persists them in an object store.
+#[derive(Debug)]
+pub struct SnapshotCreator {
+    blob_store: Arc<dyn ObjectStore>,
+    replica_pool: ConnectionPool,
+    master_pool: ConnectionPool,
+    config: SnapshotsCreatorConfig,
+    public_url: Option<String>,
+}
+
+impl SnapshotCreator {
+    pub fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        replica_pool: ConnectionPool,
+        master_pool: ConnectionPool,
+        config: SnapshotsCreatorConfig,
+    ) -> Self {
+        Self {
+            blob_store,
+            replica_pool,
+            master_pool,
+            config,
+            public_url: None,
+        }
+    }
+
+    /// Sets the base URL under which snapshot files are publicly available (e.g., via [`SnapshotsServer`]).
+    /// If set, snapshot metadata will reference files by their URLs rather than by object store paths.
+    pub fn with_public_url(mut self, public_url: String) -> Self {
+        self.public_url = Some(public_url);
+        self
+    }
+
+    fn output_filepath<V: StoredObject>(&self, filename: &str) -> String {
+        let prefix = match &self.public_url {
+            Some(url) => url.trim_end_matches('/').to_owned(),
+            None => self.blob_store.get_storage_prefix::<V>(),
+        };
+        format!("{prefix}/{filename}")
+    }
+
+    async fn process_storage_logs_single_chunk(
+        &self,
+        semaphore: &Semaphore,
+        first_miniblock_number: Option<MiniblockNumber>,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+        chunk_id: u64,
+        chunks_count: u64,
+    ) -> anyhow::Result<(String, H256)> {
+        let _permit = semaphore.acquire().await?;
+        let hashed_keys_range = get_chunk_hashed_keys_range(chunk_id, chunks_count);
+        let mut conn = self
+            .replica_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+
+        let latency =
+            METRICS.storage_logs_processing_duration[&StorageChunkStage::LoadFromPostgres].start();
+        let mut dal = conn.snapshots_creator_dal();
+        let logs = if let Some(first_miniblock_number) = first_miniblock_number {
+            dal.get_changed_storage_logs_chunk(
+                first_miniblock_number..=miniblock_number,
+                hashed_keys_range,
+            )
+            .await
+        } else {
+            dal.get_storage_logs_chunk(miniblock_number, hashed_keys_range)
+                .await
+        };
+        let logs = logs.context("Error fetching storage logs chunk")?;
+        drop(conn);
+        let latency = latency.observe();
+        tracing::info!(
+            "Loaded chunk {chunk_id} ({} logs) from Postgres in {latency:?}",
+            logs.len()
+        );
+
+        let latency =
+            METRICS.storage_logs_processing_duration[&StorageChunkStage::SaveToGcs].start();
+        let storage_logs_chunk = SnapshotStorageLogsChunk { storage_logs: logs };
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number,
+            chunk_id,
+        };
+        let (filename, checksum) = put_with_checksum(&*self.blob_store, key, &storage_logs_chunk)
+            .await
+            .context("Error storing storage logs chunk in blob store")?;
+        let output_filepath = self.output_filepath::<SnapshotStorageLogsChunk>(&filename);
+        let latency = latency.observe();
+
+        let tasks_left = METRICS.storage_logs_chunks_left_to_process.dec_by(1) - 1;
+        tracing::info!(
+            "Saved chunk {chunk_id} (overall progress {}/{chunks_count}) in {latency:?} to location: {output_filepath}",
+            chunks_count - tasks_left
+        );
+        Ok((output_filepath, checksum))
+    }
+
+    async fn process_factory_deps(
+        &self,
+        first_miniblock_number: Option<MiniblockNumber>,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<(String, H256)> {
+        let mut conn = self
+            .replica_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+
+        tracing::info!("Loading factory deps from Postgres...");
+        let latency =
+            METRICS.factory_deps_processing_duration[&FactoryDepsStage::LoadFromPostgres].start();
+        let mut dal = conn.snapshots_creator_dal();
+        let factory_deps = if let Some(first_miniblock_number) = first_miniblock_number {
+            dal.get_new_factory_deps(first_miniblock_number..=miniblock_number)
+                .await?
+        } else {
+            dal.get_all_factory_deps(miniblock_number).await?
+        };
+        drop(conn);
+        let latency = latency.observe();
+        tracing::info!("Loaded {} factory deps in {latency:?}", factory_deps.len());
+
+        tracing::info!("Saving factory deps to GCS...");
+        let latency =
+            METRICS.factory_deps_processing_duration[&FactoryDepsStage::SaveToGcs].start();
+        let factory_deps = SnapshotFactoryDependencies { factory_deps };
+        let (filename, checksum) =
+            put_with_checksum(&*self.blob_store, l1_batch_number, &factory_deps)
+                .await
+                .context("Error storing factory deps in blob store")?;
+        let output_filepath = self.output_filepath::<SnapshotFactoryDependencies>(&filename);
+        let latency = latency.observe();
+        tracing::info!(
+            "Saved {} factory deps in {latency:?} to location: {output_filepath}",
+            factory_deps.factory_deps.len()
+        );
+
+        Ok((output_filepath, checksum))
+    }
+
+    /// Decides whether the snapshot for `l1_batch_number` should be incremental. Returns the manifest
+    /// chaining the snapshot to the newest existing one, or `None` if a full snapshot should be created.
+    async fn incremental_manifest(
+        &self,
+        conn: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<IncrementalSnapshotManifest>> {
+        let max_incremental_snapshots = self.config.max_incremental_snapshots;
+        if max_incremental_snapshots == 0 {
+            return Ok(None);
+        }
+        let Some(previous_l1_batch_number) = conn
+            .snapshots_dal()
+            .get_newest_snapshot_l1_batch_number()
+            .await?
+        else {
+            return Ok(None);
+        };
+        if previous_l1_batch_number >= l1_batch_number {
+            tracing::warn!(
+                "Newest snapshot for L1 batch {previous_l1_batch_number} is not older than the snapshot \
+                 being created for L1 batch {l1_batch_number}; creating a full snapshot"
+            );
+            return Ok(None);
+        }
+
+        let previous_metadata = conn
+            .snapshots_dal()
+            .get_snapshot_metadata(previous_l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!("snapshot for L1 batch {previous_l1_batch_number} disappeared")
+            })?;
+        let base_l1_batch_number = previous_metadata
+            .incremental
+            .map_or(previous_l1_batch_number, |manifest| {
+                manifest.base_l1_batch_number
+            });
+        let chain_length = conn
+            .snapshots_dal()
+            .get_incremental_snapshots_count(base_l1_batch_number)
+            .await?;
+        if chain_length >= u64::from(max_incremental_snapshots) {
+            tracing::info!(
+                "Snapshot for L1 batch {base_l1_batch_number} already has {chain_length} incremental snapshots \
+                 based on it; creating a full snapshot"
+            );
+            return Ok(None);
+        }
+        Ok(Some(IncrementalSnapshotManifest {
+            base_l1_batch_number,
+            previous_l1_batch_number,
+        }))
+    }
+
+    /// Creates a snapshot for the L1 batch preceding the latest sealed one, unless it already exists.
+    ///
+    /// If incremental snapshots are enabled in the config, the snapshot will only contain changes since
+    /// the newest existing snapshot, until the chain of incremental snapshots reaches the configured length.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let latency = METRICS.snapshot_generation_duration.start();
+
+        let mut conn = self
+            .replica_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+
+        // We subtract 1 so that after restore, EN node has at least one L1 batch to fetch
+        let sealed_l1_batch_number = conn.blocks_dal().get_sealed_l1_batch_number().await?;
+        assert_ne!(
+            sealed_l1_batch_number,
+            L1BatchNumber(0),
+            "Cannot create snapshot when only the genesis L1 batch is present in Postgres"
+        );
+        let l1_batch_number = sealed_l1_batch_number - 1;
+
+        let mut master_conn = self
+            .master_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        if master_conn
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .is_some()
+        {
+            tracing::info!(
+                "Snapshot for L1 batch number {l1_batch_number} already exists, skipping"
+            );
+            return Ok(());
+        }
+        let incremental = self
+            .incremental_manifest(&mut master_conn, l1_batch_number)
+            .await?;
+        drop(master_conn);
+
+        let (_, last_miniblock_number_in_batch) = conn
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .context("Error fetching last miniblock number")?;
+        let first_miniblock_number = if let Some(manifest) = &incremental {
+            let (_, last_miniblock_in_previous_snapshot) = conn
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(manifest.previous_l1_batch_number)
+                .await?
+                .context("Error fetching last miniblock number of the previous snapshot")?;
+            Some(last_miniblock_in_previous_snapshot + 1)
+        } else {
+            None
+        };
+        let distinct_storage_logs_keys_count =
+            if let Some(first_miniblock_number) = first_miniblock_number {
+                conn.snapshots_creator_dal()
+                    .get_changed_storage_logs_keys_count(
+                        first_miniblock_number..=last_miniblock_number_in_batch,
+                    )
+                    .await?
+            } else {
+                conn.snapshots_creator_dal()
+                    .get_distinct_storage_logs_keys_count(l1_batch_number)
+                    .await?
+            };
+        drop(conn);
+
+        let chunk_size = self.config.storage_logs_chunk_size;
+        // We force the minimum number of chunks to avoid situations where only one chunk is created in tests.
+        let chunks_count =
+            ceil_div(distinct_storage_logs_keys_count, chunk_size).max(MIN_CHUNK_COUNT);
+
+        METRICS.storage_logs_chunks_count.set(chunks_count);
+
+        if let (Some(manifest), Some(first_miniblock_number)) =
+            (&incremental, first_miniblock_number)
+        {
+            tracing::info!(
+                "Creating incremental snapshot for storage logs changed in miniblocks \
+                 {first_miniblock_number}..={last_miniblock_number_in_batch}, L1 batch {l1_batch_number}; \
+                 previous snapshot: L1 batch {}, base snapshot: L1 batch {}",
+                manifest.previous_l1_batch_number,
+                manifest.base_l1_batch_number
+            );
+        } else {
+            tracing::info!(
+                "Creating snapshot for storage logs up to miniblock {last_miniblock_number_in_batch}, \
+                L1 batch {l1_batch_number}"
+            );
+        }
+        tracing::info!("Starting to generate {chunks_count} chunks of expected size {chunk_size}");
+
+        let (factory_deps_output_file, factory_deps_checksum) = self
+            .process_factory_deps(
+                first_miniblock_number,
+                last_miniblock_number_in_batch,
+                l1_batch_number,
+            )
+            .await?;
+
+        METRICS
+            .storage_logs_chunks_left_to_process
+            .set(chunks_count);
+
+        let semaphore = Semaphore::new(self.config.concurrent_queries_count as usize);
+        let tasks = (0..chunks_count).map(|chunk_id| {
+            self.process_storage_logs_single_chunk(
+                &semaphore,
+                first_miniblock_number,
+                last_miniblock_number_in_batch,
+                l1_batch_number,
+                chunk_id,
+                chunks_count,
+            )
+        });
+        let mut storage_logs_outputs = futures::future::try_join_all(tasks).await?;
+        // Sanity check: the number of files should equal the number of chunks.
+        assert_eq!(storage_logs_outputs.len(), chunks_count as usize);
+        storage_logs_outputs.sort();
+        let (storage_logs_output_files, storage_logs_checksums): (Vec<_>, Vec<_>) =
+            storage_logs_outputs.into_iter().unzip();
+
+        tracing::info!("Finished generating snapshot, storing progress in Postgres");
+        let mut master_conn = self
+            .master_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        master_conn
+            .snapshots_dal()
+            .add_snapshot(
+                l1_batch_number,
+                incremental,
+                &storage_logs_output_files,
+                &storage_logs_checksums,
+                &factory_deps_output_file,
+                factory_deps_checksum,
+            )
+            .await?;
+
+        METRICS.snapshot_l1_batch.set(l1_batch_number.0 as u64);
+
+        let elapsed = latency.observe();
+        tracing::info!("snapshot_generation_duration: {elapsed:?}");
+        tracing::info!("snapshot_l1_batch: {}", METRICS.snapshot_l1_batch.get());
+        tracing::info!(
+            "storage_logs_chunks_count: {}",
+            METRICS.storage_logs_chunks_count.get()
+        );
+        Ok(())
+    }
+
+    /// Checks whether a snapshot can be created, i.e., whether the L1 batch preceding the latest sealed one
+    /// is fully processed by the node. Unlike the main node, the external node may lag behind with computing
+    /// L1 batch metadata, which is required in the snapshot header.
+    async fn can_create_snapshot(&self) -> anyhow::Result<bool> {
+        let mut conn = self
+            .replica_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        let sealed_l1_batch_number = conn.blocks_dal().get_sealed_l1_batch_number().await?;
+        let last_l1_batch_with_metadata = conn
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?;
+        Ok(sealed_l1_batch_number > L1BatchNumber(0)
+            && last_l1_batch_with_metadata >= Some(sealed_l1_batch_number - 1))
+    }
+
+    async fn create_snapshot_if_ready(&self) -> anyhow::Result<()> {
+        if self.can_create_snapshot().await? {
+            self.run().await.context("failed creating snapshot")?;
+        } else {
+            tracing::debug!("Node is not ready to create a snapshot; skipping");
+        }
+        Ok(())
+    }
+
+    /// Periodically creates snapshots until a stop signal is received. Used by the external node.
+    pub async fn run_periodically(
+        self,
+        interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            // Errors are not propagated since they may be caused by transient issues (e.g., an unavailable
+            // object store), and snapshot creation is not critical for the node operation.
+            if let Err(err) = self.create_snapshot_if_ready().await {
+                METRICS.failed_snapshot_creations.inc();
+                tracing::warn!("Failed creating snapshot, will retry later: {err:#}");
+            }
+
+            if tokio::time::timeout(interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, snapshot creator is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! HTTP server allowing other nodes to download snapshot files.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing, Router,
+};
+use subtle::ConstantTimeEq;
+use tokio::sync::watch;
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_types::snapshots::SnapshotStorageLogsChunk;
+
+#[derive(Debug)]
+pub(crate) enum SnapshotsServerError {
+    Unauthorized,
+    InvalidFilename,
+    NotFound,
+    ObjectStore(ObjectStoreError),
+}
+
+impl IntoResponse for SnapshotsServerError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "invalid auth token").into_response(),
+            Self::InvalidFilename => (StatusCode::BAD_REQUEST, "invalid filename").into_response(),
+            Self::NotFound => (StatusCode::NOT_FOUND, "file not found").into_response(),
+            Self::ObjectStore(err) => {
+                tracing::warn!("Failed fetching snapshot file from object store: {err}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal error").into_response()
+            }
+        }
+    }
+}
+
+/// HTTP server serving snapshot files (storage log chunks and factory deps) from the object store
+/// by their filenames, i.e. `GET /{filename}`. Combined with [`SnapshotCreator::with_public_url()`],
+/// this allows nodes to bootstrap from snapshots created by any peer rather than only from the main node bucket.
+///
+/// If an auth token is set, requests must be authenticated with the `Authorization: Bearer <auth_token>` header.
+///
+/// [`SnapshotCreator::with_public_url()`]: crate::SnapshotCreator::with_public_url()
+#[derive(Debug)]
+pub struct SnapshotsServer {
+    blob_store: Arc<dyn ObjectStore>,
+    auth_token: Option<String>,
+}
+
+impl SnapshotsServer {
+    pub fn new(blob_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            blob_store,
+            auth_token: None,
+        }
+    }
+
+    /// Requires requests to the server to be authenticated with the specified token.
+    pub fn with_auth_token(mut self, auth_token: String) -> Self {
+        self.auth_token = Some(auth_token);
+        self
+    }
+
+    pub(crate) fn authorize(&self, headers: &HeaderMap) -> Result<(), SnapshotsServerError> {
+        let Some(auth_token) = &self.auth_token else {
+            return Ok(());
+        };
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(SnapshotsServerError::Unauthorized)?;
+        if !bool::from(token.as_bytes().ct_eq(auth_token.as_bytes())) {
+            return Err(SnapshotsServerError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn get_file(&self, filename: &str) -> Result<Vec<u8>, SnapshotsServerError> {
+        // Filenames are used as object keys as-is, which for the file-backed store means that
+        // they are joined with the base path.
+        let is_valid =
+            !filename.is_empty() && !filename.starts_with('.') && !filename.contains(['/', '\\']);
+        if !is_valid {
+            return Err(SnapshotsServerError::InvalidFilename);
+        }
+
+        // Both storage log chunks and factory deps are stored in the same bucket.
+        let bucket = SnapshotStorageLogsChunk::BUCKET;
+        match self.blob_store.get_raw(bucket, filename).await {
+            Ok(bytes) => Ok(bytes),
+            Err(ObjectStoreError::KeyNotFound(_)) => Err(SnapshotsServerError::NotFound),
+            Err(err) => Err(SnapshotsServerError::ObjectStore(err)),
+        }
+    }
+
+    async fn get_file_handler(
+        State(this): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(filename): Path<String>,
+    ) -> Result<impl IntoResponse, SnapshotsServerError> {
+        this.authorize(&headers)?;
+        let bytes = this.get_file(&filename).await?;
+        let headers = [(header::CONTENT_TYPE, "application/octet-stream")];
+        Ok((headers, bytes))
+    }
+
+    /// Runs the server until a stop signal is received.
+    pub async fn run(
+        self,
+        bind_address: SocketAddr,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        tracing::debug!("Starting snapshots server on {bind_address}");
+
+        let app = Router::new()
+            .route("/:filename", routing::get(Self::get_file_handler))
+            .with_state(Arc::new(self));
+        axum::Server::try_bind(&bind_address)
+            .with_context(|| format!("Failed binding snapshots server to {bind_address}"))?
+            .serve(app.into_make_service())
+            .with_graceful_shutdown(async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for snapshots server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, snapshots server is shutting down");
+            })
+            .await
+            .context("Snapshots server failed")?;
+
+        tracing::info!("Snapshots server shut down");
+        Ok(())
+    }
+}
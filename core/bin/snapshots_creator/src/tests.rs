@@ -2,16 +2,26 @@
 
 use std::collections::{HashMap, HashSet};
 
+use axum::http::{header, HeaderMap};
 use rand::{thread_rng, Rng};
 use zksync_dal::StorageProcessor;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
-    snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
+    snapshots::{SnapshotFactoryDependency, SnapshotStorageLog, SnapshotStorageLogsChunkMetadata},
     AccountTreeId, Address, ProtocolVersion, StorageKey, StorageLog, H256,
 };
 
 use super::*;
 
+fn test_config() -> SnapshotsCreatorConfig {
+    SnapshotsCreatorConfig {
+        storage_logs_chunk_size: 1_000_000,
+        concurrent_queries_count: 10,
+        max_incremental_snapshots: 0,
+    }
+}
+
 fn gen_storage_logs(rng: &mut impl Rng, count: usize) -> Vec<StorageLog> {
     (0..count)
         .map(|_| {
@@ -52,6 +62,7 @@ async fn create_miniblock(
         base_fee_per_gas: 0,
         l1_gas_price: 0,
         l2_fair_gas_price: 0,
+        gas_per_pubdata: None,
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
@@ -159,9 +170,15 @@ async fn persisting_snapshot_metadata() {
     let mut conn = pool.access_storage().await.unwrap();
     prepare_postgres(&mut rng, &mut conn, 10).await;
 
-    run(object_store, pool.clone(), pool.clone(), MIN_CHUNK_COUNT)
-        .await
-        .unwrap();
+    SnapshotCreator::new(
+        object_store.into(),
+        pool.clone(),
+        pool.clone(),
+        test_config(),
+    )
+    .run()
+    .await
+    .unwrap();
 
     // Check snapshot metadata in Postgres.
     let snapshots = conn.snapshots_dal().get_all_snapshots().await.unwrap();
@@ -186,6 +203,77 @@ async fn persisting_snapshot_metadata() {
         let path = path.strip_prefix("storage_logs_snapshots/").unwrap();
         assert!(path.ends_with(".proto.gzip"));
     }
+    let storage_logs_checksums = snapshot_metadata.storage_logs_checksums.unwrap();
+    assert_eq!(storage_logs_checksums.len(), MIN_CHUNK_COUNT as usize);
+    assert!(snapshot_metadata.factory_deps_checksum.is_some());
+}
+
+#[tokio::test]
+async fn serving_snapshot_files() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store: Arc<dyn ObjectStore> = object_store_factory.create_store().await.into();
+
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    SnapshotCreator::new(
+        object_store.clone(),
+        pool.clone(),
+        pool.clone(),
+        test_config(),
+    )
+    .with_public_url("http://127.0.0.1:3070/".to_owned())
+    .run()
+    .await
+    .unwrap();
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(8))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    let storage_logs_checksums = snapshot_metadata.storage_logs_checksums.unwrap();
+    let chunks = snapshot_metadata
+        .storage_logs_filepaths
+        .iter()
+        .zip(storage_logs_checksums)
+        .enumerate()
+        .map(
+            |(chunk_id, (filepath, checksum))| SnapshotStorageLogsChunkMetadata {
+                chunk_id: chunk_id as u64,
+                filepath: filepath.clone(),
+                checksum: Some(checksum),
+            },
+        );
+
+    // Emulate a node downloading snapshot files from the server and verifying them.
+    let server = SnapshotsServer::new(object_store);
+    for chunk in chunks {
+        let filename = chunk
+            .filepath
+            .strip_prefix("http://127.0.0.1:3070/")
+            .unwrap();
+        let mut bytes = server.get_file(filename).await.unwrap();
+        chunk.verify_file(&bytes).unwrap();
+        bytes[0] ^= 1;
+        let err = chunk.verify_file(&bytes).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"), "{err}");
+    }
+    let factory_deps_filename = snapshot_metadata
+        .factory_deps_filepath
+        .strip_prefix("http://127.0.0.1:3070/")
+        .unwrap();
+    let bytes = server.get_file(factory_deps_filename).await.unwrap();
+    assert_eq!(
+        snapshot_file_checksum(&bytes),
+        snapshot_metadata.factory_deps_checksum.unwrap()
+    );
+
+    for filename in ["missing.proto.gzip", "../secrets", ".."] {
+        assert!(server.get_file(filename).await.is_err());
+    }
 }
 
 #[tokio::test]
@@ -199,9 +287,15 @@ async fn persisting_snapshot_factory_deps() {
     let mut conn = pool.access_storage().await.unwrap();
     let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
 
-    run(object_store, pool.clone(), pool.clone(), MIN_CHUNK_COUNT)
-        .await
-        .unwrap();
+    SnapshotCreator::new(
+        object_store.into(),
+        pool.clone(),
+        pool.clone(),
+        test_config(),
+    )
+    .run()
+    .await
+    .unwrap();
     let snapshot_l1_batch_number = L1BatchNumber(8);
 
     let object_store = object_store_factory.create_store().await;
@@ -222,9 +316,15 @@ async fn persisting_snapshot_logs() {
     let mut conn = pool.access_storage().await.unwrap();
     let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
 
-    run(object_store, pool.clone(), pool.clone(), MIN_CHUNK_COUNT)
-        .await
-        .unwrap();
+    SnapshotCreator::new(
+        object_store.into(),
+        pool.clone(),
+        pool.clone(),
+        test_config(),
+    )
+    .run()
+    .await
+    .unwrap();
     let snapshot_l1_batch_number = L1BatchNumber(8);
 
     let object_store = object_store_factory.create_store().await;
@@ -239,3 +339,148 @@ async fn persisting_snapshot_logs() {
     }
     assert_eq!(actual_logs, expected_outputs.storage_logs);
 }
+
+/// Adds miniblocks / L1 batches with new storage logs and updates of `updated_keys`.
+async fn extend_postgres(
+    rng: &mut impl Rng,
+    conn: &mut StorageProcessor<'_>,
+    block_numbers: std::ops::Range<u32>,
+    updated_keys: &[StorageKey],
+) {
+    for block_number in block_numbers {
+        let new_logs = gen_storage_logs(rng, 20);
+        let updated_logs = updated_keys
+            .iter()
+            .map(|&key| StorageLog::new_write_log(key, H256(rng.gen())));
+        let logs = new_logs.iter().cloned().chain(updated_logs).collect();
+        create_miniblock(conn, MiniblockNumber(block_number), logs).await;
+
+        let factory_deps = gen_factory_deps(rng, 5);
+        conn.storage_dal()
+            .insert_factory_deps(MiniblockNumber(block_number), &factory_deps)
+            .await;
+        create_l1_batch(conn, L1BatchNumber(block_number), &new_logs).await;
+    }
+}
+
+async fn all_storage_logs(
+    conn: &mut StorageProcessor<'_>,
+    miniblock_number: MiniblockNumber,
+) -> HashSet<SnapshotStorageLog> {
+    let logs = conn
+        .snapshots_creator_dal()
+        .get_storage_logs_chunk(miniblock_number, H256::zero()..=H256::repeat_byte(0xff))
+        .await
+        .unwrap();
+    logs.into_iter().collect()
+}
+
+#[tokio::test]
+async fn creating_incremental_snapshots() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store: Arc<dyn ObjectStore> = object_store_factory.create_store().await.into();
+
+    let mut conn = pool.access_storage().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+    let config = SnapshotsCreatorConfig {
+        max_incremental_snapshots: 1,
+        ..test_config()
+    };
+    let creator = SnapshotCreator::new(object_store.clone(), pool.clone(), pool.clone(), config);
+    // There are no snapshots yet, so the first snapshot must be full.
+    creator.run().await.unwrap();
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(8))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(snapshot_metadata.incremental, None);
+
+    let updated_keys: Vec<_> = expected_outputs
+        .storage_logs
+        .iter()
+        .take(5)
+        .map(|log| log.key)
+        .collect();
+    extend_postgres(&mut rng, &mut conn, 10..13, &updated_keys).await;
+    creator.run().await.unwrap();
+
+    let snapshot_l1_batch_number = L1BatchNumber(11);
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(
+        snapshot_metadata.incremental,
+        Some(IncrementalSnapshotManifest {
+            base_l1_batch_number: L1BatchNumber(8),
+            previous_l1_batch_number: L1BatchNumber(8),
+        })
+    );
+
+    // The incremental snapshot must contain exactly the logs changed since the base snapshot.
+    let mut actual_logs = HashSet::new();
+    for chunk_id in 0..MIN_CHUNK_COUNT {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        actual_logs.extend(chunk.storage_logs.into_iter());
+    }
+    let base_logs = all_storage_logs(&mut conn, MiniblockNumber(8)).await;
+    let expected_logs: HashSet<_> = all_storage_logs(&mut conn, MiniblockNumber(11))
+        .await
+        .difference(&base_logs)
+        .cloned()
+        .collect();
+    assert_eq!(actual_logs, expected_logs);
+    for key in &updated_keys {
+        assert!(actual_logs.iter().any(|log| log.key == *key));
+    }
+
+    // Miniblocks 9..=11 each have new factory deps.
+    let SnapshotFactoryDependencies { factory_deps } =
+        object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(factory_deps.len(), 10 + 2 * 5);
+
+    // The chain has reached its maximum length, so the next snapshot must be full.
+    extend_postgres(&mut rng, &mut conn, 13..15, &updated_keys).await;
+    creator.run().await.unwrap();
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(13))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(snapshot_metadata.incremental, None);
+}
+
+#[tokio::test]
+async fn authorizing_snapshots_server_requests() {
+    let object_store: Arc<dyn ObjectStore> = ObjectStoreFactory::mock().create_store().await.into();
+    let server = SnapshotsServer::new(object_store.clone());
+    assert!(server.authorize(&HeaderMap::new()).is_ok());
+
+    let server = SnapshotsServer::new(object_store).with_auth_token("secret".to_owned());
+    assert!(server.authorize(&HeaderMap::new()).is_err());
+    for (auth_header, is_valid) in [
+        ("Bearer secret", true),
+        ("Bearer wrong", false),
+        ("secret", false),
+        ("Bearer secret2", false),
+    ] {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, auth_header.parse().unwrap());
+        assert_eq!(
+            server.authorize(&headers).is_ok(),
+            is_valid,
+            "{auth_header}"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::Context as _;
+use clap::{Parser, ValueEnum};
+use zksync_config::{configs::chain::NetworkConfig, PostgresConfig};
+use zksync_core::{
+    sync_layer::MainNodeClient,
+    vm_replay::{BatchReplayer, BatchSource, PostgresBatchSource, ReplayTracer, RpcBatchSource},
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::{AccountTreeId, Address, L1BatchNumber, StorageKey, VmVersion, H256};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Source {
+    /// Loads batch data from Postgres.
+    Postgres,
+    /// Loads batch data from the JSON-RPC API of the main node.
+    Rpc,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum VmVersionArg {
+    M5WithoutRefunds,
+    M5WithRefunds,
+    M6Initial,
+    M6BugWithCompressionFixed,
+    Vm1_3_2,
+    VirtualBlocks,
+    VirtualBlocksRefundsEnhancement,
+    BoojumIntegration,
+}
+
+impl From<VmVersionArg> for VmVersion {
+    fn from(arg: VmVersionArg) -> Self {
+        match arg {
+            VmVersionArg::M5WithoutRefunds => Self::M5WithoutRefunds,
+            VmVersionArg::M5WithRefunds => Self::M5WithRefunds,
+            VmVersionArg::M6Initial => Self::M6Initial,
+            VmVersionArg::M6BugWithCompressionFixed => Self::M6BugWithCompressionFixed,
+            VmVersionArg::Vm1_3_2 => Self::Vm1_3_2,
+            VmVersionArg::VirtualBlocks => Self::VmVirtualBlocks,
+            VmVersionArg::VirtualBlocksRefundsEnhancement => {
+                Self::VmVirtualBlocksRefundsEnhancement
+            }
+            VmVersionArg::BoojumIntegration => Self::VmBoojumIntegration,
+        }
+    }
+}
+
+/// Parses a storage watchpoint in the `<address>:<key>` format.
+fn parse_storage_watchpoint(watchpoint: &str) -> anyhow::Result<StorageKey> {
+    let (address, key) = watchpoint
+        .split_once(':')
+        .context("storage watchpoint must have `<address>:<key>` format")?;
+    let address: Address = address.parse().context("invalid watchpoint address")?;
+    let key: H256 = key.parse().context("invalid watchpoint key")?;
+    Ok(StorageKey::new(AccountTreeId::new(address), key))
+}
+
+/// Re-executes a sealed L1 batch and compares the produced events and storage writes with the stored ones.
+///
+/// Storage is always read from Postgres (`DATABASE_URL`), so the database must contain the state
+/// at the end of the previous L1 batch.
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "L1 batch replay utility", long_about = None)]
+struct Cli {
+    /// Number of the L1 batch to replay.
+    #[arg(long)]
+    l1_batch_number: u32,
+    /// Source of the batch transactions, environment and stored execution results.
+    #[arg(long, value_enum, default_value_t = Source::Postgres)]
+    source: Source,
+    /// URL of the main node JSON-RPC API. Required for the `rpc` source.
+    #[arg(long, required_if_eq("source", "rpc"))]
+    rpc_url: Option<String>,
+    /// VM version used for replay. By default, the version corresponding to the batch protocol version is used.
+    #[arg(long, value_enum)]
+    vm_version: Option<VmVersionArg>,
+    /// Collects call traces of transactions and includes them into the report.
+    #[arg(long)]
+    call_tracer: bool,
+    /// Halts transactions accessing more than the specified number of storage slots.
+    #[arg(long)]
+    storage_invocations_limit: Option<usize>,
+    /// Records reads and writes of the storage slot in the `<address>:<key>` format (the key is a 32-byte hex value)
+    /// by transactions and includes them into the report. Can be specified multiple times.
+    #[arg(long, value_parser = parse_storage_watchpoint)]
+    storage_watchpoint: Vec<StorageKey>,
+    /// Displays the report as a JSON object, so that it is machine-readable.
+    #[arg(long)]
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
+    let log_format = vlog::log_format_from_env();
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let opt = Cli::parse();
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let l2_chain_id = network_config.zksync_network_id;
+    let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+
+    let source: Box<dyn BatchSource> = match opt.source {
+        Source::Postgres => Box::new(PostgresBatchSource::new(pool.clone(), l2_chain_id)),
+        Source::Rpc => {
+            let url = opt.rpc_url.as_deref().context("RPC URL is not specified")?;
+            let client = <dyn MainNodeClient>::json_rpc(url).context("invalid RPC URL")?;
+            Box::new(RpcBatchSource::new(client, l2_chain_id))
+        }
+    };
+
+    let mut replayer = BatchReplayer::new(pool);
+    if let Some(vm_version) = opt.vm_version {
+        replayer = replayer.with_vm_version(vm_version.into());
+    }
+    if opt.call_tracer {
+        replayer = replayer.with_tracer(ReplayTracer::Call);
+    }
+    if let Some(limit) = opt.storage_invocations_limit {
+        replayer = replayer.with_tracer(ReplayTracer::StorageInvocations { limit });
+    }
+    if !opt.storage_watchpoint.is_empty() {
+        let keys = opt.storage_watchpoint.into_iter().collect();
+        replayer = replayer.with_tracer(ReplayTracer::StorageWatchpoints { keys });
+    }
+
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    let report = replayer.replay(source.as_ref(), l1_batch_number).await?;
+    if opt.json {
+        println!("{}", serde_json::to_string(&report).unwrap());
+    } else {
+        println!("Replayed transactions: {:#?}", report.transactions);
+        println!("Mismatched events: {:#?}", report.event_mismatches);
+        println!(
+            "Mismatched storage writes: {:#?}",
+            report.storage_write_mismatches
+        );
+    }
+    anyhow::ensure!(
+        report.is_consistent(),
+        "replayed L1 batch #{l1_batch_number} diverges from the stored data"
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::{str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -11,14 +11,16 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
-        ProofDataHandlerConfig, ProverGroupConfig, WitnessGeneratorConfig,
+        CallTracesBackfillerConfig, DADispatcherConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, PrometheusConfig, ProofDataHandlerConfig, ProverGroupConfig,
+        UpgradeDryRunConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig, ProverConfigs,
 };
 use zksync_core::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
+    eth_sender::simulate_fee_escalation, genesis::GenesisStateDump, genesis_init,
+    initialize_components, is_genesis_needed, setup_sigint_handler,
     temp_config_store::TempConfigStore, Component, Components,
 };
 use zksync_env_config::FromEnv;
@@ -35,9 +37,17 @@ struct Cli {
     /// Generate genesis block for the first contract deployment using temporary DB.
     #[arg(long)]
     genesis: bool,
+    /// Path to a JSON state dump (accounts with their balances, nonces, bytecodes and storage slots)
+    /// applied on top of the standard genesis state. Only used when genesis is performed.
+    #[arg(long, value_name = "PATH")]
+    genesis_state_dump: Option<PathBuf>,
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
+    /// Dry-run the configured L1 fee escalation policies against base fees of the specified number
+    /// of latest L1 blocks, log the results and exit.
+    #[arg(long, value_name = "BLOCKS")]
+    simulate_fee_escalation: Option<usize>,
     /// Comma-separated list of components to launch.
     #[arg(
         long,
@@ -72,6 +82,8 @@ async fn main() -> anyhow::Result<()> {
     let sentry_url = vlog::sentry_url_from_env();
     #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
     let environment = vlog::environment_from_env();
+    #[allow(deprecated)] // TODO (QIT-21): Use centralized configuration approach.
+    let otlp_url = vlog::otlp_url_from_env();
 
     let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
     if let Some(sentry_url) = &sentry_url {
@@ -80,6 +92,9 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(environment);
     }
+    if let Some(otlp_url) = &otlp_url {
+        builder = builder.with_opentelemetry(otlp_url.clone(), "zksync_server".to_owned());
+    }
     let _guard = builder.build();
 
     // Report whether sentry is running after the logging subsystem was initialized.
@@ -88,6 +103,9 @@ async fn main() -> anyhow::Result<()> {
     } else {
         tracing::info!("No sentry URL was provided");
     }
+    if let Some(otlp_url) = otlp_url {
+        tracing::info!("OpenTelemetry spans are exported to {otlp_url}");
+    }
 
     // TODO (QIT-22): Only deserialize configs on demand.
     // Right now, we are trying to deserialize all the configs that may be needed by `zksync_core`.
@@ -121,8 +139,20 @@ async fn main() -> anyhow::Result<()> {
         gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
         prover_configs: ProverConfigs::from_env().ok(),
         object_store_config: ObjectStoreConfig::from_env().ok(),
+        da_dispatcher_config: DADispatcherConfig::from_env().ok(),
+        upgrade_dry_run_config: UpgradeDryRunConfig::from_env().ok(),
+        call_traces_backfiller_config: CallTracesBackfillerConfig::from_env().ok(),
     };
 
+    if let Some(block_count) = opt.simulate_fee_escalation {
+        let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig")?;
+        let eth_client = ETHClientConfig::from_env().context("EthClientConfig")?;
+        simulate_fee_escalation(&eth_sender, &eth_client.web3_url, block_count)
+            .await
+            .context("simulate_fee_escalation")?;
+        return Ok(());
+    }
+
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
 
     if opt.genesis || is_genesis_needed(&postgres_config).await {
@@ -130,12 +160,19 @@ async fn main() -> anyhow::Result<()> {
         let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig")?;
         let contracts = ContractsConfig::from_env().context("ContractsConfig")?;
         let eth_client = ETHClientConfig::from_env().context("EthClientConfig")?;
+        let state_dump = opt
+            .genesis_state_dump
+            .as_deref()
+            .map(GenesisStateDump::load)
+            .transpose()
+            .context("failed loading genesis state dump")?;
         genesis_init(
             &postgres_config,
             &eth_sender,
             &network,
             &contracts,
             &eth_client.web3_url,
+            state_dump,
         )
         .await
         .context("genesis_init")?;
@@ -16,3 +16,23 @@ impl CircuitIdRoundTuple {
         }
     }
 }
+
+/// SNARK-wrapping backend used to compress the final FRI proof of an L1 batch for the verifier on L1.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, Eq, Hash, PartialEq)]
+pub enum ProofCompressionBackend {
+    /// PLONK wrapper, which is supported by all verifier versions.
+    #[default]
+    Plonk,
+    /// FFLONK wrapper. Its proofs are cheaper to verify, but require a dual verifier on L1.
+    Fflonk,
+}
+
+impl ProofCompressionBackend {
+    /// Returns the verifier type that the dual verifier on L1 expects as the first word of the proof.
+    pub fn l1_verifier_type(self) -> u8 {
+        match self {
+            Self::Fflonk => 0,
+            Self::Plonk => 1,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Basic types related to L1 batch commitments.
+
+use serde::{Deserialize, Serialize};
+
+/// Mode in which L1 batches are committed on L1, which determines how the batch pubdata is made available.
+///
+/// The mode doesn't influence the VM: the L1 messenger always receives the full pubdata of a batch, and
+/// the batch commitment is computed in the same way. The mode only affects the pubdata published as a part
+/// of the commit transaction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum L1BatchCommitmentMode {
+    /// Pubdata is published on L1 as a part of the commit transaction.
+    #[default]
+    Rollup,
+    /// Pubdata is not published on L1. It's either not made available at all, or is posted
+    /// to an external data availability layer, in which case the commit transaction contains
+    /// the DA inclusion data instead of the pubdata.
+    Validium,
+}
@@ -0,0 +1,233 @@
+//! Custom circuit breaker rules defined by the operator in a JSON file.
+
+use std::{collections::HashSet, fs, path::Path, time::Duration};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    ethabi::{self, Token},
+    web3::{contract::Options, types::Bytes},
+    Address, U256,
+};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Configuration of a custom circuit breaker rule.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomRuleConfig {
+    /// Unique name of the rule used in logs and metrics.
+    pub name: String,
+    /// Interval between rule checks. If not set, the default circuit breaker interval is used.
+    #[serde(default)]
+    pub check_interval_ms: Option<u64>,
+    #[serde(flatten)]
+    pub kind: CustomRuleKind,
+}
+
+impl CustomRuleConfig {
+    /// Loads rules from a JSON file containing an array of rule configs.
+    pub fn load(path: &Path) -> anyhow::Result<Vec<Self>> {
+        let contents = fs::read_to_string(path).with_context(|| {
+            format!("failed reading custom circuit breaker rules from {path:?}")
+        })?;
+        let rules: Vec<Self> = serde_json::from_str(&contents).with_context(|| {
+            format!("failed parsing custom circuit breaker rules from {path:?}")
+        })?;
+
+        let mut names = HashSet::with_capacity(rules.len());
+        for rule in &rules {
+            anyhow::ensure!(
+                names.insert(rule.name.as_str()),
+                "custom circuit breaker rule `{}` is defined multiple times",
+                rule.name
+            );
+        }
+        Ok(rules)
+    }
+
+    pub fn check_interval(&self) -> Option<Duration> {
+        self.check_interval_ms.map(Duration::from_millis)
+    }
+}
+
+/// Kind of custom circuit breaker rule together with its parameters.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CustomRuleKind {
+    /// Trips if the L1 ETH balance of the specified account (e.g., an operator account) drops below the minimum.
+    MinEthBalance { address: Address, min_balance: U256 },
+    /// Trips if a getter of the L1 diamond proxy without arguments returns a value different from the expected one.
+    /// The expected value is specified as the ABI encoding of the returned value.
+    MainContractGetter { function: String, expected: Bytes },
+}
+
+/// Circuit breaker checking a [custom rule](CustomRuleConfig).
+#[derive(Debug)]
+pub struct CustomRuleChecker<E> {
+    name: String,
+    kind: CustomRuleKind,
+    eth_client: E,
+    diamond_proxy_addr: Address,
+}
+
+impl<E: EthInterface> CustomRuleChecker<E> {
+    pub fn new(
+        config: CustomRuleConfig,
+        eth_client: E,
+        diamond_proxy_addr: Address,
+    ) -> anyhow::Result<Self> {
+        if let CustomRuleKind::MainContractGetter { function, .. } = &config.kind {
+            let contract = zksync_contracts::zksync_contract();
+            let abi_function = contract.function(function).with_context(|| {
+                format!("custom rule `{}` refers to unknown function", config.name)
+            })?;
+            anyhow::ensure!(
+                abi_function.inputs.is_empty() && abi_function.outputs.len() == 1,
+                "custom rule `{}` refers to function `{function}`, which should have no arguments \
+                 and a single return value",
+                config.name
+            );
+        }
+
+        Ok(Self {
+            name: config.name,
+            kind: config.kind,
+            eth_client,
+            diamond_proxy_addr,
+        })
+    }
+
+    fn trip(&self, reason: String) -> CircuitBreakerError {
+        CircuitBreakerError::CustomRule {
+            name: self.name.clone(),
+            reason,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: EthInterface + std::fmt::Debug> CircuitBreaker for CustomRuleChecker<E> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        match &self.kind {
+            CustomRuleKind::MinEthBalance {
+                address,
+                min_balance,
+            } => {
+                let balance = self
+                    .eth_client
+                    .eth_balance(*address, "circuit_breaker")
+                    .await
+                    .with_context(|| format!("failed getting ETH balance of {address:?}"))?;
+                if balance < *min_balance {
+                    return Err(self.trip(format!(
+                        "ETH balance of {address:?} ({balance}) is below the minimum ({min_balance})"
+                    )));
+                }
+            }
+            CustomRuleKind::MainContractGetter { function, expected } => {
+                let value: Token = self
+                    .eth_client
+                    .call_contract_function(
+                        function,
+                        (),
+                        None,
+                        Options::default(),
+                        None,
+                        self.diamond_proxy_addr,
+                        zksync_contracts::zksync_contract(),
+                    )
+                    .await
+                    .with_context(|| format!("failed calling `{function}` on diamond proxy"))?;
+                let actual = ethabi::encode(&[value]);
+                if actual != expected.0 {
+                    return Err(self.trip(format!(
+                        "`{function}` returned 0x{}, expected 0x{}",
+                        hex::encode(&actual),
+                        hex::encode(&expected.0)
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_eth_client::clients::mock::MockEthereum;
+
+    use super::*;
+
+    const RULES: &str = r#"[
+        {
+            "name": "operator_balance",
+            "check_interval_ms": 60000,
+            "kind": "min_eth_balance",
+            "address": "0x0000000000000000000000000000000000000001",
+            "min_balance": "0xde0b6b3a7640000"
+        },
+        {
+            "name": "verifier",
+            "kind": "main_contract_getter",
+            "function": "getVerifier",
+            "expected": "0x0000000000000000000000000000000000000000000000000000000000000002"
+        }
+    ]"#;
+
+    #[test]
+    fn loading_custom_rules() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("rules.json");
+        fs::write(&path, RULES).unwrap();
+
+        let rules = CustomRuleConfig::load(&path).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].name, "operator_balance");
+        assert_eq!(rules[0].check_interval(), Some(Duration::from_secs(60)));
+        assert_eq!(
+            rules[0].kind,
+            CustomRuleKind::MinEthBalance {
+                address: Address::from_low_u64_be(1),
+                min_balance: U256::exp10(18),
+            }
+        );
+        assert_eq!(rules[1].check_interval(), None);
+        assert_matches::assert_matches!(
+            &rules[1].kind,
+            CustomRuleKind::MainContractGetter { function, expected }
+                if function == "getVerifier" && expected.0.len() == 32
+        );
+
+        for rule in rules {
+            CustomRuleChecker::new(rule, MockEthereum::default(), Address::zero()).unwrap();
+        }
+    }
+
+    #[test]
+    fn custom_rules_validation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("rules.json");
+        let rules = r#"[
+            { "name": "balance", "kind": "min_eth_balance", "address": "0x0000000000000000000000000000000000000001", "min_balance": "0x1" },
+            { "name": "balance", "kind": "min_eth_balance", "address": "0x0000000000000000000000000000000000000002", "min_balance": "0x1" }
+        ]"#;
+        fs::write(&path, rules).unwrap();
+        let err = CustomRuleConfig::load(&path).unwrap_err().to_string();
+        assert!(err.contains("multiple times"), "{err}");
+
+        let rule = CustomRuleConfig {
+            name: "unknown".to_owned(),
+            check_interval_ms: None,
+            kind: CustomRuleKind::MainContractGetter {
+                function: "nonExistingFunction".to_owned(),
+                expected: Bytes::default(),
+            },
+        };
+        CustomRuleChecker::new(rule, MockEthereum::default(), Address::zero()).unwrap_err();
+    }
+}
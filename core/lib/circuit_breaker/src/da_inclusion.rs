@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::{NaiveDateTime, Utc};
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Checks that blobs posted to the DA layer are included into it in a timely manner. If the DA layer doesn't include
+/// a blob, the corresponding L1 batch cannot be committed, so L1 batch processing stalls.
+#[derive(Debug)]
+pub struct DaInclusionChecker {
+    pub pool: ConnectionPool,
+    pub inclusion_timeout: Duration,
+}
+
+fn check_inclusion(
+    l1_batch_number: L1BatchNumber,
+    sent_at: NaiveDateTime,
+    now: NaiveDateTime,
+    inclusion_timeout: Duration,
+) -> Result<(), CircuitBreakerError> {
+    // A negative duration (e.g., due to clock skew) means that the blob was just sent.
+    let elapsed = (now - sent_at).to_std().unwrap_or_default();
+    if elapsed > inclusion_timeout {
+        return Err(CircuitBreakerError::DaInclusionTimeout {
+            l1_batch_number,
+            elapsed,
+        });
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for DaInclusionChecker {
+    fn name(&self) -> &str {
+        "da_inclusion"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let blob = self
+            .pool
+            .access_storage()
+            .await?
+            .data_availability_dal()
+            .get_first_da_blob_awaiting_inclusion()
+            .await
+            .context("get_first_da_blob_awaiting_inclusion()")?;
+        let Some(blob) = blob else {
+            return Ok(());
+        };
+        check_inclusion(
+            blob.l1_batch_number,
+            blob.sent_at,
+            Utc::now().naive_utc(),
+            self.inclusion_timeout,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn checking_inclusion_timeout() {
+        let inclusion_timeout = Duration::from_secs(60);
+        let now = Utc::now().naive_utc();
+        let l1_batch_number = L1BatchNumber(1);
+
+        check_inclusion(l1_batch_number, now, now, inclusion_timeout).unwrap();
+        let sent_at = now - chrono::Duration::seconds(30);
+        check_inclusion(l1_batch_number, sent_at, now, inclusion_timeout).unwrap();
+        let sent_at = now + chrono::Duration::seconds(30);
+        check_inclusion(l1_batch_number, sent_at, now, inclusion_timeout).unwrap();
+
+        let sent_at = now - chrono::Duration::seconds(90);
+        let err = check_inclusion(l1_batch_number, sent_at, now, inclusion_timeout).unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::DaInclusionTimeout { l1_batch_number: L1BatchNumber(1), elapsed }
+                if elapsed == Duration::from_secs(90)
+        );
+    }
+}
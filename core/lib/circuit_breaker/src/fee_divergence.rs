@@ -0,0 +1,101 @@
+use anyhow::Context as _;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::EthInterface;
+use zksync_types::U256;
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Checks that the L1 gas price assumed by the state keeper in the latest sealed miniblock doesn't diverge
+/// too much from the current L1 base fee. Since the state keeper smooths L1 gas prices and may apply a multiplier
+/// to them, the maximum ratio should be generous.
+#[derive(Debug)]
+pub struct FeeDivergenceChecker<E> {
+    pub eth_client: E,
+    pub pool: ConnectionPool,
+    pub max_ratio: f64,
+}
+
+fn check_divergence(assumed: u64, actual: u64, max_ratio: f64) -> Result<(), CircuitBreakerError> {
+    if assumed == 0 || actual == 0 {
+        // Gas prices may be zero in test environments; the ratio doesn't make sense in this case.
+        return Ok(());
+    }
+    let ratio = assumed.max(actual) as f64 / assumed.min(actual) as f64;
+    if ratio > max_ratio {
+        return Err(CircuitBreakerError::FeeDivergence {
+            assumed,
+            actual,
+            max_ratio,
+        });
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl<E: EthInterface + std::fmt::Debug> CircuitBreaker for FeeDivergenceChecker<E> {
+    fn name(&self) -> &str {
+        "fee_divergence"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let last_miniblock = self
+            .pool
+            .access_storage()
+            .await?
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("get_last_sealed_miniblock_header()")?;
+        let Some(last_miniblock) = last_miniblock else {
+            return Ok(());
+        };
+
+        let base_fee = self
+            .eth_client
+            .get_pending_block_base_fee_per_gas("circuit_breaker")
+            .await
+            .context("failed getting L1 base fee")?;
+        if base_fee > U256::from(u64::MAX) {
+            return Err(anyhow::anyhow!("L1 base fee {base_fee} overflows u64").into());
+        }
+        check_divergence(
+            last_miniblock.l1_gas_price,
+            base_fee.as_u64(),
+            self.max_ratio,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn checking_fee_divergence() {
+        check_divergence(100, 100, 2.0).unwrap();
+        check_divergence(100, 200, 2.0).unwrap();
+        check_divergence(200, 100, 2.0).unwrap();
+        check_divergence(0, 1_000, 2.0).unwrap();
+
+        let err = check_divergence(100, 201, 2.0).unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::FeeDivergence {
+                assumed: 100,
+                actual: 201,
+                ..
+            }
+        );
+        let err = check_divergence(201, 100, 2.0).unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::FeeDivergence {
+                assumed: 201,
+                actual: 100,
+                ..
+            }
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::Context as _;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::EthInterface;
+use zksync_types::{web3::contract::Options, Address, H256};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Checks that the base system contract (bootloader and default account) bytecode hashes stored in the L1 diamond
+/// proxy match the hashes known to the node. The L1 hashes are compared both with the hashes used in the latest
+/// sealed miniblock and with the hashes of the latest known protocol version, so that the check doesn't trip
+/// during protocol upgrades.
+#[derive(Debug)]
+pub struct L1ContractsChecker<E> {
+    pub eth_client: E,
+    pub diamond_proxy_addr: Address,
+    pub pool: ConnectionPool,
+}
+
+impl<E: EthInterface> L1ContractsChecker<E> {
+    async fn call_diamond_proxy(&self, function: &str) -> anyhow::Result<H256> {
+        self.eth_client
+            .call_contract_function(
+                function,
+                (),
+                None,
+                Options::default(),
+                None,
+                self.diamond_proxy_addr,
+                zksync_contracts::zksync_contract(),
+            )
+            .await
+            .with_context(|| format!("failed calling `{function}` on diamond proxy"))
+    }
+
+    async fn known_hashes(&self) -> anyhow::Result<Vec<BaseSystemContractsHashes>> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut known_hashes = vec![];
+        let last_miniblock = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("get_last_sealed_miniblock_header()")?;
+        if let Some(header) = last_miniblock {
+            known_hashes.push(header.base_system_contracts_hashes);
+        }
+        if let Some(version_id) = storage.protocol_versions_dal().last_version_id().await {
+            let version = storage
+                .protocol_versions_dal()
+                .get_protocol_version(version_id)
+                .await;
+            if let Some(version) = version {
+                known_hashes.push(version.base_system_contracts_hashes);
+            }
+        }
+        Ok(known_hashes)
+    }
+}
+
+fn check_hashes(
+    l1_hashes: BaseSystemContractsHashes,
+    known_hashes: &[BaseSystemContractsHashes],
+) -> Result<(), CircuitBreakerError> {
+    // If the node has no data yet, there's nothing to compare with.
+    let Some(expected) = known_hashes.last() else {
+        return Ok(());
+    };
+    if known_hashes
+        .iter()
+        .any(|hashes| hashes.bootloader == l1_hashes.bootloader)
+    {
+        if known_hashes
+            .iter()
+            .any(|hashes| hashes.default_aa == l1_hashes.default_aa)
+        {
+            return Ok(());
+        }
+        return Err(CircuitBreakerError::L1ContractMismatch {
+            contract_kind: "default account",
+            expected: expected.default_aa,
+            actual: l1_hashes.default_aa,
+        });
+    }
+    Err(CircuitBreakerError::L1ContractMismatch {
+        contract_kind: "bootloader",
+        expected: expected.bootloader,
+        actual: l1_hashes.bootloader,
+    })
+}
+
+#[async_trait::async_trait]
+impl<E: EthInterface + std::fmt::Debug> CircuitBreaker for L1ContractsChecker<E> {
+    fn name(&self) -> &str {
+        "l1_contracts"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let l1_hashes = BaseSystemContractsHashes {
+            bootloader: self
+                .call_diamond_proxy("getL2BootloaderBytecodeHash")
+                .await?,
+            default_aa: self
+                .call_diamond_proxy("getL2DefaultAccountBytecodeHash")
+                .await?,
+        };
+        let known_hashes = self.known_hashes().await?;
+        check_hashes(l1_hashes, &known_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn checking_base_system_contract_hashes() {
+        let old_hashes = BaseSystemContractsHashes {
+            bootloader: H256::repeat_byte(1),
+            default_aa: H256::repeat_byte(2),
+        };
+        let new_hashes = BaseSystemContractsHashes {
+            bootloader: H256::repeat_byte(3),
+            default_aa: H256::repeat_byte(2),
+        };
+
+        check_hashes(old_hashes, &[]).unwrap();
+        check_hashes(old_hashes, &[old_hashes]).unwrap();
+        check_hashes(new_hashes, &[old_hashes, new_hashes]).unwrap();
+
+        let err = check_hashes(new_hashes, &[old_hashes]).unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::L1ContractMismatch { contract_kind: "bootloader", expected, actual }
+                if expected == old_hashes.bootloader && actual == new_hashes.bootloader
+        );
+
+        let l1_hashes = BaseSystemContractsHashes {
+            bootloader: old_hashes.bootloader,
+            default_aa: H256::repeat_byte(0xff),
+        };
+        let err = check_hashes(l1_hashes, &[old_hashes, new_hashes]).unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::L1ContractMismatch {
+                contract_kind: "default account",
+                ..
+            }
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::Context as _;
 use zksync_dal::ConnectionPool;
 
 use crate::{CircuitBreaker, CircuitBreakerError};
@@ -9,18 +10,20 @@ pub struct FailedL1TransactionChecker {
 
 #[async_trait::async_trait]
 impl CircuitBreaker for FailedL1TransactionChecker {
+    fn name(&self) -> &str {
+        "failed_l1_transaction"
+    }
+
     async fn check(&self) -> Result<(), CircuitBreakerError> {
-        if self
+        let failed_tx_count = self
             .pool
             .access_storage()
-            .await
-            .unwrap()
+            .await?
             .eth_sender_dal()
             .get_number_of_failed_transactions()
             .await
-            .unwrap()
-            > 0
-        {
+            .context("get_number_of_failed_transactions()")?;
+        if failed_tx_count > 0 {
             return Err(CircuitBreakerError::FailedL1Transaction);
         }
         Ok(())
@@ -1,49 +1,144 @@
-use std::time::Duration;
+//! Circuit breakers stopping the node if some invariant required for its safe operation is violated.
+//!
+//! Circuit breakers are registered as rules in [`CircuitBreakerChecker`]; each rule is checked with its own
+//! interval. Tripped rules are logged with the `circuit_breaker_audit` tracing target and are counted
+//! in the `circuit_breaker_trips` metric.
+
+use std::{fmt, time::Duration};
 
 use anyhow::Context as _;
 use futures::channel::oneshot;
 use thiserror::Error;
-use tokio::sync::watch;
+use tokio::{sync::watch, time::Instant};
 use zksync_config::configs::chain::CircuitBreakerConfig;
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::metrics::METRICS;
 
+pub mod custom;
+pub mod da_inclusion;
+pub mod fee_divergence;
+pub mod l1_contracts;
 pub mod l1_txs;
+mod metrics;
 pub mod replication_lag;
 pub mod utils;
 
+/// Tracing target used for the audit log of tripped circuit breakers.
+pub const AUDIT_LOG_TARGET: &str = "circuit_breaker_audit";
+
 #[derive(Debug, Error)]
 pub enum CircuitBreakerError {
     #[error("System has failed L1 transaction")]
     FailedL1Transaction,
     #[error("Replication lag ({0:?}) is above the threshold ({1:?})")]
     ReplicationLag(u32, u32),
+    #[error(
+        "L1 contract returned {contract_kind} bytecode hash {actual:?}, while the node expects {expected:?}"
+    )]
+    L1ContractMismatch {
+        contract_kind: &'static str,
+        expected: H256,
+        actual: H256,
+    },
+    #[error(
+        "L1 gas price assumed by the state keeper ({assumed}) diverges from the L1 base fee ({actual}) \
+         by more than {max_ratio}x"
+    )]
+    FeeDivergence {
+        assumed: u64,
+        actual: u64,
+        max_ratio: f64,
+    },
+    #[error(
+        "Blob for L1 batch #{l1_batch_number} is not included into the DA layer for {elapsed:?}"
+    )]
+    DaInclusionTimeout {
+        l1_batch_number: L1BatchNumber,
+        elapsed: Duration,
+    },
+    #[error("Custom rule `{name}` tripped: {reason}")]
+    CustomRule { name: String, reason: String },
+    /// Error checking a rule (e.g., a DB or L1 connection error). Such errors don't trip the circuit breaker;
+    /// the rule is rechecked after its check interval.
+    #[error("failed checking circuit breaker rule: {0:#}")]
+    Internal(#[from] anyhow::Error),
+}
+
+#[async_trait::async_trait]
+pub trait CircuitBreaker: fmt::Debug + Send + Sync {
+    /// Returns the name of this rule used in logs and metrics.
+    fn name(&self) -> &str;
+
+    async fn check(&self) -> Result<(), CircuitBreakerError>;
 }
 
-/// Checks circuit breakers
 #[derive(Debug)]
-pub struct CircuitBreakerChecker {
-    circuit_breakers: Vec<Box<dyn CircuitBreaker>>,
-    sync_interval: Duration,
+struct RegisteredRule {
+    rule: Box<dyn CircuitBreaker>,
+    check_interval: Duration,
 }
 
-#[async_trait::async_trait]
-pub trait CircuitBreaker: std::fmt::Debug + Send + Sync {
-    async fn check(&self) -> Result<(), CircuitBreakerError>;
+impl RegisteredRule {
+    /// Checks this rule, returning an error only if the rule is tripped.
+    async fn check(&self) -> Option<CircuitBreakerError> {
+        let name = self.rule.name();
+        match self.rule.check().await {
+            Ok(()) => None,
+            Err(CircuitBreakerError::Internal(err)) => {
+                METRICS.check_errors[&name.to_owned()].inc();
+                tracing::warn!("Failed checking circuit breaker rule `{name}`: {err:#}");
+                None
+            }
+            Err(err) => {
+                METRICS.trips[&name.to_owned()].inc();
+                tracing::error!(
+                    target: AUDIT_LOG_TARGET,
+                    rule = name,
+                    reason = %err,
+                    "Circuit breaker rule `{name}` tripped: {err}"
+                );
+                Some(err)
+            }
+        }
+    }
+}
+
+/// Registry of circuit breaker rules periodically checking all of them.
+#[derive(Debug)]
+pub struct CircuitBreakerChecker {
+    rules: Vec<RegisteredRule>,
+    default_check_interval: Duration,
 }
 
 impl CircuitBreakerChecker {
-    pub fn new(
-        circuit_breakers: Vec<Box<dyn CircuitBreaker>>,
-        config: &CircuitBreakerConfig,
-    ) -> Self {
+    pub fn new(config: &CircuitBreakerConfig) -> Self {
         Self {
-            circuit_breakers,
-            sync_interval: config.sync_interval(),
+            rules: vec![],
+            default_check_interval: config.sync_interval(),
         }
     }
 
+    /// Registers a rule checked with the specified interval. If the interval is not specified,
+    /// the default interval from the config is used.
+    pub fn register(&mut self, rule: Box<dyn CircuitBreaker>, check_interval: Option<Duration>) {
+        let check_interval = check_interval.unwrap_or(self.default_check_interval);
+        tracing::info!(
+            "Registered circuit breaker rule `{}` with check interval {check_interval:?}",
+            rule.name()
+        );
+        self.rules.push(RegisteredRule {
+            rule,
+            check_interval,
+        });
+    }
+
+    /// Checks all registered rules once, returning the first tripped rule error.
     pub async fn check(&self) -> Result<(), CircuitBreakerError> {
-        for circuit_breaker in &self.circuit_breakers {
-            circuit_breaker.check().await?;
+        for rule in &self.rules {
+            if let Some(err) = rule.check().await {
+                return Err(err);
+            }
         }
         Ok(())
     }
@@ -51,21 +146,146 @@ impl CircuitBreakerChecker {
     pub async fn run(
         self,
         circuit_breaker_sender: oneshot::Sender<CircuitBreakerError>,
-        stop_receiver: watch::Receiver<bool>,
+        mut stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
-        tracing::info!("running circuit breaker checker...");
+        tracing::info!(
+            "running circuit breaker checker with {} rules...",
+            self.rules.len()
+        );
+        let now = Instant::now();
+        let mut next_checks = vec![now; self.rules.len()];
         loop {
             if *stop_receiver.borrow() {
                 break;
             }
-            if let Err(error) = self.check().await {
-                return circuit_breaker_sender
-                    .send(error)
-                    .ok()
-                    .context("failed to send circuit breaker messsage");
+
+            let now = Instant::now();
+            for (rule, next_check) in self.rules.iter().zip(&mut next_checks) {
+                if *next_check > now {
+                    continue;
+                }
+                if let Some(error) = rule.check().await {
+                    return circuit_breaker_sender
+                        .send(error)
+                        .ok()
+                        .context("failed to send circuit breaker messsage");
+                }
+                *next_check = now + rule.check_interval;
+            }
+
+            let next_check = next_checks
+                .iter()
+                .copied()
+                .min()
+                .unwrap_or(now + self.default_check_interval);
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            if tokio::time::timeout_at(next_check, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
             }
-            tokio::time::sleep(self.sync_interval).await;
         }
+        tracing::info!("Stop signal received, circuit breaker checker is shutting down");
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct CountingRule {
+        name: &'static str,
+        counter: Arc<AtomicUsize>,
+        trip_after: Option<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl CircuitBreaker for CountingRule {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn check(&self) -> Result<(), CircuitBreakerError> {
+            let count = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+            match self.trip_after {
+                Some(trip_after) if count >= trip_after => Err(CircuitBreakerError::CustomRule {
+                    name: self.name.to_owned(),
+                    reason: format!("checked {count} times"),
+                }),
+                None if count == 1 => Err(anyhow::anyhow!("transient error").into()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn test_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            sync_interval_ms: 1_000_000,
+            http_req_max_retry_number: 5,
+            http_req_retry_interval_sec: 2,
+            replication_lag_limit_sec: None,
+            l1_contracts_check_interval_ms: None,
+            fee_divergence_max_ratio: None,
+            fee_divergence_check_interval_ms: None,
+            da_inclusion_timeout_sec: None,
+            custom_rules_path: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn internal_errors_do_not_trip_circuit_breaker() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut checker = CircuitBreakerChecker::new(&test_config());
+        let rule = CountingRule {
+            name: "flaky",
+            counter: counter.clone(),
+            trip_after: None,
+        };
+        checker.register(Box::new(rule), None);
+
+        checker.check().await.unwrap();
+        checker.check().await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rules_are_checked_with_their_intervals() {
+        let slow_counter = Arc::new(AtomicUsize::new(0));
+        let fast_counter = Arc::new(AtomicUsize::new(0));
+        let mut checker = CircuitBreakerChecker::new(&test_config());
+        let slow_rule = CountingRule {
+            name: "slow",
+            counter: slow_counter.clone(),
+            trip_after: Some(usize::MAX),
+        };
+        checker.register(Box::new(slow_rule), None);
+        let fast_rule = CountingRule {
+            name: "fast",
+            counter: fast_counter.clone(),
+            trip_after: Some(3),
+        };
+        checker.register(Box::new(fast_rule), Some(Duration::from_millis(10)));
+
+        let (sender, receiver) = oneshot::channel();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        checker.run(sender, stop_receiver).await.unwrap();
+
+        let err = receiver.await.unwrap();
+        assert_matches!(
+            err,
+            CircuitBreakerError::CustomRule { name, .. } if name == "fast"
+        );
+        assert_eq!(fast_counter.load(Ordering::SeqCst), 3);
+        assert_eq!(slow_counter.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,19 @@
+//! Circuit breaker metrics.
+
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "circuit_breaker")]
+pub(crate) struct CircuitBreakerMetrics {
+    /// Replication lag of the Postgres replica in seconds.
+    pub replication_lag: Gauge<u64>,
+    /// Number of times a circuit breaker rule has tripped.
+    #[metrics(labels = ["rule"])]
+    pub trips: LabeledFamily<String, Counter>,
+    /// Number of errors checking a circuit breaker rule.
+    #[metrics(labels = ["rule"])]
+    pub check_errors: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<CircuitBreakerMetrics> = vise::Global::new();
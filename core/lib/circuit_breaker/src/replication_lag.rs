@@ -1,6 +1,7 @@
+use anyhow::Context as _;
 use zksync_dal::ConnectionPool;
 
-use crate::{CircuitBreaker, CircuitBreakerError};
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
 
 #[derive(Debug)]
 pub struct ReplicationLagChecker {
@@ -10,17 +11,21 @@ pub struct ReplicationLagChecker {
 
 #[async_trait::async_trait]
 impl CircuitBreaker for ReplicationLagChecker {
+    fn name(&self) -> &str {
+        "replication_lag"
+    }
+
     async fn check(&self) -> Result<(), CircuitBreakerError> {
         let lag = self
             .pool
             .access_storage()
-            .await
-            .unwrap()
+            .await?
             .system_dal()
-            .get_replication_lag_sec()
-            .await;
+            .try_get_replication_lag_sec()
+            .await
+            .context("try_get_replication_lag_sec()")?;
 
-        metrics::gauge!("circuit_breaker.replication_lag", lag as f64);
+        METRICS.replication_lag.set(lag.into());
         match self.replication_lag_limit_sec {
             Some(replication_lag_limit_sec) if lag > replication_lag_limit_sec => Err(
                 CircuitBreakerError::ReplicationLag(lag, replication_lag_limit_sec),
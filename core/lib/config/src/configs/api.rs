@@ -36,15 +36,32 @@ pub struct Web3JsonRpcConfig {
     pub filters_limit: Option<u32>,
     /// Max possible limit of subscriptions to be in the state at once.
     pub subscriptions_limit: Option<u32>,
+    /// Max number of subscriptions a single WebSocket connection can hold. Default is 1024.
+    pub max_subscriptions_per_connection: Option<u32>,
+    /// Max number of notification batches buffered for each subscription type before slow subscribers
+    /// start lagging behind. Default is 1024.
+    pub max_buffered_notifications: Option<usize>,
+    /// What to do with a subscriber that lags behind by more than `max_buffered_notifications`.
+    /// Default is to disconnect such a subscriber.
+    pub subscription_backpressure_policy: Option<SubscriptionBackpressurePolicy>,
     /// Interval between polling db for pubsub (in ms).
     pub pubsub_polling_interval: Option<u64>,
     /// number of threads per server
     pub threads_per_server: u32,
     /// Tx nonce: how far ahead from the committed nonce can it be.
     pub max_nonce_ahead: u32,
+    /// Minimum increase of fee values (in percent) required for a transaction to replace a pending transaction
+    /// with the same initiator and nonce. Default is 10%.
+    pub tx_replacement_fee_bump_percent: Option<u32>,
+    /// Maximum time (in seconds) into the future that a transaction deadline may be set to when submitting
+    /// via `zks_sendRawTransactionWithDeadline`. Default is 1 day.
+    pub max_tx_deadline_sec: Option<u64>,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block
     pub gas_price_scale_factor: f64,
+    /// The multiplier applied to the L1 gas price when estimating fee for a transaction that is not expected
+    /// to fit into the pending L1 batch, since the next batch may have higher fee params. Default is 1.2.
+    pub batch_boundary_gas_price_scale_factor: Option<f64>,
     /// Inbound transaction limit used for throttling
     pub transactions_per_sec_limit: Option<u32>,
     /// Timeout for requests (in s)
@@ -64,7 +81,8 @@ pub struct Web3JsonRpcConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     /// If not set, the VM concurrency limit will be efficiently disabled.
     pub vm_concurrency_limit: Option<usize>,
-    /// Smart contract cache size in MiBs. The default value is 128 MiB.
+    /// Smart contract cache size in MiBs. The default value is 128 MiB. The cache is shared with the state keeper
+    /// if it runs in the same process.
     pub factory_deps_cache_size_mb: Option<usize>,
     /// Initial writes cache size in MiBs. The default value is 32 MiB.
     pub initial_writes_cache_size_mb: Option<usize>,
@@ -89,6 +107,20 @@ pub struct Web3JsonRpcConfig {
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Whether to expose the `analytics_` namespace with per-L1 batch cost accounting data on the HTTP API.
+    /// Disabled by default.
+    pub analytics_namespace_enabled: Option<bool>,
+    /// Whether to expose the `unstable_` namespace with internal methods for operators (e.g., inspecting seal criteria
+    /// of the pending L1 batch) on the HTTP API. Disabled by default.
+    pub unstable_namespace_enabled: Option<bool>,
+    /// Maximum nesting depth of calls returned by `debug_trace*` methods; deeper calls are omitted. Default is 1024.
+    pub trace_max_depth: Option<usize>,
+    /// Maximum number of nested calls returned by `debug_trace*` methods for a single transaction; calls
+    /// beyond the limit are omitted. Default is 10000.
+    pub trace_max_calls: Option<usize>,
+    /// Path to a JSON file with API server profiles started by the `api_profiles` component. Each profile
+    /// is an API server with its own port, served namespaces, DB pool size and VM concurrency limit.
+    pub profiles_path: Option<String>,
 }
 
 impl Web3JsonRpcConfig {
@@ -104,10 +136,16 @@ impl Web3JsonRpcConfig {
             req_entities_limit: Some(10000),
             filters_limit: Some(10000),
             subscriptions_limit: Some(10000),
+            max_subscriptions_per_connection: Default::default(),
+            max_buffered_notifications: Default::default(),
+            subscription_backpressure_policy: Default::default(),
             pubsub_polling_interval: Some(200),
             threads_per_server: 1,
             max_nonce_ahead: 50,
+            tx_replacement_fee_bump_percent: Default::default(),
+            max_tx_deadline_sec: Default::default(),
             gas_price_scale_factor: 1.2,
+            batch_boundary_gas_price_scale_factor: Default::default(),
             transactions_per_sec_limit: Default::default(),
             request_timeout: Default::default(),
             account_pks: Default::default(),
@@ -126,6 +164,11 @@ impl Web3JsonRpcConfig {
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
+            analytics_namespace_enabled: None,
+            unstable_namespace_enabled: None,
+            trace_max_depth: None,
+            trace_max_calls: None,
+            profiles_path: None,
         }
     }
 
@@ -149,6 +192,30 @@ impl Web3JsonRpcConfig {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
 
+    pub fn max_subscriptions_per_connection(&self) -> u32 {
+        self.max_subscriptions_per_connection.unwrap_or(1024)
+    }
+
+    pub fn max_buffered_notifications(&self) -> usize {
+        self.max_buffered_notifications.unwrap_or(1024)
+    }
+
+    pub fn tx_replacement_fee_bump_percent(&self) -> u32 {
+        self.tx_replacement_fee_bump_percent.unwrap_or(10)
+    }
+
+    pub fn max_tx_deadline(&self) -> Duration {
+        Duration::from_secs(self.max_tx_deadline_sec.unwrap_or(24 * 60 * 60))
+    }
+
+    pub fn batch_boundary_gas_price_scale_factor(&self) -> f64 {
+        self.batch_boundary_gas_price_scale_factor.unwrap_or(1.2)
+    }
+
+    pub fn subscription_backpressure_policy(&self) -> SubscriptionBackpressurePolicy {
+        self.subscription_backpressure_policy.unwrap_or_default()
+    }
+
     pub fn pubsub_interval(&self) -> Duration {
         Duration::from_millis(self.pubsub_polling_interval.unwrap_or(200))
     }
@@ -213,12 +280,53 @@ impl Web3JsonRpcConfig {
     pub fn tree_api_url(&self) -> Option<String> {
         self.tree_api_url.clone()
     }
+
+    pub fn analytics_namespace_enabled(&self) -> bool {
+        self.analytics_namespace_enabled.unwrap_or(false)
+    }
+
+    pub fn unstable_namespace_enabled(&self) -> bool {
+        self.unstable_namespace_enabled.unwrap_or(false)
+    }
+
+    pub fn trace_max_depth(&self) -> usize {
+        self.trace_max_depth.unwrap_or(1_024)
+    }
+
+    pub fn trace_max_calls(&self) -> usize {
+        self.trace_max_calls.unwrap_or(10_000)
+    }
+}
+
+/// Policy applied to a WebSocket subscriber that cannot keep up with the rate of notifications.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionBackpressurePolicy {
+    /// Drop the oldest buffered notifications and keep the subscriber connected. The subscriber
+    /// will miss the dropped notifications.
+    DropOldest,
+    /// Close the subscription once the subscriber lags behind.
+    #[default]
+    Disconnect,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct HealthCheckConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
+    /// Time (in seconds) the oldest transaction received after the last sealed miniblock waits for inclusion
+    /// after which the state keeper is considered degraded.
+    pub state_keeper_degraded_lag_sec: Option<u64>,
+    /// Time (in seconds) the oldest transaction received after the last sealed miniblock waits for inclusion
+    /// after which the state keeper is considered unhealthy.
+    pub state_keeper_unhealthy_lag_sec: Option<u64>,
+    /// Number of sealed L1 batches not processed by the Merkle tree after which the tree is considered degraded.
+    pub tree_degraded_lag: Option<u64>,
+    /// Number of sealed L1 batches not processed by the Merkle tree after which the tree is considered unhealthy.
+    pub tree_unhealthy_lag: Option<u64>,
+    /// Age of the oldest inflight L1 transaction (in seconds) after which `eth_sender` is considered degraded.
+    /// `eth_sender` is never considered unhealthy, since it doesn't affect serving API requests.
+    pub eth_sender_degraded_lag_sec: Option<u64>,
 }
 
 impl HealthCheckConfig {
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the call traces backfiller, which re-executes sealed L1 batches with the call tracer
+/// and saves call traces for transactions executed without tracing.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CallTracesBackfillerConfig {
+    /// First L1 batch to backfill call traces for. Earlier L1 batches are skipped. If not specified, call traces
+    /// are backfilled starting from the earliest L1 batch that was neither pruned nor archived.
+    pub first_l1_batch_number: Option<u32>,
+    /// Interval between polling the database for newly sealed L1 batches once all sealed batches are processed,
+    /// in milliseconds. The default value is 5 seconds.
+    pub polling_interval_ms: Option<u64>,
+}
+
+impl CallTracesBackfillerConfig {
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms.unwrap_or(5_000))
+    }
+}
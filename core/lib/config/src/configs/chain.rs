@@ -1,7 +1,7 @@
 use std::{str::FromStr, time::Duration};
 
-use serde::Deserialize;
-use zksync_basic_types::{network::Network, Address, L2ChainId};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{commitment::L1BatchCommitmentMode, network::Network, Address, L2ChainId};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ChainConfig {
@@ -47,7 +47,11 @@ pub struct StateKeeperConfig {
 
     /// Number of ms after which an L1 batch is going to be unconditionally sealed.
     pub block_commit_deadline_ms: u64,
-    /// Number of ms after which a miniblock should be sealed by the timeout sealer.
+    /// Number of ms after which a miniblock should be sealed by the timeout sealer. The deadline is measured
+    /// with millisecond precision from the moment the miniblock is opened. Miniblock timestamps have second
+    /// precision and must strictly increase (this is enforced by the bootloader), so the next miniblock cannot
+    /// be opened until its timestamp can be incremented; thus, values below 1,000 ms still result in at most
+    /// one miniblock per second.
     pub miniblock_commit_deadline_ms: u64,
     /// Capacity of the queue for asynchronous miniblock sealing. Once this many miniblocks are queued,
     /// sealing will block until some of the miniblocks from the queue are processed.
@@ -93,6 +97,119 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Path to a file with addresses used by the address filter (one address per line). If not specified,
+    /// transactions are not filtered by addresses.
+    pub address_filter_path: Option<String>,
+    /// Whether the addresses in the filter file are denied or allowed. Default is to deny listed addresses.
+    pub address_filter_mode: Option<AddressFilterMode>,
+    /// Interval between reloading the address filter file (in ms). Default is 10 seconds.
+    pub address_filter_reload_interval_ms: Option<u64>,
+
+    /// Path to a JSON file with overrides for the parameters that can be changed at runtime (fee model inputs,
+    /// seal criteria limits, rate limits etc.). If not specified, these parameters cannot be changed
+    /// without restarting the node.
+    pub config_overrides_path: Option<String>,
+    /// Interval between reloading the config overrides file (in ms). Default is 10 seconds.
+    pub config_overrides_reload_interval_ms: Option<u64>,
+
+    /// Protocol version whose VM is used to shadow-execute all transactions in the state keeper.
+    /// Divergences between the active and the shadow VM are logged, but do not affect the execution results.
+    /// If not specified, shadow execution is disabled.
+    pub shadow_vm_protocol_version: Option<u16>,
+
+    /// Number of upcoming mempool transactions optimistically pre-executed on a snapshot of the sealed state
+    /// while the current transaction is executed. Storage reads made during pre-execution are reused to warm up
+    /// the storage cache of the main VM unless they conflict with the changes made in the current L1 batch.
+    /// If not specified, optimistic execution is disabled.
+    pub optimistic_execution_lookahead: Option<usize>,
+
+    /// Maximum number of L1 priority transactions included into a single miniblock. Once the limit is reached,
+    /// only L2 transactions are taken from the mempool until the miniblock is sealed. Not limited if not specified.
+    pub max_l1_txs_per_miniblock: Option<usize>,
+    /// Maximum number of L1 priority transactions included into a single L1 batch. Not limited if not specified.
+    pub max_l1_txs_per_l1_batch: Option<usize>,
+
+    /// Port of the HTTP endpoint accepting ordered lists of transactions for the next miniblock from an external
+    /// block builder. If specified, the state keeper executes miniblocks proposed by the builder and falls back
+    /// to the mempool if no proposal arrives in time. The endpoint is bound to localhost.
+    /// Requires `external_builder_auth_token` to be set.
+    pub external_builder_http_port: Option<u16>,
+    /// Bearer token that the external block builder must provide in the `Authorization` header.
+    pub external_builder_auth_token: Option<String>,
+    /// Time to wait for a miniblock proposal from the external block builder (in ms) before falling back
+    /// to the mempool. Default is 500 ms.
+    pub external_builder_timeout_ms: Option<u64>,
+
+    /// Mode in which L1 batches are committed on L1 (rollup or validium). Must match the mode of the L1 contracts
+    /// and the pubdata sending mode of the L1 sender. Default is rollup.
+    #[serde(default)]
+    pub l1_batch_commitment_mode: L1BatchCommitmentMode,
+
+    /// Address of the operator-funded paymaster covering fees of sponsored (gasless for users) transactions.
+    /// Transactions using this paymaster are only accepted if they call one of `sponsored_calls`.
+    /// If not specified, transaction sponsorship is disabled.
+    pub sponsor_paymaster_addr: Option<Address>,
+    /// Calls sponsored by the operator, each specified as a contract address optionally followed by `:`
+    /// and a 4-byte function selector (e.g., `0x0000000000000000000000000000000000008006:0x3cda3351`).
+    /// A contract without a selector has all its functions sponsored.
+    pub sponsored_calls: Option<Vec<SponsoredCall>>,
+    /// Maximum total gas used by sponsored transactions in a single L1 batch. Not limited if not specified.
+    pub max_sponsored_gas_per_l1_batch: Option<u64>,
+}
+
+/// Contract call sponsored by the operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SponsoredCall {
+    pub contract: Address,
+    /// Function selector; if not specified, all functions of the contract are sponsored.
+    pub selector: Option<[u8; 4]>,
+}
+
+impl FromStr for SponsoredCall {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (contract, selector) = match s.split_once(':') {
+            Some((contract, selector)) => (contract, Some(selector)),
+            None => (s, None),
+        };
+        let contract = contract.trim();
+        let contract = contract.strip_prefix("0x").unwrap_or(contract);
+        let contract = Address::from_str(contract)
+            .map_err(|err| format!("invalid contract address `{contract}`: {err}"))?;
+        let selector = selector
+            .map(|selector| {
+                let selector = selector.trim();
+                let digits = selector.strip_prefix("0x").unwrap_or(selector);
+                if digits.len() != 8 {
+                    return Err(format!("function selector `{selector}` must have 4 bytes"));
+                }
+                u32::from_str_radix(digits, 16)
+                    .map(u32::to_be_bytes)
+                    .map_err(|err| format!("invalid function selector `{selector}`: {err}"))
+            })
+            .transpose()?;
+        Ok(Self { contract, selector })
+    }
+}
+
+impl<'de> Deserialize<'de> for SponsoredCall {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Mode of the address filter applied to L2 transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressFilterMode {
+    /// Transactions initiated by or sent to a listed address are rejected.
+    #[default]
+    Deny,
+    /// Only transactions initiated by listed addresses are accepted.
+    Allow,
 }
 
 impl StateKeeperConfig {
@@ -121,12 +238,48 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            address_filter_path: None,
+            address_filter_mode: None,
+            address_filter_reload_interval_ms: None,
+            config_overrides_path: None,
+            config_overrides_reload_interval_ms: None,
+            shadow_vm_protocol_version: None,
+            optimistic_execution_lookahead: None,
+            max_l1_txs_per_miniblock: None,
+            max_l1_txs_per_l1_batch: None,
+            external_builder_http_port: None,
+            external_builder_auth_token: None,
+            external_builder_timeout_ms: None,
+            l1_batch_commitment_mode: L1BatchCommitmentMode::Rollup,
+            sponsor_paymaster_addr: None,
+            sponsored_calls: None,
+            max_sponsored_gas_per_l1_batch: None,
         }
     }
 
     pub fn enum_index_migration_chunk_size(&self) -> usize {
         self.enum_index_migration_chunk_size.unwrap_or(1_000)
     }
+
+    pub fn address_filter_mode(&self) -> AddressFilterMode {
+        self.address_filter_mode.unwrap_or_default()
+    }
+
+    pub fn address_filter_reload_interval(&self) -> Duration {
+        Duration::from_millis(self.address_filter_reload_interval_ms.unwrap_or(10_000))
+    }
+
+    pub fn config_overrides_reload_interval(&self) -> Duration {
+        Duration::from_millis(self.config_overrides_reload_interval_ms.unwrap_or(10_000))
+    }
+
+    pub fn external_builder_timeout(&self) -> Duration {
+        Duration::from_millis(self.external_builder_timeout_ms.unwrap_or(500))
+    }
+
+    pub fn sponsored_calls(&self) -> &[SponsoredCall] {
+        self.sponsored_calls.as_deref().unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -147,6 +300,19 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Interval between checks that the base system contract hashes stored in the L1 diamond proxy match
+    /// the ones known to the node. If not set, `sync_interval_ms` is used.
+    pub l1_contracts_check_interval_ms: Option<u64>,
+    /// Maximum ratio between the L1 gas price assumed by the state keeper and the current L1 base fee
+    /// (in either direction). If not set, the fee divergence check is disabled.
+    pub fee_divergence_max_ratio: Option<f64>,
+    /// Interval between fee divergence checks. If not set, `sync_interval_ms` is used.
+    pub fee_divergence_check_interval_ms: Option<u64>,
+    /// Maximum time a blob posted to the DA layer can wait for inclusion. If not set, the DA inclusion check
+    /// is disabled.
+    pub da_inclusion_timeout_sec: Option<u64>,
+    /// Path to a JSON file with custom operator rules.
+    pub custom_rules_path: Option<String>,
 }
 
 impl CircuitBreakerConfig {
@@ -154,6 +320,20 @@ impl CircuitBreakerConfig {
         Duration::from_millis(self.sync_interval_ms)
     }
 
+    pub fn l1_contracts_check_interval(&self) -> Option<Duration> {
+        self.l1_contracts_check_interval_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn fee_divergence_check_interval(&self) -> Option<Duration> {
+        self.fee_divergence_check_interval_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn da_inclusion_timeout(&self) -> Option<Duration> {
+        self.da_inclusion_timeout_sec.map(Duration::from_secs)
+    }
+
     pub fn http_req_retry_interval(&self) -> Duration {
         Duration::from_secs(self.http_req_retry_interval_sec as u64)
     }
@@ -167,6 +347,41 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Ordering policy for ready L2 transactions. If not specified, transactions are ordered by arrival time.
+    #[serde(default)]
+    pub ordering_policy: MempoolOrderingPolicy,
+    /// Maximum number of pending L2 transactions per account kept in the mempool. Transactions with the highest nonces
+    /// exceeding this limit are evicted. If not specified, the number of transactions per account is not limited.
+    pub max_pending_txs_per_account: Option<usize>,
+    /// Policy used to evict L2 transactions once the mempool size exceeds `capacity`. If not specified,
+    /// transactions are not evicted, and a full mempool only purges accounts with non-sequential nonces.
+    pub eviction_policy: Option<MempoolEvictionPolicy>,
+    /// Time-to-live (in seconds) of pending L2 transactions submitted without an explicit validity deadline.
+    /// Once a transaction is older than this, it is dropped from the mempool and reported as expired.
+    /// If not specified, such transactions don't expire.
+    pub default_tx_ttl_sec: Option<u64>,
+}
+
+/// Ordering policy for ready L2 transactions in the mempool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolOrderingPolicy {
+    /// Transactions are ordered by the time they were received.
+    #[default]
+    Fifo,
+    /// Transactions are ordered by the effective priority fee; per-account nonce ordering is preserved.
+    FeePriority,
+}
+
+/// Policy used to evict L2 transactions from a full mempool. Only the transaction with the highest nonce
+/// of an account can be evicted, so that the remaining transactions of the account are still executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolEvictionPolicy {
+    /// Transactions with the lowest effective priority fee are evicted first.
+    LowestFee,
+    /// Transactions received the earliest are evicted first.
+    Oldest,
 }
 
 impl MempoolConfig {
@@ -181,4 +396,8 @@ impl MempoolConfig {
     pub fn delay_interval(&self) -> Duration {
         Duration::from_millis(self.delay_interval)
     }
+
+    pub fn default_tx_ttl(&self) -> Option<Duration> {
+        self.default_tx_ttl_sec.map(Duration::from_secs)
+    }
 }
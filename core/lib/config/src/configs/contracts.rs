@@ -21,6 +21,10 @@ pub struct ContractsConfig {
     pub diamond_init_addr: Address,
     pub diamond_upgrade_init_addr: Address,
     pub diamond_proxy_addr: Address,
+    /// Address of the `Bridgehub` proxy on L1 if the chain is registered in the shared bridge. If set,
+    /// the diamond proxy of the chain is resolved from the bridgehub and checked against `diamond_proxy_addr`,
+    /// and L1 batches are settled using shared bridge executor functions.
+    pub bridgehub_proxy_addr: Option<Address>,
     pub validator_timelock_addr: Address,
     pub genesis_tx_hash: H256,
     pub l1_erc20_bridge_proxy_addr: Address,
@@ -57,6 +61,7 @@ impl ContractsConfig {
             diamond_init_addr: Address::repeat_byte(0x07),
             diamond_upgrade_init_addr: Address::repeat_byte(0x08),
             diamond_proxy_addr: Address::repeat_byte(0x09),
+            bridgehub_proxy_addr: None,
             validator_timelock_addr: Address::repeat_byte(0x0a),
             genesis_tx_hash: H256::repeat_byte(0x01),
             l1_erc20_bridge_proxy_addr: Address::repeat_byte(0x0b),
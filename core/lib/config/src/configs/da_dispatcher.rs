@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// External data availability layer used by the DA dispatcher.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum DataAvailabilityClientKind {
+    /// Celestia; blobs are submitted via the JSON-RPC API of a Celestia light or bridge node.
+    Celestia,
+    /// EigenDA; blobs are submitted via the HTTP API of the EigenDA proxy.
+    EigenDA,
+    /// Avail; blobs are submitted via the HTTP API of the Avail light client.
+    Avail,
+}
+
+/// Configuration for the DA dispatcher, which posts pubdata of L1 batches to an external data availability layer.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DADispatcherConfig {
+    /// Data availability layer to post pubdata to.
+    pub client: DataAvailabilityClientKind,
+    /// URL of the data availability layer API.
+    pub api_url: String,
+    /// Authentication token for the API. Required for Celestia.
+    pub auth_token: Option<String>,
+    /// Hex-encoded namespace (up to 10 bytes) to post blobs to. Required for Celestia.
+    pub celestia_namespace: Option<String>,
+    /// Interval between polling the database for L1 batches to dispatch and blob inclusion data, in milliseconds.
+    /// The default value is 5 seconds.
+    pub polling_interval_ms: Option<u64>,
+    /// Maximum number of L1 batches to dispatch in a single iteration. The default value is 100.
+    pub max_batches_to_dispatch: Option<u32>,
+    /// Maximum number of retries for a failed request to the data availability layer. The default value is 5.
+    pub max_retries: Option<u16>,
+}
+
+impl DADispatcherConfig {
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms.unwrap_or(5_000))
+    }
+
+    pub fn max_batches_to_dispatch(&self) -> usize {
+        self.max_batches_to_dispatch.unwrap_or(100) as usize
+    }
+
+    pub fn max_retries(&self) -> u16 {
+        self.max_retries.unwrap_or(5)
+    }
+}
@@ -44,6 +44,18 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Maximum number of L1 batches for which commitments are computed concurrently in the full tree mode.
+    /// Commitment computation is CPU-bound, so this value should not exceed the number of available CPU cores.
+    #[serde(default = "MerkleTreeConfig::default_commitment_generation_concurrency")]
+    pub commitment_generation_concurrency: usize,
+    /// Interval between checks performed by the tree consistency checker component. Each check verifies
+    /// a random subtree of the tree for a random L1 batch.
+    #[serde(default = "MerkleTreeConfig::default_consistency_check_interval_ms")]
+    pub consistency_check_interval_ms: u64,
+    /// Depth (in nibbles) of subtrees verified by the tree consistency checker component. Each check
+    /// verifies ~`16^(-depth)` of the tree leaves.
+    #[serde(default = "MerkleTreeConfig::default_consistency_check_subtree_depth")]
+    pub consistency_check_subtree_depth: usize,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +68,9 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            commitment_generation_concurrency: Self::default_commitment_generation_concurrency(),
+            consistency_check_interval_ms: Self::default_consistency_check_interval_ms(),
+            consistency_check_subtree_depth: Self::default_consistency_check_subtree_depth(),
         }
     }
 }
@@ -85,6 +100,18 @@ impl MerkleTreeConfig {
         20
     }
 
+    const fn default_commitment_generation_concurrency() -> usize {
+        4
+    }
+
+    const fn default_consistency_check_interval_ms() -> u64 {
+        60_000
+    }
+
+    const fn default_consistency_check_subtree_depth() -> usize {
+        3
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -99,6 +126,199 @@ impl MerkleTreeConfig {
     pub fn stalled_writes_timeout(&self) -> Duration {
         Duration::from_secs(self.stalled_writes_timeout_sec)
     }
+
+    /// Returns the interval between checks performed by the tree consistency checker.
+    pub fn consistency_check_interval(&self) -> Duration {
+        Duration::from_millis(self.consistency_check_interval_ms)
+    }
+}
+
+/// Configuration for pruning historical data of old L1 batches from Postgres. Pruning is performed
+/// by the `db_pruner` component; only batches executed on L1 can be pruned.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PruningConfig {
+    /// Minimum age of L1 batches (in seconds) to be pruned. The age is measured using the L1 batch timestamp.
+    /// The default value is 7 days.
+    #[serde(default = "PruningConfig::default_data_retention_sec")]
+    pub data_retention_sec: u64,
+    /// Maximum number of L1 batches pruned in a single database transaction. Small chunks keep transactions
+    /// short, so that autovacuum can keep up with removed rows.
+    #[serde(default = "PruningConfig::default_chunk_size")]
+    pub chunk_size: u32,
+    /// Interval between checks for new L1 batches to prune (in ms).
+    #[serde(default = "PruningConfig::default_interval_ms")]
+    pub interval_ms: u64,
+    /// Number of latest sealed L1 batches that are never pruned, regardless of their age. If not set,
+    /// L1 batches are retained based only on their age.
+    pub retained_l1_batches: Option<u32>,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            data_retention_sec: Self::default_data_retention_sec(),
+            chunk_size: Self::default_chunk_size(),
+            interval_ms: Self::default_interval_ms(),
+            retained_l1_batches: None,
+        }
+    }
+}
+
+impl PruningConfig {
+    const fn default_data_retention_sec() -> u64 {
+        7 * 24 * 3_600
+    }
+
+    const fn default_chunk_size() -> u32 {
+        10
+    }
+
+    const fn default_interval_ms() -> u64 {
+        60_000
+    }
+
+    pub fn data_retention(&self) -> Duration {
+        Duration::from_secs(self.data_retention_sec)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Configuration for compacting storage logs of old L1 batches in Postgres. Compaction is performed
+/// by the `storage_logs_compactor` component; only batches executed on L1 can be compacted.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StorageLogsCompactionConfig {
+    /// Minimum age of L1 batches (in seconds) to be compacted. The age is measured using the L1 batch timestamp.
+    /// The default value is 1 day.
+    #[serde(default = "StorageLogsCompactionConfig::default_data_retention_sec")]
+    pub data_retention_sec: u64,
+    /// Maximum number of L1 batches compacted in a single database transaction.
+    #[serde(default = "StorageLogsCompactionConfig::default_chunk_size")]
+    pub chunk_size: u32,
+    /// Interval between checks for new L1 batches to compact (in ms).
+    #[serde(default = "StorageLogsCompactionConfig::default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+impl Default for StorageLogsCompactionConfig {
+    fn default() -> Self {
+        Self {
+            data_retention_sec: Self::default_data_retention_sec(),
+            chunk_size: Self::default_chunk_size(),
+            interval_ms: Self::default_interval_ms(),
+        }
+    }
+}
+
+impl StorageLogsCompactionConfig {
+    const fn default_data_retention_sec() -> u64 {
+        24 * 3_600
+    }
+
+    const fn default_chunk_size() -> u32 {
+        10
+    }
+
+    const fn default_interval_ms() -> u64 {
+        60_000
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Configuration for moving rarely accessed artifacts (call traces and pubdata inputs) of old L1 batches
+/// from Postgres to the object store. Archiving is performed by the `cold_storage_archiver` component;
+/// only batches executed on L1 can be archived.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColdStorageConfig {
+    /// Whether archived artifacts should be transparently read from the object store by API servers.
+    /// Must be enabled on all nodes sharing the database with the archiver.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minimum age of L1 batches (in seconds) to be archived. The age is measured using the L1 batch timestamp.
+    /// The default value is 30 days.
+    #[serde(default = "ColdStorageConfig::default_data_retention_sec")]
+    pub data_retention_sec: u64,
+    /// Interval between checks for new L1 batches to archive (in ms).
+    #[serde(default = "ColdStorageConfig::default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+impl Default for ColdStorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            data_retention_sec: Self::default_data_retention_sec(),
+            interval_ms: Self::default_interval_ms(),
+        }
+    }
+}
+
+impl ColdStorageConfig {
+    const fn default_data_retention_sec() -> u64 {
+        30 * 24 * 3_600
+    }
+
+    const fn default_interval_ms() -> u64 {
+        60_000
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Configuration for manual compaction of RocksDB instances (the state keeper cache and the Merkle tree)
+/// performed by the `rocksdb_compactor` component. Compaction is scheduled once per daily off-peak window,
+/// and can be triggered manually via the admin HTTP endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RocksdbCompactionConfig {
+    /// Hour (UTC, 0..=23) at which the off-peak window starts.
+    #[serde(default = "RocksdbCompactionConfig::default_window_start_hour")]
+    pub window_start_hour: u32,
+    /// Hour (UTC, 0..=23) at which the off-peak window ends (exclusive). If less than `window_start_hour`,
+    /// the window spans midnight. If equal to `window_start_hour`, scheduled compaction is disabled.
+    #[serde(default = "RocksdbCompactionConfig::default_window_end_hour")]
+    pub window_end_hour: u32,
+    /// Interval between checks whether scheduled compaction should be started (in ms).
+    #[serde(default = "RocksdbCompactionConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Port for the admin HTTP endpoint used to trigger compaction manually. The endpoint is bound to localhost
+    /// since it's unauthenticated. If not specified, the endpoint is not started.
+    pub admin_port: Option<u16>,
+}
+
+impl Default for RocksdbCompactionConfig {
+    fn default() -> Self {
+        Self {
+            window_start_hour: Self::default_window_start_hour(),
+            window_end_hour: Self::default_window_end_hour(),
+            check_interval_ms: Self::default_check_interval_ms(),
+            admin_port: None,
+        }
+    }
+}
+
+impl RocksdbCompactionConfig {
+    const fn default_window_start_hour() -> u32 {
+        2
+    }
+
+    const fn default_window_end_hour() -> u32 {
+        5
+    }
+
+    const fn default_check_interval_ms() -> u64 {
+        60_000
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
 }
 
 /// Database configuration.
@@ -112,6 +332,22 @@ pub struct DBConfig {
     // ^ Filled in separately in `Self::from_env()`. We cannot use `serde(flatten)` because it
     // doesn't work with 'envy`.
     pub merkle_tree: MerkleTreeConfig,
+    /// Postgres pruning configuration.
+    #[serde(skip)]
+    // ^ Filled in separately in `Self::from_env()`.
+    pub pruning: PruningConfig,
+    /// Storage logs compaction configuration.
+    #[serde(skip)]
+    // ^ Filled in separately in `Self::from_env()`.
+    pub storage_logs_compaction: StorageLogsCompactionConfig,
+    /// Cold storage configuration.
+    #[serde(skip)]
+    // ^ Filled in separately in `Self::from_env()`.
+    pub cold_storage: ColdStorageConfig,
+    /// RocksDB compaction configuration.
+    #[serde(skip)]
+    // ^ Filled in separately in `Self::from_env()`.
+    pub rocksdb_compaction: RocksdbCompactionConfig,
 }
 
 impl DBConfig {
@@ -129,10 +365,21 @@ pub struct PostgresConfig {
     pub master_url: Option<String>,
     /// URL for the replica database.
     pub replica_url: Option<String>,
+    /// URLs of additional read replicas. If specified, read-only queries of the API servers are routed
+    /// to these replicas (provided that they are not stale), while other queries use the replica URL above.
+    pub read_replica_urls: Vec<String>,
+    /// Maximum replication lag in seconds for a read replica to be used for read-only queries.
+    pub max_replica_lag_sec: Option<u64>,
     /// URL for the prover database.
     pub prover_url: Option<String>,
     /// Maximum size of the connection pool.
     pub max_connections: Option<u32>,
+    /// Minimum size of the connection pool. If specified, the main and replica pools are sized adaptively
+    /// between this value and `max_connections` based on the time spent waiting for a connection.
+    pub min_connections: Option<u32>,
+    /// Target time in milliseconds to wait for a connection from an adaptively sized pool.
+    /// If the average wait time exceeds this value, the pool grows.
+    pub pool_target_wait_ms: Option<u64>,
     /// Statement timeout in seconds for Postgres connections. Applies only to the replica
     /// connection pool used by the API servers.
     pub statement_timeout_sec: Option<u64>,
@@ -169,4 +416,15 @@ impl PostgresConfig {
     pub fn statement_timeout(&self) -> Option<Duration> {
         self.statement_timeout_sec.map(Duration::from_secs)
     }
+
+    /// Returns the target time to wait for a connection from an adaptively sized pool.
+    /// The default value is 50 milliseconds.
+    pub fn pool_target_wait(&self) -> Duration {
+        Duration::from_millis(self.pool_target_wait_ms.unwrap_or(50))
+    }
+
+    /// Returns the maximum replication lag for read replicas. The default value is 10 seconds.
+    pub fn max_replica_lag(&self) -> Duration {
+        Duration::from_secs(self.max_replica_lag_sec.unwrap_or(10))
+    }
 }
@@ -1,7 +1,9 @@
 use std::time::Duration;
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{
+    basic_fri_types::ProofCompressionBackend, commitment::L1BatchCommitmentMode, Address, H256,
+};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -10,6 +12,9 @@ pub struct ETHSenderConfig {
     pub sender: SenderConfig,
     /// Options related to the `GasAdjuster` submodule.
     pub gas_adjuster: GasAdjusterConfig,
+    /// Fee escalation policies for resubmitted L1 transactions.
+    #[serde(default)]
+    pub fee_escalation: FeeEscalationConfig,
 }
 
 impl ETHSenderConfig {
@@ -33,8 +38,23 @@ impl ETHSenderConfig {
                 aggregated_block_execute_deadline: 10,
                 timestamp_criteria_max_allowed_lag: 30,
                 l1_batch_min_age_before_execute_seconds: None,
+                l1_batch_execute_delay_seconds: None,
+                execute_max_l1_gas_price: None,
+                execute_max_postpone_seconds: None,
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_signer: OperatorSignerKind::PrivateKey,
+                operator_signer_url: None,
+                operator_address: None,
+                max_aggregation_overhead_per_l1_batch: None,
+                settlement_layer: SettlementLayerKind::L1,
+                gateway_url: None,
+                gateway_chain_id: None,
+                gateway_diamond_proxy_addr: None,
+                gateway_validator_timelock_addr: None,
+                gateway_multicall3_addr: None,
+                use_dual_verifier: None,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -45,7 +65,11 @@ impl ETHSenderConfig {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 5,
                 max_l1_gas_price: None,
+                base_fee_smoothing: BaseFeeSmoothing::MovingMedian,
+                base_fee_ewma_alpha: None,
+                base_fee_percentile: None,
             },
+            fee_escalation: FeeEscalationConfig::default(),
         }
     }
 }
@@ -63,6 +87,42 @@ pub enum ProofLoadingMode {
     FriProofFromGcs,
 }
 
+/// The way pubdata of L1 batches is made available.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum PubdataSendingMode {
+    /// Pubdata is sent to L1 as a part of commit transaction calldata. In the validium commitment mode,
+    /// no pubdata is sent.
+    #[default]
+    Calldata,
+    /// Pubdata is posted to an external data availability layer by the DA dispatcher; commit transactions
+    /// only include the DA inclusion data.
+    Custom,
+}
+
+/// The way operator transactions are signed.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum OperatorSignerKind {
+    /// Transactions are signed with the private key provided in the `ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY`
+    /// env variable.
+    #[default]
+    PrivateKey,
+    /// Transactions are signed by an external signer via the `eth_signTransaction` JSON-RPC method
+    /// (e.g., Web3Signer or Clef). The external signer can in turn keep the key in an HSM or a cloud KMS,
+    /// so that the key never resides in the node memory.
+    JsonRpc,
+}
+
+/// Chain that L1 batches are committed to, proved and executed on.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum SettlementLayerKind {
+    /// Batches are settled on Ethereum L1 via the validator timelock and the diamond proxy
+    /// specified in the contracts config.
+    #[default]
+    L1,
+    /// Batches are settled on a ZK gateway chain shared with other chains. Requires the `gateway_*` params.
+    Gateway,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
@@ -91,11 +151,56 @@ pub struct SenderConfig {
     /// Note that this number must be slightly higher than the one set on the contract,
     /// because the contract uses block.timestamp which lags behind the clock time.
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
+    /// Additional delay for executing L1 batches on top of `l1_batch_min_age_before_execute_seconds`, in seconds.
+    /// Unlike the minimum age, which mirrors the execution delay enforced by the L1 contract, this delay
+    /// is an operator policy (e.g., to leave time for reacting to incidents before L1 batches are finalized).
+    pub l1_batch_execute_delay_seconds: Option<u64>,
+    /// L1 gas price (base fee + priority fee, in wei) above which execute transactions are postponed.
+    /// Execution resumes once the gas price drops or after `execute_max_postpone_seconds`.
+    pub execute_max_l1_gas_price: Option<u64>,
+    /// Maximum time by which execution of L1 batches can be postponed because of `execute_max_l1_gas_price`,
+    /// counting from the moment L1 batches become old enough to be executed. Defaults to 1 hour.
+    pub execute_max_postpone_seconds: Option<u64>,
     // Max acceptable fee for sending tx it acts as a safeguard to prevent sending tx with very high fees.
     pub max_acceptable_priority_fee_in_gwei: u64,
 
     /// The mode in which proofs are loaded, either from DB/GCS for FRI/Old proof.
     pub proof_loading_mode: ProofLoadingMode,
+    /// The mode in which pubdata is made available.
+    #[serde(default)]
+    pub pubdata_sending_mode: PubdataSendingMode,
+    /// The way operator transactions are signed.
+    #[serde(default)]
+    pub operator_signer: OperatorSignerKind,
+    /// URL of the external signer. Required for the JSON-RPC operator signer.
+    pub operator_signer_url: Option<String>,
+    /// Operator address managed by the external signer. If not specified, the first account returned
+    /// by the signer is used. Changing the address (e.g., to rotate the operator key) is safe while the node
+    /// is stopped; transactions that were not sent yet are handed over to the new address.
+    pub operator_address: Option<Address>,
+    /// Enables the cost-based aggregation planner. The planner publishes ready L1 batches once the fixed cost
+    /// of an L1 transaction (the base cost of the operation at the current L1 gas price) split among the batches
+    /// doesn't exceed this value, in wei. Static limits (e.g., `max_aggregated_blocks_to_commit`) become
+    /// upper bounds for aggregation; aggregation deadlines still apply.
+    pub max_aggregation_overhead_per_l1_batch: Option<u64>,
+    /// Chain that L1 batches are settled on. Before switching to another chain, all eth txs sent
+    /// to the current one must be confirmed; new txs are sent to the new chain after a restart.
+    #[serde(default)]
+    pub settlement_layer: SettlementLayerKind,
+    /// URL of the gateway chain node API. Required for the gateway settlement layer.
+    pub gateway_url: Option<String>,
+    /// Chain ID of the gateway. Required for the gateway settlement layer.
+    pub gateway_chain_id: Option<u64>,
+    /// Address of the diamond proxy on the gateway. Required for the gateway settlement layer.
+    pub gateway_diamond_proxy_addr: Option<Address>,
+    /// Address of the validator timelock on the gateway. Required for the gateway settlement layer.
+    pub gateway_validator_timelock_addr: Option<Address>,
+    /// Address of the Multicall3 contract on the gateway. Required for the gateway settlement layer.
+    pub gateway_multicall3_addr: Option<Address>,
+    /// Whether the verifier on L1 is a dual verifier supporting proofs of all proof compression backends.
+    /// If set, each submitted proof is prefixed with the verifier type of its backend. Required to submit proofs
+    /// compressed with non-PLONK backends. Defaults to `false`.
+    pub use_dual_verifier: Option<bool>,
 }
 
 impl SenderConfig {
@@ -104,6 +209,25 @@ impl SenderConfig {
         Duration::from_secs(self.tx_poll_period)
     }
 
+    pub fn use_dual_verifier(&self) -> bool {
+        self.use_dual_verifier.unwrap_or(false)
+    }
+
+    /// Returns the minimum age of L1 batches to be executed (in seconds) including the additional delay.
+    pub fn l1_batch_execute_min_age_seconds(&self) -> Option<u64> {
+        match (
+            self.l1_batch_min_age_before_execute_seconds,
+            self.l1_batch_execute_delay_seconds,
+        ) {
+            (None, None) => None,
+            (min_age, delay) => Some(min_age.unwrap_or(0) + delay.unwrap_or(0)),
+        }
+    }
+
+    pub fn execute_max_postpone_seconds(&self) -> u64 {
+        self.execute_max_postpone_seconds.unwrap_or(3_600)
+    }
+
     /// Converts `self.aggregate_tx_poll_period` into `Duration`.
     pub fn aggregate_tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.aggregate_tx_poll_period)
@@ -115,13 +239,118 @@ impl SenderConfig {
             .ok()
             .map(|pk| pk.parse().unwrap())
     }
+
+    /// Checks that the pubdata sending mode is compatible with the L1 batch commitment mode of the chain.
+    pub fn validate_commitment_mode(&self, mode: L1BatchCommitmentMode) -> anyhow::Result<()> {
+        if mode == L1BatchCommitmentMode::Rollup
+            && self.pubdata_sending_mode == PubdataSendingMode::Custom
+        {
+            anyhow::bail!(
+                "Custom pubdata sending mode requires the validium L1 batch commitment mode; \
+                 rollups must publish pubdata on L1"
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that proofs compressed with the specified backend can be verified on L1.
+    pub fn validate_proof_compression_backend(
+        &self,
+        backend: ProofCompressionBackend,
+    ) -> anyhow::Result<()> {
+        if backend != ProofCompressionBackend::Plonk && !self.use_dual_verifier() {
+            anyhow::bail!(
+                "{backend:?} proof compression backend requires the dual verifier on L1; \
+                 set `use_dual_verifier` if the verifier supports it"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Strategy of increasing the base fee of an L1 transaction depending on the number of L1 blocks
+/// it has spent in the mempool.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum FeeEscalationStrategy {
+    /// `median_base_fee * initial_multiplier * step ^ blocks_in_mempool`. With the default parameters,
+    /// this is the formula used by `GasAdjuster`.
+    #[default]
+    Exponential,
+    /// `median_base_fee * initial_multiplier * (1 + step * blocks_in_mempool)`.
+    Linear,
+    /// `median_base_fee * multiplier`, where `multiplier` is taken from the last schedule step reached
+    /// by the transaction (or `initial_multiplier` if no steps are reached yet).
+    Schedule,
+}
+
+/// Fee escalation policy for L1 transactions of a certain type (commit, prove or execute).
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct FeeEscalationPolicyConfig {
+    #[serde(default)]
+    pub strategy: FeeEscalationStrategy,
+    /// Base fee multiplier for a transaction that was just sent. If not specified,
+    /// `pricing_formula_parameter_a` from the gas adjuster config is used.
+    pub initial_multiplier: Option<f64>,
+    /// Escalation step for exponential and linear strategies. If not specified, it's derived from
+    /// `pricing_formula_parameter_b` from the gas adjuster config (`b` for the exponential strategy,
+    /// `b - 1` for the linear one).
+    pub step: Option<f64>,
+    /// Numbers of L1 blocks in the mempool after which the multiplier changes. Must be strictly increasing.
+    /// Only used by the schedule strategy.
+    #[serde(default)]
+    pub schedule_blocks: Vec<u32>,
+    /// Base fee multipliers corresponding to `schedule_blocks`. Only used by the schedule strategy.
+    #[serde(default)]
+    pub schedule_multipliers: Vec<f64>,
+    /// Minimum increase of the priority fee on resubmission, in percent. Nodes reject replacement transactions
+    /// with priority fee increased by less than 10%. Default value is 20.
+    pub priority_fee_bump_percent: Option<u64>,
+    /// Cap on `max_fee_per_gas` (i.e., base fee + priority fee) of sent transactions, in wei. Once the cap is reached,
+    /// the transaction is not resubmitted anymore and waits to be mined with the fee it has.
+    pub max_fee_per_gas: Option<u64>,
+}
+
+impl FeeEscalationPolicyConfig {
+    const DEFAULT_PRIORITY_FEE_BUMP_PERCENT: u64 = 20;
+
+    pub fn priority_fee_bump_percent(&self) -> u64 {
+        self.priority_fee_bump_percent
+            .unwrap_or(Self::DEFAULT_PRIORITY_FEE_BUMP_PERCENT)
+    }
+}
+
+/// Fee escalation policies for different types of L1 transactions sent by the operator.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct FeeEscalationConfig {
+    /// Policy for commit transactions.
+    #[serde(default)]
+    pub commit: FeeEscalationPolicyConfig,
+    /// Policy for transactions publishing proofs.
+    #[serde(default)]
+    pub prove: FeeEscalationPolicyConfig,
+    /// Policy for execute transactions.
+    #[serde(default)]
+    pub execute: FeeEscalationPolicyConfig,
+}
+
+/// Algorithm used by `GasAdjuster` to smooth base fees of recent L1 blocks.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum BaseFeeSmoothing {
+    /// Median of base fees in the window.
+    #[default]
+    MovingMedian,
+    /// Exponentially weighted moving average of base fees in the window, with the smoothing factor
+    /// set by `base_fee_ewma_alpha`.
+    Ewma,
+    /// Percentile of base fees in the window set by `base_fee_percentile`.
+    Percentile,
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
 pub struct GasAdjusterConfig {
     /// Priority Fee to be used by GasAdjuster
     pub default_priority_fee_per_gas: u64,
-    /// Number of blocks collected by GasAdjuster from which base_fee median is taken
+    /// Number of blocks collected by GasAdjuster from which the smoothed base_fee is computed
     pub max_base_fee_samples: usize,
     /// Parameter of the transaction base_fee_per_gas pricing formula
     pub pricing_formula_parameter_a: f64,
@@ -135,6 +364,14 @@ pub struct GasAdjusterConfig {
     pub poll_period: u64,
     /// Max number of l1 gas price that is allowed to be used in state keeper.
     pub max_l1_gas_price: Option<u64>,
+    /// Algorithm used to smooth base fees of the last `max_base_fee_samples` L1 blocks.
+    #[serde(default)]
+    pub base_fee_smoothing: BaseFeeSmoothing,
+    /// Smoothing factor for the EWMA algorithm, in the (0, 1] range. Larger values give more weight
+    /// to recent blocks. Default value is 0.1.
+    pub base_fee_ewma_alpha: Option<f64>,
+    /// Percentile for the percentile algorithm, in the [0, 100] range. Default value is 50 (i.e., the median).
+    pub base_fee_percentile: Option<f64>,
 }
 
 impl GasAdjusterConfig {
@@ -146,4 +383,12 @@ impl GasAdjusterConfig {
     pub fn max_l1_gas_price(&self) -> u64 {
         self.max_l1_gas_price.unwrap_or(u64::MAX)
     }
+
+    pub fn base_fee_ewma_alpha(&self) -> f64 {
+        self.base_fee_ewma_alpha.unwrap_or(0.1)
+    }
+
+    pub fn base_fee_percentile(&self) -> f64 {
+        self.base_fee_percentile.unwrap_or(50.0)
+    }
 }
@@ -7,6 +7,7 @@ use serde::Deserialize;
 pub struct ETHWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// If not specified operation will be processed once its block is finalized.
+    /// This is the L1 finality depth assumed by the node: a deeper L1 reorg stops the node.
     pub confirmations_for_eth_event: Option<u64>,
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
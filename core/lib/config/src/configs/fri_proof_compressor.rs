@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use serde::Deserialize;
+use zksync_basic_types::basic_fri_types::ProofCompressionBackend;
 
 /// Configuration for the fri proof compressor
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -25,10 +26,24 @@ pub struct FriProofCompressorConfig {
 
     // Whether to verify wrapper proof or not.
     pub verify_wrapper_proof: bool,
+
+    /// SNARK-wrapping backend used to compress proofs. Proofs produced by a non-default backend
+    /// can only be verified on L1 if the L1 sender is configured to use the dual verifier.
+    #[serde(default)]
+    pub compression_backend: ProofCompressionBackend,
+    /// URL of the external FFLONK wrapping service. Required if the FFLONK backend is used.
+    pub fflonk_wrapper_url: Option<String>,
+    /// Timeout for requests to the FFLONK wrapping service. If not set, the proof generation timeout is used.
+    pub fflonk_wrapper_timeout_in_secs: Option<u64>,
 }
 
 impl FriProofCompressorConfig {
     pub fn generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
+
+    pub fn fflonk_wrapper_timeout(&self) -> Duration {
+        self.fflonk_wrapper_timeout_in_secs
+            .map_or_else(|| self.generation_timeout(), Duration::from_secs)
+    }
 }
@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use serde::Deserialize;
 
+use crate::ObjectStoreConfig;
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct FriProverGatewayConfig {
     pub api_url: String,
@@ -11,10 +13,46 @@ pub struct FriProverGatewayConfig {
     pub prometheus_listener_port: u16,
     pub prometheus_pushgateway_url: String,
     pub prometheus_push_interval_ms: Option<u64>,
+
+    /// Weight of the primary prover cluster (i.e., the one using the default prover DB and object store)
+    /// used to distribute new jobs among clusters. Defaults to 1.
+    pub primary_cluster_weight: Option<u32>,
+    /// A prover cluster is considered unhealthy and doesn't receive new jobs if its oldest queued
+    /// witness generation job is older than this timeout. Defaults to 1 hour.
+    pub cluster_stall_timeout_secs: Option<u64>,
+    /// Additional prover clusters that jobs are distributed to.
+    #[serde(skip)]
+    pub additional_clusters: Vec<ProverClusterConfig>,
+}
+
+/// Configuration of an independent prover cluster, which has its own prover DB and object store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverClusterConfig {
+    /// Name of the cluster used in logs and metrics.
+    pub name: String,
+    /// URL of the prover DB of the cluster.
+    pub prover_url: String,
+    /// Weight of the cluster used to distribute new jobs among clusters. Defaults to 1.
+    pub weight: Option<u32>,
+    pub object_store: ObjectStoreConfig,
+}
+
+impl ProverClusterConfig {
+    pub fn weight(&self) -> u32 {
+        self.weight.unwrap_or(1)
+    }
 }
 
 impl FriProverGatewayConfig {
     pub fn api_poll_duration(&self) -> Duration {
         Duration::from_secs(self.api_poll_duration_secs as u64)
     }
+
+    pub fn primary_cluster_weight(&self) -> u32 {
+        self.primary_cluster_weight.unwrap_or(1)
+    }
+
+    pub fn cluster_stall_timeout(&self) -> Duration {
+        Duration::from_secs(self.cluster_stall_timeout_secs.unwrap_or(3_600))
+    }
 }
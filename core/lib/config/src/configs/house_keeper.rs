@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 /// Configuration for the house keeper.
@@ -16,4 +18,34 @@ pub struct HouseKeeperConfig {
     pub fri_prover_stats_reporting_interval_ms: u64,
     pub fri_proof_compressor_job_retrying_interval_ms: u64,
     pub fri_proof_compressor_stats_reporting_interval_ms: u64,
+    /// Port of the HTTP API exposing prover queues and admin actions for prover jobs.
+    /// If not set, the API is disabled.
+    pub prover_api_port: Option<u16>,
+    /// Bearer token that clients of the prover API must provide in the `Authorization` header.
+    /// Required if the prover API is enabled.
+    pub prover_api_auth_token: Option<String>,
+    /// Delay before the first retry of a failed FRI prover or witness generator job. The delay doubles
+    /// with each failed attempt, up to `fri_job_retry_backoff_max_ms`. Defaults to 10 seconds.
+    pub fri_job_retry_backoff_base_ms: Option<u64>,
+    /// Maximum delay before a failed FRI prover or witness generator job is retried. Defaults to 10 minutes.
+    pub fri_job_retry_backoff_max_ms: Option<u64>,
+}
+
+impl HouseKeeperConfig {
+    const DEFAULT_FRI_JOB_RETRY_BACKOFF_BASE_MS: u64 = 10_000;
+    const DEFAULT_FRI_JOB_RETRY_BACKOFF_MAX_MS: u64 = 600_000;
+
+    pub fn fri_job_retry_backoff_base(&self) -> Duration {
+        Duration::from_millis(
+            self.fri_job_retry_backoff_base_ms
+                .unwrap_or(Self::DEFAULT_FRI_JOB_RETRY_BACKOFF_BASE_MS),
+        )
+    }
+
+    pub fn fri_job_retry_backoff_max(&self) -> Duration {
+        Duration::from_millis(
+            self.fri_job_retry_backoff_max_ms
+                .unwrap_or(Self::DEFAULT_FRI_JOB_RETRY_BACKOFF_MAX_MS),
+        )
+    }
 }
@@ -2,10 +2,12 @@
 pub use self::{
     alerts::AlertsConfig,
     api::ApiConfig,
+    call_traces_backfiller::CallTracesBackfillerConfig,
     chain::ChainConfig,
     circuit_synthesizer::CircuitSynthesizerConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
+    da_dispatcher::DADispatcherConfig,
     database::{DBConfig, PostgresConfig},
     eth_client::ETHClientConfig,
     eth_sender::{ETHSenderConfig, GasAdjusterConfig},
@@ -20,16 +22,19 @@ pub use self::{
     prover::{ProverConfig, ProverConfigs},
     prover_group::ProverGroupConfig,
     snapshots_creator::SnapshotsCreatorConfig,
+    upgrade_dry_run::UpgradeDryRunConfig,
     utils::PrometheusConfig,
     witness_generator::WitnessGeneratorConfig,
 };
 
 pub mod alerts;
 pub mod api;
+pub mod call_traces_backfiller;
 pub mod chain;
 pub mod circuit_synthesizer;
 pub mod contract_verifier;
 pub mod contracts;
+pub mod da_dispatcher;
 pub mod database;
 pub mod eth_client;
 pub mod eth_sender;
@@ -46,6 +51,7 @@ pub mod proof_data_handler;
 pub mod prover;
 pub mod prover_group;
 pub mod snapshots_creator;
+pub mod upgrade_dry_run;
 pub mod utils;
 pub mod witness_generator;
 
@@ -5,14 +5,59 @@ pub enum ObjectStoreMode {
     GCS,
     GCSWithCredentialFile,
     FileBacked,
+    /// Store compatible with the AWS S3 API (e.g., AWS S3 itself, MinIO or Cloudflare R2).
+    S3,
+}
+
+/// Server-side encryption algorithm applied to objects stored in an S3-compatible store.
+#[derive(Debug, Deserialize, Eq, PartialEq, Clone, Copy)]
+pub enum S3ServerSideEncryption {
+    /// Encryption with S3-managed keys (SSE-S3).
+    Aes256,
+    /// Encryption with AWS KMS keys (SSE-KMS).
+    AwsKms,
 }
 
 /// Configuration for the object store
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ObjectStoreConfig {
+    /// Base URL of the bucket. For the S3 mode, this is the name of the S3 bucket.
     pub bucket_base_url: String,
     pub mode: ObjectStoreMode,
     pub file_backed_base_path: String,
     pub gcs_credential_file_path: String,
     pub max_retries: u16,
+    /// Custom endpoint URL for the S3 API, e.g. for MinIO or Cloudflare R2. If not specified,
+    /// the AWS endpoint for the configured region is used.
+    pub s3_endpoint: Option<String>,
+    /// Region of the S3 bucket. If not specified, the region is taken from the standard AWS environment
+    /// (e.g., the `AWS_REGION` env variable).
+    pub s3_region: Option<String>,
+    /// Whether to address the bucket as a part of the URL path rather than a subdomain. Usually required
+    /// for self-hosted stores, such as MinIO. Defaults to `false`.
+    pub s3_force_path_style: Option<bool>,
+    /// Server-side encryption algorithm for stored objects. If not specified, the bucket defaults apply.
+    pub s3_server_side_encryption: Option<S3ServerSideEncryption>,
+    /// ID of the KMS key used for the `AwsKms` server-side encryption. If not specified,
+    /// the AWS managed key is used.
+    pub s3_kms_key_id: Option<String>,
+    /// Objects exceeding this size are uploaded using multipart upload, with parts of this size.
+    /// Values less than 5 MiB (the minimum part size supported by S3) are raised to 5 MiB.
+    /// Defaults to 16 MiB.
+    pub s3_multipart_part_size_bytes: Option<usize>,
+}
+
+impl ObjectStoreConfig {
+    /// Minimum part size for S3 multipart uploads (except for the last part).
+    const MIN_S3_MULTIPART_PART_SIZE: usize = 5 << 20;
+
+    pub fn s3_force_path_style(&self) -> bool {
+        self.s3_force_path_style.unwrap_or(false)
+    }
+
+    pub fn s3_multipart_part_size(&self) -> usize {
+        self.s3_multipart_part_size_bytes
+            .unwrap_or(16 << 20)
+            .max(Self::MIN_S3_MULTIPART_PART_SIZE)
+    }
 }
@@ -8,15 +8,62 @@ pub enum ProtocolVersionLoadingMode {
     FromEnvVar,
 }
 
+/// The way proofs for L1 batches are obtained by the proof data handler.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub enum ProofGenerationMode {
+    /// Proof generation data is served to provers, which submit generated proofs back.
+    #[default]
+    Provers,
+    /// Proof generation data is not served to provers. Instead, each L1 batch is assigned a mock proof
+    /// (i.e., an empty one) as soon as it's ready to be proven. Mock proofs are only accepted by the testnet verifier,
+    /// so this mode is intended for local and CI chains. Requires the L1 sender to send sampled proofs.
+    Mock,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ProofDataHandlerConfig {
     pub http_port: u16,
     pub proof_generation_timeout_in_secs: u16,
     pub protocol_version_loading_mode: ProtocolVersionLoadingMode,
     pub fri_protocol_version_id: u16,
+    #[serde(default)]
+    pub proof_generation_mode: ProofGenerationMode,
+    /// Whether to serve L1 batch inputs to TEE provers and accept TEE proofs, in addition to ZK proofs.
+    #[serde(default)]
+    pub tee_support: bool,
+    /// API key required from TEE provers registering attestations. If not set, attestation registration is disabled.
+    pub tee_attestation_api_key: Option<String>,
+    /// API key required from operators marking L1 batches as high-priority. If not set, batch prioritization is disabled.
+    pub prioritize_batch_api_key: Option<String>,
+    /// API keys of external provers in the `<prover_id>:<key>` format. If non-empty, the external prover API
+    /// is served, allowing the listed provers to download witness inputs and submit proofs.
+    #[serde(default)]
+    pub external_prover_api_keys: Vec<String>,
+    /// TTL of signed URLs for witness inputs served to external provers. Default is 1 hour.
+    pub external_prover_signed_url_ttl_in_secs: Option<u32>,
+    /// Maximum age of signed requests from external provers. Requests with an older timestamp are rejected,
+    /// and request nonces are retained for twice this duration. Default is 5 minutes.
+    pub external_prover_request_max_age_in_secs: Option<u32>,
 }
+
 impl ProofDataHandlerConfig {
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.proof_generation_timeout_in_secs as u64)
     }
+
+    pub fn external_prover_signed_url_ttl(&self) -> Duration {
+        Duration::from_secs(
+            self.external_prover_signed_url_ttl_in_secs
+                .unwrap_or(3_600)
+                .into(),
+        )
+    }
+
+    pub fn external_prover_request_max_age(&self) -> Duration {
+        Duration::from_secs(
+            self.external_prover_request_max_age_in_secs
+                .unwrap_or(300)
+                .into(),
+        )
+    }
 }
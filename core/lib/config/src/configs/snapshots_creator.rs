@@ -7,6 +7,12 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// Maximum number of incremental snapshots chained to a single full snapshot. An incremental snapshot
+    /// contains only storage logs and factory deps changed since the previous snapshot. Once the chain reaches
+    /// this length, the next snapshot is a full one. If set to 0 (the default), only full snapshots are created.
+    #[serde(default)]
+    pub max_incremental_snapshots: u32,
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
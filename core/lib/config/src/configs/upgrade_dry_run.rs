@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the protocol upgrade dry run, which replays recent L1 batches under a pending protocol version
+/// before the version is activated.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct UpgradeDryRunConfig {
+    /// Number of the latest sealed L1 batches replayed for each pending protocol version. The default value is 5.
+    pub l1_batches_to_replay: Option<u32>,
+    /// Interval between polling the database for pending protocol versions, in milliseconds.
+    /// The default value is 60 seconds.
+    pub polling_interval_ms: Option<u64>,
+}
+
+impl UpgradeDryRunConfig {
+    pub fn l1_batches_to_replay(&self) -> u32 {
+        self.l1_batches_to_replay.unwrap_or(5)
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms.unwrap_or(60_000))
+    }
+}
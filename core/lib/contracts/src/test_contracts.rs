@@ -1,9 +1,9 @@
 use ethabi::{ethereum_types::U256, Bytes, Token};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::get_loadnext_contract;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoadnextContractExecutionParams {
     pub reads: usize,
     pub writes: usize,
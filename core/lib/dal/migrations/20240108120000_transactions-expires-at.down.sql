@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS transactions_expires_at_idx;
+ALTER TABLE transactions DROP COLUMN IF EXISTS expires_at;
@@ -0,0 +1,3 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS transactions_expires_at_idx ON transactions (expires_at)
+    WHERE expires_at IS NOT NULL AND miniblock_number IS NULL;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS storage_logs_compaction_log (
+    compacted_l1_batch BIGINT PRIMARY KEY,
+    compacted_miniblock BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
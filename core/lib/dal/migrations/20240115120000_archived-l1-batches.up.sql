@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS archived_l1_batches (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
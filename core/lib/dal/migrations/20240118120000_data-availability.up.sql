@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS data_availability (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Identifier of the blob in the DA layer, opaque for the server.
+    blob_id TEXT NOT NULL,
+    -- Proof of the blob inclusion in the DA layer, passed to the commit transaction as the pubdata.
+    inclusion_data BYTEA,
+    sent_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
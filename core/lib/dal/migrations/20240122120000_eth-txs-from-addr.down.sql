@@ -0,0 +1 @@
+ALTER TABLE eth_txs DROP COLUMN IF EXISTS from_addr;
@@ -0,0 +1,2 @@
+-- Operator address that signs the transaction. `NULL` for transactions created before the column was added.
+ALTER TABLE eth_txs ADD COLUMN IF NOT EXISTS from_addr BYTEA;
@@ -0,0 +1,2 @@
+ALTER TABLE witness_inputs_fri DROP COLUMN IF EXISTS is_high_priority;
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS is_high_priority;
@@ -0,0 +1,3 @@
+-- High-priority L1 batches are served to provers ahead of other batches regardless of their number.
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS is_high_priority BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE witness_inputs_fri ADD COLUMN IF NOT EXISTS is_high_priority BOOLEAN NOT NULL DEFAULT FALSE;
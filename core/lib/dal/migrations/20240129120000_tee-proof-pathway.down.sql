@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS tee_proof_generation_details;
+DROP TABLE IF EXISTS tee_attestations;
+DROP TABLE IF EXISTS tee_verifier_input_producer_jobs;
+DROP TYPE IF EXISTS tee_verifier_input_producer_job_status;
@@ -0,0 +1,44 @@
+CREATE TYPE tee_verifier_input_producer_job_status AS ENUM ('Queued', 'ManuallySkipped', 'InProgress', 'Successful', 'Failed');
+
+CREATE TABLE IF NOT EXISTS tee_verifier_input_producer_jobs
+(
+    l1_batch_number       BIGINT    NOT NULL PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    attempts              SMALLINT  NOT NULL DEFAULT 0,
+    status                tee_verifier_input_producer_job_status NOT NULL,
+    picked_by             TEXT,
+    input_blob_url        TEXT,
+    error                 TEXT,
+    created_at            TIMESTAMP NOT NULL,
+    updated_at            TIMESTAMP NOT NULL,
+    processing_started_at TIMESTAMP,
+    time_taken            TIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_tee_verifier_input_producer_jobs_status_processing_attempts
+    ON tee_verifier_input_producer_jobs (status, processing_started_at, attempts);
+
+-- Public keys of TEE provers together with attestations binding the keys to the enclaves.
+CREATE TABLE IF NOT EXISTS tee_attestations
+(
+    pubkey      BYTEA     PRIMARY KEY,
+    attestation BYTEA     NOT NULL,
+    created_at  TIMESTAMP NOT NULL,
+    updated_at  TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS tee_proof_generation_details
+(
+    l1_batch_number BIGINT    PRIMARY KEY REFERENCES tee_verifier_input_producer_jobs (l1_batch_number) ON DELETE CASCADE,
+    status          TEXT      NOT NULL,
+    signature       BYTEA,
+    pubkey          BYTEA     REFERENCES tee_attestations (pubkey) ON DELETE SET NULL,
+    proof           BYTEA,
+    tee_type        TEXT,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL,
+    prover_taken_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_tee_proof_generation_details_status_prover_taken_at
+    ON tee_proof_generation_details (prover_taken_at)
+    WHERE status = 'picked_by_prover';
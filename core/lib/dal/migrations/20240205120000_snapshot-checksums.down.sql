@@ -0,0 +1,2 @@
+ALTER TABLE snapshots DROP COLUMN IF EXISTS storage_logs_checksums;
+ALTER TABLE snapshots DROP COLUMN IF EXISTS factory_deps_checksum;
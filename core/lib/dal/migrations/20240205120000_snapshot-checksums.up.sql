@@ -0,0 +1,2 @@
+ALTER TABLE snapshots ADD COLUMN storage_logs_checksums BYTEA[];
+ALTER TABLE snapshots ADD COLUMN factory_deps_checksum BYTEA;
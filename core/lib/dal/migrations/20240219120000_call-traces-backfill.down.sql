@@ -0,0 +1 @@
+DROP TABLE IF EXISTS backfilled_call_traces_l1_batches;
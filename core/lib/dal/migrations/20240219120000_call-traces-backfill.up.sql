@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS backfilled_call_traces_l1_batches (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Number of call traces saved for the batch; 0 if the batch didn't need backfilling.
+    call_traces_count INT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
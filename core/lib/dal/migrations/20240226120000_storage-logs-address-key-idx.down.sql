@@ -0,0 +1 @@
+DROP INDEX IF EXISTS storage_logs_address_key_miniblock_number_idx;
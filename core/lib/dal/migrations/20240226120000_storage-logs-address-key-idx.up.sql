@@ -0,0 +1,6 @@
+-- Used to iterate over storage slots of a contract in `debug_storageRangeAt`. The index is partial: it excludes
+-- system contracts in the kernel space (addresses up to 0xffff), which constitute the bulk of storage logs,
+-- so that it stays relatively small and takes less time to build.
+CREATE INDEX IF NOT EXISTS storage_logs_address_key_miniblock_number_idx
+    ON storage_logs (address, key, miniblock_number DESC, operation_number DESC)
+    WHERE address > '\x000000000000000000000000000000000000ffff'::bytea;
@@ -0,0 +1,3 @@
+-- Gas per pubdata byte used by the bootloader in a miniblock. `NULL` for miniblocks sealed before
+-- the value was persisted; for them, it can be derived from `l1_gas_price` and `l2_fair_gas_price`.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS gas_per_pubdata BIGINT;
@@ -0,0 +1,5 @@
+-- Hash of the L1 transaction that emitted a priority operation; used by `zks_getPriorityOpStatus`.
+-- `NULL` for L2 transactions and for priority operations stored before the hash was persisted.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS l1_tx_hash BYTEA;
+CREATE INDEX IF NOT EXISTS transactions_l1_tx_hash_idx ON transactions (l1_tx_hash)
+    WHERE l1_tx_hash IS NOT NULL;
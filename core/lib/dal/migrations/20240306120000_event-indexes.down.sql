@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS event_index_entries;
+DROP TABLE IF EXISTS event_indexes;
@@ -0,0 +1,19 @@
+-- Custom event indexes maintained by the event indexer component.
+CREATE TABLE IF NOT EXISTS event_indexes (
+    name TEXT PRIMARY KEY,
+    -- Last miniblock processed by the index; `NULL` if the index hasn't processed any miniblocks yet.
+    last_indexed_miniblock BIGINT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+-- Entries of custom event indexes. Each entry points to an event in the `events` table.
+CREATE TABLE IF NOT EXISTS event_index_entries (
+    index_name TEXT NOT NULL REFERENCES event_indexes (name) ON DELETE CASCADE,
+    key BYTEA NOT NULL,
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    event_index_in_block INT NOT NULL,
+    PRIMARY KEY (index_name, key, miniblock_number, event_index_in_block)
+);
+CREATE INDEX IF NOT EXISTS event_index_entries_miniblock_number_idx
+    ON event_index_entries (miniblock_number);
@@ -0,0 +1,3 @@
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS proof_submitted_by;
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS proof_hash;
+DROP TABLE IF EXISTS external_prover_request_nonces;
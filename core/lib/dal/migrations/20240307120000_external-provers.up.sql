@@ -0,0 +1,13 @@
+-- Nonces of signed requests made by external provers; used to reject replayed requests.
+CREATE TABLE IF NOT EXISTS external_prover_request_nonces (
+    prover_id TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (prover_id, nonce)
+);
+CREATE INDEX IF NOT EXISTS external_prover_request_nonces_created_at_idx
+    ON external_prover_request_nonces (created_at);
+
+-- Hash of the submitted proof and the external prover that submitted it (`NULL` for proofs from internal provers).
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS proof_hash BYTEA;
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS proof_submitted_by TEXT;
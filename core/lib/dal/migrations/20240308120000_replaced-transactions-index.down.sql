@@ -0,0 +1 @@
+DROP INDEX IF EXISTS replaced_transactions_initiator_address_nonce_idx;
@@ -0,0 +1,3 @@
+-- Used by the DB pruner to remove replaced transactions together with the transactions replacing them.
+CREATE INDEX IF NOT EXISTS replaced_transactions_initiator_address_nonce_idx
+    ON replaced_transactions (initiator_address, nonce);
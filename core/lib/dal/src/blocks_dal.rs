@@ -9,7 +9,7 @@ use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use sqlx::Row;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
     commitment::{L1BatchMetadata, L1BatchWithMetadata},
     Address, L1BatchNumber, LogQuery, MiniblockNumber, ProtocolVersionId, H256,
     MAX_GAS_PER_PUBDATA_BYTE, U256,
@@ -251,11 +251,13 @@ impl BlocksDal<'_, '_> {
         .await
     }
 
+    /// Returns the header of the specified L1 batch. If the pubdata input of the batch is archived,
+    /// it is read from the cold storage; see [`ColdStorageDal`](crate::cold_storage_dal::ColdStorageDal).
     pub async fn get_l1_batch_header(
         &mut self,
         number: L1BatchNumber,
     ) -> sqlx::Result<Option<L1BatchHeader>> {
-        Ok(sqlx::query_as!(
+        let header = sqlx::query_as!(
             StorageL1BatchHeader,
             r#"
             SELECT
@@ -289,8 +291,20 @@ impl BlocksDal<'_, '_> {
         .instrument("get_l1_batch_header")
         .with_arg("number", &number)
         .fetch_optional(self.storage.conn())
-        .await?
-        .map(Into::into))
+        .await?;
+
+        let Some(header) = header else {
+            return Ok(None);
+        };
+        let mut header = L1BatchHeader::from(header);
+        if header.pubdata_input.is_none() {
+            header.pubdata_input = self
+                .storage
+                .cold_storage_dal()
+                .get_archived_pubdata_input(number)
+                .await?;
+        }
+        Ok(Some(header))
     }
 
     /// Returns initial bootloader heap content for the specified L1 batch.
@@ -608,6 +622,7 @@ impl BlocksDal<'_, '_> {
                     l1_gas_price,
                     l2_fair_gas_price,
                     gas_per_pubdata_limit,
+                    gas_per_pubdata,
                     bootloader_code_hash,
                     default_aa_code_hash,
                     protocol_version,
@@ -616,7 +631,7 @@ impl BlocksDal<'_, '_> {
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW(), NOW())
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, NOW(), NOW())
             "#,
             miniblock_header.number.0 as i64,
             miniblock_header.timestamp as i64,
@@ -627,6 +642,7 @@ impl BlocksDal<'_, '_> {
             miniblock_header.l1_gas_price as i64,
             miniblock_header.l2_fair_gas_price as i64,
             MAX_GAS_PER_PUBDATA_BYTE as i64,
+            miniblock_header.gas_per_pubdata.map(|value| value as i64),
             miniblock_header
                 .base_system_contracts_hashes
                 .bootloader
@@ -736,6 +752,7 @@ impl BlocksDal<'_, '_> {
                 base_fee_per_gas,
                 l1_gas_price,
                 l2_fair_gas_price,
+                gas_per_pubdata,
                 bootloader_code_hash,
                 default_aa_code_hash,
                 protocol_version,
@@ -769,6 +786,7 @@ impl BlocksDal<'_, '_> {
                 base_fee_per_gas,
                 l1_gas_price,
                 l2_fair_gas_price,
+                gas_per_pubdata,
                 bootloader_code_hash,
                 default_aa_code_hash,
                 protocol_version,
@@ -853,6 +871,37 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Saves tree data for an L1 batch obtained without running the Merkle tree locally (e.g., from a remote
+    /// Merkle tree API). Only L1 batches without metadata are updated.
+    pub async fn save_l1_batch_tree_data(
+        &mut self,
+        number: L1BatchNumber,
+        tree_data: &L1BatchTreeData,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                hash = $1,
+                merkle_root_hash = $1,
+                rollup_last_leaf_index = $2,
+                updated_at = NOW()
+            WHERE
+                number = $3
+                AND hash IS NULL
+            "#,
+            tree_data.hash.as_bytes(),
+            tree_data.rollup_last_leaf_index as i64,
+            number.0 as i64,
+        )
+        .instrument("save_l1_batch_tree_data")
+        .with_arg("number", &number)
+        .report_latency()
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
     pub async fn save_l1_batch_metadata(
         &mut self,
         number: L1BatchNumber,
@@ -2128,6 +2177,31 @@ impl BlocksDal<'_, '_> {
         .map(|row| row.timestamp as u64))
     }
 
+    /// Returns the number of the oldest sealed L1 batch without an Ethereum commit tx.
+    pub async fn get_oldest_uncommitted_l1_batch_number(
+        &mut self,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+            WHERE
+                eth_commit_tx_id IS NULL
+                AND number > 0
+                AND is_finished = TRUE
+            ORDER BY
+                number
+            LIMIT
+                1
+            "#,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|row| L1BatchNumber(row.number as u32)))
+    }
+
     pub async fn get_batch_protocol_version_id(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -17,7 +17,7 @@ use crate::{
     models::{
         storage_block::{
             bind_block_where_sql_params, web3_block_number_to_sql, web3_block_where_sql,
-            StorageBlockDetails, StorageL1BatchDetails,
+            StorageBlockDetails, StorageL1BatchAnalytics, StorageL1BatchDetails,
         },
         storage_transaction::{extract_web3_transaction, web3_transaction_select_sql, CallTrace},
     },
@@ -441,11 +441,13 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns call traces for all transactions in the specified miniblock. If the traces are archived,
+    /// they are read from the cold storage; see [`ColdStorageDal`](crate::cold_storage_dal::ColdStorageDal).
     pub async fn get_trace_for_miniblock(
         &mut self,
         block_number: MiniblockNumber,
     ) -> sqlx::Result<Vec<Call>> {
-        Ok(sqlx::query_as!(
+        let call_traces: Vec<_> = sqlx::query_as!(
             CallTrace,
             r#"
             SELECT
@@ -468,7 +470,15 @@ impl BlocksWeb3Dal<'_, '_> {
         .await?
         .into_iter()
         .map(Call::from)
-        .collect())
+        .collect();
+
+        if !call_traces.is_empty() {
+            return Ok(call_traces);
+        }
+        self.storage
+            .cold_storage_dal()
+            .get_archived_miniblock_traces(block_number)
+            .await
     }
 
     /// Returns `base_fee_per_gas` for miniblock range [min(newest_block - block_count + 1, 0), newest_block]
@@ -623,12 +633,130 @@ impl BlocksWeb3Dal<'_, '_> {
             Ok(l1_batch_details.map(api::L1BatchDetails::from))
         }
     }
+
+    /// Returns a summary of the specified L1 batch for cost accounting. L1 costs are only returned for confirmed
+    /// L1 transactions.
+    pub async fn get_l1_batch_analytics(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<api::analytics::L1BatchAnalytics>> {
+        let Some(row) = sqlx::query_as!(
+            StorageL1BatchAnalytics,
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.timestamp,
+                l1_batches.l1_tx_count,
+                l1_batches.l2_tx_count,
+                l1_batches.pubdata_input,
+                l1_batches.created_at AS sealed_at,
+                l1_batches.predicted_commit_gas_cost,
+                l1_batches.predicted_prove_gas_cost,
+                l1_batches.predicted_execute_gas_cost,
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.base_fee_per_gas AS "commit_base_fee_per_gas?",
+                commit_tx.priority_fee_per_gas AS "commit_priority_fee_per_gas?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.base_fee_per_gas AS "prove_base_fee_per_gas?",
+                prove_tx.priority_fee_per_gas AS "prove_priority_fee_per_gas?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.base_fee_per_gas AS "execute_base_fee_per_gas?",
+                execute_tx.priority_fee_per_gas AS "execute_priority_fee_per_gas?",
+                proof_generation_details.updated_at AS "proof_generated_at?"
+            FROM
+                l1_batches
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN proof_generation_details ON (
+                    l1_batches.number = proof_generation_details.l1_batch_number
+                    AND proof_generation_details.status = 'generated'
+                )
+            WHERE
+                l1_batches.number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_analytics")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .report_latency()
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let gas_used = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(gas_limit - refunded_gas), 0) AS "gas_used!"
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_analytics#gas_used")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage.conn())
+        .await?
+        .gas_used;
+
+        let circuits = sqlx::query!(
+            r#"
+            SELECT
+                circuit_id,
+                COUNT(*) AS "count!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND aggregation_round = 0
+            GROUP BY
+                circuit_id
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_analytics#circuits")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.circuit_id as u8, row.count as u64))
+        .collect();
+
+        let pubdata_input = match &row.pubdata_input {
+            Some(pubdata_input) => Some(pubdata_input.clone()),
+            None => {
+                self.storage
+                    .cold_storage_dal()
+                    .get_archived_pubdata_input(l1_batch_number)
+                    .await?
+            }
+        };
+        Ok(Some(row.into_api(
+            bigdecimal_to_u256(gas_used),
+            pubdata_input.as_deref(),
+            circuits,
+        )))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
-        block::{MiniblockHasher, MiniblockHeader},
+        block::{BlockGasCount, L1BatchHeader, MiniblockHasher, MiniblockHeader},
         MiniblockNumber, ProtocolVersion, ProtocolVersionId,
     };
 
@@ -798,4 +926,73 @@ mod tests {
             .await;
         assert_eq!(miniblock_number.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn getting_l1_batch_analytics() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await
+            .unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.l1_tx_count = 1;
+        header.l2_tx_count = 2;
+        // No user logs, L2-to-L1 messages or published bytecodes; 10 bytes of state diffs.
+        let mut pubdata_input = vec![0; 12];
+        pubdata_input.extend([1; 10]);
+        header.pubdata_input = Some(pubdata_input);
+        let predicted_gas = BlockGasCount {
+            commit: 2,
+            prove: 3,
+            execute: 10,
+        };
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], predicted_gas, &[], &[])
+            .await
+            .unwrap();
+
+        let analytics = conn
+            .blocks_web3_dal()
+            .get_l1_batch_analytics(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no analytics for L1 batch");
+        assert_eq!(analytics.number, L1BatchNumber(1));
+        assert_eq!(analytics.timestamp, 100);
+        assert_eq!(analytics.l1_tx_count, 1);
+        assert_eq!(analytics.l2_tx_count, 2);
+        assert_eq!(analytics.gas_used, U256::zero());
+        assert_eq!(
+            analytics.pubdata,
+            Some(api::analytics::PubdataBreakdown {
+                user_logs: 4,
+                l2_to_l1_messages: 4,
+                published_bytecodes: 4,
+                state_diffs: 10,
+            })
+        );
+        assert!(analytics.circuits.is_empty());
+        // The L1 batch is not committed or proven.
+        assert_eq!(analytics.commit_cost, None);
+        assert_eq!(analytics.prove_cost, None);
+        assert_eq!(analytics.execute_cost, None);
+        assert_eq!(analytics.proof_generated_at, None);
+
+        let missing_analytics = conn
+            .blocks_web3_dal()
+            .get_l1_batch_analytics(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert!(missing_analytics.is_none());
+    }
 }
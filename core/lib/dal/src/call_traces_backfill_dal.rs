@@ -0,0 +1,202 @@
+use zksync_types::{vm_trace::Call, L1BatchNumber, H256};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// DAL persisting call traces of L1 batches originally executed without the call tracer
+/// (i.e., with call traces disabled in the state keeper config).
+///
+/// Backfilled traces are stored in the same table as the ones saved by the state keeper, so they are returned
+/// by [`TransactionsDal::get_call_trace()`] and are subject to pruning and archiving in the same way.
+///
+/// [`TransactionsDal::get_call_trace()`]: crate::transactions_dal::TransactionsDal::get_call_trace()
+#[derive(Debug)]
+pub struct CallTracesBackfillDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl CallTracesBackfillDal<'_, '_> {
+    pub async fn get_last_backfilled_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                backfilled_call_traces_l1_batches
+            "#
+        )
+        .instrument("get_last_backfilled_l1_batch")
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Checks whether call traces for any transaction in the specified L1 batch are stored in Postgres.
+    pub async fn has_call_traces(&mut self, number: L1BatchNumber) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        call_traces
+                        INNER JOIN transactions ON transactions.hash = call_traces.tx_hash
+                    WHERE
+                        transactions.l1_batch_number = $1
+                ) AS "exists!"
+            "#,
+            number.0 as i64
+        )
+        .instrument("has_call_traces")
+        .with_arg("number", &number)
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(row.exists)
+    }
+
+    /// Saves call traces for transactions in the specified L1 batch and marks the batch as backfilled.
+    /// Traces already present in Postgres are not overwritten.
+    pub async fn save_backfilled_call_traces(
+        &mut self,
+        number: L1BatchNumber,
+        call_traces: &[(H256, Call)],
+    ) -> sqlx::Result<()> {
+        let (tx_hashes, bytea_call_traces): (Vec<_>, Vec<_>) = call_traces
+            .iter()
+            .map(|(tx_hash, call_trace)| {
+                let call_trace = bincode::serialize(call_trace).unwrap();
+                (tx_hash.as_bytes().to_vec(), call_trace)
+            })
+            .unzip();
+
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                call_traces (tx_hash, call_trace)
+            SELECT
+                u.tx_hash,
+                u.call_trace
+            FROM
+                UNNEST($1::bytea[], $2::bytea[]) AS u (tx_hash, call_trace)
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            &tx_hashes,
+            &bytea_call_traces
+        )
+        .instrument("save_backfilled_call_traces#call_traces")
+        .with_arg("number", &number)
+        .with_arg("call_traces.len", &call_traces.len())
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                backfilled_call_traces_l1_batches (l1_batch_number, call_traces_count, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            number.0 as i64,
+            call_traces.len() as i32
+        )
+        .instrument("save_backfilled_call_traces#backfilled_l1_batch")
+        .with_arg("number", &number)
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId, U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    #[tokio::test]
+    async fn backfilling_call_traces() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        // The transaction is executed without a call trace.
+        let execution_results = [mock_execution_result(tx)];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &execution_results, U256::one())
+            .await;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &execution_results)
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let mut dal = conn.call_traces_backfill_dal();
+        assert_eq!(dal.get_last_backfilled_l1_batch().await.unwrap(), None);
+        assert!(!dal.has_call_traces(L1BatchNumber(1)).await.unwrap());
+        assert!(conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .is_none());
+
+        let call_trace = Call {
+            gas: 1_000,
+            ..Call::default()
+        };
+        conn.call_traces_backfill_dal()
+            .save_backfilled_call_traces(L1BatchNumber(1), &[(tx_hash, call_trace)])
+            .await
+            .unwrap();
+
+        let mut dal = conn.call_traces_backfill_dal();
+        assert_eq!(
+            dal.get_last_backfilled_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(1))
+        );
+        assert!(dal.has_call_traces(L1BatchNumber(1)).await.unwrap());
+        let call_trace = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .expect("no call trace");
+        assert_eq!(call_trace.gas, 1_000);
+    }
+}
@@ -0,0 +1,453 @@
+use std::{collections::HashMap, io};
+
+use serde::{Deserialize, Serialize};
+use zksync_object_store::{serialize_using_bincode, Bucket, ObjectStoreError, StoredObject};
+use zksync_types::{vm_trace::Call, L1BatchNumber, MiniblockNumber, H256};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Rarely accessed artifacts of an L1 batch moved from Postgres to the object store.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedL1BatchArtifacts {
+    /// Call traces of transactions in the batch, in the order of transaction execution. Traces are stored
+    /// in the same serialization format as in Postgres.
+    pub call_traces: Vec<(H256, Vec<u8>)>,
+    /// Pubdata input of the batch.
+    pub pubdata_input: Option<Vec<u8>>,
+}
+
+impl StoredObject for ArchivedL1BatchArtifacts {
+    const BUCKET: Bucket = Bucket::ArchivedL1Batches;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_{key}_artifacts.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Converts an object store error so that it can be returned from DAL methods with read-through.
+fn to_sqlx_error(err: ObjectStoreError) -> sqlx::Error {
+    sqlx::Error::Io(io::Error::new(io::ErrorKind::Other, err))
+}
+
+/// Deserializes an archived call trace. Traces are stored in the same format as in Postgres.
+fn deserialize_call_trace(call_trace: &[u8]) -> sqlx::Result<Call> {
+    bincode::deserialize(call_trace).map_err(|err| sqlx::Error::Decode(err))
+}
+
+/// DAL moving artifacts of old L1 batches (call traces and pubdata inputs) to the object store.
+///
+/// After an L1 batch is archived, its artifacts are removed from Postgres. If the connection pool
+/// is configured with the same object store (see [`ConnectionPoolBuilder::set_cold_storage()`]),
+/// DAL methods returning these artifacts (e.g., [`TransactionsDal::get_call_trace()`]
+/// or [`BlocksDal::get_l1_batch_header()`]) transparently read them from the store. Archived batches
+/// must be executed on L1, so that the artifacts are never needed by the node itself (e.g., to commit
+/// batches on L1); bulk L1 batch loaders used by the L1 sender do not read through the store.
+///
+/// Witness inputs are not archived: they are written directly to the object store (e.g., Merkle paths
+/// by the metadata calculator), and Postgres only stores their object store URLs.
+///
+/// [`ConnectionPoolBuilder::set_cold_storage()`]: crate::connection::ConnectionPoolBuilder::set_cold_storage()
+/// [`TransactionsDal::get_call_trace()`]: crate::transactions_dal::TransactionsDal::get_call_trace()
+/// [`BlocksDal::get_l1_batch_header()`]: crate::blocks_dal::BlocksDal::get_l1_batch_header()
+#[derive(Debug)]
+pub struct ColdStorageDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ColdStorageDal<'_, '_> {
+    pub async fn get_last_archived_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                archived_l1_batches
+            "#
+        )
+        .instrument("get_last_archived_l1_batch")
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Collects artifacts of the specified L1 batch stored in Postgres. Returns `None` if the batch
+    /// doesn't exist.
+    pub async fn get_l1_batch_artifacts(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<ArchivedL1BatchArtifacts>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                pubdata_input
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_l1_batch_artifacts#pubdata_input")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let call_traces = sqlx::query!(
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_traces.call_trace
+            FROM
+                call_traces
+                INNER JOIN transactions ON transactions.hash = call_traces.tx_hash
+            WHERE
+                transactions.l1_batch_number = $1
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_l1_batch_artifacts#call_traces")
+        .with_arg("number", &number)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let call_traces = call_traces
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.tx_hash), row.call_trace))
+            .collect();
+        Ok(Some(ArchivedL1BatchArtifacts {
+            call_traces,
+            pubdata_input: row.pubdata_input,
+        }))
+    }
+
+    /// Removes artifacts of the specified L1 batch from Postgres and marks the batch as archived.
+    /// The caller is responsible for putting the artifacts to the object store beforehand.
+    pub async fn mark_l1_batch_as_archived(&mut self, number: L1BatchNumber) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM call_traces USING transactions
+            WHERE
+                call_traces.tx_hash = transactions.hash
+                AND transactions.l1_batch_number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("mark_l1_batch_as_archived#call_traces")
+        .with_arg("number", &number)
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                pubdata_input = NULL,
+                updated_at = NOW()
+            WHERE
+                number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("mark_l1_batch_as_archived#pubdata_input")
+        .with_arg("number", &number)
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                archived_l1_batches (l1_batch_number, created_at, updated_at)
+            VALUES
+                ($1, NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            number.0 as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await
+    }
+
+    async fn get_archived_artifacts(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<ArchivedL1BatchArtifacts>> {
+        let Some(cold_storage) = self.storage.cold_storage.clone() else {
+            return Ok(None);
+        };
+        let artifacts: ArchivedL1BatchArtifacts =
+            cold_storage.get(number).await.map_err(to_sqlx_error)?;
+        Ok(Some(artifacts))
+    }
+
+    /// Returns the call trace for an archived transaction. Returns `None` if the transaction is not archived
+    /// or if the cold storage is not configured.
+    pub(crate) async fn get_archived_call_trace(
+        &mut self,
+        tx_hash: H256,
+    ) -> sqlx::Result<Option<Call>> {
+        if self.storage.cold_storage.is_none() {
+            return Ok(None);
+        }
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                transactions.l1_batch_number AS "l1_batch_number!"
+            FROM
+                transactions
+                INNER JOIN archived_l1_batches ON archived_l1_batches.l1_batch_number = transactions.l1_batch_number
+            WHERE
+                transactions.hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_archived_call_trace")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let l1_batch_number = L1BatchNumber(row.l1_batch_number as u32);
+        let Some(artifacts) = self.get_archived_artifacts(l1_batch_number).await? else {
+            return Ok(None);
+        };
+        artifacts
+            .call_traces
+            .into_iter()
+            .find(|(hash, _)| *hash == tx_hash)
+            .map(|(_, call_trace)| deserialize_call_trace(&call_trace))
+            .transpose()
+    }
+
+    /// Returns call traces for transactions in an archived miniblock. Returns an empty list if the miniblock
+    /// is not archived or if the cold storage is not configured.
+    pub(crate) async fn get_archived_miniblock_traces(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<Call>> {
+        if self.storage.cold_storage.is_none() {
+            return Ok(vec![]);
+        }
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash,
+                transactions.l1_batch_number AS "l1_batch_number!"
+            FROM
+                transactions
+                INNER JOIN archived_l1_batches ON archived_l1_batches.l1_batch_number = transactions.l1_batch_number
+            WHERE
+                transactions.miniblock_number = $1
+            ORDER BY
+                transactions.index_in_block
+            "#,
+            miniblock_number.0 as i64
+        )
+        .instrument("get_archived_miniblock_traces")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let Some(first_row) = rows.first() else {
+            return Ok(vec![]);
+        };
+        let l1_batch_number = L1BatchNumber(first_row.l1_batch_number as u32);
+        let Some(artifacts) = self.get_archived_artifacts(l1_batch_number).await? else {
+            return Ok(vec![]);
+        };
+        let mut call_traces: HashMap<_, _> = artifacts.call_traces.into_iter().collect();
+        rows.into_iter()
+            .filter_map(|row| call_traces.remove(&H256::from_slice(&row.hash)))
+            .map(|call_trace| deserialize_call_trace(&call_trace))
+            .collect()
+    }
+
+    /// Returns the pubdata input for an archived L1 batch. Returns `None` if the batch is not archived
+    /// or if the cold storage is not configured.
+    pub(crate) async fn get_archived_pubdata_input(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        if self.storage.cold_storage.is_none() {
+            return Ok(None);
+        }
+        let is_archived = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        archived_l1_batches
+                    WHERE
+                        l1_batch_number = $1
+                ) AS "exists!"
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_archived_pubdata_input")
+        .with_arg("number", &number)
+        .fetch_one(self.storage.conn())
+        .await?
+        .exists;
+        if !is_archived {
+            return Ok(None);
+        }
+
+        let artifacts = self.get_archived_artifacts(number).await?;
+        Ok(artifacts.and_then(|artifacts| artifacts.pubdata_input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        Address, ProtocolVersion, ProtocolVersionId, U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    #[tokio::test]
+    async fn archiving_l1_batch_artifacts() {
+        let pool = ConnectionPool::test_pool().await;
+        let cold_storage: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        let mut conn = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .with_cold_storage(Some(cold_storage.clone()));
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        header.is_finished = true;
+        header.pubdata_input = Some(vec![1, 2, 3]);
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        let mut execution_result = mock_execution_result(tx);
+        execution_result.call_traces = vec![Call::default()];
+        let execution_results = [execution_result];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &execution_results, U256::one())
+            .await;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &execution_results)
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let expected_trace = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .expect("no call trace");
+        let artifacts = conn
+            .cold_storage_dal()
+            .get_l1_batch_artifacts(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no artifacts");
+        assert_eq!(artifacts.call_traces.len(), 1);
+        assert_eq!(artifacts.call_traces[0].0, tx_hash);
+        assert_eq!(artifacts.pubdata_input, Some(vec![1, 2, 3]));
+
+        cold_storage
+            .put(L1BatchNumber(1), &artifacts)
+            .await
+            .unwrap();
+        conn.cold_storage_dal()
+            .mark_l1_batch_as_archived(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let last_archived_l1_batch = conn
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .unwrap();
+        assert_eq!(last_archived_l1_batch, Some(L1BatchNumber(1)));
+        let stored_artifacts = conn
+            .cold_storage_dal()
+            .get_l1_batch_artifacts(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no artifacts");
+        assert_eq!(stored_artifacts, ArchivedL1BatchArtifacts::default());
+
+        // Check that artifacts are transparently read from the cold storage.
+        let call_trace = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .expect("no archived call trace");
+        assert_eq!(call_trace.gas, expected_trace.gas);
+        let miniblock_traces = conn
+            .blocks_web3_dal()
+            .get_trace_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(miniblock_traces.len(), 1);
+        assert_eq!(miniblock_traces[0].gas, expected_trace.gas);
+        let header = conn
+            .blocks_dal()
+            .get_l1_batch_header(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no header");
+        assert_eq!(header.pubdata_input, Some(vec![1, 2, 3]));
+
+        // Without the cold storage, archived artifacts are not available.
+        drop(conn);
+        let mut conn = pool.access_storage().await.unwrap();
+        let call_trace = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap();
+        assert!(call_trace.is_none());
+    }
+}
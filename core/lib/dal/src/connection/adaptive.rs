@@ -0,0 +1,250 @@
+//! Adaptive sizing of connection pools.
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+
+use crate::metrics::{PoolResizeDirection, PoolResizeLabels, CONNECTION_METRICS};
+
+/// Limiter of concurrently held connections for an adaptive [`ConnectionPool`](super::ConnectionPool).
+///
+/// The underlying `sqlx` pool is created with the maximum size, and the limiter caps the number of connections
+/// that can be acquired concurrently. The cap is periodically adjusted within the configured bounds based
+/// on the average time spent waiting for the cap: it grows if the average wait exceeds the target
+/// or if all connections are in use and there are pending acquisitions, and shrinks if connections are acquired
+/// without waiting and most of them are idle.
+#[derive(Debug)]
+pub(super) struct AdaptivePoolLimiter {
+    /// Pool name used to label metrics. Metrics are not reported for unnamed pools.
+    name: Option<String>,
+    semaphore: Arc<Semaphore>,
+    size: AtomicU32,
+    min_size: u32,
+    max_size: u32,
+    target_wait: Duration,
+    /// Total wait time in microseconds since the last adjustment.
+    total_wait_us: AtomicU64,
+    /// Number of acquisitions since the last adjustment.
+    acquisitions: AtomicU64,
+    /// Number of pending acquisitions. These are not accounted in `total_wait_us` until they complete,
+    /// so they are tracked separately to grow a saturated pool.
+    waiting: AtomicU32,
+}
+
+/// Decrements the number of pending acquisitions on drop, including if the acquisition is cancelled.
+struct WaitingGuard<'a>(&'a AtomicU32);
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl AdaptivePoolLimiter {
+    pub fn new(name: Option<String>, min_size: u32, max_size: u32, target_wait: Duration) -> Self {
+        assert!(
+            min_size > 0 && min_size <= max_size,
+            "Invalid adaptive pool bounds: {min_size}..={max_size}"
+        );
+        if let Some(name) = &name {
+            CONNECTION_METRICS.pool_size_limit[name].set(min_size.into());
+        }
+        Self {
+            name,
+            semaphore: Arc::new(Semaphore::new(min_size as usize)),
+            size: AtomicU32::new(min_size),
+            min_size,
+            max_size,
+            target_wait,
+            total_wait_us: AtomicU64::new(0),
+            acquisitions: AtomicU64::new(0),
+            waiting: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the current size limit.
+    pub fn size(&self) -> u32 {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        let started_at = Instant::now();
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        let waiting_guard = WaitingGuard(&self.waiting);
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("adaptive pool semaphore is never closed");
+        drop(waiting_guard);
+        let wait = started_at.elapsed();
+        if let Some(name) = &self.name {
+            CONNECTION_METRICS.pool_limit_wait[name].observe(wait);
+        }
+        let wait_us = u64::try_from(wait.as_micros()).unwrap_or(u64::MAX);
+        self.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        permit
+    }
+
+    /// Computes the next size limit based on the average wait time, the number of connections in use
+    /// and the number of pending acquisitions.
+    fn next_size(&self, size: u32, average_wait: Duration, in_use: u32, waiting: u32) -> u32 {
+        // Acquisitions stuck waiting for a saturated pool don't contribute to `average_wait` until they complete,
+        // so the pool must grow based on them directly.
+        let is_saturated = in_use >= size && waiting > 0;
+        if average_wait > self.target_wait || is_saturated {
+            // Grow by 25%, but at least by 1 connection.
+            size.saturating_add((size / 4).max(1)).min(self.max_size)
+        } else if average_wait <= self.target_wait / 4 && in_use * 2 < size {
+            size.saturating_sub(1).max(self.min_size)
+        } else {
+            size
+        }
+    }
+
+    /// Adjusts the size limit based on the statistics collected since the previous adjustment.
+    fn adjust(&self) {
+        let total_wait_us = self.total_wait_us.swap(0, Ordering::Relaxed);
+        let acquisitions = self.acquisitions.swap(0, Ordering::Relaxed);
+        let average_wait = if acquisitions == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_micros(total_wait_us / acquisitions)
+        };
+
+        let size = self.size();
+        let available = u32::try_from(self.semaphore.available_permits()).unwrap_or(u32::MAX);
+        let in_use = size.saturating_sub(available);
+        let waiting = self.waiting.load(Ordering::Relaxed);
+        let new_size = self.next_size(size, average_wait, in_use, waiting);
+
+        let direction = if new_size > size {
+            self.semaphore.add_permits((new_size - size) as usize);
+            PoolResizeDirection::Grow
+        } else if new_size < size {
+            // Only shrink if the excess permits are not in use; otherwise, retry on the next adjustment.
+            let Ok(permits) = self.semaphore.try_acquire_many(size - new_size) else {
+                return;
+            };
+            permits.forget();
+            PoolResizeDirection::Shrink
+        } else {
+            return;
+        };
+
+        self.size.store(new_size, Ordering::Relaxed);
+        if let Some(name) = &self.name {
+            let labels = PoolResizeLabels {
+                pool: name.clone(),
+                direction,
+            };
+            CONNECTION_METRICS.pool_resizes[&labels].inc();
+            CONNECTION_METRICS.pool_size_limit[name].set(new_size.into());
+        }
+        tracing::debug!(
+            "Resized adaptive connection pool {name:?} from {size} to {new_size} connections \
+             ({in_use} in use, {waiting} waiting, {average_wait:?} average wait)",
+            name = self.name
+        );
+    }
+
+    pub async fn run_adjustments(
+        &self,
+        interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            self.adjust();
+            if tokio::time::timeout(interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, adaptive pool sizing is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_next_size() {
+        let limiter = AdaptivePoolLimiter::new(None, 2, 10, Duration::from_millis(20));
+        let high_wait = Duration::from_millis(50);
+        assert_eq!(limiter.next_size(2, high_wait, 2, 0), 3);
+        assert_eq!(limiter.next_size(8, high_wait, 8, 0), 10);
+        assert_eq!(limiter.next_size(10, high_wait, 10, 0), 10);
+
+        // Saturated pool with pending acquisitions
+        assert_eq!(limiter.next_size(4, Duration::ZERO, 4, 3), 5);
+        assert_eq!(limiter.next_size(10, Duration::ZERO, 10, 3), 10);
+        assert_eq!(limiter.next_size(4, Duration::ZERO, 4, 0), 4);
+
+        assert_eq!(limiter.next_size(5, Duration::ZERO, 1, 0), 4);
+        assert_eq!(limiter.next_size(5, Duration::ZERO, 3, 0), 5);
+        assert_eq!(limiter.next_size(2, Duration::ZERO, 0, 0), 2);
+        assert_eq!(limiter.next_size(5, Duration::from_millis(10), 1, 0), 5);
+    }
+
+    #[tokio::test]
+    async fn adjusting_size() {
+        let limiter = AdaptivePoolLimiter::new(None, 1, 3, Duration::from_millis(5));
+        let permit = limiter.acquire().await;
+        let (waiting_permit, ()) = tokio::join!(limiter.acquire(), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(permit);
+        });
+        limiter.adjust();
+        assert_eq!(limiter.size(), 2);
+
+        // The limiter shouldn't shrink while the permit is held, since half of the connections are in use.
+        limiter.adjust();
+        assert_eq!(limiter.size(), 2);
+        drop(waiting_permit);
+        limiter.adjust();
+        assert_eq!(limiter.size(), 1);
+        assert_eq!(limiter.semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn growing_saturated_pool() {
+        let limiter = Arc::new(AdaptivePoolLimiter::new(
+            Some("test".to_owned()),
+            1,
+            3,
+            Duration::from_millis(5),
+        ));
+        let permit = limiter.acquire().await;
+        let waiting_task = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                limiter.acquire().await;
+            }
+        });
+        while limiter.waiting.load(Ordering::Relaxed) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // No acquisitions have completed since the last adjustment, but the pool should still grow.
+        limiter.adjust();
+        assert_eq!(limiter.size(), 2);
+        waiting_task.await.unwrap();
+        assert_eq!(limiter.waiting.load(Ordering::Relaxed), 0);
+        drop(permit);
+    }
+}
@@ -1,14 +1,19 @@
-use std::{env, fmt, time::Duration};
+use std::{env, fmt, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use sqlx::{
     pool::PoolConnection,
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
+use tokio::sync::{watch, OwnedSemaphorePermit};
+use zksync_object_store::ObjectStore;
 
+use self::{adaptive::AdaptivePoolLimiter, replicas::ReplicaSet};
 use crate::{metrics::CONNECTION_METRICS, StorageProcessor};
 
+mod adaptive;
 pub mod holder;
+mod replicas;
 
 /// Obtains the test database URL from the environment variable.
 fn get_test_database_url() -> anyhow::Result<String> {
@@ -19,10 +24,16 @@ fn get_test_database_url() -> anyhow::Result<String> {
 }
 
 /// Builder for [`ConnectionPool`]s.
+#[derive(Clone)]
 pub struct ConnectionPoolBuilder {
+    name: Option<String>,
     database_url: String,
     max_size: u32,
     statement_timeout: Option<Duration>,
+    replica_urls: Vec<String>,
+    max_replica_lag: Duration,
+    cold_storage: Option<Arc<dyn ObjectStore>>,
+    adaptive_sizing: Option<(u32, Duration)>,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -30,13 +41,31 @@ impl fmt::Debug for ConnectionPoolBuilder {
         // Database URL is potentially sensitive, thus we omit it.
         formatter
             .debug_struct("ConnectionPoolBuilder")
+            .field("name", &self.name)
             .field("max_size", &self.max_size)
             .field("statement_timeout", &self.statement_timeout)
+            .field("replica_count", &self.replica_urls.len())
+            .field("max_replica_lag", &self.max_replica_lag)
+            .field("cold_storage", &self.cold_storage)
+            .field("adaptive_sizing", &self.adaptive_sizing)
             .finish()
     }
 }
 
 impl ConnectionPoolBuilder {
+    /// Sets the name of the built pools used to label pool size limit and saturation metrics.
+    /// These metrics are not reported for unnamed pools.
+    pub fn set_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the maximum size of the built pools.
+    pub fn set_max_size(&mut self, max_size: u32) -> &mut Self {
+        self.max_size = max_size;
+        self
+    }
+
     /// Sets the statement timeout for the pool. See [Postgres docs] for semantics.
     /// If not specified, the statement timeout will not be set.
     ///
@@ -46,30 +75,102 @@ impl ConnectionPoolBuilder {
         self
     }
 
-    /// Builds a connection pool from this builder.
-    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+    /// Sets read replicas for the pool. Read-only connections (see [`ConnectionPool::access_read_only_storage()`])
+    /// are routed to replicas with the replication lag not exceeding `max_lag`. Each replica gets a separate
+    /// connection pool with the same max size and statement timeout as the primary pool.
+    pub fn set_read_replicas(&mut self, replica_urls: Vec<String>, max_lag: Duration) -> &mut Self {
+        self.replica_urls = replica_urls;
+        self.max_replica_lag = max_lag;
+        self
+    }
+
+    /// Sets the object store with artifacts of archived L1 batches. If set, DAL methods transparently read
+    /// such artifacts from the store; see [`ColdStorageDal`](crate::cold_storage_dal::ColdStorageDal)
+    /// for details.
+    pub fn set_cold_storage(&mut self, cold_storage: Arc<dyn ObjectStore>) -> &mut Self {
+        self.cold_storage = Some(cold_storage);
+        self
+    }
+
+    /// Enables adaptive sizing for the pool. The number of concurrently held connections is limited
+    /// to a value between `min_size` and the max pool size, which is adjusted by
+    /// [`ConnectionPool::run_adaptive_sizing()`] based on the average wait time to acquire a connection
+    /// relative to `target_wait`. Read-only connections routed to replicas are not limited.
+    pub fn set_adaptive_sizing(&mut self, min_size: u32, target_wait: Duration) -> &mut Self {
+        self.adaptive_sizing = Some((min_size, target_wait));
+        self
+    }
+
+    async fn build_pg_pool(&self, database_url: &str) -> anyhow::Result<PgPool> {
         let options = PgPoolOptions::new().max_connections(self.max_size);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
+        let mut connect_options: PgConnectOptions = database_url
             .parse()
             .context("Failed parsing database URL")?;
         if let Some(timeout) = self.statement_timeout {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
+        options
             .connect_with(connect_options)
             .await
-            .context("Failed connecting to database")?;
+            .context("Failed connecting to database")
+    }
+
+    /// Builds a connection pool from this builder.
+    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+        let limiter = if let Some((min_size, target_wait)) = self.adaptive_sizing {
+            anyhow::ensure!(
+                min_size > 0 && min_size <= self.max_size,
+                "min adaptive pool size ({min_size}) must be positive and not exceed max pool size ({})",
+                self.max_size
+            );
+            Some(Arc::new(AdaptivePoolLimiter::new(
+                self.name.clone(),
+                min_size,
+                self.max_size,
+                target_wait,
+            )))
+        } else {
+            if let Some(name) = &self.name {
+                CONNECTION_METRICS.pool_size_limit[name].set(self.max_size.into());
+            }
+            None
+        };
+        let pool = self.build_pg_pool(&self.database_url).await?;
+        let mut replica_pools = Vec::with_capacity(self.replica_urls.len());
+        for (i, replica_url) in self.replica_urls.iter().enumerate() {
+            let replica_pool = self
+                .build_pg_pool(replica_url)
+                .await
+                .with_context(|| format!("failed building pool for replica #{i}"))?;
+            replica_pools.push(replica_pool);
+        }
         tracing::info!(
-            "Created pool with {max_connections} max connections \
-             and {statement_timeout:?} statement timeout",
+            "Created pool {name:?} with {max_connections} max connections, \
+             {statement_timeout:?} statement timeout, {replica_count} read replicas \
+             and adaptive sizing {adaptive_sizing:?}",
+            name = self.name,
             max_connections = self.max_size,
-            statement_timeout = self.statement_timeout
+            statement_timeout = self.statement_timeout,
+            replica_count = replica_pools.len(),
+            adaptive_sizing = self.adaptive_sizing
         );
+
+        let replicas = if replica_pools.is_empty() {
+            None
+        } else {
+            Some(Arc::new(ReplicaSet::new(
+                replica_pools,
+                self.max_replica_lag,
+            )))
+        };
         Ok(ConnectionPool {
             inner: pool,
             max_size: self.max_size,
+            replicas,
+            cold_storage: self.cold_storage.clone(),
+            limiter,
+            name: self.name.clone(),
         })
     }
 }
@@ -120,10 +221,16 @@ pub(super) async fn create_test_db() -> anyhow::Result<url::Url> {
     Ok(db_url)
 }
 
+/// Pool of connections to a Postgres database. The pool may additionally route read-only connections
+/// to Postgres replicas, see [`ConnectionPoolBuilder::set_read_replicas()`].
 #[derive(Clone)]
 pub struct ConnectionPool {
     pub(crate) inner: PgPool,
     max_size: u32,
+    replicas: Option<Arc<ReplicaSet>>,
+    cold_storage: Option<Arc<dyn ObjectStore>>,
+    limiter: Option<Arc<AdaptivePoolLimiter>>,
+    name: Option<String>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -131,6 +238,13 @@ impl fmt::Debug for ConnectionPool {
         formatter
             .debug_struct("ConnectionPool")
             .field("max_size", &self.max_size)
+            .field(
+                "replica_count",
+                &self.replicas.as_ref().map_or(0, |replicas| replicas.len()),
+            )
+            .field("cold_storage", &self.cold_storage)
+            .field("limiter", &self.limiter)
+            .field("name", &self.name)
             .finish_non_exhaustive()
     }
 }
@@ -152,9 +266,14 @@ impl ConnectionPool {
     /// Initializes a builder for connection pools.
     pub fn builder(database_url: &str, max_pool_size: u32) -> ConnectionPoolBuilder {
         ConnectionPoolBuilder {
+            name: None,
             database_url: database_url.to_string(),
             max_size: max_pool_size,
             statement_timeout: None,
+            replica_urls: Vec::new(),
+            max_replica_lag: Duration::ZERO,
+            cold_storage: None,
+            adaptive_sizing: None,
         }
     }
 
@@ -195,12 +314,90 @@ impl ConnectionPool {
         self.access_storage_inner(Some(requester)).await
     }
 
+    /// Creates a `StorageProcessor` for read-only queries. If the pool has read replicas, the connection
+    /// is acquired from one of the fresh replicas; otherwise, or if all replicas are stale or unavailable,
+    /// the connection is acquired from the primary database in the same way as in [`Self::access_storage()`].
+    ///
+    /// The caller is responsible for not performing writes using the returned processor. Since replicas
+    /// may lag behind the primary database, this method should not be used if the caller needs to read
+    /// data that it has just written.
+    pub async fn access_read_only_storage(&self) -> anyhow::Result<StorageProcessor<'_>> {
+        self.access_read_only_storage_inner(None).await
+    }
+
+    /// A version of [`Self::access_read_only_storage()`] that would also expose the duration
+    /// of the connection acquisition tagged to the `requester` name. The same caveats as for
+    /// [`Self::access_storage_tagged()`] apply.
+    pub async fn access_read_only_storage_tagged(
+        &self,
+        requester: &'static str,
+    ) -> anyhow::Result<StorageProcessor<'_>> {
+        self.access_read_only_storage_inner(Some(requester)).await
+    }
+
+    async fn access_read_only_storage_inner(
+        &self,
+        requester: Option<&'static str>,
+    ) -> anyhow::Result<StorageProcessor<'_>> {
+        let Some(replicas) = &self.replicas else {
+            return self.access_storage_inner(requester).await;
+        };
+
+        let acquire_latency = CONNECTION_METRICS.acquire.start();
+        let (conn, permit) = if let Some(conn) = replicas.acquire().await {
+            (conn, None)
+        } else {
+            self.acquire_connection_retried()
+                .await
+                .context("acquire_connection_retried()")?
+        };
+        let elapsed = acquire_latency.observe();
+        if let Some(requester) = requester {
+            CONNECTION_METRICS.acquire_tagged[&requester].observe(elapsed);
+        }
+        Ok(StorageProcessor::from_pool(conn)
+            .with_cold_storage(self.cold_storage.clone())
+            .with_guard(ConnectionGuard::new(requester, permit)))
+    }
+
+    /// Periodically checks the replication lag of read replicas of this pool, excluding stale replicas
+    /// from routing read-only connections. Until the first check completes, all read-only connections
+    /// are routed to the primary database. If the pool has no read replicas, this method is a no-op.
+    pub async fn run_replica_lag_checks(
+        self,
+        interval: Duration,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Some(replicas) = &self.replicas else {
+            return Ok(());
+        };
+        tracing::info!(
+            "Checking replication lag for {} read replicas every {interval:?}",
+            replicas.len()
+        );
+        replicas.run_lag_checks(interval, stop_receiver).await
+    }
+
+    /// Periodically adjusts the size limit of this pool if adaptive sizing is enabled
+    /// (see [`ConnectionPoolBuilder::set_adaptive_sizing()`]). Otherwise, this method is a no-op.
+    pub async fn run_adaptive_sizing(
+        self,
+        interval: Duration,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Some(limiter) = &self.limiter else {
+            return Ok(());
+        };
+        tracing::info!("Adjusting size of adaptive connection pool {limiter:?} every {interval:?}");
+        limiter.run_adjustments(interval, stop_receiver).await
+    }
+
     async fn access_storage_inner(
         &self,
         requester: Option<&'static str>,
     ) -> anyhow::Result<StorageProcessor<'_>> {
         let acquire_latency = CONNECTION_METRICS.acquire.start();
-        let conn = self
+        let (conn, permit) = self
             .acquire_connection_retried()
             .await
             .context("acquire_connection_retried()")?;
@@ -208,23 +405,31 @@ impl ConnectionPool {
         if let Some(requester) = requester {
             CONNECTION_METRICS.acquire_tagged[&requester].observe(elapsed);
         }
-        Ok(StorageProcessor::from_pool(conn))
+        Ok(StorageProcessor::from_pool(conn)
+            .with_cold_storage(self.cold_storage.clone())
+            .with_guard(ConnectionGuard::new(requester, permit)))
     }
 
-    async fn acquire_connection_retried(&self) -> anyhow::Result<PoolConnection<Postgres>> {
+    /// Acquires a connection from the primary database together with a permit from the adaptive limiter, if any.
+    async fn acquire_connection_retried(
+        &self,
+    ) -> anyhow::Result<(PoolConnection<Postgres>, Option<OwnedSemaphorePermit>)> {
         const DB_CONNECTION_RETRIES: u32 = 3;
         const BACKOFF_INTERVAL: Duration = Duration::from_secs(1);
 
+        let permit = if let Some(limiter) = &self.limiter {
+            Some(limiter.acquire().await)
+        } else {
+            None
+        };
+
         let mut retry_count = 0;
         while retry_count < DB_CONNECTION_RETRIES {
-            CONNECTION_METRICS
-                .pool_size
-                .observe(self.inner.size() as usize);
-            CONNECTION_METRICS.pool_idle.observe(self.inner.num_idle());
+            self.report_pool_stats();
 
             let connection = self.inner.acquire().await;
             let connection_err = match connection {
-                Ok(connection) => return Ok(connection),
+                Ok(connection) => return Ok((connection, permit)),
                 Err(err) => {
                     retry_count += 1;
                     err
@@ -240,7 +445,7 @@ impl ConnectionPool {
 
         // Attempting to get the pooled connection for the last time
         match self.inner.acquire().await {
-            Ok(conn) => Ok(conn),
+            Ok(conn) => Ok((conn, permit)),
             Err(err) => {
                 Self::report_connection_error(&err);
                 anyhow::bail!("Run out of retries getting a DB connection, last error: {err}");
@@ -248,11 +453,57 @@ impl ConnectionPool {
         }
     }
 
+    fn report_pool_stats(&self) {
+        let size = self.inner.size();
+        let idle = u32::try_from(self.inner.num_idle()).unwrap_or(u32::MAX);
+        CONNECTION_METRICS.pool_size.observe(size as usize);
+        CONNECTION_METRICS.pool_idle.observe(idle as usize);
+
+        let Some(name) = &self.name else {
+            return;
+        };
+        let size_limit = self
+            .limiter
+            .as_ref()
+            .map_or(self.max_size, |limiter| limiter.size());
+        let in_use = size.saturating_sub(idle);
+        let saturation = f64::from(in_use) / f64::from(size_limit.max(1));
+        CONNECTION_METRICS.pool_saturation[name].set(saturation);
+    }
+
     fn report_connection_error(err: &sqlx::Error) {
         CONNECTION_METRICS.pool_acquire_error[&err.into()].inc();
     }
 }
 
+/// Guard held by a [`StorageProcessor`] for the lifetime of a pooled connection. Reports the number
+/// of held connections per requester, and holds the permit from the adaptive pool limiter, if any.
+#[derive(Debug)]
+pub(crate) struct ConnectionGuard {
+    requester: Option<&'static str>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl ConnectionGuard {
+    fn new(requester: Option<&'static str>, permit: Option<OwnedSemaphorePermit>) -> Self {
+        if let Some(requester) = requester {
+            CONNECTION_METRICS.active_tagged[&requester].inc_by(1);
+        }
+        Self {
+            requester,
+            _permit: permit,
+        }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        if let Some(requester) = self.requester {
+            CONNECTION_METRICS.active_tagged[&requester].dec_by(1);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -283,4 +534,36 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn routing_read_only_connections_to_replicas() {
+        let db_url = create_test_db()
+            .await
+            .expect("Unable to prepare test database")
+            .to_string();
+
+        // Use the same database as a replica; its replication lag is always 0.
+        let pool = ConnectionPool::singleton(&db_url)
+            .set_read_replicas(vec![db_url.clone()], Duration::from_secs(5))
+            .build()
+            .await
+            .unwrap();
+        let replicas = pool.replicas.as_deref().unwrap();
+        assert_eq!(replicas.len(), 1);
+        // Replicas are considered stale until their lag is checked.
+        assert!(replicas.acquire().await.is_none());
+
+        replicas.check_lags().await;
+        let conn = replicas.acquire().await;
+        assert!(conn.is_some());
+        drop(conn);
+
+        let mut storage = pool.access_read_only_storage().await.unwrap();
+        let lag = storage
+            .system_dal()
+            .try_get_replication_lag_sec()
+            .await
+            .unwrap();
+        assert_eq!(lag, 0);
+    }
 }
@@ -0,0 +1,157 @@
+//! Routing of read-only connections to Postgres replicas.
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use sqlx::{
+    pool::PoolConnection,
+    postgres::{PgPool, Postgres},
+};
+use tokio::sync::watch;
+
+use crate::{metrics::REPLICA_METRICS, StorageProcessor};
+
+#[derive(Debug)]
+struct Replica {
+    /// Label of the replica used in logs and metrics. Replica URLs are potentially sensitive,
+    /// so we use replica indices instead.
+    label: String,
+    pool: PgPool,
+    is_fresh: AtomicBool,
+}
+
+impl Replica {
+    fn set_freshness(&self, is_fresh: bool) {
+        let was_fresh = self.is_fresh.swap(is_fresh, Ordering::Relaxed);
+        REPLICA_METRICS.is_fresh[&self.label].set(is_fresh.into());
+        if was_fresh && !is_fresh {
+            tracing::warn!(
+                "Replica #{} is stale or unavailable; excluding it from routing",
+                self.label
+            );
+        } else if !was_fresh && is_fresh {
+            tracing::info!("Replica #{} is fresh; including it in routing", self.label);
+        }
+    }
+
+    async fn check_lag(&self, max_lag: Duration) -> bool {
+        let conn = match self.pool.acquire().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed acquiring connection to replica #{}: {err}",
+                    self.label
+                );
+                return false;
+            }
+        };
+        let mut storage = StorageProcessor::from_pool(conn);
+        match storage.system_dal().try_get_replication_lag_sec().await {
+            Ok(lag_sec) => {
+                let lag = Duration::from_secs(lag_sec.into());
+                REPLICA_METRICS.lag[&self.label].set(lag);
+                lag <= max_lag
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed getting replication lag for replica #{}: {err}",
+                    self.label
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Set of Postgres replicas serving read-only connections for a [`ConnectionPool`](super::ConnectionPool).
+///
+/// Replicas are selected in the round-robin fashion among replicas with the replication lag not exceeding
+/// the configured limit. Replicas are considered stale until the lag is checked by
+/// [`Self::run_lag_checks()`], and become stale again if a connection to them cannot be acquired.
+#[derive(Debug)]
+pub(super) struct ReplicaSet {
+    replicas: Vec<Replica>,
+    next_replica: AtomicUsize,
+    max_lag: Duration,
+}
+
+impl ReplicaSet {
+    pub fn new(pools: Vec<PgPool>, max_lag: Duration) -> Self {
+        let replicas = pools
+            .into_iter()
+            .enumerate()
+            .map(|(i, pool)| Replica {
+                label: i.to_string(),
+                pool,
+                is_fresh: AtomicBool::new(false),
+            })
+            .collect();
+        Self {
+            replicas,
+            next_replica: AtomicUsize::new(0),
+            max_lag,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.replicas.len()
+    }
+
+    /// Acquires a connection to one of fresh replicas. Returns `None` if there are no fresh replicas,
+    /// or connections to all of them cannot be acquired.
+    pub async fn acquire(&self) -> Option<PoolConnection<Postgres>> {
+        let len = self.replicas.len();
+        if len == 0 {
+            return None;
+        }
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        for i in 0..len {
+            let replica = &self.replicas[(start + i) % len];
+            if !replica.is_fresh.load(Ordering::Relaxed) {
+                continue;
+            }
+            match replica.pool.acquire().await {
+                Ok(conn) => return Some(conn),
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed acquiring connection to replica #{}: {err}",
+                        replica.label
+                    );
+                    replica.set_freshness(false);
+                }
+            }
+        }
+        REPLICA_METRICS.primary_fallbacks.inc();
+        None
+    }
+
+    pub async fn check_lags(&self) {
+        for replica in &self.replicas {
+            let is_fresh = replica.check_lag(self.max_lag).await;
+            replica.set_freshness(is_fresh);
+        }
+    }
+
+    pub async fn run_lag_checks(
+        &self,
+        interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            self.check_lags().await;
+            if tokio::time::timeout(interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, replica lag checks are shutting down");
+        Ok(())
+    }
+}
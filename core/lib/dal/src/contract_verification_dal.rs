@@ -10,7 +10,9 @@ use zksync_types::{
         DeployContractCalldata, VerificationIncomingRequest, VerificationInfo, VerificationRequest,
         VerificationRequestStatus,
     },
-    get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
+    get_code_key,
+    vm_trace::{Call, CallType},
+    Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
 };
 
 use crate::{models::storage_verification_request::StorageVerificationRequest, StorageProcessor};
@@ -20,6 +22,16 @@ pub struct ContractVerificationDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
 }
 
+/// Finds the constructor call of the contract deployed at `address` in the call tree rooted at `call`.
+fn find_constructor_call(call: &Call, address: Address) -> Option<&Call> {
+    if matches!(call.r#type, CallType::Create) && call.to == address && call.error.is_none() {
+        return Some(call);
+    }
+    call.calls
+        .iter()
+        .find_map(|subcall| find_constructor_call(subcall, address))
+}
+
 #[derive(Debug)]
 enum Compiler {
     ZkSolc,
@@ -288,6 +300,7 @@ impl ContractVerificationDal<'_, '_> {
             r#"
             SELECT
                 factory_deps.bytecode,
+                transactions.hash AS "tx_hash?",
                 transactions.data AS "data?",
                 transactions.contract_address AS "contract_address?"
             FROM
@@ -329,7 +342,25 @@ impl ContractVerificationDal<'_, '_> {
                 let calldata = hex::decode(&calldata_str[2..]).context("invalid calldata")?;
                 DeployContractCalldata::Deploy(calldata)
             }
-            _ => DeployContractCalldata::Ignore,
+            _ => {
+                // The contract may be deployed by another contract (e.g., a factory). In this case, constructor args
+                // can be recovered from the call trace of the deployment transaction, if call traces are stored.
+                let call_trace = match row.tx_hash {
+                    Some(tx_hash) => self
+                        .storage
+                        .transactions_dal()
+                        .get_call_trace(H256::from_slice(&tx_hash))
+                        .await
+                        .context("failed getting call trace of the deployment transaction")?,
+                    None => None,
+                };
+                call_trace
+                    .as_ref()
+                    .and_then(|call| find_constructor_call(call, address))
+                    .map_or(DeployContractCalldata::Ignore, |call| {
+                        DeployContractCalldata::FactoryDeploy(call.input.clone())
+                    })
+            }
         };
         Ok(Some((row.bytecode, calldata)))
     }
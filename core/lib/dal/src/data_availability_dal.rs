@@ -0,0 +1,363 @@
+use std::ops;
+
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_types::L1BatchNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Blob with L1 batch pubdata posted to an external data availability layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataAvailabilityBlob {
+    pub l1_batch_number: L1BatchNumber,
+    /// Identifier of the blob in the DA layer. Its format is specific to the DA client.
+    pub blob_id: String,
+    /// Proof of the blob inclusion in the DA layer. `None` if the blob is not included yet.
+    pub inclusion_data: Option<Vec<u8>>,
+    pub sent_at: NaiveDateTime,
+}
+
+/// Pubdata of an L1 batch that should be posted to a DA layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1BatchPubdata {
+    pub l1_batch_number: L1BatchNumber,
+    pub pubdata: Vec<u8>,
+}
+
+/// DAL tracking L1 batch pubdata posted to an external data availability (DA) layer.
+///
+/// A batch is posted to the DA layer once it's sealed; its inclusion data is saved after the blob
+/// is included in the DA layer. In the custom pubdata sending mode, the L1 sender only commits batches
+/// that have inclusion data, which is passed to the commit transaction instead of the pubdata.
+#[derive(Debug)]
+pub struct DataAvailabilityDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DataAvailabilityDal<'_, '_> {
+    /// Records that the pubdata of the specified L1 batch was posted to the DA layer.
+    /// Does nothing if the batch is already recorded with the same blob ID.
+    pub async fn insert_l1_batch_da(
+        &mut self,
+        number: L1BatchNumber,
+        blob_id: &str,
+        sent_at: NaiveDateTime,
+    ) -> sqlx::Result<()> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                data_availability (l1_batch_number, blob_id, sent_at, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            number.0 as i64,
+            blob_id,
+            sent_at
+        )
+        .instrument("insert_l1_batch_da")
+        .with_arg("number", &number)
+        .with_arg("blob_id", &blob_id)
+        .execute(self.storage.conn())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let stored_blob_id = self.get_blob_id(number).await?;
+            if stored_blob_id.as_deref() != Some(blob_id) {
+                let err = format!(
+                    "L1 batch #{number} is already posted to the DA layer with a different blob ID: \
+                     {stored_blob_id:?}, attempted to insert {blob_id}"
+                );
+                return Err(sqlx::Error::Protocol(err));
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_blob_id(&mut self, number: L1BatchNumber) -> sqlx::Result<Option<String>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                blob_id
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_blob_id")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| row.blob_id))
+    }
+
+    /// Saves the inclusion data for the specified L1 batch. Returns an error if the batch wasn't posted
+    /// to the DA layer or has different inclusion data.
+    pub async fn save_l1_batch_inclusion_data(
+        &mut self,
+        number: L1BatchNumber,
+        inclusion_data: &[u8],
+    ) -> sqlx::Result<()> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE data_availability
+            SET
+                inclusion_data = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $2
+                AND (
+                    inclusion_data IS NULL
+                    OR inclusion_data = $1
+                )
+            "#,
+            inclusion_data,
+            number.0 as i64
+        )
+        .instrument("save_l1_batch_inclusion_data")
+        .with_arg("number", &number)
+        .execute(self.storage.conn())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        Ok(())
+    }
+
+    /// Returns the first posted blob that doesn't have inclusion data yet.
+    pub async fn get_first_da_blob_awaiting_inclusion(
+        &mut self,
+    ) -> sqlx::Result<Option<DataAvailabilityBlob>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                blob_id,
+                inclusion_data,
+                sent_at
+            FROM
+                data_availability
+            WHERE
+                inclusion_data IS NULL
+            ORDER BY
+                l1_batch_number
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_first_da_blob_awaiting_inclusion")
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| DataAvailabilityBlob {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            blob_id: row.blob_id,
+            inclusion_data: row.inclusion_data,
+            sent_at: row.sent_at,
+        }))
+    }
+
+    /// Returns pubdata of sealed L1 batches that are not posted to the DA layer yet, in the ascending order
+    /// of batch numbers.
+    pub async fn get_ready_for_da_dispatch_l1_batches(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchPubdata>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                pubdata_input
+            FROM
+                l1_batches
+                LEFT JOIN data_availability ON data_availability.l1_batch_number = l1_batches.number
+            WHERE
+                number > 0
+                AND data_availability.blob_id IS NULL
+                AND pubdata_input IS NOT NULL
+            ORDER BY
+                number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_ready_for_da_dispatch_l1_batches")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchPubdata {
+                l1_batch_number: L1BatchNumber(row.number as u32),
+                // `unwrap` is safe due to the `pubdata_input IS NOT NULL` condition
+                pubdata: row.pubdata_input.unwrap(),
+            })
+            .collect())
+    }
+
+    /// Returns inclusion data for the specified L1 batch, or `None` if the batch is not posted
+    /// to the DA layer or its blob is not included yet.
+    pub async fn get_inclusion_data(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                inclusion_data
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_inclusion_data")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.and_then(|row| row.inclusion_data))
+    }
+
+    /// Returns inclusion data for L1 batches in the specified range that have their blobs included
+    /// in the DA layer, in the ascending order of batch numbers.
+    pub async fn get_inclusion_data_for_range(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<Vec<(L1BatchNumber, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                inclusion_data AS "inclusion_data!"
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND inclusion_data IS NOT NULL
+            ORDER BY
+                l1_batch_number
+            "#,
+            numbers.start().0 as i64,
+            numbers.end().0 as i64
+        )
+        .instrument("get_inclusion_data_for_range")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    L1BatchNumber(row.l1_batch_number as u32),
+                    row.inclusion_data,
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::chrono::Utc;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        Address, ProtocolVersion, ProtocolVersionId,
+    };
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn data_availability_workflow() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let mut header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            header.pubdata_input = Some(vec![number as u8; 4]);
+            conn.blocks_dal()
+                .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+                .await
+                .unwrap();
+        }
+
+        let dal = &mut conn.data_availability_dal();
+        let ready_batches = dal.get_ready_for_da_dispatch_l1_batches(10).await.unwrap();
+        let ready_numbers: Vec<_> = ready_batches.iter().map(|b| b.l1_batch_number).collect();
+        assert_eq!(ready_numbers, [L1BatchNumber(1), L1BatchNumber(2)]);
+        assert_eq!(ready_batches[0].pubdata, [1; 4]);
+
+        let sent_at = Utc::now().naive_utc();
+        dal.insert_l1_batch_da(L1BatchNumber(1), "blob1", sent_at)
+            .await
+            .unwrap();
+        // Repeated insertion with the same blob ID should be a no-op.
+        dal.insert_l1_batch_da(L1BatchNumber(1), "blob1", sent_at)
+            .await
+            .unwrap();
+        dal.insert_l1_batch_da(L1BatchNumber(1), "other", sent_at)
+            .await
+            .unwrap_err();
+
+        let ready_batches = dal.get_ready_for_da_dispatch_l1_batches(10).await.unwrap();
+        assert_eq!(ready_batches.len(), 1);
+        assert_eq!(ready_batches[0].l1_batch_number, L1BatchNumber(2));
+
+        let awaiting_blob = dal
+            .get_first_da_blob_awaiting_inclusion()
+            .await
+            .unwrap()
+            .expect("no blob awaiting inclusion");
+        assert_eq!(awaiting_blob.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(awaiting_blob.blob_id, "blob1");
+        assert_eq!(awaiting_blob.inclusion_data, None);
+        assert_eq!(
+            dal.get_inclusion_data(L1BatchNumber(1)).await.unwrap(),
+            None
+        );
+
+        dal.save_l1_batch_inclusion_data(L1BatchNumber(1), &[42; 8])
+            .await
+            .unwrap();
+        dal.save_l1_batch_inclusion_data(L1BatchNumber(1), &[42; 8])
+            .await
+            .unwrap();
+        dal.save_l1_batch_inclusion_data(L1BatchNumber(1), &[0; 8])
+            .await
+            .unwrap_err();
+        dal.save_l1_batch_inclusion_data(L1BatchNumber(2), &[0; 8])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            dal.get_inclusion_data(L1BatchNumber(1)).await.unwrap(),
+            Some(vec![42; 8])
+        );
+        assert_eq!(
+            dal.get_first_da_blob_awaiting_inclusion().await.unwrap(),
+            None
+        );
+
+        let range = L1BatchNumber(1)..=L1BatchNumber(2);
+        let inclusion_data = dal.get_inclusion_data_for_range(range).await.unwrap();
+        assert_eq!(inclusion_data, [(L1BatchNumber(1), vec![42; 8])]);
+        let range = L1BatchNumber(2)..=L1BatchNumber(3);
+        let inclusion_data = dal.get_inclusion_data_for_range(range).await.unwrap();
+        assert_eq!(inclusion_data, []);
+    }
+}
@@ -174,6 +174,7 @@ impl EthSenderDal<'_, '_> {
         tx_type: AggregatedActionType,
         contract_address: Address,
         predicted_gas_cost: u32,
+        from_address: Address,
     ) -> sqlx::Result<EthTx> {
         let address = format!("{:#x}", contract_address);
         let eth_tx = sqlx::query_as!(
@@ -186,11 +187,12 @@ impl EthSenderDal<'_, '_> {
                     tx_type,
                     contract_address,
                     predicted_gas_cost,
+                    from_addr,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, $4, $5, NOW(), NOW())
+                ($1, $2, $3, $4, $5, $6, NOW(), NOW())
             RETURNING
                 *
             "#,
@@ -198,7 +200,8 @@ impl EthSenderDal<'_, '_> {
             nonce as i64,
             tx_type.to_string(),
             address,
-            predicted_gas_cost as i64
+            predicted_gas_cost as i64,
+            from_address.as_bytes()
         )
         .fetch_one(self.storage.conn())
         .await?;
@@ -510,24 +513,156 @@ impl EthSenderDal<'_, '_> {
         Ok(history_item.map(|tx| tx.into()))
     }
 
-    pub async fn get_next_nonce(&mut self) -> sqlx::Result<Option<u64>> {
+    /// Returns the nonce following the nonce of the last transaction signed by the specified operator address.
+    pub async fn get_next_nonce(&mut self, from_address: Address) -> sqlx::Result<Option<u64>> {
         let row = sqlx::query!(
             r#"
             SELECT
                 nonce
             FROM
                 eth_txs
+            WHERE
+                from_addr = $1
             ORDER BY
                 id DESC
             LIMIT
                 1
-            "#
+            "#,
+            from_address.as_bytes()
         )
         .fetch_optional(self.storage.conn())
         .await?;
         Ok(row.map(|row| row.nonce as u64 + 1))
     }
 
+    /// Returns transactions created before operator addresses were tracked that have been sent at least once,
+    /// together with hashes of all their send attempts (most recent first). Signers of these transactions
+    /// can be recovered from L1 and set via [`Self::set_from_addr()`].
+    pub async fn get_sent_txs_without_from_addr(
+        &mut self,
+    ) -> anyhow::Result<Vec<(u32, Vec<H256>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs.id,
+                ARRAY_AGG(
+                    eth_txs_history.tx_hash
+                    ORDER BY
+                        eth_txs_history.id DESC
+                ) AS "tx_hashes!"
+            FROM
+                eth_txs
+                JOIN eth_txs_history ON eth_txs.id = eth_txs_history.eth_tx_id
+            WHERE
+                eth_txs.from_addr IS NULL
+            GROUP BY
+                eth_txs.id
+            ORDER BY
+                eth_txs.id
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let tx_hashes = row
+                    .tx_hashes
+                    .iter()
+                    .map(|tx_hash| H256::from_str(tx_hash.trim_start_matches("0x")))
+                    .collect::<Result<_, _>>()
+                    .context("invalid tx_hash")?;
+                Ok((row.id as u32, tx_hashes))
+            })
+            .collect()
+    }
+
+    /// Sets the operator address for a transaction created before operator addresses were tracked.
+    /// Transactions with an already set operator address are not updated.
+    pub async fn set_from_addr(
+        &mut self,
+        eth_tx_id: u32,
+        from_address: Address,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                from_addr = $2,
+                updated_at = NOW()
+            WHERE
+                id = $1
+                AND from_addr IS NULL
+            "#,
+            eth_tx_id as i32,
+            from_address.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Hands over transactions that were never sent to the specified operator address if any of them
+    /// is signed by a different address (i.e., the operator address was rotated) or has an unknown signer. All unsent transactions
+    /// are renumbered sequentially starting from `first_nonce`, preserving their order.
+    ///
+    /// Returns the number of reassigned transactions; 0 if all unsent transactions are already signed
+    /// by `from_address`.
+    pub async fn reassign_unsent_txs(
+        &mut self,
+        from_address: Address,
+        first_nonce: u64,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                nonce = renumbered.nonce,
+                from_addr = $1,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        id,
+                        $2 + ROW_NUMBER() OVER (
+                            ORDER BY
+                                id
+                        ) - 1 AS nonce
+                    FROM
+                        eth_txs
+                    WHERE
+                        id > (
+                            SELECT
+                                COALESCE(MAX(eth_tx_id), 0)
+                            FROM
+                                eth_txs_history
+                        )
+                ) AS renumbered
+            WHERE
+                eth_txs.id = renumbered.id
+                AND EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        eth_txs
+                    WHERE
+                        id > (
+                            SELECT
+                                COALESCE(MAX(eth_tx_id), 0)
+                            FROM
+                                eth_txs_history
+                        )
+                        AND from_addr IS DISTINCT FROM $1
+                )
+            "#,
+            from_address.as_bytes(),
+            first_nonce as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
@@ -0,0 +1,392 @@
+use std::ops;
+
+use zksync_types::{api::Log, Address, MiniblockNumber, H256};
+
+use crate::{instrument::InstrumentExt, models::storage_event::StorageWeb3Log, StorageProcessor};
+
+/// Entry of a custom event index pointing to an event in the `events` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventIndexEntry {
+    /// Key the event is indexed by (e.g., an account address).
+    pub key: Vec<u8>,
+    pub miniblock_number: MiniblockNumber,
+    pub event_index_in_block: u32,
+}
+
+/// DAL for custom event indexes. An index maps keys to events matching a certain filter,
+/// so that querying events by a key doesn't require scanning the `events` table.
+#[derive(Debug)]
+pub struct EventIndexesDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl EventIndexesDal<'_, '_> {
+    /// Registers an index if necessary and returns the last miniblock processed by it.
+    pub async fn register_index(&mut self, name: &str) -> sqlx::Result<Option<MiniblockNumber>> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                event_indexes (name, last_indexed_miniblock, created_at, updated_at)
+            VALUES
+                ($1, NULL, NOW(), NOW())
+            ON CONFLICT (name) DO NOTHING
+            "#,
+            name
+        )
+        .instrument("register_index#insert")
+        .with_arg("name", &name)
+        .execute(self.storage.conn())
+        .await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_indexed_miniblock
+            FROM
+                event_indexes
+            WHERE
+                name = $1
+            "#,
+            name
+        )
+        .instrument("register_index#select")
+        .with_arg("name", &name)
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(row
+            .last_indexed_miniblock
+            .map(|number| MiniblockNumber(number as u32)))
+    }
+
+    /// Returns events with the specified first topic emitted in the specified miniblocks, optionally filtered
+    /// by the emitting contract.
+    pub async fn get_events_for_indexing(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        address: Option<Address>,
+        topic: H256,
+    ) -> sqlx::Result<Vec<Log>> {
+        let address = address.as_ref().map(Address::as_bytes);
+        let db_logs = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                events.address AS "address!",
+                events.topic1 AS "topic1!",
+                events.topic2 AS "topic2!",
+                events.topic3 AS "topic3!",
+                events.topic4 AS "topic4!",
+                events.value AS "value!",
+                events.miniblock_number AS "miniblock_number!",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                events.tx_hash AS "tx_hash!",
+                events.tx_index_in_block AS "tx_index_in_block!",
+                events.event_index_in_block AS "event_index_in_block!",
+                events.event_index_in_tx AS "event_index_in_tx!"
+            FROM
+                events
+                INNER JOIN miniblocks ON events.miniblock_number = miniblocks.number
+            WHERE
+                events.miniblock_number BETWEEN $1 AND $2
+                AND events.topic1 = $3
+                AND (
+                    $4::bytea IS NULL
+                    OR events.address = $4
+                )
+            ORDER BY
+                events.miniblock_number ASC,
+                events.event_index_in_block ASC
+            "#,
+            miniblocks.start().0 as i64,
+            miniblocks.end().0 as i64,
+            topic.as_bytes(),
+            address
+        )
+        .instrument("get_events_for_indexing")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("address", &address)
+        .with_arg("topic", &topic)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(db_logs.into_iter().map(Into::into).collect())
+    }
+
+    /// Saves entries of the specified index and advances the index to `last_indexed_miniblock`.
+    /// Entries that are already present are skipped.
+    pub async fn save_index_entries(
+        &mut self,
+        name: &str,
+        entries: &[EventIndexEntry],
+        last_indexed_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        let mut keys = Vec::with_capacity(entries.len());
+        let mut miniblock_numbers = Vec::with_capacity(entries.len());
+        let mut event_indexes = Vec::with_capacity(entries.len());
+        for entry in entries {
+            keys.push(entry.key.as_slice());
+            miniblock_numbers.push(entry.miniblock_number.0 as i64);
+            event_indexes.push(entry.event_index_in_block as i32);
+        }
+
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                event_index_entries (index_name, key, miniblock_number, event_index_in_block)
+            SELECT
+                $1,
+                u.key,
+                u.miniblock_number,
+                u.event_index_in_block
+            FROM
+                UNNEST($2::bytea[], $3::BIGINT[], $4::INT[]) AS u (key, miniblock_number, event_index_in_block)
+            ON CONFLICT DO NOTHING
+            "#,
+            name,
+            &keys as &[&[u8]],
+            &miniblock_numbers,
+            &event_indexes
+        )
+        .instrument("save_index_entries#entries")
+        .with_arg("name", &name)
+        .with_arg("entries.len", &entries.len())
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE event_indexes
+            SET
+                last_indexed_miniblock = $2,
+                updated_at = NOW()
+            WHERE
+                name = $1
+            "#,
+            name,
+            last_indexed_miniblock.0 as i64
+        )
+        .instrument("save_index_entries#cursor")
+        .with_arg("name", &name)
+        .with_arg("last_indexed_miniblock", &last_indexed_miniblock)
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await
+    }
+
+    /// Returns at most `limit` events indexed by `key` in the specified index, starting from `from_miniblock`.
+    /// Events are ordered by their position in the chain.
+    pub async fn get_indexed_logs(
+        &mut self,
+        name: &str,
+        key: &[u8],
+        from_miniblock: MiniblockNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<Log>> {
+        let db_logs = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                events.address AS "address!",
+                events.topic1 AS "topic1!",
+                events.topic2 AS "topic2!",
+                events.topic3 AS "topic3!",
+                events.topic4 AS "topic4!",
+                events.value AS "value!",
+                events.miniblock_number AS "miniblock_number!",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                events.tx_hash AS "tx_hash!",
+                events.tx_index_in_block AS "tx_index_in_block!",
+                events.event_index_in_block AS "event_index_in_block!",
+                events.event_index_in_tx AS "event_index_in_tx!"
+            FROM
+                event_index_entries
+                INNER JOIN events ON events.miniblock_number = event_index_entries.miniblock_number
+                AND events.event_index_in_block = event_index_entries.event_index_in_block
+                INNER JOIN miniblocks ON events.miniblock_number = miniblocks.number
+            WHERE
+                event_index_entries.index_name = $1
+                AND event_index_entries.key = $2
+                AND event_index_entries.miniblock_number >= $3
+            ORDER BY
+                event_index_entries.miniblock_number ASC,
+                event_index_entries.event_index_in_block ASC
+            LIMIT
+                $4
+            "#,
+            name,
+            key,
+            from_miniblock.0 as i64,
+            limit as i64
+        )
+        .instrument("get_indexed_logs")
+        .with_arg("name", &name)
+        .with_arg("from_miniblock", &from_miniblock)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(db_logs.into_iter().map(Into::into).collect())
+    }
+
+    /// Removes index entries for miniblocks after `last_miniblock_to_keep` and rewinds indexes accordingly.
+    pub async fn rollback_indexes(
+        &mut self,
+        last_miniblock_to_keep: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM event_index_entries
+            WHERE
+                miniblock_number > $1
+            "#,
+            last_miniblock_to_keep.0 as i64
+        )
+        .instrument("rollback_indexes#entries")
+        .with_arg("last_miniblock_to_keep", &last_miniblock_to_keep)
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE event_indexes
+            SET
+                last_indexed_miniblock = $1,
+                updated_at = NOW()
+            WHERE
+                last_indexed_miniblock > $1
+            "#,
+            last_miniblock_to_keep.0 as i64
+        )
+        .instrument("rollback_indexes#cursors")
+        .with_arg("last_miniblock_to_keep", &last_miniblock_to_keep)
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{tx::IncludedTxLocation, L1BatchNumber, ProtocolVersion, VmEvent};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    const TOPIC: H256 = H256([0xee; 32]);
+
+    fn create_vm_event(index: u8, topic: H256) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), u32::from(index)),
+            address: Address::repeat_byte(index % 2),
+            indexed_topics: vec![topic, H256::repeat_byte(index)],
+            value: vec![index],
+        }
+    }
+
+    async fn prepare_events(conn: &mut StorageProcessor<'_>) {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let location = IncludedTxLocation {
+            tx_hash: H256([1; 32]),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let events = [
+            create_vm_event(0, TOPIC),
+            create_vm_event(1, H256::zero()),
+            create_vm_event(2, TOPIC),
+            create_vm_event(3, TOPIC),
+        ];
+        for number in [1, 2] {
+            let events = vec![(location, events.iter().collect())];
+            conn.events_dal()
+                .save_events(MiniblockNumber(number), &events)
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn maintaining_event_index() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        prepare_events(&mut conn).await;
+
+        let mut dal = conn.event_indexes_dal();
+        assert_eq!(dal.register_index("test").await.unwrap(), None);
+        let range = MiniblockNumber(1)..=MiniblockNumber(2);
+        let logs = dal
+            .get_events_for_indexing(range.clone(), None, TOPIC)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 6);
+        let logs = dal
+            .get_events_for_indexing(range, Some(Address::repeat_byte(1)), TOPIC)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+        assert!(logs.iter().all(|log| log.data.0 == [3]));
+
+        let entries: Vec<_> = [1, 2]
+            .into_iter()
+            .map(|number| EventIndexEntry {
+                key: vec![42],
+                miniblock_number: MiniblockNumber(number),
+                event_index_in_block: 2,
+            })
+            .collect();
+        dal.save_index_entries("test", &entries, MiniblockNumber(2))
+            .await
+            .unwrap();
+        // Saving entries is idempotent.
+        dal.save_index_entries("test", &entries, MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.register_index("test").await.unwrap(),
+            Some(MiniblockNumber(2))
+        );
+
+        let logs = dal
+            .get_indexed_logs("test", &[42], MiniblockNumber(0), 10)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].block_number, Some(1.into()));
+        assert_eq!(logs[1].block_number, Some(2.into()));
+        assert!(logs.iter().all(|log| log.data.0 == [2]));
+        let logs = dal
+            .get_indexed_logs("test", &[42], MiniblockNumber(2), 10)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        let logs = dal
+            .get_indexed_logs("test", &[1], MiniblockNumber(0), 10)
+            .await
+            .unwrap();
+        assert!(logs.is_empty());
+
+        dal.rollback_indexes(MiniblockNumber(1)).await.unwrap();
+        assert_eq!(
+            dal.register_index("test").await.unwrap(),
+            Some(MiniblockNumber(1))
+        );
+        let logs = dal
+            .get_indexed_logs("test", &[42], MiniblockNumber(0), 10)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+    }
+}
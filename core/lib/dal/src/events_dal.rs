@@ -35,6 +35,17 @@ impl EventsDal<'_, '_> {
         block_number: MiniblockNumber,
         all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
     ) {
+        self.save_events_bulk(&[(block_number, all_block_events)])
+            .await
+            .unwrap();
+    }
+
+    /// Saves events for multiple miniblocks using a single `COPY` statement. This is more efficient
+    /// than calling [`Self::save_events()`] for each miniblock when importing many miniblocks at once.
+    pub async fn save_events_bulk(
+        &mut self,
+        events_by_block: &[(MiniblockNumber, &[(IncludedTxLocation, Vec<&VmEvent>)])],
+    ) -> sqlx::Result<()> {
         let mut copy = self
             .storage
             .conn()
@@ -48,46 +59,48 @@ impl EventsDal<'_, '_> {
                 )
                 FROM STDIN WITH (DELIMITER '|')",
             )
-            .await
-            .unwrap();
+            .await?;
 
         let mut buffer = String::new();
         let now = Utc::now().naive_utc().to_string();
-        let mut event_index_in_block = 0_u32;
-        for (tx_location, events) in all_block_events {
-            let IncludedTxLocation {
-                tx_hash,
-                tx_index_in_miniblock,
-                tx_initiator_address,
-            } = tx_location;
+        for &(block_number, all_block_events) in events_by_block {
+            let mut event_index_in_block = 0_u32;
+            for (tx_location, events) in all_block_events {
+                let IncludedTxLocation {
+                    tx_hash,
+                    tx_index_in_miniblock,
+                    tx_initiator_address,
+                } = tx_location;
 
-            for (event_index_in_tx, event) in events.iter().enumerate() {
-                write_str!(
-                    &mut buffer,
-                    r"{block_number}|\\x{tx_hash:x}|{tx_index_in_miniblock}|\\x{address:x}|",
-                    address = event.address
-                );
-                write_str!(&mut buffer, "{event_index_in_block}|{event_index_in_tx}|");
-                write_str!(
-                    &mut buffer,
-                    r"\\x{topic0:x}|\\x{topic1:x}|\\x{topic2:x}|\\x{topic3:x}|",
-                    topic0 = EventTopic(event.indexed_topics.get(0)),
-                    topic1 = EventTopic(event.indexed_topics.get(1)),
-                    topic2 = EventTopic(event.indexed_topics.get(2)),
-                    topic3 = EventTopic(event.indexed_topics.get(3))
-                );
-                writeln_str!(
-                    &mut buffer,
-                    r"\\x{value}|\\x{tx_initiator_address:x}|{now}|{now}",
-                    value = hex::encode(&event.value)
-                );
+                for (event_index_in_tx, event) in events.iter().enumerate() {
+                    write_str!(
+                        &mut buffer,
+                        r"{block_number}|\\x{tx_hash:x}|{tx_index_in_miniblock}|\\x{address:x}|",
+                        address = event.address
+                    );
+                    write_str!(&mut buffer, "{event_index_in_block}|{event_index_in_tx}|");
+                    write_str!(
+                        &mut buffer,
+                        r"\\x{topic0:x}|\\x{topic1:x}|\\x{topic2:x}|\\x{topic3:x}|",
+                        topic0 = EventTopic(event.indexed_topics.get(0)),
+                        topic1 = EventTopic(event.indexed_topics.get(1)),
+                        topic2 = EventTopic(event.indexed_topics.get(2)),
+                        topic3 = EventTopic(event.indexed_topics.get(3))
+                    );
+                    writeln_str!(
+                        &mut buffer,
+                        r"\\x{value}|\\x{tx_initiator_address:x}|{now}|{now}",
+                        value = hex::encode(&event.value)
+                    );
 
-                event_index_in_block += 1;
+                    event_index_in_block += 1;
+                }
             }
         }
-        copy.send(buffer.as_bytes()).await.unwrap();
+        copy.send(buffer.as_bytes()).await?;
         // note: all the time spent in this function is spent in `copy.finish()`
-        copy.finish().await.unwrap();
+        copy.finish().await?;
+        Ok(())
     }
 
     /// Removes events with a block number strictly greater than the specified `block_number`.
@@ -299,6 +312,56 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn storing_events_in_bulk() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let location = IncludedTxLocation {
+            tx_hash: H256([1; 32]),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let first_events = vec![create_vm_event(0, 1), create_vm_event(1, 2)];
+        let first_block_events = [(location, first_events.iter().collect::<Vec<_>>())];
+        let second_events = vec![create_vm_event(2, 3)];
+        let second_block_events = [(location, second_events.iter().collect::<Vec<_>>())];
+        conn.events_dal()
+            .save_events_bulk(&[
+                (MiniblockNumber(1), &first_block_events[..]),
+                (MiniblockNumber(2), &second_block_events[..]),
+            ])
+            .await
+            .unwrap();
+
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        let log_positions: Vec<_> = logs
+            .iter()
+            .map(|log| (log.block_number.unwrap(), log.log_index.unwrap()))
+            .collect();
+        assert_eq!(
+            log_positions,
+            [
+                (1_u64.into(), 0_u64.into()),
+                (1_u64.into(), 1_u64.into()),
+                (2_u64.into(), 0_u64.into())
+            ]
+        );
+    }
+
     fn create_l2_to_l1_log(tx_number_in_block: u16, index: u8) -> UserL2ToL1Log {
         UserL2ToL1Log(L2ToL1Log {
             shard_id: 0,
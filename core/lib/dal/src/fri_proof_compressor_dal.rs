@@ -95,6 +95,15 @@ impl FriProofCompressorDal<'_, '_> {
                     WHERE
                         status = $2
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = proof_compression_jobs_fri.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         l1_batch_number ASC
                     LIMIT
                         1
@@ -259,6 +268,7 @@ impl FriProofCompressorDal<'_, '_> {
             in_progress: results.remove("in_progress").unwrap_or(0i64) as usize,
             failed: results.remove("failed").unwrap_or(0i64) as usize,
             successful: results.remove("successful").unwrap_or(0i64) as usize,
+            quarantined: results.remove("quarantined").unwrap_or(0i64) as usize,
         }
     }
 
@@ -2,7 +2,10 @@ use std::{collections::HashMap, convert::TryFrom, time::Duration};
 
 use zksync_types::{
     basic_fri_types::CircuitIdRoundTuple,
-    proofs::{AggregationRound, FriProverJobMetadata, JobCountStatistics, StuckJobs},
+    proofs::{
+        AggregationRound, FriProverJobInfo, FriProverJobMetadata, JobCountStatistics,
+        JobRetryBackoff, StuckJobs,
+    },
     protocol_version::FriProtocolVersionId,
     L1BatchNumber,
 };
@@ -72,6 +75,15 @@ impl FriProverDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = prover_jobs_fri.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         aggregation_round DESC,
                         l1_batch_number ASC,
                         id ASC
@@ -152,12 +164,30 @@ impl FriProverDal<'_, '_> {
                                 AND pj.circuit_id = tuple.circuit_id
                                 AND pj.aggregation_round = tuple.round
                             ORDER BY
+                                EXISTS (
+                                    SELECT
+                                        1
+                                    FROM
+                                        witness_inputs_fri
+                                    WHERE
+                                        witness_inputs_fri.l1_batch_number = pj.l1_batch_number
+                                        AND witness_inputs_fri.is_high_priority
+                                ) DESC,
                                 pj.l1_batch_number ASC,
                                 pj.id ASC
                             LIMIT
                                 1
                         ) AS pj ON TRUE
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = pj.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         pj.l1_batch_number ASC,
                         pj.aggregation_round DESC,
                         pj.id ASC
@@ -285,8 +315,11 @@ impl FriProverDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
+        retry_backoff: JobRetryBackoff,
     ) -> Vec<StuckJobs> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let backoff_base = pg_interval_from_duration(retry_backoff.base);
+        let backoff_max = pg_interval_from_duration(retry_backoff.max);
         {
             sqlx::query!(
                 r#"
@@ -315,6 +348,10 @@ impl FriProverDal<'_, '_> {
                             OR (
                                 status = 'failed'
                                 AND attempts < $2
+                                AND updated_at <= NOW() - LEAST(
+                                    $3::INTERVAL * POWER(2, LEAST(attempts - 1, 16)),
+                                    $4::INTERVAL
+                                )
                             )
                         FOR UPDATE
                             SKIP LOCKED
@@ -326,6 +363,8 @@ impl FriProverDal<'_, '_> {
                 "#,
                 &processing_timeout,
                 max_attempts as i32,
+                &backoff_base,
+                &backoff_max,
             )
             .fetch_all(self.storage.conn())
             .await
@@ -340,6 +379,39 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Moves failed prover jobs that have exhausted `max_attempts` to the `quarantined` status. Quarantined jobs
+    /// are not retried automatically, so that a malformed job doesn't occupy provers in a crash loop;
+    /// they can be released by the operator with [`Self::requeue_prover_job()`]
+    /// or [`Self::requeue_quarantined_jobs()`].
+    pub async fn quarantine_failed_jobs(&mut self, max_attempts: u32) -> Vec<StuckJobs> {
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                status = 'failed'
+                AND attempts >= $1
+            RETURNING
+                id,
+                status,
+                attempts
+            "#,
+            max_attempts as i32,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| StuckJobs {
+            id: row.id as u64,
+            status: row.status,
+            attempts: row.attempts as u64,
+        })
+        .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn insert_prover_job(
         &mut self,
@@ -431,12 +503,14 @@ impl FriProverDal<'_, '_> {
                             in_progress: 0,
                             failed: 0,
                             successful: 0,
+                            quarantined: 0,
                         });
                     match status.as_ref() {
                         "queued" => stats.queued = value,
                         "in_progress" => stats.in_progress = value,
                         "failed" => stats.failed = value,
                         "successful" => stats.successful = value,
+                        "quarantined" => stats.quarantined = value,
                         _ => (),
                     }
                     acc
@@ -456,7 +530,7 @@ impl FriProverDal<'_, '_> {
                 FROM
                     prover_jobs_fri
                 WHERE
-                    status IN ('queued', 'in_gpu_proof', 'in_progress', 'failed')
+                    status IN ('queued', 'in_gpu_proof', 'in_progress', 'failed', 'quarantined')
                 GROUP BY
                     circuit_id,
                     aggregation_round
@@ -521,6 +595,141 @@ impl FriProverDal<'_, '_> {
         .unwrap();
     }
 
+    pub async fn get_prover_jobs_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Vec<FriProverJobInfo> {
+        sqlx::query!(
+            r#"
+            SELECT
+                id,
+                l1_batch_number,
+                circuit_id,
+                aggregation_round,
+                sequence_number,
+                depth,
+                status,
+                attempts,
+                error,
+                picked_by,
+                created_at,
+                updated_at,
+                processing_started_at,
+                time_taken
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                aggregation_round ASC,
+                circuit_id ASC,
+                depth ASC,
+                sequence_number ASC
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| FriProverJobInfo {
+            id: row.id as u32,
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            circuit_id: row.circuit_id as u8,
+            aggregation_round: AggregationRound::try_from(row.aggregation_round as i32).unwrap(),
+            sequence_number: row.sequence_number as usize,
+            depth: row.depth as u16,
+            status: row.status,
+            attempts: row.attempts as u8,
+            error: row.error,
+            picked_by: row.picked_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            processing_started_at: row.processing_started_at,
+            time_taken: row.time_taken,
+        })
+        .collect()
+    }
+
+    /// Requeues an unfinished prover job resetting its attempts. Returns an error if the job doesn't exist
+    /// or is already finished.
+    pub async fn requeue_prover_job(&mut self, id: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'queued',
+                attempts = 0,
+                error = NULL,
+                updated_at = NOW(),
+                processing_started_at = NULL
+            WHERE
+                id = $1
+                AND status IN ('queued', 'in_progress', 'in_gpu_proof', 'failed', 'quarantined')
+            "#,
+            id as i64,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected()
+        .eq(&1)
+        .then_some(())
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Requeues all quarantined prover jobs for the specified L1 batch resetting their attempts.
+    /// Returns the number of requeued jobs.
+    pub async fn requeue_quarantined_jobs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'queued',
+                attempts = 0,
+                error = NULL,
+                updated_at = NOW(),
+                processing_started_at = NULL
+            WHERE
+                l1_batch_number = $1
+                AND status = 'quarantined'
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Marks an unfinished prover job as failed with the maximum number of attempts, so that it's not retried.
+    /// A prover that is currently processing the job is not interrupted. Returns an error if the job doesn't exist
+    /// or is already finished.
+    pub async fn abort_prover_job(&mut self, id: u32, max_attempts: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'failed',
+                error = 'Aborted by operator',
+                attempts = GREATEST(attempts, $2),
+                updated_at = NOW()
+            WHERE
+                id = $1
+                AND status IN ('queued', 'in_progress', 'in_gpu_proof', 'failed')
+            "#,
+            id as i64,
+            max_attempts as i16,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected()
+        .eq(&1)
+        .then_some(())
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
     pub async fn save_successful_sent_proof(&mut self, l1_batch_number: L1BatchNumber) {
         sqlx::query!(
             r#"
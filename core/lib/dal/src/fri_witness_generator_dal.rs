@@ -3,7 +3,7 @@ use std::{collections::HashMap, convert::TryFrom, time::Duration};
 use sqlx::Row;
 use zksync_types::{
     proofs::{
-        AggregationRound, JobCountStatistics, LeafAggregationJobMetadata,
+        AggregationRound, JobCountStatistics, JobRetryBackoff, LeafAggregationJobMetadata,
         NodeAggregationJobMetadata, StuckJobs,
     },
     protocol_version::FriProtocolVersionId,
@@ -33,6 +33,8 @@ pub enum FriWitnessJobStatus {
     InProgress,
     #[strum(serialize = "queued")]
     Queued,
+    #[strum(serialize = "quarantined")]
+    Quarantined,
 }
 
 impl FriWitnessGeneratorDal<'_, '_> {
@@ -41,6 +43,7 @@ impl FriWitnessGeneratorDal<'_, '_> {
         block_number: L1BatchNumber,
         object_key: &str,
         protocol_version_id: FriProtocolVersionId,
+        is_high_priority: bool,
     ) {
         sqlx::query!(
             r#"
@@ -49,17 +52,19 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     l1_batch_number,
                     merkle_tree_paths_blob_url,
                     protocol_version,
+                    is_high_priority,
                     status,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, 'queued', NOW(), NOW())
+                ($1, $2, $3, $4, 'queued', NOW(), NOW())
             ON CONFLICT (l1_batch_number) DO NOTHING
             "#,
             block_number.0 as i64,
             object_key,
             protocol_version_id as i32,
+            is_high_priority,
         )
         .fetch_optional(self.storage.conn())
         .await
@@ -93,6 +98,7 @@ impl FriWitnessGeneratorDal<'_, '_> {
                         AND status = 'queued'
                         AND protocol_version = ANY ($2)
                     ORDER BY
+                        is_high_priority DESC,
                         l1_batch_number ASC
                     LIMIT
                         1
@@ -112,6 +118,55 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
     }
 
+    /// Marks the basic witness generation job for the specified L1 batch as high-priority, so that it's picked
+    /// ahead of other jobs. Returns an error if the job doesn't exist.
+    pub async fn mark_witness_job_as_high_priority(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE witness_inputs_fri
+            SET
+                is_high_priority = TRUE,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected()
+        .eq(&1)
+        .then_some(())
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Returns the age of the oldest queued basic witness generation job, or `None` if there are no queued jobs.
+    pub async fn get_oldest_queued_basic_witness_job_age(
+        &mut self,
+    ) -> sqlx::Result<Option<Duration>> {
+        let age_secs = sqlx::query!(
+            r#"
+            SELECT
+                EXTRACT(
+                    epoch
+                    FROM
+                        NOW() - MIN(created_at)
+                )::BIGINT AS "age_secs"
+            FROM
+                witness_inputs_fri
+            WHERE
+                status = 'queued'
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .age_secs;
+        Ok(age_secs.map(|secs| Duration::from_secs(secs.max(0) as u64)))
+    }
+
     pub async fn get_basic_circuit_witness_job_attempts(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -240,8 +295,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
+        retry_backoff: JobRetryBackoff,
     ) -> Vec<StuckJobs> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let backoff_base = pg_interval_from_duration(retry_backoff.base);
+        let backoff_max = pg_interval_from_duration(retry_backoff.max);
         sqlx::query!(
             r#"
             UPDATE witness_inputs_fri
@@ -263,6 +321,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 OR (
                     status = 'failed'
                     AND attempts < $2
+                    AND updated_at <= NOW() - LEAST(
+                        $3::INTERVAL * POWER(2, LEAST(attempts - 1, 16)),
+                        $4::INTERVAL
+                    )
                 )
             RETURNING
                 l1_batch_number,
@@ -271,6 +333,40 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             &processing_timeout,
             max_attempts as i32,
+            &backoff_base,
+            &backoff_max,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| StuckJobs {
+            id: row.l1_batch_number as u64,
+            status: row.status,
+            attempts: row.attempts as u64,
+        })
+        .collect()
+    }
+
+    /// Moves failed basic witness generation jobs that have exhausted `max_attempts` to the `quarantined` status.
+    /// Quarantined jobs are not retried automatically; they can be released by the operator
+    /// with [`Self::requeue_quarantined_jobs()`].
+    pub async fn quarantine_failed_jobs(&mut self, max_attempts: u32) -> Vec<StuckJobs> {
+        sqlx::query!(
+            r#"
+            UPDATE witness_inputs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                status = 'failed'
+                AND attempts >= $1
+            RETURNING
+                l1_batch_number,
+                status,
+                attempts
+            "#,
+            max_attempts as i32,
         )
         .fetch_all(self.storage.conn())
         .await
@@ -410,6 +506,15 @@ impl FriWitnessGeneratorDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = leaf_aggregation_witness_jobs_fri.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         l1_batch_number ASC,
                         id ASC
                     LIMIT
@@ -597,6 +702,15 @@ impl FriWitnessGeneratorDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = node_aggregation_witness_jobs_fri.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         l1_batch_number ASC,
                         depth ASC,
                         id ASC
@@ -830,8 +944,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
+        retry_backoff: JobRetryBackoff,
     ) -> Vec<StuckJobs> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let backoff_base = pg_interval_from_duration(retry_backoff.base);
+        let backoff_max = pg_interval_from_duration(retry_backoff.max);
         sqlx::query!(
             r#"
             UPDATE leaf_aggregation_witness_jobs_fri
@@ -848,6 +965,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 OR (
                     status = 'failed'
                     AND attempts < $2
+                    AND updated_at <= NOW() - LEAST(
+                        $3::INTERVAL * POWER(2, LEAST(attempts - 1, 16)),
+                        $4::INTERVAL
+                    )
                 )
             RETURNING
                 id,
@@ -856,6 +977,43 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             &processing_timeout,
             max_attempts as i32,
+            &backoff_base,
+            &backoff_max,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| StuckJobs {
+            id: row.id as u64,
+            status: row.status,
+            attempts: row.attempts as u64,
+        })
+        .collect()
+    }
+
+    /// Moves failed leaf aggregation jobs that have exhausted `max_attempts` to the `quarantined` status.
+    /// Quarantined jobs are not retried automatically; they can be released by the operator
+    /// with [`Self::requeue_quarantined_jobs()`].
+    pub async fn quarantine_failed_leaf_aggregations_jobs(
+        &mut self,
+        max_attempts: u32,
+    ) -> Vec<StuckJobs> {
+        sqlx::query!(
+            r#"
+            UPDATE leaf_aggregation_witness_jobs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                status = 'failed'
+                AND attempts >= $1
+            RETURNING
+                id,
+                status,
+                attempts
+            "#,
+            max_attempts as i32,
         )
         .fetch_all(self.storage.conn())
         .await
@@ -873,8 +1031,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
+        retry_backoff: JobRetryBackoff,
     ) -> Vec<StuckJobs> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let backoff_base = pg_interval_from_duration(retry_backoff.base);
+        let backoff_max = pg_interval_from_duration(retry_backoff.max);
         sqlx::query!(
             r#"
             UPDATE node_aggregation_witness_jobs_fri
@@ -891,6 +1052,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 OR (
                     status = 'failed'
                     AND attempts < $2
+                    AND updated_at <= NOW() - LEAST(
+                        $3::INTERVAL * POWER(2, LEAST(attempts - 1, 16)),
+                        $4::INTERVAL
+                    )
                 )
             RETURNING
                 id,
@@ -899,6 +1064,43 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             &processing_timeout,
             max_attempts as i32,
+            &backoff_base,
+            &backoff_max,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| StuckJobs {
+            id: row.id as u64,
+            status: row.status,
+            attempts: row.attempts as u64,
+        })
+        .collect()
+    }
+
+    /// Moves failed node aggregation jobs that have exhausted `max_attempts` to the `quarantined` status.
+    /// Quarantined jobs are not retried automatically; they can be released by the operator
+    /// with [`Self::requeue_quarantined_jobs()`].
+    pub async fn quarantine_failed_node_aggregations_jobs(
+        &mut self,
+        max_attempts: u32,
+    ) -> Vec<StuckJobs> {
+        sqlx::query!(
+            r#"
+            UPDATE node_aggregation_witness_jobs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                status = 'failed'
+                AND attempts >= $1
+            RETURNING
+                id,
+                status,
+                attempts
+            "#,
+            max_attempts as i32,
         )
         .fetch_all(self.storage.conn())
         .await
@@ -934,8 +1136,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
+        retry_backoff: JobRetryBackoff,
     ) -> Vec<StuckJobs> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let backoff_base = pg_interval_from_duration(retry_backoff.base);
+        let backoff_max = pg_interval_from_duration(retry_backoff.max);
         sqlx::query!(
             r#"
             UPDATE scheduler_witness_jobs_fri
@@ -952,6 +1157,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 OR (
                     status = 'failed'
                     AND attempts < $2
+                    AND updated_at <= NOW() - LEAST(
+                        $3::INTERVAL * POWER(2, LEAST(attempts - 1, 16)),
+                        $4::INTERVAL
+                    )
                 )
             RETURNING
                 l1_batch_number,
@@ -960,6 +1169,40 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             &processing_timeout,
             max_attempts as i32,
+            &backoff_base,
+            &backoff_max,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| StuckJobs {
+            id: row.l1_batch_number as u64,
+            status: row.status,
+            attempts: row.attempts as u64,
+        })
+        .collect()
+    }
+
+    /// Moves failed scheduler jobs that have exhausted `max_attempts` to the `quarantined` status.
+    /// Quarantined jobs are not retried automatically; they can be released by the operator
+    /// with [`Self::requeue_quarantined_jobs()`].
+    pub async fn quarantine_failed_scheduler_jobs(&mut self, max_attempts: u32) -> Vec<StuckJobs> {
+        sqlx::query!(
+            r#"
+            UPDATE scheduler_witness_jobs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                status = 'failed'
+                AND attempts >= $1
+            RETURNING
+                l1_batch_number,
+                status,
+                attempts
+            "#,
+            max_attempts as i32,
         )
         .fetch_all(self.storage.conn())
         .await
@@ -998,6 +1241,15 @@ impl FriWitnessGeneratorDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                witness_inputs_fri.l1_batch_number = scheduler_witness_jobs_fri.l1_batch_number
+                                AND witness_inputs_fri.is_high_priority
+                        ) DESC,
                         l1_batch_number ASC
                     LIMIT
                         1
@@ -1106,7 +1358,38 @@ impl FriWitnessGeneratorDal<'_, '_> {
             in_progress: results.remove("in_progress").unwrap_or(0i64) as usize,
             failed: results.remove("failed").unwrap_or(0i64) as usize,
             successful: results.remove("successful").unwrap_or(0i64) as usize,
+            quarantined: results.remove("quarantined").unwrap_or(0i64) as usize,
+        }
+    }
+
+    /// Requeues all quarantined witness generation jobs (for all aggregation rounds) for the specified L1 batch
+    /// resetting their attempts. Returns the number of requeued jobs.
+    pub async fn requeue_quarantined_jobs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        let mut requeued_jobs = 0;
+        for aggregation_round in [
+            AggregationRound::BasicCircuits,
+            AggregationRound::LeafAggregation,
+            AggregationRound::NodeAggregation,
+            AggregationRound::Scheduler,
+        ] {
+            let table_name = Self::input_table_name_for(aggregation_round);
+            let sql = format!(
+                r#"
+                UPDATE {table_name}
+                SET status = 'queued', attempts = 0, error = NULL, updated_at = NOW(), processing_started_at = NULL
+                WHERE l1_batch_number = $1 AND status = 'quarantined'
+                "#
+            );
+            let result = sqlx::query(&sql)
+                .bind(l1_batch_number.0 as i64)
+                .execute(self.storage.conn())
+                .await?;
+            requeued_jobs += result.rows_affected();
         }
+        Ok(requeued_jobs)
     }
 
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
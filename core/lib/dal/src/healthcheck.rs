@@ -7,6 +7,8 @@ use crate::ConnectionPool;
 struct ConnectionPoolHealthDetails {
     pool_size: u32,
     max_size: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 impl ConnectionPoolHealthDetails {
@@ -14,6 +16,7 @@ impl ConnectionPoolHealthDetails {
         Self {
             pool_size: pool.inner.size(),
             max_size: pool.max_size(),
+            error: None,
         }
     }
 }
@@ -41,8 +44,11 @@ impl CheckHealth for ConnectionPoolHealthCheck {
     async fn check_health(&self) -> Health {
         // This check is rather feeble, plan to make reliable here:
         // https://linear.app/matterlabs/issue/PLA-255/revamp-db-connection-health-check
-        self.connection_pool.access_storage().await.unwrap();
-        let details = ConnectionPoolHealthDetails::new(&self.connection_pool);
+        let mut details = ConnectionPoolHealthDetails::new(&self.connection_pool);
+        if let Err(err) = self.connection_pool.access_storage().await {
+            details.error = Some(format!("{err:#}"));
+            return Health::from(HealthStatus::Unhealthy).with_details(details);
+        }
         Health::from(HealthStatus::Ready).with_details(details)
     }
 }
@@ -8,6 +8,7 @@ use sqlx::{
     FromRow, IntoArguments, Postgres,
 };
 use tokio::time::{Duration, Instant};
+use tracing::Instrument as _;
 
 use crate::metrics::REQUEST_METRICS;
 
@@ -114,6 +115,8 @@ impl<'a> InstrumentedData<'a> {
             report_latency,
         } = self;
         let started_at = Instant::now();
+        let span = tracing::info_span!("dal_query", query = name);
+        let query_future = query_future.instrument(span);
         tokio::pin!(query_future);
 
         let mut is_slow = false;
@@ -167,6 +170,8 @@ impl<'a> InstrumentedData<'a> {
 ///   included in the case of a slow query, plus the error info.
 /// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
 ///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
+/// - Query execution is wrapped in a `dal_query` span with the query name, so that queries are included
+///   into exported traces.
 #[derive(Debug)]
 pub(crate) struct Instrumented<'a, Q> {
     query: Q,
@@ -1,25 +1,32 @@
 #![allow(clippy::derive_partial_eq_without_eq, clippy::format_push_string)]
 
+use std::sync::Arc;
+
 use sqlx::{pool::PoolConnection, postgres::Postgres, Connection, PgConnection, Transaction};
 pub use sqlx::{types::BigDecimal, Error as SqlxError};
+use zksync_object_store::ObjectStore;
 
 pub use crate::connection::ConnectionPool;
 use crate::{
     accounts_dal::AccountsDal, basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
-    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, connection::holder::ConnectionHolder,
-    consensus_dal::ConsensusDal, contract_verification_dal::ContractVerificationDal,
-    eth_sender_dal::EthSenderDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
-    fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
+    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
+    call_traces_backfill_dal::CallTracesBackfillDal, cold_storage_dal::ColdStorageDal,
+    connection::holder::ConnectionHolder, consensus_dal::ConsensusDal,
+    contract_verification_dal::ContractVerificationDal, data_availability_dal::DataAvailabilityDal,
+    eth_sender_dal::EthSenderDal, event_indexes_dal::EventIndexesDal, events_dal::EventsDal,
+    events_web3_dal::EventsWeb3Dal, fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
     fri_witness_generator_dal::FriWitnessGeneratorDal, gpu_prover_queue_dal::GpuProverQueueDal,
     proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, prover_dal::ProverDal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_dal::StorageDal, storage_logs_dal::StorageLogsDal,
+    pruning_dal::PruningDal, snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    storage_dal::StorageDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
+    sync_dal::SyncDal, system_dal::SystemDal, tee_proof_generation_dal::TeeProofGenerationDal,
+    tee_verifier_input_producer_dal::TeeVerifierInputProducerDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
     transactions_web3_dal::TransactionsWeb3Dal,
 };
@@ -30,10 +37,14 @@ pub mod accounts_dal;
 pub mod basic_witness_input_producer_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod call_traces_backfill_dal;
+pub mod cold_storage_dal;
 pub mod connection;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
+pub mod data_availability_dal;
 pub mod eth_sender_dal;
+pub mod event_indexes_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod fri_gpu_prover_queue_dal;
@@ -51,6 +62,7 @@ pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
 pub mod prover_dal;
+pub mod pruning_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -60,6 +72,8 @@ pub mod storage_logs_dedup_dal;
 pub mod storage_web3_dal;
 pub mod sync_dal;
 pub mod system_dal;
+pub mod tee_proof_generation_dal;
+pub mod tee_verifier_input_producer_dal;
 pub mod time_utils;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
@@ -76,6 +90,11 @@ mod tests;
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Store with artifacts of archived L1 batches; see [`ColdStorageDal`] for details.
+    cold_storage: Option<Arc<dyn ObjectStore>>,
+    /// Guard for a connection acquired from a [`ConnectionPool`]. Not set for transactions, since the guard
+    /// is held by the parent processor.
+    _guard: Option<connection::ConnectionGuard>,
 }
 
 impl<'a> StorageProcessor<'a> {
@@ -83,6 +102,7 @@ impl<'a> StorageProcessor<'a> {
         let transaction = self.conn().begin().await?;
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.cold_storage = self.cold_storage.clone();
         Ok(processor)
     }
 
@@ -95,6 +115,8 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            cold_storage: None,
+            _guard: None,
         }
     }
 
@@ -113,9 +135,21 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            cold_storage: None,
+            _guard: None,
         }
     }
 
+    pub(crate) fn with_cold_storage(mut self, cold_storage: Option<Arc<dyn ObjectStore>>) -> Self {
+        self.cold_storage = cold_storage;
+        self
+    }
+
+    pub(crate) fn with_guard(mut self, guard: connection::ConnectionGuard) -> Self {
+        self._guard = Some(guard);
+        self
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
@@ -147,6 +181,14 @@ impl<'a> StorageProcessor<'a> {
         BlocksWeb3Dal { storage: self }
     }
 
+    pub fn call_traces_backfill_dal(&mut self) -> CallTracesBackfillDal<'_, 'a> {
+        CallTracesBackfillDal { storage: self }
+    }
+
+    pub fn cold_storage_dal(&mut self) -> ColdStorageDal<'_, 'a> {
+        ColdStorageDal { storage: self }
+    }
+
     pub fn consensus_dal(&mut self) -> ConsensusDal<'_, 'a> {
         ConsensusDal { storage: self }
     }
@@ -155,6 +197,10 @@ impl<'a> StorageProcessor<'a> {
         EthSenderDal { storage: self }
     }
 
+    pub fn event_indexes_dal(&mut self) -> EventIndexesDal<'_, 'a> {
+        EventIndexesDal { storage: self }
+    }
+
     pub fn events_dal(&mut self) -> EventsDal<'_, 'a> {
         EventsDal { storage: self }
     }
@@ -229,6 +275,14 @@ impl<'a> StorageProcessor<'a> {
         ProofGenerationDal { storage: self }
     }
 
+    pub fn tee_proof_generation_dal(&mut self) -> TeeProofGenerationDal<'_, 'a> {
+        TeeProofGenerationDal { storage: self }
+    }
+
+    pub fn tee_verifier_input_producer_dal(&mut self) -> TeeVerifierInputProducerDal<'_, 'a> {
+        TeeVerifierInputProducerDal { storage: self }
+    }
+
     pub fn fri_gpu_prover_queue_dal(&mut self) -> FriGpuProverQueueDal<'_, 'a> {
         FriGpuProverQueueDal { storage: self }
     }
@@ -256,4 +310,12 @@ impl<'a> StorageProcessor<'a> {
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn data_availability_dal(&mut self) -> DataAvailabilityDal<'_, 'a> {
+        DataAvailabilityDal { storage: self }
+    }
 }
@@ -3,7 +3,7 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics,
 };
 
@@ -71,6 +71,20 @@ impl From<&sqlx::Error> for ConnectionErrorKind {
 
 const POOL_SIZE_BUCKETS: Buckets = Buckets::linear(0.0..=100.0, 10.0);
 
+/// Direction of an adaptive pool resize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum PoolResizeDirection {
+    Grow,
+    Shrink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct PoolResizeLabels {
+    pub pool: String,
+    pub direction: PoolResizeDirection,
+}
+
 /// Connection-related metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "sql_connection")]
@@ -89,7 +103,41 @@ pub(crate) struct ConnectionMetrics {
     pub pool_idle: Histogram<usize>,
     /// Number of errors occurred when acquiring a DB connection.
     pub pool_acquire_error: Family<ConnectionErrorKind, Counter>,
+    /// Number of DB connections currently held, tagged with the requester label.
+    #[metrics(labels = ["requester"])]
+    pub active_tagged: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Ratio of DB connections in use to the current pool size limit, as of the latest connection acquisition.
+    /// Only reported for named pools.
+    #[metrics(labels = ["pool"])]
+    pub pool_saturation: LabeledFamily<String, Gauge<f64>>,
+    /// Current pool size limit. For adaptive pools, the limit is adjusted between the configured bounds.
+    /// Only reported for named pools.
+    #[metrics(labels = ["pool"])]
+    pub pool_size_limit: LabeledFamily<String, Gauge<u64>>,
+    /// Latency of waiting for the adaptive pool size limit when acquiring a DB connection.
+    /// Only reported for named pools.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["pool"])]
+    pub pool_limit_wait: LabeledFamily<String, Histogram<Duration>>,
+    /// Number of adaptive pool resizes. Only reported for named pools.
+    pub pool_resizes: Family<PoolResizeLabels, Counter>,
 }
 
 #[vise::register]
 pub(crate) static CONNECTION_METRICS: vise::Global<ConnectionMetrics> = vise::Global::new();
+
+/// Metrics related to routing read-only queries to Postgres replicas.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "sql_replica")]
+pub(crate) struct ReplicaMetrics {
+    /// Replication lag of a replica as of the last check.
+    #[metrics(labels = ["replica"])]
+    pub lag: LabeledFamily<String, Gauge<Duration>>,
+    /// Whether a replica is considered fresh enough to serve read-only queries (1) or not (0).
+    #[metrics(labels = ["replica"])]
+    pub is_fresh: LabeledFamily<String, Gauge<u64>>,
+    /// Number of read-only connections routed to the primary database because no replica was available.
+    pub primary_fallbacks: Counter,
+}
+
+#[vise::register]
+pub(crate) static REPLICA_METRICS: vise::Global<ReplicaMetrics> = vise::Global::new();
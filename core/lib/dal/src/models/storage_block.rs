@@ -1,4 +1,4 @@
-use std::{convert::TryInto, str::FromStr};
+use std::{collections::BTreeMap, convert::TryInto, str::FromStr};
 
 use bigdecimal::{BigDecimal, ToPrimitive};
 use sqlx::{
@@ -13,7 +13,7 @@ use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     commitment::{L1BatchMetaParameters, L1BatchMetadata},
     l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
-    Address, L1BatchNumber, MiniblockNumber, H2048, H256,
+    Address, L1BatchNumber, MiniblockNumber, H2048, H256, U256,
 };
 
 #[derive(Debug, Error)]
@@ -498,6 +498,87 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
     }
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageL1BatchAnalytics {
+    pub number: i64,
+    pub timestamp: i64,
+    pub l1_tx_count: i32,
+    pub l2_tx_count: i32,
+    pub pubdata_input: Option<Vec<u8>>,
+    pub sealed_at: NaiveDateTime,
+    pub predicted_commit_gas_cost: i64,
+    pub predicted_prove_gas_cost: i64,
+    pub predicted_execute_gas_cost: i64,
+    pub commit_tx_hash: Option<String>,
+    pub commit_base_fee_per_gas: Option<i64>,
+    pub commit_priority_fee_per_gas: Option<i64>,
+    pub prove_tx_hash: Option<String>,
+    pub prove_base_fee_per_gas: Option<i64>,
+    pub prove_priority_fee_per_gas: Option<i64>,
+    pub execute_tx_hash: Option<String>,
+    pub execute_base_fee_per_gas: Option<i64>,
+    pub execute_priority_fee_per_gas: Option<i64>,
+    pub proof_generated_at: Option<NaiveDateTime>,
+}
+
+impl StorageL1BatchAnalytics {
+    fn stage_cost(
+        tx_hash: Option<String>,
+        predicted_gas: i64,
+        base_fee_per_gas: Option<i64>,
+        priority_fee_per_gas: Option<i64>,
+    ) -> Option<api::analytics::L1BatchStageCost> {
+        let tx_hash = H256::from_str(&tx_hash?).expect("Incorrect L1 tx hash");
+        let gas_price = base_fee_per_gas? as u64 + priority_fee_per_gas? as u64;
+        Some(api::analytics::L1BatchStageCost::new(
+            tx_hash,
+            predicted_gas as u64,
+            gas_price,
+        ))
+    }
+
+    /// Converts this model to the API representation. `pubdata_input` is passed separately since it may be loaded
+    /// from the cold storage.
+    pub(crate) fn into_api(
+        self,
+        gas_used: U256,
+        pubdata_input: Option<&[u8]>,
+        circuits: BTreeMap<u8, u64>,
+    ) -> api::analytics::L1BatchAnalytics {
+        api::analytics::L1BatchAnalytics {
+            number: L1BatchNumber(self.number as u32),
+            timestamp: self.timestamp as u64,
+            l1_tx_count: self.l1_tx_count as usize,
+            l2_tx_count: self.l2_tx_count as usize,
+            gas_used,
+            pubdata: pubdata_input.and_then(api::analytics::PubdataBreakdown::parse),
+            circuits,
+            commit_cost: Self::stage_cost(
+                self.commit_tx_hash,
+                self.predicted_commit_gas_cost,
+                self.commit_base_fee_per_gas,
+                self.commit_priority_fee_per_gas,
+            ),
+            prove_cost: Self::stage_cost(
+                self.prove_tx_hash,
+                self.predicted_prove_gas_cost,
+                self.prove_base_fee_per_gas,
+                self.prove_priority_fee_per_gas,
+            ),
+            execute_cost: Self::stage_cost(
+                self.execute_tx_hash,
+                self.predicted_execute_gas_cost,
+                self.execute_base_fee_per_gas,
+                self.execute_priority_fee_per_gas,
+            ),
+            sealed_at: DateTime::<Utc>::from_naive_utc_and_offset(self.sealed_at, Utc),
+            proof_generated_at: self
+                .proof_generated_at
+                .map(|generated_at| DateTime::<Utc>::from_naive_utc_and_offset(generated_at, Utc)),
+        }
+    }
+}
+
 pub struct StorageMiniblockHeader {
     pub number: i64,
     pub timestamp: i64,
@@ -509,6 +590,7 @@ pub struct StorageMiniblockHeader {
     // L1 gas price assumed in the corresponding batch
     pub l2_fair_gas_price: i64,
     // L2 gas price assumed in the corresponding batch
+    pub gas_per_pubdata: Option<i64>,
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
     pub protocol_version: Option<i32>,
@@ -531,6 +613,7 @@ impl From<StorageMiniblockHeader> for MiniblockHeader {
             base_fee_per_gas: row.base_fee_per_gas.to_u64().unwrap(),
             l1_gas_price: row.l1_gas_price as u64,
             l2_fair_gas_price: row.l2_fair_gas_price as u64,
+            gas_per_pubdata: row.gas_per_pubdata.map(|value| value as u64),
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 row.bootloader_code_hash,
                 row.default_aa_code_hash,
@@ -23,7 +23,7 @@ use zksync_types::{
 };
 use zksync_utils::bigdecimal_to_u256;
 
-use crate::BigDecimal;
+use crate::{transactions_dal::EXPIRED_TX_ERROR, BigDecimal};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageTransaction {
@@ -70,6 +70,8 @@ pub struct StorageTransaction {
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+
+    pub l1_tx_hash: Option<Vec<u8>>,
 }
 
 impl From<StorageTransaction> for L1TxCommonData {
@@ -142,7 +144,10 @@ impl From<StorageTransaction> for L1TxCommonData {
                 .map(bigdecimal_to_u256)
                 .unwrap_or_else(|| U256::from(1u32)),
             deadline_block: 0,
-            eth_hash: Default::default(),
+            eth_hash: tx
+                .l1_tx_hash
+                .map(|hash| H256::from_slice(&hash))
+                .unwrap_or_default(),
             eth_block: tx.l1_block_number.unwrap_or_default() as u64,
             canonical_tx_hash,
         }
@@ -422,7 +427,9 @@ pub struct StorageTransactionDetails {
 
 impl StorageTransactionDetails {
     fn get_transaction_status(&self) -> TransactionStatus {
-        if self.error.is_some() {
+        if self.miniblock_number.is_none() && self.error.as_deref() == Some(EXPIRED_TX_ERROR) {
+            TransactionStatus::Expired
+        } else if self.error.is_some() {
             TransactionStatus::Failed
         } else if self.eth_execute_tx_hash.is_some() {
             TransactionStatus::Verified
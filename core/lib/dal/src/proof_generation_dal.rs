@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use strum::{Display, EnumString};
-use zksync_types::L1BatchNumber;
+use zksync_types::{L1BatchNumber, H256};
 
 use crate::{time_utils::pg_interval_from_duration, SqlxError, StorageProcessor};
 
@@ -22,13 +22,27 @@ enum ProofGenerationJobStatus {
     Skipped,
 }
 
+/// State of the proof for an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1BatchProofState {
+    /// The batch is not proven yet.
+    Pending,
+    /// The batch is proven, or its proof generation was skipped.
+    Proven {
+        /// Hash of the proof. Only set for proofs submitted by external provers.
+        proof_hash: Option<H256>,
+    },
+}
+
 impl ProofGenerationDal<'_, '_> {
+    /// Picks the next L1 batch to be proven. High-priority batches are picked first; other batches
+    /// are picked in the order of their numbers. Returns the batch number and whether the batch is high-priority.
     pub async fn get_next_block_to_be_proven(
         &mut self,
         processing_timeout: Duration,
-    ) -> Option<L1BatchNumber> {
+    ) -> Option<(L1BatchNumber, bool)> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
-        let result: Option<L1BatchNumber> = sqlx::query!(
+        let result = sqlx::query!(
             r#"
             UPDATE proof_generation_details
             SET
@@ -48,6 +62,7 @@ impl ProofGenerationDal<'_, '_> {
                             AND prover_taken_at < NOW() - $1::INTERVAL
                         )
                     ORDER BY
+                        is_high_priority DESC,
                         l1_batch_number ASC
                     LIMIT
                         1
@@ -55,24 +70,32 @@ impl ProofGenerationDal<'_, '_> {
                         SKIP LOCKED
                 )
             RETURNING
-                proof_generation_details.l1_batch_number
+                proof_generation_details.l1_batch_number,
+                proof_generation_details.is_high_priority
             "#,
             &processing_timeout,
         )
         .fetch_optional(self.storage.conn())
         .await
         .unwrap()
-        .map(|row| L1BatchNumber(row.l1_batch_number as u32));
+        .map(|row| {
+            (
+                L1BatchNumber(row.l1_batch_number as u32),
+                row.is_high_priority,
+            )
+        });
 
         result
     }
 
+    /// Saves a proof submitted by an internal prover. Returns `false` if the L1 batch is already proven
+    /// (e.g., by an external prover), in which case the state is not changed.
     pub async fn save_proof_artifacts_metadata(
         &mut self,
         block_number: L1BatchNumber,
         proof_blob_url: &str,
-    ) -> Result<(), SqlxError> {
-        sqlx::query!(
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
             r#"
             UPDATE proof_generation_details
             SET
@@ -81,16 +104,14 @@ impl ProofGenerationDal<'_, '_> {
                 updated_at = NOW()
             WHERE
                 l1_batch_number = $2
+                AND status IN ('ready_to_be_proven', 'picked_by_prover')
             "#,
             proof_blob_url,
             block_number.0 as i64,
         )
         .execute(self.storage.conn())
-        .await?
-        .rows_affected()
-        .eq(&1)
-        .then_some(())
-        .ok_or(sqlx::Error::RowNotFound)
+        .await?;
+        Ok(result.rows_affected() == 1)
     }
 
     pub async fn insert_proof_generation_details(
@@ -138,6 +159,31 @@ impl ProofGenerationDal<'_, '_> {
         .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Marks the proof generation job for the specified L1 batch as high-priority, so that it's served
+    /// to provers ahead of other jobs.
+    pub async fn mark_proof_generation_job_as_high_priority(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            r#"
+            UPDATE proof_generation_details
+            SET
+                is_high_priority = TRUE,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected()
+        .eq(&1)
+        .then_some(())
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
     pub async fn get_oldest_unpicked_batch(&mut self) -> Option<L1BatchNumber> {
         let result: Option<L1BatchNumber> = sqlx::query!(
             r#"
@@ -183,4 +229,145 @@ impl ProofGenerationDal<'_, '_> {
 
         result
     }
+
+    /// Returns L1 batches with witness inputs which are not proven yet, in the order they should be proven
+    /// (see [`Self::get_next_block_to_be_proven()`]). Batches are returned regardless of whether they are picked
+    /// by a prover. Returns the batch numbers and whether the batches are high-priority.
+    pub async fn get_unproven_batches(
+        &mut self,
+        limit: usize,
+    ) -> Result<Vec<(L1BatchNumber, bool)>, SqlxError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                is_high_priority
+            FROM
+                proof_generation_details
+            WHERE
+                status IN ('ready_to_be_proven', 'picked_by_prover')
+            ORDER BY
+                is_high_priority DESC,
+                l1_batch_number ASC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    L1BatchNumber(row.l1_batch_number as u32),
+                    row.is_high_priority,
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the proof state for the specified L1 batch, or `None` if the batch has no witness inputs.
+    pub async fn get_proof_state(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchProofState>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                status,
+                proof_hash
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number = $1
+            "#,
+            block_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| {
+            let is_proven = row.status == ProofGenerationJobStatus::Generated.to_string()
+                || row.status == ProofGenerationJobStatus::Skipped.to_string();
+            if is_proven {
+                L1BatchProofState::Proven {
+                    proof_hash: row.proof_hash.as_deref().map(H256::from_slice),
+                }
+            } else {
+                L1BatchProofState::Pending
+            }
+        }))
+    }
+
+    /// Saves a proof submitted by an external prover. Returns `false` if the L1 batch is already proven
+    /// (e.g., by a concurrently submitted proof), in which case the state is not changed.
+    pub async fn save_external_proof_artifacts_metadata(
+        &mut self,
+        block_number: L1BatchNumber,
+        proof_blob_url: &str,
+        proof_hash: H256,
+        prover_id: &str,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE proof_generation_details
+            SET
+                status = 'generated',
+                proof_blob_url = $1,
+                proof_hash = $2,
+                proof_submitted_by = $3,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $4
+                AND status IN ('ready_to_be_proven', 'picked_by_prover')
+            "#,
+            proof_blob_url,
+            proof_hash.as_bytes(),
+            prover_id,
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Records a nonce of a signed request made by an external prover, and prunes nonces older than `retention`.
+    /// Returns `false` if the nonce was already used by the prover (i.e., the request is replayed).
+    pub async fn insert_external_prover_nonce(
+        &mut self,
+        prover_id: &str,
+        nonce: &str,
+        retention: Duration,
+    ) -> Result<bool, SqlxError> {
+        let retention = pg_interval_from_duration(retention);
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM external_prover_request_nonces
+            WHERE
+                created_at < NOW() - $1::INTERVAL
+            "#,
+            &retention
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                external_prover_request_nonces (prover_id, nonce, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (prover_id, nonce) DO NOTHING
+            "#,
+            prover_id,
+            nonce
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+        Ok(result.rows_affected() == 1)
+    }
 }
@@ -368,6 +368,7 @@ impl ProverDal<'_, '_> {
                     in_progress: 0,
                     failed: 0,
                     successful: 0,
+                    quarantined: 0,
                 });
                 match status.as_ref() {
                     "queued" => stats.queued = value,
@@ -405,6 +406,7 @@ impl ProverDal<'_, '_> {
                 in_progress: results.remove("in_progress").unwrap_or(0usize),
                 failed: results.remove("failed").unwrap_or(0usize),
                 successful: results.remove("successful").unwrap_or(0usize),
+                quarantined: 0,
             }
         }
     }
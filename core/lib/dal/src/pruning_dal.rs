@@ -0,0 +1,684 @@
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Information about the data pruned from Postgres.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningInfo {
+    pub last_pruned_l1_batch: Option<L1BatchNumber>,
+    pub last_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Information about L1 batches with compacted storage logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageLogsCompactionInfo {
+    pub last_compacted_l1_batch: Option<L1BatchNumber>,
+    pub last_compacted_miniblock: Option<MiniblockNumber>,
+}
+
+/// Number of rows removed by a single pruning operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningStats {
+    pub deleted_events: u64,
+    pub deleted_call_traces: u64,
+    pub deleted_storage_logs: u64,
+    pub deleted_l2_transactions: u64,
+    pub deleted_replaced_transactions: u64,
+}
+
+/// DAL removing historical data of old L1 batches.
+///
+/// Pruning keeps all data necessary to continue operating the node and to prove L1 batches: miniblock and L1 batch
+/// headers (including commitments), L1 and protocol upgrade transactions, L2-to-L1 logs, factory dependencies,
+/// initial writes, and the latest storage log for each storage slot. Events, call traces, L2 transactions
+/// (together with the transactions they have replaced), and storage logs overwritten in later miniblocks are removed.
+///
+/// Storage log compaction is a less destructive alternative for `storage_logs`: it only removes logs
+/// overwritten later in the *same* L1 batch. Thus, the storage state remains available as of the end
+/// of each L1 batch (which is all the Merkle tree, state keeper cache and snapshots rely on), but not
+/// as of miniblocks inside compacted L1 batches.
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> sqlx::Result<PruningInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                pruned_l1_batch,
+                pruned_miniblock
+            FROM
+                pruning_log
+            ORDER BY
+                pruned_l1_batch DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_pruning_info")
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map_or_else(PruningInfo::default, |row| PruningInfo {
+            last_pruned_l1_batch: Some(L1BatchNumber(row.pruned_l1_batch as u32)),
+            last_pruned_miniblock: Some(MiniblockNumber(row.pruned_miniblock as u32)),
+        }))
+    }
+
+    /// Returns the number of the last L1 batch that was executed on L1, was processed by the Merkle tree
+    /// and has timestamp not greater than `max_timestamp`. Only such batches can be pruned; in particular,
+    /// storage logs of L1 batches not yet processed by the tree must be retained.
+    pub async fn get_last_prunable_l1_batch(
+        &mut self,
+        max_timestamp: u64,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+                LEFT JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+            WHERE
+                execute_tx.confirmed_at IS NOT NULL
+                AND l1_batches.hash IS NOT NULL
+                AND l1_batches.timestamp <= $1
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+            max_timestamp as i64
+        )
+        .instrument("get_last_prunable_l1_batch")
+        .with_arg("max_timestamp", &max_timestamp)
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| L1BatchNumber(row.number as u32)))
+    }
+
+    /// Prunes data for all L1 batches after the last pruned one up to and including `last_l1_batch`.
+    /// The caller is responsible for ensuring that the batches can be pruned (e.g., using
+    /// [`Self::get_last_prunable_l1_batch()`]) and for limiting the number of pruned batches, so that
+    /// the operation doesn't hold locks for too long.
+    pub async fn prune_l1_batches(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<PruningStats> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let info = transaction.pruning_dal().get_pruning_info().await?;
+        if info
+            .last_pruned_l1_batch
+            .map_or(false, |number| number >= last_l1_batch)
+        {
+            return Ok(PruningStats::default());
+        }
+
+        let first_miniblock = info
+            .last_pruned_miniblock
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        let Some((_, last_miniblock)) = transaction
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .await?
+        else {
+            return Ok(PruningStats::default());
+        };
+
+        let deleted_events = sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("prune_l1_batches#events")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        let deleted_call_traces = sqlx::query!(
+            r#"
+            DELETE FROM call_traces USING transactions
+            WHERE
+                call_traces.tx_hash = transactions.hash
+                AND transactions.miniblock_number BETWEEN $1 AND $2
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("prune_l1_batches#call_traces")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        // For each slot touched in the pruned miniblocks, only the latest log is retained; the retained log
+        // may be removed later if the slot is overwritten in the following pruned miniblocks.
+        let deleted_storage_logs = sqlx::query!(
+            r#"
+            WITH
+                latest_logs AS (
+                    SELECT DISTINCT
+                        ON (hashed_key) hashed_key,
+                        miniblock_number,
+                        operation_number
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    ORDER BY
+                        hashed_key,
+                        miniblock_number DESC,
+                        operation_number DESC
+                )
+            DELETE FROM storage_logs USING latest_logs
+            WHERE
+                storage_logs.hashed_key = latest_logs.hashed_key
+                AND (
+                    storage_logs.miniblock_number,
+                    storage_logs.operation_number
+                ) < (
+                    latest_logs.miniblock_number,
+                    latest_logs.operation_number
+                )
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("prune_l1_batches#storage_logs")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        // Must be performed before removing transactions, since replaced transactions are matched
+        // by the initiator and nonce of the executed transactions.
+        let deleted_replaced_transactions = sqlx::query!(
+            r#"
+            DELETE FROM replaced_transactions USING transactions
+            WHERE
+                replaced_transactions.initiator_address = transactions.initiator_address
+                AND replaced_transactions.nonce = transactions.nonce
+                AND transactions.miniblock_number BETWEEN $1 AND $2
+                AND transactions.is_priority = FALSE
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("prune_l1_batches#replaced_transactions")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        let deleted_l2_transactions = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND is_priority = FALSE
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("prune_l1_batches#transactions")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (pruned_l1_batch, pruned_miniblock, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            "#,
+            last_l1_batch.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        Ok(PruningStats {
+            deleted_events,
+            deleted_call_traces,
+            deleted_storage_logs,
+            deleted_l2_transactions,
+            deleted_replaced_transactions,
+        })
+    }
+
+    pub async fn get_storage_logs_compaction_info(
+        &mut self,
+    ) -> sqlx::Result<StorageLogsCompactionInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                compacted_l1_batch,
+                compacted_miniblock
+            FROM
+                storage_logs_compaction_log
+            ORDER BY
+                compacted_l1_batch DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_storage_logs_compaction_info")
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map_or_else(StorageLogsCompactionInfo::default, |row| {
+            StorageLogsCompactionInfo {
+                last_compacted_l1_batch: Some(L1BatchNumber(row.compacted_l1_batch as u32)),
+                last_compacted_miniblock: Some(MiniblockNumber(row.compacted_miniblock as u32)),
+            }
+        }))
+    }
+
+    /// Compacts storage logs for all L1 batches after the last compacted one up to and including `last_l1_batch`,
+    /// so that only the latest log for each storage slot is retained in each L1 batch. Returns the number
+    /// of removed logs. Similar to [`Self::prune_l1_batches()`], the caller is responsible for ensuring
+    /// that the batches can be compacted and for limiting the number of compacted batches.
+    pub async fn compact_storage_logs(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let info = transaction
+            .pruning_dal()
+            .get_storage_logs_compaction_info()
+            .await?;
+        if info
+            .last_compacted_l1_batch
+            .map_or(false, |number| number >= last_l1_batch)
+        {
+            return Ok(0);
+        }
+
+        let first_miniblock = info
+            .last_compacted_miniblock
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        let Some((_, last_miniblock)) = transaction
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .await?
+        else {
+            return Ok(0);
+        };
+
+        let deleted_storage_logs = sqlx::query!(
+            r#"
+            WITH
+                latest_logs AS (
+                    SELECT DISTINCT
+                        ON (miniblocks.l1_batch_number, storage_logs.hashed_key) miniblocks.l1_batch_number,
+                        storage_logs.hashed_key,
+                        storage_logs.miniblock_number,
+                        storage_logs.operation_number
+                    FROM
+                        storage_logs
+                        INNER JOIN miniblocks ON miniblocks.number = storage_logs.miniblock_number
+                    WHERE
+                        storage_logs.miniblock_number BETWEEN $1 AND $2
+                    ORDER BY
+                        miniblocks.l1_batch_number,
+                        storage_logs.hashed_key,
+                        storage_logs.miniblock_number DESC,
+                        storage_logs.operation_number DESC
+                )
+            DELETE FROM storage_logs USING latest_logs,
+            miniblocks
+            WHERE
+                storage_logs.hashed_key = latest_logs.hashed_key
+                AND storage_logs.miniblock_number = miniblocks.number
+                AND miniblocks.l1_batch_number = latest_logs.l1_batch_number
+                AND (
+                    storage_logs.miniblock_number,
+                    storage_logs.operation_number
+                ) < (
+                    latest_logs.miniblock_number,
+                    latest_logs.operation_number
+                )
+            "#,
+            first_miniblock.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .instrument("compact_storage_logs")
+        .with_arg("last_miniblock", &last_miniblock)
+        .report_latency()
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                storage_logs_compaction_log (compacted_l1_batch, compacted_miniblock, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            "#,
+            last_l1_batch.0 as i64,
+            last_miniblock.0 as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+        Ok(deleted_storage_logs)
+    }
+
+    /// Checks whether the storage state as of the specified miniblock was removed by pruning or by storage logs
+    /// compaction. This is the case for all miniblocks before the last pruned one, and for all miniblocks
+    /// in compacted L1 batches except for the last miniblock in each batch.
+    pub async fn is_miniblock_state_pruned(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        miniblocks
+                    WHERE
+                        number = $1
+                        AND (
+                            number < (
+                                SELECT
+                                    MAX(pruned_miniblock)
+                                FROM
+                                    pruning_log
+                            )
+                            OR (
+                                number <= (
+                                    SELECT
+                                        MAX(compacted_miniblock)
+                                    FROM
+                                        storage_logs_compaction_log
+                                )
+                                AND number < (
+                                    SELECT
+                                        MAX(batch_miniblocks.number)
+                                    FROM
+                                        miniblocks AS batch_miniblocks
+                                    WHERE
+                                        batch_miniblocks.l1_batch_number = miniblocks.l1_batch_number
+                                )
+                            )
+                        )
+                ) AS "is_pruned!"
+            "#,
+            miniblock_number.0 as i64
+        )
+        .instrument("is_miniblock_state_pruned")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.is_pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        AccountTreeId, Address, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog, H256,
+        U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    async fn insert_l1_batch(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        header.is_finished = true;
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+
+        let logs = [(H256::zero(), logs)];
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &logs)
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pruning_l1_batches() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let first_batch_logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(1)),
+            StorageLog::new_write_log(second_key, H256::repeat_byte(2)),
+        ];
+        insert_l1_batch(&mut conn, 1, first_batch_logs).await;
+        let replaced_tx = mock_l2_transaction();
+        let replaced_tx_hash = replaced_tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(replaced_tx.clone(), Default::default())
+            .await;
+        let mut tx = mock_l2_transaction();
+        tx.common_data.initiator_address = replaced_tx.common_data.initiator_address;
+        tx.common_data.nonce = replaced_tx.common_data.nonce;
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx)],
+                U256::from(1),
+            )
+            .await;
+        for number in [2, 3] {
+            let log = StorageLog::new_write_log(first_key, H256::repeat_byte(number));
+            insert_l1_batch(&mut conn, number.into(), vec![log]).await;
+        }
+
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info, PruningInfo::default());
+
+        let stats = conn
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_storage_logs, 1);
+        assert_eq!(stats.deleted_l2_transactions, 1);
+        assert_eq!(stats.deleted_replaced_transactions, 1);
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(2)));
+        assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(2)));
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hash)
+            .await
+            .unwrap();
+        assert!(details.is_none());
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(replaced_tx_hash)
+            .await
+            .unwrap();
+        assert!(details.is_none());
+        // Values of storage slots must be retained for all unpruned miniblocks.
+        for (number, expected_value) in [(2, H256::repeat_byte(2)), (3, H256::repeat_byte(3))] {
+            let value = conn
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&first_key, MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(value, expected_value);
+            let value = conn
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&second_key, MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(value, H256::repeat_byte(2));
+        }
+        // ...while the state as of earlier miniblocks is reported as pruned.
+        for (number, expected) in [(1, true), (2, false), (3, false)] {
+            let is_pruned = conn
+                .pruning_dal()
+                .is_miniblock_state_pruned(MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(is_pruned, expected, "miniblock #{number}");
+        }
+
+        // Pruning the same L1 batches again should be a no-op.
+        let stats = conn
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(stats, PruningStats::default());
+
+        let stats = conn
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_storage_logs, 1);
+        let value = conn
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&first_key, MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(value, H256::repeat_byte(3));
+    }
+
+    #[tokio::test]
+    async fn compacting_storage_logs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let first_batch_logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(1)),
+            StorageLog::new_write_log(second_key, H256::repeat_byte(2)),
+            StorageLog::new_write_log(first_key, H256::repeat_byte(0x11)),
+        ];
+        insert_l1_batch(&mut conn, 1, first_batch_logs).await;
+        let log = StorageLog::new_write_log(first_key, H256::repeat_byte(2));
+        insert_l1_batch(&mut conn, 2, vec![log]).await;
+        // Add another miniblock to L1 batch #2.
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(3))
+            .await
+            .unwrap();
+        let logs = [(
+            H256::zero(),
+            vec![StorageLog::new_write_log(first_key, H256::repeat_byte(3))],
+        )];
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(3), &logs)
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(2))
+            .await
+            .unwrap();
+
+        let info = conn
+            .pruning_dal()
+            .get_storage_logs_compaction_info()
+            .await
+            .unwrap();
+        assert_eq!(info, StorageLogsCompactionInfo::default());
+
+        let deleted_logs = conn
+            .pruning_dal()
+            .compact_storage_logs(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(deleted_logs, 2);
+        let info = conn
+            .pruning_dal()
+            .get_storage_logs_compaction_info()
+            .await
+            .unwrap();
+        assert_eq!(info.last_compacted_l1_batch, Some(L1BatchNumber(2)));
+        assert_eq!(info.last_compacted_miniblock, Some(MiniblockNumber(3)));
+
+        // Values of storage slots must be retained as of the end of each L1 batch.
+        for (number, expected_value) in [(1, H256::repeat_byte(0x11)), (3, H256::repeat_byte(3))] {
+            let value = conn
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&first_key, MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(value, expected_value);
+            let value = conn
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&second_key, MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(value, H256::repeat_byte(2));
+        }
+        // ...while the state inside compacted L1 batches is reported as compacted.
+        for (number, expected) in [(1, false), (2, true), (3, false), (4, false)] {
+            let is_pruned = conn
+                .pruning_dal()
+                .is_miniblock_state_pruned(MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(is_pruned, expected, "miniblock #{number}");
+        }
+
+        // Compacting the same L1 batches again should be a no-op.
+        let deleted_logs = conn
+            .pruning_dal()
+            .compact_storage_logs(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(deleted_logs, 0);
+    }
+}
@@ -99,6 +99,95 @@ impl SnapshotsCreatorDal<'_, '_> {
         Ok(storage_logs)
     }
 
+    /// Returns the number of distinct storage keys changed in the specified miniblock range.
+    pub async fn get_changed_storage_logs_keys_count(
+        &mut self,
+        miniblock_range: std::ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT hashed_key) AS "count!"
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+        )
+        .instrument("get_changed_storage_logs_keys_count")
+        .with_arg("miniblock_range", &miniblock_range)
+        .report_latency()
+        .fetch_one(self.storage.conn())
+        .await?
+        .count;
+        Ok(count as u64)
+    }
+
+    /// Same as [`Self::get_storage_logs_chunk()`], but only returns the latest logs for keys changed
+    /// in the specified miniblock range. Used to create incremental snapshots.
+    pub async fn get_changed_storage_logs_chunk(
+        &mut self,
+        miniblock_range: std::ops::RangeInclusive<MiniblockNumber>,
+        hashed_keys_range: std::ops::RangeInclusive<H256>,
+    ) -> sqlx::Result<Vec<SnapshotStorageLog>> {
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                storage_logs.key AS "key!",
+                storage_logs.value AS "value!",
+                storage_logs.address AS "address!",
+                storage_logs.miniblock_number AS "miniblock_number!",
+                initial_writes.l1_batch_number AS "l1_batch_number!",
+                initial_writes.index
+            FROM
+                (
+                    SELECT
+                        hashed_key,
+                        MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                        AND hashed_key >= $3
+                        AND hashed_key < $4
+                    GROUP BY
+                        hashed_key
+                    ORDER BY
+                        hashed_key
+                ) AS keys
+                INNER JOIN storage_logs ON keys.hashed_key = storage_logs.hashed_key
+                AND storage_logs.miniblock_number = keys.op[1]
+                AND storage_logs.operation_number = keys.op[2]
+                INNER JOIN initial_writes ON keys.hashed_key = initial_writes.hashed_key;
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            hashed_keys_range.start().0.as_slice(),
+            hashed_keys_range.end().0.as_slice(),
+        )
+        .instrument("get_changed_storage_logs_chunk")
+        .with_arg("miniblock_range", &miniblock_range)
+        .with_arg("min_hashed_key", &hashed_keys_range.start())
+        .with_arg("max_hashed_key", &hashed_keys_range.end())
+        .report_latency()
+        .fetch_all(self.storage.conn())
+        .await?
+        .iter()
+        .map(|row| SnapshotStorageLog {
+            key: StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            ),
+            value: H256::from_slice(&row.value),
+            l1_batch_number_of_initial_write: L1BatchNumber(row.l1_batch_number as u32),
+            enumeration_index: row.index.unwrap() as u64,
+        })
+        .collect();
+        Ok(storage_logs)
+    }
+
     pub async fn get_all_factory_deps(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -126,4 +215,35 @@ impl SnapshotsCreatorDal<'_, '_> {
             })
             .collect())
     }
+
+    /// Returns factory deps added in the specified miniblock range. Used to create incremental snapshots.
+    pub async fn get_new_factory_deps(
+        &mut self,
+        miniblock_range: std::ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<SnapshotFactoryDependency>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+        )
+        .instrument("get_new_factory_deps")
+        .with_arg("miniblock_range", &miniblock_range)
+        .report_latency()
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SnapshotFactoryDependency {
+                bytecode: row.bytecode.into(),
+            })
+            .collect())
+    }
 }
@@ -1,6 +1,6 @@
 use zksync_types::{
-    snapshots::{AllSnapshots, SnapshotMetadata},
-    L1BatchNumber,
+    snapshots::{AllSnapshots, IncrementalSnapshotManifest, SnapshotMetadata},
+    L1BatchNumber, H256,
 };
 
 use crate::{instrument::InstrumentExt, StorageProcessor};
@@ -14,25 +14,42 @@ impl SnapshotsDal<'_, '_> {
     pub async fn add_snapshot(
         &mut self,
         l1_batch_number: L1BatchNumber,
+        incremental: Option<IncrementalSnapshotManifest>,
         storage_logs_filepaths: &[String],
+        storage_logs_checksums: &[H256],
         factory_deps_filepaths: &str,
+        factory_deps_checksum: H256,
     ) -> Result<(), sqlx::Error> {
+        let storage_logs_checksums: Vec<_> =
+            storage_logs_checksums.iter().map(H256::as_bytes).collect();
+        let base_l1_batch_number =
+            incremental.map(|manifest| i64::from(manifest.base_l1_batch_number.0));
+        let previous_l1_batch_number =
+            incremental.map(|manifest| i64::from(manifest.previous_l1_batch_number.0));
         sqlx::query!(
             r#"
             INSERT INTO
                 snapshots (
                     l1_batch_number,
                     storage_logs_filepaths,
+                    storage_logs_checksums,
                     factory_deps_filepath,
+                    factory_deps_checksum,
+                    base_l1_batch_number,
+                    previous_l1_batch_number,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, NOW(), NOW())
+                ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
             "#,
             l1_batch_number.0 as i32,
             storage_logs_filepaths,
+            &storage_logs_checksums as &[&[u8]],
             factory_deps_filepaths,
+            factory_deps_checksum.as_bytes(),
+            base_l1_batch_number,
+            previous_l1_batch_number,
         )
         .instrument("add_snapshot")
         .report_latency()
@@ -73,7 +90,11 @@ impl SnapshotsDal<'_, '_> {
             SELECT
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                factory_deps_checksum,
+                storage_logs_filepaths,
+                storage_logs_checksums,
+                base_l1_batch_number,
+                previous_l1_batch_number
             FROM
                 snapshots
             WHERE
@@ -88,15 +109,74 @@ impl SnapshotsDal<'_, '_> {
         .map(|r| SnapshotMetadata {
             l1_batch_number: L1BatchNumber(r.l1_batch_number as u32),
             factory_deps_filepath: r.factory_deps_filepath,
+            factory_deps_checksum: r.factory_deps_checksum.as_deref().map(H256::from_slice),
             storage_logs_filepaths: r.storage_logs_filepaths,
+            // Checksums are not recorded for snapshots created before they were introduced.
+            storage_logs_checksums: r.storage_logs_checksums.map(|checksums| {
+                checksums
+                    .iter()
+                    .map(|checksum| H256::from_slice(checksum))
+                    .collect()
+            }),
+            incremental: r.base_l1_batch_number.zip(r.previous_l1_batch_number).map(
+                |(base, previous)| IncrementalSnapshotManifest {
+                    base_l1_batch_number: L1BatchNumber(base as u32),
+                    previous_l1_batch_number: L1BatchNumber(previous as u32),
+                },
+            ),
         });
         Ok(record)
     }
+
+    /// Returns the L1 batch number of the newest snapshot, or `None` if there are no snapshots.
+    pub async fn get_newest_snapshot_l1_batch_number(
+        &mut self,
+    ) -> Result<Option<L1BatchNumber>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "l1_batch_number"
+            FROM
+                snapshots
+            "#
+        )
+        .instrument("get_newest_snapshot_l1_batch_number")
+        .report_latency()
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns the number of incremental snapshots based on the full snapshot for the specified L1 batch.
+    pub async fn get_incremental_snapshots_count(
+        &mut self,
+        base_l1_batch_number: L1BatchNumber,
+    ) -> Result<u64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                snapshots
+            WHERE
+                base_l1_batch_number = $1
+            "#,
+            i64::from(base_l1_batch_number.0)
+        )
+        .instrument("get_incremental_snapshots_count")
+        .with_arg("base_l1_batch_number", &base_l1_batch_number)
+        .report_latency()
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.count as u64)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::L1BatchNumber;
+    use zksync_types::{snapshots::IncrementalSnapshotManifest, L1BatchNumber, H256};
 
     use crate::ConnectionPool;
 
@@ -106,9 +186,16 @@ mod tests {
         let mut conn = pool.access_storage().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, &[], "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            l1_batch_number,
+            None,
+            &[],
+            &[],
+            "gs:///bucket/factory_deps.bin",
+            H256::zero(),
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let snapshots = dal
             .get_all_snapshots()
@@ -129,6 +216,7 @@ mod tests {
             snapshot_metadata.l1_batch_number,
             l1_batch_number as L1BatchNumber
         );
+        assert_eq!(snapshot_metadata.incremental, None);
     }
 
     #[tokio::test]
@@ -139,22 +227,95 @@ mod tests {
         let l1_batch_number = L1BatchNumber(100);
         dal.add_snapshot(
             l1_batch_number,
+            None,
             &[
                 "gs:///bucket/test_file1.bin".to_string(),
                 "gs:///bucket/test_file2.bin".to_string(),
             ],
+            &[H256::repeat_byte(1), H256::repeat_byte(2)],
             "gs:///bucket/factory_deps.bin",
+            H256::repeat_byte(3),
         )
         .await
         .expect("Failed to add snapshot");
 
-        let files = dal
+        let snapshot_metadata = dal
             .get_snapshot_metadata(l1_batch_number)
             .await
             .expect("Failed to retrieve snapshot")
-            .unwrap()
-            .storage_logs_filepaths;
+            .unwrap();
+        let files = snapshot_metadata.storage_logs_filepaths;
         assert!(files.contains(&"gs:///bucket/test_file1.bin".to_string()));
         assert!(files.contains(&"gs:///bucket/test_file2.bin".to_string()));
+        assert_eq!(
+            snapshot_metadata.storage_logs_checksums,
+            Some(vec![H256::repeat_byte(1), H256::repeat_byte(2)])
+        );
+        assert_eq!(
+            snapshot_metadata.factory_deps_checksum,
+            Some(H256::repeat_byte(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn adding_incremental_snapshots() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        assert_eq!(
+            dal.get_newest_snapshot_l1_batch_number().await.unwrap(),
+            None
+        );
+
+        let base_l1_batch_number = L1BatchNumber(100);
+        dal.add_snapshot(
+            base_l1_batch_number,
+            None,
+            &[],
+            &[],
+            "gs:///bucket/factory_deps_100.bin",
+            H256::zero(),
+        )
+        .await
+        .unwrap();
+        for (previous, l1_batch_number) in [(100, 110), (110, 120)] {
+            let manifest = IncrementalSnapshotManifest {
+                base_l1_batch_number,
+                previous_l1_batch_number: L1BatchNumber(previous),
+            };
+            dal.add_snapshot(
+                L1BatchNumber(l1_batch_number),
+                Some(manifest),
+                &[],
+                &[],
+                &format!("gs:///bucket/factory_deps_{l1_batch_number}.bin"),
+                H256::zero(),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            dal.get_newest_snapshot_l1_batch_number().await.unwrap(),
+            Some(L1BatchNumber(120))
+        );
+        assert_eq!(
+            dal.get_incremental_snapshots_count(base_l1_batch_number)
+                .await
+                .unwrap(),
+            2
+        );
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(L1BatchNumber(120))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            snapshot_metadata.incremental,
+            Some(IncrementalSnapshotManifest {
+                base_l1_batch_number,
+                previous_l1_batch_number: L1BatchNumber(110),
+            })
+        );
     }
 }
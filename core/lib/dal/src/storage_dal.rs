@@ -49,6 +49,58 @@ impl StorageDal<'_, '_> {
         .unwrap();
     }
 
+    /// Bulk version of [`Self::insert_factory_deps()`] suited for large imports (e.g., genesis or
+    /// snapshot recovery). Factory deps are streamed to a temporary table using `COPY` and are then moved
+    /// to the `factory_deps` table; as with the non-bulk version, already existing deps are skipped.
+    pub async fn insert_factory_deps_bulk(
+        &mut self,
+        block_number: MiniblockNumber,
+        factory_deps: &HashMap<H256, Vec<u8>>,
+    ) -> sqlx::Result<()> {
+        // Queries referencing the temporary table cannot be checked at compile time, hence `sqlx::query()`.
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query(
+            "CREATE TEMPORARY TABLE factory_deps_bulk (
+                bytecode_hash BYTEA NOT NULL,
+                bytecode BYTEA NOT NULL
+            )",
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let mut copy = transaction
+            .conn()
+            .copy_in_raw(
+                "COPY factory_deps_bulk (bytecode_hash, bytecode) FROM STDIN WITH (DELIMITER '|')",
+            )
+            .await?;
+        let mut buffer = String::new();
+        for (bytecode_hash, bytecode) in factory_deps {
+            writeln_str!(
+                &mut buffer,
+                r"\\x{bytecode_hash:x}|\\x{bytecode}",
+                bytecode = hex::encode(bytecode)
+            );
+        }
+        copy.send(buffer.as_bytes()).await?;
+        copy.finish().await?;
+
+        sqlx::query(
+            "INSERT INTO factory_deps (bytecode_hash, bytecode, miniblock_number, created_at, updated_at)
+            SELECT bytecode_hash, bytecode, $1, NOW(), NOW() FROM factory_deps_bulk
+            ON CONFLICT (bytecode_hash) DO NOTHING",
+        )
+        .bind(block_number.0 as i64)
+        .execute(transaction.conn())
+        .await?;
+        // The table is dropped explicitly rather than with `ON COMMIT DROP` since this method may be called
+        // multiple times in a single outer transaction.
+        sqlx::query("DROP TABLE factory_deps_bulk")
+            .execute(transaction.conn())
+            .await?;
+        transaction.commit().await
+    }
+
     /// Returns bytecode for a factory dependency with the specified bytecode `hash`.
     pub async fn get_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
         sqlx::query!(
@@ -263,10 +315,10 @@ impl StorageDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{AccountTreeId, Address};
+    use zksync_types::{AccountTreeId, Address, ProtocolVersion};
 
     use super::*;
-    use crate::ConnectionPool;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
 
     #[tokio::test]
     async fn applying_storage_logs() {
@@ -288,4 +340,48 @@ mod tests {
         let second_value = conn.storage_dal().get_by_key(&second_key).await.unwrap();
         assert_eq!(second_value, H256::repeat_byte(2));
     }
+
+    #[tokio::test]
+    async fn inserting_factory_deps_in_bulk() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await
+            .unwrap();
+
+        let factory_deps: HashMap<_, _> = (0_u8..5)
+            .map(|i| (H256::repeat_byte(i), vec![i; 32]))
+            .collect();
+        conn.storage_dal()
+            .insert_factory_deps(MiniblockNumber(0), &factory_deps)
+            .await;
+        let more_factory_deps: HashMap<_, _> = (3_u8..10)
+            .map(|i| (H256::repeat_byte(i), vec![i; 64]))
+            .collect();
+        // Calling the method twice in a single transaction checks that the temporary table is dropped.
+        let mut transaction = conn.start_transaction().await.unwrap();
+        for deps in [&more_factory_deps, &HashMap::new()] {
+            transaction
+                .storage_dal()
+                .insert_factory_deps_bulk(MiniblockNumber(0), deps)
+                .await
+                .unwrap();
+        }
+        transaction.commit().await.unwrap();
+
+        for i in 0_u8..10 {
+            let dep = conn
+                .storage_dal()
+                .get_factory_dep(H256::repeat_byte(i))
+                .await
+                .unwrap();
+            // Existing deps must not be overwritten.
+            let expected_len = if i < 5 { 32 } else { 64 };
+            assert_eq!(dep, vec![i; expected_len]);
+        }
+    }
 }
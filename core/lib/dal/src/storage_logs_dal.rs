@@ -2,9 +2,11 @@ use std::{collections::HashMap, ops, time::Instant};
 
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
-    get_code_key, AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    get_code_key, snapshots::SnapshotStorageLog, tx::tx_execution_info::DeduplicatedWritesMetrics,
+    writes::compression::compress_with_best_strategy, AccountTreeId, Address, L1BatchNumber,
+    MiniblockNumber, StorageKey, StorageLog, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
+use zksync_utils::h256_to_u256;
 
 use crate::{instrument::InstrumentExt, models::storage_log::StorageTreeEntry, StorageProcessor};
 
@@ -94,6 +96,52 @@ impl StorageLogsDal<'_, '_> {
             .await;
     }
 
+    /// Inserts storage logs recovered from a snapshot. All logs are attributed to the snapshot miniblock;
+    /// their operation numbers are set to enumeration indices so that logs from different snapshot chunks
+    /// don't conflict with each other.
+    ///
+    /// Logs are inserted using a single `COPY` statement, which is much faster than inserting rows
+    /// one by one, so this method should be used for bulk imports (e.g., during snapshot recovery).
+    pub async fn insert_storage_logs_from_snapshot(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> sqlx::Result<()> {
+        let mut copy = self
+            .storage
+            .conn()
+            .copy_in_raw(
+                "COPY storage_logs(
+                    hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
+                    created_at, updated_at
+                )
+                FROM STDIN WITH (DELIMITER '|')",
+            )
+            .await?;
+
+        let mut buffer = String::new();
+        let now = Utc::now().naive_utc().to_string();
+        for log in snapshot_storage_logs {
+            write_str!(
+                &mut buffer,
+                r"\\x{hashed_key:x}|\\x{address:x}|\\x{key:x}|\\x{value:x}|",
+                hashed_key = log.key.hashed_key(),
+                address = log.key.address(),
+                key = log.key.key(),
+                value = log.value
+            );
+            writeln_str!(
+                &mut buffer,
+                r"{operation_number}|\\x{tx_hash:x}|{miniblock_number}|{now}|{now}",
+                operation_number = log.enumeration_index,
+                tx_hash = H256::zero()
+            );
+        }
+        copy.send(buffer.as_bytes()).await?;
+        copy.finish().await?;
+        Ok(())
+    }
+
     /// Rolls back storage to the specified point in time.
     pub async fn rollback_storage(&mut self, last_miniblock_to_keep: MiniblockNumber) {
         let stage_start = Instant::now();
@@ -303,6 +351,92 @@ impl StorageLogsDal<'_, '_> {
         touched_slots.collect()
     }
 
+    /// Returns deduplicated storage write metrics for the pending L1 batch, i.e., for sealed miniblocks
+    /// not yet included into an L1 batch. A slot is only counted if its value at the end of the pending batch
+    /// differs from its value before the batch; writes to slots that don't have an initial write yet are initial.
+    pub async fn get_pending_l1_batch_writes_metrics(
+        &mut self,
+    ) -> sqlx::Result<DeduplicatedWritesMetrics> {
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                sealed AS (
+                    SELECT
+                        COALESCE(MAX(number), -1) AS miniblock_number
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number IS NOT NULL
+                )
+            SELECT DISTINCT
+                ON (storage_logs.hashed_key) storage_logs.value AS "value!",
+                (
+                    SELECT
+                        prev_logs.value
+                    FROM
+                        storage_logs AS prev_logs
+                    WHERE
+                        prev_logs.hashed_key = storage_logs.hashed_key
+                        AND prev_logs.miniblock_number <= (
+                            SELECT
+                                miniblock_number
+                            FROM
+                                sealed
+                        )
+                    ORDER BY
+                        prev_logs.miniblock_number DESC,
+                        prev_logs.operation_number DESC
+                    LIMIT
+                        1
+                ) AS "prev_value?",
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        initial_writes
+                    WHERE
+                        initial_writes.hashed_key = storage_logs.hashed_key
+                ) AS "is_repeated!"
+            FROM
+                storage_logs
+            WHERE
+                storage_logs.miniblock_number > (
+                    SELECT
+                        miniblock_number
+                    FROM
+                        sealed
+                )
+            ORDER BY
+                storage_logs.hashed_key,
+                storage_logs.miniblock_number DESC,
+                storage_logs.operation_number DESC
+            "#
+        )
+        .instrument("get_pending_l1_batch_writes_metrics")
+        .report_latency()
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let mut metrics = DeduplicatedWritesMetrics::default();
+        for row in rows {
+            let prev_value = row
+                .prev_value
+                .map_or_else(H256::zero, |value| H256::from_slice(&value));
+            let value = H256::from_slice(&row.value);
+            if value == prev_value {
+                continue;
+            }
+            if row.is_repeated {
+                metrics.repeated_storage_writes += 1;
+            } else {
+                metrics.initial_storage_writes += 1;
+            }
+            metrics.total_updated_values_size +=
+                compress_with_best_strategy(h256_to_u256(prev_value), h256_to_u256(value)).len();
+        }
+        Ok(metrics)
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -836,6 +970,75 @@ mod tests {
         assert!(value.is_none());
     }
 
+    #[tokio::test]
+    async fn getting_pending_l1_batch_writes_metrics() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let keys: Vec<_> = (0_u64..4)
+            .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+            .collect();
+        let logs = keys
+            .iter()
+            .map(|key| StorageLog::new_write_log(*key, H256::repeat_byte(1)))
+            .collect();
+        insert_miniblock(&mut conn, 1, logs).await;
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &keys)
+            .await;
+
+        let metrics = conn
+            .storage_logs_dal()
+            .get_pending_l1_batch_writes_metrics()
+            .await
+            .unwrap();
+        assert_eq!(metrics, DeduplicatedWritesMetrics::default());
+
+        // Pending miniblock: a repeated write, a write restoring the previous value, a repeated write
+        // overwritten within the batch and an initial write.
+        let new_key = StorageKey::new(account, H256::from_low_u64_be(10));
+        let pending_logs = [(
+            H256::repeat_byte(2),
+            vec![
+                StorageLog::new_write_log(keys[0], H256::repeat_byte(2)),
+                StorageLog::new_write_log(keys[1], H256::repeat_byte(1)),
+                StorageLog::new_write_log(keys[2], H256::repeat_byte(3)),
+                StorageLog::new_write_log(keys[2], H256::repeat_byte(4)),
+                StorageLog::new_write_log(new_key, H256::repeat_byte(5)),
+            ],
+        )];
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(2), &pending_logs)
+            .await;
+
+        let metrics = conn
+            .storage_logs_dal()
+            .get_pending_l1_batch_writes_metrics()
+            .await
+            .unwrap();
+        assert_eq!(metrics.initial_storage_writes, 1);
+        assert_eq!(metrics.repeated_storage_writes, 2);
+        let expected_size: usize = [
+            (H256::repeat_byte(1), H256::repeat_byte(2)),
+            (H256::repeat_byte(1), H256::repeat_byte(4)),
+            (H256::zero(), H256::repeat_byte(5)),
+        ]
+        .into_iter()
+        .map(|(prev, value)| {
+            compress_with_best_strategy(h256_to_u256(prev), h256_to_u256(value)).len()
+        })
+        .sum();
+        assert_eq!(metrics.total_updated_values_size, expected_size);
+    }
+
     #[tokio::test]
     async fn getting_storage_logs_for_revert() {
         let pool = ConnectionPool::test_pool().await;
@@ -1043,4 +1246,56 @@ mod tests {
             assert!(key_range.contains(&u256_to_h256_reversed(entry.key)));
         }
     }
+
+    #[tokio::test]
+    async fn inserting_storage_logs_from_snapshot() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        insert_miniblock(&mut conn, 1, vec![]).await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let snapshot_logs: Vec<_> = (0_u64..10)
+            .map(|i| SnapshotStorageLog {
+                key: StorageKey::new(account, H256::from_low_u64_be(i)),
+                value: H256::repeat_byte(i as u8 + 1),
+                l1_batch_number_of_initial_write: L1BatchNumber(1),
+                enumeration_index: i + 1,
+            })
+            .collect();
+        // Logs are inserted in chunks, similarly to snapshot recovery.
+        for chunk in snapshot_logs.chunks(4) {
+            conn.storage_logs_dal()
+                .insert_storage_logs_from_snapshot(MiniblockNumber(1), chunk)
+                .await
+                .unwrap();
+            conn.storage_logs_dedup_dal()
+                .insert_initial_writes_from_snapshot(chunk)
+                .await
+                .unwrap();
+        }
+
+        let hashed_keys: Vec<_> = snapshot_logs
+            .iter()
+            .map(|log| log.key.hashed_key())
+            .collect();
+        let values = conn
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, MiniblockNumber(1))
+            .await;
+        let initial_writes = conn
+            .storage_logs_dal()
+            .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+            .await;
+        for log in &snapshot_logs {
+            let hashed_key = log.key.hashed_key();
+            assert_eq!(values[&hashed_key], Some(log.value));
+            assert_eq!(
+                initial_writes[&hashed_key],
+                (L1BatchNumber(1), log.enumeration_index)
+            );
+        }
+    }
 }
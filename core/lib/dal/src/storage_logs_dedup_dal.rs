@@ -1,7 +1,10 @@
 use std::collections::HashSet;
 
 use sqlx::types::chrono::Utc;
-use zksync_types::{AccountTreeId, Address, L1BatchNumber, LogQuery, StorageKey, H256};
+use zksync_types::{
+    snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber, LogQuery, StorageKey,
+    H256,
+};
 use zksync_utils::u256_to_h256;
 
 use crate::StorageProcessor;
@@ -81,6 +84,37 @@ impl StorageLogsDedupDal<'_, '_> {
         .unwrap();
     }
 
+    /// Inserts initial writes recovered from a snapshot, preserving their enumeration indices
+    /// and L1 batches. Uses a single `COPY` statement, so it is suited for bulk imports.
+    pub async fn insert_initial_writes_from_snapshot(
+        &mut self,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> sqlx::Result<()> {
+        let mut copy = self
+            .storage
+            .conn()
+            .copy_in_raw(
+                "COPY initial_writes (hashed_key, index, l1_batch_number, created_at, updated_at) \
+                FROM STDIN WITH (DELIMITER '|')",
+            )
+            .await?;
+
+        let mut buffer = String::new();
+        let now = Utc::now().naive_utc().to_string();
+        for log in snapshot_storage_logs {
+            writeln_str!(
+                &mut buffer,
+                r"\\x{hashed_key:x}|{index}|{l1_batch_number}|{now}|{now}",
+                hashed_key = log.key.hashed_key(),
+                index = log.enumeration_index,
+                l1_batch_number = log.l1_batch_number_of_initial_write
+            );
+        }
+        copy.send(buffer.as_bytes()).await?;
+        copy.finish().await?;
+        Ok(())
+    }
+
     pub async fn get_protective_reads_for_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -1,7 +1,8 @@
 use std::ops;
 
 use zksync_types::{
-    get_code_key, get_nonce_key,
+    get_code_key, get_known_code_key, get_nonce_key,
+    pubdata::PubdataContents,
     utils::{decompose_full_nonce, storage_key_for_standard_token_balance},
     AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey,
     FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
@@ -176,6 +177,108 @@ impl StorageWeb3Dal<'_, '_> {
         .collect()
     }
 
+    /// Returns the first storage log operation number in the specified miniblock produced by a transaction
+    /// with `index_in_block >= tx_index`. Returns `None` if there are no such logs.
+    pub async fn get_first_operation_number_for_tx_index(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        tx_index: u32,
+    ) -> Result<Option<u32>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(storage_logs.operation_number) AS "operation_number"
+            FROM
+                storage_logs
+                INNER JOIN transactions ON transactions.hash = storage_logs.tx_hash
+            WHERE
+                storage_logs.miniblock_number = $1
+                AND transactions.index_in_block >= $2
+            "#,
+            miniblock_number.0 as i64,
+            tx_index as i32
+        )
+        .instrument("get_first_operation_number_for_tx_index")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("tx_index", &tx_index)
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(row.operation_number.map(|number| number as u32))
+    }
+
+    /// Returns non-zero storage slots of the specified contract with keys `>= start_key`, ordered by key.
+    /// Values are taken as of the end of the specified miniblock; if `operation_number_bound` is specified,
+    /// storage logs in this miniblock with `operation_number >= operation_number_bound` are ignored.
+    ///
+    /// Only contracts outside the kernel space are supported; for system contracts, an empty range is returned.
+    /// This method does not check if a block with this number exists in the database.
+    pub async fn get_storage_range_unchecked(
+        &mut self,
+        address: Address,
+        start_key: H256,
+        miniblock_number: MiniblockNumber,
+        operation_number_bound: Option<u32>,
+        limit: usize,
+    ) -> Result<Vec<(H256, H256)>, SqlxError> {
+        let operation_number_bound =
+            operation_number_bound.map_or(i32::MAX, |number| number as i32);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                key AS "key!",
+                value AS "value!"
+            FROM
+                (
+                    SELECT DISTINCT
+                        ON (key) key,
+                        value
+                    FROM
+                        storage_logs
+                    WHERE
+                        address = $1
+                        AND address > '\x000000000000000000000000000000000000ffff'::bytea
+                        AND key >= $2
+                        AND (
+                            miniblock_number < $3
+                            OR (
+                                miniblock_number = $3
+                                AND operation_number < $4
+                            )
+                        )
+                    ORDER BY
+                        key,
+                        miniblock_number DESC,
+                        operation_number DESC
+                ) AS latest_values
+            WHERE
+                value != $5
+            ORDER BY
+                key
+            LIMIT
+                $6
+            "#,
+            address.as_bytes(),
+            start_key.as_bytes(),
+            miniblock_number.0 as i64,
+            operation_number_bound,
+            H256::zero().as_bytes(),
+            limit as i64
+        )
+        .instrument("get_storage_range_unchecked")
+        .report_latency()
+        .with_arg("address", &address)
+        .with_arg("start_key", &start_key)
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.key), H256::from_slice(&row.value)))
+            .collect())
+    }
+
     /// This method doesn't check if block with number equals to `block_number`
     /// is present in the database. For such blocks `None` will be returned.
     pub async fn get_contract_code_unchecked(
@@ -218,6 +321,36 @@ impl StorageWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns the bytecode with the specified versioned hash. Bytecodes missing from the `factory_deps` table
+    /// (e.g., because they were recovered from L1 data) are resolved from the pubdata of the L1 batch
+    /// in which the bytecode was first marked as known, in both uncompressed and compressed forms.
+    pub async fn get_bytecode_by_hash(&mut self, hash: H256) -> Result<Option<Vec<u8>>, SqlxError> {
+        if let Some(bytecode) = self.storage.storage_dal().get_factory_dep(hash).await {
+            return Ok(Some(bytecode));
+        }
+
+        let known_code_key = get_known_code_key(&hash);
+        let Some(l1_batch_number) = self
+            .get_l1_batch_number_for_initial_write(&known_code_key)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let header = self
+            .storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?;
+        let Some(pubdata_input) = header.and_then(|header| header.pubdata_input) else {
+            return Ok(None);
+        };
+        let Some(contents) = PubdataContents::parse(&pubdata_input) else {
+            tracing::warn!("Pubdata for L1 batch #{l1_batch_number} is malformed");
+            return Ok(None);
+        };
+        Ok(contents.find_bytecode(hash))
+    }
+
     /// This method doesn't check if block with number equals to `block_number`
     /// is present in the database. For such blocks `None` will be returned.
     pub async fn get_factory_dep_unchecked(
@@ -245,3 +378,206 @@ impl StorageWeb3Dal<'_, '_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        fee::TransactionExecutionMetrics,
+        ProtocolVersion, ProtocolVersionId, StorageLog,
+    };
+    use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn getting_storage_range() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let address = Address::repeat_byte(1);
+        let account = AccountTreeId::new(address);
+        let slot = |i: u64| StorageKey::new(account, H256::from_low_u64_be(i));
+        let logs = (0..5)
+            .map(|i| StorageLog::new_write_log(slot(i), H256::from_low_u64_be(i + 1)))
+            .collect();
+        insert_miniblock(&mut conn, 1, logs).await;
+        let logs = vec![
+            StorageLog::new_write_log(slot(2), H256::zero()),
+            StorageLog::new_write_log(slot(4), H256::repeat_byte(0xff)),
+        ];
+        insert_miniblock(&mut conn, 2, logs).await;
+
+        let range = conn
+            .storage_web3_dal()
+            .get_storage_range_unchecked(address, H256::zero(), MiniblockNumber(1), None, 10)
+            .await
+            .unwrap();
+        let expected_range: Vec<_> = (0..5)
+            .map(|i| (H256::from_low_u64_be(i), H256::from_low_u64_be(i + 1)))
+            .collect();
+        assert_eq!(range, expected_range);
+
+        let range = conn
+            .storage_web3_dal()
+            .get_storage_range_unchecked(address, H256::zero(), MiniblockNumber(2), None, 10)
+            .await
+            .unwrap();
+        let keys: Vec<_> = range.iter().map(|(key, _)| key.to_low_u64_be()).collect();
+        assert_eq!(keys, [0, 1, 3, 4]);
+        assert_eq!(range[3].1, H256::repeat_byte(0xff));
+
+        // Only the first storage log in miniblock #2 should be taken into account.
+        let range = conn
+            .storage_web3_dal()
+            .get_storage_range_unchecked(address, H256::zero(), MiniblockNumber(2), Some(1), 10)
+            .await
+            .unwrap();
+        let keys: Vec<_> = range.iter().map(|(key, _)| key.to_low_u64_be()).collect();
+        assert_eq!(keys, [0, 1, 3, 4]);
+        assert_eq!(range[3].1, H256::from_low_u64_be(5));
+
+        let start_key = H256::from_low_u64_be(2);
+        let range = conn
+            .storage_web3_dal()
+            .get_storage_range_unchecked(address, start_key, MiniblockNumber(2), None, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            range,
+            [(H256::from_low_u64_be(3), H256::from_low_u64_be(4))]
+        );
+
+        let range = conn
+            .storage_web3_dal()
+            .get_storage_range_unchecked(
+                Address::zero(),
+                H256::zero(),
+                MiniblockNumber(2),
+                None,
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(range.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_first_operation_number_for_tx_index() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let txs = [mock_l2_transaction(), mock_l2_transaction()];
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = 2;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        let tx_results = txs.clone().map(mock_execution_result);
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let log = |i: u64| {
+            let key = StorageKey::new(account, H256::from_low_u64_be(i));
+            StorageLog::new_write_log(key, H256::from_low_u64_be(i + 1))
+        };
+        let logs = [
+            (txs[0].hash(), vec![log(0), log(1)]),
+            (txs[1].hash(), vec![log(2)]),
+        ];
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &logs)
+            .await;
+
+        let mut dal = conn.storage_web3_dal();
+        for (tx_index, expected) in [(0, Some(0)), (1, Some(2)), (2, None)] {
+            let operation_number = dal
+                .get_first_operation_number_for_tx_index(MiniblockNumber(1), tx_index)
+                .await
+                .unwrap();
+            assert_eq!(operation_number, expected, "tx_index={tx_index}");
+        }
+        let operation_number = dal
+            .get_first_operation_number_for_tx_index(MiniblockNumber(2), 0)
+            .await
+            .unwrap();
+        assert_eq!(operation_number, None);
+    }
+
+    #[tokio::test]
+    async fn getting_bytecode_published_in_pubdata() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let mut bytecode = vec![0; 96];
+        bytecode[32..40].fill(1);
+        let hash = hash_bytecode(&bytecode);
+        let compressed = compress_bytecode(&bytecode).unwrap();
+        // Pubdata with no user logs, a single L2-to-L1 message (the compressed bytecode), no uncompressed
+        // bytecodes and no state diffs.
+        let mut pubdata = 0_u32.to_be_bytes().to_vec();
+        pubdata.extend(1_u32.to_be_bytes());
+        pubdata.extend((compressed.len() as u32).to_be_bytes());
+        pubdata.extend(compressed);
+        pubdata.extend(0_u32.to_be_bytes());
+
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.pubdata_input = Some(pubdata);
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+
+        let mut dal = conn.storage_web3_dal();
+        assert_eq!(dal.get_bytecode_by_hash(hash).await.unwrap(), None);
+
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &[get_known_code_key(&hash)])
+            .await;
+        let mut dal = conn.storage_web3_dal();
+        assert_eq!(
+            dal.get_bytecode_by_hash(hash).await.unwrap(),
+            Some(bytecode)
+        );
+        let other_hash = H256::repeat_byte(1);
+        assert_eq!(dal.get_bytecode_by_hash(other_hash).await.unwrap(), None);
+    }
+}
@@ -8,6 +8,12 @@ pub struct SystemDal<'a, 'c> {
 
 impl SystemDal<'_, '_> {
     pub async fn get_replication_lag_sec(&mut self) -> u32 {
+        self.try_get_replication_lag_sec().await.unwrap()
+    }
+
+    /// Fallible version of [`Self::get_replication_lag_sec()`]. For a primary (i.e., non-replica)
+    /// database, the lag is always 0.
+    pub async fn try_get_replication_lag_sec(&mut self) -> sqlx::Result<u32> {
         // NOTE: lag (seconds) has a special meaning here
         // (it is not the same that replay_lag/write_lag/flush_lag from pg_stat_replication view)
         // and it is only useful when synced column is false,
@@ -15,16 +21,37 @@ impl SystemDal<'_, '_> {
         let pg_row = sqlx::query(
             "SELECT \
                  pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() AS synced, \
-                 EXTRACT(SECONDS FROM now() - pg_last_xact_replay_timestamp())::int AS lag",
+                 EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::bigint AS lag",
         )
         .fetch_one(self.storage.conn())
-        .await
-        .unwrap();
+        .await?;
 
-        match pg_row.get("synced") {
-            Some(false) => pg_row.try_get::<i64, &str>("lag").unwrap_or_default() as u32,
-            // We are synced, no lag
+        let synced: Option<bool> = pg_row.try_get("synced")?;
+        Ok(match synced {
+            Some(false) => {
+                // `lag` is `NULL` if no transactions were replayed yet.
+                let lag: Option<i64> = pg_row.try_get("lag")?;
+                lag.map_or(0, |lag| lag.clamp(0, u32::MAX.into()) as u32)
+            }
+            // We are synced (or the database is not a replica), no lag
             _ => 0,
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn replication_lag_for_primary_database() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let lag = storage
+            .system_dal()
+            .try_get_replication_lag_sec()
+            .await
+            .unwrap();
+        assert_eq!(lag, 0);
     }
 }
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use zksync_types::{tee_types::TeeType, L1BatchNumber};
+
+use crate::{time_utils::pg_interval_from_duration, SqlxError, StorageProcessor};
+
+/// DAL for TEE proofs of L1 batches, which are generated alongside ZK proofs stored in `proof_generation_details`.
+#[derive(Debug)]
+pub struct TeeProofGenerationDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TeeProofGenerationDal<'_, '_> {
+    /// Picks the next L1 batch to be proven by a TEE prover. Batches are picked in the order of their numbers;
+    /// batches picked more than `processing_timeout` ago without a submitted proof are picked again.
+    pub async fn get_next_block_to_be_proven(
+        &mut self,
+        processing_timeout: Duration,
+    ) -> Option<L1BatchNumber> {
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        sqlx::query!(
+            r#"
+            UPDATE tee_proof_generation_details
+            SET
+                status = 'picked_by_prover',
+                updated_at = NOW(),
+                prover_taken_at = NOW()
+            WHERE
+                l1_batch_number = (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        tee_proof_generation_details
+                    WHERE
+                        status = 'ready_to_be_proven'
+                        OR (
+                            status = 'picked_by_prover'
+                            AND prover_taken_at < NOW() - $1::INTERVAL
+                        )
+                    ORDER BY
+                        l1_batch_number ASC
+                    LIMIT
+                        1
+                    FOR UPDATE
+                        SKIP LOCKED
+                )
+            RETURNING
+                tee_proof_generation_details.l1_batch_number
+            "#,
+            &processing_timeout,
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+    }
+
+    /// Saves a TEE proof for the L1 batch. Returns [`SqlxError::RowNotFound`] if the batch isn't picked by a TEE prover
+    /// (e.g., it's not ready to be proven, or its proof is already saved).
+    pub async fn save_proof_artifacts_metadata(
+        &mut self,
+        block_number: L1BatchNumber,
+        signature: &[u8],
+        pubkey: &[u8],
+        proof: &[u8],
+        tee_type: TeeType,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            r#"
+            UPDATE tee_proof_generation_details
+            SET
+                status = 'generated',
+                signature = $1,
+                pubkey = $2,
+                proof = $3,
+                tee_type = $4,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $5
+                AND status = 'picked_by_prover'
+            "#,
+            signature,
+            pubkey,
+            proof,
+            tee_type.to_string(),
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await?
+        .rows_affected()
+        .eq(&1)
+        .then_some(())
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn insert_tee_proof_generation_details(&mut self, block_number: L1BatchNumber) {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                tee_proof_generation_details (l1_batch_number, status, created_at, updated_at)
+            VALUES
+                ($1, 'ready_to_be_proven', NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            block_number.0 as i64,
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Registers a TEE public key together with its attestation. Re-registering a key replaces its attestation.
+    pub async fn save_attestation(
+        &mut self,
+        pubkey: &[u8],
+        attestation: &[u8],
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                tee_attestations (pubkey, attestation, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (pubkey) DO
+            UPDATE
+            SET
+                attestation = $2,
+                updated_at = NOW()
+            "#,
+            pubkey,
+            attestation,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn is_attestation_registered(&mut self, pubkey: &[u8]) -> Result<bool, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        tee_attestations
+                    WHERE
+                        pubkey = $1
+                ) AS "exists!"
+            "#,
+            pubkey,
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.exists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        Address, ProtocolVersion, ProtocolVersionId,
+    };
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_tee_proof_requires_picked_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        conn.tee_verifier_input_producer_dal()
+            .create_tee_verifier_input_producer_job(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let dal = &mut conn.tee_proof_generation_dal();
+        dal.insert_tee_proof_generation_details(L1BatchNumber(1))
+            .await;
+        dal.save_attestation(&[1; 33], b"attestation")
+            .await
+            .unwrap();
+        assert!(dal.is_attestation_registered(&[1; 33]).await.unwrap());
+        assert!(!dal.is_attestation_registered(&[2; 33]).await.unwrap());
+
+        // The batch isn't picked by a prover yet.
+        let err = dal
+            .save_proof_artifacts_metadata(L1BatchNumber(1), &[0; 64], &[1; 33], &[], TeeType::Sgx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SqlxError::RowNotFound), "{err:?}");
+
+        let picked = dal
+            .get_next_block_to_be_proven(Duration::from_secs(60))
+            .await;
+        assert_eq!(picked, Some(L1BatchNumber(1)));
+        dal.save_proof_artifacts_metadata(L1BatchNumber(1), &[0; 64], &[1; 33], &[], TeeType::Sgx)
+            .await
+            .unwrap();
+
+        // The saved proof must not be overwritten.
+        let err = dal
+            .save_proof_artifacts_metadata(L1BatchNumber(1), &[1; 64], &[1; 33], &[], TeeType::Sgx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SqlxError::RowNotFound), "{err:?}");
+        let picked = dal
+            .get_next_block_to_be_proven(Duration::from_secs(60))
+            .await;
+        assert_eq!(picked, None);
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::{Duration, Instant};
+
+use sqlx::postgres::types::PgInterval;
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    instrument::InstrumentExt,
+    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    StorageProcessor,
+};
+
+/// DAL for the jobs of the TEE verifier input producer, which prepares inputs for TEE provers.
+#[derive(Debug)]
+pub struct TeeVerifierInputProducerDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// The amount of attempts to process a job before giving up.
+pub const JOB_MAX_ATTEMPT: i16 = 10;
+
+/// Time to wait for job to be processed
+const JOB_PROCESSING_TIMEOUT: PgInterval = pg_interval_from_duration(Duration::from_secs(10 * 60));
+
+/// Status of a TEE verifier input producer job. Mirrors the lifecycle of basic witness input producer jobs.
+#[derive(Debug, sqlx::Type)]
+#[sqlx(type_name = "tee_verifier_input_producer_job_status")]
+pub enum TeeVerifierInputProducerJobStatus {
+    /// The job is queued. Jobs are created once the basic witness input for the L1 batch is produced.
+    Queued,
+    /// The job is not going to be processed; designed for manual operations on DB.
+    ManuallySkipped,
+    /// The job is being processed.
+    InProgress,
+    /// The TEE verifier input was uploaded to the object store.
+    Successful,
+    /// The job failed; it's retried until it reaches [`JOB_MAX_ATTEMPT`] attempts.
+    Failed,
+}
+
+impl TeeVerifierInputProducerDal<'_, '_> {
+    pub async fn create_tee_verifier_input_producer_job(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                tee_verifier_input_producer_jobs (l1_batch_number, status, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            l1_batch_number.0 as i64,
+            TeeVerifierInputProducerJobStatus::Queued as TeeVerifierInputProducerJobStatus,
+        )
+        .instrument("create_tee_verifier_input_producer_job")
+        .report_latency()
+        .execute(self.storage.conn())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_next_tee_verifier_input_producer_job(
+        &mut self,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let l1_batch_number = sqlx::query!(
+            r#"
+            UPDATE tee_verifier_input_producer_jobs
+            SET
+                status = $1,
+                attempts = attempts + 1,
+                updated_at = NOW(),
+                processing_started_at = NOW()
+            WHERE
+                l1_batch_number = (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        tee_verifier_input_producer_jobs
+                    WHERE
+                        status = $2
+                        OR (
+                            status = $1
+                            AND processing_started_at < NOW() - $4::INTERVAL
+                        )
+                        OR (
+                            status = $3
+                            AND attempts < $5
+                        )
+                    ORDER BY
+                        l1_batch_number ASC
+                    LIMIT
+                        1
+                    FOR UPDATE
+                        SKIP LOCKED
+                )
+            RETURNING
+                tee_verifier_input_producer_jobs.l1_batch_number
+            "#,
+            TeeVerifierInputProducerJobStatus::InProgress as TeeVerifierInputProducerJobStatus,
+            TeeVerifierInputProducerJobStatus::Queued as TeeVerifierInputProducerJobStatus,
+            TeeVerifierInputProducerJobStatus::Failed as TeeVerifierInputProducerJobStatus,
+            &JOB_PROCESSING_TIMEOUT,
+            JOB_MAX_ATTEMPT,
+        )
+        .instrument("get_next_tee_verifier_input_producer_job")
+        .report_latency()
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|job| L1BatchNumber(job.l1_batch_number as u32));
+
+        Ok(l1_batch_number)
+    }
+
+    pub async fn get_tee_verifier_input_producer_job_attempts(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<u32>> {
+        let attempts = sqlx::query!(
+            r#"
+            SELECT
+                attempts
+            FROM
+                tee_verifier_input_producer_jobs
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|job| job.attempts as u32);
+
+        Ok(attempts)
+    }
+
+    pub async fn mark_job_as_successful(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        started_at: Instant,
+        object_path: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tee_verifier_input_producer_jobs
+            SET
+                status = $1,
+                updated_at = NOW(),
+                time_taken = $3,
+                input_blob_url = $4
+            WHERE
+                l1_batch_number = $2
+            "#,
+            TeeVerifierInputProducerJobStatus::Successful as TeeVerifierInputProducerJobStatus,
+            l1_batch_number.0 as i64,
+            duration_to_naive_time(started_at.elapsed()),
+            object_path,
+        )
+        .instrument("mark_job_as_successful")
+        .report_latency()
+        .execute(self.storage.conn())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_job_as_failed(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        started_at: Instant,
+        error: String,
+    ) -> sqlx::Result<Option<u32>> {
+        let attempts = sqlx::query!(
+            r#"
+            UPDATE tee_verifier_input_producer_jobs
+            SET
+                status = $1,
+                updated_at = NOW(),
+                time_taken = $3,
+                error = $4
+            WHERE
+                l1_batch_number = $2
+                AND status != $5
+            RETURNING
+                tee_verifier_input_producer_jobs.attempts
+            "#,
+            TeeVerifierInputProducerJobStatus::Failed as TeeVerifierInputProducerJobStatus,
+            l1_batch_number.0 as i64,
+            duration_to_naive_time(started_at.elapsed()),
+            error,
+            TeeVerifierInputProducerJobStatus::Successful as TeeVerifierInputProducerJobStatus,
+        )
+        .instrument("mark_job_as_failed")
+        .report_latency()
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|job| job.attempts as u32);
+
+        Ok(attempts)
+    }
+}
@@ -1,16 +1,21 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
+use assert_matches::assert_matches;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    api::TransactionStatus,
     block::{L1BatchHeader, MiniblockHasher, MiniblockHeader},
     fee::{Fee, TransactionExecutionMetrics},
     helpers::unix_timestamp_ms,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     l2::L2Tx,
     proofs::AggregationRound,
+    protocol_version::{FriProtocolVersionId, L1VerifierConfig},
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
     Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, ProtocolVersion, ProtocolVersionId, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, U256,
+    Nonce, PriorityOpId, ProtocolVersion, ProtocolVersionId, Transaction, H160, H256,
+    MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
 
 use crate::{
@@ -18,7 +23,7 @@ use crate::{
     connection::ConnectionPool,
     protocol_versions_dal::ProtocolVersionsDal,
     prover_dal::{GetProverJobsParams, ProverDal},
-    transactions_dal::{L2TxSubmissionResult, TransactionsDal},
+    transactions_dal::{L2TxSubmissionResult, ResetTransactionsStats, TransactionsDal},
     transactions_web3_dal::TransactionsWeb3Dal,
 };
 
@@ -40,6 +45,7 @@ pub(crate) fn create_miniblock_header(number: u32) -> MiniblockHeader {
         base_fee_per_gas: 100,
         l1_gas_price: 100,
         l2_fair_gas_price: 100,
+        gas_per_pubdata: None,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 1,
@@ -70,7 +76,7 @@ pub(crate) fn mock_l2_transaction() -> L2Tx {
     l2_tx
 }
 
-fn mock_l1_execute() -> L1Tx {
+pub(crate) fn mock_l1_execute() -> L1Tx {
     let serial_id = 1;
     let priority_op_data = L1TxCommonData {
         sender: H160::random(),
@@ -165,6 +171,120 @@ async fn workflow_with_submit_tx_diff_hashes() {
     assert_eq!(result, L2TxSubmissionResult::Replaced);
 }
 
+#[tokio::test]
+async fn replaced_tx_has_replaced_status() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+
+    let tx = mock_l2_transaction();
+    let replaced_hash = tx.hash();
+    let nonce = tx.common_data.nonce;
+    let initiator_address = tx.common_data.initiator_address;
+    let result = storage
+        .transactions_dal()
+        .insert_transaction_l2(tx, mock_tx_execution_metrics())
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Added);
+
+    let (pending_hash, _) = storage
+        .transactions_dal()
+        .get_replaceable_l2_tx(initiator_address, nonce)
+        .await
+        .unwrap();
+    assert_eq!(pending_hash, replaced_hash);
+
+    let mut tx = mock_l2_transaction();
+    tx.common_data.nonce = nonce;
+    tx.common_data.initiator_address = initiator_address;
+    let new_hash = tx.hash();
+    let result = storage
+        .transactions_dal()
+        .insert_transaction_l2(tx, mock_tx_execution_metrics())
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+
+    let (pending_hash, _) = storage
+        .transactions_dal()
+        .get_replaceable_l2_tx(initiator_address, nonce)
+        .await
+        .unwrap();
+    assert_eq!(pending_hash, new_hash);
+
+    let details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(replaced_hash)
+        .await
+        .unwrap()
+        .expect("no details for replaced transaction");
+    assert_matches!(details.status, TransactionStatus::Replaced);
+    assert_eq!(details.initiator_address, initiator_address);
+
+    let details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(new_hash)
+        .await
+        .unwrap()
+        .expect("no details for new transaction");
+    assert_matches!(details.status, TransactionStatus::Pending);
+}
+
+#[tokio::test]
+async fn expired_tx_has_expired_status() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let now = unix_timestamp_ms() / 1_000;
+
+    let expired_tx = mock_l2_transaction();
+    let expired_hash = expired_tx.hash();
+    let expired_account = expired_tx.initiator_account();
+    let valid_tx = mock_l2_transaction();
+    let valid_hash = valid_tx.hash();
+    let mut old_tx = mock_l2_transaction();
+    old_tx.received_timestamp_ms = unix_timestamp_ms() - 3_600_000;
+    let old_hash = old_tx.hash();
+    let old_account = old_tx.initiator_account();
+
+    let mut transactions_dal = storage.transactions_dal();
+    for (tx, valid_until) in [
+        (expired_tx, Some(now - 10)),
+        (valid_tx, Some(now + 3_600)),
+        (old_tx, None),
+    ] {
+        let result = transactions_dal
+            .insert_transaction_l2_with_deadline(tx, mock_tx_execution_metrics(), valid_until)
+            .await;
+        assert_eq!(result, L2TxSubmissionResult::Added);
+    }
+
+    let expired = transactions_dal.mark_expired_txs(None).await;
+    assert_eq!(expired, [(expired_account, Nonce(0))]);
+    let expired = transactions_dal
+        .mark_expired_txs(Some(Duration::from_secs(60)))
+        .await;
+    assert_eq!(expired, [(old_account, Nonce(0))]);
+    let expired = transactions_dal
+        .mark_expired_txs(Some(Duration::from_secs(60)))
+        .await;
+    assert!(expired.is_empty());
+
+    for hash in [expired_hash, old_hash] {
+        let details = storage
+            .transactions_web3_dal()
+            .get_transaction_details(hash)
+            .await
+            .unwrap()
+            .expect("no details for expired transaction");
+        assert_matches!(details.status, TransactionStatus::Expired);
+    }
+    let details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(valid_hash)
+        .await
+        .unwrap()
+        .expect("no details for valid transaction");
+    assert_matches!(details.status, TransactionStatus::Pending);
+}
+
 #[tokio::test]
 async fn remove_stuck_txs() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -207,7 +327,7 @@ async fn remove_stuck_txs() {
     // Get all txs
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
         .await
         .0;
     assert_eq!(txs.len(), 4);
@@ -230,19 +350,23 @@ async fn remove_stuck_txs() {
     // Get all txs
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
         .await
         .0;
     assert_eq!(txs.len(), 3);
+    let pending_l2_txs_count = transactions_dal.get_pending_l2_txs_count().await.unwrap();
+    assert_eq!(pending_l2_txs_count, 2);
 
     // Remove one stuck tx
     let removed_txs = transactions_dal
         .remove_stuck_txs(Duration::from_secs(500))
         .await;
     assert_eq!(removed_txs, 1);
+    let pending_l2_txs_count = transactions_dal.get_pending_l2_txs_count().await.unwrap();
+    assert_eq!(pending_l2_txs_count, 1);
     transactions_dal.reset_mempool().await;
     let txs = transactions_dal
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
         .await
         .0;
     assert_eq!(txs.len(), 2);
@@ -257,6 +381,125 @@ async fn remove_stuck_txs() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn removing_evicted_txs() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut protocol_versions_dal = ProtocolVersionsDal { storage };
+    protocol_versions_dal
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+
+    let storage = protocol_versions_dal.storage;
+    let mut transactions_dal = TransactionsDal { storage };
+    let evicted_tx = mock_l2_transaction();
+    let evicted_tx_hash = evicted_tx.hash();
+    transactions_dal
+        .insert_transaction_l2(evicted_tx, mock_tx_execution_metrics())
+        .await;
+    let kept_tx = mock_l2_transaction();
+    let kept_tx_hash = kept_tx.hash();
+    transactions_dal
+        .insert_transaction_l2(kept_tx, mock_tx_execution_metrics())
+        .await;
+
+    let txs = transactions_dal
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
+        .await
+        .0;
+    assert_eq!(txs.len(), 2);
+    transactions_dal
+        .sync_mempool(vec![], vec![], vec![evicted_tx_hash], 0, 0, 1000)
+        .await;
+
+    // Evicted transactions must not be loaded into the mempool again.
+    transactions_dal.reset_mempool().await;
+    let txs = transactions_dal
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
+        .await
+        .0;
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(tx_hashes, [kept_tx_hash]);
+}
+
+#[tokio::test]
+async fn resetting_transactions_state() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+
+    let l1_tx = mock_l1_execute();
+    storage
+        .transactions_dal()
+        .insert_transaction_l1(l1_tx.clone(), L1BlockNumber(1))
+        .await;
+    let l2_tx = mock_l2_transaction();
+    let mut conflicting_l2_tx = mock_l2_transaction();
+    conflicting_l2_tx.common_data.initiator_address = l1_tx.common_data.sender;
+    for tx in [&l2_tx, &conflicting_l2_tx] {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+    }
+    let (txs, _) = storage
+        .transactions_dal()
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
+        .await;
+    assert_eq!(txs.len(), 3);
+
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock_header(1))
+        .await
+        .unwrap();
+    let l1_tx_result = TransactionExecutionResult {
+        hash: l1_tx.hash(),
+        transaction: l1_tx.clone().into(),
+        ..mock_execution_result(l2_tx.clone())
+    };
+    let executed_txs = [
+        l1_tx_result,
+        mock_execution_result(l2_tx.clone()),
+        mock_execution_result(conflicting_l2_tx.clone()),
+    ];
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &executed_txs, U256::from(1))
+        .await;
+
+    let stats = storage
+        .transactions_dal()
+        .reset_transactions_state(MiniblockNumber(0))
+        .await;
+    assert_eq!(
+        stats,
+        ResetTransactionsStats {
+            priority_txs: 1,
+            reinjected_l2_txs: 1,
+            dropped_l2_txs: 1,
+        }
+    );
+
+    // Reset transactions should be loaded into the mempool without resetting it.
+    let (txs, _) = storage
+        .transactions_dal()
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
+        .await;
+    let tx_hashes: HashSet<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(tx_hashes, HashSet::from([l1_tx.hash(), l2_tx.hash()]));
+
+    let conflicting_tx = storage
+        .transactions_web3_dal()
+        .get_transaction_details(conflicting_l2_tx.hash())
+        .await
+        .unwrap();
+    assert!(conflicting_tx.is_none());
+}
+
 fn create_circuits() -> Vec<(&'static str, String)> {
     vec![
         ("Main VM", "1_0_Main VM_BasicCircuits.bin".to_owned()),
@@ -397,3 +640,321 @@ async fn test_requeue_prover_jobs() {
         assert!(job.is_some());
     }
 }
+
+#[tokio::test]
+async fn high_priority_batches_are_served_first_in_downstream_prover_queues() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    let protocol_version = FriProtocolVersionId::latest();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+        .await;
+
+    for number in 1..=3 {
+        let l1_batch_number = L1BatchNumber(number);
+        let header = L1BatchHeader::new(
+            l1_batch_number,
+            number.into(),
+            Default::default(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, &[], Default::default(), &[], &[])
+            .await
+            .unwrap();
+        storage
+            .fri_witness_generator_dal()
+            .save_witness_inputs(l1_batch_number, "witness_inputs", protocol_version, false)
+            .await;
+        storage
+            .fri_prover_jobs_dal()
+            .insert_prover_jobs(
+                l1_batch_number,
+                vec![(1, format!("circuit_{number}"))],
+                AggregationRound::BasicCircuits,
+                0,
+                protocol_version,
+            )
+            .await;
+        storage
+            .fri_proof_compressor_dal()
+            .insert_proof_compression_job(l1_batch_number, "proof")
+            .await;
+    }
+    // The batch is prioritized after its witness inputs are received, e.g. via the prover queue API.
+    storage
+        .fri_witness_generator_dal()
+        .mark_witness_job_as_high_priority(L1BatchNumber(2))
+        .await
+        .unwrap();
+
+    let mut prover_job_batches = vec![];
+    while let Some(job) = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[protocol_version], "test")
+        .await
+    {
+        prover_job_batches.push(job.block_number);
+    }
+    assert_eq!(
+        prover_job_batches,
+        [L1BatchNumber(2), L1BatchNumber(1), L1BatchNumber(3)]
+    );
+
+    let mut compression_job_batches = vec![];
+    while let Some(l1_batch_number) = storage
+        .fri_proof_compressor_dal()
+        .get_next_proof_compression_job("test")
+        .await
+    {
+        compression_job_batches.push(l1_batch_number);
+    }
+    assert_eq!(
+        compression_job_batches,
+        [L1BatchNumber(2), L1BatchNumber(1), L1BatchNumber(3)]
+    );
+}
+
+#[tokio::test]
+async fn quarantined_fri_jobs_are_reported_and_released() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    let protocol_version = FriProtocolVersionId::latest();
+    storage
+        .fri_protocol_versions_dal()
+        .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+        .await;
+    let l1_batch_number = L1BatchNumber(1);
+    let header = L1BatchHeader::new(
+        l1_batch_number,
+        1,
+        Default::default(),
+        Default::default(),
+        ProtocolVersionId::latest(),
+    );
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, &[], Default::default(), &[], &[])
+        .await
+        .unwrap();
+    storage
+        .fri_witness_generator_dal()
+        .save_witness_inputs(l1_batch_number, "witness_inputs", protocol_version, false)
+        .await;
+    storage
+        .fri_prover_jobs_dal()
+        .insert_prover_jobs(
+            l1_batch_number,
+            vec![(1, "circuit".to_owned())],
+            AggregationRound::BasicCircuits,
+            0,
+            protocol_version,
+        )
+        .await;
+
+    // Fail both jobs once; with `max_attempts == 1`, they should be quarantined.
+    let picked_l1_batch = storage
+        .fri_witness_generator_dal()
+        .get_next_basic_circuit_witness_job(u32::MAX, &[protocol_version], "test")
+        .await;
+    assert_eq!(picked_l1_batch, Some(l1_batch_number));
+    storage
+        .fri_witness_generator_dal()
+        .mark_witness_job_failed("error", l1_batch_number)
+        .await;
+    let prover_job = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[protocol_version], "test")
+        .await
+        .unwrap();
+    storage
+        .fri_prover_jobs_dal()
+        .save_proof_error(prover_job.id, "error".to_owned())
+        .await;
+
+    let quarantined_jobs = storage
+        .fri_witness_generator_dal()
+        .quarantine_failed_jobs(1)
+        .await;
+    assert_eq!(quarantined_jobs.len(), 1);
+    let quarantined_jobs = storage
+        .fri_prover_jobs_dal()
+        .quarantine_failed_jobs(1)
+        .await;
+    assert_eq!(quarantined_jobs.len(), 1);
+
+    let witness_stats = storage
+        .fri_witness_generator_dal()
+        .get_witness_jobs_stats(AggregationRound::BasicCircuits)
+        .await;
+    assert_eq!(witness_stats.quarantined, 1);
+    assert_eq!(witness_stats.failed, 0);
+    let prover_stats = storage.fri_prover_jobs_dal().get_prover_jobs_stats().await;
+    let prover_stats = prover_stats[&(1, AggregationRound::BasicCircuits as u8)];
+    assert_eq!(prover_stats.quarantined, 1);
+    assert_eq!(prover_stats.failed, 0);
+
+    // Quarantined jobs must not be picked.
+    let picked_l1_batch = storage
+        .fri_witness_generator_dal()
+        .get_next_basic_circuit_witness_job(u32::MAX, &[protocol_version], "test")
+        .await;
+    assert_eq!(picked_l1_batch, None);
+    let prover_job = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[protocol_version], "test")
+        .await;
+    assert!(prover_job.is_none());
+
+    let requeued_jobs = storage
+        .fri_witness_generator_dal()
+        .requeue_quarantined_jobs(l1_batch_number)
+        .await
+        .unwrap();
+    assert_eq!(requeued_jobs, 1);
+    let requeued_jobs = storage
+        .fri_prover_jobs_dal()
+        .requeue_quarantined_jobs(l1_batch_number)
+        .await
+        .unwrap();
+    assert_eq!(requeued_jobs, 1);
+
+    let witness_stats = storage
+        .fri_witness_generator_dal()
+        .get_witness_jobs_stats(AggregationRound::BasicCircuits)
+        .await;
+    assert_eq!(witness_stats.quarantined, 0);
+    assert_eq!(witness_stats.queued, 1);
+    let picked_l1_batch = storage
+        .fri_witness_generator_dal()
+        .get_next_basic_circuit_witness_job(u32::MAX, &[protocol_version], "test")
+        .await;
+    assert_eq!(picked_l1_batch, Some(l1_batch_number));
+    let prover_job = storage
+        .fri_prover_jobs_dal()
+        .get_next_job(&[protocol_version], "test")
+        .await
+        .unwrap();
+    assert_eq!(prover_job.block_number, l1_batch_number);
+    let attempts = storage
+        .fri_prover_jobs_dal()
+        .get_prover_job_attempts(prover_job.id)
+        .await
+        .unwrap();
+    assert_eq!(attempts, Some(1));
+}
+
+#[tokio::test]
+async fn recovering_operator_addresses_for_legacy_eth_txs() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let old_operator = Address::repeat_byte(1);
+    let new_operator = Address::repeat_byte(2);
+    let mut eth_tx_ids = vec![];
+    for nonce in 0..3 {
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                nonce,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::default(),
+                0,
+                old_operator,
+            )
+            .await
+            .unwrap();
+        eth_tx_ids.push(eth_tx.id);
+    }
+    for (i, tx_hash) in [H256::repeat_byte(1), H256::repeat_byte(2)]
+        .into_iter()
+        .enumerate()
+    {
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx_ids[0], i as u64, 0, tx_hash, vec![])
+            .await
+            .unwrap();
+    }
+    // Emulate txs created before operator addresses were tracked.
+    sqlx::query("UPDATE eth_txs SET from_addr = NULL")
+        .execute(storage.conn())
+        .await
+        .unwrap();
+
+    // Only sent txs are returned since unsent ones have no known signer.
+    let legacy_txs = storage
+        .eth_sender_dal()
+        .get_sent_txs_without_from_addr()
+        .await
+        .unwrap();
+    assert_eq!(
+        legacy_txs,
+        [(
+            eth_tx_ids[0],
+            vec![H256::repeat_byte(2), H256::repeat_byte(1)]
+        )]
+    );
+
+    storage
+        .eth_sender_dal()
+        .set_from_addr(eth_tx_ids[0], old_operator)
+        .await
+        .unwrap();
+    // Already set addresses must not be overwritten.
+    storage
+        .eth_sender_dal()
+        .set_from_addr(eth_tx_ids[0], new_operator)
+        .await
+        .unwrap();
+    let legacy_txs = storage
+        .eth_sender_dal()
+        .get_sent_txs_without_from_addr()
+        .await
+        .unwrap();
+    assert!(legacy_txs.is_empty(), "{legacy_txs:?}");
+    let next_nonce = storage
+        .eth_sender_dal()
+        .get_next_nonce(old_operator)
+        .await
+        .unwrap();
+    assert_eq!(next_nonce, Some(1));
+
+    // Unsent txs without an address are reassigned to the current operator.
+    let reassigned_tx_count = storage
+        .eth_sender_dal()
+        .reassign_unsent_txs(new_operator, 5)
+        .await
+        .unwrap();
+    assert_eq!(reassigned_tx_count, 2);
+    let new_txs = storage.eth_sender_dal().get_new_eth_txs(10).await.unwrap();
+    let new_txs: Vec<_> = new_txs
+        .iter()
+        .map(|tx| (tx.id, tx.nonce.0, tx.from_addr))
+        .collect();
+    assert_eq!(
+        new_txs,
+        [
+            (eth_tx_ids[1], 5, Some(new_operator)),
+            (eth_tx_ids[2], 6, Some(new_operator)),
+        ]
+    );
+    let sent_tx = storage
+        .eth_sender_dal()
+        .get_eth_tx(eth_tx_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sent_tx.from_addr, Some(old_operator));
+}
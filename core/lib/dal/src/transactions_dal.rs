@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
 
 use anyhow::Context;
 use bigdecimal::BigDecimal;
@@ -6,7 +10,7 @@ use itertools::Itertools;
 use sqlx::{error, types::chrono::NaiveDateTime};
 use zksync_types::{
     block::MiniblockExecutionData,
-    fee::TransactionExecutionMetrics,
+    fee::{Fee, TransactionExecutionMetrics},
     get_nonce_key,
     l1::L1Tx,
     l2::L2Tx,
@@ -16,7 +20,7 @@ use zksync_types::{
     Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
     PriorityOpId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
-use zksync_utils::{h256_to_u32, u256_to_big_decimal};
+use zksync_utils::{bigdecimal_to_u256, h256_to_u32, u256_to_big_decimal};
 
 use crate::{
     instrument::InstrumentExt,
@@ -25,6 +29,9 @@ use crate::{
     StorageProcessor,
 };
 
+/// Error set for L2 transactions dropped from the mempool after their validity deadline.
+pub(crate) const EXPIRED_TX_ERROR: &str = "transaction expired";
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum L2TxSubmissionResult {
     Added,
@@ -46,6 +53,17 @@ impl fmt::Display for L2TxSubmissionResult {
     }
 }
 
+/// Statistics for transactions reset by [`TransactionsDal::reset_transactions_state()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResetTransactionsStats {
+    /// Number of L1 priority operations that will be executed again.
+    pub priority_txs: usize,
+    /// Number of L2 transactions returned to the mempool.
+    pub reinjected_l2_txs: usize,
+    /// Number of L2 transactions removed because their initiator has a reset L1 priority operation.
+    pub dropped_l2_txs: usize,
+}
+
 #[derive(Debug)]
 pub struct TransactionsDal<'c, 'a> {
     pub(crate) storage: &'c mut StorageProcessor<'a>,
@@ -78,6 +96,10 @@ impl TransactionsDal<'_, '_> {
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
+            // The L1 transaction hash is zero if it's unknown, e.g. if the external node receives a priority operation
+            // that was stored on the main node before the hash was persisted.
+            let l1_tx_hash = tx.common_data.eth_hash;
+            let l1_tx_hash = (l1_tx_hash != H256::zero()).then_some(l1_tx_hash.as_bytes());
 
             sqlx::query!(
                 r#"
@@ -102,6 +124,7 @@ impl TransactionsDal<'_, '_> {
                         l1_tx_mint,
                         l1_tx_refund_recipient,
                         received_at,
+                        l1_tx_hash,
                         created_at,
                         updated_at
                     )
@@ -126,6 +149,7 @@ impl TransactionsDal<'_, '_> {
                         $16,
                         $17,
                         $18,
+                        $19,
                         NOW(),
                         NOW()
                     )
@@ -149,6 +173,7 @@ impl TransactionsDal<'_, '_> {
                 to_mint,
                 refund_recipient,
                 received_at,
+                l1_tx_hash,
             )
             .fetch_optional(self.storage.conn())
             .await
@@ -254,6 +279,19 @@ impl TransactionsDal<'_, '_> {
         &mut self,
         tx: L2Tx,
         exec_info: TransactionExecutionMetrics,
+    ) -> L2TxSubmissionResult {
+        self.insert_transaction_l2_with_deadline(tx, exec_info, None)
+            .await
+    }
+
+    /// Inserts an L2 transaction that is valid until the specified UNIX timestamp (in seconds).
+    /// Once the deadline passes, the transaction is marked as expired by [`Self::mark_expired_txs()`]
+    /// unless it has been included into a miniblock.
+    pub async fn insert_transaction_l2_with_deadline(
+        &mut self,
+        tx: L2Tx,
+        exec_info: TransactionExecutionMetrics,
+        valid_until: Option<u64>,
     ) -> L2TxSubmissionResult {
         {
             let tx_hash = tx.hash();
@@ -277,6 +315,60 @@ impl TransactionsDal<'_, '_> {
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
+            // The deadline is bounded by the caller (see `TxSender::submit_tx_with_deadline()`),
+            // so out-of-range values are considered a logical error.
+            let expires_at = valid_until.map(|timestamp| {
+                i64::try_from(timestamp)
+                    .ok()
+                    .and_then(|timestamp| NaiveDateTime::from_timestamp_opt(timestamp, 0))
+                    .expect("transaction deadline is out of range")
+            });
+
+            let mut transaction = self.storage.start_transaction().await.unwrap();
+            // If there is a pending transaction with the same initiator and nonce, it will be replaced
+            // by the query below. We record its hash so that it can be reported as replaced.
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    replaced_transactions (
+                        hash,
+                        replaced_by,
+                        initiator_address,
+                        nonce,
+                        gas_per_pubdata_limit,
+                        received_at,
+                        created_at
+                    )
+                SELECT
+                    hash,
+                    $1,
+                    initiator_address,
+                    nonce,
+                    gas_per_pubdata_limit,
+                    received_at,
+                    NOW()
+                FROM
+                    transactions
+                WHERE
+                    initiator_address = $2
+                    AND nonce = $3
+                    AND hash != $1
+                    AND is_priority = FALSE
+                    AND miniblock_number IS NULL
+                ON CONFLICT (hash) DO
+                UPDATE
+                SET
+                    replaced_by = $1,
+                    created_at = NOW()
+                "#,
+                tx_hash.as_bytes(),
+                initiator_address.as_bytes(),
+                nonce
+            )
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+
             // Besides just adding or updating(on conflict) the record, we want to extract some info
             // from the query below, to indicate what actually happened:
             // 1) transaction is added
@@ -309,6 +401,7 @@ impl TransactionsDal<'_, '_> {
                         paymaster_input,
                         execution_info,
                         received_at,
+                        expires_at,
                         created_at,
                         updated_at
                     )
@@ -332,6 +425,7 @@ impl TransactionsDal<'_, '_> {
                         $15,
                         JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
                         $19,
+                        $20,
                         NOW(),
                         NOW()
                     )
@@ -354,6 +448,7 @@ impl TransactionsDal<'_, '_> {
                     execution_info = JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
                     in_mempool = FALSE,
                     received_at = $19,
+                    expires_at = $20,
                     created_at = NOW(),
                     updated_at = NOW(),
                     error = NULL
@@ -389,9 +484,10 @@ impl TransactionsDal<'_, '_> {
                 exec_info.gas_used as i64,
                 (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
                 exec_info.contracts_used as i32,
-                received_at
+                received_at,
+                expires_at
             )
-                .fetch_optional(self.storage.conn())
+                .fetch_optional(transaction.conn())
                 .await
                 .map(|option_record| option_record.map(|record| record.is_replaced));
 
@@ -419,6 +515,7 @@ impl TransactionsDal<'_, '_> {
                     panic!("{}", err);
                 }
             };
+            transaction.commit().await.unwrap();
             tracing::debug!(
                 "{:?} l2 transaction {:?} to DB. init_acc {:?} nonce {:?} returned option {:?}",
                 l2_tx_insertion_result,
@@ -432,6 +529,55 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the hash and fee of a pending L2 transaction with the specified initiator and nonce,
+    /// i.e., a transaction that would be replaced by [`Self::insert_transaction_l2()`].
+    /// The transaction row is locked until the end of the current DB transaction, so that
+    /// concurrent replacements of the same transaction are serialized.
+    pub async fn get_replaceable_l2_tx(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+    ) -> Option<(H256, Fee)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                gas_per_pubdata_limit
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+            FOR UPDATE
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(nonce.0)
+        )
+        .instrument("get_replaceable_l2_tx")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+
+        let fee = Fee {
+            gas_limit: bigdecimal_to_u256(row.gas_limit.unwrap_or_default()),
+            max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas.unwrap_or_default()),
+            max_priority_fee_per_gas: bigdecimal_to_u256(
+                row.max_priority_fee_per_gas.unwrap_or_default(),
+            ),
+            gas_per_pubdata_limit: bigdecimal_to_u256(
+                row.gas_per_pubdata_limit.unwrap_or_default(),
+            ),
+        };
+        Some((H256::from_slice(&row.hash), fee))
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -818,9 +964,18 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
-    pub async fn reset_transactions_state(&mut self, miniblock_number: MiniblockNumber) {
+    /// Resets the state of transactions executed in miniblocks after `miniblock_number`, so that they
+    /// are executed again. L2 transactions are returned to the mempool, which executes them in the nonce order.
+    ///
+    /// Reset L1 priority operations are always executed before L2 transactions, so the relative order
+    /// of an L2 transaction and a priority operation from the same initiator cannot be preserved.
+    /// Hence, such L2 transactions are removed.
+    pub async fn reset_transactions_state(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> ResetTransactionsStats {
         {
-            let tx_hashes = sqlx::query!(
+            let reset_txs = sqlx::query!(
                 r#"
                 UPDATE transactions
                 SET
@@ -828,11 +983,14 @@ impl TransactionsDal<'_, '_> {
                     miniblock_number = NULL,
                     error = NULL,
                     index_in_block = NULL,
-                    execution_info = '{}'
+                    execution_info = '{}',
+                    in_mempool = FALSE
                 WHERE
                     miniblock_number > $1
                 RETURNING
-                    hash
+                    hash,
+                    is_priority,
+                    initiator_address
                 "#,
                 miniblock_number.0 as i64
             )
@@ -845,7 +1003,7 @@ impl TransactionsDal<'_, '_> {
                 WHERE
                     tx_hash = ANY ($1)
                 "#,
-                &tx_hashes
+                &reset_txs
                     .iter()
                     .map(|tx| tx.hash.clone())
                     .collect::<Vec<Vec<u8>>>()
@@ -853,6 +1011,37 @@ impl TransactionsDal<'_, '_> {
             .execute(self.storage.conn())
             .await
             .unwrap();
+
+            let priority_initiators: HashSet<_> = reset_txs
+                .iter()
+                .filter(|tx| tx.is_priority)
+                .map(|tx| tx.initiator_address.as_slice())
+                .collect();
+            let (conflicting_l2_txs, reinjected_l2_txs): (Vec<_>, Vec<_>) = reset_txs
+                .iter()
+                .filter(|tx| !tx.is_priority)
+                .partition(|tx| priority_initiators.contains(tx.initiator_address.as_slice()));
+            let conflicting_hashes: Vec<_> = conflicting_l2_txs
+                .iter()
+                .map(|tx| tx.hash.clone())
+                .collect();
+            sqlx::query!(
+                r#"
+                DELETE FROM transactions
+                WHERE
+                    hash = ANY ($1)
+                "#,
+                &conflicting_hashes
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+
+            ResetTransactionsStats {
+                priority_txs: reset_txs.len() - conflicting_l2_txs.len() - reinjected_l2_txs.len(),
+                reinjected_l2_txs: reinjected_l2_txs.len(),
+                dropped_l2_txs: conflicting_hashes.len(),
+            }
         }
     }
 
@@ -879,6 +1068,55 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Marks pending L2 transactions whose validity deadline has passed as expired, so that they are no longer
+    /// loaded into the mempool. Transactions without an explicit deadline expire once they are older
+    /// than `default_ttl` (if specified). Returns initiators and nonces of the expired transactions.
+    pub async fn mark_expired_txs(
+        &mut self,
+        default_ttl: Option<Duration>,
+    ) -> Vec<(Address, Nonce)> {
+        let default_ttl = default_ttl.map(pg_interval_from_duration);
+        let rows = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                in_mempool = FALSE,
+                error = $1,
+                updated_at = NOW()
+            WHERE
+                miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+                AND (
+                    expires_at < NOW()
+                    OR (
+                        expires_at IS NULL
+                        AND received_at < NOW() - $2::INTERVAL
+                    )
+                )
+            RETURNING
+                initiator_address,
+                nonce AS "nonce!"
+            "#,
+            EXPIRED_TX_ERROR,
+            default_ttl
+        )
+        .instrument("mark_expired_txs")
+        .report_latency()
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        rows.into_iter()
+            .map(|row| {
+                (
+                    Address::from_slice(&row.initiator_address),
+                    Nonce(row.nonce as u32),
+                )
+            })
+            .collect()
+    }
+
     /// Fetches new updates for mempool
     /// Returns new transactions and current nonces for related accounts
     /// Latter is only used to bootstrap mempool for given account
@@ -886,6 +1124,7 @@ impl TransactionsDal<'_, '_> {
         &mut self,
         stashed_accounts: Vec<Address>,
         purged_accounts: Vec<Address>,
+        evicted_txs: Vec<H256>,
         gas_per_pubdata: u32,
         fee_per_gas: u64,
         limit: usize,
@@ -925,6 +1164,21 @@ impl TransactionsDal<'_, '_> {
             .await
             .unwrap();
 
+            let evicted_hashes: Vec<_> = evicted_txs.iter().map(H256::as_bytes).collect();
+            sqlx::query!(
+                r#"
+                DELETE FROM transactions
+                WHERE
+                    in_mempool = TRUE
+                    AND miniblock_number IS NULL
+                    AND hash = ANY ($1)
+                "#,
+                &evicted_hashes as &[&[u8]]
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+
             // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
             let transactions = sqlx::query_as!(
                 StorageTransaction,
@@ -1036,6 +1290,53 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the number of pending L2 transactions that can be loaded into the mempool.
+    pub async fn get_pending_l2_txs_count(&mut self) -> sqlx::Result<usize> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            "#
+        )
+        .instrument("get_pending_l2_txs_count")
+        .fetch_one(self.storage.conn())
+        .await?
+        .count;
+        Ok(count as usize)
+    }
+
+    /// Returns the time the oldest transaction received after `received_after` and not yet included
+    /// into a miniblock was received at.
+    pub async fn get_oldest_pending_tx_received_at(
+        &mut self,
+        received_after: NaiveDateTime,
+    ) -> sqlx::Result<Option<NaiveDateTime>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(received_at) AS "received_at?"
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND error IS NULL
+                AND received_at > $1
+            "#,
+            received_after
+        )
+        .instrument("get_oldest_pending_tx_received_at")
+        .with_arg("received_after", &received_after)
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.received_at)
+    }
+
     pub async fn get_last_processed_l1_block(&mut self) -> Option<L1BlockNumber> {
         {
             sqlx::query!(
@@ -1059,6 +1360,32 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the earliest sealed L1 batch including a priority operation emitted after the specified L1 block.
+    pub async fn get_first_l1_batch_with_priority_ops_after(
+        &mut self,
+        l1_block_number: L1BlockNumber,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(l1_batch_number) AS "l1_batch_number"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND l1_block_number > $1
+            "#,
+            l1_block_number.0 as i32
+        )
+        .instrument("get_first_l1_batch_with_priority_ops_after")
+        .with_arg("l1_block_number", &l1_block_number)
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+
     pub async fn last_priority_id(&mut self) -> Option<PriorityOpId> {
         {
             let op_id = sqlx::query!(
@@ -1329,25 +1656,31 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
-    pub async fn get_call_trace(&mut self, tx_hash: H256) -> Option<Call> {
-        {
-            sqlx::query_as!(
-                CallTrace,
-                r#"
-                SELECT
-                    *
-                FROM
-                    call_traces
-                WHERE
-                    tx_hash = $1
-                "#,
-                tx_hash.as_bytes()
-            )
-            .fetch_optional(self.storage.conn())
-            .await
-            .unwrap()
-            .map(|trace| trace.into())
+    /// Returns the call trace for the specified transaction. If the trace is archived, it is read
+    /// from the cold storage; see [`ColdStorageDal`](crate::cold_storage_dal::ColdStorageDal).
+    pub async fn get_call_trace(&mut self, tx_hash: H256) -> sqlx::Result<Option<Call>> {
+        let call_trace = sqlx::query_as!(
+            CallTrace,
+            r#"
+            SELECT
+                *
+            FROM
+                call_traces
+            WHERE
+                tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        if let Some(call_trace) = call_trace {
+            return Ok(Some(call_trace.into()));
         }
+        self.storage
+            .cold_storage_dal()
+            .get_archived_call_trace(tx_hash)
+            .await
     }
 
     pub(crate) async fn get_tx_by_hash(&mut self, hash: H256) -> Option<Transaction> {
@@ -1,7 +1,10 @@
-use sqlx::types::chrono::NaiveDateTime;
+use std::collections::HashMap;
+
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
-    api, Address, L2ChainId, MiniblockNumber, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
+    api, ethabi, tx::tx_execution_info::ExecutionMetrics, web3::types::Bytes, Address,
+    L1BatchNumber, L2ChainId, MiniblockNumber, Nonce, PriorityOpId, Transaction,
+    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
@@ -120,6 +123,7 @@ impl TransactionsWeb3Dal<'_, '_> {
                         .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
                     logs: vec![],
                     l2_to_l1_logs: vec![],
+                    l2_to_l1_messages: vec![],
                     status,
                     root: block_hash,
                     logs_bloom: Default::default(),
@@ -183,6 +187,7 @@ impl TransactionsWeb3Dal<'_, '_> {
                         })
                         .collect();
                     receipt.l2_to_l1_logs = l2_to_l1_logs;
+                    receipt.l2_to_l1_messages = self.get_l2_to_l1_messages(&receipt).await?;
 
                     Ok(Some(receipt))
                 }
@@ -191,6 +196,57 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Extracts L2-to-L1 messages from the logs of the specified receipt and sets their L1 batch-dependent data.
+    async fn get_l2_to_l1_messages(
+        &mut self,
+        receipt: &api::TransactionReceipt,
+    ) -> Result<Vec<api::L2ToL1Message>, SqlxError> {
+        let mut messages =
+            api::L2ToL1Message::extract_from_logs(&receipt.logs, &receipt.l2_to_l1_logs);
+        let tx_index_in_l1_batch = messages
+            .first()
+            .and_then(|message| message.tx_index_in_l1_batch);
+        let (Some(l1_batch_number), Some(tx_index_in_l1_batch)) =
+            (receipt.l1_batch_number, tx_index_in_l1_batch)
+        else {
+            return Ok(messages);
+        };
+        let l1_batch_number = L1BatchNumber(l1_batch_number.as_u32());
+        let tx_number_in_block = tx_index_in_l1_batch.as_u32() as u16;
+
+        // Indices of transaction logs in the L1 batch; the same as used when building log proofs.
+        let l1_batch_logs = self
+            .storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs(l1_batch_number)
+            .await?;
+        let tx_log_indices: Vec<_> = l1_batch_logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log.tx_number_in_block == tx_number_in_block)
+            .map(|(i, _)| i)
+            .collect();
+        let last_executed_l1_batch = self
+            .storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        let proof_status = if last_executed_l1_batch >= Some(l1_batch_number) {
+            api::L2ToL1MessageProofStatus::Finalizable
+        } else {
+            api::L2ToL1MessageProofStatus::Available
+        };
+
+        for message in &mut messages {
+            let log_index_in_tx = message.log_index_in_tx.as_usize();
+            message.index_in_l1_batch = tx_log_indices
+                .get(log_index_in_tx)
+                .map(|&index| U64::from(index));
+            message.proof_status = proof_status;
+        }
+        Ok(messages)
+    }
+
     pub async fn get_transaction(
         &mut self,
         transaction_id: api::TransactionId,
@@ -280,12 +336,156 @@ impl TransactionsWeb3Dal<'_, '_> {
             .fetch_optional(self.storage.conn())
             .await?;
 
-            let tx = storage_tx_details.map(|tx_details| tx_details.into());
-
-            Ok(tx)
+            if let Some(tx_details) = storage_tx_details {
+                return Ok(Some(tx_details.into()));
+            }
+            self.get_replaced_transaction_details(hash).await
         }
     }
 
+    /// Returns details for a transaction that was replaced by another transaction with the same
+    /// initiator and nonce before being included into a block.
+    async fn get_replaced_transaction_details(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<api::TransactionDetails>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                initiator_address,
+                gas_per_pubdata_limit,
+                received_at
+            FROM
+                replaced_transactions
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_replaced_transaction_details")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| api::TransactionDetails {
+            is_l1_originated: false,
+            status: api::TransactionStatus::Replaced,
+            fee: U256::zero(),
+            gas_per_pubdata: bigdecimal_to_u256(row.gas_per_pubdata_limit.unwrap_or_default()),
+            initiator_address: Address::from_slice(&row.initiator_address),
+            received_at: DateTime::<Utc>::from_naive_utc_and_offset(row.received_at, Utc),
+            eth_commit_tx_hash: None,
+            eth_prove_tx_hash: None,
+            eth_execute_tx_hash: None,
+        }))
+    }
+
+    /// Returns the status of the priority operation emitted by the L1 transaction with the specified hash.
+    /// Returns `None` if the operation is unknown, e.g. if it's not processed by `eth_watch` yet.
+    pub async fn get_priority_op_status(
+        &mut self,
+        l1_tx_hash: H256,
+    ) -> Result<Option<api::PriorityOpStatus>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash,
+                transactions.priority_op_id,
+                transactions.l1_block_number,
+                transactions.miniblock_number,
+                transactions.error,
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                commit_tx.tx_hash AS "eth_commit_tx_hash?",
+                prove_tx.tx_hash AS "eth_prove_tx_hash?",
+                execute_tx.tx_hash AS "eth_execute_tx_hash?"
+            FROM
+                transactions
+                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                transactions.l1_tx_hash = $1
+                AND transactions.is_priority
+            "#,
+            l1_tx_hash.as_bytes()
+        )
+        .instrument("get_priority_op_status")
+        .with_arg("l1_tx_hash", &l1_tx_hash)
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| {
+            let parse_eth_tx_hash = |hash: Option<String>| {
+                hash.map(|hash| hash.parse::<H256>().expect("invalid L1 transaction hash"))
+            };
+            let eth_commit_tx_hash = parse_eth_tx_hash(row.eth_commit_tx_hash);
+            let eth_prove_tx_hash = parse_eth_tx_hash(row.eth_prove_tx_hash);
+            let eth_execute_tx_hash = parse_eth_tx_hash(row.eth_execute_tx_hash);
+            let stage = if eth_execute_tx_hash.is_some() {
+                api::PriorityOpStage::Executed
+            } else if eth_prove_tx_hash.is_some() {
+                api::PriorityOpStage::Proven
+            } else if eth_commit_tx_hash.is_some() {
+                api::PriorityOpStage::Committed
+            } else if row.miniblock_number.is_some() {
+                api::PriorityOpStage::Included
+            } else {
+                api::PriorityOpStage::Queued
+            };
+
+            api::PriorityOpStatus {
+                l1_tx_hash,
+                l2_tx_hash: H256::from_slice(&row.hash),
+                priority_op_id: PriorityOpId(row.priority_op_id.unwrap_or_default() as u64),
+                l1_block_number: row.l1_block_number.unwrap_or_default() as u64,
+                stage,
+                miniblock_number: row
+                    .miniblock_number
+                    .map(|number| MiniblockNumber(number as u32)),
+                l1_batch_number: row
+                    .l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32)),
+                failure_reason: row.error,
+                eth_commit_tx_hash,
+                eth_prove_tx_hash,
+                eth_execute_tx_hash,
+            }
+        }))
+    }
+
+    /// Returns the raw bytes of the transaction with the specified hash, as they were submitted to the node.
+    /// Returns `None` if the transaction is unknown or has no raw representation (e.g., it's an L1 transaction).
+    pub async fn get_raw_transaction(&mut self, hash: H256) -> Result<Option<Vec<u8>>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                input
+            FROM
+                transactions
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_raw_transaction")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.and_then(|row| row.input))
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -382,6 +582,253 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(U256::from(pending_nonce))
     }
 
+    /// Returns nonces of pending L2 transactions (i.e., ones not included into a sealed miniblock and not rejected)
+    /// initiated by the specified account, starting from `from_nonce`. Nonces are returned in the ascending order.
+    pub async fn get_pending_nonces_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+        from_nonce: Nonce,
+    ) -> Result<Vec<Nonce>, SqlxError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                nonce AS "nonce!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce >= $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            ORDER BY
+                nonce
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(from_nonce.0)
+        )
+        .instrument("get_pending_nonces_by_initiator_account")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("from_nonce", &from_nonce)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Nonce(row.nonce as u32))
+            .collect())
+    }
+
+    /// Returns addresses touched by transactions in miniblocks `(from_block, to_block]`: transaction initiators and
+    /// recipients, accounts with written storage slots, event emitters and recipients of ERC-20 transfers (including
+    /// transfers of the base token). Returns one entry per (transaction, address) pair, ordered by the miniblock number,
+    /// the transaction index and the address.
+    pub async fn get_address_activity(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+    ) -> Result<Vec<api::AddressActivity>, SqlxError> {
+        let transfer_signature = ethabi::long_signature(
+            "Transfer",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Uint(256),
+            ],
+        );
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                activity AS (
+                    SELECT
+                        initiator_address AS address,
+                        hash AS tx_hash,
+                        miniblock_number,
+                        index_in_block,
+                        'sender' AS kind
+                    FROM
+                        transactions
+                    WHERE
+                        miniblock_number > $1
+                        AND miniblock_number <= $2
+                    UNION ALL
+                    SELECT
+                        contract_address AS address,
+                        hash AS tx_hash,
+                        miniblock_number,
+                        index_in_block,
+                        'recipient' AS kind
+                    FROM
+                        transactions
+                    WHERE
+                        miniblock_number > $1
+                        AND miniblock_number <= $2
+                        AND contract_address IS NOT NULL
+                    UNION ALL
+                    SELECT DISTINCT
+                        storage_logs.address,
+                        storage_logs.tx_hash,
+                        storage_logs.miniblock_number,
+                        transactions.index_in_block,
+                        'storage_write' AS kind
+                    FROM
+                        storage_logs
+                        INNER JOIN transactions ON storage_logs.tx_hash = transactions.hash
+                    WHERE
+                        storage_logs.miniblock_number > $1
+                        AND storage_logs.miniblock_number <= $2
+                    UNION ALL
+                    SELECT DISTINCT
+                        address,
+                        tx_hash,
+                        miniblock_number,
+                        tx_index_in_block AS index_in_block,
+                        'event_emitter' AS kind
+                    FROM
+                        events
+                    WHERE
+                        miniblock_number > $1
+                        AND miniblock_number <= $2
+                    UNION ALL
+                    SELECT DISTINCT
+                        SUBSTRING(topic3 FROM 13 FOR 20) AS address,
+                        tx_hash,
+                        miniblock_number,
+                        tx_index_in_block AS index_in_block,
+                        'transfer_recipient' AS kind
+                    FROM
+                        events
+                    WHERE
+                        miniblock_number > $1
+                        AND miniblock_number <= $2
+                        AND topic1 = $3
+                        AND LENGTH(topic3) = 32
+                )
+            SELECT
+                activity.address AS "address!",
+                activity.tx_hash AS "tx_hash!",
+                activity.miniblock_number AS "miniblock_number!",
+                miniblocks.hash AS "miniblock_hash!",
+                activity.index_in_block AS "index_in_block!",
+                BOOL_OR(activity.kind = 'sender') AS "is_sender!",
+                BOOL_OR(activity.kind = 'recipient') AS "is_recipient!",
+                BOOL_OR(activity.kind = 'storage_write') AS "has_storage_writes!",
+                BOOL_OR(activity.kind = 'event_emitter') AS "has_events!",
+                BOOL_OR(activity.kind = 'transfer_recipient') AS "is_transfer_recipient!"
+            FROM
+                activity
+                INNER JOIN miniblocks ON activity.miniblock_number = miniblocks.number
+            GROUP BY
+                activity.miniblock_number,
+                miniblocks.hash,
+                activity.index_in_block,
+                activity.tx_hash,
+                activity.address
+            ORDER BY
+                activity.miniblock_number,
+                activity.index_in_block,
+                activity.address
+            "#,
+            from_block.0 as i64,
+            to_block.0 as i64,
+            transfer_signature.as_bytes()
+        )
+        .instrument("get_address_activity")
+        .with_arg("from_block", &from_block)
+        .with_arg("to_block", &to_block)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let kinds = [
+                    (row.is_sender, api::AddressActivityKind::Sender),
+                    (row.is_recipient, api::AddressActivityKind::Recipient),
+                    (
+                        row.has_storage_writes,
+                        api::AddressActivityKind::StorageWrite,
+                    ),
+                    (row.has_events, api::AddressActivityKind::EventEmitter),
+                    (
+                        row.is_transfer_recipient,
+                        api::AddressActivityKind::TransferRecipient,
+                    ),
+                ];
+                api::AddressActivity {
+                    address: Address::from_slice(&row.address),
+                    block_number: U64::from(row.miniblock_number as u64),
+                    block_hash: H256::from_slice(&row.miniblock_hash),
+                    transaction_hash: H256::from_slice(&row.tx_hash),
+                    transaction_index: (row.index_in_block as u64).into(),
+                    kinds: kinds
+                        .into_iter()
+                        .filter_map(|(is_present, kind)| is_present.then_some(kind))
+                        .collect(),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the number of transactions and their cumulative execution metrics for the pending L1 batch,
+    /// i.e., for sealed miniblocks not yet included into an L1 batch. Transactions in the miniblock currently
+    /// open in the state keeper are not persisted yet, so they are not accounted for.
+    pub async fn get_pending_l1_batch_execution_metrics(
+        &mut self,
+    ) -> Result<(usize, ExecutionMetrics), SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "tx_count!",
+                COALESCE(SUM((execution_info ->> 'gas_used')::BIGINT), 0)::BIGINT AS "gas_used!",
+                COALESCE(SUM((execution_info ->> 'published_bytecode_bytes')::BIGINT), 0)::BIGINT AS "published_bytecode_bytes!",
+                COALESCE(SUM((execution_info ->> 'l2_l1_long_messages')::BIGINT), 0)::BIGINT AS "l2_l1_long_messages!",
+                COALESCE(SUM((execution_info ->> 'l2_to_l1_logs')::BIGINT), 0)::BIGINT AS "l2_to_l1_logs!",
+                COALESCE(SUM((execution_info ->> 'contracts_used')::BIGINT), 0)::BIGINT AS "contracts_used!",
+                COALESCE(SUM((execution_info ->> 'contracts_deployed')::BIGINT), 0)::BIGINT AS "contracts_deployed!",
+                COALESCE(SUM((execution_info ->> 'vm_events')::BIGINT), 0)::BIGINT AS "vm_events!",
+                COALESCE(SUM((execution_info ->> 'storage_logs')::BIGINT), 0)::BIGINT AS "storage_logs!",
+                COALESCE(SUM((execution_info ->> 'total_log_queries')::BIGINT), 0)::BIGINT AS "total_log_queries!",
+                COALESCE(SUM((execution_info ->> 'cycles_used')::BIGINT), 0)::BIGINT AS "cycles_used!",
+                COALESCE(SUM((execution_info ->> 'computational_gas_used')::BIGINT), 0)::BIGINT AS "computational_gas_used!",
+                COALESCE(SUM((execution_info ->> 'pubdata_published')::BIGINT), 0)::BIGINT AS "pubdata_published!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number > COALESCE(
+                    (
+                        SELECT
+                            MAX(number)
+                        FROM
+                            miniblocks
+                        WHERE
+                            l1_batch_number IS NOT NULL
+                    ),
+                    -1
+                )
+            "#
+        )
+        .instrument("get_pending_l1_batch_execution_metrics")
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        let metrics = ExecutionMetrics {
+            gas_used: row.gas_used as usize,
+            published_bytecode_bytes: row.published_bytecode_bytes as usize,
+            l2_l1_long_messages: row.l2_l1_long_messages as usize,
+            l2_to_l1_logs: row.l2_to_l1_logs as usize,
+            contracts_used: row.contracts_used as usize,
+            contracts_deployed: row.contracts_deployed as u16,
+            vm_events: row.vm_events as usize,
+            storage_logs: row.storage_logs as usize,
+            total_log_queries: row.total_log_queries as usize,
+            cycles_used: row.cycles_used as u32,
+            computational_gas_used: row.computational_gas_used as u32,
+            pubdata_published: row.pubdata_published as u32,
+        };
+        Ok((row.tx_count as usize, metrics))
+    }
+
     /// Returns the server transactions (not API ones) from a certain miniblock.
     /// Returns an empty list if the miniblock doesn't exist.
     pub async fn get_raw_miniblock_transactions(
@@ -407,18 +854,106 @@ impl TransactionsWeb3Dal<'_, '_> {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    /// Returns raw (EIP-2718) encodings of receipts for all transactions in the specified miniblock, ordered
+    /// by the transaction index in the miniblock; see [`api::TransactionReceipt::raw_encoding()`].
+    /// Returns an empty list if the miniblock doesn't exist.
+    pub async fn get_raw_miniblock_receipts(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> Result<Vec<Bytes>, SqlxError> {
+        let tx_rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                tx_format
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            miniblock.0 as i64
+        )
+        .instrument("get_raw_miniblock_receipts")
+        .with_arg("miniblock", &miniblock)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let storage_logs = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value,
+                NULL::bytea AS "block_hash",
+                NULL::BIGINT AS "l1_batch_number?",
+                miniblock_number,
+                tx_hash,
+                tx_index_in_block,
+                event_index_in_block,
+                event_index_in_tx
+            FROM
+                events
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                event_index_in_block ASC
+            "#,
+            miniblock.0 as i64
+        )
+        .instrument("get_raw_miniblock_receipts#events")
+        .with_arg("miniblock", &miniblock)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let mut logs_by_tx = HashMap::<_, Vec<_>>::new();
+        for storage_log in storage_logs {
+            let tx_hash = H256::from_slice(&storage_log.tx_hash);
+            logs_by_tx
+                .entry(tx_hash)
+                .or_default()
+                .push(api::Log::from(storage_log));
+        }
+
+        let raw_receipts = tx_rows.into_iter().map(|row| {
+            let tx_hash = H256::from_slice(&row.hash);
+            // Only fields included into the raw encoding are populated.
+            let receipt = api::TransactionReceipt {
+                transaction_hash: tx_hash,
+                status: row.error.map(|_| U64::zero()).unwrap_or_else(U64::one),
+                transaction_type: Some(row.tx_format.map(U64::from).unwrap_or_default()),
+                logs: logs_by_tx.remove(&tx_hash).unwrap_or_default(),
+                ..api::TransactionReceipt::default()
+            };
+            receipt.raw_encoding()
+        });
+        Ok(raw_receipts.collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use zksync_types::{
-        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, ProtocolVersion,
-        ProtocolVersionId,
+        block::{BlockGasCount, L1BatchHeader, MiniblockHasher},
+        fee::TransactionExecutionMetrics,
+        l2::L2Tx,
+        tx::IncludedTxLocation,
+        AccountTreeId, L1BlockNumber, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog,
+        VmEvent,
     };
 
     use super::*;
     use crate::{
-        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l1_execute, mock_l2_transaction,
+        },
         ConnectionPool,
     };
 
@@ -510,6 +1045,40 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn getting_priority_op_status() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l1_execute();
+        let l1_tx_hash = tx.common_data.eth_hash;
+        conn.transactions_dal()
+            .insert_transaction_l1(tx.clone(), L1BlockNumber(1))
+            .await;
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_priority_op_status(l1_tx_hash)
+            .await
+            .unwrap()
+            .expect("no status for priority op");
+        assert_eq!(status.l2_tx_hash, tx.hash());
+        assert_eq!(status.priority_op_id, tx.common_data.serial_id);
+        assert_eq!(status.l1_block_number, 1);
+        assert_eq!(status.stage, api::PriorityOpStage::Queued);
+        assert_eq!(status.miniblock_number, None);
+        assert_eq!(status.l1_batch_number, None);
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_priority_op_status(H256::repeat_byte(0xff))
+            .await
+            .unwrap();
+        assert!(status.is_none());
+    }
+
     #[tokio::test]
     async fn getting_miniblock_transactions() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -536,4 +1105,190 @@ mod tests {
         assert_eq!(raw_txs.len(), 1);
         assert_eq!(raw_txs[0].hash(), tx_hash);
     }
+
+    #[tokio::test]
+    async fn getting_pending_nonces() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let initiator = Address::repeat_byte(1);
+        for nonce in [1, 2, 4, 7] {
+            let mut tx = mock_l2_transaction();
+            tx.common_data.initiator_address = initiator;
+            tx.common_data.nonce = Nonce(nonce);
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+        }
+        // Transactions of other accounts must not be returned.
+        conn.transactions_dal()
+            .insert_transaction_l2(
+                mock_l2_transaction(),
+                TransactionExecutionMetrics::default(),
+            )
+            .await;
+
+        let nonces = conn
+            .transactions_web3_dal()
+            .get_pending_nonces_by_initiator_account(initiator, Nonce(0))
+            .await
+            .unwrap();
+        assert_eq!(nonces, [Nonce(1), Nonce(2), Nonce(4), Nonce(7)]);
+        let nonces = conn
+            .transactions_web3_dal()
+            .get_pending_nonces_by_initiator_account(initiator, Nonce(3))
+            .await
+            .unwrap();
+        assert_eq!(nonces, [Nonce(4), Nonce(7)]);
+    }
+
+    #[tokio::test]
+    async fn getting_address_activity() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        let initiator = tx.initiator_account();
+        let contract = tx.execute.contract_address;
+        prepare_transaction(&mut conn, tx).await;
+
+        let emitter = Address::repeat_byte(0x23);
+        let storage_logs = [contract, emitter].map(|address| {
+            let key = StorageKey::new(AccountTreeId::new(address), H256::zero());
+            StorageLog::new_write_log(key, H256::repeat_byte(1))
+        });
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(tx_hash, storage_logs.to_vec())])
+            .await;
+        let tx_location = IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: initiator,
+        };
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: emitter,
+            indexed_topics: vec![],
+            value: vec![],
+        };
+        let transfer_recipient = Address::repeat_byte(0x42);
+        let transfer_signature = ethabi::long_signature(
+            "Transfer",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Uint(256),
+            ],
+        );
+        let transfer_event = VmEvent {
+            location: (L1BatchNumber(1), 1),
+            address: emitter,
+            indexed_topics: vec![
+                transfer_signature,
+                zksync_utils::address_to_h256(&initiator),
+                zksync_utils::address_to_h256(&transfer_recipient),
+            ],
+            value: H256::from_low_u64_be(1).0.to_vec(),
+        };
+        conn.events_dal()
+            .save_events(
+                MiniblockNumber(1),
+                &[(tx_location, vec![&event, &transfer_event])],
+            )
+            .await;
+
+        let activity = conn
+            .transactions_web3_dal()
+            .get_address_activity(MiniblockNumber(0), MiniblockNumber(1))
+            .await
+            .unwrap();
+        let mut expected = vec![
+            (initiator, vec![api::AddressActivityKind::Sender]),
+            (
+                contract,
+                vec![
+                    api::AddressActivityKind::Recipient,
+                    api::AddressActivityKind::StorageWrite,
+                ],
+            ),
+            (
+                emitter,
+                vec![
+                    api::AddressActivityKind::StorageWrite,
+                    api::AddressActivityKind::EventEmitter,
+                ],
+            ),
+            (
+                transfer_recipient,
+                vec![api::AddressActivityKind::TransferRecipient],
+            ),
+        ];
+        expected.sort_unstable_by_key(|(address, _)| *address);
+        let actual: Vec<_> = activity
+            .iter()
+            .map(|item| (item.address, item.kinds.clone()))
+            .collect();
+        assert_eq!(actual, expected);
+        for item in &activity {
+            assert_eq!(item.block_number, 1.into());
+            assert_eq!(item.transaction_hash, tx_hash);
+            assert_eq!(item.transaction_index, 0.into());
+        }
+
+        let activity = conn
+            .transactions_web3_dal()
+            .get_address_activity(MiniblockNumber(1), MiniblockNumber(10))
+            .await
+            .unwrap();
+        assert!(activity.is_empty());
+        let activity = conn
+            .transactions_web3_dal()
+            .get_address_activity(MiniblockNumber(0), MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert!(activity.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_pending_l1_batch_execution_metrics() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l2_transaction();
+        prepare_transaction(&mut conn, tx).await;
+
+        let (tx_count, _) = conn
+            .transactions_web3_dal()
+            .get_pending_l1_batch_execution_metrics()
+            .await
+            .unwrap();
+        assert_eq!(tx_count, 1);
+
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(0),
+            0,
+            Address::repeat_byte(0x42),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&l1_batch_header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(0))
+            .await
+            .unwrap();
+        let (tx_count, metrics) = conn
+            .transactions_web3_dal()
+            .get_pending_l1_batch_execution_metrics()
+            .await
+            .unwrap();
+        assert_eq!(tx_count, 0);
+        assert_eq!(metrics, ExecutionMetrics::default());
+    }
 }
@@ -50,6 +50,8 @@ impl FromEnv for MerkleTreeApiConfig {
 mod tests {
     use std::num::NonZeroU32;
 
+    use zksync_config::configs::api::SubscriptionBackpressurePolicy;
+
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
 
@@ -65,9 +67,14 @@ mod tests {
                 req_entities_limit: Some(10000),
                 filters_limit: Some(10000),
                 subscriptions_limit: Some(10000),
+                max_subscriptions_per_connection: Some(64),
+                max_buffered_notifications: Some(512),
+                subscription_backpressure_policy: Some(SubscriptionBackpressurePolicy::DropOldest),
                 pubsub_polling_interval: Some(200),
                 threads_per_server: 128,
                 max_nonce_ahead: 5,
+                tx_replacement_fee_bump_percent: Some(15),
+                max_tx_deadline_sec: Some(3600),
                 transactions_per_sec_limit: Some(1000),
                 request_timeout: Some(10),
                 account_pks: Some(vec![
@@ -76,6 +83,7 @@ mod tests {
                 ]),
                 estimate_gas_scale_factor: 1.0f64,
                 gas_price_scale_factor: 1.2,
+                batch_boundary_gas_price_scale_factor: Some(1.5),
                 estimate_gas_acceptable_overestimation: 1000,
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
@@ -90,6 +98,11 @@ mod tests {
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
+                analytics_namespace_enabled: Some(true),
+                unstable_namespace_enabled: Some(true),
+                trace_max_depth: Some(64),
+                trace_max_calls: Some(5000),
+                profiles_path: Some("/etc/zksync/api_profiles.json".to_owned()),
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -101,7 +114,14 @@ mod tests {
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
             },
-            healthcheck: HealthCheckConfig { port: 8081 },
+            healthcheck: HealthCheckConfig {
+                port: 8081,
+                state_keeper_degraded_lag_sec: Some(30),
+                state_keeper_unhealthy_lag_sec: Some(300),
+                tree_degraded_lag: Some(5),
+                tree_unhealthy_lag: None,
+                eth_sender_degraded_lag_sec: Some(1800),
+            },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
     }
@@ -117,10 +137,16 @@ mod tests {
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
+            API_WEB3_JSON_RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION=64
+            API_WEB3_JSON_RPC_MAX_BUFFERED_NOTIFICATIONS=512
+            API_WEB3_JSON_RPC_SUBSCRIPTION_BACKPRESSURE_POLICY="drop_oldest"
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
             API_WEB3_JSON_RPC_THREADS_PER_SERVER=128
             API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
+            API_WEB3_JSON_RPC_TX_REPLACEMENT_FEE_BUMP_PERCENT=15
+            API_WEB3_JSON_RPC_MAX_TX_DEADLINE_SEC=3600
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
+            API_WEB3_JSON_RPC_BATCH_BOUNDARY_GAS_PRICE_SCALE_FACTOR=1.5
             API_WEB3_JSON_RPC_TRANSACTIONS_PER_SEC_LIMIT=1000
             API_WEB3_JSON_RPC_REQUEST_TIMEOUT=10
             API_WEB3_JSON_RPC_ACCOUNT_PKS="0x0000000000000000000000000000000000000000000000000000000000000001,0x0000000000000000000000000000000000000000000000000000000000000002"
@@ -136,6 +162,11 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_ANALYTICS_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_UNSTABLE_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_TRACE_MAX_DEPTH=64
+            API_WEB3_JSON_RPC_TRACE_MAX_CALLS=5000
+            API_WEB3_JSON_RPC_PROFILES_PATH="/etc/zksync/api_profiles.json"
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_CONTRACT_VERIFICATION_THREADS_PER_SERVER=128
@@ -144,6 +175,10 @@ mod tests {
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
             API_HEALTHCHECK_PORT=8081
+            API_HEALTHCHECK_STATE_KEEPER_DEGRADED_LAG_SEC=30
+            API_HEALTHCHECK_STATE_KEEPER_UNHEALTHY_LAG_SEC=300
+            API_HEALTHCHECK_TREE_DEGRADED_LAG=5
+            API_HEALTHCHECK_ETH_SENDER_DEGRADED_LAG_SEC=1800
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
@@ -0,0 +1,35 @@
+use zksync_config::configs::CallTracesBackfillerConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for CallTracesBackfillerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("call_traces_backfiller", "CALL_TRACES_BACKFILLER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            CALL_TRACES_BACKFILLER_FIRST_L1_BATCH_NUMBER="1000"
+            CALL_TRACES_BACKFILLER_POLLING_INTERVAL_MS="10000"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = CallTracesBackfillerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            CallTracesBackfillerConfig {
+                first_l1_batch_number: Some(1_000),
+                polling_interval_ms: Some(10_000),
+            }
+        );
+    }
+}
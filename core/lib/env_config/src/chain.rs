@@ -51,7 +51,10 @@ impl FromEnv for MempoolConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_basic_types::L2ChainId;
+    use zksync_basic_types::{commitment::L1BatchCommitmentMode, L2ChainId};
+    use zksync_config::configs::chain::{
+        AddressFilterMode, MempoolEvictionPolicy, MempoolOrderingPolicy, SponsoredCall,
+    };
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -86,6 +89,31 @@ mod tests {
                 virtual_blocks_per_miniblock: 1,
                 upload_witness_inputs_to_gcs: false,
                 enum_index_migration_chunk_size: Some(2_000),
+                address_filter_path: Some("/etc/zksync/address_filter.txt".to_owned()),
+                address_filter_mode: Some(AddressFilterMode::Allow),
+                address_filter_reload_interval_ms: Some(5_000),
+                config_overrides_path: Some("/etc/zksync/config_overrides.json".to_owned()),
+                config_overrides_reload_interval_ms: None,
+                shadow_vm_protocol_version: Some(19),
+                optimistic_execution_lookahead: Some(8),
+                max_l1_txs_per_miniblock: Some(10),
+                max_l1_txs_per_l1_batch: Some(100),
+                external_builder_http_port: Some(3330),
+                external_builder_auth_token: Some("secret".to_owned()),
+                external_builder_timeout_ms: Some(250),
+                l1_batch_commitment_mode: L1BatchCommitmentMode::Validium,
+                sponsor_paymaster_addr: Some(addr("4c88e9e6b4fd6d5cee9b1e4f3e6d8b1c86ebb1a1")),
+                sponsored_calls: Some(vec![
+                    SponsoredCall {
+                        contract: addr("0000000000000000000000000000000000008006"),
+                        selector: Some([0x3c, 0xda, 0x33, 0x51]),
+                    },
+                    SponsoredCall {
+                        contract: addr("e1f0b3a5c0d4e6f8a9b7c5d3e1f0a2b4c6d8e0f2"),
+                        selector: None,
+                    },
+                ]),
+                max_sponsored_gas_per_l1_batch: Some(50_000_000),
             },
             operations_manager: OperationsManagerConfig {
                 delay_interval: 100,
@@ -97,12 +125,21 @@ mod tests {
                 stuck_tx_timeout: 10,
                 remove_stuck_txs: true,
                 delay_interval: 100,
+                ordering_policy: MempoolOrderingPolicy::FeePriority,
+                max_pending_txs_per_account: Some(64),
+                eviction_policy: Some(MempoolEvictionPolicy::LowestFee),
+                default_tx_ttl_sec: Some(3600),
             },
             circuit_breaker: CircuitBreakerConfig {
                 sync_interval_ms: 1000,
                 http_req_max_retry_number: 5,
                 http_req_retry_interval_sec: 2,
                 replication_lag_limit_sec: Some(10),
+                l1_contracts_check_interval_ms: Some(60_000),
+                fee_divergence_max_ratio: Some(10.0),
+                fee_divergence_check_interval_ms: None,
+                da_inclusion_timeout_sec: Some(3600),
+                custom_rules_path: Some("/etc/zksync/circuit_breaker_rules.json".to_owned()),
             },
         }
     }
@@ -132,6 +169,21 @@ mod tests {
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+            CHAIN_STATE_KEEPER_ADDRESS_FILTER_PATH="/etc/zksync/address_filter.txt"
+            CHAIN_STATE_KEEPER_ADDRESS_FILTER_MODE="allow"
+            CHAIN_STATE_KEEPER_ADDRESS_FILTER_RELOAD_INTERVAL_MS="5000"
+            CHAIN_STATE_KEEPER_CONFIG_OVERRIDES_PATH="/etc/zksync/config_overrides.json"
+            CHAIN_STATE_KEEPER_SHADOW_VM_PROTOCOL_VERSION="19"
+            CHAIN_STATE_KEEPER_OPTIMISTIC_EXECUTION_LOOKAHEAD="8"
+            CHAIN_STATE_KEEPER_MAX_L1_TXS_PER_MINIBLOCK="10"
+            CHAIN_STATE_KEEPER_MAX_L1_TXS_PER_L1_BATCH="100"
+            CHAIN_STATE_KEEPER_EXTERNAL_BUILDER_HTTP_PORT="3330"
+            CHAIN_STATE_KEEPER_EXTERNAL_BUILDER_AUTH_TOKEN="secret"
+            CHAIN_STATE_KEEPER_L1_BATCH_COMMITMENT_MODE="Validium"
+            CHAIN_STATE_KEEPER_EXTERNAL_BUILDER_TIMEOUT_MS="250"
+            CHAIN_STATE_KEEPER_SPONSOR_PAYMASTER_ADDR="0x4c88e9e6b4fd6d5cee9b1e4f3e6d8b1c86ebb1a1"
+            CHAIN_STATE_KEEPER_SPONSORED_CALLS="0x0000000000000000000000000000000000008006:0x3cda3351,0xe1f0b3a5c0d4e6f8a9b7c5d3e1f0a2b4c6d8e0f2"
+            CHAIN_STATE_KEEPER_MAX_SPONSORED_GAS_PER_L1_BATCH="50000000"
             CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
             CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
@@ -139,10 +191,18 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_ORDERING_POLICY="fee_priority"
+            CHAIN_MEMPOOL_MAX_PENDING_TXS_PER_ACCOUNT="64"
+            CHAIN_MEMPOOL_EVICTION_POLICY="lowest_fee"
+            CHAIN_MEMPOOL_DEFAULT_TX_TTL_SEC="3600"
             CHAIN_CIRCUIT_BREAKER_SYNC_INTERVAL_MS="1000"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_L1_CONTRACTS_CHECK_INTERVAL_MS="60000"
+            CHAIN_CIRCUIT_BREAKER_FEE_DIVERGENCE_MAX_RATIO="10"
+            CHAIN_CIRCUIT_BREAKER_DA_INCLUSION_TIMEOUT_SEC="3600"
+            CHAIN_CIRCUIT_BREAKER_CUSTOM_RULES_PATH="/etc/zksync/circuit_breaker_rules.json"
         "#;
         lock.set_env(config);
 
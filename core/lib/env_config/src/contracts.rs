@@ -28,6 +28,7 @@ mod tests {
             diamond_init_addr: addr("FFC35A5e767BE36057c34586303498e3de7C62Ba"),
             diamond_upgrade_init_addr: addr("FFC35A5e767BE36057c34586303498e3de7C62Ba"),
             diamond_proxy_addr: addr("F00B988a98Ca742e7958DeF9F7823b5908715f4a"),
+            bridgehub_proxy_addr: Some(addr("35D9D8fC5A8A3C3eCD83A5e1a9dBBd30d0c80d9A")),
             validator_timelock_addr: addr("F00B988a98Ca742e7958DeF9F7823b5908715f4a"),
             genesis_tx_hash: hash(
                 "b99ebfea46cbe05a21cd80fe5597d97b204befc52a16303f579c607dc1ac2e2e",
@@ -81,6 +82,7 @@ CONTRACTS_VERIFIER_ADDR="0x34782eE00206EAB6478F2692caa800e4A581687b"
 CONTRACTS_DIAMOND_INIT_ADDR="0xFFC35A5e767BE36057c34586303498e3de7C62Ba"
 CONTRACTS_DIAMOND_UPGRADE_INIT_ADDR="0xFFC35A5e767BE36057c34586303498e3de7C62Ba"
 CONTRACTS_DIAMOND_PROXY_ADDR="0xF00B988a98Ca742e7958DeF9F7823b5908715f4a"
+CONTRACTS_BRIDGEHUB_PROXY_ADDR="0x35D9D8fC5A8A3C3eCD83A5e1a9dBBd30d0c80d9A"
 CONTRACTS_VALIDATOR_TIMELOCK_ADDR="0xF00B988a98Ca742e7958DeF9F7823b5908715f4a"
 CONTRACTS_GENESIS_TX_HASH="0xb99ebfea46cbe05a21cd80fe5597d97b204befc52a16303f579c607dc1ac2e2e"
 CONTRACTS_L1_ERC20_BRIDGE_PROXY_ADDR="0x8656770FA78c830456B00B4fFCeE6b1De0e1b888"
@@ -0,0 +1,48 @@
+use zksync_config::configs::DADispatcherConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for DADispatcherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher", "DA_DISPATCHER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::da_dispatcher::DataAvailabilityClientKind;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> DADispatcherConfig {
+        DADispatcherConfig {
+            client: DataAvailabilityClientKind::Celestia,
+            api_url: "http://127.0.0.1:26658".to_owned(),
+            auth_token: Some("token".to_owned()),
+            celestia_namespace: Some("000000000000007a6b73".to_owned()),
+            polling_interval_ms: Some(10_000),
+            max_batches_to_dispatch: Some(50),
+            max_retries: None,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            DA_DISPATCHER_CLIENT="Celestia"
+            DA_DISPATCHER_API_URL="http://127.0.0.1:26658"
+            DA_DISPATCHER_AUTH_TOKEN="token"
+            DA_DISPATCHER_CELESTIA_NAMESPACE="000000000000007a6b73"
+            DA_DISPATCHER_POLLING_INTERVAL_MS="10000"
+            DA_DISPATCHER_MAX_BATCHES_TO_DISPATCH="50"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = DADispatcherConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.max_retries(), 5);
+    }
+}
@@ -9,6 +9,16 @@ impl FromEnv for DBConfig {
     fn from_env() -> anyhow::Result<Self> {
         Ok(Self {
             merkle_tree: envy_load("database_merkle_tree", "DATABASE_MERKLE_TREE_")?,
+            pruning: envy_load("database_pruning", "DATABASE_PRUNING_")?,
+            storage_logs_compaction: envy_load(
+                "database_storage_logs_compaction",
+                "DATABASE_STORAGE_LOGS_COMPACTION_",
+            )?,
+            cold_storage: envy_load("database_cold_storage", "DATABASE_COLD_STORAGE_")?,
+            rocksdb_compaction: envy_load(
+                "database_rocksdb_compaction",
+                "DATABASE_ROCKSDB_COMPACTION_",
+            )?,
             ..envy_load("database", "DATABASE_")?
         })
     }
@@ -20,6 +30,15 @@ impl FromEnv for PostgresConfig {
         let replica_url = env::var("DATABASE_REPLICA_URL")
             .ok()
             .or_else(|| master_url.clone());
+        let read_replica_urls = env::var("DATABASE_READ_REPLICA_URLS")
+            .map(|urls| {
+                urls.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
         let prover_url = env::var("DATABASE_PROVER_URL")
             .ok()
             .or_else(|| master_url.clone());
@@ -27,6 +46,20 @@ impl FromEnv for PostgresConfig {
             .ok()
             .map(|val| val.parse().context("failed to parse DATABASE_POOL_SIZE"))
             .transpose()?;
+        let min_connections = env::var("DATABASE_POOL_MIN_SIZE")
+            .ok()
+            .map(|val| {
+                val.parse()
+                    .context("failed to parse DATABASE_POOL_MIN_SIZE")
+            })
+            .transpose()?;
+        let pool_target_wait_ms = env::var("DATABASE_POOL_TARGET_WAIT_MS")
+            .ok()
+            .map(|val| {
+                val.parse()
+                    .context("failed to parse DATABASE_POOL_TARGET_WAIT_MS")
+            })
+            .transpose()?;
         let statement_timeout_sec = env::var("DATABASE_STATEMENT_TIMEOUT")
             .ok()
             .map(|val| {
@@ -34,12 +67,23 @@ impl FromEnv for PostgresConfig {
                     .context("failed to parse DATABASE_STATEMENT_TIMEOUT")
             })
             .transpose()?;
+        let max_replica_lag_sec = env::var("DATABASE_MAX_REPLICA_LAG_SEC")
+            .ok()
+            .map(|val| {
+                val.parse()
+                    .context("failed to parse DATABASE_MAX_REPLICA_LAG_SEC")
+            })
+            .transpose()?;
 
         Ok(Self {
             master_url,
             replica_url,
+            read_replica_urls,
+            max_replica_lag_sec,
             prover_url,
             max_connections,
+            min_connections,
+            pool_target_wait_ms,
             statement_timeout_sec,
         })
     }
@@ -47,7 +91,12 @@ impl FromEnv for PostgresConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::database::MerkleTreeMode;
+    use std::time::Duration;
+
+    use zksync_config::configs::database::{
+        ColdStorageConfig, MerkleTreeMode, PruningConfig, RocksdbCompactionConfig,
+        StorageLogsCompactionConfig,
+    };
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -65,6 +114,23 @@ mod tests {
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_COMMITMENT_GENERATION_CONCURRENCY=8
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_INTERVAL_MS=30000
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_SUBTREE_DEPTH=2
+            DATABASE_PRUNING_DATA_RETENTION_SEC=86400
+            DATABASE_PRUNING_CHUNK_SIZE=5
+            DATABASE_PRUNING_INTERVAL_MS=10000
+            DATABASE_PRUNING_RETAINED_L1_BATCHES=100
+            DATABASE_STORAGE_LOGS_COMPACTION_DATA_RETENTION_SEC=3600
+            DATABASE_STORAGE_LOGS_COMPACTION_CHUNK_SIZE=20
+            DATABASE_STORAGE_LOGS_COMPACTION_INTERVAL_MS=5000
+            DATABASE_COLD_STORAGE_ENABLED=true
+            DATABASE_COLD_STORAGE_DATA_RETENTION_SEC=864000
+            DATABASE_COLD_STORAGE_INTERVAL_MS=30000
+            DATABASE_ROCKSDB_COMPACTION_WINDOW_START_HOUR=22
+            DATABASE_ROCKSDB_COMPACTION_WINDOW_END_HOUR=3
+            DATABASE_ROCKSDB_COMPACTION_CHECK_INTERVAL_MS=10000
+            DATABASE_ROCKSDB_COMPACTION_ADMIN_PORT=3090
         "#;
         lock.set_env(config);
 
@@ -74,8 +140,25 @@ mod tests {
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Lightweight);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 250);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
+        assert_eq!(db_config.merkle_tree.commitment_generation_concurrency, 8);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(db_config.merkle_tree.consistency_check_interval_ms, 30_000);
+        assert_eq!(db_config.merkle_tree.consistency_check_subtree_depth, 2);
+        assert_eq!(db_config.pruning.data_retention_sec, 86_400);
+        assert_eq!(db_config.pruning.chunk_size, 5);
+        assert_eq!(db_config.pruning.interval_ms, 10_000);
+        assert_eq!(db_config.pruning.retained_l1_batches, Some(100));
+        assert_eq!(db_config.storage_logs_compaction.data_retention_sec, 3_600);
+        assert_eq!(db_config.storage_logs_compaction.chunk_size, 20);
+        assert_eq!(db_config.storage_logs_compaction.interval_ms, 5_000);
+        assert!(db_config.cold_storage.enabled);
+        assert_eq!(db_config.cold_storage.data_retention_sec, 864_000);
+        assert_eq!(db_config.cold_storage.interval_ms, 30_000);
+        assert_eq!(db_config.rocksdb_compaction.window_start_hour, 22);
+        assert_eq!(db_config.rocksdb_compaction.window_end_hour, 3);
+        assert_eq!(db_config.rocksdb_compaction.check_interval_ms, 10_000);
+        assert_eq!(db_config.rocksdb_compaction.admin_port, Some(3090));
     }
 
     #[test]
@@ -91,6 +174,23 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_COMMITMENT_GENERATION_CONCURRENCY",
+            "DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_INTERVAL_MS",
+            "DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_SUBTREE_DEPTH",
+            "DATABASE_PRUNING_DATA_RETENTION_SEC",
+            "DATABASE_PRUNING_CHUNK_SIZE",
+            "DATABASE_PRUNING_INTERVAL_MS",
+            "DATABASE_PRUNING_RETAINED_L1_BATCHES",
+            "DATABASE_STORAGE_LOGS_COMPACTION_DATA_RETENTION_SEC",
+            "DATABASE_STORAGE_LOGS_COMPACTION_CHUNK_SIZE",
+            "DATABASE_STORAGE_LOGS_COMPACTION_INTERVAL_MS",
+            "DATABASE_COLD_STORAGE_ENABLED",
+            "DATABASE_COLD_STORAGE_DATA_RETENTION_SEC",
+            "DATABASE_COLD_STORAGE_INTERVAL_MS",
+            "DATABASE_ROCKSDB_COMPACTION_WINDOW_START_HOUR",
+            "DATABASE_ROCKSDB_COMPACTION_WINDOW_END_HOUR",
+            "DATABASE_ROCKSDB_COMPACTION_CHECK_INTERVAL_MS",
+            "DATABASE_ROCKSDB_COMPACTION_ADMIN_PORT",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
@@ -99,9 +199,22 @@ mod tests {
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Full);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 500);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 20);
+        assert_eq!(db_config.merkle_tree.commitment_generation_concurrency, 4);
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.consistency_check_interval_ms, 60_000);
+        assert_eq!(db_config.merkle_tree.consistency_check_subtree_depth, 3);
+        assert_eq!(db_config.pruning, PruningConfig::default());
+        assert_eq!(
+            db_config.storage_logs_compaction,
+            StorageLogsCompactionConfig::default()
+        );
+        assert_eq!(db_config.cold_storage, ColdStorageConfig::default());
+        assert_eq!(
+            db_config.rocksdb_compaction,
+            RocksdbCompactionConfig::default()
+        );
 
         // Check that new env variable for Merkle tree path is supported
         lock.set_env("DATABASE_MERKLE_TREE_PATH=/db/tree/main");
@@ -120,4 +233,36 @@ mod tests {
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
     }
+
+    #[test]
+    fn postgres_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DATABASE_URL="postgres://postgres@localhost/zksync_local"
+            DATABASE_READ_REPLICA_URLS="postgres://postgres@replica-0/zksync_local, postgres://postgres@replica-1/zksync_local"
+            DATABASE_MAX_REPLICA_LAG_SEC=3
+            DATABASE_POOL_MIN_SIZE=5
+        "#;
+        lock.set_env(config);
+        lock.remove_env(&["DATABASE_REPLICA_URL", "DATABASE_POOL_TARGET_WAIT_MS"]);
+
+        let postgres_config = PostgresConfig::from_env().unwrap();
+        assert_eq!(
+            postgres_config.replica_url().unwrap(),
+            "postgres://postgres@localhost/zksync_local"
+        );
+        assert_eq!(
+            postgres_config.read_replica_urls,
+            [
+                "postgres://postgres@replica-0/zksync_local",
+                "postgres://postgres@replica-1/zksync_local"
+            ]
+        );
+        assert_eq!(postgres_config.max_replica_lag(), Duration::from_secs(3));
+        assert_eq!(postgres_config.min_connections, Some(5));
+        assert_eq!(
+            postgres_config.pool_target_wait(),
+            Duration::from_millis(50)
+        );
+    }
 }
@@ -1,5 +1,8 @@
 use anyhow::Context as _;
-use zksync_config::{configs::eth_sender::SenderConfig, ETHSenderConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{FeeEscalationConfig, FeeEscalationPolicyConfig, SenderConfig},
+    ETHSenderConfig, GasAdjusterConfig,
+};
 
 use crate::{envy_load, FromEnv};
 
@@ -8,6 +11,7 @@ impl FromEnv for ETHSenderConfig {
         Ok(Self {
             sender: SenderConfig::from_env().context("SenderConfig")?,
             gas_adjuster: GasAdjusterConfig::from_env().context("GasAdjusterConfig")?,
+            fee_escalation: FeeEscalationConfig::from_env().context("FeeEscalationConfig")?,
         })
     }
 }
@@ -24,12 +28,32 @@ impl FromEnv for GasAdjusterConfig {
     }
 }
 
+impl FromEnv for FeeEscalationConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        let load_policy = |op: &str| -> anyhow::Result<FeeEscalationPolicyConfig> {
+            let prefix = format!("ETH_SENDER_FEE_ESCALATION_{}_", op.to_uppercase());
+            envy_load(&format!("eth_sender.fee_escalation.{op}"), &prefix)
+        };
+        Ok(Self {
+            commit: load_policy("commit")?,
+            prove: load_policy("prove")?,
+            execute: load_policy("execute")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode};
+    use zksync_basic_types::{
+        basic_fri_types::ProofCompressionBackend, commitment::L1BatchCommitmentMode,
+    };
+    use zksync_config::configs::eth_sender::{
+        BaseFeeSmoothing, FeeEscalationStrategy, OperatorSignerKind, ProofLoadingMode,
+        ProofSendingMode, PubdataSendingMode, SettlementLayerKind,
+    };
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -52,8 +76,25 @@ mod tests {
                 max_txs_in_flight: 3,
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
                 l1_batch_min_age_before_execute_seconds: Some(1000),
+                l1_batch_execute_delay_seconds: Some(600),
+                execute_max_l1_gas_price: Some(50_000_000_000),
+                execute_max_postpone_seconds: Some(7200),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_signer: OperatorSignerKind::JsonRpc,
+                operator_signer_url: Some("http://127.0.0.1:9000/".to_owned()),
+                operator_address: Some(addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7")),
+                max_aggregation_overhead_per_l1_batch: Some(1_000_000_000_000_000),
+                settlement_layer: SettlementLayerKind::Gateway,
+                gateway_url: Some("http://127.0.0.1:3150/".to_owned()),
+                gateway_chain_id: Some(506),
+                gateway_diamond_proxy_addr: Some(addr("0000000000000000000000000000000000010001")),
+                gateway_validator_timelock_addr: Some(addr(
+                    "0000000000000000000000000000000000010002",
+                )),
+                gateway_multicall3_addr: Some(addr("0000000000000000000000000000000000010003")),
+                use_dual_verifier: Some(true),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -64,6 +105,27 @@ mod tests {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
                 max_l1_gas_price: Some(100000000),
+                base_fee_smoothing: BaseFeeSmoothing::Ewma,
+                base_fee_ewma_alpha: Some(0.2),
+                base_fee_percentile: None,
+            },
+            fee_escalation: FeeEscalationConfig {
+                commit: FeeEscalationPolicyConfig {
+                    strategy: FeeEscalationStrategy::Linear,
+                    initial_multiplier: Some(1.2),
+                    step: Some(0.05),
+                    max_fee_per_gas: Some(200_000_000_000),
+                    ..FeeEscalationPolicyConfig::default()
+                },
+                prove: FeeEscalationPolicyConfig::default(),
+                execute: FeeEscalationPolicyConfig {
+                    strategy: FeeEscalationStrategy::Schedule,
+                    initial_multiplier: Some(1.0),
+                    schedule_blocks: vec![10, 50],
+                    schedule_multipliers: vec![1.5, 3.0],
+                    priority_fee_bump_percent: Some(15),
+                    ..FeeEscalationPolicyConfig::default()
+                },
             },
         }
     }
@@ -85,6 +147,8 @@ mod tests {
             ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
             ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
             ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
+            ETH_SENDER_GAS_ADJUSTER_BASE_FEE_SMOOTHING="Ewma"
+            ETH_SENDER_GAS_ADJUSTER_BASE_FEE_EWMA_ALPHA="0.2"
             ETH_SENDER_WAIT_FOR_PROOFS="false"
             ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
             ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -96,8 +160,32 @@ mod tests {
             ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
             ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
             ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
+            ETH_SENDER_SENDER_L1_BATCH_EXECUTE_DELAY_SECONDS="600"
+            ETH_SENDER_SENDER_EXECUTE_MAX_L1_GAS_PRICE="50000000000"
+            ETH_SENDER_SENDER_EXECUTE_MAX_POSTPONE_SECONDS="7200"
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
+            ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+            ETH_SENDER_SENDER_OPERATOR_SIGNER="JsonRpc"
+            ETH_SENDER_SENDER_OPERATOR_SIGNER_URL="http://127.0.0.1:9000/"
+            ETH_SENDER_SENDER_OPERATOR_ADDRESS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            ETH_SENDER_SENDER_MAX_AGGREGATION_OVERHEAD_PER_L1_BATCH="1000000000000000"
+            ETH_SENDER_SENDER_SETTLEMENT_LAYER="Gateway"
+            ETH_SENDER_SENDER_GATEWAY_URL="http://127.0.0.1:3150/"
+            ETH_SENDER_SENDER_GATEWAY_CHAIN_ID="506"
+            ETH_SENDER_SENDER_GATEWAY_DIAMOND_PROXY_ADDR="0x0000000000000000000000000000000000010001"
+            ETH_SENDER_SENDER_GATEWAY_VALIDATOR_TIMELOCK_ADDR="0x0000000000000000000000000000000000010002"
+            ETH_SENDER_SENDER_GATEWAY_MULTICALL3_ADDR="0x0000000000000000000000000000000000010003"
+            ETH_SENDER_SENDER_USE_DUAL_VERIFIER="true"
+            ETH_SENDER_FEE_ESCALATION_COMMIT_STRATEGY="Linear"
+            ETH_SENDER_FEE_ESCALATION_COMMIT_INITIAL_MULTIPLIER="1.2"
+            ETH_SENDER_FEE_ESCALATION_COMMIT_STEP="0.05"
+            ETH_SENDER_FEE_ESCALATION_COMMIT_MAX_FEE_PER_GAS="200000000000"
+            ETH_SENDER_FEE_ESCALATION_EXECUTE_STRATEGY="Schedule"
+            ETH_SENDER_FEE_ESCALATION_EXECUTE_INITIAL_MULTIPLIER="1.0"
+            ETH_SENDER_FEE_ESCALATION_EXECUTE_SCHEDULE_BLOCKS="10,50"
+            ETH_SENDER_FEE_ESCALATION_EXECUTE_SCHEDULE_MULTIPLIERS="1.5,3.0"
+            ETH_SENDER_FEE_ESCALATION_EXECUTE_PRIORITY_FEE_BUMP_PERCENT="15"
         "#;
         lock.set_env(config);
 
@@ -108,4 +196,45 @@ mod tests {
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
     }
+
+    #[test]
+    fn validating_commitment_mode() {
+        let mut config = expected_config().sender;
+        for mode in [
+            L1BatchCommitmentMode::Rollup,
+            L1BatchCommitmentMode::Validium,
+        ] {
+            config.validate_commitment_mode(mode).unwrap();
+        }
+
+        config.pubdata_sending_mode = PubdataSendingMode::Custom;
+        config
+            .validate_commitment_mode(L1BatchCommitmentMode::Validium)
+            .unwrap();
+        let err = config
+            .validate_commitment_mode(L1BatchCommitmentMode::Rollup)
+            .unwrap_err();
+        assert!(err.to_string().contains("validium"), "{err}");
+    }
+
+    #[test]
+    fn validating_proof_compression_backend() {
+        let mut config = expected_config().sender;
+        config.use_dual_verifier = Some(false);
+        config
+            .validate_proof_compression_backend(ProofCompressionBackend::Plonk)
+            .unwrap();
+        let err = config
+            .validate_proof_compression_backend(ProofCompressionBackend::Fflonk)
+            .unwrap_err();
+        assert!(err.to_string().contains("dual verifier"), "{err}");
+
+        config.use_dual_verifier = Some(true);
+        for backend in [
+            ProofCompressionBackend::Plonk,
+            ProofCompressionBackend::Fflonk,
+        ] {
+            config.validate_proof_compression_backend(backend).unwrap();
+        }
+    }
 }
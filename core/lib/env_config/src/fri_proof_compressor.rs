@@ -10,6 +10,8 @@ impl FromEnv for FriProofCompressorConfig {
 
 #[cfg(test)]
 mod tests {
+    use zksync_basic_types::basic_fri_types::ProofCompressionBackend;
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
@@ -28,6 +30,9 @@ mod tests {
                 "https://storage.googleapis.com/matterlabs-setup-keys-us/setup-keys/setup_2^26.key"
                     .to_string(),
             verify_wrapper_proof: false,
+            compression_backend: ProofCompressionBackend::Fflonk,
+            fflonk_wrapper_url: Some("http://127.0.0.1:3322".to_string()),
+            fflonk_wrapper_timeout_in_secs: Some(600),
         }
     }
 
@@ -44,6 +49,9 @@ mod tests {
             FRI_PROOF_COMPRESSOR_UNIVERSAL_SETUP_PATH="keys/setup/setup_2^26.key"
             FRI_PROOF_COMPRESSOR_UNIVERSAL_SETUP_DOWNLOAD_URL="https://storage.googleapis.com/matterlabs-setup-keys-us/setup-keys/setup_2^26.key"
             FRI_PROOF_COMPRESSOR_VERIFY_WRAPPER_PROOF=false
+            FRI_PROOF_COMPRESSOR_COMPRESSION_BACKEND="Fflonk"
+            FRI_PROOF_COMPRESSOR_FFLONK_WRAPPER_URL="http://127.0.0.1:3322"
+            FRI_PROOF_COMPRESSOR_FFLONK_WRAPPER_TIMEOUT_IN_SECS=600
         "#;
         lock.set_env(config);
 
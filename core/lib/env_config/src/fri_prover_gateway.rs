@@ -1,15 +1,55 @@
-use zksync_config::configs::FriProverGatewayConfig;
+use serde::Deserialize;
+use zksync_config::configs::{fri_prover_gateway::ProverClusterConfig, FriProverGatewayConfig};
 
 use crate::{envy_load, FromEnv};
 
+/// Names of additional prover clusters. Configuration of each cluster is loaded with
+/// the `FRI_PROVER_GATEWAY_CLUSTER_<NAME>_` prefix.
+#[derive(Debug, Deserialize)]
+struct AdditionalClusterNames {
+    #[serde(default)]
+    additional_clusters: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProverClusterEnvConfig {
+    prover_url: String,
+    weight: Option<u32>,
+}
+
+fn prover_cluster_from_env(name: String) -> anyhow::Result<ProverClusterConfig> {
+    let prefix = format!("FRI_PROVER_GATEWAY_CLUSTER_{}_", name.to_uppercase());
+    let config: ProverClusterEnvConfig = envy_load(&format!("prover_cluster_{name}"), &prefix)?;
+    let object_store = envy_load(
+        &format!("prover_cluster_{name}_object_store"),
+        &format!("{prefix}OBJECT_STORE_"),
+    )?;
+    Ok(ProverClusterConfig {
+        name,
+        prover_url: config.prover_url,
+        weight: config.weight,
+        object_store,
+    })
+}
+
 impl FromEnv for FriProverGatewayConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("fri_prover_gateway", "FRI_PROVER_GATEWAY_")
+        let mut config: Self = envy_load("fri_prover_gateway", "FRI_PROVER_GATEWAY_")?;
+        let cluster_names: AdditionalClusterNames =
+            envy_load("fri_prover_gateway", "FRI_PROVER_GATEWAY_")?;
+        config.additional_clusters = cluster_names
+            .additional_clusters
+            .into_iter()
+            .map(prover_cluster_from_env)
+            .collect::<anyhow::Result<_>>()?;
+        Ok(config)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
@@ -22,6 +62,9 @@ mod tests {
             prometheus_listener_port: 3316,
             prometheus_pushgateway_url: "http://127.0.0.1:9091".to_string(),
             prometheus_push_interval_ms: Some(100),
+            primary_cluster_weight: Some(2),
+            cluster_stall_timeout_secs: Some(600),
+            additional_clusters: vec![],
         }
     }
 
@@ -33,10 +76,58 @@ mod tests {
             FRI_PROVER_GATEWAY_PROMETHEUS_LISTENER_PORT=3316
             FRI_PROVER_GATEWAY_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             FRI_PROVER_GATEWAY_PROMETHEUS_PUSH_INTERVAL_MS=100
+            FRI_PROVER_GATEWAY_PRIMARY_CLUSTER_WEIGHT=2
+            FRI_PROVER_GATEWAY_CLUSTER_STALL_TIMEOUT_SECS=600
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
+        lock.remove_env(&["FRI_PROVER_GATEWAY_ADDITIONAL_CLUSTERS"]);
         let actual = FriProverGatewayConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn from_env_with_additional_clusters() {
+        let config = r#"
+            FRI_PROVER_GATEWAY_API_URL="http://private-dns-for-server"
+            FRI_PROVER_GATEWAY_API_POLL_DURATION_SECS="100"
+            FRI_PROVER_GATEWAY_PROMETHEUS_LISTENER_PORT=3316
+            FRI_PROVER_GATEWAY_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
+            FRI_PROVER_GATEWAY_PROMETHEUS_PUSH_INTERVAL_MS=100
+            FRI_PROVER_GATEWAY_PRIMARY_CLUSTER_WEIGHT=2
+            FRI_PROVER_GATEWAY_CLUSTER_STALL_TIMEOUT_SECS=600
+            FRI_PROVER_GATEWAY_ADDITIONAL_CLUSTERS="cloud"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_PROVER_URL="postgres://postgres@cloud/prover_local"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_WEIGHT=3
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_OBJECT_STORE_BUCKET_BASE_URL="cloud-artifacts"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_OBJECT_STORE_MODE="GCS"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
+            FRI_PROVER_GATEWAY_CLUSTER_CLOUD_OBJECT_STORE_MAX_RETRIES=5
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = FriProverGatewayConfig::from_env().unwrap();
+
+        let mut expected = expected_config();
+        expected.additional_clusters = vec![ProverClusterConfig {
+            name: "cloud".to_owned(),
+            prover_url: "postgres://postgres@cloud/prover_local".to_owned(),
+            weight: Some(3),
+            object_store: ObjectStoreConfig {
+                bucket_base_url: "cloud-artifacts".to_owned(),
+                mode: ObjectStoreMode::GCS,
+                file_backed_base_path: "artifacts".to_owned(),
+                gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
+                max_retries: 5,
+                s3_endpoint: None,
+                s3_region: None,
+                s3_force_path_style: None,
+                s3_server_side_encryption: None,
+                s3_kms_key_id: None,
+                s3_multipart_part_size_bytes: None,
+            },
+        }];
+        assert_eq!(actual, expected);
+    }
 }
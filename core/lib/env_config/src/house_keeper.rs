@@ -30,6 +30,10 @@ mod tests {
             fri_prover_stats_reporting_interval_ms: 30_000,
             fri_proof_compressor_job_retrying_interval_ms: 30_000,
             fri_proof_compressor_stats_reporting_interval_ms: 30_000,
+            prover_api_port: Some(3330),
+            prover_api_auth_token: Some("secret".to_owned()),
+            fri_job_retry_backoff_base_ms: Some(5_000),
+            fri_job_retry_backoff_max_ms: Some(300_000),
         }
     }
 
@@ -50,6 +54,10 @@ mod tests {
             HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
+            HOUSE_KEEPER_PROVER_API_PORT="3330"
+            HOUSE_KEEPER_PROVER_API_AUTH_TOKEN="secret"
+            HOUSE_KEEPER_FRI_JOB_RETRY_BACKOFF_BASE_MS="5000"
+            HOUSE_KEEPER_FRI_JOB_RETRY_BACKOFF_MAX_MS="300000"
         "#;
         lock.set_env(config);
 
@@ -3,10 +3,12 @@ use serde::de::DeserializeOwned;
 
 mod alerts;
 mod api;
+mod call_traces_backfiller;
 mod chain;
 mod circuit_synthesizer;
 mod contract_verifier;
 mod contracts;
+mod da_dispatcher;
 mod database;
 mod eth_client;
 mod eth_sender;
@@ -23,6 +25,7 @@ mod proof_data_handler;
 mod prover;
 mod prover_group;
 mod snapshots_creator;
+mod upgrade_dry_run;
 mod utils;
 mod witness_generator;
 
@@ -42,7 +42,10 @@ impl FromEnv for SnapshotsObjectStoreConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+    use zksync_config::{
+        configs::object_store::{ObjectStoreMode, S3ServerSideEncryption},
+        ObjectStoreConfig,
+    };
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -56,6 +59,12 @@ mod tests {
             file_backed_base_path: "artifacts".to_string(),
             gcs_credential_file_path: "/path/to/credentials.json".to_string(),
             max_retries: 5,
+            s3_endpoint: None,
+            s3_region: None,
+            s3_force_path_style: None,
+            s3_server_side_encryption: None,
+            s3_kms_key_id: None,
+            s3_multipart_part_size_bytes: None,
         }
     }
 
@@ -118,4 +127,38 @@ mod tests {
         let actual = SnapshotsObjectStoreConfig::from_env().unwrap().0;
         assert_eq!(actual, expected_config("/snapshots_base_url"));
     }
+
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_BUCKET_BASE_URL="zksync-artifacts"
+            OBJECT_STORE_MODE="S3"
+            OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
+            OBJECT_STORE_MAX_RETRIES="5"
+            OBJECT_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+            OBJECT_STORE_S3_REGION="us-east-1"
+            OBJECT_STORE_S3_FORCE_PATH_STYLE="true"
+            OBJECT_STORE_S3_SERVER_SIDE_ENCRYPTION="AwsKms"
+            OBJECT_STORE_S3_KMS_KEY_ID="test-key"
+            OBJECT_STORE_S3_MULTIPART_PART_SIZE_BYTES="1048576"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(actual.mode, ObjectStoreMode::S3);
+        assert_eq!(
+            actual.s3_endpoint.as_deref().unwrap(),
+            "http://127.0.0.1:9000"
+        );
+        assert_eq!(actual.s3_region.as_deref().unwrap(), "us-east-1");
+        assert!(actual.s3_force_path_style());
+        assert_eq!(
+            actual.s3_server_side_encryption,
+            Some(S3ServerSideEncryption::AwsKms)
+        );
+        assert_eq!(actual.s3_kms_key_id.as_deref().unwrap(), "test-key");
+        // The part size must be raised to the minimum supported by S3.
+        assert_eq!(actual.s3_multipart_part_size(), 5 << 20);
+    }
 }
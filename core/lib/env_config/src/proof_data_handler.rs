@@ -10,7 +10,9 @@ impl FromEnv for ProofDataHandlerConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::proof_data_handler::ProtocolVersionLoadingMode;
+    use zksync_config::configs::proof_data_handler::{
+        ProofGenerationMode, ProtocolVersionLoadingMode,
+    };
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -23,6 +25,16 @@ mod tests {
             proof_generation_timeout_in_secs: 18000,
             protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
             fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Mock,
+            tee_support: true,
+            tee_attestation_api_key: Some("tee-secret".to_owned()),
+            prioritize_batch_api_key: Some("operator-secret".to_owned()),
+            external_prover_api_keys: vec![
+                "prover-1:0123456789abcdef".to_owned(),
+                "prover-2:fedcba9876543210".to_owned(),
+            ],
+            external_prover_signed_url_ttl_in_secs: Some(600),
+            external_prover_request_max_age_in_secs: None,
         }
     }
 
@@ -33,6 +45,12 @@ mod tests {
             PROOF_DATA_HANDLER_HTTP_PORT="3320"
             PROOF_DATA_HANDLER_PROTOCOL_VERSION_LOADING_MODE="FromEnvVar"
             PROOF_DATA_HANDLER_FRI_PROTOCOL_VERSION_ID="2"
+            PROOF_DATA_HANDLER_PROOF_GENERATION_MODE="Mock"
+            PROOF_DATA_HANDLER_TEE_SUPPORT="true"
+            PROOF_DATA_HANDLER_TEE_ATTESTATION_API_KEY="tee-secret"
+            PROOF_DATA_HANDLER_PRIORITIZE_BATCH_API_KEY="operator-secret"
+            PROOF_DATA_HANDLER_EXTERNAL_PROVER_API_KEYS="prover-1:0123456789abcdef,prover-2:fedcba9876543210"
+            PROOF_DATA_HANDLER_EXTERNAL_PROVER_SIGNED_URL_TTL_IN_SECS="600"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
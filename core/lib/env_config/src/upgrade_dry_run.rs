@@ -0,0 +1,35 @@
+use zksync_config::configs::UpgradeDryRunConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for UpgradeDryRunConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("upgrade_dry_run", "UPGRADE_DRY_RUN_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            UPGRADE_DRY_RUN_L1_BATCHES_TO_REPLAY="10"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = UpgradeDryRunConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            UpgradeDryRunConfig {
+                l1_batches_to_replay: Some(10),
+                polling_interval_ms: None,
+            }
+        );
+        assert_eq!(actual.polling_interval().as_secs(), 60);
+    }
+}
@@ -1,9 +1,17 @@
 use std::{fmt, sync::Arc};
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_config::{
+    configs::eth_sender::OperatorSignerKind, ContractsConfig, ETHClientConfig, ETHSenderConfig,
+};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    error::SignerError,
+    json_rpc_signer::{AddressOrIndex, SignerType},
+    raw_ethereum_tx::TransactionParameters,
+    EthereumSigner, JsonRpcSigner, PrivateKeySigner,
+};
 use zksync_types::{
     web3::{
         self,
@@ -18,7 +26,7 @@ use zksync_types::{
             H160, H256, U256, U64,
         },
     },
-    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE,
+    EIP712TypedStructure, Eip712Domain, L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE,
 };
 
 use super::{query::QueryClient, Method, LATENCIES};
@@ -38,19 +46,130 @@ impl PKSigningClient {
     ) -> Self {
         // Gather required data from the config.
         // It's done explicitly to simplify getting rid of this function later.
-        let main_node_url = &eth_client.web3_url;
         let operator_private_key = eth_sender
             .sender
             .private_key()
             .expect("Operator private key is required for signing client");
+        let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
+            .expect("Failed to get address from private key");
+
+        SigningClient::from_config_with_signer(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            operator_address,
+            PrivateKeySigner::new(operator_private_key),
+        )
+    }
+}
+
+/// Signer of operator transactions, which is either a private key or an external JSON-RPC signer.
+#[derive(Debug, Clone)]
+pub enum OperatorSigner {
+    PrivateKey(PrivateKeySigner),
+    JsonRpc(JsonRpcSigner),
+}
+
+#[async_trait]
+impl EthereumSigner for OperatorSigner {
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_message(message).await,
+            Self::JsonRpc(signer) => signer.sign_message(message).await,
+        }
+    }
+
+    async fn sign_typed_data<S: EIP712TypedStructure + Sync>(
+        &self,
+        domain: &Eip712Domain,
+        typed_struct: &S,
+    ) -> Result<PackedEthSignature, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_typed_data(domain, typed_struct).await,
+            Self::JsonRpc(signer) => signer.sign_typed_data(domain, typed_struct).await,
+        }
+    }
+
+    async fn sign_transaction(
+        &self,
+        raw_tx: TransactionParameters,
+    ) -> Result<Vec<u8>, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.sign_transaction(raw_tx).await,
+            Self::JsonRpc(signer) => signer.sign_transaction(raw_tx).await,
+        }
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        match self {
+            Self::PrivateKey(signer) => signer.get_address().await,
+            Self::JsonRpc(signer) => signer.get_address().await,
+        }
+    }
+}
+
+/// HTTP-based Ethereum client signing transactions with the operator signer specified in the config.
+pub type OperatorSigningClient = SigningClient<OperatorSigner>;
+
+impl OperatorSigningClient {
+    pub async fn from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        let sender_config = &eth_sender.sender;
+        let signer = match sender_config.operator_signer {
+            OperatorSignerKind::PrivateKey => {
+                let operator_private_key = sender_config
+                    .private_key()
+                    .context("Operator private key is required for the private key signer")?;
+                OperatorSigner::PrivateKey(PrivateKeySigner::new(operator_private_key))
+            }
+            OperatorSignerKind::JsonRpc => {
+                let signer_url = sender_config
+                    .operator_signer_url
+                    .clone()
+                    .context("Signer URL is required for the JSON-RPC operator signer")?;
+                let address = sender_config.operator_address.map(AddressOrIndex::Address);
+                // The operator signer is only used to sign transactions, so the signer type
+                // for messages is not detected.
+                let signer =
+                    JsonRpcSigner::new(signer_url, address, Some(SignerType::NotNeedPrefix), None)
+                        .await
+                        .context("failed initializing JSON-RPC operator signer")?;
+                OperatorSigner::JsonRpc(signer)
+            }
+        };
+        let operator_address = signer
+            .get_address()
+            .await
+            .context("failed getting operator address")?;
+
+        Ok(SigningClient::from_config_with_signer(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            operator_address,
+            signer,
+        ))
+    }
+}
+
+impl<S: EthereumSigner> SigningClient<S> {
+    fn from_config_with_signer(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+        operator_address: Address,
+        signer: S,
+    ) -> Self {
+        let main_node_url = &eth_client.web3_url;
         let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
         let transport =
             web3::transports::Http::new(main_node_url).expect("Failed to create transport");
-        let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
-            .expect("Failed to get address from private key");
 
         tracing::info!("Operator address: {:?}", operator_address);
 
@@ -58,7 +177,7 @@ impl PKSigningClient {
             transport,
             zksync_contract(),
             operator_address,
-            PrivateKeySigner::new(operator_private_key),
+            signer,
             diamond_proxy_addr,
             default_priority_fee_per_gas.into(),
             L1ChainId(l1_chain_id),
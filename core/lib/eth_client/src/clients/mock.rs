@@ -317,10 +317,18 @@ impl EthInterface for MockEthereum {
 
     async fn get_tx(
         &self,
-        _hash: H256,
+        hash: H256,
         _component: &'static str,
     ) -> Result<Option<Transaction>, Error> {
-        unimplemented!("Not needed right now")
+        let Some(tx) = self.sent_txs.read().unwrap().get(&hash).copied() else {
+            return Ok(None);
+        };
+        Ok(Some(Transaction {
+            hash,
+            nonce: tx.nonce.into(),
+            from: Some(self.sender_account()),
+            ..Transaction::default()
+        }))
     }
 
     async fn tx_receipt(
@@ -129,14 +129,16 @@ impl EthereumSigner for JsonRpcSigner {
             .await
             .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
 
-        // get Json object and parse it to get raw Transaction
+        // Get JSON object and parse it to get raw transaction. Some signers (e.g., Geth or Clef) return an object
+        // with the `raw` field, while others (e.g., Web3Signer) return the raw transaction hex string directly.
         let json: Value = serde_json::from_value(ret)
             .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
 
-        let raw_tx: Option<&str> = json
-            .get("raw")
-            .and_then(|value| value.as_str())
-            .map(|value| &value["0x".len()..]);
+        let raw_tx: Option<&str> = match &json {
+            Value::String(raw_tx) => Some(raw_tx.as_str()),
+            _ => json.get("raw").and_then(|value| value.as_str()),
+        }
+        .map(|value| value.strip_prefix("0x").unwrap_or(value));
 
         if let Some(raw_tx) = raw_tx {
             hex::decode(raw_tx).map_err(|err| SignerError::DecodeRawTxFailed(err.to_string()))
@@ -330,7 +332,7 @@ mod messages {
     use hex::encode;
     use serde::{Deserialize, Serialize};
     use zksync_types::{
-        eip712_signature::utils::get_eip712_json, Address, EIP712TypedStructure, Eip712Domain,
+        eip712_signature::utils::get_eip712_json, Address, EIP712TypedStructure, Eip712Domain, U64,
     };
 
     use crate::raw_ethereum_tx::TransactionParameters;
@@ -411,6 +413,8 @@ mod messages {
                     "value": serde_json::to_value(tx_data.value).expect("serialization fail"),
                     "data": serde_json::to_value(format!("0x{}", encode(tx_data.data))).expect("serialization fail"),
                     "nonce": serde_json::to_value(tx_data.nonce).expect("serialization fail"),
+                    "chainId": serde_json::to_value(U64::from(tx_data.chain_id)).expect("serialization fail"),
+                    "type": serde_json::to_value(tx_data.transaction_type).expect("serialization fail"),
                 })
             } else {
                 serde_json::json!({
@@ -422,6 +426,8 @@ mod messages {
                     "value": serde_json::to_value(tx_data.value).expect("serialization fail"),
                     "data": serde_json::to_value(format!("0x{}", encode(tx_data.data))).expect("serialization fail"),
                     "nonce": serde_json::to_value(tx_data.nonce).expect("serialization fail"),
+                    "chainId": serde_json::to_value(U64::from(tx_data.chain_id)).expect("serialization fail"),
+                    "type": serde_json::to_value(tx_data.transaction_type).expect("serialization fail"),
                 })
             };
             params.push(tx);
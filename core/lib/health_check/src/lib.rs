@@ -15,6 +15,11 @@ pub enum HealthStatus {
     NotReady,
     /// Component is ready for operations.
     Ready,
+    /// Component is operational, but its performance is degraded (e.g., it lags behind).
+    Degraded,
+    /// Component is running, but cannot properly perform its operations (e.g., it lags behind too much,
+    /// or its dependencies are unreachable).
+    Unhealthy,
     /// Component is shut down.
     ShutDown,
     /// Component has been abnormally interrupted by a panic.
@@ -22,17 +27,26 @@ pub enum HealthStatus {
 }
 
 impl HealthStatus {
-    /// Checks whether a component is ready according to this status.
+    /// Checks whether a component is ready to serve requests according to this status.
+    /// Degraded components are considered ready.
     pub fn is_ready(self) -> bool {
-        matches!(self, Self::Ready)
+        matches!(self, Self::Ready | Self::Degraded)
+    }
+
+    /// Checks whether a component is alive according to this status, i.e., whether it doesn't require
+    /// the application to be restarted. Only panicked components are considered not alive.
+    pub fn is_live(self) -> bool {
+        !matches!(self, Self::Panicked)
     }
 
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
-            Self::ShutDown => 1,
-            Self::NotReady => 2,
-            Self::Panicked => 3,
+            Self::Degraded => 1,
+            Self::ShutDown => 2,
+            Self::NotReady => 3,
+            Self::Unhealthy => 4,
+            Self::Panicked => 5,
         }
     }
 }
@@ -100,6 +114,10 @@ impl AppHealth {
     pub fn is_ready(&self) -> bool {
         self.inner.status.is_ready()
     }
+
+    pub fn is_live(&self) -> bool {
+        self.inner.status.is_live()
+    }
 }
 
 /// Interface to be used for health checks.
@@ -233,6 +251,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn aggregating_health_statuses() {
+        let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+        let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+        let checks: Vec<Box<dyn CheckHealth>> = vec![Box::new(first_check), Box::new(second_check)];
+
+        first_updater.update(HealthStatus::Ready.into());
+        second_updater.update(HealthStatus::Degraded.into());
+        let health = AppHealth::new(&checks).await;
+        assert_matches!(health.inner.status(), HealthStatus::Degraded);
+        assert!(health.is_ready());
+        assert!(health.is_live());
+
+        second_updater.update(HealthStatus::Unhealthy.into());
+        let health = AppHealth::new(&checks).await;
+        assert_matches!(health.inner.status(), HealthStatus::Unhealthy);
+        assert!(!health.is_ready());
+        assert!(health.is_live());
+
+        first_updater.update(HealthStatus::Panicked.into());
+        let health = AppHealth::new(&checks).await;
+        assert_matches!(health.inner.status(), HealthStatus::Panicked);
+        assert!(!health.is_ready());
+        assert!(!health.is_live());
+    }
+
     #[tokio::test]
     async fn updating_health_status_return_value() {
         let (health_check, health_updater) = ReactiveHealthCheck::new("test");
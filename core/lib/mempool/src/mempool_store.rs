@@ -1,15 +1,22 @@
-use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map, BTreeSet, BinaryHeap, HashMap, HashSet},
+};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{
+    AccountTransactions, EvictionReason, L2TxFilter, MempoolEviction, MempoolOrdering, MempoolScore,
+};
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Hashes of L2 transactions evicted from the mempool since the previous call.
+    pub evicted_transactions: Vec<(H256, EvictionReason)>,
 }
 
 #[derive(Debug)]
@@ -30,9 +37,16 @@ pub struct MempoolStore {
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
+    evicted_transactions: Vec<(H256, EvictionReason)>,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
+    ordering: MempoolOrdering,
+    /// Base fee per gas from the last filter provided to the mempool. Used to compute effective priority fees
+    /// of L2 transactions for ordering and eviction.
+    base_fee: u64,
+    max_txs_per_account: Option<usize>,
+    eviction: Option<MempoolEviction>,
 }
 
 impl MempoolStore {
@@ -43,11 +57,41 @@ impl MempoolStore {
             l2_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
+            evicted_transactions: vec![],
             size: 0,
             capacity,
+            ordering: MempoolOrdering::default(),
+            base_fee: 0,
+            max_txs_per_account: None,
+            eviction: None,
         }
     }
 
+    /// Sets the ordering policy for L2 transactions. Must be called before any transactions
+    /// are inserted into the mempool.
+    pub fn with_ordering(mut self, ordering: MempoolOrdering) -> Self {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "mempool ordering must be set before inserting transactions"
+        );
+        self.ordering = ordering;
+        self
+    }
+
+    /// Limits the number of L2 transactions per account. Once an account exceeds the limit, its transactions
+    /// with the highest nonces are evicted.
+    pub fn with_max_txs_per_account(mut self, max_txs_per_account: usize) -> Self {
+        self.max_txs_per_account = Some(max_txs_per_account);
+        self
+    }
+
+    /// Enables evicting L2 transactions using the specified policy once the number of L2 transactions
+    /// exceeds the mempool capacity.
+    pub fn with_eviction(mut self, eviction: MempoolEviction) -> Self {
+        self.eviction = Some(eviction);
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -57,6 +101,7 @@ impl MempoolStore {
         transactions: Vec<Transaction>,
         initial_nonces: HashMap<Address, Nonce>,
     ) {
+        let mut updated_accounts = HashSet::new();
         for transaction in transactions {
             let Transaction {
                 common_data,
@@ -78,6 +123,7 @@ impl MempoolStore {
                 }
                 ExecuteTransactionCommon::L2(data) => {
                     tracing::trace!("inserting L2 transaction {}", data.nonce);
+                    updated_accounts.insert(data.initiator_address);
                     self.insert_l2_transaction(
                         L2Tx {
                             execute,
@@ -93,6 +139,65 @@ impl MempoolStore {
                 }
             }
         }
+
+        if let Some(max_txs_per_account) = self.max_txs_per_account {
+            for account in updated_accounts {
+                while self.l2_transactions_per_account[&account].len() > max_txs_per_account {
+                    self.evict_last_transaction(account, EvictionReason::AccountQuota);
+                }
+            }
+        }
+        if let Some(eviction) = self.eviction {
+            self.evict_over_capacity(eviction);
+        }
+    }
+
+    /// Evicts transactions until the mempool size fits into its capacity. Transactions are evicted
+    /// from the ends of account queues so that accounts don't get nonce gaps.
+    fn evict_over_capacity(&mut self, eviction: MempoolEviction) {
+        if self.size <= self.capacity {
+            return;
+        }
+
+        let base_fee = self.base_fee;
+        let mut candidates: BinaryHeap<_> = self
+            .l2_transactions_per_account
+            .iter()
+            .filter_map(|(&account, txs)| {
+                Some(Reverse((eviction.key(txs.last()?, base_fee), account)))
+            })
+            .collect();
+        while self.size > self.capacity {
+            let Some(Reverse((_, account))) = candidates.pop() else {
+                break;
+            };
+            self.evict_last_transaction(account, EvictionReason::Capacity);
+            if let Some(tx) = self.l2_transactions_per_account[&account].last() {
+                candidates.push(Reverse((eviction.key(tx, base_fee), account)));
+            }
+        }
+    }
+
+    fn evict_last_transaction(&mut self, account: Address, reason: EvictionReason) {
+        let txs = self
+            .l2_transactions_per_account
+            .get_mut(&account)
+            .expect("mempool: evicting transaction of unknown account");
+        let Some((transaction, score)) = txs.remove_last() else {
+            return;
+        };
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size = self
+            .size
+            .checked_sub(1)
+            .expect("mempool size can't be negative");
+        tracing::debug!(
+            "evicted L2 transaction {:?} from mempool: {reason:?}",
+            transaction.hash()
+        );
+        self.evicted_transactions.push((transaction.hash(), reason));
     }
 
     fn insert_l2_transaction(
@@ -106,9 +211,8 @@ impl MempoolStore {
             hash_map::Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
             hash_map::Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
-                entry
-                    .insert(AccountTransactions::new(account_nonce))
-                    .insert(transaction)
+                let txs = AccountTransactions::new(account_nonce, self.ordering, self.base_fee);
+                entry.insert(txs).insert(transaction)
             }
         };
         if let Some(score) = metadata.previous_score {
@@ -134,11 +238,20 @@ impl MempoolStore {
 
     /// Returns next transaction for execution from mempool
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
-            self.next_priority_id += 1;
-            return Some(transaction.into());
-        }
+        self.next_l1_transaction()
+            .or_else(|| self.next_l2_transaction(filter))
+    }
+
+    /// Returns next L1 transaction for execution from mempool, skipping L2 transactions.
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        let transaction = self.l1_transactions.remove(&self.next_priority_id)?;
+        self.next_priority_id += 1;
+        Some(transaction.into())
+    }
 
+    /// Returns next L2 transaction for execution from mempool, skipping L1 transactions.
+    pub fn next_l2_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
+        self.update_base_fee(filter);
         let mut removed = 0;
         // We want to fetch the next transaction that would match the fee requirements.
         let tx_pointer = self
@@ -179,6 +292,99 @@ impl MempoolStore {
         Some(transaction.into())
     }
 
+    /// Updates the base fee used to compute effective priority fees of L2 transactions to the one
+    /// from `filter`. With the fee priority ordering, this reorders the priority queue.
+    fn update_base_fee(&mut self, filter: &L2TxFilter) {
+        let base_fee = filter.fee_per_gas;
+        if self.base_fee == base_fee {
+            return;
+        }
+        self.base_fee = base_fee;
+        // With the FIFO ordering, scores don't depend on the base fee, so they don't need to be updated.
+        if self.ordering == MempoolOrdering::FeePriority {
+            for txs in self.l2_transactions_per_account.values_mut() {
+                txs.set_base_fee(base_fee);
+            }
+            self.l2_priority_queue = self
+                .l2_transactions_per_account
+                .values()
+                .filter_map(AccountTransactions::next_score)
+                .collect();
+        }
+    }
+
+    /// Returns up to `limit` transactions that are going to be returned by the following calls
+    /// to [`Self::next_transaction()`] with the same filter, without removing them from the mempool.
+    /// Only the next transaction of each account is considered. Transactions with hashes for which `skip`
+    /// returns `true` are not cloned and don't count towards the limit.
+    pub fn peek_transactions(
+        &mut self,
+        filter: &L2TxFilter,
+        limit: usize,
+        skip: impl Fn(&H256) -> bool,
+    ) -> Vec<Transaction> {
+        self.update_base_fee(filter);
+        let l1_transactions = (0..)
+            .map_while(|i| self.l1_transactions.get(&(self.next_priority_id + i)))
+            .filter(|tx| !skip(&tx.hash()))
+            .map(|tx| Transaction::from(tx.clone()));
+        let l2_transactions = self
+            .l2_priority_queue
+            .iter()
+            .rev()
+            .filter(|score| score.matches_filter(filter))
+            .filter_map(|score| self.l2_transactions_per_account.get(&score.account)?.peek())
+            .filter(|tx| !skip(&tx.hash()))
+            .map(|tx| Transaction::from(tx.clone()));
+        l1_transactions.chain(l2_transactions).take(limit).collect()
+    }
+
+    /// Notifies the mempool that an L2 transaction was returned to the state keeper bypassing the mempool
+    /// (e.g., as a part of a block proposed by an external builder). Mempool transactions of the same initiator
+    /// with the same or lower nonce are removed since they cannot be executed anymore. Like with transactions
+    /// returned by [`Self::next_transaction()`], the nonce can be reset using [`Self::rollback()`].
+    pub fn skip_l2_transaction(&mut self, transaction: &Transaction) {
+        let account = transaction.initiator_account();
+        let nonce = transaction
+            .nonce()
+            .expect("nonce is not set for L2 transaction");
+        let (ordering, base_fee) = (self.ordering, self.base_fee);
+        let metadata = self
+            .l2_transactions_per_account
+            .entry(account)
+            .or_insert_with(|| AccountTransactions::new(nonce, ordering, base_fee))
+            .advance_nonce(nonce + 1);
+
+        if let Some(score) = metadata.previous_score {
+            self.l2_priority_queue.remove(&score);
+        }
+        if let Some(score) = metadata.new_score {
+            self.l2_priority_queue.insert(score);
+        }
+        self.size = self
+            .size
+            .checked_sub(metadata.removed as u64)
+            .expect("mempool size can't be negative");
+    }
+
+    /// Removes an L2 transaction with the specified initiator and nonce (e.g., because it has expired).
+    /// Transactions of the same initiator with higher nonces are kept, but cannot be executed until
+    /// the nonce gap is filled. Returns the hash of the removed transaction, if any.
+    pub fn remove_l2_transaction(&mut self, account: Address, nonce: Nonce) -> Option<H256> {
+        let (transaction, score) = self
+            .l2_transactions_per_account
+            .get_mut(&account)?
+            .remove(nonce)?;
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size = self
+            .size
+            .checked_sub(1)
+            .expect("mempool size can't be negative");
+        Some(transaction.hash())
+    }
+
     /// When a state_keeper starts the block over after a rejected transaction,
     /// we have to rollback the nonces/ids in the mempool and
     /// reinsert the transactions from the block back into mempool.
@@ -209,6 +415,7 @@ impl MempoolStore {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
             purged_accounts: self.gc(),
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
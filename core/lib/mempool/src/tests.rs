@@ -12,7 +12,10 @@ use zksync_types::{
     H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::MempoolStore,
+    types::{EvictionReason, L2TxFilter, MempoolEviction, MempoolOrdering},
+};
 
 #[test]
 fn basic_flow() {
@@ -365,6 +368,312 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn fee_priority_ordering() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority);
+    let cheap_account = Address::random();
+    let expensive_account = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(cheap_account, Nonce(0), 0, 1),
+        gen_l2_tx_with_priority_fee(cheap_account, Nonce(1), 1, 100),
+        gen_l2_tx_with_priority_fee(expensive_account, Nonce(0), 2, 10),
+        gen_l2_tx_with_priority_fee(expensive_account, Nonce(1), 3, 1),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // Transactions of each account are returned in the nonce order, even if a later transaction
+    // has higher fee.
+    let filter = L2TxFilter::default();
+    assert_eq!(
+        view(mempool.next_transaction(&filter)),
+        (expensive_account, 0)
+    );
+    assert_eq!(view(mempool.next_transaction(&filter)), (cheap_account, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (cheap_account, 1));
+    assert_eq!(
+        view(mempool.next_transaction(&filter)),
+        (expensive_account, 1)
+    );
+    assert_eq!(mempool.next_transaction(&filter), None);
+}
+
+#[test]
+fn fee_priority_ordering_falls_back_to_fifo() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 1, 10),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 0, 10),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+}
+
+#[test]
+fn fee_priority_ordering_accounts_for_base_fee() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_fee(account0, Nonce(0), 0, 150, 100),
+        gen_l2_tx_with_fee(account1, Nonce(0), 1, 1_000, 60),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let peeked = mempool.peek_transactions(&L2TxFilter::default(), 10, |_| false);
+    let peeked: Vec<_> = peeked.into_iter().map(Some).map(view).collect();
+    assert_eq!(peeked, [(account0, 0), (account1, 0)]);
+
+    // With the base fee taken into account, the first transaction pays a lower priority fee (50 vs 60).
+    let filter = L2TxFilter {
+        fee_per_gas: 100,
+        ..L2TxFilter::default()
+    };
+    let peeked = mempool.peek_transactions(&filter, 10, |_| false);
+    let peeked: Vec<_> = peeked.into_iter().map(Some).map(view).collect();
+    assert_eq!(peeked, [(account1, 0), (account0, 0)]);
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(mempool.next_transaction(&filter), None);
+}
+
+#[test]
+fn peeking_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l2_tx_with_timestamp(account0, Nonce(0), 1),
+        gen_l2_tx_with_timestamp(account0, Nonce(1), 2),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), 3),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    let peeked = mempool.peek_transactions(&filter, 10, |_| false);
+    assert_eq!(peeked.len(), 3);
+    assert!(peeked[0].is_l1());
+    let peeked_l2: Vec<_> = peeked[1..].iter().cloned().map(Some).map(view).collect();
+    assert_eq!(peeked_l2, [(account0, 0), (account1, 0)]);
+    assert_eq!(mempool.peek_transactions(&filter, 2, |_| false).len(), 2);
+
+    // Skipped transactions must not count towards the limit.
+    let skipped_hash = peeked[0].hash();
+    let peeked_with_skip = mempool.peek_transactions(&filter, 2, |hash| *hash == skipped_hash);
+    let peeked_with_skip: Vec<_> = peeked_with_skip.into_iter().map(Some).map(view).collect();
+    assert_eq!(peeked_with_skip, [(account0, 0), (account1, 0)]);
+
+    // Peeking must not change the mempool state.
+    assert!(mempool.next_transaction(&filter).unwrap().is_l1());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    let peeked = mempool.peek_transactions(&filter, 10, |_| false);
+    let peeked: Vec<_> = peeked.into_iter().map(Some).map(view).collect();
+    assert_eq!(peeked, [(account0, 1), (account1, 0)]);
+}
+
+#[test]
+fn skipping_l1_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let transactions = vec![gen_l1_tx(PriorityOpId(0)), gen_l2_tx(account, Nonce(0))];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_l2_transaction(&filter)), (account, 0));
+    assert!(mempool.next_l2_transaction(&filter).is_none());
+    // The skipped L1 transaction is still returned afterwards.
+    assert!(mempool.next_transaction(&filter).unwrap().is_l1());
+}
+
+#[test]
+fn skipping_l2_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account0, Nonce(2)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // A transaction with the same nonce, but a different hash, is included bypassing the mempool.
+    let filter = L2TxFilter::default();
+    mempool.skip_l2_transaction(&gen_l2_tx(account0, Nonce(0)));
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    mempool.skip_l2_transaction(&gen_l2_tx(account0, Nonce(2)));
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+    assert!(mempool.next_transaction(&filter).is_none());
+
+    // Skipping a transaction of an account unknown to the mempool should advance its nonce.
+    mempool.skip_l2_transaction(&gen_l2_tx(account1, Nonce(0)));
+    mempool.insert(
+        vec![gen_l2_tx(account1, Nonce(0)), gen_l2_tx(account1, Nonce(1))],
+        HashMap::new(),
+    );
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 1));
+
+    // Rolling back the skipped transaction should reset the nonce.
+    let skipped_tx = gen_l2_tx(account1, Nonce(2));
+    mempool.skip_l2_transaction(&skipped_tx);
+    mempool.rollback(&skipped_tx);
+    mempool.insert(vec![skipped_tx], HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 2));
+}
+
+#[test]
+fn removing_l2_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account1, Nonce(0)),
+        gen_l2_tx(account1, Nonce(1)),
+    ];
+    let removed_hash = transactions[0].hash();
+    mempool.insert(transactions, HashMap::new());
+
+    assert_eq!(
+        mempool.remove_l2_transaction(account0, Nonce(0)),
+        Some(removed_hash)
+    );
+    assert_eq!(mempool.remove_l2_transaction(account0, Nonce(0)), None);
+    assert_eq!(
+        mempool.remove_l2_transaction(Address::random(), Nonce(0)),
+        None
+    );
+    assert!(mempool.remove_l2_transaction(account1, Nonce(1)).is_some());
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+
+    // The remaining transaction of `account0` is not executable because of the nonce gap.
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert!(mempool.next_transaction(&filter).is_none());
+    mempool.insert(vec![gen_l2_tx(account0, Nonce(0))], HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+}
+
+#[test]
+fn account_quota() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_max_txs_per_account(2);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account0, Nonce(3)),
+        gen_l2_tx(account1, Nonce(0)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+    let evicted = mempool.get_mempool_info().evicted_transactions;
+    assert_eq!(evicted.len(), 2);
+    assert!(evicted
+        .iter()
+        .all(|(_, reason)| *reason == EvictionReason::AccountQuota));
+
+    let filter = L2TxFilter::default();
+    let remaining: HashSet<_> = (0..3)
+        .map(|_| view(mempool.next_transaction(&filter)))
+        .collect();
+    assert_eq!(
+        remaining,
+        HashSet::from_iter(vec![(account0, 0), (account0, 1), (account1, 0)])
+    );
+    assert!(mempool.next_transaction(&filter).is_none());
+}
+
+#[test]
+fn evicting_lowest_fee_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3)
+        .with_ordering(MempoolOrdering::FeePriority)
+        .with_eviction(MempoolEviction::LowestFee);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 0, 5),
+        gen_l2_tx_with_priority_fee(account0, Nonce(1), 5, 1),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 2, 3),
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), 3, 2),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let evicted = mempool.get_mempool_info().evicted_transactions;
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].1, EvictionReason::Capacity);
+
+    // The lowest-fee transaction is evicted even though it is the newest one.
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    assert!(mempool.next_transaction(&filter).is_none());
+}
+
+#[test]
+fn evicting_lowest_fee_transactions_accounts_for_base_fee() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 1)
+        .with_ordering(MempoolOrdering::FeePriority)
+        .with_eviction(MempoolEviction::LowestFee);
+    let filter = L2TxFilter {
+        fee_per_gas: 100,
+        ..L2TxFilter::default()
+    };
+    // Make the mempool learn the current base fee.
+    assert_eq!(mempool.next_transaction(&filter), None);
+
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_fee(account0, Nonce(0), 0, 150, 100),
+        gen_l2_tx_with_fee(account1, Nonce(0), 1, 1_000, 60),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let evicted = mempool.get_mempool_info().evicted_transactions;
+    assert_eq!(evicted.len(), 1);
+
+    // The first transaction has a higher max priority fee, but pays a lower priority fee on top of the base fee.
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert!(mempool.next_transaction(&filter).is_none());
+}
+
+#[test]
+fn evicting_oldest_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 2).with_eviction(MempoolEviction::Oldest);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account0, Nonce(0), 1),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), 2),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+
+    mempool.insert(
+        vec![gen_l2_tx_with_timestamp(account2, Nonce(0), 3)],
+        HashMap::new(),
+    );
+    assert_eq!(mempool.get_mempool_info().evicted_transactions.len(), 1);
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    assert!(mempool.next_transaction(&filter).is_none());
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -384,6 +693,33 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+fn gen_l2_tx_with_priority_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    priority_fee: u64,
+) -> Transaction {
+    gen_l2_tx_with_fee(address, nonce, received_at_ms, 1_000, priority_fee)
+}
+
+fn gen_l2_tx_with_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_fee: u64,
+    priority_fee: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_fee_per_gas = U256::from(max_fee);
+            data.fee.max_priority_fee_per_gas = U256::from(priority_fee);
+        }
+        _ => unreachable!(),
+    }
+    tx
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
@@ -10,13 +10,19 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// ordering used for scores of account transactions
+    ordering: MempoolOrdering,
+    /// base fee per gas used for scores of account transactions
+    base_fee: u64,
 }
 
 impl AccountTransactions {
-    pub fn new(nonce: Nonce) -> Self {
+    pub fn new(nonce: Nonce, ordering: MempoolOrdering, base_fee: u64) -> Self {
         Self {
             transactions: HashMap::new(),
             nonce,
+            ordering,
+            base_fee,
         }
     }
 
@@ -28,11 +34,11 @@ impl AccountTransactions {
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
+        let new_score = self.score_for_transaction(&transaction);
         let previous_score = self
             .transactions
             .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+            .map(|tx| self.score_for_transaction(&tx));
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -49,13 +55,75 @@ impl AccountTransactions {
             .remove(&self.nonce)
             .expect("missing transaction in mempool");
         self.nonce += 1;
-        let score = self
-            .transactions
-            .get(&self.nonce)
-            .map(Self::score_for_transaction);
+        let score = self.next_score();
         (transaction, score)
     }
 
+    /// Returns the transaction that will be returned by the next call to [`Self::next()`], if any.
+    pub fn peek(&self) -> Option<&L2Tx> {
+        self.transactions.get(&self.nonce)
+    }
+
+    /// Returns the score of the transaction returned by [`Self::peek()`], if any.
+    pub fn next_score(&self) -> Option<MempoolScore> {
+        self.peek().map(|tx| self.score_for_transaction(tx))
+    }
+
+    /// Sets the base fee per gas used for scores of account transactions. Scores previously returned
+    /// by the account are invalidated.
+    pub fn set_base_fee(&mut self, base_fee: u64) {
+        self.base_fee = base_fee;
+    }
+
+    /// Returns the transaction with the highest nonce, if any.
+    pub fn last(&self) -> Option<&L2Tx> {
+        self.transactions
+            .iter()
+            .max_by_key(|(nonce, _)| **nonce)
+            .map(|(_, tx)| tx)
+    }
+
+    /// Removes the transaction with the highest nonce. Returns the removed transaction and its score
+    /// if it was the next transaction to be included in block.
+    pub fn remove_last(&mut self) -> Option<(L2Tx, Option<MempoolScore>)> {
+        let last_nonce = *self.transactions.keys().max()?;
+        let transaction = self.transactions.remove(&last_nonce)?;
+        let score = if last_nonce == self.nonce {
+            Some(self.score_for_transaction(&transaction))
+        } else {
+            None
+        };
+        Some((transaction, score))
+    }
+
+    /// Removes the transaction with the specified nonce. Returns the removed transaction and its score
+    /// if it was the next transaction to be included in block.
+    pub fn remove(&mut self, nonce: Nonce) -> Option<(L2Tx, Option<MempoolScore>)> {
+        let transaction = self.transactions.remove(&nonce)?;
+        let score = if nonce == self.nonce {
+            Some(self.score_for_transaction(&transaction))
+        } else {
+            None
+        };
+        Some((transaction, score))
+    }
+
+    /// Advances the account nonce to `next_nonce`, removing transactions with lower nonces.
+    /// Used when transactions of the account are included into a block bypassing the mempool.
+    pub fn advance_nonce(&mut self, next_nonce: Nonce) -> NonceAdvanceMetadata {
+        let mut metadata = NonceAdvanceMetadata::default();
+        if next_nonce <= self.nonce {
+            return metadata;
+        }
+        metadata.previous_score = self.next_score();
+        let len = self.transactions.len();
+        self.transactions.retain(|&nonce, _| nonce >= next_nonce);
+        metadata.removed = len - self.transactions.len();
+        self.nonce = next_nonce;
+        metadata.new_score = self.next_score();
+        metadata
+    }
+
     /// Handles transaction rejection. Returns optional score of its successor
     pub fn reset(&mut self, transaction: &Transaction) -> Option<MempoolScore> {
         // current nonce for the group needs to be reset
@@ -65,44 +133,112 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| self.score_for_transaction(tx))
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    fn score_for_transaction(&self, transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
             received_at_ms: transaction.received_timestamp_ms,
             fee_data: transaction.common_data.fee.clone(),
+            ordering: self.ordering,
+            base_fee: self.base_fee,
         }
     }
 }
 
+/// Ordering policy for ready L2 transactions in the mempool. Transactions of a single account
+/// are always returned in the nonce order, regardless of the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MempoolOrdering {
+    /// Transactions are ordered by the time they were received (earlier transactions go first).
+    #[default]
+    Fifo,
+    /// Transactions are ordered by the effective priority fee (higher fee goes first);
+    /// transactions with equal fees are ordered by the time they were received.
+    FeePriority,
+}
+
+/// Policy used to evict L2 transactions from a full mempool. Only the transaction with the highest nonce
+/// of an account can be evicted, so that the remaining transactions of the account stay executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MempoolEviction {
+    /// Transactions with the lowest effective priority fee are evicted first; transactions with equal fees
+    /// are evicted in the order they were received.
+    LowestFee,
+    /// Transactions are evicted in the order they were received.
+    Oldest,
+}
+
+impl MempoolEviction {
+    /// Returns the key determining the eviction order (transactions with lower keys are evicted first).
+    pub(crate) fn key(self, transaction: &L2Tx, base_fee: u64) -> (U256, u64) {
+        let fee_key = match self {
+            Self::LowestFee => effective_priority_fee(&transaction.common_data.fee, base_fee),
+            Self::Oldest => U256::zero(),
+        };
+        (fee_key, transaction.received_timestamp_ms)
+    }
+}
+
+/// Returns the priority fee per gas a transaction with the specified fee parameters pays on top
+/// of `base_fee`, i.e. `min(max_priority_fee_per_gas, max_fee_per_gas - base_fee)`.
+fn effective_priority_fee(fee: &Fee, base_fee: u64) -> U256 {
+    let max_priority_fee = fee.max_fee_per_gas.saturating_sub(U256::from(base_fee));
+    fee.max_priority_fee_per_gas.min(max_priority_fee)
+}
+
+/// Reason of evicting a transaction from the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionReason {
+    /// The initiator account exceeded the maximum number of pending transactions.
+    AccountQuota,
+    /// The mempool exceeded its capacity.
+    Capacity,
+}
+
 /// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// according to the [`MempoolOrdering`] policy.
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
     pub received_at_ms: u64,
-    // Not used for actual scoring, but state keeper would request
+    // Used for scoring only with the fee priority ordering, but state keeper would request
     // transactions that have acceptable fee values (so transactions
     // with fee too low would be ignored until prices go down).
     pub fee_data: Fee,
+    pub ordering: MempoolOrdering,
+    /// Base fee per gas used to compute the effective priority fee with the fee priority ordering.
+    pub base_fee: u64,
 }
 
 impl MempoolScore {
+    /// Returns the priority fee per gas the transaction is willing to pay.
+    fn effective_priority_fee(&self) -> U256 {
+        effective_priority_fee(&self.fee_data, self.base_fee)
+    }
+
     /// Checks whether transaction matches requirements provided by state keeper.
     pub fn matches_filter(&self, filter: &L2TxFilter) -> bool {
-        self.fee_data.max_fee_per_gas >= U256::from(filter.fee_per_gas)
-            && self.fee_data.gas_per_pubdata_limit >= U256::from(filter.gas_per_pubdata)
+        filter.matches_fee(&self.fee_data)
     }
 }
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
+        if self.ordering == MempoolOrdering::FeePriority {
+            match self
+                .effective_priority_fee()
+                .cmp(&other.effective_priority_fee())
+            {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
         match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
             Ordering::Equal => {}
             ordering => return ordering,
@@ -124,6 +260,13 @@ pub(crate) struct InsertionMetadata {
     pub is_new: bool,
 }
 
+#[derive(Debug, Default)]
+pub(crate) struct NonceAdvanceMetadata {
+    pub previous_score: Option<MempoolScore>,
+    pub new_score: Option<MempoolScore>,
+    pub removed: usize,
+}
+
 /// Structure that can be used by state keeper to describe
 /// criteria for transaction it wants to fetch.
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -136,6 +279,14 @@ pub struct L2TxFilter {
     pub gas_per_pubdata: u32,
 }
 
+impl L2TxFilter {
+    /// Checks whether a transaction with the specified fee parameters matches this filter.
+    pub fn matches_fee(&self, fee: &Fee) -> bool {
+        fee.max_fee_per_gas >= U256::from(self.fee_per_gas)
+            && fee.gas_per_pubdata_limit >= U256::from(self.gas_per_pubdata)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +315,8 @@ mod tests {
                 max_priority_fee_per_gas: U256::from(MAX_PRIORITY_FEE_PER_GAS),
                 gas_per_pubdata_limit: U256::from(GAS_PER_PUBDATA_LIMIT),
             },
+            ordering: MempoolOrdering::Fifo,
+            base_fee: 0,
         };
 
         let noop_filter = filter(0, 0, 0);
@@ -25,6 +25,9 @@ struct Cli {
     /// Perform random recovery instead of linear recovery.
     #[arg(name = "random", long)]
     random: bool,
+    /// Parallelize random recovery of each chunk.
+    #[arg(name = "parallel", long, requires = "random")]
+    parallel: bool,
     /// Use a no-op hashing function.
     #[arg(name = "no-hash", long)]
     no_hashing: bool,
@@ -109,7 +112,9 @@ impl Cli {
                     }
                 })
                 .collect();
-            if self.random {
+            if self.parallel {
+                recovery.extend_parallel(recovery_entries);
+            } else if self.random {
                 recovery.extend_random(recovery_entries);
             } else {
                 recovery.extend_linear(recovery_entries);
@@ -106,6 +106,95 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         Ok(())
     }
 
+    /// Verifies consistency of a single subtree of the tree at the specified `version` and recomputes
+    /// the tree root hash based on the verified subtree. The subtree is the one containing `key` with the root
+    /// at `depth` nibbles; e.g., `depth == 2` corresponds to ~1/256 of all tree leaves. If the subtree
+    /// consists of a single leaf at a lesser depth, this leaf is verified; if the subtree is empty,
+    /// only the path to it is verified.
+    ///
+    /// Besides verifying the subtree itself, the method checks that the subtree hash is correctly propagated
+    /// to the tree root. Hence, comparing the returned root hash with an externally stored one allows
+    /// to detect tree corruption at a fraction of the cost of [`Self::verify_consistency()`], e.g. by
+    /// periodically sampling random subtrees. Leaf indices are not validated since this requires
+    /// traversing the entire tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (the first encountered one if there are multiple).
+    pub fn verify_subtree_consistency(
+        &self,
+        version: u64,
+        key: Key,
+        depth: usize,
+    ) -> Result<ValueHash, ConsistencyError> {
+        let manifest = self.db.try_manifest()?;
+        let manifest = manifest.ok_or(ConsistencyError::MissingVersion(version))?;
+        if version >= manifest.version_count {
+            return Err(ConsistencyError::MissingVersion(version));
+        }
+
+        let root = self
+            .db
+            .try_root(version)?
+            .ok_or(ConsistencyError::MissingRoot(version))?;
+        let Root::Filled { node, .. } = root else {
+            return Ok(self.hasher.empty_tree_hash());
+        };
+
+        // Descend along the path to `key`, recording ancestors of the verified subtree.
+        let mut node = node;
+        let mut node_key = Nibbles::EMPTY.with_version(version);
+        let mut ancestors = vec![];
+        let mut is_empty_subtree = false;
+        while node_key.nibbles.nibble_count() < depth {
+            let Node::Internal(internal_node) = &node else {
+                break;
+            };
+            let nibble = Nibbles::nibble(&key, node_key.nibbles.nibble_count());
+            let Some(child_ref) = internal_node.child_ref(nibble) else {
+                is_empty_subtree = true;
+                break;
+            };
+            let child_key = node_key
+                .nibbles
+                .push(nibble)
+                .ok_or(ConsistencyError::TerminalInternalNode { key: node_key })?;
+            let child_key = child_key.with_version(child_ref.version);
+            let child = self
+                .db
+                .try_tree_node(&child_key, child_ref.is_leaf)?
+                .ok_or(ConsistencyError::MissingNode {
+                    key: child_key,
+                    is_leaf: child_ref.is_leaf,
+                })?;
+            let expected_hash = child_ref.hash;
+            ancestors.push((node_key, node, nibble, expected_hash));
+            node = child;
+            node_key = child_key;
+        }
+
+        // Verify the subtree and propagate its hash to the root.
+        let mut hash = if is_empty_subtree {
+            let level = node_key.nibbles.nibble_count() * 4;
+            node.hash(&mut HasherWithStats::new(&self.hasher), level)
+        } else {
+            self.validate_node(&node, node_key, None)?
+        };
+        for (parent_key, parent, nibble, expected) in ancestors.into_iter().rev() {
+            if expected != hash {
+                return Err(ConsistencyError::HashMismatch {
+                    key: parent_key,
+                    nibble,
+                    expected,
+                    actual: hash,
+                });
+            }
+            let level = parent_key.nibbles.nibble_count() * 4;
+            hash = parent.hash(&mut HasherWithStats::new(&self.hasher), level);
+        }
+        Ok(hash)
+    }
+
     fn validate_node(
         &self,
         node: &Node,
@@ -400,6 +489,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn subtree_consistency_checks() {
+        let db = prepare_database();
+        let tree = MerkleTree::new(db);
+        let expected_root_hash = tree.root_hash(0).unwrap();
+        for key in [FIRST_KEY, SECOND_KEY, Key::zero()] {
+            for depth in [0, 1, 2, 8, 64] {
+                let root_hash = tree.verify_subtree_consistency(0, key, depth).unwrap();
+                assert_eq!(root_hash, expected_root_hash, "key={key:?}, depth={depth}");
+            }
+        }
+
+        let err = tree
+            .verify_subtree_consistency(1, FIRST_KEY, 2)
+            .unwrap_err();
+        assert_matches!(err, ConsistencyError::MissingVersion(1));
+    }
+
+    #[test]
+    fn hash_mismatch_error_in_subtree() {
+        let mut db = prepare_database();
+
+        let root = db.root_mut(0).unwrap();
+        let Root::Filled {
+            node: Node::Internal(node),
+            ..
+        } = root
+        else {
+            panic!("unexpected root: {root:?}");
+        };
+        let child_ref = node.child_ref_mut(0xd).unwrap();
+        child_ref.hash = ValueHash::zero();
+
+        let tree = MerkleTree::new(db);
+        let err = tree
+            .verify_subtree_consistency(0, FIRST_KEY, 2)
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ConsistencyError::HashMismatch {
+                key,
+                nibble: 0xd,
+                expected,
+                ..
+            } if key == NodeKey::empty(0) && expected == ValueHash::zero()
+        );
+        // The corrupted subtree is not checked if it's not on the path to the key.
+        tree.verify_subtree_consistency(0, Key::zero(), 2).unwrap();
+    }
+
     #[test]
     fn full_key_mismatch_error() {
         let mut db = prepare_database();
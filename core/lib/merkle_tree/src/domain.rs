@@ -2,6 +2,7 @@
 
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
+use zksync_storage::CompactionStats;
 use zksync_types::{
     proofs::{PrepareBasicCircuitsJob, StorageLogMetadata},
     writes::{InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord},
@@ -15,7 +16,7 @@ use crate::{
         Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
         TREE_DEPTH,
     },
-    BlockOutput, HashTree, MerkleTree, NoVersionError,
+    BlockOutput, ConsistencyError, Database, HashTree, MerkleTree, NoVersionError, PruneDatabase,
 };
 
 /// Metadata for the current tree state.
@@ -458,6 +459,59 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Returns the minimum L1 batch number retained by the tree. Tree versions for earlier L1 batches
+    /// may be pruned, so their roots and subtrees cannot be accessed reliably.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn min_l1_batch_number(&self) -> L1BatchNumber {
+        // Stale keys are pruned together with the nodes they point to. Nodes reachable from version
+        // `min_stale_key_version - 1` or newer only become stale in the retained stale key versions,
+        // so these tree versions are intact. If there are no stale keys (e.g., all of them are pruned),
+        // we conservatively treat only the latest version as retained.
+        let latest_version = self.0.latest_version().unwrap_or(0);
+        let version = self
+            .0
+            .db
+            .min_stale_key_version()
+            .map_or(latest_version, |version| version.saturating_sub(1));
+        let version = version.min(latest_version);
+        let number = u32::try_from(version).expect("integer overflow for L1 batch number");
+        L1BatchNumber(number)
+    }
+
+    /// Returns the root hash and the number of leaves in the tree after the specified L1 batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version for the L1 batch is missing.
+    pub fn root_info(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(ValueHash, u64), NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        let no_version_err = || NoVersionError {
+            missing_version: version,
+            version_count: self.0.db.manifest().unwrap_or_default().version_count,
+        };
+        let root = self.0.root(version).ok_or_else(no_version_err)?;
+        let root_hash = self.0.root_hash(version).ok_or_else(no_version_err)?;
+        Ok((root_hash, root.leaf_count()))
+    }
+
+    /// Reads entries with the specified keys from the tree after the specified L1 batch. The entries are returned
+    /// in the same order as requested.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version for the L1 batch is missing.
+    pub fn entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries(version, keys)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -472,4 +526,26 @@ impl ZkSyncTreeReader {
         let version = u64::from(l1_batch_number.0);
         self.0.entries_with_proofs(version, keys)
     }
+
+    /// Verifies consistency of the subtree containing `key` with the root at `depth` nibbles, and recomputes
+    /// the tree root hash after the specified L1 batch based on the verified subtree.
+    /// See [`MerkleTree::verify_subtree_consistency()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an inconsistency is detected, or if the tree version for the L1 batch is missing.
+    pub fn verify_subtree_consistency(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        key: Key,
+        depth: usize,
+    ) -> Result<ValueHash, ConsistencyError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.verify_subtree_consistency(version, key, depth)
+    }
+
+    /// Compacts the underlying RocksDB instance. See [`RocksDBWrapper::compact()`] for details.
+    pub fn compact_db(&self) -> Vec<CompactionStats> {
+        self.0.db.compact()
+    }
 }
@@ -1,6 +1,13 @@
 //! Tree pruning logic.
 
-use std::{fmt, sync::mpsc, time::Duration};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
 
 use crate::{
     metrics::{PruningStats, PRUNING_TIMINGS},
@@ -14,9 +21,21 @@ use crate::{
 #[derive(Debug)]
 pub struct MerkleTreePrunerHandle {
     aborted_sender: mpsc::Sender<()>,
+    retained_version_limit: Arc<AtomicU64>,
 }
 
 impl MerkleTreePrunerHandle {
+    /// Limits tree versions that can be pruned: the pruner will retain all versions starting from `version`,
+    /// even if they are older than allowed by the pruner policy. This allows coordinating pruning with
+    /// external data (e.g., only pruning tree versions for blocks already pruned elsewhere).
+    ///
+    /// By default, there is no limit. The limit can be both raised and lowered; lowering it doesn't restore
+    /// already pruned versions.
+    pub fn set_retained_version_limit(&self, version: u64) {
+        self.retained_version_limit
+            .store(version, Ordering::Relaxed);
+    }
+
     /// Aborts the pruner that this handle is attached to. If the pruner has already terminated
     /// (e.g., due to a panic), this is a no-op.
     pub fn abort(self) {
@@ -36,10 +55,12 @@ impl MerkleTreePrunerHandle {
 /// by a certain range of tree versions, and removes the corresponding nodes from the tree
 /// (in RocksDB, this uses simple pointwise `delete_cf()` operations). The range of versions
 /// depends on pruning policies; for now, it's "remove versions older than `latest_version - N`",
-/// where `N` is a configurable number set when the pruner [is created](Self::new()).
+/// where `N` is a configurable number set when the pruner [is created](Self::new()), additionally
+/// limited by [`MerkleTreePrunerHandle::set_retained_version_limit()`].
 pub struct MerkleTreePruner<DB> {
     db: DB,
     past_versions_to_keep: u64,
+    retained_version_limit: Arc<AtomicU64>,
     target_pruned_key_count: usize,
     poll_interval: Duration,
     aborted_receiver: mpsc::Receiver<()>,
@@ -50,6 +71,10 @@ impl<DB> fmt::Debug for MerkleTreePruner<DB> {
         formatter
             .debug_struct("MerkleTreePruner")
             .field("past_versions_to_keep", &self.past_versions_to_keep)
+            .field(
+                "retained_version_limit",
+                &self.retained_version_limit.load(Ordering::Relaxed),
+            )
             .field("target_pruned_key_count", &self.target_pruned_key_count)
             .field("poll_interval", &self.poll_interval)
             .finish_non_exhaustive()
@@ -66,10 +91,15 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
     /// is dropped.*
     pub fn new(db: DB, past_versions_to_keep: u64) -> (Self, MerkleTreePrunerHandle) {
         let (aborted_sender, aborted_receiver) = mpsc::channel();
-        let handle = MerkleTreePrunerHandle { aborted_sender };
+        let retained_version_limit = Arc::new(AtomicU64::new(u64::MAX));
+        let handle = MerkleTreePrunerHandle {
+            aborted_sender,
+            retained_version_limit: retained_version_limit.clone(),
+        };
         let this = Self {
             db,
             past_versions_to_keep,
+            retained_version_limit,
             target_pruned_key_count: 500_000,
             poll_interval: Duration::from_secs(60),
             aborted_receiver,
@@ -97,7 +127,8 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
     fn target_retained_version(&self) -> Option<u64> {
         let manifest = self.db.manifest()?;
         let latest_version = manifest.version_count.checked_sub(1)?;
-        latest_version.checked_sub(self.past_versions_to_keep)
+        let target_version = latest_version.checked_sub(self.past_versions_to_keep)?;
+        Some(target_version.min(self.retained_version_limit.load(Ordering::Relaxed)))
     }
 
     #[doc(hidden)] // Used in integration tests; logically private
@@ -233,6 +264,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pruner_respects_retained_version_limit() {
+        let mut db = create_db();
+        let (mut pruner, handle) = MerkleTreePruner::new(&mut db, 0);
+        handle.set_retained_version_limit(2);
+
+        let stats = pruner.run_once().unwrap();
+        assert_eq!(stats.deleted_stale_key_versions, 1..3);
+        assert_eq!(stats.target_retained_version, 2);
+        assert!(pruner.run_once().is_none());
+
+        handle.set_retained_version_limit(10);
+        let stats = pruner.run_once().unwrap();
+        assert_eq!(stats.deleted_stale_key_versions, 3..5);
+        assert_eq!(stats.target_retained_version, 4);
+
+        for version in 0..4 {
+            assert!(db.root_mut(version).is_none());
+        }
+        assert!(db.root_mut(4).is_some());
+    }
+
     #[test]
     fn pruner_is_aborted_immediately_when_requested() {
         let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default(), 0);
@@ -18,6 +18,8 @@
 //!   using ordinary [`MerkleTree`] APIs.
 //! 2. Update the tree from a snapshot, which [is fed to the tree](MerkleTreeRecovery::extend())
 //!   as [`RecoveryEntry`] chunks. Recovery entries must be ordered by increasing key.
+//!   Alternatively, unordered chunks can be fed using [`MerkleTreeRecovery::extend_random()`],
+//!   or [`MerkleTreeRecovery::extend_parallel()`] which parallelizes processing of each chunk.
 //! 3. Finalize recovery using [`MerkleTreeRecovery::finalize()`]. To check integrity, you may compare
 //!   [`MerkleTreeRecovery::root_hash()`] to the reference value.
 //!
@@ -183,6 +185,33 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
         tracing::debug!("Finished persisting to DB; took {:?}", started_at.elapsed());
     }
 
+    /// Extends a tree with a chunk of entries, parallelizing the extension using `rayon`. As with
+    /// [`Self::extend_random()`], entries may be ordered in any way you like.
+    ///
+    /// Entries are split into groups by the first nibble of the key; each group corresponds to a disjoint subtree,
+    /// so groups are inserted into the tree on separate threads, and then the produced subtrees are merged.
+    /// This is most efficient for large chunks of entries with uniformly distributed keys.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            recovered_version = self.recovered_version,
+            entries.len = entries.len(),
+        ),
+    )]
+    pub fn extend_parallel(&mut self, entries: Vec<TreeEntry>) {
+        tracing::debug!("Started extending tree");
+
+        let started_at = Instant::now();
+        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+        let patch = storage.extend_during_parallel_recovery(entries);
+        tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
+
+        let started_at = Instant::now();
+        self.db.apply_patch(patch);
+        tracing::debug!("Finished persisting to DB; took {:?}", started_at.elapsed());
+    }
+
     /// Finalizes the recovery process marking it as complete in the tree manifest.
     #[tracing::instrument(
         level = "debug",
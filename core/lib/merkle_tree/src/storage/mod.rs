@@ -1,6 +1,9 @@
 //! Storage-related logic.
 
+use rayon::prelude::*;
+
 pub(crate) use self::patch::{LoadAncestorsResult, WorkingPatchSet};
+use self::proofs::SUBTREE_COUNT;
 pub use self::{
     database::{Database, NodeKeys, Patched, PruneDatabase, PrunePatchSet},
     patch::PatchSet,
@@ -364,6 +367,69 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
         patch
     }
 
+    /// Same as [`Self::extend_during_random_recovery()`], but parallelizes tree traversal. Entries are split
+    /// by the first key nibble; each group of entries only touches the corresponding subtree with the root
+    /// at level 4, so groups can be processed independently (cf. [`Self::extend_with_proofs()`]).
+    /// The resulting patch sets are disjoint except for the root node, which is merged separately.
+    pub fn extend_during_parallel_recovery(mut self, recovery_entries: Vec<TreeEntry>) -> PatchSet {
+        const EMPTY_VEC: Vec<(TreeEntry, Nibbles)> = Vec::new();
+        // ^ Need to extract this to a constant to be usable as an array initializer.
+
+        if recovery_entries.is_empty() {
+            // Converting the root node to an internal one (see below) is not valid for an empty tree.
+            return self.extend_during_random_recovery(recovery_entries);
+        }
+
+        let load_nodes_latency = BLOCK_TIMINGS.load_nodes.start();
+        let sorted_keys = SortedKeys::new(recovery_entries.iter().map(|entry| entry.key));
+        let parent_nibbles = self.updater.load_ancestors(&sorted_keys, self.db);
+        let load_nodes_latency = load_nodes_latency.observe();
+        tracing::debug!("Load stage took {load_nodes_latency:?}");
+
+        let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
+        self.leaf_count += recovery_entries.len() as u64;
+        let mut entry_parts = [EMPTY_VEC; SUBTREE_COUNT];
+        for (entry, parent_nibbles) in recovery_entries.into_iter().zip(parent_nibbles) {
+            let first_nibble = Nibbles::nibble(&entry.key, 0);
+            entry_parts[first_nibble as usize].push((entry, parent_nibbles));
+        }
+
+        let mut root = self.updater.patch_set.ensure_internal_root_node();
+        let initial_metrics = self.updater.metrics;
+        let updater_parts = self.updater.split();
+        // `into_par_iter()` below uses `rayon` to parallelize tree traversal.
+        let updater_parts: Vec<_> = updater_parts
+            .into_par_iter()
+            .zip_eq(entry_parts)
+            .map(|(mut updater, entries)| {
+                for (entry, parent_nibbles) in entries {
+                    updater.insert(entry, &parent_nibbles);
+                }
+                updater
+            })
+            .collect();
+
+        // Each part has only modified the root child ref corresponding to its subtree.
+        for (i, part) in updater_parts.iter().enumerate() {
+            let nibble = u8::try_from(i).unwrap();
+            if let Some(child_ref) = part.patch_set.child_ref(&Nibbles::EMPTY, nibble) {
+                root.insert_child_ref(nibble, *child_ref);
+            }
+        }
+        self.updater = updater_parts
+            .into_iter()
+            .reduce(TreeUpdater::merge)
+            .unwrap();
+        // ^ `unwrap()` is safe: `updater_parts` is non-empty
+        self.updater.metrics += initial_metrics;
+        self.updater.set_root_node(root.into());
+        let extend_patch_latency = extend_patch_latency.observe();
+        tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
+
+        let (_, patch) = self.finalize();
+        patch
+    }
+
     fn finalize(self) -> (ValueHash, PatchSet) {
         tracing::debug!(
             "Finished updating tree; total leaf count: {}, stats: {:?}",
@@ -171,14 +171,14 @@ impl TreeUpdater {
         (operation, merkle_path)
     }
 
-    fn split(self) -> [Self; SUBTREE_COUNT] {
+    pub(super) fn split(self) -> [Self; SUBTREE_COUNT] {
         self.patch_set.split().map(|patch_set| Self {
             metrics: TreeUpdaterStats::default(),
             patch_set,
         })
     }
 
-    fn merge(mut self, other: Self) -> Self {
+    pub(super) fn merge(mut self, other: Self) -> Self {
         self.patch_set.merge(other.patch_set);
         self.metrics += other.metrics;
         self
@@ -3,7 +3,7 @@
 use std::path::Path;
 
 use rayon::prelude::*;
-use zksync_storage::{db::NamedColumnFamily, rocksdb::DBPinnableSlice, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, rocksdb::DBPinnableSlice, CompactionStats, RocksDB};
 
 use crate::{
     errors::{DeserializeError, ErrorContext},
@@ -129,6 +129,13 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Compacts all column families of the underlying RocksDB instance, blocking the current thread
+    /// until compaction is complete. Compaction doesn't change the logical contents of the database,
+    /// so it can be performed concurrently with tree updates.
+    pub fn compact(&self) -> Vec<CompactionStats> {
+        self.db.compact()
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
@@ -6,7 +6,9 @@ use serde::{Deserialize, Serialize};
 use serde_with::{hex::Hex, serde_as};
 use tempfile::TempDir;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
-use zksync_merkle_tree::{domain::ZkSyncTree, HashTree, TreeEntry, TreeInstruction};
+use zksync_merkle_tree::{
+    domain::ZkSyncTree, HashTree, MerkleTreePruner, RocksDBWrapper, TreeEntry, TreeInstruction,
+};
 use zksync_storage::RocksDB;
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
@@ -111,6 +113,54 @@ fn basic_workflow_multiblock() {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(12));
 }
 
+#[test]
+fn reading_root_info_for_past_l1_batches() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let db = RocksDB::new(temp_dir.as_ref());
+    let mut tree = ZkSyncTree::new_lightweight(db.into());
+    let logs = gen_storage_logs();
+    let metadata: Vec<_> = logs
+        .chunks(9)
+        .map(|block| tree.process_l1_batch(block))
+        .collect();
+    tree.save();
+
+    let reader = tree.reader();
+    for (l1_batch_number, metadata) in (0..).map(L1BatchNumber).zip(&metadata) {
+        let (root_hash, leaf_count) = reader.root_info(l1_batch_number).unwrap();
+        assert_eq!(root_hash, metadata.root_hash);
+        assert_eq!(leaf_count + 1, metadata.rollup_last_leaf_index);
+    }
+
+    let missing_l1_batch_number = L1BatchNumber(metadata.len() as u32);
+    reader.root_info(missing_l1_batch_number).unwrap_err();
+}
+
+#[test]
+fn min_l1_batch_number_with_pruning() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let db = RocksDBWrapper::from(RocksDB::new(temp_dir.as_ref()));
+    let mut tree = ZkSyncTree::new_lightweight(db.clone());
+    assert_eq!(tree.reader().min_l1_batch_number(), L1BatchNumber(0));
+
+    let logs = gen_storage_logs();
+    for block in logs.chunks(9) {
+        tree.process_l1_batch(block);
+    }
+    tree.save();
+    let reader = tree.reader();
+    assert_eq!(reader.min_l1_batch_number(), L1BatchNumber(0));
+
+    let (mut pruner, _handle) = MerkleTreePruner::new(db, 5);
+    pruner.run_once().expect("no pruning performed");
+    // The tree has 12 versions, the last 6 of which should be retained.
+    let min_l1_batch_number = reader.min_l1_batch_number();
+    assert_eq!(min_l1_batch_number, L1BatchNumber(6));
+    for l1_batch_number in min_l1_batch_number.0..12 {
+        tree.verify_consistency(L1BatchNumber(l1_batch_number));
+    }
+}
+
 #[test]
 fn filtering_out_no_op_writes() {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
@@ -13,10 +13,11 @@ use crate::common::{convert_to_writes, generate_key_value_pairs, TreeMap, ENTRIE
 enum RecoveryKind {
     Linear,
     Random,
+    Parallel,
 }
 
 impl RecoveryKind {
-    const ALL: [Self; 2] = [Self::Linear, Self::Random];
+    const ALL: [Self; 3] = [Self::Linear, Self::Random, Self::Parallel];
 }
 
 #[test]
@@ -55,6 +56,7 @@ fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk
         match kind {
             RecoveryKind::Linear => recovery.extend_linear(chunk.to_vec()),
             RecoveryKind::Random => recovery.extend_random(chunk.to_vec()),
+            RecoveryKind::Parallel => recovery.extend_parallel(chunk.to_vec()),
         }
         if i % 3 == 1 {
             recovery = MerkleTreeRecovery::new(&mut db, recovered_version);
@@ -117,7 +119,7 @@ fn test_tree_after_recovery<DB: Database>(
     }
 }
 
-#[test_casing(8, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
+#[test_casing(12, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
 fn recovery_in_chunks(kind: RecoveryKind, chunk_size: usize) {
     test_recovery_in_chunks(PatchSet::default(), kind, chunk_size);
 }
@@ -128,7 +130,7 @@ mod rocksdb {
 
     use super::*;
 
-    #[test_casing(8, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
+    #[test_casing(12, test_casing::Product((RecoveryKind::ALL, [6, 10, 17, 42])))]
     fn recovery_in_chunks(kind: RecoveryKind, chunk_size: usize) {
         let temp_dir = TempDir::new().unwrap();
         let db = RocksDBWrapper::new(temp_dir.path());
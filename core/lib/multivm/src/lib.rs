@@ -16,11 +16,13 @@ pub use crate::{
         history_mode::HistoryMode,
         tracers::{MultiVMTracer, MultiVmTracerPointer},
     },
+    shadow::ShadowVm,
     vm_instance::VmInstance,
 };
 
 mod glue;
 pub mod interface;
+mod shadow;
 pub mod tracers;
 pub mod versions;
 mod vm_instance;
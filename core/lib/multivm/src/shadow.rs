@@ -0,0 +1,364 @@
+//! Shadow VM execution. A [`ShadowVm`] executes everything through the main VM and additionally
+//! through a candidate VM version, reporting any divergences between the two. This allows to check
+//! a new VM version on real load before activating it.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use vise::{Counter, Metrics};
+use zksync_state::{ReadStorage, StoragePtr, StorageView, WriteStorage};
+use zksync_types::{L1BatchNumber, StorageKey, StorageValue, Transaction, VmVersion, H256};
+use zksync_utils::bytecode::CompressedBytecodeInfo;
+
+use crate::{
+    glue::history_mode::HistoryMode,
+    interface::{
+        BootloaderMemory, BytecodeCompressionError, CurrentExecutionState, FinishedL1Batch,
+        L1BatchEnv, L2BlockEnv, SystemEnv, VmExecutionMode, VmExecutionResultAndLogs, VmInterface,
+        VmInterfaceHistoryEnabled, VmMemoryMetrics,
+    },
+    tracers::TracerDispatcher,
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "vm_shadow")]
+struct ShadowVmMetrics {
+    /// Number of detected divergences between the main and the shadow VM.
+    divergences: Counter,
+    /// Number of panics in the shadow VM.
+    panics: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ShadowVmMetrics> = vise::Global::new();
+
+/// Read-only access to the main VM storage used by the shadow VM. The shadow VM keeps its own
+/// writes in a separate [`StorageView`], so it never modifies the main storage.
+///
+/// Since the shadow VM reads values that can be modified by the main VM, the shadow VM
+/// always executes each operation *before* the main VM.
+#[derive(Debug)]
+struct MainStorageReads<S>(StoragePtr<S>);
+
+impl<S: ReadStorage> ReadStorage for MainStorageReads<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.0.borrow_mut().read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.0.borrow_mut().is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.0.borrow_mut().load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.0.borrow_mut().get_enumeration_index(key)
+    }
+}
+
+type ShadowInstance<S, H> = VmInstance<StorageView<MainStorageReads<S>>, H>;
+
+#[derive(Debug, Default)]
+struct DivergenceErrors(Vec<String>);
+
+impl DivergenceErrors {
+    fn check_match<T: fmt::Debug + PartialEq>(&mut self, context: &str, main: &T, shadow: &T) {
+        if main != shadow {
+            self.0
+                .push(format!("{context}: main = {main:?}, shadow = {shadow:?}"));
+        }
+    }
+
+    /// Compares vectors reporting only the first mismatch, since vectors can be large.
+    fn check_vec_match<T: fmt::Debug + PartialEq>(
+        &mut self,
+        context: &str,
+        main: &[T],
+        shadow: &[T],
+    ) {
+        if main.len() != shadow.len() {
+            self.0.push(format!(
+                "{context}: length mismatch, main = {}, shadow = {}",
+                main.len(),
+                shadow.len()
+            ));
+        } else if let Some((i, (main_item, shadow_item))) = main
+            .iter()
+            .zip(shadow)
+            .enumerate()
+            .find(|(_, (main_item, shadow_item))| main_item != shadow_item)
+        {
+            self.0.push(format!(
+                "{context}[{i}]: main = {main_item:?}, shadow = {shadow_item:?}"
+            ));
+        }
+    }
+
+    fn check_results(
+        &mut self,
+        main: &VmExecutionResultAndLogs,
+        shadow: &VmExecutionResultAndLogs,
+    ) {
+        self.check_match("result", &main.result, &shadow.result);
+        self.check_vec_match("logs.events", &main.logs.events, &shadow.logs.events);
+        self.check_vec_match(
+            "logs.storage_logs",
+            &main.logs.storage_logs,
+            &shadow.logs.storage_logs,
+        );
+        self.check_vec_match(
+            "logs.user_l2_to_l1_logs",
+            &main.logs.user_l2_to_l1_logs,
+            &shadow.logs.user_l2_to_l1_logs,
+        );
+        self.check_vec_match(
+            "logs.system_l2_to_l1_logs",
+            &main.logs.system_l2_to_l1_logs,
+            &shadow.logs.system_l2_to_l1_logs,
+        );
+        self.check_match(
+            "statistics.gas_used",
+            &main.statistics.gas_used,
+            &shadow.statistics.gas_used,
+        );
+        self.check_match(
+            "refunds.gas_refunded",
+            &main.refunds.gas_refunded,
+            &shadow.refunds.gas_refunded,
+        );
+    }
+
+    fn check_final_states(&mut self, main: &CurrentExecutionState, shadow: &CurrentExecutionState) {
+        self.check_vec_match("final_state.events", &main.events, &shadow.events);
+        self.check_vec_match(
+            "final_state.storage_log_queries",
+            &main.storage_log_queries,
+            &shadow.storage_log_queries,
+        );
+        self.check_vec_match(
+            "final_state.system_logs",
+            &main.system_logs,
+            &shadow.system_logs,
+        );
+        self.check_vec_match(
+            "final_state.user_l2_to_l1_logs",
+            &main.user_l2_to_l1_logs,
+            &shadow.user_l2_to_l1_logs,
+        );
+        self.check_vec_match(
+            "final_state.storage_refunds",
+            &main.storage_refunds,
+            &shadow.storage_refunds,
+        );
+    }
+}
+
+/// VM executing everything through the main VM and a shadow VM of a candidate version.
+///
+/// Only outputs of the main VM are returned; the shadow VM cannot affect them. Divergences between
+/// the VMs are logged and reported via metrics. After a divergence or a panic, the shadow VM state
+/// is no longer meaningful, so shadow execution is disabled for the rest of the batch.
+#[derive(Debug)]
+pub struct ShadowVm<S: WriteStorage, H: HistoryMode> {
+    main: VmInstance<S, H>,
+    shadow: Option<ShadowInstance<S, H>>,
+    shadow_version: VmVersion,
+    l1_batch_number: L1BatchNumber,
+    last_tx_hash: Option<H256>,
+}
+
+impl<S: WriteStorage, H: HistoryMode> ShadowVm<S, H> {
+    /// Creates a VM with the main VM of the version corresponding to the protocol version in `system_env`,
+    /// and the shadow VM of the specified version.
+    pub fn with_shadow_version(
+        batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage: StoragePtr<S>,
+        shadow_version: VmVersion,
+    ) -> Self {
+        let l1_batch_number = batch_env.number;
+        let shadow_storage = StorageView::new(MainStorageReads(storage.clone())).to_rc_ptr();
+        let shadow = VmInstance::new_with_specific_version(
+            batch_env.clone(),
+            system_env.clone(),
+            shadow_storage,
+            shadow_version,
+        );
+        let main = VmInstance::new(batch_env, system_env, storage);
+        Self {
+            main,
+            shadow: Some(shadow),
+            shadow_version,
+            l1_batch_number,
+            last_tx_hash: None,
+        }
+    }
+
+    /// Runs the specified action on the shadow VM (if it is still enabled), catching panics.
+    fn run_shadow<T>(
+        &mut self,
+        action: &str,
+        f: impl FnOnce(&mut ShadowInstance<S, H>) -> T,
+    ) -> Option<T> {
+        let shadow = self.shadow.as_mut()?;
+        match panic::catch_unwind(AssertUnwindSafe(|| f(shadow))) {
+            Ok(output) => Some(output),
+            Err(_) => {
+                METRICS.panics.inc();
+                tracing::error!(
+                    "Shadow VM {:?} panicked on `{action}` in L1 batch #{} (last transaction: {:?}); \
+                     disabling it for the rest of the batch",
+                    self.shadow_version,
+                    self.l1_batch_number,
+                    self.last_tx_hash
+                );
+                self.shadow = None;
+                None
+            }
+        }
+    }
+
+    fn report_divergence(&mut self, action: &str, errors: DivergenceErrors) {
+        if errors.0.is_empty() {
+            return;
+        }
+        METRICS.divergences.inc();
+        tracing::error!(
+            "Shadow VM {:?} diverged from the main VM on `{action}` in L1 batch #{} (last transaction: {:?}): {}; \
+             disabling it for the rest of the batch",
+            self.shadow_version,
+            self.l1_batch_number,
+            self.last_tx_hash,
+            errors.0.join("; ")
+        );
+        self.shadow = None;
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmInterface<S, H> for ShadowVm<S, H> {
+    type TracerDispatcher = TracerDispatcher<S, H>;
+
+    /// Creates a VM shadowed by the latest VM version.
+    fn new(batch_env: L1BatchEnv, system_env: SystemEnv, storage: StoragePtr<S>) -> Self {
+        Self::with_shadow_version(batch_env, system_env, storage, VmVersion::latest())
+    }
+
+    fn push_transaction(&mut self, tx: Transaction) {
+        self.last_tx_hash = Some(tx.hash());
+        self.run_shadow("push_transaction", |vm| vm.push_transaction(tx.clone()));
+        self.main.push_transaction(tx);
+    }
+
+    fn inspect(
+        &mut self,
+        dispatcher: Self::TracerDispatcher,
+        execution_mode: VmExecutionMode,
+    ) -> VmExecutionResultAndLogs {
+        let shadow_result = self.run_shadow("execute", |vm| vm.execute(execution_mode));
+        let main_result = self.main.inspect(dispatcher, execution_mode);
+        if let Some(shadow_result) = shadow_result {
+            let mut errors = DivergenceErrors::default();
+            errors.check_results(&main_result, &shadow_result);
+            self.report_divergence("execute", errors);
+        }
+        main_result
+    }
+
+    fn get_bootloader_memory(&self) -> BootloaderMemory {
+        self.main.get_bootloader_memory()
+    }
+
+    fn get_last_tx_compressed_bytecodes(&self) -> Vec<CompressedBytecodeInfo> {
+        self.main.get_last_tx_compressed_bytecodes()
+    }
+
+    fn start_new_l2_block(&mut self, l2_block_env: L2BlockEnv) {
+        self.run_shadow("start_new_l2_block", |vm| {
+            vm.start_new_l2_block(l2_block_env);
+        });
+        self.main.start_new_l2_block(l2_block_env);
+    }
+
+    fn get_current_execution_state(&self) -> CurrentExecutionState {
+        self.main.get_current_execution_state()
+    }
+
+    fn inspect_transaction_with_bytecode_compression(
+        &mut self,
+        tracer: Self::TracerDispatcher,
+        tx: Transaction,
+        with_compression: bool,
+    ) -> Result<VmExecutionResultAndLogs, BytecodeCompressionError> {
+        self.last_tx_hash = Some(tx.hash());
+        let shadow_result = self.run_shadow("execute_transaction", |vm| {
+            vm.execute_transaction_with_bytecode_compression(tx.clone(), with_compression)
+        });
+        let main_result =
+            self.main
+                .inspect_transaction_with_bytecode_compression(tracer, tx, with_compression);
+
+        if let Some(shadow_result) = shadow_result {
+            let mut errors = DivergenceErrors::default();
+            match (&main_result, &shadow_result) {
+                (Ok(main), Ok(shadow)) => errors.check_results(main, shadow),
+                (Err(_), Err(_)) => { /* Both VMs failed to compress bytecodes */ }
+                _ => errors.check_match(
+                    "bytecode_compression_failed",
+                    &main_result.is_err(),
+                    &shadow_result.is_err(),
+                ),
+            }
+            self.report_divergence("execute_transaction", errors);
+        }
+        main_result
+    }
+
+    fn record_vm_memory_metrics(&self) -> VmMemoryMetrics {
+        self.main.record_vm_memory_metrics()
+    }
+
+    fn finish_batch(&mut self) -> FinishedL1Batch {
+        let shadow_batch = self.run_shadow("finish_batch", |vm| vm.finish_batch());
+        let main_batch = self.main.finish_batch();
+        if let Some(shadow_batch) = shadow_batch {
+            let mut errors = DivergenceErrors::default();
+            errors.check_results(
+                &main_batch.block_tip_execution_result,
+                &shadow_batch.block_tip_execution_result,
+            );
+            errors.check_final_states(
+                &main_batch.final_execution_state,
+                &shadow_batch.final_execution_state,
+            );
+            self.report_divergence("finish_batch", errors);
+        }
+        main_batch
+    }
+}
+
+impl<S: WriteStorage> VmInterfaceHistoryEnabled<S> for ShadowVm<S, HistoryEnabled> {
+    fn make_snapshot(&mut self) {
+        self.run_shadow("make_snapshot", |vm| vm.make_snapshot());
+        self.main.make_snapshot();
+    }
+
+    fn rollback_to_the_latest_snapshot(&mut self) {
+        self.run_shadow("rollback_to_the_latest_snapshot", |vm| {
+            vm.rollback_to_the_latest_snapshot();
+        });
+        self.main.rollback_to_the_latest_snapshot();
+    }
+
+    fn pop_snapshot_no_rollback(&mut self) {
+        self.run_shadow("pop_snapshot_no_rollback", |vm| {
+            vm.pop_snapshot_no_rollback();
+        });
+        self.main.pop_snapshot_no_rollback();
+    }
+}
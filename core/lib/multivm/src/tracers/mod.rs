@@ -1,8 +1,10 @@
 pub mod call_tracer;
 mod multivm_dispatcher;
 pub mod storage_invocation;
+pub mod storage_watchpoint;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
 pub use multivm_dispatcher::TracerDispatcher;
 pub use storage_invocation::StorageInvocations;
+pub use storage_watchpoint::StorageWatchpointTracer;
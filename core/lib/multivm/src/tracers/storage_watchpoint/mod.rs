@@ -0,0 +1,64 @@
+use std::{collections::HashSet, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    vm_trace::{StorageAccess, StorageAccessFrame, StorageAccessKind},
+    StorageKey, H256,
+};
+
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer recording all reads and writes of the watched storage slots, together with the far call stack
+/// active during each access.
+#[derive(Debug, Clone)]
+pub struct StorageWatchpointTracer {
+    watchpoints: HashSet<StorageKey>,
+    accesses: Vec<StorageAccess>,
+    result: Arc<OnceCell<Vec<StorageAccess>>>,
+}
+
+impl StorageWatchpointTracer {
+    pub fn new(
+        watchpoints: HashSet<StorageKey>,
+        result: Arc<OnceCell<Vec<StorageAccess>>>,
+    ) -> Self {
+        Self {
+            watchpoints,
+            accesses: vec![],
+            result,
+        }
+    }
+
+    fn is_watched(&self, key: &StorageKey) -> bool {
+        self.watchpoints.contains(key)
+    }
+
+    fn record_access(
+        &mut self,
+        key: &StorageKey,
+        value: H256,
+        written_value: Option<H256>,
+        call_stack: Vec<StorageAccessFrame>,
+    ) {
+        let kind = if written_value.is_some() {
+            StorageAccessKind::Write
+        } else {
+            StorageAccessKind::Read
+        };
+        self.accesses.push(StorageAccess {
+            kind,
+            address: *key.address(),
+            key: *key.key(),
+            value,
+            written_value,
+            call_stack,
+        });
+    }
+
+    fn store_result(&mut self) {
+        let accesses = std::mem::take(&mut self.accesses);
+        self.result.set(accesses).unwrap();
+    }
+}
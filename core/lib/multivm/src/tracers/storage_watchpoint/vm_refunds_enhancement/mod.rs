@@ -0,0 +1,67 @@
+use zk_evm_1_3_3::{
+    tracing::{BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{LogOpcode, Opcode},
+};
+use zksync_state::{StoragePtr, WriteStorage};
+use zksync_system_constants::BOOTLOADER_ADDRESS;
+use zksync_types::{vm_trace::StorageAccessFrame, AccountTreeId, StorageKey};
+use zksync_utils::u256_to_h256;
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_3_3::DynTracer},
+    tracers::storage_watchpoint::StorageWatchpointTracer,
+    vm_refunds_enhancement::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for StorageWatchpointTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        storage: StoragePtr<S>,
+    ) {
+        let is_write = match data.opcode.variant.opcode {
+            Opcode::Log(LogOpcode::StorageRead) => false,
+            Opcode::Log(LogOpcode::StorageWrite) => true,
+            _ => return,
+        };
+        let callstack = &state.vm_local_state.callstack;
+        let key = StorageKey::new(
+            AccountTreeId::new(callstack.current.this_address),
+            u256_to_h256(data.src0_value.value),
+        );
+        if !self.is_watched(&key) {
+            return;
+        }
+
+        // The storage oracle writes values through to the storage, so it contains the current slot value.
+        let value = storage.borrow_mut().read_value(&key);
+        let written_value = is_write.then(|| u256_to_h256(data.src1_value.value));
+        // The VM state contains a formal frame preceding the bootloader one, which is skipped.
+        let call_stack = callstack
+            .inner
+            .iter()
+            .chain([&callstack.current])
+            .skip_while(|frame| frame.this_address != BOOTLOADER_ADDRESS)
+            .filter(|frame| !frame.is_local_frame)
+            .map(|frame| StorageAccessFrame {
+                from: frame.msg_sender,
+                to: frame.this_address,
+                code_address: frame.code_address,
+            })
+            .collect();
+        self.record_access(&key, value, written_value, call_stack);
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for StorageWatchpointTracer {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result()
+    }
+}
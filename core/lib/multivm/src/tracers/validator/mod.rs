@@ -7,8 +7,9 @@ use zksync_system_constants::{
     L2_ETH_TOKEN_ADDRESS, MSG_VALUE_SIMULATOR_ADDRESS, SYSTEM_CONTEXT_ADDRESS,
 };
 use zksync_types::{
-    vm_trace::ViolatedValidationRule, web3::signing::keccak256, AccountTreeId, Address, StorageKey,
-    H256, U256,
+    vm_trace::{ValidationViolationReport, ViolatedValidationRule},
+    web3::signing::keccak256,
+    AccountTreeId, Address, StorageKey, H256, U256,
 };
 use zksync_utils::{be_bytes_to_safe_address, u256_to_account_address, u256_to_h256};
 
@@ -36,14 +37,14 @@ pub struct ValidationTracer<H> {
     trusted_address_slots: HashSet<(Address, U256)>,
     computational_gas_used: u32,
     computational_gas_limit: u32,
-    pub result: Arc<OnceCell<ViolatedValidationRule>>,
+    pub result: Arc<OnceCell<ValidationViolationReport>>,
     _marker: PhantomData<fn(H) -> H>,
 }
 
 type ValidationRoundResult = Result<NewTrustedValidationItems, ViolatedValidationRule>;
 
 impl<H> ValidationTracer<H> {
-    pub fn new(params: ValidationTracerParams) -> (Self, Arc<OnceCell<ViolatedValidationRule>>) {
+    pub fn new(params: ValidationTracerParams) -> (Self, Arc<OnceCell<ValidationViolationReport>>) {
         let result = Arc::new(OnceCell::new());
         (
             Self {
@@ -65,7 +66,10 @@ impl<H> ValidationTracer<H> {
         )
     }
 
-    fn process_validation_round_result(&mut self, result: ValidationRoundResult) {
+    fn process_validation_round_result(
+        &mut self,
+        result: Result<NewTrustedValidationItems, ValidationViolationReport>,
+    ) {
         match result {
             Ok(NewTrustedValidationItems {
                 new_allowed_slots,
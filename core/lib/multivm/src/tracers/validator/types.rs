@@ -1,6 +1,6 @@
 use std::{collections::HashSet, fmt::Display};
 
-use zksync_types::{vm_trace::ViolatedValidationRule, Address, H256, U256};
+use zksync_types::{vm_trace::ValidationViolationReport, Address, H256, U256};
 
 use crate::interface::Halt;
 
@@ -40,7 +40,7 @@ pub struct ValidationTracerParams {
 #[derive(Debug, Clone)]
 pub enum ValidationError {
     FailedTx(Halt),
-    ViolatedRule(ViolatedValidationRule),
+    ViolatedRule(ValidationViolationReport),
 }
 
 impl Display for ValidationError {
@@ -49,8 +49,8 @@ impl Display for ValidationError {
             Self::FailedTx(revert_reason) => {
                 write!(f, "Validation revert: {}", revert_reason)
             }
-            Self::ViolatedRule(rule) => {
-                write!(f, "Violated validation rules: {}", rule)
+            Self::ViolatedRule(report) => {
+                write!(f, "Violated validation rules: {}", report)
             }
         }
     }
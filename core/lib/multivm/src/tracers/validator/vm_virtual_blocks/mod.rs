@@ -5,7 +5,9 @@ use zk_evm_1_3_3::{
 use zksync_state::{StoragePtr, WriteStorage};
 use zksync_system_constants::KECCAK256_PRECOMPILE_ADDRESS;
 use zksync_types::{
-    get_code_key, vm_trace::ViolatedValidationRule, AccountTreeId, StorageKey, H256,
+    get_code_key,
+    vm_trace::{ValidationViolationReport, ViolatedValidationRule},
+    AccountTreeId, StorageKey, H256,
 };
 use zksync_utils::{h256_to_account_address, u256_to_account_address, u256_to_h256};
 
@@ -139,8 +141,14 @@ impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H::VmVirtualBloc
                 .computational_gas_used
                 .saturating_add(computational_gas_price(state, &data));
 
-            let validation_round_result =
-                self.check_user_restrictions_vm_virtual_blocks(state, data, memory, storage);
+            let validation_round_result = self
+                .check_user_restrictions_vm_virtual_blocks(state, data, memory, storage)
+                .map_err(|rule| ValidationViolationReport {
+                    rule,
+                    opcode: format!("{:?}", data.opcode.variant.opcode),
+                    contract_address: state.vm_local_state.callstack.current.this_address,
+                    call_depth: state.vm_local_state.callstack.depth(),
+                });
             self.process_validation_round_result(validation_round_result);
         }
 
@@ -14,6 +14,7 @@ mod refunds;
 mod require_eip712;
 mod rollbacks;
 mod simple_execution;
+mod storage_watchpoint;
 mod tester;
 mod tracing_execution_error;
 mod upgrade;
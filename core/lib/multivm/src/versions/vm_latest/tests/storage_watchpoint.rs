@@ -0,0 +1,78 @@
+use std::{collections::HashSet, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_system_constants::BOOTLOADER_ADDRESS;
+use zksync_types::{
+    vm_trace::StorageAccessKind, AccountTreeId, Address, Execute, StorageKey, H256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    tracers::StorageWatchpointTracer,
+    vm_latest::{
+        constants::BLOCK_GAS_LIMIT,
+        tests::{tester::VmTesterBuilder, utils::read_test_contract},
+        HistoryEnabled, ToTracerPointer,
+    },
+};
+
+#[test]
+fn test_storage_watchpoints() {
+    let contract = read_test_contract();
+    let address = Address::random();
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_random_rich_accounts(1)
+        .with_deployer()
+        .with_gas_limit(BLOCK_GAS_LIMIT)
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_custom_contracts(vec![(contract, address, true)])
+        .build();
+
+    let increment_by_6_calldata =
+        "7cf5dab00000000000000000000000000000000000000000000000000000000000000006";
+
+    let account = &mut vm.rich_accounts[0];
+    let tx = account.get_l2_tx_for_execute(
+        Execute {
+            contract_address: address,
+            calldata: hex::decode(increment_by_6_calldata).unwrap(),
+            value: Default::default(),
+            factory_deps: None,
+        },
+        None,
+    );
+
+    // `Counter.value` is stored in the slot 0.
+    let counter_key = StorageKey::new(AccountTreeId::new(address), H256::zero());
+    let unrelated_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(1));
+    let watchpoints = HashSet::from([counter_key, unrelated_key]);
+    let result = Arc::new(OnceCell::new());
+    let tracer = StorageWatchpointTracer::new(watchpoints, result.clone()).into_tracer_pointer();
+    vm.vm.push_transaction(tx);
+    let res = vm.vm.inspect(tracer.into(), VmExecutionMode::OneTx);
+    assert!(!res.result.is_failed());
+
+    let accesses = result.get().unwrap();
+    assert!(!accesses.is_empty());
+    for access in accesses {
+        assert_eq!(access.address, address);
+        assert_eq!(access.key, H256::zero());
+        assert_eq!(access.call_stack[0].to, BOOTLOADER_ADDRESS);
+        assert_eq!(access.call_stack.last().unwrap().to, address);
+    }
+
+    let read = accesses
+        .iter()
+        .find(|access| access.kind == StorageAccessKind::Read)
+        .unwrap();
+    assert_eq!(read.value, H256::zero());
+    assert_eq!(read.written_value, None);
+    let write = accesses
+        .iter()
+        .find(|access| access.kind == StorageAccessKind::Write)
+        .unwrap();
+    assert_eq!(write.value, H256::zero());
+    assert_eq!(write.written_value, Some(u256_to_h256(6.into())));
+}
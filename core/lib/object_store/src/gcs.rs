@@ -15,11 +15,12 @@ use google_cloud_storage::{
         },
         Error as HttpError,
     },
+    sign::{SignedURLMethod, SignedURLOptions},
 };
 use http::StatusCode;
 
 use crate::{
-    metrics::GCS_METRICS,
+    metrics::OBJECT_STORE_METRICS,
     raw::{Bucket, ObjectStore, ObjectStoreError},
 };
 
@@ -148,7 +149,7 @@ impl From<HttpError> for ObjectStoreError {
 #[async_trait]
 impl ObjectStore for GoogleCloudStorage {
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
-        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Fetching data from GCS for key {filename} from bucket {}",
@@ -179,7 +180,7 @@ impl ObjectStore for GoogleCloudStorage {
         key: &str,
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError> {
-        let store_latency = GCS_METRICS.start_store(bucket);
+        let store_latency = OBJECT_STORE_METRICS.start_store(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Storing data to GCS for key {filename} from bucket {}",
@@ -215,6 +216,31 @@ impl ObjectStore for GoogleCloudStorage {
             bucket.as_str()
         )
     }
+
+    async fn signed_url_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Signing GCS URL for key {filename} from bucket {}",
+            self.bucket_prefix
+        );
+
+        let options = SignedURLOptions {
+            method: SignedURLMethod::GET,
+            expires: expires_in,
+            ..SignedURLOptions::default()
+        };
+        let url = self
+            .client
+            .signed_url(&self.bucket_prefix, &filename, None, None, options)
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        Ok(Some(url))
+    }
 }
 
 #[cfg(test)]
@@ -3,6 +3,7 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - Storage compatible with the AWS S3 API (AWS S3 itself, MinIO, Cloudflare R2 etc.)
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -29,6 +30,7 @@ mod metrics;
 mod mock;
 mod objects;
 mod raw;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
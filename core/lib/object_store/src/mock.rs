@@ -1,6 +1,6 @@
 //! Mock implementation of [`ObjectStore`].
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
 use tokio::sync::Mutex;
@@ -49,4 +49,16 @@ impl ObjectStore for MockStore {
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         bucket.to_string()
     }
+
+    async fn signed_url_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        let expires_in = expires_in.as_secs();
+        Ok(Some(format!(
+            "mock://{bucket}/{key}?expires_in={expires_in}"
+        )))
+    }
 }
@@ -1,18 +1,22 @@
 //! Stored objects.
 
-use std::io::{Read, Write};
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
 
 use anyhow::Context;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
-    aggregated_operations::L1BatchProofForL1,
+    aggregated_operations::{FflonkL1BatchProofForL1, L1BatchProof, L1BatchProofForL1},
     proofs::{AggregationRound, PrepareBasicCircuitsJob},
     snapshots::{
         SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
     storage::witness_block_state::WitnessBlockState,
+    tee_types::TeeVerifierInput,
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit,
         bellman::bn256::Bn256,
@@ -142,6 +146,17 @@ impl StoredObject for WitnessBlockState {
     serialize_using_bincode!();
 }
 
+impl StoredObject for TeeVerifierInput {
+    const BUCKET: Bucket = Bucket::TeeVerifierInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("tee_verifier_input_for_l1_batch_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 impl StoredObject for PrepareBasicCircuitsJob {
     const BUCKET: Bucket = Bucket::WitnessInput;
     type Key<'a> = L1BatchNumber;
@@ -281,6 +296,17 @@ impl StoredObject for L1BatchProofForL1 {
     serialize_using_bincode!();
 }
 
+impl StoredObject for FflonkL1BatchProofForL1 {
+    const BUCKET: Bucket = Bucket::ProofsFri;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_fflonk_proof_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -311,6 +337,61 @@ impl dyn ObjectStore + '_ {
         Ok(key)
     }
 
+    /// Fetches the final proof for the specified L1 batch produced by any proof compression backend.
+    /// Returns `Ok(None)` if the proof doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof cannot be accessed or deserialized.
+    pub async fn get_l1_batch_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchProof>, ObjectStoreError> {
+        match self.get::<L1BatchProofForL1>(l1_batch_number).await {
+            Ok(proof) => return Ok(Some(L1BatchProof::Plonk(proof))),
+            // The proof may be produced by another backend.
+            Err(ObjectStoreError::KeyNotFound(_)) => {}
+            Err(err) => return Err(err),
+        }
+        match self.get::<FflonkL1BatchProofForL1>(l1_batch_number).await {
+            Ok(proof) => Ok(Some(L1BatchProof::Fflonk(proof))),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Stores the final proof for the specified L1 batch. The proof is stored under a key specific
+    /// to the compression backend that produced it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or the insertion / replacement operation fails.
+    pub async fn put_l1_batch_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        proof: &L1BatchProof,
+    ) -> Result<String, ObjectStoreError> {
+        match proof {
+            L1BatchProof::Plonk(proof) => self.put(l1_batch_number, proof).await,
+            L1BatchProof::Fflonk(proof) => self.put(l1_batch_number, proof).await,
+        }
+    }
+
+    /// Creates a URL allowing to download the object with the given key without credentials until
+    /// the URL expires. Returns `Ok(None)` if the store does not support signed URLs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be signed.
+    pub async fn signed_url<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        let key = V::encode_key(key);
+        self.signed_url_raw(V::BUCKET, &key, expires_in).await
+    }
+
     pub fn get_storage_prefix<V: StoredObject>(&self) -> String {
         self.storage_prefix_raw(V::BUCKET)
     }
@@ -1,9 +1,9 @@
-use std::{error, fmt, sync::Arc};
+use std::{error, fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
 
-use crate::{file::FileBackedObjectStore, gcs::GoogleCloudStorage, mock::MockStore};
+use crate::{file::FileBackedObjectStore, gcs::GoogleCloudStorage, mock::MockStore, s3::S3Storage};
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -20,6 +20,8 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    ArchivedL1Batches,
+    TeeVerifierInput,
 }
 
 impl Bucket {
@@ -36,6 +38,8 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::ArchivedL1Batches => "archived_l1_batches",
+            Self::TeeVerifierInput => "tee_verifier_inputs",
         }
     }
 }
@@ -117,6 +121,22 @@ pub trait ObjectStore: fmt::Debug + Send + Sync {
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError>;
 
     fn storage_prefix_raw(&self, bucket: Bucket) -> String;
+
+    /// Creates a URL allowing to download the object with the given key without credentials until
+    /// the URL expires. Returns `Ok(None)` if the store does not support signed URLs (e.g., if it is file-backed).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL cannot be signed.
+    async fn signed_url_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        let _ = (bucket, key, expires_in);
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -141,6 +161,15 @@ impl<T: ObjectStore + ?Sized> ObjectStore for Arc<T> {
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         (**self).storage_prefix_raw(bucket)
     }
+
+    async fn signed_url_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        (**self).signed_url_raw(bucket, key, expires_in).await
+    }
 }
 
 #[derive(Debug)]
@@ -218,6 +247,11 @@ impl ObjectStoreFactory {
                 let store = FileBackedObjectStore::new(config.file_backed_base_path.clone()).await;
                 Box::new(store)
             }
+            ObjectStoreMode::S3 => {
+                tracing::trace!("Initialized S3 Object store");
+                let store = S3Storage::new(config).await;
+                Box::new(store)
+            }
         }
     }
 }
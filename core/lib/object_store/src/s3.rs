@@ -0,0 +1,368 @@
+//! [`ObjectStore`] implementation for stores compatible with the AWS S3 API (AWS S3 itself, MinIO,
+//! Cloudflare R2 etc.).
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use aws_sdk_s3::{
+    config::{http::HttpResponse, retry::RetryConfig, Builder as S3ConfigBuilder, Region},
+    error::SdkError,
+    presigning::PresigningConfig,
+    primitives::{ByteStream, ByteStreamError},
+    types::{CompletedMultipartUpload, CompletedPart, ServerSideEncryption},
+    Client,
+};
+use http::StatusCode;
+use zksync_config::configs::object_store::{ObjectStoreConfig, S3ServerSideEncryption};
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+impl<E> From<SdkError<E, HttpResponse>> for ObjectStoreError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(err: SdkError<E, HttpResponse>) -> Self {
+        let is_not_found = err.raw_response().map_or(false, |response| {
+            response.status().as_u16() == StatusCode::NOT_FOUND.as_u16()
+        });
+
+        if is_not_found {
+            ObjectStoreError::KeyNotFound(err.into())
+        } else {
+            ObjectStoreError::Other(err.into())
+        }
+    }
+}
+
+impl From<ByteStreamError> for ObjectStoreError {
+    fn from(err: ByteStreamError) -> Self {
+        ObjectStoreError::Other(err.into())
+    }
+}
+
+/// Object store backed by an S3-compatible service.
+///
+/// Credentials are obtained using the standard AWS credential provider chain (e.g., from the `AWS_ACCESS_KEY_ID`
+/// and `AWS_SECRET_ACCESS_KEY` env variables, or from the instance metadata). Retries with exponential backoff
+/// are delegated to the AWS SDK, which only retries transient errors (unlike, e.g., missing keys).
+pub(crate) struct S3Storage {
+    client: Client,
+    bucket_name: String,
+    endpoint: Option<String>,
+    region: Option<String>,
+    server_side_encryption: Option<ServerSideEncryption>,
+    kms_key_id: Option<String>,
+    multipart_part_size: usize,
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Storage")
+            .field("bucket_name", &self.bucket_name)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("server_side_encryption", &self.server_side_encryption)
+            .field("multipart_part_size", &self.multipart_part_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    pub async fn new(config: &ObjectStoreConfig) -> Self {
+        let retry_config =
+            RetryConfig::standard().with_max_attempts(u32::from(config.max_retries) + 1);
+        let mut loader =
+            aws_config::defaults(aws_config::BehaviorVersion::latest()).retry_config(retry_config);
+        if let Some(region) = &config.s3_region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let sdk_config = loader.load().await;
+        let region = sdk_config.region().map(ToString::to_string);
+
+        let mut client_config =
+            S3ConfigBuilder::from(&sdk_config).force_path_style(config.s3_force_path_style());
+        if let Some(endpoint) = &config.s3_endpoint {
+            client_config = client_config.endpoint_url(endpoint);
+        }
+
+        let server_side_encryption =
+            config
+                .s3_server_side_encryption
+                .map(|encryption| match encryption {
+                    S3ServerSideEncryption::Aes256 => ServerSideEncryption::Aes256,
+                    S3ServerSideEncryption::AwsKms => ServerSideEncryption::AwsKms,
+                });
+        Self {
+            client: Client::from_conf(client_config.build()),
+            bucket_name: config.bucket_base_url.clone(),
+            endpoint: config.s3_endpoint.clone(),
+            region,
+            server_side_encryption,
+            kms_key_id: config.s3_kms_key_id.clone(),
+            multipart_part_size: config.s3_multipart_part_size(),
+        }
+    }
+
+    fn filename(bucket: &str, filename: &str) -> String {
+        format!("{bucket}/{filename}")
+    }
+
+    async fn put_object(&self, filename: String, value: Vec<u8>) -> Result<(), ObjectStoreError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .body(ByteStream::from(value))
+            .set_server_side_encryption(self.server_side_encryption.clone())
+            .set_ssekms_key_id(self.kms_key_id.clone())
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Uploads an object in parts. If any part fails to upload, the upload is aborted so that
+    /// the uploaded parts do not linger in the bucket.
+    async fn put_object_multipart(
+        &self,
+        filename: String,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(&filename)
+            .set_server_side_encryption(self.server_side_encryption.clone())
+            .set_ssekms_key_id(self.kms_key_id.clone())
+            .send()
+            .await?;
+        let upload_id = output.upload_id().ok_or_else(|| {
+            let message = format!("S3 returned no multipart upload ID for `{filename}`");
+            ObjectStoreError::Other(message.into())
+        })?;
+
+        let upload_result = self.upload_parts(&filename, upload_id, &value).await;
+        if upload_result.is_err() {
+            let abort_result = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket_name)
+                .key(&filename)
+                .upload_id(upload_id)
+                .send()
+                .await;
+            if let Err(err) = abort_result {
+                tracing::warn!(
+                    %err,
+                    "Failed aborting multipart upload `{upload_id}` for `{filename}`"
+                );
+            }
+        }
+        upload_result
+    }
+
+    async fn upload_parts(
+        &self,
+        filename: &str,
+        upload_id: &str,
+        value: &[u8],
+    ) -> Result<(), ObjectStoreError> {
+        let mut completed_parts = vec![];
+        for (i, chunk) in value.chunks(self.multipart_part_size).enumerate() {
+            let part_number = i32::try_from(i + 1).map_err(|_| {
+                let message = format!("too many parts in multipart upload for `{filename}`");
+                ObjectStoreError::Other(message.into())
+            })?;
+            let output = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket_name)
+                .key(filename)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk.to_vec()))
+                .send()
+                .await?;
+            let part = CompletedPart::builder()
+                .set_e_tag(output.e_tag().map(str::to_owned))
+                .part_number(part_number)
+                .build();
+            completed_parts.push(part);
+        }
+
+        let multipart_upload = CompletedMultipartUpload::builder()
+            .set_parts(Some(completed_parts))
+            .build();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .upload_id(upload_id)
+            .multipart_upload(multipart_upload)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Storage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Fetching data from S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .send()
+            .await?;
+        let blob = output.body.collect().await?.into_bytes().to_vec();
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = OBJECT_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Storing data to S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        let result = if value.len() > self.multipart_part_size {
+            self.put_object_multipart(filename, value).await
+        } else {
+            self.put_object(filename, value).await
+        };
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        result
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Removing data from S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        let bucket = bucket.as_str();
+        match (&self.endpoint, &self.region) {
+            (Some(endpoint), _) => {
+                let endpoint = endpoint.trim_end_matches('/');
+                format!("{endpoint}/{}/{bucket}", self.bucket_name)
+            }
+            (None, Some(region)) => {
+                format!(
+                    "https://{}.s3.{region}.amazonaws.com/{bucket}",
+                    self.bucket_name
+                )
+            }
+            (None, None) => format!("https://{}.s3.amazonaws.com/{bucket}", self.bucket_name),
+        }
+    }
+
+    async fn signed_url_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>, ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Presigning S3 URL for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .presigned(presigning_config)
+            .await?;
+        Ok(Some(request.uri().to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::object_store::ObjectStoreMode;
+
+    use super::*;
+
+    fn test_config() -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            bucket_base_url: "zksync-artifacts".to_owned(),
+            mode: ObjectStoreMode::S3,
+            file_backed_base_path: String::new(),
+            gcs_credential_file_path: String::new(),
+            max_retries: 3,
+            s3_endpoint: None,
+            s3_region: Some("eu-central-1".to_owned()),
+            s3_force_path_style: None,
+            s3_server_side_encryption: Some(S3ServerSideEncryption::AwsKms),
+            s3_kms_key_id: None,
+            s3_multipart_part_size_bytes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn storage_prefix() {
+        let store = S3Storage::new(&test_config()).await;
+        assert_eq!(
+            store.server_side_encryption,
+            Some(ServerSideEncryption::AwsKms)
+        );
+        assert_eq!(store.multipart_part_size, 16 << 20);
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::ProofsFri),
+            "https://zksync-artifacts.s3.eu-central-1.amazonaws.com/proofs_fri"
+        );
+
+        let config = ObjectStoreConfig {
+            s3_endpoint: Some("http://127.0.0.1:9000/".to_owned()),
+            s3_force_path_style: Some(true),
+            ..test_config()
+        };
+        let store = S3Storage::new(&config).await;
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::ProofsFri),
+            "http://127.0.0.1:9000/zksync-artifacts/proofs_fri"
+        );
+    }
+}
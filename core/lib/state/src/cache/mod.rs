@@ -2,6 +2,8 @@
 
 use std::hash::Hash;
 
+use zksync_types::H256;
+
 mod metrics;
 
 use self::metrics::{Method, RequestOutcome, METRICS};
@@ -106,12 +108,46 @@ where
     }
 }
 
+/// Size-bounded LRU cache for contract bytecodes (aka factory dependencies) keyed by the bytecode hash.
+///
+/// The cache is cheaply cloneable; clones share the underlying storage, so a single cache can be shared
+/// among components executing the VM (e.g., the API server sandbox and the state keeper). Since the cache
+/// is content-addressable, it never needs to be invalidated.
+#[derive(Debug, Clone)]
+pub struct BytecodeCache(Cache<H256, Vec<u8>>);
+
+impl BytecodeCache {
+    /// Creates a new cache with the specified capacity in bytes. If the capacity is zero,
+    /// the cache is a no-op.
+    pub fn new(capacity: u64) -> Self {
+        Self(Cache::new("factory_deps_cache", capacity))
+    }
+
+    /// Returns the bytecode with the specified hash if it is cached.
+    pub fn get(&self, hash: &H256) -> Option<Vec<u8>> {
+        self.0.get(hash)
+    }
+
+    /// Caches the bytecode with the specified hash.
+    pub fn insert(&self, hash: H256, bytecode: Vec<u8>) {
+        self.0.insert(hash, bytecode);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use zksync_types::H256;
 
     use super::*;
 
+    #[test]
+    fn bytecode_cache_is_shared_among_clones() {
+        let cache = BytecodeCache::new(1_024);
+        let cache_clone = cache.clone();
+        cache.insert(H256::repeat_byte(1), vec![1; 32]);
+        assert_eq!(cache_clone.get(&H256::repeat_byte(1)), Some(vec![1; 32]));
+        assert_eq!(cache_clone.get(&H256::repeat_byte(2)), None);
+    }
+
     #[test]
     fn cache_with_zero_capacity() {
         let zero_cache = Cache::<H256, Vec<u8>>::new("test", 0);
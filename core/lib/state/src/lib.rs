@@ -22,16 +22,19 @@ mod in_memory;
 mod postgres;
 mod rocksdb;
 mod shadow_storage;
+mod storage_overrides;
 mod storage_view;
 #[cfg(test)]
 mod test_utils;
 mod witness;
 
 pub use self::{
+    cache::BytecodeCache,
     in_memory::{InMemoryStorage, IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID},
     postgres::{PostgresStorage, PostgresStorageCaches},
     rocksdb::RocksdbStorage,
     shadow_storage::ShadowStorage,
+    storage_overrides::StorageWithOverrides,
     storage_view::{StorageView, StorageViewMetrics},
     witness::WitnessStorage,
 };
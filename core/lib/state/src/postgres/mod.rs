@@ -9,7 +9,7 @@ use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H25
 
 use self::metrics::{Method, ValuesUpdateStage, CACHE_METRICS, STORAGE_METRICS};
 use crate::{
-    cache::{Cache, CacheValue},
+    cache::{BytecodeCache, Cache, CacheValue},
     ReadStorage,
 };
 
@@ -17,9 +17,6 @@ mod metrics;
 #[cfg(test)]
 mod tests;
 
-/// Type alias for smart contract source code cache.
-type FactoryDepsCache = Cache<H256, Vec<u8>>;
-
 impl CacheValue<H256> for Vec<u8> {
     fn cache_weight(&self) -> u32 {
         self.len().try_into().expect("Cached bytes are too large")
@@ -222,7 +219,7 @@ struct ValuesCacheAndUpdater {
 /// - Cache of the VM storage snapshot corresponding to the latest sealed miniblock
 #[derive(Debug, Clone)]
 pub struct PostgresStorageCaches {
-    factory_deps: FactoryDepsCache,
+    factory_deps: BytecodeCache,
     initial_writes: InitialWritesCache,
     // Besides L1 batch numbers for initial writes, we also cache information that a certain key
     // was not written to before the certain L1 batch (i.e., this lower boundary is the cached value).
@@ -240,13 +237,24 @@ impl PostgresStorageCaches {
 
     /// Creates caches with the specified capacities measured in bytes.
     pub fn new(factory_deps_capacity: u64, initial_writes_capacity: u64) -> Self {
+        Self::with_bytecode_cache(
+            BytecodeCache::new(factory_deps_capacity),
+            initial_writes_capacity,
+        )
+    }
+
+    /// Creates caches using the provided (potentially shared) bytecode cache. The capacity of initial writes caches
+    /// is measured in bytes.
+    pub fn with_bytecode_cache(
+        bytecode_cache: BytecodeCache,
+        initial_writes_capacity: u64,
+    ) -> Self {
         tracing::debug!(
-            "Initialized VM execution cache with {factory_deps_capacity}B capacity for factory deps, \
-             {initial_writes_capacity}B capacity for initial writes"
+            "Initialized VM execution cache with {initial_writes_capacity}B capacity for initial writes"
         );
 
         Self {
-            factory_deps: FactoryDepsCache::new("factory_deps_cache", factory_deps_capacity),
+            factory_deps: bytecode_cache,
             initial_writes: InitialWritesCache::new(
                 "initial_writes_cache",
                 initial_writes_capacity / 2,
@@ -23,7 +23,7 @@ use std::{collections::HashMap, convert::TryInto, mem, path::Path, time::Instant
 
 use itertools::{Either, Itertools};
 use zksync_dal::StorageProcessor;
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, rocksdb, CompactionStats, RocksDB};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
@@ -122,6 +122,48 @@ impl RocksdbStorage {
         }
     }
 
+    /// Opens an existing storage with the provided RocksDB `path` in the read-only mode. The storage
+    /// can be used to inspect the state (e.g., [`Self::l1_batch_number()`]), but not to update it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RocksDB instance doesn't exist or cannot be opened.
+    pub fn open_read_only(path: &Path) -> Result<Self, rocksdb::Error> {
+        let db = RocksDB::open_read_only(path)?;
+        Ok(Self {
+            db,
+            pending_patch: InMemoryStorage::default(),
+            enum_index_migration_chunk_size: 100,
+        })
+    }
+
+    /// Returns another handle to the same RocksDB instance. The handle can be used to read the state
+    /// concurrently with this storage (e.g., from another thread); it doesn't include changes
+    /// that were not saved yet.
+    pub fn read_only_handle(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            pending_patch: InMemoryStorage::default(),
+            enum_index_migration_chunk_size: self.enum_index_migration_chunk_size,
+        }
+    }
+
+    /// Creates a consistent point-in-time copy of this storage at the specified `path`, which must not exist.
+    /// Changes that were not saved yet are not included into the copy.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB errors.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.db.create_checkpoint(path)
+    }
+
+    /// Compacts all column families of the underlying RocksDB instance, blocking the current thread
+    /// until compaction is complete.
+    pub fn compact(&self) -> Vec<CompactionStats> {
+        self.db.compact()
+    }
+
     /// Enables enum indices migration.
     pub fn enable_enum_index_migration(&mut self, chunk_size: usize) {
         self.enum_index_migration_chunk_size = chunk_size;
@@ -0,0 +1,118 @@
+//! Read-only storage wrapper overriding values of the underlying storage.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_types::{AccountTreeId, StorageKey, StorageValue, H256};
+
+use crate::ReadStorage;
+
+/// [`ReadStorage`] wrapper that overrides storage values and factory dependencies of the underlying storage,
+/// and allows to erase the entire storage of an account. The underlying storage is never modified.
+///
+/// The wrapper is used to execute transactions on a modified state, e.g. for `eth_call` with state overrides.
+/// Overrides are treated as a part of the base state; i.e., they are not reported as storage writes
+/// by the [`StorageView`](crate::StorageView) wrapping this storage.
+#[derive(Debug)]
+pub struct StorageWithOverrides<S> {
+    storage_handle: S,
+    overridden_slots: HashMap<StorageKey, StorageValue>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+    erased_accounts: HashSet<AccountTreeId>,
+}
+
+impl<S: ReadStorage> StorageWithOverrides<S> {
+    /// Creates storage without any overrides.
+    pub fn new(storage_handle: S) -> Self {
+        Self {
+            storage_handle,
+            overridden_slots: HashMap::new(),
+            overridden_factory_deps: HashMap::new(),
+            erased_accounts: HashSet::new(),
+        }
+    }
+
+    /// Overrides the value of the specified storage slot.
+    pub fn set_value(&mut self, key: StorageKey, value: StorageValue) {
+        self.overridden_slots.insert(key, value);
+    }
+
+    /// Adds a factory dependency, so that it can be loaded by the VM.
+    pub fn store_factory_dep(&mut self, hash: H256, bytecode: Vec<u8>) {
+        self.overridden_factory_deps.insert(hash, bytecode);
+    }
+
+    /// Erases the storage of the specified account. All slots of the account that are not overridden
+    /// with [`Self::set_value()`] will be read as zeros.
+    pub fn erase_account(&mut self, account: AccountTreeId) {
+        self.erased_accounts.insert(account);
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithOverrides<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(&value) = self.overridden_slots.get(key) {
+            return value;
+        }
+        if self.erased_accounts.contains(key.account()) {
+            return H256::zero();
+        }
+        self.storage_handle.read_value(key)
+    }
+
+    /// Overrides are not taken into account; i.e., a write is initial iff it is initial
+    /// for the underlying storage.
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.overridden_factory_deps.get(&hash) {
+            return Some(bytecode.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.storage_handle.get_enumeration_index(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::Address;
+
+    use super::*;
+    use crate::InMemoryStorage;
+
+    #[test]
+    fn overriding_storage() {
+        let account = AccountTreeId::new(Address::repeat_byte(0xfe));
+        let other_account = AccountTreeId::new(Address::repeat_byte(0xef));
+        let key = StorageKey::new(account, H256::from_low_u64_be(61));
+        let other_key = StorageKey::new(account, H256::from_low_u64_be(62));
+        let other_account_key = StorageKey::new(other_account, H256::from_low_u64_be(61));
+        let value = H256::from_low_u64_be(73);
+
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, value);
+        raw_storage.set_value(other_key, value);
+        raw_storage.set_value(other_account_key, value);
+        let mut storage = StorageWithOverrides::new(&raw_storage);
+
+        let new_value = H256::from_low_u64_be(74);
+        storage.set_value(key, new_value);
+        assert_eq!(storage.read_value(&key), new_value);
+        assert_eq!(storage.read_value(&other_key), value);
+        assert!(!storage.is_write_initial(&key));
+
+        storage.erase_account(account);
+        assert_eq!(storage.read_value(&key), new_value);
+        assert_eq!(storage.read_value(&other_key), H256::zero());
+        assert_eq!(storage.read_value(&other_account_key), value);
+
+        let dep_hash = H256::repeat_byte(1);
+        assert_eq!(storage.load_factory_dep(dep_hash), None);
+        storage.store_factory_dep(dep_hash, vec![1; 32]);
+        assert_eq!(storage.load_factory_dep(dep_hash), Some(vec![1; 32]));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{hash_map, HashMap},
     fmt, mem,
     rc::Rc,
     time::{Duration, Instant},
@@ -8,7 +8,7 @@ use std::{
 
 use zksync_types::{witness_block_state::WitnessBlockState, StorageKey, StorageValue, H256};
 
-use crate::{ReadStorage, WriteStorage};
+use crate::{BytecodeCache, ReadStorage, WriteStorage};
 
 /// Metrics for [`StorageView`].
 #[derive(Debug, Default, Clone, Copy)]
@@ -49,6 +49,8 @@ pub struct StorageView<S> {
     read_storage_keys: HashMap<StorageKey, StorageValue>,
     // Cache for `contains_key()` checks. The cache is only valid within one L1 batch execution.
     initial_writes_cache: HashMap<StorageKey, bool>,
+    // Bytecode cache potentially shared with other views.
+    bytecode_cache: Option<BytecodeCache>,
     metrics: StorageViewMetrics,
 }
 
@@ -95,10 +97,18 @@ impl<S: ReadStorage + fmt::Debug> StorageView<S> {
             modified_storage_keys: HashMap::new(),
             read_storage_keys: HashMap::new(),
             initial_writes_cache: HashMap::new(),
+            bytecode_cache: None,
             metrics: StorageViewMetrics::default(),
         }
     }
 
+    /// Uses the provided cache for factory dependencies loaded from the underlying storage.
+    #[must_use]
+    pub fn with_bytecode_cache(mut self, cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(cache);
+        self
+    }
+
     fn get_value_no_log(&mut self, key: &StorageKey) -> StorageValue {
         let started_at = Instant::now();
 
@@ -129,6 +139,25 @@ impl<S: ReadStorage + fmt::Debug> StorageView<S> {
         }
     }
 
+    /// Populates the read cache using values read from the same underlying storage by another view,
+    /// e.g. the one used to pre-execute transactions. Keys already cached by this view are skipped.
+    /// Returns the number of newly cached values.
+    pub fn warm_up_cache(&mut self, state: WitnessBlockState) -> usize {
+        let mut cached_count = 0;
+        for (key, value) in state.read_storage_key {
+            if let hash_map::Entry::Vacant(entry) = self.read_storage_keys.entry(key) {
+                entry.insert(value);
+                cached_count += 1;
+            }
+        }
+        for (key, is_write_initial) in state.is_write_initial {
+            self.initial_writes_cache
+                .entry(key)
+                .or_insert(is_write_initial);
+        }
+        cached_count
+    }
+
     /// Make a Rc RefCell ptr to the storage
     pub fn to_rc_ptr(self) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(self))
@@ -166,7 +195,15 @@ impl<S: ReadStorage + fmt::Debug> ReadStorage for StorageView<S> {
     }
 
     fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
-        self.storage_handle.load_factory_dep(hash)
+        let Some(cache) = &self.bytecode_cache else {
+            return self.storage_handle.load_factory_dep(hash);
+        };
+        if let Some(bytecode) = cache.get(&hash) {
+            return Some(bytecode);
+        }
+        let bytecode = self.storage_handle.load_factory_dep(hash)?;
+        cache.insert(hash, bytecode.clone());
+        Some(bytecode)
     }
 
     fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
@@ -251,4 +288,46 @@ mod test {
         assert_eq!(metrics.get_value_storage_invocations, 3);
         assert_eq!(metrics.set_value_storage_invocations, 2);
     }
+
+    #[test]
+    fn loading_factory_deps_with_shared_cache() {
+        let hash = H256::repeat_byte(1);
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.store_factory_dep(hash, vec![1; 32]);
+        let cache = BytecodeCache::new(1_024);
+
+        let mut storage_view = StorageView::new(&raw_storage).with_bytecode_cache(cache.clone());
+        assert_eq!(storage_view.load_factory_dep(hash), Some(vec![1; 32]));
+        assert_eq!(storage_view.load_factory_dep(H256::zero()), None);
+        assert_eq!(cache.get(&hash), Some(vec![1; 32]));
+
+        // Another view should use the cached bytecode even if it's not present in the underlying storage.
+        let empty_storage = InMemoryStorage::default();
+        let mut other_view = StorageView::new(&empty_storage).with_bytecode_cache(cache);
+        assert_eq!(other_view.load_factory_dep(hash), Some(vec![1; 32]));
+    }
+
+    #[test]
+    fn warming_up_cache() {
+        let account: AccountTreeId = AccountTreeId::new(Address::from([0xfe; 20]));
+        let key = StorageKey::new(account, H256::from_low_u64_be(61));
+        let other_key = StorageKey::new(account, H256::from_low_u64_be(62));
+        let value = H256::from_low_u64_be(73);
+
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, value);
+        let mut other_view = StorageView::new(&raw_storage);
+        assert_eq!(other_view.read_value(&key), value);
+        assert!(!other_view.is_write_initial(&key));
+        assert_eq!(other_view.read_value(&other_key), H256::zero());
+
+        let mut storage_view = StorageView::new(&raw_storage);
+        storage_view.read_value(&other_key);
+        let cached_count = storage_view.warm_up_cache(other_view.witness_block_state());
+        assert_eq!(cached_count, 1);
+
+        assert_eq!(storage_view.read_value(&key), value);
+        assert!(!storage_view.is_write_initial(&key));
+        assert_eq!(storage_view.metrics().storage_invocations_missed, 1);
+    }
 }
@@ -75,6 +75,7 @@ pub(crate) async fn create_miniblock(
         base_fee_per_gas: 0,
         l1_gas_price: 0,
         l2_fair_gas_price: 0,
+        gas_per_pubdata: None,
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
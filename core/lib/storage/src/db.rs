@@ -11,8 +11,9 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    checkpoint::Checkpoint, properties, BlockBasedOptions, Cache, ColumnFamily,
+    ColumnFamilyDescriptor, DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange,
+    ReadOptions, WriteOptions, DB,
 };
 
 use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
@@ -260,6 +261,27 @@ impl StalledWritesRetries {
     }
 }
 
+/// Statistics of a manual compaction of a single column family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionStats {
+    /// Name of the compacted column family.
+    pub cf_name: &'static str,
+    /// Total size of SST files in the column family before compaction.
+    pub size_before: u64,
+    /// Total size of SST files in the column family after compaction.
+    pub size_after: u64,
+    /// Duration of the compaction.
+    pub latency: Duration,
+}
+
+impl CompactionStats {
+    /// Returns the number of bytes reclaimed by the compaction. Compaction may increase the size
+    /// if the column family was concurrently written to; in this case, 0 is returned.
+    pub fn reclaimed_bytes(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
 /// [`RocksDB`] options.
 #[derive(Debug, Clone, Copy)]
 pub struct RocksDBOptions {
@@ -379,6 +401,44 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         }
     }
 
+    /// Opens an existing RocksDB instance at the specified `path` in the read-only mode. Unlike [`Self::new()`],
+    /// this method doesn't create the database or missing column families; all writes to the returned instance
+    /// will fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database doesn't exist or cannot be opened.
+    pub fn open_read_only(path: &Path) -> Result<Self, rocksdb::Error> {
+        let db_options = Options::default();
+        let existing_cfs = DB::list_cf(&db_options, path)?;
+        // In the read-only mode, RocksDB allows opening a subset of column families, but all of them must exist.
+        let cf_names: HashSet<_> = CF::ALL
+            .iter()
+            .map(|cf| cf.name())
+            .filter(|&cf_name| existing_cfs.iter().any(|name| name == cf_name))
+            .collect();
+        let db = DB::open_cf_for_read_only(&db_options, path, cf_names.iter().copied(), false)?;
+        let inner = Arc::new(RocksDBInner {
+            db,
+            db_name: CF::DB_NAME,
+            cf_names,
+            _registry_entry: RegistryEntry::new(),
+            _caches: RocksDBCaches::new(None),
+        });
+
+        tracing::info!(
+            "Opened RocksDB `{}` at `{}` in the read-only mode",
+            CF::DB_NAME,
+            path.display()
+        );
+        Ok(Self {
+            inner,
+            sync_writes: false,
+            stalled_writes_retries: RocksDBOptions::default().stalled_writes_retries,
+            _cf: PhantomData,
+        })
+    }
+
     /// Switches on sync writes in [`Self::write()`] and [`Self::put()`]. This has a performance
     /// penalty and is mostly useful for tests.
     #[must_use]
@@ -502,6 +562,56 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         }
     }
 
+    /// Creates a consistent point-in-time copy of this database at the specified `path`, which must not exist.
+    /// If `path` resides on the same filesystem as the database, SST files are hard-linked rather than copied,
+    /// so the operation is cheap.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        let checkpoint = Checkpoint::new(&self.inner.db)?;
+        checkpoint.create_checkpoint(path)
+    }
+
+    /// Compacts the entire key range of the specified column family, blocking the current thread
+    /// until compaction is complete. Writes to the column family are not blocked during compaction.
+    pub fn compact_cf(&self, cf: CF) -> CompactionStats {
+        let cf_name = cf.name();
+        let cf_handle = self.column_family(cf);
+        let started_at = Instant::now();
+        let size_before = self
+            .inner
+            .int_property(cf_handle, properties::TOTAL_SST_FILES_SIZE)
+            .unwrap_or(0);
+        tracing::info!(
+            "Starting manual compaction of CF `{cf_name}` in RocksDB `{}` ({size_before}B of SST files)",
+            CF::DB_NAME
+        );
+
+        self.inner
+            .db
+            .compact_range_cf::<&[u8], &[u8]>(cf_handle, None, None);
+
+        let size_after = self
+            .inner
+            .int_property(cf_handle, properties::TOTAL_SST_FILES_SIZE)
+            .unwrap_or(0);
+        let stats = CompactionStats {
+            cf_name,
+            size_before,
+            size_after,
+            latency: started_at.elapsed(),
+        };
+        tracing::info!(
+            "Finished manual compaction of CF `{cf_name}` in RocksDB `{}`: {stats:?}",
+            CF::DB_NAME
+        );
+        METRICS.report_manual_compaction(CF::DB_NAME, cf_name, &stats);
+        stats
+    }
+
+    /// Compacts all column families in this database one by one. See [`Self::compact_cf()`] for details.
+    pub fn compact(&self) -> Vec<CompactionStats> {
+        CF::ALL.iter().map(|&cf| self.compact_cf(cf)).collect()
+    }
+
     fn column_family(&self, cf: CF) -> &ColumnFamily {
         self.inner
             .db
@@ -702,6 +812,32 @@ mod tests {
         assert_eq!(value.unwrap(), b"value");
     }
 
+    #[test]
+    fn opening_db_in_read_only_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        RocksDB::<NewColumnFamilies>::open_read_only(temp_dir.path()).unwrap_err();
+        // The database must not be created in the read-only mode.
+        assert!(!temp_dir.path().join("CURRENT").exists());
+
+        let db = RocksDB::<NewColumnFamilies>::new(temp_dir.path()).with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test", b"value");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let db = RocksDB::<NewColumnFamilies>::open_read_only(temp_dir.path()).unwrap();
+        let value = db.get_cf(NewColumnFamilies::Other, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test", b"other_value");
+        db.write(batch).unwrap_err();
+        drop(db);
+
+        let db = RocksDB::<NewColumnFamilies>::new(temp_dir.path());
+        let value = db.get_cf(NewColumnFamilies::Other, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+    }
+
     #[test]
     fn write_batch_can_be_restored_from_bytes() {
         let temp_dir = TempDir::new().unwrap();
@@ -726,4 +862,38 @@ mod tests {
             .unwrap();
         assert_eq!(value, b"value2");
     }
+
+    #[test]
+    fn manual_compaction_reclaims_space() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(temp_dir.path()).with_sync_writes();
+        for chunk in 0_u8..4 {
+            let mut batch = db.new_write_batch();
+            for i in 0_u32..1_000 {
+                let key = i.to_be_bytes();
+                batch.put_cf(NewColumnFamilies::Other, &key, &[chunk; 256]);
+            }
+            db.write(batch).unwrap();
+            let cf = db.column_family(NewColumnFamilies::Other);
+            db.inner.db.flush_cf(cf).unwrap();
+        }
+
+        let stats = db.compact_cf(NewColumnFamilies::Other);
+        assert_eq!(stats.cf_name, "other");
+        assert!(stats.size_before > stats.size_after, "{stats:?}");
+        assert_eq!(
+            stats.reclaimed_bytes(),
+            stats.size_before - stats.size_after
+        );
+
+        let value = db
+            .get_cf(NewColumnFamilies::Other, &0_u32.to_be_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, [3; 256]);
+
+        let all_stats = db.compact();
+        let cf_names: Vec<_> = all_stats.iter().map(|stats| stats.cf_name).collect();
+        assert_eq!(cf_names, ["default", "other"]);
+    }
 }
@@ -9,7 +9,7 @@ use std::{
 use once_cell::sync::Lazy;
 use vise::{Buckets, Collector, Counter, EncodeLabelSet, Family, Gauge, Histogram, Metrics, Unit};
 
-use crate::db::RocksDBInner;
+use crate::db::{CompactionStats, RocksDBInner};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
 struct DbLabel {
@@ -51,6 +51,15 @@ pub(crate) struct RocksdbMetrics {
     /// leads to a panic).
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     stalled_write_duration: Family<DbLabel, Histogram<Duration>>,
+    /// Duration of manual compactions for a column family.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    manual_compaction_duration: Family<RocksdbLabels, Histogram<Duration>>,
+    /// Total number of bytes reclaimed by manual compactions for a column family.
+    #[metrics(unit = Unit::Bytes)]
+    manual_compaction_reclaimed: Family<RocksdbLabels, Counter>,
+    /// Total size of SST files in a column family after the latest manual compaction.
+    #[metrics(unit = Unit::Bytes)]
+    manual_compaction_size_after: Family<RocksdbLabels, Gauge<u64>>,
 }
 
 impl RocksdbMetrics {
@@ -69,6 +78,18 @@ impl RocksdbMetrics {
     ) {
         self.stalled_write_duration[&db.into()].observe(stall_duration);
     }
+
+    pub(crate) fn report_manual_compaction(
+        &self,
+        db: &'static str,
+        cf: &'static str,
+        stats: &CompactionStats,
+    ) {
+        let labels = RocksdbLabels::new(db, cf);
+        self.manual_compaction_duration[&labels].observe(stats.latency);
+        self.manual_compaction_reclaimed[&labels].inc_by(stats.reclaimed_bytes());
+        self.manual_compaction_size_after[&labels].set(stats.size_after);
+    }
 }
 
 #[vise::register]
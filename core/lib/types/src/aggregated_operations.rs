@@ -7,9 +7,12 @@ use zkevm_test_harness::{
     bellman::{bn256::Bn256, plonk::better_better_cs::proof::Proof},
     witness::oracle::VmWitnessOracle,
 };
-use zksync_basic_types::{ethabi::Token, L1BatchNumber};
+use zksync_basic_types::{basic_fri_types::ProofCompressionBackend, ethabi::Token, L1BatchNumber};
 
-use crate::{commitment::L1BatchWithMetadata, ProtocolVersionId, U256};
+use crate::{
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    ProtocolVersionId, U256,
+};
 
 fn l1_batch_range_from_batches(
     batches: &[L1BatchWithMetadata],
@@ -29,18 +32,38 @@ fn l1_batch_range_from_batches(
 pub struct L1BatchCommitOperation {
     pub last_committed_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
+    /// Inclusion data of batch pubdata in an external data availability layer, one entry per batch
+    /// in `l1_batches`. If set, inclusion data is passed to the commit transaction instead of the pubdata.
+    pub da_inclusion_data: Option<Vec<Vec<u8>>>,
+    /// Mode in which batches are committed. Ignored if `da_inclusion_data` is set.
+    pub commitment_mode: L1BatchCommitmentMode,
 }
 
 impl L1BatchCommitOperation {
-    pub fn get_eth_tx_args(&self) -> Vec<Token> {
+    pub fn get_eth_tx_args(&self) -> anyhow::Result<Vec<Token>> {
         let stored_batch_info = self.last_committed_l1_batch.l1_header_data();
-        let l1_batches_to_commit = self
-            .l1_batches
-            .iter()
-            .map(L1BatchWithMetadata::l1_commit_data)
-            .collect();
+        let l1_batches_to_commit = if let Some(da_inclusion_data) = &self.da_inclusion_data {
+            anyhow::ensure!(
+                da_inclusion_data.len() == self.l1_batches.len(),
+                "DA inclusion data must be provided for each committed L1 batch; got {} entries for {} batches",
+                da_inclusion_data.len(),
+                self.l1_batches.len()
+            );
+            self.l1_batches
+                .iter()
+                .zip(da_inclusion_data)
+                .map(|(batch, inclusion_data)| {
+                    batch.l1_commit_data_with_da_inclusion(inclusion_data)
+                })
+                .collect::<anyhow::Result<_>>()?
+        } else {
+            self.l1_batches
+                .iter()
+                .map(|batch| batch.l1_commit_data_for_mode(self.commitment_mode))
+                .collect::<anyhow::Result<_>>()?
+        };
 
-        vec![stored_batch_info, Token::Array(l1_batches_to_commit)]
+        Ok(vec![stored_batch_info, Token::Array(l1_batches_to_commit)])
     }
 
     pub fn l1_batch_range(&self) -> ops::RangeInclusive<L1BatchNumber> {
@@ -69,16 +92,57 @@ impl fmt::Debug for L1BatchProofForL1 {
     }
 }
 
+/// L1 batch proof produced by the FFLONK wrapper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FflonkL1BatchProofForL1 {
+    pub aggregation_result_coords: [[u8; 32]; 4],
+    /// Proof serialized into words as expected by the FFLONK verifier on L1.
+    pub proof: Vec<U256>,
+}
+
+/// L1 batch proof tagged by the compression backend that produced it.
+#[derive(Debug, Clone)]
+pub enum L1BatchProof {
+    Plonk(L1BatchProofForL1),
+    Fflonk(FflonkL1BatchProofForL1),
+}
+
+impl L1BatchProof {
+    pub fn backend(&self) -> ProofCompressionBackend {
+        match self {
+            Self::Plonk(_) => ProofCompressionBackend::Plonk,
+            Self::Fflonk(_) => ProofCompressionBackend::Fflonk,
+        }
+    }
+
+    pub fn aggregation_result_coords(&self) -> &[[u8; 32]; 4] {
+        match self {
+            Self::Plonk(proof) => &proof.aggregation_result_coords,
+            Self::Fflonk(proof) => &proof.aggregation_result_coords,
+        }
+    }
+
+    fn serialized_proof(&self) -> Vec<U256> {
+        match self {
+            Self::Plonk(proof) => serialize_proof(&proof.scheduler_proof).1,
+            Self::Fflonk(proof) => proof.proof.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct L1BatchProofOperation {
     pub prev_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
-    pub proofs: Vec<L1BatchProofForL1>,
+    pub proofs: Vec<L1BatchProof>,
     pub should_verify: bool,
+    /// Whether the verifier on L1 is a dual verifier, which expects the verifier type
+    /// (see [`ProofCompressionBackend::l1_verifier_type()`]) as the first word of the proof.
+    pub use_dual_verifier: bool,
 }
 
 impl L1BatchProofOperation {
-    pub fn get_eth_tx_args(&self) -> Vec<Token> {
+    pub fn get_eth_tx_args(&self) -> anyhow::Result<Vec<Token>> {
         let prev_l1_batch = self.prev_l1_batch.l1_header_data();
         let batches_arg = self
             .l1_batches
@@ -89,15 +153,25 @@ impl L1BatchProofOperation {
 
         if self.should_verify {
             // currently we only support submitting a single proof
-            assert_eq!(self.proofs.len(), 1);
-            assert_eq!(self.l1_batches.len(), 1);
-
-            let L1BatchProofForL1 {
-                aggregation_result_coords,
-                scheduler_proof,
-            } = self.proofs.first().unwrap();
+            anyhow::ensure!(
+                self.proofs.len() == 1 && self.l1_batches.len() == 1,
+                "Only a single proof for a single L1 batch can be submitted; got {} proofs for {} batches",
+                self.proofs.len(),
+                self.l1_batches.len()
+            );
 
-            let (_, proof) = serialize_proof(scheduler_proof);
+            let l1_batch_proof = &self.proofs[0];
+            let aggregation_result_coords = l1_batch_proof.aggregation_result_coords();
+            let mut proof = l1_batch_proof.serialized_proof();
+            let backend = l1_batch_proof.backend();
+            if self.use_dual_verifier {
+                proof.insert(0, U256::from(backend.l1_verifier_type()));
+            } else {
+                anyhow::ensure!(
+                    backend == ProofCompressionBackend::Plonk,
+                    "Only PLONK proofs can be verified without the dual verifier; got a {backend:?} proof"
+                );
+            }
 
             let aggregation_result_coords = if self.l1_batches[0]
                 .header
@@ -119,13 +193,13 @@ impl L1BatchProofOperation {
                 Token::Array(proof.into_iter().map(Token::Uint).collect()),
             ]);
 
-            vec![prev_l1_batch, batches_arg, proof_input]
+            Ok(vec![prev_l1_batch, batches_arg, proof_input])
         } else {
-            vec![
+            Ok(vec![
                 prev_l1_batch,
                 batches_arg,
                 Token::Tuple(vec![Token::Array(vec![]), Token::Array(vec![])]),
-            ]
+            ])
         }
     }
 
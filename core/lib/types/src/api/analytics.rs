@@ -0,0 +1,190 @@
+//! API types related to L1 batch analytics used for cost accounting.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{L1BatchNumber, H256, U256};
+
+use crate::{commitment::SerializeCommitment, l2_to_l1_log::L2ToL1Log};
+
+/// Breakdown of the L1 batch pubdata by category, in bytes. Each category includes the length prefixes
+/// used in the pubdata encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PubdataBreakdown {
+    pub user_logs: u64,
+    pub l2_to_l1_messages: u64,
+    pub published_bytecodes: u64,
+    pub state_diffs: u64,
+}
+
+impl PubdataBreakdown {
+    /// Parses pubdata in the format produced by the L1 messenger for post-boojum L1 batches: user L2-to-L1 logs,
+    /// L2-to-L1 messages and published bytecodes (each prefixed by the number of items), followed by state diffs.
+    /// Returns `None` if the pubdata is malformed.
+    pub fn parse(pubdata: &[u8]) -> Option<Self> {
+        fn read_u32(bytes: &[u8], pos: &mut usize) -> Option<usize> {
+            let value = bytes.get(*pos..*pos + 4)?;
+            *pos += 4;
+            Some(u32::from_be_bytes(value.try_into().unwrap()) as usize)
+        }
+
+        fn skip_length_prefixed_items(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+            let start = *pos;
+            let count = read_u32(bytes, pos)?;
+            for _ in 0..count {
+                let len = read_u32(bytes, pos)?;
+                *pos = pos.checked_add(len).filter(|&end| end <= bytes.len())?;
+            }
+            Some((*pos - start) as u64)
+        }
+
+        let mut pos = 0;
+        let user_logs_count = read_u32(pubdata, &mut pos)?;
+        pos = user_logs_count
+            .checked_mul(L2ToL1Log::SERIALIZED_SIZE)
+            .and_then(|len| pos.checked_add(len))
+            .filter(|&end| end <= pubdata.len())?;
+        let user_logs = pos as u64;
+        let l2_to_l1_messages = skip_length_prefixed_items(pubdata, &mut pos)?;
+        let published_bytecodes = skip_length_prefixed_items(pubdata, &mut pos)?;
+        Some(Self {
+            user_logs,
+            l2_to_l1_messages,
+            published_bytecodes,
+            state_diffs: (pubdata.len() - pos) as u64,
+        })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.user_logs + self.l2_to_l1_messages + self.published_bytecodes + self.state_diffs
+    }
+}
+
+/// L1 costs of a single stage (commit, prove or execute) of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStageCost {
+    /// Hash of the confirmed L1 transaction. A single transaction may process several L1 batches.
+    pub tx_hash: H256,
+    /// Gas attributed to the L1 batch, as predicted by the L1 batch aggregator.
+    pub predicted_gas: u64,
+    /// Effective gas price (base fee + priority fee) of the L1 transaction, in wei.
+    pub gas_price: u64,
+    /// Cost attributed to the L1 batch, in wei (`predicted_gas * gas_price`).
+    pub cost: U256,
+}
+
+impl L1BatchStageCost {
+    pub fn new(tx_hash: H256, predicted_gas: u64, gas_price: u64) -> Self {
+        Self {
+            tx_hash,
+            predicted_gas,
+            gas_price,
+            cost: U256::from(predicted_gas) * U256::from(gas_price),
+        }
+    }
+}
+
+/// Summary of an L1 batch for cost accounting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchAnalytics {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub l1_tx_count: usize,
+    pub l2_tx_count: usize,
+    /// Total gas used by transactions in the batch. Pruned L2 transactions are not taken into account.
+    pub gas_used: U256,
+    /// Pubdata breakdown; `None` for pre-boojum L1 batches, or if the pubdata was pruned.
+    pub pubdata: Option<PubdataBreakdown>,
+    /// Number of basic circuits proven for the L1 batch, keyed by the circuit ID. Empty if the batch
+    /// was not processed by FRI provers sharing the database with the node.
+    pub circuits: BTreeMap<u8, u64>,
+    pub commit_cost: Option<L1BatchStageCost>,
+    pub prove_cost: Option<L1BatchStageCost>,
+    pub execute_cost: Option<L1BatchStageCost>,
+    pub sealed_at: DateTime<Utc>,
+    /// Time when the L1 batch proof was generated.
+    pub proof_generated_at: Option<DateTime<Utc>>,
+}
+
+impl L1BatchAnalytics {
+    /// Returns the sum of L1 costs for all confirmed stages of the L1 batch, in wei.
+    pub fn total_l1_cost(&self) -> U256 {
+        [&self.commit_cost, &self.prove_cost, &self.execute_cost]
+            .into_iter()
+            .flatten()
+            .fold(U256::zero(), |acc, cost| acc + cost.cost)
+    }
+
+    /// Returns the time between sealing the L1 batch and generating its proof.
+    pub fn proof_latency(&self) -> Option<chrono::Duration> {
+        Some(self.proof_generated_at? - self.sealed_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn encode_items(items: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = (items.len() as u32).to_be_bytes().to_vec();
+        for item in items {
+            bytes.extend((item.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(item);
+        }
+        bytes
+    }
+
+    #[test]
+    fn parsing_pubdata_breakdown() {
+        let mut pubdata = 2_u32.to_be_bytes().to_vec();
+        pubdata.extend([0; 2 * L2ToL1Log::SERIALIZED_SIZE]);
+        pubdata.extend(encode_items(&[b"message"]));
+        pubdata.extend(encode_items(&[&[1; 32], &[2; 64]]));
+        pubdata.extend([3; 100]);
+
+        let breakdown = PubdataBreakdown::parse(&pubdata).unwrap();
+        assert_eq!(
+            breakdown,
+            PubdataBreakdown {
+                user_logs: 4 + 2 * 88,
+                l2_to_l1_messages: 4 + 4 + 7,
+                published_bytecodes: 4 + 4 + 32 + 4 + 64,
+                state_diffs: 100,
+            }
+        );
+        assert_eq!(breakdown.total(), pubdata.len() as u64);
+
+        assert_eq!(PubdataBreakdown::parse(&[]), None);
+        assert_eq!(PubdataBreakdown::parse(&pubdata[..50]), None);
+    }
+
+    #[test]
+    fn computing_total_l1_cost() {
+        let timestamp = Utc.timestamp_opt(1_000, 0).unwrap();
+        let analytics = L1BatchAnalytics {
+            number: L1BatchNumber(1),
+            timestamp: 1_000,
+            l1_tx_count: 0,
+            l2_tx_count: 1,
+            gas_used: 100.into(),
+            pubdata: None,
+            circuits: BTreeMap::new(),
+            commit_cost: Some(L1BatchStageCost::new(H256::repeat_byte(1), 1_000, 10)),
+            prove_cost: None,
+            execute_cost: Some(L1BatchStageCost::new(H256::repeat_byte(2), 500, 20)),
+            sealed_at: timestamp,
+            proof_generated_at: Some(timestamp + chrono::Duration::seconds(30)),
+        };
+        assert_eq!(analytics.total_l1_cost(), U256::from(20_000));
+        assert_eq!(
+            analytics.proof_latency(),
+            Some(chrono::Duration::seconds(30))
+        );
+    }
+}
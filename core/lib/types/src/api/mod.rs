@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -6,18 +8,27 @@ use zksync_basic_types::{
     L1BatchNumber,
 };
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_utils::h256_to_account_address;
 
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    commitment::{l1_batch_proof_public_input, L1BatchWithMetadata},
+    ethabi,
+    event::L1_MESSAGE_EVENT_SIGNATURE,
+    fee::Fee,
     protocol_version::L1VerifierConfig,
     vm_trace::{Call, CallType},
     web3::types::{AccessList, Index, H2048},
-    Address, MiniblockNumber, ProtocolVersionId,
+    Address, MiniblockNumber, Nonce, PriorityOpId, ProtocolVersionId, L1_MESSENGER_ADDRESS,
 };
 
+pub mod analytics;
 pub mod en;
+mod raw;
+pub mod sealing;
+pub mod state_override;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -150,6 +161,21 @@ impl From<BlockIdVariant> for BlockId {
     }
 }
 
+/// Identifier of an L1 batch used in `zks_getProof`. Numbers (both integer and hex-encoded) are interpreted
+/// as L1 batch numbers, and tags are resolved to the latest L1 batch with the corresponding status.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum L1BatchIdVariant {
+    Number(L1BatchNumber),
+    Tag(BlockNumber),
+}
+
+impl From<L1BatchNumber> for L1BatchIdVariant {
+    fn from(number: L1BatchNumber) -> Self {
+        Self::Number(number)
+    }
+}
+
 /// Transaction variant
 ///
 /// Utility structure. Some Web3 API methods have to return a block with a list of either full
@@ -245,6 +271,9 @@ pub struct TransactionReceipt {
     /// L2 to L1 logs generated within this transaction.
     #[serde(rename = "l2ToL1Logs")]
     pub l2_to_l1_logs: Vec<L2ToL1Log>,
+    /// L2 to L1 messages sent by this transaction via the L1 messenger contract.
+    #[serde(rename = "l2ToL1Messages", default)]
+    pub l2_to_l1_messages: Vec<L2ToL1Message>,
     /// Status: either 1 (success) or 0 (failure).
     pub status: U64,
     /// State root.
@@ -443,6 +472,89 @@ pub struct L2ToL1Log {
     pub value: H256,
 }
 
+/// Availability of the inclusion proof for an L2-to-L1 message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L2ToL1MessageProofStatus {
+    /// The L1 batch with the message is not sealed yet, so the proof is not available.
+    Pending,
+    /// The L1 batch with the message is sealed, so the proof can be obtained using `zks_getL2ToL1LogProof`.
+    /// The batch is not executed on L1 yet, so the message cannot be consumed on L1.
+    Available,
+    /// The L1 batch with the message is executed on L1, so the message can be consumed on L1
+    /// (e.g., a withdrawal can be finalized).
+    Finalizable,
+}
+
+/// L2-to-L1 message sent via the L1 messenger contract, together with the data necessary to prove its inclusion on L1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2ToL1Message {
+    /// Address of the account that has sent the message.
+    pub sender: Address,
+    pub message: Bytes,
+    /// Keccak-256 hash of the message.
+    pub message_hash: H256,
+    /// Index of the L2-to-L1 log corresponding to the message among the logs of the transaction.
+    /// Can be passed to `zks_getL2ToL1LogProof` to get the message proof.
+    pub log_index_in_tx: U64,
+    /// Number of the L1 batch including the message. `None` if the batch is not sealed yet.
+    pub l1_batch_number: Option<U64>,
+    /// Index of the transaction within the L1 batch.
+    pub tx_index_in_l1_batch: Option<U64>,
+    /// Index of the message within the L1 batch, i.e., the index of its leaf in the L2-to-L1 logs Merkle tree
+    /// (the same as `id` in the proof). `None` if the batch is not sealed yet.
+    pub index_in_l1_batch: Option<U64>,
+    pub proof_status: L2ToL1MessageProofStatus,
+}
+
+impl L2ToL1Message {
+    /// Extracts messages sent by a transaction from its events and L2-to-L1 logs. The returned messages
+    /// do not have L1 batch-dependent data (`index_in_l1_batch` and `proof_status`) set.
+    pub fn extract_from_logs(logs: &[Log], l2_to_l1_logs: &[L2ToL1Log]) -> Vec<Self> {
+        let message_events = logs.iter().filter(|log| {
+            log.address == L1_MESSENGER_ADDRESS
+                && log.topics.len() == 3
+                && log.topics[0] == *L1_MESSAGE_EVENT_SIGNATURE
+        });
+        // Events and the corresponding logs are ordered in the same way, so we can match them in a single pass.
+        let mut l2_to_l1_logs = l2_to_l1_logs.iter();
+        let mut messages = vec![];
+        for event in message_events {
+            let (sender_topic, message_hash) = (event.topics[1], event.topics[2]);
+            let Ok(tokens) = ethabi::decode(&[ethabi::ParamType::Bytes], &event.data.0) else {
+                continue;
+            };
+            let Some(message) = tokens
+                .into_iter()
+                .next()
+                .and_then(ethabi::Token::into_bytes)
+            else {
+                continue;
+            };
+            let Some(l2_to_l1_log) = l2_to_l1_logs.find(|log| {
+                log.sender == L1_MESSENGER_ADDRESS
+                    && log.key == sender_topic
+                    && log.value == message_hash
+            }) else {
+                break;
+            };
+
+            messages.push(Self {
+                sender: h256_to_account_address(&sender_topic),
+                message: Bytes(message),
+                message_hash,
+                log_index_in_tx: l2_to_l1_log.transaction_log_index.as_u64().into(),
+                l1_batch_number: l2_to_l1_log.l1_batch_number,
+                tx_index_in_l1_batch: l2_to_l1_log.tx_index_in_l1_batch,
+                index_in_l1_batch: None,
+                proof_status: L2ToL1MessageProofStatus::Pending,
+            });
+        }
+        messages
+    }
+}
+
 /// Description of a Transaction, pending or in the chain.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Transaction {
@@ -530,6 +642,11 @@ pub enum TransactionStatus {
     Included,
     Verified,
     Failed,
+    /// Transaction was replaced by another transaction with the same initiator and nonce
+    /// before it was included into a block.
+    Replaced,
+    /// Transaction was not included into a block before its validity deadline and was dropped from the mempool.
+    Expired,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -546,6 +663,166 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Lifecycle stage of an L1 -> L2 priority operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityOpStage {
+    /// The operation is seen on L1 and waits in the mempool to be included into a miniblock.
+    Queued,
+    /// The operation is included into a miniblock, but its L1 batch is not committed on L1 yet.
+    Included,
+    /// The L1 batch with the operation is committed on L1.
+    Committed,
+    /// The L1 batch with the operation is proven on L1.
+    Proven,
+    /// The L1 batch with the operation is executed on L1, i.e., the operation is removed from the L1 priority queue.
+    Executed,
+}
+
+/// Status of an L1 -> L2 priority operation returned by `zks_getPriorityOpStatus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpStatus {
+    /// Hash of the L1 transaction that emitted the operation.
+    pub l1_tx_hash: H256,
+    /// Hash of the corresponding L2 transaction.
+    pub l2_tx_hash: H256,
+    pub priority_op_id: PriorityOpId,
+    /// Number of the L1 block with the emitting transaction.
+    pub l1_block_number: u64,
+    pub stage: PriorityOpStage,
+    pub miniblock_number: Option<MiniblockNumber>,
+    pub l1_batch_number: Option<L1BatchNumber>,
+    /// Reason the operation failed on L2, such as a revert reason. Failed operations still progress
+    /// through L1 batch stages since they must be removed from the L1 priority queue.
+    pub failure_reason: Option<String>,
+    pub eth_commit_tx_hash: Option<H256>,
+    pub eth_prove_tx_hash: Option<H256>,
+    pub eth_execute_tx_hash: Option<H256>,
+}
+
+/// Nonce information for an account returned by `zks_getAccountNonceDetails`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountNonceDetails {
+    /// Nonce as of the last L1 batch committed on L1.
+    pub committed_nonce: Nonce,
+    /// Nonce as of the latest sealed miniblock, i.e. taking into account all executed transactions.
+    pub latest_nonce: Nonce,
+    /// Nonce that should be used for the next transaction; same as `eth_getTransactionCount` for the pending block.
+    pub next_nonce: Nonce,
+    /// Nonces of transactions waiting in the mempool, in the ascending order.
+    pub pending_nonces: Vec<Nonce>,
+    /// Nonces missing between `latest_nonce` and the greatest pending nonce. Pending transactions after a gap
+    /// are not executed until the gap is filled (e.g., by resubmitting the dropped transactions).
+    pub nonce_gaps: Vec<Nonce>,
+}
+
+impl AccountNonceDetails {
+    /// Creates nonce details. `pending_nonces` must be sorted and not contain nonces less than `latest_nonce`.
+    pub fn new(committed_nonce: Nonce, latest_nonce: Nonce, pending_nonces: Vec<Nonce>) -> Self {
+        let mut nonce_gaps = vec![];
+        let mut expected_nonce = latest_nonce;
+        for &nonce in &pending_nonces {
+            if nonce >= expected_nonce {
+                nonce_gaps.extend((expected_nonce.0..nonce.0).map(Nonce));
+                expected_nonce = nonce + 1;
+            }
+        }
+        let next_nonce = nonce_gaps.first().copied().unwrap_or(expected_nonce);
+        Self {
+            committed_nonce,
+            latest_nonce,
+            next_nonce,
+            pending_nonces,
+            nonce_gaps,
+        }
+    }
+}
+
+/// Soft confirmation of a transaction: the transaction was executed by the state keeper and included
+/// into the pending miniblock, but the miniblock is not sealed yet. Thus, the miniblock hash is not known,
+/// and the transaction may still be lost if the node is restarted before sealing the miniblock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSoftConfirmation {
+    pub transaction_hash: H256,
+    /// Index of the transaction within the pending miniblock.
+    pub transaction_index: Index,
+    /// Number of the pending miniblock the transaction is included into.
+    pub block_number: U64,
+    /// Number of the pending L1 batch the transaction is included into.
+    pub l1_batch_number: U64,
+    pub from: Address,
+    pub to: Option<Address>,
+    /// Either 1 (success) or 0 (failure).
+    pub status: U64,
+    pub gas_used: U256,
+    /// Logs generated within this transaction. Block hashes of the logs are not set.
+    pub logs: Vec<Log>,
+    pub revert_reason: Option<String>,
+}
+
+/// Way a transaction has touched an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressActivityKind {
+    /// The address is the transaction initiator.
+    Sender,
+    /// The address is the transaction recipient, i.e. the contract called by the transaction.
+    Recipient,
+    /// The transaction has written to the storage of the address.
+    StorageWrite,
+    /// The address has emitted an event during the transaction.
+    EventEmitter,
+    /// The address has received ERC-20 tokens (including the base token) in the transaction,
+    /// as indicated by a `Transfer` event.
+    TransferRecipient,
+}
+
+/// Notification that an address was touched by a transaction in a sealed miniblock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressActivity {
+    pub address: Address,
+    pub block_number: U64,
+    pub block_hash: H256,
+    pub transaction_hash: H256,
+    /// Index of the transaction within the miniblock.
+    pub transaction_index: Index,
+    /// Ways the transaction has touched the address. Never empty.
+    pub kinds: Vec<AddressActivityKind>,
+}
+
+/// Options for `zks_estimateFee`. Uses the same field casing as [`FeeEstimate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimateOptions {
+    /// Whether to return [`FeeInclusionHint`] together with the estimated fee.
+    #[serde(default)]
+    pub inclusion_hint: bool,
+}
+
+/// Hint on how soon a transaction with the estimated fee is likely to be included, based on the capacity
+/// left in the pending L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeInclusionHint {
+    /// Whether the transaction is expected to fit into the pending L1 batch according to the seal criteria.
+    pub fits_pending_l1_batch: bool,
+    /// Seal criterion that would postpone the transaction to the next L1 batch, if any.
+    pub limiting_criterion: Option<String>,
+    /// Number of miniblocks within which the transaction is likely to be included.
+    pub likely_included_within_blocks: U64,
+}
+
+/// Fee estimate returned by `zks_estimateFee`. Serialized as [`Fee`] with an optional inclusion hint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    #[serde(flatten)]
+    pub fee: Fee,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inclusion_hint: Option<FeeInclusionHint>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
@@ -582,32 +859,109 @@ pub struct DebugCall {
     pub error: Option<String>,
     pub revert_reason: Option<String>,
     pub calls: Vec<DebugCall>,
+    /// Number of nested calls (at any depth) omitted from `calls` because of [`CallTraceLimits`].
+    /// `None` if no calls were omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated_calls: Option<usize>,
 }
 
-impl From<Call> for DebugCall {
-    fn from(value: Call) -> Self {
-        let calls = value.calls.into_iter().map(DebugCall::from).collect();
-        let debug_type = match value.r#type {
+/// Limits on the size of call traces returned by the debug API.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallTraceLimits {
+    /// Maximum nesting depth of returned calls; the top-level call has depth 0.
+    pub max_depth: usize,
+    /// Maximum number of returned nested calls, not counting the top-level call. Calls are retained
+    /// in the order of their execution.
+    pub max_calls: usize,
+}
+
+impl DebugCall {
+    /// Converts a call trace respecting the specified limits. Omitted calls are accounted
+    /// in [`Self::truncated_calls`] of their closest retained ancestor.
+    pub fn from_call_with_limits(call: Call, limits: CallTraceLimits) -> Self {
+        let mut remaining_calls = limits.max_calls;
+        Self::convert_with_limits(call, limits.max_depth, &mut remaining_calls)
+    }
+
+    fn convert_with_limits(
+        mut call: Call,
+        remaining_depth: usize,
+        remaining_calls: &mut usize,
+    ) -> Self {
+        let nested_calls = std::mem::take(&mut call.calls);
+        let mut truncated_calls = 0;
+        let mut calls = Vec::with_capacity(nested_calls.len().min(*remaining_calls));
+        for nested_call in nested_calls {
+            if remaining_depth == 0 || *remaining_calls == 0 {
+                truncated_calls += Self::count_calls(&nested_call);
+                continue;
+            }
+            *remaining_calls -= 1;
+            calls.push(Self::convert_with_limits(
+                nested_call,
+                remaining_depth - 1,
+                remaining_calls,
+            ));
+        }
+        let truncated_calls = (truncated_calls > 0).then_some(truncated_calls);
+        Self::new(call, calls, truncated_calls)
+    }
+
+    /// Counts the call together with all its nested calls.
+    fn count_calls(call: &Call) -> usize {
+        1 + call.calls.iter().map(Self::count_calls).sum::<usize>()
+    }
+
+    fn new(call: Call, calls: Vec<DebugCall>, truncated_calls: Option<usize>) -> Self {
+        let debug_type = match call.r#type {
             CallType::Call(_) => DebugCallType::Call,
             CallType::Create => DebugCallType::Create,
             CallType::NearCall => unreachable!("We have to filter our near calls before"),
         };
         Self {
             r#type: debug_type,
-            from: value.from,
-            to: value.to,
-            gas: U256::from(value.gas),
-            gas_used: U256::from(value.gas_used),
-            value: value.value,
-            output: Bytes::from(value.output.clone()),
-            input: Bytes::from(value.input.clone()),
-            error: value.error.clone(),
-            revert_reason: value.revert_reason,
+            from: call.from,
+            to: call.to,
+            gas: U256::from(call.gas),
+            gas_used: U256::from(call.gas_used),
+            value: call.value,
+            output: Bytes::from(call.output),
+            input: Bytes::from(call.input),
+            error: call.error,
+            revert_reason: call.revert_reason,
             calls,
+            truncated_calls,
         }
     }
 }
 
+impl From<Call> for DebugCall {
+    fn from(mut value: Call) -> Self {
+        let calls = std::mem::take(&mut value.calls)
+            .into_iter()
+            .map(DebugCall::from)
+            .collect();
+        Self::new(value, calls, None)
+    }
+}
+
+/// Storage slot returned by `debug_storageRangeAt`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StorageEntry {
+    pub key: H256,
+    pub value: H256,
+}
+
+/// Result of `debug_storageRangeAt`. Unlike Geth, slots are ordered and paginated by their keys rather than
+/// by key hashes; entries are keyed by the hashed storage key used in the Merkle tree.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    pub storage: BTreeMap<H256, StorageEntry>,
+    /// Key to start the next page from, or `None` if there are no more non-zero slots.
+    pub next_key: Option<H256>,
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 pub struct ProtocolVersion {
     /// Protocol version ID
@@ -679,6 +1033,24 @@ pub struct BlockDetails {
     pub protocol_version: Option<ProtocolVersionId>,
 }
 
+/// Fee model parameters used in a miniblock, returned by `zks_getFeeParamsAt`. Allows to reconstruct
+/// how fees of transactions in the miniblock were computed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeParams {
+    pub number: MiniblockNumber,
+    /// L1 gas price (in wei) assumed in the miniblock.
+    pub l1_gas_price: u64,
+    /// Fair L2 gas price (in wei) that the operator agrees on.
+    pub fair_l2_gas_price: u64,
+    /// Base fee (in wei) that transactions in the miniblock were charged.
+    pub base_fee_per_gas: u64,
+    /// Price (in wei) of publishing one byte of pubdata on L1.
+    pub fair_pubdata_price: u64,
+    /// Gas charged for each published byte of pubdata.
+    pub gas_per_pubdata: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct L1BatchDetails {
@@ -687,6 +1059,68 @@ pub struct L1BatchDetails {
     pub base: BlockDetailsBase,
 }
 
+/// Public inputs of the proof for an L1 batch returned by `zks_getBatchProofPublicInputs`, together with the data
+/// they are derived from. The batch commitment is `keccak256(passThroughDataHash ++ metaParametersHash ++ auxDataHash)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchProofPublicInputs {
+    pub number: L1BatchNumber,
+    pub prev_commitment: H256,
+    pub commitment: H256,
+    /// Public input checked by the L1 verifier. `None` for pre-boojum L1 batches, for which the public input
+    /// additionally depends on verifier parameters.
+    pub public_input: Option<U256>,
+    pub prev_root_hash: H256,
+    pub prev_rollup_last_leaf_index: u64,
+    pub root_hash: H256,
+    pub rollup_last_leaf_index: u64,
+    pub pass_through_data_hash: H256,
+    pub meta_parameters_hash: H256,
+    pub aux_data_hash: H256,
+    pub zkporter_is_available: bool,
+    pub bootloader_code_hash: H256,
+    pub default_aa_code_hash: H256,
+    /// Components of the auxiliary output; `None` for pre-boojum L1 batches.
+    pub system_logs_linear_hash: Option<H256>,
+    pub state_diffs_hash: Option<H256>,
+    pub bootloader_initial_content_commitment: Option<H256>,
+    pub events_queue_commitment: Option<H256>,
+}
+
+impl L1BatchProofPublicInputs {
+    pub fn new(prev_l1_batch: &L1BatchWithMetadata, l1_batch: &L1BatchWithMetadata) -> Self {
+        let metadata = &l1_batch.metadata;
+        let prev_commitment = prev_l1_batch.metadata.commitment;
+        let is_pre_boojum = l1_batch
+            .header
+            .protocol_version
+            .map_or(true, |version| version.is_pre_boojum());
+        let public_input = (!is_pre_boojum)
+            .then(|| l1_batch_proof_public_input(prev_commitment, metadata.commitment));
+
+        Self {
+            number: l1_batch.header.number,
+            prev_commitment,
+            commitment: metadata.commitment,
+            public_input,
+            prev_root_hash: prev_l1_batch.metadata.root_hash,
+            prev_rollup_last_leaf_index: prev_l1_batch.metadata.rollup_last_leaf_index,
+            root_hash: metadata.root_hash,
+            rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+            pass_through_data_hash: metadata.pass_through_data_hash,
+            meta_parameters_hash: metadata.meta_parameters_hash,
+            aux_data_hash: metadata.aux_data_hash,
+            zkporter_is_available: metadata.block_meta_params.zkporter_is_available,
+            bootloader_code_hash: metadata.block_meta_params.bootloader_code_hash,
+            default_aa_code_hash: metadata.block_meta_params.default_aa_code_hash,
+            system_logs_linear_hash: (!is_pre_boojum).then(|| l1_batch.system_logs_linear_hash()),
+            state_diffs_hash: l1_batch.state_diffs_hash(),
+            bootloader_initial_content_commitment: metadata.bootloader_initial_content_commitment,
+            events_queue_commitment: metadata.events_queue_commitment,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
@@ -702,3 +1136,193 @@ pub struct Proof {
     pub address: Address,
     pub storage_proof: Vec<StorageProof>,
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::address_to_h256;
+
+    use super::*;
+    use crate::web3::signing::keccak256;
+
+    fn mock_l2_to_l1_log(transaction_log_index: u32, key: H256, value: H256) -> L2ToL1Log {
+        L2ToL1Log {
+            block_hash: None,
+            block_number: 1.into(),
+            l1_batch_number: None,
+            log_index: transaction_log_index.into(),
+            transaction_index: 0.into(),
+            transaction_hash: H256::repeat_byte(1),
+            transaction_log_index: transaction_log_index.into(),
+            tx_index_in_l1_batch: Some(3.into()),
+            shard_id: 0.into(),
+            is_service: true,
+            sender: L1_MESSENGER_ADDRESS,
+            key,
+            value,
+        }
+    }
+
+    fn mock_message_event(sender: Address, message: &[u8]) -> Log {
+        Log {
+            address: L1_MESSENGER_ADDRESS,
+            topics: vec![
+                *L1_MESSAGE_EVENT_SIGNATURE,
+                address_to_h256(&sender),
+                keccak256(message).into(),
+            ],
+            data: Bytes(ethabi::encode(&[ethabi::Token::Bytes(message.to_vec())])),
+            block_hash: None,
+            block_number: Some(1.into()),
+            l1_batch_number: None,
+            transaction_hash: Some(H256::repeat_byte(1)),
+            transaction_index: Some(0.into()),
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn extracting_l2_to_l1_messages() {
+        let sender = Address::repeat_byte(0x11);
+        let message = b"withdrawal";
+        let message_hash = H256(keccak256(message));
+        let other_event = Log {
+            address: Address::repeat_byte(0x22),
+            ..mock_message_event(sender, b"other")
+        };
+        let logs = [other_event, mock_message_event(sender, message)];
+        let l2_to_l1_logs = [
+            mock_l2_to_l1_log(0, H256::repeat_byte(0x33), H256::zero()),
+            mock_l2_to_l1_log(1, address_to_h256(&sender), message_hash),
+        ];
+
+        let messages = L2ToL1Message::extract_from_logs(&logs, &l2_to_l1_logs);
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(message.sender, sender);
+        assert_eq!(message.message.0, b"withdrawal");
+        assert_eq!(message.message_hash, message_hash);
+        assert_eq!(message.log_index_in_tx, 1.into());
+        assert_eq!(message.tx_index_in_l1_batch, Some(3.into()));
+        assert_eq!(message.proof_status, L2ToL1MessageProofStatus::Pending);
+
+        // Messages without a matching L2-to-L1 log are skipped.
+        let messages = L2ToL1Message::extract_from_logs(&logs, &l2_to_l1_logs[..1]);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn computing_nonce_gaps() {
+        let nonces = |values: &[u32]| values.iter().copied().map(Nonce).collect::<Vec<_>>();
+
+        let details = AccountNonceDetails::new(Nonce(3), Nonce(5), vec![]);
+        assert_eq!(details.next_nonce, Nonce(5));
+        assert!(details.nonce_gaps.is_empty());
+
+        let details = AccountNonceDetails::new(Nonce(3), Nonce(5), nonces(&[5, 6, 9, 11]));
+        assert_eq!(details.next_nonce, Nonce(7));
+        assert_eq!(details.nonce_gaps, nonces(&[7, 8, 10]));
+
+        let details = AccountNonceDetails::new(Nonce(5), Nonce(5), nonces(&[7]));
+        assert_eq!(details.next_nonce, Nonce(5));
+        assert_eq!(details.nonce_gaps, nonces(&[5, 6]));
+    }
+
+    #[test]
+    fn fee_estimate_serialization() {
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        let estimate = FeeEstimate {
+            fee: fee.clone(),
+            inclusion_hint: None,
+        };
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json, serde_json::to_value(&fee).unwrap());
+
+        let estimate = FeeEstimate {
+            fee,
+            inclusion_hint: Some(FeeInclusionHint {
+                fits_pending_l1_batch: false,
+                limiting_criterion: Some("pub_data_size".to_owned()),
+                likely_included_within_blocks: 3.into(),
+            }),
+        };
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json["gas_limit"], "0xf4240");
+        assert_eq!(json["inclusion_hint"]["fits_pending_l1_batch"], false);
+        let restored: FeeEstimate = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, estimate);
+
+        let options: FeeEstimateOptions =
+            serde_json::from_value(serde_json::json!({ "inclusion_hint": true })).unwrap();
+        assert!(options.inclusion_hint);
+    }
+
+    fn mock_call(to: u64, calls: Vec<Call>) -> Call {
+        Call {
+            to: Address::from_low_u64_be(to),
+            calls,
+            ..Call::default()
+        }
+    }
+
+    #[test]
+    fn converting_call_trace_with_limits() {
+        // 1 -> (2 -> (3 -> 4), 5 -> 6, 7)
+        let call = mock_call(
+            1,
+            vec![
+                mock_call(2, vec![mock_call(3, vec![mock_call(4, vec![])])]),
+                mock_call(5, vec![mock_call(6, vec![])]),
+                mock_call(7, vec![]),
+            ],
+        );
+
+        let unlimited = CallTraceLimits {
+            max_depth: usize::MAX,
+            max_calls: usize::MAX,
+        };
+        let debug_call = DebugCall::from_call_with_limits(call.clone(), unlimited);
+        assert_eq!(debug_call.truncated_calls, None);
+        let json = serde_json::to_value(&debug_call).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_value(DebugCall::from(call.clone())).unwrap()
+        );
+        assert!(json.get("truncatedCalls").is_none());
+
+        let limits = CallTraceLimits {
+            max_depth: 1,
+            max_calls: usize::MAX,
+        };
+        let debug_call = DebugCall::from_call_with_limits(call.clone(), limits);
+        assert_eq!(debug_call.calls.len(), 3);
+        assert_eq!(debug_call.truncated_calls, None);
+        assert_eq!(debug_call.calls[0].truncated_calls, Some(2));
+        assert_eq!(debug_call.calls[1].truncated_calls, Some(1));
+        assert_eq!(debug_call.calls[2].truncated_calls, None);
+        assert!(debug_call.calls.iter().all(|call| call.calls.is_empty()));
+
+        let limits = CallTraceLimits {
+            max_depth: usize::MAX,
+            max_calls: 3,
+        };
+        let debug_call = DebugCall::from_call_with_limits(call, limits);
+        let nested_call = &debug_call.calls[0];
+        assert_eq!(nested_call.to, Address::from_low_u64_be(2));
+        assert_eq!(
+            nested_call.calls[0].calls[0].to,
+            Address::from_low_u64_be(4)
+        );
+        assert_eq!(debug_call.calls.len(), 1);
+        assert_eq!(debug_call.truncated_calls, Some(3));
+        let json = serde_json::to_value(&debug_call).unwrap();
+        assert_eq!(json["truncatedCalls"], 3);
+    }
+}
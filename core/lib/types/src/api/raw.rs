@@ -0,0 +1,139 @@
+//! Raw (RLP) encodings of API types returned by `debug_getRaw*` methods.
+
+use rlp::RlpStream;
+use zksync_basic_types::web3::types::Bytes;
+
+use super::{Block, Log, TransactionReceipt};
+
+impl Log {
+    fn rlp_append(&self, rlp: &mut RlpStream) {
+        rlp.begin_list(3);
+        rlp.append(&self.address);
+        rlp.append_list(&self.topics);
+        rlp.append(&self.data.0);
+    }
+}
+
+impl TransactionReceipt {
+    /// Encodes this receipt in the EIP-2718 format: `rlp([status, cumulativeGasUsed, logsBloom, logs])`,
+    /// prefixed with the transaction type unless it is a legacy transaction.
+    pub fn raw_encoding(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(4);
+        rlp.append(&self.status.as_u64());
+        rlp.append(&self.cumulative_gas_used);
+        rlp.append(&self.logs_bloom.as_bytes());
+        rlp.begin_list(self.logs.len());
+        for log in &self.logs {
+            log.rlp_append(&mut rlp);
+        }
+
+        let tx_type = self.transaction_type.map_or(0, |ty| ty.as_u64());
+        let mut encoding = Vec::new();
+        if tx_type != 0 {
+            encoding.push(tx_type as u8);
+        }
+        encoding.extend_from_slice(&rlp.out());
+        Bytes(encoding)
+    }
+}
+
+impl<TX> Block<TX> {
+    /// Encodes this block as `rlp([header, transactions, uncles])` similarly to Ethereum blocks.
+    /// The header fields follow the Ethereum order. `raw_transactions` are included into the
+    /// transaction list as byte strings in the order they are provided.
+    pub fn raw_encoding(&self, raw_transactions: &[Bytes]) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(3);
+
+        rlp.begin_list(16);
+        rlp.append(&self.parent_hash);
+        rlp.append(&self.uncles_hash);
+        rlp.append(&self.author);
+        rlp.append(&self.state_root);
+        rlp.append(&self.transactions_root);
+        rlp.append(&self.receipts_root);
+        rlp.append(&self.logs_bloom.as_bytes());
+        rlp.append(&self.difficulty);
+        rlp.append(&self.number.as_u64());
+        rlp.append(&self.gas_limit);
+        rlp.append(&self.gas_used);
+        rlp.append(&self.timestamp);
+        rlp.append(&self.extra_data.0);
+        rlp.append(&self.mix_hash);
+        rlp.append(&self.nonce.as_bytes());
+        rlp.append(&self.base_fee_per_gas);
+
+        rlp.begin_list(raw_transactions.len());
+        for tx in raw_transactions {
+            rlp.append(&tx.0);
+        }
+        rlp.append_list(&self.uncles);
+        Bytes(rlp.out().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rlp::Rlp;
+
+    use super::*;
+    use crate::{Address, H256, U64};
+
+    #[test]
+    fn receipt_encoding_has_type_prefix() {
+        let log = Log {
+            address: Address::repeat_byte(1),
+            topics: vec![H256::repeat_byte(2)],
+            data: Bytes(vec![3; 10]),
+            block_hash: None,
+            block_number: None,
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+        let mut receipt = TransactionReceipt {
+            status: U64::one(),
+            logs: vec![log],
+            transaction_type: Some(U64::from(2)),
+            ..TransactionReceipt::default()
+        };
+
+        let encoding = receipt.raw_encoding();
+        assert_eq!(encoding.0[0], 2);
+        let decoded = Rlp::new(&encoding.0[1..]);
+        assert_eq!(decoded.item_count().unwrap(), 4);
+        assert_eq!(decoded.val_at::<u64>(0).unwrap(), 1);
+        let logs = decoded.at(3).unwrap();
+        assert_eq!(logs.item_count().unwrap(), 1);
+        let address: Address = logs.at(0).unwrap().val_at(0).unwrap();
+        assert_eq!(address, Address::repeat_byte(1));
+
+        receipt.transaction_type = Some(U64::zero());
+        let legacy_encoding = receipt.raw_encoding();
+        assert_eq!(legacy_encoding.0, &encoding.0[1..]);
+    }
+
+    #[test]
+    fn block_encoding_contains_transactions() {
+        let block = Block::<()> {
+            number: U64::from(5),
+            ..Block::default()
+        };
+        let raw_transactions = [Bytes(vec![1, 2, 3]), Bytes(vec![4, 5])];
+        let encoding = block.raw_encoding(&raw_transactions);
+
+        let decoded = Rlp::new(&encoding.0);
+        assert_eq!(decoded.item_count().unwrap(), 3);
+        let header = decoded.at(0).unwrap();
+        assert_eq!(header.item_count().unwrap(), 16);
+        assert_eq!(header.val_at::<u64>(8).unwrap(), 5);
+        let transactions: Vec<Vec<u8>> = decoded.list_at(1).unwrap();
+        assert_eq!(transactions, [vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(decoded.at(2).unwrap().item_count().unwrap(), 0);
+    }
+}
@@ -0,0 +1,61 @@
+//! API types related to L1 batch sealing, used by operators to inspect the pending L1 batch.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::L1BatchNumber;
+
+/// Usage of a seal criterion limit by an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SealCriterionStatus {
+    /// Criterion name, the same as used in logs and metrics (e.g., `gas` or `pub_data_size`).
+    pub criterion: String,
+    /// Value of the limited quantity for the L1 batch, e.g. the number of pubdata bytes.
+    pub used: u64,
+    /// Hard limit for the L1 batch. Transactions that would make the batch exceed this limit are postponed
+    /// to the next batch.
+    pub limit: u64,
+    /// Value after exceeding which the L1 batch is sealed.
+    pub seal_threshold: u64,
+    /// `used` as a percentage of `seal_threshold`; the batch is sealed when this value reaches 100.
+    pub threshold_percentage: f64,
+}
+
+/// Seal criteria status of the pending L1 batch returned by `unstable_getSealCriteriaStatus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchSealStatus {
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of transactions in the L1 batch.
+    pub tx_count: u64,
+    /// Status of the built-in seal criteria that have a limit applicable to the batch.
+    pub criteria: Vec<SealCriterionStatus>,
+}
+
+/// Decision on sealing an L1 batch after executing a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulatedSealResolution {
+    /// The transaction is included and the batch is kept open.
+    NoSeal,
+    /// The transaction is included and the batch is sealed after it.
+    IncludeAndSeal,
+    /// The transaction is postponed to the next batch, and the batch is sealed without it.
+    ExcludeAndSeal,
+    /// The transaction is rejected since it cannot be included into any batch.
+    Unexecutable,
+}
+
+/// Effect of adding a transaction to the pending L1 batch returned by `unstable_simulateSealCriteria`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SealSimulation {
+    pub resolution: SimulatedSealResolution,
+    /// Criteria that have triggered sealing or rejection of the transaction.
+    pub triggered_criteria: Vec<String>,
+    /// Reason why the transaction is unexecutable, if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unexecutable_reason: Option<String>,
+    /// Status of the pending L1 batch with the transaction included (or without it if the transaction
+    /// is unexecutable).
+    pub batch: L1BatchSealStatus,
+}
@@ -0,0 +1,151 @@
+//! Geth-style state overrides for `eth_call` and `eth_estimateGas`.
+
+use std::collections::HashMap;
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+use zksync_basic_types::{web3::types::Bytes, Address, H256, U256};
+use zksync_utils::bytecode::validate_bytecode;
+
+/// Collection of overridden accounts, keyed by the account address.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateOverride(HashMap<Address, OverrideAccount>);
+
+impl StateOverride {
+    pub fn new(accounts: HashMap<Address, OverrideAccount>) -> Self {
+        Self(accounts)
+    }
+
+    pub fn get(&self, address: &Address) -> Option<&OverrideAccount> {
+        self.0.get(address)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Address, &OverrideAccount)> + '_ {
+        self.0.iter()
+    }
+}
+
+/// Overrides for a single account. All fields are optional; unspecified fields are not overridden.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    pub balance: Option<U256>,
+    /// Transaction nonce of the account. The deployment nonce is not overridden.
+    pub nonce: Option<U256>,
+    /// zkEVM bytecode of the account. Must be a valid bytecode (i.e., consist of an odd number of 32-byte words).
+    pub code: Option<Bytes>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub state: Option<OverrideState>,
+}
+
+/// Storage overrides for an account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverrideState {
+    /// Replaces the entire account storage; slots not mentioned are read as zeros.
+    State(HashMap<H256, H256>),
+    /// Overrides the specified storage slots, leaving the other slots intact.
+    StateDiff(HashMap<H256, H256>),
+}
+
+impl<'de> Deserialize<'de> for OverrideAccount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase", deny_unknown_fields)]
+        struct RawOverrideAccount {
+            balance: Option<U256>,
+            nonce: Option<U256>,
+            code: Option<Bytes>,
+            state: Option<HashMap<H256, H256>>,
+            state_diff: Option<HashMap<H256, H256>>,
+        }
+
+        let raw = RawOverrideAccount::deserialize(deserializer)?;
+        if let Some(code) = &raw.code {
+            validate_bytecode(&code.0)
+                .map_err(|err| de::Error::custom(format!("invalid account code: {err}")))?;
+        }
+        let state = match (raw.state, raw.state_diff) {
+            (Some(_), Some(_)) => {
+                return Err(de::Error::custom(
+                    "account cannot have both `state` and `stateDiff` overrides",
+                ));
+            }
+            (Some(state), None) => Some(OverrideState::State(state)),
+            (None, Some(state_diff)) => Some(OverrideState::StateDiff(state_diff)),
+            (None, None) => None,
+        };
+        Ok(Self {
+            balance: raw.balance,
+            nonce: raw.nonce,
+            code: raw.code,
+            state,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializing_state_override() {
+        let json = serde_json::json!({
+            "0x0101010101010101010101010101010101010101": {
+                "balance": "0x1000",
+                "nonce": "0x3",
+                "stateDiff": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001":
+                        "0x0000000000000000000000000000000000000000000000000000000000000002",
+                },
+            },
+            "0x0202020202020202020202020202020202020202": {
+                "code": format!("0x{}", "00".repeat(32)),
+                "state": {},
+            },
+        });
+        let state_override: StateOverride = serde_json::from_value(json.clone()).unwrap();
+
+        let account = state_override.get(&Address::repeat_byte(1)).unwrap();
+        assert_eq!(account.balance, Some(0x1000.into()));
+        assert_eq!(account.nonce, Some(3.into()));
+        assert_eq!(account.code, None);
+        assert_eq!(
+            account.state,
+            Some(OverrideState::StateDiff(HashMap::from([(
+                H256::from_low_u64_be(1),
+                H256::from_low_u64_be(2)
+            )])))
+        );
+        let account = state_override.get(&Address::repeat_byte(2)).unwrap();
+        assert_eq!(account.code, Some(Bytes(vec![0; 32])));
+        assert_eq!(account.state, Some(OverrideState::State(HashMap::new())));
+
+        let serialized = serde_json::to_value(&state_override).unwrap();
+        let roundtrip: StateOverride = serde_json::from_value(serialized).unwrap();
+        assert_eq!(roundtrip, state_override);
+    }
+
+    #[test]
+    fn invalid_state_overrides() {
+        let json = serde_json::json!({
+            "0x0101010101010101010101010101010101010101": {
+                "state": {},
+                "stateDiff": {},
+            },
+        });
+        let err = serde_json::from_value::<StateOverride>(json).unwrap_err();
+        assert!(
+            err.to_string().contains("both `state` and `stateDiff`"),
+            "{err}"
+        );
+
+        // Even number of 32-byte words
+        let json = serde_json::json!({
+            "0x0101010101010101010101010101010101010101": {
+                "code": format!("0x{}", "00".repeat(64)),
+            },
+        });
+        let err = serde_json::from_value::<StateOverride>(json).unwrap_err();
+        assert!(err.to_string().contains("invalid account code"), "{err}");
+    }
+}
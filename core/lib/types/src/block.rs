@@ -68,6 +68,15 @@ pub struct L1BatchHeader {
     pub pubdata_input: Option<Vec<u8>>,
 }
 
+/// Subset of the L1 batch metadata produced by the Merkle tree.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchTreeData {
+    /// Root hash of the Merkle tree after the L1 batch.
+    pub hash: H256,
+    /// 1-based index of the next leaf to be inserted in the tree after the L1 batch.
+    pub rollup_last_leaf_index: u64,
+}
+
 /// Holder for the miniblock metadata that is not available from transactions themselves.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MiniblockHeader {
@@ -80,6 +89,9 @@ pub struct MiniblockHeader {
 
     pub l1_gas_price: u64, // L1 gas price assumed in the corresponding batch
     pub l2_fair_gas_price: u64, // L2 gas price assumed in the corresponding batch
+    /// Gas per pubdata byte used by the bootloader in this miniblock. `None` for miniblocks sealed
+    /// before this value was persisted.
+    pub gas_per_pubdata: Option<u64>,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
     pub protocol_version: Option<ProtocolVersionId>,
     /// The maximal number of virtual blocks to be created in the miniblock.
@@ -87,7 +99,7 @@ pub struct MiniblockHeader {
 }
 
 /// Data needed to execute a miniblock in the VM.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiniblockExecutionData {
     pub number: MiniblockNumber,
     pub timestamp: u64,
@@ -8,7 +8,9 @@
 
 use std::{collections::HashMap, convert::TryFrom};
 
+use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
+pub use zksync_basic_types::commitment::L1BatchCommitmentMode;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::{
     L2_TO_L1_LOGS_TREE_ROOT_KEY, STATE_DIFF_HASH_KEY, ZKPORTER_IS_AVAILABLE,
@@ -150,6 +152,42 @@ impl L1BatchWithMetadata {
     }
 
     pub fn l1_commit_data(&self) -> Token {
+        self.l1_commit_data_inner(None)
+    }
+
+    /// Returns commit data for the specified commitment mode. In the validium mode, the batch pubdata
+    /// is not published, i.e., it's replaced with empty bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the validium mode is requested for a batch with a pre-boojum protocol version.
+    pub fn l1_commit_data_for_mode(&self, mode: L1BatchCommitmentMode) -> anyhow::Result<Token> {
+        match mode {
+            L1BatchCommitmentMode::Rollup => Ok(self.l1_commit_data()),
+            L1BatchCommitmentMode::Validium => self.l1_commit_data_with_da_inclusion(&[]),
+        }
+    }
+
+    /// Same as [`Self::l1_commit_data()`], but the batch pubdata is replaced with the provided inclusion data
+    /// of the pubdata blob in an external data availability layer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch has a pre-boojum protocol version, which doesn't support validium mode.
+    pub fn l1_commit_data_with_da_inclusion(&self, inclusion_data: &[u8]) -> anyhow::Result<Token> {
+        let protocol_version = self
+            .header
+            .protocol_version
+            .with_context(|| format!("L1 batch #{} has no protocol version", self.header.number))?;
+        anyhow::ensure!(
+            !protocol_version.is_pre_boojum(),
+            "L1 batch #{} has a pre-boojum protocol version, which doesn't support validium mode",
+            self.header.number
+        );
+        Ok(self.l1_commit_data_inner(Some(inclusion_data)))
+    }
+
+    fn l1_commit_data_inner(&self, da_inclusion_data: Option<&[u8]>) -> Token {
         if self.header.protocol_version.unwrap().is_pre_boojum() {
             Token::Tuple(vec![
                 Token::Uint(U256::from(self.header.number.0)),
@@ -209,12 +247,14 @@ impl L1BatchWithMetadata {
                         .to_vec(),
                 ),
                 Token::Bytes(self.metadata.l2_l1_messages_compressed.clone()),
-                Token::Bytes(
-                    self.header
+                Token::Bytes(match da_inclusion_data {
+                    Some(inclusion_data) => inclusion_data.to_vec(),
+                    None => self
+                        .header
                         .pubdata_input
                         .clone()
                         .unwrap_or(self.construct_pubdata()),
-                ),
+                }),
             ])
         }
     }
@@ -223,6 +263,19 @@ impl L1BatchWithMetadata {
         crate::ethabi::encode(&[Token::Array(vec![self.l1_commit_data()])]).len()
     }
 
+    /// Same as [`Self::l1_commit_data_size()`], but for the commit data in the specified commitment mode.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from [`Self::l1_commit_data_for_mode()`].
+    pub fn l1_commit_data_size_for_mode(
+        &self,
+        mode: L1BatchCommitmentMode,
+    ) -> anyhow::Result<usize> {
+        let commit_data = self.l1_commit_data_for_mode(mode)?;
+        Ok(crate::ethabi::encode(&[Token::Array(vec![commit_data])]).len())
+    }
+
     /// Packs all pubdata needed for batch commitment in boojum into one bytes array. The packing contains the
     /// following: logs, messages, bytecodes, and compressed state diffs.
     /// This data is currently part of calldata but will be submitted as part of the blob section post EIP-4844.
@@ -254,6 +307,33 @@ impl L1BatchWithMetadata {
 
         res
     }
+
+    /// Returns the linear hash of system logs emitted in this batch, which is a part of the auxiliary output
+    /// for post-boojum batches.
+    pub fn system_logs_linear_hash(&self) -> H256 {
+        H256(keccak256(&serialize_commitments(&self.header.system_logs)))
+    }
+
+    /// Returns the state diffs hash reported in system logs, or `None` if the batch has no system logs
+    /// (i.e., is pre-boojum).
+    pub fn state_diffs_hash(&self) -> Option<H256> {
+        let state_diffs_hash_key = u256_to_h256(STATE_DIFF_HASH_KEY.into());
+        self.header.system_logs.iter().find_map(|log| {
+            let log = &log.0;
+            (log.key == state_diffs_hash_key).then_some(log.value)
+        })
+    }
+}
+
+/// Number of bits the public input of an L1 batch proof is shifted by, so that it fits into the proof system field.
+const PUBLIC_INPUT_SHIFT: usize = 32;
+
+/// Computes the public input of a post-boojum L1 batch proof in the same way as the L1 verifier does.
+pub fn l1_batch_proof_public_input(prev_commitment: H256, commitment: H256) -> U256 {
+    let mut input = [0_u8; 64];
+    input[..32].copy_from_slice(prev_commitment.as_bytes());
+    input[32..].copy_from_slice(commitment.as_bytes());
+    U256::from_big_endian(&keccak256(&input)) >> PUBLIC_INPUT_SHIFT
 }
 
 impl SerializeCommitment for L2ToL1Log {
@@ -672,10 +752,11 @@ mod tests {
 
     use crate::{
         commitment::{
-            L1BatchAuxiliaryOutput, L1BatchCommitment, L1BatchMetaParameters,
-            L1BatchPassThroughData,
+            l1_batch_proof_public_input, L1BatchAuxiliaryOutput, L1BatchCommitment,
+            L1BatchMetaParameters, L1BatchPassThroughData,
         },
         l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+        web3::signing::keccak256,
         writes::{InitialStorageWrite, RepeatedStorageWrite},
         H256, U256,
     };
@@ -840,4 +921,17 @@ mod tests {
             commitment_test.expected_outputs.commitment_hash
         );
     }
+
+    #[test]
+    fn computing_proof_public_input() {
+        let prev_commitment = H256::repeat_byte(1);
+        let commitment = H256::repeat_byte(2);
+        let public_input = l1_batch_proof_public_input(prev_commitment, commitment);
+
+        let hash = keccak256(&[[1_u8; 32], [2_u8; 32]].concat());
+        let mut expected_bytes = [0_u8; 32];
+        expected_bytes[4..].copy_from_slice(&hash[..28]);
+        assert_eq!(public_input, U256::from_big_endian(&expected_bytes));
+        assert!(public_input < U256::one() << 224);
+    }
 }
@@ -235,7 +235,11 @@ pub struct VerificationRequestStatus {
 
 #[derive(Debug)]
 pub enum DeployContractCalldata {
+    /// Calldata of a transaction calling the contract deployer directly.
     Deploy(Vec<u8>),
+    /// Constructor calldata of a contract deployed by another contract (e.g., a factory). Extracted
+    /// from the call trace of the deployment transaction.
+    FactoryDeploy(Vec<u8>),
     Ignore,
 }
 
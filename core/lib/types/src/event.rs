@@ -44,7 +44,7 @@ pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
-static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+pub(crate) static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "L1MessageSent",
         &[
@@ -44,10 +44,12 @@ pub mod l2;
 pub mod l2_to_l1_log;
 pub mod priority_op_onchain_data;
 pub mod protocol_version;
+pub mod pubdata;
 pub mod snapshots;
 pub mod storage;
 pub mod storage_writes_deduplicator;
 pub mod system_contracts;
+pub mod tee_types;
 pub mod tokens;
 pub mod tx;
 pub mod vm_trace;
@@ -4,9 +4,10 @@ use std::{
     net::IpAddr,
     ops::Add,
     str::FromStr,
+    time::Duration,
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
 use zkevm_test_harness::{
@@ -202,24 +203,39 @@ impl PrepareBasicCircuitsJob {
         self.merkle_paths.push(path);
     }
 
-    /// Converts this job into an iterator over the contained Merkle paths.
+    /// Converts this job into an iterator over the contained Merkle paths. Paths are decompressed lazily,
+    /// so that only the compressed paths are kept in memory.
     pub fn into_merkle_paths(self) -> impl ExactSizeIterator<Item = StorageLogMetadata> {
-        let mut merkle_paths = self.merkle_paths;
-        if let [first, rest @ ..] = merkle_paths.as_mut_slice() {
-            for path in rest {
-                assert!(
-                    path.merkle_paths.len() <= first.merkle_paths.len(),
-                    "Merkle paths in `PrepareBasicCircuitsJob` are malformed; the first path is not \
-                     the longest one"
-                );
-                let spliced_len = first.merkle_paths.len() - path.merkle_paths.len();
-                let spliced_hashes = &first.merkle_paths[0..spliced_len];
-                path.merkle_paths
-                    .splice(0..0, spliced_hashes.iter().cloned());
-                debug_assert_eq!(path.merkle_paths.len(), first.merkle_paths.len());
-            }
-        }
-        merkle_paths.into_iter()
+        let (first_path, merkle_paths) = self.into_compressed_merkle_paths();
+        merkle_paths.into_iter().map(move |mut path| {
+            assert!(
+                path.merkle_paths.len() <= first_path.len(),
+                "Merkle paths in `PrepareBasicCircuitsJob` are malformed; the first path is not \
+                 the longest one"
+            );
+            Self::decompress_merkle_path(&first_path, &mut path);
+            debug_assert_eq!(path.merkle_paths.len(), first_path.len());
+            path
+        })
+    }
+
+    /// Converts this job into the full Merkle path of the first operation and compressed Merkle paths
+    /// for all operations. Paths can be decompressed one by one using [`Self::decompress_merkle_path()`].
+    pub fn into_compressed_merkle_paths(self) -> (Vec<[u8; HASH_LEN]>, Vec<StorageLogMetadata>) {
+        let first_path = self
+            .merkle_paths
+            .first()
+            .map(|path| path.merkle_paths.clone())
+            .unwrap_or_default();
+        (first_path, self.merkle_paths)
+    }
+
+    /// Restores a Merkle path compressed by [`Self::push_merkle_path()`] using the full Merkle path
+    /// of the first operation in the job. Paths that are not shorter than the first path are left as is.
+    pub fn decompress_merkle_path(first_path: &[[u8; HASH_LEN]], path: &mut StorageLogMetadata) {
+        let spliced_len = first_path.len().saturating_sub(path.merkle_paths.len());
+        path.merkle_paths
+            .splice(0..0, first_path[..spliced_len].iter().copied());
     }
 }
 
@@ -418,6 +434,8 @@ pub struct JobCountStatistics {
     pub in_progress: usize,
     pub failed: usize,
     pub successful: usize,
+    /// Jobs that have exhausted their attempts and are not retried until released by the operator.
+    pub quarantined: usize,
 }
 
 impl Add for JobCountStatistics {
@@ -429,6 +447,7 @@ impl Add for JobCountStatistics {
             in_progress: self.in_progress + rhs.in_progress,
             failed: self.failed + rhs.failed,
             successful: self.successful + rhs.successful,
+            quarantined: self.quarantined + rhs.quarantined,
         }
     }
 }
@@ -440,6 +459,34 @@ pub struct StuckJobs {
     pub attempts: u64,
 }
 
+/// Exponential backoff for retrying failed jobs: a job that failed `n` times is retried no earlier than
+/// `min(base * 2^(n - 1), max)` after the last failure.
+#[derive(Debug, Clone, Copy)]
+pub struct JobRetryBackoff {
+    pub base: Duration,
+    pub max: Duration,
+}
+
+/// Status and timings of an FRI prover job.
+#[derive(Debug, Clone, Serialize)]
+pub struct FriProverJobInfo {
+    pub id: u32,
+    pub l1_batch_number: L1BatchNumber,
+    pub circuit_id: u8,
+    pub aggregation_round: AggregationRound,
+    pub sequence_number: usize,
+    pub depth: u16,
+    pub status: String,
+    pub attempts: u8,
+    pub error: Option<String>,
+    pub picked_by: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub processing_started_at: Option<NaiveDateTime>,
+    /// Time taken to generate the proof; only set for successful jobs.
+    pub time_taken: Option<NaiveTime>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SocketAddress {
     pub host: IpAddr,
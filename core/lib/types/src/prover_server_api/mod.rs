@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::L1BatchNumber;
+use zksync_basic_types::{web3::signing::keccak256, L1BatchNumber, H256};
 
 use crate::{
-    aggregated_operations::L1BatchProofForL1,
+    aggregated_operations::{FflonkL1BatchProofForL1, L1BatchProof, L1BatchProofForL1},
     proofs::PrepareBasicCircuitsJob,
     protocol_version::{FriProtocolVersionId, L1VerifierConfig},
+    tee_types::{TeeType, TeeVerifierInput},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,6 +14,10 @@ pub struct ProofGenerationData {
     pub data: PrepareBasicCircuitsJob,
     pub fri_protocol_version_id: FriProtocolVersionId,
     pub l1_verifier_config: L1VerifierConfig,
+    /// Whether the batch was marked as high-priority by the operator, so that its witness jobs
+    /// should be processed ahead of other jobs.
+    #[serde(default)]
+    pub is_high_priority: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,12 +32,197 @@ pub enum ProofGenerationDataResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum SubmitProofRequest {
     Proof(Box<L1BatchProofForL1>),
+    /// Proof compressed with the FFLONK backend.
+    FflonkProof(Box<FflonkL1BatchProofForL1>),
     // The proof generation was skipped due to sampling
     SkippedProofGeneration,
 }
 
+impl From<L1BatchProof> for SubmitProofRequest {
+    fn from(proof: L1BatchProof) -> Self {
+        match proof {
+            L1BatchProof::Plonk(proof) => Self::Proof(Box::new(proof)),
+            L1BatchProof::Fflonk(proof) => Self::FflonkProof(Box::new(proof)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum SubmitProofResponse {
     Success,
     Error(String),
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeeProofGenerationDataRequest {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TeeProofGenerationDataResponse {
+    Success(Option<Box<TeeVerifierInput>>),
+    Error(String),
+}
+
+/// Result of executing an L1 batch inside a TEE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L1BatchTeeProofForL1 {
+    /// Signature over the L1 batch root hash made with the key attested by the TEE.
+    pub signature: Vec<u8>,
+    /// Public key of the TEE; must be registered with an attestation beforehand.
+    pub pubkey: Vec<u8>,
+    /// TEE-specific proof of execution.
+    pub proof: Vec<u8>,
+    pub tee_type: TeeType,
+}
+
+impl L1BatchTeeProofForL1 {
+    /// Verifies that [`Self::signature`] is a valid ECDSA signature of `l1_batch_root_hash` made with [`Self::pubkey`].
+    /// The signature is expected in the compact (64-byte) format, optionally followed by a recovery ID byte.
+    pub fn verify_signature(&self, l1_batch_root_hash: H256) -> Result<(), secp256k1::Error> {
+        let compact_signature = match self.signature.len() {
+            64 | 65 => &self.signature[..64],
+            _ => return Err(secp256k1::Error::InvalidSignature),
+        };
+        let mut signature = secp256k1::ecdsa::Signature::from_compact(compact_signature)?;
+        signature.normalize_s();
+        let pubkey = secp256k1::PublicKey::from_slice(&self.pubkey)?;
+        let message = secp256k1::Message::from_slice(l1_batch_root_hash.as_bytes())?;
+        secp256k1::Secp256k1::verification_only().verify_ecdsa(&message, &signature, &pubkey)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitTeeProofRequest(pub Box<L1BatchTeeProofForL1>);
+
+/// Registers a TEE public key together with the attestation (e.g., an SGX quote) binding the key to the enclave.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterTeeAttestationRequest {
+    pub attestation: Vec<u8>,
+    pub pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum RegisterTeeAttestationResponse {
+    Success,
+}
+
+/// Header with the ID of the external prover making the request.
+pub const EXTERNAL_PROVER_ID_HEADER: &str = "x-prover-id";
+/// Header with the UNIX timestamp (in seconds) of the request made by an external prover.
+pub const EXTERNAL_PROVER_TIMESTAMP_HEADER: &str = "x-prover-timestamp";
+/// Header with the nonce of the request made by an external prover. Nonces must be unique per prover.
+pub const EXTERNAL_PROVER_NONCE_HEADER: &str = "x-prover-nonce";
+/// Header with the hex-encoded signature of the request made by an external prover
+/// (see [`sign_external_prover_request()`]).
+pub const EXTERNAL_PROVER_SIGNATURE_HEADER: &str = "x-prover-signature";
+
+/// Signs a request made by an external prover with the prover API key. The signature is computed as
+/// `keccak256(api_key || method || '\n' || path || '\n' || timestamp || '\n' || nonce || '\n' || keccak256(body))`.
+pub fn sign_external_prover_request(
+    api_key: &str,
+    method: &str,
+    path: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8],
+) -> H256 {
+    let body_hash = keccak256(body);
+    let mut message = api_key.as_bytes().to_vec();
+    message.extend_from_slice(format!("{method}\n{path}\n{timestamp}\n{nonce}\n").as_bytes());
+    message.extend_from_slice(&body_hash);
+    H256(keccak256(&message))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExternalProverBatchesRequest {
+    /// Maximum number of returned batches.
+    pub limit: Option<usize>,
+}
+
+/// L1 batch that has witness inputs ready and is not proven yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalProverBatch {
+    pub l1_batch_number: L1BatchNumber,
+    pub is_high_priority: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ExternalProverBatchesResponse {
+    /// Batches in the order they should be proven.
+    Success(Vec<ExternalProverBatch>),
+    Error(String),
+}
+
+/// Witness inputs for an L1 batch served to an external prover.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalProverWitnessInputs {
+    pub l1_batch_number: L1BatchNumber,
+    /// Signed object store URL to download the bincode-serialized [`PrepareBasicCircuitsJob`] from.
+    pub witness_input_url: String,
+    /// Number of seconds the URL is valid for.
+    pub url_expires_in_secs: u64,
+    pub fri_protocol_version_id: FriProtocolVersionId,
+    pub l1_verifier_config: L1VerifierConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ExternalProverWitnessInputsResponse {
+    Success(Box<ExternalProverWitnessInputs>),
+    Error(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_external_prover_request() {
+        let signature = sign_external_prover_request("key", "POST", "/path", 100, "nonce", b"{}");
+        assert_eq!(
+            signature,
+            sign_external_prover_request("key", "POST", "/path", 100, "nonce", b"{}")
+        );
+
+        let other_signatures = [
+            sign_external_prover_request("other_key", "POST", "/path", 100, "nonce", b"{}"),
+            sign_external_prover_request("key", "POST", "/path2", 100, "nonce", b"{}"),
+            sign_external_prover_request("key", "POST", "/path", 101, "nonce", b"{}"),
+            sign_external_prover_request("key", "POST", "/path", 100, "nonce2", b"{}"),
+            sign_external_prover_request("key", "POST", "/path", 100, "nonce", b"[]"),
+        ];
+        for other_signature in other_signatures {
+            assert_ne!(signature, other_signature);
+        }
+    }
+
+    #[test]
+    fn verifying_tee_proof_signature() {
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+        let root_hash = H256::repeat_byte(0x23);
+        let message = secp256k1::Message::from_slice(root_hash.as_bytes()).unwrap();
+        let signature = secp.sign_ecdsa(&message, &secret_key);
+
+        let mut proof = L1BatchTeeProofForL1 {
+            signature: signature.serialize_compact().to_vec(),
+            pubkey: pubkey.serialize().to_vec(),
+            proof: vec![],
+            tee_type: TeeType::Sgx,
+        };
+        proof.verify_signature(root_hash).unwrap();
+        proof.verify_signature(H256::repeat_byte(0x42)).unwrap_err();
+
+        proof.pubkey = pubkey.serialize_uncompressed().to_vec();
+        proof.verify_signature(root_hash).unwrap();
+
+        let other_secret_key = secp256k1::SecretKey::from_slice(&[2; 32]).unwrap();
+        proof.pubkey = secp256k1::PublicKey::from_secret_key(&secp, &other_secret_key)
+            .serialize()
+            .to_vec();
+        proof.verify_signature(root_hash).unwrap_err();
+
+        proof.pubkey = pubkey.serialize().to_vec();
+        proof.signature.truncate(63);
+        proof.verify_signature(root_hash).unwrap_err();
+    }
+}
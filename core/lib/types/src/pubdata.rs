@@ -0,0 +1,125 @@
+//! Utilities for L1 batch pubdata.
+
+use zksync_basic_types::H256;
+use zksync_utils::bytecode::{decompress_bytecode, hash_bytecode, validate_bytecode};
+
+use crate::{commitment::SerializeCommitment, l2_to_l1_log::L2ToL1Log};
+
+/// Contents of the L1 batch pubdata relevant for resolving published data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PubdataContents<'a> {
+    /// L2-to-L1 messages, including compressed bytecodes sent by the `Compressor` system contract.
+    pub l2_to_l1_messages: Vec<&'a [u8]>,
+    /// Bytecodes published in the uncompressed form.
+    pub published_bytecodes: Vec<&'a [u8]>,
+}
+
+impl<'a> PubdataContents<'a> {
+    /// Parses pubdata in the format produced by the L1 messenger for post-boojum L1 batches. State diffs
+    /// are not parsed. Returns `None` if the pubdata is malformed.
+    pub fn parse(pubdata: &'a [u8]) -> Option<Self> {
+        fn read_u32(bytes: &[u8], pos: &mut usize) -> Option<usize> {
+            let value = bytes.get(*pos..*pos + 4)?;
+            *pos += 4;
+            Some(u32::from_be_bytes(value.try_into().unwrap()) as usize)
+        }
+
+        fn read_length_prefixed_items<'b>(
+            bytes: &'b [u8],
+            pos: &mut usize,
+        ) -> Option<Vec<&'b [u8]>> {
+            let count = read_u32(bytes, pos)?;
+            let mut items = Vec::with_capacity(count.min(bytes.len()));
+            for _ in 0..count {
+                let len = read_u32(bytes, pos)?;
+                let end = pos.checked_add(len).filter(|&end| end <= bytes.len())?;
+                items.push(&bytes[*pos..end]);
+                *pos = end;
+            }
+            Some(items)
+        }
+
+        let mut pos = 0;
+        let user_logs_count = read_u32(pubdata, &mut pos)?;
+        pos = user_logs_count
+            .checked_mul(L2ToL1Log::SERIALIZED_SIZE)
+            .and_then(|len| pos.checked_add(len))
+            .filter(|&end| end <= pubdata.len())?;
+        let l2_to_l1_messages = read_length_prefixed_items(pubdata, &mut pos)?;
+        let published_bytecodes = read_length_prefixed_items(pubdata, &mut pos)?;
+        Some(Self {
+            l2_to_l1_messages,
+            published_bytecodes,
+        })
+    }
+
+    /// Finds a bytecode with the specified versioned hash, which was published either in the uncompressed form,
+    /// or compressed in an L2-to-L1 message.
+    pub fn find_bytecode(&self, hash: H256) -> Option<Vec<u8>> {
+        let uncompressed = self.published_bytecodes.iter().find_map(|&bytecode| {
+            let is_match = validate_bytecode(bytecode).is_ok() && hash_bytecode(bytecode) == hash;
+            is_match.then(|| bytecode.to_vec())
+        });
+        uncompressed.or_else(|| {
+            self.l2_to_l1_messages.iter().find_map(|&message| {
+                let bytecode = decompress_bytecode(message).ok()?;
+                (hash_bytecode(&bytecode) == hash).then_some(bytecode)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::bytecode::compress_bytecode;
+
+    use super::*;
+
+    fn encode_items(items: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = (items.len() as u32).to_be_bytes().to_vec();
+        for item in items {
+            bytes.extend((item.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(item);
+        }
+        bytes
+    }
+
+    fn test_bytecode(fill: u8) -> Vec<u8> {
+        let mut bytecode = vec![0; 96];
+        bytecode[40..48].fill(fill);
+        bytecode
+    }
+
+    #[test]
+    fn finding_bytecodes_in_pubdata() {
+        let published_bytecode = test_bytecode(1);
+        let compressed_bytecode = test_bytecode(2);
+        let missing_bytecode = test_bytecode(3);
+        let compressed = compress_bytecode(&compressed_bytecode).unwrap();
+
+        let mut pubdata = 1_u32.to_be_bytes().to_vec();
+        pubdata.extend([0; L2ToL1Log::SERIALIZED_SIZE]);
+        pubdata.extend(encode_items(&[b"message", &compressed]));
+        pubdata.extend(encode_items(&[&published_bytecode]));
+        pubdata.extend([0; 64]); // state diffs
+
+        let contents = PubdataContents::parse(&pubdata).unwrap();
+        assert_eq!(contents.l2_to_l1_messages.len(), 2);
+        assert_eq!(
+            contents.published_bytecodes,
+            [published_bytecode.as_slice()]
+        );
+
+        let found = contents.find_bytecode(hash_bytecode(&published_bytecode));
+        assert_eq!(found, Some(published_bytecode));
+        let found = contents.find_bytecode(hash_bytecode(&compressed_bytecode));
+        assert_eq!(found, Some(compressed_bytecode));
+        assert_eq!(
+            contents.find_bytecode(hash_bytecode(&missing_bytecode)),
+            None
+        );
+
+        pubdata.truncate(20);
+        assert_eq!(PubdataContents::parse(&pubdata), None);
+    }
+}
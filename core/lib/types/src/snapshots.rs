@@ -5,7 +5,33 @@ use serde::{Deserialize, Serialize};
 use zksync_basic_types::{AccountTreeId, L1BatchNumber, MiniblockNumber, H256};
 use zksync_protobuf::{required, ProtoFmt};
 
-use crate::{commitment::L1BatchWithMetadata, Bytes, StorageKey, StorageValue};
+use crate::{
+    commitment::L1BatchWithMetadata, web3::signing::keccak256, Bytes, StorageKey, StorageValue,
+};
+
+/// Computes the checksum of a snapshot file (a storage logs chunk or factory deps) as recorded in snapshot headers.
+pub fn snapshot_file_checksum(file_bytes: &[u8]) -> H256 {
+    H256(keccak256(file_bytes))
+}
+
+/// Checks that the downloaded contents of a snapshot file match the checksum from the snapshot header.
+/// Files of snapshots created before checksums were introduced (i.e., with `expected_checksum == None`)
+/// cannot be verified and are accepted as is.
+fn verify_snapshot_file(
+    file_bytes: &[u8],
+    expected_checksum: Option<H256>,
+    filepath: &str,
+) -> anyhow::Result<()> {
+    if let Some(expected_checksum) = expected_checksum {
+        let checksum = snapshot_file_checksum(file_bytes);
+        anyhow::ensure!(
+            checksum == expected_checksum,
+            "checksum mismatch for snapshot file `{filepath}`: expected {expected_checksum:?}, \
+             got {checksum:?}"
+        );
+    }
+    Ok(())
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,7 +45,26 @@ pub struct AllSnapshots {
 pub struct SnapshotMetadata {
     pub l1_batch_number: L1BatchNumber,
     pub factory_deps_filepath: String,
+    pub factory_deps_checksum: Option<H256>,
     pub storage_logs_filepaths: Vec<String>,
+    // ordered by chunk ids; `None` for snapshots created before checksums were introduced
+    pub storage_logs_checksums: Option<Vec<H256>>,
+    // `None` for full snapshots
+    pub incremental: Option<IncrementalSnapshotManifest>,
+}
+
+/// Manifest of an incremental snapshot chaining it to a full (base) snapshot.
+///
+/// An incremental snapshot contains only storage logs and factory deps changed since the previous snapshot in the chain.
+/// To restore the state, the base snapshot must be applied first, followed by all incremental snapshots in the chain
+/// in the ascending L1 batch order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncrementalSnapshotManifest {
+    /// L1 batch number of the full snapshot the chain is based on.
+    pub base_l1_batch_number: L1BatchNumber,
+    /// L1 batch number of the previous snapshot in the chain (either the base snapshot or an incremental one).
+    pub previous_l1_batch_number: L1BatchNumber,
 }
 
 //contains all data not contained in factory_deps/storage_logs files to perform restore process
@@ -31,7 +76,24 @@ pub struct SnapshotHeader {
     //ordered by chunk ids
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    // Keccak-256 hash of the factory deps file contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_deps_checksum: Option<H256>,
     pub last_l1_batch_with_metadata: L1BatchWithMetadata,
+    // set for incremental snapshots, which must be applied on top of the previous snapshot in the chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incremental: Option<IncrementalSnapshotManifest>,
+}
+
+impl SnapshotHeader {
+    /// Verifies the downloaded contents of the factory deps file against the checksum in this header.
+    pub fn verify_factory_deps_file(&self, file_bytes: &[u8]) -> anyhow::Result<()> {
+        verify_snapshot_file(
+            file_bytes,
+            self.factory_deps_checksum,
+            &self.factory_deps_filepath,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +102,16 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub chunk_id: u64,
     // can be either be a file available under http(s) or local filesystem path
     pub filepath: String,
+    // Keccak-256 hash of the chunk file contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<H256>,
+}
+
+impl SnapshotStorageLogsChunkMetadata {
+    /// Verifies the downloaded contents of the chunk file against the checksum in this metadata.
+    pub fn verify_file(&self, file_bytes: &[u8]) -> anyhow::Result<()> {
+        verify_snapshot_file(file_bytes, self.checksum, &self.filepath)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -0,0 +1,49 @@
+//! Types used by the TEE (trusted execution environment) proof pathway, in which L1 batches are re-executed
+//! inside a TEE that attests to the execution result. TEE proofs are produced in addition to ZK proofs.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{L1BatchNumber, L2ChainId, H256};
+
+use crate::{
+    block::{L1BatchHeader, MiniblockExecutionData},
+    proofs::PrepareBasicCircuitsJob,
+    storage::witness_block_state::WitnessBlockState,
+};
+
+/// Type of a trusted execution environment.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display, strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TeeType {
+    /// Intel SGX enclave.
+    #[strum(serialize = "sgx")]
+    Sgx,
+}
+
+/// Everything a TEE prover needs to re-execute an L1 batch and check the resulting state root hash,
+/// without access to the node database.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeeVerifierInput {
+    /// Header of the L1 batch, containing its execution params (timestamp, gas prices, base system contracts etc.).
+    pub l1_batch_header: L1BatchHeader,
+    /// Hash of the previous L1 batch the batch is applied on top of.
+    pub prev_l1_batch_hash: H256,
+    /// State root hash expected after executing the batch.
+    pub l1_batch_root_hash: H256,
+    pub l2_chain_id: L2ChainId,
+    /// Miniblocks of the batch together with their transactions.
+    pub miniblocks: Vec<MiniblockExecutionData>,
+    /// Bytecodes of contracts used in the batch (including base system contracts), keyed by their hashes.
+    pub used_contracts: Vec<(H256, Vec<u8>)>,
+    /// Storage slots read during batch execution.
+    pub witness_block_state: WitnessBlockState,
+    /// Merkle paths for the storage logs of the batch, allowing to verify read values and compute the new root hash.
+    pub merkle_paths: PrepareBasicCircuitsJob,
+}
+
+impl TeeVerifierInput {
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        self.l1_batch_header.number
+    }
+}
@@ -9,7 +9,7 @@ use zk_evm::zkevm_opcode_defs::FarCallOpcode;
 use zksync_system_constants::BOOTLOADER_ADDRESS;
 use zksync_utils::u256_to_h256;
 
-use crate::{Address, U256};
+use crate::{Address, H256, U256};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum VmTrace {
@@ -201,7 +201,43 @@ impl fmt::Debug for Call {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Kind of access to a watched storage slot.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageAccessKind {
+    Read,
+    Write,
+}
+
+/// Far call frame active during a storage access.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAccessFrame {
+    /// Address of the caller (same as [`Call::from`] in the call trace).
+    pub from: Address,
+    /// Address of the callee (same as [`Call::to`] in the call trace).
+    pub to: Address,
+    /// Address of the executed code. Differs from `to` for delegate calls.
+    pub code_address: Address,
+}
+
+/// Access to a watched storage slot recorded by the storage watchpoint tracer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAccess {
+    pub kind: StorageAccessKind,
+    pub address: Address,
+    pub key: H256,
+    /// Value of the slot before the access (i.e., the read value for reads).
+    pub value: H256,
+    /// Value written to the slot; `None` for reads.
+    pub written_value: Option<H256>,
+    /// Far call frames active during the access, from the outermost (bootloader) to the innermost one.
+    /// Accesses in frames that are reverted later are recorded as well.
+    pub call_stack: Vec<StorageAccessFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ViolatedValidationRule {
     TouchedUnallowedStorageSlots(Address, U256),
     CalledContractWithNoCode(Address),
@@ -234,3 +270,138 @@ impl Display for ViolatedValidationRule {
         }
     }
 }
+
+impl ViolatedValidationRule {
+    /// Returns a stable machine-readable name of the rule.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TouchedUnallowedStorageSlots(..) => "touchedUnallowedStorageSlots",
+            Self::CalledContractWithNoCode(_) => "calledContractWithNoCode",
+            Self::TouchedUnallowedContext => "touchedUnallowedContext",
+            Self::TookTooManyComputationalGas(_) => "tookTooManyComputationalGas",
+        }
+    }
+}
+
+/// Diagnostics for a violated account validation rule: the rule itself together with the execution context
+/// in which it was violated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationViolationReport {
+    pub rule: ViolatedValidationRule,
+    /// Debug representation of the opcode that violated the rule (e.g., `Log(StorageRead)`).
+    pub opcode: String,
+    /// Address of the contract executing the opcode.
+    pub contract_address: Address,
+    /// Depth of the VM call stack when the opcode was executed.
+    pub call_depth: usize,
+}
+
+impl Display for ValidationViolationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (opcode {} executed by contract {} at call depth {})",
+            self.rule,
+            self.opcode,
+            hex::encode(self.contract_address),
+            self.call_depth
+        )
+    }
+}
+
+/// Serializes the report as a flat JSON object, with the rule-specific details (the offending storage slot,
+/// the called address or the computational gas limit) present only for the corresponding rules.
+impl Serialize for ValidationViolationReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct StorageSlot {
+            address: Address,
+            key: H256,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SerializedReport<'a> {
+            rule: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            storage_slot: Option<StorageSlot>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            called_address: Option<Address>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            computational_gas_limit: Option<u32>,
+            opcode: &'a str,
+            contract_address: Address,
+            call_depth: usize,
+        }
+
+        let mut report = SerializedReport {
+            rule: self.rule.name(),
+            storage_slot: None,
+            called_address: None,
+            computational_gas_limit: None,
+            opcode: &self.opcode,
+            contract_address: self.contract_address,
+            call_depth: self.call_depth,
+        };
+        match &self.rule {
+            ViolatedValidationRule::TouchedUnallowedStorageSlots(address, key) => {
+                report.storage_slot = Some(StorageSlot {
+                    address: *address,
+                    key: u256_to_h256(*key),
+                });
+            }
+            ViolatedValidationRule::CalledContractWithNoCode(address) => {
+                report.called_address = Some(*address);
+            }
+            ViolatedValidationRule::TouchedUnallowedContext => {}
+            ViolatedValidationRule::TookTooManyComputationalGas(gas_limit) => {
+                report.computational_gas_limit = Some(*gas_limit);
+            }
+        }
+        report.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializing_validation_violation_report() {
+        let report = ValidationViolationReport {
+            rule: ViolatedValidationRule::TouchedUnallowedStorageSlots(
+                Address::repeat_byte(1),
+                U256::from(2),
+            ),
+            opcode: "Log(StorageRead)".to_owned(),
+            contract_address: Address::repeat_byte(1),
+            call_depth: 3,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "rule": "touchedUnallowedStorageSlots",
+                "storageSlot": {
+                    "address": "0x0101010101010101010101010101010101010101",
+                    "key": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                },
+                "opcode": "Log(StorageRead)",
+                "contractAddress": "0x0101010101010101010101010101010101010101",
+                "callDepth": 3,
+            })
+        );
+
+        let report = ValidationViolationReport {
+            rule: ViolatedValidationRule::TookTooManyComputationalGas(500_000),
+            opcode: "Add".to_owned(),
+            contract_address: Address::repeat_byte(1),
+            call_depth: 1,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["rule"], "tookTooManyComputationalGas");
+        assert_eq!(json["computationalGasLimit"], 500_000);
+        assert!(json.get("storageSlot").is_none());
+    }
+}
@@ -97,6 +97,43 @@ pub fn compress_bytecode(code: &[u8]) -> Result<Vec<u8>, FailedToCompressBytecod
     Ok(compressed)
 }
 
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum FailedToDecompressBytecodeError {
+    #[error("Compressed bytecode is truncated")]
+    Truncated,
+    #[error("Encoded data references chunk #{0} outside of the dictionary")]
+    ChunkOutOfBounds(u16),
+    #[error("Decompressed bytecode is invalid: {0}")]
+    InvalidBytecode(#[from] InvalidBytecodeError),
+}
+
+/// Decompresses the bytecode compressed with [`compress_bytecode()`].
+pub fn decompress_bytecode(compressed: &[u8]) -> Result<Vec<u8>, FailedToDecompressBytecodeError> {
+    let dictionary_len = compressed
+        .get(0..2)
+        .ok_or(FailedToDecompressBytecodeError::Truncated)?;
+    let dictionary_len = usize::from(u16::from_be_bytes(dictionary_len.try_into().unwrap()));
+    let dictionary = compressed
+        .get(2..2 + dictionary_len * 8)
+        .ok_or(FailedToDecompressBytecodeError::Truncated)?;
+    let encoded_data = &compressed[2 + dictionary_len * 8..];
+    if encoded_data.len() % 2 != 0 {
+        return Err(FailedToDecompressBytecodeError::Truncated);
+    }
+
+    let mut decompressed = Vec::with_capacity(encoded_data.len() * 4);
+    for index_bytes in encoded_data.chunks(2) {
+        let index = u16::from_be_bytes(index_bytes.try_into().unwrap());
+        let start = usize::from(index) * 8;
+        let chunk = dictionary
+            .get(start..start + 8)
+            .ok_or(FailedToDecompressBytecodeError::ChunkOutOfBounds(index))?;
+        decompressed.extend_from_slice(chunk);
+    }
+    validate_bytecode(&decompressed)?;
+    Ok(decompressed)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompressedBytecodeInfo {
     pub original: Vec<u8>,
@@ -175,40 +212,44 @@ pub fn bytecode_len_in_bytes(bytecodehash: H256) -> usize {
 mod test {
     use super::*;
 
-    fn decompress_bytecode(raw_compressed_bytecode: &[u8]) -> Vec<u8> {
-        let mut decompressed: Vec<u8> = Vec::new();
-        let mut dictionary: Vec<u64> = Vec::new();
-
-        let dictionary_len = u16::from_be_bytes(raw_compressed_bytecode[0..2].try_into().unwrap());
-        for index in 0..dictionary_len {
-            let chunk = u64::from_be_bytes(
-                raw_compressed_bytecode[2 + index as usize * 8..10 + index as usize * 8]
-                    .try_into()
-                    .unwrap(),
-            );
-            dictionary.push(chunk);
-        }
-
-        let encoded_data = &raw_compressed_bytecode[2 + dictionary_len as usize * 8..];
-        for index_bytes in encoded_data.chunks(2) {
-            let index = u16::from_be_bytes(index_bytes.try_into().unwrap());
-
-            let chunk = dictionary[index as usize];
-            decompressed.extend(chunk.to_be_bytes());
-        }
-
-        decompressed
-    }
-
     #[test]
     fn bytecode_compression_test() {
         let example_code = hex::decode("000200000000000200010000000103550000006001100270000000150010019d0000000101200190000000080000c13d0000000001000019004e00160000040f0000000101000039004e00160000040f0000001504000041000000150510009c000000000104801900000040011002100000000001310019000000150320009c0000000002048019000000600220021000000000012100190000004f0001042e000000000100001900000050000104300000008002000039000000400020043f0000000002000416000000000110004c000000240000613d000000000120004c0000004d0000c13d000000200100003900000100001004430000012000000443000001000100003900000040020000390000001d03000041004e000a0000040f000000000120004c0000004d0000c13d0000000001000031000000030110008c0000004d0000a13d0000000101000367000000000101043b0000001601100197000000170110009c0000004d0000c13d0000000101000039000000000101041a0000000202000039000000000202041a000000400300043d00000040043000390000001805200197000000000600041a0000000000540435000000180110019700000020043000390000000000140435000000a0012002700000001901100197000000600430003900000000001404350000001a012001980000001b010000410000000001006019000000b8022002700000001c02200197000000000121019f0000008002300039000000000012043500000018016001970000000000130435000000400100043d0000000002130049000000a0022000390000000003000019004e000a0000040f004e00140000040f0000004e000004320000004f0001042e000000500001043000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000ffffffffffffffff000000000000000000000000000000000000000000000000000000008903573000000000000000000000000000000000000000000000000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffffffff0000000000000000000000000000000000000000000000000000000000ffffff0000000000008000000000000000000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80000000000000000000000000000000000000000000000000000000000000007fffff00000002000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
         let compressed = compress_bytecode(&example_code).unwrap();
-        let decompressed = decompress_bytecode(&compressed);
+        let decompressed = decompress_bytecode(&compressed).unwrap();
 
         assert_eq!(example_code, decompressed);
     }
 
+    #[test]
+    fn decompressing_malformed_bytecode() {
+        assert_eq!(
+            decompress_bytecode(&[0]),
+            Err(FailedToDecompressBytecodeError::Truncated)
+        );
+        // Dictionary of 1 chunk, which is truncated.
+        assert_eq!(
+            decompress_bytecode(&[0, 1, 0, 0]),
+            Err(FailedToDecompressBytecodeError::Truncated)
+        );
+
+        let mut compressed = vec![0, 1];
+        compressed.extend([0xff; 8]);
+        compressed.extend([0, 1]);
+        assert_eq!(
+            decompress_bytecode(&compressed),
+            Err(FailedToDecompressBytecodeError::ChunkOutOfBounds(1))
+        );
+
+        // Single 8-byte chunk is not a valid bytecode.
+        compressed.truncate(10);
+        compressed.extend([0, 0]);
+        assert!(matches!(
+            decompress_bytecode(&compressed),
+            Err(FailedToDecompressBytecodeError::InvalidBytecode(_))
+        ));
+    }
+
     #[test]
     fn bytecode_compression_statistics_test() {
         let example_code =
@@ -1,13 +1,28 @@
 //! This module contains the observability subsystem.
 //! It is responsible for providing a centralized interface for consistent observability configuration.
 
-use std::{backtrace::Backtrace, borrow::Cow, panic::PanicInfo};
+use std::{backtrace::Backtrace, borrow::Cow, collections::HashMap, panic::PanicInfo};
 
+use opentelemetry::{
+    global,
+    propagation::TextMapPropagator as _,
+    sdk::{
+        propagation::TraceContextPropagator,
+        trace::{self, Tracer},
+        Resource,
+    },
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
 // Temporary re-export of `sentry::capture_message` aiming to simplify the transition from `vlog` to using
 // crates directly.
 pub use sentry::{capture_message, Level as AlertLevel};
 use sentry::{types::Dsn, ClientInitGuard};
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::Subscriber;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt,
+};
 
 /// Specifies the format of the logs in stdout.
 #[derive(Debug, Clone, Copy, Default)]
@@ -17,19 +32,39 @@ pub enum LogFormat {
     Json,
 }
 
+/// Options for exporting spans to an OpenTelemetry collector.
+#[derive(Debug)]
+struct OpenTelemetryOptions {
+    /// OTLP HTTP endpoint, e.g. `http://127.0.0.1:4318/v1/traces`.
+    otlp_endpoint: String,
+    /// Service name reported in the exported span resource.
+    service_name: String,
+}
+
 /// Builder for the observability subsystem.
-/// Currently capable of configuring logging output and sentry integration.
+/// Currently capable of configuring logging output, sentry integration and exporting spans via OTLP.
 #[derive(Debug, Default)]
 pub struct ObservabilityBuilder {
     log_format: LogFormat,
     sentry_url: Option<Dsn>,
     sentry_environment: Option<String>,
+    opentelemetry_options: Option<OpenTelemetryOptions>,
 }
 
 /// Guard for the observability subsystem.
 /// Releases configured integrations upon being dropped.
 pub struct ObservabilityGuard {
     _sentry_guard: Option<ClientInitGuard>,
+    otlp_tracing_enabled: bool,
+}
+
+impl Drop for ObservabilityGuard {
+    fn drop(&mut self) {
+        if self.otlp_tracing_enabled {
+            // Flushes spans buffered by the batch exporter.
+            global::shutdown_tracer_provider();
+        }
+    }
 }
 
 impl std::fmt::Debug for ObservabilityGuard {
@@ -69,13 +104,49 @@ impl ObservabilityBuilder {
         self
     }
 
+    /// Enables exporting spans to an OpenTelemetry collector using OTLP over HTTP. Spans are filtered
+    /// in the same way as logs (i.e., using the `RUST_LOG` env variable).
+    ///
+    /// The exporter is run on the Tokio runtime, so [`Self::build()`] must be called in the runtime context.
+    pub fn with_opentelemetry(mut self, otlp_endpoint: String, service_name: String) -> Self {
+        self.opentelemetry_options = Some(OpenTelemetryOptions {
+            otlp_endpoint,
+            service_name,
+        });
+        self
+    }
+
+    fn opentelemetry_layer<S>(&self) -> Option<OpenTelemetryLayer<S, Tracer>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let options = self.opentelemetry_options.as_ref()?;
+        let resource = Resource::new([SERVICE_NAME.string(options.service_name.clone())]);
+        let exporter = opentelemetry_otlp::new_exporter()
+            .http()
+            .with_endpoint(&options.otlp_endpoint);
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(trace::config().with_resource(resource))
+            .install_batch(opentelemetry::runtime::Tokio)
+            .expect("Failed installing OTLP tracing pipeline");
+        // Allows to continue traces started by other services; see [`set_remote_parent()`].
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        Some(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+
     /// Initializes the observability subsystem.
     pub fn build(self) -> ObservabilityGuard {
+        let opentelemetry_layer = self.opentelemetry_layer();
+        let otlp_tracing_enabled = opentelemetry_layer.is_some();
+
         // Initialize logs.
         match self.log_format {
             LogFormat::Plain => {
                 tracing_subscriber::registry()
                     .with(tracing_subscriber::EnvFilter::from_default_env())
+                    .with(opentelemetry_layer)
                     .with(fmt::Layer::default())
                     .init();
             }
@@ -83,6 +154,7 @@ impl ObservabilityBuilder {
                 let timer = tracing_subscriber::fmt::time::UtcTime::rfc_3339();
                 tracing_subscriber::registry()
                     .with(tracing_subscriber::EnvFilter::from_default_env())
+                    .with(opentelemetry_layer)
                     .with(
                         fmt::Layer::default()
                             .with_file(true)
@@ -121,10 +193,26 @@ impl ObservabilityBuilder {
 
         ObservabilityGuard {
             _sentry_guard: sentry_guard,
+            otlp_tracing_enabled,
         }
     }
 }
 
+/// Sets the parent of the provided span to the remote span context propagated using
+/// [W3C Trace Context](https://www.w3.org/TR/trace-context/) headers (`traceparent` and `tracestate`).
+/// Header names must be lowercase. Does nothing if OTLP tracing is not enabled or the headers are missing.
+pub fn set_remote_parent<'a>(
+    span: &tracing::Span,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    let carrier: HashMap<_, _> = headers
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .collect();
+    let parent_context = global::get_text_map_propagator(|propagator| propagator.extract(&carrier));
+    span.set_parent(parent_context);
+}
+
 /// Loads the log format from the environment variable according to the existing zkSync configuration scheme.
 /// If the variable is not set, the default value is used.
 ///
@@ -168,6 +256,22 @@ pub fn sentry_url_from_env() -> Option<String> {
     }
 }
 
+/// Loads the OTLP endpoint for exported spans from the environment variable according to the existing zkSync
+/// configuration scheme. Similarly to [`sentry_url_from_env()`], `unset` value is treated as a missing one.
+///
+/// This is a deprecated function existing for compatibility with the old configuration scheme.
+/// Not recommended for use in new applications.
+#[deprecated(
+    note = "This function will be removed in the future. Applications are expected to handle their configuration themselves."
+)]
+pub fn otlp_url_from_env() -> Option<String> {
+    match std::env::var("MISC_OTLP_URL") {
+        Ok(str) if str == "unset" => None,
+        Ok(str) => Some(str),
+        Err(_) => None,
+    }
+}
+
 /// Prepared the Sentry environment ID from the environment variable according to the existing zkSync configuration
 /// scheme.
 /// This function mimics like `vlog` configuration worked historically, e.g. it would also try to load environment
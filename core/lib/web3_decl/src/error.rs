@@ -1,7 +1,10 @@
 //! Definition of errors that can occur in the zkSync Web3 API.
 
 use thiserror::Error;
-use zksync_types::api::SerializationTransactionError;
+use zksync_types::{
+    api::SerializationTransactionError, vm_trace::ValidationViolationReport, Address,
+    L1BatchNumber, MiniblockNumber,
+};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -19,6 +22,9 @@ pub enum Web3Error {
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Transaction was rejected because its account validation violated a rule. The report is returned as error data.
+    #[error("{0}")]
+    ValidationRulesViolated(String, ValidationViolationReport),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
@@ -37,4 +43,13 @@ pub enum Web3Error {
     InvalidFilterBlockHash,
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Merkle tree doesn't have data for L1 batch #{0}; the batch is either not processed yet, or pruned")]
+    NoTreeVersion(L1BatchNumber),
+    #[error("Storage range is not available for system contract {0:?}")]
+    NoStorageRange(Address),
+    #[error(
+        "Data for miniblock #{0} is pruned; for compacted L1 batches, only the storage state as of the last miniblock \
+         in a batch is available"
+    )]
+    PrunedBlock(MiniblockNumber),
 }
@@ -0,0 +1,31 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{api::analytics::L1BatchAnalytics, L1BatchNumber};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "analytics")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "analytics")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "analytics")
+)]
+pub trait AnalyticsNamespace {
+    #[method(name = "getL1BatchAnalytics")]
+    async fn get_l1_batch_analytics(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchAnalytics>>;
+
+    /// Returns analytics for up to `limit` consecutive L1 batches starting from `from_l1_batch_number`.
+    /// The limit is capped at 100 L1 batches; the response ends at the first missing L1 batch.
+    #[method(name = "getL1BatchesAnalytics")]
+    async fn get_l1_batches_analytics(
+        &self,
+        from_l1_batch_number: L1BatchNumber,
+        limit: u32,
+    ) -> RpcResult<Vec<L1BatchAnalytics>>;
+}
@@ -1,10 +1,10 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, StorageRangeResult, TracerConfig},
     transaction_request::CallRequest,
 };
 
-use crate::types::H256;
+use crate::types::{Address, Bytes, H256};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -44,4 +44,19 @@ pub trait DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
+    #[method(name = "getRawBlock")]
+    async fn get_raw_block(&self, block: BlockId) -> RpcResult<Option<Bytes>>;
+    #[method(name = "getRawReceipts")]
+    async fn get_raw_receipts(&self, block: BlockId) -> RpcResult<Option<Vec<Bytes>>>;
+    #[method(name = "getRawTransaction")]
+    async fn get_raw_transaction(&self, tx_hash: H256) -> RpcResult<Option<Bytes>>;
+    #[method(name = "storageRangeAt")]
+    async fn storage_range_at(
+        &self,
+        block: BlockId,
+        tx_index: usize,
+        address: Address,
+        start_key: H256,
+        max_result: usize,
+    ) -> RpcResult<StorageRangeResult>;
 }
@@ -1,21 +1,24 @@
+pub mod analytics;
 pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
 pub mod snapshots;
+pub mod unstable;
 pub mod web3;
 pub mod zks;
 
 #[cfg(feature = "client")]
 pub use self::{
-    debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    analytics::AnalyticsNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer,
+    unstable::UnstableNamespaceClient, web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
-    debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
-    eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceClient,
+    analytics::AnalyticsNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, eth::EthPubSubServer, net::NetNamespaceServer,
+    snapshots::SnapshotsNamespaceClient, unstable::UnstableNamespaceServer,
     web3::Web3NamespaceServer, zks::ZksNamespaceServer,
 };
@@ -0,0 +1,30 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::sealing::{L1BatchSealStatus, SealSimulation},
+    transaction_request::CallRequest,
+};
+
+/// Internal methods intended for node operators. Their interface may change without notice.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "unstable")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "unstable")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "unstable")
+)]
+pub trait UnstableNamespace {
+    /// Reports how close the pending L1 batch is to being sealed by each of the built-in seal criteria.
+    /// Returns `null` if the node doesn't have the state keeper config (e.g., on the external node).
+    #[method(name = "getSealCriteriaStatus")]
+    async fn get_seal_criteria_status(&self) -> RpcResult<Option<L1BatchSealStatus>>;
+
+    /// Simulates adding a transaction to the pending L1 batch and reports whether the batch would be sealed
+    /// as a result. The transaction is executed in the same way as during fee estimation; it is not submitted.
+    #[method(name = "simulateSealCriteria")]
+    async fn simulate_seal_criteria(&self, req: CallRequest) -> RpcResult<Option<SealSimulation>>;
+}
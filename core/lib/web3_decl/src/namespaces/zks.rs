@@ -4,12 +4,12 @@ use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        AccountNonceDetails, BlockDetails, BridgeAddresses, FeeEstimate, FeeEstimateOptions,
+        FeeParams, L1BatchDetails, L1BatchIdVariant, L1BatchProofPublicInputs, L2ToL1LogProof,
+        L2ToL1Message, Log, PriorityOpStatus, Proof, ProtocolVersion, TransactionDetails,
     },
-    fee::Fee,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 
 use crate::types::Token;
@@ -28,7 +28,11 @@ use crate::types::Token;
 )]
 pub trait ZksNamespace {
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        options: Option<FeeEstimateOptions>,
+    ) -> RpcResult<FeeEstimate>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
@@ -54,6 +58,9 @@ pub trait ZksNamespace {
     async fn get_all_account_balances(&self, address: Address)
         -> RpcResult<HashMap<Address, U256>>;
 
+    #[method(name = "getAccountNonceDetails")]
+    async fn get_account_nonce_details(&self, address: Address) -> RpcResult<AccountNonceDetails>;
+
     #[method(name = "getL2ToL1MsgProof")]
     async fn get_l2_to_l1_msg_proof(
         &self,
@@ -70,6 +77,21 @@ pub trait ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>>;
 
+    #[method(name = "getL2ToL1Messages")]
+    async fn get_l2_to_l1_messages(&self, tx_hash: H256) -> RpcResult<Option<Vec<L2ToL1Message>>>;
+
+    /// Returns logs indexed by `key` in the specified event index (e.g., `erc20_transfers`), starting from
+    /// the `from_block` miniblock and ordered by their position in the chain. The number of returned logs
+    /// is capped by the server. Returns an empty list if the index is not maintained by the node.
+    #[method(name = "getIndexedLogs")]
+    async fn get_indexed_logs(
+        &self,
+        index: String,
+        key: Bytes,
+        from_block: MiniblockNumber,
+        limit: Option<u32>,
+    ) -> RpcResult<Vec<Log>>;
+
     #[method(name = "L1BatchNumber")]
     async fn get_l1_batch_number(&self) -> RpcResult<U64>;
 
@@ -82,9 +104,22 @@ pub trait ZksNamespace {
         block_number: MiniblockNumber,
     ) -> RpcResult<Option<BlockDetails>>;
 
+    /// Returns fee model parameters used in the specified miniblock, or `None` if the miniblock doesn't exist.
+    #[method(name = "getFeeParamsAt")]
+    async fn get_fee_params_at(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<FeeParams>>;
+
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    /// Returns the lifecycle status of a priority operation identified by the hash of the L1 transaction
+    /// that submitted it, or `None` if the operation isn't known to the node.
+    #[method(name = "getPriorityOpStatus")]
+    async fn get_priority_op_status(&self, l1_tx_hash: H256)
+        -> RpcResult<Option<PriorityOpStatus>>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
@@ -95,6 +130,12 @@ pub trait ZksNamespace {
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    #[method(name = "getBatchProofPublicInputs")]
+    async fn get_batch_proof_public_inputs(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchProofPublicInputs>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
@@ -107,11 +148,22 @@ pub trait ZksNamespace {
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    /// Returns Merkle proofs for the storage `keys` of the `address` as of the end of the specified L1 batch.
+    /// Proofs can be requested for any L1 batch retained by the Merkle tree, not only for the latest one.
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
         address: Address,
         keys: Vec<H256>,
-        l1_batch_number: L1BatchNumber,
+        l1_batch: L1BatchIdVariant,
     ) -> RpcResult<Proof>;
+
+    /// Same as `eth_sendRawTransaction`, but the transaction is dropped from the mempool if it's not included
+    /// into a block before `valid_until` (UNIX timestamp in seconds).
+    #[method(name = "sendRawTransactionWithDeadline")]
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> RpcResult<H256>;
 }
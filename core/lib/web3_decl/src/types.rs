@@ -15,7 +15,10 @@ use itertools::unfold;
 use rlp::Rlp;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 pub use zksync_types::{
-    api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
+    api::{
+        AddressActivity, AddressActivityKind, Block, BlockNumber, Log, TransactionReceipt,
+        TransactionRequest, TransactionSoftConfirmation,
+    },
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
     web3::{
         ethabi,
@@ -345,6 +348,8 @@ pub enum PubSubResult {
     Log(Log),
     TxHash(H256),
     Syncing(bool),
+    SoftConfirmation(TransactionSoftConfirmation),
+    AddressActivity(AddressActivity),
 }
 
 #[cfg(test)]
@@ -0,0 +1,234 @@
+//! Address filter allowing operators to reject L2 transactions based on their initiator and target addresses.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::chain::AddressFilterMode;
+use zksync_types::Address;
+
+#[derive(Debug, Default)]
+struct AddressList {
+    mode: AddressFilterMode,
+    addresses: HashSet<Address>,
+}
+
+/// Filter rejecting L2 transactions based on addresses. In the deny mode, transactions initiated by
+/// or sent to a listed address are rejected. In the allow mode, only transactions initiated by a listed address
+/// are accepted.
+///
+/// The filter is cheaply cloneable; all clones share the same address list, which can be reloaded
+/// using [`Self::run_reloader()`]. The default filter accepts all transactions.
+#[derive(Debug, Clone, Default)]
+pub struct AddressFilter(Arc<RwLock<AddressList>>);
+
+impl AddressFilter {
+    pub fn new(mode: AddressFilterMode, addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self(Arc::new(RwLock::new(AddressList {
+            mode,
+            addresses: addresses.into_iter().collect(),
+        })))
+    }
+
+    /// Loads the filter from a file with one address per line. Empty lines and lines starting
+    /// with `#` are ignored.
+    pub fn load(path: &Path, mode: AddressFilterMode) -> anyhow::Result<Self> {
+        let addresses = read_addresses(path)?;
+        tracing::info!(
+            "Loaded address filter with {} addresses in {mode:?} mode from `{}`",
+            addresses.len(),
+            path.display()
+        );
+        Ok(Self::new(mode, addresses))
+    }
+
+    /// Returns a reason for rejecting a transaction with the specified initiator and target addresses,
+    /// or `None` if the transaction passes the filter.
+    pub fn rejection_reason(
+        &self,
+        initiator: Address,
+        contract_address: Address,
+    ) -> Option<String> {
+        let list = self.0.read().expect("address filter is poisoned");
+        match list.mode {
+            AddressFilterMode::Deny if list.addresses.contains(&initiator) => Some(format!(
+                "transactions from address {initiator:?} are not accepted by the operator"
+            )),
+            AddressFilterMode::Deny if list.addresses.contains(&contract_address) => Some(format!(
+                "transactions to address {contract_address:?} are not accepted by the operator"
+            )),
+            AddressFilterMode::Allow if !list.addresses.contains(&initiator) => Some(format!(
+                "address {initiator:?} is not allowed to send transactions by the operator"
+            )),
+            _ => None,
+        }
+    }
+
+    /// Changes the filter mode; the addresses are retained.
+    pub(crate) fn set_mode(&self, mode: AddressFilterMode) {
+        let mut list = self.0.write().expect("address filter is poisoned");
+        if list.mode != mode {
+            tracing::info!(
+                "Changed address filter mode from {:?} to {mode:?}",
+                list.mode
+            );
+            list.mode = mode;
+        }
+    }
+
+    fn replace_addresses(&self, addresses: HashSet<Address>) {
+        let mut list = self.0.write().expect("address filter is poisoned");
+        if list.addresses != addresses {
+            tracing::info!(
+                "Updated address filter; it now contains {} addresses",
+                addresses.len()
+            );
+            list.addresses = addresses;
+        }
+    }
+
+    /// Periodically reloads addresses from the specified file. If the file cannot be read or parsed,
+    /// the previously loaded addresses are retained.
+    pub async fn run_reloader(
+        self,
+        path: PathBuf,
+        reload_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        loop {
+            if tokio::time::timeout(reload_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+            match read_addresses(&path) {
+                Ok(addresses) => self.replace_addresses(addresses),
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed reloading address filter from `{}`: {err:#}",
+                        path.display()
+                    );
+                }
+            }
+        }
+        tracing::info!("Stop signal received, address filter reloader is shutting down");
+        Ok(())
+    }
+}
+
+fn read_addresses(path: &Path) -> anyhow::Result<HashSet<Address>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed reading address filter file `{}`", path.display()))?;
+    parse_addresses(&contents)
+}
+
+fn parse_addresses(contents: &str) -> anyhow::Result<HashSet<Address>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            line.parse()
+                .with_context(|| format!("invalid address `{line}` on line {}", i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn parsing_addresses() {
+        let contents = "
+            # Comment
+            0x0000000000000000000000000000000000000001
+
+            0000000000000000000000000000000000000002
+        ";
+        let addresses = parse_addresses(contents).unwrap();
+        assert_eq!(
+            addresses,
+            HashSet::from([Address::from_low_u64_be(1), Address::from_low_u64_be(2)])
+        );
+
+        let contents = "0x0000000000000000000000000000000000000001\nnot an address";
+        let err = parse_addresses(contents).unwrap_err();
+        assert!(format!("{err:#}").contains("line 2"), "{err:#}");
+    }
+
+    #[test]
+    fn deny_mode() {
+        let denied = Address::repeat_byte(1);
+        let filter = AddressFilter::new(AddressFilterMode::Deny, [denied]);
+        let other = Address::repeat_byte(2);
+
+        assert!(filter.rejection_reason(other, other).is_none());
+        let reason = filter.rejection_reason(denied, other).unwrap();
+        assert!(reason.contains("from address"), "{reason}");
+        let reason = filter.rejection_reason(other, denied).unwrap();
+        assert!(reason.contains("to address"), "{reason}");
+    }
+
+    #[test]
+    fn allow_mode() {
+        let allowed = Address::repeat_byte(1);
+        let filter = AddressFilter::new(AddressFilterMode::Allow, [allowed]);
+        let other = Address::repeat_byte(2);
+
+        assert!(filter.rejection_reason(allowed, other).is_none());
+        assert!(filter.rejection_reason(other, allowed).is_some());
+    }
+
+    #[test]
+    fn default_filter_accepts_everything() {
+        let filter = AddressFilter::default();
+        let address = Address::repeat_byte(1);
+        assert!(filter.rejection_reason(address, address).is_none());
+    }
+
+    #[tokio::test]
+    async fn reloading_filter() {
+        let denied = Address::repeat_byte(1);
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{denied:?}").unwrap();
+        let filter = AddressFilter::load(file.path(), AddressFilterMode::Deny).unwrap();
+        assert!(filter.rejection_reason(denied, denied).is_some());
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let reloader_task = tokio::spawn(filter.clone().run_reloader(
+            file.path().to_owned(),
+            Duration::from_millis(10),
+            stop_receiver,
+        ));
+
+        let new_denied = Address::repeat_byte(2);
+        fs::write(file.path(), format!("{new_denied:?}\n")).unwrap();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while filter.rejection_reason(new_denied, new_denied).is_none() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("address filter was not reloaded");
+        assert!(filter.rejection_reason(denied, denied).is_none());
+
+        // Invalid file contents should not reset the filter.
+        fs::write(file.path(), "garbage").unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(filter.rejection_reason(new_denied, new_denied).is_some());
+
+        stop_sender.send_replace(true);
+        reloader_task.await.unwrap().unwrap();
+    }
+}
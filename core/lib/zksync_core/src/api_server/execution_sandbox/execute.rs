@@ -9,8 +9,8 @@ use multivm::{
 use tracing::{span, Level};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
-    PackedEthSignature, Transaction, U256,
+    api::state_override::StateOverride, fee::TransactionExecutionMetrics, l2::L2Tx,
+    ExecuteTransactionCommon, Nonce, PackedEthSignature, Transaction, U256,
 };
 
 use super::{apply, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit};
@@ -22,6 +22,8 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// State overrides applied on top of the storage before executing the transaction.
+    pub state_override: Option<StateOverride>,
 }
 
 impl TxExecutionArgs {
@@ -32,12 +34,14 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
         }
     }
 
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         Self {
@@ -46,6 +50,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            state_override,
         }
     }
 
@@ -53,6 +58,7 @@ impl TxExecutionArgs {
         vm_execution_cache_misses_limit: Option<usize>,
         tx: &Transaction,
         base_fee: u64,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         // For L2 transactions we need to explicitly put enough balance into the account of the users
@@ -69,6 +75,7 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            state_override,
         }
     }
 }
@@ -81,10 +88,14 @@ pub(crate) async fn execute_tx_eth_call(
     block_args: BlockArgs,
     vm_execution_cache_misses_limit: Option<usize>,
     custom_tracers: Vec<ApiTracer>,
+    state_override: Option<StateOverride>,
 ) -> VmExecutionResultAndLogs {
     let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
-    let execution_args =
-        TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+    let execution_args = TxExecutionArgs::for_eth_call(
+        enforced_base_fee,
+        vm_execution_cache_misses_limit,
+        state_override,
+    );
 
     if tx.common_data.signature.is_empty() {
         tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
@@ -16,6 +16,18 @@ async fn check_health(health_checks: State<SharedHealthchecks>) -> (StatusCode,
     (response_code, Json(response))
 }
 
+/// Liveness check. Unlike readiness checks, it only fails if the application needs to be restarted
+/// (i.e., if any of its components has panicked).
+async fn check_liveness(health_checks: State<SharedHealthchecks>) -> (StatusCode, Json<AppHealth>) {
+    let response = AppHealth::new(&health_checks).await;
+    let response_code = if response.is_live() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (response_code, Json(response))
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     health_checks: Vec<Box<dyn CheckHealth>>,
@@ -27,7 +39,7 @@ async fn run_server(
         if !health_check_names.insert(health_check_name) {
             tracing::warn!(
                 "Health check with name `{health_check_name}` is defined multiple times; only the last mention \
-                 will be present in `/health` endpoint output"
+                 will be present in health endpoints output"
             );
         }
     }
@@ -38,6 +50,8 @@ async fn run_server(
     let health_checks = SharedHealthchecks::from(health_checks);
     let app = Router::new()
         .route("/health", get(check_health))
+        .route("/readyz", get(check_health))
+        .route("/livez", get(check_liveness))
         .with_state(health_checks);
 
     axum::Server::bind(bind_address)
@@ -9,6 +9,8 @@ use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics
 pub(super) enum MerkleTreeApiMethod {
     Info,
     GetProofs,
+    GetEntries,
+    GetL1BatchTreeData,
 }
 
 /// Metrics for Merkle tree API.
@@ -5,7 +5,7 @@ use std::{fmt, future::Future, net::SocketAddr, pin::Pin};
 use anyhow::Context as _;
 use async_trait::async_trait;
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
     routing, Json, Router,
@@ -13,7 +13,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
 use zksync_merkle_tree::NoVersionError;
-use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_types::{block::L1BatchTreeData, L1BatchNumber, H256, U256};
 
 use self::metrics::{MerkleTreeApiMethod, API_METRICS};
 use crate::metadata_calculator::{AsyncTreeReader, MerkleTreeInfo};
@@ -33,6 +33,34 @@ struct TreeProofsResponse {
     entries: Vec<TreeEntryWithProof>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeEntriesRequest {
+    l1_batch_number: L1BatchNumber,
+    hashed_keys: Vec<U256>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeEntriesResponse {
+    entries: Vec<TreeEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TreeEntry {
+    #[serde(default, skip_serializing_if = "H256::is_zero")]
+    pub value: H256,
+    #[serde(default, skip_serializing_if = "TreeEntryWithProof::is_zero")]
+    pub index: u64,
+}
+
+impl From<zksync_merkle_tree::TreeEntry> for TreeEntry {
+    fn from(src: zksync_merkle_tree::TreeEntry) -> Self {
+        Self {
+            value: src.value,
+            index: src.leaf_index,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct TreeEntryWithProof {
     #[serde(default, skip_serializing_if = "H256::is_zero")]
@@ -96,6 +124,33 @@ pub(crate) trait TreeApiClient {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> anyhow::Result<Vec<TreeEntryWithProof>>;
+
+    /// Obtains values and leaf indices for the specified `hashed_keys` at the specified tree version
+    /// (= L1 batch number). Unlike [`Self::get_proofs()`], this doesn't compute Merkle proofs, so it's much cheaper.
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>>;
+
+    /// Obtains the tree root hash and the next leaf index after the specified L1 batch.
+    async fn get_l1_batch_tree_data(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchTreeData>;
+}
+
+/// Checks whether an error returned by a [`TreeApiClient`] is caused by a missing tree version, i.e.,
+/// the requested L1 batch is not processed by the tree yet or was pruned.
+pub(crate) fn is_missing_version_error(err: &anyhow::Error) -> bool {
+    if err.downcast_ref::<NoVersionError>().is_some() {
+        return true;
+    }
+    // `TreeApiHttpClient` receives missing versions as 404 responses; see `TreeApiError`.
+    let status = err
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status);
+    status == Some(reqwest::StatusCode::NOT_FOUND)
 }
 
 /// In-memory client implementation.
@@ -114,6 +169,26 @@ impl TreeApiClient for AsyncTreeReader {
             .await
             .map_err(Into::into)
     }
+
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        self.get_entries_inner(l1_batch_number, hashed_keys)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_l1_batch_tree_data(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchTreeData> {
+        self.clone()
+            .l1_batch_tree_data(l1_batch_number)
+            .await
+            .map_err(Into::into)
+    }
 }
 
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
@@ -122,6 +197,8 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    entries_url: String,
+    l1_batches_url: String,
 }
 
 impl TreeApiHttpClient {
@@ -130,6 +207,8 @@ impl TreeApiHttpClient {
             inner: reqwest::Client::new(),
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            entries_url: format!("{url_base}/entries"),
+            l1_batches_url: format!("{url_base}/l1_batches"),
         }
     }
 }
@@ -175,6 +254,52 @@ impl TreeApiClient for TreeApiHttpClient {
         })?;
         Ok(response.entries)
     }
+
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        let response = self
+            .inner
+            .post(&self.entries_url)
+            .json(&TreeEntriesRequest {
+                l1_batch_number,
+                hashed_keys,
+            })
+            .send()
+            .await
+            .with_context(|| {
+                format!("Failed requesting entries for L1 batch #{l1_batch_number}")
+            })?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting entries for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        let response: TreeEntriesResponse = response.json().await.with_context(|| {
+            format!("Failed deserializing entries for L1 batch #{l1_batch_number}")
+        })?;
+        Ok(response.entries)
+    }
+
+    async fn get_l1_batch_tree_data(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchTreeData> {
+        let response = self
+            .inner
+            .get(format!("{}/{l1_batch_number}", self.l1_batches_url))
+            .send()
+            .await
+            .with_context(|| {
+                format!("Failed requesting tree data for L1 batch #{l1_batch_number}")
+            })?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting tree data for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        response.json().await.with_context(|| {
+            format!("Failed deserializing tree data for L1 batch #{l1_batch_number}")
+        })
+    }
 }
 
 impl AsyncTreeReader {
@@ -211,6 +336,42 @@ impl AsyncTreeReader {
         Ok(Json(response))
     }
 
+    async fn get_entries_inner(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let entries = self.clone().entries(l1_batch_number, hashed_keys).await?;
+        Ok(entries.into_iter().map(TreeEntry::from).collect())
+    }
+
+    async fn get_entries_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeEntriesRequest>,
+    ) -> Result<Json<TreeEntriesResponse>, TreeApiError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetEntries].start();
+        let entries = this
+            .get_entries_inner(request.l1_batch_number, request.hashed_keys)
+            .await
+            .map_err(TreeApiError::NoTreeVersion)?;
+        let response = TreeEntriesResponse { entries };
+        latency.observe();
+        Ok(Json(response))
+    }
+
+    async fn get_l1_batch_tree_data_handler(
+        State(this): State<Self>,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<Json<L1BatchTreeData>, TreeApiError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetL1BatchTreeData].start();
+        let tree_data = this
+            .l1_batch_tree_data(L1BatchNumber(l1_batch_number))
+            .await
+            .map_err(TreeApiError::NoTreeVersion)?;
+        latency.observe();
+        Ok(Json(tree_data))
+    }
+
     fn create_api_server(
         self,
         bind_address: &SocketAddr,
@@ -221,6 +382,11 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route("/entries", routing::post(Self::get_entries_handler))
+            .route(
+                "/l1_batches/:l1_batch_number",
+                routing::get(Self::get_l1_batch_tree_data_handler),
+            )
             .with_state(self);
 
         let server = axum::Server::try_bind(bind_address)
@@ -46,20 +46,62 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
-    for (i, proof) in proofs.into_iter().enumerate() {
+    for (i, proof) in proofs.iter().enumerate() {
         let should_be_present = i < 10;
         assert_eq!(proof.index == 0, !should_be_present);
         assert!(!proof.merkle_path.is_empty());
     }
 
+    // Entries should be consistent with the values and indices in proofs.
+    let entries = api_client
+        .get_entries(L1BatchNumber(5), hashed_keys.clone())
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 20);
+    for (entry, proof) in entries.iter().zip(&proofs) {
+        assert_eq!(entry.value, proof.value);
+        assert_eq!(entry.index, proof.index);
+    }
+
+    // Query proofs for a historical tree version. Only the first 2 keys are inserted in L1 batch #1.
+    let proofs = api_client
+        .get_proofs(L1BatchNumber(1), hashed_keys)
+        .await
+        .unwrap();
+    assert_eq!(proofs.len(), 20);
+    for (i, proof) in proofs.into_iter().enumerate() {
+        let should_be_present = i < 2;
+        assert_eq!(proof.index == 0, !should_be_present);
+        assert!(!proof.merkle_path.is_empty());
+    }
+
+    let tree_data = api_client
+        .get_l1_batch_tree_data(L1BatchNumber(5))
+        .await
+        .unwrap();
+    assert_eq!(tree_data.hash, tree_info.root_hash);
+    assert_eq!(tree_data.rollup_last_leaf_index, tree_info.leaf_count + 1);
+    let err = api_client
+        .get_l1_batch_tree_data(L1BatchNumber(10))
+        .await
+        .unwrap_err();
+    assert!(is_missing_version_error(&err), "{err:?}");
+
+    let err = api_client
+        .get_entries(L1BatchNumber(10), vec![])
+        .await
+        .unwrap_err();
+    assert!(is_missing_version_error(&err), "{err:?}");
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
         .unwrap_err();
+    assert!(is_missing_version_error(&err), "{err:?}");
     let err = format!("{err:?}");
     // Check that the error message contains all necessary info to troubleshoot it.
     assert!(
@@ -0,0 +1,141 @@
+//! Custom filters for transactions submitted via the API.
+
+use std::{fmt, sync::Arc};
+
+use zksync_types::l2::L2Tx;
+
+/// Custom check applied to every L2 transaction submitted to the node before it's inserted into the mempool.
+///
+/// Filters allow node operators to enforce policies not covered by built-in checks (e.g., calldata patterns
+/// or restrictions on contract deployment). Filters are registered in [`TxFilters`] and passed
+/// to [`TxSenderBuilder::with_tx_filters()`](super::TxSenderBuilder::with_tx_filters()).
+pub trait TxFilter: fmt::Debug + Send + Sync + 'static {
+    /// Name of the filter used in rejection errors and logs.
+    fn name(&self) -> &'static str;
+
+    /// Returns a human-readable reason for rejecting the transaction, or `None` if the transaction is allowed.
+    fn rejection_reason(&self, tx: &L2Tx) -> Option<String>;
+}
+
+/// Rejection of a transaction by a [`TxFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxRejection {
+    /// Name of the rejecting filter.
+    pub filter: &'static str,
+    /// Reason for the rejection.
+    pub reason: String,
+}
+
+/// Ordered collection of [`TxFilter`]s.
+#[derive(Debug, Clone, Default)]
+pub struct TxFilters(Vec<Arc<dyn TxFilter>>);
+
+impl TxFilters {
+    /// Registers a filter. Filters are applied in the order of their registration; the first rejection wins.
+    pub fn register<F: TxFilter>(&mut self, filter: F) -> &mut Self {
+        self.0.push(Arc::new(filter));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies all registered filters to the transaction.
+    pub(super) fn check(&self, tx: &L2Tx) -> Result<(), TxRejection> {
+        for filter in &self.0 {
+            if let Some(reason) = filter.rejection_reason(tx) {
+                return Err(TxRejection {
+                    filter: filter.name(),
+                    reason,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::Fee, L2ChainId, Nonce, H256};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct NoContractDeployments;
+
+    impl TxFilter for NoContractDeployments {
+        fn name(&self) -> &'static str {
+            "no_contract_deployments"
+        }
+
+        fn rejection_reason(&self, tx: &L2Tx) -> Option<String> {
+            let has_factory_deps = tx
+                .execute
+                .factory_deps
+                .as_ref()
+                .map_or(false, |deps| !deps.is_empty());
+            has_factory_deps.then(|| "contract deployments are disabled".to_owned())
+        }
+    }
+
+    #[derive(Debug)]
+    struct CalldataPrefix(Vec<u8>);
+
+    impl TxFilter for CalldataPrefix {
+        fn name(&self) -> &'static str {
+            "calldata_prefix"
+        }
+
+        fn rejection_reason(&self, tx: &L2Tx) -> Option<String> {
+            let calldata = &tx.execute.calldata;
+            calldata
+                .starts_with(&self.0)
+                .then(|| format!("calldata starts with 0x{}", hex::encode(&self.0)))
+        }
+    }
+
+    fn create_tx(calldata: Vec<u8>, factory_deps: Option<Vec<Vec<u8>>>) -> L2Tx {
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        L2Tx::new_signed(
+            Default::default(),
+            calldata,
+            Nonce(0),
+            fee,
+            0.into(),
+            L2ChainId::default(),
+            &H256::repeat_byte(1),
+            factory_deps,
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn applying_tx_filters() {
+        let mut filters = TxFilters::default();
+        let tx = create_tx(vec![1, 2, 3], Some(vec![vec![0; 32]]));
+        filters.check(&tx).unwrap();
+
+        filters
+            .register(CalldataPrefix(vec![1, 2]))
+            .register(NoContractDeployments);
+        assert!(!filters.is_empty());
+
+        let rejection = filters.check(&tx).unwrap_err();
+        assert_eq!(rejection.filter, "calldata_prefix");
+        assert_eq!(rejection.reason, "calldata starts with 0x0102");
+
+        let tx = create_tx(vec![3, 2, 1], Some(vec![vec![0; 32]]));
+        let rejection = filters.check(&tx).unwrap_err();
+        assert_eq!(rejection.filter, "no_contract_deployments");
+
+        let tx = create_tx(vec![3, 2, 1], None);
+        filters.check(&tx).unwrap();
+    }
+}
@@ -1,7 +1,13 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, num::NonZeroU32, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    num::NonZeroU32,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use governor::{
     clock::MonotonicClock,
     middleware::NoOpMiddleware,
@@ -18,23 +24,36 @@ use multivm::{
         },
     },
 };
+use tokio::sync::watch;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
+    api::{
+        sealing::{
+            L1BatchSealStatus, SealCriterionStatus, SealSimulation, SimulatedSealResolution,
+        },
+        state_override::StateOverride,
+        FeeEstimate, FeeInclusionHint,
+    },
+    ethabi,
     fee::{Fee, TransactionExecutionMetrics},
     get_code_key, get_intrinsic_constants,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
+    tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     utils::storage_key_for_eth_balance,
-    AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, Nonce, PackedEthSignature,
-    ProtocolVersionId, Transaction, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    AccountTreeId, Address, ExecuteTransactionCommon, L1BatchNumber, L2ChainId, Nonce,
+    PackedEthSignature, ProtocolVersionId, Transaction, H160, H256, MAX_GAS_PER_PUBDATA_BYTE,
+    MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch};
 
-pub(super) use self::{proxy::TxProxy, result::SubmitTxError};
+pub use self::filter::{TxFilter, TxFilters, TxRejection};
+pub(super) use self::proxy::TxProxy;
+pub(crate) use self::result::SubmitTxError;
 use crate::{
+    address_filter::AddressFilter,
     api_server::{
         execution_sandbox::{
             adjust_l1_gas_price_for_tx, execute_tx_eth_call, execute_tx_with_pending_state,
@@ -43,18 +62,55 @@ use crate::{
         },
         tx_sender::result::ApiCallResult,
     },
+    config_watcher::ConfigOverrides,
+    gas_tracker::{gas_count_from_metrics, gas_count_from_writes, new_block_gas_count},
     l1_gas_price::L1GasPriceProvider,
     metrics::{TxStage, APP_METRICS},
-    state_keeper::seal_criteria::{ConditionalSealer, SealData},
+    sponsored_txs::SponsorshipPolicy,
+    state_keeper::seal_criteria::{ConditionalSealer, SealData, SealResolution},
+    sync_layer::FailoverMainNodeClient,
 };
 
+mod filter;
 mod proxy;
 mod result;
+mod revert_reason;
+#[cfg(test)]
+mod tests;
 
 /// Type alias for the rate limiter implementation.
 type TxSenderRateLimiter =
     RateLimiter<NotKeyed, InMemoryState, MonotonicClock, NoOpMiddleware<Instant>>;
 
+/// Rate limiter for tx submissions. The limit may change at runtime, in which case the underlying limiter
+/// is recreated.
+#[derive(Default)]
+struct SubmissionRateLimiter(RwLock<Option<(u32, TxSenderRateLimiter)>>);
+
+impl SubmissionRateLimiter {
+    /// Checks whether a transaction can be submitted given the current limit (in transactions per second).
+    fn check(&self, transactions_per_sec: Option<u32>) -> bool {
+        let Some(transactions_per_sec) = transactions_per_sec else {
+            return true;
+        };
+        let limiter = self.0.read().expect("rate limiter is poisoned");
+        if let Some((limit, limiter)) = &*limiter {
+            if *limit == transactions_per_sec {
+                return limiter.check().is_ok();
+            }
+        }
+        drop(limiter);
+
+        let limiter = RateLimiter::direct_with_clock(
+            Quota::per_second(NonZeroU32::new(transactions_per_sec).unwrap()),
+            &MonotonicClock,
+        );
+        let is_allowed = limiter.check().is_ok();
+        *self.0.write().expect("rate limiter is poisoned") = Some((transactions_per_sec, limiter));
+        is_allowed
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MultiVMBaseSystemContracts {
     /// Contracts to be used for pre-virtual-blocks protocol versions.
@@ -140,13 +196,19 @@ pub struct TxSenderBuilder {
     replica_connection_pool: ConnectionPool,
     /// Connection pool for write requests. If not set, `proxy` must be set.
     master_connection_pool: Option<ConnectionPool>,
-    /// Rate limiter for tx submissions.
-    rate_limiter: Option<TxSenderRateLimiter>,
+    /// Limit for tx submissions per second.
+    transactions_per_sec_limit: Option<u32>,
     /// Proxy to submit transactions to the network. If not set, `master_connection_pool` must be set.
     proxy: Option<TxProxy>,
     /// Actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Filter rejecting transactions based on their initiator and target addresses.
+    address_filter: Option<AddressFilter>,
+    /// Custom filters applied to submitted transactions.
+    tx_filters: TxFilters,
+    /// Runtime overrides for the fair L2 gas price, seal criteria limits and the rate limit.
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
 }
 
 impl TxSenderBuilder {
@@ -155,25 +217,25 @@ impl TxSenderBuilder {
             config,
             replica_connection_pool,
             master_connection_pool: None,
-            rate_limiter: None,
+            transactions_per_sec_limit: None,
             proxy: None,
             state_keeper_config: None,
+            address_filter: None,
+            tx_filters: TxFilters::default(),
+            config_overrides: None,
         }
     }
 
     pub fn with_rate_limiter(self, transactions_per_sec: u32) -> Self {
-        let rate_limiter = RateLimiter::direct_with_clock(
-            Quota::per_second(NonZeroU32::new(transactions_per_sec).unwrap()),
-            &MonotonicClock,
-        );
+        assert!(transactions_per_sec > 0, "Rate limit must be positive");
         Self {
-            rate_limiter: Some(rate_limiter),
+            transactions_per_sec_limit: Some(transactions_per_sec),
             ..self
         }
     }
 
-    pub fn with_tx_proxy(mut self, main_node_url: &str) -> Self {
-        self.proxy = Some(TxProxy::new(main_node_url));
+    pub fn with_tx_proxy(mut self, main_node_client: FailoverMainNodeClient) -> Self {
+        self.proxy = Some(TxProxy::new(main_node_client));
         self
     }
 
@@ -187,6 +249,22 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_address_filter(mut self, address_filter: AddressFilter) -> Self {
+        self.address_filter = Some(address_filter);
+        self
+    }
+
+    pub fn with_config_overrides(mut self, overrides: watch::Receiver<ConfigOverrides>) -> Self {
+        self.config_overrides = Some(overrides);
+        self
+    }
+
+    /// Sets custom filters applied to submitted transactions before they are inserted into the mempool.
+    pub fn with_tx_filters(mut self, tx_filters: TxFilters) -> Self {
+        self.tx_filters = tx_filters;
+        self
+    }
+
     pub async fn build<G: L1GasPriceProvider>(
         self,
         l1_gas_price_source: Arc<G>,
@@ -205,9 +283,17 @@ impl TxSenderBuilder {
             replica_connection_pool: self.replica_connection_pool,
             l1_gas_price_source,
             api_contracts,
-            rate_limiter: self.rate_limiter,
+            transactions_per_sec_limit: self.transactions_per_sec_limit,
+            rate_limiter: SubmissionRateLimiter::default(),
             proxy: self.proxy,
+            sponsorship_policy: self
+                .state_keeper_config
+                .as_ref()
+                .and_then(SponsorshipPolicy::from_config),
             state_keeper_config: self.state_keeper_config,
+            address_filter: self.address_filter,
+            tx_filters: self.tx_filters,
+            config_overrides: self.config_overrides,
             vm_concurrency_limiter,
             storage_caches,
         }))
@@ -222,7 +308,13 @@ impl TxSenderBuilder {
 pub struct TxSenderConfig {
     pub fee_account_addr: Address,
     pub gas_price_scale_factor: f64,
+    /// Multiplier for the L1 gas price used in fee estimates for transactions not fitting into the pending L1 batch.
+    pub batch_boundary_gas_price_scale_factor: f64,
     pub max_nonce_ahead: u32,
+    /// Minimum fee increase (in percent) required to replace a pending transaction with the same nonce.
+    pub tx_replacement_fee_bump_percent: u32,
+    /// Maximum time into the future that a transaction deadline may be set to.
+    pub max_tx_deadline: Duration,
     pub max_allowed_l2_tx_gas_limit: u32,
     pub fair_l2_gas_price: u64,
     pub vm_execution_cache_misses_limit: Option<usize>,
@@ -239,7 +331,11 @@ impl TxSenderConfig {
         Self {
             fee_account_addr: state_keeper_config.fee_account_addr,
             gas_price_scale_factor: web3_json_config.gas_price_scale_factor,
+            batch_boundary_gas_price_scale_factor: web3_json_config
+                .batch_boundary_gas_price_scale_factor(),
             max_nonce_ahead: web3_json_config.max_nonce_ahead,
+            tx_replacement_fee_bump_percent: web3_json_config.tx_replacement_fee_bump_percent(),
+            max_tx_deadline: web3_json_config.max_tx_deadline(),
             max_allowed_l2_tx_gas_limit: state_keeper_config.max_allowed_l2_tx_gas_limit,
             fair_l2_gas_price: state_keeper_config.fair_l2_gas_price,
             vm_execution_cache_misses_limit: web3_json_config.vm_execution_cache_misses_limit,
@@ -250,6 +346,70 @@ impl TxSenderConfig {
     }
 }
 
+/// Performs stateless checks of the fee and gas parameters of an L2 transaction. These checks are a part
+/// of the transaction validation in [`TxSender`], and are also applied to transactions proposed
+/// by an external block builder, which bypass the API server.
+pub(crate) fn validate_tx_fee_and_gas(
+    tx: &L2Tx,
+    max_allowed_l2_tx_gas_limit: u32,
+    fair_l2_gas_price: u64,
+    l1_gas_price: u64,
+) -> Result<(), SubmitTxError> {
+    let max_gas = U256::from(u32::MAX);
+    if tx.common_data.fee.gas_limit > max_gas || tx.common_data.fee.gas_per_pubdata_limit > max_gas
+    {
+        return Err(SubmitTxError::GasLimitIsTooBig);
+    }
+
+    // TODO (SMA-1715): do not subsidize the overhead for the transaction
+
+    if tx.common_data.fee.gas_limit > max_allowed_l2_tx_gas_limit.into() {
+        tracing::info!(
+            "Submitted Tx is Unexecutable {:?} because of GasLimitIsTooBig {}",
+            tx.hash(),
+            tx.common_data.fee.gas_limit,
+        );
+        return Err(SubmitTxError::GasLimitIsTooBig);
+    }
+    if tx.common_data.fee.max_fee_per_gas < fair_l2_gas_price.into() {
+        tracing::info!(
+            "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
+            tx.hash(),
+            tx.common_data.fee.max_fee_per_gas
+        );
+        return Err(SubmitTxError::MaxFeePerGasTooLow);
+    }
+    if tx.common_data.fee.max_fee_per_gas < tx.common_data.fee.max_priority_fee_per_gas {
+        tracing::info!(
+            "Submitted Tx is Unexecutable {:?} because of MaxPriorityFeeGreaterThanMaxFee {}",
+            tx.hash(),
+            tx.common_data.fee.max_fee_per_gas
+        );
+        return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
+    }
+    if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
+        return Err(SubmitTxError::TooManyFactoryDependencies(
+            tx.execute.factory_deps_length(),
+            MAX_NEW_FACTORY_DEPS,
+        ));
+    }
+
+    let (_, gas_per_pubdata_byte) =
+        derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+    let effective_gas_per_pubdata = cmp::min(
+        tx.common_data.fee.gas_per_pubdata_limit,
+        gas_per_pubdata_byte.into(),
+    );
+
+    let intrinsic_consts = get_intrinsic_constants();
+    let min_gas_limit = U256::from(intrinsic_consts.l2_tx_intrinsic_gas)
+        + U256::from(intrinsic_consts.l2_tx_intrinsic_pubdata) * effective_gas_per_pubdata;
+    if tx.common_data.fee.gas_limit < min_gas_limit {
+        return Err(SubmitTxError::IntrinsicGas);
+    }
+    Ok(())
+}
+
 pub struct TxSenderInner<G> {
     pub(super) sender_config: TxSenderConfig,
     pub master_connection_pool: Option<ConnectionPool>,
@@ -257,14 +417,24 @@ pub struct TxSenderInner<G> {
     // Used to keep track of gas prices for the fee ticker.
     pub l1_gas_price_source: Arc<G>,
     pub(super) api_contracts: ApiContracts,
-    /// Optional rate limiter that will limit the amount of transactions per second sent from a single entity.
-    rate_limiter: Option<TxSenderRateLimiter>,
+    /// Optional limit for the amount of transactions per second sent from a single entity.
+    transactions_per_sec_limit: Option<u32>,
+    rate_limiter: SubmissionRateLimiter,
     /// Optional transaction proxy to be used for transaction submission.
     pub(super) proxy: Option<TxProxy>,
     /// An up-to-date version of the state keeper config.
     /// This field may be omitted on the external node, since the configuration may change unexpectedly.
     /// If this field is set to `None`, `TxSender` will assume that any transaction is executable.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Optional filter rejecting transactions based on their initiator and target addresses.
+    address_filter: Option<AddressFilter>,
+    /// Custom filters applied to submitted transactions.
+    tx_filters: TxFilters,
+    /// Policy restricting transactions paid for by the operator-funded sponsor paymaster. Derived from
+    /// `state_keeper_config`, so it's not checked on the external node (the main node checks it instead).
+    sponsorship_policy: Option<SponsorshipPolicy>,
+    /// Runtime overrides for the fair L2 gas price, seal criteria limits and the rate limit.
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     /// Used to limit the amount of VMs that can be executed simultaneously.
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
@@ -297,11 +467,67 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         self.0.storage_caches.clone()
     }
 
-    #[tracing::instrument(skip(self, tx))]
+    /// Returns the fair L2 gas price taking runtime overrides into account.
+    fn fair_l2_gas_price(&self) -> u64 {
+        self.0
+            .config_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.borrow().fair_l2_gas_price)
+            .unwrap_or(self.0.sender_config.fair_l2_gas_price)
+    }
+
+    #[tracing::instrument(skip(self, tx), fields(tx_hash = ?tx.hash()))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
-        if let Some(rate_limiter) = &self.0.rate_limiter {
-            if rate_limiter.check().is_err() {
-                return Err(SubmitTxError::RateLimitExceeded);
+        self.submit_tx_with_deadline(tx, None).await
+    }
+
+    /// Submits a transaction that is valid until the specified UNIX timestamp (in seconds). If the transaction
+    /// isn't included into a block before the deadline, it's dropped from the mempool and reported as expired.
+    #[tracing::instrument(skip(self, tx), fields(tx_hash = ?tx.hash()))]
+    pub async fn submit_tx_with_deadline(
+        &self,
+        tx: L2Tx,
+        valid_until: Option<u64>,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        if let Some(valid_until) = valid_until {
+            let now = seconds_since_epoch();
+            if valid_until <= now {
+                return Err(SubmitTxError::DeadlineExpired);
+            }
+            let max_deadline = now.saturating_add(self.0.sender_config.max_tx_deadline.as_secs());
+            if valid_until > max_deadline {
+                return Err(SubmitTxError::DeadlineTooFar(max_deadline));
+            }
+        }
+        let transactions_per_sec_limit = self
+            .0
+            .config_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.borrow().transactions_per_sec_limit)
+            .or(self.0.transactions_per_sec_limit);
+        if !self.0.rate_limiter.check(transactions_per_sec_limit) {
+            return Err(SubmitTxError::RateLimitExceeded);
+        }
+        if let Some(address_filter) = &self.0.address_filter {
+            let reason = address_filter
+                .rejection_reason(tx.initiator_account(), tx.execute.contract_address);
+            if let Some(reason) = reason {
+                return Err(SubmitTxError::AddressFiltered(reason));
+            }
+        }
+        if let Err(rejection) = self.0.tx_filters.check(&tx) {
+            tracing::debug!(
+                "Transaction {:?} rejected by `{}` filter: {}",
+                tx.hash(),
+                rejection.filter,
+                rejection.reason
+            );
+            return Err(SubmitTxError::Filtered(rejection));
+        }
+        if let Some(policy) = &self.0.sponsorship_policy {
+            let paymaster = tx.common_data.paymaster_params.paymaster;
+            if let Some(reason) = policy.rejection_reason(paymaster, &tx.execute) {
+                return Err(SubmitTxError::NotSponsored(reason));
             }
         }
 
@@ -354,7 +580,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             // But before we do that, save the tx to cache in case someone will request it
             // Before it reaches the main node.
             proxy.save_tx(tx.hash(), tx.clone()).await;
-            proxy.submit_tx(&tx).await?;
+            proxy.submit_tx(&tx, valid_until).await?;
             // Now, after we are sure that the tx is on the main node, remove it from cache
             // since we don't want to store txs that might have been replaced or otherwise removed
             // from the mempool.
@@ -372,17 +598,17 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let expected_nonce = self.get_expected_nonce(&tx).await;
-        let submission_res_handle = self
+        let mut storage = self
             .0
             .master_connection_pool
             .as_ref()
             .unwrap() // Checked above
             .access_storage_tagged("api")
             .await
-            .unwrap()
-            .transactions_dal()
-            .insert_transaction_l2(tx, tx_metrics)
-            .await;
+            .unwrap();
+        let submission_res_handle = self
+            .insert_l2_tx(&mut storage, tx, tx_metrics, valid_until)
+            .await?;
 
         APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
 
@@ -401,11 +627,63 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         }
     }
 
+    /// Inserts `tx` into the mempool, replacing a pending transaction with the same initiator and nonce
+    /// if `tx` pays sufficiently higher fees.
+    async fn insert_l2_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: L2Tx,
+        tx_metrics: TransactionExecutionMetrics,
+        valid_until: Option<u64>,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        // The replacement check and the insertion must be performed in a single DB transaction;
+        // otherwise, the replaced transaction may change between them.
+        let mut transaction = storage.start_transaction().await.unwrap();
+        let replaced_tx = transaction
+            .transactions_dal()
+            .get_replaceable_l2_tx(tx.initiator_account(), tx.common_data.nonce)
+            .await;
+        if let Some((replaced_hash, replaced_fee)) = replaced_tx {
+            self.ensure_replacement_fee(&tx, replaced_hash, &replaced_fee)?;
+        }
+        let submission_result = transaction
+            .transactions_dal()
+            .insert_transaction_l2_with_deadline(tx, tx_metrics, valid_until)
+            .await;
+        transaction.commit().await.unwrap();
+        Ok(submission_result)
+    }
+
+    /// Checks that `tx` pays sufficiently higher fees than the pending transaction it replaces.
+    /// Resubmitting the same transaction is not considered a replacement.
+    fn ensure_replacement_fee(
+        &self,
+        tx: &L2Tx,
+        replaced_hash: H256,
+        replaced_fee: &Fee,
+    ) -> Result<(), SubmitTxError> {
+        if replaced_hash == tx.hash() {
+            return Ok(());
+        }
+        let bump_percent = self.0.sender_config.tx_replacement_fee_bump_percent;
+        let min_fee = |value: U256| value * (100 + bump_percent) / 100;
+        let fee = &tx.common_data.fee;
+        if fee.max_fee_per_gas < min_fee(replaced_fee.max_fee_per_gas)
+            || fee.max_priority_fee_per_gas < min_fee(replaced_fee.max_priority_fee_per_gas)
+        {
+            return Err(SubmitTxError::ReplacementUnderpriced(
+                bump_percent,
+                replaced_hash,
+            ));
+        }
+        Ok(())
+    }
+
     fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             l1_gas_price: self.0.l1_gas_price_source.estimate_effective_gas_price(),
-            fair_l2_gas_price: self.0.sender_config.fair_l2_gas_price,
+            fair_l2_gas_price: self.fair_l2_gas_price(),
             base_system_contracts: self.0.api_contracts.eth_call.clone(),
             caches: self.storage_caches(),
             validation_computational_gas_limit: self
@@ -417,62 +695,13 @@ impl<G: L1GasPriceProvider> TxSender<G> {
     }
 
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
-        let max_gas = U256::from(u32::MAX);
-        if tx.common_data.fee.gas_limit > max_gas
-            || tx.common_data.fee.gas_per_pubdata_limit > max_gas
-        {
-            return Err(SubmitTxError::GasLimitIsTooBig);
-        }
-
-        // TODO (SMA-1715): do not subsidize the overhead for the transaction
-
-        if tx.common_data.fee.gas_limit > self.0.sender_config.max_allowed_l2_tx_gas_limit.into() {
-            tracing::info!(
-                "Submitted Tx is Unexecutable {:?} because of GasLimitIsTooBig {}",
-                tx.hash(),
-                tx.common_data.fee.gas_limit,
-            );
-            return Err(SubmitTxError::GasLimitIsTooBig);
-        }
-        if tx.common_data.fee.max_fee_per_gas < self.0.sender_config.fair_l2_gas_price.into() {
-            tracing::info!(
-                "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
-                tx.hash(),
-                tx.common_data.fee.max_fee_per_gas
-            );
-            return Err(SubmitTxError::MaxFeePerGasTooLow);
-        }
-        if tx.common_data.fee.max_fee_per_gas < tx.common_data.fee.max_priority_fee_per_gas {
-            tracing::info!(
-                "Submitted Tx is Unexecutable {:?} because of MaxPriorityFeeGreaterThanMaxFee {}",
-                tx.hash(),
-                tx.common_data.fee.max_fee_per_gas
-            );
-            return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
-        }
-        if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
-            return Err(SubmitTxError::TooManyFactoryDependencies(
-                tx.execute.factory_deps_length(),
-                MAX_NEW_FACTORY_DEPS,
-            ));
-        }
-
         let l1_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
-        let (_, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
+        validate_tx_fee_and_gas(
+            tx,
+            self.0.sender_config.max_allowed_l2_tx_gas_limit,
+            self.fair_l2_gas_price(),
             l1_gas_price,
-            self.0.sender_config.fair_l2_gas_price,
-        );
-        let effective_gas_per_pubdata = cmp::min(
-            tx.common_data.fee.gas_per_pubdata_limit,
-            gas_per_pubdata_byte.into(),
-        );
-
-        let intrinsic_consts = get_intrinsic_constants();
-        let min_gas_limit = U256::from(intrinsic_consts.l2_tx_intrinsic_gas)
-            + U256::from(intrinsic_consts.l2_tx_intrinsic_pubdata) * effective_gas_per_pubdata;
-        if tx.common_data.fee.gas_limit < min_gas_limit {
-            return Err(SubmitTxError::IntrinsicGas);
-        }
+        )?;
 
         // We still double-check the nonce manually
         // to make sure that only the correct nonce is submitted and the transaction's hashes never repeat
@@ -541,8 +770,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         // Estimate the minimum fee price user will agree to.
         let gas_price = cmp::min(
             tx.common_data.fee.max_fee_per_gas,
-            U256::from(self.0.sender_config.fair_l2_gas_price)
-                + tx.common_data.fee.max_priority_fee_per_gas,
+            U256::from(self.fair_l2_gas_price()) + tx.common_data.fee.max_priority_fee_per_gas,
         );
         let max_fee = tx.common_data.fee.gas_limit * gas_price;
         let max_fee_and_value = max_fee + tx.execute.value;
@@ -586,6 +814,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         tx_gas_limit: u32,
         l1_gas_price: u64,
         base_fee: u64,
+        state_override: Option<StateOverride>,
     ) -> (VmExecutionResultAndLogs, TransactionExecutionMetrics) {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -617,8 +846,12 @@ impl<G: L1GasPriceProvider> TxSender<G> {
 
         let shared_args = self.shared_args_for_gas_estimate(l1_gas_price);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
-            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        let execution_args = TxExecutionArgs::for_gas_estimate(
+            vm_execution_cache_misses_limit,
+            &tx,
+            base_fee,
+            state_override,
+        );
         let (exec_result, tx_metrics) = execute_tx_with_pending_state(
             vm_permit,
             shared_args,
@@ -636,7 +869,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         TxSharedArgs {
             operator_account: AccountTreeId::new(config.fee_account_addr),
             l1_gas_price,
-            fair_l2_gas_price: config.fair_l2_gas_price,
+            fair_l2_gas_price: self.fair_l2_gas_price(),
             // We want to bypass the computation gas limit check for gas estimation
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
@@ -647,29 +880,121 @@ impl<G: L1GasPriceProvider> TxSender<G> {
 
     pub async fn get_txs_fee_in_wei(
         &self,
-        mut tx: Transaction,
+        tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
     ) -> Result<Fee, SubmitTxError> {
+        let estimate = self
+            .estimate_fee(
+                tx,
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+                state_override,
+                false,
+            )
+            .await?;
+        Ok(estimate.fee)
+    }
+
+    /// Estimates the fee for a transaction. If `with_inclusion_hint` is set, the estimate takes into account
+    /// the capacity left in the pending L1 batch.
+    ///
+    /// If the transaction isn't expected to fit into the pending L1 batch, it will be included into the next one,
+    /// which may have higher fee params. In this case, the fee is re-estimated with the L1 gas price scaled by
+    /// [`TxSenderConfig::batch_boundary_gas_price_scale_factor`], so that the estimate doesn't fail at execution time.
+    pub async fn estimate_fee(
+        &self,
+        tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
+        with_inclusion_hint: bool,
+    ) -> Result<FeeEstimate, SubmitTxError> {
+        let result = self
+            .estimate_fee_with_l1_gas_price_scale(
+                tx.clone(),
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+                state_override.clone(),
+                1.0,
+            )
+            .await;
+        let (fee, estimated_tx, tx_metrics) = self
+            .decode_revert_reason(result, tx.execute.contract_address)
+            .await?;
+        if !with_inclusion_hint {
+            return Ok(FeeEstimate {
+                fee,
+                inclusion_hint: None,
+            });
+        }
+        let inclusion_hint = self
+            .pending_l1_batch_inclusion_hint(estimated_tx, &tx_metrics)
+            .await?;
+
+        let l1_gas_price_scale_factor = self.0.sender_config.batch_boundary_gas_price_scale_factor;
+        let fee = match &inclusion_hint {
+            Some(hint) if !hint.fits_pending_l1_batch && l1_gas_price_scale_factor > 1.0 => {
+                tracing::debug!(
+                    "Transaction is not expected to fit into the pending L1 batch because of `{}`; \
+                     re-estimating fee with L1 gas price scaled by {l1_gas_price_scale_factor}",
+                    hint.limiting_criterion.as_deref().unwrap_or_default()
+                );
+                let (fee, ..) = self
+                    .estimate_fee_with_l1_gas_price_scale(
+                        tx,
+                        estimated_fee_scale_factor,
+                        acceptable_overestimation,
+                        state_override,
+                        l1_gas_price_scale_factor,
+                    )
+                    .await?;
+                fee
+            }
+            _ => fee,
+        };
+        Ok(FeeEstimate {
+            fee,
+            inclusion_hint,
+        })
+    }
+
+    /// Returns the estimated fee together with the transaction and its execution metrics for the suggested gas limit.
+    async fn estimate_fee_with_l1_gas_price_scale(
+        &self,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
+        l1_gas_price_scale_factor: f64,
+    ) -> Result<(Fee, Transaction, TransactionExecutionMetrics), SubmitTxError> {
         let estimation_started_at = Instant::now();
+        if let (Some(policy), ExecuteTransactionCommon::L2(common_data)) =
+            (&self.0.sponsorship_policy, &tx.common_data)
+        {
+            let paymaster = common_data.paymaster_params.paymaster;
+            if let Some(reason) = policy.rejection_reason(paymaster, &tx.execute) {
+                return Err(SubmitTxError::NotSponsored(reason));
+            }
+        }
         let l1_gas_price = {
             let effective_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
-            let current_l1_gas_price =
-                ((effective_gas_price as f64) * self.0.sender_config.gas_price_scale_factor) as u64;
+            let current_l1_gas_price = ((effective_gas_price as f64)
+                * self.0.sender_config.gas_price_scale_factor
+                * l1_gas_price_scale_factor) as u64;
 
             // In order for execution to pass smoothly, we need to ensure that block's required gasPerPubdata will be
             // <= to the one in the transaction itself.
             adjust_l1_gas_price_for_tx(
                 current_l1_gas_price,
-                self.0.sender_config.fair_l2_gas_price,
+                self.fair_l2_gas_price(),
                 tx.gas_per_pubdata_byte_limit(),
             )
         };
 
-        let (base_fee, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
-            l1_gas_price,
-            self.0.sender_config.fair_l2_gas_price,
-        );
+        let (base_fee, gas_per_pubdata_byte) =
+            derive_base_fee_and_gas_per_pubdata(l1_gas_price, self.fair_l2_gas_price());
         match &mut tx.common_data {
             ExecuteTransactionCommon::L2(common_data) => {
                 common_data.fee.max_fee_per_gas = base_fee.into();
@@ -684,6 +1009,10 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         }
 
         let hashed_key = get_code_key(&tx.initiator_account());
+        // If the initiator account is overridden, its balance and code in Postgres are irrelevant.
+        let is_initiator_overridden = state_override
+            .as_ref()
+            .map_or(false, |state| state.get(&tx.initiator_account()).is_some());
         // if the default account does not have enough funds
         // for transferring tx.value, without taking into account the fee,
         // there is no sense to estimate the fee
@@ -699,6 +1028,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             .unwrap_or_default();
 
         if !tx.is_l1()
+            && !is_initiator_overridden
             && account_code_hash == H256::zero()
             && tx.execute.value > self.get_balance(&tx.initiator_account()).await
         {
@@ -777,6 +1107,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                     try_gas_limit,
                     l1_gas_price,
                     base_fee,
+                    state_override.clone(),
                 )
                 .await;
 
@@ -814,6 +1145,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 suggested_gas_limit,
                 l1_gas_price,
                 base_fee,
+                state_override,
             )
             .await;
 
@@ -838,24 +1170,205 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 }
             };
 
-        Ok(Fee {
+        let fee = Fee {
             max_fee_per_gas: base_fee.into(),
             max_priority_fee_per_gas: 0u32.into(),
             gas_limit: full_gas_limit.into(),
             gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+        };
+        Ok((fee, tx, tx_metrics))
+    }
+
+    /// Checks whether a transaction with the specified execution metrics is expected to fit into the pending
+    /// L1 batch according to the built-in seal criteria. Returns `None` if the state keeper config isn't available
+    /// (e.g., on the external node).
+    ///
+    /// The check is approximate: transactions in the miniblock currently open in the state keeper aren't persisted
+    /// yet, and the encoding size is only known for the checked transaction.
+    async fn pending_l1_batch_inclusion_hint(
+        &self,
+        transaction: Transaction,
+        tx_metrics: &TransactionExecutionMetrics,
+    ) -> anyhow::Result<Option<FeeInclusionHint>> {
+        /// A transaction that doesn't fit into the pending L1 batch has to wait until the batch is sealed,
+        /// which involves sealing the open miniblock and the fictive one, and is then included into the first
+        /// miniblock of the next batch.
+        const BLOCKS_UNTIL_NEXT_L1_BATCH: u64 = 3;
+
+        let Some(sk_config) = self.effective_state_keeper_config() else {
+            return Ok(None);
+        };
+        let pending_batch = self.load_pending_l1_batch().await?;
+
+        let protocol_version = ProtocolVersionId::latest();
+        let tx_data = SealData::for_transaction(
+            transaction,
+            tx_metrics,
+            protocol_version,
+            sk_config.sponsor_paymaster_addr,
+        );
+        let batch_data = pending_batch.seal_data(&tx_data, protocol_version);
+        let limiting_criterion = ConditionalSealer::find_exclusion_reason(
+            &sk_config,
+            pending_batch.tx_count + 1,
+            &batch_data,
+            &tx_data,
+            protocol_version,
+        );
+        Ok(Some(FeeInclusionHint {
+            fits_pending_l1_batch: limiting_criterion.is_none(),
+            limiting_criterion: limiting_criterion.map(str::to_owned),
+            likely_included_within_blocks: if limiting_criterion.is_some() {
+                BLOCKS_UNTIL_NEXT_L1_BATCH.into()
+            } else {
+                1.into()
+            },
+        }))
+    }
+
+    /// Loads data for the pending L1 batch, i.e., for miniblocks persisted by the state keeper
+    /// but not yet included into a sealed L1 batch.
+    async fn load_pending_l1_batch(&self) -> anyhow::Result<PendingL1Batch> {
+        let mut storage = self
+            .0
+            .replica_connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("failed getting Postgres connection")?;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("failed getting sealed L1 batch number")?;
+        let (tx_count, execution_metrics) = storage
+            .transactions_web3_dal()
+            .get_pending_l1_batch_execution_metrics()
+            .await
+            .context("failed getting execution metrics for pending L1 batch")?;
+        let writes_metrics = storage
+            .storage_logs_dal()
+            .get_pending_l1_batch_writes_metrics()
+            .await
+            .context("failed getting storage writes for pending L1 batch")?;
+        Ok(PendingL1Batch {
+            number: sealed_l1_batch_number + 1,
+            tx_count,
+            execution_metrics,
+            writes_metrics,
         })
     }
 
+    /// Reports how close the pending L1 batch is to being sealed by each of the built-in seal criteria.
+    /// Returns `None` if the state keeper config isn't available (e.g., on the external node).
+    ///
+    /// Like fee inclusion hints, the status is approximate: it doesn't account
+    /// for the miniblock currently open in the state keeper.
+    pub async fn pending_l1_batch_seal_status(&self) -> anyhow::Result<Option<L1BatchSealStatus>> {
+        let Some(sk_config) = self.effective_state_keeper_config() else {
+            return Ok(None);
+        };
+        let status = self.load_pending_l1_batch_seal_status(&sk_config).await?;
+        Ok(Some(status))
+    }
+
+    async fn load_pending_l1_batch_seal_status(
+        &self,
+        sk_config: &StateKeeperConfig,
+    ) -> anyhow::Result<L1BatchSealStatus> {
+        let pending_batch = self.load_pending_l1_batch().await?;
+        let batch_data = pending_batch.seal_data(&SealData::default(), ProtocolVersionId::latest());
+        Ok(l1_batch_seal_status(
+            sk_config,
+            pending_batch.number,
+            pending_batch.tx_count,
+            &batch_data,
+        ))
+    }
+
+    /// Simulates adding a transaction to the pending L1 batch. The transaction is executed as during fee estimation,
+    /// and the built-in seal criteria are evaluated for the pending batch with the transaction included.
+    /// Returns `None` if the state keeper config isn't available (e.g., on the external node).
+    pub async fn simulate_pending_l1_batch_sealing(
+        &self,
+        tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<Option<SealSimulation>, SubmitTxError> {
+        let Some(sk_config) = self.effective_state_keeper_config() else {
+            return Ok(None);
+        };
+
+        let contract_address = tx.execute.contract_address;
+        let result = self
+            .estimate_fee_with_l1_gas_price_scale(
+                tx,
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+                None,
+                1.0,
+            )
+            .await;
+        let (tx, tx_metrics) = match self.decode_revert_reason(result, contract_address).await {
+            Ok((_, tx, tx_metrics)) => (tx, tx_metrics),
+            Err(SubmitTxError::Unexecutable(reason)) => {
+                // The transaction is rejected regardless of the pending batch state, so we report
+                // the batch status without it.
+                return Ok(Some(SealSimulation {
+                    resolution: SimulatedSealResolution::Unexecutable,
+                    triggered_criteria: vec![],
+                    unexecutable_reason: Some(reason),
+                    batch: self.load_pending_l1_batch_seal_status(&sk_config).await?,
+                }));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let protocol_version = ProtocolVersionId::latest();
+        let tx_data = SealData::for_transaction(
+            tx,
+            &tx_metrics,
+            protocol_version,
+            sk_config.sponsor_paymaster_addr,
+        );
+        let pending_batch = self.load_pending_l1_batch().await?;
+        let batch_data = pending_batch.seal_data(&tx_data, protocol_version);
+        let tx_count = pending_batch.tx_count + 1;
+        let (resolution, triggered_criteria) = ConditionalSealer::simulate(
+            &sk_config,
+            tx_count,
+            &batch_data,
+            &tx_data,
+            protocol_version,
+        );
+
+        let (resolution, unexecutable_reason) = match resolution {
+            SealResolution::NoSeal => (SimulatedSealResolution::NoSeal, None),
+            SealResolution::IncludeAndSeal => (SimulatedSealResolution::IncludeAndSeal, None),
+            SealResolution::ExcludeAndSeal => (SimulatedSealResolution::ExcludeAndSeal, None),
+            SealResolution::Unexecutable(reason) => {
+                (SimulatedSealResolution::Unexecutable, Some(reason))
+            }
+        };
+        Ok(Some(SealSimulation {
+            resolution,
+            triggered_criteria: triggered_criteria.into_iter().map(str::to_owned).collect(),
+            unexecutable_reason,
+            batch: l1_batch_seal_status(&sk_config, pending_batch.number, tx_count, &batch_data),
+        }))
+    }
+
     pub(super) async fn eth_call(
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        state_override: Option<StateOverride>,
     ) -> Result<Vec<u8>, SubmitTxError> {
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        execute_tx_eth_call(
+        let contract_address = tx.execute.contract_address;
+        let result = execute_tx_eth_call(
             vm_permit,
             self.shared_args(),
             self.0.replica_connection_pool.clone(),
@@ -863,28 +1376,93 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             block_args,
             vm_execution_cache_misses_limit,
             vec![],
+            state_override,
         )
         .await
-        .into_api_call_result()
+        .into_api_call_result();
+        self.decode_revert_reason(result, contract_address).await
+    }
+
+    /// Decodes the revert reason of a reverted transaction if the VM hasn't decoded it. Custom errors are decoded
+    /// using the ABI of the called contract if it's verified. The raw revert data is retained as error data.
+    async fn decode_revert_reason<T>(
+        &self,
+        result: Result<T, SubmitTxError>,
+        contract_address: Address,
+    ) -> Result<T, SubmitTxError> {
+        let Err(SubmitTxError::ExecutionReverted(message, data)) = result else {
+            return result;
+        };
+        if !message.is_empty() || data.is_empty() {
+            return Err(SubmitTxError::ExecutionReverted(message, data));
+        }
+
+        let mut message = revert_reason::decode_builtin_error(&data);
+        if message.is_none() {
+            if let Some(abi) = self.load_verified_abi(contract_address).await {
+                message = revert_reason::decode_custom_error(&abi, &data);
+            }
+        }
+        Err(SubmitTxError::ExecutionReverted(
+            message.unwrap_or_default(),
+            data,
+        ))
+    }
+
+    /// Loads the ABI of a verified contract. Errors are logged and ignored since the ABI is only used
+    /// to improve error messages.
+    async fn load_verified_abi(&self, address: Address) -> Option<ethabi::Contract> {
+        let mut storage = self
+            .0
+            .replica_connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .map_err(|err| tracing::warn!("Failed getting connection to load ABI: {err}"))
+            .ok()?;
+        let info = storage
+            .contract_verification_dal()
+            .get_contract_verification_info(address)
+            .await
+            .map_err(|err| {
+                tracing::warn!("Failed loading verification info for {address:?}: {err:#}")
+            })
+            .ok()??;
+        serde_json::from_value(info.artifacts.abi)
+            .map_err(|err| tracing::warn!("Invalid ABI of verified contract {address:?}: {err}"))
+            .ok()
     }
 
     pub fn gas_price(&self) -> u64 {
         let gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
         let l1_gas_price = (gas_price as f64 * self.0.sender_config.gas_price_scale_factor).round();
-        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(
-            l1_gas_price as u64,
-            self.0.sender_config.fair_l2_gas_price,
-        );
+        let (base_fee, _) =
+            derive_base_fee_and_gas_per_pubdata(l1_gas_price as u64, self.fair_l2_gas_price());
         base_fee
     }
 
+    /// Returns the state keeper config with runtime overrides applied, or `None` if the config isn't available.
+    fn effective_state_keeper_config(&self) -> Option<StateKeeperConfig> {
+        let sk_config = self.0.state_keeper_config.as_ref()?;
+        let overrides = self
+            .0
+            .config_overrides
+            .as_ref()
+            .map(watch::Receiver::borrow);
+        Some(match &overrides {
+            Some(overrides) => overrides
+                .apply_to_state_keeper_config(sk_config)
+                .into_owned(),
+            None => sk_config.clone(),
+        })
+    }
+
     fn ensure_tx_executable(
         &self,
         transaction: Transaction,
         tx_metrics: &TransactionExecutionMetrics,
         log_message: bool,
     ) -> Result<(), SubmitTxError> {
-        let Some(sk_config) = &self.0.state_keeper_config else {
+        let Some(sk_config) = self.effective_state_keeper_config() else {
             // No config provided, so we can't check if transaction satisfies the seal criteria.
             // We assume that it's executable, and if it's not, it will be caught by the main server
             // (where this check is always performed).
@@ -903,9 +1481,14 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         // but the API assumes we are post boojum. In this situation we will determine a tx as being executable but the StateKeeper will
         // still reject them as it's not.
         let protocol_version = ProtocolVersionId::latest();
-        let seal_data = SealData::for_transaction(transaction, tx_metrics, protocol_version);
+        let seal_data = SealData::for_transaction(
+            transaction,
+            tx_metrics,
+            protocol_version,
+            sk_config.sponsor_paymaster_addr,
+        );
         if let Some(reason) =
-            ConditionalSealer::find_unexecutable_reason(sk_config, &seal_data, protocol_version)
+            ConditionalSealer::find_unexecutable_reason(&sk_config, &seal_data, protocol_version)
         {
             let message = format!(
                 "Tx is Unexecutable because of {reason}; inputs for decision: {seal_data:?}"
@@ -918,3 +1501,67 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         Ok(())
     }
 }
+
+/// Data for the pending L1 batch loaded from Postgres.
+#[derive(Debug)]
+struct PendingL1Batch {
+    number: L1BatchNumber,
+    tx_count: usize,
+    execution_metrics: ExecutionMetrics,
+    writes_metrics: DeduplicatedWritesMetrics,
+}
+
+impl PendingL1Batch {
+    /// Builds seal data for the batch after adding a transaction with `tx_data` to it (use default `tx_data`
+    /// to get data for the batch as is). The encoding size is only known for the added transaction; its storage writes
+    /// are not deduplicated against the writes in the batch.
+    fn seal_data(&self, tx_data: &SealData, protocol_version: ProtocolVersionId) -> SealData {
+        let writes_metrics = DeduplicatedWritesMetrics {
+            initial_storage_writes: self.writes_metrics.initial_storage_writes
+                + tx_data.writes_metrics.initial_storage_writes,
+            repeated_storage_writes: self.writes_metrics.repeated_storage_writes
+                + tx_data.writes_metrics.repeated_storage_writes,
+            total_updated_values_size: self.writes_metrics.total_updated_values_size
+                + tx_data.writes_metrics.total_updated_values_size,
+        };
+        SealData {
+            execution_metrics: self.execution_metrics + tx_data.execution_metrics,
+            gas_count: new_block_gas_count()
+                + gas_count_from_metrics(&self.execution_metrics)
+                + gas_count_from_writes(&self.writes_metrics, protocol_version)
+                + tx_data.gas_count,
+            cumulative_size: tx_data.cumulative_size,
+            writes_metrics,
+            sponsored_gas: tx_data.sponsored_gas,
+        }
+    }
+}
+
+fn l1_batch_seal_status(
+    config: &StateKeeperConfig,
+    l1_batch_number: L1BatchNumber,
+    tx_count: usize,
+    batch_data: &SealData,
+) -> L1BatchSealStatus {
+    let usage = ConditionalSealer::criteria_usage(
+        config,
+        tx_count,
+        batch_data,
+        ProtocolVersionId::latest(),
+    );
+    let criteria = usage
+        .into_iter()
+        .map(|(criterion, usage)| SealCriterionStatus {
+            criterion: criterion.to_owned(),
+            used: usage.used,
+            limit: usage.limit,
+            seal_threshold: usage.seal_threshold,
+            threshold_percentage: usage.used as f64 / usage.seal_threshold.max(1) as f64 * 100.0,
+        })
+        .collect();
+    L1BatchSealStatus {
+        l1_batch_number,
+        tx_count: tx_count as u64,
+        criteria,
+    }
+}
@@ -7,22 +7,22 @@ use zksync_types::{
     H256,
 };
 use zksync_web3_decl::{
-    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
     RpcResult,
 };
 
+use crate::sync_layer::FailoverMainNodeClient;
+
 /// Used by external node to proxy transaction to the main node
 /// and store them while they're not synced back yet
 #[derive(Debug)]
 pub struct TxProxy {
     tx_cache: RwLock<HashMap<H256, L2Tx>>,
-    client: HttpClient,
+    client: FailoverMainNodeClient,
 }
 
 impl TxProxy {
-    pub fn new(main_node_url: &str) -> Self {
-        let client = HttpClientBuilder::default().build(main_node_url).unwrap();
+    pub fn new(client: FailoverMainNodeClient) -> Self {
         Self {
             client,
             tx_cache: RwLock::new(HashMap::new()),
@@ -41,30 +41,49 @@ impl TxProxy {
         self.tx_cache.write().await.insert(tx_hash, tx);
     }
 
-    pub async fn submit_tx(&self, tx: &L2Tx) -> RpcResult<H256> {
+    pub async fn submit_tx(&self, tx: &L2Tx, valid_until: Option<u64>) -> RpcResult<H256> {
         let input_data = tx.common_data.input_data().expect("raw tx is absent");
         let raw_tx = zksync_types::Bytes(input_data.to_vec());
         tracing::info!("Proxying tx {}", tx.hash());
-        self.client.send_raw_transaction(raw_tx).await
+        self.client
+            .call(|client| {
+                let raw_tx = raw_tx.clone();
+                async move {
+                    if let Some(valid_until) = valid_until {
+                        client
+                            .send_raw_transaction_with_deadline(raw_tx, valid_until.into())
+                            .await
+                    } else {
+                        client.send_raw_transaction(raw_tx).await
+                    }
+                }
+            })
+            .await
     }
 
     pub async fn request_tx(&self, id: TransactionId) -> RpcResult<Option<Transaction>> {
-        match id {
-            TransactionId::Block(BlockId::Hash(block), index) => {
-                self.client
-                    .get_transaction_by_block_hash_and_index(block, index)
-                    .await
-            }
-            TransactionId::Block(BlockId::Number(block), index) => {
-                self.client
-                    .get_transaction_by_block_number_and_index(block, index)
-                    .await
-            }
-            TransactionId::Hash(hash) => self.client.get_transaction_by_hash(hash).await,
-        }
+        self.client
+            .call(|client| async move {
+                match id {
+                    TransactionId::Block(BlockId::Hash(block), index) => {
+                        client
+                            .get_transaction_by_block_hash_and_index(block, index)
+                            .await
+                    }
+                    TransactionId::Block(BlockId::Number(block), index) => {
+                        client
+                            .get_transaction_by_block_number_and_index(block, index)
+                            .await
+                    }
+                    TransactionId::Hash(hash) => client.get_transaction_by_hash(hash).await,
+                }
+            })
+            .await
     }
 
     pub async fn request_tx_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
-        self.client.get_transaction_details(hash).await
+        self.client
+            .call(|client| async move { client.get_transaction_details(hash).await })
+            .await
     }
 }
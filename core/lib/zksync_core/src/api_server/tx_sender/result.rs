@@ -3,8 +3,10 @@ use multivm::{
     tracers::validator::ValidationError,
 };
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
+use zksync_types::{l2::error::TxCheckError, vm_trace::ValidationViolationReport, H256, U256};
+use zksync_web3_decl::error::Web3Error;
 
+use super::filter::TxRejection;
 use crate::api_server::execution_sandbox::SandboxExecutionError;
 
 #[derive(Debug, Error)]
@@ -25,12 +27,20 @@ pub enum SubmitTxError {
     Unexecutable(String),
     #[error("too many transactions")]
     RateLimitExceeded,
+    #[error("transaction rejected by address filter: {0}")]
+    AddressFiltered(String),
+    #[error("transaction rejected by `{}` filter: {}", .0.filter, .0.reason)]
+    Filtered(TxRejection),
+    #[error("transaction cannot use the sponsor paymaster: {0}")]
+    NotSponsored(String),
     #[error("server shutting down")]
     ServerShuttingDown,
     #[error("failed to include transaction in the system. reason: {0}")]
     BootloaderFailure(String),
     #[error("failed to validate the transaction. reason: {0}")]
     ValidationFailed(String),
+    #[error("failed to validate the transaction. reason: Violated validation rules: {0}")]
+    ViolatedValidationRules(ValidationViolationReport),
     #[error("not enough balance to cover the fee. error message: {0}")]
     FailedToChargeFee(String),
     #[error("failed paymaster validation. error message: {0}")]
@@ -43,6 +53,11 @@ pub enum SubmitTxError {
     MaxFeePerGasTooLow,
     #[error("max priority fee per gas higher than max fee per gas")]
     MaxPriorityFeeGreaterThanMaxFee,
+    #[error(
+        "replacement transaction underpriced: fee values must be increased by at least {0}% \
+         to replace pending transaction {1:?}"
+    )]
+    ReplacementUnderpriced(u32, H256),
     #[error(
         "virtual machine entered unexpected state. please contact developers and provide transaction details \
         that caused this error. Error description: {0}"
@@ -66,9 +81,16 @@ pub enum SubmitTxError {
     /// than required to start the invocation.
     #[error("intrinsic gas too low")]
     IntrinsicGas,
+    #[error("transaction deadline has already passed")]
+    DeadlineExpired,
+    #[error("transaction deadline is too far in the future; it must not exceed {0}")]
+    DeadlineTooFar(u64),
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] zksync_web3_decl::jsonrpsee::core::ClientError),
+    /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
+    #[error("internal error")]
+    Internal(#[from] anyhow::Error),
 }
 
 impl SubmitTxError {
@@ -82,15 +104,19 @@ impl SubmitTxError {
             Self::GasLimitIsTooBig => "gas-limit-is-too-big",
             Self::Unexecutable(_) => "unexecutable",
             Self::RateLimitExceeded => "rate-limit-exceeded",
+            Self::AddressFiltered(_) => "address-filtered",
+            Self::Filtered(_) => "filtered",
+            Self::NotSponsored(_) => "not-sponsored",
             Self::ServerShuttingDown => "shutting-down",
             Self::BootloaderFailure(_) => "bootloader-failure",
-            Self::ValidationFailed(_) => "validation-failed",
+            Self::ValidationFailed(_) | Self::ViolatedValidationRules(_) => "validation-failed",
             Self::FailedToChargeFee(_) => "failed-too-charge-fee",
             Self::PaymasterValidationFailed(_) => "failed-paymaster-validation",
             Self::PrePaymasterPreparationFailed(_) => "failed-prepaymaster-preparation",
             Self::FromIsNotAnAccount => "from-is-not-an-account",
             Self::MaxFeePerGasTooLow => "max-fee-per-gas-too-low",
             Self::MaxPriorityFeeGreaterThanMaxFee => "max-priority-fee-greater-than-max-fee",
+            Self::ReplacementUnderpriced(..) => "replacement-underpriced",
             Self::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             Self::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
             Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
@@ -98,7 +124,10 @@ impl SubmitTxError {
             Self::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
             Self::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             Self::IntrinsicGas => "intrinsic-gas",
+            Self::DeadlineExpired => "deadline-expired",
+            Self::DeadlineTooFar(_) => "deadline-too-far",
             Self::ProxyError(_) => "proxy-error",
+            Self::Internal(_) => "internal",
         }
     }
 
@@ -142,7 +171,26 @@ impl From<SandboxExecutionError> for SubmitTxError {
 
 impl From<ValidationError> for SubmitTxError {
     fn from(err: ValidationError) -> Self {
-        Self::ValidationFailed(err.to_string())
+        match err {
+            ValidationError::ViolatedRule(report) => Self::ViolatedValidationRules(report),
+            ValidationError::FailedTx(_) => Self::ValidationFailed(err.to_string()),
+        }
+    }
+}
+
+impl From<SubmitTxError> for Web3Error {
+    fn from(err: SubmitTxError) -> Self {
+        let message = err.to_string();
+        match err {
+            SubmitTxError::ViolatedValidationRules(report) => {
+                Self::ValidationRulesViolated(message, report)
+            }
+            SubmitTxError::Internal(err) => {
+                tracing::error!("Internal error processing transaction: {err:#}");
+                Self::InternalError
+            }
+            _ => Self::SubmitTransactionError(message, err.data()),
+        }
     }
 }
 
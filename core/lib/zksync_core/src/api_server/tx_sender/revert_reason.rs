@@ -0,0 +1,179 @@
+//! Decoding of revert reasons returned by API methods.
+//!
+//! The VM only decodes `Error(string)` revert payloads. `Panic(uint256)` payloads and custom errors are decoded here,
+//! so that API clients get a human-readable message in addition to the raw revert data.
+
+use zksync_types::{
+    ethabi::{self, Contract, ParamType, Token},
+    U256,
+};
+
+/// Selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+fn split_selector(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    (data.len() >= 4).then(|| data.split_at(4))
+}
+
+/// Decodes `Error(string)` and `Panic(uint256)` revert payloads, which are emitted by the Solidity compiler.
+pub(crate) fn decode_builtin_error(data: &[u8]) -> Option<String> {
+    let (selector, args) = split_selector(data)?;
+    if selector == ERROR_SELECTOR {
+        let tokens = ethabi::decode(&[ParamType::String], args).ok()?;
+        let [Token::String(message)] = tokens.as_slice() else {
+            return None;
+        };
+        Some(message.clone())
+    } else if selector == PANIC_SELECTOR {
+        let tokens = ethabi::decode(&[ParamType::Uint(256)], args).ok()?;
+        let [Token::Uint(code)] = tokens.as_slice() else {
+            return None;
+        };
+        Some(format!(
+            "panic: {} (0x{code:02x})",
+            panic_description(*code)
+        ))
+    } else {
+        None
+    }
+}
+
+/// Describes a panic code as per the Solidity docs.
+fn panic_description(code: U256) -> &'static str {
+    if code > U256::from(u8::MAX) {
+        return "unknown panic";
+    }
+    match code.low_u32() {
+        0x00 => "generic panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "too much memory allocated",
+        0x51 => "call to zero-initialized internal function",
+        _ => "unknown panic",
+    }
+}
+
+/// Decodes a custom error declared in the contract ABI. Returns the error name with decoded arguments,
+/// e.g. `InsufficientBalance(100, 200)`.
+pub(crate) fn decode_custom_error(abi: &Contract, data: &[u8]) -> Option<String> {
+    let (selector, args) = split_selector(data)?;
+    abi.errors.values().flatten().find_map(|error| {
+        let param_types: Vec<_> = error
+            .inputs
+            .iter()
+            .map(|param| param.kind.clone())
+            .collect();
+        if ethabi::short_signature(&error.name, &param_types) != selector {
+            return None;
+        }
+        let tokens = ethabi::decode(&param_types, args).ok()?;
+        let tokens: Vec<_> = tokens.iter().map(format_token).collect();
+        Some(format!("{}({})", error.name, tokens.join(", ")))
+    })
+}
+
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{address:?}"),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) if value.bit(255) => {
+            // Two's complement representation of a negative value
+            let abs_value = (!*value).overflowing_add(U256::one()).0;
+            format!("-{abs_value}")
+        }
+        Token::Int(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{value:?}"),
+        Token::FixedArray(tokens) | Token::Array(tokens) => {
+            let tokens: Vec<_> = tokens.iter().map(format_token).collect();
+            format!("[{}]", tokens.join(", "))
+        }
+        Token::Tuple(tokens) => {
+            let tokens: Vec<_> = tokens.iter().map(format_token).collect();
+            format!("({})", tokens.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::Address;
+
+    use super::*;
+
+    fn encode_error(signature: &str, param_types: &[ParamType], tokens: &[Token]) -> Vec<u8> {
+        let mut data = ethabi::short_signature(signature, param_types).to_vec();
+        data.extend(ethabi::encode(tokens));
+        data
+    }
+
+    #[test]
+    fn decoding_builtin_errors() {
+        let data = encode_error(
+            "Error",
+            &[ParamType::String],
+            &[Token::String("not enough balance".to_owned())],
+        );
+        assert_eq!(data[..4], ERROR_SELECTOR);
+        assert_eq!(decode_builtin_error(&data).unwrap(), "not enough balance");
+
+        let data = encode_error(
+            "Panic",
+            &[ParamType::Uint(256)],
+            &[Token::Uint(0x11.into())],
+        );
+        assert_eq!(data[..4], PANIC_SELECTOR);
+        assert_eq!(
+            decode_builtin_error(&data).unwrap(),
+            "panic: arithmetic underflow or overflow (0x11)"
+        );
+
+        assert_eq!(decode_builtin_error(&data[..10]), None);
+        assert_eq!(decode_builtin_error(&[1, 2, 3, 4]), None);
+        assert_eq!(decode_builtin_error(&[]), None);
+    }
+
+    #[test]
+    fn decoding_custom_errors() {
+        let abi = r#"[{
+            "type": "error",
+            "name": "InsufficientBalance",
+            "inputs": [
+                { "name": "account", "type": "address" },
+                { "name": "delta", "type": "int256" },
+                { "name": "ids", "type": "uint8[]" }
+            ]
+        }]"#;
+        let abi: Contract = serde_json::from_str(abi).unwrap();
+
+        let param_types = [
+            ParamType::Address,
+            ParamType::Int(256),
+            ParamType::Array(Box::new(ParamType::Uint(8))),
+        ];
+        let tokens = [
+            Token::Address(Address::repeat_byte(0x11)),
+            Token::Int(U256::MAX - 99), // -100
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+        ];
+        let data = encode_error("InsufficientBalance", &param_types, &tokens);
+        assert_eq!(
+            decode_custom_error(&abi, &data).unwrap(),
+            format!(
+                "InsufficientBalance({:?}, -100, [1, 2])",
+                Address::repeat_byte(0x11)
+            )
+        );
+
+        let data = encode_error("OtherError", &[], &[]);
+        assert_eq!(decode_custom_error(&abi, &data), None);
+    }
+}
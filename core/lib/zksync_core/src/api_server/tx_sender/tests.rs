@@ -0,0 +1,229 @@
+//! Tests for the transaction sender.
+
+use assert_matches::assert_matches;
+use zksync_types::{
+    api::TransactionStatus,
+    contract_verification_api::{
+        CompilationArtifacts, CompilerVersions, SourceCodeData, VerificationIncomingRequest,
+        VerificationInfo, VerificationRequest,
+    },
+    ethabi::{ParamType, Token},
+};
+
+use super::*;
+use crate::state_keeper::tests::create_l2_transaction;
+
+/// Mock [`L1GasPriceProvider`] that returns a constant value.
+struct MockL1GasPriceProvider(u64);
+
+impl L1GasPriceProvider for MockL1GasPriceProvider {
+    fn estimate_effective_gas_price(&self) -> u64 {
+        self.0
+    }
+}
+
+/// ABI of the `SimpleRequire` test contract (`etc/contracts-test-data/contracts/error/error.sol`).
+const SIMPLE_REQUIRE_ABI: &str = r#"[{
+    "type": "error",
+    "name": "TestError",
+    "inputs": [
+        { "name": "one", "type": "uint256" },
+        { "name": "two", "type": "uint256" },
+        { "name": "three", "type": "uint256" },
+        { "name": "data", "type": "string" }
+    ]
+}]"#;
+
+async fn create_tx_sender(pool: ConnectionPool) -> TxSender<MockL1GasPriceProvider> {
+    let state_keeper_config = StateKeeperConfig::for_tests();
+    let web3_config = Web3JsonRpcConfig::for_tests();
+    let config = TxSenderConfig::new(&state_keeper_config, &web3_config, L2ChainId::default());
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    TxSenderBuilder::new(config, pool.clone())
+        .with_main_connection_pool(pool)
+        .build(
+            Arc::new(MockL1GasPriceProvider(1)),
+            Arc::new(vm_concurrency_limiter),
+            ApiContracts::load_from_disk(),
+            PostgresStorageCaches::new(1, 1),
+        )
+        .await
+}
+
+async fn store_verified_abi(pool: &ConnectionPool, address: Address, abi: &str) {
+    let mut storage = pool.access_storage().await.unwrap();
+    let request = VerificationIncomingRequest {
+        contract_address: address,
+        source_code_data: SourceCodeData::SolSingleFile(String::new()),
+        contract_name: "SimpleRequire".to_owned(),
+        compiler_versions: CompilerVersions::Solc {
+            compiler_zksolc_version: "v1.3.14".to_owned(),
+            compiler_solc_version: "0.8.20".to_owned(),
+        },
+        optimization_used: true,
+        optimizer_mode: None,
+        constructor_arguments: Default::default(),
+        is_system: false,
+    };
+    let id = storage
+        .contract_verification_dal()
+        .add_contract_verification_request(request.clone())
+        .await
+        .unwrap();
+    let info = VerificationInfo {
+        request: VerificationRequest { id, req: request },
+        artifacts: CompilationArtifacts {
+            bytecode: vec![],
+            abi: serde_json::from_str(abi).unwrap(),
+        },
+        verified_at: Default::default(),
+    };
+    storage
+        .contract_verification_dal()
+        .save_verification_info(info)
+        .await
+        .unwrap();
+}
+
+fn test_error_data() -> Vec<u8> {
+    let param_types = [
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::String,
+    ];
+    let mut data = ethabi::short_signature("TestError", &param_types).to_vec();
+    data.extend(ethabi::encode(&[
+        Token::Uint(1.into()),
+        Token::Uint(2.into()),
+        Token::Uint(1.into()),
+        Token::String("data".to_owned()),
+    ]));
+    data
+}
+
+#[tokio::test]
+async fn decoding_custom_error_in_eth_call() {
+    let pool = ConnectionPool::test_pool().await;
+    let tx_sender = create_tx_sender(pool.clone()).await;
+    let verified_address = Address::repeat_byte(1);
+    store_verified_abi(&pool, verified_address, SIMPLE_REQUIRE_ABI).await;
+
+    // This is the output of `eth_call` execution for a call reverting with `TestError`; the VM doesn't decode it.
+    let reverted = || -> Result<Vec<u8>, _> {
+        Err(SubmitTxError::ExecutionReverted(
+            String::new(),
+            test_error_data(),
+        ))
+    };
+    let err = tx_sender
+        .decode_revert_reason(reverted(), verified_address)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ExecutionReverted(message, data)
+            if message == r#"TestError(1, 2, 1, "data")"# && data == test_error_data()
+    );
+
+    // The ABI of an unverified contract is unknown, so the revert reason must remain undecoded.
+    let err = tx_sender
+        .decode_revert_reason(reverted(), Address::repeat_byte(2))
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ExecutionReverted(message, data)
+            if message.is_empty() && data == test_error_data()
+    );
+}
+
+#[tokio::test]
+async fn submitting_tx_with_out_of_range_deadline() {
+    let pool = ConnectionPool::test_pool().await;
+    let tx_sender = create_tx_sender(pool).await;
+    let tx = create_l2_transaction(10, 100);
+    let max_tx_deadline = tx_sender.0.sender_config.max_tx_deadline.as_secs();
+
+    let err = tx_sender
+        .submit_tx_with_deadline(tx.clone(), Some(seconds_since_epoch() - 1))
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::DeadlineExpired);
+
+    let too_far_deadline = seconds_since_epoch() + max_tx_deadline + 100;
+    let err = tx_sender
+        .submit_tx_with_deadline(tx.clone(), Some(too_far_deadline))
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::DeadlineTooFar(max) if max < too_far_deadline);
+
+    // Values not fitting into `i64` must be rejected rather than reaching the DB layer.
+    let err = tx_sender
+        .submit_tx_with_deadline(tx, Some(u64::MAX))
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::DeadlineTooFar(_));
+}
+
+#[tokio::test]
+async fn replacing_pending_tx() {
+    let pool = ConnectionPool::test_pool().await;
+    let tx_sender = create_tx_sender(pool.clone()).await;
+    let bump_percent = u64::from(tx_sender.0.sender_config.tx_replacement_fee_bump_percent);
+    let mut storage = pool.access_storage().await.unwrap();
+
+    let tx = create_l2_transaction(100, 100);
+    let tx_hash = tx.hash();
+    let result = tx_sender
+        .insert_l2_tx(&mut storage, tx.clone(), Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+
+    let replacement_with_fee = |fee_per_gas: u64| {
+        let mut replacement = create_l2_transaction(fee_per_gas, 100);
+        replacement.common_data.initiator_address = tx.common_data.initiator_address;
+        replacement.common_data.nonce = tx.common_data.nonce;
+        replacement
+    };
+
+    // The replacement doesn't pay enough to replace the pending transaction.
+    let underpriced_tx = replacement_with_fee(100 + bump_percent - 1);
+    let err = tx_sender
+        .insert_l2_tx(&mut storage, underpriced_tx, Default::default(), None)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ReplacementUnderpriced(_, hash) if hash == tx_hash
+    );
+    let (pending_hash, _) = storage
+        .transactions_dal()
+        .get_replaceable_l2_tx(tx.initiator_account(), tx.common_data.nonce)
+        .await
+        .unwrap();
+    assert_eq!(pending_hash, tx_hash);
+
+    let replacement = replacement_with_fee(100 + bump_percent);
+    let replacement_hash = replacement.hash();
+    let result = tx_sender
+        .insert_l2_tx(&mut storage, replacement, Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+    let (pending_hash, _) = storage
+        .transactions_dal()
+        .get_replaceable_l2_tx(tx.initiator_account(), tx.common_data.nonce)
+        .await
+        .unwrap();
+    assert_eq!(pending_hash, replacement_hash);
+
+    let details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(tx_hash)
+        .await
+        .unwrap()
+        .expect("replaced transaction details");
+    assert_eq!(details.status, TransactionStatus::Replaced);
+}
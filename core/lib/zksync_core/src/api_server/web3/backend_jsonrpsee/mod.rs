@@ -13,6 +13,7 @@ use crate::api_server::web3::metrics::API_METRICS;
 
 pub mod batch_limiter_middleware;
 pub mod namespaces;
+pub(crate) mod trace_context_middleware;
 
 pub fn from_std_error(e: impl Error) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(ErrorCode::InternalError.code(), e.to_string(), Some(()))
@@ -30,18 +31,28 @@ pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
+            | Web3Error::NoTreeVersion(_)
+            | Web3Error::NoStorageRange(_)
+            | Web3Error::PrunedBlock(_)
             | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
-            Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
+            Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::ValidationRulesViolated(_, _)
+            | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
             Web3Error::TreeApiUnavailable => 6,
         },
         match err {
-            Web3Error::SubmitTransactionError(ref message, _) => message.clone(),
+            Web3Error::SubmitTransactionError(ref message, _)
+            | Web3Error::ValidationRulesViolated(ref message, _) => message.clone(),
             _ => err.to_string(),
         },
         match err {
-            Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
+            Web3Error::SubmitTransactionError(_, data) => Some(serde_json::Value::String(format!(
+                "0x{}",
+                hex::encode(data)
+            ))),
+            Web3Error::ValidationRulesViolated(_, report) => serde_json::to_value(report).ok(),
             _ => None,
         },
     )
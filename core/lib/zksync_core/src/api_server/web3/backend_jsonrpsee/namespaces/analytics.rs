@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use zksync_types::{api::analytics::L1BatchAnalytics, L1BatchNumber};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AnalyticsNamespaceServer};
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::AnalyticsNamespace},
+    l1_gas_price::L1GasPriceProvider,
+};
+
+#[async_trait]
+impl<G: L1GasPriceProvider + Send + Sync + 'static> AnalyticsNamespaceServer
+    for AnalyticsNamespace<G>
+{
+    async fn get_l1_batch_analytics(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchAnalytics>> {
+        self.get_l1_batch_analytics_impl(l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batches_analytics(
+        &self,
+        from_l1_batch_number: L1BatchNumber,
+        limit: u32,
+    ) -> RpcResult<Vec<L1BatchAnalytics>> {
+        self.get_l1_batches_analytics_impl(from_l1_batch_number, limit)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
@@ -1,7 +1,7 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, StorageRangeResult, TracerConfig},
     transaction_request::CallRequest,
-    H256,
+    Address, Bytes, H256,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -45,6 +45,36 @@ impl DebugNamespaceServer for DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>> {
-        Ok(self.debug_trace_transaction_impl(tx_hash, options).await)
+        self.debug_trace_transaction_impl(tx_hash, options)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_raw_block(&self, block: BlockId) -> RpcResult<Option<Bytes>> {
+        self.get_raw_block_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+    async fn get_raw_receipts(&self, block: BlockId) -> RpcResult<Option<Vec<Bytes>>> {
+        self.get_raw_receipts_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+    async fn get_raw_transaction(&self, tx_hash: H256) -> RpcResult<Option<Bytes>> {
+        self.get_raw_transaction_impl(tx_hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+    async fn storage_range_at(
+        &self,
+        block: BlockId,
+        tx_index: usize,
+        address: Address,
+        start_key: H256,
+        max_result: usize,
+    ) -> RpcResult<StorageRangeResult> {
+        self.storage_range_at_impl(block, tx_index, address, start_key, max_result)
+            .await
+            .map_err(into_jsrpc_error)
     }
 }
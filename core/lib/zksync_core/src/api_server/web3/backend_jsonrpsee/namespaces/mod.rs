@@ -1,8 +1,10 @@
+pub mod analytics;
 pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
 pub mod snapshots;
+pub mod unstable;
 pub mod web3;
 pub mod zks;
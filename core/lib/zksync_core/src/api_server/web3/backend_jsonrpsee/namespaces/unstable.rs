@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::sealing::{L1BatchSealStatus, SealSimulation},
+    transaction_request::CallRequest,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::UnstableNamespaceServer};
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::UnstableNamespace},
+    l1_gas_price::L1GasPriceProvider,
+};
+
+#[async_trait]
+impl<G: L1GasPriceProvider + Send + Sync + 'static> UnstableNamespaceServer
+    for UnstableNamespace<G>
+{
+    async fn get_seal_criteria_status(&self) -> RpcResult<Option<L1BatchSealStatus>> {
+        self.get_seal_criteria_status_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn simulate_seal_criteria(&self, req: CallRequest) -> RpcResult<Option<SealSimulation>> {
+        self.simulate_seal_criteria_impl(req)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
@@ -3,12 +3,12 @@ use std::collections::HashMap;
 use bigdecimal::BigDecimal;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        AccountNonceDetails, BlockDetails, BridgeAddresses, FeeEstimate, FeeEstimateOptions,
+        FeeParams, L1BatchDetails, L1BatchIdVariant, L1BatchProofPublicInputs, L2ToL1LogProof,
+        L2ToL1Message, Log, PriorityOpStatus, Proof, ProtocolVersion, TransactionDetails,
     },
-    fee::Fee,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -23,8 +23,14 @@ use crate::{
 
 #[async_trait]
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNamespace<G> {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
-        self.estimate_fee_impl(req).await.map_err(into_jsrpc_error)
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        options: Option<FeeEstimateOptions>,
+    ) -> RpcResult<FeeEstimate> {
+        self.estimate_fee_impl(req, options)
+            .await
+            .map_err(into_jsrpc_error)
     }
 
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
@@ -70,6 +76,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_account_nonce_details(&self, address: Address) -> RpcResult<AccountNonceDetails> {
+        self.get_account_nonce_details_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l2_to_l1_msg_proof(
         &self,
         block: MiniblockNumber,
@@ -92,6 +104,24 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_l2_to_l1_messages(&self, tx_hash: H256) -> RpcResult<Option<Vec<L2ToL1Message>>> {
+        self.get_l2_to_l1_messages_impl(tx_hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_indexed_logs(
+        &self,
+        index: String,
+        key: Bytes,
+        from_block: MiniblockNumber,
+        limit: Option<u32>,
+    ) -> RpcResult<Vec<Log>> {
+        self.get_indexed_logs_impl(&index, &key.0, from_block, limit)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l1_batch_number(&self) -> RpcResult<U64> {
         self.get_l1_batch_number_impl()
             .await
@@ -113,12 +143,30 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_fee_params_at(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<FeeParams>> {
+        self.get_fee_params_at_impl(block_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
         self.get_transaction_details_impl(hash)
             .await
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_priority_op_status(
+        &self,
+        l1_tx_hash: H256,
+    ) -> RpcResult<Option<PriorityOpStatus>> {
+        self.get_priority_op_status_impl(l1_tx_hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
@@ -137,8 +185,19 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_batch_proof_public_inputs(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchProofPublicInputs>> {
+        self.get_batch_proof_public_inputs_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
-        Ok(self.get_bytecode_by_hash_impl(hash).await)
+        self.get_bytecode_by_hash_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
     }
 
     async fn get_l1_gas_price(&self) -> RpcResult<U64> {
@@ -156,9 +215,19 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
         &self,
         address: Address,
         keys: Vec<H256>,
-        l1_batch_number: L1BatchNumber,
+        l1_batch: L1BatchIdVariant,
     ) -> RpcResult<Proof> {
-        self.get_proofs_impl(address, keys, l1_batch_number)
+        self.get_proofs_impl(address, keys, l1_batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_with_deadline_impl(tx_bytes, valid_until)
             .await
             .map_err(into_jsrpc_error)
     }
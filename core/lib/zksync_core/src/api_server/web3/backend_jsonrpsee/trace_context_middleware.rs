@@ -0,0 +1,51 @@
+//! HTTP middleware continuing traces propagated by API clients.
+
+use std::task::{Context, Poll};
+
+use axum::http::Request;
+use tower::{Layer, Service};
+use tracing::{instrument::Instrumented, Instrument};
+
+/// Layer wrapping each HTTP request into an `rpc_request` span. If the request has
+/// [W3C Trace Context](https://www.w3.org/TR/trace-context/) headers, the span continues the propagated trace,
+/// so that spans created while processing the request (e.g., in `TxSender` or in DAL queries) are exported
+/// as a part of the client trace.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TraceContextLayer;
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContext<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContext { inner }
+    }
+}
+
+/// Service produced by [`TraceContextLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct TraceContext<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for TraceContext<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let span = tracing::info_span!("rpc_request", path = request.uri().path());
+        let headers = request
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+        vlog::set_remote_parent(&span, headers);
+        self.inner.call(request).instrument(span)
+    }
+}
@@ -179,6 +179,8 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    SoftConfirmations,
+    AddressActivity,
 }
 
 #[derive(Debug, Metrics)]
@@ -203,6 +205,9 @@ pub(super) struct PubSubMetrics {
     /// Number of skipped broadcast messages.
     #[metrics(buckets = Buckets::exponential(1.0..=128.0, 2.0))]
     pub skipped_broadcast_messages: Family<SubscriptionType, Histogram<u64>>,
+    /// Number of subscribers dropped because they lagged behind the broadcast channel. Only incremented
+    /// with the `disconnect` backpressure policy.
+    pub lagged_subscriber_disconnects: Family<SubscriptionType, Counter>,
     /// Number of subscribers dropped because of a send timeout.
     pub subscriber_send_timeouts: Family<SubscriptionType, Counter>,
 }
@@ -5,10 +5,11 @@ use chrono::NaiveDateTime;
 use futures::future;
 use serde::Deserialize;
 use tokio::{
-    sync::{mpsc, oneshot, watch, Mutex},
+    sync::{broadcast, mpsc, oneshot, watch, Mutex},
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
+use zksync_config::configs::api::SubscriptionBackpressurePolicy;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{api, MiniblockNumber};
@@ -19,8 +20,9 @@ use zksync_web3_decl::{
         RpcModule,
     },
     namespaces::{
-        DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        AnalyticsNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        EthPubSubServer, NetNamespaceServer, SnapshotsNamespaceServer, UnstableNamespaceServer,
+        Web3NamespaceServer, ZksNamespaceServer,
     },
     types::Filter,
 };
@@ -29,16 +31,20 @@ use self::{
     backend_jsonrpsee::internal_error,
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        AnalyticsNamespace, DebugNamespace, EnNamespace, EthNamespace, NetNamespace,
+        SnapshotsNamespace, UnstableNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
     state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
 };
 use crate::{
     api_server::{
-        execution_sandbox::VmConcurrencyBarrier, tree::TreeApiHttpClient, tx_sender::TxSender,
-        web3::backend_jsonrpsee::batch_limiter_middleware::LimitMiddleware,
+        execution_sandbox::VmConcurrencyBarrier,
+        tree::TreeApiHttpClient,
+        tx_sender::TxSender,
+        web3::backend_jsonrpsee::{
+            batch_limiter_middleware::LimitMiddleware, trace_context_middleware::TraceContextLayer,
+        },
     },
     l1_gas_price::L1GasPriceProvider,
     sync_layer::SyncState,
@@ -47,6 +53,7 @@ use crate::{
 pub mod backend_jsonrpsee;
 mod metrics;
 pub mod namespaces;
+pub mod profiles;
 mod pubsub;
 pub mod state;
 #[cfg(test)]
@@ -54,6 +61,8 @@ pub(crate) mod tests;
 
 /// Timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default number of notification batches buffered for each subscription type.
+const DEFAULT_MAX_BUFFERED_NOTIFICATIONS: usize = 1_024;
 
 /// Represents all kinds of `Filter`.
 #[derive(Debug, Clone)]
@@ -83,6 +92,8 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Analytics,
+    Unstable,
 }
 
 impl Namespace {
@@ -110,11 +121,16 @@ struct OptionalApiParams {
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
+    max_subscriptions_per_connection: Option<u32>,
+    max_buffered_notifications: Option<usize>,
+    subscription_backpressure_policy: SubscriptionBackpressurePolicy,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api_url: Option<String>,
+    health_check_name: Option<&'static str>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    soft_confirmations: Option<broadcast::Sender<api::TransactionSoftConfirmation>>,
 }
 
 /// Full API server parameters.
@@ -205,6 +221,27 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    pub fn with_max_subscriptions_per_connection(
+        mut self,
+        max_subscriptions_per_connection: u32,
+    ) -> Self {
+        self.optional.max_subscriptions_per_connection = Some(max_subscriptions_per_connection);
+        self
+    }
+
+    pub fn with_max_buffered_notifications(mut self, max_buffered_notifications: usize) -> Self {
+        self.optional.max_buffered_notifications = Some(max_buffered_notifications);
+        self
+    }
+
+    pub fn with_subscription_backpressure_policy(
+        mut self,
+        policy: SubscriptionBackpressurePolicy,
+    ) -> Self {
+        self.optional.subscription_backpressure_policy = policy;
+        self
+    }
+
     pub fn with_batch_request_size_limit(mut self, batch_request_size_limit: usize) -> Self {
         self.optional.batch_request_size_limit = Some(batch_request_size_limit);
         self
@@ -224,6 +261,16 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Enables the `softConfirmations` subscription fed by the state keeper via the provided channel.
+    /// Has no effect unless the server uses the WebSocket transport with the pubsub namespace enabled.
+    pub fn with_soft_confirmations(
+        mut self,
+        soft_confirmations: broadcast::Sender<api::TransactionSoftConfirmation>,
+    ) -> Self {
+        self.optional.soft_confirmations = Some(soft_confirmations);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -244,6 +291,13 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Overrides the name of the server health check, which is `http_api` or `ws_api` by default
+    /// depending on the transport.
+    pub fn with_health_check_name(mut self, name: &'static str) -> Self {
+        self.optional.health_check_name = Some(name);
+        self
+    }
+
     pub fn with_tree_api(mut self, tree_api_url: Option<String>) -> Self {
         self.optional.tree_api_url = tree_api_url;
         self
@@ -349,9 +403,17 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
-            rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
+            rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+        if namespaces.contains(&Namespace::Analytics) {
+            rpc.merge(AnalyticsNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge analytics namespace");
+        }
+        if namespaces.contains(&Namespace::Unstable) {
+            rpc.merge(UnstableNamespace::new(rpc_state).into_rpc())
+                .expect("Can't merge unstable namespace");
+        }
         rpc
     }
 
@@ -404,10 +466,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<ApiServerHandles> {
         let transport = self.transport;
-        let (runtime_thread_name, health_check_name) = match transport {
+        let (runtime_thread_name, default_health_check_name) = match transport {
             ApiTransport::Http(_) => ("jsonrpsee-http-worker", "http_api"),
             ApiTransport::WebSocket(_) => ("jsonrpsee-ws-worker", "ws_api"),
         };
+        let health_check_name = self
+            .optional
+            .health_check_name
+            .unwrap_or(default_health_check_name);
         let (health_check, health_updater) = ReactiveHealthCheck::new(health_check_name);
         let vm_barrier = self.vm_barrier.clone();
         let batch_request_config = self
@@ -423,6 +489,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
 
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
         let subscriptions_limit = self.optional.subscriptions_limit;
+        let max_subscriptions_per_connection = self.optional.max_subscriptions_per_connection;
 
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -438,7 +505,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
         if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
-            let mut pub_sub = EthSubscribe::new();
+            let max_buffered_notifications = self
+                .optional
+                .max_buffered_notifications
+                .unwrap_or(DEFAULT_MAX_BUFFERED_NOTIFICATIONS);
+            let mut pub_sub = EthSubscribe::new(
+                max_buffered_notifications,
+                self.optional.subscription_backpressure_policy,
+            );
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
@@ -448,6 +522,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
                 self.polling_interval,
                 stop_receiver.clone(),
             ));
+            if let Some(soft_confirmations) = &self.optional.soft_confirmations {
+                tasks.push(
+                    pub_sub.spawn_soft_confirmations_forwarder(
+                        soft_confirmations,
+                        stop_receiver.clone(),
+                    ),
+                );
+            }
             pubsub = Some(pub_sub);
         }
 
@@ -465,6 +547,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
                 batch_request_config,
                 response_body_size_limit,
                 subscriptions_limit,
+                max_subscriptions_per_connection,
                 websocket_requests_per_minute_limit,
             ));
             runtime.shutdown_timeout(GRACEFUL_SHUTDOWN_TIMEOUT);
@@ -501,6 +584,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
         batch_request_config: BatchRequestConfig,
         response_body_size_limit: u32,
         subscriptions_limit: Option<usize>,
+        max_subscriptions_per_connection: Option<u32>,
         websocket_requests_per_minute_limit: Option<NonZeroU32>,
     ) -> anyhow::Result<()> {
         let (transport_str, is_http, addr) = match transport {
@@ -526,10 +610,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
                 future::ready(())
             }),
         );
+        // Continue traces propagated by clients. WS requests aren't traced since a single connection
+        // may be used to send requests belonging to different traces.
+        let trace_context = is_http.then_some(TraceContextLayer);
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(trace_context);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -552,6 +640,11 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> FullApiParams<G> {
             (server.local_addr(), server.start(rpc))
         } else {
             // WS specific settings
+            let server_builder = if let Some(limit) = max_subscriptions_per_connection {
+                server_builder.max_subscriptions_per_connection(limit)
+            } else {
+                server_builder
+            };
             let server = server_builder
                 .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(move |a| {
                     LimitMiddleware::new(a, websocket_requests_per_minute_limit)
@@ -602,3 +695,44 @@ async fn resolve_block(
         .map_err(|err| internal_error(method_name, err))?
         .ok_or(Web3Error::NoBlock)
 }
+
+/// Returns an error if the storage state as of `block_number` was removed by pruning or storage logs compaction;
+/// otherwise, reading the state would silently return incorrect values.
+async fn ensure_storage_state_available(
+    connection: &mut StorageProcessor<'_>,
+    block_number: MiniblockNumber,
+    method_name: &'static str,
+) -> Result<(), Web3Error> {
+    let is_pruned = connection
+        .pruning_dal()
+        .is_miniblock_state_pruned(block_number)
+        .await
+        .map_err(|err| internal_error(method_name, err))?;
+    if is_pruned {
+        Err(Web3Error::PrunedBlock(block_number))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error if transactions, events, receipts and call traces of the miniblock `block_number` were removed
+/// by pruning; otherwise, they would be silently reported as missing.
+async fn ensure_block_data_available(
+    connection: &mut StorageProcessor<'_>,
+    block_number: MiniblockNumber,
+    method_name: &'static str,
+) -> Result<(), Web3Error> {
+    let pruning_info = connection
+        .pruning_dal()
+        .get_pruning_info()
+        .await
+        .map_err(|err| internal_error(method_name, err))?;
+    let is_pruned = pruning_info
+        .last_pruned_miniblock
+        .map_or(false, |last_pruned| block_number <= last_pruned);
+    if is_pruned {
+        Err(Web3Error::PrunedBlock(block_number))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use zksync_types::{api::analytics::L1BatchAnalytics, L1BatchNumber};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
+    l1_gas_price::L1GasPriceProvider,
+};
+
+/// Maximum number of L1 batches returned by `analytics_getL1BatchesAnalytics`.
+const MAX_L1_BATCHES_PER_REQUEST: u32 = 100;
+
+#[derive(Debug)]
+pub struct AnalyticsNamespace<G> {
+    state: RpcState<G>,
+}
+
+impl<G> Clone for AnalyticsNamespace<G> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<G: L1GasPriceProvider> AnalyticsNamespace<G> {
+    pub fn new(state: RpcState<G>) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_l1_batch_analytics_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchAnalytics>, Web3Error> {
+        let method_name = "get_l1_batch_analytics";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let analytics = storage
+            .blocks_web3_dal()
+            .get_l1_batch_analytics(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        analytics
+    }
+
+    pub async fn get_l1_batches_analytics_impl(
+        &self,
+        from_l1_batch_number: L1BatchNumber,
+        limit: u32,
+    ) -> Result<Vec<L1BatchAnalytics>, Web3Error> {
+        let method_name = "get_l1_batches_analytics";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        let limit = limit.min(MAX_L1_BATCHES_PER_REQUEST);
+        let mut batches = Vec::with_capacity(limit as usize);
+        for number in (from_l1_batch_number.0..).take(limit as usize) {
+            let analytics = storage
+                .blocks_web3_dal()
+                .get_l1_batch_analytics(L1BatchNumber(number))
+                .await
+                .map_err(|err| internal_error(method_name, err))?;
+            let Some(analytics) = analytics else {
+                break;
+            };
+            batches.push(analytics);
+        }
+        method_latency.observe();
+        Ok(batches)
+    }
+}
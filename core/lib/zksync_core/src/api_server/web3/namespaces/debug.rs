@@ -1,15 +1,19 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT};
 use once_cell::sync::OnceCell;
 use zksync_dal::ConnectionPool;
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, CallTraceLimits, DebugCall, ResultDebugCall, StorageEntry,
+        StorageRangeResult, TracerConfig,
+    },
     l2::L2Tx,
     transaction_request::CallRequest,
     vm_trace::Call,
-    AccountTreeId, L2ChainId, H256, USED_BOOTLOADER_MEMORY_BYTES,
+    AccountTreeId, Address, Bytes, L2ChainId, MiniblockNumber, StorageKey, H256,
+    USED_BOOTLOADER_MEMORY_BYTES,
 };
 use zksync_web3_decl::error::Web3Error;
 
@@ -21,6 +25,7 @@ use crate::{
         tx_sender::ApiContracts,
         web3::{
             backend_jsonrpsee::internal_error,
+            ensure_block_data_available, ensure_storage_state_available,
             metrics::API_METRICS,
             resolve_block,
             state::{RpcState, SealedMiniblockNumber},
@@ -29,6 +34,11 @@ use crate::{
     l1_gas_price::L1GasPriceProvider,
 };
 
+/// Maximum number of storage slots returned by `debug_storageRangeAt` in a single call.
+const MAX_STORAGE_RANGE_SIZE: usize = 1_024;
+/// Maximum address in the kernel space, which is reserved for system contracts.
+const MAX_KERNEL_SPACE_ADDRESS: u64 = 0xffff;
+
 #[derive(Debug, Clone)]
 pub struct DebugNamespace {
     connection_pool: ConnectionPool,
@@ -39,6 +49,7 @@ pub struct DebugNamespace {
     storage_caches: PostgresStorageCaches,
     last_sealed_miniblock: SealedMiniblockNumber,
     chain_id: L2ChainId,
+    trace_limits: CallTraceLimits,
 }
 
 impl DebugNamespace {
@@ -55,6 +66,7 @@ impl DebugNamespace {
             storage_caches: state.tx_sender.storage_caches(),
             last_sealed_miniblock: state.last_sealed_miniblock,
             chain_id: sender_config.chain_id,
+            trace_limits: state.api_config.trace_limits,
         }
     }
 
@@ -72,23 +84,20 @@ impl DebugNamespace {
             .unwrap_or(false);
         let mut connection = self
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
         let call_trace = connection
             .blocks_web3_dal()
             .get_trace_for_miniblock(block_number)
             .await
-            .unwrap();
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
         let call_trace = call_trace
             .into_iter()
-            .map(|call_trace| {
-                let mut result: DebugCall = call_trace.into();
-                if only_top_call {
-                    result.calls = vec![];
-                }
-                ResultDebugCall { result }
+            .map(|call_trace| ResultDebugCall {
+                result: self.convert_call_trace(call_trace, only_top_call),
             })
             .collect();
 
@@ -102,25 +111,22 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Option<DebugCall> {
+    ) -> Result<Option<DebugCall>, Web3Error> {
+        const METHOD_NAME: &str = "debug_trace_transaction";
+
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
             .unwrap_or(false);
         let call_trace = self
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .transactions_dal()
             .get_call_trace(tx_hash)
-            .await;
-        call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
-            }
-            result
-        })
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        Ok(call_trace.map(|call_trace| self.convert_call_trace(call_trace, only_top_call)))
     }
 
     #[tracing::instrument(skip(self, request, block_id))]
@@ -140,13 +146,19 @@ impl DebugNamespace {
 
         let mut connection = self
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_args = BlockArgs::new(&mut connection, block_id)
             .await
             .map_err(|err| internal_error("debug_trace_call", err))?
             .ok_or(Web3Error::NoBlock)?;
+        ensure_storage_state_available(
+            &mut connection,
+            block_args.resolved_block_number(),
+            METHOD_NAME,
+        )
+        .await?;
         drop(connection);
 
         let tx = L2Tx::from_request(request.into(), USED_BOOTLOADER_MEMORY_BYTES)?;
@@ -171,6 +183,7 @@ impl DebugNamespace {
             block_args,
             self.vm_execution_cache_misses_limit,
             custom_tracers,
+            None,
         )
         .await;
 
@@ -202,7 +215,179 @@ impl DebugNamespace {
 
         let block_diff = self.last_sealed_miniblock.diff_with_block_args(&block_args);
         method_latency.observe(block_diff);
-        Ok(call.into())
+        Ok(self.convert_call_trace(call, only_top_call))
+    }
+
+    /// Converts a call trace to the API format, omitting nested calls beyond the configured trace limits
+    /// so that traces of huge transactions don't exhaust server memory or exceed the response size limit.
+    fn convert_call_trace(&self, mut call_trace: Call, only_top_call: bool) -> DebugCall {
+        if only_top_call {
+            call_trace.calls = vec![];
+            DebugCall::from(call_trace)
+        } else {
+            DebugCall::from_call_with_limits(call_trace, self.trace_limits)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_block_impl(&self, block_id: BlockId) -> Result<Option<Bytes>, Web3Error> {
+        const METHOD_NAME: &str = "debug_get_raw_block";
+
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap();
+        let block = connection
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, self.chain_id)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(block) = block else {
+            method_latency.observe_without_diff();
+            return Ok(None);
+        };
+
+        let block_number = MiniblockNumber(block.number.as_u32());
+        ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
+        let transactions = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        // Transactions originating from L1 have no raw L2 encoding; they are represented
+        // by empty byte strings so that positions in the list match transaction indices.
+        let raw_transactions: Vec<_> = transactions
+            .into_iter()
+            .map(|tx| tx.raw_bytes.unwrap_or_default())
+            .collect();
+
+        let block_diff = self.last_sealed_miniblock.diff(block_number);
+        method_latency.observe(block_diff);
+        Ok(Some(block.raw_encoding(&raw_transactions)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_receipts_impl(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<Bytes>>, Web3Error> {
+        const METHOD_NAME: &str = "debug_get_raw_receipts";
+
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap();
+        let block_number = match resolve_block(&mut connection, block_id, METHOD_NAME).await {
+            Ok(number) => number,
+            Err(Web3Error::NoBlock) => {
+                method_latency.observe_without_diff();
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
+        let raw_receipts = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_receipts(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let block_diff = self.last_sealed_miniblock.diff(block_number);
+        method_latency.observe(block_diff);
+        Ok(Some(raw_receipts))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_transaction_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<Bytes>, Web3Error> {
+        const METHOD_NAME: &str = "debug_get_raw_transaction";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let raw_transaction = self
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap()
+            .transactions_web3_dal()
+            .get_raw_transaction(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(raw_transaction.map(Bytes))
+    }
+
+    /// Returns non-zero storage slots of a contract as of the state before executing the transaction
+    /// with the specified index in the block, starting from `start_key`.
+    #[tracing::instrument(skip(self))]
+    pub async fn storage_range_at_impl(
+        &self,
+        block_id: BlockId,
+        tx_index: usize,
+        address: Address,
+        start_key: H256,
+        max_result: usize,
+    ) -> Result<StorageRangeResult, Web3Error> {
+        const METHOD_NAME: &str = "debug_storage_range_at";
+
+        // Storage logs of system contracts are not indexed by the contract address; see the DAL method.
+        if address <= Address::from_low_u64_be(MAX_KERNEL_SPACE_ADDRESS) {
+            return Err(Web3Error::NoStorageRange(address));
+        }
+
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap();
+        let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        // Indices that don't fit into `u32` are past the end of the block anyway.
+        let operation_number_bound = match u32::try_from(tx_index) {
+            Ok(tx_index) => connection
+                .storage_web3_dal()
+                .get_first_operation_number_for_tx_index(block_number, tx_index)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?,
+            Err(_) => None,
+        };
+
+        let max_result = max_result.min(MAX_STORAGE_RANGE_SIZE);
+        // Request an additional slot to determine the next key.
+        let mut slots = connection
+            .storage_web3_dal()
+            .get_storage_range_unchecked(
+                address,
+                start_key,
+                block_number,
+                operation_number_bound,
+                max_result + 1,
+            )
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let next_key = if slots.len() > max_result {
+            slots.pop().map(|(key, _)| key)
+        } else {
+            None
+        };
+
+        let account = AccountTreeId::new(address);
+        let storage: BTreeMap<_, _> = slots
+            .into_iter()
+            .map(|(key, value)| {
+                let hashed_key = StorageKey::new(account, key).hashed_key();
+                (hashed_key, StorageEntry { key, value })
+            })
+            .collect();
+
+        let block_diff = self.last_sealed_miniblock.diff(block_number);
+        method_latency.observe(block_diff);
+        Ok(StorageRangeResult { storage, next_key })
     }
 
     fn shared_args(&self) -> TxSharedArgs {
@@ -35,7 +35,7 @@ impl<G: L1GasPriceProvider> EnNamespace<G> {
         let mut storage = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         storage
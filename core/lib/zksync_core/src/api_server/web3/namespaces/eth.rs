@@ -1,7 +1,7 @@
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        state_override::StateOverride, BlockId, BlockNumber, GetLogsFilter, Transaction,
+        TransactionId, TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
@@ -22,6 +22,7 @@ use crate::{
         execution_sandbox::BlockArgs,
         web3::{
             backend_jsonrpsee::internal_error,
+            ensure_block_data_available, ensure_storage_state_available,
             metrics::{BlockCallObserver, API_METRICS},
             resolve_block,
             state::RpcState,
@@ -60,7 +61,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let block_number = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -73,11 +74,12 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         block_number
     }
 
-    #[tracing::instrument(skip(self, request, block_id))]
+    #[tracing::instrument(skip(self, request, block_id, state_override))]
     pub async fn call_impl(
         &self,
         request: CallRequest,
         block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<Bytes, Web3Error> {
         const METHOD_NAME: &str = "call";
 
@@ -86,20 +88,29 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_args = BlockArgs::new(&mut connection, block_id)
             .await
             .map_err(|err| internal_error("eth_call", err))?
             .ok_or(Web3Error::NoBlock)?;
+        ensure_storage_state_available(
+            &mut connection,
+            block_args.resolved_block_number(),
+            METHOD_NAME,
+        )
+        .await?;
         drop(connection);
 
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
-        let res_bytes = call_result
-            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, state_override)
+            .await;
+        let res_bytes = call_result.map_err(Web3Error::from)?;
 
         let block_diff = self
             .state
@@ -109,16 +120,26 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         Ok(res_bytes.into())
     }
 
-    #[tracing::instrument(skip(self, request, _block))]
+    #[tracing::instrument(skip(self, request, _block, state_override))]
     pub async fn estimate_gas_impl(
         &self,
         request: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "estimate_gas";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut request_with_gas_per_pubdata_overridden = request;
+        if request_with_gas_per_pubdata_overridden.nonce.is_none() {
+            // Use the overridden nonce of the sender (if any) instead of the one stored in Postgres.
+            let from = request_with_gas_per_pubdata_overridden
+                .from
+                .unwrap_or_default();
+            request_with_gas_per_pubdata_overridden.nonce = state_override
+                .as_ref()
+                .and_then(|state| state.get(&from)?.nonce);
+        }
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
             .await?;
@@ -158,9 +179,14 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation,
+                state_override,
+            )
             .await
-            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
+            .map_err(Web3Error::from)?;
 
         method_latency.observe();
         Ok(fee.gas_limit)
@@ -189,10 +215,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        ensure_storage_state_available(&mut connection, block_number, METHOD_NAME).await?;
         let balance = connection
             .storage_web3_dal()
             .standard_token_historical_balance(
@@ -279,12 +306,13 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         };
         let method_latency = API_METRICS.start_block_call(method_name, block_id);
 
-        let block = self
+        let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let block = connection
             .blocks_web3_dal()
             .get_block_by_web3_block_id(
                 block_id,
@@ -292,15 +320,17 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 self.state.api_config.l2_chain_id,
             )
             .await
-            .map_err(|err| internal_error(method_name, err));
+            .map_err(|err| internal_error(method_name, err))?;
 
-        if let Ok(Some(block)) = &block {
+        if let Some(block) = &block {
             let block_number = MiniblockNumber(block.number.as_u32());
+            // Transactions of pruned blocks are removed, so the block would be returned with an incomplete body.
+            ensure_block_data_available(&mut connection, block_number, method_name).await?;
             self.report_latency_with_block_id(method_latency, block_number);
         } else {
             method_latency.observe_without_diff();
         }
-        block
+        Ok(block)
     }
 
     #[tracing::instrument(skip(self))]
@@ -311,23 +341,25 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_block_transaction_count";
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
-        let tx_count = self
+        let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let tx_count = connection
             .blocks_web3_dal()
             .get_block_tx_count(block_id)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
 
-        if let Ok(Some((block_number, _))) = &tx_count {
-            self.report_latency_with_block_id(method_latency, *block_number);
+        if let Some((block_number, _)) = tx_count {
+            ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
+            self.report_latency_with_block_id(method_latency, block_number);
         } else {
             method_latency.observe_without_diff();
         }
-        Ok(tx_count?.map(|(_, count)| count))
+        Ok(tx_count.map(|(_, count)| count))
     }
 
     #[tracing::instrument(skip(self))]
@@ -343,10 +375,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        ensure_storage_state_available(&mut connection, block_number, METHOD_NAME).await?;
         let contract_code = connection
             .storage_web3_dal()
             .get_contract_code_unchecked(address, block_number)
@@ -377,10 +410,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
+        ensure_storage_state_available(&mut connection, block_number, METHOD_NAME).await?;
         let value = connection
             .storage_web3_dal()
             .get_historical_value_unchecked(&storage_key, block_number)
@@ -408,7 +442,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
 
@@ -423,6 +457,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             }
             _ => {
                 let block_number = resolve_block(&mut connection, block_id, method_name).await?;
+                ensure_storage_state_available(&mut connection, block_number, method_name).await?;
                 let nonce = connection
                     .storage_web3_dal()
                     .get_address_historical_nonce(address, block_number)
@@ -451,16 +486,28 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_transaction";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let mut transaction = self
+        let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        if let TransactionId::Block(block_id, _) = id {
+            match resolve_block(&mut connection, block_id, METHOD_NAME).await {
+                Ok(block_number) => {
+                    ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
+                }
+                // If the block doesn't exist, the transaction won't be found below.
+                Err(Web3Error::NoBlock) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let mut transaction = connection
             .transactions_web3_dal()
             .get_transaction(id, self.state.api_config.l2_chain_id)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err));
+        drop(connection);
 
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
             // We're running an external node - check the proxy cache in
@@ -500,7 +547,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let receipt = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .transactions_web3_dal()
@@ -520,7 +567,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut conn = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
         let last_block_number = conn
@@ -641,7 +688,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let submit_result = submit_result.map(|_| hash).map_err(|err| {
             tracing::debug!("Send raw transaction error: {err}");
             API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
-            Web3Error::SubmitTransactionError(err.to_string(), err.data())
+            Web3Error::from(err)
         });
 
         method_latency.observe();
@@ -692,7 +739,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let newest_miniblock =
@@ -739,7 +786,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 let mut conn = self
                     .state
                     .connection_pool
-                    .access_storage_tagged("api")
+                    .access_read_only_storage_tagged("api")
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
                 let (block_hashes, last_block_number) = conn
@@ -760,7 +807,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 let mut conn = self
                     .state
                     .connection_pool
-                    .access_storage_tagged("api")
+                    .access_read_only_storage_tagged("api")
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
                 let (tx_hashes, last_timestamp) = conn
@@ -818,9 +865,10 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                 let mut storage = self
                     .state
                     .connection_pool
-                    .access_storage_tagged("api")
+                    .access_read_only_storage_tagged("api")
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
+                ensure_block_data_available(&mut storage, *from_block, METHOD_NAME).await?;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
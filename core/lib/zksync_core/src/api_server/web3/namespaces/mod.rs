@@ -1,15 +1,18 @@
 //! Actual implementation of Web3 API namespaces logic, not tied to the backend
 //! used to create a JSON RPC server.
 
+mod analytics;
 mod debug;
 mod en;
 pub(crate) mod eth;
 mod net;
 mod snapshots;
+mod unstable;
 mod web3;
 mod zks;
 
 pub use self::{
-    debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
+    analytics::AnalyticsNamespace, debug::DebugNamespace, en::EnNamespace, eth::EthNamespace,
+    net::NetNamespace, snapshots::SnapshotsNamespace, unstable::UnstableNamespace,
+    web3::Web3Namespace, zks::ZksNamespace,
 };
@@ -32,7 +32,7 @@ impl<G: L1GasPriceProvider> SnapshotsNamespace<G> {
         let mut storage_processor = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .map_err(|err| internal_error(method_name, err))?;
         let mut snapshots_dal = storage_processor.snapshots_dal();
@@ -53,7 +53,7 @@ impl<G: L1GasPriceProvider> SnapshotsNamespace<G> {
         let mut storage_processor = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .map_err(|err| internal_error(method_name, err))?;
         let mut snapshots_dal = storage_processor.snapshots_dal();
@@ -63,12 +63,14 @@ impl<G: L1GasPriceProvider> SnapshotsNamespace<G> {
             .map_err(|err| internal_error(method_name, err))?;
         if let Some(snapshot_metadata) = snapshot_metadata {
             let snapshot_files = snapshot_metadata.storage_logs_filepaths.clone();
+            let checksums = snapshot_metadata.storage_logs_checksums.as_deref();
             let chunks = snapshot_files
                 .iter()
                 .enumerate()
                 .map(|(chunk_id, filepath)| SnapshotStorageLogsChunkMetadata {
                     chunk_id: chunk_id as u64,
                     filepath: filepath.clone(),
+                    checksum: checksums.and_then(|checksums| checksums.get(chunk_id).copied()),
                 })
                 .collect();
             let l1_batch_with_metadata = storage_processor
@@ -91,6 +93,8 @@ impl<G: L1GasPriceProvider> SnapshotsNamespace<G> {
                 last_l1_batch_with_metadata: l1_batch_with_metadata,
                 storage_logs_chunks: chunks,
                 factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
+                factory_deps_checksum: snapshot_metadata.factory_deps_checksum,
+                incremental: snapshot_metadata.incremental,
             }))
         } else {
             method_latency.observe();
@@ -0,0 +1,84 @@
+use zksync_types::{
+    api::sealing::{L1BatchSealStatus, SealSimulation},
+    l2::L2Tx,
+    transaction_request::CallRequest,
+    MAX_GAS_PER_PUBDATA_BYTE,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
+    l1_gas_price::L1GasPriceProvider,
+};
+
+#[derive(Debug)]
+pub struct UnstableNamespace<G> {
+    state: RpcState<G>,
+}
+
+impl<G> Clone for UnstableNamespace<G> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<G: L1GasPriceProvider> UnstableNamespace<G> {
+    pub fn new(state: RpcState<G>) -> Self {
+        Self { state }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_seal_criteria_status_impl(
+        &self,
+    ) -> Result<Option<L1BatchSealStatus>, Web3Error> {
+        const METHOD_NAME: &str = "get_seal_criteria_status";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let status = self
+            .state
+            .tx_sender
+            .pending_l1_batch_seal_status()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, format!("{err:#}")))?;
+        method_latency.observe();
+        Ok(status)
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    pub async fn simulate_seal_criteria_impl(
+        &self,
+        request: CallRequest,
+    ) -> Result<Option<SealSimulation>, Web3Error> {
+        let method_latency = API_METRICS.start_call("simulate_seal_criteria");
+        let mut request_with_gas_per_pubdata_overridden = request;
+        self.state
+            .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
+            .await?;
+        if let Some(ref mut eip712_meta) = request_with_gas_per_pubdata_overridden.eip712_meta {
+            eip712_meta.gas_per_pubdata = MAX_GAS_PER_PUBDATA_BYTE.into();
+        }
+
+        let mut tx = L2Tx::from_request(
+            request_with_gas_per_pubdata_overridden.into(),
+            self.state.api_config.max_tx_size,
+        )?;
+        // The transaction is executed as during fee estimation, so provided fee values are not considered.
+        tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
+        tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
+
+        let simulation = self
+            .state
+            .tx_sender
+            .simulate_pending_l1_batch_sealing(
+                tx.into(),
+                self.state.api_config.estimate_gas_scale_factor,
+                self.state.api_config.estimate_gas_acceptable_overestimation,
+            )
+            .await
+            .map_err(Web3Error::from)?;
+        method_latency.observe();
+        Ok(simulation)
+    }
+}
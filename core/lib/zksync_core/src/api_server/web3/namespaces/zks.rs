@@ -1,22 +1,25 @@
 use std::{collections::HashMap, convert::TryInto};
 
 use bigdecimal::{BigDecimal, Zero};
+use multivm::vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata;
 use zksync_dal::StorageProcessor;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
+        AccountNonceDetails, BlockDetails, BlockNumber, BridgeAddresses, FeeEstimate,
+        FeeEstimateOptions, FeeParams, GetLogsFilter, L1BatchDetails, L1BatchIdVariant,
+        L1BatchProofPublicInputs, L2ToL1LogProof, L2ToL1Message, Log, PriorityOpStatus, Proof,
         ProtocolVersion, StorageProof, TransactionDetails,
     },
-    fee::Fee,
+    block::MiniblockHeader,
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
-    AccountTreeId, L1BatchNumber, MiniblockNumber, StorageKey, Transaction, L1_MESSENGER_ADDRESS,
-    L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
-    U64,
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, Nonce, StorageKey, Transaction,
+    L1_GAS_PER_PUBDATA_BYTE, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 use zksync_utils::{address_to_h256, ratio_to_big_decimal_normalized};
 use zksync_web3_decl::{
@@ -26,8 +29,12 @@ use zksync_web3_decl::{
 
 use crate::{
     api_server::{
-        tree::TreeApiClient,
-        web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, RpcState},
+        tree::{is_missing_version_error, TreeApiClient, TreeApiHttpClient},
+        tx_sender::SubmitTxError,
+        web3::{
+            backend_jsonrpsee::internal_error, ensure_block_data_available, metrics::API_METRICS,
+            RpcState,
+        },
     },
     l1_gas_price::L1GasPriceProvider,
 };
@@ -51,7 +58,11 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub async fn estimate_fee_impl(
+        &self,
+        request: CallRequest,
+        options: Option<FeeEstimateOptions>,
+    ) -> Result<FeeEstimate, Web3Error> {
         const METHOD_NAME: &str = "estimate_fee";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
@@ -75,9 +86,12 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
 
-        let fee = self.estimate_fee(tx.into()).await?;
+        let with_inclusion_hint = options.unwrap_or_default().inclusion_hint;
+        let estimate = self
+            .estimate_fee(tx.into(), with_inclusion_hint, METHOD_NAME)
+            .await?;
         method_latency.observe();
-        Ok(fee)
+        Ok(estimate)
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -101,24 +115,35 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .try_into()
             .map_err(Web3Error::SerializationError)?;
 
-        let fee = self.estimate_fee(tx.into()).await?;
+        let estimate = self.estimate_fee(tx.into(), false, METHOD_NAME).await?;
         method_latency.observe();
-        Ok(fee.gas_limit)
+        Ok(estimate.fee.gas_limit)
     }
 
-    async fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
+    async fn estimate_fee(
+        &self,
+        tx: Transaction,
+        with_inclusion_hint: bool,
+        method_name: &'static str,
+    ) -> Result<FeeEstimate, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
 
-        let fee = self
-            .state
+        self.state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .estimate_fee(
+                tx,
+                scale_factor,
+                acceptable_overestimation,
+                None,
+                with_inclusion_hint,
+            )
             .await
-            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
-
-        Ok(fee)
+            .map_err(|err| match err {
+                SubmitTxError::Internal(err) => internal_error(method_name, format!("{err:#}")),
+                err => err.into(),
+            })
     }
 
     #[tracing::instrument(skip(self))]
@@ -153,7 +178,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let tokens = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .tokens_web3_dal()
@@ -192,7 +217,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             let mut storage = self
                 .state
                 .connection_pool
-                .access_storage_tagged("api")
+                .access_read_only_storage_tagged("api")
                 .await
                 .unwrap();
             storage.tokens_web3_dal().get_token_price(&l2_token).await
@@ -223,7 +248,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let balances = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .accounts_dal()
@@ -244,6 +269,68 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         Ok(balances)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_account_nonce_details_impl(
+        &self,
+        address: Address,
+    ) -> Result<AccountNonceDetails, Web3Error> {
+        const METHOD_NAME: &str = "get_account_nonce_details";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap();
+        let latest_miniblock = storage
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let latest_nonce = storage
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, latest_miniblock)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let committed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let committed_miniblock = match committed_l1_batch {
+            Some(l1_batch_number) => storage
+                .blocks_web3_dal()
+                .get_miniblock_range_of_l1_batch(l1_batch_number)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .map(|(_, last_miniblock)| last_miniblock),
+            None => None,
+        };
+        // If no L1 batches are committed yet, use the genesis state.
+        let committed_miniblock = committed_miniblock.unwrap_or(MiniblockNumber(0));
+        let committed_nonce = storage
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, committed_miniblock)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let latest_nonce = Nonce(latest_nonce.as_u32());
+        let pending_nonces = storage
+            .transactions_web3_dal()
+            .get_pending_nonces_by_initiator_account(address, latest_nonce)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(AccountNonceDetails::new(
+            Nonce(committed_nonce.as_u32()),
+            latest_nonce,
+            pending_nonces,
+        ))
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l2_to_l1_msg_proof_impl(
         &self,
@@ -258,7 +345,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let mut storage = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let l1_batch_number = match storage
@@ -387,7 +474,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let mut storage = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         let Some((l1_batch_number, l1_batch_tx_index)) = storage
@@ -413,6 +500,58 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         Ok(log_proof)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l2_to_l1_messages_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<Vec<L2ToL1Message>>, Web3Error> {
+        const METHOD_NAME: &str = "get_l2_to_l1_messages";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let receipt = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap()
+            .transactions_web3_dal()
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(receipt.map(|receipt| receipt.l2_to_l1_messages))
+    }
+
+    #[tracing::instrument(skip(self, key))]
+    pub async fn get_indexed_logs_impl(
+        &self,
+        index: &str,
+        key: &[u8],
+        from_block: MiniblockNumber,
+        limit: Option<u32>,
+    ) -> Result<Vec<Log>, Web3Error> {
+        const METHOD_NAME: &str = "get_indexed_logs";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| (limit as usize).min(max_limit));
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let logs = storage
+            .event_indexes_dal()
+            .get_indexed_logs(index, key, from_block, limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err));
+
+        method_latency.observe();
+        logs
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_number_impl(&self) -> Result<U64, Web3Error> {
         const METHOD_NAME: &str = "get_l1_batch_number";
@@ -421,7 +560,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let l1_batch_number = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -445,7 +584,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let minmax = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -469,7 +608,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let block_details = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -484,6 +623,68 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         block_details
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_fee_params_at_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<FeeParams>, Web3Error> {
+        const METHOD_NAME: &str = "get_fee_params_at";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let header = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap()
+            .blocks_dal()
+            .get_miniblock_header(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(header.map(|header| Self::fee_params(&header)))
+    }
+
+    /// Gas per pubdata isn't persisted for older miniblocks, so it's derived from the persisted gas prices
+    /// for them. The derivation formula is the same for all VM versions.
+    fn fee_params(header: &MiniblockHeader) -> FeeParams {
+        let gas_per_pubdata = header.gas_per_pubdata.unwrap_or_else(|| {
+            derive_base_fee_and_gas_per_pubdata(header.l1_gas_price, header.l2_fair_gas_price).1
+        });
+        FeeParams {
+            number: header.number,
+            l1_gas_price: header.l1_gas_price,
+            fair_l2_gas_price: header.l2_fair_gas_price,
+            base_fee_per_gas: header.base_fee_per_gas,
+            fair_pubdata_price: header.l1_gas_price * u64::from(L1_GAS_PER_PUBDATA_BYTE),
+            gas_per_pubdata,
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_priority_op_status_impl(
+        &self,
+        l1_tx_hash: H256,
+    ) -> Result<Option<PriorityOpStatus>, Web3Error> {
+        const METHOD_NAME: &str = "get_priority_op_status";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let status = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap()
+            .transactions_web3_dal()
+            .get_priority_op_status(l1_tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err));
+
+        method_latency.observe();
+        status
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_raw_block_transactions_impl(
         &self,
@@ -492,12 +693,14 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_raw_block_transactions";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let transactions = self
+        let mut connection = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        ensure_block_data_available(&mut connection, block_number, METHOD_NAME).await?;
+        let transactions = connection
             .transactions_web3_dal()
             .get_raw_miniblock_transactions(block_number)
             .await
@@ -518,7 +721,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let mut tx_details = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .transactions_web3_dal()
@@ -553,7 +756,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let l1_batch = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -566,22 +769,72 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn get_bytecode_by_hash_impl(&self, hash: H256) -> Option<Vec<u8>> {
+    pub async fn get_batch_proof_public_inputs_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchProofPublicInputs>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_proof_public_inputs";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        // The genesis L1 batch is not proven.
+        let Some(prev_batch_number) = batch_number.0.checked_sub(1) else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .unwrap();
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, format!("{err:#}")))?;
+        // Metadata is missing if the commitment for the L1 batch is not computed yet.
+        let Some(l1_batch) = l1_batch else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let prev_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(prev_batch_number))
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, format!("{err:#}")))?
+            .ok_or_else(|| {
+                let err = format!("metadata for L1 batch #{prev_batch_number} is missing");
+                internal_error(METHOD_NAME, err)
+            })?;
+
+        method_latency.observe();
+        Ok(Some(L1BatchProofPublicInputs::new(
+            &prev_l1_batch,
+            &l1_batch,
+        )))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_bytecode_by_hash_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<Vec<u8>>, Web3Error> {
         const METHOD_NAME: &str = "get_bytecode_by_hash";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let bytecode = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
-            .storage_dal()
-            .get_factory_dep(hash)
-            .await;
+            .storage_web3_dal()
+            .get_bytecode_by_hash(hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
 
         method_latency.observe();
-        bytecode
+        Ok(bytecode)
     }
 
     #[tracing::instrument(skip(self))]
@@ -612,7 +865,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             Some(id) => {
                 self.state
                     .connection_pool
-                    .access_storage()
+                    .access_read_only_storage()
                     .await
                     .unwrap()
                     .protocol_versions_web3_dal()
@@ -622,7 +875,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             None => Some(
                 self.state
                     .connection_pool
-                    .access_storage()
+                    .access_read_only_storage()
                     .await
                     .unwrap()
                     .protocol_versions_web3_dal()
@@ -640,23 +893,35 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         &self,
         address: Address,
         keys: Vec<H256>,
-        l1_batch_number: L1BatchNumber,
+        l1_batch: L1BatchIdVariant,
     ) -> Result<Proof, Web3Error> {
         const METHOD_NAME: &str = "get_proofs";
 
+        let tree_api = self
+            .state
+            .tree_api
+            .as_ref()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        let l1_batch_number = self
+            .resolve_l1_batch_for_proofs(tree_api, l1_batch)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
             .collect();
-
-        let storage_proof = self
-            .state
-            .tree_api
-            .as_ref()
-            .ok_or(Web3Error::TreeApiUnavailable)?
+        let proofs = tree_api
             .get_proofs(l1_batch_number, hashed_keys)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| {
+                if is_missing_version_error(&err) {
+                    Web3Error::NoTreeVersion(l1_batch_number)
+                } else {
+                    internal_error(METHOD_NAME, err)
+                }
+            })?;
+        let storage_proof = proofs
             .into_iter()
             .zip(keys)
             .map(|(proof, key)| StorageProof {
@@ -672,4 +937,71 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             storage_proof,
         })
     }
+
+    /// Resolves the L1 batch to get proofs for. Tags are resolved as follows: `earliest` is the genesis L1 batch,
+    /// `finalized` is the last L1 batch executed on L1, and other tags correspond to the last L1 batch processed
+    /// by the Merkle tree.
+    async fn resolve_l1_batch_for_proofs(
+        &self,
+        tree_api: &TreeApiHttpClient,
+        l1_batch: L1BatchIdVariant,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(match l1_batch {
+            L1BatchIdVariant::Number(number) => number,
+            L1BatchIdVariant::Tag(BlockNumber::Number(number)) => {
+                let number = u32::try_from(number.as_u64()).unwrap_or(u32::MAX);
+                L1BatchNumber(number)
+            }
+            L1BatchIdVariant::Tag(BlockNumber::Earliest) => L1BatchNumber(0),
+            L1BatchIdVariant::Tag(BlockNumber::Finalized) => {
+                let mut storage = self
+                    .state
+                    .connection_pool
+                    .access_read_only_storage_tagged("api")
+                    .await?;
+                storage
+                    .blocks_dal()
+                    .get_number_of_last_l1_batch_executed_on_eth()
+                    .await?
+                    .unwrap_or(L1BatchNumber(0))
+            }
+            L1BatchIdVariant::Tag(
+                BlockNumber::Latest | BlockNumber::Committed | BlockNumber::Pending,
+            ) => {
+                let info = tree_api.get_info().await?;
+                anyhow::ensure!(
+                    info.next_l1_batch_number > L1BatchNumber(0),
+                    "Merkle tree doesn't contain any L1 batches"
+                );
+                info.next_l1_batch_number - 1
+            }
+        })
+    }
+
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub async fn send_raw_transaction_with_deadline_impl(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> Result<H256, Web3Error> {
+        const METHOD_NAME: &str = "send_raw_transaction_with_deadline";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+
+        let submit_result = self
+            .state
+            .tx_sender
+            .submit_tx_with_deadline(tx, Some(valid_until.as_u64()))
+            .await;
+        let submit_result = submit_result.map(|_| hash).map_err(|err| {
+            tracing::debug!("Send raw transaction with deadline error: {err}");
+            API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
+            Web3Error::from(err)
+        });
+
+        method_latency.observe();
+        submit_result
+    }
 }
@@ -0,0 +1,200 @@
+//! Deployment profiles for Web3 API servers.
+//!
+//! A profile describes a single API server serving a subset of namespaces with dedicated resources, e.g. a "trace node"
+//! serving only the `debug` namespace with a low VM concurrency limit, or a "public node" serving `eth`, `zks`
+//! and `net`. Profiles are loaded from a JSON file and started by the `api_profiles` component, so that each deployment
+//! can run a subset of APIs instead of the monolithic HTTP / WS API servers.
+
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use super::Namespace;
+
+/// Transport of an API server started for a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiProfileTransport {
+    Http,
+    Ws,
+}
+
+/// Configuration of a single API server profile.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiProfileConfig {
+    /// Name of the profile used in logs and health checks (e.g., `trace_node`).
+    pub name: String,
+    pub transport: ApiProfileTransport,
+    /// Port the API server is listening on.
+    pub port: u16,
+    /// Namespaces served by the API server.
+    pub namespaces: Vec<Namespace>,
+    /// Maximum number of connections in the replica DB pool used by the server. If not set, the server shares
+    /// the replica pool with other API servers in the process.
+    pub pool_size: Option<u32>,
+    /// Maximum number of VM instances concurrently spawned by the server. If not set,
+    /// `api.web3_json_rpc.vm_concurrency_limit` is used.
+    pub vm_concurrency_limit: Option<usize>,
+    /// Number of server threads. If not set, the thread count for the transport from the Web3 API config is used.
+    pub threads: Option<usize>,
+}
+
+impl ApiProfileConfig {
+    /// Loads profiles from a JSON file containing an array of profile configs.
+    pub fn load(path: &Path) -> anyhow::Result<Vec<Self>> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed reading API profiles from {path:?}"))?;
+        let profiles: Vec<Self> = serde_json::from_str(&contents)
+            .with_context(|| format!("failed parsing API profiles from {path:?}"))?;
+        Self::validate(&profiles)?;
+        Ok(profiles)
+    }
+
+    fn validate(profiles: &[Self]) -> anyhow::Result<()> {
+        let mut names = HashSet::with_capacity(profiles.len());
+        let mut ports = HashSet::with_capacity(profiles.len());
+        for profile in profiles {
+            let name = &profile.name;
+            anyhow::ensure!(
+                names.insert(name.as_str()),
+                "API profile `{name}` is defined multiple times"
+            );
+            anyhow::ensure!(
+                ports.insert(profile.port),
+                "API profile `{name}` uses port {} already used by another profile",
+                profile.port
+            );
+            anyhow::ensure!(
+                !profile.namespaces.is_empty(),
+                "API profile `{name}` serves no namespaces"
+            );
+            anyhow::ensure!(
+                profile.transport == ApiProfileTransport::Ws
+                    || !profile.namespaces.contains(&Namespace::Pubsub),
+                "API profile `{name}` serves the pubsub namespace, which requires the WS transport"
+            );
+            anyhow::ensure!(
+                profile.pool_size != Some(0),
+                "API profile `{name}` has zero pool size"
+            );
+            anyhow::ensure!(
+                profile.vm_concurrency_limit != Some(0),
+                "API profile `{name}` has zero VM concurrency limit"
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that `profiles` don't use any of `reserved_ports`, e.g. ports of other API servers in the same process.
+    /// Each reserved port is accompanied by a human-readable name of its user.
+    pub fn ensure_ports_available(
+        profiles: &[Self],
+        reserved_ports: &[(&str, u16)],
+    ) -> anyhow::Result<()> {
+        for profile in profiles {
+            if let Some((port_user, _)) = reserved_ports
+                .iter()
+                .find(|(_, port)| *port == profile.port)
+            {
+                anyhow::bail!(
+                    "API profile `{}` uses port {} already used by the {port_user} server",
+                    profile.name,
+                    profile.port
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the health check name for the API server started for this profile.
+    pub(crate) fn health_check_name(&self) -> String {
+        format!("api_profile_{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = r#"[
+        {
+            "name": "trace_node",
+            "transport": "http",
+            "port": 3060,
+            "namespaces": ["debug"],
+            "pool_size": 10,
+            "vm_concurrency_limit": 16
+        },
+        {
+            "name": "public_node",
+            "transport": "ws",
+            "port": 3061,
+            "namespaces": ["eth", "zks", "net", "pubsub"],
+            "threads": 4
+        }
+    ]"#;
+
+    #[test]
+    fn loading_profiles() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("profiles.json");
+        fs::write(&path, PROFILES).unwrap();
+
+        let profiles = ApiProfileConfig::load(&path).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "trace_node");
+        assert_eq!(profiles[0].transport, ApiProfileTransport::Http);
+        assert_eq!(profiles[0].namespaces, [Namespace::Debug]);
+        assert_eq!(profiles[0].pool_size, Some(10));
+        assert_eq!(profiles[0].vm_concurrency_limit, Some(16));
+        assert_eq!(profiles[0].threads, None);
+        assert_eq!(profiles[0].health_check_name(), "api_profile_trace_node");
+        assert_eq!(profiles[1].transport, ApiProfileTransport::Ws);
+        assert_eq!(
+            profiles[1].namespaces,
+            [
+                Namespace::Eth,
+                Namespace::Zks,
+                Namespace::Net,
+                Namespace::Pubsub
+            ]
+        );
+        assert_eq!(profiles[1].pool_size, None);
+        assert_eq!(profiles[1].threads, Some(4));
+    }
+
+    #[test]
+    fn validating_profiles() {
+        let mut profiles: Vec<ApiProfileConfig> = serde_json::from_str(PROFILES).unwrap();
+        ApiProfileConfig::validate(&profiles).unwrap();
+
+        let mut invalid_profiles = profiles.clone();
+        invalid_profiles[1].port = invalid_profiles[0].port;
+        let err = ApiProfileConfig::validate(&invalid_profiles).unwrap_err();
+        assert!(err.to_string().contains("port 3060"), "{err}");
+
+        let mut invalid_profiles = profiles.clone();
+        invalid_profiles[1].name = invalid_profiles[0].name.clone();
+        let err = ApiProfileConfig::validate(&invalid_profiles).unwrap_err();
+        assert!(err.to_string().contains("multiple times"), "{err}");
+
+        profiles[1].transport = ApiProfileTransport::Http;
+        let err = ApiProfileConfig::validate(&profiles).unwrap_err();
+        assert!(err.to_string().contains("pubsub"), "{err}");
+    }
+
+    #[test]
+    fn checking_reserved_ports() {
+        let profiles: Vec<ApiProfileConfig> = serde_json::from_str(PROFILES).unwrap();
+        ApiProfileConfig::ensure_ports_available(&profiles, &[("HTTP API", 3050)]).unwrap();
+
+        let err = ApiProfileConfig::ensure_ports_available(
+            &profiles,
+            &[("HTTP API", 3050), ("WS API", 3061)],
+        )
+        .unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("port 3061") && err.contains("WS API"), "{err}");
+    }
+}
@@ -1,5 +1,7 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::collections::HashSet;
+
 use anyhow::Context as _;
 use futures::FutureExt;
 use tokio::{
@@ -7,8 +9,12 @@ use tokio::{
     task::JoinHandle,
     time::{interval, Duration},
 };
+use zksync_config::configs::api::SubscriptionBackpressurePolicy;
 use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_types::{
+    api::{AddressActivity, TransactionSoftConfirmation},
+    Address, MiniblockNumber, H128, H256,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -25,8 +31,11 @@ use super::{
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
 };
 
-const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of addresses watched by a single `addressActivity` subscription.
+const WATCHED_ADDRESS_NUMBER_LIMIT: usize = 10_000;
+/// Maximum number of miniblocks scanned for address activity in a single poll.
+const MAX_ADDRESS_ACTIVITY_BLOCKS_PER_POLL: u32 = 100;
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -57,9 +66,9 @@ struct PubSubNotifier {
 impl PubSubNotifier {
     async fn sealed_miniblock_number(&self) -> anyhow::Result<MiniblockNumber> {
         self.connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
+            .context("access_read_only_storage_tagged")?
             .blocks_web3_dal()
             .get_sealed_miniblock_number()
             .await
@@ -111,9 +120,9 @@ impl PubSubNotifier {
         last_block_number: MiniblockNumber,
     ) -> anyhow::Result<Vec<BlockHeader>> {
         self.connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
+            .context("access_read_only_storage_tagged")?
             .blocks_web3_dal()
             .get_block_headers_after(last_block_number)
             .await
@@ -149,9 +158,9 @@ impl PubSubNotifier {
         last_time: chrono::NaiveDateTime,
     ) -> anyhow::Result<(Vec<H256>, Option<chrono::NaiveDateTime>)> {
         self.connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
+            .context("access_read_only_storage_tagged")?
             .transactions_web3_dal()
             .get_pending_txs_hashes_after(last_time, None)
             .await
@@ -184,14 +193,104 @@ impl PubSubNotifier {
 
     async fn new_logs(&self, last_block_number: MiniblockNumber) -> anyhow::Result<Vec<Log>> {
         self.connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
+            .context("access_read_only_storage_tagged")?
             .events_web3_dal()
             .get_all_logs(last_block_number)
             .await
             .context("events_web3_dal().get_all_logs()")
     }
+
+    async fn notify_address_activity(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        const SUB_TYPE: SubscriptionType = SubscriptionType::AddressActivity;
+
+        let mut last_block_number = self.sealed_miniblock_number().await?;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_address_activity_notifier is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            if self.sender.receiver_count() == 0 {
+                // The activity query is relatively expensive, so we skip it if there's no one to notify.
+                last_block_number = self.sealed_miniblock_number().await?;
+                self.emit_event(PubSubEvent::NotifyIterationFinished(SUB_TYPE));
+                continue;
+            }
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SUB_TYPE].start();
+            let sealed_block_number = self.sealed_miniblock_number().await?;
+            // Bound the scanned range so that a notifier lagging behind catches up in several polls
+            // instead of scanning all missed miniblocks at once.
+            let to_block_number = MiniblockNumber(
+                sealed_block_number
+                    .0
+                    .min(last_block_number.0 + MAX_ADDRESS_ACTIVITY_BLOCKS_PER_POLL),
+            );
+            let new_activity = if to_block_number > last_block_number {
+                self.new_address_activity(last_block_number, to_block_number)
+                    .await?
+            } else {
+                vec![]
+            };
+            db_latency.observe();
+            last_block_number = last_block_number.max(to_block_number);
+
+            if !new_activity.is_empty() {
+                let new_activity = new_activity
+                    .into_iter()
+                    .map(PubSubResult::AddressActivity)
+                    .collect();
+                self.send_pub_sub_results(new_activity, SUB_TYPE);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(SUB_TYPE));
+        }
+        Ok(())
+    }
+
+    async fn new_address_activity(
+        &self,
+        last_block_number: MiniblockNumber,
+        to_block_number: MiniblockNumber,
+    ) -> anyhow::Result<Vec<AddressActivity>> {
+        self.connection_pool
+            .access_read_only_storage_tagged("api")
+            .await
+            .context("access_read_only_storage_tagged")?
+            .transactions_web3_dal()
+            .get_address_activity(last_block_number, to_block_number)
+            .await
+            .with_context(|| {
+                format!("get_address_activity({last_block_number}, {to_block_number})")
+            })
+    }
+}
+
+/// Filter applied to notifications for a single subscriber.
+#[derive(Debug)]
+enum SubscriberFilter {
+    Logs(PubSubFilter),
+    WatchedAddresses(HashSet<Address>),
+}
+
+impl SubscriberFilter {
+    fn matches(&self, item: &PubSubResult) -> bool {
+        match (self, item) {
+            (Self::Logs(filter), PubSubResult::Log(log)) => filter.matches(log),
+            (Self::WatchedAddresses(addresses), PubSubResult::AddressActivity(activity)) => {
+                addresses.contains(&activity.address)
+            }
+            _ => true,
+        }
+    }
 }
 
 /// Subscription support for Web3 APIs.
@@ -199,19 +298,35 @@ pub(super) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
     transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    address_activity: broadcast::Sender<Vec<PubSubResult>>,
+    /// Set only if soft confirmations are enabled using [`Self::spawn_soft_confirmations_forwarder()`].
+    soft_confirmations: Option<broadcast::Sender<Vec<PubSubResult>>>,
+    max_buffered_notifications: usize,
+    backpressure_policy: SubscriptionBackpressurePolicy,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
 impl EthSubscribe {
-    pub fn new() -> Self {
-        let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
-        let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
-        let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+    /// Creates a new instance. `max_buffered_notifications` is the capacity of broadcast channels
+    /// for each subscription type; subscribers lagging behind by more notifications are handled
+    /// according to `backpressure_policy`.
+    pub fn new(
+        max_buffered_notifications: usize,
+        backpressure_policy: SubscriptionBackpressurePolicy,
+    ) -> Self {
+        let (blocks, _) = broadcast::channel(max_buffered_notifications);
+        let (transactions, _) = broadcast::channel(max_buffered_notifications);
+        let (logs, _) = broadcast::channel(max_buffered_notifications);
+        let (address_activity, _) = broadcast::channel(max_buffered_notifications);
 
         Self {
             blocks,
             transactions,
             logs,
+            address_activity,
+            soft_confirmations: None,
+            max_buffered_notifications,
+            backpressure_policy,
             events_sender: None,
         }
     }
@@ -233,7 +348,8 @@ impl EthSubscribe {
         sink: SubscriptionSink,
         subscription_type: SubscriptionType,
         mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
-        filter: Option<PubSubFilter>,
+        filter: Option<SubscriberFilter>,
+        backpressure_policy: SubscriptionBackpressurePolicy,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
         let lifetime_latency = PUB_SUB_METRICS.subscriber_lifetime[&subscription_type].start();
@@ -254,7 +370,19 @@ impl EthSubscribe {
                             PUB_SUB_METRICS
                                 .skipped_broadcast_messages[&subscription_type]
                                 .observe(message_count);
-                            break;
+                            match backpressure_policy {
+                                SubscriptionBackpressurePolicy::DropOldest => {
+                                    // The oldest messages were already dropped by the broadcast channel,
+                                    // and the receiver will resume from the oldest retained message.
+                                    continue;
+                                }
+                                SubscriptionBackpressurePolicy::Disconnect => {
+                                    PUB_SUB_METRICS
+                                        .lagged_subscriber_disconnects[&subscription_type]
+                                        .inc();
+                                    break;
+                                }
+                            }
                         }
                     };
 
@@ -282,15 +410,13 @@ impl EthSubscribe {
         sink: &SubscriptionSink,
         subscription_type: SubscriptionType,
         new_items: Vec<PubSubResult>,
-        filter: Option<&PubSubFilter>,
+        filter: Option<&SubscriberFilter>,
     ) -> Result<(), SendTimeoutError> {
         let notify_latency = PUB_SUB_METRICS.notify_subscribers_latency[&subscription_type].start();
         for item in new_items {
-            if let PubSubResult::Log(log) = &item {
-                if let Some(filter) = &filter {
-                    if !filter.matches(log) {
-                        continue;
-                    }
+            if let Some(filter) = filter {
+                if !filter.matches(&item) {
+                    continue;
                 }
             }
 
@@ -326,6 +452,7 @@ impl EthSubscribe {
                     SubscriptionType::Blocks,
                     blocks_rx,
                     None,
+                    self.backpressure_policy,
                 ));
 
                 Some(SubscriptionType::Blocks)
@@ -340,6 +467,7 @@ impl EthSubscribe {
                     SubscriptionType::Txs,
                     transactions_rx,
                     None,
+                    self.backpressure_policy,
                 ));
                 Some(SubscriptionType::Txs)
             }
@@ -359,11 +487,56 @@ impl EthSubscribe {
                         sink,
                         SubscriptionType::Logs,
                         logs_rx,
-                        Some(filter),
+                        Some(SubscriberFilter::Logs(filter)),
+                        self.backpressure_policy,
                     ));
                     Some(SubscriptionType::Logs)
                 }
             }
+            "addressActivity" => {
+                // Only the `address` field of the filter is used; `topics` are not supported.
+                let addresses = params
+                    .filter(|filter| filter.topics.is_none())
+                    .and_then(|filter| filter.address);
+                let addresses: HashSet<_> = addresses
+                    .map(|addresses| addresses.0.into_iter().collect())
+                    .unwrap_or_default();
+
+                if addresses.is_empty() || addresses.len() > WATCHED_ADDRESS_NUMBER_LIMIT {
+                    Self::reject(pending_sink).await;
+                    None
+                } else {
+                    let Ok(sink) = pending_sink.accept().await else {
+                        return;
+                    };
+                    tokio::spawn(Self::run_subscriber(
+                        sink,
+                        SubscriptionType::AddressActivity,
+                        self.address_activity.subscribe(),
+                        Some(SubscriberFilter::WatchedAddresses(addresses)),
+                        self.backpressure_policy,
+                    ));
+                    Some(SubscriptionType::AddressActivity)
+                }
+            }
+            "softConfirmations" => {
+                if let Some(soft_confirmations) = &self.soft_confirmations {
+                    let Ok(sink) = pending_sink.accept().await else {
+                        return;
+                    };
+                    tokio::spawn(Self::run_subscriber(
+                        sink,
+                        SubscriptionType::SoftConfirmations,
+                        soft_confirmations.subscribe(),
+                        None,
+                        self.backpressure_policy,
+                    ));
+                    Some(SubscriptionType::SoftConfirmations)
+                } else {
+                    Self::reject(pending_sink).await;
+                    None
+                }
+            }
             "syncing" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
@@ -398,7 +571,7 @@ impl EthSubscribe {
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(4);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
@@ -420,15 +593,71 @@ impl EthSubscribe {
 
         let notifier = PubSubNotifier {
             sender: self.logs.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.address_activity.clone(),
             connection_pool,
             polling_interval,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
+        let notifier_task = tokio::spawn(notifier.notify_address_activity(stop_receiver));
 
         notifier_tasks.push(notifier_task);
         notifier_tasks
     }
+
+    /// Enables `softConfirmations` subscriptions and spawns a task forwarding soft confirmations
+    /// from the provided state keeper channel to subscribers.
+    pub fn spawn_soft_confirmations_forwarder(
+        &mut self,
+        source: &broadcast::Sender<TransactionSoftConfirmation>,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> JoinHandle<anyhow::Result<()>> {
+        let (sender, _) = broadcast::channel(self.max_buffered_notifications);
+        self.soft_confirmations = Some(sender.clone());
+        let receiver = source.subscribe();
+        tokio::spawn(Self::forward_soft_confirmations(
+            receiver,
+            sender,
+            stop_receiver,
+        ))
+    }
+
+    async fn forward_soft_confirmations(
+        mut receiver: broadcast::Receiver<TransactionSoftConfirmation>,
+        sender: broadcast::Sender<Vec<PubSubResult>>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        const SUB_TYPE: SubscriptionType = SubscriptionType::SoftConfirmations;
+
+        loop {
+            tokio::select! {
+                confirmation = receiver.recv() => match confirmation {
+                    Ok(confirmation) => {
+                        // Errors only on 0 receivers, which is fine.
+                        sender.send(vec![PubSubResult::SoftConfirmation(confirmation)]).ok();
+                        PUB_SUB_METRICS.broadcast_channel_len[&SUB_TYPE].set(sender.len());
+                    }
+                    Err(broadcast::error::RecvError::Lagged(message_count)) => {
+                        PUB_SUB_METRICS.skipped_broadcast_messages[&SUB_TYPE].observe(message_count);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        tracing::info!("State keeper stopped sending soft confirmations");
+                        break;
+                    }
+                },
+                _ = stop_receiver.changed() => break,
+            }
+        }
+        tracing::info!("Stop signal received, soft confirmations forwarder is shutting down");
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
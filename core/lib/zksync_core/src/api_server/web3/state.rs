@@ -45,6 +45,7 @@ pub struct InternalApiConfig {
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
+    pub trace_limits: api::CallTraceLimits,
 }
 
 impl InternalApiConfig {
@@ -70,6 +71,10 @@ impl InternalApiConfig {
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
+            trace_limits: api::CallTraceLimits {
+                max_depth: web3_config.trace_max_depth(),
+                max_calls: web3_config.trace_max_calls(),
+            },
         }
     }
 }
@@ -213,7 +218,7 @@ impl<E> RpcState<E> {
         let block_id = api::BlockId::Number(block_number);
         let mut conn = self
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap();
         Ok(conn
@@ -241,7 +246,7 @@ impl<E> RpcState<E> {
             (Some(block_hash), None, None) => {
                 let block_number = self
                     .connection_pool
-                    .access_storage_tagged("api")
+                    .access_read_only_storage_tagged("api")
                     .await
                     .unwrap()
                     .blocks_web3_dal()
@@ -269,7 +274,7 @@ impl<E> RpcState<E> {
 
         let pending_block = self
             .connection_pool
-            .access_storage_tagged("api")
+            .access_read_only_storage_tagged("api")
             .await
             .unwrap()
             .blocks_web3_dal()
@@ -298,7 +303,7 @@ impl<E> RpcState<E> {
             let block_id = api::BlockId::Number(api::BlockNumber::Latest);
             let mut connection = self
                 .connection_pool
-                .access_storage_tagged("api")
+                .access_read_only_storage_tagged("api")
                 .await
                 .unwrap();
             let block_number = resolve_block(&mut connection, block_id, METHOD_NAME).await?;
@@ -9,22 +9,38 @@ use zksync_config::configs::{
     ContractsConfig,
 };
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_dal::{
+    event_indexes_dal::EventIndexEntry, transactions_dal::L2TxSubmissionResult, ConnectionPool,
+};
 use zksync_health_check::CheckHealth;
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
-    block::MiniblockHeader, fee::TransactionExecutionMetrics, tx::IncludedTxLocation, Address,
-    L1BatchNumber, ProtocolVersionId, VmEvent, H256, U64,
+    api::sealing::L1BatchSealStatus,
+    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    ethabi,
+    fee::TransactionExecutionMetrics,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+    tx::{
+        tx_execution_info::{DeduplicatedWritesMetrics, TxExecutionStatus},
+        ExecutionMetrics, IncludedTxLocation, TransactionExecutionResult,
+    },
+    web3::signing::keccak256,
+    writes::compression::compress_with_best_strategy,
+    AccountTreeId, Address, L1BatchNumber, ProtocolVersionId, StorageKey, StorageLog, VmEvent,
+    H256, L1_MESSENGER_ADDRESS, U256, U64,
 };
+use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
     jsonrpsee::{core::ClientError as RpcError, http_client::HttpClient, types::error::ErrorCode},
-    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    namespaces::{
+        DebugNamespaceClient, EthNamespaceClient, UnstableNamespaceClient, ZksNamespaceClient,
+    },
     types::FilterChanges,
 };
 
 use super::{metrics::ApiTransportLabel, *};
 use crate::{
-    api_server::tx_sender::TxSenderConfig,
+    api_server::tx_sender::{TxFilters, TxSenderConfig},
     genesis::{ensure_genesis_state, GenesisParams},
     state_keeper::tests::create_l2_transaction,
 };
@@ -87,6 +103,8 @@ pub(crate) async fn spawn_http_server(
         pool,
         stop_receiver,
         None,
+        None,
+        None,
     )
     .await
     .0
@@ -97,6 +115,8 @@ async fn spawn_ws_server(
     pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    max_subscriptions_per_connection: Option<u32>,
+    soft_confirmations: Option<broadcast::Sender<api::TransactionSoftConfirmation>>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     spawn_server(
         ApiTransportLabel::Ws,
@@ -104,6 +124,8 @@ async fn spawn_ws_server(
         pool,
         stop_receiver,
         websocket_requests_per_minute_limit,
+        max_subscriptions_per_connection,
+        soft_confirmations,
     )
     .await
 }
@@ -114,6 +136,8 @@ async fn spawn_server(
     pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    max_subscriptions_per_connection: Option<u32>,
+    soft_confirmations: Option<broadcast::Sender<api::TransactionSoftConfirmation>>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let contracts_config = ContractsConfig::for_tests();
     let web3_config = Web3JsonRpcConfig::for_tests();
@@ -131,10 +155,15 @@ async fn spawn_server(
         pool.clone(),
         pool.clone(),
         gas_adjuster,
+        None,
+        TxFilters::default(),
+        None,
         storage_caches,
     )
     .await;
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
+    let mut namespaces = Namespace::DEFAULT.to_vec();
+    namespaces.extend([Namespace::Debug, Namespace::Unstable]);
 
     let server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
@@ -146,6 +175,13 @@ async fn spawn_server(
                 builder = builder
                     .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
             }
+            if let Some(max_subscriptions_per_connection) = max_subscriptions_per_connection {
+                builder =
+                    builder.with_max_subscriptions_per_connection(max_subscriptions_per_connection);
+            }
+            if let Some(soft_confirmations) = soft_confirmations {
+                builder = builder.with_soft_confirmations(soft_confirmations);
+            }
             builder
         }
     };
@@ -154,7 +190,7 @@ async fn spawn_server(
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender, vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
-        .enable_api_namespaces(Namespace::DEFAULT.to_vec())
+        .enable_api_namespaces(namespaces)
         .build(stop_receiver)
         .await
         .expect("Failed spawning JSON-RPC server");
@@ -203,7 +239,7 @@ fn assert_logs_match(actual_logs: &[api::Log], expected_logs: &[&VmEvent]) {
     }
 }
 
-fn create_miniblock(number: u32) -> MiniblockHeader {
+pub(crate) fn create_miniblock(number: u32) -> MiniblockHeader {
     MiniblockHeader {
         number: MiniblockNumber(number),
         timestamp: number.into(),
@@ -213,6 +249,7 @@ fn create_miniblock(number: u32) -> MiniblockHeader {
         base_fee_per_gas: 100,
         l1_gas_price: 100,
         l2_fair_gas_price: 100,
+        gas_per_pubdata: None,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 1,
@@ -516,3 +553,593 @@ impl HttpTest for LogFilterChangesWithBlockBoundaries {
 async fn log_filter_changes_with_block_boundaries() {
     test_http_server(LogFilterChangesWithBlockBoundaries).await;
 }
+
+#[derive(Debug)]
+struct FeeParamsAt;
+
+#[async_trait]
+impl HttpTest for FeeParamsAt {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let miniblock = MiniblockHeader {
+            gas_per_pubdata: Some(42),
+            ..create_miniblock(1)
+        };
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        // Miniblock without persisted gas per pubdata; it should be derived from gas prices.
+        let miniblock = MiniblockHeader {
+            l1_gas_price: 1_000,
+            ..create_miniblock(2)
+        };
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        drop(storage);
+
+        let fee_params = client
+            .get_fee_params_at(MiniblockNumber(1))
+            .await?
+            .context("no fee params for miniblock #1")?;
+        assert_eq!(fee_params.number, MiniblockNumber(1));
+        assert_eq!(fee_params.l1_gas_price, 100);
+        assert_eq!(fee_params.fair_l2_gas_price, 100);
+        assert_eq!(fee_params.base_fee_per_gas, 100);
+        assert_eq!(fee_params.fair_pubdata_price, 1_700);
+        assert_eq!(fee_params.gas_per_pubdata, 42);
+
+        let fee_params = client
+            .get_fee_params_at(MiniblockNumber(2))
+            .await?
+            .context("no fee params for miniblock #2")?;
+        assert_eq!(fee_params.l1_gas_price, 1_000);
+        assert_eq!(fee_params.fair_pubdata_price, 17_000);
+        assert_eq!(fee_params.gas_per_pubdata, 170);
+
+        let fee_params = client.get_fee_params_at(MiniblockNumber(3)).await?;
+        assert_eq!(fee_params, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_fee_params_at_miniblock() {
+    test_http_server(FeeParamsAt).await;
+}
+
+#[derive(Debug)]
+struct L2ToL1Messages;
+
+#[async_trait]
+impl HttpTest for L2ToL1Messages {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let tx = create_l2_transaction(1, 2);
+        let tx_hash = tx.hash();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        let miniblock = MiniblockHeader {
+            l2_tx_count: 1,
+            ..create_miniblock(1)
+        };
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        let tx_result = TransactionExecutionResult {
+            hash: tx_hash,
+            transaction: tx.into(),
+            execution_info: ExecutionMetrics::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces: vec![],
+            revert_reason: None,
+        };
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+
+        let sender = Address::repeat_byte(0x11);
+        let message = b"hello, L1".to_vec();
+        let message_hash = H256(keccak256(&message));
+        let tx_location = IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(2),
+        };
+        let message_event_signature = ethabi::long_signature(
+            "L1MessageSent",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::FixedBytes(32),
+                ethabi::ParamType::Bytes,
+            ],
+        );
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: L1_MESSENGER_ADDRESS,
+            indexed_topics: vec![
+                message_event_signature,
+                address_to_h256(&sender),
+                message_hash,
+            ],
+            value: ethabi::encode(&[ethabi::Token::Bytes(message.clone())]),
+        };
+        storage
+            .events_dal()
+            .save_events(MiniblockNumber(1), &[(tx_location, vec![&event])])
+            .await;
+        let log = UserL2ToL1Log(L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: 0,
+            sender: L1_MESSENGER_ADDRESS,
+            key: address_to_h256(&sender),
+            value: message_hash,
+        });
+        storage
+            .events_dal()
+            .save_user_l2_to_l1_logs(MiniblockNumber(1), &[(tx_location, vec![&log])])
+            .await;
+        drop(storage);
+
+        let messages = client
+            .get_l2_to_l1_messages(tx_hash)
+            .await?
+            .context("no messages for executed transaction")?;
+        assert_eq!(messages.len(), 1);
+        let sent_message = &messages[0];
+        assert_eq!(sent_message.sender, sender);
+        assert_eq!(sent_message.message.0, message);
+        assert_eq!(sent_message.message_hash, message_hash);
+        assert_eq!(sent_message.log_index_in_tx, 0.into());
+        // The miniblock is not included into an L1 batch yet.
+        assert_eq!(sent_message.l1_batch_number, None);
+        assert_eq!(sent_message.index_in_l1_batch, None);
+        assert_eq!(
+            sent_message.proof_status,
+            api::L2ToL1MessageProofStatus::Pending
+        );
+
+        let receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("no receipt for executed transaction")?;
+        assert_eq!(receipt.l2_to_l1_messages, messages);
+
+        let messages = client
+            .get_l2_to_l1_messages(H256::repeat_byte(0xff))
+            .await?;
+        assert_eq!(messages, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l2_to_l1_messages() {
+    test_http_server(L2ToL1Messages).await;
+}
+
+#[derive(Debug)]
+struct RawReceipts;
+
+#[async_trait]
+impl HttpTest for RawReceipts {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let txs = [create_l2_transaction(1, 2), create_l2_transaction(1, 2)];
+        for tx in &txs {
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let miniblock = MiniblockHeader {
+            l2_tx_count: txs.len() as u16,
+            ..create_miniblock(1)
+        };
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        // The second transaction is failed, so that receipts have different statuses.
+        let statuses = [TxExecutionStatus::Success, TxExecutionStatus::Failure];
+        let tx_results: Vec<_> = txs
+            .iter()
+            .zip(statuses)
+            .map(|(tx, execution_status)| TransactionExecutionResult {
+                hash: tx.hash(),
+                transaction: tx.clone().into(),
+                execution_info: ExecutionMetrics::default(),
+                execution_status,
+                refunded_gas: 0,
+                operator_suggested_refund: 0,
+                compressed_bytecodes: vec![],
+                call_traces: vec![],
+                revert_reason: None,
+            })
+            .collect();
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, 1.into())
+            .await;
+
+        let events: Vec<_> = (0..3)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(1), i),
+                address: Address::repeat_byte(23),
+                indexed_topics: vec![H256::repeat_byte(i as u8)],
+                value: i.to_le_bytes().to_vec(),
+            })
+            .collect();
+        let tx_events: Vec<(_, Vec<&VmEvent>)> = txs
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| {
+                let tx_location = IncludedTxLocation {
+                    tx_hash: tx.hash(),
+                    tx_index_in_miniblock: i as u32,
+                    tx_initiator_address: tx.initiator_account(),
+                };
+                // The first transaction emits a single event, and the second one emits the rest.
+                let tx_events = if i == 0 { &events[..1] } else { &events[1..] };
+                (tx_location, tx_events.iter().collect())
+            })
+            .collect();
+        storage
+            .events_dal()
+            .save_events(MiniblockNumber(1), &tx_events)
+            .await;
+        drop(storage);
+
+        let block_id = |number: u32| api::BlockId::Number(api::BlockNumber::Number(number.into()));
+        let raw_receipts = client
+            .get_raw_receipts(block_id(1))
+            .await?
+            .context("no receipts for miniblock #1")?;
+        assert_eq!(raw_receipts.len(), txs.len());
+        for (tx, raw_receipt) in txs.iter().zip(&raw_receipts) {
+            let receipt = client
+                .get_transaction_receipt(tx.hash())
+                .await?
+                .context("no receipt for executed transaction")?;
+            assert_eq!(*raw_receipt, receipt.raw_encoding());
+        }
+        assert_ne!(raw_receipts[0], raw_receipts[1]);
+
+        let raw_receipts = client.get_raw_receipts(block_id(0)).await?;
+        assert_eq!(raw_receipts, Some(vec![]));
+        let raw_receipts = client.get_raw_receipts(block_id(100)).await?;
+        assert_eq!(raw_receipts, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_raw_receipts() {
+    test_http_server(RawReceipts).await;
+}
+
+#[derive(Debug)]
+struct IndexedLogs;
+
+#[async_trait]
+impl HttpTest for IndexedLogs {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        const INDEX_NAME: &str = "test_index";
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        let entries = [EventIndexEntry {
+            key: b"key".to_vec(),
+            miniblock_number: MiniblockNumber(1),
+            event_index_in_block: 3,
+        }];
+        storage
+            .event_indexes_dal()
+            .register_index(INDEX_NAME)
+            .await?;
+        storage
+            .event_indexes_dal()
+            .save_index_entries(INDEX_NAME, &entries, MiniblockNumber(1))
+            .await?;
+
+        let logs = client
+            .get_indexed_logs(
+                INDEX_NAME.to_owned(),
+                b"key".to_vec().into(),
+                MiniblockNumber(0),
+                None,
+            )
+            .await?;
+        assert_logs_match(&logs, &[&events[3]]);
+        assert_eq!(logs[0].block_number, Some(1.into()));
+
+        let logs = client
+            .get_indexed_logs(
+                INDEX_NAME.to_owned(),
+                b"key".to_vec().into(),
+                MiniblockNumber(2),
+                None,
+            )
+            .await?;
+        assert!(logs.is_empty(), "{logs:?}");
+        let logs = client
+            .get_indexed_logs(
+                INDEX_NAME.to_owned(),
+                b"key".to_vec().into(),
+                MiniblockNumber(0),
+                Some(0),
+            )
+            .await?;
+        assert!(logs.is_empty(), "{logs:?}");
+        let logs = client
+            .get_indexed_logs(
+                "unknown_index".to_owned(),
+                b"key".to_vec().into(),
+                MiniblockNumber(0),
+                None,
+            )
+            .await?;
+        assert!(logs.is_empty(), "{logs:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_indexed_logs() {
+    test_http_server(IndexedLogs).await;
+}
+
+#[derive(Debug)]
+struct SealCriteriaStatus;
+
+impl SealCriteriaStatus {
+    fn pubdata_usage(status: &L1BatchSealStatus) -> u64 {
+        let criterion = status
+            .criteria
+            .iter()
+            .find(|criterion| criterion.criterion == "pub_data_size")
+            .expect("no pubdata criterion");
+        criterion.used
+    }
+}
+
+#[async_trait]
+impl HttpTest for SealCriteriaStatus {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let status = client
+            .get_seal_criteria_status()
+            .await?
+            .context("no seal criteria status")?;
+        assert_eq!(status.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(status.tx_count, 0);
+        let initial_pubdata_usage = Self::pubdata_usage(&status);
+
+        // Store a pending miniblock with a new storage slot repeatedly written to.
+        let mut storage = pool.access_storage().await?;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(1))
+            .await?;
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let logs = [(
+            H256::repeat_byte(1),
+            vec![
+                StorageLog::new_write_log(key, H256::repeat_byte(2)),
+                StorageLog::new_write_log(key, H256::repeat_byte(1)),
+            ],
+        )];
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &logs)
+            .await;
+
+        let status = client
+            .get_seal_criteria_status()
+            .await?
+            .context("no seal criteria status")?;
+        assert_eq!(status.l1_batch_number, L1BatchNumber(1));
+        let expected_writes = DeduplicatedWritesMetrics {
+            initial_storage_writes: 1,
+            repeated_storage_writes: 0,
+            total_updated_values_size: compress_with_best_strategy(
+                U256::zero(),
+                h256_to_u256(H256::repeat_byte(1)),
+            )
+            .len(),
+        };
+        assert_eq!(
+            Self::pubdata_usage(&status) - initial_pubdata_usage,
+            expected_writes.size(ProtocolVersionId::latest()) as u64
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_seal_criteria_status() {
+    test_http_server(SealCriteriaStatus).await;
+}
+
+#[derive(Debug)]
+struct CompactedStorageState;
+
+#[async_trait]
+impl HttpTest for CompactedStorageState {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        let key = StorageKey::new(AccountTreeId::new(address), H256::zero());
+        let mut storage = pool.access_storage().await?;
+        for number in [1, 2] {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock(number))
+                .await?;
+            let value = H256::from_low_u64_be(number.into());
+            let logs = [(H256::zero(), vec![StorageLog::new_write_log(key, value)])];
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &logs)
+                .await;
+        }
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&l1_batch_header, &[], BlockGasCount::default(), &[], &[])
+            .await?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await?;
+        storage
+            .pruning_dal()
+            .compact_storage_logs(L1BatchNumber(1))
+            .await?;
+        drop(storage);
+
+        let block_id = |number: u32| {
+            Some(api::BlockIdVariant::BlockNumber(api::BlockNumber::Number(
+                number.into(),
+            )))
+        };
+        let value = client
+            .get_storage_at(address, U256::zero(), block_id(2))
+            .await?;
+        assert_eq!(value, H256::from_low_u64_be(2));
+
+        let err = client
+            .get_storage_at(address, U256::zero(), block_id(1))
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+                && err.message().contains("pruned")
+        );
+        let err = client.get_balance(address, block_id(1)).await.unwrap_err();
+        assert_matches!(err, RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_compacted_storage_state() {
+    test_http_server(CompactedStorageState).await;
+}
+
+#[derive(Debug)]
+struct PrunedData;
+
+impl PrunedData {
+    async fn insert_l1_batch(
+        storage: &mut StorageProcessor<'_>,
+        number: u32,
+    ) -> anyhow::Result<()> {
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&l1_batch_header, &[], BlockGasCount::default(), &[], &[])
+            .await?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HttpTest for PrunedData {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        let key = StorageKey::new(AccountTreeId::new(address), H256::zero());
+        let mut storage = pool.access_storage().await?;
+        // L1 batch #1 contains miniblocks #1 and #2, and L1 batch #2 contains miniblock #3.
+        for number in [1, 2, 3] {
+            store_events(&mut storage, number, 0).await?;
+            let value = H256::from_low_u64_be(number.into());
+            let logs = [(H256::zero(), vec![StorageLog::new_write_log(key, value)])];
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &logs)
+                .await;
+            if number != 1 {
+                Self::insert_l1_batch(&mut storage, number - 1).await?;
+            }
+        }
+        storage
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(1))
+            .await?;
+        drop(storage);
+
+        let assert_pruned = |err: RpcError| {
+            assert_matches!(
+                err,
+                RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+                    && err.message().contains("pruned")
+            );
+        };
+        let block_id = |number: u32| {
+            Some(api::BlockIdVariant::BlockNumber(api::BlockNumber::Number(
+                number.into(),
+            )))
+        };
+
+        // The storage state is retained as of the last pruned miniblock, but not before it.
+        let value = client
+            .get_storage_at(address, U256::zero(), block_id(2))
+            .await?;
+        assert_eq!(value, H256::from_low_u64_be(2));
+        let err = client
+            .get_storage_at(address, U256::zero(), block_id(1))
+            .await
+            .unwrap_err();
+        assert_pruned(err);
+
+        // Transactions and events are removed for all pruned miniblocks.
+        for number in [1, 2] {
+            let block_number = api::BlockNumber::Number(number.into());
+            let err = client
+                .get_block_by_number(block_number, false)
+                .await
+                .unwrap_err();
+            assert_pruned(err);
+            let err = client
+                .get_block_transaction_count_by_number(block_number)
+                .await
+                .unwrap_err();
+            assert_pruned(err);
+        }
+        let block = client
+            .get_block_by_number(api::BlockNumber::Number(3.into()), false)
+            .await?
+            .expect("no block #3");
+        assert_eq!(block.number, 3.into());
+
+        let filter = |from_block: u32| Filter {
+            from_block: Some(api::BlockNumber::Number(from_block.into())),
+            to_block: Some(api::BlockNumber::Number(3.into())),
+            ..Filter::default()
+        };
+        let err = client.get_logs(filter(1)).await.unwrap_err();
+        assert_pruned(err);
+        let logs = client.get_logs(filter(3)).await?;
+        assert_eq!(logs.len(), 4);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_pruned_data() {
+    test_http_server(PrunedData).await;
+}
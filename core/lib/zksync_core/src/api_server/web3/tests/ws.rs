@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use jsonrpsee::core::{client::ClientT, params::BatchRequestBuilder, ClientError};
 use reqwest::StatusCode;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
 use zksync_types::{api, Address, L1BatchNumber, H256, U64};
@@ -14,7 +14,7 @@ use zksync_web3_decl::{
         ws_client::{WsClient, WsClientBuilder},
     },
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
-    types::{BlockHeader, PubSubFilter},
+    types::{BlockHeader, PubSubFilter, ValueOrArray},
 };
 
 use super::*;
@@ -76,6 +76,14 @@ trait WsTest {
     fn websocket_requests_per_minute_limit(&self) -> Option<NonZeroU32> {
         None
     }
+
+    fn max_subscriptions_per_connection(&self) -> Option<u32> {
+        None
+    }
+
+    fn soft_confirmations(&self) -> Option<broadcast::Sender<api::TransactionSoftConfirmation>> {
+        None
+    }
 }
 
 async fn test_ws_server(test: impl WsTest) {
@@ -99,6 +107,8 @@ async fn test_ws_server(test: impl WsTest) {
         pool.clone(),
         stop_receiver,
         test.websocket_requests_per_minute_limit(),
+        test.max_subscriptions_per_connection(),
+        test.soft_confirmations(),
     )
     .await;
     server_handles.wait_until_ready().await;
@@ -555,3 +565,198 @@ impl WsTest for BatchGetsRateLimited {
 async fn batch_rate_limiting() {
     test_ws_server(BatchGetsRateLimited).await;
 }
+
+#[derive(Debug)]
+struct SubscriptionsPerConnectionLimit;
+
+#[async_trait]
+impl WsTest for SubscriptionsPerConnectionLimit {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let mut subscriptions = Vec::with_capacity(2);
+        for _ in 0..2 {
+            let params = rpc_params!["newHeads"];
+            let subscription = client
+                .subscribe::<BlockHeader, _>("eth_subscribe", params, "eth_unsubscribe")
+                .await?;
+            wait_for_subscription(&mut pub_sub_events, SubscriptionType::Blocks).await;
+            subscriptions.push(subscription);
+        }
+
+        let params = rpc_params!["newHeads"];
+        let err = client
+            .subscribe::<BlockHeader, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+        Ok(())
+    }
+
+    fn max_subscriptions_per_connection(&self) -> Option<u32> {
+        Some(2)
+    }
+}
+
+#[tokio::test]
+async fn subscriptions_per_connection_limit() {
+    test_ws_server(SubscriptionsPerConnectionLimit).await;
+}
+
+#[derive(Debug)]
+struct SoftConfirmationsSubscription {
+    sender: broadcast::Sender<api::TransactionSoftConfirmation>,
+}
+
+impl SoftConfirmationsSubscription {
+    fn new() -> Self {
+        Self {
+            sender: broadcast::channel(16).0,
+        }
+    }
+}
+
+#[async_trait]
+impl WsTest for SoftConfirmationsSubscription {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let params = rpc_params!["softConfirmations"];
+        let mut subscription = client
+            .subscribe::<api::TransactionSoftConfirmation, _>(
+                "eth_subscribe",
+                params,
+                "eth_unsubscribe",
+            )
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::SoftConfirmations).await;
+
+        let confirmation = api::TransactionSoftConfirmation {
+            transaction_hash: H256::repeat_byte(1),
+            transaction_index: 0.into(),
+            block_number: 1.into(),
+            l1_batch_number: 1.into(),
+            from: Address::repeat_byte(2),
+            to: Some(Address::repeat_byte(3)),
+            status: 1.into(),
+            gas_used: 21_000.into(),
+            logs: vec![],
+            revert_reason: None,
+        };
+        self.sender.send(confirmation.clone())?;
+
+        let received_confirmation = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for soft confirmation")?
+            .context("Soft confirmations subscription terminated")??;
+        assert_eq!(received_confirmation, confirmation);
+        subscription.unsubscribe().await?;
+        Ok(())
+    }
+
+    fn soft_confirmations(&self) -> Option<broadcast::Sender<api::TransactionSoftConfirmation>> {
+        Some(self.sender.clone())
+    }
+}
+
+#[tokio::test]
+async fn soft_confirmations_subscription() {
+    test_ws_server(SoftConfirmationsSubscription::new()).await;
+}
+
+#[derive(Debug)]
+struct SoftConfirmationsAreDisabledByDefault;
+
+#[async_trait]
+impl WsTest for SoftConfirmationsAreDisabledByDefault {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool,
+        _pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let params = rpc_params!["softConfirmations"];
+        let err = client
+            .subscribe::<api::TransactionSoftConfirmation, _>(
+                "eth_subscribe",
+                params,
+                "eth_unsubscribe",
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn soft_confirmations_are_disabled_by_default() {
+    test_ws_server(SoftConfirmationsAreDisabledByDefault).await;
+}
+
+#[derive(Debug)]
+struct AddressActivitySubscription;
+
+#[async_trait]
+impl WsTest for AddressActivitySubscription {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        // Subscriptions without watched addresses are rejected.
+        let params = rpc_params!["addressActivity"];
+        let err = client
+            .subscribe::<api::AddressActivity, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+
+        wait_for_notifier(&mut pub_sub_events, SubscriptionType::AddressActivity).await;
+        let watched_address = Address::repeat_byte(23);
+        let filter = PubSubFilter {
+            address: Some(ValueOrArray(vec![
+                watched_address,
+                Address::repeat_byte(0xff),
+            ])),
+            topics: None,
+        };
+        let params = rpc_params!["addressActivity", filter];
+        let mut subscription = client
+            .subscribe::<api::AddressActivity, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::AddressActivity).await;
+
+        let mut storage = pool.access_storage().await?;
+        let (tx_location, _) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+
+        let activity = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for address activity")?
+            .context("Address activity subscription terminated")??;
+        assert_eq!(activity.address, watched_address);
+        assert_eq!(activity.block_number, 1.into());
+        assert_eq!(activity.transaction_hash, tx_location.tx_hash);
+        assert_eq!(activity.kinds, [api::AddressActivityKind::EventEmitter]);
+
+        wait_for_notifier(&mut pub_sub_events, SubscriptionType::AddressActivity).await;
+        // Activity of other addresses (e.g., `Address::zero()` emitting events) must not be sent to the subscriber.
+        tokio::time::timeout(POLL_INTERVAL, subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn address_activity_subscription() {
+    test_ws_server(AddressActivitySubscription).await;
+}
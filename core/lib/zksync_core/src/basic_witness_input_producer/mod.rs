@@ -27,6 +27,8 @@ pub struct BasicWitnessInputProducer {
     connection_pool: ConnectionPool,
     l2_chain_id: L2ChainId,
     object_store: Arc<dyn ObjectStore>,
+    /// Whether to queue TEE verifier input producer jobs for processed L1 batches.
+    create_tee_verifier_jobs: bool,
 }
 
 impl BasicWitnessInputProducer {
@@ -34,11 +36,13 @@ impl BasicWitnessInputProducer {
         connection_pool: ConnectionPool,
         store_factory: &ObjectStoreFactory,
         l2_chain_id: L2ChainId,
+        create_tee_verifier_jobs: bool,
     ) -> anyhow::Result<Self> {
         Ok(BasicWitnessInputProducer {
             connection_pool,
             object_store: store_factory.create_store().await.into(),
             l2_chain_id,
+            create_tee_verifier_jobs,
         })
     }
 
@@ -190,6 +194,13 @@ impl JobProcessor for BasicWitnessInputProducer {
             .mark_job_as_successful(job_id, started_at, &object_path)
             .await
             .context("failed to mark job as successful for BasicWitnessInputProducer")?;
+        if self.create_tee_verifier_jobs {
+            transaction
+                .tee_verifier_input_producer_dal()
+                .create_tee_verifier_input_producer_job(job_id)
+                .await
+                .context("failed to create TEE verifier input producer job")?;
+        }
         transaction
             .commit()
             .await
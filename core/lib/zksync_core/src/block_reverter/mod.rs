@@ -1,5 +1,6 @@
-use std::{path::Path, time::Duration};
+use std::{ops::RangeInclusive, path::Path, time::Duration};
 
+use anyhow::Context as _;
 use bitflags::bitflags;
 use serde::Serialize;
 use tokio::time::sleep;
@@ -19,9 +20,12 @@ use zksync_types::{
         types::{BlockId, BlockNumber},
         Web3,
     },
-    L1BatchNumber, PackedEthSignature, H160, H256, U256,
+    L1BatchNumber, MiniblockNumber, PackedEthSignature, H160, H256, U256,
 };
 
+#[cfg(test)]
+mod tests;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -146,6 +150,100 @@ impl BlockReverter {
         }
     }
 
+    /// Reports the data that would be removed by [`Self::rollback_db()`] with the same arguments, without
+    /// modifying any state. The Ethereum contract state and the object store are not affected by DB rollbacks,
+    /// so they are not included in the report. RocksDB instances are opened in the read-only mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `last_l1_batch_to_keep` doesn't exist, or if any of the DBs cannot be accessed.
+    pub async fn dry_run(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+    ) -> anyhow::Result<RollbackReport> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await
+            .context("failed getting miniblock range for L1 batch")?
+            .with_context(|| format!("L1 batch #{last_l1_batch_to_keep} doesn't exist"))?;
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("failed getting last executed L1 batch")?;
+        let reverts_executed_l1_batches =
+            last_executed_l1_batch.map_or(false, |number| number > last_l1_batch_to_keep);
+
+        let postgres = if flags.contains(BlockReverterFlags::POSTGRES) {
+            let sealed_l1_batch = storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await
+                .context("failed getting sealed L1 batch")?;
+            let sealed_miniblock = storage
+                .blocks_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .context("failed getting sealed miniblock")?;
+            Some(PostgresRollbackReport {
+                l1_batches: (sealed_l1_batch > last_l1_batch_to_keep)
+                    .then(|| (last_l1_batch_to_keep + 1)..=sealed_l1_batch),
+                miniblocks: (sealed_miniblock > last_miniblock_to_keep)
+                    .then(|| (last_miniblock_to_keep + 1)..=sealed_miniblock),
+            })
+        } else {
+            None
+        };
+        drop(storage);
+
+        let tree = if flags.contains(BlockReverterFlags::TREE) {
+            let merkle_tree_path = Path::new(&self.merkle_tree_path);
+            Some(if merkle_tree_path.exists() {
+                let db = RocksDB::open_read_only(merkle_tree_path).with_context(|| {
+                    format!(
+                        "failed opening Merkle tree at `{}`",
+                        merkle_tree_path.display()
+                    )
+                })?;
+                let tree = ZkSyncTree::new_lightweight(db.into());
+                RocksdbRollbackReport::new(last_l1_batch_to_keep, tree.next_l1_batch_number())
+            } else {
+                RocksdbRollbackReport::Missing
+            })
+        } else {
+            None
+        };
+
+        let state_keeper_cache = if flags.contains(BlockReverterFlags::SK_CACHE) {
+            let sk_cache_path = Path::new(&self.state_keeper_cache_path);
+            Some(if sk_cache_path.exists() {
+                let sk_cache =
+                    RocksdbStorage::open_read_only(sk_cache_path).with_context(|| {
+                        format!(
+                            "failed opening state keeper cache at `{}`",
+                            sk_cache_path.display()
+                        )
+                    })?;
+                RocksdbRollbackReport::new(last_l1_batch_to_keep, sk_cache.l1_batch_number())
+            } else {
+                RocksdbRollbackReport::Missing
+            })
+        } else {
+            None
+        };
+
+        Ok(RollbackReport {
+            last_l1_batch_to_keep,
+            reverts_executed_l1_batches,
+            postgres,
+            tree,
+            state_keeper_cache,
+        })
+    }
+
     async fn rollback_rocks_dbs(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
@@ -232,15 +330,28 @@ impl BlockReverter {
             .expect("L1 batch should contain at least one miniblock");
 
         tracing::info!("rolling back transactions state...");
-        transaction
+        let reset_stats = transaction
             .transactions_dal()
             .reset_transactions_state(last_miniblock_to_keep)
             .await;
+        tracing::info!(
+            "reset {} priority operations; returned {} L2 transactions to mempool, \
+             dropped {} L2 transactions conflicting with priority operations",
+            reset_stats.priority_txs,
+            reset_stats.reinjected_l2_txs,
+            reset_stats.dropped_l2_txs
+        );
         tracing::info!("rolling back events...");
         transaction
             .events_dal()
             .rollback_events(last_miniblock_to_keep)
             .await;
+        tracing::info!("rolling back event indexes...");
+        transaction
+            .event_indexes_dal()
+            .rollback_indexes(last_miniblock_to_keep)
+            .await
+            .unwrap();
         tracing::info!("rolling back l2 to l1 logs...");
         transaction
             .events_dal()
@@ -439,3 +550,56 @@ pub struct SuggestedRollbackValues {
     pub nonce: u64,
     pub priority_fee: u64,
 }
+
+/// Data that would be removed by a DB rollback, as reported by [`BlockReverter::dry_run()`].
+#[derive(Debug, Serialize)]
+pub struct RollbackReport {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    /// Whether the rollback affects L1 batches already executed on L1. Such a rollback is only allowed
+    /// with [`L1ExecutedBatchesRevert::Allowed`].
+    pub reverts_executed_l1_batches: bool,
+    /// Postgres data to be removed, or `None` if Postgres is not rolled back.
+    pub postgres: Option<PostgresRollbackReport>,
+    /// Merkle tree versions to be removed, or `None` if the tree is not rolled back.
+    pub tree: Option<RocksdbRollbackReport>,
+    /// State keeper cache data to be removed, or `None` if the cache is not rolled back.
+    pub state_keeper_cache: Option<RocksdbRollbackReport>,
+}
+
+/// Postgres data to be removed by a rollback. All data associated with the removed L1 batches and miniblocks
+/// (transaction execution results, events, L2-to-L1 logs, storage logs, factory deps and created tokens)
+/// is removed as well, and the rolled back transactions are returned to the mempool.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PostgresRollbackReport {
+    /// Range of L1 batches to be removed, or `None` if there are no L1 batches after the target one.
+    pub l1_batches: Option<RangeInclusive<L1BatchNumber>>,
+    /// Range of miniblocks to be removed (including miniblocks not yet included into an L1 batch),
+    /// or `None` if there are no miniblocks after the target L1 batch.
+    pub miniblocks: Option<RangeInclusive<MiniblockNumber>>,
+}
+
+/// Data to be removed from a RocksDB instance by a rollback.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RocksdbRollbackReport {
+    /// The RocksDB instance doesn't exist.
+    Missing,
+    /// The RocksDB instance doesn't contain data after the target L1 batch.
+    UpToDate,
+    /// Data for the specified range of L1 batches will be removed.
+    RolledBack {
+        l1_batches: RangeInclusive<L1BatchNumber>,
+    },
+}
+
+impl RocksdbRollbackReport {
+    fn new(last_l1_batch_to_keep: L1BatchNumber, next_l1_batch_number: L1BatchNumber) -> Self {
+        if next_l1_batch_number > last_l1_batch_to_keep + 1 {
+            Self::RolledBack {
+                l1_batches: (last_l1_batch_to_keep + 1)..=(next_l1_batch_number - 1),
+            }
+        } else {
+            Self::UpToDate
+        }
+    }
+}
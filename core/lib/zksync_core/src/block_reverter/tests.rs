@@ -0,0 +1,204 @@
+//! Tests for the block reverter.
+
+use tempfile::TempDir;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    Address, L2ChainId, ProtocolVersionId,
+};
+
+use super::*;
+use crate::genesis::{ensure_genesis_state, GenesisParams};
+
+async fn seal_l1_batch_with_miniblock(storage: &mut StorageProcessor<'_>, number: u32) {
+    let miniblock_header = MiniblockHeader {
+        number: MiniblockNumber(number),
+        timestamp: number.into(),
+        hash: H256::from_low_u64_be(number.into()),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        base_fee_per_gas: 0,
+        l1_gas_price: 0,
+        l2_fair_gas_price: 0,
+        gas_per_pubdata: None,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(ProtocolVersionId::latest()),
+        virtual_blocks: 1,
+    };
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock_header)
+        .await
+        .unwrap();
+
+    let l1_batch_header = L1BatchHeader::new(
+        L1BatchNumber(number),
+        number.into(),
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&l1_batch_header, &[], BlockGasCount::default(), &[], &[])
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        seal_l1_batch_with_miniblock(&mut storage, number).await;
+    }
+}
+
+fn create_reverter(pool: ConnectionPool, temp_dir: &TempDir) -> BlockReverter {
+    let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_owned();
+    BlockReverter::new(
+        path("state_keeper_cache"),
+        path("tree"),
+        None,
+        pool,
+        L1ExecutedBatchesRevert::Disallowed,
+    )
+}
+
+#[tokio::test]
+async fn dry_run_errors_on_nonexistent_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let block_reverter = create_reverter(pool, &temp_dir);
+
+    let err = block_reverter
+        .dry_run(L1BatchNumber(10), BlockReverterFlags::all())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("doesn't exist"), "{err:#}");
+}
+
+#[tokio::test]
+async fn dry_run_for_postgres() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let block_reverter = create_reverter(pool.clone(), &temp_dir);
+
+    let report = block_reverter
+        .dry_run(L1BatchNumber(1), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert_eq!(report.last_l1_batch_to_keep, L1BatchNumber(1));
+    assert!(!report.reverts_executed_l1_batches);
+    assert_eq!(
+        report.postgres,
+        Some(PostgresRollbackReport {
+            l1_batches: Some(L1BatchNumber(2)..=L1BatchNumber(3)),
+            miniblocks: Some(MiniblockNumber(2)..=MiniblockNumber(3)),
+        })
+    );
+    assert_eq!(report.tree, None);
+    assert_eq!(report.state_keeper_cache, None);
+
+    let report = block_reverter
+        .dry_run(L1BatchNumber(3), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.postgres,
+        Some(PostgresRollbackReport {
+            l1_batches: None,
+            miniblocks: None,
+        })
+    );
+
+    // Check that Postgres data is not removed.
+    let mut storage = pool.access_storage().await.unwrap();
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, L1BatchNumber(3));
+    let sealed_miniblock = storage
+        .blocks_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_miniblock, MiniblockNumber(3));
+}
+
+#[tokio::test]
+async fn dry_run_for_missing_rocksdb_instances() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let block_reverter = create_reverter(pool, &temp_dir);
+
+    let report = block_reverter
+        .dry_run(
+            L1BatchNumber(1),
+            BlockReverterFlags::TREE | BlockReverterFlags::SK_CACHE,
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.postgres, None);
+    assert_eq!(report.tree, Some(RocksdbRollbackReport::Missing));
+    assert_eq!(
+        report.state_keeper_cache,
+        Some(RocksdbRollbackReport::Missing)
+    );
+    // Missing instances must not be created.
+    assert!(!temp_dir.path().join("tree").exists());
+    assert!(!temp_dir.path().join("state_keeper_cache").exists());
+}
+
+#[tokio::test]
+async fn dry_run_for_rocksdb_instances() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let block_reverter = create_reverter(pool, &temp_dir);
+
+    let tree_path = temp_dir.path().join("tree");
+    let mut tree = ZkSyncTree::new_lightweight(RocksDB::new(&tree_path).into());
+    for _ in 0..=3 {
+        tree.process_l1_batch(&[]);
+    }
+    tree.save();
+    drop(tree);
+    drop(RocksdbStorage::new(
+        &temp_dir.path().join("state_keeper_cache"),
+    ));
+
+    let report = block_reverter
+        .dry_run(
+            L1BatchNumber(1),
+            BlockReverterFlags::TREE | BlockReverterFlags::SK_CACHE,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        report.tree,
+        Some(RocksdbRollbackReport::RolledBack {
+            l1_batches: L1BatchNumber(2)..=L1BatchNumber(3),
+        })
+    );
+    assert_eq!(
+        report.state_keeper_cache,
+        Some(RocksdbRollbackReport::UpToDate)
+    );
+
+    // Check that the tree is not rolled back.
+    let tree = ZkSyncTree::new_lightweight(RocksDB::new(&tree_path).into());
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(4));
+}
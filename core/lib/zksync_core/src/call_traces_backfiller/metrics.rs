@@ -0,0 +1,24 @@
+//! Call traces backfiller metrics.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_call_traces_backfiller")]
+pub(super) struct CallTracesBackfillerMetrics {
+    /// Number of the last L1 batch processed by the backfiller.
+    pub last_processed_l1_batch: Gauge<u64>,
+    /// Number of call traces saved by the backfiller.
+    pub backfilled_call_traces: Counter,
+    /// Number of re-executed L1 batches diverging from the stored data; such batches are skipped.
+    pub diverged_l1_batches: Counter,
+    /// Number of errors processing L1 batches; L1 batches are retried after an error.
+    pub errors: Counter,
+    /// Latency of re-executing an L1 batch and saving its call traces.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub l1_batch_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<CallTracesBackfillerMetrics> = vise::Global::new();
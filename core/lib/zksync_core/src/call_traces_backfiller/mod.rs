@@ -0,0 +1,259 @@
+//! Backfilling call traces for L1 batches executed without the call tracer.
+//!
+//! If the state keeper runs with call traces disabled, `debug_traceTransaction` and `debug_traceBlockByNumber`
+//! return nothing for the executed transactions. The backfiller re-executes sealed L1 batches with the call tracer
+//! enabled, checks that the execution results match the stored ones, and saves the produced call traces,
+//! so that the debug API works for the full chain history that is still stored in Postgres.
+
+use std::{collections::HashMap, time::Instant};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::CallTracesBackfillerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{vm_trace::Call, L1BatchNumber, L2ChainId, Transaction};
+
+use self::metrics::METRICS;
+use crate::vm_replay::{
+    diff_events, diff_storage_writes, BatchReplayer, BatchSource, PostgresBatchSource,
+    ReplayTracer, ReplayedTransaction,
+};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Returns the first L1 batch that should be processed by the backfiller. Pruned and archived L1 batches
+/// are skipped since they cannot be re-executed, or their call traces are no longer stored in Postgres.
+fn first_l1_batch_to_process(
+    last_processed_l1_batch: Option<L1BatchNumber>,
+    last_pruned_l1_batch: Option<L1BatchNumber>,
+    last_archived_l1_batch: Option<L1BatchNumber>,
+    configured_first_l1_batch: Option<L1BatchNumber>,
+) -> L1BatchNumber {
+    let next = |number: Option<L1BatchNumber>| number.map_or(L1BatchNumber(0), |number| number + 1);
+    next(last_processed_l1_batch)
+        .max(next(last_pruned_l1_batch))
+        .max(next(last_archived_l1_batch))
+        .max(configured_first_l1_batch.unwrap_or(L1BatchNumber(0)))
+        // The genesis L1 batch has no transactions.
+        .max(L1BatchNumber(1))
+}
+
+/// Creates a top-level call trace for a transaction in the same way as the state keeper.
+fn high_level_call_trace(tx: &Transaction, replayed: ReplayedTransaction) -> Option<Call> {
+    let calls = replayed.call_trace.unwrap_or_default();
+    if calls.is_empty() {
+        return None;
+    }
+    let gas_limit = tx.gas_limit().as_u32();
+    Some(Call::new_high_level(
+        gas_limit,
+        gas_limit - replayed.refunded_gas,
+        tx.execute.value,
+        tx.execute.calldata.clone(),
+        vec![],
+        replayed.revert_reason,
+        calls,
+    ))
+}
+
+/// Component re-executing sealed L1 batches with the call tracer and saving call traces for batches
+/// that were executed without tracing. The component processes L1 batches sequentially and follows
+/// newly sealed batches once the history is backfilled.
+#[derive(Debug)]
+pub struct CallTracesBackfiller {
+    config: CallTracesBackfillerConfig,
+    pool: ConnectionPool,
+    source: PostgresBatchSource,
+    replayer: BatchReplayer,
+}
+
+impl CallTracesBackfiller {
+    pub fn new(
+        config: CallTracesBackfillerConfig,
+        pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            config,
+            source: PostgresBatchSource::new(pool.clone(), l2_chain_id),
+            replayer: BatchReplayer::new(pool.clone()).with_tracer(ReplayTracer::Call),
+            pool,
+        }
+    }
+
+    /// Returns the next L1 batch to process, or `None` if all sealed L1 batches are processed.
+    async fn next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("call_traces_backfiller")
+            .await?;
+        let last_processed_l1_batch = storage
+            .call_traces_backfill_dal()
+            .get_last_backfilled_l1_batch()
+            .await?;
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        let last_archived_l1_batch = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await?;
+        let sealed_l1_batch_number = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+
+        let next_l1_batch = first_l1_batch_to_process(
+            last_processed_l1_batch,
+            pruning_info.last_pruned_l1_batch,
+            last_archived_l1_batch,
+            self.config.first_l1_batch_number.map(L1BatchNumber),
+        );
+        Ok((next_l1_batch <= sealed_l1_batch_number).then_some(next_l1_batch))
+    }
+
+    /// Saves call traces for the specified L1 batch if necessary. Returns the number of saved traces,
+    /// or `None` if the re-executed batch diverges from the stored data; such batches are skipped.
+    async fn process_l1_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<usize>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("call_traces_backfiller")
+            .await?;
+        let has_call_traces = storage
+            .call_traces_backfill_dal()
+            .has_call_traces(l1_batch_number)
+            .await?;
+        if has_call_traces {
+            // The batch was executed with the call tracer enabled.
+            storage
+                .call_traces_backfill_dal()
+                .save_backfilled_call_traces(l1_batch_number, &[])
+                .await?;
+            return Ok(Some(0));
+        }
+        drop(storage);
+
+        let batch = self
+            .source
+            .load_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed loading L1 batch #{l1_batch_number}"))?;
+        let output = self
+            .replayer
+            .execute(batch.clone())
+            .await
+            .with_context(|| format!("failed re-executing L1 batch #{l1_batch_number}"))?;
+
+        // Check that the replayed batch matches the stored one, so that saved call traces are correct.
+        let stored_events = self
+            .source
+            .stored_events(&batch)
+            .await
+            .context("failed loading stored events")?;
+        let written_keys: Vec<_> = output.storage_writes.keys().copied().collect();
+        let stored_writes = self
+            .source
+            .stored_storage_writes(&batch, &written_keys)
+            .await
+            .context("failed loading stored storage writes")?;
+        let event_mismatches = diff_events(output.events, stored_events);
+        let storage_write_mismatches = diff_storage_writes(&output.storage_writes, &stored_writes);
+        if !event_mismatches.is_empty() || !storage_write_mismatches.is_empty() {
+            // Call traces produced for a diverging batch cannot be trusted. The batch is marked as processed
+            // without traces, so that it doesn't block backfilling subsequent batches.
+            tracing::error!(
+                "Re-executed L1 batch #{l1_batch_number} diverges from the stored data: {} event mismatches, \
+                 {} storage write mismatches; skipping it. Use the `vm-replay` tool to investigate",
+                event_mismatches.len(),
+                storage_write_mismatches.len()
+            );
+            METRICS.diverged_l1_batches.inc();
+            let mut storage = self
+                .pool
+                .access_storage_tagged("call_traces_backfiller")
+                .await?;
+            storage
+                .call_traces_backfill_dal()
+                .save_backfilled_call_traces(l1_batch_number, &[])
+                .await?;
+            return Ok(None);
+        }
+
+        let transactions: HashMap<_, _> = batch
+            .miniblocks
+            .iter()
+            .flat_map(|miniblock| &miniblock.txs)
+            .map(|tx| (tx.hash(), tx))
+            .collect();
+        let call_traces: Vec<_> = output
+            .transactions
+            .into_iter()
+            .filter_map(|replayed| {
+                let hash = replayed.hash;
+                let tx = transactions[&hash];
+                Some((hash, high_level_call_trace(tx, replayed)?))
+            })
+            .collect();
+
+        let mut storage = self
+            .pool
+            .access_storage_tagged("call_traces_backfiller")
+            .await?;
+        storage
+            .call_traces_backfill_dal()
+            .save_backfilled_call_traces(l1_batch_number, &call_traces)
+            .await?;
+        Ok(Some(call_traces.len()))
+    }
+
+    /// Processes the next L1 batch if there is one. Returns `false` if there are no L1 batches to process.
+    async fn process_next_l1_batch(&self) -> anyhow::Result<bool> {
+        let Some(l1_batch_number) = self.next_l1_batch().await? else {
+            return Ok(false);
+        };
+        let started_at = Instant::now();
+        let Some(call_traces_count) = self.process_l1_batch(l1_batch_number).await? else {
+            return Ok(true);
+        };
+        let latency = started_at.elapsed();
+        METRICS.l1_batch_latency.observe(latency);
+        METRICS
+            .backfilled_call_traces
+            .inc_by(call_traces_count as u64);
+        METRICS
+            .last_processed_l1_batch
+            .set(l1_batch_number.0.into());
+        tracing::info!(
+            "Processed L1 batch #{l1_batch_number} in {latency:?}; saved {call_traces_count} call traces"
+        );
+        Ok(true)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            match self.process_next_l1_batch().await {
+                // Continue without a delay; there may be more L1 batches to process.
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(err) => {
+                    // Errors are most probably transient (e.g., DB connectivity issues); the batch is retried
+                    // after the polling interval.
+                    METRICS.errors.inc();
+                    tracing::warn!("Failed backfilling call traces, will retry: {err:#}");
+                }
+            }
+
+            if tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, call traces backfiller is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests for the call traces backfiller.
+
+use std::time::Duration;
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader},
+    vm_trace::Call,
+    Address, Execute, MiniblockNumber, ProtocolVersionId, U256,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    state_keeper::tests::create_l2_transaction,
+};
+
+#[test]
+fn determining_first_l1_batch_to_process() {
+    let first = first_l1_batch_to_process(None, None, None, None);
+    assert_eq!(first, L1BatchNumber(1));
+    let first = first_l1_batch_to_process(Some(L1BatchNumber(5)), None, None, None);
+    assert_eq!(first, L1BatchNumber(6));
+    let first = first_l1_batch_to_process(
+        Some(L1BatchNumber(5)),
+        Some(L1BatchNumber(10)),
+        Some(L1BatchNumber(7)),
+        None,
+    );
+    assert_eq!(first, L1BatchNumber(11));
+    let first = first_l1_batch_to_process(
+        Some(L1BatchNumber(5)),
+        None,
+        Some(L1BatchNumber(7)),
+        Some(L1BatchNumber(100)),
+    );
+    assert_eq!(first, L1BatchNumber(100));
+}
+
+#[test]
+fn creating_high_level_call_trace() {
+    let execute = Execute {
+        contract_address: Address::repeat_byte(1),
+        calldata: vec![1, 2, 3],
+        value: U256::from(10),
+        factory_deps: None,
+    };
+    let tx = Transaction {
+        execute,
+        ..create_l2_transaction(10, 100).into()
+    };
+    let replayed = |call_trace| ReplayedTransaction {
+        hash: tx.hash(),
+        miniblock: MiniblockNumber(1),
+        status: "success".to_owned(),
+        gas_used: 0,
+        refunded_gas: 1_000,
+        revert_reason: None,
+        call_trace,
+        storage_accesses: None,
+    };
+    assert!(high_level_call_trace(&tx, replayed(None)).is_none());
+
+    let call_trace = high_level_call_trace(&tx, replayed(Some(vec![Call::default()]))).unwrap();
+    let gas_limit = tx.gas_limit().as_u32();
+    assert_eq!(call_trace.gas, gas_limit);
+    assert_eq!(call_trace.gas_used, gas_limit - 1_000);
+    assert_eq!(call_trace.input, [1, 2, 3]);
+    assert_eq!(call_trace.value, U256::from(10));
+    assert_eq!(call_trace.calls.len(), 1);
+}
+
+#[tokio::test]
+async fn no_l1_batches_to_process_after_genesis() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let config = CallTracesBackfillerConfig {
+        first_l1_batch_number: None,
+        polling_interval_ms: None,
+    };
+    let backfiller = CallTracesBackfiller::new(config, pool, L2ChainId::default());
+    assert_eq!(backfiller.next_l1_batch().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn backfiller_keeps_running_after_processing_error() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    // An L1 batch without miniblocks cannot be re-executed.
+    let header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        1,
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let config = CallTracesBackfillerConfig {
+        first_l1_batch_number: None,
+        polling_interval_ms: Some(10),
+    };
+    let backfiller = CallTracesBackfiller::new(config, pool.clone(), L2ChainId::default());
+    backfiller.process_next_l1_batch().await.unwrap_err();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let backfiller_task = tokio::spawn(backfiller.run(stop_receiver));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!backfiller_task.is_finished(), "backfiller stopped");
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let last_backfilled_l1_batch = storage
+        .call_traces_backfill_dal()
+        .get_last_backfilled_l1_batch()
+        .await
+        .unwrap();
+    assert_eq!(last_backfilled_l1_batch, None);
+
+    stop_sender.send_replace(true);
+    backfiller_task.await.unwrap().unwrap();
+}
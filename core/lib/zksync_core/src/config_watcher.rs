@@ -0,0 +1,431 @@
+//! Hot reloading of a whitelisted set of configuration parameters.
+//!
+//! Overrides are specified in a JSON file (see [`ConfigOverrides`] for the supported parameters), which is
+//! periodically reloaded by [`ConfigWatcher`]. Components subscribe to overrides and apply them on top
+//! of their static configuration. All changes are logged with the `config_audit` tracing target.
+
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_config::configs::chain::{AddressFilterMode, StateKeeperConfig};
+use zksync_system_constants::MAX_TXS_IN_BLOCK;
+
+use crate::address_filter::AddressFilter;
+
+/// Tracing target used for the audit log of changed parameters.
+pub const AUDIT_LOG_TARGET: &str = "config_audit";
+
+/// Overrides for the parameters that can be changed at runtime. If a parameter is not set, its value
+/// is taken from the static configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigOverrides {
+    /// Overrides `chain.state_keeper.fair_l2_gas_price`.
+    pub fair_l2_gas_price: Option<u64>,
+    /// Overrides `eth_sender.gas_adjuster.internal_l1_pricing_multiplier`.
+    pub internal_l1_pricing_multiplier: Option<f64>,
+    /// Overrides `eth_sender.gas_adjuster.internal_enforced_l1_gas_price`.
+    pub internal_enforced_l1_gas_price: Option<u64>,
+
+    /// Overrides `chain.state_keeper.transaction_slots`.
+    pub transaction_slots: Option<usize>,
+    /// Overrides `chain.state_keeper.max_single_tx_gas`.
+    pub max_single_tx_gas: Option<u32>,
+    /// Overrides `chain.state_keeper.reject_tx_at_geometry_percentage`.
+    pub reject_tx_at_geometry_percentage: Option<f64>,
+    /// Overrides `chain.state_keeper.reject_tx_at_eth_params_percentage`.
+    pub reject_tx_at_eth_params_percentage: Option<f64>,
+    /// Overrides `chain.state_keeper.reject_tx_at_gas_percentage`.
+    pub reject_tx_at_gas_percentage: Option<f64>,
+    /// Overrides `chain.state_keeper.close_block_at_geometry_percentage`.
+    pub close_block_at_geometry_percentage: Option<f64>,
+    /// Overrides `chain.state_keeper.close_block_at_eth_params_percentage`.
+    pub close_block_at_eth_params_percentage: Option<f64>,
+    /// Overrides `chain.state_keeper.close_block_at_gas_percentage`.
+    pub close_block_at_gas_percentage: Option<f64>,
+
+    /// Overrides `api.web3_json_rpc.transactions_per_sec_limit`.
+    pub transactions_per_sec_limit: Option<u32>,
+
+    /// Overrides `chain.state_keeper.address_filter_mode`. Has no effect if the address filter is not configured.
+    /// The filtered addresses themselves are reloaded from the address filter file.
+    pub address_filter_mode: Option<AddressFilterMode>,
+}
+
+impl ConfigOverrides {
+    /// Loads overrides from a JSON file and validates them.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path).with_context(|| {
+            format!("failed reading config overrides from `{}`", path.display())
+        })?;
+        let overrides: Self = serde_json::from_str(&contents).with_context(|| {
+            format!("failed parsing config overrides from `{}`", path.display())
+        })?;
+        overrides.validate()?;
+        Ok(overrides)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let percentages = [
+            (
+                "reject_tx_at_geometry_percentage",
+                self.reject_tx_at_geometry_percentage,
+            ),
+            (
+                "reject_tx_at_eth_params_percentage",
+                self.reject_tx_at_eth_params_percentage,
+            ),
+            (
+                "reject_tx_at_gas_percentage",
+                self.reject_tx_at_gas_percentage,
+            ),
+            (
+                "close_block_at_geometry_percentage",
+                self.close_block_at_geometry_percentage,
+            ),
+            (
+                "close_block_at_eth_params_percentage",
+                self.close_block_at_eth_params_percentage,
+            ),
+            (
+                "close_block_at_gas_percentage",
+                self.close_block_at_gas_percentage,
+            ),
+        ];
+        for (name, value) in percentages {
+            if let Some(value) = value {
+                anyhow::ensure!(
+                    value > 0.0 && value <= 1.0,
+                    "`{name}` must be in (0, 1], got {value}"
+                );
+            }
+        }
+
+        if let Some(slots) = self.transaction_slots {
+            anyhow::ensure!(
+                slots > 0 && slots <= MAX_TXS_IN_BLOCK,
+                "`transaction_slots` must be in 1..={MAX_TXS_IN_BLOCK}, got {slots}"
+            );
+        }
+        if let Some(multiplier) = self.internal_l1_pricing_multiplier {
+            anyhow::ensure!(
+                multiplier.is_finite() && multiplier > 0.0,
+                "`internal_l1_pricing_multiplier` must be positive, got {multiplier}"
+            );
+        }
+        anyhow::ensure!(
+            self.max_single_tx_gas != Some(0),
+            "`max_single_tx_gas` must be positive"
+        );
+        anyhow::ensure!(
+            self.fair_l2_gas_price != Some(0),
+            "`fair_l2_gas_price` must be positive"
+        );
+        anyhow::ensure!(
+            self.transactions_per_sec_limit != Some(0),
+            "`transactions_per_sec_limit` must be positive"
+        );
+        Ok(())
+    }
+
+    /// Applies overrides to the state keeper config. Only fee model inputs and seal criteria limits are overridden.
+    pub fn apply_to_state_keeper_config<'a>(
+        &self,
+        config: &'a StateKeeperConfig,
+    ) -> Cow<'a, StateKeeperConfig> {
+        let is_overridden = self.fair_l2_gas_price.is_some()
+            || self.transaction_slots.is_some()
+            || self.max_single_tx_gas.is_some()
+            || self.reject_tx_at_geometry_percentage.is_some()
+            || self.reject_tx_at_eth_params_percentage.is_some()
+            || self.reject_tx_at_gas_percentage.is_some()
+            || self.close_block_at_geometry_percentage.is_some()
+            || self.close_block_at_eth_params_percentage.is_some()
+            || self.close_block_at_gas_percentage.is_some();
+        if !is_overridden {
+            return Cow::Borrowed(config);
+        }
+
+        let mut config = config.clone();
+        config.fair_l2_gas_price = self.fair_l2_gas_price.unwrap_or(config.fair_l2_gas_price);
+        config.transaction_slots = self.transaction_slots.unwrap_or(config.transaction_slots);
+        config.max_single_tx_gas = self.max_single_tx_gas.unwrap_or(config.max_single_tx_gas);
+        config.reject_tx_at_geometry_percentage = self
+            .reject_tx_at_geometry_percentage
+            .unwrap_or(config.reject_tx_at_geometry_percentage);
+        config.reject_tx_at_eth_params_percentage = self
+            .reject_tx_at_eth_params_percentage
+            .unwrap_or(config.reject_tx_at_eth_params_percentage);
+        config.reject_tx_at_gas_percentage = self
+            .reject_tx_at_gas_percentage
+            .unwrap_or(config.reject_tx_at_gas_percentage);
+        config.close_block_at_geometry_percentage = self
+            .close_block_at_geometry_percentage
+            .unwrap_or(config.close_block_at_geometry_percentage);
+        config.close_block_at_eth_params_percentage = self
+            .close_block_at_eth_params_percentage
+            .unwrap_or(config.close_block_at_eth_params_percentage);
+        config.close_block_at_gas_percentage = self
+            .close_block_at_gas_percentage
+            .unwrap_or(config.close_block_at_gas_percentage);
+        Cow::Owned(config)
+    }
+
+    /// Returns `(name, old_value, new_value)` tuples for all parameters changed in `new` compared to `self`.
+    fn changes(&self, new: &Self) -> Vec<(String, serde_json::Value, serde_json::Value)> {
+        let (serde_json::Value::Object(old), serde_json::Value::Object(mut new)) = (
+            serde_json::to_value(self).expect("failed serializing overrides"),
+            serde_json::to_value(new).expect("failed serializing overrides"),
+        ) else {
+            unreachable!("overrides are serialized as an object");
+        };
+        old.into_iter()
+            .filter_map(|(name, old_value)| {
+                let new_value = new.remove(&name)?;
+                (old_value != new_value).then_some((name, old_value, new_value))
+            })
+            .collect()
+    }
+}
+
+/// Periodically reloads [`ConfigOverrides`] from a file and distributes them to subscribed components.
+/// If the reloaded file cannot be read or is invalid, the previously loaded overrides are retained.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    reload_interval: Duration,
+    overrides_sender: watch::Sender<ConfigOverrides>,
+    address_filter: Option<(AddressFilter, AddressFilterMode)>,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher loading the initial overrides from the specified file. Unlike with reloading,
+    /// an error reading the file is returned.
+    pub fn new(path: PathBuf, reload_interval: Duration) -> anyhow::Result<Self> {
+        let overrides = ConfigOverrides::load(&path)?;
+        tracing::info!(
+            target: AUDIT_LOG_TARGET,
+            "Loaded config overrides from `{}`: {overrides:?}",
+            path.display()
+        );
+        Ok(Self {
+            path,
+            reload_interval,
+            overrides_sender: watch::channel(overrides).0,
+            address_filter: None,
+        })
+    }
+
+    /// Makes the watcher change the mode of the provided address filter. `default_mode` is used
+    /// if the mode is not overridden.
+    pub fn with_address_filter(
+        mut self,
+        address_filter: AddressFilter,
+        default_mode: AddressFilterMode,
+    ) -> Self {
+        let mode = self
+            .overrides_sender
+            .borrow()
+            .address_filter_mode
+            .unwrap_or(default_mode);
+        address_filter.set_mode(mode);
+        self.address_filter = Some((address_filter, default_mode));
+        self
+    }
+
+    /// Subscribes to the overrides. The returned receiver always contains the latest loaded overrides.
+    pub fn subscribe(&self) -> watch::Receiver<ConfigOverrides> {
+        self.overrides_sender.subscribe()
+    }
+
+    fn reload(&self) -> anyhow::Result<()> {
+        let new_overrides = ConfigOverrides::load(&self.path)?;
+        let changes = self.overrides_sender.borrow().changes(&new_overrides);
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        for (name, old_value, new_value) in &changes {
+            tracing::info!(
+                target: AUDIT_LOG_TARGET,
+                parameter = name,
+                %old_value,
+                %new_value,
+                "Config parameter `{name}` is changed from {old_value} to {new_value}"
+            );
+        }
+        if let Some((address_filter, default_mode)) = &self.address_filter {
+            address_filter.set_mode(new_overrides.address_filter_mode.unwrap_or(*default_mode));
+        }
+        self.overrides_sender.send_replace(new_overrides);
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if tokio::time::timeout(self.reload_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+            if let Err(err) = self.reload() {
+                tracing::warn!(
+                    target: AUDIT_LOG_TARGET,
+                    "Failed reloading config overrides from `{}`; previous overrides are retained: {err:#}",
+                    self.path.display()
+                );
+            }
+        }
+        tracing::info!("Stop signal received, config watcher is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::Address;
+
+    use super::*;
+
+    #[test]
+    fn applying_overrides_to_state_keeper_config() {
+        let config = StateKeeperConfig::for_tests();
+        let overrides = ConfigOverrides::default();
+        assert_matches::assert_matches!(
+            overrides.apply_to_state_keeper_config(&config),
+            Cow::Borrowed(_)
+        );
+
+        let overrides = ConfigOverrides {
+            fair_l2_gas_price: Some(1_000),
+            transaction_slots: Some(10),
+            close_block_at_gas_percentage: Some(0.5),
+            transactions_per_sec_limit: Some(100),
+            ..ConfigOverrides::default()
+        };
+        let overridden_config = overrides.apply_to_state_keeper_config(&config);
+        assert_eq!(overridden_config.fair_l2_gas_price, 1_000);
+        assert_eq!(overridden_config.transaction_slots, 10);
+        assert_eq!(overridden_config.close_block_at_gas_percentage, 0.5);
+        assert_eq!(
+            overridden_config.max_single_tx_gas,
+            config.max_single_tx_gas
+        );
+    }
+
+    #[test]
+    fn validating_overrides() {
+        let overrides: ConfigOverrides = serde_json::from_str(
+            r#"{ "fair_l2_gas_price": 100000000, "address_filter_mode": "allow" }"#,
+        )
+        .unwrap();
+        overrides.validate().unwrap();
+        assert_eq!(
+            overrides.address_filter_mode,
+            Some(AddressFilterMode::Allow)
+        );
+
+        let err = serde_json::from_str::<ConfigOverrides>(r#"{ "fee_account_addr": "0x01" }"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{err}");
+
+        let overrides = ConfigOverrides {
+            reject_tx_at_gas_percentage: Some(1.5),
+            ..ConfigOverrides::default()
+        };
+        let err = overrides.validate().unwrap_err().to_string();
+        assert!(err.contains("reject_tx_at_gas_percentage"), "{err}");
+
+        let overrides = ConfigOverrides {
+            transaction_slots: Some(MAX_TXS_IN_BLOCK + 1),
+            ..ConfigOverrides::default()
+        };
+        let err = overrides.validate().unwrap_err().to_string();
+        assert!(err.contains("transaction_slots"), "{err}");
+
+        let overrides = ConfigOverrides {
+            transactions_per_sec_limit: Some(0),
+            ..ConfigOverrides::default()
+        };
+        let err = overrides.validate().unwrap_err().to_string();
+        assert!(err.contains("transactions_per_sec_limit"), "{err}");
+    }
+
+    #[test]
+    fn reloading_overrides() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("overrides.json");
+        fs::write(&path, r#"{ "fair_l2_gas_price": 100 }"#).unwrap();
+
+        let address = Address::repeat_byte(1);
+        let address_filter = AddressFilter::new(AddressFilterMode::Deny, [address]);
+        let watcher = ConfigWatcher::new(path.clone(), Duration::from_secs(1))
+            .unwrap()
+            .with_address_filter(address_filter.clone(), AddressFilterMode::Deny);
+        let mut overrides = watcher.subscribe();
+        assert_eq!(overrides.borrow().fair_l2_gas_price, Some(100));
+        assert!(address_filter
+            .rejection_reason(address, Address::zero())
+            .is_some());
+
+        fs::write(
+            &path,
+            r#"{ "fair_l2_gas_price": 200, "address_filter_mode": "allow" }"#,
+        )
+        .unwrap();
+        watcher.reload().unwrap();
+        assert!(overrides.has_changed().unwrap());
+        let new_overrides = overrides.borrow_and_update().clone();
+        assert_eq!(new_overrides.fair_l2_gas_price, Some(200));
+        assert!(address_filter
+            .rejection_reason(address, Address::zero())
+            .is_none());
+
+        // Invalid overrides must not be applied.
+        fs::write(&path, r#"{ "fair_l2_gas_price": 0 }"#).unwrap();
+        watcher.reload().unwrap_err();
+        assert!(!overrides.has_changed().unwrap());
+        assert_eq!(overrides.borrow().fair_l2_gas_price, Some(200));
+    }
+
+    #[test]
+    fn computing_changes() {
+        let old = ConfigOverrides {
+            fair_l2_gas_price: Some(100),
+            transaction_slots: Some(10),
+            ..ConfigOverrides::default()
+        };
+        let new = ConfigOverrides {
+            fair_l2_gas_price: Some(200),
+            max_single_tx_gas: Some(1_000_000),
+            transaction_slots: Some(10),
+            ..ConfigOverrides::default()
+        };
+        let mut changes = old.changes(&new);
+        changes.sort_by(|(name, ..), (other_name, ..)| name.cmp(other_name));
+        assert_eq!(
+            changes,
+            [
+                (
+                    "fair_l2_gas_price".to_owned(),
+                    serde_json::json!(100),
+                    serde_json::json!(200)
+                ),
+                (
+                    "max_single_tx_gas".to_owned(),
+                    serde_json::Value::Null,
+                    serde_json::json!(1_000_000)
+                ),
+            ]
+        );
+        assert!(new.changes(&new).is_empty());
+    }
+}
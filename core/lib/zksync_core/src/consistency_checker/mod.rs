@@ -1,13 +1,22 @@
 use std::time::Duration;
 
+use anyhow::Context as _;
 use zksync_contracts::PRE_BOOJUM_COMMIT_FUNCTION;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    web3::{error, ethabi, transports::Http, types::TransactionId, Web3},
-    L1BatchNumber,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    web3::{error, ethabi, signing::keccak256, transports::Http, types::TransactionId, Web3},
+    L1BatchNumber, H256, U256, U64,
 };
 
-use crate::metrics::{CheckerComponent, EN_METRICS};
+use crate::{
+    da_dispatcher::DataAvailabilityClient,
+    metrics::{CheckerComponent, EN_METRICS},
+    shared_bridge::shared_bridge_function,
+};
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug)]
 pub struct ConsistencyChecker {
@@ -17,12 +26,41 @@ pub struct ConsistencyChecker {
     max_batches_to_recheck: u32,
     web3: Web3<Http>,
     db: ConnectionPool,
+    // Mode in which L1 batches are expected to be committed
+    commitment_mode: L1BatchCommitmentMode,
+    // Number of L1 confirmations required for commit txs to be checked
+    l1_finality_depth: Option<u64>,
+    // Client of the external DA layer used to check pubdata blobs referenced by validium commitments
+    da_client: Option<Box<dyn DataAvailabilityClient>>,
+}
+
+/// Outcome of checking a single L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckOutcome {
+    Consistent,
+    Inconsistent,
+    /// The batch is consistent with L1, but was committed in a different commitment mode
+    /// than the configured one.
+    ModeMismatch(L1BatchCommitmentMode),
+    /// The commit tx doesn't have enough confirmations on L1 yet, or was reverted by an L1 reorg.
+    NotConfirmed,
+    /// The pubdata blob referenced by the L1 commitment is not available in the external DA layer yet.
+    DaBlobUnavailable,
+    /// The pubdata blob referenced by the L1 commitment doesn't match the locally computed pubdata,
+    /// or the commitment doesn't reference a blob at all.
+    DaBlobMismatch,
 }
 
 const SLEEP_DELAY: Duration = Duration::from_secs(5);
 
 impl ConsistencyChecker {
-    pub fn new(web3_url: &str, max_batches_to_recheck: u32, db: ConnectionPool) -> Self {
+    pub fn new(
+        web3_url: &str,
+        max_batches_to_recheck: u32,
+        db: ConnectionPool,
+        commitment_mode: L1BatchCommitmentMode,
+        l1_finality_depth: Option<u64>,
+    ) -> Self {
         let web3 = Web3::new(Http::new(web3_url).unwrap());
         let contract = zksync_contracts::zksync_contract();
         Self {
@@ -30,10 +68,143 @@ impl ConsistencyChecker {
             contract,
             max_batches_to_recheck,
             db,
+            commitment_mode,
+            l1_finality_depth,
+            da_client: None,
+        }
+    }
+
+    /// Makes the checker fetch pubdata blobs referenced by validium commitments from an external DA layer
+    /// and compare them with the locally computed pubdata.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checker isn't configured for the validium commitment mode.
+    pub fn with_da_client(mut self, da_client: Box<dyn DataAvailabilityClient>) -> Self {
+        assert_eq!(
+            self.commitment_mode,
+            L1BatchCommitmentMode::Validium,
+            "DA blobs can only be checked in the validium commitment mode"
+        );
+        self.da_client = Some(da_client);
+        self
+    }
+
+    /// Checks whether an L1 block with the specified number has the required number of confirmations.
+    async fn is_confirmed(&self, l1_block_number: Option<U64>) -> Result<bool, error::Error> {
+        let Some(l1_block_number) = l1_block_number else {
+            return Ok(false);
+        };
+        let Some(depth) = self.l1_finality_depth else {
+            return Ok(true);
+        };
+        let latest_block_number = self.web3.eth().block_number().await?;
+        Ok(l1_block_number.as_u64() + depth <= latest_block_number.as_u64())
+    }
+
+    /// Compares the batch commitment published on L1 with the locally computed one.
+    ///
+    /// In the validium mode, the pubdata field (the last one) is not compared since it is either empty
+    /// or contains inclusion data for an external DA layer, neither of which can be reproduced locally.
+    /// Pre-boojum batches don't support the validium mode and are always compared in full.
+    fn compare_commitment(
+        commitment_mode: L1BatchCommitmentMode,
+        local: &L1BatchWithMetadata,
+        l1_commitment: &ethabi::Token,
+    ) -> CheckOutcome {
+        let expected = local.l1_commit_data();
+        if local.header.protocol_version.unwrap().is_pre_boojum() {
+            return if l1_commitment == &expected {
+                CheckOutcome::Consistent
+            } else {
+                CheckOutcome::Inconsistent
+            };
+        }
+
+        let (ethabi::Token::Tuple(expected), ethabi::Token::Tuple(actual)) =
+            (&expected, l1_commitment)
+        else {
+            return CheckOutcome::Inconsistent;
+        };
+        let (Some((expected_pubdata, expected_fields)), Some((actual_pubdata, actual_fields))) =
+            (expected.split_last(), actual.split_last())
+        else {
+            return CheckOutcome::Inconsistent;
+        };
+        if expected_fields != actual_fields {
+            return CheckOutcome::Inconsistent;
+        }
+
+        let is_rollup_pubdata = actual_pubdata == expected_pubdata;
+        match commitment_mode {
+            L1BatchCommitmentMode::Rollup if is_rollup_pubdata => CheckOutcome::Consistent,
+            L1BatchCommitmentMode::Rollup => {
+                CheckOutcome::ModeMismatch(L1BatchCommitmentMode::Validium)
+            }
+            L1BatchCommitmentMode::Validium
+                if is_rollup_pubdata && actual_pubdata != &ethabi::Token::Bytes(vec![]) =>
+            {
+                CheckOutcome::ModeMismatch(L1BatchCommitmentMode::Rollup)
+            }
+            L1BatchCommitmentMode::Validium => CheckOutcome::Consistent,
+        }
+    }
+
+    /// Returns the pubdata field (the last one) of a post-boojum L1 commitment, which contains inclusion data
+    /// for the external DA layer in the validium mode.
+    fn committed_pubdata(l1_commitment: &ethabi::Token) -> Option<&[u8]> {
+        let ethabi::Token::Tuple(fields) = l1_commitment else {
+            return None;
+        };
+        match fields.last()? {
+            ethabi::Token::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Fetches the pubdata blob referenced by the inclusion data committed on L1 from the external DA layer,
+    /// and compares its hash with the hash of the locally computed pubdata.
+    async fn check_da_blob(
+        da_client: &dyn DataAvailabilityClient,
+        local: &L1BatchWithMetadata,
+        l1_commitment: &ethabi::Token,
+    ) -> anyhow::Result<CheckOutcome> {
+        let batch_number = local.header.number;
+        let inclusion_data = Self::committed_pubdata(l1_commitment).unwrap_or_default();
+        if inclusion_data.is_empty() {
+            tracing::error!(
+                "L1 batch #{batch_number} is committed on L1 without DA inclusion data"
+            );
+            return Ok(CheckOutcome::DaBlobMismatch);
+        }
+
+        let blobs = da_client
+            .get_blobs(inclusion_data)
+            .await
+            .with_context(|| format!("failed fetching DA blob for L1 batch #{batch_number}"))?;
+        if blobs.is_empty() {
+            return Ok(CheckOutcome::DaBlobUnavailable);
+        }
+
+        let local_pubdata = local
+            .header
+            .pubdata_input
+            .clone()
+            .unwrap_or_else(|| local.construct_pubdata());
+        let expected_hash = H256(keccak256(&local_pubdata));
+        let blob_hashes: Vec<_> = blobs.iter().map(|blob| H256(keccak256(blob))).collect();
+        if blob_hashes.contains(&expected_hash) {
+            Ok(CheckOutcome::Consistent)
+        } else {
+            tracing::error!(
+                "Pubdata blobs fetched from DA layer for L1 batch #{batch_number} have hashes {blob_hashes:?}, \
+                 while the locally computed pubdata hash is {expected_hash:?}"
+            );
+            Ok(CheckOutcome::DaBlobMismatch)
         }
     }
 
-    async fn check_commitments(&self, batch_number: L1BatchNumber) -> Result<bool, error::Error> {
+    async fn check_commitments(&self, batch_number: L1BatchNumber) -> anyhow::Result<CheckOutcome> {
         let mut storage = self.db.access_storage().await.unwrap();
 
         let storage_l1_batch = storage
@@ -83,51 +254,113 @@ impl ConsistencyChecker {
             .web3
             .eth()
             .transaction(TransactionId::Hash(commit_tx_hash))
-            .await?
-            .expect("Commit tx not found on L1");
-
-        let commit_tx_status = self
-            .web3
-            .eth()
-            .transaction_receipt(commit_tx_hash)
-            .await?
-            .expect("Commit tx receipt not found on L1")
-            .status;
+            .await?;
+        let commit_tx_receipt = self.web3.eth().transaction_receipt(commit_tx_hash).await?;
+        let (Some(commit_tx), Some(commit_tx_receipt)) = (commit_tx, commit_tx_receipt) else {
+            // The commit tx may be reverted by an L1 reorg; it will be included into L1 again by the main node.
+            tracing::warn!(
+                "Commit tx {commit_tx_hash:?} for batch {} is not found on L1",
+                batch_number.0
+            );
+            return Ok(CheckOutcome::NotConfirmed);
+        };
+        if !self.is_confirmed(commit_tx_receipt.block_number).await? {
+            return Ok(CheckOutcome::NotConfirmed);
+        }
 
         assert_eq!(
-            commit_tx_status,
+            commit_tx_receipt.status,
             Some(1.into()),
             "Main node gave us a failed commit tx"
         );
 
-        let commit_function = if block_metadata
+        let is_pre_boojum = block_metadata
             .header
             .protocol_version
-            .unwrap()
-            .is_pre_boojum()
-        {
-            PRE_BOOJUM_COMMIT_FUNCTION.clone()
-        } else {
-            self.contract.function("commitBatches").unwrap().clone()
-        };
-
-        let commitments = commit_function
-            .decode_input(&commit_tx.input.0[4..])
-            .unwrap()
-            .pop()
-            .unwrap()
-            .into_array()
-            .unwrap();
+            .context("protocol version is not set for L1 batch")?
+            .is_pre_boojum();
+        let commitments =
+            Self::decode_commitments(&self.contract, is_pre_boojum, &commit_tx.input.0)
+                .with_context(|| format!("failed decoding commit tx {commit_tx_hash:?}"))?;
 
         // Commit transactions usually publish multiple commitments at once, so we need to find
         // the one that corresponds to the batch we're checking.
-        let first_batch_number = match &commitments[0] {
-            ethabi::Token::Tuple(tuple) => tuple[0].clone().into_uint().unwrap().as_usize(),
-            _ => panic!("ABI does not match the expected one"),
+        let first_batch_number = match commitments.first() {
+            Some(ethabi::Token::Tuple(tuple)) => tuple
+                .first()
+                .cloned()
+                .and_then(ethabi::Token::into_uint)
+                .context("commitment doesn't start with the L1 batch number")?,
+            _ => {
+                anyhow::bail!("ABI of commit tx {commit_tx_hash:?} does not match the expected one")
+            }
+        };
+        let commitment = U256::from(batch_number.0)
+            .checked_sub(first_batch_number)
+            .and_then(|index| commitments.get(index.as_usize()))
+            .with_context(|| {
+                format!(
+                    "commit tx {commit_tx_hash:?} doesn't contain a commitment for L1 batch #{batch_number}"
+                )
+            })?;
+
+        let outcome = Self::compare_commitment(self.commitment_mode, &block_metadata, commitment);
+        match &self.da_client {
+            Some(da_client) if outcome == CheckOutcome::Consistent => {
+                Self::check_da_blob(da_client.as_ref(), &block_metadata, commitment).await
+            }
+            _ => Ok(outcome),
+        }
+    }
+
+    /// Decodes L1 batch commitments from the calldata of a commit tx. Besides `commitBatches` of the diamond proxy,
+    /// batches of chains registered in the shared bridge are committed with `commitBatchesSharedBridge`, which takes
+    /// the L2 chain ID as the first argument. The function is chosen by the calldata selector.
+    fn decode_commitments(
+        contract: &ethabi::Contract,
+        is_pre_boojum: bool,
+        calldata: &[u8],
+    ) -> anyhow::Result<Vec<ethabi::Token>> {
+        let commit_function = if is_pre_boojum {
+            PRE_BOOJUM_COMMIT_FUNCTION.clone()
+        } else {
+            contract
+                .function("commitBatches")
+                .context("`commitBatches` function is missing in contract ABI")?
+                .clone()
         };
-        let commitment = &commitments[batch_number.0 as usize - first_batch_number];
+        anyhow::ensure!(
+            calldata.len() >= 4,
+            "calldata is too short: {} bytes",
+            calldata.len()
+        );
+        let (selector, args) = calldata.split_at(4);
 
-        Ok(commitment == &block_metadata.l1_commit_data())
+        let mut tokens = if selector == commit_function.short_signature() {
+            commit_function
+                .decode_input(args)
+                .with_context(|| format!("failed decoding `{}` args", commit_function.name))?
+        } else {
+            let shared_bridge_function = (!is_pre_boojum)
+                .then(|| shared_bridge_function(commit_function))
+                .filter(|function| selector == function.short_signature())
+                .with_context(|| {
+                    format!(
+                        "unexpected commit function selector 0x{}",
+                        hex::encode(selector)
+                    )
+                })?;
+            let mut tokens = shared_bridge_function.decode_input(args).with_context(|| {
+                format!("failed decoding `{}` args", shared_bridge_function.name)
+            })?;
+            // Strip the L2 chain ID; other args are the same as for `commitBatches`.
+            tokens.remove(0);
+            tokens
+        };
+        tokens
+            .pop()
+            .and_then(ethabi::Token::into_array)
+            .context("commit tx args don't end with an array of commitments")
     }
 
     async fn last_committed_batch(&self) -> L1BatchNumber {
@@ -187,17 +420,49 @@ impl ConsistencyChecker {
             }
 
             match self.check_commitments(batch_number).await {
-                Ok(true) => {
+                Ok(CheckOutcome::Consistent) => {
                     tracing::info!("Batch {} is consistent with L1", batch_number.0);
                     EN_METRICS.last_correct_batch[&CheckerComponent::ConsistencyChecker]
                         .set(batch_number.0.into());
                     batch_number.0 += 1;
                 }
-                Ok(false) => {
+                Ok(CheckOutcome::Inconsistent) => {
                     tracing::warn!("Batch {} is inconsistent with L1", batch_number.0);
                 }
+                Ok(CheckOutcome::NotConfirmed) => {
+                    tracing::info!(
+                        "Commit tx for batch {} is not confirmed on L1 yet",
+                        batch_number.0
+                    );
+                    tokio::time::sleep(SLEEP_DELAY).await;
+                }
+                Ok(CheckOutcome::DaBlobUnavailable) => {
+                    tracing::info!(
+                        "Pubdata blob for batch {} is not available in DA layer yet",
+                        batch_number.0
+                    );
+                    tokio::time::sleep(SLEEP_DELAY).await;
+                }
+                Ok(CheckOutcome::DaBlobMismatch) => {
+                    tracing::error!(
+                        "Pubdata blob for batch {} in DA layer is inconsistent with the local pubdata",
+                        batch_number.0
+                    );
+                    EN_METRICS
+                        .inconsistent_da_blob_batch
+                        .set(batch_number.0.into());
+                    tokio::time::sleep(SLEEP_DELAY).await;
+                }
+                Ok(CheckOutcome::ModeMismatch(l1_mode)) => {
+                    anyhow::bail!(
+                        "Batch {} is committed on L1 in {l1_mode:?} mode, while the node is configured \
+                         for {:?} mode",
+                        batch_number.0,
+                        self.commitment_mode
+                    );
+                }
                 Err(e) => {
-                    tracing::warn!("Consistency checker error: {}", e);
+                    tracing::warn!("Consistency checker error: {e:#}");
                     tokio::time::sleep(SLEEP_DELAY).await;
                 }
             }
@@ -0,0 +1,217 @@
+//! Tests for the consistency checker.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{block::L1BatchHeader, Address, ProtocolVersionId};
+
+use super::*;
+use crate::state_keeper::tests::create_l1_batch_metadata;
+
+/// Mock DA client returning blobs by their inclusion data.
+#[derive(Debug, Default)]
+struct MockDataAvailabilityClient {
+    blobs: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+#[async_trait]
+impl DataAvailabilityClient for MockDataAvailabilityClient {
+    async fn dispatch_blob(
+        &self,
+        _l1_batch_number: L1BatchNumber,
+        _data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        anyhow::bail!("not used by the consistency checker")
+    }
+
+    async fn get_inclusion_data(&self, _blob_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        anyhow::bail!("not used by the consistency checker")
+    }
+
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        Ok(self
+            .blobs
+            .get(inclusion_data)
+            .cloned()
+            .into_iter()
+            .collect())
+    }
+}
+
+fn create_l1_batch(number: u32, pubdata: Vec<u8>) -> L1BatchWithMetadata {
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(number),
+        number.into(),
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    header.pubdata_input = Some(pubdata);
+    L1BatchWithMetadata {
+        header,
+        metadata: create_l1_batch_metadata(number),
+        factory_deps: vec![],
+    }
+}
+
+#[test]
+fn commit_data_depends_on_commitment_mode() {
+    let l1_batch = create_l1_batch(1, vec![1, 2, 3]);
+    let rollup_commitment = l1_batch
+        .l1_commit_data_for_mode(L1BatchCommitmentMode::Rollup)
+        .unwrap();
+    assert_eq!(rollup_commitment, l1_batch.l1_commit_data());
+    let validium_commitment = l1_batch
+        .l1_commit_data_for_mode(L1BatchCommitmentMode::Validium)
+        .unwrap();
+    assert_eq!(
+        validium_commitment,
+        l1_batch.l1_commit_data_with_da_inclusion(&[]).unwrap()
+    );
+
+    let rollup_size = l1_batch
+        .l1_commit_data_size_for_mode(L1BatchCommitmentMode::Rollup)
+        .unwrap();
+    assert_eq!(rollup_size, l1_batch.l1_commit_data_size());
+    let validium_size = l1_batch
+        .l1_commit_data_size_for_mode(L1BatchCommitmentMode::Validium)
+        .unwrap();
+    assert!(
+        validium_size < rollup_size,
+        "{validium_size} >= {rollup_size}"
+    );
+
+    let mut pre_boojum_l1_batch = l1_batch;
+    pre_boojum_l1_batch.header.protocol_version = Some(ProtocolVersionId::Version10);
+    let err = pre_boojum_l1_batch
+        .l1_commit_data_for_mode(L1BatchCommitmentMode::Validium)
+        .unwrap_err();
+    assert!(err.to_string().contains("pre-boojum"), "{err}");
+}
+
+#[test]
+fn comparing_commitments_in_different_modes() {
+    let l1_batch = create_l1_batch(1, vec![1, 2, 3]);
+    let rollup_commitment = l1_batch.l1_commit_data();
+    let validium_commitment = l1_batch.l1_commit_data_with_da_inclusion(&[]).unwrap();
+
+    let outcome = ConsistencyChecker::compare_commitment(
+        L1BatchCommitmentMode::Rollup,
+        &l1_batch,
+        &rollup_commitment,
+    );
+    assert_eq!(outcome, CheckOutcome::Consistent);
+    let outcome = ConsistencyChecker::compare_commitment(
+        L1BatchCommitmentMode::Validium,
+        &l1_batch,
+        &validium_commitment,
+    );
+    assert_eq!(outcome, CheckOutcome::Consistent);
+
+    let outcome = ConsistencyChecker::compare_commitment(
+        L1BatchCommitmentMode::Rollup,
+        &l1_batch,
+        &validium_commitment,
+    );
+    assert_eq!(
+        outcome,
+        CheckOutcome::ModeMismatch(L1BatchCommitmentMode::Validium)
+    );
+    let outcome = ConsistencyChecker::compare_commitment(
+        L1BatchCommitmentMode::Validium,
+        &l1_batch,
+        &rollup_commitment,
+    );
+    assert_eq!(
+        outcome,
+        CheckOutcome::ModeMismatch(L1BatchCommitmentMode::Rollup)
+    );
+
+    let other_l1_batch = create_l1_batch(2, vec![1, 2, 3]);
+    let outcome = ConsistencyChecker::compare_commitment(
+        L1BatchCommitmentMode::Validium,
+        &other_l1_batch,
+        &validium_commitment,
+    );
+    assert_eq!(outcome, CheckOutcome::Inconsistent);
+}
+
+#[tokio::test]
+async fn checking_da_blobs() {
+    let l1_batch = create_l1_batch(1, vec![1, 2, 3]);
+    let mut client = MockDataAvailabilityClient::default();
+    client.blobs.insert(b"ok".to_vec(), vec![1, 2, 3]);
+    client.blobs.insert(b"bogus".to_vec(), vec![3, 2, 1]);
+
+    let commitment = l1_batch.l1_commit_data_with_da_inclusion(b"ok").unwrap();
+    assert_eq!(
+        ConsistencyChecker::compare_commitment(
+            L1BatchCommitmentMode::Validium,
+            &l1_batch,
+            &commitment
+        ),
+        CheckOutcome::Consistent
+    );
+    let outcome = ConsistencyChecker::check_da_blob(&client, &l1_batch, &commitment)
+        .await
+        .unwrap();
+    assert_eq!(outcome, CheckOutcome::Consistent);
+
+    let commitment = l1_batch.l1_commit_data_with_da_inclusion(b"bogus").unwrap();
+    let outcome = ConsistencyChecker::check_da_blob(&client, &l1_batch, &commitment)
+        .await
+        .unwrap();
+    assert_eq!(outcome, CheckOutcome::DaBlobMismatch);
+
+    let commitment = l1_batch
+        .l1_commit_data_with_da_inclusion(b"missing")
+        .unwrap();
+    let outcome = ConsistencyChecker::check_da_blob(&client, &l1_batch, &commitment)
+        .await
+        .unwrap();
+    assert_eq!(outcome, CheckOutcome::DaBlobUnavailable);
+
+    let commitment = l1_batch.l1_commit_data_with_da_inclusion(&[]).unwrap();
+    let outcome = ConsistencyChecker::check_da_blob(&client, &l1_batch, &commitment)
+        .await
+        .unwrap();
+    assert_eq!(outcome, CheckOutcome::DaBlobMismatch);
+}
+
+#[test]
+fn decoding_commitments_from_calldata() {
+    let contract = zksync_contracts::zksync_contract();
+    let last_committed_batch = create_l1_batch(1, vec![]);
+    let l1_batches = [create_l1_batch(2, vec![1]), create_l1_batch(3, vec![2])];
+    let expected_commitments: Vec<_> = l1_batches
+        .iter()
+        .map(L1BatchWithMetadata::l1_commit_data)
+        .collect();
+    let args = [
+        last_committed_batch.l1_header_data(),
+        ethabi::Token::Array(expected_commitments.clone()),
+    ];
+
+    let commit_function = contract.function("commitBatches").unwrap();
+    let calldata = commit_function.encode_input(&args).unwrap();
+    let commitments = ConsistencyChecker::decode_commitments(&contract, false, &calldata).unwrap();
+    assert_eq!(commitments, expected_commitments);
+
+    let shared_bridge_function = shared_bridge_function(commit_function.clone());
+    let shared_bridge_args: Vec<_> = [ethabi::Token::Uint(270.into())]
+        .into_iter()
+        .chain(args)
+        .collect();
+    let calldata = shared_bridge_function
+        .encode_input(&shared_bridge_args)
+        .unwrap();
+    let commitments = ConsistencyChecker::decode_commitments(&contract, false, &calldata).unwrap();
+    assert_eq!(commitments, expected_commitments);
+
+    // Shared bridge functions are not available for pre-boojum batches.
+    let err = ConsistencyChecker::decode_commitments(&contract, true, &calldata).unwrap_err();
+    assert!(err.to_string().contains("selector"), "{err}");
+    let err = ConsistencyChecker::decode_commitments(&contract, false, &[1, 2]).unwrap_err();
+    assert!(err.to_string().contains("too short"), "{err}");
+}
@@ -0,0 +1,420 @@
+//! HTTP clients for supported data availability layers.
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use zksync_config::configs::DADispatcherConfig;
+use zksync_types::L1BatchNumber;
+
+use super::DataAvailabilityClient;
+
+/// Version of Celestia namespaces used for blobs. Version 0 namespaces have 18 leading zero bytes
+/// followed by a user-specified ID of up to 10 bytes.
+const CELESTIA_NAMESPACE_VERSION: u8 = 0;
+const CELESTIA_NAMESPACE_ID_LEN: usize = 10;
+const CELESTIA_NAMESPACE_LEN: usize = 29;
+
+/// Parses a hex-encoded Celestia namespace ID into a full version 0 namespace.
+pub(super) fn parse_celestia_namespace(
+    namespace_id: &str,
+) -> anyhow::Result<[u8; CELESTIA_NAMESPACE_LEN]> {
+    let namespace_id = namespace_id.strip_prefix("0x").unwrap_or(namespace_id);
+    let namespace_id = hex::decode(namespace_id).context("Celestia namespace is not valid hex")?;
+    anyhow::ensure!(
+        !namespace_id.is_empty() && namespace_id.len() <= CELESTIA_NAMESPACE_ID_LEN,
+        "Celestia namespace ID must have 1 to {CELESTIA_NAMESPACE_ID_LEN} bytes, got {}",
+        namespace_id.len()
+    );
+
+    let mut namespace = [0_u8; CELESTIA_NAMESPACE_LEN];
+    namespace[0] = CELESTIA_NAMESPACE_VERSION;
+    namespace[CELESTIA_NAMESPACE_LEN - namespace_id.len()..].copy_from_slice(&namespace_id);
+    Ok(namespace)
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a, P> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: P,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CelestiaBlob {
+    namespace: String,
+    data: String,
+    share_version: u32,
+}
+
+/// Client posting blobs to Celestia via the JSON-RPC API of a Celestia light or bridge node.
+///
+/// The node only returns from `blob.Submit` once the blob is included into a Celestia block, and Celestia
+/// blocks are final once produced. Hence, the blob ID is the height of the including block, and the inclusion
+/// data is the 8-byte big-endian height followed by the 29-byte namespace of the blob.
+#[derive(Debug)]
+pub struct CelestiaClient {
+    inner: reqwest::Client,
+    api_url: String,
+    auth_token: String,
+    namespace: [u8; CELESTIA_NAMESPACE_LEN],
+}
+
+impl CelestiaClient {
+    pub fn new(config: &DADispatcherConfig) -> anyhow::Result<Self> {
+        let auth_token = config
+            .auth_token
+            .clone()
+            .context("Celestia client requires an auth token")?;
+        let namespace = config
+            .celestia_namespace
+            .as_deref()
+            .context("Celestia client requires a namespace")?;
+        Ok(Self {
+            inner: reqwest::Client::new(),
+            api_url: config.api_url.clone(),
+            auth_token,
+            namespace: parse_celestia_namespace(namespace)?,
+        })
+    }
+
+    async fn call<P: Serialize, T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> anyhow::Result<T> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        };
+        let response = self
+            .inner
+            .post(&self.api_url)
+            .bearer_auth(&self.auth_token)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Failed calling `{method}` on Celestia node"))?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Calling `{method}` on Celestia node returned non-OK response")
+        })?;
+        let response: JsonRpcResponse<T> = response
+            .json()
+            .await
+            .with_context(|| format!("Failed deserializing `{method}` response"))?;
+        if let Some(err) = response.error {
+            anyhow::bail!(
+                "Celestia node returned error for `{method}`: {} (code {})",
+                err.message,
+                err.code
+            );
+        }
+        response
+            .result
+            .with_context(|| format!("Celestia node returned no result for `{method}`"))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for CelestiaClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let blob = CelestiaBlob {
+            namespace: base64::encode(self.namespace),
+            data: base64::encode(data),
+            share_version: 0,
+        };
+        // The second param is the gas price; a negative value means that the node estimates the price itself.
+        let height: u64 = self
+            .call("blob.Submit", (vec![blob], -1.0))
+            .await
+            .with_context(|| format!("Failed submitting blob for L1 batch #{l1_batch_number}"))?;
+        Ok(height.to_string())
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let height: u64 = blob_id
+            .parse()
+            .with_context(|| format!("Invalid Celestia blob ID: {blob_id}"))?;
+        let mut inclusion_data = height.to_be_bytes().to_vec();
+        inclusion_data.extend_from_slice(&self.namespace);
+        Ok(Some(inclusion_data))
+    }
+
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        anyhow::ensure!(
+            inclusion_data.len() == 8 + CELESTIA_NAMESPACE_LEN,
+            "Invalid Celestia inclusion data length: {}",
+            inclusion_data.len()
+        );
+        let (height, namespace) = inclusion_data.split_at(8);
+        let height = u64::from_be_bytes(height.try_into().unwrap());
+        let blobs: Vec<CelestiaBlob> = self
+            .call("blob.GetAll", (height, [base64::encode(namespace)]))
+            .await
+            .with_context(|| format!("Failed getting blobs at Celestia height {height}"))?;
+        blobs
+            .into_iter()
+            .map(|blob| base64::decode(blob.data).context("Celestia blob data is not valid base64"))
+            .collect()
+    }
+}
+
+/// Client posting blobs to EigenDA via the HTTP API of the EigenDA proxy.
+///
+/// The blob ID is the hex-encoded certificate returned by the proxy, and the inclusion data is the certificate
+/// itself. The certificate is returned as inclusion data once the blob can be retrieved from the proxy.
+#[derive(Debug)]
+pub struct EigenDAClient {
+    inner: reqwest::Client,
+    api_url: String,
+}
+
+impl EigenDAClient {
+    pub fn new(config: &DADispatcherConfig) -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+            api_url: config.api_url.trim_end_matches('/').to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for EigenDAClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let response = self
+            .inner
+            .post(format!("{}/put/", self.api_url))
+            .body(data)
+            .send()
+            .await
+            .with_context(|| format!("Failed posting blob for L1 batch #{l1_batch_number}"))?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Posting blob for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        let certificate = response
+            .bytes()
+            .await
+            .context("Failed reading EigenDA certificate")?;
+        anyhow::ensure!(
+            !certificate.is_empty(),
+            "EigenDA proxy returned empty certificate"
+        );
+        Ok(hex::encode(certificate))
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let certificate =
+            hex::decode(blob_id).with_context(|| format!("Invalid EigenDA blob ID: {blob_id}"))?;
+        let response = self
+            .inner
+            .get(format!("{}/get/0x{blob_id}", self.api_url))
+            .send()
+            .await
+            .context("Failed requesting EigenDA blob")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response
+            .error_for_status()
+            .context("Requesting EigenDA blob returned non-OK response")?;
+        Ok(Some(certificate))
+    }
+
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        let response = self
+            .inner
+            .get(format!(
+                "{}/get/0x{}",
+                self.api_url,
+                hex::encode(inclusion_data)
+            ))
+            .send()
+            .await
+            .context("Failed requesting EigenDA blob")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        let response = response
+            .error_for_status()
+            .context("Requesting EigenDA blob returned non-OK response")?;
+        let blob = response
+            .bytes()
+            .await
+            .context("Failed reading EigenDA blob")?;
+        Ok(vec![blob.to_vec()])
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AvailSubmitRequest {
+    data: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailSubmitResponse {
+    block_number: u32,
+    index: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailBlockStatus {
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailDataTransaction {
+    data: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailBlockData {
+    data_transactions: Vec<AvailDataTransaction>,
+}
+
+/// Client posting blobs to Avail via the HTTP API of the Avail light client.
+///
+/// The blob ID has the `{block_number}-{index}` format, where `index` is the index of the data submission
+/// extrinsic in the block. The inclusion data is the 4-byte big-endian block number followed by the 4-byte
+/// big-endian extrinsic index; it's returned once the light client has verified availability of the block.
+#[derive(Debug)]
+pub struct AvailClient {
+    inner: reqwest::Client,
+    api_url: String,
+}
+
+impl AvailClient {
+    /// Block status reported by the light client once the availability of block data is verified.
+    const VERIFIED_BLOCK_STATUS: &'static str = "finished";
+
+    pub fn new(config: &DADispatcherConfig) -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+            api_url: config.api_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    fn parse_blob_id(blob_id: &str) -> anyhow::Result<(u32, u32)> {
+        let (block_number, index) = blob_id
+            .split_once('-')
+            .with_context(|| format!("Invalid Avail blob ID: {blob_id}"))?;
+        let block_number = block_number
+            .parse()
+            .with_context(|| format!("Invalid block number in Avail blob ID: {blob_id}"))?;
+        let index = index
+            .parse()
+            .with_context(|| format!("Invalid extrinsic index in Avail blob ID: {blob_id}"))?;
+        Ok((block_number, index))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for AvailClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let request = AvailSubmitRequest {
+            data: base64::encode(data),
+        };
+        let response = self
+            .inner
+            .post(format!("{}/v2/submit", self.api_url))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Failed submitting blob for L1 batch #{l1_batch_number}"))?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Submitting blob for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        let response: AvailSubmitResponse = response
+            .json()
+            .await
+            .context("Failed deserializing Avail submission response")?;
+        Ok(format!("{}-{}", response.block_number, response.index))
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let (block_number, index) = Self::parse_blob_id(blob_id)?;
+        let response = self
+            .inner
+            .get(format!("{}/v2/blocks/{block_number}", self.api_url))
+            .send()
+            .await
+            .with_context(|| format!("Failed requesting status of Avail block #{block_number}"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting status of Avail block #{block_number} returned non-OK response")
+        })?;
+        let response: AvailBlockStatus = response
+            .json()
+            .await
+            .context("Failed deserializing Avail block status")?;
+        if response.status != Self::VERIFIED_BLOCK_STATUS {
+            return Ok(None);
+        }
+
+        let mut inclusion_data = block_number.to_be_bytes().to_vec();
+        inclusion_data.extend_from_slice(&index.to_be_bytes());
+        Ok(Some(inclusion_data))
+    }
+
+    /// The light client only returns data submissions for its app ID, and doesn't expose extrinsic indices
+    /// for them, so all submissions in the block are returned.
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        anyhow::ensure!(
+            inclusion_data.len() == 8,
+            "Invalid Avail inclusion data length: {}",
+            inclusion_data.len()
+        );
+        let block_number = u32::from_be_bytes(inclusion_data[..4].try_into().unwrap());
+        let response = self
+            .inner
+            .get(format!(
+                "{}/v2/blocks/{block_number}/data?fields=data",
+                self.api_url
+            ))
+            .send()
+            .await
+            .with_context(|| format!("Failed requesting data of Avail block #{block_number}"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting data of Avail block #{block_number} returned non-OK response")
+        })?;
+        let response: AvailBlockData = response
+            .json()
+            .await
+            .context("Failed deserializing Avail block data")?;
+        response
+            .data_transactions
+            .into_iter()
+            .map(|tx| base64::decode(tx.data).context("Avail submission data is not valid base64"))
+            .collect()
+    }
+}
@@ -0,0 +1,39 @@
+//! DA dispatcher metrics.
+
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "action", rename_all = "snake_case")]
+pub(super) enum DataAvailabilityAction {
+    DispatchBlob,
+    GetInclusionData,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_dispatcher")]
+pub(super) struct DataAvailabilityDispatcherMetrics {
+    /// Latency of posting a blob to the DA layer, including retries.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub blob_dispatch_latency: Histogram<Duration>,
+    /// Time between posting a blob to the DA layer and receiving its inclusion data.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub inclusion_latency: Histogram<Duration>,
+    /// Size of posted blobs.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=16.0 * 1_024.0 * 1_024.0, 4.0), unit = Unit::Bytes)]
+    pub blob_size: Histogram<usize>,
+    /// Number of the last L1 batch posted to the DA layer.
+    pub last_dispatched_l1_batch: Gauge<u64>,
+    /// Number of the last L1 batch with inclusion data.
+    pub last_included_l1_batch: Gauge<u64>,
+    /// Number of retried requests to the DA layer, grouped by the action.
+    pub retries: Family<DataAvailabilityAction, Counter>,
+    /// Number of dispatcher iterations that failed after exhausting retries, grouped by the action.
+    pub errors: Family<DataAvailabilityAction, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DataAvailabilityDispatcherMetrics> = vise::Global::new();
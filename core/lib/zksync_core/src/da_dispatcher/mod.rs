@@ -0,0 +1,219 @@
+//! Posting pubdata of L1 batches to an external data availability (DA) layer.
+//!
+//! The dispatcher posts pubdata of each sealed L1 batch to the DA layer and polls the layer for the inclusion data
+//! of posted blobs. If the L1 sender runs in the custom pubdata sending mode, it only commits batches
+//! with inclusion data, which is passed to the commit transaction instead of the pubdata.
+
+use std::{fmt, future::Future, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::watch;
+use zksync_config::configs::{da_dispatcher::DataAvailabilityClientKind, DADispatcherConfig};
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+pub use self::clients::{AvailClient, CelestiaClient, EigenDAClient};
+use self::metrics::{DataAvailabilityAction, METRICS};
+
+mod clients;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Client of an external data availability layer.
+#[async_trait]
+pub trait DataAvailabilityClient: 'static + fmt::Debug + Send + Sync {
+    /// Posts pubdata of the specified L1 batch to the DA layer. Returns an ID of the posted blob, which is later
+    /// passed to [`Self::get_inclusion_data()`].
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String>;
+
+    /// Returns the inclusion data for a previously posted blob, or `None` if the blob is not included
+    /// in the DA layer yet. The inclusion data is passed to the commit transaction as-is.
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Fetches blobs located by the inclusion data previously returned by [`Self::get_inclusion_data()`].
+    /// Some DA layers can only locate a blob up to a block, in which case all blobs in the block posted by the node
+    /// are returned. Returns an empty list if the blobs are not available.
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>>;
+}
+
+/// Creates a DA client based on the provided config.
+pub fn create_da_client(
+    config: &DADispatcherConfig,
+) -> anyhow::Result<Box<dyn DataAvailabilityClient>> {
+    Ok(match config.client {
+        DataAvailabilityClientKind::Celestia => Box::new(CelestiaClient::new(config)?),
+        DataAvailabilityClientKind::EigenDA => Box::new(EigenDAClient::new(config)),
+        DataAvailabilityClientKind::Avail => Box::new(AvailClient::new(config)),
+    })
+}
+
+/// Component posting pubdata of L1 batches to the DA layer and storing the inclusion data of posted blobs;
+/// see [`DataAvailabilityDal`](zksync_dal::data_availability_dal::DataAvailabilityDal) for details.
+#[derive(Debug)]
+pub struct DataAvailabilityDispatcher {
+    config: DADispatcherConfig,
+    pool: ConnectionPool,
+    client: Box<dyn DataAvailabilityClient>,
+}
+
+impl DataAvailabilityDispatcher {
+    const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+    pub fn new(
+        config: DADispatcherConfig,
+        pool: ConnectionPool,
+        client: Box<dyn DataAvailabilityClient>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            client,
+        }
+    }
+
+    /// Retries `action` with exponential backoff up to the configured number of retries.
+    async fn retry<T, Fut>(
+        &self,
+        action: DataAvailabilityAction,
+        mut f: impl FnMut() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let max_retries = self.config.max_retries();
+        let mut backoff = Self::INITIAL_RETRY_BACKOFF;
+        let mut retries = 0;
+        loop {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retries < max_retries => {
+                    retries += 1;
+                    METRICS.retries[&action].inc();
+                    tracing::warn!(
+                        "DA layer request failed ({retries}/{max_retries} retries), retrying in {backoff:?}: {err:#}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Posts pubdata of sealed L1 batches to the DA layer. Returns the number of posted batches.
+    async fn dispatch(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        let batches = storage
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(self.config.max_batches_to_dispatch())
+            .await
+            .context("failed getting L1 batches ready for DA dispatch")?;
+        drop(storage);
+
+        for batch in &batches {
+            let number = batch.l1_batch_number;
+            let latency = METRICS.blob_dispatch_latency.start();
+            let blob_id = self
+                .retry(DataAvailabilityAction::DispatchBlob, || {
+                    self.client.dispatch_blob(number, batch.pubdata.clone())
+                })
+                .await
+                .with_context(|| format!("failed posting pubdata for L1 batch #{number}"))?;
+            let latency = latency.observe();
+            let sent_at = Utc::now().naive_utc();
+
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            storage
+                .data_availability_dal()
+                .insert_l1_batch_da(number, &blob_id, sent_at)
+                .await
+                .with_context(|| format!("failed saving blob ID for L1 batch #{number}"))?;
+            drop(storage);
+
+            METRICS.blob_size.observe(batch.pubdata.len());
+            METRICS.last_dispatched_l1_batch.set(number.0.into());
+            tracing::info!("Posted pubdata for L1 batch #{number} to DA layer in {latency:?}; blob ID: {blob_id}");
+        }
+        Ok(batches.len())
+    }
+
+    /// Polls the DA layer for the inclusion data of the first blob awaiting inclusion. Returns the number
+    /// of the L1 batch if its inclusion data was saved.
+    async fn poll_for_inclusion(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        let blob = storage
+            .data_availability_dal()
+            .get_first_da_blob_awaiting_inclusion()
+            .await
+            .context("failed getting blob awaiting inclusion")?;
+        drop(storage);
+        let Some(blob) = blob else {
+            return Ok(None);
+        };
+
+        let number = blob.l1_batch_number;
+        let inclusion_data = self
+            .retry(DataAvailabilityAction::GetInclusionData, || {
+                self.client.get_inclusion_data(&blob.blob_id)
+            })
+            .await
+            .with_context(|| format!("failed getting inclusion data for L1 batch #{number}"))?;
+        let Some(inclusion_data) = inclusion_data else {
+            return Ok(None);
+        };
+
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        storage
+            .data_availability_dal()
+            .save_l1_batch_inclusion_data(number, &inclusion_data)
+            .await
+            .with_context(|| format!("failed saving inclusion data for L1 batch #{number}"))?;
+        drop(storage);
+
+        let inclusion_latency = Utc::now().naive_utc() - blob.sent_at;
+        if let Ok(latency) = inclusion_latency.to_std() {
+            METRICS.inclusion_latency.observe(latency);
+        }
+        METRICS.last_included_l1_batch.set(number.0.into());
+        tracing::info!("Received inclusion data for L1 batch #{number} from DA layer");
+        Ok(Some(number))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            // DA layer outages and transient DB errors shouldn't stop the node; all errors are retried
+            // on the next iteration.
+            if let Err(err) = self.dispatch().await {
+                METRICS.errors[&DataAvailabilityAction::DispatchBlob].inc();
+                tracing::error!("Failed posting pubdata to DA layer: {err:#}");
+            }
+            match self.poll_for_inclusion().await {
+                // Continue polling without a delay; the next blob may already be included as well.
+                Ok(Some(_)) => continue,
+                Ok(None) => {}
+                Err(err) => {
+                    METRICS.errors[&DataAvailabilityAction::GetInclusionData].inc();
+                    tracing::error!("Failed polling DA layer for inclusion data: {err:#}");
+                }
+            }
+            if tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, DA dispatcher is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+//! Tests for the DA dispatcher.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    block::{BlockGasCount, L1BatchHeader},
+    Address, L2ChainId, ProtocolVersionId,
+};
+
+use super::{clients::parse_celestia_namespace, *};
+use crate::genesis::{ensure_genesis_state, GenesisParams};
+
+/// Mock DA client storing blobs in memory. Blobs are included once [`Self::include_all()`] is called.
+/// Clones share the state.
+#[derive(Debug, Default, Clone)]
+struct MockDataAvailabilityClient {
+    blobs: Arc<Mutex<HashMap<String, (Vec<u8>, bool)>>>,
+    /// Number of dispatch requests to fail before succeeding.
+    failing_dispatches: Arc<AtomicUsize>,
+}
+
+impl MockDataAvailabilityClient {
+    fn include_all(&self) {
+        for (_, is_included) in self.blobs.lock().unwrap().values_mut() {
+            *is_included = true;
+        }
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for MockDataAvailabilityClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let should_fail = self
+            .failing_dispatches
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        anyhow::ensure!(!should_fail, "DA layer is unavailable");
+
+        let blob_id = format!("blob-{l1_batch_number}");
+        self.blobs
+            .lock()
+            .unwrap()
+            .insert(blob_id.clone(), (data, false));
+        Ok(blob_id)
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let blobs = self.blobs.lock().unwrap();
+        let (data, is_included) = blobs.get(blob_id).context("unknown blob")?;
+        Ok(is_included.then(|| data.iter().rev().copied().collect()))
+    }
+
+    async fn get_blobs(&self, inclusion_data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        let data: Vec<_> = inclusion_data.iter().rev().copied().collect();
+        let blobs = self.blobs.lock().unwrap();
+        let is_included = blobs
+            .values()
+            .any(|(blob, is_included)| *is_included && *blob == data);
+        Ok(if is_included { vec![data] } else { vec![] })
+    }
+}
+
+async fn seal_l1_batch(storage: &mut StorageProcessor<'_>, number: u32) {
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(number),
+        number.into(),
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    header.pubdata_input = Some(vec![number as u8, 1, 2, 3]);
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+        .await
+        .unwrap();
+}
+
+fn test_config() -> DADispatcherConfig {
+    DADispatcherConfig {
+        client: DataAvailabilityClientKind::Celestia,
+        api_url: "http://localhost:26658".to_owned(),
+        auth_token: None,
+        celestia_namespace: None,
+        polling_interval_ms: Some(10),
+        max_batches_to_dispatch: Some(2),
+        max_retries: Some(1),
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool, l1_batch_count: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=l1_batch_count {
+        seal_l1_batch(&mut storage, number).await;
+    }
+}
+
+#[test]
+fn parsing_celestia_namespace() {
+    let namespace = parse_celestia_namespace("0x7a6b73").unwrap();
+    assert_eq!(namespace[..26], [0; 26]);
+    assert_eq!(namespace[26..], [0x7a, 0x6b, 0x73]);
+
+    parse_celestia_namespace("").unwrap_err();
+    parse_celestia_namespace("zz").unwrap_err();
+    parse_celestia_namespace(&"01".repeat(11)).unwrap_err();
+}
+
+#[tokio::test]
+async fn dispatching_and_polling_for_inclusion() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let client = MockDataAvailabilityClient::default();
+    // The first request should be retried.
+    client.failing_dispatches.store(1, Ordering::SeqCst);
+    let dispatcher = DataAvailabilityDispatcher::new(test_config(), pool.clone(), Box::new(client));
+
+    assert_eq!(dispatcher.dispatch().await.unwrap(), 2);
+    assert_eq!(dispatcher.dispatch().await.unwrap(), 1);
+    assert_eq!(dispatcher.dispatch().await.unwrap(), 0);
+    assert_eq!(dispatcher.poll_for_inclusion().await.unwrap(), None);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let blob = storage
+        .data_availability_dal()
+        .get_first_da_blob_awaiting_inclusion()
+        .await
+        .unwrap()
+        .expect("no blobs awaiting inclusion");
+    assert_eq!(blob.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(blob.blob_id, "blob-1");
+}
+
+#[tokio::test]
+async fn saving_inclusion_data() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let client = MockDataAvailabilityClient::default();
+    let dispatcher =
+        DataAvailabilityDispatcher::new(test_config(), pool.clone(), Box::new(client.clone()));
+
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(dispatcher.poll_for_inclusion().await.unwrap(), None);
+    client.include_all();
+    assert_eq!(
+        dispatcher.poll_for_inclusion().await.unwrap(),
+        Some(L1BatchNumber(1))
+    );
+    assert_eq!(
+        dispatcher.poll_for_inclusion().await.unwrap(),
+        Some(L1BatchNumber(2))
+    );
+    assert_eq!(dispatcher.poll_for_inclusion().await.unwrap(), None);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let inclusion_data = storage
+        .data_availability_dal()
+        .get_inclusion_data(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(inclusion_data, Some(vec![3, 2, 1, 2]));
+}
+
+#[tokio::test]
+async fn dispatcher_fails_after_exhausting_retries() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let client = MockDataAvailabilityClient::default();
+    client.failing_dispatches.store(2, Ordering::SeqCst);
+    let dispatcher = DataAvailabilityDispatcher::new(test_config(), pool.clone(), Box::new(client));
+
+    let err = dispatcher.dispatch().await.unwrap_err().to_string();
+    assert!(err.contains("L1 batch #1"), "{err}");
+    let mut storage = pool.access_storage().await.unwrap();
+    let ready_batches = storage
+        .data_availability_dal()
+        .get_ready_for_da_dispatch_l1_batches(10)
+        .await
+        .unwrap();
+    assert_eq!(ready_batches.len(), 1);
+}
+
+#[tokio::test]
+async fn dispatcher_recovers_after_da_layer_outage() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let client = MockDataAvailabilityClient::default();
+    // The first iteration of the dispatcher loop fails after exhausting retries.
+    client.failing_dispatches.store(3, Ordering::SeqCst);
+    let dispatcher = DataAvailabilityDispatcher::new(test_config(), pool.clone(), Box::new(client));
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let dispatcher_task = tokio::spawn(dispatcher.run(stop_receiver));
+
+    loop {
+        let mut storage = pool.access_storage().await.unwrap();
+        let ready_batches = storage
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        if ready_batches.is_empty() {
+            break;
+        }
+        drop(storage);
+        assert!(!dispatcher_task.is_finished(), "dispatcher stopped");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    stop_sender.send_replace(true);
+    dispatcher_task.await.unwrap().unwrap();
+}
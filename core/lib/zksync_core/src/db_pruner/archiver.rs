@@ -0,0 +1,115 @@
+//! Archiving of rarely accessed artifacts of old L1 batches to the object store.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::database::ColdStorageConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStore;
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+use super::metrics::ARCHIVER_METRICS;
+
+/// Component moving call traces and pubdata inputs of L1 batches that are older than the configured retention
+/// period and are executed on L1 from Postgres to the object store. Batches are archived one by one in order;
+/// see [`ColdStorageDal`](zksync_dal::cold_storage_dal::ColdStorageDal) for the details on reading archived data.
+#[derive(Debug)]
+pub struct ColdStorageArchiver {
+    config: ColdStorageConfig,
+    pool: ConnectionPool,
+    object_store: Box<dyn ObjectStore>,
+}
+
+impl ColdStorageArchiver {
+    pub fn new(
+        config: ColdStorageConfig,
+        pool: ConnectionPool,
+        object_store: Box<dyn ObjectStore>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            object_store,
+        }
+    }
+
+    /// Archives the next L1 batch. Returns the number of the archived L1 batch,
+    /// or `None` if there are no L1 batches to archive.
+    async fn archive_next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("cold_storage_archiver")
+            .await?;
+        let last_archived_l1_batch = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .context("failed getting last archived L1 batch")?;
+        let max_timestamp = seconds_since_epoch().saturating_sub(self.config.data_retention_sec);
+        let last_archivable_l1_batch = storage
+            .pruning_dal()
+            .get_last_prunable_l1_batch(max_timestamp)
+            .await
+            .context("failed getting last archivable L1 batch")?;
+        let Some(last_archivable_l1_batch) = last_archivable_l1_batch else {
+            return Ok(None);
+        };
+
+        let l1_batch_number = last_archived_l1_batch.map_or(L1BatchNumber(0), |number| number + 1);
+        if l1_batch_number > last_archivable_l1_batch {
+            return Ok(None);
+        }
+
+        let latency = ARCHIVER_METRICS.l1_batch_latency.start();
+        let artifacts = storage
+            .cold_storage_dal()
+            .get_l1_batch_artifacts(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting artifacts for L1 batch #{l1_batch_number}"))?
+            .with_context(|| format!("L1 batch #{l1_batch_number} disappeared from Postgres"))?;
+        // If the archiver is restarted after putting artifacts to the store, but before marking the batch
+        // as archived, the stored artifacts will be overwritten with the same data.
+        self.object_store
+            .put(l1_batch_number, &artifacts)
+            .await
+            .with_context(|| format!("failed storing artifacts for L1 batch #{l1_batch_number}"))?;
+        storage
+            .cold_storage_dal()
+            .mark_l1_batch_as_archived(l1_batch_number)
+            .await
+            .with_context(|| format!("failed marking L1 batch #{l1_batch_number} as archived"))?;
+        let latency = latency.observe();
+
+        let call_trace_count = artifacts.call_traces.len();
+        ARCHIVER_METRICS
+            .archived_call_traces
+            .inc_by(call_trace_count as u64);
+        ARCHIVER_METRICS
+            .last_archived_l1_batch
+            .set(l1_batch_number.0.into());
+        tracing::info!(
+            "Archived L1 batch #{l1_batch_number} with {call_trace_count} call traces in {latency:?}"
+        );
+        Ok(Some(l1_batch_number))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if self.archive_next_l1_batch().await?.is_some() {
+                // Continue archiving without a delay until all archivable L1 batches are processed.
+                continue;
+            }
+            if tokio::time::timeout(self.config.interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, cold storage archiver is shutting down");
+        Ok(())
+    }
+}
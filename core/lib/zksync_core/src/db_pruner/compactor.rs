@@ -0,0 +1,103 @@
+//! Compaction of storage logs of old L1 batches in Postgres.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::database::StorageLogsCompactionConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+use super::metrics::COMPACTOR_METRICS;
+
+/// Component removing storage logs overwritten later in the same L1 batch for L1 batches that are older
+/// than the configured retention period and are executed on L1. Batches are compacted in chunks, with each chunk
+/// compacted in a separate database transaction. See [`PruningDal`](zksync_dal::pruning_dal::PruningDal)
+/// for the details on the retained data.
+///
+/// After compaction, the storage state is only available as of the last miniblock of each compacted L1 batch;
+/// historical storage queries for other miniblocks in these batches may return outdated values.
+#[derive(Debug)]
+pub struct StorageLogsCompactor {
+    config: StorageLogsCompactionConfig,
+    pool: ConnectionPool,
+}
+
+impl StorageLogsCompactor {
+    pub fn new(config: StorageLogsCompactionConfig, pool: ConnectionPool) -> Self {
+        Self { config, pool }
+    }
+
+    /// Compacts the next chunk of L1 batches. Returns the number of the last compacted L1 batch,
+    /// or `None` if there are no L1 batches to compact.
+    async fn compact_next_chunk(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("storage_logs_compactor")
+            .await?;
+        let info = storage
+            .pruning_dal()
+            .get_storage_logs_compaction_info()
+            .await
+            .context("failed getting storage logs compaction info")?;
+        let max_timestamp = seconds_since_epoch().saturating_sub(self.config.data_retention_sec);
+        let last_compactable_l1_batch = storage
+            .pruning_dal()
+            .get_last_prunable_l1_batch(max_timestamp)
+            .await
+            .context("failed getting last compactable L1 batch")?;
+        let Some(last_compactable_l1_batch) = last_compactable_l1_batch else {
+            return Ok(None);
+        };
+
+        let first_l1_batch = info
+            .last_compacted_l1_batch
+            .map_or(L1BatchNumber(0), |number| number + 1);
+        if first_l1_batch > last_compactable_l1_batch {
+            return Ok(None);
+        }
+        let chunk_size = self.config.chunk_size.max(1);
+        let last_l1_batch = last_compactable_l1_batch.min(first_l1_batch + (chunk_size - 1));
+
+        let latency = COMPACTOR_METRICS.chunk_latency.start();
+        let deleted_storage_logs = storage
+            .pruning_dal()
+            .compact_storage_logs(last_l1_batch)
+            .await
+            .with_context(|| {
+                format!("failed compacting storage logs for L1 batches {first_l1_batch}..={last_l1_batch}")
+            })?;
+        let latency = latency.observe();
+
+        COMPACTOR_METRICS
+            .deleted_storage_logs
+            .inc_by(deleted_storage_logs);
+        COMPACTOR_METRICS
+            .last_compacted_l1_batch
+            .set(last_l1_batch.0.into());
+        tracing::info!(
+            "Compacted storage logs for L1 batches {first_l1_batch}..={last_l1_batch} in {latency:?}: \
+             removed {deleted_storage_logs} logs"
+        );
+        Ok(Some(last_l1_batch))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if self.compact_next_chunk().await?.is_some() {
+                // Continue compaction without a delay until all compactable L1 batches are processed.
+                continue;
+            }
+            if tokio::time::timeout(self.config.interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, storage logs compactor is shutting down");
+        Ok(())
+    }
+}
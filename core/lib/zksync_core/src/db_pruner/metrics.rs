@@ -0,0 +1,61 @@
+//! Postgres pruner metrics.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "table", rename_all = "snake_case")]
+pub(super) enum PrunedTable {
+    Events,
+    CallTraces,
+    StorageLogs,
+    Transactions,
+    ReplacedTransactions,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "db_pruner")]
+pub(super) struct DbPrunerMetrics {
+    /// Latency of pruning a single chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub chunk_latency: Histogram<Duration>,
+    /// Number of the last pruned L1 batch.
+    pub last_pruned_l1_batch: Gauge<u64>,
+    /// Number of rows removed by the pruner, grouped by the table.
+    pub deleted_rows: Family<PrunedTable, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "storage_logs_compactor")]
+pub(super) struct StorageLogsCompactorMetrics {
+    /// Latency of compacting a single chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub chunk_latency: Histogram<Duration>,
+    /// Number of the last compacted L1 batch.
+    pub last_compacted_l1_batch: Gauge<u64>,
+    /// Number of storage logs removed by the compactor.
+    pub deleted_storage_logs: Counter,
+}
+
+#[vise::register]
+pub(super) static COMPACTOR_METRICS: vise::Global<StorageLogsCompactorMetrics> =
+    vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "cold_storage_archiver")]
+pub(super) struct ColdStorageArchiverMetrics {
+    /// Latency of archiving a single L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub l1_batch_latency: Histogram<Duration>,
+    /// Number of the last archived L1 batch.
+    pub last_archived_l1_batch: Gauge<u64>,
+    /// Number of archived call traces.
+    pub archived_call_traces: Counter,
+}
+
+#[vise::register]
+pub(super) static ARCHIVER_METRICS: vise::Global<ColdStorageArchiverMetrics> = vise::Global::new();
@@ -0,0 +1,117 @@
+//! Pruning, compaction and archiving of historical data of old L1 batches in Postgres.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::database::PruningConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::{PrunedTable, METRICS};
+pub use self::{archiver::ColdStorageArchiver, compactor::StorageLogsCompactor};
+
+mod archiver;
+mod compactor;
+mod metrics;
+
+/// Component removing events, call traces, L2 transactions and overwritten storage logs of L1 batches
+/// that are older than the configured retention period, are not among the configured number of latest L1 batches,
+/// are executed on L1 and are processed by the Merkle tree. Batches are pruned in chunks,
+/// with each chunk pruned in a separate database transaction. See [`PruningDal`](zksync_dal::pruning_dal::PruningDal)
+/// for the details on the retained data.
+///
+/// Historical data for pruned miniblocks (e.g., their transactions and events, or the storage state
+/// as of these miniblocks) is no longer available via the API.
+#[derive(Debug)]
+pub struct DbPruner {
+    config: PruningConfig,
+    pool: ConnectionPool,
+}
+
+impl DbPruner {
+    pub fn new(config: PruningConfig, pool: ConnectionPool) -> Self {
+        Self { config, pool }
+    }
+
+    /// Prunes the next chunk of L1 batches. Returns the number of the last pruned L1 batch,
+    /// or `None` if there are no L1 batches to prune.
+    async fn prune_next_chunk(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self.pool.access_storage_tagged("db_pruner").await?;
+        let info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        let max_timestamp = seconds_since_epoch().saturating_sub(self.config.data_retention_sec);
+        let last_prunable_l1_batch = storage
+            .pruning_dal()
+            .get_last_prunable_l1_batch(max_timestamp)
+            .await
+            .context("failed getting last prunable L1 batch")?;
+        let Some(mut last_prunable_l1_batch) = last_prunable_l1_batch else {
+            return Ok(None);
+        };
+        if let Some(retained_l1_batches) = self.config.retained_l1_batches {
+            let sealed_l1_batch = storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await
+                .context("failed getting sealed L1 batch number")?;
+            let Some(max_l1_batch) = sealed_l1_batch.0.checked_sub(retained_l1_batches) else {
+                return Ok(None);
+            };
+            last_prunable_l1_batch = last_prunable_l1_batch.min(L1BatchNumber(max_l1_batch));
+        }
+
+        let first_l1_batch = info
+            .last_pruned_l1_batch
+            .map_or(L1BatchNumber(0), |number| number + 1);
+        if first_l1_batch > last_prunable_l1_batch {
+            return Ok(None);
+        }
+        let chunk_size = self.config.chunk_size.max(1);
+        let last_l1_batch = last_prunable_l1_batch.min(first_l1_batch + (chunk_size - 1));
+
+        let latency = METRICS.chunk_latency.start();
+        let stats = storage
+            .pruning_dal()
+            .prune_l1_batches(last_l1_batch)
+            .await
+            .with_context(|| {
+                format!("failed pruning L1 batches {first_l1_batch}..={last_l1_batch}")
+            })?;
+        let latency = latency.observe();
+
+        METRICS.deleted_rows[&PrunedTable::Events].inc_by(stats.deleted_events);
+        METRICS.deleted_rows[&PrunedTable::CallTraces].inc_by(stats.deleted_call_traces);
+        METRICS.deleted_rows[&PrunedTable::StorageLogs].inc_by(stats.deleted_storage_logs);
+        METRICS.deleted_rows[&PrunedTable::Transactions].inc_by(stats.deleted_l2_transactions);
+        METRICS.deleted_rows[&PrunedTable::ReplacedTransactions]
+            .inc_by(stats.deleted_replaced_transactions);
+        METRICS.last_pruned_l1_batch.set(last_l1_batch.0.into());
+        tracing::info!(
+            "Pruned L1 batches {first_l1_batch}..={last_l1_batch} in {latency:?}: {stats:?}"
+        );
+        Ok(Some(last_l1_batch))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if self.prune_next_chunk().await?.is_some() {
+                // Continue pruning without a delay until all prunable L1 batches are processed.
+                continue;
+            }
+            if tokio::time::timeout(self.config.interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, DB pruner is shutting down");
+        Ok(())
+    }
+}
@@ -1,4 +1,9 @@
-use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use zksync_config::configs::eth_sender::{
+    ProofLoadingMode, ProofSendingMode, PubdataSendingMode, SenderConfig,
+};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
 use zksync_object_store::ObjectStore;
@@ -8,28 +13,39 @@ use zksync_types::{
         AggregatedActionType, AggregatedOperation, L1BatchCommitOperation, L1BatchExecuteOperation,
         L1BatchProofOperation,
     },
-    commitment::L1BatchWithMetadata,
+    basic_fri_types::ProofCompressionBackend,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
     helpers::unix_timestamp_ms,
     protocol_version::L1VerifierConfig,
     L1BatchNumber, ProtocolVersionId,
 };
 
-use super::publish_criterion::{
-    DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
-    TimestampDeadlineCriterion,
+use super::{
+    metrics::METRICS,
+    publish_criterion::{
+        CostCriterion, DataSizeCriterion, ExecuteGasPriceGate, GasCriterion,
+        L1BatchPublishCriterion, NumberCriterion, TimestampDeadlineCriterion,
+    },
 };
+use crate::l1_gas_price::L1TxParamsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
     commit_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
     proof_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
     execute_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
+    execute_gas_price_gate: Option<ExecuteGasPriceGate>,
     config: SenderConfig,
     blob_store: Box<dyn ObjectStore>,
+    commitment_mode: L1BatchCommitmentMode,
 }
 
 impl Aggregator {
-    pub fn new(config: SenderConfig, blob_store: Box<dyn ObjectStore>) -> Self {
+    pub fn new(
+        config: SenderConfig,
+        blob_store: Box<dyn ObjectStore>,
+        commitment_mode: L1BatchCommitmentMode,
+    ) -> Self {
         Self {
             commit_criteria: vec![
                 Box::from(NumberCriterion {
@@ -43,6 +59,7 @@ impl Aggregator {
                 Box::from(DataSizeCriterion {
                     op: AggregatedActionType::Commit,
                     data_limit: config.max_eth_tx_data_size,
+                    commitment_mode,
                 }),
                 Box::from(TimestampDeadlineCriterion {
                     op: AggregatedActionType::Commit,
@@ -83,9 +100,100 @@ impl Aggregator {
                     max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
                 }),
             ],
+            execute_gas_price_gate: None,
             config,
             blob_store,
+            commitment_mode,
+        }
+    }
+
+    /// Enables the cost-based aggregation planner if it's configured (see
+    /// [`SenderConfig::max_aggregation_overhead_per_l1_batch`]). `l1_tx_params` provides the current L1 gas price.
+    pub fn with_cost_based_planner(
+        mut self,
+        l1_tx_params: Arc<dyn L1TxParamsProvider + Send + Sync>,
+    ) -> Self {
+        let Some(max_overhead_per_l1_batch) = self.config.max_aggregation_overhead_per_l1_batch
+        else {
+            return self;
+        };
+        let criteria = [
+            (AggregatedActionType::Commit, &mut self.commit_criteria),
+            (
+                AggregatedActionType::PublishProofOnchain,
+                &mut self.proof_criteria,
+            ),
+            (AggregatedActionType::Execute, &mut self.execute_criteria),
+        ];
+        for (op, criteria) in criteria {
+            criteria.push(Box::new(CostCriterion {
+                op,
+                max_overhead_per_l1_batch,
+                l1_tx_params: l1_tx_params.clone(),
+            }));
         }
+        self
+    }
+
+    /// Enables postponing execute operations while the L1 gas price is high if it's configured (see
+    /// [`SenderConfig::execute_max_l1_gas_price`]). `l1_tx_params` provides the current L1 gas price.
+    pub fn with_execute_gas_price_gate(
+        mut self,
+        l1_tx_params: Arc<dyn L1TxParamsProvider + Send + Sync>,
+    ) -> Self {
+        if let Some(max_l1_gas_price) = self.config.execute_max_l1_gas_price {
+            self.execute_gas_price_gate = Some(ExecuteGasPriceGate {
+                max_l1_gas_price,
+                min_age_seconds: self.config.l1_batch_execute_min_age_seconds().unwrap_or(0),
+                max_postpone_seconds: self.config.execute_max_postpone_seconds(),
+                l1_tx_params,
+            });
+        }
+        self
+    }
+
+    /// Checks that all L1 batches yet to be committed support the configured commitment mode. Pre-boojum
+    /// L1 batches can only be committed in the rollup mode. Should be called on node startup, so that
+    /// the incompatibility is detected before any L1 batches are aggregated.
+    pub async fn validate_commitment_mode(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        if self.commitment_mode == L1BatchCommitmentMode::Rollup {
+            return Ok(());
+        }
+
+        // Protocol versions of L1 batches are non-decreasing, so it's sufficient to check the oldest
+        // uncommitted L1 batch and the latest protocol version (which will be used by future batches).
+        let oldest_uncommitted_l1_batch = storage
+            .blocks_dal()
+            .get_oldest_uncommitted_l1_batch_number()
+            .await
+            .context("failed getting oldest uncommitted L1 batch")?;
+        if let Some(l1_batch_number) = oldest_uncommitted_l1_batch {
+            let protocol_version = storage
+                .blocks_dal()
+                .get_batch_protocol_version_id(l1_batch_number)
+                .await?
+                .with_context(|| format!("L1 batch #{l1_batch_number} has no protocol version"))?;
+            anyhow::ensure!(
+                !protocol_version.is_pre_boojum(),
+                "L1 batch #{l1_batch_number} pending commitment has pre-boojum protocol version {protocol_version:?}, \
+                 which doesn't support {:?} commitment mode",
+                self.commitment_mode
+            );
+        }
+
+        let last_protocol_version = storage.protocol_versions_dal().last_version_id().await;
+        if let Some(protocol_version) = last_protocol_version {
+            anyhow::ensure!(
+                !protocol_version.is_pre_boojum(),
+                "Latest protocol version {protocol_version:?} is pre-boojum and doesn't support \
+                 {:?} commitment mode",
+                self.commitment_mode
+            );
+        }
+        Ok(())
     }
 
     pub async fn get_next_ready_operation(
@@ -94,7 +202,7 @@ impl Aggregator {
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
         l1_verifier_config: L1VerifierConfig,
-    ) -> Option<AggregatedOperation> {
+    ) -> anyhow::Result<Option<AggregatedOperation>> {
         let last_sealed_l1_batch_number = storage
             .blocks_dal()
             .get_sealed_l1_batch_number()
@@ -108,7 +216,7 @@ impl Aggregator {
             )
             .await
         {
-            Some(AggregatedOperation::Execute(op))
+            Ok(Some(AggregatedOperation::Execute(op)))
         } else if let Some(op) = self
             .get_proof_operation(
                 storage,
@@ -118,17 +226,18 @@ impl Aggregator {
             )
             .await
         {
-            Some(AggregatedOperation::PublishProofOnchain(op))
+            Ok(Some(AggregatedOperation::PublishProofOnchain(op)))
         } else {
-            self.get_commit_operation(
-                storage,
-                self.config.max_aggregated_blocks_to_commit as usize,
-                last_sealed_l1_batch_number,
-                base_system_contracts_hashes,
-                protocol_version_id,
-            )
-            .await
-            .map(AggregatedOperation::Commit)
+            let op = self
+                .get_commit_operation(
+                    storage,
+                    self.config.max_aggregated_blocks_to_commit as usize,
+                    last_sealed_l1_batch_number,
+                    base_system_contracts_hashes,
+                    protocol_version_id,
+                )
+                .await?;
+            Ok(op.map(AggregatedOperation::Commit))
         }
     }
 
@@ -140,22 +249,37 @@ impl Aggregator {
     ) -> Option<L1BatchExecuteOperation> {
         let max_l1_batch_timestamp_millis = self
             .config
-            .l1_batch_min_age_before_execute_seconds
+            .l1_batch_execute_min_age_seconds()
             .map(|age| unix_timestamp_ms() - age * 1_000);
         let ready_for_execute_batches = storage
             .blocks_dal()
             .get_ready_for_execute_l1_batches(limit, max_l1_batch_timestamp_millis)
             .await
             .unwrap();
+        METRICS
+            .ready_for_execute_l1_batches
+            .set(ready_for_execute_batches.len());
         let l1_batches = extract_ready_subrange(
             storage,
             &mut self.execute_criteria,
             ready_for_execute_batches,
             last_sealed_l1_batch,
         )
-        .await;
+        .await?;
 
-        l1_batches.map(|l1_batches| L1BatchExecuteOperation { l1_batches })
+        if let Some(gate) = &self.execute_gas_price_gate {
+            if !gate.allows_execution(&l1_batches) {
+                return None;
+            }
+        }
+        let now = unix_timestamp_ms() / 1_000;
+        for l1_batch in &l1_batches {
+            let lag = now.saturating_sub(l1_batch.header.timestamp);
+            METRICS
+                .l1_batch_execute_lag
+                .observe(Duration::from_secs(lag));
+        }
+        Some(L1BatchExecuteOperation { l1_batches })
     }
 
     async fn get_commit_operation(
@@ -165,14 +289,17 @@ impl Aggregator {
         last_sealed_batch: L1BatchNumber,
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
-    ) -> Option<L1BatchCommitOperation> {
+    ) -> anyhow::Result<Option<L1BatchCommitOperation>> {
         let mut blocks_dal = storage.blocks_dal();
-        let last_committed_l1_batch = blocks_dal
+        let Some(last_committed_l1_batch) = blocks_dal
             .get_last_committed_to_eth_l1_batch()
             .await
-            .unwrap()?;
+            .unwrap()
+        else {
+            return Ok(None);
+        };
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -205,18 +332,60 @@ impl Aggregator {
                 }
             });
 
-        let batches = extract_ready_subrange(
+        let mut da_inclusion_data = None;
+        if self.config.pubdata_sending_mode == PubdataSendingMode::Custom {
+            // Only batches with pubdata included in the DA layer can be committed.
+            let inclusion_data =
+                Self::load_da_inclusion_data(storage, &ready_for_commit_l1_batches)
+                    .await
+                    .context("failed loading DA inclusion data")?;
+            ready_for_commit_l1_batches.truncate(inclusion_data.len());
+            da_inclusion_data = Some(inclusion_data);
+        }
+
+        let Some(batches) = extract_ready_subrange(
             storage,
             &mut self.commit_criteria,
             ready_for_commit_l1_batches,
             last_sealed_batch,
         )
-        .await;
+        .await
+        else {
+            return Ok(None);
+        };
 
-        batches.map(|batches| L1BatchCommitOperation {
+        if let Some(inclusion_data) = &mut da_inclusion_data {
+            inclusion_data.truncate(batches.len());
+        }
+        Ok(Some(L1BatchCommitOperation {
             last_committed_l1_batch,
             l1_batches: batches,
-        })
+            da_inclusion_data,
+            commitment_mode: self.commitment_mode,
+        }))
+    }
+
+    /// Loads DA inclusion data for the longest prefix of `l1_batches` included in the DA layer.
+    /// `l1_batches` must be sequential.
+    async fn load_da_inclusion_data(
+        storage: &mut StorageProcessor<'_>,
+        l1_batches: &[L1BatchWithMetadata],
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let (Some(first), Some(last)) = (l1_batches.first(), l1_batches.last()) else {
+            return Ok(vec![]);
+        };
+        let included = storage
+            .data_availability_dal()
+            .get_inclusion_data_for_range(first.header.number..=last.header.number)
+            .await?;
+
+        let expected_numbers = l1_batches.iter().map(|batch| batch.header.number);
+        let inclusion_data = expected_numbers
+            .zip(included)
+            .take_while(|(expected_number, (number, _))| expected_number == number)
+            .map(|(_, (_, inclusion_data))| inclusion_data)
+            .collect();
+        Ok(inclusion_data)
     }
 
     async fn load_real_proof_operation(
@@ -224,6 +393,7 @@ impl Aggregator {
         l1_verifier_config: L1VerifierConfig,
         proof_loading_mode: &ProofLoadingMode,
         blob_store: &dyn ObjectStore,
+        use_dual_verifier: bool,
     ) -> Option<L1BatchProofOperation> {
         let previous_proven_batch_number = storage
             .blocks_dal()
@@ -268,6 +438,14 @@ impl Aggregator {
         }
 
         assert_eq!(proofs.len(), 1);
+        let backend = proofs[0].backend();
+        if !use_dual_verifier && backend != ProofCompressionBackend::Plonk {
+            tracing::error!(
+                "Proof for L1 batch #{batch_to_prove} is compressed with the {backend:?} backend, \
+                 which requires the dual verifier on L1; enable `use_dual_verifier` if the verifier supports it"
+            );
+            return None;
+        }
 
         let previous_proven_batch_metadata = storage
             .blocks_dal()
@@ -297,6 +475,7 @@ impl Aggregator {
             l1_batches: vec![metadata_for_batch_being_proved],
             proofs,
             should_verify: true,
+            use_dual_verifier,
         })
     }
 
@@ -326,6 +505,7 @@ impl Aggregator {
             l1_batches: batches,
             proofs: vec![],
             should_verify: false,
+            use_dual_verifier: false,
         })
     }
 
@@ -343,6 +523,7 @@ impl Aggregator {
                     l1_verifier_config,
                     &self.config.proof_loading_mode,
                     &*self.blob_store,
+                    self.config.use_dual_verifier(),
                 )
                 .await
             }
@@ -368,6 +549,7 @@ impl Aggregator {
                     l1_verifier_config,
                     &self.config.proof_loading_mode,
                     &*self.blob_store,
+                    self.config.use_dual_verifier(),
                 )
                 .await
                 {
@@ -7,4 +7,6 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] types::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error("Internal error: {0:#}")]
+    Internal(#[from] anyhow::Error),
 }
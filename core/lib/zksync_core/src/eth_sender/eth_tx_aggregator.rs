@@ -1,10 +1,11 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, sync::Arc};
 
+use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::BoundEthInterface;
+use zksync_eth_client::{BoundEthInterface, EthInterface};
 use zksync_types::{
     aggregated_operations::AggregatedOperation,
     contracts::{Multicall3Call, Multicall3Result},
@@ -20,7 +21,7 @@ use crate::{
     eth_sender::{
         metrics::{PubdataKind, METRICS},
         zksync_functions::ZkSyncFunctions,
-        Aggregator, ETHSenderError,
+        Aggregator, ETHSenderError, SettlementLayer,
     },
     gas_tracker::agg_l1_batch_base_cost,
     metrics::BlockL1Stage,
@@ -42,31 +43,28 @@ pub struct MulticallData {
 pub struct EthTxAggregator {
     aggregator: Aggregator,
     config: SenderConfig,
-    timelock_contract_address: Address,
-    l1_multicall3_address: Address,
-    pub(super) main_zksync_contract_address: Address,
+    settlement_layer: Arc<dyn SettlementLayer>,
     functions: ZkSyncFunctions,
     base_nonce: u64,
+    operator_address: Address,
 }
 
 impl EthTxAggregator {
     pub fn new(
         config: SenderConfig,
         aggregator: Aggregator,
-        timelock_contract_address: Address,
-        l1_multicall3_address: Address,
-        main_zksync_contract_address: Address,
+        settlement_layer: Arc<dyn SettlementLayer>,
         base_nonce: u64,
+        operator_address: Address,
     ) -> Self {
         let functions = ZkSyncFunctions::default();
         Self {
             config,
             aggregator,
-            timelock_contract_address,
-            l1_multicall3_address,
-            main_zksync_contract_address,
+            settlement_layer,
             functions,
             base_nonce,
+            operator_address,
         }
     }
 
@@ -76,6 +74,18 @@ impl EthTxAggregator {
         eth_client: E,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            eth_client.sender_account() == self.operator_address,
+            "Operator address {:?} doesn't match the address of the signing client {:?}",
+            self.operator_address,
+            eth_client.sender_account()
+        );
+        let mut storage = pool.access_storage_tagged("eth_sender").await?;
+        self.hand_over_unsent_txs(&mut storage, &eth_client)
+            .await
+            .context("failed handing over unsent txs to the operator")?;
+        drop(storage);
+
         loop {
             let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
 
@@ -95,6 +105,73 @@ impl EthTxAggregator {
         Ok(())
     }
 
+    /// Ensures that all txs that were not sent yet are signed by the current operator. If the operator address
+    /// was rotated, txs created for the previous address are reassigned to the current one and get new nonces.
+    /// Txs that were already sent by the previous operator are left as is; `EthTxManager` waits
+    /// for them to be mined before sending txs from the current address.
+    pub(super) async fn hand_over_unsent_txs<E: EthInterface>(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        eth_client: &E,
+    ) -> anyhow::Result<()> {
+        Self::recover_legacy_tx_signers(storage, eth_client).await?;
+
+        let mut transaction = storage.start_transaction().await?;
+        let first_nonce = self.get_next_nonce(&mut transaction).await?;
+        let reassigned_tx_count = transaction
+            .eth_sender_dal()
+            .reassign_unsent_txs(self.operator_address, first_nonce)
+            .await?;
+        if reassigned_tx_count > 0 {
+            tracing::warn!(
+                "Reassigned {reassigned_tx_count} unsent eth txs not signed by the current operator \
+                 to {:?} starting from nonce {first_nonce}",
+                self.operator_address
+            );
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Sets operator addresses for sent txs created before operator addresses were tracked, recovering
+    /// signers from L1. Txs not found on L1 are left without an address; unsent txs without an address
+    /// are reassigned to the current operator by [`Self::hand_over_unsent_txs()`].
+    async fn recover_legacy_tx_signers<E: EthInterface>(
+        storage: &mut StorageProcessor<'_>,
+        eth_client: &E,
+    ) -> anyhow::Result<()> {
+        let legacy_txs = storage
+            .eth_sender_dal()
+            .get_sent_txs_without_from_addr()
+            .await?;
+        for (eth_tx_id, tx_hashes) in legacy_txs {
+            let mut signer = None;
+            for tx_hash in tx_hashes {
+                let tx = eth_client
+                    .get_tx(tx_hash, "eth_tx_aggregator")
+                    .await
+                    .with_context(|| format!("failed getting L1 tx {tx_hash:?}"))?;
+                signer = tx.and_then(|tx| tx.from);
+                if signer.is_some() {
+                    break;
+                }
+            }
+
+            if let Some(signer) = signer {
+                tracing::info!("Recovered operator address {signer:?} for eth tx {eth_tx_id}");
+                storage
+                    .eth_sender_dal()
+                    .set_from_addr(eth_tx_id, signer)
+                    .await?;
+            } else {
+                tracing::warn!(
+                    "Cannot recover operator address for eth tx {eth_tx_id}: none of its attempts are known on L1"
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub(super) async fn get_multicall_data<E: BoundEthInterface>(
         &mut self,
         eth_client: &E,
@@ -107,7 +184,7 @@ impl EthTxAggregator {
                 None,
                 Options::default(),
                 None,
-                self.l1_multicall3_address,
+                self.settlement_layer.multicall3_address(),
                 self.functions.multicall_contract.clone(),
             )
             .await?;
@@ -128,7 +205,7 @@ impl EthTxAggregator {
             .encode_input(&[])
             .unwrap();
         let get_bootloader_hash_call = Multicall3Call {
-            target: self.main_zksync_contract_address,
+            target: self.settlement_layer.diamond_proxy_address(),
             allow_failure: ALLOW_FAILURE,
             calldata: get_l2_bootloader_hash_input,
         };
@@ -140,7 +217,7 @@ impl EthTxAggregator {
             .encode_input(&[])
             .unwrap();
         let get_default_aa_hash_call = Multicall3Call {
-            target: self.main_zksync_contract_address,
+            target: self.settlement_layer.diamond_proxy_address(),
             allow_failure: ALLOW_FAILURE,
             calldata: get_l2_default_aa_hash_input,
         };
@@ -152,7 +229,7 @@ impl EthTxAggregator {
             .encode_input(&[])
             .unwrap();
         let get_verifier_params_call = Multicall3Call {
-            target: self.main_zksync_contract_address,
+            target: self.settlement_layer.diamond_proxy_address(),
             allow_failure: ALLOW_FAILURE,
             calldata: get_verifier_params_input,
         };
@@ -160,7 +237,7 @@ impl EthTxAggregator {
         // Fourth zksync contract call
         let get_verifier_input = self.functions.get_verifier.encode_input(&[]).unwrap();
         let get_verifier_call = Multicall3Call {
-            target: self.main_zksync_contract_address,
+            target: self.settlement_layer.diamond_proxy_address(),
             allow_failure: ALLOW_FAILURE,
             calldata: get_verifier_input,
         };
@@ -172,7 +249,7 @@ impl EthTxAggregator {
             .encode_input(&[])
             .unwrap();
         let get_protocol_version_call = Multicall3Call {
-            target: self.main_zksync_contract_address,
+            target: self.settlement_layer.diamond_proxy_address(),
             allow_failure: ALLOW_FAILURE,
             calldata: get_protocol_version_input,
         };
@@ -384,6 +461,7 @@ impl EthTxAggregator {
                 l1_verifier_config,
             )
             .await
+            .context("failed getting next ready operation")?
         {
             let tx = self
                 .save_eth_tx(storage, &agg_op, contracts_are_pre_boojum)
@@ -424,55 +502,13 @@ impl EthTxAggregator {
             .await;
     }
 
-    fn encode_aggregated_op(
-        &self,
-        op: &AggregatedOperation,
-        contracts_are_pre_boojum: bool,
-    ) -> Vec<u8> {
-        let operation_is_pre_boojum = op.protocol_version().is_pre_boojum();
-
-        // For "commit" and "prove" operations it's necessary that the contracts are of the same version as L1 batches are.
-        // For "execute" it's not required, i.e. we can "execute" pre-boojum batches with post-boojum contracts.
-        match &op {
-            AggregatedOperation::Commit(op) => {
-                assert_eq!(contracts_are_pre_boojum, operation_is_pre_boojum);
-                let f = if contracts_are_pre_boojum {
-                    &self.functions.pre_boojum_commit
-                } else {
-                    self.functions
-                        .post_boojum_commit
-                        .as_ref()
-                        .expect("Missing ABI for commitBatches")
-                };
-                f.encode_input(&op.get_eth_tx_args())
-            }
-            AggregatedOperation::PublishProofOnchain(op) => {
-                assert_eq!(contracts_are_pre_boojum, operation_is_pre_boojum);
-                let f = if contracts_are_pre_boojum {
-                    &self.functions.pre_boojum_prove
-                } else {
-                    self.functions
-                        .post_boojum_prove
-                        .as_ref()
-                        .expect("Missing ABI for proveBatches")
-                };
-                f.encode_input(&op.get_eth_tx_args())
-            }
-            AggregatedOperation::Execute(op) => {
-                let f = if contracts_are_pre_boojum {
-                    &self.functions.pre_boojum_execute
-                } else {
-                    self.functions
-                        .post_boojum_execute
-                        .as_ref()
-                        .expect("Missing ABI for executeBatches")
-                };
-                f.encode_input(&op.get_eth_tx_args())
-            }
-        }
-        .expect("Failed to encode transaction data")
-    }
-
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            op = %aggregated_op.get_action_type(),
+            l1_batches = ?aggregated_op.l1_batch_range()
+        )
+    )]
     pub(super) async fn save_eth_tx(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -481,7 +517,10 @@ impl EthTxAggregator {
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await.unwrap();
         let nonce = self.get_next_nonce(&mut transaction).await?;
-        let calldata = self.encode_aggregated_op(aggregated_op, contracts_are_pre_boojum);
+        let calldata = self
+            .settlement_layer
+            .encode_operation(aggregated_op, contracts_are_pre_boojum)
+            .context("failed encoding aggregated operation")?;
         let l1_batch_number_range = aggregated_op.l1_batch_range();
         let op_type = aggregated_op.get_action_type();
 
@@ -498,8 +537,9 @@ impl EthTxAggregator {
                 nonce,
                 calldata,
                 op_type,
-                self.timelock_contract_address,
+                self.settlement_layer.validator_timelock_address(),
                 eth_tx_predicted_gas,
+                self.operator_address,
             )
             .await
             .unwrap();
@@ -519,7 +559,7 @@ impl EthTxAggregator {
     ) -> Result<u64, ETHSenderError> {
         let db_nonce = storage
             .eth_sender_dal()
-            .get_next_nonce()
+            .get_next_nonce(self.operator_address)
             .await
             .unwrap()
             .unwrap_or(0);
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
@@ -15,19 +15,17 @@ use zksync_types::{
         error::Error as Web3Error,
         types::{BlockId, BlockNumber},
     },
-    L1BlockNumber, Nonce, H256, U256,
+    Address, L1BlockNumber, Nonce, H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{
+    fee_escalation::{EthFee, FeeEscalationPolicies, FeeEscalationPolicy},
+    metrics::METRICS,
+    ETHSenderError,
+};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
-#[derive(Debug)]
-struct EthFee {
-    base_fee_per_gas: u64,
-    priority_fee_per_gas: u64,
-}
-
 #[derive(Debug, Clone, Copy)]
 struct OperatorNonce {
     // Nonce on finalized block
@@ -46,11 +44,12 @@ pub(super) struct L1BlockNumbers {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price, which is determined by the fee escalation policy for the tx type.
 #[derive(Debug)]
 pub struct EthTxManager<E, G> {
     ethereum_gateway: E,
     config: SenderConfig,
+    fee_escalation: FeeEscalationPolicies,
     gas_adjuster: Arc<G>,
 }
 
@@ -59,10 +58,16 @@ where
     E: BoundEthInterface + Sync,
     G: L1TxParamsProvider,
 {
-    pub fn new(config: SenderConfig, gas_adjuster: Arc<G>, ethereum_gateway: E) -> Self {
+    pub fn new(
+        config: SenderConfig,
+        fee_escalation: FeeEscalationPolicies,
+        gas_adjuster: Arc<G>,
+        ethereum_gateway: E,
+    ) -> Self {
         Self {
             ethereum_gateway,
             config,
+            fee_escalation,
             gas_adjuster,
         }
     }
@@ -111,23 +116,29 @@ where
         tx: &EthTx,
         time_in_mempool: u32,
     ) -> Result<EthFee, ETHSenderError> {
-        let base_fee_per_gas = self.gas_adjuster.get_base_fee(time_in_mempool);
+        let policy = self.fee_escalation.get(tx.tx_type);
+        let smoothed_base_fee = self.gas_adjuster.get_smoothed_base_fee();
+        let base_fee_per_gas = policy.base_fee(smoothed_base_fee, time_in_mempool);
 
-        let priority_fee_per_gas = if time_in_mempool != 0 {
+        let fee = if time_in_mempool != 0 {
             METRICS.transaction_resent.inc();
-            let priority_fee_per_gas = self
-                .increase_priority_fee(storage, tx.id, base_fee_per_gas)
+            let fee = self
+                .increase_fee(storage, tx.id, policy, base_fee_per_gas)
                 .await?;
             tracing::info!(
                 "Resending operation {} with base fee {:?} and priority fee {:?}",
                 tx.id,
-                base_fee_per_gas,
-                priority_fee_per_gas
+                fee.base_fee_per_gas,
+                fee.priority_fee_per_gas
             );
-            priority_fee_per_gas
+            fee
         } else {
-            self.gas_adjuster.get_priority_fee()
+            policy.cap_fee(EthFee {
+                base_fee_per_gas,
+                priority_fee_per_gas: self.gas_adjuster.get_priority_fee(),
+            })
         };
+        let priority_fee_per_gas = fee.priority_fee_per_gas;
 
         // Extra check to prevent sending transaction will extremely high priority fee.
         if priority_fee_per_gas > self.config.max_acceptable_priority_fee_in_gwei {
@@ -138,18 +149,16 @@ where
             );
         }
 
-        Ok(EthFee {
-            base_fee_per_gas,
-            priority_fee_per_gas,
-        })
+        Ok(fee)
     }
 
-    async fn increase_priority_fee(
+    async fn increase_fee(
         &self,
         storage: &mut StorageProcessor<'_>,
         eth_tx_id: u32,
+        policy: &FeeEscalationPolicy,
         base_fee_per_gas: u64,
-    ) -> Result<u64, ETHSenderError> {
+    ) -> Result<EthFee, ETHSenderError> {
         let previous_sent_tx = storage
             .eth_sender_dal()
             .get_last_sent_eth_tx(eth_tx_id)
@@ -159,6 +168,18 @@ where
 
         let previous_base_fee = previous_sent_tx.base_fee_per_gas;
         let previous_priority_fee = previous_sent_tx.priority_fee_per_gas;
+        let previous_fee = EthFee {
+            base_fee_per_gas: previous_base_fee,
+            priority_fee_per_gas: previous_priority_fee,
+        };
+        if policy.is_capped(previous_fee) {
+            // The transaction cannot be replaced without exceeding the fee cap, so it just waits to be mined.
+            tracing::info!(
+                "Skipping gas adjustment for operation {eth_tx_id}, previously sent fee {previous_fee:?} \
+                 has reached the fee cap"
+            );
+            return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
+        }
         let next_block_minimal_base_fee = self.gas_adjuster.get_next_block_minimal_base_fee();
 
         if base_fee_per_gas <= next_block_minimal_base_fee.min(previous_base_fee) {
@@ -175,11 +196,26 @@ where
             return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
         }
 
-        // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction underpriced" error.
-        Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
-            .max(self.gas_adjuster.get_priority_fee()))
+        // Increase `priority_fee_per_gas` (by 20% by default) to prevent "replacement transaction underpriced" error.
+        let priority_fee_per_gas = policy
+            .bump_priority_fee(previous_priority_fee)
+            .max(self.gas_adjuster.get_priority_fee());
+        let fee = EthFee {
+            base_fee_per_gas,
+            priority_fee_per_gas,
+        };
+        // Nodes also require `max_fee_per_gas` to grow by at least 10%, so the fee is bumped before capping.
+        let Some(fee) = policy.replacement_fee(previous_fee, fee) else {
+            tracing::info!(
+                "Skipping gas adjustment for operation {eth_tx_id}, replacing previously sent fee {previous_fee:?} \
+                 would exceed the fee cap"
+            );
+            return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
+        };
+        Ok(fee)
     }
 
+    #[tracing::instrument(skip_all, fields(eth_tx_id = tx.id, tx_type = %tx.tx_type))]
     pub(crate) async fn send_eth_tx(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -256,6 +292,34 @@ where
         }
     }
 
+    /// Checks whether the tx is signed by the current operator. Txs signed by a previous operator
+    /// (i.e., before the operator address was rotated) cannot be resent.
+    fn is_signed_by_operator(&self, tx: &EthTx) -> bool {
+        tx.from_addr
+            .map_or(true, |addr| addr == self.ethereum_gateway.sender_account())
+    }
+
+    async fn get_previous_operator_nonce(
+        &self,
+        account: Address,
+        block_numbers: L1BlockNumbers,
+    ) -> Result<OperatorNonce, ETHSenderError> {
+        let finalized = self
+            .ethereum_gateway
+            .nonce_at_for_account(account, block_numbers.finalized.0.into(), "eth_tx_manager")
+            .await?
+            .as_u32()
+            .into();
+
+        let latest = self
+            .ethereum_gateway
+            .nonce_at_for_account(account, block_numbers.latest.0.into(), "eth_tx_manager")
+            .await?
+            .as_u32()
+            .into();
+        Ok(OperatorNonce { finalized, latest })
+    }
+
     async fn get_operator_nonce(
         &self,
         block_numbers: L1BlockNumbers,
@@ -328,14 +392,41 @@ where
             operator_nonce.finalized,
         );
 
+        // Nonces of previous operators that have signed some of inflight txs
+        let mut previous_operator_nonces = HashMap::new();
         // Not confirmed transactions, ordered by nonce
         for tx in inflight_txs {
             tracing::trace!("Checking tx id: {}", tx.id,);
 
+            let is_signed_by_operator = self.is_signed_by_operator(&tx);
+            let operator_nonce = match tx.from_addr {
+                Some(from_addr) if !is_signed_by_operator => {
+                    if let Some(&nonce) = previous_operator_nonces.get(&from_addr) {
+                        nonce
+                    } else {
+                        let nonce = self
+                            .get_previous_operator_nonce(from_addr, l1_block_numbers)
+                            .await?;
+                        previous_operator_nonces.insert(from_addr, nonce);
+                        nonce
+                    }
+                }
+                _ => operator_nonce,
+            };
+
             // If the `operator_nonce.latest` <= `tx.nonce`, this means
             // that `tx` is not mined and we should resend it.
             // We only resend the first unmined transaction.
             if operator_nonce.latest <= tx.nonce {
+                if !is_signed_by_operator {
+                    tracing::info!(
+                        "Waiting for tx {} signed by previous operator {:?} to be mined; \
+                         it cannot be resent by the current operator",
+                        tx.id,
+                        tx.from_addr
+                    );
+                    return Ok(None);
+                }
                 // None means txs hasn't been sent yet
                 let first_sent_at_block = storage
                     .eth_sender_dal()
@@ -497,6 +588,7 @@ where
         panic!("We can't operate after tx fail");
     }
 
+    #[tracing::instrument(skip_all, fields(eth_tx_id = tx.id, tx_type = %tx.tx_type))]
     pub async fn confirm_tx(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -592,12 +684,22 @@ where
         storage: &mut StorageProcessor<'_>,
         current_block: L1BlockNumber,
     ) {
-        let number_inflight_txs = storage
-            .eth_sender_dal()
-            .get_inflight_txs()
-            .await
-            .unwrap()
-            .len();
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
+        // If the operator address was rotated, txs sent by the previous operator must be mined
+        // before sending txs from the current address; otherwise, the new txs may be mined
+        // out of order and fail.
+        if let Some(tx) = inflight_txs
+            .iter()
+            .find(|tx| !self.is_signed_by_operator(tx))
+        {
+            tracing::debug!(
+                "Not sending new txs until inflight tx {} signed by previous operator {:?} is mined",
+                tx.id,
+                tx.from_addr
+            );
+            return;
+        }
+        let number_inflight_txs = inflight_txs.len();
         let number_of_available_slots_for_eth_txs = self
             .config
             .max_txs_in_flight
@@ -612,6 +714,15 @@ where
                 .unwrap();
 
             for tx in new_eth_tx {
+                if !self.is_signed_by_operator(&tx) {
+                    // The tx will be reassigned to the current operator by `EthTxAggregator`.
+                    tracing::debug!(
+                        "Not sending tx {} created for another operator {:?}",
+                        tx.id,
+                        tx.from_addr
+                    );
+                    break;
+                }
                 let _ = self.send_eth_tx(storage, &tx, 0, current_block).await;
             }
         }
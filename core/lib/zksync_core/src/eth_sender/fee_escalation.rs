@@ -0,0 +1,621 @@
+//! Fee escalation policies for resubmitted L1 transactions.
+
+use anyhow::Context as _;
+use zksync_config::{
+    configs::eth_sender::{FeeEscalationConfig, FeeEscalationPolicyConfig, FeeEscalationStrategy},
+    ETHSenderConfig, GasAdjusterConfig,
+};
+use zksync_eth_client::{clients::http::QueryClient, EthInterface};
+use zksync_types::aggregated_operations::AggregatedActionType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct EthFee {
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+}
+
+impl EthFee {
+    /// Returns the `max_fee_per_gas` value (EIP-1559) of the transaction.
+    pub fn max_fee_per_gas(&self) -> u64 {
+        self.base_fee_per_gas + self.priority_fee_per_gas
+    }
+}
+
+/// Minimum increase of fees of a replacement transaction accepted by nodes (e.g., Geth), in percent.
+const MIN_REPLACEMENT_FEE_BUMP_PERCENT: u64 = 10;
+
+/// Returns the minimum fee value for a replacement transaction, rounding up.
+fn min_replacement_fee(previous_fee: u64) -> u64 {
+    previous_fee + (previous_fee * MIN_REPLACEMENT_FEE_BUMP_PERCENT + 99) / 100
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BaseFeeMultiplier {
+    Exponential {
+        initial: f64,
+        step: f64,
+    },
+    Linear {
+        initial: f64,
+        step: f64,
+    },
+    Schedule {
+        initial: f64,
+        steps: Vec<(u32, f64)>,
+    },
+}
+
+impl BaseFeeMultiplier {
+    fn get(&self, time_in_mempool: u32) -> f64 {
+        match self {
+            Self::Exponential { initial, step } => initial * step.powf(time_in_mempool.into()),
+            Self::Linear { initial, step } => initial * (1.0 + step * f64::from(time_in_mempool)),
+            Self::Schedule { initial, steps } => steps
+                .iter()
+                .take_while(|(blocks, _)| *blocks <= time_in_mempool)
+                .last()
+                .map_or(*initial, |(_, multiplier)| *multiplier),
+        }
+    }
+}
+
+/// Fee escalation policy for L1 transactions of a certain type; see [`FeeEscalationPolicyConfig`]
+/// for the description of parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEscalationPolicy {
+    multiplier: BaseFeeMultiplier,
+    priority_fee_bump_percent: u64,
+    max_fee_per_gas: Option<u64>,
+}
+
+impl FeeEscalationPolicy {
+    fn new(
+        config: &FeeEscalationPolicyConfig,
+        gas_adjuster_config: &GasAdjusterConfig,
+    ) -> anyhow::Result<Self> {
+        let initial = config
+            .initial_multiplier
+            .unwrap_or(gas_adjuster_config.pricing_formula_parameter_a);
+        anyhow::ensure!(
+            initial > 0.0,
+            "initial base fee multiplier must be positive, got {initial}"
+        );
+        let default_step = gas_adjuster_config.pricing_formula_parameter_b;
+
+        let multiplier = match config.strategy {
+            FeeEscalationStrategy::Exponential => {
+                let step = config.step.unwrap_or(default_step);
+                anyhow::ensure!(
+                    step >= 1.0,
+                    "exponential escalation step must be at least 1, got {step}"
+                );
+                BaseFeeMultiplier::Exponential { initial, step }
+            }
+            FeeEscalationStrategy::Linear => {
+                let step = config.step.unwrap_or(default_step - 1.0);
+                anyhow::ensure!(
+                    step >= 0.0,
+                    "linear escalation step must be non-negative, got {step}"
+                );
+                BaseFeeMultiplier::Linear { initial, step }
+            }
+            FeeEscalationStrategy::Schedule => {
+                anyhow::ensure!(
+                    config.schedule_blocks.len() == config.schedule_multipliers.len(),
+                    "escalation schedule has {} block thresholds, but {} multipliers",
+                    config.schedule_blocks.len(),
+                    config.schedule_multipliers.len()
+                );
+                anyhow::ensure!(
+                    config
+                        .schedule_blocks
+                        .windows(2)
+                        .all(|window| window[0] < window[1]),
+                    "escalation schedule block thresholds must be strictly increasing: {:?}",
+                    config.schedule_blocks
+                );
+                let steps: Vec<_> = config
+                    .schedule_blocks
+                    .iter()
+                    .copied()
+                    .zip(config.schedule_multipliers.iter().copied())
+                    .collect();
+                let mut prev_multiplier = initial;
+                for &(blocks, multiplier) in &steps {
+                    anyhow::ensure!(
+                        multiplier >= prev_multiplier,
+                        "escalation schedule multiplier {multiplier} at {blocks} blocks is lower than the previous one"
+                    );
+                    prev_multiplier = multiplier;
+                }
+                BaseFeeMultiplier::Schedule { initial, steps }
+            }
+        };
+
+        Ok(Self {
+            multiplier,
+            priority_fee_bump_percent: config.priority_fee_bump_percent(),
+            max_fee_per_gas: config.max_fee_per_gas,
+        })
+    }
+
+    /// Returns the base fee for a transaction that has spent `time_in_mempool` L1 blocks in the mempool.
+    pub fn base_fee(&self, median_base_fee: u64, time_in_mempool: u32) -> u64 {
+        (median_base_fee as f64 * self.multiplier.get(time_in_mempool)) as u64
+    }
+
+    /// Returns the minimum priority fee for a transaction replacing one with `previous_priority_fee`.
+    pub fn bump_priority_fee(&self, previous_priority_fee: u64) -> u64 {
+        previous_priority_fee + previous_priority_fee * self.priority_fee_bump_percent / 100 + 1
+    }
+
+    /// Caps the fee according to `max_fee_per_gas`. The priority fee is preserved if possible.
+    pub(super) fn cap_fee(&self, fee: EthFee) -> EthFee {
+        let Some(max_fee_per_gas) = self.max_fee_per_gas else {
+            return fee;
+        };
+        if fee.max_fee_per_gas() <= max_fee_per_gas {
+            return fee;
+        }
+        let priority_fee_per_gas = fee.priority_fee_per_gas.min(max_fee_per_gas);
+        EthFee {
+            base_fee_per_gas: max_fee_per_gas - priority_fee_per_gas,
+            priority_fee_per_gas,
+        }
+    }
+
+    /// Returns the fee for a transaction replacing one sent with `previous_fee`. Before capping, both the priority fee
+    /// and `max_fee_per_gas` of the suggested `fee` are raised to at least 110% of the previous values, since nodes
+    /// reject replacement transactions with smaller fee increases. Returns `None` if the increased fee exceeds the cap,
+    /// i.e., the transaction cannot be replaced.
+    pub(super) fn replacement_fee(&self, previous_fee: EthFee, fee: EthFee) -> Option<EthFee> {
+        let min_priority_fee_per_gas = min_replacement_fee(previous_fee.priority_fee_per_gas);
+        let min_max_fee_per_gas = min_replacement_fee(previous_fee.max_fee_per_gas());
+        let priority_fee_per_gas = fee.priority_fee_per_gas.max(min_priority_fee_per_gas);
+        let max_fee_per_gas =
+            (fee.base_fee_per_gas + priority_fee_per_gas).max(min_max_fee_per_gas);
+        let fee = self.cap_fee(EthFee {
+            base_fee_per_gas: max_fee_per_gas - priority_fee_per_gas,
+            priority_fee_per_gas,
+        });
+
+        let is_valid_replacement = fee.priority_fee_per_gas >= min_priority_fee_per_gas
+            && fee.max_fee_per_gas() >= min_max_fee_per_gas;
+        is_valid_replacement.then_some(fee)
+    }
+
+    /// Checks whether a transaction sent with `fee` has reached the `max_fee_per_gas` cap, so that it cannot
+    /// be replaced anymore.
+    pub(super) fn is_capped(&self, fee: EthFee) -> bool {
+        self.max_fee_per_gas.map_or(false, |max_fee_per_gas| {
+            fee.max_fee_per_gas() >= max_fee_per_gas
+        })
+    }
+
+    /// Simulates sending a transaction according to this policy with the specified history of L1 block base fees.
+    /// The transaction is sent at the first block, and then resubmitted each block according to the policy.
+    /// `median_base_fee` is used as the median base fee for the entire simulation and `priority_fee`
+    /// as the minimum priority fee.
+    ///
+    /// A transaction is considered to be included in the first block with the base fee not exceeding
+    /// the base fee of the latest submission.
+    pub fn simulate(
+        &self,
+        median_base_fee: u64,
+        priority_fee: u64,
+        base_fee_history: &[u64],
+    ) -> FeeEscalationOutcome {
+        let mut fee = self.cap_fee(EthFee {
+            base_fee_per_gas: self.base_fee(median_base_fee, 0),
+            priority_fee_per_gas: priority_fee,
+        });
+        let mut resubmissions = 0;
+        for (time_in_mempool, &block_base_fee) in base_fee_history.iter().enumerate() {
+            let time_in_mempool = time_in_mempool as u32;
+            if time_in_mempool > 0 && !self.is_capped(fee) {
+                let base_fee_per_gas = self.base_fee(median_base_fee, time_in_mempool);
+                // Mirrors the resubmission logic in `EthTxManager`: the transaction is only replaced
+                // if its base fee grows.
+                if base_fee_per_gas > fee.base_fee_per_gas {
+                    let new_fee = EthFee {
+                        base_fee_per_gas,
+                        priority_fee_per_gas: self
+                            .bump_priority_fee(fee.priority_fee_per_gas)
+                            .max(priority_fee),
+                    };
+                    if let Some(new_fee) = self.replacement_fee(fee, new_fee) {
+                        fee = new_fee;
+                        resubmissions += 1;
+                    }
+                }
+            }
+
+            if block_base_fee <= fee.base_fee_per_gas {
+                return FeeEscalationOutcome {
+                    included_after_blocks: Some(time_in_mempool),
+                    resubmissions,
+                    fee_per_gas: block_base_fee + fee.priority_fee_per_gas,
+                    max_fee_per_gas: fee.max_fee_per_gas(),
+                };
+            }
+        }
+        FeeEscalationOutcome {
+            included_after_blocks: None,
+            resubmissions,
+            fee_per_gas: 0,
+            max_fee_per_gas: fee.max_fee_per_gas(),
+        }
+    }
+}
+
+impl FeeEscalationPolicy {
+    /// Simulates sending a transaction at each block of `base_fee_history` after the first `median_window` blocks.
+    /// For each transaction, the median base fee is computed over the preceding `median_window` blocks,
+    /// similarly to `GasAdjuster`.
+    pub fn simulate_history(
+        &self,
+        base_fee_history: &[u64],
+        median_window: usize,
+        priority_fee: u64,
+    ) -> FeeEscalationReport {
+        let mut report = FeeEscalationReport::default();
+        let mut total_blocks_to_inclusion = 0_u64;
+        let mut total_fee_per_gas = 0_u128;
+        let mut window = Vec::with_capacity(median_window);
+        for start in median_window..base_fee_history.len() {
+            window.clear();
+            window.extend_from_slice(&base_fee_history[start - median_window..start]);
+            let median_base_fee = if window.is_empty() {
+                base_fee_history[start]
+            } else {
+                let median_idx = window.len() / 2;
+                *window.select_nth_unstable(median_idx).1
+            };
+
+            let outcome = self.simulate(median_base_fee, priority_fee, &base_fee_history[start..]);
+            report.simulated_txs += 1;
+            report.total_resubmissions += outcome.resubmissions;
+            if let Some(blocks) = outcome.included_after_blocks {
+                report.included_txs += 1;
+                report.max_blocks_to_inclusion = report.max_blocks_to_inclusion.max(blocks);
+                total_blocks_to_inclusion += u64::from(blocks);
+                total_fee_per_gas += u128::from(outcome.fee_per_gas);
+            }
+        }
+
+        if report.included_txs > 0 {
+            let included_txs = report.included_txs as f64;
+            report.avg_blocks_to_inclusion = total_blocks_to_inclusion as f64 / included_txs;
+            report.avg_fee_per_gas = total_fee_per_gas as f64 / included_txs;
+        }
+        report
+    }
+}
+
+/// Outcome of a [simulation](FeeEscalationPolicy::simulate()) of a fee escalation policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEscalationOutcome {
+    /// Number of L1 blocks after which the transaction is included, or `None` if it's not included
+    /// during the simulation.
+    pub included_after_blocks: Option<u32>,
+    /// Number of times the transaction was resubmitted.
+    pub resubmissions: usize,
+    /// Effective fee per gas paid by the transaction (base fee of the including block + priority fee), in wei.
+    /// 0 if the transaction is not included.
+    pub fee_per_gas: u64,
+    /// `max_fee_per_gas` of the latest submission, in wei.
+    pub max_fee_per_gas: u64,
+}
+
+/// Aggregated outcomes of [simulations](FeeEscalationPolicy::simulate_history()) of a fee escalation policy
+/// against historical base fees.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FeeEscalationReport {
+    /// Number of simulated transactions.
+    pub simulated_txs: usize,
+    /// Number of transactions included before the end of the history.
+    pub included_txs: usize,
+    /// Total number of resubmissions over all simulated transactions.
+    pub total_resubmissions: usize,
+    /// Average number of L1 blocks before a transaction is included.
+    pub avg_blocks_to_inclusion: f64,
+    /// Maximum number of L1 blocks before a transaction is included.
+    pub max_blocks_to_inclusion: u32,
+    /// Average effective fee per gas paid by included transactions, in wei.
+    pub avg_fee_per_gas: f64,
+}
+
+/// Fee escalation policies for all types of L1 transactions sent by the operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEscalationPolicies {
+    commit: FeeEscalationPolicy,
+    prove: FeeEscalationPolicy,
+    execute: FeeEscalationPolicy,
+}
+
+impl FeeEscalationPolicies {
+    /// Creates policies from the provided configs. The gas adjuster config provides default values
+    /// for the policy parameters.
+    pub fn new(
+        config: &FeeEscalationConfig,
+        gas_adjuster_config: &GasAdjusterConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            commit: FeeEscalationPolicy::new(&config.commit, gas_adjuster_config)
+                .context("invalid fee escalation policy for commit transactions")?,
+            prove: FeeEscalationPolicy::new(&config.prove, gas_adjuster_config)
+                .context("invalid fee escalation policy for prove transactions")?,
+            execute: FeeEscalationPolicy::new(&config.execute, gas_adjuster_config)
+                .context("invalid fee escalation policy for execute transactions")?,
+        })
+    }
+
+    /// Returns the policy for transactions of the specified type.
+    pub fn get(&self, tx_type: AggregatedActionType) -> &FeeEscalationPolicy {
+        match tx_type {
+            AggregatedActionType::Commit => &self.commit,
+            AggregatedActionType::PublishProofOnchain => &self.prove,
+            AggregatedActionType::Execute => &self.execute,
+        }
+    }
+}
+
+/// Dry-runs the configured fee escalation policies against the base fees of the last `block_count` L1 blocks
+/// and logs the results. Doesn't send any transactions.
+pub async fn simulate_fee_escalation(
+    config: &ETHSenderConfig,
+    eth_client_url: &str,
+    block_count: usize,
+) -> anyhow::Result<()> {
+    const COMPONENT: &str = "fee_escalation_simulation";
+
+    let policies = FeeEscalationPolicies::new(&config.fee_escalation, &config.gas_adjuster)?;
+    let eth_client = QueryClient::new(eth_client_url)?;
+    // Same as in `GasAdjuster`, the latest block is skipped since it may be missing on the node.
+    let current_block = eth_client
+        .block_number(COMPONENT)
+        .await
+        .context("failed getting L1 block number")?
+        .as_usize()
+        .saturating_sub(1);
+    let median_window = config.gas_adjuster.max_base_fee_samples.min(block_count);
+    let history = eth_client
+        .base_fee_history(current_block, median_window + block_count, COMPONENT)
+        .await
+        .context("failed getting L1 base fee history")?;
+    let median_window = median_window.min(history.len() / 2);
+    tracing::info!(
+        "Simulating fee escalation policies for {} L1 blocks up to #{current_block} \
+         (median base fee is computed over {median_window} blocks)",
+        history.len() - median_window
+    );
+
+    let priority_fee = config.gas_adjuster.default_priority_fee_per_gas;
+    for tx_type in [
+        AggregatedActionType::Commit,
+        AggregatedActionType::PublishProofOnchain,
+        AggregatedActionType::Execute,
+    ] {
+        let report = policies
+            .get(tx_type)
+            .simulate_history(&history, median_window, priority_fee);
+        tracing::info!("Fee escalation simulation for {tx_type} transactions: {report:?}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::ETHSenderConfig;
+
+    use super::*;
+
+    fn create_policy(config: FeeEscalationPolicyConfig) -> FeeEscalationPolicy {
+        let gas_adjuster_config = GasAdjusterConfig {
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.1,
+            ..ETHSenderConfig::for_tests().gas_adjuster
+        };
+        FeeEscalationPolicy::new(&config, &gas_adjuster_config).unwrap()
+    }
+
+    #[test]
+    fn default_policy_matches_gas_adjuster_formula() {
+        let policy = create_policy(FeeEscalationPolicyConfig::default());
+        for time_in_mempool in [0, 1, 10, 100] {
+            let expected = (1_000.0 * 1.5 * 1.1_f64.powf(time_in_mempool.into())) as u64;
+            assert_eq!(policy.base_fee(1_000, time_in_mempool), expected);
+        }
+        assert_eq!(policy.bump_priority_fee(100), 121);
+    }
+
+    #[test]
+    fn linear_and_schedule_strategies() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            strategy: FeeEscalationStrategy::Linear,
+            initial_multiplier: Some(1.0),
+            step: Some(0.5),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        assert_eq!(policy.base_fee(1_000, 0), 1_000);
+        assert_eq!(policy.base_fee(1_000, 3), 2_500);
+
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            strategy: FeeEscalationStrategy::Schedule,
+            initial_multiplier: Some(1.0),
+            schedule_blocks: vec![5, 10],
+            schedule_multipliers: vec![2.0, 4.0],
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let fees: Vec<_> = [0, 4, 5, 9, 10, 100]
+            .into_iter()
+            .map(|time_in_mempool| policy.base_fee(1_000, time_in_mempool))
+            .collect();
+        assert_eq!(fees, [1_000, 1_000, 2_000, 2_000, 4_000, 4_000]);
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let gas_adjuster_config = ETHSenderConfig::for_tests().gas_adjuster;
+        let invalid_configs = [
+            FeeEscalationPolicyConfig {
+                strategy: FeeEscalationStrategy::Schedule,
+                schedule_blocks: vec![5, 10],
+                schedule_multipliers: vec![2.0],
+                ..FeeEscalationPolicyConfig::default()
+            },
+            FeeEscalationPolicyConfig {
+                strategy: FeeEscalationStrategy::Schedule,
+                schedule_blocks: vec![10, 5],
+                schedule_multipliers: vec![2.0, 3.0],
+                ..FeeEscalationPolicyConfig::default()
+            },
+            FeeEscalationPolicyConfig {
+                strategy: FeeEscalationStrategy::Exponential,
+                step: Some(0.9),
+                ..FeeEscalationPolicyConfig::default()
+            },
+        ];
+        for config in invalid_configs {
+            FeeEscalationPolicy::new(&config, &gas_adjuster_config).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn capping_fee() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            max_fee_per_gas: Some(1_000),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let fee = EthFee {
+            base_fee_per_gas: 500,
+            priority_fee_per_gas: 100,
+        };
+        assert_eq!(policy.cap_fee(fee), fee);
+        assert!(!policy.is_capped(fee));
+
+        let capped_fee = policy.cap_fee(EthFee {
+            base_fee_per_gas: 2_000,
+            priority_fee_per_gas: 100,
+        });
+        assert_eq!(
+            capped_fee,
+            EthFee {
+                base_fee_per_gas: 900,
+                priority_fee_per_gas: 100,
+            }
+        );
+        assert!(policy.is_capped(capped_fee));
+    }
+
+    #[test]
+    fn replacement_fee_is_bumped_by_at_least_10_percent() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            priority_fee_bump_percent: Some(5),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let previous_fee = EthFee {
+            base_fee_per_gas: 1_000,
+            priority_fee_per_gas: 100,
+        };
+        let fee = EthFee {
+            base_fee_per_gas: 1_010,
+            priority_fee_per_gas: policy.bump_priority_fee(100),
+        };
+        assert_eq!(fee.priority_fee_per_gas, 106);
+        let replacement_fee = policy.replacement_fee(previous_fee, fee).unwrap();
+        assert_eq!(
+            replacement_fee,
+            EthFee {
+                base_fee_per_gas: 1_100,
+                priority_fee_per_gas: 110,
+            }
+        );
+
+        // Sufficiently increased fees are not changed.
+        let fee = EthFee {
+            base_fee_per_gas: 2_000,
+            priority_fee_per_gas: 200,
+        };
+        assert_eq!(policy.replacement_fee(previous_fee, fee), Some(fee));
+    }
+
+    #[test]
+    fn replacement_fee_respects_cap() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            max_fee_per_gas: Some(1_250),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let previous_fee = EthFee {
+            base_fee_per_gas: 1_000,
+            priority_fee_per_gas: 100,
+        };
+        let fee = EthFee {
+            base_fee_per_gas: 2_000,
+            priority_fee_per_gas: 121,
+        };
+        let replacement_fee = policy.replacement_fee(previous_fee, fee).unwrap();
+        assert_eq!(
+            replacement_fee,
+            EthFee {
+                base_fee_per_gas: 1_129,
+                priority_fee_per_gas: 121,
+            }
+        );
+
+        // The bumped fee exceeds the cap, so the transaction cannot be replaced.
+        let previous_fee = EthFee {
+            base_fee_per_gas: 1_050,
+            priority_fee_per_gas: 100,
+        };
+        assert_eq!(policy.replacement_fee(previous_fee, fee), None);
+    }
+
+    #[test]
+    fn simulating_policy() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            strategy: FeeEscalationStrategy::Linear,
+            initial_multiplier: Some(1.0),
+            step: Some(0.5),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let outcome = policy.simulate(100, 10, &[300, 300, 180, 120]);
+        assert_eq!(
+            outcome,
+            FeeEscalationOutcome {
+                included_after_blocks: Some(2),
+                resubmissions: 2,
+                fee_per_gas: 180 + 16,
+                max_fee_per_gas: 200 + 16,
+            }
+        );
+
+        let capped_policy = create_policy(FeeEscalationPolicyConfig {
+            strategy: FeeEscalationStrategy::Exponential,
+            initial_multiplier: Some(1.0),
+            step: Some(2.0),
+            max_fee_per_gas: Some(150),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let outcome = capped_policy.simulate(100, 10, &[300; 10]);
+        assert_eq!(outcome.included_after_blocks, None);
+        assert_eq!(outcome.max_fee_per_gas, 150);
+    }
+
+    #[test]
+    fn simulating_policy_against_history() {
+        let policy = create_policy(FeeEscalationPolicyConfig {
+            strategy: FeeEscalationStrategy::Linear,
+            initial_multiplier: Some(1.0),
+            step: Some(1.0),
+            ..FeeEscalationPolicyConfig::default()
+        });
+        let history = [100, 100, 100, 250, 100, 1_000];
+        let report = policy.simulate_history(&history, 2, 0);
+        assert_eq!(report.simulated_txs, 4);
+        // The last transaction is never included.
+        assert_eq!(report.included_txs, 3);
+        assert_eq!(report.total_resubmissions, 1);
+        assert_eq!(report.max_blocks_to_inclusion, 1);
+    }
+}
@@ -40,6 +40,43 @@ pub(super) struct AggregationReasonLabels {
     op: ActionTypeLabel,
 }
 
+/// Decision of the cost-based aggregation planner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum PlannerDecision {
+    /// Ready L1 batches are published.
+    Publish,
+    /// The planner waits for more L1 batches to amortize the L1 transaction cost.
+    Wait,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct PlannerDecisionLabels {
+    op: ActionTypeLabel,
+    decision: PlannerDecision,
+}
+
+impl From<(AggregatedActionType, PlannerDecision)> for PlannerDecisionLabels {
+    fn from((op, decision): (AggregatedActionType, PlannerDecision)) -> Self {
+        Self {
+            op: op.into(),
+            decision,
+        }
+    }
+}
+
+/// Decision of the execute scheduling policy for a range of L1 batches ready to be executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "decision", rename_all = "snake_case")]
+pub(super) enum ExecuteSchedulingDecision {
+    /// L1 batches are executed.
+    Execute,
+    /// Execution is postponed because of the high L1 gas price.
+    Postpone,
+    /// L1 batches are executed despite the high L1 gas price because execution was postponed for too long.
+    ForceExecute,
+}
+
 impl From<(AggregatedActionType, &'static str)> for AggregationReasonLabels {
     fn from((op, r#type): (AggregatedActionType, &'static str)) -> Self {
         Self {
@@ -53,6 +90,8 @@ impl From<(AggregatedActionType, &'static str)> for AggregationReasonLabels {
 const FEE_BUCKETS: Buckets = Buckets::values(&[
     1e7, 2e7, 5e7, 1e8, 2e8, 5e8, 1e9, 2e9, 5e9, 1e10, 2e10, 5e10, 1e11, 2e11, 5e11,
 ]);
+/// Buckets for the L1 batch execution lag (1 minute – ~1 week).
+const EXECUTE_LAG_BUCKETS: Buckets = Buckets::exponential(60.0..=604_800.0, 2.0);
 /// Roughly exponential buckets for gas (10k – 50M).
 const GAS_BUCKETS: Buckets =
     Buckets::values(&[1e4, 2e4, 5e4, 1e5, 2e5, 5e5, 1e6, 2e6, 5e6, 1e7, 2e7, 5e7]);
@@ -87,6 +126,26 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Number of decisions made by the cost-based aggregation planner.
+    pub planner_decisions: Family<PlannerDecisionLabels, Counter>,
+    /// Minimum number of L1 batches for an operation required by the cost-based aggregation planner
+    /// at the current L1 gas price.
+    pub planner_min_l1_batches: Family<ActionTypeLabel, Gauge<u64>>,
+    /// L1 gas price used by the cost-based aggregation planner in its latest decision, in wei.
+    pub planner_l1_gas_price: Gauge<u64>,
+    /// Fixed cost of an L1 transaction per ready L1 batch estimated by the cost-based aggregation planner
+    /// in its latest decision for an operation, in wei.
+    pub planner_overhead_per_l1_batch: Family<ActionTypeLabel, Gauge<u64>>,
+    /// Age of L1 batches at the moment an execute operation is created for them.
+    #[metrics(buckets = EXECUTE_LAG_BUCKETS)]
+    pub l1_batch_execute_lag: Histogram<Duration>,
+    /// Number of L1 batches that are old enough and ready to be executed, but not executed yet.
+    /// Capped by `max_aggregated_blocks_to_execute`.
+    pub ready_for_execute_l1_batches: Gauge<usize>,
+    /// Number of decisions made by the execute scheduling policy.
+    pub execute_scheduling_decisions: Family<ExecuteSchedulingDecision, Counter>,
+    /// L1 gas price observed by the execute scheduling policy in its latest decision, in wei.
+    pub execute_gate_l1_gas_price: Gauge<u64>,
 }
 
 impl EthSenderMetrics {
@@ -2,14 +2,25 @@ mod aggregator;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
+mod fee_escalation;
 mod metrics;
 mod publish_criterion;
+mod settlement_layer;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::ETHSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    fee_escalation::{
+        simulate_fee_escalation, FeeEscalationOutcome, FeeEscalationPolicies, FeeEscalationPolicy,
+        FeeEscalationReport,
+    },
+    settlement_layer::{
+        settlement_layer_from_config, GatewaySettlementLayer, L1SettlementLayer, SettlementLayer,
+    },
 };
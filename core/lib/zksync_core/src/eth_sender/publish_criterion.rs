@@ -1,14 +1,16 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
 use chrono::Utc;
 use zksync_dal::StorageProcessor;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, L1BatchNumber,
+    aggregated_operations::AggregatedActionType,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    L1BatchNumber,
 };
 
-use super::metrics::METRICS;
-use crate::gas_tracker::agg_l1_batch_base_cost;
+use super::metrics::{ExecuteSchedulingDecision, PlannerDecision, METRICS};
+use crate::{gas_tracker::agg_l1_batch_base_cost, l1_gas_price::L1TxParamsProvider};
 
 #[async_trait]
 pub trait L1BatchPublishCriterion: fmt::Debug + Send + Sync {
@@ -197,6 +199,8 @@ impl L1BatchPublishCriterion for GasCriterion {
 pub struct DataSizeCriterion {
     pub op: AggregatedActionType,
     pub data_limit: usize,
+    /// Commitment mode determining whether L1 batch pubdata is a part of the commit data.
+    pub commitment_mode: L1BatchCommitmentMode,
 }
 
 #[async_trait]
@@ -215,12 +219,21 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
         let mut data_size_left = self.data_limit - STORED_BLOCK_INFO_SIZE;
 
         for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
-            if data_size_left < l1_batch.l1_commit_data_size() {
+            let commit_data_size = l1_batch
+                .l1_commit_data_size_for_mode(self.commitment_mode)
+                .unwrap_or_else(|err| {
+                    // The commitment mode is checked against pending L1 batches on startup,
+                    // see `Aggregator::validate_commitment_mode()`.
+                    panic!(
+                        "Cannot get commit data for L1 batch #{}: {err:#}",
+                        l1_batch.header.number
+                    )
+                });
+            if data_size_left < commit_data_size {
                 if index == 0 {
                     panic!(
-                        "L1 batch #{} requires {} data, which is more than the range limit of {}",
+                        "L1 batch #{} requires {commit_data_size} data, which is more than the range limit of {}",
                         l1_batch.header.number,
-                        l1_batch.l1_commit_data_size(),
                         self.data_limit
                     );
                 }
@@ -236,9 +249,150 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
                 METRICS.block_aggregation_reason[&(self.op, "data_size").into()].inc();
                 return Some(output);
             }
-            data_size_left -= l1_batch.l1_commit_data_size();
+            data_size_left -= commit_data_size;
         }
 
         None
     }
 }
+
+/// Criterion implementing the cost-based aggregation planner. The L1 batch range is published once the fixed cost
+/// of the L1 transaction (i.e., the base cost of the operation not attributed to any L1 batch) at the current
+/// L1 gas price, split among the ready L1 batches, doesn't exceed `max_overhead_per_l1_batch`.
+///
+/// Thus, fewer L1 batches are aggregated when L1 gas is cheap, and more batches are aggregated when it's expensive.
+/// Only ready L1 batches are considered (e.g., batches with available proofs for the prove operation); the upper bound
+/// on the range size is provided by other criteria, such as [`NumberCriterion`] and [`GasCriterion`],
+/// and liveness is ensured by [`TimestampDeadlineCriterion`].
+pub struct CostCriterion {
+    pub op: AggregatedActionType,
+    /// Maximum fixed cost of the L1 transaction per L1 batch, in wei.
+    pub max_overhead_per_l1_batch: u64,
+    pub l1_tx_params: Arc<dyn L1TxParamsProvider + Send + Sync>,
+}
+
+impl fmt::Debug for CostCriterion {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CostCriterion")
+            .field("op", &self.op)
+            .field("max_overhead_per_l1_batch", &self.max_overhead_per_l1_batch)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CostCriterion {
+    /// Returns the minimum number of L1 batches to aggregate so that the fixed cost per batch fits into the limit.
+    fn min_l1_batch_count(&self, gas_price: u64) -> u64 {
+        let overhead = u128::from(agg_l1_batch_base_cost(self.op)) * u128::from(gas_price);
+        let max_overhead_per_l1_batch = u128::from(self.max_overhead_per_l1_batch.max(1));
+        let count = (overhead + max_overhead_per_l1_batch - 1) / max_overhead_per_l1_batch;
+        u64::try_from(count).unwrap_or(u64::MAX).max(1)
+    }
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for CostCriterion {
+    fn name(&self) -> &'static str {
+        "cost"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        _storage: &mut StorageProcessor<'_>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        _last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let first_l1_batch = consecutive_l1_batches.first()?;
+        let last_l1_batch = consecutive_l1_batches.last()?;
+        let batch_count = consecutive_l1_batches.len() as u64;
+
+        // Use the fee which a transaction would be sent with right now.
+        let gas_price = self.l1_tx_params.get_base_fee(0) + self.l1_tx_params.get_priority_fee();
+        let min_batch_count = self.min_l1_batch_count(gas_price);
+        let overhead_per_l1_batch =
+            u64::from(agg_l1_batch_base_cost(self.op)).saturating_mul(gas_price) / batch_count;
+        METRICS.planner_l1_gas_price.set(gas_price);
+        METRICS.planner_min_l1_batches[&self.op.into()].set(min_batch_count);
+        METRICS.planner_overhead_per_l1_batch[&self.op.into()].set(overhead_per_l1_batch);
+
+        if batch_count < min_batch_count {
+            METRICS.planner_decisions[&(self.op, PlannerDecision::Wait).into()].inc();
+            return None;
+        }
+        tracing::debug!(
+            "`cost` publish criterion (gas_price={gas_price}, min_batch_count={min_batch_count}) triggered for op {} \
+             with L1 batch range {:?}",
+            self.op,
+            first_l1_batch.header.number.0..=last_l1_batch.header.number.0
+        );
+        METRICS.planner_decisions[&(self.op, PlannerDecision::Publish).into()].inc();
+        METRICS.block_aggregation_reason[&(self.op, "cost").into()].inc();
+        Some(last_l1_batch.header.number)
+    }
+}
+
+/// Policy postponing execution of L1 batches while the L1 gas price is high. Unlike publish criteria,
+/// the gate can only veto publishing an L1 batch range selected by the criteria.
+///
+/// To ensure liveness, execution is postponed by at most `max_postpone_seconds` counting from the moment
+/// the oldest L1 batch in the range became old enough to be executed.
+pub struct ExecuteGasPriceGate {
+    /// Maximum L1 gas price (base fee + priority fee) to send execute transactions with, in wei.
+    pub max_l1_gas_price: u64,
+    /// Minimum age of L1 batches before execution, in seconds.
+    pub min_age_seconds: u64,
+    /// Maximum time by which execution can be postponed, in seconds.
+    pub max_postpone_seconds: u64,
+    pub l1_tx_params: Arc<dyn L1TxParamsProvider + Send + Sync>,
+}
+
+impl fmt::Debug for ExecuteGasPriceGate {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ExecuteGasPriceGate")
+            .field("max_l1_gas_price", &self.max_l1_gas_price)
+            .field("min_age_seconds", &self.min_age_seconds)
+            .field("max_postpone_seconds", &self.max_postpone_seconds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExecuteGasPriceGate {
+    /// Checks whether the specified L1 batches can be executed right now.
+    pub fn allows_execution(&self, l1_batches: &[L1BatchWithMetadata]) -> bool {
+        let Some(first_l1_batch) = l1_batches.first() else {
+            return true;
+        };
+        let gas_price = self.l1_tx_params.get_base_fee(0) + self.l1_tx_params.get_priority_fee();
+        METRICS.execute_gate_l1_gas_price.set(gas_price);
+        if gas_price <= self.max_l1_gas_price {
+            METRICS.execute_scheduling_decisions[&ExecuteSchedulingDecision::Execute].inc();
+            return true;
+        }
+
+        let oldest_l1_batch_age_seconds =
+            (Utc::now().timestamp() as u64).saturating_sub(first_l1_batch.header.timestamp);
+        let postponed_seconds = oldest_l1_batch_age_seconds.saturating_sub(self.min_age_seconds);
+        if postponed_seconds >= self.max_postpone_seconds {
+            tracing::info!(
+                "Executing L1 batches #{}..=#{} despite high L1 gas price ({gas_price} > {}) since execution \
+                 was postponed for {postponed_seconds}s",
+                first_l1_batch.header.number,
+                l1_batches.last().unwrap().header.number,
+                self.max_l1_gas_price
+            );
+            METRICS.execute_scheduling_decisions[&ExecuteSchedulingDecision::ForceExecute].inc();
+            return true;
+        }
+
+        tracing::debug!(
+            "Postponing execution of L1 batches #{}..=#{} because of high L1 gas price ({gas_price} > {})",
+            first_l1_batch.header.number,
+            l1_batches.last().unwrap().header.number,
+            self.max_l1_gas_price
+        );
+        METRICS.execute_scheduling_decisions[&ExecuteSchedulingDecision::Postpone].inc();
+        false
+    }
+}
@@ -0,0 +1,301 @@
+//! Chains that L1 batches can be settled on.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::Context as _;
+use zksync_config::{
+    configs::eth_sender::{SenderConfig, SettlementLayerKind},
+    ContractsConfig, ETHClientConfig,
+};
+use zksync_types::{
+    aggregated_operations::AggregatedOperation,
+    ethabi::{Function, Token},
+    Address, L2ChainId, U256,
+};
+
+use super::zksync_functions::ZkSyncFunctions;
+use crate::shared_bridge::{shared_bridge_function, ChainL1Contracts};
+
+/// Chain that aggregated operations (commit, prove and execute) for L1 batches are sent to.
+///
+/// The settlement layer defines where eth txs are sent and how they are encoded; the rest of the L1 sender
+/// (aggregation, nonce management, resending and confirmation tracking) is chain-agnostic.
+pub trait SettlementLayer: fmt::Debug + Send + Sync {
+    fn kind(&self) -> SettlementLayerKind;
+
+    /// Returns the config to connect to the settlement layer node with.
+    fn client_config(&self) -> ETHClientConfig;
+
+    /// Returns the address of the diamond proxy that settles L1 batches of this chain.
+    fn diamond_proxy_address(&self) -> Address;
+
+    /// Returns the address that eth txs are sent to.
+    fn validator_timelock_address(&self) -> Address;
+
+    /// Returns the address of the Multicall3 contract used to query the diamond proxy.
+    fn multicall3_address(&self) -> Address;
+
+    /// Encodes calldata for the aggregated operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation cannot be settled with the current version of contracts,
+    /// or if the operation is malformed.
+    fn encode_operation(
+        &self,
+        op: &AggregatedOperation,
+        contracts_are_pre_boojum: bool,
+    ) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Creates the settlement layer specified in the L1 sender config for the specified chain.
+pub fn settlement_layer_from_config(
+    config: &SenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+    chain: &ChainL1Contracts,
+) -> anyhow::Result<Arc<dyn SettlementLayer>> {
+    Ok(match config.settlement_layer {
+        SettlementLayerKind::L1 => Arc::new(L1SettlementLayer::new(
+            chain,
+            contracts_config,
+            eth_client_config,
+        )?),
+        SettlementLayerKind::Gateway => {
+            Arc::new(GatewaySettlementLayer::new(config, chain.chain_id)?)
+        }
+    })
+}
+
+/// Executor functions of a settlement layer shared among multiple chains. These functions have the `SharedBridge`
+/// suffix and take the L2 chain ID as the first argument; other arguments are the same as for single-chain
+/// executor functions. Only post-boojum batches can be settled with these functions.
+#[derive(Debug)]
+struct SharedBridgeFunctions {
+    l2_chain_id: L2ChainId,
+    commit: Function,
+    prove: Function,
+    execute: Function,
+}
+
+impl SharedBridgeFunctions {
+    fn new(l2_chain_id: L2ChainId) -> anyhow::Result<Self> {
+        let functions = ZkSyncFunctions::default();
+        let shared_bridge_function = |function: Option<Function>| {
+            let function = function.context("missing post-boojum ABI for an executor function")?;
+            anyhow::Ok(shared_bridge_function(function))
+        };
+        Ok(Self {
+            l2_chain_id,
+            commit: shared_bridge_function(functions.post_boojum_commit)?,
+            prove: shared_bridge_function(functions.post_boojum_prove)?,
+            execute: shared_bridge_function(functions.post_boojum_execute)?,
+        })
+    }
+
+    fn encode_operation(&self, op: &AggregatedOperation) -> anyhow::Result<Vec<u8>> {
+        let (f, args) = match op {
+            AggregatedOperation::Commit(op) => (&self.commit, op.get_eth_tx_args()?),
+            AggregatedOperation::PublishProofOnchain(op) => (&self.prove, op.get_eth_tx_args()?),
+            AggregatedOperation::Execute(op) => (&self.execute, op.get_eth_tx_args()),
+        };
+        let chain_id = Token::Uint(U256::from(self.l2_chain_id.as_u64()));
+        let args: Vec<_> = [chain_id].into_iter().chain(args).collect();
+        f.encode_input(&args)
+            .context("failed to encode transaction data")
+    }
+}
+
+/// Settlement on Ethereum L1.
+///
+/// If the chain is registered in the shared bridge, the validator timelock is shared among chains,
+/// so eth txs are encoded using shared bridge executor functions.
+#[derive(Debug)]
+pub struct L1SettlementLayer {
+    client_config: ETHClientConfig,
+    diamond_proxy_address: Address,
+    validator_timelock_address: Address,
+    multicall3_address: Address,
+    functions: ZkSyncFunctions,
+    shared_bridge_functions: Option<SharedBridgeFunctions>,
+}
+
+impl L1SettlementLayer {
+    pub fn new(
+        chain: &ChainL1Contracts,
+        contracts_config: &ContractsConfig,
+        eth_client_config: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        let shared_bridge_functions = if chain.is_shared_bridge() {
+            Some(SharedBridgeFunctions::new(chain.chain_id)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            client_config: eth_client_config.clone(),
+            diamond_proxy_address: chain.diamond_proxy_addr,
+            validator_timelock_address: contracts_config.validator_timelock_addr,
+            multicall3_address: contracts_config.l1_multicall3_addr,
+            functions: ZkSyncFunctions::default(),
+            shared_bridge_functions,
+        })
+    }
+}
+
+impl SettlementLayer for L1SettlementLayer {
+    fn kind(&self) -> SettlementLayerKind {
+        SettlementLayerKind::L1
+    }
+
+    fn client_config(&self) -> ETHClientConfig {
+        self.client_config.clone()
+    }
+
+    fn diamond_proxy_address(&self) -> Address {
+        self.diamond_proxy_address
+    }
+
+    fn validator_timelock_address(&self) -> Address {
+        self.validator_timelock_address
+    }
+
+    fn multicall3_address(&self) -> Address {
+        self.multicall3_address
+    }
+
+    fn encode_operation(
+        &self,
+        op: &AggregatedOperation,
+        contracts_are_pre_boojum: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        let operation_is_pre_boojum = op.protocol_version().is_pre_boojum();
+        if let Some(functions) = &self.shared_bridge_functions {
+            anyhow::ensure!(
+                !contracts_are_pre_boojum && !operation_is_pre_boojum,
+                "Pre-boojum L1 batches cannot be settled via the shared bridge"
+            );
+            return functions.encode_operation(op);
+        }
+
+        // For "commit" and "prove" operations it's necessary that the contracts are of the same version as L1 batches are.
+        // For "execute" it's not required, i.e. we can "execute" pre-boojum batches with post-boojum contracts.
+        match &op {
+            AggregatedOperation::Commit(op) => {
+                anyhow::ensure!(
+                    contracts_are_pre_boojum == operation_is_pre_boojum,
+                    "L1 batches must be committed with contracts of the same version"
+                );
+                let f = if contracts_are_pre_boojum {
+                    &self.functions.pre_boojum_commit
+                } else {
+                    self.functions
+                        .post_boojum_commit
+                        .as_ref()
+                        .context("missing ABI for commitBatches")?
+                };
+                f.encode_input(&op.get_eth_tx_args()?)
+            }
+            AggregatedOperation::PublishProofOnchain(op) => {
+                anyhow::ensure!(
+                    contracts_are_pre_boojum == operation_is_pre_boojum,
+                    "L1 batches must be proven with contracts of the same version"
+                );
+                let f = if contracts_are_pre_boojum {
+                    &self.functions.pre_boojum_prove
+                } else {
+                    self.functions
+                        .post_boojum_prove
+                        .as_ref()
+                        .context("missing ABI for proveBatches")?
+                };
+                f.encode_input(&op.get_eth_tx_args()?)
+            }
+            AggregatedOperation::Execute(op) => {
+                let f = if contracts_are_pre_boojum {
+                    &self.functions.pre_boojum_execute
+                } else {
+                    self.functions
+                        .post_boojum_execute
+                        .as_ref()
+                        .context("missing ABI for executeBatches")?
+                };
+                f.encode_input(&op.get_eth_tx_args())
+            }
+        }
+        .context("failed to encode transaction data")
+    }
+}
+
+/// Settlement on a ZK gateway chain.
+///
+/// The gateway settles batches of multiple chains, so eth txs are encoded using shared bridge executor functions.
+/// Only post-boojum batches can be settled on the gateway.
+#[derive(Debug)]
+pub struct GatewaySettlementLayer {
+    client_config: ETHClientConfig,
+    diamond_proxy_address: Address,
+    validator_timelock_address: Address,
+    multicall3_address: Address,
+    functions: SharedBridgeFunctions,
+}
+
+impl GatewaySettlementLayer {
+    pub fn new(config: &SenderConfig, l2_chain_id: L2ChainId) -> anyhow::Result<Self> {
+        let client_config = ETHClientConfig {
+            chain_id: config
+                .gateway_chain_id
+                .context("gateway settlement layer requires a chain ID")?,
+            web3_url: config
+                .gateway_url
+                .clone()
+                .context("gateway settlement layer requires a URL")?,
+        };
+        Ok(Self {
+            client_config,
+            diamond_proxy_address: config
+                .gateway_diamond_proxy_addr
+                .context("gateway settlement layer requires a diamond proxy address")?,
+            validator_timelock_address: config
+                .gateway_validator_timelock_addr
+                .context("gateway settlement layer requires a validator timelock address")?,
+            multicall3_address: config
+                .gateway_multicall3_addr
+                .context("gateway settlement layer requires a Multicall3 address")?,
+            functions: SharedBridgeFunctions::new(l2_chain_id)?,
+        })
+    }
+}
+
+impl SettlementLayer for GatewaySettlementLayer {
+    fn kind(&self) -> SettlementLayerKind {
+        SettlementLayerKind::Gateway
+    }
+
+    fn client_config(&self) -> ETHClientConfig {
+        self.client_config.clone()
+    }
+
+    fn diamond_proxy_address(&self) -> Address {
+        self.diamond_proxy_address
+    }
+
+    fn validator_timelock_address(&self) -> Address {
+        self.validator_timelock_address
+    }
+
+    fn multicall3_address(&self) -> Address {
+        self.multicall3_address
+    }
+
+    fn encode_operation(
+        &self,
+        op: &AggregatedOperation,
+        contracts_are_pre_boojum: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        anyhow::ensure!(
+            !contracts_are_pre_boojum && !op.protocol_version().is_pre_boojum(),
+            "Pre-boojum L1 batches cannot be settled on the gateway"
+        );
+        self.functions.encode_operation(op)
+    }
+}
@@ -1,32 +1,45 @@
-use std::sync::{atomic::Ordering, Arc};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
 use zksync_config::{
-    configs::eth_sender::{ProofSendingMode, SenderConfig},
-    ContractsConfig, ETHSenderConfig, GasAdjusterConfig,
+    configs::eth_sender::{ProofSendingMode, SenderConfig, SettlementLayerKind},
+    ContractsConfig, ETHClientConfig, ETHSenderConfig, GasAdjusterConfig,
 };
-use zksync_contracts::BaseSystemContractsHashes;
+use zksync_contracts::{zksync_contract, BaseSystemContractsHashes};
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{clients::mock::MockEthereum, EthInterface};
+use zksync_eth_client::{clients::mock::MockEthereum, BoundEthInterface, EthInterface};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     aggregated_operations::{
-        AggregatedOperation, L1BatchCommitOperation, L1BatchExecuteOperation, L1BatchProofOperation,
+        AggregatedActionType, AggregatedOperation, FflonkL1BatchProofForL1, L1BatchCommitOperation,
+        L1BatchExecuteOperation, L1BatchProof, L1BatchProofOperation,
     },
+    basic_fri_types::ProofCompressionBackend,
     block::L1BatchHeader,
-    commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
-    ethabi::Token,
+    commitment::{
+        L1BatchCommitmentMode, L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata,
+    },
+    ethabi::{self, ParamType, Token},
     helpers::unix_timestamp_ms,
+    protocol_version::ProtocolVersion,
     web3::contract::Error,
-    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
+    Address, L1BatchNumber, L1BlockNumber, L2ChainId, ProtocolVersionId, H256, U256,
 };
 
 use crate::{
     eth_sender::{
-        eth_tx_manager::L1BlockNumbers, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
+        eth_tx_manager::L1BlockNumbers,
+        publish_criterion::{CostCriterion, ExecuteGasPriceGate, L1BatchPublishCriterion},
+        settlement_layer_from_config, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
+        FeeEscalationPolicies, GatewaySettlementLayer, L1SettlementLayer, SettlementLayer,
     },
-    l1_gas_price::GasAdjuster,
+    gas_tracker::agg_l1_batch_base_cost,
+    l1_gas_price::{GasAdjuster, L1GasPriceProvider, L1TxParamsProvider},
+    shared_bridge::ChainL1Contracts,
 };
 
 // Alias to conveniently call static methods of ETHSender.
@@ -48,6 +61,22 @@ static DUMMY_OPERATION: Lazy<AggregatedOperation> = Lazy::new(|| {
     })
 });
 
+/// Creates an L1 settlement layer with random contract addresses.
+fn l1_settlement_layer(contracts_config: &ContractsConfig) -> Arc<dyn SettlementLayer> {
+    let contracts_config = ContractsConfig {
+        validator_timelock_addr: Address::random(),
+        diamond_proxy_addr: Address::random(),
+        ..contracts_config.clone()
+    };
+    let eth_client_config = ETHClientConfig {
+        chain_id: 9,
+        web3_url: "http://127.0.0.1:8545".to_owned(),
+    };
+    let chain =
+        ChainL1Contracts::standalone(L2ChainId::default(), contracts_config.diamond_proxy_addr);
+    Arc::new(L1SettlementLayer::new(&chain, &contracts_config, &eth_client_config).unwrap())
+}
+
 #[derive(Debug)]
 struct EthSenderTester {
     conn: ConnectionPool,
@@ -88,18 +117,16 @@ impl EthSenderTester {
             .block_number
             .fetch_add(Self::WAIT_CONFIRMATIONS, Ordering::Relaxed);
 
+        let gas_adjuster_config = GasAdjusterConfig {
+            max_base_fee_samples: Self::MAX_BASE_FEE_SAMPLES,
+            pricing_formula_parameter_a: 3.0,
+            pricing_formula_parameter_b: 2.0,
+            ..eth_sender_config.gas_adjuster
+        };
         let gas_adjuster = Arc::new(
-            GasAdjuster::new(
-                gateway.clone(),
-                GasAdjusterConfig {
-                    max_base_fee_samples: Self::MAX_BASE_FEE_SAMPLES,
-                    pricing_formula_parameter_a: 3.0,
-                    pricing_formula_parameter_b: 2.0,
-                    ..eth_sender_config.gas_adjuster
-                },
-            )
-            .await
-            .unwrap(),
+            GasAdjuster::new(gateway.clone(), gas_adjuster_config)
+                .await
+                .unwrap(),
         );
         let store_factory = ObjectStoreFactory::mock();
 
@@ -112,16 +139,19 @@ impl EthSenderTester {
             Aggregator::new(
                 aggregator_config.clone(),
                 store_factory.create_store().await,
+                L1BatchCommitmentMode::Rollup,
             ),
-            // zkSync contract address
-            Address::random(),
-            contracts_config.l1_multicall3_addr,
-            Address::random(),
+            l1_settlement_layer(&contracts_config),
             0,
+            gateway.sender_account(),
         );
 
+        let fee_escalation =
+            FeeEscalationPolicies::new(&eth_sender_config.fee_escalation, &gas_adjuster_config)
+                .unwrap();
         let manager = EthTxManager::new(
             eth_sender_config.sender,
+            fee_escalation,
             gas_adjuster.clone(),
             gateway.clone(),
         );
@@ -775,6 +805,423 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn handing_over_unsent_txs_after_operator_rotation() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+
+    let mut txs = vec![];
+    for _ in 0..3 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &DUMMY_OPERATION,
+                true,
+            )
+            .await?;
+        txs.push(tx);
+    }
+    let old_operator = tester.gateway.sender_account();
+    assert!(txs.iter().all(|tx| tx.from_addr == Some(old_operator)));
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &txs[0],
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+
+    // Handover for the same operator should be a no-op.
+    tester
+        .aggregator
+        .hand_over_unsent_txs(&mut tester.storage().await, tester.gateway.as_ref())
+        .await?;
+    let new_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_new_eth_txs(10)
+        .await?;
+    let new_nonces: Vec<_> = new_txs.iter().map(|tx| tx.nonce.0).collect();
+    assert_eq!(new_nonces, [1, 2]);
+
+    let new_operator = Address::repeat_byte(0x22);
+    let rotated_aggregator = EthTxAggregator::new(
+        ETHSenderConfig::for_tests().sender,
+        Aggregator::new(
+            ETHSenderConfig::for_tests().sender,
+            ObjectStoreFactory::mock().create_store().await,
+            L1BatchCommitmentMode::Rollup,
+        ),
+        l1_settlement_layer(&ContractsConfig::for_tests()),
+        5,
+        new_operator,
+    );
+    rotated_aggregator
+        .hand_over_unsent_txs(&mut tester.storage().await, tester.gateway.as_ref())
+        .await?;
+
+    let mut storage = tester.storage().await;
+    let new_txs = storage.eth_sender_dal().get_new_eth_txs(10).await?;
+    let new_nonces: Vec<_> = new_txs.iter().map(|tx| tx.nonce.0).collect();
+    assert_eq!(new_nonces, [5, 6]);
+    assert!(new_txs.iter().all(|tx| tx.from_addr == Some(new_operator)));
+    // The sent tx must not be reassigned.
+    let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await?;
+    assert_eq!(inflight_txs.len(), 1);
+    assert_eq!(inflight_txs[0].nonce.0, 0);
+    assert_eq!(inflight_txs[0].from_addr, Some(old_operator));
+    Ok(())
+}
+
+/// L1 transaction params provider with a fixed gas price and zero priority fee.
+#[derive(Debug)]
+struct FixedGasPrice(AtomicU64);
+
+impl L1GasPriceProvider for FixedGasPrice {
+    fn estimate_effective_gas_price(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl L1TxParamsProvider for FixedGasPrice {
+    fn get_base_fee(&self, _time_in_mempool: u32) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn get_smoothed_base_fee(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn get_priority_fee(&self) -> u64 {
+        0
+    }
+
+    fn get_next_block_minimal_base_fee(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[tokio::test]
+async fn cost_criterion_depends_on_l1_gas_price() {
+    const GAS_PRICE: u64 = 10_000_000_000;
+
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    let gas_price = Arc::new(FixedGasPrice(AtomicU64::new(GAS_PRICE)));
+    let base_cost = agg_l1_batch_base_cost(AggregatedActionType::Commit);
+    let mut criterion = CostCriterion {
+        op: AggregatedActionType::Commit,
+        // 4 L1 batches are required at the initial gas price.
+        max_overhead_per_l1_batch: u64::from(base_cost) * GAS_PRICE / 4,
+        l1_tx_params: gas_price.clone(),
+    };
+    let l1_batches: Vec<_> = (1..=3)
+        .map(|number| {
+            l1_batch_with_metadata(L1BatchHeader::new(
+                L1BatchNumber(number),
+                0,
+                Address::zero(),
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            ))
+        })
+        .collect();
+    let last_sealed_l1_batch = L1BatchNumber(3);
+
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, last_sealed_l1_batch)
+        .await;
+    assert_eq!(last_l1_batch, None);
+
+    // With the halved gas price, 2 L1 batches are enough.
+    gas_price.0.store(GAS_PRICE / 2, Ordering::Relaxed);
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, last_sealed_l1_batch)
+        .await;
+    assert_eq!(last_l1_batch, Some(L1BatchNumber(3)));
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches[..1], last_sealed_l1_batch)
+        .await;
+    assert_eq!(last_l1_batch, None);
+}
+
+#[tokio::test]
+async fn validating_commitment_mode_for_pending_l1_batches() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    let sender_config = ETHSenderConfig::for_tests().sender;
+    let store_factory = ObjectStoreFactory::mock();
+    let rollup_aggregator = Aggregator::new(
+        sender_config.clone(),
+        store_factory.create_store().await,
+        L1BatchCommitmentMode::Rollup,
+    );
+    let validium_aggregator = Aggregator::new(
+        sender_config,
+        store_factory.create_store().await,
+        L1BatchCommitmentMode::Validium,
+    );
+
+    let pre_boojum_version = ProtocolVersionId::Version10;
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion {
+            id: pre_boojum_version,
+            ..ProtocolVersion::default()
+        })
+        .await;
+    let err = validium_aggregator
+        .validate_commitment_mode(&mut storage)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pre-boojum"), "{err}");
+    rollup_aggregator
+        .validate_commitment_mode(&mut storage)
+        .await
+        .unwrap();
+
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    validium_aggregator
+        .validate_commitment_mode(&mut storage)
+        .await
+        .unwrap();
+
+    // A pending pre-boojum L1 batch cannot be committed in the validium mode.
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        0,
+        Address::zero(),
+        BaseSystemContractsHashes::default(),
+        pre_boojum_version,
+    );
+    header.is_finished = true;
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, &[], Default::default(), &[], &[])
+        .await
+        .unwrap();
+    let err = validium_aggregator
+        .validate_commitment_mode(&mut storage)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("L1 batch #1"), "{err}");
+    rollup_aggregator
+        .validate_commitment_mode(&mut storage)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn execute_gas_price_gate_postpones_execution() {
+    const GAS_PRICE: u64 = 10_000_000_000;
+    const MIN_AGE_SECONDS: u64 = 100;
+
+    let gas_price = Arc::new(FixedGasPrice(AtomicU64::new(GAS_PRICE)));
+    let gate = ExecuteGasPriceGate {
+        max_l1_gas_price: GAS_PRICE,
+        min_age_seconds: MIN_AGE_SECONDS,
+        max_postpone_seconds: 1_000,
+        l1_tx_params: gas_price.clone(),
+    };
+    let now = unix_timestamp_ms() / 1_000;
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        now - MIN_AGE_SECONDS,
+        Address::zero(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    let l1_batches = [l1_batch_with_metadata(header.clone())];
+    assert!(gate.allows_execution(&l1_batches));
+
+    gas_price.0.store(GAS_PRICE + 1, Ordering::Relaxed);
+    assert!(!gate.allows_execution(&l1_batches));
+
+    // Execution is forced once it has been postponed for too long.
+    header.timestamp = now - MIN_AGE_SECONDS - 1_000;
+    let l1_batches = [l1_batch_with_metadata(header)];
+    assert!(gate.allows_execution(&l1_batches));
+}
+
+#[test]
+fn gateway_settlement_layer_encodes_chain_id() {
+    let mut config = ETHSenderConfig::for_tests().sender;
+    config.settlement_layer = SettlementLayerKind::Gateway;
+    let l2_chain_id = L2ChainId::from(270);
+    let err = settlement_layer_from_config(
+        &config,
+        &ContractsConfig::for_tests(),
+        &ETHClientConfig {
+            chain_id: 9,
+            web3_url: "http://127.0.0.1:8545".to_owned(),
+        },
+        &ChainL1Contracts::standalone(l2_chain_id, Address::repeat_byte(1)),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("requires"), "{err}");
+
+    config.gateway_url = Some("http://127.0.0.1:3150".to_owned());
+    config.gateway_chain_id = Some(506);
+    config.gateway_diamond_proxy_addr = Some(Address::repeat_byte(1));
+    config.gateway_validator_timelock_addr = Some(Address::repeat_byte(2));
+    config.gateway_multicall3_addr = Some(Address::repeat_byte(3));
+    let settlement_layer = GatewaySettlementLayer::new(&config, l2_chain_id).unwrap();
+    assert_eq!(settlement_layer.client_config().chain_id, 506);
+    assert_eq!(
+        settlement_layer.validator_timelock_address(),
+        Address::repeat_byte(2)
+    );
+
+    let calldata = settlement_layer
+        .encode_operation(&DUMMY_OPERATION, false)
+        .unwrap();
+    let l1_function = zksync_contract()
+        .function("executeBatches")
+        .unwrap()
+        .clone();
+    let param_types: Vec<_> = [ParamType::Uint(256)]
+        .into_iter()
+        .chain(l1_function.inputs.iter().map(|param| param.kind.clone()))
+        .collect();
+    let selector = ethabi::short_signature("executeBatchesSharedBridge", &param_types);
+    assert_eq!(calldata[..4], selector);
+    let args = ethabi::decode(&param_types, &calldata[4..]).unwrap();
+    assert_eq!(args[0], Token::Uint(U256::from(270)));
+    let AggregatedOperation::Execute(op) = &*DUMMY_OPERATION else {
+        unreachable!();
+    };
+    assert_eq!(args[1..], op.get_eth_tx_args());
+}
+
+#[test]
+fn shared_bridge_l1_settlement_layer_encodes_chain_id() {
+    let contracts_config = ContractsConfig::for_tests();
+    let eth_client_config = ETHClientConfig {
+        chain_id: 9,
+        web3_url: "http://127.0.0.1:8545".to_owned(),
+    };
+    let chain = ChainL1Contracts {
+        chain_id: L2ChainId::from(271),
+        diamond_proxy_addr: Address::repeat_byte(1),
+        bridgehub_proxy_addr: Some(Address::repeat_byte(2)),
+    };
+    let settlement_layer =
+        L1SettlementLayer::new(&chain, &contracts_config, &eth_client_config).unwrap();
+    assert_eq!(
+        settlement_layer.diamond_proxy_address(),
+        Address::repeat_byte(1)
+    );
+    assert_eq!(
+        settlement_layer.validator_timelock_address(),
+        contracts_config.validator_timelock_addr
+    );
+
+    let calldata = settlement_layer
+        .encode_operation(&DUMMY_OPERATION, false)
+        .unwrap();
+    let l1_function = zksync_contract()
+        .function("executeBatches")
+        .unwrap()
+        .clone();
+    let param_types: Vec<_> = [ParamType::Uint(256)]
+        .into_iter()
+        .chain(l1_function.inputs.iter().map(|param| param.kind.clone()))
+        .collect();
+    let selector = ethabi::short_signature("executeBatchesSharedBridge", &param_types);
+    assert_eq!(calldata[..4], selector);
+    let args = ethabi::decode(&param_types, &calldata[4..]).unwrap();
+    assert_eq!(args[0], Token::Uint(U256::from(271)));
+
+    // Chains deployed without the shared bridge use single-chain executor functions.
+    let chain = ChainL1Contracts::standalone(chain.chain_id, chain.diamond_proxy_addr);
+    let settlement_layer =
+        L1SettlementLayer::new(&chain, &contracts_config, &eth_client_config).unwrap();
+    let calldata = settlement_layer
+        .encode_operation(&DUMMY_OPERATION, false)
+        .unwrap();
+    assert_eq!(calldata[..4], l1_function.short_signature());
+}
+
+#[test]
+fn encoding_malformed_commit_operation_returns_error() {
+    let contracts_config = ContractsConfig::for_tests();
+    let settlement_layer = l1_settlement_layer(&contracts_config);
+    let AggregatedOperation::Execute(op) = &*DUMMY_OPERATION else {
+        unreachable!();
+    };
+    let l1_batch = op.l1_batches[0].clone();
+    let mut commit_op = L1BatchCommitOperation {
+        last_committed_l1_batch: l1_batch.clone(),
+        l1_batches: vec![l1_batch.clone(), l1_batch],
+        da_inclusion_data: Some(vec![vec![1; 32]]),
+        commitment_mode: L1BatchCommitmentMode::Rollup,
+    };
+    // Inclusion data is missing for the second batch.
+    commit_op.get_eth_tx_args().unwrap_err();
+    let operation = AggregatedOperation::Commit(commit_op.clone());
+    settlement_layer
+        .encode_operation(&operation, false)
+        .unwrap_err();
+
+    commit_op.da_inclusion_data = Some(vec![vec![1; 32], vec![2; 32]]);
+    let operation = AggregatedOperation::Commit(commit_op);
+    settlement_layer
+        .encode_operation(&operation, false)
+        .unwrap();
+}
+
+#[test]
+fn encoding_fflonk_proof_requires_dual_verifier() {
+    let contracts_config = ContractsConfig::for_tests();
+    let settlement_layer = l1_settlement_layer(&contracts_config);
+    let AggregatedOperation::Execute(op) = &*DUMMY_OPERATION else {
+        unreachable!();
+    };
+    let l1_batch = op.l1_batches[0].clone();
+    let proof = L1BatchProof::Fflonk(FflonkL1BatchProofForL1 {
+        aggregation_result_coords: [[0; 32]; 4],
+        proof: vec![U256::from(1), U256::from(2)],
+    });
+    let mut proof_op = L1BatchProofOperation {
+        prev_l1_batch: l1_batch.clone(),
+        l1_batches: vec![l1_batch],
+        proofs: vec![proof],
+        should_verify: true,
+        use_dual_verifier: false,
+    };
+    let err = proof_op.get_eth_tx_args().unwrap_err();
+    assert!(err.to_string().contains("dual verifier"), "{err}");
+    let operation = AggregatedOperation::PublishProofOnchain(proof_op.clone());
+    settlement_layer
+        .encode_operation(&operation, false)
+        .unwrap_err();
+
+    proof_op.use_dual_verifier = true;
+    let args = proof_op.get_eth_tx_args().unwrap();
+    let Token::Tuple(proof_input) = &args[2] else {
+        panic!("unexpected proof input: {:?}", args[2]);
+    };
+    let expected_verifier_type = ProofCompressionBackend::Fflonk.l1_verifier_type();
+    let expected_proof = [
+        U256::from(expected_verifier_type),
+        U256::from(1),
+        U256::from(2),
+    ];
+    let expected_proof = expected_proof.into_iter().map(Token::Uint).collect();
+    assert_eq!(proof_input[1], Token::Array(expected_proof));
+    let operation = AggregatedOperation::PublishProofOnchain(proof_op);
+    settlement_layer
+        .encode_operation(&operation, false)
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -927,6 +1374,7 @@ async fn prove_l1_batch(
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
         proofs: vec![],
         should_verify: false,
+        use_dual_verifier: false,
     });
     send_operation(tester, operation, confirm).await
 }
@@ -940,6 +1388,8 @@ async fn commit_l1_batch(
     let operation = AggregatedOperation::Commit(L1BatchCommitOperation {
         last_committed_l1_batch: l1_batch_with_metadata(last_committed_l1_batch),
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
+        da_inclusion_data: None,
+        commitment_mode: L1BatchCommitmentMode::Rollup,
     });
     send_operation(tester, operation, confirm).await
 }
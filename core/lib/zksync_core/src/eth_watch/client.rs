@@ -1,3 +1,4 @@
+use futures::{future::BoxFuture, FutureExt};
 use zksync_contracts::verifier_contract;
 use zksync_eth_client::{types::Error as EthClientError, EthInterface};
 use zksync_types::{
@@ -20,6 +21,14 @@ pub enum Error {
     EthClient(#[from] EthClientError),
     #[error("Infinite recursion caused by too many responses")]
     InfiniteRecursion,
+    #[error("L1 event handler failed: {0:#}")]
+    EventHandler(anyhow::Error),
+    #[error("L1 reorg deeper than the confirmation depth: blocks after #{fork_block_number} were reverted")]
+    L1Reorg { fork_block_number: u64 },
+    /// L1 block is not known to the Ethereum node, e.g. because the node lags behind or is being load-balanced.
+    /// Unlike [`Self::L1Reorg`], this error is transient.
+    #[error("L1 block #{0} is not available from the Ethereum node")]
+    MissingL1Block(u64),
 }
 
 #[async_trait::async_trait]
@@ -31,10 +40,21 @@ pub trait EthClient {
         to: BlockNumber,
         retries_left: usize,
     ) -> Result<Vec<Log>, Error>;
+    /// Returns events emitted by the specified contract with the specified topic in a given block range.
+    async fn get_contract_events(
+        &self,
+        contract_address: Address,
+        topic: H256,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<Log>, Error>;
     /// Returns finalized L1 block number.
     async fn finalized_block_number(&self) -> Result<u64, Error>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address) -> Result<H256, Error>;
+    /// Returns the hash of the L1 block with the specified number, or `None` if the block is not present.
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error>;
     /// Sets list of topics to return events for.
     fn set_topics(&mut self, topics: Vec<H256>);
 }
@@ -79,18 +99,13 @@ impl<E: EthInterface> EthHttpQueryClient<E> {
 
     async fn get_filter_logs(
         &self,
+        addresses: Vec<Address>,
+        topics: Vec<H256>,
         from: BlockNumber,
         to: BlockNumber,
-        topics: Vec<H256>,
     ) -> Result<Vec<Log>, Error> {
         let filter = FilterBuilder::default()
-            .address(
-                [Some(self.zksync_contract_addr), self.governance_address]
-                    .iter()
-                    .flatten()
-                    .copied()
-                    .collect(),
-            )
+            .address(addresses)
             .from_block(from)
             .to_block(to)
             .topics(Some(topics), None, None, None)
@@ -98,6 +113,105 @@ impl<E: EthInterface> EthHttpQueryClient<E> {
 
         self.client.logs(filter, "watch").await.map_err(Into::into)
     }
+
+    /// Returns logs matching the filter, splitting the block range if the provider returns too many results.
+    fn get_filtered_events<'a>(
+        &'a self,
+        addresses: &'a [Address],
+        topics: &'a [H256],
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> BoxFuture<'a, Result<Vec<Log>, Error>> {
+        async move {
+            let mut result = self
+                .get_filter_logs(addresses.to_vec(), topics.to_vec(), from, to)
+                .await;
+
+            // This code is compatible with both Infura and Alchemy API providers.
+            // Note: we don't handle rate-limits here - assumption is that we're never going to hit them.
+            if let Err(Error::EthClient(EthClientError::EthereumGateway(err))) = &result {
+                tracing::warn!("Provider returned error message: {:?}", err);
+                let err_message = err.to_string();
+                let err_code = if let web3::Error::Rpc(err) = err {
+                    Some(err.code.code())
+                } else {
+                    None
+                };
+
+                let should_retry = |err_code, err_message: String| {
+                    // All of these can be emitted by either API provider.
+                    err_code == Some(-32603)             // Internal error
+                        || err_message.contains("failed")    // Server error
+                        || err_message.contains("timed out") // Time-out error
+                };
+
+                // check whether the error is related to having too many results
+                if err_message.contains(TOO_MANY_RESULTS_INFURA)
+                    || err_message.contains(TOO_MANY_RESULTS_ALCHEMY)
+                {
+                    // get the numeric block ids
+                    let from_number = match from {
+                        BlockNumber::Number(num) => num,
+                        _ => {
+                            // invalid variant
+                            return result;
+                        }
+                    };
+                    let to_number = match to {
+                        BlockNumber::Number(num) => num,
+                        BlockNumber::Latest => self.client.block_number("watch").await?,
+                        _ => {
+                            // invalid variant
+                            return result;
+                        }
+                    };
+
+                    // divide range into two halves and recursively fetch them
+                    let mid = (from_number + to_number) / 2;
+
+                    // safety check to prevent infinite recursion (quite unlikely)
+                    if from_number >= mid {
+                        return Err(Error::InfiniteRecursion);
+                    }
+                    tracing::warn!(
+                        "Splitting block range in half: {:?} - {:?} - {:?}",
+                        from,
+                        mid,
+                        to
+                    );
+                    let mut first_half = self
+                        .get_filtered_events(
+                            addresses,
+                            topics,
+                            from,
+                            BlockNumber::Number(mid),
+                            RETRY_LIMIT,
+                        )
+                        .await?;
+                    let mut second_half = self
+                        .get_filtered_events(
+                            addresses,
+                            topics,
+                            BlockNumber::Number(mid + 1u64),
+                            to,
+                            RETRY_LIMIT,
+                        )
+                        .await?;
+
+                    first_half.append(&mut second_half);
+                    result = Ok(first_half);
+                } else if should_retry(err_code, err_message) && retries_left > 0 {
+                    tracing::warn!("Retrying. Retries left: {:?}", retries_left);
+                    result = self
+                        .get_filtered_events(addresses, topics, from, to, retries_left - 1)
+                        .await;
+                }
+            }
+            result
+        }
+        .boxed()
+    }
 }
 
 #[async_trait::async_trait]
@@ -146,79 +260,29 @@ impl<E: EthInterface + Send + Sync + 'static> EthClient for EthHttpQueryClient<E
         retries_left: usize,
     ) -> Result<Vec<Log>, Error> {
         let latency = METRICS.get_priority_op_events.start();
-        let mut result = self.get_filter_logs(from, to, self.topics.clone()).await;
-
-        // This code is compatible with both Infura and Alchemy API providers.
-        // Note: we don't handle rate-limits here - assumption is that we're never going to hit them.
-        if let Err(Error::EthClient(EthClientError::EthereumGateway(err))) = &result {
-            tracing::warn!("Provider returned error message: {:?}", err);
-            let err_message = err.to_string();
-            let err_code = if let web3::Error::Rpc(err) = err {
-                Some(err.code.code())
-            } else {
-                None
-            };
-
-            let should_retry = |err_code, err_message: String| {
-                // All of these can be emitted by either API provider.
-                err_code == Some(-32603)             // Internal error
-                    || err_message.contains("failed")    // Server error
-                    || err_message.contains("timed out") // Time-out error
-            };
-
-            // check whether the error is related to having too many results
-            if err_message.contains(TOO_MANY_RESULTS_INFURA)
-                || err_message.contains(TOO_MANY_RESULTS_ALCHEMY)
-            {
-                // get the numeric block ids
-                let from_number = match from {
-                    BlockNumber::Number(num) => num,
-                    _ => {
-                        // invalid variant
-                        return result;
-                    }
-                };
-                let to_number = match to {
-                    BlockNumber::Number(num) => num,
-                    BlockNumber::Latest => self.client.block_number("watch").await?,
-                    _ => {
-                        // invalid variant
-                        return result;
-                    }
-                };
-
-                // divide range into two halves and recursively fetch them
-                let mid = (from_number + to_number) / 2;
-
-                // safety check to prevent infinite recursion (quite unlikely)
-                if from_number >= mid {
-                    return Err(Error::InfiniteRecursion);
-                }
-                tracing::warn!(
-                    "Splitting block range in half: {:?} - {:?} - {:?}",
-                    from,
-                    mid,
-                    to
-                );
-                let mut first_half = self
-                    .get_events(from, BlockNumber::Number(mid), RETRY_LIMIT)
-                    .await?;
-                let mut second_half = self
-                    .get_events(BlockNumber::Number(mid + 1u64), to, RETRY_LIMIT)
-                    .await?;
-
-                first_half.append(&mut second_half);
-                result = Ok(first_half);
-            } else if should_retry(err_code, err_message) && retries_left > 0 {
-                tracing::warn!("Retrying. Retries left: {:?}", retries_left);
-                result = self.get_events(from, to, retries_left - 1).await;
-            }
-        }
-
+        let addresses: Vec<_> = [Some(self.zksync_contract_addr), self.governance_address]
+            .into_iter()
+            .flatten()
+            .collect();
+        let result = self
+            .get_filtered_events(&addresses, &self.topics, from, to, retries_left)
+            .await;
         latency.observe();
         result
     }
 
+    async fn get_contract_events(
+        &self,
+        contract_address: Address,
+        topic: H256,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<Log>, Error> {
+        self.get_filtered_events(&[contract_address], &[topic], from, to, retries_left)
+            .await
+    }
+
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         if let Some(confirmations) = self.confirmations_for_eth_event {
             let latest_block_number = self.client.block_number("watch").await?.as_u64();
@@ -237,6 +301,14 @@ impl<E: EthInterface + Send + Sync + 'static> EthClient for EthHttpQueryClient<E
         }
     }
 
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error> {
+        let block = self
+            .client
+            .block(BlockId::Number(BlockNumber::Number(number.into())), "watch")
+            .await?;
+        Ok(block.and_then(|block| block.hash))
+    }
+
     fn set_topics(&mut self, topics: Vec<H256>) {
         self.topics = topics;
     }
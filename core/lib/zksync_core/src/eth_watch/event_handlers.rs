@@ -0,0 +1,158 @@
+//! Handlers of L1 contract events that can be registered by node extensions in addition to the built-in
+//! event processors (priority operations and protocol upgrades).
+
+use std::fmt;
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    ethabi,
+    web3::types::{BlockNumber, Log},
+    Address, L1BlockNumber, H256,
+};
+
+use super::client::{Error, EthClient, RETRY_LIMIT};
+
+/// Decoded L1 event together with its location on L1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1Event<T> {
+    pub l1_block_number: L1BlockNumber,
+    pub tx_hash: H256,
+    pub log_index: u64,
+    pub event: T,
+}
+
+/// Handler of events with a specific topic emitted by a specific L1 contract.
+///
+/// Events are only passed to the handler once the containing L1 block is finalized (or has the configured number
+/// of confirmations). The same events may be passed to the handler multiple times, e.g. after a failed poll
+/// or a node restart, so the handler must be idempotent.
+#[async_trait::async_trait]
+pub trait L1EventHandler: fmt::Debug + Send + 'static {
+    /// Typed representation of the handled event.
+    type Event: fmt::Debug + Send;
+
+    /// Returns the address of the contract emitting handled events.
+    fn contract_address(&self) -> Address;
+
+    /// Returns the topic (i.e., the hashed event signature) of handled events.
+    fn topic(&self) -> H256;
+
+    /// Decodes a raw log. An error is returned to the watcher, which will poll the logs again later.
+    fn decode(&self, log: &Log) -> anyhow::Result<Self::Event>;
+
+    /// Persists events emitted in a polled range of L1 blocks, in the order of emission. The handler is only called
+    /// for non-empty ranges; changes to `storage` are committed if the handler succeeds.
+    async fn handle_events(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        events: Vec<L1Event<Self::Event>>,
+    ) -> anyhow::Result<()>;
+}
+
+/// Decodes params of the `event` from a raw log. Useful to implement [`L1EventHandler::decode()`]
+/// for events with a known ABI.
+pub fn decode_event_log(event: &ethabi::Event, log: &Log) -> anyhow::Result<ethabi::Log> {
+    let raw_log = ethabi::RawLog {
+        topics: log.topics.clone(),
+        data: log.data.0.clone(),
+    };
+    event
+        .parse_log(raw_log)
+        .with_context(|| format!("failed decoding `{}` event", event.name))
+}
+
+/// Object-safe version of [`L1EventHandler`].
+#[async_trait::async_trait]
+trait ErasedL1EventHandler: fmt::Debug + Send {
+    fn contract_address(&self) -> Address;
+
+    fn topic(&self) -> H256;
+
+    async fn process_logs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        logs: Vec<Log>,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+impl<H: L1EventHandler> ErasedL1EventHandler for H {
+    fn contract_address(&self) -> Address {
+        L1EventHandler::contract_address(self)
+    }
+
+    fn topic(&self) -> H256 {
+        L1EventHandler::topic(self)
+    }
+
+    async fn process_logs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        logs: Vec<Log>,
+    ) -> anyhow::Result<()> {
+        let events = logs
+            .iter()
+            .map(|log| {
+                let l1_block_number = log.block_number.context("log without block number")?;
+                Ok(L1Event {
+                    l1_block_number: L1BlockNumber(l1_block_number.as_u32()),
+                    tx_hash: log.transaction_hash.context("log without tx hash")?,
+                    log_index: log.log_index.context("log without index")?.as_u64(),
+                    event: self.decode(log)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut transaction = storage.start_transaction().await?;
+        self.handle_events(&mut transaction, events).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+/// Collection of [`L1EventHandler`]s registered by node extensions.
+#[derive(Debug, Default)]
+pub struct L1EventHandlers(Vec<Box<dyn ErasedL1EventHandler>>);
+
+impl L1EventHandlers {
+    /// Registers a handler. Events are passed to handlers in the order of their registration.
+    pub fn register<H: L1EventHandler>(&mut self, handler: H) -> &mut Self {
+        self.0.push(Box::new(handler));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Passes events emitted in the specified range of L1 blocks to all handlers.
+    pub(super) async fn process<W: EthClient + Sync>(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        client: &W,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<(), Error> {
+        for handler in &mut self.0 {
+            let logs = client
+                .get_contract_events(
+                    handler.contract_address(),
+                    handler.topic(),
+                    from,
+                    to,
+                    RETRY_LIMIT,
+                )
+                .await?;
+            handler
+                .process_logs(storage, logs)
+                .await
+                .with_context(|| format!("failed processing events for {handler:?}"))
+                .map_err(Error::EventHandler)?;
+        }
+        Ok(())
+    }
+}
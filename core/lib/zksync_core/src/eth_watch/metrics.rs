@@ -10,6 +10,7 @@ pub(super) enum PollStage {
     Request,
     PersistL1Txs,
     PersistUpgrades,
+    CustomEvents,
 }
 
 #[derive(Debug, Metrics)]
@@ -20,6 +21,8 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of detected L1 reorgs deeper than the confirmation depth.
+    pub l1_reorgs: Counter,
 }
 
 #[vise::register]
@@ -1,21 +1,31 @@
 //! Ethereum watcher polls the Ethereum node for PriorityQueue events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
+//! Node extensions can subscribe to other L1 events by registering [`L1EventHandler`]s.
+//!
+//! The watcher tracks a single chain identified by its [`ChainL1Contracts`]: priority operations and upgrades
+//! are only accepted from the diamond proxy of this chain. To track several chains registered in the same
+//! bridgehub, a watcher is started for each chain with the chain's connection pool.
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//! If an L1 reorg deeper than the number of confirmations is detected, the watcher stops the node
+//! and reports how to revert the node state.
 
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
+use anyhow::Context as _;
 use tokio::{sync::watch, task::JoinHandle};
+use tracing::Instrument;
 use zksync_config::ETHWatchConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
-    ProtocolVersionId,
+    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, L1BlockNumber,
+    PriorityOpId, ProtocolVersionId, H256,
 };
 
+pub use self::event_handlers::{decode_event_log, L1Event, L1EventHandler, L1EventHandlers};
 use self::{
     client::{Error, EthClient, EthHttpQueryClient, RETRY_LIMIT},
     event_processors::{
@@ -24,8 +34,10 @@ use self::{
     },
     metrics::{PollStage, METRICS},
 };
+use crate::shared_bridge::ChainL1Contracts;
 
 mod client;
+mod event_handlers;
 mod event_processors;
 mod metrics;
 #[cfg(test)]
@@ -43,11 +55,17 @@ pub struct EthWatch<W: EthClient + Sync> {
     client: W,
     poll_interval: Duration,
     event_processors: Vec<Box<dyn EventProcessor<W>>>,
+    event_handlers: L1EventHandlers,
 
     last_processed_ethereum_block: u64,
+    /// Numbers and hashes of recently processed L1 blocks used to detect L1 reorgs, from oldest to newest.
+    processed_l1_blocks: VecDeque<(u64, H256)>,
 }
 
 impl<W: EthClient + Sync> EthWatch<W> {
+    /// Maximum number of processed L1 blocks checked on reorgs.
+    const MAX_TRACKED_L1_BLOCKS: usize = 128;
+
     pub async fn new(
         diamond_proxy_address: Address,
         governance_contract: Option<Contract>,
@@ -88,10 +106,18 @@ impl<W: EthClient + Sync> EthWatch<W> {
             client,
             poll_interval,
             event_processors,
+            event_handlers: L1EventHandlers::default(),
             last_processed_ethereum_block: state.last_processed_ethereum_block,
+            processed_l1_blocks: VecDeque::with_capacity(Self::MAX_TRACKED_L1_BLOCKS),
         }
     }
 
+    /// Sets handlers for additional L1 events. Handlers are invoked after the built-in event processors.
+    pub fn with_event_handlers(mut self, event_handlers: L1EventHandlers) -> Self {
+        self.event_handlers = event_handlers;
+        self
+    }
+
     async fn initialize_state(client: &W, storage: &mut StorageProcessor<'_>) -> EthWatchState {
         let next_expected_priority_id: PriorityOpId = storage
             .transactions_dal()
@@ -144,35 +170,111 @@ impl<W: EthClient + Sync> EthWatch<W> {
             METRICS.eth_poll.inc();
 
             let mut storage = pool.access_storage_tagged("eth_watch").await.unwrap();
-            if let Err(error) = self.loop_iteration(&mut storage).await {
-                // This is an error because otherwise we could potentially miss a priority operation
-                // thus entering priority mode, which is not desired.
-                tracing::error!("Failed to process new blocks {}", error);
-                self.last_processed_ethereum_block =
-                    Self::initialize_state(&self.client, &mut storage)
-                        .await
-                        .last_processed_ethereum_block;
+            match self.loop_iteration(&mut storage).await {
+                Ok(()) => {}
+                Err(Error::L1Reorg { fork_block_number }) => {
+                    METRICS.l1_reorgs.inc();
+                    return Err(Self::l1_reorg_error(&mut storage, fork_block_number).await);
+                }
+                Err(err @ Error::MissingL1Block(_)) => {
+                    tracing::warn!(
+                        "Cannot check processed L1 blocks for reorgs: {err}; retrying on the next poll"
+                    );
+                }
+                Err(error) => {
+                    // This is an error because otherwise we could potentially miss a priority operation
+                    // thus entering priority mode, which is not desired.
+                    tracing::error!("Failed to process new blocks {}", error);
+                    self.last_processed_ethereum_block =
+                        Self::initialize_state(&self.client, &mut storage)
+                            .await
+                            .last_processed_ethereum_block;
+                }
             }
         }
         Ok(())
     }
 
+    /// Creates an error stopping the node after a deep L1 reorg, with instructions on reverting the node state.
+    async fn l1_reorg_error(
+        storage: &mut StorageProcessor<'_>,
+        fork_block_number: u64,
+    ) -> anyhow::Error {
+        let fork_block_number = L1BlockNumber(fork_block_number as u32);
+        let first_affected_batch = storage
+            .transactions_dal()
+            .get_first_l1_batch_with_priority_ops_after(fork_block_number)
+            .await
+            .context("failed getting L1 batches affected by L1 reorg");
+        let instructions = match first_affected_batch {
+            Ok(Some(batch)) => {
+                let last_correct_batch = batch.0.saturating_sub(1);
+                format!(
+                    "Priority operations from reverted L1 blocks are included into L1 batches starting from #{batch}. \
+                     Revert the node state with `block_reverter rollback-db --l1-batch-number {last_correct_batch} \
+                     --rollback-postgres --rollback-tree --rollback-sk-cache` (if affected batches are committed \
+                     on L1, revert them first with `block_reverter send-eth-transaction`), and restart the node \
+                     once L1 is stable"
+                )
+            }
+            Ok(None) => {
+                "No sealed L1 batches include priority operations from reverted L1 blocks; restart \
+                 the node once L1 is stable"
+                    .to_owned()
+            }
+            Err(err) => return err,
+        };
+        tracing::error!(
+            "Detected L1 reorg: L1 blocks after #{fork_block_number} were reverted. {instructions}"
+        );
+        anyhow::anyhow!(
+            "L1 reorg deeper than the configured number of confirmations: L1 blocks after \
+             #{fork_block_number} were reverted. {instructions}"
+        )
+    }
+
+    /// Checks that the processed L1 blocks are not reverted. An L1 block unknown to the Ethereum node
+    /// is not treated as a reorg; instead, the check fails with a transient error and is retried on the next poll.
+    async fn check_l1_reorg(&self) -> Result<(), Error> {
+        let Some(&(number, hash)) = self.processed_l1_blocks.back() else {
+            return Ok(());
+        };
+        if self.canonical_block_hash(number).await? == hash {
+            return Ok(());
+        }
+
+        // If none of the tracked blocks are canonical, we assume that the fork happened just before the oldest one.
+        let (oldest_number, _) = self.processed_l1_blocks[0];
+        let mut fork_block_number = oldest_number.saturating_sub(1);
+        for &(number, hash) in self.processed_l1_blocks.iter().rev().skip(1) {
+            if self.canonical_block_hash(number).await? == hash {
+                fork_block_number = number;
+                break;
+            }
+        }
+        Err(Error::L1Reorg { fork_block_number })
+    }
+
+    async fn canonical_block_hash(&self, number: u64) -> Result<H256, Error> {
+        self.client
+            .block_hash(number)
+            .await?
+            .ok_or(Error::MissingL1Block(number))
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(&mut self, storage: &mut StorageProcessor<'_>) -> Result<(), Error> {
+        self.check_l1_reorg().await?;
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
         let to_block = self.client.finalized_block_number().await?;
         if to_block <= self.last_processed_ethereum_block {
             return Ok(());
         }
 
-        let events = self
-            .client
-            .get_events(
-                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
-                Web3BlockNumber::Number(to_block.into()),
-                RETRY_LIMIT,
-            )
-            .await?;
+        let from = Web3BlockNumber::Number(self.last_processed_ethereum_block.into());
+        let to = Web3BlockNumber::Number(to_block.into());
+        let events = self.client.get_events(from, to, RETRY_LIMIT).await?;
         stage_latency.observe();
 
         for processor in self.event_processors.iter_mut() {
@@ -180,36 +282,55 @@ impl<W: EthClient + Sync> EthWatch<W> {
                 .process_events(storage, &self.client, events.clone())
                 .await?;
         }
+        if !self.event_handlers.is_empty() {
+            let stage_latency = METRICS.poll_eth_node[&PollStage::CustomEvents].start();
+            self.event_handlers
+                .process(storage, &self.client, from, to)
+                .await?;
+            stage_latency.observe();
+        }
         self.last_processed_ethereum_block = to_block;
+
+        if let Some(hash) = self.client.block_hash(to_block).await? {
+            if self.processed_l1_blocks.len() == Self::MAX_TRACKED_L1_BLOCKS {
+                self.processed_l1_blocks.pop_front();
+            }
+            self.processed_l1_blocks.push_back((to_block, hash));
+        }
         Ok(())
     }
 }
 
+/// Starts the Ethereum watcher for the specified chain. `pool` must point to the database of this chain.
 pub async fn start_eth_watch<E: EthInterface + Send + Sync + 'static>(
     config: ETHWatchConfig,
     pool: ConnectionPool,
     eth_gateway: E,
-    diamond_proxy_addr: Address,
+    chain: ChainL1Contracts,
     governance: (Contract, Address),
+    event_handlers: L1EventHandlers,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let span = tracing::info_span!("eth_watch", chain_id = chain.chain_id.as_u64());
     let eth_client = EthHttpQueryClient::new(
         eth_gateway,
-        diamond_proxy_addr,
+        chain.diamond_proxy_addr,
         Some(governance.1),
         config.confirmations_for_eth_event,
     );
 
     let mut eth_watch = EthWatch::new(
-        diamond_proxy_addr,
+        chain.diamond_proxy_addr,
         Some(governance.0),
         eth_client,
         &pool,
         config.poll_interval(),
     )
-    .await;
+    .instrument(span.clone())
+    .await
+    .with_event_handlers(event_handlers);
 
-    Ok(tokio::spawn(async move {
-        eth_watch.run(pool, stop_receiver).await
-    }))
+    Ok(tokio::spawn(
+        async move { eth_watch.run(pool, stop_receiver).await }.instrument(span),
+    ))
 }
@@ -1,26 +1,36 @@
-use std::{collections::HashMap, convert::TryInto, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    sync::{Arc, Mutex},
+};
 
+use assert_matches::assert_matches;
 use tokio::sync::RwLock;
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{
-    ethabi::{encode, Hash, Token},
+    ethabi::{self, encode, Hash, Token},
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     protocol_version::{ProtocolUpgradeTx, ProtocolUpgradeTxCommonData},
     web3::types::{Address, BlockNumber, Log},
-    Execute, L1TxCommonData, PriorityOpId, ProtocolUpgrade, ProtocolVersion, ProtocolVersionId,
-    Transaction, H256, U256,
+    Execute, L1BlockNumber, L1TxCommonData, PriorityOpId, ProtocolUpgrade, ProtocolVersion,
+    ProtocolVersionId, Transaction, H256, U256,
 };
 
 use super::client::Error;
 use crate::eth_watch::{
-    client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+    client::EthClient, decode_event_log, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE,
+    EthWatch, L1Event, L1EventHandler, L1EventHandlers,
 };
 
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<Log>>,
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
+    contract_events: HashMap<u64, Vec<Log>>,
+    /// Overridden hashes of L1 blocks; by default, the hash of a block is derived from its number.
+    block_hashes: HashMap<u64, H256>,
+    missing_blocks: HashSet<u64>,
     last_finalized_block_number: u64,
 }
 
@@ -30,6 +40,9 @@ impl FakeEthClientData {
             transactions: Default::default(),
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
+            contract_events: Default::default(),
+            block_hashes: Default::default(),
+            missing_blocks: Default::default(),
             last_finalized_block_number: 0,
         }
     }
@@ -62,6 +75,16 @@ impl FakeEthClientData {
         }
     }
 
+    fn add_contract_events(&mut self, logs: &[Log]) {
+        for log in logs {
+            let eth_block = log.block_number.unwrap().as_u64();
+            self.contract_events
+                .entry(eth_block)
+                .or_default()
+                .push(log.clone());
+        }
+    }
+
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
@@ -91,6 +114,23 @@ impl FakeEthClient {
         self.inner.write().await.add_governance_upgrades(upgrades);
     }
 
+    async fn add_contract_events(&mut self, logs: &[Log]) {
+        self.inner.write().await.add_contract_events(logs);
+    }
+
+    async fn set_block_hash(&mut self, number: u64, hash: H256) {
+        self.inner.write().await.block_hashes.insert(number, hash);
+    }
+
+    async fn set_block_missing(&mut self, number: u64, is_missing: bool) {
+        let missing_blocks = &mut self.inner.write().await.missing_blocks;
+        if is_missing {
+            missing_blocks.insert(number);
+        } else {
+            missing_blocks.remove(&number);
+        }
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -135,6 +175,26 @@ impl EthClient for FakeEthClient {
         Ok(logs)
     }
 
+    async fn get_contract_events(
+        &self,
+        contract_address: Address,
+        topic: H256,
+        from: BlockNumber,
+        to: BlockNumber,
+        _retries_left: usize,
+    ) -> Result<Vec<Log>, Error> {
+        let from = self.block_to_number(from).await;
+        let to = self.block_to_number(to).await;
+        let inner = self.inner.read().await;
+        let logs = (from..=to)
+            .filter_map(|number| inner.contract_events.get(&number))
+            .flatten()
+            .filter(|log| log.address == contract_address && log.topics[0] == topic)
+            .cloned()
+            .collect();
+        Ok(logs)
+    }
+
     fn set_topics(&mut self, _topics: Vec<Hash>) {}
 
     async fn scheduler_vk_hash(&self, _verifier_address: Address) -> Result<H256, Error> {
@@ -144,6 +204,15 @@ impl EthClient for FakeEthClient {
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error> {
+        let inner = self.inner.read().await;
+        if inner.missing_blocks.contains(&number) {
+            return Ok(None);
+        }
+        let hash = inner.block_hashes.get(&number).copied();
+        Ok(Some(hash.unwrap_or_else(|| H256::from_low_u64_be(number))))
+    }
 }
 
 fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
@@ -522,11 +591,153 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+/// Handler of `ValueSet(address indexed, uint256)` events recording all received events.
+#[derive(Debug)]
+struct RecordingEventHandler {
+    event: ethabi::Event,
+    received_events: Arc<Mutex<Vec<L1Event<(Address, U256)>>>>,
+}
+
+impl RecordingEventHandler {
+    const CONTRACT_ADDRESS: Address = Address::repeat_byte(0x42);
+
+    fn new() -> Self {
+        let event = ethabi::Event {
+            name: "ValueSet".to_owned(),
+            inputs: vec![
+                ethabi::EventParam {
+                    name: "account".to_owned(),
+                    kind: ethabi::ParamType::Address,
+                    indexed: true,
+                },
+                ethabi::EventParam {
+                    name: "value".to_owned(),
+                    kind: ethabi::ParamType::Uint(256),
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        };
+        Self {
+            event,
+            received_events: Arc::default(),
+        }
+    }
+
+    fn event_log(&self, address: Address, account: Address, value: U256, eth_block: u64) -> Log {
+        Log {
+            address,
+            topics: vec![self.event.signature(), H256::from(account)],
+            data: encode(&[Token::Uint(value)]).into(),
+            block_hash: Some(H256::repeat_byte(0x11)),
+            block_number: Some(eth_block.into()),
+            transaction_hash: Some(H256::from_low_u64_be(eth_block)),
+            transaction_index: Some(0u64.into()),
+            log_index: Some(0u64.into()),
+            transaction_log_index: Some(0u64.into()),
+            log_type: None,
+            removed: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl L1EventHandler for RecordingEventHandler {
+    type Event = (Address, U256);
+
+    fn contract_address(&self) -> Address {
+        Self::CONTRACT_ADDRESS
+    }
+
+    fn topic(&self) -> H256 {
+        self.event.signature()
+    }
+
+    fn decode(&self, log: &Log) -> anyhow::Result<Self::Event> {
+        let log = decode_event_log(&self.event, log)?;
+        let account = log.params[0].value.clone().into_address().unwrap();
+        let value = log.params[1].value.clone().into_uint().unwrap();
+        Ok((account, value))
+    }
+
+    async fn handle_events(
+        &mut self,
+        _storage: &mut StorageProcessor<'_>,
+        events: Vec<L1Event<Self::Event>>,
+    ) -> anyhow::Result<()> {
+        self.received_events.lock().unwrap().extend(events);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_custom_event_handler() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let handler = RecordingEventHandler::new();
+    let account = Address::repeat_byte(1);
+    client
+        .add_contract_events(&[
+            handler.event_log(
+                RecordingEventHandler::CONTRACT_ADDRESS,
+                account,
+                1.into(),
+                5,
+            ),
+            // Events from other contracts must be ignored.
+            handler.event_log(Address::repeat_byte(0x43), account, 2.into(), 7),
+            handler.event_log(
+                RecordingEventHandler::CONTRACT_ADDRESS,
+                account,
+                3.into(),
+                12,
+            ),
+        ])
+        .await;
+    let received_events = handler.received_events.clone();
+    let mut handlers = L1EventHandlers::default();
+    handlers.register(handler);
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+    )
+    .await
+    .with_event_handlers(handlers);
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    client.set_last_finalized_block_number(10).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    {
+        let received_events = received_events.lock().unwrap();
+        assert_eq!(
+            *received_events,
+            [L1Event {
+                l1_block_number: L1BlockNumber(5),
+                tx_hash: H256::from_low_u64_be(5),
+                log_index: 0,
+                event: (account, 1.into()),
+            }]
+        );
+    }
+
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let received_events = received_events.lock().unwrap();
+    assert_eq!(received_events.len(), 2);
+    assert_eq!(received_events[1].l1_block_number, L1BlockNumber(12));
+    assert_eq!(received_events[1].event, (account, 3.into()));
+}
+
 async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await;
     storage
         .transactions_dal()
-        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .sync_mempool(vec![], vec![], vec![], 0, 0, 1000)
         .await
         .0
 }
@@ -772,3 +983,80 @@ async fn setup_db(connection_pool: &ConnectionPool) {
         })
         .await;
 }
+
+#[tokio::test]
+async fn test_l1_reorg_detection() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    for block_number in [10, 15, 20] {
+        client.set_last_finalized_block_number(block_number).await;
+        watcher.loop_iteration(&mut storage).await.unwrap();
+    }
+
+    // Revert L1 blocks after #15.
+    client.set_block_hash(20, H256::repeat_byte(0xff)).await;
+    client.set_last_finalized_block_number(25).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert_matches!(
+        err,
+        Error::L1Reorg {
+            fork_block_number: 15
+        }
+    );
+    assert_eq!(watcher.last_processed_ethereum_block, 20);
+
+    // Revert all tracked L1 blocks.
+    client.set_block_hash(10, H256::repeat_byte(0xff)).await;
+    client.set_block_hash(15, H256::repeat_byte(0xff)).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert_matches!(
+        err,
+        Error::L1Reorg {
+            fork_block_number: 9
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_l1_block_is_not_treated_as_reorg() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    for block_number in [10, 15, 20] {
+        client.set_last_finalized_block_number(block_number).await;
+        watcher.loop_iteration(&mut storage).await.unwrap();
+    }
+
+    client.set_block_missing(20, true).await;
+    client.set_last_finalized_block_number(25).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert_matches!(err, Error::MissingL1Block(20));
+    assert_eq!(watcher.last_processed_ethereum_block, 20);
+
+    client.set_block_missing(20, false).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(watcher.last_processed_ethereum_block, 25);
+}
@@ -0,0 +1,27 @@
+//! Event indexer metrics.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, Family, Gauge, Histogram, Metrics};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct IndexLabels {
+    pub index: &'static str,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_event_indexer")]
+pub(super) struct EventIndexerMetrics {
+    /// Number of the last miniblock processed by an index.
+    pub last_indexed_miniblock: Family<IndexLabels, Gauge<u64>>,
+    /// Number of entries saved for an index.
+    pub saved_entries: Family<IndexLabels, Counter>,
+    /// Latency of processing a chunk of miniblocks for an index.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub chunk_latency: Family<IndexLabels, Histogram<Duration>>,
+    /// Number of errors processing chunks of miniblocks for an index; chunks are retried after an error.
+    pub errors: Family<IndexLabels, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<EventIndexerMetrics> = vise::Global::new();
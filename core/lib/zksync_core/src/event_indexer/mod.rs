@@ -0,0 +1,269 @@
+//! Custom event indexes maintained as miniblocks are sealed.
+//!
+//! Querying events by their params (e.g., all ERC-20 transfers of a certain account) requires scanning the generic
+//! `events` table, which is slow for explorer-like workloads. Node extensions can declare [`EventIndex`]es mapping
+//! keys extracted from events of a certain type to the events; the [`EventIndexer`] component keeps the indexes
+//! up to date, and indexed events can be queried using [`EventIndexesDal::get_indexed_logs()`].
+//!
+//! [`EventIndexesDal::get_indexed_logs()`]: zksync_dal::event_indexes_dal::EventIndexesDal::get_indexed_logs()
+
+use std::{fmt, ops, time::Duration};
+
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use zksync_dal::{event_indexes_dal::EventIndexEntry, ConnectionPool};
+use zksync_types::{api::Log, ethabi, Address, MiniblockNumber, H256};
+
+use self::metrics::{IndexLabels, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Index of events of a certain type. Each indexed event is associated with zero or more keys.
+pub trait EventIndex: fmt::Debug + Send + Sync + 'static {
+    /// Unique name of the index. Index entries and progress are persisted under this name, so it must not change
+    /// between node restarts.
+    fn name(&self) -> &'static str;
+
+    /// Returns the address of the contract emitting indexed events, or `None` if events emitted
+    /// by any contract are indexed.
+    fn contract_address(&self) -> Option<Address>;
+
+    /// Returns the first topic (i.e., the hashed event signature) of indexed events.
+    fn topic(&self) -> H256;
+
+    /// Returns keys the event should be indexed by. If no keys are returned, the event is not indexed.
+    fn keys(&self, log: &Log) -> Vec<Vec<u8>>;
+}
+
+/// Collection of [`EventIndex`]es registered by node extensions.
+#[derive(Debug, Default)]
+pub struct EventIndexes(Vec<Box<dyn EventIndex>>);
+
+impl EventIndexes {
+    /// Registers an index.
+    pub fn register<I: EventIndex>(&mut self, index: I) -> &mut Self {
+        self.0.push(Box::new(index));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+static TRANSFER_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+/// Built-in index of ERC-20 transfers (including transfers of the base token). Each transfer is indexed
+/// by the sender and the recipient, both on their own (see [`Self::account_key()`]) and together
+/// with the token address (see [`Self::token_account_key()`]).
+#[derive(Debug)]
+pub struct Erc20TransfersIndex;
+
+impl Erc20TransfersIndex {
+    pub const NAME: &'static str = "erc20_transfers";
+
+    /// Returns the key for transfers of all tokens from or to `account`.
+    pub fn account_key(account: Address) -> Vec<u8> {
+        account.as_bytes().to_vec()
+    }
+
+    /// Returns the key for transfers of `token` from or to `account`.
+    pub fn token_account_key(token: Address, account: Address) -> Vec<u8> {
+        [token.as_bytes(), account.as_bytes()].concat()
+    }
+}
+
+impl EventIndex for Erc20TransfersIndex {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn contract_address(&self) -> Option<Address> {
+        None
+    }
+
+    fn topic(&self) -> H256 {
+        *TRANSFER_EVENT_SIGNATURE
+    }
+
+    fn keys(&self, log: &Log) -> Vec<Vec<u8>> {
+        // ERC-721 transfers have the same signature, but the token ID is indexed as well.
+        let [_, from, to] = log.topics.as_slice() else {
+            return vec![];
+        };
+        let from = Address::from_slice(&from.as_bytes()[12..]);
+        let to = Address::from_slice(&to.as_bytes()[12..]);
+        let mut accounts = vec![from];
+        if to != from {
+            accounts.push(to);
+        }
+        accounts
+            .into_iter()
+            .flat_map(|account| {
+                [
+                    Self::account_key(account),
+                    Self::token_account_key(log.address, account),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Component keeping [`EventIndex`]es up to date. Each index is processed independently, so that a newly registered
+/// index can catch up with the chain history without blocking other indexes.
+#[derive(Debug)]
+pub struct EventIndexer {
+    pool: ConnectionPool,
+    indexes: Vec<Box<dyn EventIndex>>,
+    max_miniblocks_per_chunk: u32,
+    polling_interval: Duration,
+}
+
+impl EventIndexer {
+    const DEFAULT_MAX_MINIBLOCKS_PER_CHUNK: u32 = 1_000;
+    const DEFAULT_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(pool: ConnectionPool, indexes: EventIndexes) -> Self {
+        Self {
+            pool,
+            indexes: indexes.0,
+            max_miniblocks_per_chunk: Self::DEFAULT_MAX_MINIBLOCKS_PER_CHUNK,
+            polling_interval: Self::DEFAULT_POLLING_INTERVAL,
+        }
+    }
+
+    /// Returns the next range of miniblocks to be processed by the index, or `None` if the index is up to date.
+    async fn next_chunk(
+        &self,
+        index: &dyn EventIndex,
+    ) -> anyhow::Result<Option<ops::RangeInclusive<MiniblockNumber>>> {
+        let mut storage = self.pool.access_storage_tagged("event_indexer").await?;
+        let last_indexed_miniblock = storage
+            .event_indexes_dal()
+            .register_index(index.name())
+            .await?;
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        let sealed_miniblock = storage.blocks_dal().get_sealed_miniblock_number().await?;
+
+        let next = |number: Option<MiniblockNumber>| number.map_or(MiniblockNumber(0), |n| n + 1);
+        let first_miniblock =
+            next(last_indexed_miniblock).max(next(pruning_info.last_pruned_miniblock));
+        if first_miniblock > sealed_miniblock {
+            return Ok(None);
+        }
+        let last_miniblock =
+            (first_miniblock + (self.max_miniblocks_per_chunk - 1)).min(sealed_miniblock);
+        Ok(Some(first_miniblock..=last_miniblock))
+    }
+
+    /// Processes the next chunk of miniblocks for the index. Returns `false` if the index is up to date.
+    async fn process_next_chunk(&self, index: &dyn EventIndex) -> anyhow::Result<bool> {
+        let Some(miniblocks) = self.next_chunk(index).await? else {
+            return Ok(false);
+        };
+        let labels = IndexLabels {
+            index: index.name(),
+        };
+        let latency = METRICS.chunk_latency[&labels].start();
+
+        let mut storage = self.pool.access_storage_tagged("event_indexer").await?;
+        let logs = storage
+            .event_indexes_dal()
+            .get_events_for_indexing(miniblocks.clone(), index.contract_address(), index.topic())
+            .await?;
+        let entries: Vec<_> = logs
+            .iter()
+            .flat_map(|log| {
+                let location = log_location(log);
+                index.keys(log).into_iter().map(move |key| {
+                    let (miniblock_number, event_index_in_block) = location;
+                    EventIndexEntry {
+                        key,
+                        miniblock_number,
+                        event_index_in_block,
+                    }
+                })
+            })
+            .collect();
+        storage
+            .event_indexes_dal()
+            .save_index_entries(index.name(), &entries, *miniblocks.end())
+            .await?;
+
+        let latency = latency.observe();
+        METRICS.saved_entries[&labels].inc_by(entries.len() as u64);
+        METRICS.last_indexed_miniblock[&labels].set(miniblocks.end().0.into());
+        tracing::debug!(
+            "Processed miniblocks {miniblocks:?} for index `{}` in {latency:?}; saved {} entries",
+            index.name(),
+            entries.len()
+        );
+        Ok(true)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        if self.indexes.is_empty() {
+            tracing::info!("No event indexes are registered; event indexer is shutting down");
+            return Ok(());
+        }
+        let index_names: Vec<_> = self.indexes.iter().map(|index| index.name()).collect();
+        tracing::info!("Starting event indexer with indexes: {index_names:?}");
+
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            let mut has_progress = false;
+            for index in &self.indexes {
+                match self.process_next_chunk(index.as_ref()).await {
+                    Ok(index_progress) => has_progress |= index_progress,
+                    Err(err) => {
+                        // Errors are most probably transient (e.g., DB connectivity issues). The chunk
+                        // is retried after the polling interval; other indexes are processed as usual.
+                        let labels = IndexLabels {
+                            index: index.name(),
+                        };
+                        METRICS.errors[&labels].inc();
+                        tracing::warn!(
+                            "Failed processing event index `{}`, will retry: {err:#}",
+                            index.name()
+                        );
+                    }
+                }
+            }
+            if has_progress {
+                // Continue without a delay; there may be more miniblocks to process.
+                continue;
+            }
+
+            if tokio::time::timeout(self.polling_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, event indexer is shutting down");
+        Ok(())
+    }
+}
+
+fn log_location(log: &Log) -> (MiniblockNumber, u32) {
+    let miniblock_number = log.block_number.expect("no block number for stored log");
+    let event_index = log.log_index.expect("no index for stored log");
+    (
+        MiniblockNumber(miniblock_number.as_u32()),
+        event_index.as_u32(),
+    )
+}
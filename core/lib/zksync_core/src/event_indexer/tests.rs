@@ -0,0 +1,153 @@
+//! Tests for the event indexer.
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{tx::IncludedTxLocation, L1BatchNumber, L2ChainId, VmEvent};
+
+use super::*;
+use crate::{
+    api_server::web3::tests::create_miniblock,
+    genesis::{ensure_genesis_state, GenesisParams},
+};
+
+fn address_topic(address: Address) -> H256 {
+    H256::from(address)
+}
+
+fn create_transfer_event(token: Address, from: Address, to: Address) -> VmEvent {
+    VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: token,
+        indexed_topics: vec![
+            *TRANSFER_EVENT_SIGNATURE,
+            address_topic(from),
+            address_topic(to),
+        ],
+        value: H256::from_low_u64_be(1).0.to_vec(),
+    }
+}
+
+fn create_log(event: &VmEvent) -> Log {
+    Log {
+        address: event.address,
+        topics: event.indexed_topics.clone(),
+        data: event.value.clone().into(),
+        block_hash: None,
+        block_number: None,
+        l1_batch_number: None,
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        transaction_log_index: None,
+        log_type: None,
+        removed: None,
+    }
+}
+
+#[test]
+fn extracting_erc20_transfer_keys() {
+    let token = Address::repeat_byte(1);
+    let from = Address::repeat_byte(2);
+    let to = Address::repeat_byte(3);
+    let log = create_log(&create_transfer_event(token, from, to));
+
+    let keys = Erc20TransfersIndex.keys(&log);
+    assert_eq!(
+        keys,
+        [
+            Erc20TransfersIndex::account_key(from),
+            Erc20TransfersIndex::token_account_key(token, from),
+            Erc20TransfersIndex::account_key(to),
+            Erc20TransfersIndex::token_account_key(token, to),
+        ]
+    );
+
+    let self_transfer = create_log(&create_transfer_event(token, from, from));
+    assert_eq!(Erc20TransfersIndex.keys(&self_transfer).len(), 2);
+
+    // ERC-721 transfer has an additional indexed topic.
+    let mut nft_transfer = log;
+    nft_transfer.topics.push(H256::from_low_u64_be(1));
+    assert!(Erc20TransfersIndex.keys(&nft_transfer).is_empty());
+}
+
+async fn store_transfers(storage: &mut StorageProcessor<'_>, events: &[VmEvent]) {
+    for (i, event) in events.iter().enumerate() {
+        let miniblock_number = i as u32 + 1;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(miniblock_number))
+            .await
+            .unwrap();
+        let location = IncludedTxLocation {
+            tx_hash: H256::from_low_u64_be(miniblock_number.into()),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        storage
+            .events_dal()
+            .save_events(
+                MiniblockNumber(miniblock_number),
+                &[(location, vec![event])],
+            )
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn indexing_erc20_transfers() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let token = Address::repeat_byte(1);
+    let alice = Address::repeat_byte(2);
+    let bob = Address::repeat_byte(3);
+    let events = [
+        create_transfer_event(token, alice, bob),
+        create_transfer_event(Address::repeat_byte(0xff), bob, alice),
+        create_transfer_event(token, bob, bob),
+    ];
+    store_transfers(&mut storage, &events).await;
+
+    let mut indexes = EventIndexes::default();
+    indexes.register(Erc20TransfersIndex);
+    let mut indexer = EventIndexer::new(pool.clone(), indexes);
+    indexer.max_miniblocks_per_chunk = 2;
+    let index = indexer.indexes[0].as_ref();
+
+    let chunk = indexer.next_chunk(index).await.unwrap();
+    assert_eq!(chunk, Some(MiniblockNumber(0)..=MiniblockNumber(1)));
+    assert!(indexer.process_next_chunk(index).await.unwrap());
+    let chunk = indexer.next_chunk(index).await.unwrap();
+    assert_eq!(chunk, Some(MiniblockNumber(2)..=MiniblockNumber(3)));
+    assert!(indexer.process_next_chunk(index).await.unwrap());
+    assert!(!indexer.process_next_chunk(index).await.unwrap());
+
+    let mut dal = storage.event_indexes_dal();
+    let alice_logs = dal
+        .get_indexed_logs(
+            Erc20TransfersIndex::NAME,
+            &Erc20TransfersIndex::account_key(alice),
+            MiniblockNumber(0),
+            10,
+        )
+        .await
+        .unwrap();
+    let alice_blocks: Vec<_> = alice_logs.iter().map(|log| log.block_number).collect();
+    assert_eq!(alice_blocks, [Some(1.into()), Some(2.into())]);
+
+    let bob_token_logs = dal
+        .get_indexed_logs(
+            Erc20TransfersIndex::NAME,
+            &Erc20TransfersIndex::token_account_key(token, bob),
+            MiniblockNumber(0),
+            10,
+        )
+        .await
+        .unwrap();
+    let bob_token_blocks: Vec<_> = bob_token_logs.iter().map(|log| log.block_number).collect();
+    assert_eq!(bob_token_blocks, [Some(1.into()), Some(3.into())]);
+    assert!(bob_token_logs.iter().all(|log| log.address == token));
+}
@@ -5,12 +5,14 @@ use std::collections::HashMap;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L1BatchHeader},
-    commitment::{L1BatchMetadata, L1BatchWithMetadata},
+    commitment::{L1BatchCommitmentMode, L1BatchMetadata, L1BatchWithMetadata},
     tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     ExecuteTransactionCommon, ProtocolVersionId, Transaction, H256,
 };
 
 mod constants;
+#[cfg(test)]
+mod tests;
 
 use self::constants::*;
 
@@ -93,12 +95,25 @@ pub fn gas_count_from_writes(
     }
 }
 
+/// Estimates L1 gas for committing the L1 batch. In the validium mode, batch pubdata (L2-to-L1 messages,
+/// published bytecodes and state diffs) isn't a part of the commit calldata, so only the base cost
+/// and compressed L2-to-L1 logs are accounted for.
 pub(crate) fn commit_gas_count_for_l1_batch(
     header: &L1BatchHeader,
     unsorted_factory_deps: &HashMap<H256, Vec<u8>>,
     metadata: &L1BatchMetadata,
+    commitment_mode: L1BatchCommitmentMode,
 ) -> u32 {
     let base_cost = l1_batch_base_cost(AggregatedActionType::Commit);
+    let is_pre_boojum = header
+        .protocol_version
+        .map(|v| v.is_pre_boojum())
+        .unwrap_or(true);
+    if commitment_mode == L1BatchCommitmentMode::Validium && !is_pre_boojum {
+        let additional_calldata_bytes = metadata.l2_l1_messages_compressed.len() as u32;
+        return base_cost + additional_calldata_bytes * GAS_PER_BYTE;
+    }
+
     let total_messages_len: u32 = header
         .l2_to_l1_messages
         .iter()
@@ -111,10 +126,6 @@ pub(crate) fn commit_gas_count_for_l1_batch(
         .sum();
 
     // Boojum upgrade changes how storage writes are communicated/compressed.
-    let is_pre_boojum = header
-        .protocol_version
-        .map(|v| v.is_pre_boojum())
-        .unwrap_or(true);
     let state_diff_size = if is_pre_boojum {
         metadata.initial_writes_compressed.len() as u32
             + metadata.repeated_writes_compressed.len() as u32
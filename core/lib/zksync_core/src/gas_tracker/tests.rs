@@ -0,0 +1,66 @@
+//! Tests for L1 gas estimates.
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{Address, L1BatchNumber};
+
+use super::*;
+use crate::state_keeper::tests::create_l1_batch_metadata;
+
+fn create_l1_batch_header(protocol_version: ProtocolVersionId) -> L1BatchHeader {
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        1,
+        Address::default(),
+        BaseSystemContractsHashes::default(),
+        protocol_version,
+    );
+    header.l2_to_l1_messages = vec![vec![1; 32]];
+    header
+}
+
+#[test]
+fn commit_gas_in_validium_mode_excludes_pubdata() {
+    let header = create_l1_batch_header(ProtocolVersionId::latest());
+    let mut metadata = create_l1_batch_metadata(1);
+    metadata.l2_l1_messages_compressed = vec![2; 10];
+    metadata.state_diffs_compressed = vec![3; 100];
+    let factory_deps = HashMap::new();
+
+    let base_cost = l1_batch_base_cost(AggregatedActionType::Commit);
+    let rollup_gas = commit_gas_count_for_l1_batch(
+        &header,
+        &factory_deps,
+        &metadata,
+        L1BatchCommitmentMode::Rollup,
+    );
+    assert_eq!(rollup_gas, base_cost + (100 + 10 + 32) * GAS_PER_BYTE);
+    let validium_gas = commit_gas_count_for_l1_batch(
+        &header,
+        &factory_deps,
+        &metadata,
+        L1BatchCommitmentMode::Validium,
+    );
+    assert_eq!(validium_gas, base_cost + 10 * GAS_PER_BYTE);
+}
+
+#[test]
+fn commit_gas_for_pre_boojum_batch_does_not_depend_on_mode() {
+    let header = create_l1_batch_header(ProtocolVersionId::Version10);
+    let mut metadata = create_l1_batch_metadata(1);
+    metadata.initial_writes_compressed = vec![3; 100];
+    let factory_deps = HashMap::new();
+
+    let rollup_gas = commit_gas_count_for_l1_batch(
+        &header,
+        &factory_deps,
+        &metadata,
+        L1BatchCommitmentMode::Rollup,
+    );
+    let validium_gas = commit_gas_count_for_l1_batch(
+        &header,
+        &factory_deps,
+        &metadata,
+        L1BatchCommitmentMode::Validium,
+    );
+    assert_eq!(rollup_gas, validium_gas);
+}
@@ -1,6 +1,9 @@
 //! This module aims to provide a genesis setup for the zkSync Era network.
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
+//! Optionally, the genesis state can be pre-seeded with a [state dump](GenesisStateDump).
+
+use std::collections::HashMap;
 
 use anyhow::Context as _;
 use zksync_contracts::BaseSystemContracts;
@@ -18,8 +21,11 @@ use zksync_types::{
 };
 use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
 
+pub use self::state_dump::{GenesisAccount, GenesisStateDump};
 use crate::metadata_calculator::L1BatchWithLogs;
 
+mod state_dump;
+
 #[derive(Debug, Clone)]
 pub struct GenesisParams {
     pub first_validator: Address,
@@ -28,6 +34,8 @@ pub struct GenesisParams {
     pub system_contracts: Vec<DeployedContract>,
     pub first_verifier_address: Address,
     pub first_l1_verifier_config: L1VerifierConfig,
+    /// State dump applied on top of the standard genesis state.
+    pub state_dump: Option<GenesisStateDump>,
 }
 
 impl GenesisParams {
@@ -42,6 +50,7 @@ impl GenesisParams {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::zero(),
+            state_dump: None,
         }
     }
 }
@@ -72,7 +81,17 @@ pub async fn ensure_genesis_state(
         system_contracts,
         first_verifier_address,
         first_l1_verifier_config,
+        state_dump,
     } = genesis_params;
+    if let Some(state_dump) = state_dump {
+        state_dump
+            .validate()
+            .context("invalid genesis state dump")?;
+        tracing::info!(
+            "applying genesis state dump with {} accounts",
+            state_dump.accounts.len()
+        );
+    }
 
     let base_system_contracts_hashes = base_system_contracts.hashes();
 
@@ -85,6 +104,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         *first_l1_verifier_config,
         *first_verifier_address,
+        state_dump.as_ref(),
     )
     .await;
     tracing::info!("chain_schema_genesis is complete");
@@ -168,8 +188,11 @@ async fn insert_system_contracts(
     storage: &mut StorageProcessor<'_>,
     contracts: &[DeployedContract],
     chain_id: L2ChainId,
+    state_dump: Option<&GenesisStateDump>,
 ) {
     let system_context_init_logs = (H256::default(), get_system_context_init_logs(chain_id));
+    // State dump logs go last, so that they take precedence over the standard genesis logs.
+    let state_dump_logs = state_dump.map(|dump| (H256::default(), dump.storage_logs()));
 
     let storage_logs: Vec<(H256, Vec<StorageLog>)> = contracts
         .iter()
@@ -183,6 +206,7 @@ async fn insert_system_contracts(
             )
         })
         .chain(Some(system_context_init_logs))
+        .chain(state_dump_logs)
         .collect();
 
     let mut transaction = storage.start_transaction().await.unwrap();
@@ -246,14 +270,18 @@ async fn insert_system_contracts(
         .apply_storage_logs(&storage_logs)
         .await;
 
-    let factory_deps = contracts
+    let mut factory_deps: HashMap<_, _> = contracts
         .iter()
         .map(|c| (hash_bytecode(&c.bytecode), c.bytecode.clone()))
         .collect();
+    if let Some(dump) = state_dump {
+        factory_deps.extend(dump.factory_deps());
+    }
     transaction
         .storage_dal()
-        .insert_factory_deps(MiniblockNumber(0), &factory_deps)
-        .await;
+        .insert_factory_deps_bulk(MiniblockNumber(0), &factory_deps)
+        .await
+        .unwrap();
 
     transaction.commit().await.unwrap();
 }
@@ -268,6 +296,7 @@ pub(crate) async fn create_genesis_l1_batch(
     system_contracts: &[DeployedContract],
     l1_verifier_config: L1VerifierConfig,
     verifier_address: Address,
+    state_dump: Option<&GenesisStateDump>,
 ) {
     let version = ProtocolVersion {
         id: protocol_version,
@@ -296,6 +325,7 @@ pub(crate) async fn create_genesis_l1_batch(
         base_fee_per_gas: 0,
         l1_gas_price: 0,
         l2_fair_gas_price: 0,
+        gas_per_pubdata: Some(0),
         base_system_contracts_hashes: base_system_contracts.hashes(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 0,
@@ -330,7 +360,7 @@ pub(crate) async fn create_genesis_l1_batch(
         .unwrap();
 
     insert_base_system_contracts_to_factory_deps(&mut transaction, base_system_contracts).await;
-    insert_system_contracts(&mut transaction, system_contracts, chain_id).await;
+    insert_system_contracts(&mut transaction, system_contracts, chain_id, state_dump).await;
 
     add_eth_token(&mut transaction).await;
 
@@ -397,7 +427,9 @@ pub(crate) async fn save_genesis_l1_batch_metadata(
 #[cfg(test)]
 mod tests {
     use zksync_dal::ConnectionPool;
-    use zksync_types::system_contracts::get_system_smart_contracts;
+    use zksync_types::{
+        system_contracts::get_system_smart_contracts, utils::storage_key_for_eth_balance, U256,
+    };
 
     use super::*;
 
@@ -414,6 +446,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            state_dump: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
             .await
@@ -434,6 +467,57 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn running_genesis_with_state_dump() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.blocks_dal().delete_genesis().await.unwrap();
+
+        let account = GenesisAccount {
+            address: Address::repeat_byte(0x11),
+            balance: Some(U256::exp10(18)),
+            nonce: Some(5),
+            deployment_nonce: None,
+            bytecode: Some(vec![1; 32].into()),
+            storage: [(H256::zero(), H256::repeat_byte(0x22))].into(),
+        };
+        let mut params = GenesisParams::mock();
+        let root_hash_without_dump = {
+            let pool = ConnectionPool::test_pool().await;
+            let mut conn = pool.access_storage().await.unwrap();
+            conn.blocks_dal().delete_genesis().await.unwrap();
+            ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
+                .await
+                .unwrap()
+        };
+
+        params.state_dump = Some(GenesisStateDump {
+            accounts: vec![account.clone()],
+        });
+        let root_hash = ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
+            .await
+            .unwrap();
+        assert_ne!(root_hash, root_hash_without_dump);
+
+        let balance_key = storage_key_for_eth_balance(&account.address);
+        let balance = conn.storage_dal().get_by_key(&balance_key).await;
+        assert_eq!(balance, Some(u256_to_h256(U256::exp10(18))));
+        let slot_key = StorageKey::new(AccountTreeId::new(account.address), H256::zero());
+        let slot_value = conn.storage_dal().get_by_key(&slot_key).await;
+        assert_eq!(slot_value, Some(H256::repeat_byte(0x22)));
+
+        let bytecode_hash = hash_bytecode(&[1; 32]);
+        let code_key = get_code_key(&account.address);
+        let code_hash = conn.storage_dal().get_by_key(&code_key).await;
+        assert_eq!(code_hash, Some(bytecode_hash));
+        let bytecode = conn
+            .storage_dal()
+            .get_factory_dep(bytecode_hash)
+            .await
+            .unwrap();
+        assert_eq!(bytecode, [1; 32]);
+    }
+
     #[tokio::test]
     async fn running_genesis_with_big_chain_id() {
         let pool = ConnectionPool::test_pool().await;
@@ -447,6 +531,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            state_dump: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::max(), &params)
             .await
@@ -0,0 +1,256 @@
+//! State dumps used to pre-seed the genesis state of a chain, e.g. when migrating an existing app-chain.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_types::{
+    get_code_key, get_known_code_key, get_nonce_key,
+    utils::{nonces_to_full_nonce, storage_key_for_eth_balance},
+    web3::types::Bytes,
+    AccountTreeId, Address, StorageKey, StorageLog, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    u256_to_h256,
+};
+
+/// Maximum address in the kernel space, which is reserved for system contracts.
+const MAX_KERNEL_SPACE_ADDRESS: u64 = 0xffff;
+
+/// Account state in a [`GenesisStateDump`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisAccount {
+    pub address: Address,
+    /// Base token balance of the account.
+    #[serde(default)]
+    pub balance: Option<U256>,
+    /// Transaction nonce of the account.
+    #[serde(default)]
+    pub nonce: Option<u64>,
+    /// Deployment nonce of the account (i.e., the number of contracts deployed by it).
+    #[serde(default)]
+    pub deployment_nonce: Option<u64>,
+    /// Bytecode of the contract deployed at the account address.
+    #[serde(default)]
+    pub bytecode: Option<Bytes>,
+    /// Storage slots of the account.
+    #[serde(default)]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// State dump applied on top of the standard genesis state (i.e., after system contracts are deployed).
+/// Entries in the dump take precedence over the standard genesis state; e.g., the dump may override storage slots
+/// of system contracts.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisStateDump {
+    pub accounts: Vec<GenesisAccount>,
+}
+
+impl GenesisStateDump {
+    /// Loads a state dump from a JSON file and validates it.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed reading genesis state dump from {path:?}"))?;
+        let dump: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("failed parsing genesis state dump from {path:?}"))?;
+        dump.validate()?;
+        Ok(dump)
+    }
+
+    pub(super) fn validate(&self) -> anyhow::Result<()> {
+        let mut addresses = HashSet::with_capacity(self.accounts.len());
+        for account in &self.accounts {
+            let address = account.address;
+            anyhow::ensure!(
+                addresses.insert(address),
+                "account {address:?} is specified multiple times"
+            );
+            if let Some(bytecode) = &account.bytecode {
+                anyhow::ensure!(
+                    address > Address::from_low_u64_be(MAX_KERNEL_SPACE_ADDRESS),
+                    "bytecode for {address:?} cannot be specified since it is in the kernel space"
+                );
+                validate_bytecode(&bytecode.0)
+                    .with_context(|| format!("invalid bytecode for {address:?}"))?;
+            }
+        }
+
+        let mut keys = HashSet::new();
+        for log in self.storage_logs() {
+            anyhow::ensure!(
+                keys.insert(log.key),
+                "storage slot {:?} of {:?} is written multiple times",
+                log.key.key(),
+                log.key.address()
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns storage logs for all entries in the dump.
+    pub(super) fn storage_logs(&self) -> Vec<StorageLog> {
+        let mut logs = vec![];
+        for account in &self.accounts {
+            let address = &account.address;
+            if let Some(balance) = account.balance {
+                let key = storage_key_for_eth_balance(address);
+                logs.push(StorageLog::new_write_log(key, u256_to_h256(balance)));
+            }
+            if account.nonce.is_some() || account.deployment_nonce.is_some() {
+                let full_nonce = nonces_to_full_nonce(
+                    account.nonce.unwrap_or(0).into(),
+                    account.deployment_nonce.unwrap_or(0).into(),
+                );
+                logs.push(StorageLog::new_write_log(
+                    get_nonce_key(address),
+                    u256_to_h256(full_nonce),
+                ));
+            }
+            if let Some(bytecode) = &account.bytecode {
+                let hash = hash_bytecode(&bytecode.0);
+                logs.push(StorageLog::new_write_log(get_code_key(address), hash));
+                // Mark the bytecode as known, so that it can be used to deploy new contracts.
+                logs.push(StorageLog::new_write_log(
+                    get_known_code_key(&hash),
+                    H256::from_low_u64_be(1),
+                ));
+            }
+            for (&key, &value) in &account.storage {
+                let key = StorageKey::new(AccountTreeId::new(*address), key);
+                logs.push(StorageLog::new_write_log(key, value));
+            }
+        }
+        logs
+    }
+
+    /// Returns bytecodes of all contracts in the dump keyed by their hashes.
+    pub(super) fn factory_deps(&self) -> HashMap<H256, Vec<u8>> {
+        self.accounts
+            .iter()
+            .filter_map(|account| {
+                let bytecode = account.bytecode.as_ref()?;
+                Some((hash_bytecode(&bytecode.0), bytecode.0.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_bytecode() -> Vec<u8> {
+        vec![1; 32]
+    }
+
+    #[test]
+    fn parsing_state_dump() {
+        let json = serde_json::json!({
+            "accounts": [
+                {
+                    "address": "0x0000000000000000000000000000000000010001",
+                    "balance": "0x3e8",
+                    "nonce": 3,
+                },
+                {
+                    "address": "0x0000000000000000000000000000000000010002",
+                    "bytecode": format!("0x{}", hex::encode(mock_bytecode())),
+                    "deployment_nonce": 1,
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x0000000000000000000000000000000000000000000000000000000000000002",
+                    },
+                },
+            ],
+        });
+        let dump: GenesisStateDump = serde_json::from_value(json).unwrap();
+        dump.validate().unwrap();
+
+        let first_address = Address::from_low_u64_be(0x1_0001);
+        let second_address = Address::from_low_u64_be(0x1_0002);
+        let bytecode_hash = hash_bytecode(&mock_bytecode());
+        let logs = dump.storage_logs();
+        let expected_logs = [
+            (
+                storage_key_for_eth_balance(&first_address),
+                H256::from_low_u64_be(1_000),
+            ),
+            (get_nonce_key(&first_address), H256::from_low_u64_be(3)),
+            (
+                get_nonce_key(&second_address),
+                u256_to_h256(U256::one() << 128),
+            ),
+            (get_code_key(&second_address), bytecode_hash),
+            (get_known_code_key(&bytecode_hash), H256::from_low_u64_be(1)),
+            (
+                StorageKey::new(AccountTreeId::new(second_address), H256::from_low_u64_be(1)),
+                H256::from_low_u64_be(2),
+            ),
+        ];
+        let logs: Vec<_> = logs.iter().map(|log| (log.key, log.value)).collect();
+        assert_eq!(logs, expected_logs);
+
+        let factory_deps = dump.factory_deps();
+        assert_eq!(factory_deps.len(), 1);
+        assert_eq!(factory_deps[&bytecode_hash], mock_bytecode());
+    }
+
+    #[test]
+    fn validating_state_dump() {
+        let account = GenesisAccount {
+            address: Address::from_low_u64_be(0x1_0001),
+            balance: Some(1.into()),
+            nonce: None,
+            deployment_nonce: None,
+            bytecode: None,
+            storage: BTreeMap::new(),
+        };
+        let dump = GenesisStateDump {
+            accounts: vec![account.clone(), account.clone()],
+        };
+        let err = dump.validate().unwrap_err().to_string();
+        assert!(err.contains("multiple times"), "{err}");
+
+        let kernel_account = GenesisAccount {
+            address: Address::from_low_u64_be(0x8008),
+            bytecode: Some(mock_bytecode().into()),
+            ..account.clone()
+        };
+        let dump = GenesisStateDump {
+            accounts: vec![kernel_account],
+        };
+        let err = dump.validate().unwrap_err().to_string();
+        assert!(err.contains("kernel space"), "{err}");
+
+        let invalid_bytecode_account = GenesisAccount {
+            bytecode: Some(vec![1; 64].into()),
+            ..account.clone()
+        };
+        let dump = GenesisStateDump {
+            accounts: vec![invalid_bytecode_account],
+        };
+        let err = dump.validate().unwrap_err().to_string();
+        assert!(err.contains("invalid bytecode"), "{err}");
+
+        // Overwrites the balance set via the `balance` field.
+        let balance_key = storage_key_for_eth_balance(&account.address);
+        let balance_account = GenesisAccount {
+            address: *balance_key.address(),
+            balance: None,
+            storage: BTreeMap::from([(*balance_key.key(), H256::repeat_byte(1))]),
+            ..account.clone()
+        };
+        let dump = GenesisStateDump {
+            accounts: vec![account, balance_account],
+        };
+        let err = dump.validate().unwrap_err().to_string();
+        assert!(err.contains("written multiple times"), "{err}");
+    }
+}
@@ -0,0 +1,375 @@
+//! Health checks reporting processing lags of node components based on Postgres data.
+
+use anyhow::Context as _;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_utils::time::seconds_since_epoch;
+
+/// Thresholds for a lag value determining whether a component is degraded or unhealthy.
+/// If a threshold is not set, the corresponding status is never reported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LagThresholds {
+    pub degraded: Option<u64>,
+    pub unhealthy: Option<u64>,
+}
+
+impl LagThresholds {
+    fn status(&self, lag: u64) -> HealthStatus {
+        let exceeds =
+            |threshold: Option<u64>| threshold.map_or(false, |threshold| lag >= threshold);
+        if exceeds(self.unhealthy) {
+            HealthStatus::Unhealthy
+        } else if exceeds(self.degraded) {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ready
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorDetails {
+    error: String,
+}
+
+fn error_health(err: &anyhow::Error) -> Health {
+    Health::from(HealthStatus::Unhealthy).with_details(ErrorDetails {
+        error: format!("{err:#}"),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct StateKeeperLagDetails {
+    last_miniblock: MiniblockNumber,
+    last_miniblock_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_pending_tx_received_at: Option<u64>,
+    lag_sec: u64,
+}
+
+/// Health check for the state keeper based on the time the oldest transaction received after the last sealed
+/// miniblock waits for inclusion. If there are no such transactions, the chain is idle, and the state keeper
+/// is considered healthy regardless of the last miniblock timestamp.
+#[derive(Debug)]
+pub struct StateKeeperLagHealthCheck {
+    pool: ConnectionPool,
+    thresholds: LagThresholds,
+}
+
+impl StateKeeperLagHealthCheck {
+    pub fn new(pool: ConnectionPool, thresholds: LagThresholds) -> Self {
+        Self { pool, thresholds }
+    }
+
+    async fn check_health_inner(&self) -> anyhow::Result<Health> {
+        let mut storage = self.pool.access_storage_tagged("health_check").await?;
+        let header = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("failed getting last sealed miniblock header")?;
+        let Some(header) = header else {
+            return Ok(HealthStatus::NotReady.into());
+        };
+
+        let last_miniblock_time = NaiveDateTime::from_timestamp_opt(header.timestamp as i64, 0)
+            .with_context(|| {
+                format!(
+                    "invalid timestamp {} of miniblock #{}",
+                    header.timestamp, header.number
+                )
+            })?;
+        let oldest_pending_tx_received_at = storage
+            .transactions_dal()
+            .get_oldest_pending_tx_received_at(last_miniblock_time)
+            .await
+            .context("failed getting oldest pending transaction")?
+            .map(|received_at| received_at.timestamp().max(0) as u64);
+
+        let lag_sec = oldest_pending_tx_received_at.map_or(0, |received_at| {
+            seconds_since_epoch().saturating_sub(received_at)
+        });
+        let details = StateKeeperLagDetails {
+            last_miniblock: header.number,
+            last_miniblock_timestamp: header.timestamp,
+            oldest_pending_tx_received_at,
+            lag_sec,
+        };
+        Ok(Health::from(self.thresholds.status(lag_sec)).with_details(details))
+    }
+}
+
+#[async_trait]
+impl CheckHealth for StateKeeperLagHealthCheck {
+    fn name(&self) -> &'static str {
+        "state_keeper_lag"
+    }
+
+    async fn check_health(&self) -> Health {
+        self.check_health_inner()
+            .await
+            .unwrap_or_else(|err| error_health(&err))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TreeLagDetails {
+    sealed_l1_batch: L1BatchNumber,
+    last_l1_batch_with_metadata: L1BatchNumber,
+    lag: u64,
+}
+
+/// Health check for the Merkle tree based on the number of sealed L1 batches without computed metadata.
+#[derive(Debug)]
+pub struct TreeLagHealthCheck {
+    pool: ConnectionPool,
+    thresholds: LagThresholds,
+}
+
+impl TreeLagHealthCheck {
+    pub fn new(pool: ConnectionPool, thresholds: LagThresholds) -> Self {
+        Self { pool, thresholds }
+    }
+
+    async fn check_health_inner(&self) -> anyhow::Result<Health> {
+        let mut storage = self.pool.access_storage_tagged("health_check").await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?;
+        let Some(last_l1_batch_with_metadata) = last_l1_batch_with_metadata else {
+            // Genesis or snapshot recovery is not completed yet.
+            return Ok(HealthStatus::NotReady.into());
+        };
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+
+        let lag = u64::from(
+            sealed_l1_batch
+                .0
+                .saturating_sub(last_l1_batch_with_metadata.0),
+        );
+        let details = TreeLagDetails {
+            sealed_l1_batch,
+            last_l1_batch_with_metadata,
+            lag,
+        };
+        Ok(Health::from(self.thresholds.status(lag)).with_details(details))
+    }
+}
+
+#[async_trait]
+impl CheckHealth for TreeLagHealthCheck {
+    fn name(&self) -> &'static str {
+        "tree_lag"
+    }
+
+    async fn check_health(&self) -> Health {
+        self.check_health_inner()
+            .await
+            .unwrap_or_else(|err| error_health(&err))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EthSenderDetails {
+    inflight_tx_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_inflight_nonce: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_inflight_tx_age_sec: Option<u64>,
+    failed_tx_count: i64,
+}
+
+/// Health check for `eth_sender` based on the age of the oldest inflight L1 transaction. `eth_sender` is considered
+/// degraded if there are failed L1 transactions, since it stops sending new transactions in this case.
+///
+/// Since `eth_sender` issues don't affect serving API requests, the check never reports a status
+/// worse than [`HealthStatus::Degraded`], so that it doesn't make the node not ready.
+#[derive(Debug)]
+pub struct EthSenderHealthCheck {
+    pool: ConnectionPool,
+    thresholds: LagThresholds,
+}
+
+impl EthSenderHealthCheck {
+    pub fn new(pool: ConnectionPool, degraded_lag_sec: Option<u64>) -> Self {
+        let thresholds = LagThresholds {
+            degraded: degraded_lag_sec,
+            unhealthy: None,
+        };
+        Self { pool, thresholds }
+    }
+
+    async fn check_health_inner(&self) -> anyhow::Result<Health> {
+        let mut storage = self.pool.access_storage_tagged("health_check").await?;
+        let inflight_txs = storage
+            .eth_sender_dal()
+            .get_inflight_txs()
+            .await
+            .context("failed getting inflight L1 transactions")?;
+        let failed_tx_count = storage
+            .eth_sender_dal()
+            .get_number_of_failed_transactions()
+            .await?;
+
+        let oldest_inflight_tx = inflight_txs.iter().min_by_key(|tx| tx.nonce);
+        let oldest_inflight_tx_age_sec = oldest_inflight_tx
+            .map(|tx| seconds_since_epoch().saturating_sub(tx.created_at_timestamp));
+        let status = if failed_tx_count > 0 {
+            HealthStatus::Degraded
+        } else {
+            self.thresholds
+                .status(oldest_inflight_tx_age_sec.unwrap_or(0))
+        };
+        let details = EthSenderDetails {
+            inflight_tx_count: inflight_txs.len(),
+            oldest_inflight_nonce: oldest_inflight_tx.map(|tx| tx.nonce.0.into()),
+            oldest_inflight_tx_age_sec,
+            failed_tx_count,
+        };
+        Ok(Health::from(status).with_details(details))
+    }
+}
+
+#[async_trait]
+impl CheckHealth for EthSenderHealthCheck {
+    fn name(&self) -> &'static str {
+        "eth_sender"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.check_health_inner().await {
+            Ok(health) => health,
+            Err(err) => Health::from(HealthStatus::Degraded).with_details(ErrorDetails {
+                error: format!("{err:#}"),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        fee::TransactionExecutionMetrics,
+        Address, L2ChainId, ProtocolVersionId,
+    };
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        state_keeper::tests::create_l2_transaction,
+    };
+
+    #[test]
+    fn lag_thresholds() {
+        let thresholds = LagThresholds::default();
+        assert_eq!(thresholds.status(u64::MAX), HealthStatus::Ready);
+
+        let thresholds = LagThresholds {
+            degraded: Some(10),
+            unhealthy: Some(100),
+        };
+        assert_eq!(thresholds.status(0), HealthStatus::Ready);
+        assert_eq!(thresholds.status(10), HealthStatus::Degraded);
+        assert_eq!(thresholds.status(99), HealthStatus::Degraded);
+        assert_eq!(thresholds.status(100), HealthStatus::Unhealthy);
+
+        let thresholds = LagThresholds {
+            degraded: None,
+            unhealthy: Some(100),
+        };
+        assert_eq!(thresholds.status(99), HealthStatus::Ready);
+        assert_eq!(thresholds.status(100), HealthStatus::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn lag_health_checks() {
+        let pool = ConnectionPool::test_pool().await;
+        let thresholds = LagThresholds {
+            degraded: Some(1),
+            unhealthy: Some(2),
+        };
+        let state_keeper_check = StateKeeperLagHealthCheck::new(pool.clone(), thresholds);
+        let tree_check = TreeLagHealthCheck::new(pool.clone(), thresholds);
+        assert_eq!(
+            state_keeper_check.check_health().await.status(),
+            HealthStatus::NotReady
+        );
+        assert_eq!(
+            tree_check.check_health().await.status(),
+            HealthStatus::NotReady
+        );
+
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        // The genesis miniblock has zero timestamp, but the chain is idle.
+        assert_eq!(
+            state_keeper_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+        assert_eq!(
+            tree_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        assert_eq!(
+            tree_check.check_health().await.status(),
+            HealthStatus::Degraded
+        );
+
+        let eth_sender_check = EthSenderHealthCheck::new(pool.clone(), Some(1));
+        assert_eq!(
+            eth_sender_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+    }
+
+    #[tokio::test]
+    async fn state_keeper_lag_is_measured_from_pending_transactions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        // Any pending transaction makes the state keeper unhealthy.
+        let thresholds = LagThresholds {
+            degraded: None,
+            unhealthy: Some(0),
+        };
+        let state_keeper_check = StateKeeperLagHealthCheck::new(pool.clone(), thresholds);
+        assert_eq!(
+            state_keeper_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+
+        let tx = create_l2_transaction(10, 100);
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await;
+        assert_eq!(
+            state_keeper_check.check_health().await.status(),
+            HealthStatus::Unhealthy
+        );
+    }
+}
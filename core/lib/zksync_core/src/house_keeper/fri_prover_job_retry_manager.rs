@@ -3,12 +3,14 @@ use std::time::Duration;
 use async_trait::async_trait;
 use zksync_dal::ConnectionPool;
 use zksync_prover_utils::periodic_job::PeriodicJob;
+use zksync_types::proofs::JobRetryBackoff;
 
 #[derive(Debug)]
 pub struct FriProverJobRetryManager {
     pool: ConnectionPool,
     max_attempts: u32,
     processing_timeout: Duration,
+    retry_backoff: JobRetryBackoff,
     retry_interval_ms: u64,
 }
 
@@ -16,37 +18,55 @@ impl FriProverJobRetryManager {
     pub fn new(
         max_attempts: u32,
         processing_timeout: Duration,
+        retry_backoff: JobRetryBackoff,
         retry_interval_ms: u64,
         pool: ConnectionPool,
     ) -> Self {
         Self {
             max_attempts,
             processing_timeout,
+            retry_backoff,
             retry_interval_ms,
             pool,
         }
     }
 }
 
-/// Invoked periodically to re-queue stuck fri prover jobs.
+/// Invoked periodically to re-queue stuck fri prover jobs. Failed jobs are re-queued with exponential backoff,
+/// and jobs that fail `max_attempts` times are quarantined.
 #[async_trait]
 impl PeriodicJob for FriProverJobRetryManager {
     const SERVICE_NAME: &'static str = "FriProverJobRetryManager";
 
     async fn run_routine_task(&mut self) -> anyhow::Result<()> {
-        let stuck_jobs = self
-            .pool
-            .access_storage()
-            .await
-            .unwrap()
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let stuck_jobs = storage
             .fri_prover_jobs_dal()
-            .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
+            .requeue_stuck_jobs(
+                self.processing_timeout,
+                self.max_attempts,
+                self.retry_backoff,
+            )
             .await;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
             tracing::info!("re-queuing fri prover job {:?}", stuck_job);
         }
         metrics::counter!("server.prover_fri.requeued_jobs", job_len as u64);
+
+        let quarantined_jobs = storage
+            .fri_prover_jobs_dal()
+            .quarantine_failed_jobs(self.max_attempts)
+            .await;
+        let job_len = quarantined_jobs.len();
+        for quarantined_job in quarantined_jobs {
+            tracing::error!(
+                "quarantining fri prover job {:?} after {} failed attempts",
+                quarantined_job,
+                self.max_attempts
+            );
+        }
+        metrics::counter!("server.prover_fri.quarantined_jobs", job_len as u64);
         Ok(())
     }
 
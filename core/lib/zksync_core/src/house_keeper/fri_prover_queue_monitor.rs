@@ -72,6 +72,15 @@ impl PeriodicJob for FriProverStatsReporter {
               "aggregation_round" => aggregation_round.to_string(),
               "prover_group_id" => group_id.to_string(),
             );
+
+            metrics::gauge!(
+              "fri_prover.prover.jobs",
+              stats.quarantined as f64,
+              "type" => "quarantined",
+              "circuit_id" => circuit_id.to_string(),
+              "aggregation_round" => aggregation_round.to_string(),
+              "prover_group_id" => group_id.to_string(),
+            );
         }
 
         let lag_by_circuit_type = conn
@@ -3,12 +3,14 @@ use std::time::Duration;
 use async_trait::async_trait;
 use zksync_dal::ConnectionPool;
 use zksync_prover_utils::periodic_job::PeriodicJob;
+use zksync_types::proofs::{JobRetryBackoff, StuckJobs};
 
 #[derive(Debug)]
 pub struct FriWitnessGeneratorJobRetryManager {
     pool: ConnectionPool,
     max_attempts: u32,
     processing_timeout: Duration,
+    retry_backoff: JobRetryBackoff,
     retry_interval_ms: u64,
 }
 
@@ -16,12 +18,14 @@ impl FriWitnessGeneratorJobRetryManager {
     pub fn new(
         max_attempts: u32,
         processing_timeout: Duration,
+        retry_backoff: JobRetryBackoff,
         retry_interval_ms: u64,
         pool: ConnectionPool,
     ) -> Self {
         Self {
             max_attempts,
             processing_timeout,
+            retry_backoff,
             retry_interval_ms,
             pool,
         }
@@ -34,7 +38,11 @@ impl FriWitnessGeneratorJobRetryManager {
             .await
             .unwrap()
             .fri_witness_generator_dal()
-            .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
+            .requeue_stuck_jobs(
+                self.processing_timeout,
+                self.max_attempts,
+                self.retry_backoff,
+            )
             .await;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
@@ -50,7 +58,11 @@ impl FriWitnessGeneratorJobRetryManager {
             .await
             .unwrap()
             .fri_witness_generator_dal()
-            .requeue_stuck_leaf_aggregations_jobs(self.processing_timeout, self.max_attempts)
+            .requeue_stuck_leaf_aggregations_jobs(
+                self.processing_timeout,
+                self.max_attempts,
+                self.retry_backoff,
+            )
             .await;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
@@ -69,7 +81,11 @@ impl FriWitnessGeneratorJobRetryManager {
             .await
             .unwrap()
             .fri_witness_generator_dal()
-            .requeue_stuck_node_aggregations_jobs(self.processing_timeout, self.max_attempts)
+            .requeue_stuck_node_aggregations_jobs(
+                self.processing_timeout,
+                self.max_attempts,
+                self.retry_backoff,
+            )
             .await;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
@@ -88,7 +104,11 @@ impl FriWitnessGeneratorJobRetryManager {
             .await
             .unwrap()
             .fri_witness_generator_dal()
-            .requeue_stuck_scheduler_jobs(self.processing_timeout, self.max_attempts)
+            .requeue_stuck_scheduler_jobs(
+                self.processing_timeout,
+                self.max_attempts,
+                self.retry_backoff,
+            )
             .await;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
@@ -96,9 +116,59 @@ impl FriWitnessGeneratorJobRetryManager {
         }
         metrics::counter!("server.scheduler_jobs_fri.requeued_jobs", job_len as u64);
     }
+
+    fn report_quarantined_jobs(&self, kind: &str, quarantined_jobs: Vec<StuckJobs>) {
+        for quarantined_job in quarantined_jobs {
+            tracing::error!(
+                "quarantining fri {kind} job {:?} after {} failed attempts",
+                quarantined_job,
+                self.max_attempts
+            );
+        }
+    }
+
+    pub async fn quarantine_failed_jobs(&mut self) {
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let mut dal = storage.fri_witness_generator_dal();
+
+        let jobs = dal.quarantine_failed_jobs(self.max_attempts).await;
+        metrics::counter!(
+            "server.witness_inputs_fri.quarantined_jobs",
+            jobs.len() as u64
+        );
+        self.report_quarantined_jobs("witness input", jobs);
+
+        let jobs = dal
+            .quarantine_failed_leaf_aggregations_jobs(self.max_attempts)
+            .await;
+        metrics::counter!(
+            "server.leaf_aggregations_jobs_fri.quarantined_jobs",
+            jobs.len() as u64
+        );
+        self.report_quarantined_jobs("leaf aggregation", jobs);
+
+        let jobs = dal
+            .quarantine_failed_node_aggregations_jobs(self.max_attempts)
+            .await;
+        metrics::counter!(
+            "server.node_aggregations_jobs_fri.quarantined_jobs",
+            jobs.len() as u64
+        );
+        self.report_quarantined_jobs("node aggregation", jobs);
+
+        let jobs = dal
+            .quarantine_failed_scheduler_jobs(self.max_attempts)
+            .await;
+        metrics::counter!(
+            "server.scheduler_jobs_fri.quarantined_jobs",
+            jobs.len() as u64
+        );
+        self.report_quarantined_jobs("scheduler", jobs);
+    }
 }
 
-/// Invoked periodically to re-queue stuck fri witness generator jobs.
+/// Invoked periodically to re-queue stuck fri witness generator jobs. Failed jobs are re-queued with exponential
+/// backoff, and jobs that fail `max_attempts` times are quarantined.
 #[async_trait]
 impl PeriodicJob for FriWitnessGeneratorJobRetryManager {
     const SERVICE_NAME: &'static str = "FriWitnessGeneratorJobRetryManager";
@@ -108,6 +178,7 @@ impl PeriodicJob for FriWitnessGeneratorJobRetryManager {
         self.requeue_stuck_leaf_aggregations_jobs().await;
         self.requeue_stuck_node_aggregations_jobs().await;
         self.requeue_stuck_scheduler_jobs().await;
+        self.quarantine_failed_jobs().await;
         Ok(())
     }
 
@@ -75,6 +75,13 @@ fn emit_metrics_for_round(round: AggregationRound, stats: JobCountStatistics) {
         "type" => "in_progress",
         "round" => format!("{:?}", round)
     );
+
+    metrics::gauge!(
+        format!("server.{}.jobs", FRI_WITNESS_GENERATOR_SERVICE_NAME),
+        stats.quarantined as f64,
+        "type" => "quarantined",
+        "round" => format!("{:?}", round)
+    );
 }
 
 /// Invoked periodically to push job statistics to Prometheus
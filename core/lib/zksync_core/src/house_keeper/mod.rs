@@ -8,5 +8,6 @@ pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
 pub mod gpu_prover_queue_monitor;
 pub mod prover_job_retry_manager;
+pub(crate) mod prover_queue_api;
 pub mod prover_queue_monitor;
 pub mod waiting_to_queued_fri_witness_job_mover;
@@ -0,0 +1,330 @@
+//! HTTP API allowing prover farm operators to inspect prover queues and manage prover jobs.
+//!
+//! Requests must be authenticated with the `Authorization: Bearer <auth_token>` header.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_types::{
+    proofs::{AggregationRound, FriProverJobInfo, JobCountStatistics},
+    L1BatchNumber,
+};
+
+const AGGREGATION_ROUNDS: [AggregationRound; 4] = [
+    AggregationRound::BasicCircuits,
+    AggregationRound::LeafAggregation,
+    AggregationRound::NodeAggregation,
+    AggregationRound::Scheduler,
+];
+
+#[derive(Debug)]
+enum ApiError {
+    Unauthorized,
+    NotFound(String),
+    Storage(SqlxError),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status_code, message) = match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "Invalid auth token".to_owned()),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            Self::Storage(err) => {
+                tracing::error!("Prover API storage error: {err}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed accessing prover database".to_owned(),
+                )
+            }
+        };
+        (status_code, message).into_response()
+    }
+}
+
+impl ApiError {
+    fn from_update_error(err: SqlxError, not_found_message: impl FnOnce() -> String) -> Self {
+        match err {
+            SqlxError::RowNotFound => Self::NotFound(not_found_message()),
+            err => Self::Storage(err),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JobCounts {
+    queued: usize,
+    in_progress: usize,
+    failed: usize,
+    quarantined: usize,
+}
+
+impl From<JobCountStatistics> for JobCounts {
+    fn from(stats: JobCountStatistics) -> Self {
+        Self {
+            queued: stats.queued,
+            in_progress: stats.in_progress,
+            failed: stats.failed,
+            quarantined: stats.quarantined,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WitnessGeneratorQueue {
+    aggregation_round: AggregationRound,
+    #[serde(flatten)]
+    jobs: JobCounts,
+}
+
+#[derive(Debug, Serialize)]
+struct ProverQueue {
+    circuit_id: u8,
+    aggregation_round: AggregationRound,
+    #[serde(flatten)]
+    jobs: JobCounts,
+}
+
+#[derive(Debug, Serialize)]
+struct QueuesResponse {
+    witness_generators: Vec<WitnessGeneratorQueue>,
+    provers: Vec<ProverQueue>,
+    proof_compressor: JobCounts,
+}
+
+#[derive(Debug, Serialize)]
+struct RequeuedJobsResponse {
+    witness_generator_jobs: u64,
+    prover_jobs: u64,
+}
+
+#[derive(Debug)]
+struct ProverQueueApi {
+    expected_auth_header: String,
+    prover_max_attempts: u32,
+    pool: ConnectionPool,
+}
+
+impl ProverQueueApi {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), ApiError> {
+        let auth_header = headers.get(header::AUTHORIZATION);
+        if auth_header.map(|value| value.as_bytes()) != Some(self.expected_auth_header.as_bytes()) {
+            return Err(ApiError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    async fn queues(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+    ) -> Result<Json<QueuesResponse>, ApiError> {
+        api.authorize(&headers)?;
+        let mut storage = api.pool.access_storage().await.unwrap();
+
+        let mut witness_generators = Vec::with_capacity(AGGREGATION_ROUNDS.len());
+        for aggregation_round in AGGREGATION_ROUNDS {
+            let stats = storage
+                .fri_witness_generator_dal()
+                .get_witness_jobs_stats(aggregation_round)
+                .await;
+            witness_generators.push(WitnessGeneratorQueue {
+                aggregation_round,
+                jobs: stats.into(),
+            });
+        }
+
+        let mut provers: Vec<_> = storage
+            .fri_prover_jobs_dal()
+            .get_prover_jobs_stats()
+            .await
+            .into_iter()
+            .map(|((circuit_id, aggregation_round), stats)| ProverQueue {
+                circuit_id,
+                aggregation_round: aggregation_round.into(),
+                jobs: stats.into(),
+            })
+            .collect();
+        provers.sort_unstable_by_key(|queue| (queue.aggregation_round as u8, queue.circuit_id));
+
+        let proof_compressor = storage.fri_proof_compressor_dal().get_jobs_stats().await;
+        Ok(Json(QueuesResponse {
+            witness_generators,
+            provers,
+            proof_compressor: proof_compressor.into(),
+        }))
+    }
+
+    async fn prover_jobs(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<Json<Vec<FriProverJobInfo>>, ApiError> {
+        api.authorize(&headers)?;
+        let mut storage = api.pool.access_storage().await.unwrap();
+        let jobs = storage
+            .fri_prover_jobs_dal()
+            .get_prover_jobs_for_l1_batch(L1BatchNumber(l1_batch_number))
+            .await;
+        Ok(Json(jobs))
+    }
+
+    async fn requeue_prover_job(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(id): Path<u32>,
+    ) -> Result<(), ApiError> {
+        api.authorize(&headers)?;
+        let mut storage = api.pool.access_storage().await.unwrap();
+        storage
+            .fri_prover_jobs_dal()
+            .requeue_prover_job(id)
+            .await
+            .map_err(|err| {
+                ApiError::from_update_error(err, || {
+                    format!("Prover job #{id} doesn't exist or is finished")
+                })
+            })?;
+        tracing::info!("Prover job #{id} was requeued by operator");
+        Ok(())
+    }
+
+    /// Releases all quarantined witness generator and prover jobs for an L1 batch, so that they are retried.
+    async fn requeue_quarantined_jobs(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<Json<RequeuedJobsResponse>, ApiError> {
+        api.authorize(&headers)?;
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let mut storage = api.pool.access_storage().await.unwrap();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(ApiError::Storage)?;
+        let witness_generator_jobs = transaction
+            .fri_witness_generator_dal()
+            .requeue_quarantined_jobs(l1_batch_number)
+            .await
+            .map_err(ApiError::Storage)?;
+        let prover_jobs = transaction
+            .fri_prover_jobs_dal()
+            .requeue_quarantined_jobs(l1_batch_number)
+            .await
+            .map_err(ApiError::Storage)?;
+        transaction.commit().await.map_err(ApiError::Storage)?;
+
+        tracing::info!(
+            "{witness_generator_jobs} witness generator and {prover_jobs} prover jobs quarantined \
+             for L1 batch #{l1_batch_number} were requeued by operator"
+        );
+        Ok(Json(RequeuedJobsResponse {
+            witness_generator_jobs,
+            prover_jobs,
+        }))
+    }
+
+    async fn abort_prover_job(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(id): Path<u32>,
+    ) -> Result<(), ApiError> {
+        api.authorize(&headers)?;
+        let mut storage = api.pool.access_storage().await.unwrap();
+        storage
+            .fri_prover_jobs_dal()
+            .abort_prover_job(id, api.prover_max_attempts)
+            .await
+            .map_err(|err| {
+                ApiError::from_update_error(err, || {
+                    format!("Prover job #{id} doesn't exist or is finished")
+                })
+            })?;
+        tracing::info!("Prover job #{id} was aborted by operator");
+        Ok(())
+    }
+
+    async fn prioritize_l1_batch(
+        State(api): State<Arc<Self>>,
+        headers: HeaderMap,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<(), ApiError> {
+        api.authorize(&headers)?;
+        let mut storage = api.pool.access_storage().await.unwrap();
+        storage
+            .fri_witness_generator_dal()
+            .mark_witness_job_as_high_priority(L1BatchNumber(l1_batch_number))
+            .await
+            .map_err(|err| {
+                ApiError::from_update_error(err, || {
+                    format!("Witness inputs for L1 batch #{l1_batch_number} are not received yet")
+                })
+            })?;
+        tracing::info!(
+            "Witness generation for L1 batch #{l1_batch_number} was prioritized by operator"
+        );
+        Ok(())
+    }
+}
+
+/// Runs the prover queue API server.
+pub(crate) async fn run_server(
+    port: u16,
+    auth_token: String,
+    prover_max_attempts: u32,
+    pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = SocketAddr::from(([0, 0, 0, 0], port));
+    tracing::debug!("Starting prover queue API server on {bind_address}");
+    let api = ProverQueueApi {
+        expected_auth_header: format!("Bearer {auth_token}"),
+        prover_max_attempts,
+        pool,
+    };
+    let app = Router::new()
+        .route("/queues", get(ProverQueueApi::queues))
+        .route(
+            "/l1_batches/:l1_batch_number/prover_jobs",
+            get(ProverQueueApi::prover_jobs),
+        )
+        .route(
+            "/l1_batches/:l1_batch_number/prioritize",
+            post(ProverQueueApi::prioritize_l1_batch),
+        )
+        .route(
+            "/l1_batches/:l1_batch_number/requeue_quarantined",
+            post(ProverQueueApi::requeue_quarantined_jobs),
+        )
+        .route(
+            "/prover_jobs/:id/requeue",
+            post(ProverQueueApi::requeue_prover_job),
+        )
+        .route(
+            "/prover_jobs/:id/abort",
+            post(ProverQueueApi::abort_prover_job),
+        )
+        .with_state(Arc::new(api));
+
+    axum::Server::try_bind(&bind_address)
+        .with_context(|| format!("Failed binding prover queue API server to {bind_address}"))?
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!("Stop signal sender for prover queue API server was dropped without sending a signal");
+            }
+            tracing::info!("Stop signal received, prover queue API server is shutting down");
+        })
+        .await
+        .context("Prover queue API server failed")?;
+    tracing::info!("Prover queue API server shut down");
+    Ok(())
+}
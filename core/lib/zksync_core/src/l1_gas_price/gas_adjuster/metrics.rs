@@ -6,6 +6,8 @@ use vise::{Gauge, Metrics};
 #[metrics(prefix = "server_gas_adjuster")]
 pub(super) struct GasAdjusterMetrics {
     pub current_base_fee_per_gas: Gauge<u64>,
+    /// Smoothed base fee over recent L1 blocks. Despite the name, the smoothing algorithm is configurable
+    /// and is not necessarily the median.
     pub median_base_fee_per_gas: Gauge<u64>,
 }
 
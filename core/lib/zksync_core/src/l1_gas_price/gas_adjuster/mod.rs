@@ -5,25 +5,40 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use serde::Serialize;
 use tokio::sync::watch;
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::eth_sender::BaseFeeSmoothing, GasAdjusterConfig};
 use zksync_eth_client::{types::Error, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 
 use self::metrics::METRICS;
 use super::{L1GasPriceProvider, L1TxParamsProvider};
-use crate::state_keeper::metrics::KEEPER_METRICS;
+use crate::{config_watcher::ConfigOverrides, state_keeper::metrics::KEEPER_METRICS};
 
 mod metrics;
 #[cfg(test)]
 mod tests;
 
-/// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
-/// It is used to adjust the base_fee of transactions sent to L1.
+/// Information about the gas adjuster state reported as health details.
+#[derive(Debug, Serialize)]
+struct GasAdjusterHealthDetails {
+    smoothing: Smoothing,
+    samples: usize,
+    last_processed_l1_block: usize,
+    last_base_fee_per_gas: u64,
+    smoothed_base_fee_per_gas: u64,
+}
+
+/// This component keeps track of the smoothed base_fee (by default, the median) from the last
+/// `max_base_fee_samples` blocks. It is used to adjust the base_fee of transactions sent to L1.
 #[derive(Debug)]
 pub struct GasAdjuster<E> {
     pub(super) statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
+    /// Runtime overrides for L1 gas pricing parameters from `config`.
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     eth_client: E,
+    health_updater: HealthUpdater,
 }
 
 impl<E: EthInterface> GasAdjuster<E> {
@@ -39,11 +54,46 @@ impl<E: EthInterface> GasAdjuster<E> {
         let history = eth_client
             .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
             .await?;
-        Ok(Self {
-            statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
+        let smoothing = Smoothing::new(&config);
+        let this = Self {
+            statistics: GasStatistics::new(
+                smoothing,
+                config.max_base_fee_samples,
+                current_block,
+                &history,
+            ),
             eth_client,
             config,
-        })
+            config_overrides: None,
+            health_updater: ReactiveHealthCheck::new("gas_adjuster").1,
+        };
+        this.update_health();
+        Ok(this)
+    }
+
+    /// Makes the adjuster respect runtime overrides of the L1 pricing multiplier and the enforced L1 gas price.
+    pub fn with_config_overrides(mut self, overrides: watch::Receiver<ConfigOverrides>) -> Self {
+        self.config_overrides = Some(overrides);
+        self
+    }
+
+    /// Returns a health check exposing the base fee statistics collected by this adjuster.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn update_health(&self) {
+        let statistics = self.statistics.0.read().unwrap();
+        let details = GasAdjusterHealthDetails {
+            smoothing: statistics.smoothing,
+            samples: statistics.samples.len(),
+            last_processed_l1_block: statistics.last_processed_block,
+            last_base_fee_per_gas: statistics.last_added_value(),
+            smoothed_base_fee_per_gas: statistics.smoothed_base_fee(),
+        };
+        drop(statistics);
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(details));
     }
 
     /// Performs an actualization routine for `GasAdjuster`.
@@ -76,6 +126,7 @@ impl<E: EthInterface> GasAdjuster<E> {
                 .current_base_fee_per_gas
                 .set(*history.last().unwrap());
             self.statistics.add_samples(&history);
+            self.update_health();
         }
         Ok(())
     }
@@ -114,14 +165,22 @@ impl<E: EthInterface> L1GasPriceProvider for GasAdjuster<E> {
     /// Returns the sum of base and priority fee, in wei, not considering time in mempool.
     /// Can be used to get an estimate of current gas price.
     fn estimate_effective_gas_price(&self) -> u64 {
-        if let Some(price) = self.config.internal_enforced_l1_gas_price {
+        let mut enforced_price = self.config.internal_enforced_l1_gas_price;
+        let mut pricing_multiplier = self.config.internal_l1_pricing_multiplier;
+        if let Some(overrides) = &self.config_overrides {
+            let overrides = overrides.borrow();
+            enforced_price = overrides.internal_enforced_l1_gas_price.or(enforced_price);
+            pricing_multiplier = overrides
+                .internal_l1_pricing_multiplier
+                .unwrap_or(pricing_multiplier);
+        }
+        if let Some(price) = enforced_price {
             return price;
         }
 
         let effective_gas_price = self.get_base_fee(0) + self.get_priority_fee();
 
-        let calculated_price =
-            (self.config.internal_l1_pricing_multiplier * effective_gas_price as f64) as u64;
+        let calculated_price = (pricing_multiplier * effective_gas_price as f64) as u64;
 
         // Bound the price if it's too high.
         self.bound_gas_price(calculated_price)
@@ -143,12 +202,17 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
         // The alternative is a linear one:
         // let scale_factor = a + b * time_in_mempool as f64;
         let scale_factor = a * b.powf(time_in_mempool as f64);
-        let median = self.statistics.median();
-        METRICS.median_base_fee_per_gas.set(median);
-        let new_fee = median as f64 * scale_factor;
+        let smoothed_base_fee = self.get_smoothed_base_fee();
+        let new_fee = smoothed_base_fee as f64 * scale_factor;
         new_fee as u64
     }
 
+    fn get_smoothed_base_fee(&self) -> u64 {
+        let smoothed_base_fee = self.statistics.smoothed_base_fee();
+        METRICS.median_base_fee_per_gas.set(smoothed_base_fee);
+        smoothed_base_fee
+    }
+
     fn get_next_block_minimal_base_fee(&self) -> u64 {
         let last_block_base_fee = self.statistics.last_added_value();
 
@@ -168,22 +232,81 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
     }
 }
 
+/// Algorithm smoothing base fees of recent L1 blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub(super) enum Smoothing {
+    Percentile { percentile: f64 },
+    Ewma { alpha: f64 },
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Self::Percentile { percentile: 50.0 }
+    }
+}
+
+impl Smoothing {
+    fn new(config: &GasAdjusterConfig) -> Self {
+        match config.base_fee_smoothing {
+            BaseFeeSmoothing::MovingMedian => Self::default(),
+            BaseFeeSmoothing::Percentile => {
+                let percentile = config.base_fee_percentile();
+                assert!(
+                    (0.0..=100.0).contains(&percentile),
+                    "Base fee percentile must be in [0, 100] range, got {percentile}"
+                );
+                Self::Percentile { percentile }
+            }
+            BaseFeeSmoothing::Ewma => {
+                let alpha = config.base_fee_ewma_alpha();
+                assert!(
+                    alpha > 0.0 && alpha <= 1.0,
+                    "Base fee EWMA smoothing factor must be in (0, 1] range, got {alpha}"
+                );
+                Self::Ewma { alpha }
+            }
+        }
+    }
+
+    /// Applies smoothing to the non-empty sequence of base fees ordered from the oldest block.
+    fn apply(self, samples: &VecDeque<u64>) -> u64 {
+        match self {
+            Self::Percentile { percentile } => {
+                let mut samples: Vec<_> = samples.iter().copied().collect();
+                // For the 50th percentile, this is the same as `samples.len() / 2`.
+                let index = ((samples.len() - 1) as f64 * percentile / 100.0).round() as usize;
+                *samples.select_nth_unstable(index).1
+            }
+            Self::Ewma { alpha } => {
+                let mut samples = samples.iter().map(|&fee| fee as f64);
+                let first = samples.next().unwrap();
+                let average =
+                    samples.fold(first, |average, fee| alpha * fee + (1.0 - alpha) * average);
+                average.round() as u64
+            }
+        }
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
-/// calculating the median base fee.
+/// calculating the smoothed base fee.
 #[derive(Debug, Clone, Default)]
 pub(super) struct GasStatisticsInner {
+    smoothing: Smoothing,
     samples: VecDeque<u64>,
-    median_cached: u64,
+    smoothed_cached: u64,
     max_samples: usize,
     last_processed_block: usize,
 }
 
 impl GasStatisticsInner {
-    fn new(max_samples: usize, block: usize, fee_history: &[u64]) -> Self {
+    fn new(smoothing: Smoothing, max_samples: usize, block: usize, fee_history: &[u64]) -> Self {
         let mut statistics = Self {
+            smoothing,
             max_samples,
             samples: VecDeque::with_capacity(max_samples),
-            median_cached: 0,
+            smoothed_cached: 0,
             last_processed_block: 0,
         };
 
@@ -195,12 +318,12 @@ impl GasStatisticsInner {
         }
     }
 
-    fn median(&self) -> u64 {
-        self.median_cached
+    fn smoothed_base_fee(&self) -> u64 {
+        self.smoothed_cached
     }
 
     fn last_added_value(&self) -> u64 {
-        self.samples.back().copied().unwrap_or(self.median_cached)
+        self.samples.back().copied().unwrap_or(self.smoothed_cached)
     }
 
     fn add_samples(&mut self, fees: &[u64]) {
@@ -210,10 +333,9 @@ impl GasStatisticsInner {
         let extra = self.samples.len().saturating_sub(self.max_samples);
         self.samples.drain(..extra);
 
-        let mut samples: Vec<_> = self.samples.iter().cloned().collect();
-        let (_, &mut median, _) = samples.select_nth_unstable(self.samples.len() / 2);
-
-        self.median_cached = median;
+        if !self.samples.is_empty() {
+            self.smoothed_cached = self.smoothing.apply(&self.samples);
+        }
     }
 }
 
@@ -221,16 +343,22 @@ impl GasStatisticsInner {
 pub(super) struct GasStatistics(RwLock<GasStatisticsInner>);
 
 impl GasStatistics {
-    pub fn new(max_samples: usize, block: usize, fee_history: &[u64]) -> Self {
+    pub fn new(
+        smoothing: Smoothing,
+        max_samples: usize,
+        block: usize,
+        fee_history: &[u64],
+    ) -> Self {
         Self(RwLock::new(GasStatisticsInner::new(
+            smoothing,
             max_samples,
             block,
             fee_history,
         )))
     }
 
-    pub fn median(&self) -> u64 {
-        self.0.read().unwrap().median()
+    pub fn smoothed_base_fee(&self) -> u64 {
+        self.0.read().unwrap().smoothed_base_fee()
     }
 
     pub fn last_added_value(&self) -> u64 {
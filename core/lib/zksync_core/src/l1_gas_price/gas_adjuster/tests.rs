@@ -1,23 +1,49 @@
 use std::{collections::VecDeque, sync::Arc};
 
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::eth_sender::BaseFeeSmoothing, GasAdjusterConfig};
 use zksync_eth_client::clients::mock::MockEthereum;
 
-use super::{GasAdjuster, GasStatisticsInner};
+use super::{GasAdjuster, GasStatisticsInner, Smoothing};
 
 /// Check that we compute the median correctly
 #[test]
 fn median() {
     // sorted: 4 4 6 7 8
-    assert_eq!(GasStatisticsInner::new(5, 5, &[6, 4, 7, 8, 4]).median(), 6);
+    assert_eq!(
+        GasStatisticsInner::new(Smoothing::default(), 5, 5, &[6, 4, 7, 8, 4]).smoothed_base_fee(),
+        6
+    );
     // sorted: 4 4 8 10
-    assert_eq!(GasStatisticsInner::new(4, 4, &[8, 4, 4, 10]).median(), 8);
+    assert_eq!(
+        GasStatisticsInner::new(Smoothing::default(), 4, 4, &[8, 4, 4, 10]).smoothed_base_fee(),
+        8
+    );
+}
+
+/// Check that alternative smoothing algorithms are computed correctly
+#[test]
+fn percentile_and_ewma() {
+    let fees = [6, 4, 7, 8, 4];
+    let percentile = |percentile| {
+        GasStatisticsInner::new(Smoothing::Percentile { percentile }, 5, 5, &fees)
+            .smoothed_base_fee()
+    };
+    // sorted: 4 4 6 7 8
+    assert_eq!(percentile(0.0), 4);
+    assert_eq!(percentile(75.0), 7);
+    assert_eq!(percentile(100.0), 8);
+
+    let stats = GasStatisticsInner::new(Smoothing::Ewma { alpha: 0.5 }, 3, 3, &[4, 8, 12]);
+    // 4 -> 0.5 * 8 + 0.5 * 4 = 6 -> 0.5 * 12 + 0.5 * 6 = 9
+    assert_eq!(stats.smoothed_base_fee(), 9);
+    let stats = GasStatisticsInner::new(Smoothing::Ewma { alpha: 1.0 }, 3, 3, &[4, 8, 12]);
+    assert_eq!(stats.smoothed_base_fee(), 12);
 }
 
 /// Check that we properly manage the block base fee queue
 #[test]
 fn samples_queue() {
-    let mut stats = GasStatisticsInner::new(5, 5, &[6, 4, 7, 8, 4, 5]);
+    let mut stats = GasStatisticsInner::new(Smoothing::default(), 5, 5, &[6, 4, 7, 8, 4, 5]);
 
     assert_eq!(stats.samples, VecDeque::from([4, 7, 8, 4, 5]));
 
@@ -44,17 +70,20 @@ async fn kept_updated() {
             internal_enforced_l1_gas_price: None,
             poll_period: 5,
             max_l1_gas_price: None,
+            base_fee_smoothing: BaseFeeSmoothing::MovingMedian,
+            base_fee_ewma_alpha: None,
+            base_fee_percentile: None,
         },
     )
     .await
     .unwrap();
 
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
-    assert_eq!(adjuster.statistics.0.read().unwrap().median(), 6);
+    assert_eq!(adjuster.statistics.0.read().unwrap().smoothed_base_fee(), 6);
 
     eth_client.advance_block_number(3);
     adjuster.keep_updated().await.unwrap();
 
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
-    assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
+    assert_eq!(adjuster.statistics.0.read().unwrap().smoothed_base_fee(), 7);
 }
@@ -7,12 +7,10 @@ use std::{
 };
 
 use tokio::sync::watch::Receiver;
-use zksync_web3_decl::{
-    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
-    namespaces::ZksNamespaceClient,
-};
+use zksync_web3_decl::namespaces::ZksNamespaceClient;
 
 use super::L1GasPriceProvider;
+use crate::sync_layer::FailoverMainNodeClient;
 
 const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -24,24 +22,18 @@ const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 /// since it relies on the configuration, which may change.
 #[derive(Debug)]
 pub struct MainNodeGasPriceFetcher {
-    client: HttpClient,
+    client: FailoverMainNodeClient,
     gas_price: AtomicU64,
 }
 
 impl MainNodeGasPriceFetcher {
-    pub fn new(main_node_url: &str) -> Self {
+    pub fn new(client: FailoverMainNodeClient) -> Self {
         Self {
-            client: Self::build_client(main_node_url),
+            client,
             gas_price: AtomicU64::new(1u64), // Start with 1 wei until the first update.
         }
     }
 
-    fn build_client(main_node_url: &str) -> HttpClient {
-        HttpClientBuilder::default()
-            .build(main_node_url)
-            .expect("Unable to create a main node client")
-    }
-
     pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) -> anyhow::Result<()> {
         loop {
             if *stop_receiver.borrow() {
@@ -49,7 +41,10 @@ impl MainNodeGasPriceFetcher {
                 break;
             }
 
-            let main_node_gas_price = match self.client.get_l1_gas_price().await {
+            let gas_price_request = self
+                .client
+                .call(|client| async move { client.get_l1_gas_price().await });
+            let main_node_gas_price = match gas_price_request.await {
                 Ok(price) => price,
                 Err(err) => {
                     tracing::warn!("Unable to get the gas price: {}", err);
@@ -24,6 +24,10 @@ pub trait L1TxParamsProvider: L1GasPriceProvider {
     /// Returns the recommended `max_fee_per_gas` value (EIP1559).
     fn get_base_fee(&self, time_in_mempool: u32) -> u64;
 
+    /// Returns the smoothed base fee over recent L1 blocks (by default, the median). Fee escalation policies
+    /// of the L1 sender scale this value depending on the time a transaction spends in the mempool.
+    fn get_smoothed_base_fee(&self) -> u64;
+
     /// Returns the recommended `max_priority_fee_per_gas` value (EIP1559).
     fn get_priority_fee(&self) -> u64;
 
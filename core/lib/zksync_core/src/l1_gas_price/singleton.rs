@@ -7,8 +7,9 @@ use tokio::{
 };
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::clients::http::QueryClient;
+use zksync_health_check::ReactiveHealthCheck;
 
-use crate::l1_gas_price::GasAdjuster;
+use crate::{config_watcher::ConfigOverrides, l1_gas_price::GasAdjuster};
 
 /// Special struct for creating a singleton of `GasAdjuster`.
 /// This is needed only for running the server.
@@ -16,6 +17,7 @@ use crate::l1_gas_price::GasAdjuster;
 pub struct GasAdjusterSingleton {
     web3_url: String,
     gas_adjuster_config: GasAdjusterConfig,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     singleton: OnceCell<Result<Arc<GasAdjuster<QueryClient>>, Error>>,
 }
 
@@ -34,25 +36,40 @@ impl GasAdjusterSingleton {
         Self {
             web3_url,
             gas_adjuster_config,
+            config_overrides: None,
             singleton: OnceCell::new(),
         }
     }
 
+    /// Sets runtime overrides for the created gas adjuster. Has no effect if the adjuster is already initialized.
+    pub fn set_config_overrides(&mut self, overrides: watch::Receiver<ConfigOverrides>) {
+        self.config_overrides = Some(overrides);
+    }
+
     pub async fn get_or_init(&mut self) -> Result<Arc<GasAdjuster<QueryClient>>, Error> {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
                 let query_client =
                     QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
-                let adjuster = GasAdjuster::new(query_client.clone(), self.gas_adjuster_config)
+                let mut adjuster = GasAdjuster::new(query_client.clone(), self.gas_adjuster_config)
                     .await
                     .context("GasAdjuster::new()")?;
+                if let Some(overrides) = self.config_overrides.clone() {
+                    adjuster = adjuster.with_config_overrides(overrides);
+                }
                 Ok(Arc::new(adjuster))
             })
             .await;
         adjuster.clone()
     }
 
+    /// Returns the health check of the gas adjuster if it's successfully initialized.
+    pub fn health_check(&self) -> Option<ReactiveHealthCheck> {
+        let gas_adjuster = self.singleton.get()?.as_ref().ok()?;
+        Some(gas_adjuster.health_check())
+    }
+
     pub fn run_if_initialized(
         self,
         stop_signal: watch::Receiver<bool>,
@@ -1,14 +1,28 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    net::Ipv4Addr,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use futures::channel::oneshot;
 use prometheus_exporter::PrometheusExporterConfig;
 use temp_config_store::TempConfigStore;
-use tokio::{sync::watch, task::JoinHandle};
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinHandle,
+};
 use zksync_circuit_breaker::{
-    l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker, CircuitBreaker,
+    custom::{CustomRuleChecker, CustomRuleConfig},
+    da_inclusion::DaInclusionChecker,
+    fee_divergence::FeeDivergenceChecker,
+    l1_contracts::L1ContractsChecker,
+    l1_txs::FailedL1TransactionChecker,
+    replication_lag::ReplicationLagChecker,
     CircuitBreakerChecker, CircuitBreakerError,
 };
 use zksync_config::{
@@ -20,38 +34,59 @@ use zksync_config::{
         },
         contracts::ProverAtGenesis,
         database::MerkleTreeMode,
+        eth_sender::{ProofSendingMode, PubdataSendingMode, SettlementLayerKind},
+        proof_data_handler::ProofGenerationMode,
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_eth_client::{
-    clients::http::{PKSigningClient, QueryClient},
+    clients::http::{OperatorSigningClient, QueryClient},
     BoundEthInterface, EthInterface,
 };
 use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_utils::periodic_job::PeriodicJob;
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{BytecodeCache, PostgresStorageCaches};
 use zksync_types::{
+    api::TransactionSoftConfirmation,
+    commitment::L1BatchCommitmentMode,
+    proofs::JobRetryBackoff,
     protocol_version::{L1VerifierConfig, VerifierParams},
     system_contracts::get_system_smart_contracts,
     L2ChainId, PackedEthSignature, ProtocolVersionId,
 };
 
 use crate::{
+    address_filter::AddressFilter,
     api_server::{
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
-        tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
+        tx_sender::{ApiContracts, TxFilters, TxSender, TxSenderBuilder, TxSenderConfig},
         web3,
-        web3::{state::InternalApiConfig, ApiServerHandles, Namespace},
+        web3::{
+            profiles::{ApiProfileConfig, ApiProfileTransport},
+            state::InternalApiConfig,
+            ApiServerHandles, Namespace,
+        },
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
-    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
-    eth_watch::start_eth_watch,
+    call_traces_backfiller::CallTracesBackfiller,
+    config_watcher::{ConfigOverrides, ConfigWatcher},
+    da_dispatcher::{create_da_client, DataAvailabilityDispatcher},
+    db_pruner::{ColdStorageArchiver, DbPruner, StorageLogsCompactor},
+    eth_sender::{
+        settlement_layer_from_config, Aggregator, EthTxAggregator, EthTxManager,
+        FeeEscalationPolicies, SettlementLayer,
+    },
+    eth_watch::{start_eth_watch, L1EventHandlers},
+    event_indexer::{Erc20TransfersIndex, EventIndexer, EventIndexes},
+    health_checks::{
+        EthSenderHealthCheck, LagThresholds, StateKeeperLagHealthCheck, TreeLagHealthCheck,
+    },
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
         fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
@@ -62,44 +97,76 @@ use crate::{
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
         gpu_prover_queue_monitor::GpuProverQueueMonitor,
-        prover_job_retry_manager::ProverJobRetryManager, prover_queue_monitor::ProverStatsReporter,
+        prover_job_retry_manager::ProverJobRetryManager, prover_queue_api,
+        prover_queue_monitor::ProverStatsReporter,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
     l1_gas_price::{GasAdjusterSingleton, L1GasPriceProvider},
     metadata_calculator::{
         MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
+        TreeConsistencyChecker,
     },
     metrics::{InitStage, APP_METRICS},
-    state_keeper::{create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer},
+    rocksdb_compactor::{
+        state_keeper_compaction_channel, RocksdbCompactor, StateKeeperCompactionRequests,
+    },
+    shared_bridge::ChainL1Contracts,
+    state_keeper::{
+        create_state_keeper, external_builder, MempoolFetcher, MempoolGuard, MiniblockSealer,
+        SealCriterion,
+    },
+    tee_verifier_input_producer::TeeVerifierInputProducer,
+    upgrade_dry_run::UpgradeDryRunner,
 };
 
+pub mod address_filter;
 pub mod api_server;
 pub mod basic_witness_input_producer;
 pub mod block_reverter;
+pub mod call_traces_backfiller;
+pub mod config_watcher;
 mod consensus;
 pub mod consistency_checker;
+pub mod da_dispatcher;
+pub mod db_pruner;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod event_indexer;
 pub mod gas_tracker;
 pub mod genesis;
+pub mod health_checks;
 pub mod house_keeper;
 pub mod l1_gas_price;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
 pub mod reorg_detector;
+pub mod rocksdb_compactor;
+pub mod shared_bridge;
+pub mod sponsored_txs;
+pub mod state_archive;
 pub mod state_keeper;
 pub mod sync_layer;
+pub mod tee_verifier_input_producer;
 pub mod temp_config_store;
+pub mod upgrade_dry_run;
 mod utils;
+pub mod vm_replay;
 
-/// Inserts the initial information about zkSync tokens into the database.
+/// Capacity of the channel passing soft confirmations from the state keeper to the WS API server.
+const SOFT_CONFIRMATIONS_CHANNEL_CAPACITY: usize = 1_024;
+/// Interval between size adjustments for adaptively sized connection pools.
+const POOL_ADJUSTMENT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Inserts the initial information about zkSync tokens into the database. If a `state_dump` is provided,
+/// it is applied on top of the standard genesis state.
 pub async fn genesis_init(
     postgres_config: &PostgresConfig,
     eth_sender: &ETHSenderConfig,
     network_config: &NetworkConfig,
     contracts_config: &ContractsConfig,
     eth_client_url: &str,
+    state_dump: Option<genesis::GenesisStateDump>,
 ) -> anyhow::Result<()> {
     let db_url = postgres_config.master_url()?;
     let pool = ConnectionPool::singleton(db_url)
@@ -107,13 +174,15 @@ pub async fn genesis_init(
         .await
         .context("failed to build connection_pool")?;
     let mut storage = pool.access_storage().await.context("access_storage()")?;
-    let operator_address = PackedEthSignature::address_from_private_key(
-        &eth_sender
+    let operator_address = if let Some(private_key) = eth_sender.sender.private_key() {
+        PackedEthSignature::address_from_private_key(&private_key)
+            .context("Failed to restore operator address from private key")?
+    } else {
+        eth_sender
             .sender
-            .private_key()
-            .context("Private key is required for genesis init")?,
-    )
-    .context("Failed to restore operator address from private key")?;
+            .operator_address
+            .context("Either private key or operator address is required for genesis init")?
+    };
 
     // Select the first prover to be used during genesis.
     // Later we can change provers using the system upgrades, but for genesis
@@ -172,6 +241,7 @@ pub async fn genesis_init(
             system_contracts: get_system_smart_contracts(),
             first_verifier_address: contracts_config.verifier_addr,
             first_l1_verifier_config,
+            state_dump,
         },
     )
     .await?;
@@ -212,12 +282,17 @@ pub enum Component {
     HttpApi,
     /// Public Web3 API (including PubSub) running on WebSocket server.
     WsApi,
+    /// Web3 API servers defined by profiles in `api.web3_json_rpc.profiles_path`, each serving a subset of namespaces
+    /// with dedicated resources.
+    ApiProfiles,
     /// REST API for contract verification.
     ContractVerificationApi,
     /// Metadata calculator.
     Tree,
     /// Merkle tree API.
     TreeApi,
+    /// Periodically verifies random subtrees of the Merkle tree.
+    TreeConsistencyChecker,
     EthWatcher,
     /// Eth tx generator.
     EthTxAggregator,
@@ -228,10 +303,28 @@ pub enum Component {
     /// Produces input for basic witness generator and uploads it as bin encoded file (blob) to GCS.
     /// The blob is later used as input for Basic Witness Generators.
     BasicWitnessInputProducer,
+    /// Produces input for TEE provers (re-execution data of L1 batches) and uploads it to the object store.
+    TeeVerifierInputProducer,
     /// Component for housekeeping task such as cleaning blobs from GCS, reporting metrics etc.
     Housekeeper,
     /// Component for exposing APIs to prover for providing proof generation data and accepting proofs.
     ProofDataHandler,
+    /// Removes historical data of old L1 batches from Postgres.
+    DbPruner,
+    /// Removes storage logs of old L1 batches overwritten later in the same L1 batch.
+    StorageLogsCompactor,
+    /// Moves call traces and pubdata inputs of old L1 batches from Postgres to the object store.
+    ColdStorageArchiver,
+    /// Compacts RocksDB instances of the state keeper and the Merkle tree during off-peak hours or on demand.
+    RocksdbCompactor,
+    /// Posts pubdata of L1 batches to an external data availability layer.
+    DADispatcher,
+    /// Replays recent L1 batches under a pending protocol version and reports behavioral differences.
+    UpgradeDryRun,
+    /// Re-executes sealed L1 batches with the call tracer and saves call traces for batches executed without tracing.
+    CallTracesBackfiller,
+    /// Maintains the built-in ERC-20 transfers index and custom event indexes registered by node extensions.
+    EventIndexer,
 }
 
 #[derive(Debug)]
@@ -249,14 +342,19 @@ impl FromStr for Components {
             ])),
             "http_api" => Ok(Components(vec![Component::HttpApi])),
             "ws_api" => Ok(Components(vec![Component::WsApi])),
+            "api_profiles" => Ok(Components(vec![Component::ApiProfiles])),
             "contract_verification_api" => Ok(Components(vec![Component::ContractVerificationApi])),
             "tree" => Ok(Components(vec![Component::Tree])),
             "tree_api" => Ok(Components(vec![Component::TreeApi])),
+            "tree_consistency_checker" => Ok(Components(vec![Component::TreeConsistencyChecker])),
             "state_keeper" => Ok(Components(vec![Component::StateKeeper])),
             "housekeeper" => Ok(Components(vec![Component::Housekeeper])),
             "basic_witness_input_producer" => {
                 Ok(Components(vec![Component::BasicWitnessInputProducer]))
             }
+            "tee_verifier_input_producer" => {
+                Ok(Components(vec![Component::TeeVerifierInputProducer]))
+            }
             "eth" => Ok(Components(vec![
                 Component::EthWatcher,
                 Component::EthTxAggregator,
@@ -266,6 +364,14 @@ impl FromStr for Components {
             "eth_tx_aggregator" => Ok(Components(vec![Component::EthTxAggregator])),
             "eth_tx_manager" => Ok(Components(vec![Component::EthTxManager])),
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
+            "db_pruner" => Ok(Components(vec![Component::DbPruner])),
+            "storage_logs_compactor" => Ok(Components(vec![Component::StorageLogsCompactor])),
+            "cold_storage_archiver" => Ok(Components(vec![Component::ColdStorageArchiver])),
+            "rocksdb_compactor" => Ok(Components(vec![Component::RocksdbCompactor])),
+            "da_dispatcher" => Ok(Components(vec![Component::DADispatcher])),
+            "upgrade_dry_run" => Ok(Components(vec![Component::UpgradeDryRun])),
+            "call_traces_backfiller" => Ok(Components(vec![Component::CallTracesBackfiller])),
+            "event_indexer" => Ok(Components(vec![Component::EventIndexer])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -279,24 +385,91 @@ pub async fn initialize_components(
     watch::Sender<bool>,
     oneshot::Receiver<CircuitBreakerError>,
     HealthCheckHandle,
+)> {
+    initialize_components_with_seal_criteria(configs, components, vec![]).await
+}
+
+/// Same as [`initialize_components()`], but additionally registers `custom_seal_criteria`
+/// for the state keeper. Custom criteria are ignored if the state keeper component is not run.
+pub async fn initialize_components_with_seal_criteria(
+    configs: &TempConfigStore,
+    components: Vec<Component>,
+    custom_seal_criteria: Vec<Box<dyn SealCriterion>>,
+) -> anyhow::Result<(
+    Vec<JoinHandle<anyhow::Result<()>>>,
+    watch::Sender<bool>,
+    oneshot::Receiver<CircuitBreakerError>,
+    HealthCheckHandle,
+)> {
+    initialize_components_with_extensions(
+        configs,
+        components,
+        custom_seal_criteria,
+        L1EventHandlers::default(),
+        TxFilters::default(),
+        EventIndexes::default(),
+    )
+    .await
+}
+
+/// Same as [`initialize_components_with_seal_criteria()`], but additionally registers `l1_event_handlers`
+/// for the Ethereum watcher, `tx_filters` for the HTTP and WS API servers, and `event_indexes` for the event indexer.
+/// Extensions are ignored if the corresponding components are not run.
+pub async fn initialize_components_with_extensions(
+    configs: &TempConfigStore,
+    components: Vec<Component>,
+    custom_seal_criteria: Vec<Box<dyn SealCriterion>>,
+    l1_event_handlers: L1EventHandlers,
+    tx_filters: TxFilters,
+    event_indexes: EventIndexes,
+) -> anyhow::Result<(
+    Vec<JoinHandle<anyhow::Result<()>>>,
+    watch::Sender<bool>,
+    oneshot::Receiver<CircuitBreakerError>,
+    HealthCheckHandle,
 )> {
     tracing::info!("Starting the components: {components:?}");
 
     let db_config = configs.db_config.clone().context("db_config")?;
     let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
 
+    let object_store_config = configs
+        .object_store_config
+        .clone()
+        .context("object_store_config")?;
+    let store_factory = ObjectStoreFactory::new(object_store_config);
+
     let statement_timeout = postgres_config.statement_timeout();
     let pool_size = postgres_config.max_connections()?;
-    let connection_pool = ConnectionPool::builder(postgres_config.master_url()?, pool_size)
+    let mut connection_pool_builder =
+        ConnectionPool::builder(postgres_config.master_url()?, pool_size);
+    connection_pool_builder.set_name("master");
+    let mut replica_pool_builder =
+        ConnectionPool::builder(postgres_config.replica_url()?, pool_size);
+    replica_pool_builder.set_name("replica");
+    if let Some(min_pool_size) = postgres_config.min_connections {
+        let target_wait = postgres_config.pool_target_wait();
+        connection_pool_builder.set_adaptive_sizing(min_pool_size, target_wait);
+        replica_pool_builder.set_adaptive_sizing(min_pool_size, target_wait);
+    }
+    let connection_pool = connection_pool_builder
         .build()
         .await
         .context("failed to build connection_pool")?;
-    let replica_connection_pool =
-        ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
-            .set_statement_timeout(statement_timeout)
-            .build()
-            .await
-            .context("failed to build replica_connection_pool")?;
+    replica_pool_builder
+        .set_statement_timeout(statement_timeout)
+        .set_read_replicas(
+            postgres_config.read_replica_urls.clone(),
+            postgres_config.max_replica_lag(),
+        );
+    if db_config.cold_storage.enabled {
+        // Only API servers (which use the replica pool) need to read archived L1 batch artifacts.
+        replica_pool_builder.set_cold_storage(store_factory.create_store().await.into());
+    }
+    let replica_connection_pool = replica_pool_builder
+        .build()
+        .await
+        .context("failed to build replica_connection_pool")?;
 
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
     let contracts_config = configs
@@ -312,20 +485,37 @@ pub async fn initialize_components(
         .clone()
         .context("circuit_breaker_config")?;
 
-    let circuit_breaker_checker = CircuitBreakerChecker::new(
-        circuit_breakers_for_components(&components, &postgres_config, &circuit_breaker_config)
-            .await
-            .context("circuit_breakers_for_components")?,
+    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    let mut circuit_breaker_checker = CircuitBreakerChecker::new(&circuit_breaker_config);
+    register_circuit_breakers(
+        &mut circuit_breaker_checker,
+        &components,
+        &postgres_config,
         &circuit_breaker_config,
-    );
+        &query_client,
+        &contracts_config,
+    )
+    .await
+    .context("register_circuit_breakers")?;
     circuit_breaker_checker.check().await.unwrap_or_else(|err| {
         panic!("Circuit breaker triggered: {}", err);
     });
 
-    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
     let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
     let mut gas_adjuster =
         GasAdjusterSingleton::new(eth_client_config.web3_url.clone(), gas_adjuster_config);
+    // Fees for eth txs sent to a gateway are based on the gateway fee history rather than on L1 one.
+    let mut settlement_gas_adjuster = match &configs.eth_sender_config {
+        Some(config) if config.sender.settlement_layer == SettlementLayerKind::Gateway => {
+            let web3_url = config
+                .sender
+                .gateway_url
+                .clone()
+                .context("gateway settlement layer requires a URL")?;
+            Some(GasAdjusterSingleton::new(web3_url, gas_adjuster_config))
+        }
+        _ => None,
+    };
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let (cb_sender, cb_receiver) = oneshot::channel();
@@ -353,8 +543,49 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
     ];
 
+    let (address_filter, config_overrides) = if components.contains(&Component::WsApi)
+        || components.contains(&Component::HttpApi)
+        || components.contains(&Component::ApiProfiles)
+        || components.contains(&Component::StateKeeper)
+    {
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        let address_filter =
+            build_address_filter(state_keeper_config, &mut task_futures, &stop_receiver)
+                .context("build_address_filter()")?;
+        let config_overrides = build_config_watcher(
+            state_keeper_config,
+            address_filter.as_ref(),
+            &mut task_futures,
+            &stop_receiver,
+        )
+        .context("build_config_watcher()")?;
+        (address_filter, config_overrides)
+    } else {
+        (None, None)
+    };
+    if let Some(overrides) = &config_overrides {
+        gas_adjuster.set_config_overrides(overrides.clone());
+    }
+
+    // Soft confirmations are passed from the state keeper to the WS API server via an in-process channel,
+    // so they are only available if both components run in the same process.
+    let soft_confirmations = (components.contains(&Component::StateKeeper)
+        && (components.contains(&Component::WsApi)
+            || components.contains(&Component::ApiProfiles)))
+    .then(|| broadcast::channel(SOFT_CONFIRMATIONS_CHANNEL_CAPACITY).0);
+
+    // Bytecode cache is shared by the API servers and the state keeper if they run in the same process.
+    let bytecode_cache = configs
+        .web3_json_rpc_config
+        .as_ref()
+        .map(|config| BytecodeCache::new(config.factory_deps_cache_size() as u64));
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
+        || components.contains(&Component::ApiProfiles)
         || components.contains(&Component::ContractVerificationApi)
     {
         let api_config = configs.api_config.clone().context("api_config")?;
@@ -382,8 +613,13 @@ pub async fn initialize_components(
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
-                build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
-                    .context("build_storage_caches()")?,
+                build_storage_caches(
+                    configs,
+                    bytecode_cache.clone(),
+                    &replica_connection_pool,
+                    &mut task_futures,
+                )
+                .context("build_storage_caches()")?,
             );
 
             let started_at = Instant::now();
@@ -403,6 +639,9 @@ pub async fn initialize_components(
                 stop_receiver.clone(),
                 bounded_gas_adjuster.clone(),
                 state_keeper_config.save_call_traces,
+                address_filter.clone(),
+                tx_filters.clone(),
+                config_overrides.clone(),
                 storage_caches.clone().unwrap(),
             )
             .await
@@ -419,10 +658,19 @@ pub async fn initialize_components(
         }
 
         if components.contains(&Component::WsApi) {
-            let storage_caches = match storage_caches {
-                Some(storage_caches) => storage_caches,
-                None => build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
-                    .context("build_storage_caches()")?,
+            let storage_caches = match &storage_caches {
+                Some(storage_caches) => storage_caches.clone(),
+                None => storage_caches
+                    .insert(
+                        build_storage_caches(
+                            configs,
+                            bytecode_cache.clone(),
+                            &replica_connection_pool,
+                            &mut task_futures,
+                        )
+                        .context("build_storage_caches()")?,
+                    )
+                    .clone(),
             };
 
             let started_at = Instant::now();
@@ -441,6 +689,10 @@ pub async fn initialize_components(
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
+                address_filter.clone(),
+                tx_filters.clone(),
+                config_overrides.clone(),
+                soft_confirmations.clone(),
                 storage_caches,
             )
             .await
@@ -456,6 +708,96 @@ pub async fn initialize_components(
             );
         }
 
+        if components.contains(&Component::ApiProfiles) {
+            // Storage caches are shared with the HTTP / WS API servers if they run in the same process.
+            let storage_caches = match storage_caches {
+                Some(storage_caches) => storage_caches,
+                None => build_storage_caches(
+                    configs,
+                    bytecode_cache.clone(),
+                    &replica_connection_pool,
+                    &mut task_futures,
+                )
+                .context("build_storage_caches()")?,
+            };
+            let profiles_path =
+                api_config.web3_json_rpc.profiles_path.as_deref().context(
+                    "`api_profiles` component requires `api.web3_json_rpc.profiles_path`",
+                )?;
+            let profiles = ApiProfileConfig::load(Path::new(profiles_path))?;
+            // Profile servers run in the same process as the HTTP / WS API servers, so they cannot reuse their ports.
+            let mut reserved_ports = vec![];
+            if components.contains(&Component::HttpApi) {
+                reserved_ports.push(("HTTP API", api_config.web3_json_rpc.http_port));
+            }
+            if components.contains(&Component::WsApi) {
+                reserved_ports.push(("WS API", api_config.web3_json_rpc.ws_port));
+            }
+            ApiProfileConfig::ensure_ports_available(&profiles, &reserved_ports)?;
+
+            let started_at = Instant::now();
+            tracing::info!("Initializing {} API profiles", profiles.len());
+            let bounded_gas_adjuster = gas_adjuster
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            for profile in &profiles {
+                let profile_pool = match profile.pool_size {
+                    Some(pool_size) => {
+                        // The builder is cloned so that the max size and name don't leak to other profiles.
+                        let profile_pool = replica_pool_builder
+                            .clone()
+                            .set_max_size(pool_size)
+                            .set_name(format!("api_profile_{}", profile.name))
+                            .build()
+                            .await
+                            .with_context(|| {
+                                format!("failed to build pool for API profile `{}`", profile.name)
+                            })?;
+                        if postgres_config.min_connections.is_some() {
+                            let adjustments = profile_pool.clone().run_adaptive_sizing(
+                                POOL_ADJUSTMENT_INTERVAL,
+                                stop_receiver.clone(),
+                            );
+                            task_futures.push(tokio::spawn(adjustments));
+                        }
+                        profile_pool
+                    }
+                    None => replica_connection_pool.clone(),
+                };
+                let server_handles = run_profile_api(
+                    profile,
+                    &postgres_config,
+                    &tx_sender_config,
+                    &state_keeper_config,
+                    &internal_api_config,
+                    &api_config,
+                    bounded_gas_adjuster.clone(),
+                    connection_pool.clone(),
+                    profile_pool,
+                    stop_receiver.clone(),
+                    address_filter.clone(),
+                    tx_filters.clone(),
+                    config_overrides.clone(),
+                    soft_confirmations.clone(),
+                    storage_caches.clone(),
+                )
+                .await
+                .with_context(|| format!("run_profile_api({})", profile.name))?;
+
+                task_futures.extend(server_handles.tasks);
+                healthchecks.push(Box::new(server_handles.health_check));
+                tracing::info!(
+                    "Initialized API profile `{}` on {:?}",
+                    profile.name,
+                    server_handles.local_addr
+                );
+            }
+            let elapsed = started_at.elapsed();
+            APP_METRICS.init_latency[&InitStage::ApiProfiles].set(elapsed);
+            tracing::info!("Initialized API profiles in {elapsed:?}");
+        }
+
         if components.contains(&Component::ContractVerificationApi) {
             let started_at = Instant::now();
             tracing::info!("initializing contract verification REST API");
@@ -471,15 +813,26 @@ pub async fn initialize_components(
         }
     }
 
-    let object_store_config = configs
-        .object_store_config
-        .clone()
-        .context("object_store_config")?;
-    let store_factory = ObjectStoreFactory::new(object_store_config);
+    let mut rocksdb_compactor = if components.contains(&Component::RocksdbCompactor) {
+        anyhow::ensure!(
+            components.contains(&Component::StateKeeper) || components.contains(&Component::Tree),
+            "RocksDB compactor cannot be started without a state keeper or a tree component"
+        );
+        let compactor = RocksdbCompactor::new(db_config.rocksdb_compaction.clone())
+            .context("failed to create RocksDB compactor")?;
+        Some(compactor)
+    } else {
+        None
+    };
 
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         tracing::info!("initializing State Keeper");
+        let compaction_requests = rocksdb_compactor.as_mut().map(|compactor| {
+            let (handle, requests) = state_keeper_compaction_channel();
+            compactor.set_state_keeper(handle);
+            requests
+        });
         let bounded_gas_adjuster = gas_adjuster
             .get_or_init()
             .await
@@ -497,6 +850,12 @@ pub async fn initialize_components(
             &configs.mempool_config.clone().context("mempool_config")?,
             bounded_gas_adjuster,
             store_factory.create_store().await,
+            address_filter,
+            config_overrides,
+            custom_seal_criteria,
+            soft_confirmations,
+            compaction_requests,
+            bytecode_cache,
             stop_receiver.clone(),
         )
         .await
@@ -507,7 +866,6 @@ pub async fn initialize_components(
         tracing::info!("initialized State Keeper in {elapsed:?}");
     }
 
-    let main_zksync_contract_address = contracts_config.diamond_proxy_addr;
     if components.contains(&Component::EthWatcher) {
         let started_at = Instant::now();
         tracing::info!("initializing ETH-Watcher");
@@ -520,13 +878,15 @@ pub async fn initialize_components(
             .eth_watch_config
             .clone()
             .context("eth_watch_config")?;
+        let chain = load_chain_l1_contracts(configs, &query_client, &contracts_config).await?;
         task_futures.push(
             start_eth_watch(
                 eth_watch_config,
                 eth_watch_pool,
                 query_client.clone(),
-                main_zksync_contract_address,
+                chain,
                 governance,
+                l1_event_handlers,
                 stop_receiver.clone(),
             )
             .await
@@ -549,19 +909,66 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
+        let commitment_mode = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?
+            .l1_batch_commitment_mode;
+        eth_sender
+            .sender
+            .validate_commitment_mode(commitment_mode)
+            .context("L1 sender config is inconsistent with L1 batch commitment mode")?;
+        if let Some(compressor_config) = &configs.fri_proof_compressor_config {
+            eth_sender
+                .sender
+                .validate_proof_compression_backend(compressor_config.compression_backend)
+                .context("L1 sender config is inconsistent with proof compression backend")?;
+        }
+        let chain = load_chain_l1_contracts(configs, &query_client, &contracts_config).await?;
+        let (settlement_layer, eth_client) = settlement_layer_with_client(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            &chain,
+        )
+        .await?;
         let nonce = eth_client.pending_nonce("eth_sender").await.unwrap();
+        let mut aggregator = Aggregator::new(
+            eth_sender.sender.clone(),
+            store_factory.create_store().await,
+            commitment_mode,
+        );
+        let mut storage = eth_sender_pool
+            .access_storage()
+            .await
+            .context("access_storage()")?;
+        aggregator
+            .validate_commitment_mode(&mut storage)
+            .await
+            .context("L1 batch commitment mode is incompatible with pending L1 batches")?;
+        drop(storage);
+        let sender_config = &eth_sender.sender;
+        if sender_config
+            .max_aggregation_overhead_per_l1_batch
+            .is_some()
+            || sender_config.execute_max_l1_gas_price.is_some()
+        {
+            let l1_tx_params = settlement_gas_adjuster
+                .as_mut()
+                .unwrap_or(&mut gas_adjuster)
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            aggregator = aggregator
+                .with_cost_based_planner(l1_tx_params.clone())
+                .with_execute_gas_price_gate(l1_tx_params);
+        }
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
-            Aggregator::new(
-                eth_sender.sender.clone(),
-                store_factory.create_store().await,
-            ),
-            contracts_config.validator_timelock_addr,
-            contracts_config.l1_multicall3_addr,
-            main_zksync_contract_address,
+            aggregator,
+            settlement_layer,
             nonce.as_u64(),
+            eth_client.sender_account(),
         );
         task_futures.push(tokio::spawn(eth_tx_aggregator_actor.run(
             eth_sender_pool,
@@ -584,11 +991,23 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
+        let chain = load_chain_l1_contracts(configs, &query_client, &contracts_config).await?;
+        let (_, eth_client) = settlement_layer_with_client(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            &chain,
+        )
+        .await?;
+        let fee_escalation =
+            FeeEscalationPolicies::new(&eth_sender.fee_escalation, &eth_sender.gas_adjuster)
+                .context("FeeEscalationPolicies::new()")?;
         let eth_tx_manager_actor = EthTxManager::new(
             eth_sender.sender,
-            gas_adjuster
+            fee_escalation,
+            settlement_gas_adjuster
+                .as_mut()
+                .unwrap_or(&mut gas_adjuster)
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
@@ -608,40 +1027,85 @@ pub async fn initialize_components(
         &mut healthchecks,
         &components,
         &store_factory,
+        rocksdb_compactor.as_mut(),
         stop_receiver.clone(),
     )
     .await
     .context("add_trees_to_task_futures()")?;
 
+    if let Some(compactor) = rocksdb_compactor {
+        task_futures.push(tokio::spawn(compactor.run(stop_receiver.clone())));
+    }
+
     if components.contains(&Component::BasicWitnessInputProducer) {
         let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
             .build()
             .await
             .context("failed to build singleton connection_pool")?;
         let network_config = configs.network_config.clone().context("network_config")?;
+        let tee_support = configs
+            .proof_data_handler_config
+            .as_ref()
+            .map_or(false, |config| config.tee_support);
         add_basic_witness_input_producer_to_task_futures(
             &mut task_futures,
             &singleton_connection_pool,
             &store_factory,
             network_config.zksync_network_id,
+            tee_support,
             stop_receiver.clone(),
         )
         .await
         .context("add_basic_witness_input_producer_to_task_futures()")?;
     }
 
+    if components.contains(&Component::TeeVerifierInputProducer) {
+        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build singleton connection_pool")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        add_tee_verifier_input_producer_to_task_futures(
+            &mut task_futures,
+            &singleton_connection_pool,
+            &store_factory,
+            network_config.zksync_network_id,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("add_tee_verifier_input_producer_to_task_futures()")?;
+    }
+
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(configs, &mut task_futures)
+        add_house_keeper_to_task_futures(configs, &mut task_futures, stop_receiver.clone())
             .await
             .context("add_house_keeper_to_task_futures()")?;
     }
 
     if components.contains(&Component::ProofDataHandler) {
+        let proof_data_handler_config = configs
+            .proof_data_handler_config
+            .clone()
+            .context("proof_data_handler_config")?;
+        if proof_data_handler_config.proof_generation_mode == ProofGenerationMode::Mock {
+            let proof_sending_mode = configs
+                .eth_sender_config
+                .as_ref()
+                .map(|config| config.sender.proof_sending_mode);
+            if proof_sending_mode == Some(ProofSendingMode::OnlyRealProofs) {
+                anyhow::bail!(
+                    "Mock proofs are never sent with the `OnlyRealProofs` proof sending mode; \
+                     use `OnlySampledProofs` instead"
+                );
+            }
+            task_futures.push(tokio::spawn(proof_data_handler::run_mock_prover(
+                connection_pool.clone(),
+                proof_data_handler_config.proof_generation_timeout(),
+                stop_receiver.clone(),
+            )));
+        }
         task_futures.push(tokio::spawn(proof_data_handler::run_server(
-            configs
-                .proof_data_handler_config
-                .clone()
-                .context("proof_data_handler_config")?,
+            proof_data_handler_config,
             configs
                 .contracts_config
                 .clone()
@@ -652,24 +1116,240 @@ pub async fn initialize_components(
         )));
     }
 
+    if components.contains(&Component::DbPruner) {
+        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build singleton connection_pool")?;
+        let db_pruner = DbPruner::new(db_config.pruning.clone(), singleton_connection_pool);
+        task_futures.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::StorageLogsCompactor) {
+        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build singleton connection_pool")?;
+        let compactor = StorageLogsCompactor::new(
+            db_config.storage_logs_compaction.clone(),
+            singleton_connection_pool,
+        );
+        task_futures.push(tokio::spawn(compactor.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::ColdStorageArchiver) {
+        anyhow::ensure!(
+            db_config.cold_storage.enabled,
+            "Cold storage archiver requires cold storage to be enabled in the database config, \
+             so that archived data remains available via the API"
+        );
+        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build singleton connection_pool")?;
+        let archiver = ColdStorageArchiver::new(
+            db_config.cold_storage.clone(),
+            singleton_connection_pool,
+            store_factory.create_store().await,
+        );
+        task_futures.push(tokio::spawn(archiver.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::DADispatcher) {
+        let eth_sender_config = configs
+            .eth_sender_config
+            .clone()
+            .context("eth_sender_config")?;
+        anyhow::ensure!(
+            eth_sender_config.sender.pubdata_sending_mode == PubdataSendingMode::Custom,
+            "DA dispatcher requires the custom pubdata sending mode in the L1 sender config, \
+             so that L1 batches are committed with DA inclusion data"
+        );
+        let commitment_mode = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?
+            .l1_batch_commitment_mode;
+        eth_sender_config
+            .sender
+            .validate_commitment_mode(commitment_mode)
+            .context("L1 sender config is inconsistent with L1 batch commitment mode")?;
+        let da_dispatcher_config = configs
+            .da_dispatcher_config
+            .clone()
+            .context("da_dispatcher_config")?;
+        let da_client = create_da_client(&da_dispatcher_config)?;
+        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build singleton connection_pool")?;
+        let dispatcher = DataAvailabilityDispatcher::new(
+            da_dispatcher_config,
+            singleton_connection_pool,
+            da_client,
+        );
+        task_futures.push(tokio::spawn(dispatcher.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::UpgradeDryRun) {
+        let upgrade_dry_run_config = configs
+            .upgrade_dry_run_config
+            .clone()
+            .context("upgrade_dry_run_config")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let dry_runner = UpgradeDryRunner::new(
+            upgrade_dry_run_config,
+            pool,
+            network_config.zksync_network_id,
+        );
+        task_futures.push(tokio::spawn(dry_runner.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::CallTracesBackfiller) {
+        let backfiller_config = configs
+            .call_traces_backfiller_config
+            .clone()
+            .context("call_traces_backfiller_config")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let backfiller =
+            CallTracesBackfiller::new(backfiller_config, pool, network_config.zksync_network_id);
+        task_futures.push(tokio::spawn(backfiller.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::EventIndexer) {
+        let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let mut indexes = event_indexes;
+        indexes.register(Erc20TransfersIndex);
+        let indexer = EventIndexer::new(pool, indexes);
+        task_futures.push(tokio::spawn(indexer.run(stop_receiver.clone())));
+    }
+
+    if !postgres_config.read_replica_urls.is_empty() {
+        const REPLICA_LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+        let replica_lag_checks = replica_connection_pool
+            .clone()
+            .run_replica_lag_checks(REPLICA_LAG_CHECK_INTERVAL, stop_receiver.clone());
+        task_futures.push(tokio::spawn(replica_lag_checks));
+    }
+
+    if postgres_config.min_connections.is_some() {
+        for pool in [&connection_pool, &replica_connection_pool] {
+            let adjustments = pool
+                .clone()
+                .run_adaptive_sizing(POOL_ADJUSTMENT_INTERVAL, stop_receiver.clone());
+            task_futures.push(tokio::spawn(adjustments));
+        }
+    }
+
     // Run healthcheck server for all components.
     healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
         replica_connection_pool,
     )));
+    if let Some(health_check) = gas_adjuster.health_check() {
+        healthchecks.push(Box::new(health_check));
+    }
 
     let healtcheck_api_config = configs
         .health_check_config
         .clone()
         .context("health_check_config")?;
+    if components.contains(&Component::StateKeeper) {
+        let thresholds = LagThresholds {
+            degraded: healtcheck_api_config.state_keeper_degraded_lag_sec,
+            unhealthy: healtcheck_api_config.state_keeper_unhealthy_lag_sec,
+        };
+        healthchecks.push(Box::new(StateKeeperLagHealthCheck::new(
+            connection_pool.clone(),
+            thresholds,
+        )));
+    }
+    if components.contains(&Component::Tree) {
+        let thresholds = LagThresholds {
+            degraded: healtcheck_api_config.tree_degraded_lag,
+            unhealthy: healtcheck_api_config.tree_unhealthy_lag,
+        };
+        healthchecks.push(Box::new(TreeLagHealthCheck::new(
+            connection_pool.clone(),
+            thresholds,
+        )));
+    }
+    if components.contains(&Component::EthTxManager) {
+        healthchecks.push(Box::new(EthSenderHealthCheck::new(
+            connection_pool.clone(),
+            healtcheck_api_config.eth_sender_degraded_lag_sec,
+        )));
+    }
     let health_check_handle =
         HealthCheckHandle::spawn_server(healtcheck_api_config.bind_addr(), healthchecks);
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
     }
+    if let Some(settlement_gas_adjuster) = settlement_gas_adjuster {
+        if let Some(task) = settlement_gas_adjuster.run_if_initialized(stop_receiver.clone()) {
+            task_futures.push(task);
+        }
+    }
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+/// Loads L1 contracts of the chain served by the node, resolving them from the bridgehub if the chain
+/// is registered in the shared bridge.
+async fn load_chain_l1_contracts(
+    configs: &TempConfigStore,
+    query_client: &QueryClient,
+    contracts_config: &ContractsConfig,
+) -> anyhow::Result<ChainL1Contracts> {
+    let network_config = configs.network_config.as_ref().context("network_config")?;
+    ChainL1Contracts::load(
+        query_client,
+        contracts_config,
+        network_config.zksync_network_id,
+    )
+    .await
+    .context("ChainL1Contracts::load()")
+}
+
+/// Creates the settlement layer specified in the L1 sender config and the operator client connected to it.
+async fn settlement_layer_with_client(
+    eth_sender: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+    chain: &ChainL1Contracts,
+) -> anyhow::Result<(Arc<dyn SettlementLayer>, OperatorSigningClient)> {
+    let settlement_layer = settlement_layer_from_config(
+        &eth_sender.sender,
+        contracts_config,
+        eth_client_config,
+        chain,
+    )
+    .context("settlement_layer_from_config()")?;
+    let contracts_config = ContractsConfig {
+        diamond_proxy_addr: settlement_layer.diamond_proxy_address(),
+        ..contracts_config.clone()
+    };
+    let eth_client = OperatorSigningClient::from_config(
+        eth_sender,
+        &contracts_config,
+        &settlement_layer.client_config(),
+    )
+    .await
+    .context("OperatorSigningClient::from_config()")?;
+    Ok((settlement_layer, eth_client))
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync + 'static>(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -681,6 +1361,12 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
     mempool_config: &MempoolConfig,
     gas_adjuster: Arc<E>,
     object_store: Box<dyn ObjectStore>,
+    address_filter: Option<AddressFilter>,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
+    custom_seal_criteria: Vec<Box<dyn SealCriterion>>,
+    soft_confirmations: Option<broadcast::Sender<TransactionSoftConfirmation>>,
+    compaction_requests: Option<StateKeeperCompactionRequests>,
+    bytecode_cache: Option<BytecodeCache>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let fair_l2_gas_price = state_keeper_config.fair_l2_gas_price;
@@ -696,9 +1382,27 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         .transactions_dal()
         .next_priority_id()
         .await;
-    let mempool = MempoolGuard::new(next_priority_id, mempool_config.capacity);
+    let mempool = MempoolGuard::from_config(next_priority_id, mempool_config);
     mempool.register_metrics();
 
+    let external_proposals = if let Some(port) = state_keeper_config.external_builder_http_port {
+        let auth_token = state_keeper_config
+            .external_builder_auth_token
+            .clone()
+            .context("external_builder_auth_token must be set to enable external block builder")?;
+        let (proposals_sender, proposals_receiver) = external_builder::proposals_channel();
+        task_futures.push(tokio::spawn(external_builder::run_server(
+            port,
+            auth_token,
+            network_config.zksync_network_id,
+            proposals_sender,
+            stop_receiver.clone(),
+        )));
+        Some(proposals_receiver)
+    } else {
+        None
+    };
+
     let miniblock_sealer_pool = pool_builder
         .build()
         .await
@@ -709,7 +1413,7 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
     );
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
-    let state_keeper = create_state_keeper(
+    let mut state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
         db_config,
@@ -720,16 +1424,28 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         gas_adjuster.clone(),
         miniblock_sealer_handle,
         object_store,
+        address_filter,
+        config_overrides.clone(),
+        external_proposals,
+        custom_seal_criteria,
+        compaction_requests,
+        bytecode_cache,
         stop_receiver.clone(),
     )
     .await;
+    if let Some(soft_confirmations) = soft_confirmations {
+        state_keeper = state_keeper.with_soft_confirmations(soft_confirmations);
+    }
     task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = pool_builder
         .build()
         .await
         .context("failed to build mempool_fetcher_pool")?;
-    let mempool_fetcher = MempoolFetcher::new(mempool, gas_adjuster, mempool_config);
+    let mut mempool_fetcher = MempoolFetcher::new(mempool, gas_adjuster, mempool_config);
+    if let Some(overrides) = config_overrides {
+        mempool_fetcher = mempool_fetcher.with_config_overrides(overrides);
+    }
     let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(
         mempool_fetcher_pool,
         mempool_config.remove_stuck_txs,
@@ -747,6 +1463,7 @@ async fn add_trees_to_task_futures(
     healthchecks: &mut Vec<Box<dyn CheckHealth>>,
     components: &[Component],
     store_factory: &ObjectStoreFactory,
+    rocksdb_compactor: Option<&mut RocksdbCompactor>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     if !components.contains(&Component::Tree) {
@@ -754,6 +1471,10 @@ async fn add_trees_to_task_futures(
             !components.contains(&Component::TreeApi),
             "Merkle tree API cannot be started without a tree component"
         );
+        anyhow::ensure!(
+            !components.contains(&Component::TreeConsistencyChecker),
+            "Merkle tree consistency checker cannot be started without a tree component"
+        );
         return Ok(());
     }
 
@@ -771,6 +1492,12 @@ async fn add_trees_to_task_futures(
     let api_config = components
         .contains(&Component::TreeApi)
         .then_some(&api_config);
+    let run_consistency_checker = components.contains(&Component::TreeConsistencyChecker);
+    let commitment_mode = configs
+        .state_keeper_config
+        .as_ref()
+        .context("state_keeper_config")?
+        .l1_batch_commitment_mode;
 
     let mode = match db_config.merkle_tree.mode {
         MerkleTreeMode::Lightweight => MetadataCalculatorModeConfig::Lightweight,
@@ -785,8 +1512,11 @@ async fn add_trees_to_task_futures(
         &postgres_config,
         &db_config,
         api_config,
+        run_consistency_checker,
+        rocksdb_compactor,
         &operation_config,
         mode,
+        commitment_mode,
         stop_receiver,
     )
     .await
@@ -800,8 +1530,11 @@ async fn run_tree(
     postgres_config: &PostgresConfig,
     db_config: &DBConfig,
     api_config: Option<&MerkleTreeApiConfig>,
+    run_consistency_checker: bool,
+    rocksdb_compactor: Option<&mut RocksdbCompactor>,
     operation_manager: &OperationsManagerConfig,
     mode: MetadataCalculatorModeConfig<'_>,
+    commitment_mode: L1BatchCommitmentMode,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
@@ -812,8 +1545,12 @@ async fn run_tree(
     };
     tracing::info!("Initializing Merkle tree in {mode_str} mode");
 
-    let config =
-        MetadataCalculatorConfig::for_main_node(&db_config.merkle_tree, operation_manager, mode);
+    let config = MetadataCalculatorConfig::for_main_node(
+        &db_config.merkle_tree,
+        operation_manager,
+        mode,
+        commitment_mode,
+    );
     let metadata_calculator = MetadataCalculator::new(&config).await;
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
@@ -826,6 +1563,23 @@ async fn run_tree(
                 .await
         }));
     }
+    if run_consistency_checker {
+        let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build connection pool for tree consistency checker")?;
+        let checker = TreeConsistencyChecker::new(pool, &db_config.merkle_tree);
+        healthchecks.push(Box::new(checker.health_check()));
+        let tree_reader = metadata_calculator.tree_reader();
+        let stop_receiver = stop_receiver.clone();
+        task_futures.push(tokio::spawn(async move {
+            checker.run(tree_reader.await, stop_receiver).await
+        }));
+    }
+
+    if let Some(compactor) = rocksdb_compactor {
+        compactor.set_merkle_tree(metadata_calculator.tree_reader());
+    }
 
     let tree_health_check = metadata_calculator.tree_health_check();
     healthchecks.push(Box::new(tree_health_check));
@@ -847,6 +1601,7 @@ async fn add_basic_witness_input_producer_to_task_futures(
     connection_pool: &ConnectionPool,
     store_factory: &ObjectStoreFactory,
     l2_chain_id: L2ChainId,
+    tee_support: bool,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     // Witness Generator won't be spawned with `ZKSYNC_LOCAL_SETUP` running.
@@ -856,8 +1611,13 @@ async fn add_basic_witness_input_producer_to_task_futures(
     }
     let started_at = Instant::now();
     tracing::info!("initializing BasicWitnessInputProducer");
-    let producer =
-        BasicWitnessInputProducer::new(connection_pool.clone(), store_factory, l2_chain_id).await?;
+    let producer = BasicWitnessInputProducer::new(
+        connection_pool.clone(),
+        store_factory,
+        l2_chain_id,
+        tee_support,
+    )
+    .await?;
     task_futures.push(tokio::spawn(producer.run(stop_receiver, None)));
     tracing::info!(
         "Initialized BasicWitnessInputProducer in {:?}",
@@ -868,9 +1628,31 @@ async fn add_basic_witness_input_producer_to_task_futures(
     Ok(())
 }
 
+async fn add_tee_verifier_input_producer_to_task_futures(
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    connection_pool: &ConnectionPool,
+    store_factory: &ObjectStoreFactory,
+    l2_chain_id: L2ChainId,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let started_at = Instant::now();
+    tracing::info!("initializing TeeVerifierInputProducer");
+    let producer =
+        TeeVerifierInputProducer::new(connection_pool.clone(), store_factory, l2_chain_id).await?;
+    task_futures.push(tokio::spawn(producer.run(stop_receiver, None)));
+    tracing::info!(
+        "Initialized TeeVerifierInputProducer in {:?}",
+        started_at.elapsed()
+    );
+    let elapsed = started_at.elapsed();
+    APP_METRICS.init_latency[&InitStage::TeeVerifierInputProducer].set(elapsed);
+    Ok(())
+}
+
 async fn add_house_keeper_to_task_futures(
     configs: &TempConfigStore,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let house_keeper_config = configs
         .house_keeper_config
@@ -924,6 +1706,10 @@ async fn add_house_keeper_to_task_futures(
     task_futures.push(tokio::spawn(prover_job_retry_manager.run()));
 
     // All FRI Prover related components are configured below.
+    let fri_job_retry_backoff = JobRetryBackoff {
+        base: house_keeper_config.fri_job_retry_backoff_base(),
+        max: house_keeper_config.fri_job_retry_backoff_max(),
+    };
     let fri_prover_config = configs
         .fri_prover_config
         .clone()
@@ -931,11 +1717,26 @@ async fn add_house_keeper_to_task_futures(
     let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
         fri_prover_config.max_attempts,
         fri_prover_config.proof_generation_timeout(),
+        fri_job_retry_backoff,
         house_keeper_config.fri_prover_job_retrying_interval_ms,
         prover_connection_pool.clone(),
     );
     task_futures.push(tokio::spawn(fri_prover_job_retry_manager.run()));
 
+    if let Some(port) = house_keeper_config.prover_api_port {
+        let auth_token = house_keeper_config
+            .prover_api_auth_token
+            .clone()
+            .context("prover_api_auth_token must be set to enable prover API")?;
+        task_futures.push(tokio::spawn(prover_queue_api::run_server(
+            port,
+            auth_token,
+            fri_prover_config.max_attempts,
+            prover_connection_pool.clone(),
+            stop_receiver,
+        )));
+    }
+
     let fri_witness_gen_config = configs
         .fri_witness_generator_config
         .clone()
@@ -943,6 +1744,7 @@ async fn add_house_keeper_to_task_futures(
     let fri_witness_gen_job_retry_manager = FriWitnessGeneratorJobRetryManager::new(
         fri_witness_gen_config.max_attempts,
         fri_witness_gen_config.witness_generation_timeout(),
+        fri_job_retry_backoff,
         house_keeper_config.fri_witness_generator_job_retrying_interval_ms,
         prover_connection_pool.clone(),
     );
@@ -1000,6 +1802,7 @@ async fn add_house_keeper_to_task_futures(
 
 fn build_storage_caches(
     configs: &TempConfigStore,
+    bytecode_cache: Option<BytecodeCache>,
     replica_connection_pool: &ConnectionPool,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
 ) -> anyhow::Result<PostgresStorageCaches> {
@@ -1007,11 +1810,12 @@ fn build_storage_caches(
         .web3_json_rpc_config
         .clone()
         .context("web3_json_rpc_config")?;
-    let factory_deps_capacity = rpc_config.factory_deps_cache_size() as u64;
+    let bytecode_cache = bytecode_cache
+        .unwrap_or_else(|| BytecodeCache::new(rpc_config.factory_deps_cache_size() as u64));
     let initial_writes_capacity = rpc_config.initial_writes_cache_size() as u64;
     let values_capacity = rpc_config.latest_values_cache_size() as u64;
     let mut storage_caches =
-        PostgresStorageCaches::new(factory_deps_capacity, initial_writes_capacity);
+        PostgresStorageCaches::with_bytecode_cache(bytecode_cache, initial_writes_capacity);
 
     if values_capacity > 0 {
         let values_cache_task = storage_caches.configure_storage_values_cache(
@@ -1024,6 +1828,47 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+fn build_address_filter(
+    state_keeper_config: &StateKeeperConfig,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: &watch::Receiver<bool>,
+) -> anyhow::Result<Option<AddressFilter>> {
+    let Some(path) = &state_keeper_config.address_filter_path else {
+        return Ok(None);
+    };
+    let filter = AddressFilter::load(Path::new(path), state_keeper_config.address_filter_mode())
+        .with_context(|| format!("failed loading address filter from `{path}`"))?;
+    task_futures.push(tokio::spawn(filter.clone().run_reloader(
+        path.into(),
+        state_keeper_config.address_filter_reload_interval(),
+        stop_receiver.clone(),
+    )));
+    Ok(Some(filter))
+}
+
+fn build_config_watcher(
+    state_keeper_config: &StateKeeperConfig,
+    address_filter: Option<&AddressFilter>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: &watch::Receiver<bool>,
+) -> anyhow::Result<Option<watch::Receiver<ConfigOverrides>>> {
+    let Some(path) = &state_keeper_config.config_overrides_path else {
+        return Ok(None);
+    };
+    let mut watcher = ConfigWatcher::new(
+        path.into(),
+        state_keeper_config.config_overrides_reload_interval(),
+    )
+    .with_context(|| format!("failed loading config overrides from `{path}`"))?;
+    if let Some(filter) = address_filter {
+        watcher =
+            watcher.with_address_filter(filter.clone(), state_keeper_config.address_filter_mode());
+    }
+    let overrides = watcher.subscribe();
+    task_futures.push(tokio::spawn(watcher.run(stop_receiver.clone())));
+    Ok(Some(overrides))
+}
+
 async fn build_tx_sender<G: L1GasPriceProvider>(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1031,16 +1876,26 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     replica_pool: ConnectionPool,
     master_pool: ConnectionPool,
     l1_gas_price_provider: Arc<G>,
+    address_filter: Option<AddressFilter>,
+    tx_filters: TxFilters,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     storage_caches: PostgresStorageCaches,
 ) -> (TxSender<G>, VmConcurrencyBarrier) {
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
-        .with_state_keeper_config(state_keeper_config.clone());
+        .with_state_keeper_config(state_keeper_config.clone())
+        .with_tx_filters(tx_filters);
 
     // Add rate limiter if enabled.
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
         tx_sender_builder = tx_sender_builder.with_rate_limiter(transactions_per_sec_limit);
     };
+    if let Some(address_filter) = address_filter {
+        tx_sender_builder = tx_sender_builder.with_address_filter(address_filter);
+    }
+    if let Some(overrides) = config_overrides {
+        tx_sender_builder = tx_sender_builder.with_config_overrides(overrides);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1068,6 +1923,9 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     stop_receiver: watch::Receiver<bool>,
     gas_adjuster: Arc<G>,
     with_debug_namespace: bool,
+    address_filter: Option<AddressFilter>,
+    tx_filters: TxFilters,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     storage_caches: PostgresStorageCaches,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
@@ -1077,6 +1935,9 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         replica_connection_pool.clone(),
         master_connection_pool,
         gas_adjuster,
+        address_filter,
+        tx_filters,
+        config_overrides,
         storage_caches,
     )
     .await;
@@ -1086,6 +1947,12 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         namespaces.push(Namespace::Debug)
     }
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.analytics_namespace_enabled() {
+        namespaces.push(Namespace::Analytics);
+    }
+    if api_config.web3_json_rpc.unstable_namespace_enabled() {
+        namespaces.push(Namespace::Unstable);
+    }
 
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
         .build()
@@ -1117,6 +1984,10 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
+    address_filter: Option<AddressFilter>,
+    tx_filters: TxFilters,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
+    soft_confirmations: Option<broadcast::Sender<TransactionSoftConfirmation>>,
     storage_caches: PostgresStorageCaches,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
@@ -1126,6 +1997,9 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         replica_connection_pool.clone(),
         master_connection_pool,
         gas_adjuster,
+        address_filter,
+        tx_filters,
+        config_overrides,
         storage_caches,
     )
     .await;
@@ -1137,12 +2011,19 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.push(Namespace::Snapshots);
 
-    let api_builder =
+    let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
             .ws(api_config.web3_json_rpc.ws_port)
             .with_last_miniblock_pool(last_miniblock_pool)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
+            .with_max_subscriptions_per_connection(
+                api_config.web3_json_rpc.max_subscriptions_per_connection(),
+            )
+            .with_max_buffered_notifications(api_config.web3_json_rpc.max_buffered_notifications())
+            .with_subscription_backpressure_policy(
+                api_config.web3_json_rpc.subscription_backpressure_policy(),
+            )
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
             .with_websocket_requests_per_minute_limit(
@@ -1155,17 +2036,109 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
             .with_tx_sender(tx_sender, vm_barrier)
             .enable_api_namespaces(namespaces);
+    if let Some(soft_confirmations) = soft_confirmations {
+        api_builder = api_builder.with_soft_confirmations(soft_confirmations);
+    }
 
     api_builder.build(stop_receiver.clone()).await
 }
 
-async fn circuit_breakers_for_components(
+#[allow(clippy::too_many_arguments)]
+async fn run_profile_api<G: L1GasPriceProvider + Send + Sync + 'static>(
+    profile: &ApiProfileConfig,
+    postgres_config: &PostgresConfig,
+    tx_sender_config: &TxSenderConfig,
+    state_keeper_config: &StateKeeperConfig,
+    internal_api: &InternalApiConfig,
+    api_config: &ApiConfig,
+    gas_adjuster: Arc<G>,
+    master_connection_pool: ConnectionPool,
+    replica_connection_pool: ConnectionPool,
+    stop_receiver: watch::Receiver<bool>,
+    address_filter: Option<AddressFilter>,
+    tx_filters: TxFilters,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
+    soft_confirmations: Option<broadcast::Sender<TransactionSoftConfirmation>>,
+    storage_caches: PostgresStorageCaches,
+) -> anyhow::Result<ApiServerHandles> {
+    let web3_config = &api_config.web3_json_rpc;
+    if profile.namespaces.contains(&Namespace::Debug) && !state_keeper_config.save_call_traces {
+        tracing::warn!(
+            "API profile `{}` serves the debug namespace, but call traces are not saved by the state keeper",
+            profile.name
+        );
+    }
+
+    // Each profile gets its own VM concurrency limiter, so that heavy requests served by one profile
+    // don't starve other profiles.
+    let profile_web3_config = Web3JsonRpcConfig {
+        vm_concurrency_limit: profile
+            .vm_concurrency_limit
+            .or(web3_config.vm_concurrency_limit),
+        ..web3_config.clone()
+    };
+    let (tx_sender, vm_barrier) = build_tx_sender(
+        tx_sender_config,
+        &profile_web3_config,
+        state_keeper_config,
+        replica_connection_pool.clone(),
+        master_connection_pool,
+        gas_adjuster,
+        address_filter,
+        tx_filters,
+        config_overrides,
+        storage_caches,
+    )
+    .await;
+    let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build last_miniblock_pool")?;
+
+    // Profiles are created once on node startup, so leaking their names is fine.
+    let health_check_name: &'static str = Box::leak(profile.health_check_name().into_boxed_str());
+    let api_builder =
+        web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
+            .with_last_miniblock_pool(last_miniblock_pool)
+            .with_filter_limit(web3_config.filters_limit())
+            .with_tree_api(web3_config.tree_api_url())
+            .with_batch_request_size_limit(web3_config.max_batch_request_size())
+            .with_response_body_size_limit(web3_config.max_response_body_size())
+            .with_health_check_name(health_check_name)
+            .with_tx_sender(tx_sender, vm_barrier)
+            .enable_api_namespaces(profile.namespaces.clone());
+    let mut api_builder = match profile.transport {
+        ApiProfileTransport::Http => api_builder
+            .http(profile.port)
+            .with_threads(profile.threads.unwrap_or(web3_config.http_server_threads())),
+        ApiProfileTransport::Ws => api_builder
+            .ws(profile.port)
+            .with_threads(profile.threads.unwrap_or(web3_config.ws_server_threads()))
+            .with_subscriptions_limit(web3_config.subscriptions_limit())
+            .with_max_subscriptions_per_connection(web3_config.max_subscriptions_per_connection())
+            .with_max_buffered_notifications(web3_config.max_buffered_notifications())
+            .with_subscription_backpressure_policy(web3_config.subscription_backpressure_policy())
+            .with_websocket_requests_per_minute_limit(
+                web3_config.websocket_requests_per_minute_limit(),
+            )
+            .with_polling_interval(web3_config.pubsub_interval()),
+    };
+    if let Some(soft_confirmations) = soft_confirmations {
+        if profile.transport == ApiProfileTransport::Ws {
+            api_builder = api_builder.with_soft_confirmations(soft_confirmations);
+        }
+    }
+    api_builder.build(stop_receiver).await
+}
+
+async fn register_circuit_breakers(
+    checker: &mut CircuitBreakerChecker,
     components: &[Component],
     postgres_config: &PostgresConfig,
     circuit_breaker_config: &CircuitBreakerConfig,
-) -> anyhow::Result<Vec<Box<dyn CircuitBreaker>>> {
-    let mut circuit_breakers: Vec<Box<dyn CircuitBreaker>> = Vec::new();
-
+    eth_client: &QueryClient,
+    contracts_config: &ContractsConfig,
+) -> anyhow::Result<()> {
     if components
         .iter()
         .any(|c| matches!(c, Component::EthTxAggregator | Component::EthTxManager))
@@ -1174,22 +2147,87 @@ async fn circuit_breakers_for_components(
             .build()
             .await
             .context("failed to build a connection pool")?;
-        circuit_breakers.push(Box::new(FailedL1TransactionChecker { pool }));
+        checker.register(Box::new(FailedL1TransactionChecker { pool }), None);
+    }
+
+    if components.contains(&Component::EthTxAggregator) {
+        let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let rule = L1ContractsChecker {
+            eth_client: eth_client.clone(),
+            diamond_proxy_addr: contracts_config.diamond_proxy_addr,
+            pool,
+        };
+        checker.register(
+            Box::new(rule),
+            circuit_breaker_config.l1_contracts_check_interval(),
+        );
+    }
+
+    if let Some(max_ratio) = circuit_breaker_config.fee_divergence_max_ratio {
+        if components.contains(&Component::StateKeeper) {
+            let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let rule = FeeDivergenceChecker {
+                eth_client: eth_client.clone(),
+                pool,
+                max_ratio,
+            };
+            checker.register(
+                Box::new(rule),
+                circuit_breaker_config.fee_divergence_check_interval(),
+            );
+        }
+    }
+
+    if let Some(inclusion_timeout) = circuit_breaker_config.da_inclusion_timeout() {
+        if components.contains(&Component::DADispatcher) {
+            let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let rule = DaInclusionChecker {
+                pool,
+                inclusion_timeout,
+            };
+            checker.register(Box::new(rule), None);
+        }
     }
 
     if components.iter().any(|c| {
         matches!(
             c,
-            Component::HttpApi | Component::WsApi | Component::ContractVerificationApi
+            Component::HttpApi
+                | Component::WsApi
+                | Component::ApiProfiles
+                | Component::ContractVerificationApi
         )
     }) {
         let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
             .build()
             .await?;
-        circuit_breakers.push(Box::new(ReplicationLagChecker {
+        let rule = ReplicationLagChecker {
             pool,
             replication_lag_limit_sec: circuit_breaker_config.replication_lag_limit_sec,
-        }));
+        };
+        checker.register(Box::new(rule), None);
+    }
+
+    if let Some(path) = &circuit_breaker_config.custom_rules_path {
+        let rules = CustomRuleConfig::load(Path::new(path))?;
+        for rule in rules {
+            let check_interval = rule.check_interval();
+            let rule = CustomRuleChecker::new(
+                rule,
+                eth_client.clone(),
+                contracts_config.diamond_proxy_addr,
+            )?;
+            checker.register(Box::new(rule), check_interval);
+        }
     }
-    Ok(circuit_breakers)
+    Ok(())
 }
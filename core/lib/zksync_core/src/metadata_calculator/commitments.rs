@@ -0,0 +1,133 @@
+//! Concurrent computation of L1 batch commitments that do not depend on the Merkle tree.
+
+use std::collections::VecDeque;
+
+use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queue_commitment};
+use zksync_dal::StorageProcessor;
+use zksync_types::{L1BatchNumber, H256};
+
+use super::metrics::{TreeUpdateStage, METRICS};
+
+/// Commitments of an L1 batch computed outside the Merkle tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct L1BatchCommitments {
+    pub events_queue_commitment: Option<H256>,
+    pub bootloader_initial_content_commitment: Option<H256>,
+}
+
+/// Computes [`L1BatchCommitments`] for a range of L1 batches.
+///
+/// Commitment computation is CPU-heavy, so it's performed on blocking threads for up to `concurrency`
+/// L1 batches at a time, concurrently with updating the Merkle tree. Inputs for the computations are loaded
+/// from Postgres sequentially using the connection provided by the caller, so that the pipeline doesn't require
+/// additional DB connections. Computed commitments are returned in the L1 batch order, so that L1 batch metadata
+/// is still persisted sequentially.
+#[derive(Debug)]
+pub(super) struct CommitmentsPipeline {
+    concurrency: usize,
+    next_l1_batch_number: L1BatchNumber,
+    last_l1_batch_number: L1BatchNumber,
+    pending: VecDeque<(L1BatchNumber, tokio::task::JoinHandle<L1BatchCommitments>)>,
+}
+
+impl CommitmentsPipeline {
+    pub fn new(
+        first_l1_batch_number: L1BatchNumber,
+        last_l1_batch_number: L1BatchNumber,
+        concurrency: usize,
+    ) -> Self {
+        assert!(
+            concurrency > 0,
+            "Commitment generation concurrency must be positive"
+        );
+        Self {
+            concurrency,
+            next_l1_batch_number: first_l1_batch_number,
+            last_l1_batch_number,
+            pending: VecDeque::with_capacity(concurrency),
+        }
+    }
+
+    /// Starts computing commitments for the following L1 batches until the concurrency limit is reached.
+    pub async fn fill(&mut self, storage: &mut StorageProcessor<'_>) {
+        while self.pending.len() < self.concurrency
+            && self.next_l1_batch_number <= self.last_l1_batch_number
+        {
+            let l1_batch_number = self.next_l1_batch_number;
+            let task = Self::spawn_computation(storage, l1_batch_number).await;
+            self.pending.push_back((l1_batch_number, task));
+            self.next_l1_batch_number = l1_batch_number + 1;
+        }
+    }
+
+    /// Waits until commitments for the specified L1 batch are computed. L1 batches must be requested in order.
+    pub async fn next(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> L1BatchCommitments {
+        self.fill(storage).await;
+        let (pending_l1_batch_number, task) = self
+            .pending
+            .pop_front()
+            .expect("Requested commitments for an L1 batch outside the pipeline range");
+        assert_eq!(
+            pending_l1_batch_number, l1_batch_number,
+            "L1 batch commitments are requested out of order"
+        );
+        task.await.expect("Commitment computation panicked")
+    }
+
+    async fn spawn_computation(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> tokio::task::JoinHandle<L1BatchCommitments> {
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("Missing header for L1 batch #{l1_batch_number}"));
+        let is_pre_boojum = header
+            .protocol_version
+            .map(|v| v.is_pre_boojum())
+            .unwrap_or(true);
+        let events_queue = if is_pre_boojum {
+            None
+        } else {
+            let events_queue = storage
+                .blocks_dal()
+                .get_events_queue(l1_batch_number)
+                .await
+                .unwrap();
+            Some(events_queue.expect("Events queue is required for post-boojum batch"))
+        };
+        let initial_bootloader_contents = storage
+            .blocks_dal()
+            .get_initial_bootloader_heap(l1_batch_number)
+            .await
+            .unwrap()
+            .unwrap();
+
+        tokio::task::spawn_blocking(move || {
+            let events_queue_commitment_latency =
+                METRICS.start_stage(TreeUpdateStage::EventsCommitment);
+            let events_queue_commitment = events_queue.map(|events_queue| {
+                events_queue_commitment(&events_queue, is_pre_boojum)
+                    .expect("Events queue commitment is required for post-boojum batch")
+            });
+            events_queue_commitment_latency.observe();
+
+            let bootloader_commitment_latency =
+                METRICS.start_stage(TreeUpdateStage::BootloaderCommitment);
+            let bootloader_initial_content_commitment =
+                bootloader_initial_content_commitment(&initial_bootloader_contents, is_pre_boojum);
+            bootloader_commitment_latency.observe();
+
+            L1BatchCommitments {
+                events_queue_commitment,
+                bootloader_initial_content_commitment,
+            }
+        })
+    }
+}
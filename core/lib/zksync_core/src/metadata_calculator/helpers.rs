@@ -16,10 +16,14 @@ use zksync_health_check::{Health, HealthStatus};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
     recovery::MerkleTreeRecovery,
-    Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
+    ConsistencyError, Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof,
+    TreeInstruction,
+};
+use zksync_storage::{CompactionStats, RocksDB, RocksDBOptions, StalledWritesRetries};
+use zksync_types::{
+    block::{L1BatchHeader, L1BatchTreeData},
+    L1BatchNumber, StorageKey, H256,
 };
-use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
-use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
 
@@ -29,6 +33,8 @@ pub(crate) struct MerkleTreeInfo {
     pub mode: MerkleTreeMode,
     pub root_hash: H256,
     pub next_l1_batch_number: L1BatchNumber,
+    /// Minimum L1 batch number retained by the tree; earlier versions of the tree may be pruned.
+    pub min_l1_batch_number: L1BatchNumber,
     pub leaf_count: u64,
 }
 
@@ -197,12 +203,23 @@ impl AsyncTreeReader {
             mode: self.mode,
             root_hash: self.inner.root_hash(),
             next_l1_batch_number: self.inner.next_l1_batch_number(),
+            min_l1_batch_number: self.inner.min_l1_batch_number(),
             leaf_count: self.inner.leaf_count(),
         })
         .await
         .unwrap()
     }
 
+    pub async fn entries(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        tokio::task::spawn_blocking(move || self.inner.entries(l1_batch_number, &keys))
+            .await
+            .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -212,6 +229,43 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    pub async fn l1_batch_tree_data(
+        self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<L1BatchTreeData, NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            let (hash, leaf_count) = self.inner.root_info(l1_batch_number)?;
+            Ok(L1BatchTreeData {
+                hash,
+                rollup_last_leaf_index: leaf_count + 1,
+            })
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Verifies consistency of the subtree containing `key` and returns the recomputed tree root hash.
+    pub async fn verify_subtree_consistency(
+        self,
+        l1_batch_number: L1BatchNumber,
+        key: Key,
+        depth: usize,
+    ) -> Result<H256, ConsistencyError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner
+                .verify_subtree_consistency(l1_batch_number, key, depth)
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Compacts the underlying RocksDB instance.
+    pub async fn compact_db(self) -> Vec<CompactionStats> {
+        tokio::task::spawn_blocking(move || self.inner.compact_db())
+            .await
+            .unwrap()
+    }
 }
 
 /// Async wrapper for [`MerkleTreeRecovery`].
@@ -259,11 +313,11 @@ impl AsyncTreeRecovery {
         root_hash
     }
 
-    /// Extends the tree with a chunk of recovery entries.
+    /// Extends the tree with a chunk of recovery entries. Tree traversal is parallelized across subtrees.
     pub async fn extend(&mut self, entries: Vec<TreeEntry>) {
         let mut tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
         let tree = tokio::task::spawn_blocking(move || {
-            tree.extend_random(entries);
+            tree.extend_parallel(entries);
             tree
         })
         .await
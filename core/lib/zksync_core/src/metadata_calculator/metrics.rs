@@ -3,8 +3,8 @@
 use std::time::{Duration, Instant};
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
-    Unit,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics, Unit,
 };
 use zksync_types::block::L1BatchHeader;
 use zksync_utils::time::seconds_since_epoch;
@@ -210,3 +210,30 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum TreeCheckOutcome {
+    Consistent,
+    Inconsistent,
+}
+
+/// Metrics for the Merkle tree consistency checker.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_tree_consistency_checker")]
+pub(super) struct TreeConsistencyCheckerMetrics {
+    /// Whether the tree is consistent according to all performed checks (1) or not (0). Once an inconsistency
+    /// is detected, the gauge stays at 0 until the checker is restarted.
+    pub is_consistent: Gauge<u64>,
+    /// Number of performed checks grouped by the outcome.
+    pub checks: Family<TreeCheckOutcome, Counter>,
+    /// Number of the last L1 batch checked.
+    pub last_checked_l1_batch: Gauge<u64>,
+    /// Latency of a single check.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub check_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static CHECKER_METRICS: vise::Global<TreeConsistencyCheckerMetrics> =
+    vise::Global::new();
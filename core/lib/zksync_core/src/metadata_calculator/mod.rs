@@ -13,27 +13,35 @@ use zksync_config::configs::{
 };
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
-use zksync_merkle_tree::domain::TreeMetadata;
+use zksync_merkle_tree::{
+    domain::TreeMetadata, MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper,
+};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     block::L1BatchHeader,
-    commitment::{L1BatchCommitment, L1BatchMetadata},
+    commitment::{L1BatchCommitment, L1BatchCommitmentMode, L1BatchMetadata},
     H256,
 };
 
-pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
     helpers::{create_db, Delayer, GenericAsyncTree},
     metrics::{TreeUpdateStage, METRICS},
     updater::TreeUpdater,
 };
+pub(crate) use self::{
+    helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo},
+    tree_checker::TreeConsistencyChecker,
+};
 use crate::gas_tracker::commit_gas_count_for_l1_batch;
 
+mod commitments;
 mod helpers;
 mod metrics;
+mod pruning;
 mod recovery;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tree_checker;
 mod updater;
 
 /// Part of [`MetadataCalculator`] related to the operation mode of the Merkle tree.
@@ -70,6 +78,8 @@ pub struct MetadataCalculatorConfig<'a> {
     pub delay_interval: Duration,
     /// Maximum number of L1 batches to get from Postgres on a single update iteration.
     pub max_l1_batches_per_iter: usize,
+    /// Maximum number of L1 batches for which commitments are computed concurrently. Only used in the full tree mode.
+    pub commitment_generation_concurrency: usize,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     pub multi_get_chunk_size: usize,
@@ -80,6 +90,11 @@ pub struct MetadataCalculatorConfig<'a> {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// If set, old tree versions are pruned, retaining the specified number of past versions. Versions are only
+    /// pruned for L1 batches already pruned in Postgres by the [`DbPruner`](crate::db_pruner::DbPruner).
+    pub pruning_past_versions_to_keep: Option<u64>,
+    /// L1 batch commitment mode of the chain. Influences the commit gas estimated for L1 batches.
+    pub commitment_mode: L1BatchCommitmentMode,
 }
 
 impl<'a> MetadataCalculatorConfig<'a> {
@@ -87,16 +102,20 @@ impl<'a> MetadataCalculatorConfig<'a> {
         merkle_tree_config: &'a MerkleTreeConfig,
         operation_config: &'a OperationsManagerConfig,
         mode: MetadataCalculatorModeConfig<'a>,
+        commitment_mode: L1BatchCommitmentMode,
     ) -> Self {
         Self {
             db_path: &merkle_tree_config.path,
             mode,
             delay_interval: operation_config.delay_interval(),
             max_l1_batches_per_iter: merkle_tree_config.max_l1_batches_per_iter,
+            commitment_generation_concurrency: merkle_tree_config.commitment_generation_concurrency,
             multi_get_chunk_size: merkle_tree_config.multi_get_chunk_size,
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            pruning_past_versions_to_keep: None,
+            commitment_mode,
         }
     }
 }
@@ -109,6 +128,9 @@ pub struct MetadataCalculator {
     delayer: Delayer,
     health_updater: HealthUpdater,
     max_l1_batches_per_iter: usize,
+    commitment_generation_concurrency: usize,
+    commitment_mode: L1BatchCommitmentMode,
+    pruner: Option<(MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle)>,
 }
 
 impl MetadataCalculator {
@@ -118,6 +140,10 @@ impl MetadataCalculator {
             config.max_l1_batches_per_iter > 0,
             "Maximum L1 batches per iteration is misconfigured to be 0; please update it to positive value"
         );
+        assert!(
+            config.commitment_generation_concurrency > 0,
+            "Commitment generation concurrency is misconfigured to be 0; please update it to positive value"
+        );
 
         let mode = config.mode.to_mode();
         let object_store = match config.mode {
@@ -136,6 +162,9 @@ impl MetadataCalculator {
             config.multi_get_chunk_size,
         )
         .await;
+        let pruner = config
+            .pruning_past_versions_to_keep
+            .map(|past_versions_to_keep| MerkleTreePruner::new(db.clone(), past_versions_to_keep));
         let tree = GenericAsyncTree::new(db, mode).await;
 
         let (_, health_updater) = ReactiveHealthCheck::new("tree");
@@ -146,6 +175,9 @@ impl MetadataCalculator {
             delayer: Delayer::new(config.delay_interval),
             health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
+            commitment_generation_concurrency: config.commitment_generation_concurrency,
+            commitment_mode: config.commitment_mode,
+            pruner,
         }
     }
 
@@ -184,10 +216,26 @@ impl MetadataCalculator {
         };
         self.tree_reader.send_replace(Some(tree.reader()));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
-        updater
-            .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
-            .await
+        let updater = TreeUpdater::new(
+            tree,
+            self.max_l1_batches_per_iter,
+            self.commitment_generation_concurrency,
+            self.commitment_mode,
+            self.object_store,
+        );
+        let Some(pruner) = self.pruner else {
+            return updater
+                .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
+                .await;
+        };
+
+        let poll_interval = self.delayer.delay_interval();
+        let pruner_task =
+            pruning::run_tree_pruner(pruner, &pool, poll_interval, stop_receiver.clone());
+        let updater_task =
+            updater.loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater);
+        tokio::try_join!(updater_task, pruner_task)?;
+        Ok(())
     }
 
     /// This is used to improve L1 gas estimation for the commit operation. The estimations are computed
@@ -197,6 +245,7 @@ impl MetadataCalculator {
         storage: &mut StorageProcessor<'_>,
         header: &L1BatchHeader,
         metadata: &L1BatchMetadata,
+        commitment_mode: L1BatchCommitmentMode,
     ) {
         let estimate_latency = METRICS.start_stage(TreeUpdateStage::ReestimateGasCost);
         let unsorted_factory_deps = storage
@@ -204,8 +253,12 @@ impl MetadataCalculator {
             .get_l1_batch_factory_deps(header.number)
             .await
             .unwrap();
-        let commit_gas_cost =
-            commit_gas_count_for_l1_batch(header, &unsorted_factory_deps, metadata);
+        let commit_gas_cost = commit_gas_count_for_l1_batch(
+            header,
+            &unsorted_factory_deps,
+            metadata,
+            commitment_mode,
+        );
         storage
             .blocks_dal()
             .update_predicted_l1_batch_commit_gas(header.number, commit_gas_cost)
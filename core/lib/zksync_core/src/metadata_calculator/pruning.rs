@@ -0,0 +1,55 @@
+//! Merkle tree pruning coordinated with pruning of Postgres data.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper};
+
+/// Runs the Merkle tree pruner on a separate thread, periodically updating the tree version retained by it.
+/// Tree versions are only pruned for L1 batches already pruned in Postgres, so that the tree and Postgres
+/// data are available for the same range of L1 batches.
+pub(super) async fn run_tree_pruner(
+    (pruner, pruner_handle): (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle),
+    pool: &ConnectionPool,
+    poll_interval: Duration,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    // Set the limit before the pruner is started, so that it doesn't prune versions that should be retained.
+    update_retained_version_limit(&pruner_handle, pool).await?;
+    let pruner_task = tokio::task::spawn_blocking(|| pruner.run());
+
+    while !*stop_receiver.borrow_and_update() {
+        if tokio::time::timeout(poll_interval, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            break;
+        }
+        update_retained_version_limit(&pruner_handle, pool).await?;
+    }
+
+    tracing::info!("Stop signal received, Merkle tree pruner is shutting down");
+    pruner_handle.abort();
+    pruner_task.await.context("Merkle tree pruner panicked")
+}
+
+async fn update_retained_version_limit(
+    pruner_handle: &MerkleTreePrunerHandle,
+    pool: &ConnectionPool,
+) -> anyhow::Result<()> {
+    let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+    let pruning_info = storage
+        .pruning_dal()
+        .get_pruning_info()
+        .await
+        .context("failed getting Postgres pruning info")?;
+    // Tree version N corresponds to the state after L1 batch #N, so version N is the oldest version
+    // required for the oldest unpruned L1 batch #N.
+    let retained_version = pruning_info
+        .last_pruned_l1_batch
+        .map_or(0, |l1_batch| u64::from(l1_batch.0) + 1);
+    pruner_handle.set_retained_version_limit(retained_version);
+    Ok(())
+}
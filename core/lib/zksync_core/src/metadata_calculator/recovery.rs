@@ -14,7 +14,9 @@
 //! and feeding each chunk to the tree. Chunks are loaded concurrently since this is the most
 //! I/O-heavy operation; the concurrency is naturally limited by the number of connections to
 //! Postgres in the supplied connection pool, but we explicitly use a [`Semaphore`] to control it
-//! in order to not run into DB timeout errors. Before starting recovery in chunks, we filter out
+//! in order to not run into DB timeout errors. Chunks are applied to the tree one at a time, but applying
+//! each chunk is parallelized: entries are split into groups corresponding to disjoint subtrees, which are
+//! processed on separate threads and then merged. Before starting recovery in chunks, we filter out
 //! chunks that have already been recovered by checking if the first key in a chunk is present
 //! in the tree. (Note that for this to work, chunks **must** always be defined in the same way.)
 //!
@@ -16,6 +16,7 @@ use zksync_merkle_tree::domain::ZkSyncTree;
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     block::{BlockGasCount, L1BatchHeader, MiniblockHasher, MiniblockHeader},
+    commitment::{L1BatchCommitmentMode, L1BatchMetadata},
     proofs::PrepareBasicCircuitsJob,
     AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
     StorageKey, StorageLog, H256,
@@ -24,7 +25,7 @@ use zksync_utils::u32_to_h256;
 
 use super::{
     GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig,
-    MetadataCalculatorModeConfig,
+    MetadataCalculatorModeConfig, TreeConsistencyChecker,
 };
 use crate::genesis::{ensure_genesis_state, GenesisParams};
 
@@ -84,6 +85,47 @@ async fn basic_workflow() {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(2));
 }
 
+#[tokio::test]
+async fn tree_consistency_checker_basics() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 3).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let GenericAsyncTree::Ready(tree) = &calculator.tree else {
+        panic!("Unexpected tree state: {:?}", calculator.tree);
+    };
+    let (merkle_tree_config, _) = create_config(temp_dir.path());
+    let config = MerkleTreeConfig {
+        consistency_check_interval_ms: 10,
+        ..merkle_tree_config
+    };
+    let checker = TreeConsistencyChecker::new(pool.clone(), &config);
+    let health_check = checker.health_check();
+    assert_eq!(health_check.name(), "tree_consistency_checker");
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::NotReady
+    );
+
+    let (stop_sx, stop_rx) = watch::channel(false);
+    let checker_handle = tokio::spawn(checker.run(tree.reader(), stop_rx));
+    run_with_timeout(RUN_TIMEOUT, async {
+        while health_check.check_health().await.status() != HealthStatus::Ready {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+
+    stop_sx.send(true).unwrap();
+    run_with_timeout(RUN_TIMEOUT, checker_handle)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
 async fn expected_tree_hash(pool: &ConnectionPool) -> H256 {
     let mut storage = pool.access_storage().await.unwrap();
     let sealed_l1_batch_number = storage
@@ -185,6 +227,71 @@ async fn multi_l1_batch_workflow() {
     }
 }
 
+#[tokio::test]
+async fn multi_l1_batch_workflow_with_concurrent_commitments() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_calculator_with_commitment_concurrency(temp_dir.path(), &pool, 1).await;
+    reset_db_state(&pool, 10).await;
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+    let expected_metadata = load_l1_batches_metadata(&pool, 1..=10).await;
+
+    // Recompute metadata from scratch with commitments for multiple L1 batches computed concurrently.
+    // `save_l1_batch_metadata()` additionally checks that the new metadata matches the previously computed one.
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_calculator_with_commitment_concurrency(temp_dir.path(), &pool, 3).await;
+    let concurrent_root_hash = run_calculator(calculator, pool.clone()).await;
+    assert_eq!(concurrent_root_hash, root_hash);
+
+    let metadata = load_l1_batches_metadata(&pool, 1..=10).await;
+    for (actual, expected) in metadata.iter().zip(&expected_metadata) {
+        assert_eq!(actual.commitment, expected.commitment);
+        assert_eq!(
+            actual.bootloader_initial_content_commitment,
+            expected.bootloader_initial_content_commitment
+        );
+        assert_eq!(
+            actual.events_queue_commitment,
+            expected.events_queue_commitment
+        );
+    }
+}
+
+async fn setup_calculator_with_commitment_concurrency(
+    db_path: &Path,
+    pool: &ConnectionPool,
+    commitment_generation_concurrency: usize,
+) -> MetadataCalculator {
+    let (merkle_tree_config, operation_config) = create_config(db_path);
+    let merkle_tree_config = MerkleTreeConfig {
+        commitment_generation_concurrency,
+        ..merkle_tree_config
+    };
+    let store_factory = ObjectStoreFactory::mock();
+    let mode = MetadataCalculatorModeConfig::Full {
+        store_factory: Some(&store_factory),
+    };
+    setup_calculator_with_options(&merkle_tree_config, &operation_config, pool, mode).await
+}
+
+async fn load_l1_batches_metadata(
+    pool: &ConnectionPool,
+    l1_batch_numbers: ops::RangeInclusive<u32>,
+) -> Vec<L1BatchMetadata> {
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut all_metadata = vec![];
+    for l1_batch_number in l1_batch_numbers {
+        let metadata = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap()
+            .expect("no metadata for L1 batch");
+        all_metadata.push(metadata.metadata);
+    }
+    all_metadata
+}
+
 #[tokio::test]
 async fn running_metadata_calculator_with_additional_blocks() {
     let pool = ConnectionPool::test_pool().await;
@@ -399,8 +506,12 @@ async fn setup_calculator_with_options(
     pool: &ConnectionPool,
     mode: MetadataCalculatorModeConfig<'_>,
 ) -> MetadataCalculator {
-    let calculator_config =
-        MetadataCalculatorConfig::for_main_node(merkle_tree_config, operation_config, mode);
+    let calculator_config = MetadataCalculatorConfig::for_main_node(
+        merkle_tree_config,
+        operation_config,
+        mode,
+        L1BatchCommitmentMode::Rollup,
+    );
     let metadata_calculator = MetadataCalculator::new(&calculator_config).await;
 
     let mut storage = pool.access_storage().await.unwrap();
@@ -504,6 +615,7 @@ pub(super) async fn extend_db_state(
             base_fee_per_gas: header.base_fee_per_gas,
             l1_gas_price: 0,
             l2_fair_gas_price: 0,
+            gas_per_pubdata: None,
             base_system_contracts_hashes: base_system_contracts.hashes(),
             protocol_version: Some(ProtocolVersionId::latest()),
             virtual_blocks: 0,
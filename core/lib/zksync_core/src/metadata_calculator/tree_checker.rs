@@ -0,0 +1,208 @@
+//! Continuous consistency checks for the Merkle tree.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use rand::Rng;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::configs::database::MerkleTreeConfig;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_merkle_tree::{ConsistencyError, Key};
+use zksync_types::{L1BatchNumber, H256};
+
+use super::{
+    metrics::{TreeCheckOutcome, CHECKER_METRICS},
+    AsyncTreeReader,
+};
+
+/// Outcome of a single subtree check.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CheckResult {
+    Consistent,
+    /// The subtree is internally inconsistent (e.g., has a missing node or a hash mismatch).
+    InconsistentSubtree {
+        error: String,
+    },
+    /// The subtree is internally consistent, but the recomputed root hash doesn't match
+    /// the one persisted in Postgres.
+    RootHashMismatch {
+        expected: H256,
+        actual: H256,
+    },
+}
+
+/// Health details reported by [`TreeConsistencyChecker`].
+#[derive(Debug, Serialize)]
+struct TreeCheckerHealthDetails {
+    checked_l1_batch: L1BatchNumber,
+    checked_key: H256,
+    total_checks: u64,
+    /// First detected inconsistency, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inconsistency: Option<CheckResult>,
+}
+
+/// Component periodically verifying random subtrees of the Merkle tree. For each check, the checker
+/// selects a random L1 batch processed and retained (i.e., not pruned) by the tree and a random key, verifies consistency of the subtree
+/// at the configured depth containing the key, and compares the tree root hash recomputed from this subtree
+/// with the root hash of the L1 batch persisted in Postgres.
+///
+/// Once an inconsistency is detected, it is reported via the health check and metrics until
+/// the checker is restarted; the checker itself doesn't stop and doesn't affect other components.
+#[derive(Debug)]
+pub(crate) struct TreeConsistencyChecker {
+    pool: ConnectionPool,
+    check_interval: Duration,
+    subtree_depth: usize,
+    health_updater: HealthUpdater,
+}
+
+impl TreeConsistencyChecker {
+    pub fn new(pool: ConnectionPool, config: &MerkleTreeConfig) -> Self {
+        Self {
+            pool,
+            check_interval: config.consistency_check_interval(),
+            subtree_depth: config.consistency_check_subtree_depth,
+            health_updater: ReactiveHealthCheck::new("tree_consistency_checker").1,
+        }
+    }
+
+    /// Returns a health check for this checker.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Performs a single check. Returns `None` if the tree is empty.
+    async fn check_random_subtree(
+        &self,
+        tree_reader: &AsyncTreeReader,
+    ) -> anyhow::Result<Option<(L1BatchNumber, H256, CheckResult)>> {
+        let info = tree_reader.clone().info().await;
+        let Some(last_l1_batch) = info.next_l1_batch_number.0.checked_sub(1) else {
+            return Ok(None);
+        };
+        // Older tree versions may be pruned, so we only sample L1 batches retained by the tree.
+        let first_l1_batch = info.min_l1_batch_number.0.min(last_l1_batch);
+        let (l1_batch_number, key_bytes) = {
+            let mut rng = rand::thread_rng();
+            (
+                L1BatchNumber(rng.gen_range(first_l1_batch..=last_l1_batch)),
+                rng.gen::<[u8; 32]>(),
+            )
+        };
+        let key = Key::from_big_endian(&key_bytes);
+
+        let latency = CHECKER_METRICS.check_latency.start();
+        let check_result = tree_reader
+            .clone()
+            .verify_subtree_consistency(l1_batch_number, key, self.subtree_depth)
+            .await;
+        let result = match check_result {
+            Ok(root_hash) => self.compare_root_hash(l1_batch_number, root_hash).await?,
+            Err(ConsistencyError::MissingVersion(_)) => {
+                // The tree may have been truncated by a concurrent revert.
+                tracing::info!(
+                    "L1 batch #{l1_batch_number} is missing from the tree; skipping check"
+                );
+                return Ok(None);
+            }
+            Err(err) => CheckResult::InconsistentSubtree {
+                error: err.to_string(),
+            },
+        };
+        latency.observe();
+        Ok(Some((l1_batch_number, H256(key_bytes), result)))
+    }
+
+    async fn compare_root_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        root_hash: H256,
+    ) -> anyhow::Result<CheckResult> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("tree_consistency_checker")
+            .await?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting root hash for L1 batch #{l1_batch_number}"))?;
+        Ok(match expected_root_hash {
+            Some(expected) if expected != root_hash => CheckResult::RootHashMismatch {
+                expected,
+                actual: root_hash,
+            },
+            // If the root hash is not persisted yet, we can only check the internal tree consistency.
+            _ => CheckResult::Consistent,
+        })
+    }
+
+    pub async fn run(
+        self,
+        tree_reader: AsyncTreeReader,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting Merkle tree consistency checker with interval {:?} and subtree depth {}",
+            self.check_interval,
+            self.subtree_depth
+        );
+        CHECKER_METRICS.is_consistent.set(1);
+        let mut total_checks = 0_u64;
+        let mut inconsistency = None;
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+
+            if let Some((l1_batch_number, key, result)) =
+                self.check_random_subtree(&tree_reader).await?
+            {
+                total_checks += 1;
+                CHECKER_METRICS
+                    .last_checked_l1_batch
+                    .set(l1_batch_number.0.into());
+                if matches!(result, CheckResult::Consistent) {
+                    CHECKER_METRICS.checks[&TreeCheckOutcome::Consistent].inc();
+                    tracing::debug!(
+                        "Checked subtree for key {key:?} in L1 batch #{l1_batch_number}; no issues found"
+                    );
+                } else {
+                    CHECKER_METRICS.checks[&TreeCheckOutcome::Inconsistent].inc();
+                    CHECKER_METRICS.is_consistent.set(0);
+                    tracing::error!(
+                        "Merkle tree inconsistency detected for key {key:?} in L1 batch #{l1_batch_number}: {result:?}"
+                    );
+                    inconsistency.get_or_insert(result);
+                }
+
+                let status = if inconsistency.is_some() {
+                    HealthStatus::NotReady
+                } else {
+                    HealthStatus::Ready
+                };
+                let details = TreeCheckerHealthDetails {
+                    checked_l1_batch: l1_batch_number,
+                    checked_key: key,
+                    total_checks,
+                    inconsistency: inconsistency.clone(),
+                };
+                self.health_updater
+                    .update(Health::from(status).with_details(details));
+            }
+
+            if tokio::time::timeout(self.check_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree consistency checker is shutting down");
+        Ok(())
+    }
+}
@@ -5,15 +5,18 @@ use std::{ops, time::Instant};
 use anyhow::Context as _;
 use futures::{future, FutureExt};
 use tokio::sync::watch;
-use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queue_commitment};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::domain::TreeMetadata;
 use zksync_object_store::ObjectStore;
-use zksync_types::{block::L1BatchHeader, writes::InitialStorageWrite, L1BatchNumber, H256, U256};
+use zksync_types::{
+    block::L1BatchHeader, commitment::L1BatchCommitmentMode, writes::InitialStorageWrite,
+    L1BatchNumber, U256,
+};
 
 use super::{
+    commitments::{CommitmentsPipeline, L1BatchCommitments},
     helpers::{AsyncTree, Delayer, L1BatchWithLogs},
     metrics::{TreeUpdateStage, METRICS},
     MetadataCalculator,
@@ -24,6 +27,8 @@ use crate::utils::wait_for_l1_batch;
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
+    commitment_generation_concurrency: usize,
+    commitment_mode: L1BatchCommitmentMode,
     object_store: Option<Box<dyn ObjectStore>>,
 }
 
@@ -31,11 +36,15 @@ impl TreeUpdater {
     pub fn new(
         tree: AsyncTree,
         max_l1_batches_per_iter: usize,
+        commitment_generation_concurrency: usize,
+        commitment_mode: L1BatchCommitmentMode,
         object_store: Option<Box<dyn ObjectStore>>,
     ) -> Self {
         Self {
             tree,
             max_l1_batches_per_iter,
+            commitment_generation_concurrency,
+            commitment_mode,
             object_store,
         }
     }
@@ -82,6 +91,10 @@ impl TreeUpdater {
     /// the first L1 batch data beforehand.) This allows saving some time if we actually process
     /// multiple L1 batches at once (e.g., during the initial tree syncing), and if loading data from Postgres
     /// is slow for whatever reason.
+    ///
+    /// In the full tree mode, L1 batch commitments not depending on the tree are computed by
+    /// [`CommitmentsPipeline`] concurrently for multiple L1 batches and with updating the tree.
+    /// Metadata is still persisted in the L1 batch order.
     async fn process_multiple_batches(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -92,6 +105,13 @@ impl TreeUpdater {
         let first_l1_batch_number = L1BatchNumber(*l1_batch_numbers.start());
         let last_l1_batch_number = L1BatchNumber(*l1_batch_numbers.end());
         let mut l1_batch_data = L1BatchWithLogs::new(storage, first_l1_batch_number).await;
+        let mut commitments_pipeline = (self.tree.mode() == MerkleTreeMode::Full).then(|| {
+            CommitmentsPipeline::new(
+                first_l1_batch_number,
+                last_l1_batch_number,
+                self.commitment_generation_concurrency,
+            )
+        });
 
         let mut previous_root_hash = self.tree.root_hash();
         let mut total_logs = 0;
@@ -102,6 +122,9 @@ impl TreeUpdater {
                 return l1_batch_number;
             };
             total_logs += current_l1_batch_data.storage_logs.len();
+            if let Some(pipeline) = &mut commitments_pipeline {
+                pipeline.fill(storage).await;
+            }
 
             let process_l1_batch_task = self.process_l1_batch(current_l1_batch_data);
             let load_next_l1_batch_task = async {
@@ -123,25 +146,30 @@ impl TreeUpdater {
             .await;
             check_consistency_latency.observe();
 
-            let (events_queue_commitment, bootloader_initial_content_commitment) =
-                if self.tree.mode() == MerkleTreeMode::Full {
-                    self.calculate_commitments(storage, &header).await
-                } else {
-                    (None, None)
-                };
+            let commitments = if let Some(pipeline) = &mut commitments_pipeline {
+                pipeline.next(storage, l1_batch_number).await
+            } else {
+                L1BatchCommitments::default()
+            };
 
             let build_metadata_latency = METRICS.start_stage(TreeUpdateStage::BuildMetadata);
             let metadata = MetadataCalculator::build_l1_batch_metadata(
                 metadata,
                 &header,
-                events_queue_commitment,
-                bootloader_initial_content_commitment,
+                commitments.events_queue_commitment,
+                commitments.bootloader_initial_content_commitment,
             );
             build_metadata_latency.observe();
 
             let reestimate_gas_cost_latency =
                 METRICS.start_stage(TreeUpdateStage::ReestimateGasCost);
-            MetadataCalculator::reestimate_l1_batch_commit_gas(storage, &header, &metadata).await;
+            MetadataCalculator::reestimate_l1_batch_commit_gas(
+                storage,
+                &header,
+                &metadata,
+                self.commitment_mode,
+            )
+            .await;
             reestimate_gas_cost_latency.observe();
 
             let save_postgres_latency = METRICS.start_stage(TreeUpdateStage::SavePostgres);
@@ -191,49 +219,6 @@ impl TreeUpdater {
         last_l1_batch_number + 1
     }
 
-    async fn calculate_commitments(
-        &self,
-        conn: &mut StorageProcessor<'_>,
-        header: &L1BatchHeader,
-    ) -> (Option<H256>, Option<H256>) {
-        let events_queue_commitment_latency =
-            METRICS.start_stage(TreeUpdateStage::EventsCommitment);
-        let events_queue = conn
-            .blocks_dal()
-            .get_events_queue(header.number)
-            .await
-            .unwrap();
-
-        let is_pre_boojum = header
-            .protocol_version
-            .map(|v| v.is_pre_boojum())
-            .unwrap_or(true);
-        let events_queue_commitment = (!is_pre_boojum).then(|| {
-            let events_queue =
-                events_queue.expect("Events queue is required for post-boojum batch");
-            events_queue_commitment(&events_queue, is_pre_boojum)
-                .expect("Events queue commitment is required for post-boojum batch")
-        });
-        events_queue_commitment_latency.observe();
-
-        let bootloader_commitment_latency =
-            METRICS.start_stage(TreeUpdateStage::BootloaderCommitment);
-        let initial_bootloader_contents = conn
-            .blocks_dal()
-            .get_initial_bootloader_heap(header.number)
-            .await
-            .unwrap()
-            .unwrap();
-        let bootloader_initial_content_commitment =
-            bootloader_initial_content_commitment(&initial_bootloader_contents, is_pre_boojum);
-        bootloader_commitment_latency.observe();
-
-        (
-            events_queue_commitment,
-            bootloader_initial_content_commitment,
-        )
-    }
-
     async fn step(
         &mut self,
         mut storage: StorageProcessor<'_>,
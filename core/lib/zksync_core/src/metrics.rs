@@ -11,6 +11,7 @@ use zksync_types::aggregated_operations::AggregatedActionType;
 pub(crate) enum InitStage {
     HttpApi,
     WsApi,
+    ApiProfiles,
     ContractVerificationApi,
     StateKeeper,
     EthWatcher,
@@ -18,6 +19,7 @@ pub(crate) enum InitStage {
     EthTxManager,
     Tree,
     BasicWitnessInputProducer,
+    TeeVerifierInputProducer,
 }
 
 impl fmt::Display for InitStage {
@@ -25,6 +27,7 @@ impl fmt::Display for InitStage {
         match self {
             Self::HttpApi => formatter.write_str("http_api"),
             Self::WsApi => formatter.write_str("ws_api"),
+            Self::ApiProfiles => formatter.write_str("api_profiles"),
             Self::ContractVerificationApi => formatter.write_str("contract_verification_api"),
             Self::StateKeeper => formatter.write_str("state_keeper"),
             Self::EthWatcher => formatter.write_str("eth_watcher"),
@@ -32,6 +35,7 @@ impl fmt::Display for InitStage {
             Self::EthTxManager => formatter.write_str("eth_tx_manager"),
             Self::Tree => formatter.write_str("tree"),
             Self::BasicWitnessInputProducer => formatter.write_str("basic_witness_input_producer"),
+            Self::TeeVerifierInputProducer => formatter.write_str("tee_verifier_input_producer"),
         }
     }
 }
@@ -177,6 +181,9 @@ pub(crate) struct ExternalNodeMetrics {
     pub last_correct_batch: Family<CheckerComponent, Gauge<u64>>,
     /// Number of the last miniblock checked by the re-org detector or consistency checker.
     pub last_correct_miniblock: Family<CheckerComponent, Gauge<u64>>,
+    /// Number of the last L1 batch whose pubdata blob in the external DA layer was found inconsistent
+    /// with the local pubdata by the consistency checker.
+    pub inconsistent_da_blob_batch: Gauge<u64>,
 }
 
 #[vise::register]
@@ -0,0 +1,754 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::Bytes,
+    extract::Path,
+    http::{HeaderMap, Method, Uri},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use subtle::ConstantTimeEq;
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::{proof_generation_dal::L1BatchProofState, ConnectionPool, SqlxError};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    aggregated_operations::L1BatchProof,
+    proofs::PrepareBasicCircuitsJob,
+    prover_server_api::{
+        sign_external_prover_request, ExternalProverBatch, ExternalProverBatchesRequest,
+        ExternalProverBatchesResponse, ExternalProverWitnessInputs,
+        ExternalProverWitnessInputsResponse, SubmitProofRequest, SubmitProofResponse,
+        EXTERNAL_PROVER_ID_HEADER, EXTERNAL_PROVER_NONCE_HEADER, EXTERNAL_PROVER_SIGNATURE_HEADER,
+        EXTERNAL_PROVER_TIMESTAMP_HEADER,
+    },
+    web3::signing::keccak256,
+    L1BatchNumber, H256,
+};
+
+use crate::proof_data_handler::request_processor::{
+    check_aux_outputs, RequestProcessor, RequestProcessorError,
+};
+
+/// Maximum number of batches returned to external provers in a single response.
+const MAX_BATCHES_LIMIT: usize = 100;
+/// Maximum length of a request nonce.
+const MAX_NONCE_LEN: usize = 64;
+
+/// Parses external prover API keys in the `<prover_id>:<key>` format.
+pub(crate) fn parse_api_keys(keys: &[String]) -> anyhow::Result<HashMap<String, String>> {
+    let mut parsed = HashMap::with_capacity(keys.len());
+    for key in keys {
+        let (prover_id, api_key) = key.trim().split_once(':').ok_or_else(|| {
+            anyhow::anyhow!("external prover API key is not in the `<prover_id>:<key>` format")
+        })?;
+        anyhow::ensure!(
+            !prover_id.is_empty() && !api_key.is_empty(),
+            "external prover ID and API key must be non-empty"
+        );
+        let prev_key = parsed.insert(prover_id.to_owned(), api_key.to_owned());
+        anyhow::ensure!(
+            prev_key.is_none(),
+            "external prover `{prover_id}` has multiple API keys"
+        );
+    }
+    Ok(parsed)
+}
+
+/// Authenticated request made by an external prover.
+#[derive(Debug)]
+struct AuthenticatedRequest {
+    prover_id: String,
+    nonce: String,
+}
+
+/// Serves witness inputs to external (e.g., permissionless) provers and accepts proofs from them.
+///
+/// Each request must be signed with the API key of the prover (see [`sign_external_prover_request()`]).
+/// Replayed requests are rejected based on the request timestamp and nonce. Unlike internal provers,
+/// external ones do not lock L1 batches; instead, the first valid proof submitted for a batch is accepted,
+/// and resubmitting the same proof is a no-op.
+#[derive(Clone)]
+pub(crate) struct ExternalProverProcessor {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool,
+    config: ProofDataHandlerConfig,
+    api_keys: Arc<HashMap<String, String>>,
+    request_processor: RequestProcessor,
+}
+
+impl ExternalProverProcessor {
+    pub(crate) fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        pool: ConnectionPool,
+        config: ProofDataHandlerConfig,
+        api_keys: HashMap<String, String>,
+        request_processor: RequestProcessor,
+    ) -> Self {
+        Self {
+            blob_store,
+            pool,
+            config,
+            api_keys: Arc::new(api_keys),
+            request_processor,
+        }
+    }
+
+    fn authenticate(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: Duration,
+    ) -> Result<AuthenticatedRequest, RequestProcessorError> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| {
+                    RequestProcessorError::Unauthorized(format!(
+                        "missing or invalid `{name}` header"
+                    ))
+                })
+        };
+        let prover_id = header(EXTERNAL_PROVER_ID_HEADER)?;
+        let timestamp = header(EXTERNAL_PROVER_TIMESTAMP_HEADER)?;
+        let nonce = header(EXTERNAL_PROVER_NONCE_HEADER)?;
+        let signature = header(EXTERNAL_PROVER_SIGNATURE_HEADER)?;
+
+        let api_key = self.api_keys.get(prover_id).ok_or_else(|| {
+            RequestProcessorError::Unauthorized(format!("unknown external prover `{prover_id}`"))
+        })?;
+        let timestamp: u64 = timestamp.parse().map_err(|_| {
+            RequestProcessorError::Unauthorized("request timestamp is not an integer".to_owned())
+        })?;
+        let max_age = self.config.external_prover_request_max_age();
+        if now.as_secs().abs_diff(timestamp) > max_age.as_secs() {
+            return Err(RequestProcessorError::Unauthorized(format!(
+                "request timestamp {timestamp} is outside the allowed window of {max_age:?}"
+            )));
+        }
+        if nonce.is_empty() || nonce.len() > MAX_NONCE_LEN {
+            return Err(RequestProcessorError::Unauthorized(format!(
+                "request nonce must be non-empty and have at most {MAX_NONCE_LEN} chars"
+            )));
+        }
+
+        let signature = signature.strip_prefix("0x").unwrap_or(signature);
+        let signature = hex::decode(signature).map_err(|_| {
+            RequestProcessorError::Unauthorized("request signature is not hex-encoded".to_owned())
+        })?;
+        let expected_signature = sign_external_prover_request(
+            api_key,
+            method.as_str(),
+            uri.path(),
+            timestamp,
+            nonce,
+            body,
+        );
+        // Compare in constant time to not leak the expected signature via timing.
+        if !bool::from(signature.ct_eq(expected_signature.as_bytes())) {
+            return Err(RequestProcessorError::Unauthorized(
+                "invalid request signature".to_owned(),
+            ));
+        }
+        Ok(AuthenticatedRequest {
+            prover_id: prover_id.to_owned(),
+            nonce: nonce.to_owned(),
+        })
+    }
+
+    /// Authenticates the request and checks that it is not replayed.
+    async fn check_request(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<String, RequestProcessorError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("incorrect system time");
+        let request = self.authenticate(method, uri, headers, body, now)?;
+        // Requests are accepted while their timestamp is within `max_age` from the current time in either direction,
+        // so a nonce must be retained for `2 * max_age` to catch all replays.
+        let nonce_retention = self.config.external_prover_request_max_age() * 2;
+        let is_new_nonce = self
+            .pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .insert_external_prover_nonce(&request.prover_id, &request.nonce, nonce_retention)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if !is_new_nonce {
+            return Err(RequestProcessorError::Unauthorized(format!(
+                "nonce `{}` was already used",
+                request.nonce
+            )));
+        }
+        Ok(request.prover_id)
+    }
+
+    fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, RequestProcessorError> {
+        serde_json::from_slice(body).map_err(|err| {
+            RequestProcessorError::InvalidRequest(format!("malformed request body: {err}"))
+        })
+    }
+
+    pub(crate) async fn get_batches(
+        &self,
+        method: Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Json<ExternalProverBatchesResponse>, RequestProcessorError> {
+        let prover_id = self.check_request(&method, &uri, &headers, &body).await?;
+        let request: ExternalProverBatchesRequest = Self::parse_body(&body)?;
+        tracing::debug!(
+            "Received request for batches from external prover `{prover_id}`: {request:?}"
+        );
+
+        let limit = request
+            .limit
+            .unwrap_or(MAX_BATCHES_LIMIT)
+            .min(MAX_BATCHES_LIMIT);
+        let batches = self
+            .pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .get_unproven_batches(limit)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        let batches = batches
+            .into_iter()
+            .map(|(l1_batch_number, is_high_priority)| ExternalProverBatch {
+                l1_batch_number,
+                is_high_priority,
+            })
+            .collect();
+        Ok(Json(ExternalProverBatchesResponse::Success(batches)))
+    }
+
+    pub(crate) async fn get_witness_inputs(
+        &self,
+        Path(l1_batch_number): Path<u32>,
+        method: Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Json<ExternalProverWitnessInputsResponse>, RequestProcessorError> {
+        let prover_id = self.check_request(&method, &uri, &headers, &body).await?;
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        tracing::info!(
+            "Received request for witness inputs for L1 batch #{l1_batch_number} from external prover `{prover_id}`"
+        );
+
+        self.ensure_unproven(l1_batch_number).await?;
+        let ttl = self.config.external_prover_signed_url_ttl();
+        let witness_input_url = self
+            .blob_store
+            .signed_url::<PrepareBasicCircuitsJob>(l1_batch_number, ttl)
+            .await
+            .map_err(RequestProcessorError::ObjectStore)?
+            .ok_or_else(|| {
+                RequestProcessorError::NotSupported(
+                    "object store does not support signed URLs".to_owned(),
+                )
+            })?;
+
+        let (fri_protocol_version_id, l1_verifier_config) =
+            self.request_processor.protocol_version_params();
+        let inputs = ExternalProverWitnessInputs {
+            l1_batch_number,
+            witness_input_url,
+            url_expires_in_secs: ttl.as_secs(),
+            fri_protocol_version_id,
+            l1_verifier_config,
+        };
+        Ok(Json(ExternalProverWitnessInputsResponse::Success(
+            Box::new(inputs),
+        )))
+    }
+
+    async fn ensure_unproven(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), RequestProcessorError> {
+        let state = self
+            .pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .get_proof_state(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        match state {
+            None => Err(RequestProcessorError::Sqlx(SqlxError::RowNotFound)),
+            Some(L1BatchProofState::Pending) => Ok(()),
+            Some(L1BatchProofState::Proven { .. }) => Err(RequestProcessorError::Conflict(
+                format!("L1 batch #{l1_batch_number} is already proven"),
+            )),
+        }
+    }
+
+    pub(crate) async fn submit_proof(
+        &self,
+        Path(l1_batch_number): Path<u32>,
+        method: Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
+        let prover_id = self.check_request(&method, &uri, &headers, &body).await?;
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let request: SubmitProofRequest = Self::parse_body(&body)?;
+        // Hash the re-serialized request, so that the hash doesn't depend on the JSON formatting.
+        let proof_hash = H256(keccak256(
+            &serde_json::to_vec(&request).expect("failed serializing proof"),
+        ));
+        tracing::info!(
+            "Received proof {proof_hash:?} for L1 batch #{l1_batch_number} from external prover `{prover_id}`"
+        );
+
+        let proof = match request {
+            SubmitProofRequest::Proof(proof) => L1BatchProof::Plonk(*proof),
+            SubmitProofRequest::FflonkProof(proof) => L1BatchProof::Fflonk(*proof),
+            SubmitProofRequest::SkippedProofGeneration => {
+                return Err(RequestProcessorError::InvalidRequest(
+                    "external provers cannot skip proof generation".to_owned(),
+                ));
+            }
+        };
+
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let state = storage
+            .proof_generation_dal()
+            .get_proof_state(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?
+            .ok_or(RequestProcessorError::Sqlx(SqlxError::RowNotFound))?;
+        if let L1BatchProofState::Proven {
+            proof_hash: existing_hash,
+        } = state
+        {
+            return if existing_hash == Some(proof_hash) {
+                tracing::info!(
+                    "Proof {proof_hash:?} for L1 batch #{l1_batch_number} is already submitted"
+                );
+                Ok(Json(SubmitProofResponse::Success))
+            } else {
+                Err(RequestProcessorError::Conflict(format!(
+                    "L1 batch #{l1_batch_number} is already proven"
+                )))
+            };
+        }
+
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .ok_or(RequestProcessorError::Sqlx(SqlxError::RowNotFound))?;
+        check_aux_outputs(&l1_batch, &proof).map_err(RequestProcessorError::InvalidRequest)?;
+        drop(storage);
+
+        let blob_url = self
+            .blob_store
+            .put_l1_batch_proof(l1_batch_number, &proof)
+            .await
+            .map_err(RequestProcessorError::ObjectStore)?;
+        let is_saved = self
+            .pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .save_external_proof_artifacts_metadata(
+                l1_batch_number,
+                &blob_url,
+                proof_hash,
+                &prover_id,
+            )
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if !is_saved {
+            // Another proof was accepted concurrently.
+            return Err(RequestProcessorError::Conflict(format!(
+                "L1 batch #{l1_batch_number} is already proven"
+            )));
+        }
+        Ok(Json(SubmitProofResponse::Success))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use zksync_config::configs::proof_data_handler::{
+        ProofGenerationMode, ProtocolVersionLoadingMode,
+    };
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{
+        aggregated_operations::FflonkL1BatchProofForL1,
+        block::{BlockGasCount, L1BatchHeader},
+        commitment::{serialize_commitments, L1BatchWithMetadata},
+        l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log},
+        protocol_version::L1VerifierConfig,
+        Address, ProtocolVersion, ProtocolVersionId, U256,
+    };
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+    use crate::state_keeper::tests::create_l1_batch_metadata;
+
+    fn test_config() -> ProofDataHandlerConfig {
+        ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 18000,
+            protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
+            fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Provers,
+            tee_support: false,
+            tee_attestation_api_key: None,
+            prioritize_batch_api_key: None,
+            external_prover_api_keys: vec!["prover:secret".to_owned()],
+            external_prover_signed_url_ttl_in_secs: None,
+            external_prover_request_max_age_in_secs: Some(60),
+        }
+    }
+
+    async fn test_processor(pool: ConnectionPool) -> ExternalProverProcessor {
+        let config = test_config();
+        let blob_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        let api_keys = parse_api_keys(&config.external_prover_api_keys).unwrap();
+        let request_processor = RequestProcessor::new(
+            blob_store.clone(),
+            pool.clone(),
+            config.clone(),
+            Some(L1VerifierConfig::default()),
+        );
+        ExternalProverProcessor::new(blob_store, pool, config, api_keys, request_processor)
+    }
+
+    fn signed_headers(
+        api_key: &str,
+        path: &str,
+        timestamp: u64,
+        nonce: &str,
+        body: &[u8],
+    ) -> HeaderMap {
+        let signature = sign_external_prover_request(api_key, "POST", path, timestamp, nonce, body);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            EXTERNAL_PROVER_ID_HEADER,
+            HeaderValue::from_static("prover"),
+        );
+        headers.insert(
+            EXTERNAL_PROVER_TIMESTAMP_HEADER,
+            HeaderValue::from_str(&timestamp.to_string()).unwrap(),
+        );
+        headers.insert(
+            EXTERNAL_PROVER_NONCE_HEADER,
+            HeaderValue::from_str(nonce).unwrap(),
+        );
+        headers.insert(
+            EXTERNAL_PROVER_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("{signature:?}")).unwrap(),
+        );
+        headers
+    }
+
+    /// Signs a request at the current time with a unique nonce.
+    fn signed_request(path: &str, body: &[u8]) -> (Uri, HeaderMap) {
+        static NONCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+        let nonce = NONCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let headers = signed_headers("secret", path, now.as_secs(), &nonce.to_string(), body);
+        (path.parse().unwrap(), headers)
+    }
+
+    /// Inserts L1 batch #1 with metadata and makes it ready to be proven.
+    async fn prepare_batch_for_proving(pool: &ConnectionPool) -> L1BatchWithMetadata {
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        header.system_logs = vec![SystemL2ToL1Log(L2ToL1Log {
+            key: u256_to_h256(2.into()),
+            value: H256::repeat_byte(0x12),
+            ..L2ToL1Log::default()
+        })];
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .save_l1_batch_metadata(
+                L1BatchNumber(1),
+                &create_l1_batch_metadata(1),
+                H256::zero(),
+                false,
+            )
+            .await
+            .unwrap();
+        storage
+            .proof_generation_dal()
+            .insert_proof_generation_details(L1BatchNumber(1), "witness_inputs_1.bin")
+            .await;
+        storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no L1 batch metadata")
+    }
+
+    /// Creates a proof with auxiliary outputs matching the provided L1 batch.
+    fn create_proof(l1_batch: &L1BatchWithMetadata, proof_word: u64) -> SubmitProofRequest {
+        let system_logs_hash = keccak256(&serialize_commitments(&l1_batch.header.system_logs));
+        let state_diff_hash = l1_batch.header.system_logs[0].0.value;
+        let metadata = &l1_batch.metadata;
+        let aggregation_result_coords = [
+            system_logs_hash,
+            state_diff_hash.0,
+            metadata.bootloader_initial_content_commitment.unwrap().0,
+            metadata.events_queue_commitment.unwrap().0,
+        ];
+        SubmitProofRequest::FflonkProof(Box::new(FflonkL1BatchProofForL1 {
+            aggregation_result_coords,
+            proof: vec![U256::from(proof_word)],
+        }))
+    }
+
+    async fn submit_proof(
+        processor: &ExternalProverProcessor,
+        request: &SubmitProofRequest,
+    ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
+        let path = "/external_prover/submit_proof/1";
+        let body = serde_json::to_vec(request).unwrap();
+        let (uri, headers) = signed_request(path, &body);
+        processor
+            .submit_proof(Path(1), Method::POST, uri, headers, body.into())
+            .await
+    }
+
+    #[test]
+    fn parsing_api_keys() {
+        let keys = ["first:key1".to_owned(), " second:key:2 ".to_owned()];
+        let keys = parse_api_keys(&keys).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["first"], "key1");
+        assert_eq!(keys["second"], "key:2");
+
+        assert!(parse_api_keys(&["no_key".to_owned()]).is_err());
+        assert!(parse_api_keys(&[":key".to_owned()]).is_err());
+        assert!(parse_api_keys(&["id:1".to_owned(), "id:2".to_owned()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn authenticating_requests() {
+        let processor = test_processor(ConnectionPool::test_pool().await).await;
+        let method = Method::POST;
+        let uri: Uri = "/external_prover/batches".parse().unwrap();
+        let body = b"{}";
+        let now = Duration::from_secs(1_000);
+
+        let headers = signed_headers("secret", uri.path(), 1_000, "nonce", body);
+        let request = processor
+            .authenticate(&method, &uri, &headers, body, now)
+            .unwrap();
+        assert_eq!(request.prover_id, "prover");
+        assert_eq!(request.nonce, "nonce");
+
+        // Tampered body
+        let err = processor
+            .authenticate(&method, &uri, &headers, b"[]", now)
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+        // Another path
+        let other_uri: Uri = "/external_prover/submit_proof/1".parse().unwrap();
+        let err = processor
+            .authenticate(&method, &other_uri, &headers, body, now)
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+        // Wrong key
+        let headers = signed_headers("wrong", uri.path(), 1_000, "nonce", body);
+        let err = processor
+            .authenticate(&method, &uri, &headers, body, now)
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+        // Stale request
+        let headers = signed_headers("secret", uri.path(), 900, "nonce", body);
+        let err = processor
+            .authenticate(&method, &uri, &headers, body, now)
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn rejecting_replayed_requests() {
+        let processor = test_processor(ConnectionPool::test_pool().await).await;
+        let method = Method::POST;
+        let uri: Uri = "/external_prover/batches".parse().unwrap();
+        let body = b"{}";
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let headers = signed_headers("secret", uri.path(), now.as_secs(), "nonce", body);
+
+        let prover_id = processor
+            .check_request(&method, &uri, &headers, body)
+            .await
+            .unwrap();
+        assert_eq!(prover_id, "prover");
+        let err = processor
+            .check_request(&method, &uri, &headers, body)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn getting_witness_inputs() {
+        let pool = ConnectionPool::test_pool().await;
+        let processor = test_processor(pool.clone()).await;
+        let get_witness_inputs = |l1_batch_number: u32| {
+            let path = format!("/external_prover/witness_inputs/{l1_batch_number}");
+            let (uri, headers) = signed_request(&path, b"");
+            processor.get_witness_inputs(
+                Path(l1_batch_number),
+                Method::POST,
+                uri,
+                headers,
+                Bytes::new(),
+            )
+        };
+
+        let err = get_witness_inputs(1).await.unwrap_err();
+        assert!(matches!(
+            err,
+            RequestProcessorError::Sqlx(SqlxError::RowNotFound)
+        ));
+
+        prepare_batch_for_proving(&pool).await;
+        let Json(response) = get_witness_inputs(1).await.unwrap();
+        let ExternalProverWitnessInputsResponse::Success(inputs) = response else {
+            panic!("unexpected response: {response:?}");
+        };
+        assert_eq!(inputs.l1_batch_number, L1BatchNumber(1));
+        assert!(
+            inputs.witness_input_url.starts_with("mock://"),
+            "{}",
+            inputs.witness_input_url
+        );
+        assert_eq!(
+            inputs.url_expires_in_secs,
+            test_config().external_prover_signed_url_ttl().as_secs()
+        );
+
+        let is_saved = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .save_proof_artifacts_metadata(L1BatchNumber(1), "proof_1.bin")
+            .await
+            .unwrap();
+        assert!(is_saved);
+        let err = get_witness_inputs(1).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn submitting_proofs() {
+        let pool = ConnectionPool::test_pool().await;
+        let l1_batch = prepare_batch_for_proving(&pool).await;
+        let processor = test_processor(pool.clone()).await;
+
+        let proof = create_proof(&l1_batch, 1);
+        submit_proof(&processor, &proof).await.unwrap();
+        let state = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .get_proof_state(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            state,
+            Some(L1BatchProofState::Proven {
+                proof_hash: Some(_)
+            })
+        ));
+
+        // Resubmitting the same proof is a no-op.
+        submit_proof(&processor, &proof).await.unwrap();
+        // Another proof for the same batch is rejected.
+        let other_proof = create_proof(&l1_batch, 2);
+        let err = submit_proof(&processor, &other_proof).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Conflict(_)));
+        // ...as well as a proof submitted by an internal prover.
+        let err = processor
+            .request_processor
+            .submit_proof(Path(1), Json(other_proof))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Conflict(_)));
+
+        let new_state = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .get_proof_state(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(new_state, state);
+    }
+
+    #[tokio::test]
+    async fn rejecting_proof_with_invalid_aux_outputs() {
+        let pool = ConnectionPool::test_pool().await;
+        let l1_batch = prepare_batch_for_proving(&pool).await;
+        let processor = test_processor(pool.clone()).await;
+
+        let mut proof = create_proof(&l1_batch, 1);
+        let SubmitProofRequest::FflonkProof(fflonk_proof) = &mut proof else {
+            unreachable!();
+        };
+        fflonk_proof.aggregation_result_coords[1] = [0xff; 32];
+        let err = submit_proof(&processor, &proof).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::InvalidRequest(_)));
+
+        let state = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .get_proof_state(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(state, Some(L1BatchProofState::Pending));
+    }
+}
@@ -0,0 +1,52 @@
+//! Mock prover assigning mock proofs to L1 batches, so that chains without provers can reach the executed state.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Marks L1 batches that are ready to be proven as proven with a mock proof, in the order they are picked
+/// by provers. The L1 sender sends an empty proof for such batches, which is accepted by the testnet verifier.
+pub(crate) async fn run_mock_prover(
+    pool: ConnectionPool,
+    proof_generation_timeout: Duration,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    tracing::info!("Proof data handler is running in the mock proof generation mode");
+    while !*stop_receiver.borrow_and_update() {
+        let mut storage = pool.access_storage_tagged("proof_data_handler").await?;
+        let Some((l1_batch_number, _)) = storage
+            .proof_generation_dal()
+            .get_next_block_to_be_proven(proof_generation_timeout)
+            .await
+        else {
+            drop(storage);
+            if tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+            continue;
+        };
+
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .proof_generation_dal()
+            .mark_proof_generation_job_as_skipped(l1_batch_number)
+            .await
+            .with_context(|| format!("failed marking L1 batch #{l1_batch_number} as proven"))?;
+        transaction
+            .blocks_dal()
+            .set_skip_proof_for_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed marking L1 batch #{l1_batch_number} as proven"))?;
+        transaction.commit().await?;
+        tracing::info!("Assigned mock proof to L1 batch #{l1_batch_number}");
+    }
+    tracing::info!("Stop signal received, mock prover is shutting down");
+    Ok(())
+}
@@ -1,7 +1,13 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Context as _;
-use axum::{extract::Path, routing::post, Json, Router};
+use axum::{
+    body::Bytes,
+    extract::Path,
+    http::{HeaderMap, Method, Uri},
+    routing::post,
+    Json, Router,
+};
 use tokio::sync::watch;
 use zksync_config::{
     configs::{proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig},
@@ -11,13 +17,24 @@ use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     protocol_version::{L1VerifierConfig, VerifierParams},
-    prover_server_api::{ProofGenerationDataRequest, SubmitProofRequest},
+    prover_server_api::{
+        ProofGenerationDataRequest, RegisterTeeAttestationRequest, SubmitProofRequest,
+        SubmitTeeProofRequest, TeeProofGenerationDataRequest,
+    },
     H256,
 };
 
-use crate::proof_data_handler::request_processor::RequestProcessor;
+pub(crate) use self::mock_prover::run_mock_prover;
+use crate::proof_data_handler::{
+    external_prover_processor::{parse_api_keys, ExternalProverProcessor},
+    request_processor::RequestProcessor,
+    tee_request_processor::TeeRequestProcessor,
+};
 
+mod external_prover_processor;
+mod mock_prover;
 mod request_processor;
+mod tee_request_processor;
 
 fn fri_l1_verifier_config(contracts_config: &ContractsConfig) -> L1VerifierConfig {
     L1VerifierConfig {
@@ -44,10 +61,29 @@ pub(crate) async fn run_server(
         ProtocolVersionLoadingMode::FromDb => None,
         ProtocolVersionLoadingMode::FromEnvVar => Some(fri_l1_verifier_config(&contracts_config)),
     };
-    let get_proof_gen_processor =
-        RequestProcessor::new(blob_store, pool, config, l1_verifier_config);
+    let blob_store: Arc<dyn ObjectStore> = blob_store.into();
+    let tee_support = config.tee_support;
+    let external_prover_api_keys = parse_api_keys(&config.external_prover_api_keys)
+        .context("invalid external prover API keys")?;
+    let tee_processor = TeeRequestProcessor::new(blob_store.clone(), pool.clone(), config.clone());
+    let get_proof_gen_processor = RequestProcessor::new(
+        blob_store.clone(),
+        pool.clone(),
+        config.clone(),
+        l1_verifier_config,
+    );
+    let external_prover_processor = (!external_prover_api_keys.is_empty()).then(|| {
+        ExternalProverProcessor::new(
+            blob_store,
+            pool,
+            config,
+            external_prover_api_keys,
+            get_proof_gen_processor.clone(),
+        )
+    });
     let submit_proof_processor = get_proof_gen_processor.clone();
-    let app = Router::new()
+    let prioritize_batch_processor = get_proof_gen_processor.clone();
+    let mut app = Router::new()
         .route(
             "/proof_generation_data",
             post(
@@ -69,8 +105,102 @@ pub(crate) async fn run_server(
                         .await
                 },
             ),
+        )
+        .route(
+            // Marks the batch as high-priority, so that it's proven ahead of other pending batches.
+            "/prioritize_batch/:l1_batch_number",
+            post(
+                move |headers: HeaderMap, l1_batch_number: Path<u32>| async move {
+                    prioritize_batch_processor
+                        .prioritize_batch(headers, l1_batch_number)
+                        .await
+                },
+            ),
         );
 
+    if tee_support {
+        let get_tee_proof_gen_processor = tee_processor.clone();
+        let submit_tee_proof_processor = tee_processor.clone();
+        let register_tee_attestation_processor = tee_processor;
+        app = app
+            .route(
+                "/tee/proof_inputs",
+                post(
+                    move |payload: Json<TeeProofGenerationDataRequest>| async move {
+                        get_tee_proof_gen_processor.get_proof_inputs(payload).await
+                    },
+                ),
+            )
+            .route(
+                "/tee/submit_proofs/:l1_batch_number",
+                post(
+                    move |l1_batch_number: Path<u32>, payload: Json<SubmitTeeProofRequest>| async move {
+                        submit_tee_proof_processor
+                            .submit_proof(l1_batch_number, payload)
+                            .await
+                    },
+                ),
+            )
+            .route(
+                "/tee/register_attestation",
+                post(
+                    move |headers: HeaderMap,
+                          payload: Json<RegisterTeeAttestationRequest>| async move {
+                        register_tee_attestation_processor
+                            .register_attestation(headers, payload)
+                            .await
+                    },
+                ),
+            );
+    }
+
+    if let Some(processor) = external_prover_processor {
+        tracing::info!("Serving external prover API");
+        let get_batches_processor = processor.clone();
+        let get_witness_inputs_processor = processor.clone();
+        let submit_external_proof_processor = processor;
+        // Requests are signed over the raw body, so handlers parse the body themselves after authentication.
+        app = app
+            .route(
+                "/external_prover/batches",
+                post(
+                    move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| async move {
+                        get_batches_processor
+                            .get_batches(method, uri, headers, body)
+                            .await
+                    },
+                ),
+            )
+            .route(
+                "/external_prover/witness_inputs/:l1_batch_number",
+                post(
+                    move |l1_batch_number: Path<u32>,
+                          method: Method,
+                          uri: Uri,
+                          headers: HeaderMap,
+                          body: Bytes| async move {
+                        get_witness_inputs_processor
+                            .get_witness_inputs(l1_batch_number, method, uri, headers, body)
+                            .await
+                    },
+                ),
+            )
+            .route(
+                "/external_prover/submit_proof/:l1_batch_number",
+                post(
+                    move |l1_batch_number: Path<u32>,
+                          method: Method,
+                          uri: Uri,
+                          headers: HeaderMap,
+                          body: Bytes| async move {
+                        submit_external_proof_processor
+                            .submit_proof(l1_batch_number, method, uri, headers, body)
+                            .await
+                    },
+                ),
+            );
+    }
+
     axum::Server::bind(&bind_address)
         .serve(app.into_make_service())
         .with_graceful_shutdown(async move {
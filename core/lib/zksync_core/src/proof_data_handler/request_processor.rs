@@ -2,17 +2,20 @@ use std::{convert::TryFrom, sync::Arc};
 
 use axum::{
     extract::Path,
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use subtle::ConstantTimeEq;
 use zksync_config::configs::{
-    proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig,
+    proof_data_handler::{ProofGenerationMode, ProtocolVersionLoadingMode},
+    ProofDataHandlerConfig,
 };
-use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_dal::{proof_generation_dal::L1BatchProofState, ConnectionPool, SqlxError};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
-    commitment::serialize_commitments,
+    aggregated_operations::L1BatchProof,
+    commitment::{serialize_commitments, L1BatchWithMetadata},
     protocol_version::{FriProtocolVersionId, L1VerifierConfig},
     prover_server_api::{
         ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
@@ -31,9 +34,15 @@ pub(crate) struct RequestProcessor {
     l1_verifier_config: Option<L1VerifierConfig>,
 }
 
+#[derive(Debug)]
 pub(crate) enum RequestProcessorError {
     ObjectStore(ObjectStoreError),
     Sqlx(SqlxError),
+    InvalidRequest(String),
+    Unauthorized(String),
+    /// The request conflicts with the current state (e.g., the L1 batch is already proven).
+    Conflict(String),
+    NotSupported(String),
 }
 
 impl IntoResponse for RequestProcessorError {
@@ -58,6 +67,19 @@ impl IntoResponse for RequestProcessorError {
                     ),
                 }
             }
+            RequestProcessorError::InvalidRequest(message) => {
+                tracing::warn!("Invalid request: {message}");
+                (StatusCode::BAD_REQUEST, message)
+            }
+            RequestProcessorError::Unauthorized(message) => {
+                tracing::warn!("Unauthorized request: {message}");
+                (StatusCode::UNAUTHORIZED, message)
+            }
+            RequestProcessorError::Conflict(message) => (StatusCode::CONFLICT, message),
+            RequestProcessorError::NotSupported(message) => {
+                tracing::warn!("Unsupported request: {message}");
+                (StatusCode::NOT_IMPLEMENTED, message)
+            }
         };
         (status_code, message).into_response()
     }
@@ -65,24 +87,46 @@ impl IntoResponse for RequestProcessorError {
 
 impl RequestProcessor {
     pub(crate) fn new(
-        blob_store: Box<dyn ObjectStore>,
+        blob_store: Arc<dyn ObjectStore>,
         pool: ConnectionPool,
         config: ProofDataHandlerConfig,
         l1_verifier_config: Option<L1VerifierConfig>,
     ) -> Self {
         Self {
-            blob_store: Arc::from(blob_store),
+            blob_store,
             pool,
             config,
             l1_verifier_config,
         }
     }
 
+    /// Returns the FRI protocol version and L1 verifier config provers should use.
+    pub(crate) fn protocol_version_params(&self) -> (FriProtocolVersionId, L1VerifierConfig) {
+        let fri_protocol_version_id =
+            FriProtocolVersionId::try_from(self.config.fri_protocol_version_id)
+                .expect("Invalid FRI protocol version id");
+
+        let l1_verifier_config= match self.config.protocol_version_loading_mode {
+            ProtocolVersionLoadingMode::FromDb => {
+                panic!("Loading protocol version from db is not implemented yet")
+            }
+            ProtocolVersionLoadingMode::FromEnvVar => {
+                self.l1_verifier_config
+                    .expect("l1_verifier_config must be set while running ProtocolVersionLoadingMode::FromEnvVar mode")
+            }
+        };
+        (fri_protocol_version_id, l1_verifier_config)
+    }
+
     pub(crate) async fn get_proof_generation_data(
         &self,
         request: Json<ProofGenerationDataRequest>,
     ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
         tracing::info!("Received request for proof generation data: {:?}", request);
+        if self.config.proof_generation_mode == ProofGenerationMode::Mock {
+            tracing::warn!("Proof generation data is not served in the mock proof generation mode");
+            return Ok(Json(ProofGenerationDataResponse::Success(None)));
+        }
 
         let l1_batch_number_result = self
             .pool
@@ -93,8 +137,8 @@ impl RequestProcessor {
             .get_next_block_to_be_proven(self.config.proof_generation_timeout())
             .await;
 
-        let l1_batch_number = match l1_batch_number_result {
-            Some(number) => number,
+        let (l1_batch_number, is_high_priority) = match l1_batch_number_result {
+            Some(job) => job,
             None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
         };
 
@@ -104,25 +148,13 @@ impl RequestProcessor {
             .await
             .map_err(RequestProcessorError::ObjectStore)?;
 
-        let fri_protocol_version_id =
-            FriProtocolVersionId::try_from(self.config.fri_protocol_version_id)
-                .expect("Invalid FRI protocol version id");
-
-        let l1_verifier_config= match self.config.protocol_version_loading_mode {
-            ProtocolVersionLoadingMode::FromDb => {
-                panic!("Loading protocol version from db is not implemented yet")
-            }
-            ProtocolVersionLoadingMode::FromEnvVar => {
-                self.l1_verifier_config
-                    .expect("l1_verifier_config must be set while running ProtocolVersionLoadingMode::FromEnvVar mode")
-            }
-        };
-
+        let (fri_protocol_version_id, l1_verifier_config) = self.protocol_version_params();
         let proof_gen_data = ProofGenerationData {
             l1_batch_number,
             data: blob,
             fri_protocol_version_id,
             l1_verifier_config,
+            is_high_priority,
         };
 
         Ok(Json(ProofGenerationDataResponse::Success(Some(
@@ -130,6 +162,50 @@ impl RequestProcessor {
         ))))
     }
 
+    /// Checks that the request carries the configured batch prioritization API key as a bearer token.
+    fn authenticate_operator(&self, headers: &HeaderMap) -> Result<(), RequestProcessorError> {
+        let Some(api_key) = &self.config.prioritize_batch_api_key else {
+            return Err(RequestProcessorError::Unauthorized(
+                "L1 batch prioritization is disabled".to_owned(),
+            ));
+        };
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                RequestProcessorError::Unauthorized(
+                    "missing or invalid `Authorization` header".to_owned(),
+                )
+            })?;
+        if !bool::from(token.as_bytes().ct_eq(api_key.as_bytes())) {
+            return Err(RequestProcessorError::Unauthorized(
+                "invalid batch prioritization API key".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Marks the L1 batch as high-priority, so that it's served to provers ahead of other pending batches.
+    /// The priority is propagated to the prover DB when the batch is fetched by the prover gateway; batches
+    /// already fetched by the gateway should be prioritized using the prover queue API of the house keeper.
+    pub(crate) async fn prioritize_batch(
+        &self,
+        headers: HeaderMap,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<(), RequestProcessorError> {
+        self.authenticate_operator(&headers)?;
+        tracing::info!("Received request to prioritize proving of L1 batch #{l1_batch_number}");
+        self.pool
+            .access_storage()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .mark_proof_generation_job_as_high_priority(L1BatchNumber(l1_batch_number))
+            .await
+            .map_err(RequestProcessorError::Sqlx)
+    }
+
     pub(crate) async fn submit_proof(
         &self,
         Path(l1_batch_number): Path<u32>,
@@ -137,91 +213,64 @@ impl RequestProcessor {
     ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
         tracing::info!("Received proof for block number: {:?}", l1_batch_number);
         let l1_batch_number = L1BatchNumber(l1_batch_number);
-        match payload {
-            SubmitProofRequest::Proof(proof) => {
+        let proof = match payload {
+            SubmitProofRequest::Proof(proof) => Some(L1BatchProof::Plonk(*proof)),
+            SubmitProofRequest::FflonkProof(proof) => Some(L1BatchProof::Fflonk(*proof)),
+            SubmitProofRequest::SkippedProofGeneration => None,
+        };
+        match proof {
+            Some(proof) => {
+                tracing::info!(
+                    "Proof for L1 batch #{l1_batch_number} is compressed with the {:?} backend",
+                    proof.backend()
+                );
+                // Check the state before uploading the proof, so that a proof accepted from another prover
+                // (e.g., an external one) isn't overwritten in the object store.
+                let state = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .unwrap()
+                    .proof_generation_dal()
+                    .get_proof_state(l1_batch_number)
+                    .await
+                    .map_err(RequestProcessorError::Sqlx)?
+                    .ok_or(RequestProcessorError::Sqlx(SqlxError::RowNotFound))?;
+                if let L1BatchProofState::Proven { .. } = state {
+                    return Err(RequestProcessorError::Conflict(format!(
+                        "L1 batch #{l1_batch_number} is already proven"
+                    )));
+                }
+
                 let blob_url = self
                     .blob_store
-                    .put(l1_batch_number, &*proof)
+                    .put_l1_batch_proof(l1_batch_number, &proof)
                     .await
                     .map_err(RequestProcessorError::ObjectStore)?;
 
-                let system_logs_hash_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[0]);
-                let state_diff_hash_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[1]);
-                let bootloader_heap_initial_content_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[2]);
-                let events_queue_state_from_prover =
-                    H256::from_slice(&proof.aggregation_result_coords[3]);
-
                 let mut storage = self.pool.access_storage().await.unwrap();
-
                 let l1_batch = storage
                     .blocks_dal()
                     .get_l1_batch_metadata(l1_batch_number)
                     .await
                     .unwrap()
                     .expect("Proved block without metadata");
-
-                let is_pre_boojum = l1_batch
-                    .header
-                    .protocol_version
-                    .map(|v| v.is_pre_boojum())
-                    .unwrap_or(true);
-                if !is_pre_boojum {
-                    let events_queue_state = l1_batch
-                        .metadata
-                        .events_queue_commitment
-                        .expect("No events_queue_commitment");
-                    let bootloader_heap_initial_content = l1_batch
-                        .metadata
-                        .bootloader_initial_content_commitment
-                        .expect("No bootloader_initial_content_commitment");
-
-                    if events_queue_state != events_queue_state_from_prover
-                        || bootloader_heap_initial_content
-                            != bootloader_heap_initial_content_from_prover
-                    {
-                        let server_values = format!("events_queue_state = {events_queue_state}, bootloader_heap_initial_content = {bootloader_heap_initial_content}");
-                        let prover_values = format!("events_queue_state = {events_queue_state_from_prover}, bootloader_heap_initial_content = {bootloader_heap_initial_content_from_prover}");
-                        panic!(
-                            "Auxilary output doesn't match, server values: {} prover values: {}",
-                            server_values, prover_values
-                        );
-                    }
+                if let Err(err) = check_aux_outputs(&l1_batch, &proof) {
+                    panic!("{err}");
                 }
 
-                let system_logs = serialize_commitments(&l1_batch.header.system_logs);
-                let system_logs_hash = H256(keccak256(&system_logs));
-
-                if !is_pre_boojum {
-                    let state_diff_hash = l1_batch
-                        .header
-                        .system_logs
-                        .into_iter()
-                        .find(|elem| elem.0.key == u256_to_h256(2.into()))
-                        .expect("No state diff hash key")
-                        .0
-                        .value;
-
-                    if state_diff_hash != state_diff_hash_from_prover
-                        || system_logs_hash != system_logs_hash_from_prover
-                    {
-                        let server_values = format!("system_logs_hash = {system_logs_hash}, state_diff_hash = {state_diff_hash}");
-                        let prover_values = format!("system_logs_hash = {system_logs_hash_from_prover}, state_diff_hash = {state_diff_hash_from_prover}");
-                        panic!(
-                            "Auxilary output doesn't match, server values: {} prover values: {}",
-                            server_values, prover_values
-                        );
-                    }
-                }
-                storage
+                let is_saved = storage
                     .proof_generation_dal()
                     .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
                     .await
                     .map_err(RequestProcessorError::Sqlx)?;
+                if !is_saved {
+                    return Err(RequestProcessorError::Conflict(format!(
+                        "L1 batch #{l1_batch_number} is already proven"
+                    )));
+                }
             }
-            SubmitProofRequest::SkippedProofGeneration => {
+            None => {
                 self.pool
                     .access_storage()
                     .await
@@ -236,3 +285,230 @@ impl RequestProcessor {
         Ok(Json(SubmitProofResponse::Success))
     }
 }
+
+/// Checks that auxiliary outputs of the proof match the L1 batch metadata. Returns a description
+/// of the mismatch if they don't.
+pub(crate) fn check_aux_outputs(
+    l1_batch: &L1BatchWithMetadata,
+    proof: &L1BatchProof,
+) -> Result<(), String> {
+    let aggregation_result_coords = proof.aggregation_result_coords();
+    let system_logs_hash_from_prover = H256::from_slice(&aggregation_result_coords[0]);
+    let state_diff_hash_from_prover = H256::from_slice(&aggregation_result_coords[1]);
+    let bootloader_heap_initial_content_from_prover =
+        H256::from_slice(&aggregation_result_coords[2]);
+    let events_queue_state_from_prover = H256::from_slice(&aggregation_result_coords[3]);
+
+    let is_pre_boojum = l1_batch
+        .header
+        .protocol_version
+        .map(|v| v.is_pre_boojum())
+        .unwrap_or(true);
+    if is_pre_boojum {
+        return Ok(());
+    }
+
+    let events_queue_state = l1_batch
+        .metadata
+        .events_queue_commitment
+        .expect("No events_queue_commitment");
+    let bootloader_heap_initial_content = l1_batch
+        .metadata
+        .bootloader_initial_content_commitment
+        .expect("No bootloader_initial_content_commitment");
+    if events_queue_state != events_queue_state_from_prover
+        || bootloader_heap_initial_content != bootloader_heap_initial_content_from_prover
+    {
+        let server_values = format!("events_queue_state = {events_queue_state}, bootloader_heap_initial_content = {bootloader_heap_initial_content}");
+        let prover_values = format!("events_queue_state = {events_queue_state_from_prover}, bootloader_heap_initial_content = {bootloader_heap_initial_content_from_prover}");
+        return Err(format!(
+            "Auxilary output doesn't match, server values: {server_values} prover values: {prover_values}"
+        ));
+    }
+
+    let system_logs = serialize_commitments(&l1_batch.header.system_logs);
+    let system_logs_hash = H256(keccak256(&system_logs));
+    let state_diff_hash = l1_batch
+        .header
+        .system_logs
+        .iter()
+        .find(|elem| elem.0.key == u256_to_h256(2.into()))
+        .expect("No state diff hash key")
+        .0
+        .value;
+    if state_diff_hash != state_diff_hash_from_prover
+        || system_logs_hash != system_logs_hash_from_prover
+    {
+        let server_values =
+            format!("system_logs_hash = {system_logs_hash}, state_diff_hash = {state_diff_hash}");
+        let prover_values = format!("system_logs_hash = {system_logs_hash_from_prover}, state_diff_hash = {state_diff_hash_from_prover}");
+        return Err(format!(
+            "Auxilary output doesn't match, server values: {server_values} prover values: {prover_values}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        proofs::PrepareBasicCircuitsJob,
+        Address, ProtocolVersion, ProtocolVersionId,
+    };
+
+    use super::*;
+
+    fn test_config() -> ProofDataHandlerConfig {
+        ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 18000,
+            protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
+            fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Provers,
+            tee_support: false,
+            tee_attestation_api_key: None,
+            prioritize_batch_api_key: Some("secret".to_owned()),
+            external_prover_api_keys: vec![],
+            external_prover_signed_url_ttl_in_secs: None,
+            external_prover_request_max_age_in_secs: None,
+        }
+    }
+
+    fn bearer_headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    /// Inserts L1 batches with the specified numbers and makes them ready to be proven.
+    async fn prepare_batches_for_proving(
+        pool: &ConnectionPool,
+        blob_store: &dyn ObjectStore,
+        numbers: &[u32],
+    ) {
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for &number in numbers {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            storage
+                .blocks_dal()
+                .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+                .await
+                .unwrap();
+            let blob_url = blob_store
+                .put(L1BatchNumber(number), &PrepareBasicCircuitsJob::new(1))
+                .await
+                .unwrap();
+            storage
+                .proof_generation_dal()
+                .insert_proof_generation_details(L1BatchNumber(number), &blob_url)
+                .await;
+        }
+    }
+
+    async fn next_proven_batch(processor: &RequestProcessor) -> Option<(L1BatchNumber, bool)> {
+        let response = processor
+            .get_proof_generation_data(Json(ProofGenerationDataRequest {}))
+            .await
+            .unwrap();
+        match response.0 {
+            ProofGenerationDataResponse::Success(data) => {
+                data.map(|data| (data.l1_batch_number, data.is_high_priority))
+            }
+            ProofGenerationDataResponse::Error(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn prioritizing_batch_requires_api_key() {
+        let pool = ConnectionPool::test_pool().await;
+        let blob_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        prepare_batches_for_proving(&pool, &*blob_store, &[1]).await;
+        let mut processor = RequestProcessor::new(
+            blob_store,
+            pool,
+            test_config(),
+            Some(L1VerifierConfig::default()),
+        );
+
+        let err = processor
+            .prioritize_batch(HeaderMap::new(), Path(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+        let err = processor
+            .prioritize_batch(bearer_headers("wrong"), Path(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+
+        processor
+            .prioritize_batch(bearer_headers("secret"), Path(1))
+            .await
+            .unwrap();
+        let err = processor
+            .prioritize_batch(bearer_headers("secret"), Path(2))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RequestProcessorError::Sqlx(SqlxError::RowNotFound)
+        ));
+
+        processor.config.prioritize_batch_api_key = None;
+        let err = processor
+            .prioritize_batch(bearer_headers("secret"), Path(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn prioritized_batch_is_served_first() {
+        let pool = ConnectionPool::test_pool().await;
+        let blob_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        prepare_batches_for_proving(&pool, &*blob_store, &[1, 2, 3]).await;
+        let processor = RequestProcessor::new(
+            blob_store,
+            pool,
+            test_config(),
+            Some(L1VerifierConfig::default()),
+        );
+
+        processor
+            .prioritize_batch(bearer_headers("secret"), Path(3))
+            .await
+            .unwrap();
+        assert_eq!(
+            next_proven_batch(&processor).await,
+            Some((L1BatchNumber(3), true))
+        );
+        assert_eq!(
+            next_proven_batch(&processor).await,
+            Some((L1BatchNumber(1), false))
+        );
+        assert_eq!(
+            next_proven_batch(&processor).await,
+            Some((L1BatchNumber(2), false))
+        );
+        assert_eq!(next_proven_batch(&processor).await, None);
+    }
+}
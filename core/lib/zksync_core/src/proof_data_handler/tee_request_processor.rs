@@ -0,0 +1,376 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap},
+    Json,
+};
+use subtle::ConstantTimeEq;
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    prover_server_api::{
+        RegisterTeeAttestationRequest, RegisterTeeAttestationResponse, SubmitProofResponse,
+        SubmitTeeProofRequest, TeeProofGenerationDataRequest, TeeProofGenerationDataResponse,
+    },
+    tee_types::TeeVerifierInput,
+    L1BatchNumber,
+};
+
+use crate::proof_data_handler::request_processor::RequestProcessorError;
+
+/// Serves L1 batch inputs to TEE provers and accepts attested TEE proofs.
+#[derive(Clone)]
+pub(crate) struct TeeRequestProcessor {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool,
+    config: ProofDataHandlerConfig,
+}
+
+impl TeeRequestProcessor {
+    pub(crate) fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        pool: ConnectionPool,
+        config: ProofDataHandlerConfig,
+    ) -> Self {
+        Self {
+            blob_store,
+            pool,
+            config,
+        }
+    }
+
+    pub(crate) async fn get_proof_inputs(
+        &self,
+        request: Json<TeeProofGenerationDataRequest>,
+    ) -> Result<Json<TeeProofGenerationDataResponse>, RequestProcessorError> {
+        tracing::info!(
+            "Received request for TEE proof generation data: {:?}",
+            request
+        );
+
+        let l1_batch_number = self
+            .pool
+            .access_storage()
+            .await
+            .unwrap()
+            .tee_proof_generation_dal()
+            .get_next_block_to_be_proven(self.config.proof_generation_timeout())
+            .await;
+        let Some(l1_batch_number) = l1_batch_number else {
+            // no batches pending to be proven
+            return Ok(Json(TeeProofGenerationDataResponse::Success(None)));
+        };
+
+        let tee_verifier_input: TeeVerifierInput = self
+            .blob_store
+            .get(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::ObjectStore)?;
+
+        Ok(Json(TeeProofGenerationDataResponse::Success(Some(
+            Box::new(tee_verifier_input),
+        ))))
+    }
+
+    pub(crate) async fn submit_proof(
+        &self,
+        Path(l1_batch_number): Path<u32>,
+        Json(SubmitTeeProofRequest(proof)): Json<SubmitTeeProofRequest>,
+    ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        tracing::info!(
+            "Received {} TEE proof for L1 batch #{l1_batch_number}",
+            proof.tee_type
+        );
+
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let is_registered = storage
+            .tee_proof_generation_dal()
+            .is_attestation_registered(&proof.pubkey)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if !is_registered {
+            return Err(RequestProcessorError::InvalidRequest(format!(
+                "TEE public key 0x{} has no registered attestation",
+                hex::encode(&proof.pubkey)
+            )));
+        }
+
+        let root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?
+            .ok_or_else(|| {
+                RequestProcessorError::InvalidRequest(format!(
+                    "L1 batch #{l1_batch_number} has no root hash"
+                ))
+            })?;
+        proof.verify_signature(root_hash).map_err(|err| {
+            RequestProcessorError::InvalidRequest(format!(
+                "invalid TEE signature for L1 batch #{l1_batch_number}: {err}"
+            ))
+        })?;
+
+        storage
+            .tee_proof_generation_dal()
+            .save_proof_artifacts_metadata(
+                l1_batch_number,
+                &proof.signature,
+                &proof.pubkey,
+                &proof.proof,
+                proof.tee_type,
+            )
+            .await
+            .map_err(|err| match err {
+                SqlxError::RowNotFound => RequestProcessorError::Conflict(format!(
+                    "L1 batch #{l1_batch_number} is not picked by a TEE prover or is already proven"
+                )),
+                err => RequestProcessorError::Sqlx(err),
+            })?;
+        Ok(Json(SubmitProofResponse::Success))
+    }
+
+    /// Checks that the request carries the configured attestation API key as a bearer token.
+    fn authenticate_attestation(&self, headers: &HeaderMap) -> Result<(), RequestProcessorError> {
+        let Some(api_key) = &self.config.tee_attestation_api_key else {
+            return Err(RequestProcessorError::Unauthorized(
+                "TEE attestation registration is disabled".to_owned(),
+            ));
+        };
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                RequestProcessorError::Unauthorized(
+                    "missing or invalid `Authorization` header".to_owned(),
+                )
+            })?;
+        if !bool::from(token.as_bytes().ct_eq(api_key.as_bytes())) {
+            return Err(RequestProcessorError::Unauthorized(
+                "invalid TEE attestation API key".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn register_attestation(
+        &self,
+        headers: HeaderMap,
+        Json(payload): Json<RegisterTeeAttestationRequest>,
+    ) -> Result<Json<RegisterTeeAttestationResponse>, RequestProcessorError> {
+        self.authenticate_attestation(&headers)?;
+        tracing::info!(
+            "Received attestation for TEE public key 0x{}",
+            hex::encode(&payload.pubkey)
+        );
+        self.pool
+            .access_storage()
+            .await
+            .unwrap()
+            .tee_proof_generation_dal()
+            .save_attestation(&payload.pubkey, &payload.attestation)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        Ok(Json(RegisterTeeAttestationResponse::Success))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use zksync_config::configs::proof_data_handler::{
+        ProofGenerationMode, ProtocolVersionLoadingMode,
+    };
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        prover_server_api::L1BatchTeeProofForL1,
+        tee_types::TeeType,
+        Address, ProtocolVersion, ProtocolVersionId, H256,
+    };
+
+    use super::*;
+
+    fn test_config() -> ProofDataHandlerConfig {
+        ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 18000,
+            protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
+            fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Provers,
+            tee_support: true,
+            tee_attestation_api_key: Some("secret".to_owned()),
+            prioritize_batch_api_key: None,
+            external_prover_api_keys: vec![],
+            external_prover_signed_url_ttl_in_secs: None,
+            external_prover_request_max_age_in_secs: None,
+        }
+    }
+
+    async fn test_processor(pool: ConnectionPool) -> TeeRequestProcessor {
+        let blob_store: Arc<dyn ObjectStore> =
+            ObjectStoreFactory::mock().create_store().await.into();
+        TeeRequestProcessor::new(blob_store, pool, test_config())
+    }
+
+    fn bearer_headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    fn attestation_request(pubkey: &[u8]) -> Json<RegisterTeeAttestationRequest> {
+        Json(RegisterTeeAttestationRequest {
+            attestation: b"attestation".to_vec(),
+            pubkey: pubkey.to_vec(),
+        })
+    }
+
+    /// Inserts an L1 batch with the specified root hash and picks it for TEE proving.
+    async fn prepare_batch_for_tee_proving(pool: &ConnectionPool, root_hash: H256) {
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(1), root_hash)
+            .await
+            .unwrap();
+        storage
+            .tee_verifier_input_producer_dal()
+            .create_tee_verifier_input_producer_job(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let dal = &mut storage.tee_proof_generation_dal();
+        dal.insert_tee_proof_generation_details(L1BatchNumber(1))
+            .await;
+        let picked = dal
+            .get_next_block_to_be_proven(test_config().proof_generation_timeout())
+            .await;
+        assert_eq!(picked, Some(L1BatchNumber(1)));
+    }
+
+    #[tokio::test]
+    async fn registering_attestation_requires_api_key() {
+        let pool = ConnectionPool::test_pool().await;
+        let processor = test_processor(pool.clone()).await;
+        let pubkey = [1; 33];
+
+        let err = processor
+            .register_attestation(HeaderMap::new(), attestation_request(&pubkey))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+        let err = processor
+            .register_attestation(bearer_headers("wrong"), attestation_request(&pubkey))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let is_registered = storage
+            .tee_proof_generation_dal()
+            .is_attestation_registered(&pubkey)
+            .await
+            .unwrap();
+        assert!(!is_registered);
+
+        processor
+            .register_attestation(bearer_headers("secret"), attestation_request(&pubkey))
+            .await
+            .unwrap();
+        let is_registered = storage
+            .tee_proof_generation_dal()
+            .is_attestation_registered(&pubkey)
+            .await
+            .unwrap();
+        assert!(is_registered);
+    }
+
+    #[tokio::test]
+    async fn registering_attestation_is_disabled_without_api_key() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut processor = test_processor(pool).await;
+        processor.config.tee_attestation_api_key = None;
+
+        let err = processor
+            .register_attestation(bearer_headers("secret"), attestation_request(&[1; 33]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn submitting_tee_proof() {
+        let pool = ConnectionPool::test_pool().await;
+        let root_hash = H256::repeat_byte(0x23);
+        prepare_batch_for_tee_proving(&pool, root_hash).await;
+        let processor = test_processor(pool).await;
+
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key)
+            .serialize()
+            .to_vec();
+        let sign = |hash: H256| {
+            let message = secp256k1::Message::from_slice(hash.as_bytes()).unwrap();
+            secp.sign_ecdsa(&message, &secret_key)
+                .serialize_compact()
+                .to_vec()
+        };
+        let proof = L1BatchTeeProofForL1 {
+            signature: sign(root_hash),
+            pubkey: pubkey.clone(),
+            proof: vec![1, 2, 3],
+            tee_type: TeeType::Sgx,
+        };
+        let submit = |proof: &L1BatchTeeProofForL1| {
+            processor.submit_proof(
+                Path(1),
+                Json(SubmitTeeProofRequest(Box::new(proof.clone()))),
+            )
+        };
+
+        // The public key is not registered yet.
+        let err = submit(&proof).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::InvalidRequest(_)));
+        processor
+            .register_attestation(bearer_headers("secret"), attestation_request(&pubkey))
+            .await
+            .unwrap();
+
+        let invalid_proof = L1BatchTeeProofForL1 {
+            signature: sign(H256::repeat_byte(0x42)),
+            ..proof.clone()
+        };
+        let err = submit(&invalid_proof).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::InvalidRequest(_)));
+
+        submit(&proof).await.unwrap();
+        // The accepted proof must not be overwritten.
+        let err = submit(&proof).await.unwrap_err();
+        assert!(matches!(err, RequestProcessorError::Conflict(_)));
+    }
+}
@@ -6,15 +6,13 @@ use tokio::sync::watch;
 use zksync_dal::ConnectionPool;
 use zksync_types::{L1BatchNumber, MiniblockNumber, H256};
 use zksync_web3_decl::{
-    jsonrpsee::{
-        core::ClientError as RpcError,
-        http_client::{HttpClient, HttpClientBuilder},
-    },
+    jsonrpsee::{core::ClientError as RpcError, http_client::HttpClient},
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
+    sync_layer::FailoverMainNodeClient,
     utils::wait_for_l1_batch_with_metadata,
 };
 
@@ -70,6 +68,19 @@ impl MainNodeClient for HttpClient {
     }
 }
 
+#[async_trait]
+impl MainNodeClient for FailoverMainNodeClient {
+    async fn miniblock_hash(&self, number: MiniblockNumber) -> Result<Option<H256>, RpcError> {
+        self.call(|client| async move { client.miniblock_hash(number).await })
+            .await
+    }
+
+    async fn l1_batch_root_hash(&self, number: L1BatchNumber) -> Result<Option<H256>, RpcError> {
+        self.call(|client| async move { client.l1_batch_root_hash(number).await })
+            .await
+    }
+}
+
 trait UpdateCorrectBlock: fmt::Debug + Send + Sync {
     fn update_correct_block(
         &mut self,
@@ -104,7 +115,7 @@ impl UpdateCorrectBlock for () {
 /// and revert all batches after it, to keep being consistent with the main node.
 ///
 /// This is the only component that is expected to finish its execution
-/// in the even of re-org, since we have to restart the node after a rollback is performed,
+/// in the event of re-org, since node components have to be restarted after a rollback is performed,
 /// and is special-cased in the `zksync_external_node` crate.
 #[derive(Debug)]
 pub struct ReorgDetector {
@@ -118,10 +129,11 @@ pub struct ReorgDetector {
 impl ReorgDetector {
     const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 
-    pub fn new(url: &str, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) -> Self {
-        let client = HttpClientBuilder::default()
-            .build(url)
-            .expect("Failed to create HTTP client");
+    pub fn new(
+        client: FailoverMainNodeClient,
+        pool: ConnectionPool,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> Self {
         Self {
             client: Box::new(client),
             block_updater: Box::new(()),
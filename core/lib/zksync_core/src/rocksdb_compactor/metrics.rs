@@ -0,0 +1,36 @@
+//! RocksDB compactor metrics.
+
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics, Unit};
+
+use super::CompactedDb;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "trigger", rename_all = "snake_case")]
+pub(super) enum CompactionTrigger {
+    /// Compaction was triggered by the off-peak window scheduler.
+    Scheduled,
+    /// Compaction was triggered via the admin endpoint.
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct CompactionLabels {
+    pub db: CompactedDb,
+    pub trigger: CompactionTrigger,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_rocksdb_compactor")]
+pub(super) struct RocksdbCompactorMetrics {
+    /// Number of performed compactions of entire databases, grouped by the database and the trigger.
+    pub compactions: Family<CompactionLabels, Counter>,
+    /// Number of bytes reclaimed by the latest compaction of the database.
+    #[metrics(unit = Unit::Bytes)]
+    pub last_reclaimed: Family<CompactedDb, Gauge<u64>>,
+    /// Total number of bytes reclaimed by compactions of the database.
+    #[metrics(unit = Unit::Bytes)]
+    pub reclaimed: Family<CompactedDb, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<RocksdbCompactorMetrics> = vise::Global::new();
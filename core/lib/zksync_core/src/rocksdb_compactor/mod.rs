@@ -0,0 +1,530 @@
+//! Manual and scheduled compaction of RocksDB instances used by the node (the state keeper cache
+//! and the Merkle tree).
+//!
+//! RocksDB compacts data automatically in background, but auto-compaction can cause I/O and latency spikes
+//! during peak load. The compactor allows compacting databases during a daily off-peak window, or on demand
+//! via an admin HTTP endpoint bound to localhost.
+
+use std::{
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use futures::future;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{mpsc, oneshot, watch, OwnedMutexGuard},
+    task::JoinHandle,
+};
+use zksync_config::configs::database::RocksdbCompactionConfig;
+use zksync_state::RocksdbStorage;
+use zksync_storage::CompactionStats;
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::{CompactionLabels, CompactionTrigger, METRICS};
+use crate::metadata_calculator::AsyncTreeReader;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const SECONDS_PER_HOUR: u64 = 3_600;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// RocksDB instance that can be compacted.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    vise::EncodeLabelValue,
+    vise::EncodeLabelSet,
+)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "db", rename_all = "snake_case")]
+pub enum CompactedDb {
+    StateKeeper,
+    MerkleTree,
+}
+
+type CompactionResponder = oneshot::Sender<Vec<CompactionStats>>;
+
+/// Handle to request compaction of the state keeper cache.
+#[derive(Debug, Clone)]
+pub struct StateKeeperCompactionHandle(mpsc::Sender<CompactionResponder>);
+
+impl StateKeeperCompactionHandle {
+    /// Requests compaction and waits until it's complete. Returns `None` if the state keeper has stopped.
+    async fn compact(&self) -> Option<Vec<CompactionStats>> {
+        let (responder, response) = oneshot::channel();
+        self.0.send(responder).await.ok()?;
+        response.await.ok()
+    }
+}
+
+/// Compaction requests for the state keeper cache served by [`MainBatchExecutorBuilder`].
+///
+/// The state keeper opens its RocksDB cache anew for each L1 batch, and RocksDB cannot be opened
+/// by multiple handles at once, so compaction is started by the state keeper itself when starting an L1 batch.
+/// Compaction is performed in a background task concurrently with executing the batch; the state keeper
+/// only waits for it before opening the cache for the next batch.
+///
+/// [`MainBatchExecutorBuilder`]: crate::state_keeper::MainBatchExecutorBuilder
+#[derive(Debug, Clone)]
+pub struct StateKeeperCompactionRequests {
+    receiver: Arc<Mutex<mpsc::Receiver<CompactionResponder>>>,
+    pending_compaction: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl StateKeeperCompactionRequests {
+    /// Waits until the compaction started by [`Self::serve()`] (if any) is complete. Must be called
+    /// before opening the state keeper cache.
+    pub(crate) async fn wait_for_compaction(&self) {
+        let pending_compaction = self.pending_compaction.lock().await.take();
+        if let Some(task) = pending_compaction {
+            tracing::info!("Waiting for state keeper cache compaction to complete");
+            if let Err(err) = task.await {
+                tracing::error!("State keeper cache compaction failed: {err}");
+            }
+        }
+    }
+
+    /// Starts serving all pending compaction requests using the provided storage in a background task.
+    pub(crate) async fn serve(&self, storage: &RocksdbStorage) {
+        let responders: Vec<_> = {
+            let mut receiver = self
+                .receiver
+                .lock()
+                .expect("compaction requests are poisoned");
+            std::iter::from_fn(|| receiver.try_recv().ok()).collect()
+        };
+        if responders.is_empty() {
+            return;
+        }
+        self.wait_for_compaction().await;
+
+        tracing::info!("Compacting state keeper cache in background");
+        let storage = storage.read_only_handle();
+        let task = tokio::spawn(async move {
+            let stats = tokio::task::spawn_blocking(move || storage.compact())
+                .await
+                .expect("state keeper cache compaction panicked");
+            for responder in responders {
+                responder.send(stats.clone()).ok();
+            }
+        });
+        *self.pending_compaction.lock().await = Some(task);
+    }
+}
+
+/// Creates a channel for compacting the state keeper cache.
+pub fn state_keeper_compaction_channel(
+) -> (StateKeeperCompactionHandle, StateKeeperCompactionRequests) {
+    let (sender, receiver) = mpsc::channel(1);
+    let requests = StateKeeperCompactionRequests {
+        receiver: Arc::new(Mutex::new(receiver)),
+        pending_compaction: Arc::default(),
+    };
+    (StateKeeperCompactionHandle(sender), requests)
+}
+
+/// Daily off-peak window in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OffPeakWindow {
+    start_hour: u64,
+    end_hour: u64,
+}
+
+impl OffPeakWindow {
+    /// Returns `None` if scheduled compaction is disabled in the config.
+    fn new(config: &RocksdbCompactionConfig) -> anyhow::Result<Option<Self>> {
+        let (start_hour, end_hour) = (config.window_start_hour, config.window_end_hour);
+        anyhow::ensure!(
+            start_hour < 24 && end_hour < 24,
+            "Invalid off-peak window for RocksDB compaction: {start_hour}..{end_hour}"
+        );
+        Ok((start_hour != end_hour).then_some(Self {
+            start_hour: start_hour.into(),
+            end_hour: end_hour.into(),
+        }))
+    }
+
+    /// Returns the start timestamp of the window containing `timestamp`, or `None` if `timestamp`
+    /// is outside the window.
+    fn containing_window_start(&self, timestamp: u64) -> Option<u64> {
+        let day_start = timestamp - timestamp % SECONDS_PER_DAY;
+        let hour = (timestamp - day_start) / SECONDS_PER_HOUR;
+        let start_today = day_start + self.start_hour * SECONDS_PER_HOUR;
+
+        if self.start_hour < self.end_hour {
+            (self.start_hour..self.end_hour)
+                .contains(&hour)
+                .then_some(start_today)
+        } else if hour >= self.start_hour {
+            Some(start_today)
+        } else if hour < self.end_hour {
+            // The window has started on the previous day.
+            start_today.checked_sub(SECONDS_PER_DAY)
+        } else {
+            None
+        }
+    }
+}
+
+/// Result of compacting a single column family returned by the admin endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CompactionResult {
+    db: CompactedDb,
+    cf: String,
+    size_before: u64,
+    size_after: u64,
+    reclaimed_bytes: u64,
+    latency_ms: u64,
+}
+
+impl CompactionResult {
+    fn new(db: CompactedDb, stats: &CompactionStats) -> Self {
+        Self {
+            db,
+            cf: stats.cf_name.to_owned(),
+            size_before: stats.size_before,
+            size_after: stats.size_after,
+            reclaimed_bytes: stats.reclaimed_bytes(),
+            latency_ms: u64::try_from(stats.latency.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CompactionQuery {
+    /// Database to compact. If not specified, all databases are compacted.
+    db: Option<CompactedDb>,
+}
+
+/// Compaction status returned by the admin endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CompactionStatus {
+    /// Whether compaction is currently in progress.
+    in_progress: bool,
+    /// Databases compacted by the current or the latest compaction.
+    dbs: Vec<CompactedDb>,
+    /// Timestamp (in seconds since epoch) when the current or the latest compaction has started.
+    started_at: Option<u64>,
+    /// Timestamp (in seconds since epoch) when the latest compaction has finished.
+    finished_at: Option<u64>,
+    /// Results of the latest finished compaction.
+    results: Vec<CompactionResult>,
+}
+
+#[derive(Debug)]
+struct CompactionTargets {
+    state_keeper: Option<StateKeeperCompactionHandle>,
+    merkle_tree: Option<AsyncTreeReader>,
+    /// Ensures that scheduled and manual compactions do not run concurrently.
+    lock: Arc<tokio::sync::Mutex<()>>,
+    status: Mutex<CompactionStatus>,
+}
+
+impl CompactionTargets {
+    fn new(
+        state_keeper: Option<StateKeeperCompactionHandle>,
+        merkle_tree: Option<AsyncTreeReader>,
+    ) -> Self {
+        Self {
+            state_keeper,
+            merkle_tree,
+            lock: Arc::default(),
+            status: Mutex::default(),
+        }
+    }
+
+    fn dbs(&self) -> Vec<CompactedDb> {
+        let state_keeper = self.state_keeper.as_ref().map(|_| CompactedDb::StateKeeper);
+        let merkle_tree = self.merkle_tree.as_ref().map(|_| CompactedDb::MerkleTree);
+        state_keeper.into_iter().chain(merkle_tree).collect()
+    }
+
+    fn status(&self) -> CompactionStatus {
+        self.status
+            .lock()
+            .expect("compaction status is poisoned")
+            .clone()
+    }
+
+    fn mark_started(&self, dbs: &[CompactedDb]) {
+        let mut status = self.status.lock().expect("compaction status is poisoned");
+        status.in_progress = true;
+        status.dbs = dbs.to_vec();
+        status.started_at = Some(seconds_since_epoch());
+    }
+
+    /// Waits until other compactions are finished and marks compaction of `dbs` as started.
+    async fn start(&self, dbs: &[CompactedDb]) -> OwnedMutexGuard<()> {
+        let guard = self.lock.clone().lock_owned().await;
+        self.mark_started(dbs);
+        guard
+    }
+
+    /// Marks compaction of `dbs` as started. Returns `None` if another compaction is in progress.
+    fn try_start(&self, dbs: &[CompactedDb]) -> Option<OwnedMutexGuard<()>> {
+        let guard = self.lock.clone().try_lock_owned().ok()?;
+        self.mark_started(dbs);
+        Some(guard)
+    }
+
+    /// Compacts `dbs` after compaction was started using [`Self::start()`] or [`Self::try_start()`].
+    async fn compact_dbs(
+        &self,
+        _guard: OwnedMutexGuard<()>,
+        dbs: &[CompactedDb],
+        trigger: CompactionTrigger,
+    ) {
+        let mut results = vec![];
+        for &db in dbs {
+            if let Some(db_results) = self.compact(db, trigger).await {
+                results.extend(db_results);
+            } else {
+                tracing::warn!("{db:?} RocksDB is no longer available for compaction");
+            }
+        }
+
+        let mut status = self.status.lock().expect("compaction status is poisoned");
+        status.in_progress = false;
+        status.finished_at = Some(seconds_since_epoch());
+        status.results = results;
+    }
+
+    /// Returns `None` if the database is not available.
+    async fn compact(
+        &self,
+        db: CompactedDb,
+        trigger: CompactionTrigger,
+    ) -> Option<Vec<CompactionResult>> {
+        tracing::info!("Starting {trigger:?} compaction of {db:?} RocksDB");
+        let stats = match db {
+            CompactedDb::StateKeeper => self.state_keeper.as_ref()?.compact().await?,
+            CompactedDb::MerkleTree => self.merkle_tree.clone()?.compact_db().await,
+        };
+
+        let results: Vec<_> = stats
+            .iter()
+            .map(|stats| CompactionResult::new(db, stats))
+            .collect();
+        let reclaimed_bytes = results.iter().map(|result| result.reclaimed_bytes).sum();
+        METRICS.compactions[&CompactionLabels { db, trigger }].inc();
+        METRICS.last_reclaimed[&db].set(reclaimed_bytes);
+        METRICS.reclaimed[&db].inc_by(reclaimed_bytes);
+        tracing::info!(
+            "Finished {trigger:?} compaction of {db:?} RocksDB; reclaimed {reclaimed_bytes}B"
+        );
+        Some(results)
+    }
+
+    async fn compact_all(&self, trigger: CompactionTrigger) {
+        let dbs = self.dbs();
+        let guard = self.start(&dbs).await;
+        self.compact_dbs(guard, &dbs, trigger).await;
+    }
+
+    async fn compact_handler(
+        State(this): State<Arc<Self>>,
+        Query(query): Query<CompactionQuery>,
+    ) -> Result<(StatusCode, Json<CompactionStatus>), (StatusCode, String)> {
+        let dbs = if let Some(db) = query.db {
+            if !this.dbs().contains(&db) {
+                let message = format!("{db:?} RocksDB is not available on this node");
+                return Err((StatusCode::NOT_FOUND, message));
+            }
+            vec![db]
+        } else {
+            this.dbs()
+        };
+        let guard = this.try_start(&dbs).ok_or_else(|| {
+            let message = "RocksDB compaction is already in progress".to_owned();
+            (StatusCode::CONFLICT, message)
+        })?;
+        let status = this.status();
+        tokio::spawn(async move {
+            this.compact_dbs(guard, &dbs, CompactionTrigger::Manual)
+                .await;
+        });
+        Ok((StatusCode::ACCEPTED, Json(status)))
+    }
+
+    async fn status_handler(State(this): State<Arc<Self>>) -> Json<CompactionStatus> {
+        Json(this.status())
+    }
+}
+
+/// Component compacting RocksDB instances of the node once per daily off-peak window, and exposing
+/// an admin HTTP endpoint on localhost to trigger compaction manually: `POST /compact`, optionally with a `db`
+/// query param set to `state_keeper` or `merkle_tree`. The endpoint starts compaction in background and responds
+/// immediately; compaction status can be polled via `GET /compact`. The state keeper cache is compacted
+/// once the state keeper starts the next L1 batch.
+pub struct RocksdbCompactor {
+    config: RocksdbCompactionConfig,
+    window: Option<OffPeakWindow>,
+    state_keeper: Option<StateKeeperCompactionHandle>,
+    merkle_tree: Option<Pin<Box<dyn Future<Output = AsyncTreeReader> + Send>>>,
+}
+
+impl fmt::Debug for RocksdbCompactor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RocksdbCompactor")
+            .field("config", &self.config)
+            .field("window", &self.window)
+            .field("state_keeper", &self.state_keeper)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RocksdbCompactor {
+    pub fn new(config: RocksdbCompactionConfig) -> anyhow::Result<Self> {
+        let window = OffPeakWindow::new(&config)?;
+        anyhow::ensure!(
+            window.is_some() || config.admin_port.is_some(),
+            "RocksDB compactor requires either a non-empty off-peak window or an admin port"
+        );
+        Ok(Self {
+            config,
+            window,
+            state_keeper: None,
+            merkle_tree: None,
+        })
+    }
+
+    /// Enables compaction of the state keeper cache.
+    pub fn set_state_keeper(&mut self, handle: StateKeeperCompactionHandle) -> &mut Self {
+        self.state_keeper = Some(handle);
+        self
+    }
+
+    /// Enables compaction of the Merkle tree. Compaction will start after the tree is initialized.
+    pub(crate) fn set_merkle_tree(
+        &mut self,
+        tree_reader: impl Future<Output = AsyncTreeReader> + Send + 'static,
+    ) -> &mut Self {
+        self.merkle_tree = Some(Box::pin(tree_reader));
+        self
+    }
+
+    async fn run_scheduler(
+        window: OffPeakWindow,
+        check_interval: Duration,
+        targets: Arc<CompactionTargets>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut last_compacted_window = None;
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+
+            let window_start = window.containing_window_start(seconds_since_epoch());
+            if window_start.is_some() && window_start != last_compacted_window {
+                targets.compact_all(CompactionTrigger::Scheduled).await;
+                last_compacted_window = window_start;
+            }
+
+            if tokio::time::timeout(check_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, RocksDB compactor is shutting down");
+        Ok(())
+    }
+
+    async fn run_admin_server(
+        port: u16,
+        targets: Arc<CompactionTargets>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        // The endpoint is unauthenticated, so it's only exposed on localhost.
+        let bind_address = SocketAddr::from(([127, 0, 0, 1], port));
+        tracing::debug!("Starting RocksDB compaction admin server on {bind_address}");
+        let app = Router::new()
+            .route(
+                "/compact",
+                post(CompactionTargets::compact_handler).get(CompactionTargets::status_handler),
+            )
+            .with_state(targets);
+
+        axum::Server::try_bind(&bind_address)
+            .with_context(|| {
+                format!("Failed binding RocksDB compaction admin server to {bind_address}")
+            })?
+            .serve(app.into_make_service())
+            .with_graceful_shutdown(async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!("Stop signal sender for RocksDB compaction admin server was dropped without sending a signal");
+                }
+                tracing::info!("Stop signal received, RocksDB compaction admin server is shutting down");
+            })
+            .await
+            .context("RocksDB compaction admin server failed")?;
+        tracing::info!("RocksDB compaction admin server shut down");
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let merkle_tree = if let Some(tree_reader) = self.merkle_tree {
+            let stop = Box::pin(stop_receiver.changed());
+            match future::select(tree_reader, stop).await {
+                future::Either::Left((tree_reader, _)) => Some(tree_reader),
+                future::Either::Right(_) => {
+                    tracing::info!(
+                        "Stop signal received before Merkle tree was initialized, RocksDB compactor is shutting down"
+                    );
+                    return Ok(());
+                }
+            }
+        } else {
+            None
+        };
+        let targets = Arc::new(CompactionTargets::new(self.state_keeper, merkle_tree));
+        tracing::info!(
+            "Starting RocksDB compactor for {:?} with off-peak window {:?}",
+            targets.dbs(),
+            self.window
+        );
+
+        let scheduler = if let Some(window) = self.window {
+            let scheduler = Self::run_scheduler(
+                window,
+                self.config.check_interval(),
+                targets.clone(),
+                stop_receiver.clone(),
+            );
+            future::Either::Left(scheduler)
+        } else {
+            future::Either::Right(async { Ok(()) })
+        };
+        let admin_server = if let Some(port) = self.config.admin_port {
+            let server = Self::run_admin_server(port, targets, stop_receiver);
+            future::Either::Left(server)
+        } else {
+            future::Either::Right(async { Ok(()) })
+        };
+        future::try_join(scheduler, admin_server).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Tests for the RocksDB compactor.
+
+use tempfile::TempDir;
+
+use super::*;
+
+fn off_peak_window(start_hour: u32, end_hour: u32) -> Option<OffPeakWindow> {
+    let config = RocksdbCompactionConfig {
+        window_start_hour: start_hour,
+        window_end_hour: end_hour,
+        ..RocksdbCompactionConfig::default()
+    };
+    OffPeakWindow::new(&config).unwrap()
+}
+
+#[test]
+fn off_peak_window_computation() {
+    const DAY_START: u64 = 1_700_006_400; // 2023-11-15 00:00:00 UTC
+
+    let window = off_peak_window(2, 5).unwrap();
+    assert_eq!(window.containing_window_start(DAY_START), None);
+    assert_eq!(
+        window.containing_window_start(DAY_START + 2 * SECONDS_PER_HOUR),
+        Some(DAY_START + 2 * SECONDS_PER_HOUR)
+    );
+    assert_eq!(
+        window.containing_window_start(DAY_START + 5 * SECONDS_PER_HOUR - 1),
+        Some(DAY_START + 2 * SECONDS_PER_HOUR)
+    );
+    assert_eq!(
+        window.containing_window_start(DAY_START + 5 * SECONDS_PER_HOUR),
+        None
+    );
+
+    let window = off_peak_window(22, 3).unwrap();
+    let window_start = DAY_START + 22 * SECONDS_PER_HOUR;
+    assert_eq!(
+        window.containing_window_start(DAY_START + 12 * SECONDS_PER_HOUR),
+        None
+    );
+    assert_eq!(
+        window.containing_window_start(window_start + 1),
+        Some(window_start)
+    );
+    // Same window on the next day.
+    assert_eq!(
+        window.containing_window_start(DAY_START + SECONDS_PER_DAY + 2 * SECONDS_PER_HOUR),
+        Some(window_start)
+    );
+    assert_eq!(
+        window.containing_window_start(DAY_START + SECONDS_PER_DAY + 3 * SECONDS_PER_HOUR),
+        None
+    );
+}
+
+#[test]
+fn off_peak_window_validation() {
+    assert_eq!(off_peak_window(3, 3), None);
+    let config = RocksdbCompactionConfig {
+        window_start_hour: 24,
+        ..RocksdbCompactionConfig::default()
+    };
+    OffPeakWindow::new(&config).unwrap_err();
+}
+
+#[tokio::test]
+async fn compacting_state_keeper_cache() {
+    let temp_dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+    let storage = RocksdbStorage::new(temp_dir.path());
+    let (handle, requests) = state_keeper_compaction_channel();
+
+    // Serving requests is a no-op if there are no requests.
+    requests.serve(&storage).await;
+
+    let compaction = tokio::spawn(async move { handle.compact().await });
+    while !compaction.is_finished() {
+        requests.serve(&storage).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let stats = compaction.await.unwrap().expect("state keeper has stopped");
+    assert!(!stats.is_empty());
+
+    // The cache can be reopened after the background compaction is complete.
+    drop(storage);
+    requests.wait_for_compaction().await;
+    assert!(requests.pending_compaction.lock().await.is_none());
+    RocksdbStorage::new(temp_dir.path());
+}
+
+#[tokio::test]
+async fn compaction_after_state_keeper_is_stopped() {
+    let (handle, requests) = state_keeper_compaction_channel();
+    drop(requests);
+    assert!(handle.compact().await.is_none());
+}
+
+#[tokio::test]
+async fn admin_endpoint_with_missing_db() {
+    let targets = Arc::new(CompactionTargets::new(None, None));
+    let query = CompactionQuery {
+        db: Some(CompactedDb::MerkleTree),
+    };
+    let (status, _) = CompactionTargets::compact_handler(State(targets.clone()), Query(query))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let query = CompactionQuery { db: None };
+    let (status, Json(response)) =
+        CompactionTargets::compact_handler(State(targets.clone()), Query(query))
+            .await
+            .unwrap();
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert!(response.in_progress);
+    assert!(response.dbs.is_empty());
+
+    loop {
+        let Json(status) = CompactionTargets::status_handler(State(targets.clone())).await;
+        if !status.in_progress {
+            assert!(status.finished_at.is_some());
+            assert!(status.results.is_empty());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn admin_endpoint_rejects_concurrent_compaction() {
+    let targets = Arc::new(CompactionTargets::new(None, None));
+    let guard = targets.start(&[]).await;
+
+    let query = CompactionQuery { db: None };
+    let (status, _) = CompactionTargets::compact_handler(State(targets.clone()), Query(query))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+    let Json(status) = CompactionTargets::status_handler(State(targets.clone())).await;
+    assert!(status.in_progress);
+
+    targets
+        .compact_dbs(guard, &[], CompactionTrigger::Manual)
+        .await;
+    let Json(status) = CompactionTargets::status_handler(State(targets)).await;
+    assert!(!status.in_progress);
+}
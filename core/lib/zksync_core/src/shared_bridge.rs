@@ -0,0 +1,153 @@
+//! Shared bridge support.
+//!
+//! With the shared bridge, chains (hyperchains) are registered in a single `Bridgehub` contract on L1. Each chain
+//! has its own diamond proxy with its own priority queue, and the validator timelock is called with executor functions
+//! that take the chain ID as the first argument. [`ChainL1Contracts`] identify L1 contracts of a single chain;
+//! L1 components (the Ethereum watcher and the L1 sender) are parameterized by them, so that a single node process
+//! can run these components for several chains registered in the same bridgehub.
+
+use anyhow::Context as _;
+use once_cell::sync::Lazy;
+use zksync_config::ContractsConfig;
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    ethabi::{Contract, Function, Param, ParamType},
+    Address, L2ChainId, U256,
+};
+
+/// ABI of the `Bridgehub` functions used by the node.
+static BRIDGEHUB_CONTRACT: Lazy<Contract> = Lazy::new(|| {
+    let abi = r#"[{
+        "type": "function",
+        "name": "getHyperchain",
+        "inputs": [{ "name": "_chainId", "type": "uint256" }],
+        "outputs": [{ "name": "", "type": "address" }],
+        "stateMutability": "view"
+    }]"#;
+    serde_json::from_str(abi).expect("invalid bridgehub ABI")
+});
+
+/// Converts an L1 executor function (e.g., `commitBatches`) into its shared bridge counterpart
+/// (e.g., `commitBatchesSharedBridge`) accepting the L2 chain ID as the first argument.
+pub(crate) fn shared_bridge_function(function: Function) -> Function {
+    let chain_id_param = Param {
+        name: "_chainId".to_owned(),
+        kind: ParamType::Uint(256),
+        internal_type: None,
+    };
+    Function {
+        name: format!("{}SharedBridge", function.name),
+        inputs: [chain_id_param]
+            .into_iter()
+            .chain(function.inputs)
+            .collect(),
+        ..function
+    }
+}
+
+/// L1 contracts of a single chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainL1Contracts {
+    pub chain_id: L2ChainId,
+    /// Address of the diamond proxy of the chain. Priority operations and protocol upgrades of the chain
+    /// are emitted by this contract.
+    pub diamond_proxy_addr: Address,
+    /// Address of the bridgehub the chain is registered in, or `None` if the chain is deployed
+    /// without the shared bridge.
+    pub bridgehub_proxy_addr: Option<Address>,
+}
+
+impl ChainL1Contracts {
+    /// Creates contracts of a chain deployed without the shared bridge.
+    pub fn standalone(chain_id: L2ChainId, diamond_proxy_addr: Address) -> Self {
+        Self {
+            chain_id,
+            diamond_proxy_addr,
+            bridgehub_proxy_addr: None,
+        }
+    }
+
+    /// Checks whether the chain is registered in the shared bridge.
+    pub fn is_shared_bridge(&self) -> bool {
+        self.bridgehub_proxy_addr.is_some()
+    }
+
+    /// Loads contracts of the chain served by the node. If the bridgehub is configured, the diamond proxy
+    /// is resolved from it and checked against the configured one.
+    pub async fn load(
+        eth_client: &impl EthInterface,
+        contracts_config: &ContractsConfig,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        let Some(bridgehub_proxy_addr) = contracts_config.bridgehub_proxy_addr else {
+            return Ok(Self::standalone(
+                chain_id,
+                contracts_config.diamond_proxy_addr,
+            ));
+        };
+        let contracts = Self::resolve(eth_client, bridgehub_proxy_addr, chain_id).await?;
+        anyhow::ensure!(
+            contracts.diamond_proxy_addr == contracts_config.diamond_proxy_addr,
+            "Diamond proxy of chain {} in bridgehub {bridgehub_proxy_addr:?} is {:?}, while {:?} is configured",
+            chain_id.as_u64(),
+            contracts.diamond_proxy_addr,
+            contracts_config.diamond_proxy_addr
+        );
+        Ok(contracts)
+    }
+
+    /// Resolves contracts of a chain registered in the specified bridgehub.
+    pub async fn resolve(
+        eth_client: &impl EthInterface,
+        bridgehub_proxy_addr: Address,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        let diamond_proxy_addr: Address = eth_client
+            .call_contract_function(
+                "getHyperchain",
+                U256::from(chain_id.as_u64()),
+                None,
+                Default::default(),
+                None,
+                bridgehub_proxy_addr,
+                BRIDGEHUB_CONTRACT.clone(),
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "failed getting diamond proxy of chain {} from bridgehub",
+                    chain_id.as_u64()
+                )
+            })?;
+        anyhow::ensure!(
+            diamond_proxy_addr != Address::zero(),
+            "Chain {} is not registered in bridgehub {bridgehub_proxy_addr:?}",
+            chain_id.as_u64()
+        );
+        tracing::info!(
+            "Resolved diamond proxy of chain {} from bridgehub: {diamond_proxy_addr:?}",
+            chain_id.as_u64()
+        );
+        Ok(Self {
+            chain_id,
+            diamond_proxy_addr,
+            bridgehub_proxy_addr: Some(bridgehub_proxy_addr),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::ethabi::{self, ParamType, Token};
+
+    use super::*;
+
+    #[test]
+    fn encoding_bridgehub_call() {
+        let function = BRIDGEHUB_CONTRACT.function("getHyperchain").unwrap();
+        let calldata = function.encode_input(&[Token::Uint(270.into())]).unwrap();
+        let selector = ethabi::short_signature("getHyperchain", &[ParamType::Uint(256)]);
+        assert_eq!(calldata[..4], selector);
+        assert_eq!(U256::from_big_endian(&calldata[4..]), U256::from(270));
+    }
+}
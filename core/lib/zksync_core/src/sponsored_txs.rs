@@ -0,0 +1,185 @@
+//! Policy for transactions sponsored by the operator.
+//!
+//! The bootloader requires every L2 transaction to pay at least the batch base fee, so sponsored transactions
+//! are paid for by an operator-funded paymaster rather than having zero fee fields. From the user's perspective,
+//! such transactions are gasless: the initiator's balance isn't charged. To prevent draining the paymaster,
+//! only calls from the operator-configured allowlist may use it, and the total gas used by sponsored transactions
+//! in an L1 batch can be limited.
+
+use std::collections::HashSet;
+
+use zksync_config::configs::chain::{SponsoredCall, StateKeeperConfig};
+use zksync_types::{Address, Execute, ExecuteTransactionCommon, Transaction};
+
+/// Checks whether the transaction is paid for by the specified paymaster.
+pub(crate) fn is_paid_by_paymaster(tx: &Transaction, paymaster: Address) -> bool {
+    matches!(
+        &tx.common_data,
+        ExecuteTransactionCommon::L2(data) if data.paymaster_params.paymaster == paymaster
+    )
+}
+
+/// Policy deciding which transactions may use the sponsor paymaster.
+#[derive(Debug, Clone)]
+pub struct SponsorshipPolicy {
+    paymaster: Address,
+    /// Contracts with all functions sponsored.
+    contracts: HashSet<Address>,
+    /// Sponsored (contract, function selector) pairs.
+    functions: HashSet<(Address, [u8; 4])>,
+}
+
+impl SponsorshipPolicy {
+    pub fn new(paymaster: Address, calls: &[SponsoredCall]) -> Self {
+        let mut contracts = HashSet::new();
+        let mut functions = HashSet::new();
+        for call in calls {
+            match call.selector {
+                Some(selector) => {
+                    functions.insert((call.contract, selector));
+                }
+                None => {
+                    contracts.insert(call.contract);
+                }
+            }
+        }
+        Self {
+            paymaster,
+            contracts,
+            functions,
+        }
+    }
+
+    /// Creates a policy from the state keeper config. Returns `None` if sponsorship is disabled.
+    pub fn from_config(config: &StateKeeperConfig) -> Option<Self> {
+        let paymaster = config.sponsor_paymaster_addr?;
+        Some(Self::new(paymaster, config.sponsored_calls()))
+    }
+
+    /// Returns a reason for rejecting a transaction paid for by `paymaster` that doesn't call an allowlisted
+    /// contract function, or `None` if the transaction is not sponsored or is allowlisted.
+    pub fn rejection_reason(&self, paymaster: Address, execute: &Execute) -> Option<String> {
+        if paymaster != self.paymaster {
+            return None;
+        }
+        let contract = execute.contract_address;
+        if self.contracts.contains(&contract) {
+            return None;
+        }
+        let selector: Option<[u8; 4]> = execute
+            .calldata
+            .get(..4)
+            .map(|selector| selector.try_into().unwrap());
+        if let Some(selector) = selector {
+            if self.functions.contains(&(contract, selector)) {
+                return None;
+            }
+        }
+        Some(format!(
+            "call to {contract:?} with selector {} is not sponsored by the operator",
+            selector.map_or_else(|| "(none)".to_owned(), |s| format!("0x{}", hex::encode(s)))
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        fee::Fee, l2::L2Tx, transaction_request::PaymasterParams, L2ChainId, Nonce, H256,
+    };
+
+    use super::*;
+
+    fn create_tx(contract: Address, calldata: Vec<u8>, paymaster: Address) -> L2Tx {
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        let paymaster_params = PaymasterParams {
+            paymaster,
+            paymaster_input: vec![],
+        };
+        L2Tx::new_signed(
+            contract,
+            calldata,
+            Nonce(0),
+            fee,
+            0.into(),
+            L2ChainId::default(),
+            &H256::repeat_byte(1),
+            None,
+            paymaster_params,
+        )
+        .unwrap()
+    }
+
+    fn rejection_reason(policy: &SponsorshipPolicy, tx: &L2Tx) -> Option<String> {
+        policy.rejection_reason(tx.common_data.paymaster_params.paymaster, &tx.execute)
+    }
+
+    #[test]
+    fn sponsorship_policy_basics() {
+        let paymaster = Address::repeat_byte(0xaa);
+        let sponsored_contract = Address::repeat_byte(1);
+        let sponsored_function_contract = Address::repeat_byte(2);
+        let selector = [0x3c, 0xda, 0x33, 0x51];
+        let policy = SponsorshipPolicy::new(
+            paymaster,
+            &[
+                SponsoredCall {
+                    contract: sponsored_contract,
+                    selector: None,
+                },
+                SponsoredCall {
+                    contract: sponsored_function_contract,
+                    selector: Some(selector),
+                },
+            ],
+        );
+
+        let tx = create_tx(sponsored_contract, vec![1, 2, 3, 4, 5], paymaster);
+        assert!(is_paid_by_paymaster(&tx.clone().into(), paymaster));
+        assert_eq!(rejection_reason(&policy, &tx), None);
+        let tx = create_tx(sponsored_contract, vec![], paymaster);
+        assert_eq!(rejection_reason(&policy, &tx), None);
+
+        let calldata = [&selector[..], &[0; 32]].concat();
+        let tx = create_tx(sponsored_function_contract, calldata, paymaster);
+        assert_eq!(rejection_reason(&policy, &tx), None);
+        let tx = create_tx(sponsored_function_contract, vec![0; 4], paymaster);
+        let reason = rejection_reason(&policy, &tx).unwrap();
+        assert!(reason.contains("0x00000000"), "{reason}");
+        let tx = create_tx(sponsored_function_contract, vec![], paymaster);
+        let reason = rejection_reason(&policy, &tx).unwrap();
+        assert!(reason.contains("(none)"), "{reason}");
+
+        let tx = create_tx(Address::repeat_byte(3), vec![], paymaster);
+        assert!(rejection_reason(&policy, &tx).is_some());
+
+        // Transactions not using the sponsor paymaster are not affected by the policy.
+        let tx = create_tx(Address::repeat_byte(3), vec![], Address::zero());
+        assert!(!is_paid_by_paymaster(&tx.clone().into(), paymaster));
+        assert_eq!(rejection_reason(&policy, &tx), None);
+    }
+
+    #[test]
+    fn parsing_sponsored_calls() {
+        let call: SponsoredCall = "0x0000000000000000000000000000000000008006:0x3cda3351"
+            .parse()
+            .unwrap();
+        assert_eq!(call.contract, Address::from_low_u64_be(0x8006));
+        assert_eq!(call.selector, Some([0x3c, 0xda, 0x33, 0x51]));
+
+        let call: SponsoredCall = "0000000000000000000000000000000000008006".parse().unwrap();
+        assert_eq!(call.contract, Address::from_low_u64_be(0x8006));
+        assert_eq!(call.selector, None);
+
+        let err = "0x0000000000000000000000000000000000008006:0x3cda"
+            .parse::<SponsoredCall>()
+            .unwrap_err();
+        assert!(err.contains("4 bytes"), "{err}");
+        "0x8006".parse::<SponsoredCall>().unwrap_err();
+    }
+}
@@ -0,0 +1,446 @@
+//! Export and import of the RocksDB state of a node (the state keeper cache and the Merkle tree).
+//!
+//! Archives allow bootstrapping a node without re-executing all L1 batches: the node only needs
+//! a Postgres instance with the data up to the archived L1 batch, and the archived RocksDB instances.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::{domain::ZkSyncTree, MerkleTreeColumnFamily};
+use zksync_state::RocksdbStorage;
+use zksync_storage::RocksDB;
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert};
+
+/// Version of the archive layout. Should be incremented on breaking changes of the layout or the manifest.
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const STATE_KEEPER_DIR: &str = "state_keeper";
+const MERKLE_TREE_DIR: &str = "tree";
+
+/// File included into a state archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    /// Path relative to the archive root, with `/` used as a separator.
+    pub path: String,
+    pub size: u64,
+}
+
+/// Manifest of a state archive. Stored in the root of the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateArchiveManifest {
+    pub version: u32,
+    /// Last L1 batch processed by both archived databases.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the Merkle tree after processing `l1_batch_number`.
+    pub root_hash: H256,
+    /// All files in the archive except for the manifest, sorted by path.
+    pub files: Vec<ArchivedFile>,
+}
+
+impl StateArchiveManifest {
+    fn list_files(archive_path: &Path) -> anyhow::Result<Vec<ArchivedFile>> {
+        let mut files = vec![];
+        for dir_name in [STATE_KEEPER_DIR, MERKLE_TREE_DIR] {
+            list_files_recursively(archive_path, &archive_path.join(dir_name), &mut files)?;
+        }
+        files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Checks that the archive contains exactly the files listed in the manifest, with the expected sizes.
+    /// Must be called before opening archived databases since RocksDB writes auxiliary files when opening a DB.
+    fn verify_files(&self, archive_path: &Path) -> anyhow::Result<()> {
+        let actual_files = Self::list_files(archive_path)?;
+        for expected in &self.files {
+            let actual = actual_files.iter().find(|file| file.path == expected.path);
+            let actual = actual
+                .with_context(|| format!("file `{}` is missing from the archive", expected.path))?;
+            anyhow::ensure!(
+                actual.size == expected.size,
+                "file `{}` has unexpected size: expected {} bytes, got {} bytes",
+                expected.path,
+                expected.size,
+                actual.size
+            );
+        }
+        if let Some(extra) = actual_files
+            .iter()
+            .find(|file| !self.files.iter().any(|expected| expected.path == file.path))
+        {
+            anyhow::bail!(
+                "file `{}` is not listed in the archive manifest",
+                extra.path
+            );
+        }
+        Ok(())
+    }
+}
+
+fn list_files_recursively(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<ArchivedFile>,
+) -> anyhow::Result<()> {
+    let entries = fs::read_dir(dir).with_context(|| format!("cannot read `{}`", dir.display()))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("cannot read `{}`", dir.display()))?;
+        let path = entry.path();
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("cannot get metadata for `{}`", path.display()))?;
+        if metadata.is_dir() {
+            list_files_recursively(root, &path, files)?;
+        } else {
+            let relative_path = path.strip_prefix(root)?;
+            let components: Vec<_> = relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            files.push(ArchivedFile {
+                path: components.join("/"),
+                size: metadata.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir_recursively(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to).with_context(|| format!("cannot create `{}`", to.display()))?;
+    let entries =
+        fs::read_dir(from).with_context(|| format!("cannot read `{}`", from.display()))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("cannot read `{}`", from.display()))?;
+        let target_path = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursively(&entry.path(), &target_path)?;
+        } else {
+            fs::copy(entry.path(), &target_path).with_context(|| {
+                format!(
+                    "cannot copy `{}` to `{}`",
+                    entry.path().display(),
+                    target_path.display()
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn path_to_string(path: &Path) -> anyhow::Result<String> {
+    let path = path
+        .to_str()
+        .with_context(|| format!("path `{}` is not valid UTF-8", path.display()))?;
+    Ok(path.to_owned())
+}
+
+/// Exports and imports the state keeper cache and the Merkle tree RocksDB instances of a node.
+///
+/// The node must be stopped during export and import since RocksDB instances cannot be opened
+/// by multiple processes.
+#[derive(Debug)]
+pub struct StateArchiver {
+    state_keeper_db_path: PathBuf,
+    merkle_tree_path: PathBuf,
+    pool: ConnectionPool,
+}
+
+impl StateArchiver {
+    pub fn new(
+        state_keeper_db_path: impl Into<PathBuf>,
+        merkle_tree_path: impl Into<PathBuf>,
+        pool: ConnectionPool,
+    ) -> Self {
+        Self {
+            state_keeper_db_path: state_keeper_db_path.into(),
+            merkle_tree_path: merkle_tree_path.into(),
+            pool,
+        }
+    }
+
+    /// Exports RocksDB instances to a new directory at `archive_path`. Both instances are aligned
+    /// at the latest L1 batch processed by both of them; the Merkle tree root hash for this batch
+    /// is checked against the root hash stored in Postgres.
+    ///
+    /// The archive directory can be packed and transferred to another machine, and then imported
+    /// using [`Self::import()`].
+    pub async fn export(&self, archive_path: &Path) -> anyhow::Result<StateArchiveManifest> {
+        anyhow::ensure!(
+            self.state_keeper_db_path.exists() && self.merkle_tree_path.exists(),
+            "state keeper cache (`{}`) or Merkle tree (`{}`) doesn't exist",
+            self.state_keeper_db_path.display(),
+            self.merkle_tree_path.display()
+        );
+        anyhow::ensure!(
+            !archive_path.exists(),
+            "archive path `{}` already exists",
+            archive_path.display()
+        );
+        fs::create_dir_all(archive_path)
+            .with_context(|| format!("cannot create `{}`", archive_path.display()))?;
+
+        let archived_sk_path = archive_path.join(STATE_KEEPER_DIR);
+        let archived_tree_path = archive_path.join(MERKLE_TREE_DIR);
+        tracing::info!("Creating checkpoints of RocksDB instances...");
+        RocksdbStorage::new(&self.state_keeper_db_path)
+            .create_checkpoint(&archived_sk_path)
+            .context("failed creating state keeper cache checkpoint")?;
+        RocksDB::<MerkleTreeColumnFamily>::new(&self.merkle_tree_path)
+            .create_checkpoint(&archived_tree_path)
+            .context("failed creating Merkle tree checkpoint")?;
+
+        let sk_next_l1_batch = RocksdbStorage::new(&archived_sk_path).l1_batch_number();
+        let tree_next_l1_batch = Self::open_tree(&archived_tree_path).next_l1_batch_number();
+        let next_l1_batch = sk_next_l1_batch.min(tree_next_l1_batch);
+        anyhow::ensure!(
+            next_l1_batch > L1BatchNumber(0),
+            "state keeper cache or Merkle tree doesn't contain any L1 batches"
+        );
+        let l1_batch_number = next_l1_batch - 1;
+
+        tracing::info!(
+            "Aligning archived state keeper cache (next L1 batch: {sk_next_l1_batch}) and Merkle tree \
+             (next L1 batch: {tree_next_l1_batch}) at L1 batch #{l1_batch_number}..."
+        );
+        // Only the archived copies are rolled back, so it's safe to revert L1 batches executed on L1.
+        let reverter = BlockReverter::new(
+            path_to_string(&archived_sk_path)?,
+            path_to_string(&archived_tree_path)?,
+            None,
+            self.pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        );
+        reverter
+            .rollback_db(
+                l1_batch_number,
+                BlockReverterFlags::TREE | BlockReverterFlags::SK_CACHE,
+            )
+            .await;
+
+        let root_hash = self
+            .verify_state(&archived_sk_path, &archived_tree_path, l1_batch_number)
+            .await?;
+        let manifest = StateArchiveManifest {
+            version: ARCHIVE_FORMAT_VERSION,
+            l1_batch_number,
+            root_hash,
+            files: StateArchiveManifest::list_files(archive_path)?,
+        };
+        let manifest_path = archive_path.join(MANIFEST_FILE_NAME);
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        fs::write(&manifest_path, manifest_json)
+            .with_context(|| format!("cannot write `{}`", manifest_path.display()))?;
+
+        tracing::info!(
+            "Exported state at L1 batch #{l1_batch_number} with root hash {root_hash:?} to `{}`",
+            archive_path.display()
+        );
+        Ok(manifest)
+    }
+
+    /// Imports RocksDB instances from the archive at `archive_path`. The configured database paths
+    /// must not exist. Before the import, the archive files are checked against the manifest;
+    /// after the import, the state of the imported RocksDB instances is checked against the manifest
+    /// and the L1 batch data in Postgres. If any of the checks fails, imported instances are removed.
+    ///
+    /// If `verify_tree_consistency` is set, the imported Merkle tree is additionally checked
+    /// for consistency, which can take a long time for large trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the imported Merkle tree is inconsistent.
+    pub async fn import(
+        &self,
+        archive_path: &Path,
+        verify_tree_consistency: bool,
+    ) -> anyhow::Result<StateArchiveManifest> {
+        let manifest_path = archive_path.join(MANIFEST_FILE_NAME);
+        let manifest = fs::read(&manifest_path)
+            .with_context(|| format!("cannot read `{}`", manifest_path.display()))?;
+        let manifest: StateArchiveManifest =
+            serde_json::from_slice(&manifest).context("cannot parse archive manifest")?;
+        anyhow::ensure!(
+            manifest.version == ARCHIVE_FORMAT_VERSION,
+            "unsupported archive version {}, expected {ARCHIVE_FORMAT_VERSION}",
+            manifest.version
+        );
+        manifest
+            .verify_files(archive_path)
+            .context("archive doesn't match its manifest")?;
+
+        for path in [&self.state_keeper_db_path, &self.merkle_tree_path] {
+            anyhow::ensure!(
+                !path.exists(),
+                "`{}` already exists; remove it before importing state",
+                path.display()
+            );
+        }
+
+        tracing::info!(
+            "Importing state at L1 batch #{} from `{}`...",
+            manifest.l1_batch_number,
+            archive_path.display()
+        );
+        let import_result = self
+            .import_inner(archive_path, &manifest, verify_tree_consistency)
+            .await;
+        if import_result.is_err() {
+            tracing::warn!("Import failed; removing imported RocksDB instances");
+            for path in [&self.state_keeper_db_path, &self.merkle_tree_path] {
+                if path.exists() {
+                    fs::remove_dir_all(path)
+                        .with_context(|| format!("cannot remove `{}`", path.display()))?;
+                }
+            }
+        }
+        import_result?;
+
+        tracing::info!(
+            "Imported state at L1 batch #{} with root hash {:?}",
+            manifest.l1_batch_number,
+            manifest.root_hash
+        );
+        Ok(manifest)
+    }
+
+    async fn import_inner(
+        &self,
+        archive_path: &Path,
+        manifest: &StateArchiveManifest,
+        verify_tree_consistency: bool,
+    ) -> anyhow::Result<()> {
+        copy_dir_recursively(
+            &archive_path.join(STATE_KEEPER_DIR),
+            &self.state_keeper_db_path,
+        )?;
+        copy_dir_recursively(&archive_path.join(MERKLE_TREE_DIR), &self.merkle_tree_path)?;
+
+        let root_hash = self
+            .verify_state(
+                &self.state_keeper_db_path,
+                &self.merkle_tree_path,
+                manifest.l1_batch_number,
+            )
+            .await?;
+        anyhow::ensure!(
+            root_hash == manifest.root_hash,
+            "Merkle tree root hash {root_hash:?} differs from the root hash {:?} in the manifest",
+            manifest.root_hash
+        );
+
+        if verify_tree_consistency {
+            tracing::info!("Verifying consistency of the imported Merkle tree...");
+            Self::open_tree(&self.merkle_tree_path).verify_consistency(manifest.l1_batch_number);
+        }
+        Ok(())
+    }
+
+    fn open_tree(path: &Path) -> ZkSyncTree {
+        ZkSyncTree::new_lightweight(RocksDB::new(path).into())
+    }
+
+    /// Checks that the state keeper cache and the Merkle tree at the specified paths have processed
+    /// `l1_batch_number` as their last L1 batch, and that the tree root hash matches the one in Postgres.
+    /// Returns the tree root hash.
+    async fn verify_state(
+        &self,
+        sk_path: &Path,
+        tree_path: &Path,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<H256> {
+        let sk_next_l1_batch = RocksdbStorage::new(sk_path).l1_batch_number();
+        anyhow::ensure!(
+            sk_next_l1_batch == l1_batch_number + 1,
+            "unexpected next L1 batch in state keeper cache: expected {}, got {sk_next_l1_batch}",
+            l1_batch_number + 1
+        );
+        let tree = Self::open_tree(tree_path);
+        let tree_next_l1_batch = tree.next_l1_batch_number();
+        anyhow::ensure!(
+            tree_next_l1_batch == l1_batch_number + 1,
+            "unexpected next L1 batch in Merkle tree: expected {}, got {tree_next_l1_batch}",
+            l1_batch_number + 1
+        );
+        let root_hash = tree.root_hash();
+
+        let mut storage = self.pool.access_storage_tagged("state_archiver").await?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting root hash for L1 batch #{l1_batch_number}"))?
+            .with_context(|| {
+                format!("root hash for L1 batch #{l1_batch_number} is not present in Postgres")
+            })?;
+        anyhow::ensure!(
+            root_hash == expected_root_hash,
+            "Merkle tree root hash {root_hash:?} for L1 batch #{l1_batch_number} differs from the root hash \
+             {expected_root_hash:?} in Postgres"
+        );
+        Ok(root_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn create_archive_files(archive_path: &Path) {
+        for (dir_name, file_name, contents) in [
+            (STATE_KEEPER_DIR, "000001.sst", b"state".as_slice()),
+            (MERKLE_TREE_DIR, "000002.sst", b"tree".as_slice()),
+            (MERKLE_TREE_DIR, "CURRENT", b"MANIFEST-000001\n".as_slice()),
+        ] {
+            let dir = archive_path.join(dir_name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(file_name), contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn verifying_archive_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path();
+        create_archive_files(archive_path);
+
+        let manifest = StateArchiveManifest {
+            version: ARCHIVE_FORMAT_VERSION,
+            l1_batch_number: L1BatchNumber(1),
+            root_hash: H256::repeat_byte(1),
+            files: StateArchiveManifest::list_files(archive_path).unwrap(),
+        };
+        let paths: Vec<_> = manifest
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            ["state_keeper/000001.sst", "tree/000002.sst", "tree/CURRENT"]
+        );
+        manifest.verify_files(archive_path).unwrap();
+
+        let tree_file_path = archive_path.join(MERKLE_TREE_DIR).join("000002.sst");
+        fs::write(&tree_file_path, b"truncated tree").unwrap();
+        let err = manifest.verify_files(archive_path).unwrap_err().to_string();
+        assert!(err.contains("unexpected size"), "{err}");
+
+        fs::remove_file(&tree_file_path).unwrap();
+        let err = manifest.verify_files(archive_path).unwrap_err().to_string();
+        assert!(err.contains("missing"), "{err}");
+
+        create_archive_files(archive_path);
+        fs::write(archive_path.join(STATE_KEEPER_DIR).join("LOG"), b"log").unwrap();
+        let err = manifest.verify_files(archive_path).unwrap_err().to_string();
+        assert!(err.contains("not listed"), "{err}");
+    }
+}
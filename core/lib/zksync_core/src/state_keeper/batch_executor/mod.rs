@@ -1,4 +1,8 @@
-use std::{fmt, sync::Arc};
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{mpsc as std_mpsc, Arc},
+};
 
 use async_trait::async_trait;
 use multivm::{
@@ -6,9 +10,9 @@ use multivm::{
         ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv, VmExecutionMode,
         VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
     },
-    tracers::CallTracer,
+    tracers::{CallTracer, TracerDispatcher},
     vm_latest::HistoryEnabled,
-    MultiVMTracer, VmInstance,
+    MultiVMTracer, ShadowVm, VmInstance,
 };
 use once_cell::sync::OnceCell;
 use tokio::{
@@ -16,19 +20,27 @@ use tokio::{
     task::JoinHandle,
 };
 use zksync_dal::ConnectionPool;
-use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, witness_block_state::WitnessBlockState, Transaction, U256};
+use zksync_state::{BytecodeCache, RocksdbStorage, StoragePtr, StorageView, WriteStorage};
+use zksync_types::{
+    vm_trace::Call, witness_block_state::WitnessBlockState, Transaction, VmVersion, H256, U256,
+};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
+use self::optimistic::{OptimisticExecutorHandle, PreExecutedTx};
 use crate::{
     gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics},
     metrics::{InteractionType, TxStage, APP_METRICS},
+    rocksdb_compactor::StateKeeperCompactionRequests,
     state_keeper::{
-        metrics::{ExecutorCommand, TxExecutionStage, EXECUTOR_METRICS, KEEPER_METRICS},
+        metrics::{
+            ExecutorCommand, OptimisticExecutionOutcome, PreExecutedReadKind, TxExecutionStage,
+            EXECUTOR_METRICS, KEEPER_METRICS,
+        },
         types::ExecutionMetricsForCriteria,
     },
 };
 
+mod optimistic;
 #[cfg(test)]
 mod tests;
 
@@ -89,6 +101,10 @@ pub struct MainBatchExecutorBuilder {
     max_allowed_tx_gas_limit: U256,
     upload_witness_inputs_to_gcs: bool,
     enum_index_migration_chunk_size: usize,
+    shadow_vm_version: Option<VmVersion>,
+    optimistic_execution_lookahead: Option<usize>,
+    compaction_requests: Option<StateKeeperCompactionRequests>,
+    bytecode_cache: Option<BytecodeCache>,
 }
 
 impl MainBatchExecutorBuilder {
@@ -107,8 +123,43 @@ impl MainBatchExecutorBuilder {
             max_allowed_tx_gas_limit,
             upload_witness_inputs_to_gcs,
             enum_index_migration_chunk_size,
+            shadow_vm_version: None,
+            optimistic_execution_lookahead: None,
+            compaction_requests: None,
+            bytecode_cache: None,
         }
     }
+
+    /// Enables shadow execution of all transactions using the specified VM version. Divergences
+    /// with the main VM are logged, but do not influence execution results.
+    pub fn with_shadow_vm(mut self, shadow_vm_version: VmVersion) -> Self {
+        self.shadow_vm_version = Some(shadow_vm_version);
+        self
+    }
+
+    /// Enables optimistic pre-execution of up to `lookahead` upcoming transactions, which is used
+    /// to warm up the storage cache of the VM. Pre-execution does not influence execution results.
+    pub fn with_optimistic_execution(mut self, lookahead: usize) -> Self {
+        assert!(
+            lookahead > 0,
+            "optimistic execution lookahead must be positive"
+        );
+        self.optimistic_execution_lookahead = Some(lookahead);
+        self
+    }
+
+    /// Enables serving compaction requests for the state keeper cache. Compaction is started in background
+    /// when starting each L1 batch.
+    pub fn with_compaction_requests(mut self, requests: StateKeeperCompactionRequests) -> Self {
+        self.compaction_requests = Some(requests);
+        self
+    }
+
+    /// Sets the bytecode cache used by the VM. The cache may be shared with other components (e.g., the API server).
+    pub fn with_bytecode_cache(mut self, cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(cache);
+        self
+    }
 }
 
 #[async_trait]
@@ -118,8 +169,15 @@ impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
     ) -> BatchExecutorHandle {
+        if let Some(requests) = &self.compaction_requests {
+            // RocksDB cannot be opened while it's compacted using a handle from the previous L1 batch.
+            requests.wait_for_compaction().await;
+        }
         let mut secondary_storage = RocksdbStorage::new(self.state_keeper_db_path.as_ref());
         secondary_storage.enable_enum_index_migration(self.enum_index_migration_chunk_size);
+        if let Some(requests) = &self.compaction_requests {
+            requests.serve(&secondary_storage).await;
+        }
         let mut conn = self
             .pool
             .access_storage_tagged("state_keeper")
@@ -135,6 +193,9 @@ impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
             l1_batch_params,
             system_env,
             self.upload_witness_inputs_to_gcs,
+            self.shadow_vm_version,
+            self.optimistic_execution_lookahead,
+            self.bytecode_cache.clone(),
         )
     }
 }
@@ -146,6 +207,7 @@ impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
 pub struct BatchExecutorHandle {
     handle: JoinHandle<()>,
     commands: mpsc::Sender<Command>,
+    optimistic_executor: Option<OptimisticExecutorHandle>,
 }
 
 impl BatchExecutorHandle {
@@ -158,7 +220,21 @@ impl BatchExecutorHandle {
         l1_batch_env: L1BatchEnv,
         system_env: SystemEnv,
         upload_witness_inputs_to_gcs: bool,
+        shadow_vm_version: Option<VmVersion>,
+        optimistic_execution_lookahead: Option<usize>,
+        bytecode_cache: Option<BytecodeCache>,
     ) -> Self {
+        let (optimistic_executor, pre_executed) = optimistic_execution_lookahead
+            .map(|lookahead| {
+                OptimisticExecutorHandle::spawn(
+                    lookahead,
+                    &secondary_storage,
+                    &l1_batch_env,
+                    &system_env,
+                )
+            })
+            .unzip();
+
         // Since we process `BatchExecutor` commands one-by-one (the next command is never enqueued
         // until a previous command is processed), capacity 1 is enough for the commands channel.
         let (commands_sender, commands_receiver) = mpsc::channel(1);
@@ -166,6 +242,9 @@ impl BatchExecutorHandle {
             save_call_traces,
             max_allowed_tx_gas_limit,
             commands: commands_receiver,
+            pre_executed,
+            pre_executed_tx_hashes: HashSet::new(),
+            bytecode_cache,
         };
 
         let handle = tokio::task::spawn_blocking(move || {
@@ -174,11 +253,13 @@ impl BatchExecutorHandle {
                 l1_batch_env,
                 system_env,
                 upload_witness_inputs_to_gcs,
+                shadow_vm_version,
             )
         });
         Self {
             handle,
             commands: commands_sender,
+            optimistic_executor,
         }
     }
 
@@ -186,7 +267,33 @@ impl BatchExecutorHandle {
     /// Can be used to inject an alternative batch executor implementation.
     #[cfg(test)]
     pub(super) fn from_raw(handle: JoinHandle<()>, commands: mpsc::Sender<Command>) -> Self {
-        Self { handle, commands }
+        Self {
+            handle,
+            commands,
+            optimistic_executor: None,
+        }
+    }
+
+    /// Returns the number of upcoming transactions that should be supplied to [`Self::pre_execute_txs()`],
+    /// or `None` if optimistic execution is disabled.
+    pub(super) fn optimistic_execution_lookahead(&self) -> Option<usize> {
+        Some(self.optimistic_executor.as_ref()?.lookahead())
+    }
+
+    /// Checks whether the transaction with the specified hash was already supplied to [`Self::pre_execute_txs()`]
+    /// for the current L1 batch. Such transactions should not be supplied again.
+    pub(super) fn is_pre_executed(&self, tx_hash: &H256) -> bool {
+        self.optimistic_executor
+            .as_ref()
+            .map_or(false, |executor| executor.is_enqueued(tx_hash))
+    }
+
+    /// Optimistically pre-executes the provided upcoming transactions in the background. This is a no-op
+    /// if optimistic execution is disabled.
+    pub(super) fn pre_execute_txs(&self, txs: Vec<Transaction>) {
+        if let Some(optimistic_executor) = &self.optimistic_executor {
+            optimistic_executor.pre_execute(txs);
+        }
     }
 
     pub(super) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
@@ -286,25 +393,53 @@ pub(super) struct BatchExecutor {
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
     commands: mpsc::Receiver<Command>,
+    /// Results of optimistic pre-execution; `None` if optimistic execution is disabled.
+    pre_executed: Option<std_mpsc::Receiver<PreExecutedTx>>,
+    pre_executed_tx_hashes: HashSet<H256>,
+    bytecode_cache: Option<BytecodeCache>,
 }
 
 impl BatchExecutor {
     pub(super) fn run(
-        mut self,
+        self,
         secondary_storage: RocksdbStorage,
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
         upload_witness_inputs_to_gcs: bool,
+        shadow_vm_version: Option<VmVersion>,
     ) {
         tracing::info!("Starting executing batch #{:?}", &l1_batch_params.number);
 
-        let storage_view = StorageView::new(secondary_storage).to_rc_ptr();
-
-        let mut vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
+        let mut storage_view = StorageView::new(secondary_storage);
+        if let Some(cache) = self.bytecode_cache.clone() {
+            storage_view = storage_view.with_bytecode_cache(cache);
+        }
+        let storage_view = storage_view.to_rc_ptr();
+        if let Some(shadow_vm_version) = shadow_vm_version {
+            tracing::info!("Shadowing batch execution with VM version {shadow_vm_version:?}");
+            let vm = ShadowVm::with_shadow_version(
+                l1_batch_params,
+                system_env,
+                storage_view.clone(),
+                shadow_vm_version,
+            );
+            self.run_with_vm(vm, &storage_view, upload_witness_inputs_to_gcs);
+        } else {
+            let vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
+            self.run_with_vm(vm, &storage_view, upload_witness_inputs_to_gcs);
+        }
+    }
 
+    fn run_with_vm(
+        mut self,
+        mut vm: impl VmInterfaceHistoryEnabled<StorageView<RocksdbStorage>>,
+        storage_view: &StoragePtr<StorageView<RocksdbStorage>>,
+        upload_witness_inputs_to_gcs: bool,
+    ) {
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
+                    self.warm_up_storage_cache(&tx, storage_view);
                     let result = self.execute_tx(&tx, &mut vm);
                     resp.send(result).unwrap();
                 }
@@ -340,10 +475,50 @@ impl BatchExecutor {
         tracing::info!("State keeper exited with an unfinished batch");
     }
 
+    /// Warms up the storage cache using the storage reads made during optimistic pre-execution.
+    fn warm_up_storage_cache(
+        &mut self,
+        tx: &Transaction,
+        storage_view: &StoragePtr<StorageView<RocksdbStorage>>,
+    ) {
+        let Some(pre_executed) = &self.pre_executed else {
+            return;
+        };
+
+        let mut storage_view = storage_view.borrow_mut();
+        for PreExecutedTx {
+            tx_hash,
+            storage_reads,
+        } in pre_executed.try_iter()
+        {
+            // Keys modified in the current batch are read from the view's modified keys, so the values
+            // read by pre-execution for these keys are not used.
+            let modified_keys = storage_view.modified_storage_keys();
+            let conflicting_count = storage_reads
+                .read_storage_key
+                .keys()
+                .filter(|&key| modified_keys.contains_key(key))
+                .count();
+            let reused_count = storage_view.warm_up_cache(storage_reads);
+            EXECUTOR_METRICS.pre_executed_reads[&PreExecutedReadKind::Conflicting]
+                .inc_by(conflicting_count as u64);
+            EXECUTOR_METRICS.pre_executed_reads[&PreExecutedReadKind::Reused]
+                .inc_by(reused_count as u64);
+            self.pre_executed_tx_hashes.insert(tx_hash);
+        }
+
+        let outcome = if self.pre_executed_tx_hashes.remove(&tx.hash()) {
+            OptimisticExecutionOutcome::PreExecuted
+        } else {
+            OptimisticExecutionOutcome::Missed
+        };
+        EXECUTOR_METRICS.optimistic_execution[&outcome].inc();
+    }
+
     fn execute_tx<S: WriteStorage>(
         &self,
         tx: &Transaction,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut impl VmInterfaceHistoryEnabled<S>,
     ) -> TxExecutionResult {
         // Save pre-`execute_next_tx` VM snapshot.
         vm.make_snapshot();
@@ -402,7 +577,7 @@ impl BatchExecutor {
         }
     }
 
-    fn rollback_last_tx<S: WriteStorage>(&self, vm: &mut VmInstance<S, HistoryEnabled>) {
+    fn rollback_last_tx<S: WriteStorage>(&self, vm: &mut impl VmInterfaceHistoryEnabled<S>) {
         let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::TxRollback].start();
         vm.rollback_to_the_latest_snapshot();
         latency.observe();
@@ -411,14 +586,14 @@ impl BatchExecutor {
     fn start_next_miniblock<S: WriteStorage>(
         &self,
         l2_block_env: L2BlockEnv,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut impl VmInterfaceHistoryEnabled<S>,
     ) {
         vm.start_new_l2_block(l2_block_env);
     }
 
     fn finish_batch<S: WriteStorage>(
         &self,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut impl VmInterfaceHistoryEnabled<S>,
     ) -> FinishedL1Batch {
         // The vm execution was paused right after the last transaction was executed.
         // There is some post-processing work that the VM needs to do before the block is fully processed.
@@ -439,7 +614,7 @@ impl BatchExecutor {
     fn execute_tx_in_vm<S: WriteStorage>(
         &self,
         tx: &Transaction,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut impl VmInterfaceHistoryEnabled<S>,
     ) -> (
         VmExecutionResultAndLogs,
         Vec<CompressedBytecodeInfo>,
@@ -464,9 +639,11 @@ impl BatchExecutor {
             vec![]
         };
 
-        if let Ok(result) =
-            vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), true)
-        {
+        if let Ok(result) = vm.inspect_transaction_with_bytecode_compression(
+            TracerDispatcher::<S, HistoryEnabled>::from(tracer).into(),
+            tx.clone(),
+            true,
+        ) {
             let compressed_bytecodes = vm.get_last_tx_compressed_bytecodes();
             vm.pop_snapshot_no_rollback();
 
@@ -486,7 +663,11 @@ impl BatchExecutor {
         };
 
         let result = vm
-            .inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), false)
+            .inspect_transaction_with_bytecode_compression(
+                TracerDispatcher::<S, HistoryEnabled>::from(tracer).into(),
+                tx.clone(),
+                false,
+            )
             .expect("Compression can't fail if we don't apply it");
         let compressed_bytecodes = vm.get_last_tx_compressed_bytecodes();
 
@@ -501,7 +682,7 @@ impl BatchExecutor {
 
     fn dryrun_block_tip<S: WriteStorage>(
         &self,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut impl VmInterfaceHistoryEnabled<S>,
     ) -> (VmExecutionResultAndLogs, ExecutionMetricsForCriteria) {
         let total_latency =
             KEEPER_METRICS.tx_execution_time[&TxExecutionStage::DryRunRollback].start();
@@ -0,0 +1,147 @@
+//! Optimistic pre-execution of upcoming transactions.
+//!
+//! While the batch executor executes a transaction, upcoming transactions are pre-executed in a separate thread
+//! on a snapshot of the state as of the start of the L1 batch. The main VM cannot reuse the VM state produced
+//! by pre-execution (the snapshot lacks changes made in the current batch), but storage reads made during it
+//! remain valid for the main VM unless the read keys were modified in the current batch. These reads are used
+//! to warm up the storage cache of the main VM, so that it doesn't need to access RocksDB for them.
+
+use std::{
+    collections::HashSet,
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+use multivm::{
+    interface::{L1BatchEnv, SystemEnv, VmInterface},
+    vm_latest::HistoryDisabled,
+    VmInstance,
+};
+use zksync_state::{RocksdbStorage, StorageView};
+use zksync_types::{witness_block_state::WitnessBlockState, Transaction, H256};
+
+use crate::state_keeper::metrics::EXECUTOR_METRICS;
+
+/// Storage reads made while pre-executing a transaction.
+#[derive(Debug)]
+pub(super) struct PreExecutedTx {
+    pub tx_hash: H256,
+    pub storage_reads: WitnessBlockState,
+}
+
+/// Handle allowing to enqueue transactions for pre-execution.
+#[derive(Debug)]
+pub(super) struct OptimisticExecutorHandle {
+    lookahead: usize,
+    txs_sender: mpsc::SyncSender<Transaction>,
+    /// Hashes of transactions enqueued for pre-execution. The state keeper supplies the same upcoming transactions
+    /// repeatedly; tracking them allows to skip them without cloning.
+    enqueued_tx_hashes: Mutex<HashSet<H256>>,
+}
+
+impl OptimisticExecutorHandle {
+    /// Spawns a thread pre-executing transactions for the specified L1 batch. Returns the handle for the thread
+    /// and the receiver of pre-execution results. The thread exits once the handle is dropped.
+    pub fn spawn(
+        lookahead: usize,
+        storage: &RocksdbStorage,
+        l1_batch_env: &L1BatchEnv,
+        system_env: &SystemEnv,
+    ) -> (Self, mpsc::Receiver<PreExecutedTx>) {
+        let (txs_sender, txs_receiver) = mpsc::sync_channel(lookahead);
+        let (results_sender, results_receiver) = mpsc::channel();
+        let executor = OptimisticExecutor {
+            storage: storage.read_only_handle(),
+            l1_batch_env: l1_batch_env.clone(),
+            system_env: system_env.clone(),
+            txs: txs_receiver,
+            results: results_sender,
+        };
+        thread::Builder::new()
+            .name("optimistic_executor".to_owned())
+            .spawn(move || executor.run())
+            .expect("failed spawning optimistic executor thread");
+
+        let this = Self {
+            lookahead,
+            txs_sender,
+            enqueued_tx_hashes: Mutex::default(),
+        };
+        (this, results_receiver)
+    }
+
+    pub fn lookahead(&self) -> usize {
+        self.lookahead
+    }
+
+    /// Checks whether a transaction with the specified hash was enqueued for pre-execution.
+    pub fn is_enqueued(&self, tx_hash: &H256) -> bool {
+        let enqueued_tx_hashes = self.enqueued_tx_hashes.lock().expect("poisoned");
+        enqueued_tx_hashes.contains(tx_hash)
+    }
+
+    /// Enqueues transactions for pre-execution. Already enqueued transactions and transactions not fitting
+    /// into the queue are skipped; the latter is fine since pre-execution never influences execution results.
+    pub fn pre_execute(&self, txs: Vec<Transaction>) {
+        let mut enqueued_tx_hashes = self.enqueued_tx_hashes.lock().expect("poisoned");
+        for tx in txs {
+            let tx_hash = tx.hash();
+            if enqueued_tx_hashes.contains(&tx_hash) {
+                continue;
+            }
+            if self.txs_sender.try_send(tx).is_err() {
+                break;
+            }
+            enqueued_tx_hashes.insert(tx_hash);
+        }
+    }
+}
+
+struct OptimisticExecutor {
+    storage: RocksdbStorage,
+    l1_batch_env: L1BatchEnv,
+    system_env: SystemEnv,
+    txs: mpsc::Receiver<Transaction>,
+    results: mpsc::Sender<PreExecutedTx>,
+}
+
+impl OptimisticExecutor {
+    fn run(self) {
+        while let Ok(tx) = self.txs.recv() {
+            let tx_hash = tx.hash();
+            let storage_reads = self.pre_execute(tx);
+            let result = PreExecutedTx {
+                tx_hash,
+                storage_reads,
+            };
+            if self.results.send(result).is_err() {
+                break; // The batch executor has finished the batch
+            }
+        }
+        tracing::debug!(
+            "Optimistic executor for L1 batch #{} is shutting down",
+            self.l1_batch_env.number
+        );
+    }
+
+    /// Executes a transaction as the first one in the batch and returns storage reads made during execution.
+    /// The execution result itself is discarded.
+    fn pre_execute(&self, tx: Transaction) -> WitnessBlockState {
+        let latency = EXECUTOR_METRICS.tx_pre_execution_time.start();
+        let storage_view = StorageView::new(self.storage.read_only_handle()).to_rc_ptr();
+        let mut vm = VmInstance::<_, HistoryDisabled>::new(
+            self.l1_batch_env.clone(),
+            self.system_env.clone(),
+            storage_view.clone(),
+        );
+        if let Err(err) = vm.execute_transaction_with_bytecode_compression(tx, true) {
+            // Storage reads made before the error are still valid, so we don't re-execute the transaction.
+            tracing::trace!("Failed compressing bytecodes during pre-execution: {err}");
+        }
+        drop(vm);
+
+        let storage_reads = storage_view.borrow().witness_block_state();
+        latency.observe();
+        storage_reads
+    }
+}
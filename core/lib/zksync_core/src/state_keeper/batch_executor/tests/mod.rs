@@ -1,7 +1,7 @@
 use assert_matches::assert_matches;
 use zksync_dal::ConnectionPool;
 use zksync_test_account::Account;
-use zksync_types::PriorityOpId;
+use zksync_types::{PriorityOpId, VmVersion};
 
 use self::tester::Tester;
 use super::TxExecutionResult;
@@ -83,6 +83,69 @@ async fn execute_l2_and_l1_txs() {
     executor.finish_batch().await;
 }
 
+/// Checks that shadow VM execution does not influence execution results.
+#[tokio::test]
+async fn execute_txs_with_shadow_vm() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut alice = Account::random();
+
+    let tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            shadow_vm_version: Some(VmVersion::latest()),
+            ..TestConfig::new()
+        },
+    );
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let executor = tester.create_batch_executor().await;
+
+    let tx = alice.execute();
+    let res = executor.execute_tx(tx.clone()).await;
+    assert_executed(&res);
+    executor.rollback_last_tx().await;
+
+    let res = executor.execute_tx(tx).await;
+    assert_executed(&res);
+    let res = executor.execute_tx(alice.l1_execute(PriorityOpId(1))).await;
+    assert_executed(&res);
+
+    executor.finish_batch().await;
+}
+
+/// Checks that optimistic pre-execution of upcoming transactions does not influence execution results.
+#[tokio::test]
+async fn execute_txs_with_optimistic_execution() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+
+    let tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            optimistic_execution_lookahead: Some(2),
+            ..TestConfig::new()
+        },
+    );
+    tester.genesis().await;
+    tester.fund(&[alice.address(), bob.address()]).await;
+    let executor = tester.create_batch_executor().await;
+    assert_eq!(executor.optimistic_execution_lookahead(), Some(2));
+
+    // The second Alice's transaction will be rejected during pre-execution because of its nonce,
+    // which must not influence its execution in the batch.
+    let txs = [alice.execute(), alice.execute(), bob.execute()];
+    executor.pre_execute_txs(txs.to_vec());
+    // The first 2 transactions always fit into the pre-execution queue.
+    assert!(executor.is_pre_executed(&txs[0].hash()));
+    assert!(executor.is_pre_executed(&txs[1].hash()));
+    for tx in txs {
+        let res = executor.execute_tx(tx).await;
+        assert_executed(&res);
+    }
+    executor.finish_batch().await;
+}
+
 /// Checks that we can successfully rollback the transaction and execute it once again.
 #[tokio::test]
 async fn rollback() {
@@ -327,6 +390,7 @@ async fn bootloader_out_of_gas_for_any_tx() {
             max_allowed_tx_gas_limit: u32::MAX,
             validation_computational_gas_limit: u32::MAX,
             upload_witness_inputs_to_gcs: false,
+            shadow_vm_version: None,
         },
     );
 
@@ -364,6 +428,7 @@ async fn bootloader_tip_out_of_gas() {
         max_allowed_tx_gas_limit: u32::MAX,
         validation_computational_gas_limit: u32::MAX,
         upload_witness_inputs_to_gcs: false,
+        shadow_vm_version: None,
     });
 
     let second_executor = tester.create_batch_executor().await;
@@ -14,8 +14,8 @@ use zksync_test_account::{Account, DeployContractsTx, TxType};
 use zksync_types::{
     ethabi::Token, fee::Fee, system_contracts::get_system_smart_contracts,
     utils::storage_key_for_standard_token_balance, AccountTreeId, Address, Execute, L1BatchNumber,
-    L2ChainId, MiniblockNumber, PriorityOpId, ProtocolVersionId, StorageLog, Transaction, H256,
-    L2_ETH_TOKEN_ADDRESS, SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
+    L2ChainId, MiniblockNumber, PriorityOpId, ProtocolVersionId, StorageLog, Transaction,
+    VmVersion, H256, L2_ETH_TOKEN_ADDRESS, SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
 };
 use zksync_utils::u256_to_h256;
 
@@ -39,6 +39,8 @@ pub(super) struct TestConfig {
     pub(super) max_allowed_tx_gas_limit: u32,
     pub(super) validation_computational_gas_limit: u32,
     pub(super) upload_witness_inputs_to_gcs: bool,
+    pub(super) shadow_vm_version: Option<VmVersion>,
+    pub(super) optimistic_execution_lookahead: Option<usize>,
 }
 
 impl TestConfig {
@@ -51,6 +53,8 @@ impl TestConfig {
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
             upload_witness_inputs_to_gcs: false,
+            shadow_vm_version: None,
+            optimistic_execution_lookahead: None,
         }
     }
 }
@@ -112,6 +116,9 @@ impl Tester {
             l1_batch,
             system_env,
             self.config.upload_witness_inputs_to_gcs,
+            self.config.shadow_vm_version,
+            self.config.optimistic_execution_lookahead,
+            None,
         )
     }
 
@@ -150,6 +157,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 Default::default(),
                 Default::default(),
+                None,
             )
             .await;
         }
@@ -0,0 +1,488 @@
+//! Intake of miniblocks proposed by an external block builder.
+//!
+//! The builder submits a fully ordered list of L2 transactions for the next miniblock to an authenticated
+//! HTTP endpoint. Proposals are statically validated by the endpoint and are then executed by the state keeper
+//! instead of mempool transactions. If no proposal for a miniblock arrives in time, the miniblock
+//! is built from the mempool.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tokio::sync::{mpsc, watch};
+use zksync_types::{
+    api::TransactionRequest, l2::L2Tx, Bytes, L2ChainId, MiniblockNumber, Transaction, H256,
+};
+
+use super::metrics::{ExternalProposalOutcome, KEEPER_METRICS};
+
+/// Maximum size of a single proposed transaction; matches the default limit of the API server.
+const MAX_TX_SIZE: usize = 1_000_000;
+/// Maximum number of proposals buffered before they are processed by the state keeper.
+const PROPOSALS_CAPACITY: usize = 16;
+
+/// Transactions proposed by the external block builder for a specific miniblock.
+#[derive(Debug)]
+pub(crate) struct ExternalBlockProposal {
+    pub miniblock_number: MiniblockNumber,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockProposalRequest {
+    miniblock_number: MiniblockNumber,
+    /// Signed L2 transactions in the execution order, encoded in the same way as for `eth_sendRawTransaction`.
+    transactions: Vec<Bytes>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockProposalResponse {
+    transaction_hashes: Vec<H256>,
+}
+
+#[derive(Debug)]
+enum ProposalError {
+    Unauthorized,
+    Invalid(String),
+    Overloaded,
+}
+
+impl IntoResponse for ProposalError {
+    fn into_response(self) -> Response {
+        KEEPER_METRICS.external_proposals[&ExternalProposalOutcome::Rejected].inc();
+        let (status_code, message) = match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "Invalid auth token".to_owned()),
+            Self::Invalid(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Overloaded => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many unprocessed proposals".to_owned(),
+            ),
+        };
+        (status_code, message).into_response()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ProposalIntake {
+    expected_auth_header: Arc<str>,
+    chain_id: L2ChainId,
+    proposals_sender: mpsc::Sender<ExternalBlockProposal>,
+}
+
+impl ProposalIntake {
+    fn submit(
+        &self,
+        headers: &HeaderMap,
+        request: BlockProposalRequest,
+    ) -> Result<Json<BlockProposalResponse>, ProposalError> {
+        let auth_header = headers
+            .get(header::AUTHORIZATION)
+            .map_or(&[][..], |value| value.as_bytes());
+        if !bool::from(auth_header.ct_eq(self.expected_auth_header.as_bytes())) {
+            return Err(ProposalError::Unauthorized);
+        }
+
+        let proposal = self.parse_proposal(request)?;
+        let transaction_hashes = proposal
+            .transactions
+            .iter()
+            .map(Transaction::hash)
+            .collect();
+        tracing::debug!(
+            "Received proposal for miniblock #{} with {} transactions from external block builder",
+            proposal.miniblock_number,
+            proposal.transactions.len()
+        );
+        self.proposals_sender
+            .try_send(proposal)
+            .map_err(|_| ProposalError::Overloaded)?;
+        Ok(Json(BlockProposalResponse { transaction_hashes }))
+    }
+
+    fn parse_proposal(
+        &self,
+        request: BlockProposalRequest,
+    ) -> Result<ExternalBlockProposal, ProposalError> {
+        if request.transactions.is_empty() {
+            return Err(ProposalError::Invalid(
+                "Proposal contains no transactions".to_owned(),
+            ));
+        }
+
+        let mut hashes = HashSet::with_capacity(request.transactions.len());
+        let mut next_nonces = HashMap::new();
+        let transactions = request
+            .transactions
+            .into_iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                let tx = parse_transaction(bytes, self.chain_id).map_err(|err| {
+                    ProposalError::Invalid(format!("Transaction #{i} is invalid: {err}"))
+                })?;
+                if !hashes.insert(tx.hash()) {
+                    return Err(ProposalError::Invalid(format!(
+                        "Transaction #{i} ({:?}) is duplicated",
+                        tx.hash()
+                    )));
+                }
+                // Transactions of each account must have consecutive nonces; whether the first nonce is correct
+                // is checked during execution.
+                let nonce = tx.common_data.nonce;
+                if let Some(expected_nonce) = next_nonces.insert(tx.initiator_account(), nonce + 1)
+                {
+                    if nonce != expected_nonce {
+                        return Err(ProposalError::Invalid(format!(
+                            "Transaction #{i} has nonce {}, while {} was expected",
+                            nonce.0, expected_nonce.0
+                        )));
+                    }
+                }
+                Ok(tx.into())
+            });
+
+        Ok(ExternalBlockProposal {
+            miniblock_number: request.miniblock_number,
+            transactions: transactions.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn parse_transaction(bytes: Bytes, chain_id: L2ChainId) -> anyhow::Result<L2Tx> {
+    let (tx_request, hash) = TransactionRequest::from_bytes(&bytes.0, chain_id)?;
+    let mut tx = L2Tx::from_request(tx_request, MAX_TX_SIZE)?;
+    tx.set_input(bytes.0, hash);
+    Ok(tx)
+}
+
+/// Creates a channel for proposals submitted to [`run_server()`].
+pub(crate) fn proposals_channel() -> (
+    mpsc::Sender<ExternalBlockProposal>,
+    mpsc::Receiver<ExternalBlockProposal>,
+) {
+    mpsc::channel(PROPOSALS_CAPACITY)
+}
+
+/// Runs the HTTP server accepting miniblock proposals from the external block builder. Requests must be
+/// authenticated with the `Authorization: Bearer <auth_token>` header. The server is bound to localhost;
+/// a builder running on another host should access it via a reverse proxy.
+pub(crate) async fn run_server(
+    port: u16,
+    auth_token: String,
+    chain_id: L2ChainId,
+    proposals_sender: mpsc::Sender<ExternalBlockProposal>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = SocketAddr::from(([127, 0, 0, 1], port));
+    tracing::debug!("Starting external block builder server on {bind_address}");
+    let intake = ProposalIntake {
+        expected_auth_header: format!("Bearer {auth_token}").into(),
+        chain_id,
+        proposals_sender,
+    };
+    let app = Router::new().route(
+        "/proposals",
+        post(
+            move |headers: HeaderMap, Json(request): Json<BlockProposalRequest>| async move {
+                intake.submit(&headers, request)
+            },
+        ),
+    );
+
+    axum::Server::bind(&bind_address)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!("Stop signal sender for external block builder server was dropped without sending a signal");
+            }
+            tracing::info!("Stop signal received, external block builder server is shutting down");
+        })
+        .await
+        .context("External block builder server failed")?;
+    tracing::info!("External block builder server shut down");
+    Ok(())
+}
+
+/// Result of [`ExternalProposals::next_transaction()`].
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)] // Values are short-lived; boxing the transaction isn't worth it.
+pub(crate) enum ProposedTx {
+    /// Next transaction from the proposal for the current miniblock.
+    Next(Transaction),
+    /// The proposal for the current miniblock hasn't arrived yet.
+    Wait,
+    /// There is no proposal for the current miniblock (or it's exhausted), so the miniblock should be built
+    /// from the mempool.
+    Fallback,
+}
+
+#[derive(Debug)]
+enum MiniblockSource {
+    /// Waiting for a proposal for the current miniblock since the specified moment.
+    Pending(Option<Instant>),
+    /// Executing transactions from a proposal; contains transactions not returned to the state keeper yet.
+    External(VecDeque<Transaction>),
+    Mempool,
+}
+
+/// Proposals from the external block builder processed by the state keeper IO.
+///
+/// Transactions from a proposal are executed in order. If the miniblock is sealed before all of them are executed
+/// (e.g., because the L1 batch is sealed), the remaining transactions are carried over to the next miniblock,
+/// and proposals for that miniblock are ignored.
+#[derive(Debug)]
+pub(crate) struct ExternalProposals {
+    proposals_receiver: mpsc::Receiver<ExternalBlockProposal>,
+    timeout: Duration,
+    /// Latest received proposal for a future miniblock.
+    next_proposal: Option<ExternalBlockProposal>,
+    source: MiniblockSource,
+}
+
+impl ExternalProposals {
+    pub fn new(
+        proposals_receiver: mpsc::Receiver<ExternalBlockProposal>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            proposals_receiver,
+            timeout,
+            next_proposal: None,
+            source: MiniblockSource::Pending(None),
+        }
+    }
+
+    fn receive_proposals(&mut self, miniblock_number: MiniblockNumber) {
+        while let Ok(proposal) = self.proposals_receiver.try_recv() {
+            let is_stale = proposal.miniblock_number < miniblock_number
+                || (proposal.miniblock_number == miniblock_number
+                    && !matches!(self.source, MiniblockSource::Pending(_)));
+            let superseded_proposal = if is_stale {
+                Some(proposal)
+            } else {
+                self.next_proposal.replace(proposal)
+            };
+            if let Some(proposal) = superseded_proposal {
+                tracing::info!(
+                    "Ignoring stale proposal for miniblock #{} from external block builder",
+                    proposal.miniblock_number
+                );
+                KEEPER_METRICS.external_proposals[&ExternalProposalOutcome::Stale].inc();
+            }
+        }
+    }
+
+    /// Returns the next transaction for the specified miniblock.
+    pub fn next_transaction(&mut self, miniblock_number: MiniblockNumber) -> ProposedTx {
+        self.receive_proposals(miniblock_number);
+        if let MiniblockSource::Pending(waiting_since) = &mut self.source {
+            let has_proposal = self.next_proposal.as_ref().map_or(false, |proposal| {
+                proposal.miniblock_number == miniblock_number
+            });
+            if has_proposal {
+                let proposal = self.next_proposal.take().unwrap();
+                tracing::debug!(
+                    "Executing proposal for miniblock #{miniblock_number} from external block builder"
+                );
+                KEEPER_METRICS.external_proposals[&ExternalProposalOutcome::Executed].inc();
+                self.source = MiniblockSource::External(proposal.transactions.into());
+            } else if waiting_since.get_or_insert_with(Instant::now).elapsed() >= self.timeout {
+                tracing::info!(
+                    "External block builder didn't propose miniblock #{miniblock_number} in time; \
+                     falling back to mempool"
+                );
+                KEEPER_METRICS.external_builder_fallbacks.inc();
+                self.source = MiniblockSource::Mempool;
+            } else {
+                return ProposedTx::Wait;
+            }
+        }
+
+        match &mut self.source {
+            MiniblockSource::External(transactions) => match transactions.pop_front() {
+                Some(tx) => ProposedTx::Next(tx),
+                None => {
+                    // All proposed transactions were rejected; the miniblock cannot be sealed while empty.
+                    self.source = MiniblockSource::Mempool;
+                    ProposedTx::Fallback
+                }
+            },
+            MiniblockSource::Mempool => ProposedTx::Fallback,
+            MiniblockSource::Pending(_) => unreachable!(),
+        }
+    }
+
+    /// Returns up to `limit` upcoming transactions from the proposal being executed.
+    pub fn upcoming_transactions(
+        &self,
+        limit: usize,
+        skip: &dyn Fn(&H256) -> bool,
+    ) -> Option<Vec<Transaction>> {
+        match &self.source {
+            MiniblockSource::External(transactions) => Some(
+                transactions
+                    .iter()
+                    .filter(|tx| !skip(&tx.hash()))
+                    .take(limit)
+                    .cloned()
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the current miniblock is built from a proposal.
+    pub fn is_executing_proposal(&self) -> bool {
+        matches!(self.source, MiniblockSource::External(_))
+    }
+
+    /// Checks whether all transactions from the proposal for the current miniblock were returned.
+    pub fn is_proposal_exhausted(&self) -> bool {
+        matches!(&self.source, MiniblockSource::External(transactions) if transactions.is_empty())
+    }
+
+    /// Returns an L2 transaction to the front of the proposal being executed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current miniblock is not built from a proposal.
+    pub fn rollback(&mut self, tx: Transaction) {
+        let MiniblockSource::External(transactions) = &mut self.source else {
+            panic!(
+                "cannot return transaction {:?} to proposal: no proposal is being executed",
+                tx.hash()
+            );
+        };
+        transactions.push_front(tx);
+    }
+
+    /// Resets the state after the current miniblock is sealed.
+    pub fn start_next_miniblock(&mut self) {
+        if !matches!(&self.source, MiniblockSource::External(transactions) if !transactions.is_empty())
+        {
+            self.source = MiniblockSource::Pending(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_keeper::tests::create_transaction;
+
+    fn proposal(miniblock_number: u32, tx_count: usize) -> ExternalBlockProposal {
+        ExternalBlockProposal {
+            miniblock_number: MiniblockNumber(miniblock_number),
+            transactions: (0..tx_count).map(|_| create_transaction(10, 100)).collect(),
+        }
+    }
+
+    #[test]
+    fn executing_proposals() {
+        let (proposals_sender, proposals_receiver) = proposals_channel();
+        let mut proposals = ExternalProposals::new(proposals_receiver, Duration::from_secs(60));
+        assert!(matches!(
+            proposals.next_transaction(MiniblockNumber(1)),
+            ProposedTx::Wait
+        ));
+
+        proposals_sender.try_send(proposal(0, 1)).unwrap(); // stale
+        proposals_sender.try_send(proposal(2, 1)).unwrap(); // future
+        proposals_sender.try_send(proposal(1, 2)).unwrap();
+        // The future proposal is superseded by the proposal for the current miniblock.
+        for _ in 0..2 {
+            assert!(matches!(
+                proposals.next_transaction(MiniblockNumber(1)),
+                ProposedTx::Next(_)
+            ));
+        }
+        assert!(proposals.is_proposal_exhausted());
+        proposals.start_next_miniblock();
+        assert!(matches!(
+            proposals.next_transaction(MiniblockNumber(2)),
+            ProposedTx::Wait
+        ));
+    }
+
+    #[test]
+    fn carrying_over_proposal_transactions() {
+        let (proposals_sender, proposals_receiver) = proposals_channel();
+        let mut proposals = ExternalProposals::new(proposals_receiver, Duration::from_secs(60));
+        proposals_sender.try_send(proposal(1, 2)).unwrap();
+        let ProposedTx::Next(tx) = proposals.next_transaction(MiniblockNumber(1)) else {
+            panic!("no transaction from proposal");
+        };
+        proposals.rollback(tx);
+        proposals.start_next_miniblock();
+
+        // The proposal for the next miniblock should be ignored.
+        proposals_sender.try_send(proposal(2, 5)).unwrap();
+        for _ in 0..2 {
+            assert!(matches!(
+                proposals.next_transaction(MiniblockNumber(2)),
+                ProposedTx::Next(_)
+            ));
+        }
+        assert!(proposals.is_proposal_exhausted());
+        assert!(matches!(
+            proposals.next_transaction(MiniblockNumber(2)),
+            ProposedTx::Fallback
+        ));
+    }
+
+    #[test]
+    fn falling_back_to_mempool() {
+        let (_proposals_sender, proposals_receiver) = proposals_channel();
+        let mut proposals = ExternalProposals::new(proposals_receiver, Duration::ZERO);
+        assert!(matches!(
+            proposals.next_transaction(MiniblockNumber(1)),
+            ProposedTx::Fallback
+        ));
+        assert!(!proposals.is_executing_proposal());
+    }
+
+    #[test]
+    fn validating_proposals() {
+        let (proposals_sender, _proposals_receiver) = proposals_channel();
+        let intake = ProposalIntake {
+            expected_auth_header: "Bearer secret".into(),
+            chain_id: L2ChainId::default(),
+            proposals_sender,
+        };
+        let request = BlockProposalRequest {
+            miniblock_number: MiniblockNumber(1),
+            transactions: vec![],
+        };
+        let err = intake.submit(&HeaderMap::new(), request).unwrap_err();
+        assert!(matches!(err, ProposalError::Unauthorized), "{err:?}");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        let request = BlockProposalRequest {
+            miniblock_number: MiniblockNumber(1),
+            transactions: vec![],
+        };
+        let err = intake.submit(&headers, request).unwrap_err();
+        assert!(matches!(err, ProposalError::Invalid(_)), "{err:?}");
+
+        let request = BlockProposalRequest {
+            miniblock_number: MiniblockNumber(1),
+            transactions: vec![Bytes(vec![1, 2, 3])],
+        };
+        let err = intake.submit(&headers, request).unwrap_err();
+        assert!(matches!(err, ProposalError::Invalid(_)), "{err:?}");
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     cmp,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    mem,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -10,21 +11,26 @@ use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv, SystemEnv},
     vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata,
 };
+use tokio::sync::watch;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
-    block::MiniblockHeader, protocol_version::ProtocolUpgradeTx,
+    block::MiniblockHeader, l2::L2Tx, protocol_version::ProtocolUpgradeTx,
     witness_block_state::WitnessBlockState, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, U256,
+    ProtocolVersionId, Transaction, H256, U256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
 use zksync_utils::time::millis_since_epoch;
 
 use crate::{
+    address_filter::AddressFilter,
+    api_server::tx_sender::validate_tx_fee_and_gas,
+    config_watcher::ConfigOverrides,
     l1_gas_price::L1GasPriceProvider,
     state_keeper::{
+        external_builder::{ExternalProposals, ProposedTx},
         extractors,
         io::{
             common::{l1_batch_params, load_pending_batch, poll_iters},
@@ -33,7 +39,7 @@ use crate::{
         mempool_actor::l2_tx_filter,
         metrics::KEEPER_METRICS,
         seal_criteria::{IoSealCriteria, TimeoutSealer},
-        updates::UpdatesManager,
+        updates::{PreInsertTxs, UpdatesManager},
         MempoolGuard,
     },
 };
@@ -49,12 +55,16 @@ pub(crate) struct MempoolIO<G> {
     object_store: Box<dyn ObjectStore>,
     timeout_sealer: TimeoutSealer,
     filter: L2TxFilter,
+    address_filter: AddressFilter,
     current_miniblock_number: MiniblockNumber,
     miniblock_sealer_handle: MiniblockSealerHandle,
     current_l1_batch_number: L1BatchNumber,
     fee_account: Address,
     fair_l2_gas_price: u64,
+    /// Runtime overrides for `fair_l2_gas_price`.
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     validation_computational_gas_limit: u32,
+    max_allowed_l2_tx_gas_limit: u32,
     delay_interval: Duration,
     // Used to keep track of gas prices to set accepted price per pubdata byte in blocks.
     l1_gas_price_provider: Arc<G>,
@@ -63,6 +73,17 @@ pub(crate) struct MempoolIO<G> {
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
+
+    max_l1_txs_per_miniblock: Option<usize>,
+    max_l1_txs_per_l1_batch: Option<usize>,
+    // Numbers of L1 transactions returned by the IO for the current miniblock / L1 batch (minus rolled back ones).
+    l1_txs_in_miniblock: usize,
+    l1_txs_in_l1_batch: usize,
+
+    external_proposals: Option<ExternalProposals>,
+    /// Hashes of transactions from the external proposal returned by the IO for the current miniblock
+    /// (minus rolled back and rejected ones).
+    proposed_tx_hashes: HashSet<H256>,
 }
 
 impl<G> IoSealCriteria for MempoolIO<G>
@@ -75,7 +96,13 @@ where
     }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
-        self.timeout_sealer.should_seal_miniblock(manager)
+        // A miniblock built from a proposal is sealed as soon as all proposed transactions are processed.
+        let is_proposal_exhausted = self
+            .external_proposals
+            .as_ref()
+            .map_or(false, ExternalProposals::is_proposal_exhausted);
+        (is_proposal_exhausted && !manager.miniblock.executed_transactions.is_empty())
+            || self.timeout_sealer.should_seal_miniblock(manager)
     }
 }
 
@@ -111,6 +138,13 @@ where
             self.chain_id,
         )
         .await?;
+        // Account for L1 transactions in the pending batch, so that the L1 transaction limit for the batch
+        // is respected after the restart.
+        self.l1_txs_in_l1_batch = pending_miniblocks
+            .iter()
+            .flat_map(|miniblock| &miniblock.txs)
+            .filter(|tx| tx.is_l1())
+            .count();
         // Initialize the filter for the transactions that come after the pending batch.
         // We use values from the pending block to match the filter with one used before the restart.
         let (base_fee, gas_per_pubdata) = derive_base_fee_and_gas_per_pubdata(
@@ -141,7 +175,8 @@ where
         for _ in 0..poll_iters(self.delay_interval, max_wait) {
             // We create a new filter each time, since parameters may change and a previously
             // ignored transaction in the mempool may be scheduled for the execution.
-            self.filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), self.fair_l2_gas_price);
+            let fair_l2_gas_price = self.fair_l2_gas_price();
+            self.filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), fair_l2_gas_price);
             // We only need to get the root hash when we're certain that we have a new transaction.
             if !self.mempool.has_next(&self.filter) {
                 tokio::time::sleep(self.delay_interval).await;
@@ -169,7 +204,7 @@ where
                 "(l1_gas_price, fair_l2_gas_price) for L1 batch #{} is ({}, {})",
                 self.current_l1_batch_number.0,
                 self.filter.l1_gas_price,
-                self.fair_l2_gas_price
+                fair_l2_gas_price
             );
             let mut storage = self.pool.access_storage().await.unwrap();
             let (base_system_contracts, protocol_version) = storage
@@ -183,7 +218,7 @@ where
                 current_timestamp,
                 prev_l1_batch_hash,
                 self.filter.l1_gas_price,
-                self.fair_l2_gas_price,
+                fair_l2_gas_price,
                 self.current_miniblock_number,
                 prev_miniblock_hash,
                 base_system_contracts,
@@ -222,9 +257,30 @@ where
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
         for _ in 0..poll_iters(self.delay_interval, max_wait) {
             let get_latency = KEEPER_METRICS.get_tx_from_mempool.start();
-            let res = self.mempool.next_transaction(&self.filter);
+            let res = self.next_transaction();
             get_latency.observe();
             if let Some(res) = res {
+                // The address filter may have changed after the transaction was accepted by the API server.
+                // L1 transactions cannot be rejected, so they are not filtered.
+                if !res.is_l1() {
+                    // Proposed transactions bypass the API server, so they are validated here.
+                    let reason = if self.proposed_tx_hashes.contains(&res.hash()) {
+                        self.validate_proposed_tx(&res).err()
+                    } else {
+                        None
+                    };
+                    let reason = reason.or_else(|| {
+                        self.address_filter
+                            .rejection_reason(res.initiator_account(), res.execute.contract_address)
+                    });
+                    if let Some(reason) = reason {
+                        self.reject(&res, &reason).await;
+                        continue;
+                    }
+                } else {
+                    self.l1_txs_in_miniblock += 1;
+                    self.l1_txs_in_l1_batch += 1;
+                }
                 return Some(res);
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -234,7 +290,42 @@ where
         None
     }
 
+    fn upcoming_transactions(
+        &self,
+        limit: usize,
+        skip: &dyn Fn(&H256) -> bool,
+    ) -> Vec<Transaction> {
+        let proposed_transactions = self
+            .external_proposals
+            .as_ref()
+            .and_then(|proposals| proposals.upcoming_transactions(limit, skip));
+        let mut transactions = proposed_transactions
+            .unwrap_or_else(|| self.mempool.peek_transactions(&self.filter, limit, skip));
+        transactions.retain(|tx| {
+            tx.is_l1()
+                || self
+                    .address_filter
+                    .rejection_reason(tx.initiator_account(), tx.execute.contract_address)
+                    .is_none()
+        });
+        transactions
+    }
+
     async fn rollback(&mut self, tx: Transaction) {
+        if tx.is_l1() {
+            self.l1_txs_in_miniblock = self.l1_txs_in_miniblock.saturating_sub(1);
+            self.l1_txs_in_l1_batch = self.l1_txs_in_l1_batch.saturating_sub(1);
+        } else if let Some(proposals) = self
+            .external_proposals
+            .as_mut()
+            .filter(|proposals| proposals.is_executing_proposal())
+        {
+            // A proposed transaction is returned to the proposal; the mempool nonce isn't reset
+            // since it's only advanced after the transaction is sealed.
+            self.proposed_tx_hashes.remove(&tx.hash());
+            proposals.rollback(tx);
+            return;
+        }
         // Reset nonces in the mempool.
         self.mempool.rollback(&tx);
         // Insert the transaction back.
@@ -248,8 +339,11 @@ where
             error
         );
 
-        // Reset the nonces in the mempool, but don't insert the transaction back.
-        self.mempool.rollback(rejected);
+        // Reset the nonces in the mempool, but don't insert the transaction back. Proposed transactions
+        // don't influence mempool nonces before they are sealed, so there's nothing to reset for them.
+        if !self.proposed_tx_hashes.remove(&rejected.hash()) {
+            self.mempool.rollback(rejected);
+        }
 
         // Mark tx as rejected in the storage.
         let mut storage = self
@@ -270,15 +364,34 @@ where
     }
 
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        // Proposed transactions are not stored in Postgres before execution, so they need to be inserted
+        // when sealing the miniblock.
+        let proposed_tx_hashes = mem::take(&mut self.proposed_tx_hashes);
+        let pre_insert_txs = if proposed_tx_hashes.is_empty() {
+            PreInsertTxs::None
+        } else {
+            // Mempool transactions with the same initiator and nonce as sealed proposed transactions
+            // can no longer be executed.
+            for tx in &updates_manager.miniblock.executed_transactions {
+                if proposed_tx_hashes.contains(&tx.hash) {
+                    self.mempool.skip_l2_transaction(&tx.transaction);
+                }
+            }
+            PreInsertTxs::Only(proposed_tx_hashes)
+        };
         let command = updates_manager.seal_miniblock_command(
             self.current_l1_batch_number,
             self.current_miniblock_number,
             self.l2_erc20_bridge_addr,
             None,
-            false,
+            pre_insert_txs,
         );
         self.miniblock_sealer_handle.submit(command).await;
         self.current_miniblock_number += 1;
+        self.l1_txs_in_miniblock = 0;
+        if let Some(proposals) = &mut self.external_proposals {
+            proposals.start_next_miniblock();
+        }
     }
 
     async fn seal_l1_batch(
@@ -330,6 +443,8 @@ where
             .await;
         self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
         self.current_l1_batch_number += 1;
+        self.l1_txs_in_miniblock = 0;
+        self.l1_txs_in_l1_batch = 0;
         Ok(())
     }
 
@@ -445,21 +560,117 @@ impl<G: L1GasPriceProvider> MempoolIO<G> {
             timeout_sealer: TimeoutSealer::new(config),
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
+            address_filter: AddressFilter::default(),
             current_l1_batch_number: last_sealed_l1_batch_header.number + 1,
             miniblock_sealer_handle,
             current_miniblock_number: last_miniblock_number + 1,
             fee_account: config.fee_account_addr,
             fair_l2_gas_price: config.fair_l2_gas_price,
+            config_overrides: None,
             validation_computational_gas_limit,
+            max_allowed_l2_tx_gas_limit: config.max_allowed_l2_tx_gas_limit,
             delay_interval,
             l1_gas_price_provider,
             l2_erc20_bridge_addr,
             chain_id,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
+            max_l1_txs_per_miniblock: config.max_l1_txs_per_miniblock,
+            max_l1_txs_per_l1_batch: config.max_l1_txs_per_l1_batch,
+            l1_txs_in_miniblock: 0,
+            l1_txs_in_l1_batch: 0,
+            external_proposals: None,
+            proposed_tx_hashes: HashSet::new(),
         }
     }
 
+    /// Checks whether L1 transactions can be added to the current miniblock without exceeding the configured limits.
+    fn l1_txs_allowed(&self) -> bool {
+        let within_miniblock_limit = self
+            .max_l1_txs_per_miniblock
+            .map_or(true, |limit| self.l1_txs_in_miniblock < limit);
+        let within_l1_batch_limit = self
+            .max_l1_txs_per_l1_batch
+            .map_or(true, |limit| self.l1_txs_in_l1_batch < limit);
+        within_miniblock_limit && within_l1_batch_limit
+    }
+
+    /// Makes the IO execute miniblocks proposed by an external block builder. L1 transactions are still taken
+    /// from the mempool, and are executed before proposed transactions.
+    pub(in crate::state_keeper) fn with_external_proposals(
+        mut self,
+        proposals: ExternalProposals,
+    ) -> Self {
+        self.external_proposals = Some(proposals);
+        self
+    }
+
+    fn next_transaction(&mut self) -> Option<Transaction> {
+        let l1_txs_allowed = self.l1_txs_allowed();
+        let Some(proposals) = &mut self.external_proposals else {
+            return if l1_txs_allowed {
+                self.mempool.next_transaction(&self.filter)
+            } else {
+                self.mempool.next_l2_transaction(&self.filter)
+            };
+        };
+
+        if l1_txs_allowed {
+            if let Some(tx) = self.mempool.next_l1_transaction() {
+                return Some(tx);
+            }
+        }
+        match proposals.next_transaction(self.current_miniblock_number) {
+            ProposedTx::Next(tx) => {
+                self.proposed_tx_hashes.insert(tx.hash());
+                Some(tx)
+            }
+            ProposedTx::Wait => None,
+            ProposedTx::Fallback => self.mempool.next_l2_transaction(&self.filter),
+        }
+    }
+
+    /// Validates a transaction proposed by the external block builder in the same way as the API server
+    /// validates submitted transactions, and checks it against the mempool filter.
+    fn validate_proposed_tx(&self, tx: &Transaction) -> Result<(), String> {
+        let tx = L2Tx::try_from(tx.clone()).map_err(|err| err.to_string())?;
+        if !self.filter.matches_fee(&tx.common_data.fee) {
+            return Err(format!(
+                "fee parameters don't match the current filter {:?}",
+                self.filter
+            ));
+        }
+        validate_tx_fee_and_gas(
+            &tx,
+            self.max_allowed_l2_tx_gas_limit,
+            self.fair_l2_gas_price(),
+            self.filter.l1_gas_price,
+        )
+        .map_err(|err| err.to_string())
+    }
+
+    /// Sets the address filter used to reject L2 transactions taken from the mempool.
+    pub(in crate::state_keeper) fn with_address_filter(mut self, filter: AddressFilter) -> Self {
+        self.address_filter = filter;
+        self
+    }
+
+    /// Makes the IO respect runtime overrides of the fair L2 gas price for new L1 batches.
+    pub(in crate::state_keeper) fn with_config_overrides(
+        mut self,
+        overrides: watch::Receiver<ConfigOverrides>,
+    ) -> Self {
+        self.config_overrides = Some(overrides);
+        self
+    }
+
+    fn fair_l2_gas_price(&self) -> u64 {
+        self.config_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.borrow().fair_l2_gas_price)
+            .unwrap_or(self.fair_l2_gas_price)
+    }
+
     async fn load_previous_l1_batch_hash(&self) -> U256 {
         tracing::info!(
             "Getting previous L1 batch hash for L1 batch #{}",
@@ -6,11 +6,12 @@ use std::{
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
 use tokio::sync::{mpsc, oneshot};
+use tracing::Instrument;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
     witness_block_state::WitnessBlockState, L1BatchNumber, MiniblockNumber, ProtocolVersionId,
-    Transaction,
+    Transaction, H256,
 };
 
 pub(crate) use self::mempool::MempoolIO;
@@ -86,6 +87,17 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     /// Blocks for up to `max_wait` until the next transaction is available for execution.
     /// Returns `None` if no transaction became available until the timeout.
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction>;
+    /// Returns up to `limit` transactions that are likely to be returned by the following calls
+    /// to [`Self::wait_for_next_tx()`], without removing them from the IO. Transactions with hashes
+    /// for which `skip` returns `true` (e.g., ones already pre-executed) are omitted. Used to optimistically
+    /// pre-execute transactions; the default implementation returns no transactions.
+    fn upcoming_transactions(
+        &self,
+        _limit: usize,
+        _skip: &dyn Fn(&H256) -> bool,
+    ) -> Vec<Transaction> {
+        Vec::new()
+    }
     /// Marks the transaction as "not executed", so it can be retrieved from the IO again.
     async fn rollback(&mut self, tx: Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
@@ -123,6 +135,9 @@ impl fmt::Debug for dyn StateKeeperIO {
 struct Completable<T> {
     command: T,
     completion_sender: oneshot::Sender<()>,
+    /// Span in which the command was submitted. Used as a parent for the span processing the command,
+    /// so that processing is traced as a part of the submitting operation.
+    span: tracing::Span,
 }
 
 /// Handle for [`MiniblockSealer`] allowing to submit [`MiniblockSealCommand`]s.
@@ -155,6 +170,7 @@ impl MiniblockSealerHandle {
         let command = Completable {
             command,
             completion_sender,
+            span: tracing::Span::current(),
         };
         self.commands_sender
             .send(command)
@@ -259,7 +275,14 @@ impl MiniblockSealer {
                 .access_storage_tagged("state_keeper")
                 .await
                 .unwrap();
-            completable.command.seal(&mut conn).await;
+            let command = &completable.command;
+            let span = tracing::info_span!(
+                parent: &completable.span,
+                "seal_miniblock",
+                miniblock = %command.miniblock_number,
+                l1_batch = %command.l1_batch_number
+            );
+            command.seal(&mut conn).instrument(span).await;
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
@@ -7,7 +7,10 @@ use std::{
 };
 
 use itertools::Itertools;
-use multivm::interface::{FinishedL1Batch, L1BatchEnv};
+use multivm::{
+    interface::{FinishedL1Batch, L1BatchEnv},
+    vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata,
+};
 use zksync_dal::{blocks_dal::ConsensusBlockFields, StorageProcessor};
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
@@ -35,7 +38,7 @@ use crate::{
     state_keeper::{
         extractors,
         metrics::{L1BatchSealStage, MiniblockSealStage, L1_BATCH_METRICS, MINIBLOCK_METRICS},
-        updates::{MiniblockSealCommand, UpdatesManager},
+        updates::{MiniblockSealCommand, PreInsertTxs, UpdatesManager},
     },
 };
 
@@ -43,6 +46,7 @@ impl UpdatesManager {
     /// Persists an L1 batch in the storage.
     /// This action includes a creation of an empty "fictive" miniblock that contains
     /// the events generated during the bootloader "tip phase".
+    #[tracing::instrument(skip_all, fields(l1_batch = %l1_batch_env.number))]
     pub(crate) async fn seal_l1_batch(
         mut self,
         storage: &mut StorageProcessor<'_>,
@@ -65,7 +69,7 @@ impl UpdatesManager {
             current_miniblock_number,
             l2_erc20_bridge_addr,
             consensus,
-            false, // fictive miniblocks don't have txs, so it's fine to pass `false` here.
+            PreInsertTxs::None, // fictive miniblocks don't have txs, so it's fine to pass `None` here.
         );
         miniblock_command.seal_inner(&mut transaction, true).await;
         progress.observe(None);
@@ -279,9 +283,15 @@ impl MiniblockSealCommand {
         self.assert_valid_miniblock(is_fictive);
 
         let mut transaction = storage.start_transaction().await.unwrap();
-        if self.pre_insert_txs {
+        if !matches!(self.pre_insert_txs, PreInsertTxs::None) {
             let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::PreInsertTxs, is_fictive);
-            for tx in &self.miniblock.executed_transactions {
+            let txs_to_insert: Vec<_> = self
+                .miniblock
+                .executed_transactions
+                .iter()
+                .filter(|tx| self.pre_insert_txs.contains(&tx.hash))
+                .collect();
+            for tx in &txs_to_insert {
                 if let Ok(l1_tx) = L1Tx::try_from(tx.transaction.clone()) {
                     let l1_block_number = L1BlockNumber(l1_tx.common_data.eth_block as u32);
                     transaction
@@ -305,7 +315,7 @@ impl MiniblockSealCommand {
                     unreachable!("Transaction {:?} is neither L1 nor L2", tx.transaction);
                 }
             }
-            progress.observe(Some(self.miniblock.executed_transactions.len()));
+            progress.observe(Some(txs_to_insert.len()));
         }
 
         let l1_batch_number = self.l1_batch_number;
@@ -325,6 +335,8 @@ impl MiniblockSealCommand {
             event_count = self.miniblock.events.len()
         );
 
+        let (_, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(self.l1_gas_price, self.fair_l2_gas_price);
         let miniblock_header = MiniblockHeader {
             number: miniblock_number,
             timestamp: self.miniblock.timestamp,
@@ -334,6 +346,7 @@ impl MiniblockSealCommand {
             base_fee_per_gas: self.base_fee_per_gas,
             l1_gas_price: self.l1_gas_price,
             l2_fair_gas_price: self.fair_l2_gas_price,
+            gas_per_pubdata: Some(gas_per_pubdata),
             base_system_contracts_hashes: self.base_system_contracts_hashes,
             protocol_version: self.protocol_version,
             virtual_blocks: self.miniblock.virtual_blocks,
@@ -609,10 +622,9 @@ impl MiniblockSealCommand {
             .observe(self.miniblock.executed_transactions.len());
         MINIBLOCK_METRICS.sealed_time.observe(started_at.elapsed());
 
-        let miniblock_latency =
-            ((millis_since_epoch() - self.miniblock.timestamp as u128 * 1_000) as f64) / 1_000.0;
+        let miniblock_latency = Duration::from_millis(self.miniblock.millis_since_opened());
         let stage = &MiniblockStage::Sealed;
-        APP_METRICS.miniblock_latency[stage].observe(Duration::from_secs_f64(miniblock_latency));
+        APP_METRICS.miniblock_latency[stage].observe(miniblock_latency);
         APP_METRICS.miniblock_number[stage].set(miniblock_number.0.into());
         APP_METRICS.miniblock_virtual_block_number[stage].set(latest_virtual_block_number);
 
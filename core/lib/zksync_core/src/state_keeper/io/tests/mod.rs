@@ -2,24 +2,32 @@ use std::time::Duration;
 
 use futures::FutureExt;
 use multivm::vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, AccountTreeId, Address, L1BatchNumber,
-    MiniblockNumber, ProtocolVersionId, StorageKey, VmEvent, H256, U256,
+    block::BlockGasCount,
+    fee::Fee,
+    l1::{L1Tx, OpProcessingType, PriorityQueueType},
+    l2::L2Tx,
+    tx::ExecutionMetrics,
+    AccountTreeId, Address, Execute, L1BatchNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
+    Nonce, PaymasterParams, PriorityOpId, ProtocolVersionId, StorageKey, Transaction, VmEvent,
+    H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
 use self::tester::Tester;
 use crate::state_keeper::{
+    external_builder::{proposals_channel, ExternalBlockProposal, ExternalProposals},
     io::{MiniblockParams, MiniblockSealer, StateKeeperIO},
     mempool_actor::l2_tx_filter,
     tests::{
         create_execution_result, create_l1_batch_metadata, create_transaction,
         create_updates_manager, default_l1_batch_env, default_vm_block_result, Query,
     },
-    updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
+    updates::{MiniblockSealCommand, MiniblockUpdates, PreInsertTxs, UpdatesManager},
 };
 
 mod tester;
@@ -241,7 +249,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
         protocol_version: Some(ProtocolVersionId::latest()),
         l2_erc20_bridge_addr: Address::default(),
         consensus: None,
-        pre_insert_txs: false,
+        pre_insert_txs: PreInsertTxs::None,
     };
     let mut conn = connection_pool
         .access_storage_tagged("state_keeper")
@@ -318,7 +326,7 @@ async fn processing_events_when_sealing_miniblock() {
         protocol_version: Some(ProtocolVersionId::latest()),
         l2_erc20_bridge_addr: Address::default(),
         consensus: None,
-        pre_insert_txs: false,
+        pre_insert_txs: PreInsertTxs::None,
     };
     let mut conn = pool.access_storage_tagged("state_keeper").await.unwrap();
     conn.protocol_versions_dal()
@@ -433,7 +441,7 @@ async fn miniblock_sealer_handle_blocking() {
         MiniblockNumber(1),
         Address::default(),
         None,
-        false,
+        PreInsertTxs::None,
     );
     sealer_handle.submit(seal_command).await;
 
@@ -443,7 +451,7 @@ async fn miniblock_sealer_handle_blocking() {
         MiniblockNumber(2),
         Address::default(),
         None,
-        false,
+        PreInsertTxs::None,
     );
     {
         let submit_future = sealer_handle.submit(seal_command);
@@ -473,7 +481,7 @@ async fn miniblock_sealer_handle_blocking() {
         MiniblockNumber(3),
         Address::default(),
         None,
-        false,
+        PreInsertTxs::None,
     );
     sealer_handle.submit(seal_command).await;
     let command = sealer.commands_receiver.recv().await.unwrap();
@@ -494,7 +502,7 @@ async fn miniblock_sealer_handle_parallel_processing() {
             MiniblockNumber(i),
             Address::default(),
             None,
-            false,
+            PreInsertTxs::None,
         );
         sealer_handle.submit(seal_command).await;
     }
@@ -527,3 +535,230 @@ async fn different_timestamp_for_miniblocks_in_same_batch() {
         .unwrap();
     assert!(next_timestamp > current_timestamp);
 }
+
+/// Creates an L2 transaction with zero nonce signed by the specified key.
+fn create_signed_transaction(private_key: &H256, fee_per_gas: u64) -> Transaction {
+    let fee = Fee {
+        gas_limit: 10_000_000_u64.into(),
+        max_fee_per_gas: fee_per_gas.into(),
+        max_priority_fee_per_gas: 0_u64.into(),
+        gas_per_pubdata_limit: 800_u64.into(),
+    };
+    let mut tx = L2Tx::new_signed(
+        Address::random(),
+        vec![],
+        Nonce(0),
+        fee,
+        U256::zero(),
+        L2ChainId::from(270),
+        private_key,
+        None,
+        PaymasterParams::default(),
+    )
+    .unwrap();
+    tx.set_input(H256::random().0.to_vec(), H256::random());
+    tx.into()
+}
+
+#[tokio::test]
+async fn rejected_proposed_tx_does_not_drop_mempool_txs() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let (io, mut mempool) = tester.create_test_mempool_io(pool.clone(), 0).await;
+    let (proposals_sender, proposals_receiver) = proposals_channel();
+    let mut io =
+        io.with_external_proposals(ExternalProposals::new(proposals_receiver, Duration::ZERO));
+
+    let private_key = H256::repeat_byte(1);
+    let mempool_tx = create_signed_transaction(&private_key, tester.fair_l2_gas_price());
+    mempool.insert(vec![mempool_tx.clone()], Default::default());
+    // The proposed transaction has the same initiator and nonce as the mempool one, but it fails validation
+    // since its max fee is lower than the fair L2 gas price.
+    let proposed_tx = create_signed_transaction(&private_key, tester.fair_l2_gas_price() - 1);
+    proposals_sender
+        .try_send(ExternalBlockProposal {
+            miniblock_number: io.current_miniblock_number(),
+            transactions: vec![proposed_tx.clone()],
+        })
+        .unwrap();
+
+    // The rejected proposal is exhausted, so the IO falls back to the mempool.
+    let tx = io.wait_for_next_tx(Duration::from_secs(10)).await.unwrap();
+    assert_eq!(tx.hash(), mempool_tx.hash());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let proposed_tx_details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(proposed_tx.hash())
+        .await
+        .unwrap();
+    assert!(proposed_tx_details.is_none());
+}
+
+#[tokio::test]
+async fn sealing_miniblock_with_proposed_tx() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let (io, mut mempool) = tester.create_test_mempool_io(pool.clone(), 0).await;
+    let (proposals_sender, proposals_receiver) = proposals_channel();
+    let mut io =
+        io.with_external_proposals(ExternalProposals::new(proposals_receiver, Duration::ZERO));
+
+    let private_key = H256::repeat_byte(1);
+    let mempool_tx = create_signed_transaction(&private_key, tester.fair_l2_gas_price());
+    mempool.insert(vec![mempool_tx.clone()], Default::default());
+    let proposed_tx = create_signed_transaction(&private_key, tester.fair_l2_gas_price());
+    proposals_sender
+        .try_send(ExternalBlockProposal {
+            miniblock_number: io.current_miniblock_number(),
+            transactions: vec![proposed_tx.clone()],
+        })
+        .unwrap();
+
+    let tx = io.wait_for_next_tx(Duration::from_secs(10)).await.unwrap();
+    assert_eq!(tx.hash(), proposed_tx.hash());
+
+    let l1_batch_env = default_l1_batch_env(1, 1, Address::random());
+    let mut updates = UpdatesManager::new(
+        l1_batch_env,
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    updates.extend_from_executed_transaction(
+        tx,
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    io.seal_miniblock(&updates).await;
+
+    // The proposed transaction must be inserted to Postgres when sealing the miniblock.
+    let mut storage = pool.access_storage().await.unwrap();
+    let proposed_tx_details = storage
+        .transactions_web3_dal()
+        .get_transaction_details(proposed_tx.hash())
+        .await
+        .unwrap();
+    assert!(proposed_tx_details.is_some());
+    drop(storage);
+
+    // The mempool transaction with the same initiator and nonce can no longer be executed.
+    let tx = io.wait_for_next_tx(Duration::from_secs(1)).await;
+    assert!(tx.is_none(), "{tx:?}");
+}
+
+fn create_l1_transaction(serial_id: u64) -> Transaction {
+    let tx = L1Tx {
+        execute: Execute {
+            contract_address: Address::repeat_byte(0x11),
+            calldata: vec![1, 2, 3],
+            factory_deps: None,
+            value: U256::zero(),
+        },
+        common_data: L1TxCommonData {
+            serial_id: PriorityOpId(serial_id),
+            sender: Address::repeat_byte(1),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 1,
+            gas_limit: Default::default(),
+            max_fee_per_gas: Default::default(),
+            gas_per_pubdata_limit: 1_u32.into(),
+            full_fee: Default::default(),
+            layer_2_tip_fee: Default::default(),
+            refund_recipient: Address::zero(),
+            to_mint: Default::default(),
+            priority_queue_type: PriorityQueueType::Deque,
+            op_processing_type: OpProcessingType::Common,
+            canonical_tx_hash: H256::from_low_u64_be(serial_id),
+        },
+        received_timestamp_ms: 0,
+    };
+    tx.into()
+}
+
+#[tokio::test]
+async fn l1_tx_limits_are_reset_on_miniblock_and_l1_batch_boundaries() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    // Save metadata for the genesis L1 batch so that we don't hang in `seal_l1_batch`.
+    let metadata = create_l1_batch_metadata(0);
+    storage
+        .blocks_dal()
+        .save_l1_batch_metadata(L1BatchNumber(0), &metadata, H256::zero(), false)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let config = StateKeeperConfig {
+        max_l1_txs_per_miniblock: Some(1),
+        max_l1_txs_per_l1_batch: Some(2),
+        ..tester.state_keeper_config()
+    };
+    let (mut io, mut mempool) = tester
+        .create_test_mempool_io_with_config(pool.clone(), 1, &config)
+        .await;
+    let l1_txs: Vec<_> = (0..3).map(create_l1_transaction).collect();
+    mempool.insert(l1_txs.clone(), Default::default());
+
+    let fee_account = Address::random();
+    let mut updates = UpdatesManager::new(
+        default_l1_batch_env(1, 1, fee_account),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    let max_wait = Duration::from_millis(10);
+
+    let tx = io.wait_for_next_tx(max_wait).await.unwrap();
+    assert_eq!(tx.hash(), l1_txs[0].hash());
+    // The miniblock limit is reached.
+    let tx = io.wait_for_next_tx(max_wait).await;
+    assert!(tx.is_none(), "{tx:?}");
+
+    // Sealed miniblocks must contain at least one transaction.
+    updates.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    io.seal_miniblock(&updates).await;
+    updates.push_miniblock(MiniblockParams {
+        timestamp: 2,
+        virtual_blocks: 1,
+    });
+    let tx = io.wait_for_next_tx(max_wait).await.unwrap();
+    assert_eq!(tx.hash(), l1_txs[1].hash());
+
+    updates.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        create_execution_result(1, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    io.seal_miniblock(&updates).await;
+    updates.push_miniblock(MiniblockParams {
+        timestamp: 3,
+        virtual_blocks: 1,
+    });
+    // The L1 batch limit is reached even though the miniblock counter is reset.
+    let tx = io.wait_for_next_tx(max_wait).await;
+    assert!(tx.is_none(), "{tx:?}");
+
+    let l1_batch_env = default_l1_batch_env(1, 1, fee_account);
+    io.seal_l1_batch(None, updates, &l1_batch_env, default_vm_block_result())
+        .await
+        .unwrap();
+    let tx = io.wait_for_next_tx(max_wait).await.unwrap();
+    assert_eq!(tx.hash(), l1_txs[2].hash());
+}
@@ -3,7 +3,13 @@
 use std::{sync::Arc, time::Duration};
 
 use multivm::vm_latest::constants::BLOCK_GAS_LIMIT;
-use zksync_config::{configs::chain::StateKeeperConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::{
+        chain::{MempoolOrderingPolicy, StateKeeperConfig},
+        eth_sender::BaseFeeSmoothing,
+    },
+    GasAdjusterConfig,
+};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
 use zksync_eth_client::clients::mock::MockEthereum;
@@ -49,6 +55,9 @@ impl Tester {
             internal_enforced_l1_gas_price: None,
             poll_period: 10,
             max_l1_gas_price: None,
+            base_fee_smoothing: BaseFeeSmoothing::MovingMedian,
+            base_fee_ewma_alpha: None,
+            base_fee_percentile: None,
         };
 
         GasAdjuster::new(eth_client, gas_adjuster_config)
@@ -61,23 +70,38 @@ impl Tester {
         100
     }
 
+    pub(super) fn state_keeper_config(&self) -> StateKeeperConfig {
+        StateKeeperConfig {
+            fair_l2_gas_price: self.fair_l2_gas_price(),
+            max_allowed_l2_tx_gas_limit: u32::MAX,
+            virtual_blocks_interval: 1,
+            virtual_blocks_per_miniblock: 1,
+            ..StateKeeperConfig::default()
+        }
+    }
+
     pub(super) async fn create_test_mempool_io(
         &self,
         pool: ConnectionPool,
         miniblock_sealer_capacity: usize,
+    ) -> (MempoolIO<GasAdjuster<MockEthereum>>, MempoolGuard) {
+        let config = self.state_keeper_config();
+        self.create_test_mempool_io_with_config(pool, miniblock_sealer_capacity, &config)
+            .await
+    }
+
+    pub(super) async fn create_test_mempool_io_with_config(
+        &self,
+        pool: ConnectionPool,
+        miniblock_sealer_capacity: usize,
+        config: &StateKeeperConfig,
     ) -> (MempoolIO<GasAdjuster<MockEthereum>>, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
-        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100, MempoolOrderingPolicy::default());
         let (miniblock_sealer, miniblock_sealer_handle) =
             MiniblockSealer::new(pool.clone(), miniblock_sealer_capacity);
         tokio::spawn(miniblock_sealer.run());
 
-        let config = StateKeeperConfig {
-            fair_l2_gas_price: self.fair_l2_gas_price(),
-            virtual_blocks_interval: 1,
-            virtual_blocks_per_miniblock: 1,
-            ..StateKeeperConfig::default()
-        };
         let object_store = ObjectStoreFactory::mock().create_store().await;
         let l2_erc20_bridge_addr = Address::repeat_byte(0x5a); // Isn't relevant.
         let io = MempoolIO::new(
@@ -86,7 +110,7 @@ impl Tester {
             miniblock_sealer_handle,
             gas_adjuster,
             pool,
-            &config,
+            config,
             Duration::from_secs(1),
             l2_erc20_bridge_addr,
             BLOCK_GAS_LIMIT,
@@ -113,6 +137,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 L1VerifierConfig::default(),
                 Address::zero(),
+                None,
             )
             .await;
         }
@@ -138,6 +163,7 @@ impl Tester {
                 base_fee_per_gas,
                 l1_gas_price,
                 l2_fair_gas_price,
+                gas_per_pubdata: None,
                 base_system_contracts_hashes: self.base_system_contracts.hashes(),
                 protocol_version: Some(ProtocolVersionId::latest()),
                 virtual_blocks: 0,
@@ -5,10 +5,11 @@ use std::{
 
 use anyhow::Context as _;
 use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 use zksync_types::{
-    block::MiniblockExecutionData, l2::TransactionType, protocol_version::ProtocolUpgradeTx,
-    storage_writes_deduplicator::StorageWritesDeduplicator, Transaction,
+    api::TransactionSoftConfirmation, block::MiniblockExecutionData, l2::TransactionType,
+    protocol_version::ProtocolUpgradeTx, storage_writes_deduplicator::StorageWritesDeduplicator,
+    L1BatchNumber, Transaction, H256, U256,
 };
 
 use super::{
@@ -20,7 +21,7 @@ use super::{
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
 };
-use crate::gas_tracker::gas_count_from_writes;
+use crate::{gas_tracker::gas_count_from_writes, sponsored_txs::is_paid_by_paymaster};
 
 /// Amount of time to block on waiting for some resource. The exact value is not really important,
 /// we only need it to not block on waiting indefinitely and be able to process cancellation requests.
@@ -59,6 +60,7 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn L1BatchExecutorBuilder>,
     sealer: Option<ConditionalSealer>,
+    soft_confirmations: Option<broadcast::Sender<TransactionSoftConfirmation>>,
 }
 
 impl ZkSyncStateKeeper {
@@ -73,6 +75,7 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer: Some(sealer),
+            soft_confirmations: None,
         }
     }
 
@@ -86,9 +89,20 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer: None,
+            soft_confirmations: None,
         }
     }
 
+    /// Enables broadcasting soft confirmations for transactions included into the pending miniblock.
+    /// Confirmations are sent right after a transaction is executed, before the miniblock is sealed.
+    pub fn with_soft_confirmations(
+        mut self,
+        sender: broadcast::Sender<TransactionSoftConfirmation>,
+    ) -> Self {
+        self.soft_confirmations = Some(sender);
+        self
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         match self.run_inner().await {
             Ok(_) => unreachable!(),
@@ -101,6 +115,34 @@ impl ZkSyncStateKeeper {
     }
 
     /// Fallible version of `run` routine that allows to easily exit upon cancellation.
+    /// Reports resources spent by the operator on sponsored transactions in the L1 batch being sealed.
+    fn report_sponsored_spending(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        updates_manager: &UpdatesManager,
+    ) {
+        let Some(paymaster) = self
+            .sealer
+            .as_ref()
+            .and_then(ConditionalSealer::sponsor_paymaster)
+        else {
+            return;
+        };
+        let spending = updates_manager.pending_paymaster_spending(paymaster);
+        let fee = spending.fee(updates_manager.base_fee_per_gas());
+        L1_BATCH_METRICS.sponsored_txs.observe(spending.tx_count);
+        L1_BATCH_METRICS.sponsored_gas.observe(spending.gas_used);
+        L1_BATCH_METRICS
+            .sponsored_fees_gwei
+            .inc_by((fee / U256::exp10(9)).low_u64());
+        tracing::debug!(
+            "L1 batch #{l1_batch_number} contains {} sponsored txs using {} gas; \
+             fees paid by sponsor paymaster {paymaster:?}: {fee} wei",
+            spending.tx_count,
+            spending.gas_used
+        );
+    }
+
     async fn run_inner(&mut self) -> Result<Infallible, Error> {
         tracing::info!(
             "Starting state keeper. Next l1 batch to seal: {}, Next miniblock to seal: {}",
@@ -200,6 +242,7 @@ impl ZkSyncStateKeeper {
             }
             let (finished_batch, witness_block_state) = batch_executor.finish_batch().await;
             let sealed_batch_protocol_version = updates_manager.protocol_version();
+            self.report_sponsored_spending(l1_batch_env.number, &updates_manager);
             self.io
                 .seal_l1_batch(
                     witness_block_state,
@@ -372,6 +415,7 @@ impl ZkSyncStateKeeper {
         Ok(())
     }
 
+    #[tracing::instrument(skip_all, fields(l1_batch = %self.io.current_l1_batch_number()))]
     async fn process_l1_batch(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -425,6 +469,12 @@ impl ZkSyncStateKeeper {
             };
             waiting_latency.observe();
 
+            if let Some(lookahead) = batch_executor.optimistic_execution_lookahead() {
+                let is_pre_executed = |tx_hash: &H256| batch_executor.is_pre_executed(tx_hash);
+                let upcoming_txs = self.io.upcoming_transactions(lookahead, &is_pre_executed);
+                batch_executor.pre_execute_txs(upcoming_txs);
+            }
+
             let tx_hash = tx.hash();
             let (seal_resolution, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
@@ -448,6 +498,7 @@ impl ZkSyncStateKeeper {
                         l1_gas: tx_l1_gas_this_tx,
                         execution_metrics: tx_execution_metrics,
                     } = tx_metrics;
+                    let first_event_index = updates_manager.miniblock.events.len();
                     updates_manager.extend_from_executed_transaction(
                         tx,
                         *tx_result,
@@ -456,6 +507,7 @@ impl ZkSyncStateKeeper {
                         tx_execution_metrics,
                         call_tracer_result,
                     );
+                    self.send_soft_confirmation(updates_manager, first_event_index);
                 }
                 SealResolution::ExcludeAndSeal => {
                     batch_executor.rollback_last_tx().await;
@@ -479,6 +531,19 @@ impl ZkSyncStateKeeper {
         Err(Error::Canceled)
     }
 
+    fn send_soft_confirmation(&self, updates_manager: &UpdatesManager, first_event_index: usize) {
+        let Some(sender) = &self.soft_confirmations else {
+            return;
+        };
+        let confirmation = updates_manager
+            .miniblock
+            .last_tx_soft_confirmation(self.io.current_l1_batch_number(), first_event_index);
+        if let Some(confirmation) = confirmation {
+            // Errors only if there are no receivers, which is fine.
+            sender.send(confirmation).ok();
+        }
+    }
+
     async fn process_upgrade_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -547,6 +612,7 @@ impl ZkSyncStateKeeper {
     /// 2. Seal manager decided that batch is ready to be sealed.
     /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
     /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
+    #[tracing::instrument(skip_all, fields(tx_hash = ?tx.hash()))]
     async fn process_one_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -634,11 +700,28 @@ impl ZkSyncStateKeeper {
                     gas_count_from_writes(&tx_writes_metrics, updates_manager.protocol_version());
                 let tx_gas_excluding_writes = tx_l1_gas_this_tx + finish_block_l1_gas;
 
+                let sponsor_paymaster = self
+                    .sealer
+                    .as_ref()
+                    .and_then(ConditionalSealer::sponsor_paymaster);
+                let tx_sponsored_gas = match sponsor_paymaster {
+                    Some(paymaster) if is_paid_by_paymaster(&tx, paymaster) => {
+                        tx_execution_metrics.gas_used
+                    }
+                    _ => 0,
+                };
+                let pending_sponsored_gas = sponsor_paymaster.map_or(0, |paymaster| {
+                    updates_manager
+                        .pending_paymaster_spending(paymaster)
+                        .gas_used
+                });
+
                 let tx_data = SealData {
                     execution_metrics: tx_execution_metrics + finish_block_execution_metrics,
                     gas_count: tx_gas_excluding_writes + tx_writes_l1_gas,
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
+                    sponsored_gas: tx_sponsored_gas,
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -649,6 +732,7 @@ impl ZkSyncStateKeeper {
                     cumulative_size: tx_data.cumulative_size
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
+                    sponsored_gas: tx_sponsored_gas + pending_sponsored_gas,
                 };
 
                 if let Some(sealer) = &self.sealer {
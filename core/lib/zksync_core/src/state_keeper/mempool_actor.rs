@@ -1,13 +1,24 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use multivm::vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata;
 use tokio::sync::watch;
 use zksync_config::configs::chain::MempoolConfig;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_mempool::L2TxFilter;
+use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
-use crate::l1_gas_price::L1GasPriceProvider;
+use super::{
+    metrics::{MempoolEvictionReason, KEEPER_METRICS},
+    types::MempoolGuard,
+};
+use crate::{config_watcher::ConfigOverrides, l1_gas_price::L1GasPriceProvider};
+
+/// Interval between checks for expired L2 transactions.
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
 /// The filter is used to filter out transactions from the mempool that do not cover expenses
@@ -33,6 +44,8 @@ pub struct MempoolFetcher<G> {
     l1_gas_price_provider: Arc<G>,
     sync_interval: Duration,
     sync_batch_size: usize,
+    default_tx_ttl: Option<Duration>,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
 }
 
 impl<G: L1GasPriceProvider> MempoolFetcher<G> {
@@ -46,9 +59,66 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             l1_gas_price_provider,
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
+            default_tx_ttl: config.default_tx_ttl(),
+            config_overrides: None,
         }
     }
 
+    /// Makes the fetcher respect runtime overrides of the fair L2 gas price when filtering transactions.
+    pub fn with_config_overrides(mut self, overrides: watch::Receiver<ConfigOverrides>) -> Self {
+        self.config_overrides = Some(overrides);
+        self
+    }
+
+    /// Returns the time elapsed since the last miniblock was sealed, i.e., for how long the state keeper has been
+    /// stopped if the mempool is being started after a restart.
+    async fn downtime(storage: &mut StorageProcessor<'_>) -> anyhow::Result<Duration> {
+        let last_miniblock = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("failed getting last sealed miniblock")?;
+        let last_miniblock_timestamp = last_miniblock.map_or(0, |header| header.timestamp);
+        Ok(Duration::from_secs(
+            seconds_since_epoch().saturating_sub(last_miniblock_timestamp),
+        ))
+    }
+
+    /// Restores the mempool after a restart. Pending L2 transactions are persisted in Postgres together with
+    /// their receipt time, so they are reloaded by the subsequent mempool syncs in the original order.
+    async fn restore(
+        storage: &mut StorageProcessor<'_>,
+        remove_stuck_txs: bool,
+        stuck_tx_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        if remove_stuck_txs {
+            // Transactions cannot get executed while the state keeper is stopped, so the downtime
+            // is not counted towards the stuck transaction timeout.
+            let downtime = Self::downtime(storage).await?;
+            let removed_txs = storage
+                .transactions_dal()
+                .remove_stuck_txs(stuck_tx_timeout + downtime)
+                .await;
+            KEEPER_METRICS.mempool_removed_stuck_txs.set(removed_txs);
+            if removed_txs > 0 {
+                tracing::warn!(
+                    "Removed {removed_txs} L2 transactions stuck for more than {stuck_tx_timeout:?} \
+                     (not counting {downtime:?} of downtime)"
+                );
+            }
+        }
+        storage.transactions_dal().reset_mempool().await;
+
+        let restored_txs = storage
+            .transactions_dal()
+            .get_pending_l2_txs_count()
+            .await
+            .context("failed getting number of pending L2 transactions")?;
+        KEEPER_METRICS.mempool_restored_txs.set(restored_txs);
+        tracing::info!("Restoring {restored_txs} pending L2 transactions in the mempool");
+        Ok(())
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
@@ -59,16 +129,10 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
     ) -> anyhow::Result<()> {
         {
             let mut storage = pool.access_storage_tagged("state_keeper").await.unwrap();
-            if remove_stuck_txs {
-                let removed_txs = storage
-                    .transactions_dal()
-                    .remove_stuck_txs(stuck_tx_timeout)
-                    .await;
-                tracing::info!("Number of stuck txs was removed: {}", removed_txs);
-            }
-            storage.transactions_dal().reset_mempool().await;
+            Self::restore(&mut storage, remove_stuck_txs, stuck_tx_timeout).await?;
         }
 
+        let mut last_expiration_check: Option<Instant> = None;
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("Stop signal received, mempool is shutting down");
@@ -76,14 +140,46 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             }
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = pool.access_storage_tagged("state_keeper").await.unwrap();
+            if last_expiration_check.map_or(true, |at| at.elapsed() >= EXPIRATION_CHECK_INTERVAL) {
+                last_expiration_check = Some(Instant::now());
+                let expired_txs = storage
+                    .transactions_dal()
+                    .mark_expired_txs(self.default_tx_ttl)
+                    .await;
+                for (account, nonce) in expired_txs {
+                    if let Some(hash) = self.mempool.remove_l2_transaction(account, nonce) {
+                        tracing::debug!(
+                            "L2 transaction {hash:?} expired and was removed from mempool"
+                        );
+                    }
+                    KEEPER_METRICS.mempool_expired_txs.inc();
+                }
+            }
             let mempool_info = self.mempool.get_mempool_info();
-            let l2_tx_filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), fair_l2_gas_price);
+            let current_fair_l2_gas_price = self
+                .config_overrides
+                .as_ref()
+                .and_then(|overrides| overrides.borrow().fair_l2_gas_price)
+                .unwrap_or(fair_l2_gas_price);
+            let l2_tx_filter = l2_tx_filter(
+                self.l1_gas_price_provider.as_ref(),
+                current_fair_l2_gas_price,
+            );
+            for &(_, reason) in &mempool_info.evicted_transactions {
+                KEEPER_METRICS.mempool_evictions[&MempoolEvictionReason::from(reason)].inc();
+            }
+            let evicted_txs = mempool_info
+                .evicted_transactions
+                .into_iter()
+                .map(|(hash, _)| hash)
+                .collect();
 
             let (transactions, nonces) = storage
                 .transactions_dal()
                 .sync_mempool(
                     mempool_info.stashed_accounts,
                     mempool_info.purged_accounts,
+                    evicted_txs,
                     l2_tx_filter.gas_per_pubdata,
                     l2_tx_filter.fee_per_gas,
                     self.sync_batch_size,
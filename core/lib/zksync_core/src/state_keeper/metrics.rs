@@ -9,7 +9,7 @@ use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
     Metrics,
 };
-use zksync_mempool::MempoolStore;
+use zksync_mempool::{EvictionReason, MempoolStore};
 
 use super::seal_criteria::SealResolution;
 use crate::metrics::InteractionType;
@@ -31,6 +31,46 @@ pub(crate) enum TxExecutionStage {
     DryRunRollback,
 }
 
+/// Outcome for a transaction that does not fit into the pubdata budget of the current L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(crate) enum OversizedPubdataOutcome {
+    /// The transaction exceeds the pubdata budget of a whole L1 batch and is rejected.
+    Rejected,
+    /// The transaction only exceeds the remaining budget; the batch is sealed, and the transaction
+    /// is retried in the next batch.
+    SealedAndRetried,
+}
+
+/// Reason of evicting a transaction from the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum MempoolEvictionReason {
+    AccountQuota,
+    Capacity,
+}
+
+impl From<EvictionReason> for MempoolEvictionReason {
+    fn from(reason: EvictionReason) -> Self {
+        match reason {
+            EvictionReason::AccountQuota => Self::AccountQuota,
+            EvictionReason::Capacity => Self::Capacity,
+        }
+    }
+}
+
+/// Outcome of a miniblock proposal submitted by an external block builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(crate) enum ExternalProposalOutcome {
+    /// The proposal failed authentication or validation, or could not be enqueued.
+    Rejected,
+    /// The proposal was executed by the state keeper.
+    Executed,
+    /// The proposal was superseded by another proposal, or arrived after its miniblock was started.
+    Stale,
+}
+
 /// General-purpose state keeper metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper")]
@@ -57,6 +97,21 @@ pub(crate) struct StateKeeperMetrics {
     pub tx_execution_time: Family<TxExecutionStage, Histogram<Duration>>,
     /// Number of times gas price was reported as too high.
     pub gas_price_too_high: Counter,
+    /// Number of transactions processed by the state keeper exceeding the pubdata budget of an L1 batch,
+    /// grouped by the outcome.
+    pub oversized_pubdata_txs: Family<OversizedPubdataOutcome, Counter>,
+    /// Number of L2 transactions evicted from the mempool, grouped by the eviction reason.
+    pub mempool_evictions: Family<MempoolEvictionReason, Counter>,
+    /// Number of pending L2 transactions dropped from the mempool after their validity deadline.
+    pub mempool_expired_txs: Counter,
+    /// Number of pending L2 transactions restored from Postgres when the mempool was started.
+    pub mempool_restored_txs: Gauge<usize>,
+    /// Number of stuck L2 transactions removed when the mempool was started.
+    pub mempool_removed_stuck_txs: Gauge<usize>,
+    /// Number of miniblock proposals submitted by the external block builder, grouped by the outcome.
+    pub external_proposals: Family<ExternalProposalOutcome, Counter>,
+    /// Number of miniblocks built from the mempool because the external block builder didn't propose them in time.
+    pub external_builder_fallbacks: Counter,
 }
 
 #[vise::register]
@@ -194,6 +249,14 @@ pub(crate) struct L1BatchMetrics {
     /// Number of transactions in a single L1 batch.
     #[metrics(buckets = COUNT_BUCKETS)]
     pub transactions_in_l1_batch: Histogram<usize>,
+    /// Number of transactions paid for by the sponsor paymaster in a single L1 batch.
+    #[metrics(buckets = COUNT_BUCKETS)]
+    pub sponsored_txs: Histogram<usize>,
+    /// Gas used by transactions paid for by the sponsor paymaster in a single L1 batch.
+    #[metrics(buckets = Buckets::exponential(1_000.0..=1_000_000_000.0, 10.0))]
+    pub sponsored_gas: Histogram<usize>,
+    /// Total fees paid by the sponsor paymaster, in gwei.
+    pub sponsored_fees_gwei: Counter,
     /// Total latency of sealing an L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub sealed_time: Histogram<Duration>,
@@ -349,6 +412,26 @@ pub(super) enum ExecutorCommand {
     FinishBatch,
 }
 
+/// Outcome of executing a transaction in the batch executor with optimistic execution enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum OptimisticExecutionOutcome {
+    /// The transaction was pre-executed before being executed in the main VM.
+    PreExecuted,
+    /// The transaction was not pre-executed (e.g., pre-execution didn't catch up).
+    Missed,
+}
+
+/// Kind of storage reads collected during optimistic pre-execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum PreExecutedReadKind {
+    /// Value was used to warm up the storage cache of the main VM.
+    Reused,
+    /// Value is shadowed by a change made in the current L1 batch, so it is not used by the main VM.
+    Conflicting,
+}
+
 const GAS_PER_NANOSECOND_BUCKETS: Buckets = Buckets::values(&[
     0.01, 0.03, 0.1, 0.3, 0.5, 0.75, 1., 1.5, 3., 5., 10., 20., 50.,
 ]);
@@ -368,6 +451,13 @@ pub(super) struct ExecutorMetrics {
     pub computational_gas_per_nanosecond: Histogram<f64>,
     #[metrics(buckets = GAS_PER_NANOSECOND_BUCKETS)]
     pub failed_tx_gas_limit_per_nanosecond: Histogram<f64>,
+    /// Number of transactions executed with optimistic execution enabled.
+    pub optimistic_execution: Family<OptimisticExecutionOutcome, Counter>,
+    /// Number of storage reads collected during optimistic pre-execution of transactions.
+    pub pre_executed_reads: Family<PreExecutedReadKind, Counter>,
+    /// Latency of pre-executing a single transaction.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub tx_pre_execution_time: Histogram<Duration>,
 }
 
 #[vise::register]
@@ -1,26 +1,34 @@
 use std::sync::Arc;
 
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 use zksync_config::{
     configs::chain::{MempoolConfig, NetworkConfig, StateKeeperConfig},
     ContractsConfig, DBConfig,
 };
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
+use zksync_state::BytecodeCache;
 use zksync_system_constants::MAX_TXS_IN_BLOCK;
+use zksync_types::ProtocolVersionId;
 
-use self::io::MempoolIO;
 pub use self::{
     batch_executor::{L1BatchExecutorBuilder, MainBatchExecutorBuilder},
     io::{MiniblockSealer, MiniblockSealerHandle},
     keeper::ZkSyncStateKeeper,
+    seal_criteria::{ConditionalSealer, SealCriterion, SealData, SealResolution},
 };
-pub(crate) use self::{
-    mempool_actor::MempoolFetcher, seal_criteria::ConditionalSealer, types::MempoolGuard,
+use self::{
+    external_builder::{ExternalBlockProposal, ExternalProposals},
+    io::MempoolIO,
+};
+pub(crate) use self::{mempool_actor::MempoolFetcher, types::MempoolGuard};
+use crate::{
+    address_filter::AddressFilter, config_watcher::ConfigOverrides,
+    l1_gas_price::L1GasPriceProvider, rocksdb_compactor::StateKeeperCompactionRequests,
 };
-use crate::l1_gas_price::L1GasPriceProvider;
 
 mod batch_executor;
+pub(crate) mod external_builder;
 pub(crate) mod extractors;
 pub(crate) mod io;
 mod keeper;
@@ -44,6 +52,12 @@ pub(crate) async fn create_state_keeper<G>(
     l1_gas_price_provider: Arc<G>,
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Box<dyn ObjectStore>,
+    address_filter: Option<AddressFilter>,
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
+    external_proposals: Option<mpsc::Receiver<ExternalBlockProposal>>,
+    custom_seal_criteria: Vec<Box<dyn SealCriterion>>,
+    compaction_requests: Option<StateKeeperCompactionRequests>,
+    bytecode_cache: Option<BytecodeCache>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper
 where
@@ -56,7 +70,7 @@ where
         MAX_TXS_IN_BLOCK
     );
 
-    let batch_executor_base = MainBatchExecutorBuilder::new(
+    let mut batch_executor_base = MainBatchExecutorBuilder::new(
         db_config.state_keeper_db_path.clone(),
         pool.clone(),
         state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
@@ -64,6 +78,21 @@ where
         state_keeper_config.upload_witness_inputs_to_gcs,
         state_keeper_config.enum_index_migration_chunk_size(),
     );
+    if let Some(raw_version) = state_keeper_config.shadow_vm_protocol_version {
+        let protocol_version = ProtocolVersionId::try_from(raw_version).unwrap_or_else(|_| {
+            panic!("Configured shadow_vm_protocol_version ({raw_version}) is not supported")
+        });
+        batch_executor_base = batch_executor_base.with_shadow_vm(protocol_version.into());
+    }
+    if let Some(lookahead) = state_keeper_config.optimistic_execution_lookahead {
+        batch_executor_base = batch_executor_base.with_optimistic_execution(lookahead);
+    }
+    if let Some(requests) = compaction_requests {
+        batch_executor_base = batch_executor_base.with_compaction_requests(requests);
+    }
+    if let Some(cache) = bytecode_cache {
+        batch_executor_base = batch_executor_base.with_bytecode_cache(cache);
+    }
 
     let io = MempoolIO::new(
         mempool,
@@ -78,8 +107,27 @@ where
         network_config.zksync_network_id,
     )
     .await;
+    let io = match address_filter {
+        Some(filter) => io.with_address_filter(filter),
+        None => io,
+    };
+    let io = match config_overrides.clone() {
+        Some(overrides) => io.with_config_overrides(overrides),
+        None => io,
+    };
+    let io = match external_proposals {
+        Some(receiver) => io.with_external_proposals(ExternalProposals::new(
+            receiver,
+            state_keeper_config.external_builder_timeout(),
+        )),
+        None => io,
+    };
 
-    let sealer = ConditionalSealer::new(state_keeper_config);
+    let mut sealer =
+        ConditionalSealer::new(state_keeper_config).with_custom_sealers(custom_seal_criteria);
+    if let Some(overrides) = config_overrides {
+        sealer = sealer.with_config_overrides(overrides);
+    }
     ZkSyncStateKeeper::new(
         stop_receiver,
         Box::new(io),
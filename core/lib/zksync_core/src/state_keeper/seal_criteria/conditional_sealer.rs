@@ -3,10 +3,16 @@
 //! It is used on the main node to decide when the batch should be sealed (as opposed to the external node,
 //! which unconditionally follows the instructions from the main node).
 
+use std::{borrow::Cow, mem};
+
+use tokio::sync::watch;
 use zksync_config::configs::chain::StateKeeperConfig;
-use zksync_types::ProtocolVersionId;
+use zksync_types::{Address, ProtocolVersionId};
 
-use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use super::{
+    criteria, CriterionUsage, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS,
+};
+use crate::config_watcher::ConfigOverrides;
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 ///
@@ -15,11 +21,14 @@ use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRI
 #[derive(Debug)]
 pub struct ConditionalSealer {
     config: StateKeeperConfig,
+    /// Runtime overrides for seal criteria limits in `config`.
+    config_overrides: Option<watch::Receiver<ConfigOverrides>>,
     sealers: Vec<Box<dyn SealCriterion>>,
 }
 
 impl ConditionalSealer {
     /// Finds a reason why a transaction with the specified `data` is unexecutable.
+    /// Only built-in criteria are checked.
     pub(crate) fn find_unexecutable_reason(
         config: &StateKeeperConfig,
         data: &SealData,
@@ -44,9 +53,113 @@ impl ConditionalSealer {
         None
     }
 
-    pub(crate) fn new(config: StateKeeperConfig) -> Self {
+    /// Finds a reason why a transaction with the specified `tx_data` would be excluded from an L1 batch
+    /// and postponed to the next one. `batch_data` and `tx_count` must *include* the transaction.
+    /// Only built-in criteria are checked.
+    pub(crate) fn find_exclusion_reason(
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        batch_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<&'static str> {
+        for sealer in &Self::default_sealers() {
+            const MOCK_BLOCK_TIMESTAMP: u128 = 0;
+
+            let resolution = sealer.should_seal(
+                config,
+                MOCK_BLOCK_TIMESTAMP,
+                tx_count,
+                batch_data,
+                tx_data,
+                protocol_version,
+            );
+            if matches!(resolution, SealResolution::ExcludeAndSeal) {
+                return Some(sealer.prom_criterion_name());
+            }
+        }
+        None
+    }
+
+    /// Reports the usage of limits of the built-in criteria by an L1 batch. `batch_data` and `tx_count` describe
+    /// the entire batch. Criteria without a limit applicable to the batch are skipped.
+    pub(crate) fn criteria_usage(
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        batch_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Vec<(&'static str, CriterionUsage)> {
+        Self::default_sealers()
+            .iter()
+            .filter_map(|sealer| {
+                let usage = sealer.usage(config, tx_count, batch_data, protocol_version)?;
+                Some((sealer.prom_criterion_name(), usage))
+            })
+            .collect()
+    }
+
+    /// Evaluates the built-in criteria for a transaction with the specified `tx_data` added to an L1 batch.
+    /// `batch_data` and `tx_count` must *include* the transaction. Returns the strictest resolution together
+    /// with the names of criteria that have returned it.
+    pub(crate) fn simulate(
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        batch_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> (SealResolution, Vec<&'static str>) {
+        const MOCK_BLOCK_TIMESTAMP: u128 = 0;
+
+        let resolutions: Vec<_> = Self::default_sealers()
+            .iter()
+            .map(|sealer| {
+                let resolution = sealer.should_seal(
+                    config,
+                    MOCK_BLOCK_TIMESTAMP,
+                    tx_count,
+                    batch_data,
+                    tx_data,
+                    protocol_version,
+                );
+                (sealer.prom_criterion_name(), resolution)
+            })
+            .collect();
+        let final_resolution = resolutions
+            .iter()
+            .fold(SealResolution::NoSeal, |acc, (_, resolution)| {
+                acc.stricter(resolution.clone())
+            });
+        let triggered_criteria = resolutions
+            .into_iter()
+            .filter(|(_, resolution)| {
+                *resolution != SealResolution::NoSeal
+                    && mem::discriminant(resolution) == mem::discriminant(&final_resolution)
+            })
+            .map(|(name, _)| name)
+            .collect();
+        (final_resolution, triggered_criteria)
+    }
+
+    pub fn new(config: StateKeeperConfig) -> Self {
         let sealers = Self::default_sealers();
-        Self { config, sealers }
+        Self {
+            config,
+            config_overrides: None,
+            sealers,
+        }
+    }
+
+    /// Makes the sealer respect runtime overrides of seal criteria limits.
+    pub fn with_config_overrides(mut self, overrides: watch::Receiver<ConfigOverrides>) -> Self {
+        self.config_overrides = Some(overrides);
+        self
+    }
+
+    /// Registers custom seal criteria. They are checked after the built-in ones; the strictest
+    /// returned resolution wins.
+    pub fn with_custom_sealers(mut self, sealers: Vec<Box<dyn SealCriterion>>) -> Self {
+        self.sealers.extend(sealers);
+        self
     }
 
     #[cfg(test)]
@@ -54,7 +167,16 @@ impl ConditionalSealer {
         config: StateKeeperConfig,
         sealers: Vec<Box<dyn SealCriterion>>,
     ) -> Self {
-        Self { config, sealers }
+        Self {
+            config,
+            config_overrides: None,
+            sealers,
+        }
+    }
+
+    /// Returns the address of the paymaster whose transactions are subject to the sponsored gas limit.
+    pub(crate) fn sponsor_paymaster(&self) -> Option<Address> {
+        self.config.sponsor_paymaster_addr
     }
 
     pub fn should_seal_l1_batch(
@@ -72,10 +194,15 @@ impl ConditionalSealer {
             block_data.execution_metrics
         );
 
+        let overrides = self.config_overrides.as_ref().map(watch::Receiver::borrow);
+        let config = match &overrides {
+            Some(overrides) => overrides.apply_to_state_keeper_config(&self.config),
+            None => Cow::Borrowed(&self.config),
+        };
         let mut final_seal_resolution = SealResolution::NoSeal;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
-                &self.config,
+                &config,
                 block_open_timestamp_ms,
                 tx_count,
                 block_data,
@@ -94,6 +221,7 @@ impl ConditionalSealer {
                 }
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
+            sealer.report_resolution(&seal_resolution);
 
             final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
         }
@@ -111,6 +239,7 @@ impl ConditionalSealer {
             Box::new(criteria::ComputationalGasCriterion),
             Box::new(criteria::TxEncodingSizeCriterion),
             Box::new(criteria::L2ToL1LogsCriterion),
+            Box::new(criteria::SponsoredGasCriterion),
         ]
     }
 }
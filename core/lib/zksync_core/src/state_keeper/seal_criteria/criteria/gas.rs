@@ -2,7 +2,9 @@ use zksync_types::ProtocolVersionId;
 
 use crate::{
     gas_tracker::new_block_gas_count,
-    state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution, StateKeeperConfig},
+    state_keeper::seal_criteria::{
+        CriterionUsage, SealCriterion, SealData, SealResolution, StateKeeperConfig,
+    },
 };
 
 /// This is a temporary solution.
@@ -43,6 +45,27 @@ impl SealCriterion for GasCriterion {
         }
     }
 
+    fn usage(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<CriterionUsage> {
+        let gas_count = &block_data.gas_count;
+        let block_bound =
+            (config.max_single_tx_gas as f64 * config.close_block_at_gas_percentage).round() as u64;
+        Some(CriterionUsage {
+            used: gas_count
+                .commit
+                .max(gas_count.prove)
+                .max(gas_count.execute)
+                .into(),
+            limit: config.max_single_tx_gas.into(),
+            seal_threshold: block_bound,
+        })
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "gas"
     }
@@ -9,7 +9,7 @@ use zksync_types::{
 };
 
 // Local uses
-use crate::state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution};
+use crate::state_keeper::seal_criteria::{CriterionUsage, SealCriterion, SealData, SealResolution};
 
 // Collected vm execution metrics should fit into geometry limits.
 // Otherwise witness generation will fail and proof won't be generated.
@@ -66,6 +66,25 @@ where
         }
     }
 
+    fn usage(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version_id: ProtocolVersionId,
+    ) -> Option<CriterionUsage> {
+        let limit = T::limit_per_block(protocol_version_id);
+        if limit == usize::MAX {
+            return None; // The criterion is not applicable for the protocol version
+        }
+        let close_bound = (limit as f64 * config.close_block_at_geometry_percentage).round();
+        Some(CriterionUsage {
+            used: T::extract(&block_data.execution_metrics, &block_data.writes_metrics) as u64,
+            limit: limit as u64,
+            seal_threshold: close_bound as u64,
+        })
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         T::PROM_METRIC_CRITERION_NAME
     }
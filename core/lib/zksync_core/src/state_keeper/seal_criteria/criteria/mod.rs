@@ -2,6 +2,7 @@ mod gas;
 mod geometry_seal_criteria;
 mod pubdata_bytes;
 mod slots;
+mod sponsored_gas;
 mod tx_encoding_size;
 
 pub(in crate::state_keeper) use self::{
@@ -12,5 +13,6 @@ pub(in crate::state_keeper) use self::{
     },
     pubdata_bytes::PubDataBytesCriterion,
     slots::SlotsCriterion,
+    sponsored_gas::SponsoredGasCriterion,
     tx_encoding_size::TxEncodingSizeCriterion,
 };
@@ -1,7 +1,8 @@
 use zksync_types::{ProtocolVersionId, MAX_PUBDATA_PER_L1_BATCH};
 
-use crate::state_keeper::seal_criteria::{
-    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+use crate::state_keeper::{
+    metrics::{OversizedPubdataOutcome, KEEPER_METRICS},
+    seal_criteria::{CriterionUsage, SealCriterion, SealData, SealResolution, StateKeeperConfig},
 };
 
 #[derive(Debug)]
@@ -12,7 +13,7 @@ impl SealCriterion for PubDataBytesCriterion {
         &self,
         config: &StateKeeperConfig,
         _block_open_timestamp_ms: u128,
-        _tx_count: usize,
+        tx_count: usize,
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
@@ -33,9 +34,17 @@ impl SealCriterion for PubDataBytesCriterion {
         } else {
             tx_data.execution_metrics.pubdata_published as usize
         };
-        if tx_size > reject_bound as usize {
-            let message = "Transaction cannot be sent to L1 due to pubdata limits";
-            SealResolution::Unexecutable(message.into())
+        // If the transaction is the only one in the batch, exceeding the batch limit means that the transaction
+        // won't fit into any batch. Sealing the batch in this case would lead to the transaction being retried
+        // in a new batch forever.
+        let exceeds_batch_budget = tx_size > reject_bound as usize
+            || (tx_count == 1 && block_size > max_pubdata_per_l1_batch);
+        if exceeds_batch_budget {
+            let message = format!(
+                "Transaction cannot be sent to L1 due to pubdata limits: it publishes {tx_size} bytes of pubdata, \
+                 while an L1 batch can contain at most {max_pubdata_per_l1_batch} bytes"
+            );
+            SealResolution::Unexecutable(message)
         } else if block_size > max_pubdata_per_l1_batch {
             SealResolution::ExcludeAndSeal
         } else if block_size > include_and_seal_bound as usize {
@@ -45,6 +54,34 @@ impl SealCriterion for PubDataBytesCriterion {
         }
     }
 
+    fn usage(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<CriterionUsage> {
+        let max_pubdata_per_l1_batch = MAX_PUBDATA_PER_L1_BATCH;
+        let include_and_seal_bound =
+            (max_pubdata_per_l1_batch as f64 * config.close_block_at_eth_params_percentage).round();
+        let block_size =
+            block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
+        Some(CriterionUsage {
+            used: block_size as u64,
+            limit: max_pubdata_per_l1_batch,
+            seal_threshold: include_and_seal_bound as u64,
+        })
+    }
+
+    fn report_resolution(&self, resolution: &SealResolution) {
+        let outcome = match resolution {
+            SealResolution::Unexecutable(_) => OversizedPubdataOutcome::Rejected,
+            SealResolution::ExcludeAndSeal => OversizedPubdataOutcome::SealedAndRetried,
+            SealResolution::NoSeal | SealResolution::IncludeAndSeal => return,
+        };
+        KEEPER_METRICS.oversized_pubdata_txs[&outcome].inc();
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "pub_data_size"
     }
@@ -52,6 +89,7 @@ impl SealCriterion for PubDataBytesCriterion {
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use zksync_types::tx::ExecutionMetrics;
 
     use super::*;
@@ -126,4 +164,65 @@ mod tests {
         );
         assert_eq!(full_block_resolution, SealResolution::ExcludeAndSeal);
     }
+
+    #[test]
+    fn oversized_tx_is_rejected_rather_than_retried() {
+        let config = StateKeeperConfig {
+            reject_tx_at_eth_params_percentage: 0.95,
+            close_block_at_eth_params_percentage: 0.95,
+            ..Default::default()
+        };
+        let criterion = PubDataBytesCriterion;
+
+        // The transaction fits into an empty batch, but not into the remaining budget.
+        let tx_data = SealData {
+            execution_metrics: ExecutionMetrics {
+                l2_l1_long_messages: MAX_PUBDATA_PER_L1_BATCH as usize / 2,
+                ..ExecutionMetrics::default()
+            },
+            ..SealData::default()
+        };
+        let block_data = SealData {
+            execution_metrics: ExecutionMetrics {
+                l2_l1_long_messages: MAX_PUBDATA_PER_L1_BATCH as usize + 1,
+                ..ExecutionMetrics::default()
+            },
+            ..SealData::default()
+        };
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            2,
+            &block_data,
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::ExcludeAndSeal);
+
+        // The same block data with the only transaction in the batch means that the transaction
+        // will never fit into a batch.
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            1,
+            &block_data,
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_matches!(
+            resolution,
+            SealResolution::Unexecutable(reason) if reason.contains("pubdata limits")
+        );
+
+        // The transaction exceeds the budget of a whole batch.
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            2,
+            &block_data,
+            &block_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_matches!(resolution, SealResolution::Unexecutable(_));
+    }
 }
@@ -1,7 +1,7 @@
 use zksync_types::ProtocolVersionId;
 
 use crate::state_keeper::seal_criteria::{
-    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+    CriterionUsage, SealCriterion, SealData, SealResolution, StateKeeperConfig,
 };
 
 /// Checks whether we should seal the block because we've run out of transaction slots.
@@ -25,6 +25,20 @@ impl SealCriterion for SlotsCriterion {
         }
     }
 
+    fn usage(
+        &self,
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<CriterionUsage> {
+        Some(CriterionUsage {
+            used: tx_count as u64,
+            limit: config.transaction_slots as u64,
+            seal_threshold: config.transaction_slots as u64,
+        })
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "slots"
     }
@@ -0,0 +1,91 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Limits the total gas used by sponsored transactions in an L1 batch. Once the limit is reached,
+/// sponsored transactions are postponed to the next batch, while other transactions are not affected.
+#[derive(Debug)]
+pub struct SponsoredGasCriterion;
+
+impl SealCriterion for SponsoredGasCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version_id: ProtocolVersionId,
+    ) -> SealResolution {
+        let Some(limit) = config.max_sponsored_gas_per_l1_batch else {
+            return SealResolution::NoSeal;
+        };
+        if tx_data.sponsored_gas == 0 {
+            return SealResolution::NoSeal;
+        }
+
+        if tx_data.sponsored_gas as u64 > limit {
+            let message = "Sponsored transaction uses more gas than allowed for sponsored transactions in a batch";
+            SealResolution::Unexecutable(message.into())
+        } else if block_data.sponsored_gas as u64 > limit {
+            SealResolution::ExcludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "sponsored_gas"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn seal_data(sponsored_gas: usize) -> SealData {
+        SealData {
+            sponsored_gas,
+            ..SealData::default()
+        }
+    }
+
+    #[test]
+    fn seal_criterion() {
+        let criterion = SponsoredGasCriterion;
+        let resolution = criterion.should_seal(
+            &StateKeeperConfig::default(),
+            0,
+            0,
+            &seal_data(1_000_000),
+            &seal_data(1_000_000),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+
+        let config = StateKeeperConfig {
+            max_sponsored_gas_per_l1_batch: Some(1_000),
+            ..StateKeeperConfig::default()
+        };
+        let should_seal = |block_gas, tx_gas| {
+            criterion.should_seal(
+                &config,
+                0,
+                0,
+                &seal_data(block_gas),
+                &seal_data(tx_gas),
+                ProtocolVersionId::latest(),
+            )
+        };
+
+        assert_eq!(should_seal(1_000, 500), SealResolution::NoSeal);
+        assert_eq!(should_seal(1_001, 500), SealResolution::ExcludeAndSeal);
+        // Non-sponsored transactions are not affected by the limit.
+        assert_eq!(should_seal(1_500, 0), SealResolution::NoSeal);
+        assert_matches!(should_seal(1_001, 1_001), SealResolution::Unexecutable(_));
+    }
+}
@@ -18,16 +18,19 @@ use zksync_types::{
     block::BlockGasCount,
     fee::TransactionExecutionMetrics,
     tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
-    ProtocolVersionId, Transaction,
+    Address, ProtocolVersionId, Transaction,
 };
 use zksync_utils::time::millis_since;
 
 mod conditional_sealer;
 pub(super) mod criteria;
 
-pub(crate) use self::conditional_sealer::ConditionalSealer;
+pub use self::conditional_sealer::ConditionalSealer;
 use super::{extractors, metrics::AGGREGATION_METRICS, updates::UpdatesManager};
-use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
+use crate::{
+    gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes},
+    sponsored_txs::is_paid_by_paymaster,
+};
 
 /// Reported decision regarding block sealing.
 #[derive(Debug, Clone, PartialEq)]
@@ -77,10 +80,16 @@ impl SealResolution {
 /// to the entire miniblock / L1 batch.
 #[derive(Debug, Default)]
 pub struct SealData {
-    pub(super) execution_metrics: ExecutionMetrics,
-    pub(super) gas_count: BlockGasCount,
-    pub(super) cumulative_size: usize,
-    pub(super) writes_metrics: DeduplicatedWritesMetrics,
+    /// Metrics produced by VM execution.
+    pub execution_metrics: ExecutionMetrics,
+    /// Gas expected to be spent on L1 to commit, prove and execute the data.
+    pub gas_count: BlockGasCount,
+    /// Total size of the bootloader encoding of transactions.
+    pub cumulative_size: usize,
+    /// Metrics for deduplicated storage writes.
+    pub writes_metrics: DeduplicatedWritesMetrics,
+    /// Gas used by transactions paid for by the sponsor paymaster (see `StateKeeperConfig::sponsor_paymaster_addr`).
+    pub sponsored_gas: usize,
 }
 
 impl SealData {
@@ -90,7 +99,11 @@ impl SealData {
         transaction: Transaction,
         tx_metrics: &TransactionExecutionMetrics,
         protocol_version: ProtocolVersionId,
+        sponsor_paymaster: Option<Address>,
     ) -> Self {
+        let is_sponsored = sponsor_paymaster.map_or(false, |paymaster| {
+            is_paid_by_paymaster(&transaction, paymaster)
+        });
         let execution_metrics = ExecutionMetrics::from_tx_metrics(tx_metrics);
         let writes_metrics = DeduplicatedWritesMetrics::from_tx_metrics(tx_metrics);
         let gas_count = gas_count_from_tx_and_metrics(&transaction, &execution_metrics)
@@ -100,11 +113,29 @@ impl SealData {
             gas_count,
             cumulative_size: transaction.bootloader_encoding_size(),
             writes_metrics,
+            sponsored_gas: if is_sponsored { tx_metrics.gas_used } else { 0 },
         }
     }
 }
 
-pub(super) trait SealCriterion: fmt::Debug + Send + 'static {
+/// Usage of a seal criterion limit by an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CriterionUsage {
+    /// Value of the limited quantity for the L1 batch.
+    pub used: u64,
+    /// Hard limit for the L1 batch.
+    pub limit: u64,
+    /// Value at which the L1 batch is sealed.
+    pub seal_threshold: u64,
+}
+
+/// Deterministic criterion deciding whether an L1 batch should be sealed after executing a transaction.
+///
+/// Besides the built-in criteria (gas, pubdata, circuits etc.), custom criteria can be registered
+/// for the main node state keeper using [`ConditionalSealer::with_custom_sealers()`].
+pub trait SealCriterion: fmt::Debug + Send + 'static {
+    /// Decides whether the batch should be sealed. `block_data` contains data for the entire batch
+    /// *including* the latest transaction; `tx_data` contains data for the latest transaction only.
     fn should_seal(
         &self,
         config: &StateKeeperConfig,
@@ -115,6 +146,27 @@ pub(super) trait SealCriterion: fmt::Debug + Send + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the usage of the criterion limit by an L1 batch with the specified data (including all its transactions),
+    /// or `None` if the criterion has no limit applicable to the batch. Used to report how close the batch is
+    /// to being sealed.
+    fn usage(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<CriterionUsage> {
+        None
+    }
+
+    /// Reports the resolution of this criterion for a transaction processed by the state keeper. The criteria
+    /// are also used by the API server when estimating fees, so criterion-specific metrics should be reported here
+    /// rather than in [`Self::should_seal()`].
+    fn report_resolution(&self, _resolution: &SealResolution) {
+        // Do nothing by default
+    }
+
+    /// Returns the criterion name used in logs and metrics.
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -137,6 +189,14 @@ pub(super) struct TimeoutSealer {
 
 impl TimeoutSealer {
     pub fn new(config: &StateKeeperConfig) -> Self {
+        if config.miniblock_commit_deadline_ms < 1_000 {
+            tracing::warn!(
+                "Miniblock commit deadline is set to {}ms; miniblocks will be sealed after this deadline, \
+                 but since miniblock timestamps have second precision and must strictly increase, \
+                 at most one miniblock per second will be produced",
+                config.miniblock_commit_deadline_ms
+            );
+        }
         Self {
             block_commit_deadline_ms: config.block_commit_deadline_ms,
             miniblock_commit_deadline_ms: config.miniblock_commit_deadline_ms,
@@ -171,13 +231,15 @@ impl IoSealCriteria for TimeoutSealer {
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
         !manager.miniblock.executed_transactions.is_empty()
-            && millis_since(manager.miniblock.timestamp) > self.miniblock_commit_deadline_ms
+            && manager.miniblock.millis_since_opened() > self.miniblock_commit_deadline_ms
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_utils::time::seconds_since_epoch;
+    use std::collections::HashMap;
+
+    use zksync_utils::time::{millis_since_epoch, seconds_since_epoch};
 
     use super::*;
     use crate::state_keeper::tests::{
@@ -206,7 +268,7 @@ mod tests {
 
         let mut manager = create_updates_manager();
         // Empty miniblock should not trigger.
-        manager.miniblock.timestamp = seconds_since_epoch() - 10;
+        manager.miniblock.opened_at_ms = millis_since_epoch() as u64 - 20_000;
         assert!(
             !timeout_miniblock_sealer.should_seal_miniblock(&manager),
             "Empty miniblock shouldn't be sealed"
@@ -222,10 +284,140 @@ mod tests {
         // Check the timestamp logic. This relies on the fact that the test shouldn't run
         // for more than 10 seconds (while the test itself is trivial, it may be preempted
         // by other tests).
-        manager.miniblock.timestamp = seconds_since_epoch();
+        manager.miniblock.opened_at_ms = millis_since_epoch() as u64;
         assert!(
             !timeout_miniblock_sealer.should_seal_miniblock(&manager),
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn timeout_miniblock_sealer_with_subsecond_deadline() {
+        let mut timeout_miniblock_sealer = TimeoutSealer {
+            block_commit_deadline_ms: 10_000,
+            miniblock_commit_deadline_ms: 250,
+        };
+
+        let mut manager = create_updates_manager();
+        apply_tx_to_manager(&mut manager);
+        // The miniblock timestamp has second precision, so it shouldn't influence the sealing decision.
+        manager.miniblock.timestamp = seconds_since_epoch() - 1;
+        manager.miniblock.opened_at_ms = millis_since_epoch() as u64;
+        assert!(
+            !timeout_miniblock_sealer.should_seal_miniblock(&manager),
+            "Miniblock opened just now shouldn't be sealed"
+        );
+
+        manager.miniblock.opened_at_ms = millis_since_epoch() as u64 - 500;
+        assert!(
+            timeout_miniblock_sealer.should_seal_miniblock(&manager),
+            "Miniblock opened after the deadline should be sealed"
+        );
+    }
+
+    #[test]
+    fn finding_exclusion_reason() {
+        let config = StateKeeperConfig::for_tests();
+        let gas_data = |commit| SealData {
+            gas_count: BlockGasCount {
+                commit,
+                prove: 0,
+                execute: 0,
+            },
+            ..SealData::default()
+        };
+        let tx_data = gas_data(100_000);
+
+        let reason = ConditionalSealer::find_exclusion_reason(
+            &config,
+            2,
+            &gas_data(1_000_000),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(reason, None);
+
+        let reason = ConditionalSealer::find_exclusion_reason(
+            &config,
+            2,
+            &gas_data(config.max_single_tx_gas + 1),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(reason, Some("gas"));
+    }
+
+    #[test]
+    fn reporting_criteria_usage() {
+        let config = StateKeeperConfig::for_tests();
+        let batch_data = SealData {
+            gas_count: BlockGasCount {
+                commit: 1_000_000,
+                prove: 10,
+                execute: 10,
+            },
+            ..SealData::default()
+        };
+
+        let usage =
+            ConditionalSealer::criteria_usage(&config, 2, &batch_data, ProtocolVersionId::latest());
+        let usage: HashMap<_, _> = usage.into_iter().collect();
+        assert_eq!(
+            usage["slots"],
+            CriterionUsage {
+                used: 2,
+                limit: config.transaction_slots as u64,
+                seal_threshold: config.transaction_slots as u64,
+            }
+        );
+        assert_eq!(usage["gas"].used, 1_000_000);
+        assert_eq!(usage["gas"].limit, u64::from(config.max_single_tx_gas));
+        assert!(usage.contains_key("pub_data_size"));
+        // Geometry limits for storage writes don't apply to post-boojum batches.
+        assert!(!usage.contains_key("initial_storage_writes"));
+    }
+
+    #[test]
+    fn simulating_seal_criteria() {
+        let config = StateKeeperConfig::for_tests();
+        let gas_data = |commit| SealData {
+            gas_count: BlockGasCount {
+                commit,
+                prove: 0,
+                execute: 0,
+            },
+            ..SealData::default()
+        };
+        let tx_data = gas_data(100_000);
+
+        let (resolution, criteria) = ConditionalSealer::simulate(
+            &config,
+            2,
+            &gas_data(1_000_000),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+        assert!(criteria.is_empty(), "{criteria:?}");
+
+        let (resolution, criteria) = ConditionalSealer::simulate(
+            &config,
+            config.transaction_slots,
+            &gas_data(1_000_000),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::IncludeAndSeal);
+        assert_eq!(criteria, ["slots"]);
+
+        let (resolution, criteria) = ConditionalSealer::simulate(
+            &config,
+            config.transaction_slots,
+            &gas_data(config.max_single_tx_gas + 1),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::ExcludeAndSeal);
+        assert_eq!(criteria, ["gas"]);
+    }
 }
@@ -42,7 +42,7 @@ use crate::{
         keeper::POLL_WAIT_DURATION,
         seal_criteria::{
             criteria::{GasCriterion, SlotsCriterion},
-            ConditionalSealer,
+            ConditionalSealer, SealCriterion, SealData, SealResolution,
         },
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
@@ -376,6 +376,54 @@ async fn batch_sealed_before_miniblock_does() {
         .await;
 }
 
+/// Custom criterion sealing the batch once it contains the specified number of transactions.
+#[derive(Debug)]
+struct TxCountCriterion(usize);
+
+impl SealCriterion for TxCountCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        if tx_count >= self.0 {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "test_tx_count"
+    }
+}
+
+#[tokio::test]
+async fn sealed_by_custom_criterion() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = ConditionalSealer::with_sealers(config, vec![Box::new(SlotsCriterion)])
+        .with_custom_sealers(vec![Box::new(TxCountCriterion(2))]);
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock 1")
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock 2")
+        .batch_sealed_with("Batch sealed by custom criterion", |_, updates, _| {
+            assert_eq!(updates.pending_executed_transactions_len(), 2);
+        })
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn rejected_tx() {
     let config = StateKeeperConfig {
@@ -3,9 +3,10 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_config::configs::chain::{MempoolConfig, MempoolEvictionPolicy, MempoolOrderingPolicy};
+use zksync_mempool::{L2TxFilter, MempoolEviction, MempoolInfo, MempoolOrdering, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use super::metrics::StateKeeperGauges;
@@ -14,11 +15,45 @@ use super::metrics::StateKeeperGauges;
 pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
 
 impl MempoolGuard {
-    pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
+    pub fn new(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering_policy: MempoolOrderingPolicy,
+    ) -> Self {
+        let store = Self::create_store(next_priority_id, capacity, ordering_policy);
         Self(Arc::new(Mutex::new(store)))
     }
 
+    /// Creates a mempool with the capacity, ordering, per-account quota and eviction policy
+    /// specified in the config.
+    pub fn from_config(next_priority_id: PriorityOpId, config: &MempoolConfig) -> Self {
+        let mut store =
+            Self::create_store(next_priority_id, config.capacity, config.ordering_policy);
+        if let Some(max_txs) = config.max_pending_txs_per_account {
+            store = store.with_max_txs_per_account(max_txs);
+        }
+        if let Some(policy) = config.eviction_policy {
+            let eviction = match policy {
+                MempoolEvictionPolicy::LowestFee => MempoolEviction::LowestFee,
+                MempoolEvictionPolicy::Oldest => MempoolEviction::Oldest,
+            };
+            store = store.with_eviction(eviction);
+        }
+        Self(Arc::new(Mutex::new(store)))
+    }
+
+    fn create_store(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering_policy: MempoolOrderingPolicy,
+    ) -> MempoolStore {
+        let ordering = match ordering_policy {
+            MempoolOrderingPolicy::Fifo => MempoolOrdering::Fifo,
+            MempoolOrderingPolicy::FeePriority => MempoolOrdering::FeePriority,
+        };
+        MempoolStore::new(next_priority_id, capacity).with_ordering(ordering)
+    }
+
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
         self.0
             .lock()
@@ -40,6 +75,46 @@ impl MempoolGuard {
             .next_transaction(filter)
     }
 
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_l1_transaction()
+    }
+
+    pub fn next_l2_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_l2_transaction(filter)
+    }
+
+    pub fn peek_transactions(
+        &self,
+        filter: &L2TxFilter,
+        limit: usize,
+        skip: impl Fn(&H256) -> bool,
+    ) -> Vec<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .peek_transactions(filter, limit, skip)
+    }
+
+    pub fn skip_l2_transaction(&mut self, transaction: &Transaction) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .skip_l2_transaction(transaction);
+    }
+
+    pub fn remove_l2_transaction(&mut self, account: Address, nonce: Nonce) -> Option<H256> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remove_l2_transaction(account, nonce)
+    }
+
     pub fn rollback(&mut self, rejected: &Transaction) {
         self.0
             .lock()
@@ -1,11 +1,13 @@
+use std::collections::HashMap;
+
 use zksync_types::{
     block::BlockGasCount,
     priority_op_onchain_data::PriorityOpOnchainData,
     tx::{tx_execution_info::ExecutionMetrics, TransactionExecutionResult},
-    ExecuteTransactionCommon,
+    Address, ExecuteTransactionCommon,
 };
 
-use super::miniblock_updates::MiniblockUpdates;
+use super::{merge_paymaster_spending, miniblock_updates::MiniblockUpdates, PaymasterSpending};
 use crate::gas_tracker::new_block_gas_count;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +18,8 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Resources spent by paymasters on transactions in sealed miniblocks of the batch.
+    pub paymaster_spending: HashMap<Address, PaymasterSpending>,
 }
 
 impl L1BatchUpdates {
@@ -26,6 +30,7 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            paymaster_spending: HashMap::new(),
         }
     }
 
@@ -42,6 +47,10 @@ impl L1BatchUpdates {
         self.l1_gas_count += miniblock_updates.l1_gas_count;
         self.block_execution_metrics += miniblock_updates.block_execution_metrics;
         self.txs_encoding_size += miniblock_updates.txs_encoding_size;
+        merge_paymaster_spending(
+            &mut self.paymaster_spending,
+            &miniblock_updates.paymaster_spending,
+        );
     }
 }
 
@@ -5,14 +5,22 @@ use multivm::{
     vm_latest::TransactionVmExt,
 };
 use zksync_types::{
+    api,
     block::{BlockGasCount, MiniblockHasher},
     event::extract_bytecodes_marked_as_known,
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
     vm_trace::Call,
-    MiniblockNumber, ProtocolVersionId, StorageLogQuery, Transaction, VmEvent, H256,
+    web3::types::{Bytes, Index},
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageLogQuery, Transaction,
+    VmEvent, H256, U256, U64,
 };
-use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
+use zksync_utils::{
+    bytecode::{hash_bytecode, CompressedBytecodeInfo},
+    time::millis_since_epoch,
+};
+
+use super::PaymasterSpending;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct MiniblockUpdates {
@@ -26,7 +34,14 @@ pub struct MiniblockUpdates {
     pub l1_gas_count: BlockGasCount,
     pub block_execution_metrics: ExecutionMetrics,
     pub txs_encoding_size: usize,
+    /// Resources spent by paymasters on transactions in this miniblock.
+    pub paymaster_spending: HashMap<Address, PaymasterSpending>,
+    /// Miniblock timestamp in seconds. This is the timestamp passed to the bootloader and included
+    /// into the miniblock header; it must strictly increase between miniblocks.
     pub timestamp: u64,
+    /// Wall-clock time when the miniblock was opened, in milliseconds since UNIX epoch. Used by the state keeper
+    /// for sealing decisions, which consequently are not bound by the second precision of `timestamp`.
+    pub opened_at_ms: u64,
     pub number: u32,
     pub prev_block_hash: H256,
     pub virtual_blocks: u32,
@@ -51,7 +66,10 @@ impl MiniblockUpdates {
             l1_gas_count: BlockGasCount::default(),
             block_execution_metrics: ExecutionMetrics::default(),
             txs_encoding_size: 0,
+            paymaster_spending: HashMap::new(),
             timestamp,
+            // The timestamp may be in the future if the system clock is behind the previous miniblock.
+            opened_at_ms: (millis_since_epoch() as u64).max(timestamp.saturating_mul(1_000)),
             number,
             prev_block_hash,
             virtual_blocks,
@@ -59,6 +77,11 @@ impl MiniblockUpdates {
         }
     }
 
+    /// Returns the number of milliseconds elapsed since this miniblock was opened.
+    pub(crate) fn millis_since_opened(&self) -> u64 {
+        (millis_since_epoch() as u64).saturating_sub(self.opened_at_ms)
+    }
+
     pub(crate) fn extend_from_fictive_transaction(&mut self, result: VmExecutionResultAndLogs) {
         self.events.extend(result.logs.events);
         self.storage_logs.extend(result.logs.storage_logs);
@@ -122,6 +145,11 @@ impl MiniblockUpdates {
         self.l1_gas_count += tx_l1_gas_this_tx;
         self.block_execution_metrics += execution_metrics;
         self.txs_encoding_size += tx.bootloader_encoding_size();
+        if let Some((paymaster, spending)) =
+            PaymasterSpending::for_transaction(&tx, &execution_metrics, gas_refunded)
+        {
+            *self.paymaster_spending.entry(paymaster).or_default() += spending;
+        }
         self.storage_logs
             .extend(tx_execution_result.logs.storage_logs);
 
@@ -151,6 +179,54 @@ impl MiniblockUpdates {
         digest.finalize(self.protocol_version)
     }
 
+    /// Creates a soft confirmation for the last executed transaction in this miniblock.
+    /// `first_event_index` is the index of the first event emitted by the transaction in `self.events`.
+    pub(crate) fn last_tx_soft_confirmation(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        first_event_index: usize,
+    ) -> Option<api::TransactionSoftConfirmation> {
+        let tx = self.executed_transactions.last()?;
+        let transaction_index = Index::from(self.executed_transactions.len() - 1);
+        let block_number = U64::from(self.number);
+        let l1_batch_number = U64::from(l1_batch_number.0);
+        let logs = self.events[first_event_index..]
+            .iter()
+            .enumerate()
+            .map(|(i, event)| api::Log {
+                address: event.address,
+                topics: event.indexed_topics.clone(),
+                data: Bytes(event.value.clone()),
+                block_hash: None,
+                block_number: Some(block_number),
+                l1_batch_number: Some(l1_batch_number),
+                transaction_hash: Some(tx.hash),
+                transaction_index: Some(transaction_index),
+                log_index: Some(U256::from(first_event_index + i)),
+                transaction_log_index: Some(U256::from(i)),
+                log_type: None,
+                removed: Some(false),
+            })
+            .collect();
+        let status = match tx.execution_status {
+            TxExecutionStatus::Success => U64::one(),
+            TxExecutionStatus::Failure => U64::zero(),
+        };
+
+        Some(api::TransactionSoftConfirmation {
+            transaction_hash: tx.hash,
+            transaction_index,
+            block_number,
+            l1_batch_number,
+            from: tx.transaction.initiator_account(),
+            to: Some(tx.transaction.execute.contract_address),
+            status,
+            gas_used: tx.transaction.gas_limit() - tx.refunded_gas,
+            logs,
+            revert_reason: tx.revert_reason.clone(),
+        })
+    }
+
     pub(crate) fn get_miniblock_env(&self) -> L2BlockEnv {
         L2BlockEnv {
             number: self.number,
@@ -164,6 +240,7 @@ impl MiniblockUpdates {
 #[cfg(test)]
 mod tests {
     use multivm::vm_latest::TransactionVmExt;
+    use zksync_types::Address;
 
     use super::*;
     use crate::state_keeper::tests::{create_execution_result, create_transaction};
@@ -193,4 +270,55 @@ mod tests {
         assert_eq!(accumulator.block_execution_metrics.l2_to_l1_logs, 0);
         assert_eq!(accumulator.txs_encoding_size, bootloader_encoding_size);
     }
+
+    #[test]
+    fn soft_confirmation_for_last_tx() {
+        let mut accumulator =
+            MiniblockUpdates::new(0, 5, H256::random(), 0, ProtocolVersionId::latest());
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![3; 32],
+        };
+        let mut execution_result = create_execution_result(0, []);
+        execution_result.logs.events = vec![event.clone(); 2];
+        accumulator.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            execution_result,
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+
+        let first_event_index = accumulator.events.len();
+        let tx = create_transaction(10, 100);
+        let tx_hash = tx.hash();
+        let mut execution_result = create_execution_result(1, []);
+        execution_result.logs.events = vec![event];
+        accumulator.extend_from_executed_transaction(
+            tx,
+            execution_result,
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+
+        let confirmation = accumulator
+            .last_tx_soft_confirmation(L1BatchNumber(1), first_event_index)
+            .unwrap();
+        assert_eq!(confirmation.transaction_hash, tx_hash);
+        assert_eq!(confirmation.transaction_index, 1.into());
+        assert_eq!(confirmation.block_number, 5.into());
+        assert_eq!(confirmation.status, 1.into());
+        assert_eq!(confirmation.logs.len(), 1);
+        let log = &confirmation.logs[0];
+        assert_eq!(log.address, Address::repeat_byte(1));
+        assert_eq!(log.transaction_hash, Some(tx_hash));
+        assert_eq!(log.log_index, Some(2.into()));
+        assert_eq!(log.transaction_log_index, Some(0.into()));
+        assert_eq!(log.block_hash, None);
+    }
 }
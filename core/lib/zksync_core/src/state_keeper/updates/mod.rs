@@ -1,10 +1,15 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops,
+};
+
 use multivm::interface::{L1BatchEnv, VmExecutionResultAndLogs};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::blocks_dal::ConsensusBlockFields;
 use zksync_types::{
     block::BlockGasCount, storage_writes_deduplicator::StorageWritesDeduplicator,
-    tx::tx_execution_info::ExecutionMetrics, vm_trace::Call, Address, L1BatchNumber,
-    MiniblockNumber, ProtocolVersionId, Transaction,
+    tx::tx_execution_info::ExecutionMetrics, vm_trace::Call, Address, ExecuteTransactionCommon,
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction, H256, U256,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -14,6 +19,78 @@ use super::io::MiniblockParams;
 pub mod l1_batch_updates;
 pub mod miniblock_updates;
 
+/// Resources spent by a paymaster on transactions it has paid for.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PaymasterSpending {
+    pub tx_count: usize,
+    /// Gas used by the transactions according to their execution metrics.
+    pub gas_used: usize,
+    /// Gas charged from the paymaster, i.e., the gas limit of transactions minus refunds.
+    pub charged_gas: u64,
+}
+
+impl ops::AddAssign for PaymasterSpending {
+    fn add_assign(&mut self, other: Self) {
+        self.tx_count += other.tx_count;
+        self.gas_used += other.gas_used;
+        self.charged_gas += other.charged_gas;
+    }
+}
+
+impl ops::Add for PaymasterSpending {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl PaymasterSpending {
+    /// Returns the paymaster and its spending on the specified L2 transaction, or `None` if the transaction
+    /// doesn't use a paymaster.
+    fn for_transaction(
+        tx: &Transaction,
+        execution_metrics: &ExecutionMetrics,
+        refunded_gas: u32,
+    ) -> Option<(Address, Self)> {
+        let ExecuteTransactionCommon::L2(data) = &tx.common_data else {
+            return None;
+        };
+        let paymaster = data.paymaster_params.paymaster;
+        if paymaster == Address::zero() {
+            return None;
+        }
+        let charged_gas = data
+            .fee
+            .gas_limit
+            .saturating_sub(refunded_gas.into())
+            .try_into()
+            .unwrap_or(u64::MAX);
+        let spending = Self {
+            tx_count: 1,
+            gas_used: execution_metrics.gas_used,
+            charged_gas,
+        };
+        Some((paymaster, spending))
+    }
+
+    /// Returns the fee paid by the paymaster given the L2 base fee of the batch.
+    pub fn fee(&self, base_fee_per_gas: u64) -> U256 {
+        U256::from(self.charged_gas) * base_fee_per_gas
+    }
+}
+
+/// Accumulates [`PaymasterSpending`] for all paymasters encountered in a batch or miniblock.
+fn merge_paymaster_spending(
+    target: &mut HashMap<Address, PaymasterSpending>,
+    source: &HashMap<Address, PaymasterSpending>,
+) {
+    for (&paymaster, &spending) in source {
+        *target.entry(paymaster).or_default() += spending;
+    }
+}
+
 /// Most of the information needed to seal the l1 batch/mini-block is contained within the VM,
 /// things that are not captured there are accumulated externally.
 /// `MiniblockUpdates` keeps updates for the pending mini-block.
@@ -80,7 +157,7 @@ impl UpdatesManager {
         miniblock_number: MiniblockNumber,
         l2_erc20_bridge_addr: Address,
         consensus: Option<ConsensusBlockFields>,
-        pre_insert_txs: bool,
+        pre_insert_txs: PreInsertTxs,
     ) -> MiniblockSealCommand {
         MiniblockSealCommand {
             l1_batch_number,
@@ -159,6 +236,18 @@ impl UpdatesManager {
     pub(crate) fn pending_txs_encoding_size(&self) -> usize {
         self.l1_batch.txs_encoding_size + self.miniblock.txs_encoding_size
     }
+
+    pub(crate) fn base_fee_per_gas(&self) -> u64 {
+        self.base_fee_per_gas
+    }
+
+    /// Returns resources spent by the specified paymaster on pending transactions.
+    pub(crate) fn pending_paymaster_spending(&self, paymaster: Address) -> PaymasterSpending {
+        let batch_spending = self.l1_batch.paymaster_spending.get(&paymaster);
+        let miniblock_spending = self.miniblock.paymaster_spending.get(&paymaster);
+        batch_spending.copied().unwrap_or_default()
+            + miniblock_spending.copied().unwrap_or_default()
+    }
 }
 
 /// Command to seal a miniblock containing all necessary data for it.
@@ -175,10 +264,30 @@ pub(crate) struct MiniblockSealCommand {
     pub protocol_version: Option<ProtocolVersionId>,
     pub l2_erc20_bridge_addr: Address,
     pub consensus: Option<ConsensusBlockFields>,
-    /// Whether transactions should be pre-inserted to DB.
-    /// Should be set to `true` for EN's IO as EN doesn't store transactions in DB
-    /// before they are included into miniblocks.
-    pub pre_insert_txs: bool,
+    /// Transactions that should be pre-inserted to DB.
+    pub pre_insert_txs: PreInsertTxs,
+}
+
+/// Transactions in a miniblock that are not stored in DB before they are included into the miniblock,
+/// and thus should be inserted when sealing it.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum PreInsertTxs {
+    #[default]
+    None,
+    /// Should be used for EN's IO as EN doesn't store transactions in DB before they are included into miniblocks.
+    All,
+    /// Transactions with the specified hashes, e.g. ones proposed by an external block builder.
+    Only(HashSet<H256>),
+}
+
+impl PreInsertTxs {
+    pub fn contains(&self, tx_hash: &H256) -> bool {
+        match self {
+            Self::None => false,
+            Self::All => true,
+            Self::Only(hashes) => hashes.contains(tx_hash),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -225,4 +334,71 @@ mod tests {
         assert_eq!(updates_manager.miniblock.executed_transactions.len(), 0);
         assert_eq!(updates_manager.l1_batch.executed_transactions.len(), 1);
     }
+
+    #[test]
+    fn tracking_paymaster_spending() {
+        let paymaster = Address::repeat_byte(0x11);
+        let create_tx = |paymaster: Option<Address>| {
+            let mut tx = create_transaction(10, 100);
+            if let (Some(paymaster), ExecuteTransactionCommon::L2(data)) =
+                (paymaster, &mut tx.common_data)
+            {
+                data.paymaster_params.paymaster = paymaster;
+            }
+            tx
+        };
+        let execution_metrics = ExecutionMetrics {
+            gas_used: 100,
+            ..ExecutionMetrics::default()
+        };
+
+        let mut updates_manager = create_updates_manager();
+        for tx in [create_tx(Some(paymaster)), create_tx(None)] {
+            updates_manager.extend_from_executed_transaction(
+                tx,
+                create_execution_result(0, []),
+                vec![],
+                new_block_gas_count(),
+                execution_metrics,
+                vec![],
+            );
+        }
+        let expected_spending = PaymasterSpending {
+            tx_count: 1,
+            gas_used: 100,
+            charged_gas: 1_000,
+        };
+        assert_eq!(
+            updates_manager.pending_paymaster_spending(paymaster),
+            expected_spending
+        );
+
+        updates_manager.push_miniblock(MiniblockParams {
+            timestamp: 2,
+            virtual_blocks: 1,
+        });
+        assert_eq!(
+            updates_manager.pending_paymaster_spending(paymaster),
+            expected_spending
+        );
+        updates_manager.extend_from_executed_transaction(
+            create_tx(Some(paymaster)),
+            create_execution_result(0, []),
+            vec![],
+            new_block_gas_count(),
+            execution_metrics,
+            vec![],
+        );
+
+        let spending = updates_manager.pending_paymaster_spending(paymaster);
+        assert_eq!(spending, expected_spending + expected_spending);
+        assert_eq!(
+            spending.fee(updates_manager.base_fee_per_gas()),
+            U256::from(2_000) * updates_manager.base_fee_per_gas()
+        );
+        assert_eq!(
+            updates_manager.pending_paymaster_spending(Address::repeat_byte(0x22)),
+            PaymasterSpending::default()
+        );
+    }
 }
@@ -7,13 +7,12 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType, api::BlockDetails, L1BatchNumber, MiniblockNumber,
     H256,
 };
-use zksync_web3_decl::{
-    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
-    namespaces::ZksNamespaceClient,
-    RpcResult,
-};
+use zksync_web3_decl::{namespaces::ZksNamespaceClient, RpcResult};
 
-use super::metrics::{FetchStage, L1BatchStage, FETCHER_METRICS};
+use super::{
+    metrics::{FetchStage, L1BatchStage, FETCHER_METRICS},
+    FailoverMainNodeClient,
+};
 use crate::metrics::EN_METRICS;
 
 /// Represents a change in the batch status.
@@ -51,7 +50,7 @@ impl StatusChanges {
 /// the module updates the database to mirror the state observable from the main node.
 #[derive(Debug)]
 pub struct BatchStatusUpdater {
-    client: HttpClient,
+    client: FailoverMainNodeClient,
     pool: ConnectionPool,
 
     last_executed_l1_batch: L1BatchNumber,
@@ -60,11 +59,7 @@ pub struct BatchStatusUpdater {
 }
 
 impl BatchStatusUpdater {
-    pub async fn new(main_node_url: &str, pool: ConnectionPool) -> Self {
-        let client = HttpClientBuilder::default()
-            .build(main_node_url)
-            .expect("Unable to create a main node client");
-
+    pub async fn new(client: FailoverMainNodeClient, pool: ConnectionPool) -> Self {
         let mut storage = pool.access_storage_tagged("sync_layer").await.unwrap();
         let last_executed_l1_batch = storage
             .blocks_dal()
@@ -150,18 +145,25 @@ impl BatchStatusUpdater {
             // While we may receive `None` for the `self.current_l1_batch`, it's OK: open batch is guaranteed to not
             // be sent to L1.
             let request_latency = FETCHER_METRICS.requests[&FetchStage::GetMiniblockRange].start();
-            let Some((start_miniblock, _)) = self.client.get_miniblock_range(batch).await? else {
+            let miniblock_range = self
+                .client
+                .call(|client| async move { client.get_miniblock_range(batch).await })
+                .await?;
+            let Some((start_miniblock, _)) = miniblock_range else {
                 return Ok(());
             };
             request_latency.observe();
 
             // We could've used any miniblock from the range, all of them share the same info.
             let request_latency = FETCHER_METRICS.requests[&FetchStage::GetBlockDetails].start();
-            let Some(batch_info) = self
+            let start_miniblock_number = MiniblockNumber(start_miniblock.as_u32());
+            let batch_info = self
                 .client
-                .get_block_details(MiniblockNumber(start_miniblock.as_u32()))
-                .await?
-            else {
+                .call(
+                    |client| async move { client.get_block_details(start_miniblock_number).await },
+                )
+                .await?;
+            let Some(batch_info) = batch_info else {
                 // We cannot recover from an external API inconsistency.
                 panic!(
                     "Node API is inconsistent: miniblock {} was reported to be a part of {} L1 batch, \
@@ -26,7 +26,7 @@ use crate::{
         },
         metrics::KEEPER_METRICS,
         seal_criteria::IoSealCriteria,
-        updates::UpdatesManager,
+        updates::{PreInsertTxs, UpdatesManager},
     },
 };
 
@@ -462,7 +462,7 @@ impl StateKeeperIO for ExternalIO {
             self.current_miniblock_number,
             self.l2_erc20_bridge_addr,
             consensus,
-            true,
+            PreInsertTxs::All,
         );
         self.miniblock_sealer_handle.submit(command).await;
 
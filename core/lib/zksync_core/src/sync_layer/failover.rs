@@ -0,0 +1,311 @@
+//! Main node client failing over between multiple main node URLs.
+
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_contracts::SystemContractCode;
+use zksync_types::{api, api::en::SyncBlock, Address, MiniblockNumber, ProtocolVersionId, H256};
+use zksync_web3_decl::{
+    jsonrpsee::{core::ClientError as RpcError, http_client::HttpClient},
+    namespaces::EthNamespaceClient,
+};
+
+use super::{client::MainNodeClient, metrics::MAIN_NODE_CLIENT_METRICS};
+
+/// Index of the primary main node in the list of main nodes.
+const PRIMARY_IDX: usize = 0;
+
+/// Errors returned by main node requests.
+pub(crate) trait MainNodeError: fmt::Display {
+    /// Checks whether the error signals that the main node is unreachable (as opposed to, e.g., the main node
+    /// rejecting the request). Only such errors lead to failing over to another main node.
+    fn is_transient(&self) -> bool;
+}
+
+impl MainNodeError for RpcError {
+    fn is_transient(&self) -> bool {
+        matches!(self, Self::Transport(_) | Self::RequestTimeout)
+    }
+}
+
+impl MainNodeError for anyhow::Error {
+    fn is_transient(&self) -> bool {
+        self.downcast_ref::<RpcError>()
+            .map_or(false, RpcError::is_transient)
+    }
+}
+
+#[derive(Debug)]
+struct MainNode {
+    url: String,
+    client: HttpClient,
+}
+
+#[derive(Debug)]
+struct Inner {
+    main_nodes: Vec<MainNode>,
+    active_idx: AtomicUsize,
+}
+
+/// JSON-RPC client for the main node supporting multiple main node URLs, the first of which is the primary one.
+///
+/// Requests are sent to a single main node. If a request fails because the main node is unreachable
+/// (i.e., with a transport error or a timeout), the client fails over to the next main node and retries
+/// the request. The new main node remains in use (i.e., the client is sticky) until it becomes unreachable itself,
+/// or until the primary main node is reachable again according to [`Self::run_health_probe()`].
+#[derive(Debug, Clone)]
+pub struct FailoverMainNodeClient {
+    inner: Arc<Inner>,
+}
+
+impl FailoverMainNodeClient {
+    /// Creates a client for the specified URLs. The first URL corresponds to the primary main node.
+    pub fn new(urls: &[String]) -> anyhow::Result<Self> {
+        anyhow::ensure!(!urls.is_empty(), "No main node URLs specified");
+        let main_nodes = urls
+            .iter()
+            .map(|url| {
+                let client = <dyn MainNodeClient>::json_rpc(url)
+                    .with_context(|| format!("Failed creating JSON-RPC client for {url}"))?;
+                Ok(MainNode {
+                    url: url.clone(),
+                    client,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        MAIN_NODE_CLIENT_METRICS
+            .active_main_node
+            .set(PRIMARY_IDX as u64);
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                main_nodes,
+                active_idx: AtomicUsize::new(PRIMARY_IDX),
+            }),
+        })
+    }
+
+    fn active_idx(&self) -> usize {
+        self.inner.active_idx.load(Ordering::SeqCst)
+    }
+
+    fn fail_over(&self, failed_idx: usize, err: &dyn fmt::Display) {
+        let main_nodes = &self.inner.main_nodes;
+        let next_idx = (failed_idx + 1) % main_nodes.len();
+        // If the exchange fails, the client was already switched to another main node by a concurrent request.
+        let exchange_result = self.inner.active_idx.compare_exchange(
+            failed_idx,
+            next_idx,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        if exchange_result.is_ok() {
+            tracing::warn!(
+                "Main node at {} is unreachable ({err}); failing over to main node at {}",
+                main_nodes[failed_idx].url,
+                main_nodes[next_idx].url
+            );
+            MAIN_NODE_CLIENT_METRICS.failovers.inc();
+            MAIN_NODE_CLIENT_METRICS
+                .active_main_node
+                .set(next_idx as u64);
+        }
+    }
+
+    /// Performs a request to the active main node, failing over to other main nodes on transient errors.
+    /// Each main node is tried at most once; if all main nodes are unreachable, the last error is returned.
+    pub(crate) async fn call<T, E, F, Fut>(&self, request: F) -> Result<T, E>
+    where
+        E: MainNodeError,
+        F: Fn(HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempts = 1;
+        loop {
+            let active_idx = self.active_idx();
+            let client = self.inner.main_nodes[active_idx].client.clone();
+            match request(client).await {
+                Err(err) if err.is_transient() && attempts < self.inner.main_nodes.len() => {
+                    self.fail_over(active_idx, &err);
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Periodically probes the primary main node while the client is failed over to another main node,
+    /// and switches the client back to the primary main node once it is reachable.
+    pub async fn run_health_probe(
+        self,
+        probe_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            let active_idx = self.active_idx();
+            if active_idx != PRIMARY_IDX {
+                let primary = &self.inner.main_nodes[PRIMARY_IDX];
+                match primary.client.get_block_number().await {
+                    Ok(_) => {
+                        let exchange_result = self.inner.active_idx.compare_exchange(
+                            active_idx,
+                            PRIMARY_IDX,
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        );
+                        if exchange_result.is_ok() {
+                            tracing::info!(
+                                "Primary main node at {} is reachable again; reconnecting to it",
+                                primary.url
+                            );
+                            MAIN_NODE_CLIENT_METRICS
+                                .active_main_node
+                                .set(PRIMARY_IDX as u64);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::debug!(
+                            "Primary main node at {} is still unreachable: {err}",
+                            primary.url
+                        );
+                    }
+                }
+            }
+
+            if tokio::time::timeout(probe_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, main node health probe is shutting down");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for FailoverMainNodeClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        hash: H256,
+    ) -> anyhow::Result<SystemContractCode> {
+        self.call(|client| async move { client.fetch_system_contract_by_hash(hash).await })
+            .await
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        address: Address,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        self.call(|client| async move { client.fetch_genesis_contract_bytecode(address).await })
+            .await
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<api::ProtocolVersion> {
+        self.call(|client| async move { client.fetch_protocol_version(protocol_version).await })
+            .await
+    }
+
+    async fn fetch_genesis_l1_batch_hash(&self) -> anyhow::Result<H256> {
+        self.call(|client| async move { client.fetch_genesis_l1_batch_hash().await })
+            .await
+    }
+
+    async fn fetch_l2_block_number(&self) -> anyhow::Result<MiniblockNumber> {
+        self.call(|client| async move { client.fetch_l2_block_number().await })
+            .await
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> anyhow::Result<Option<SyncBlock>> {
+        self.call(|client| async move { client.fetch_l2_block(number, with_transactions).await })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_client() -> FailoverMainNodeClient {
+        let urls = ["http://127.0.0.1:3050", "http://127.0.0.1:3051"].map(String::from);
+        FailoverMainNodeClient::new(&urls).unwrap()
+    }
+
+    #[tokio::test]
+    async fn failing_over_on_transient_errors() {
+        let client = create_client();
+        let attempts = AtomicUsize::new(0);
+        client
+            .call(|_| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        Err(RpcError::RequestTimeout)
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(client.active_idx(), 1);
+
+        // The client should stick to the new main node.
+        client
+            .call(|_| async { Ok::<_, RpcError>(()) })
+            .await
+            .unwrap();
+        assert_eq!(client.active_idx(), 1);
+    }
+
+    #[tokio::test]
+    async fn not_failing_over_on_fatal_errors() {
+        let client = create_client();
+        let attempts = AtomicUsize::new(0);
+        let err = client
+            .call(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(anyhow::Error::from(RpcError::HttpNotImplemented)) }
+            })
+            .await
+            .unwrap_err();
+        assert!(!err.is_transient());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(client.active_idx(), PRIMARY_IDX);
+    }
+
+    #[tokio::test]
+    async fn each_main_node_is_tried_once() {
+        let client = create_client();
+        let attempts = AtomicUsize::new(0);
+        let err = client
+            .call(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(RpcError::RequestTimeout) }
+            })
+            .await
+            .unwrap_err();
+        assert!(err.is_transient());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(client.active_idx(), 1);
+    }
+}
@@ -7,18 +7,23 @@ use zksync_types::{
 };
 
 use super::client::MainNodeClient;
-use crate::genesis::{ensure_genesis_state, GenesisParams};
+use crate::genesis::{ensure_genesis_state, GenesisParams, GenesisStateDump};
 
+/// Performs genesis if the node storage is empty. If the main node genesis was pre-seeded with a state dump,
+/// the same `state_dump` must be provided; the dump cannot be fetched from the main node, and without it
+/// the genesis root hash won't match the main node.
 pub async fn perform_genesis_if_needed(
     storage: &mut StorageProcessor<'_>,
     zksync_chain_id: L2ChainId,
     client: &dyn MainNodeClient,
+    state_dump: Option<GenesisStateDump>,
 ) -> anyhow::Result<()> {
     let mut transaction = storage.start_transaction().await?;
     // We want to check whether the genesis is needed before we create genesis params to not
     // make the node startup slower.
     let genesis_block_hash = if transaction.blocks_dal().is_genesis_needed().await? {
-        let genesis_params = create_genesis_params(client).await?;
+        let mut genesis_params = create_genesis_params(client).await?;
+        genesis_params.state_dump = state_dump;
         ensure_genesis_state(&mut transaction, zksync_chain_id, &genesis_params)
             .await
             .context("ensure_genesis_state")?
@@ -95,6 +100,7 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
         first_validator,
         first_l1_verifier_config,
         first_verifier_address,
+        state_dump: None,
     })
 }
 
@@ -107,7 +113,9 @@ async fn validate_genesis_state(
     let genesis_l1_batch_hash = client.fetch_genesis_l1_batch_hash().await?;
     anyhow::ensure!(
         genesis_l1_batch_hash == root_hash,
-        "Genesis L1 batch root hash mismatch with main node: expected {root_hash}, got {genesis_l1_batch_hash}"
+        "Genesis L1 batch root hash mismatch with main node: expected {root_hash}, got {genesis_l1_batch_hash}. \
+         If the main node genesis was pre-seeded with a state dump, restart the node on an empty database \
+         with the same dump provided via `--genesis-state-dump`"
     );
     Ok(())
 }
@@ -174,6 +174,10 @@ impl<T: ContiguousBlockStore> Buffered<T> {
         &self.inner
     }
 
+    pub(super) fn into_inner(self) -> T {
+        self.inner
+    }
+
     #[cfg(test)]
     async fn buffer_len(&self) -> usize {
         self.buffer.lock().await.blocks.len()
@@ -1,17 +1,18 @@
 //! Consensus adapter for EN synchronization logic.
 
-use std::sync::Arc;
+use std::{fs, path::Path, sync::Arc};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_concurrency::{ctx, error::Wrap as _, scope};
+use zksync_consensus_crypto::Text;
 use zksync_consensus_executor::{Executor, ExecutorConfig};
 use zksync_consensus_roles::node;
 use zksync_dal::ConnectionPool;
 use zksync_types::Address;
 
 use self::{buffered::Buffered, storage::PostgresBlockStorage};
-use super::{fetcher::FetcherCursor, sync_action::ActionQueueSender};
+use super::{fetcher::FetcherCursor, sync_action::ActionQueueSender, MainNodeClient, SyncState};
 
 mod buffered;
 mod conversions;
@@ -21,25 +22,69 @@ mod storage;
 mod tests;
 mod utils;
 
-/// Starts fetching L2 blocks using peer-to-peer gossip network.
+/// Configuration of the gossip fetcher.
+#[derive(Debug)]
+pub struct GossipConfig {
+    /// Configuration of the consensus executor, including the gossip network params and the validator set
+    /// used to verify signatures of received L2 blocks.
+    pub executor: ExecutorConfig,
+    /// Secret key of the node in the gossip network.
+    pub node_key: node::SecretKey,
+}
+
+impl GossipConfig {
+    /// Reads the executor config (in the JSON encoding of the corresponding Protobuf message)
+    /// and the node secret key (in the text encoding) from the specified files.
+    pub fn read(executor_config_path: &Path, node_key_path: &Path) -> anyhow::Result<Self> {
+        let executor_config = fs::read_to_string(executor_config_path).with_context(|| {
+            format!("failed reading executor config from {executor_config_path:?}")
+        })?;
+        let mut deserializer = serde_json::Deserializer::from_str(&executor_config);
+        let executor = zksync_protobuf::serde::deserialize(&mut deserializer)
+            .context("failed decoding executor config")?;
+        deserializer
+            .end()
+            .context("trailing data after executor config")?;
+
+        let node_key = fs::read_to_string(node_key_path)
+            .with_context(|| format!("failed reading node key from {node_key_path:?}"))?;
+        let node_key = Text::new(node_key.trim())
+            .decode()
+            .context("failed decoding node key")?;
+        Ok(Self { executor, node_key })
+    }
+}
+
+/// Starts fetching L2 blocks using peer-to-peer gossip network. Received blocks are only accepted if they are
+/// signed by the validators specified in the gossip `config`.
+///
+/// If the gossip fetcher fails, fetching falls back to JSON-RPC requests to the main node via `main_node_client`,
+/// starting from the first L2 block not processed by the gossip fetcher.
 pub async fn run_gossip_fetcher(
     pool: ConnectionPool,
     actions: ActionQueueSender,
-    executor_config: ExecutorConfig,
-    node_key: node::SecretKey,
+    config: GossipConfig,
+    main_node_client: Box<dyn MainNodeClient>,
+    sync_state: SyncState,
     mut stop_receiver: watch::Receiver<bool>,
     operator_address: Address,
 ) -> anyhow::Result<()> {
-    scope::run!(&ctx::root(), |ctx, s| async {
-        s.spawn_bg(run_gossip_fetcher_inner(
-            ctx,
-            pool,
-            actions,
-            executor_config,
-            node_key,
-            operator_address,
-        ));
-        if stop_receiver.changed().await.is_err() {
+    let ctx = &ctx::root();
+    let GossipConfig {
+        executor: executor_config,
+        node_key,
+    } = config;
+    tracing::info!(
+        "Starting gossip fetcher with {executor_config:?} and node key {:?}",
+        node_key.public()
+    );
+    let store = create_gossip_storage(ctx, pool, actions, &executor_config, operator_address)
+        .await
+        .context("create_gossip_storage()")?;
+
+    let result = scope::run!(ctx, |ctx, s| async {
+        s.spawn_bg(run_executor(ctx, &store, executor_config, node_key));
+        if ctx.wait(stop_receiver.changed()).await?.is_err() {
             tracing::warn!(
                 "Stop signal sender for gossip fetcher was dropped without sending a signal"
             );
@@ -47,22 +92,32 @@ pub async fn run_gossip_fetcher(
         tracing::info!("Stop signal received, gossip fetcher is shutting down");
         Ok(())
     })
-    .await
+    .await;
+
+    let err = match result {
+        Ok(()) => return Ok(()),
+        Err(_) if *stop_receiver.borrow() => return Ok(()),
+        Err(err) => err,
+    };
+    tracing::error!(
+        "Gossip fetcher failed, falling back to fetching L2 blocks via JSON-RPC: {err:#}"
+    );
+    let store = Arc::try_unwrap(store)
+        .map_err(|_| anyhow::anyhow!("gossip fetcher storage is still in use"))?;
+    let (actions, cursor) = store.into_inner().into_parts();
+    cursor
+        .into_fetcher(main_node_client, actions, sync_state, stop_receiver)
+        .run()
+        .await
 }
 
-async fn run_gossip_fetcher_inner(
+async fn create_gossip_storage(
     ctx: &ctx::Ctx,
     pool: ConnectionPool,
     actions: ActionQueueSender,
-    executor_config: ExecutorConfig,
-    node_key: node::SecretKey,
+    executor_config: &ExecutorConfig,
     operator_address: Address,
-) -> anyhow::Result<()> {
-    tracing::info!(
-        "Starting gossip fetcher with {executor_config:?} and node key {:?}",
-        node_key.public()
-    );
-
+) -> anyhow::Result<Arc<Buffered<PostgresBlockStorage>>> {
     let mut storage = pool
         .access_storage_tagged("sync_layer")
         .await
@@ -82,9 +137,16 @@ async fn run_gossip_fetcher_inner(
     )
     .await
     .wrap("PostgresBlockStorage::new()")?;
-    let buffered = Arc::new(Buffered::new(store));
-    let store = buffered.inner();
+    Ok(Arc::new(Buffered::new(store)))
+}
 
+async fn run_executor(
+    ctx: &ctx::Ctx,
+    buffered: &Arc<Buffered<PostgresBlockStorage>>,
+    executor_config: ExecutorConfig,
+    node_key: node::SecretKey,
+) -> anyhow::Result<()> {
+    let store = buffered.inner();
     scope::run!(ctx, |ctx, s| async {
         let executor = Executor::new(ctx, executor_config, node_key, buffered.clone())
             .await
@@ -106,3 +168,18 @@ async fn run_gossip_fetcher_inner(
     })
     .await
 }
+
+#[cfg(test)]
+async fn run_gossip_fetcher_inner(
+    ctx: &ctx::Ctx,
+    pool: ConnectionPool,
+    actions: ActionQueueSender,
+    executor_config: ExecutorConfig,
+    node_key: node::SecretKey,
+    operator_address: Address,
+) -> anyhow::Result<()> {
+    let store = create_gossip_storage(ctx, pool, actions, &executor_config, operator_address)
+        .await
+        .context("create_gossip_storage()")?;
+    run_executor(ctx, &store, executor_config, node_key).await
+}
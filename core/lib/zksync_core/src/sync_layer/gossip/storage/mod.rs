@@ -125,6 +125,12 @@ impl PostgresBlockStorage {
         }
     }
 
+    /// Returns the action queue sender and the cursor pointing to the first L2 block that was not queued
+    /// for the state keeper, so that L2 blocks can be fetched in a different way.
+    pub fn into_parts(self) -> (ActionQueueSender, FetcherCursor) {
+        (self.actions, self.cursor.into_inner().inner)
+    }
+
     async fn ensure_genesis_block(
         ctx: &ctx::Ctx,
         storage: &mut StorageProcessor<'_>,
@@ -57,3 +57,29 @@ pub(super) struct ActionQueueMetrics {
 
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
+
+/// Metrics for the main node client with failover.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_main_node_client")]
+pub(super) struct MainNodeClientMetrics {
+    /// Index of the main node the client sends requests to; 0 is the primary main node.
+    pub active_main_node: Gauge<u64>,
+    /// Number of times the client failed over to another main node.
+    pub failovers: Counter,
+}
+
+#[vise::register]
+pub(super) static MAIN_NODE_CLIENT_METRICS: vise::Global<MainNodeClientMetrics> =
+    vise::Global::new();
+
+/// Metrics for the tree data fetcher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_tree_data_fetcher")]
+pub(super) struct TreeDataFetcherMetrics {
+    /// Number of the last L1 batch with tree data fetched from the remote Merkle tree API.
+    pub last_updated_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static TREE_DATA_FETCHER_METRICS: vise::Global<TreeDataFetcherMetrics> =
+    vise::Global::new();
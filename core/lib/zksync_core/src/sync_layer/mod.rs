@@ -1,6 +1,7 @@
 pub mod batch_status_updater;
 mod client;
 pub mod external_io;
+mod failover;
 pub mod fetcher;
 pub mod genesis;
 mod gossip;
@@ -9,8 +10,13 @@ pub(crate) mod sync_action;
 mod sync_state;
 #[cfg(test)]
 mod tests;
+pub mod tree_data_fetcher;
 
 pub use self::{
-    client::MainNodeClient, external_io::ExternalIO, gossip::run_gossip_fetcher,
-    sync_action::ActionQueue, sync_state::SyncState,
+    client::MainNodeClient,
+    external_io::ExternalIO,
+    failover::FailoverMainNodeClient,
+    gossip::{run_gossip_fetcher, GossipConfig},
+    sync_action::ActionQueue,
+    sync_state::SyncState,
 };
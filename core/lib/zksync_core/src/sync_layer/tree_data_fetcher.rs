@@ -0,0 +1,257 @@
+//! Fetcher of Merkle tree data for L1 batches, used by the external node running without a local Merkle tree.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+use super::metrics::TREE_DATA_FETCHER_METRICS;
+use crate::api_server::tree::{is_missing_version_error, TreeApiClient, TreeApiHttpClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepOutcome {
+    UpdatedBatch(L1BatchNumber),
+    NoProgress,
+    RemoteTreeIsLagging,
+    RemoteTreeUnavailable,
+}
+
+/// Component filling in Merkle tree data (the root hash and the next leaf index) for sealed L1 batches
+/// by querying a remote Merkle tree API, instead of maintaining the Merkle tree locally.
+///
+/// Fetched data is not verified by this component; a root hash mismatch with the main node will be detected
+/// by the reorg detector.
+pub struct TreeDataFetcher {
+    tree_api: Box<dyn TreeApiClient + Send + Sync>,
+    pool: ConnectionPool,
+    poll_interval: Duration,
+}
+
+impl fmt::Debug for TreeDataFetcher {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TreeDataFetcher")
+            .field("pool", &self.pool)
+            .field("poll_interval", &self.poll_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TreeDataFetcher {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates a fetcher for the Merkle tree API server located at `tree_api_url`.
+    pub fn new(tree_api_url: &str, pool: ConnectionPool) -> Self {
+        Self {
+            tree_api: Box::new(TreeApiHttpClient::new(tree_api_url)),
+            pool,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    async fn step(&self) -> anyhow::Result<StepOutcome> {
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?;
+        let Some(last_l1_batch_with_metadata) = last_l1_batch_with_metadata else {
+            // Genesis or snapshot recovery is not completed yet.
+            return Ok(StepOutcome::NoProgress);
+        };
+        let l1_batch_to_fetch = last_l1_batch_with_metadata + 1;
+        let sealed_l1_batch_number = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        if l1_batch_to_fetch > sealed_l1_batch_number {
+            return Ok(StepOutcome::NoProgress);
+        }
+        drop(storage);
+
+        let tree_data = match self
+            .tree_api
+            .get_l1_batch_tree_data(l1_batch_to_fetch)
+            .await
+        {
+            Ok(tree_data) => tree_data,
+            Err(err) if is_missing_version_error(&err) => {
+                tracing::debug!(
+                    "L1 batch #{l1_batch_to_fetch} is not processed by the remote Merkle tree yet"
+                );
+                return Ok(StepOutcome::RemoteTreeIsLagging);
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed fetching tree data for L1 batch #{l1_batch_to_fetch}: {err:#}"
+                );
+                return Ok(StepOutcome::RemoteTreeUnavailable);
+            }
+        };
+
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(l1_batch_to_fetch, &tree_data)
+            .await
+            .with_context(|| {
+                format!("failed saving tree data for L1 batch #{l1_batch_to_fetch}")
+            })?;
+        TREE_DATA_FETCHER_METRICS
+            .last_updated_l1_batch
+            .set(l1_batch_to_fetch.0.into());
+        Ok(StepOutcome::UpdatedBatch(l1_batch_to_fetch))
+    }
+
+    /// Runs the fetcher until a stop signal is received.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            let need_to_wait = match self.step().await? {
+                StepOutcome::UpdatedBatch(l1_batch_number) => {
+                    tracing::info!("Updated tree data for L1 batch #{l1_batch_number}");
+                    false
+                }
+                StepOutcome::NoProgress
+                | StepOutcome::RemoteTreeIsLagging
+                | StepOutcome::RemoteTreeUnavailable => true,
+            };
+
+            if need_to_wait
+                && tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                    .await
+                    .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, tree data fetcher is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_dal::StorageProcessor;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader, L1BatchTreeData},
+        Address, L2ChainId, ProtocolVersionId, H256, U256,
+    };
+
+    use super::*;
+    use crate::{
+        api_server::tree::{TreeEntry, TreeEntryWithProof},
+        genesis::{ensure_genesis_state, GenesisParams},
+        metadata_calculator::MerkleTreeInfo,
+    };
+
+    #[derive(Debug, Default)]
+    struct MockTreeApiClient(HashMap<L1BatchNumber, L1BatchTreeData>);
+
+    #[async_trait]
+    impl TreeApiClient for MockTreeApiClient {
+        async fn get_info(&self) -> anyhow::Result<MerkleTreeInfo> {
+            anyhow::bail!("not supported by mock client")
+        }
+
+        async fn get_proofs(
+            &self,
+            _l1_batch_number: L1BatchNumber,
+            _hashed_keys: Vec<U256>,
+        ) -> anyhow::Result<Vec<TreeEntryWithProof>> {
+            anyhow::bail!("not supported by mock client")
+        }
+
+        async fn get_entries(
+            &self,
+            _l1_batch_number: L1BatchNumber,
+            _hashed_keys: Vec<U256>,
+        ) -> anyhow::Result<Vec<TreeEntry>> {
+            anyhow::bail!("not supported by mock client")
+        }
+
+        async fn get_l1_batch_tree_data(
+            &self,
+            l1_batch_number: L1BatchNumber,
+        ) -> anyhow::Result<L1BatchTreeData> {
+            self.0
+                .get(&l1_batch_number)
+                .copied()
+                .with_context(|| format!("L1 batch #{l1_batch_number} is unknown"))
+        }
+    }
+
+    async fn seal_l1_batch(storage: &mut StorageProcessor<'_>, number: u32) {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_l1_batch(&header, &[], BlockGasCount::default(), &[], &[])
+            .await
+            .unwrap();
+    }
+
+    fn mock_tree_data(number: u32) -> L1BatchTreeData {
+        L1BatchTreeData {
+            hash: H256::from_low_u64_be(number.into()),
+            rollup_last_leaf_index: u64::from(number) * 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn fetching_tree_data() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        seal_l1_batch(&mut storage, 1).await;
+
+        let tree_api = MockTreeApiClient(HashMap::from([
+            (L1BatchNumber(1), mock_tree_data(1)),
+            (L1BatchNumber(2), mock_tree_data(2)),
+        ]));
+        let fetcher = TreeDataFetcher {
+            tree_api: Box::new(tree_api),
+            pool: pool.clone(),
+            poll_interval: TreeDataFetcher::DEFAULT_POLL_INTERVAL,
+        };
+
+        let outcome = fetcher.step().await.unwrap();
+        assert_eq!(outcome, StepOutcome::UpdatedBatch(L1BatchNumber(1)));
+        let outcome = fetcher.step().await.unwrap();
+        assert_eq!(outcome, StepOutcome::NoProgress);
+
+        seal_l1_batch(&mut storage, 2).await;
+        let outcome = fetcher.step().await.unwrap();
+        assert_eq!(outcome, StepOutcome::UpdatedBatch(L1BatchNumber(2)));
+
+        for number in [1, 2] {
+            let root_hash = storage
+                .blocks_dal()
+                .get_l1_batch_state_root(L1BatchNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(root_hash, Some(mock_tree_data(number).hash));
+        }
+
+        // L1 batch #3 is unknown to the remote tree.
+        seal_l1_batch(&mut storage, 3).await;
+        let outcome = fetcher.step().await.unwrap();
+        assert_eq!(outcome, StepOutcome::RemoteTreeUnavailable);
+        let root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(root_hash, None);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{collections::HashSet, sync::Arc, time::Instant};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::task::JoinHandle;
+use zksync_dal::{tee_verifier_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPool};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_queued_job_processor::JobProcessor;
+use zksync_types::{
+    proofs::PrepareBasicCircuitsJob, tee_types::TeeVerifierInput,
+    witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId,
+};
+use zksync_utils::u256_to_h256;
+
+use self::metrics::METRICS;
+
+mod metrics;
+
+/// Component that assembles all data necessary for a TEE prover to re-execute an L1 batch and verify its
+/// state transition: batch execution params and transactions from DB, plus storage reads and Merkle paths
+/// from the object store. Runs after the [`BasicWitnessInputProducer`](crate::basic_witness_input_producer)
+/// and uploads TEE verifier inputs to the object store, from which they are served to TEE provers.
+#[derive(Debug)]
+pub struct TeeVerifierInputProducer {
+    connection_pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl TeeVerifierInputProducer {
+    pub async fn new(
+        connection_pool: ConnectionPool,
+        store_factory: &ObjectStoreFactory,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        Ok(TeeVerifierInputProducer {
+            connection_pool,
+            object_store: store_factory.create_store().await.into(),
+            l2_chain_id,
+        })
+    }
+
+    async fn process_job_impl(
+        l1_batch_number: L1BatchNumber,
+        started_at: Instant,
+        connection_pool: ConnectionPool,
+        object_store: Arc<dyn ObjectStore>,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<TeeVerifierInput> {
+        let witness_block_state: WitnessBlockState = object_store
+            .get(l1_batch_number)
+            .await
+            .context("failed to get WitnessBlockState from object store")?;
+        let merkle_paths: PrepareBasicCircuitsJob = object_store
+            .get(l1_batch_number)
+            .await
+            .context("failed to get PrepareBasicCircuitsJob from object store")?;
+
+        let mut connection = connection_pool
+            .access_storage()
+            .await
+            .context("failed to get connection for TeeVerifierInputProducer")?;
+        let l1_batch_header = connection
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("header is missing for L1 batch #{l1_batch_number}"))?;
+        let prev_l1_batch_hash = connection
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number - 1)
+            .await?
+            .with_context(|| {
+                format!("root hash is missing for L1 batch #{}", l1_batch_number - 1)
+            })?;
+        let l1_batch_root_hash = connection
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await?
+            .with_context(|| format!("root hash is missing for L1 batch #{l1_batch_number}"))?;
+        let miniblocks = connection
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?;
+
+        let base_system_contracts_hashes = l1_batch_header.base_system_contracts_hashes;
+        let used_contract_hashes: HashSet<_> = l1_batch_header
+            .used_contract_hashes
+            .iter()
+            .map(|&hash| u256_to_h256(hash))
+            .chain([
+                base_system_contracts_hashes.bootloader,
+                base_system_contracts_hashes.default_aa,
+            ])
+            .collect();
+        let used_contracts = connection
+            .storage_dal()
+            .get_factory_deps(&used_contract_hashes)
+            .await
+            .into_iter()
+            .map(|(hash, bytecode)| (u256_to_h256(hash), bytecode.concat()))
+            .collect();
+
+        METRICS.process_batch_time.observe(started_at.elapsed());
+        tracing::info!(
+            "TeeVerifierInputProducer took {:?} for L1BatchNumber {}",
+            started_at.elapsed(),
+            l1_batch_number.0
+        );
+
+        Ok(TeeVerifierInput {
+            l1_batch_header,
+            prev_l1_batch_hash,
+            l1_batch_root_hash,
+            l2_chain_id,
+            miniblocks,
+            used_contracts,
+            witness_block_state,
+            merkle_paths,
+        })
+    }
+}
+
+#[async_trait]
+impl JobProcessor for TeeVerifierInputProducer {
+    type Job = L1BatchNumber;
+    type JobId = L1BatchNumber;
+    type JobArtifacts = TeeVerifierInput;
+    const SERVICE_NAME: &'static str = "tee_verifier_input_producer";
+
+    async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
+        let mut connection = self.connection_pool.access_storage().await?;
+        let l1_batch_to_process = connection
+            .tee_verifier_input_producer_dal()
+            .get_next_tee_verifier_input_producer_job()
+            .await
+            .context("failed to get next TEE verifier input producer job")?;
+        Ok(l1_batch_to_process.map(|number| (number, number)))
+    }
+
+    async fn save_failure(&self, job_id: Self::JobId, started_at: Instant, error: String) {
+        let attempts = self
+            .connection_pool
+            .access_storage()
+            .await
+            .unwrap()
+            .tee_verifier_input_producer_dal()
+            .mark_job_as_failed(job_id, started_at, error)
+            .await
+            .expect("errored whilst marking job as failed");
+        if let Some(tries) = attempts {
+            tracing::warn!("Failed to process job: {job_id:?}, after {tries} tries.");
+        } else {
+            tracing::warn!("L1 Batch {job_id:?} was processed successfully by another worker.");
+        }
+    }
+
+    async fn process_job(
+        &self,
+        job: Self::Job,
+        started_at: Instant,
+    ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
+        let l2_chain_id = self.l2_chain_id;
+        let connection_pool = self.connection_pool.clone();
+        let object_store = self.object_store.clone();
+        tokio::spawn(Self::process_job_impl(
+            job,
+            started_at,
+            connection_pool,
+            object_store,
+            l2_chain_id,
+        ))
+    }
+
+    async fn save_result(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        artifacts: Self::JobArtifacts,
+    ) -> anyhow::Result<()> {
+        let upload_started_at = Instant::now();
+        let object_path = self
+            .object_store
+            .put(job_id, &artifacts)
+            .await
+            .context("failed to upload artifacts for TeeVerifierInputProducer")?;
+        METRICS
+            .upload_input_time
+            .observe(upload_started_at.elapsed());
+        let mut connection = self
+            .connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for TeeVerifierInputProducer")?;
+        let mut transaction = connection
+            .start_transaction()
+            .await
+            .context("failed to acquire DB transaction for TeeVerifierInputProducer")?;
+        transaction
+            .tee_verifier_input_producer_dal()
+            .mark_job_as_successful(job_id, started_at, &object_path)
+            .await
+            .context("failed to mark job as successful for TeeVerifierInputProducer")?;
+        transaction
+            .tee_proof_generation_dal()
+            .insert_tee_proof_generation_details(job_id)
+            .await;
+        transaction
+            .commit()
+            .await
+            .context("failed to commit DB transaction for TeeVerifierInputProducer")?;
+        METRICS.block_number_processed.set(job_id.0 as i64);
+        Ok(())
+    }
+
+    fn max_attempts(&self) -> u32 {
+        JOB_MAX_ATTEMPT as u32
+    }
+
+    async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
+        let mut connection = self
+            .connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for TeeVerifierInputProducer")?;
+        connection
+            .tee_verifier_input_producer_dal()
+            .get_tee_verifier_input_producer_job_attempts(*job_id)
+            .await
+            .map(|attempts| attempts.unwrap_or(0))
+            .context("failed to get job attempts for TeeVerifierInputProducer")
+    }
+}
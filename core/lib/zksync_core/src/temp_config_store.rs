@@ -7,8 +7,9 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
-        ProofDataHandlerConfig, ProverGroupConfig, WitnessGeneratorConfig,
+        CallTracesBackfillerConfig, DADispatcherConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, PrometheusConfig, ProofDataHandlerConfig, ProverGroupConfig,
+        UpgradeDryRunConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig, ProverConfigs,
@@ -46,4 +47,7 @@ pub struct TempConfigStore {
     pub gas_adjuster_config: Option<GasAdjusterConfig>,
     pub prover_configs: Option<ProverConfigs>,
     pub object_store_config: Option<ObjectStoreConfig>,
+    pub da_dispatcher_config: Option<DADispatcherConfig>,
+    pub upgrade_dry_run_config: Option<UpgradeDryRunConfig>,
+    pub call_traces_backfiller_config: Option<CallTracesBackfillerConfig>,
 }
@@ -0,0 +1,30 @@
+//! Protocol upgrade dry run metrics.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_upgrade_dry_run")]
+pub(super) struct UpgradeDryRunMetrics {
+    /// Latest pending protocol version for which the dry run was performed.
+    pub last_protocol_version: Gauge<u64>,
+    /// Number of transactions that succeeded before the upgrade, but failed after it, in the last dry run.
+    pub regressed_transactions: Gauge<usize>,
+    /// Number of transactions with changed execution status or gas usage in the last dry run.
+    pub changed_transactions: Gauge<usize>,
+    /// Number of mismatched events in the last dry run.
+    pub event_mismatches: Gauge<usize>,
+    /// Number of mismatched storage writes in the last dry run.
+    pub storage_write_mismatches: Gauge<usize>,
+    /// Number of L1 batches in the last dry run in which the upgrade transaction didn't succeed.
+    pub failed_upgrade_txs: Gauge<usize>,
+    /// Number of dry runs that could not be completed, e.g. because of a VM error.
+    pub failed_dry_runs: Counter,
+    /// Latency of dry-running a single L1 batch, i.e., replaying it under the current and pending protocol versions.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub l1_batch_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<UpgradeDryRunMetrics> = vise::Global::new();
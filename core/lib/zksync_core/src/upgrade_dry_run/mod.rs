@@ -0,0 +1,312 @@
+//! Dry run of pending protocol upgrades.
+//!
+//! Once a new protocol version is observed on L1 (i.e., it's saved to Postgres by the L1 watcher), but before it is
+//! activated by the state keeper, the latest sealed L1 batches are replayed in shadow mode twice: under the protocol
+//! version they were executed with, and under the pending version (with the new VM version, new base system contracts
+//! and the upgrade transaction executed at the start of the batch). Behavioral differences between the two runs are
+//! logged and reported as metrics, so that operators can catch upgrade regressions before the activation batch.
+//! Dry runs don't modify Postgres.
+
+use std::time::Instant;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::configs::UpgradeDryRunConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+};
+
+use self::metrics::METRICS;
+use crate::vm_replay::{
+    diff_events, diff_storage_writes, BatchExecutionOutput, BatchReplayer, BatchSource,
+    EventMismatch, PostgresBatchSource, StorageWriteMismatch,
+};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const SUCCESS_STATUS: &str = "success";
+
+/// Difference in execution of a transaction under the current and pending protocol versions.
+#[derive(Debug, Serialize)]
+pub struct TransactionDiff {
+    pub hash: H256,
+    pub miniblock: MiniblockNumber,
+    pub baseline_status: String,
+    pub upgraded_status: String,
+    pub baseline_gas_used: u32,
+    pub upgraded_gas_used: u32,
+}
+
+impl TransactionDiff {
+    /// Checks whether the transaction succeeded under the current protocol version, but fails under the pending one.
+    pub fn is_regression(&self) -> bool {
+        self.baseline_status == SUCCESS_STATUS && self.upgraded_status != SUCCESS_STATUS
+    }
+}
+
+/// Report of dry-running a pending protocol version on a single L1 batch. In event and storage write mismatches,
+/// `replayed` values correspond to the pending protocol version, and `stored` ones to the current version.
+#[derive(Debug, Serialize)]
+pub struct UpgradeDryRunReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub baseline_protocol_version: ProtocolVersionId,
+    pub upgraded_protocol_version: ProtocolVersionId,
+    /// Execution status of the upgrade transaction; `None` if the upgrade has no L2 transaction.
+    pub upgrade_tx_status: Option<String>,
+    pub transaction_diffs: Vec<TransactionDiff>,
+    /// Event mismatches, not taking into account events produced by the upgrade transaction.
+    pub event_mismatches: Vec<EventMismatch>,
+    /// Storage write mismatches. Unlike events, these include writes performed by the upgrade transaction.
+    pub storage_write_mismatches: Vec<StorageWriteMismatch>,
+}
+
+impl UpgradeDryRunReport {
+    pub fn regressions(&self) -> impl Iterator<Item = &TransactionDiff> + '_ {
+        self.transaction_diffs
+            .iter()
+            .filter(|diff| diff.is_regression())
+    }
+
+    pub fn is_upgrade_tx_successful(&self) -> bool {
+        self.upgrade_tx_status
+            .as_ref()
+            .map_or(true, |status| status == SUCCESS_STATUS)
+    }
+}
+
+/// Component dry-running pending protocol upgrades on recent L1 batches.
+#[derive(Debug)]
+pub struct UpgradeDryRunner {
+    config: UpgradeDryRunConfig,
+    pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+    last_dry_run_version: Option<ProtocolVersionId>,
+}
+
+impl UpgradeDryRunner {
+    pub fn new(config: UpgradeDryRunConfig, pool: ConnectionPool, l2_chain_id: L2ChainId) -> Self {
+        Self {
+            config,
+            pool,
+            l2_chain_id,
+            last_dry_run_version: None,
+        }
+    }
+
+    /// Returns the latest protocol version saved to Postgres if it's not used by any L1 batch yet.
+    async fn pending_version(&self) -> anyhow::Result<Option<ProtocolVersionId>> {
+        let mut storage = self.pool.access_storage_tagged("upgrade_dry_run").await?;
+        let last_version = storage.protocol_versions_dal().last_version_id().await;
+        let last_used_version = storage.protocol_versions_dal().last_used_version_id().await;
+        Ok(match (last_version, last_used_version) {
+            (Some(last), Some(last_used)) if last > last_used => Some(last),
+            _ => None,
+        })
+    }
+
+    /// Dry-runs the specified protocol version on the latest sealed L1 batches.
+    pub async fn dry_run(
+        &self,
+        version_id: ProtocolVersionId,
+    ) -> anyhow::Result<Vec<UpgradeDryRunReport>> {
+        let mut storage = self.pool.access_storage_tagged("upgrade_dry_run").await?;
+        let base_system_contracts = storage
+            .protocol_versions_dal()
+            .load_base_system_contracts_by_version_id(version_id as u16)
+            .await
+            .with_context(|| format!("protocol version {version_id:?} is not persisted"))?;
+        let upgrade_tx = storage
+            .protocol_versions_dal()
+            .get_protocol_upgrade_tx(version_id)
+            .await;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("failed getting sealed L1 batch number")?;
+        drop(storage);
+
+        // The genesis L1 batch cannot be replayed.
+        let first_l1_batch_number = sealed_l1_batch_number
+            .0
+            .saturating_sub(self.config.l1_batches_to_replay().saturating_sub(1))
+            .max(1);
+        let source = PostgresBatchSource::new(self.pool.clone(), self.l2_chain_id);
+        let replayer = BatchReplayer::new(self.pool.clone());
+        let upgrade_tx = upgrade_tx.map(Transaction::from);
+
+        let mut reports = vec![];
+        for number in first_l1_batch_number..=sealed_l1_batch_number.0 {
+            let l1_batch_number = L1BatchNumber(number);
+            let started_at = Instant::now();
+            let batch = source
+                .load_batch(l1_batch_number)
+                .await
+                .with_context(|| format!("failed loading L1 batch #{l1_batch_number}"))?;
+            let baseline_protocol_version = batch.system_env.version;
+            let baseline = replayer.execute(batch.clone()).await.with_context(|| {
+                format!("failed replaying L1 batch #{l1_batch_number} under the current protocol version")
+            })?;
+
+            let mut upgraded_batch = batch;
+            upgraded_batch.system_env.version = version_id;
+            upgraded_batch.system_env.base_system_smart_contracts = base_system_contracts.clone();
+            if let Some(upgrade_tx) = &upgrade_tx {
+                // The state keeper executes the upgrade transaction first in the activation batch.
+                upgraded_batch.miniblocks[0]
+                    .txs
+                    .insert(0, upgrade_tx.clone());
+            }
+            let upgraded = replayer.execute(upgraded_batch).await.with_context(|| {
+                format!("failed replaying L1 batch #{l1_batch_number} under protocol version {version_id:?}")
+            })?;
+
+            let report = compare_outputs(
+                l1_batch_number,
+                baseline_protocol_version,
+                version_id,
+                baseline,
+                upgraded,
+                upgrade_tx.as_ref().map(|tx| tx.hash()),
+            );
+            METRICS.l1_batch_latency.observe(started_at.elapsed());
+            log_report(&report);
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    fn report_metrics(version_id: ProtocolVersionId, reports: &[UpgradeDryRunReport]) {
+        let sum = |f: fn(&UpgradeDryRunReport) -> usize| reports.iter().map(f).sum::<usize>();
+        METRICS.last_protocol_version.set(version_id as u64);
+        METRICS
+            .regressed_transactions
+            .set(sum(|report| report.regressions().count()));
+        METRICS
+            .changed_transactions
+            .set(sum(|report| report.transaction_diffs.len()));
+        METRICS
+            .event_mismatches
+            .set(sum(|report| report.event_mismatches.len()));
+        METRICS
+            .storage_write_mismatches
+            .set(sum(|report| report.storage_write_mismatches.len()));
+        METRICS.failed_upgrade_txs.set(sum(|report| {
+            usize::from(!report.is_upgrade_tx_successful())
+        }));
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            let pending_version = self.pending_version().await?;
+            if let Some(version_id) = pending_version {
+                if self.last_dry_run_version != Some(version_id) {
+                    tracing::info!("Dry-running pending protocol version {version_id:?}");
+                    // A failed dry run doesn't affect the node operation, so it's not propagated.
+                    match self.dry_run(version_id).await {
+                        Ok(reports) => Self::report_metrics(version_id, &reports),
+                        Err(err) => {
+                            METRICS.failed_dry_runs.inc();
+                            tracing::error!(
+                                "Dry run for protocol version {version_id:?} failed: {err:#}"
+                            );
+                        }
+                    }
+                    self.last_dry_run_version = Some(version_id);
+                }
+            }
+
+            if tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, upgrade dry runner is shutting down");
+        Ok(())
+    }
+}
+
+fn compare_outputs(
+    l1_batch_number: L1BatchNumber,
+    baseline_protocol_version: ProtocolVersionId,
+    upgraded_protocol_version: ProtocolVersionId,
+    baseline: BatchExecutionOutput,
+    mut upgraded: BatchExecutionOutput,
+    upgrade_tx_hash: Option<H256>,
+) -> UpgradeDryRunReport {
+    let upgrade_tx_status = upgrade_tx_hash.map(|hash| {
+        let upgrade_tx = upgraded.transactions.remove(0);
+        assert_eq!(upgrade_tx.hash, hash, "upgrade transaction is not first");
+        upgrade_tx.status
+    });
+    if let Some(hash) = upgrade_tx_hash {
+        upgraded.events.retain(|event| event.tx_hash != hash);
+    }
+
+    let transaction_diffs = baseline
+        .transactions
+        .into_iter()
+        .zip(upgraded.transactions)
+        .filter_map(|(baseline, upgraded)| {
+            let is_changed =
+                baseline.status != upgraded.status || baseline.gas_used != upgraded.gas_used;
+            is_changed.then(|| TransactionDiff {
+                hash: baseline.hash,
+                miniblock: baseline.miniblock,
+                baseline_status: baseline.status,
+                upgraded_status: upgraded.status,
+                baseline_gas_used: baseline.gas_used,
+                upgraded_gas_used: upgraded.gas_used,
+            })
+        })
+        .collect();
+    let baseline_storage_writes = baseline.storage_writes.into_iter().collect();
+
+    UpgradeDryRunReport {
+        l1_batch_number,
+        baseline_protocol_version,
+        upgraded_protocol_version,
+        upgrade_tx_status,
+        transaction_diffs,
+        event_mismatches: diff_events(upgraded.events, baseline.events),
+        storage_write_mismatches: diff_storage_writes(
+            &upgraded.storage_writes,
+            &baseline_storage_writes,
+        ),
+    }
+}
+
+fn log_report(report: &UpgradeDryRunReport) {
+    let l1_batch_number = report.l1_batch_number;
+    let version_id = report.upgraded_protocol_version;
+    if !report.is_upgrade_tx_successful() {
+        tracing::warn!(
+            "Upgrade transaction for protocol version {version_id:?} didn't succeed in L1 batch #{l1_batch_number}: {}",
+            report.upgrade_tx_status.as_deref().unwrap_or_default()
+        );
+    }
+    for diff in report.regressions() {
+        tracing::warn!(
+            "Transaction {:?} in L1 batch #{l1_batch_number} fails under protocol version {version_id:?}: {}",
+            diff.hash,
+            diff.upgraded_status
+        );
+    }
+    tracing::info!(
+        "Dry-ran protocol version {version_id:?} on L1 batch #{l1_batch_number}: {} changed transactions, \
+         {} event mismatches, {} storage write mismatches",
+        report.transaction_diffs.len(),
+        report.event_mismatches.len(),
+        report.storage_write_mismatches.len()
+    );
+    tracing::debug!("Dry run report for L1 batch #{l1_batch_number}: {report:?}");
+}
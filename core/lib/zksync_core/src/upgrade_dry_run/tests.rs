@@ -0,0 +1,136 @@
+//! Tests for the protocol upgrade dry run.
+
+use zksync_types::{web3::types::Bytes, Address, ProtocolVersion};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    vm_replay::{ReplayedEvent, ReplayedTransaction},
+};
+
+fn transaction(byte: u8, status: &str, gas_used: u32) -> ReplayedTransaction {
+    ReplayedTransaction {
+        hash: H256::repeat_byte(byte),
+        miniblock: MiniblockNumber(1),
+        status: status.to_owned(),
+        gas_used,
+        refunded_gas: 0,
+        revert_reason: None,
+        call_trace: None,
+        storage_accesses: None,
+    }
+}
+
+fn event(tx_byte: u8, data: u8) -> ReplayedEvent {
+    ReplayedEvent {
+        miniblock: MiniblockNumber(1),
+        tx_hash: H256::repeat_byte(tx_byte),
+        address: Address::repeat_byte(0xff),
+        topics: vec![],
+        data: Bytes(vec![data]),
+    }
+}
+
+#[test]
+fn comparing_outputs_with_upgrade_tx() {
+    let baseline = BatchExecutionOutput {
+        transactions: vec![
+            transaction(1, "success", 100),
+            transaction(2, "success", 200),
+            transaction(3, "reverted: error", 300),
+        ],
+        events: vec![event(1, 0), event(2, 0)],
+        storage_writes: Default::default(),
+    };
+    let upgrade_tx_hash = H256::repeat_byte(0xaa);
+    let upgraded = BatchExecutionOutput {
+        transactions: vec![
+            transaction(0xaa, "success", 1_000),
+            transaction(1, "success", 100),
+            transaction(2, "halted: out of gas", 150),
+            transaction(3, "reverted: error", 300),
+        ],
+        events: vec![event(0xaa, 0), event(1, 0), event(2, 1)],
+        storage_writes: Default::default(),
+    };
+
+    let report = compare_outputs(
+        L1BatchNumber(1),
+        ProtocolVersionId::latest(),
+        ProtocolVersionId::next(),
+        baseline,
+        upgraded,
+        Some(upgrade_tx_hash),
+    );
+
+    assert_eq!(report.upgrade_tx_status.as_deref(), Some("success"));
+    assert!(report.is_upgrade_tx_successful());
+    assert_eq!(report.transaction_diffs.len(), 1);
+    let diff = &report.transaction_diffs[0];
+    assert_eq!(diff.hash, H256::repeat_byte(2));
+    assert_eq!(diff.upgraded_gas_used, 150);
+    let regressions: Vec<_> = report.regressions().map(|diff| diff.hash).collect();
+    assert_eq!(regressions, [H256::repeat_byte(2)]);
+    // The event produced by the upgrade transaction must be ignored.
+    assert_eq!(report.event_mismatches.len(), 1);
+    assert_eq!(report.event_mismatches[0].index, 1);
+}
+
+#[test]
+fn comparing_outputs_with_failed_upgrade_tx() {
+    let baseline = BatchExecutionOutput {
+        transactions: vec![transaction(1, "success", 100)],
+        ..BatchExecutionOutput::default()
+    };
+    let upgraded = BatchExecutionOutput {
+        transactions: vec![
+            transaction(0xaa, "reverted: upgrade failed", 1_000),
+            transaction(1, "success", 110),
+        ],
+        ..BatchExecutionOutput::default()
+    };
+
+    let report = compare_outputs(
+        L1BatchNumber(1),
+        ProtocolVersionId::latest(),
+        ProtocolVersionId::next(),
+        baseline,
+        upgraded,
+        Some(H256::repeat_byte(0xaa)),
+    );
+    assert!(!report.is_upgrade_tx_successful());
+    assert_eq!(report.transaction_diffs.len(), 1);
+    assert_eq!(report.regressions().count(), 0);
+}
+
+#[tokio::test]
+async fn detecting_pending_protocol_version() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let config = UpgradeDryRunConfig {
+        l1_batches_to_replay: None,
+        polling_interval_ms: None,
+    };
+    let runner = UpgradeDryRunner::new(config, pool.clone(), L2ChainId::default());
+    assert_eq!(runner.pending_version().await.unwrap(), None);
+
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion {
+            id: ProtocolVersionId::next(),
+            base_system_contracts_hashes: GenesisParams::mock().base_system_contracts.hashes(),
+            ..ProtocolVersion::default()
+        })
+        .await;
+    assert_eq!(
+        runner.pending_version().await.unwrap(),
+        Some(ProtocolVersionId::next())
+    );
+
+    // There are no L1 batches to replay except for genesis.
+    let reports = runner.dry_run(ProtocolVersionId::next()).await.unwrap();
+    assert!(reports.is_empty());
+}
@@ -0,0 +1,532 @@
+//! Re-execution of sealed L1 batches for debugging, e.g. during state divergence incidents.
+//!
+//! A batch is re-executed on top of the Postgres state at the end of the previous batch, and the produced events
+//! and storage writes are compared with the ones stored for the batch.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use itertools::{EitherOrBoth, Itertools};
+use multivm::{
+    interface::{
+        ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs, VmInterface,
+        VmInterfaceHistoryEnabled,
+    },
+    tracers::{CallTracer, StorageInvocations, StorageWatchpointTracer, TracerDispatcher},
+    vm_latest::HistoryEnabled,
+    MultiVMTracer, MultiVmTracerPointer, VmInstance, VmVersion,
+};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tokio::runtime::Handle;
+use zksync_dal::ConnectionPool;
+use zksync_state::{PostgresStorage, StorageView, WriteStorage};
+use zksync_types::{
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    vm_trace::{Call, StorageAccess},
+    web3::types::Bytes,
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, StorageLogQuery,
+    Transaction, VmEvent, H256,
+};
+use zksync_utils::u256_to_h256;
+
+pub use self::source::{BatchData, BatchSource, PostgresBatchSource, RpcBatchSource};
+
+mod source;
+
+/// Tracer applied to transactions during replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayTracer {
+    /// Collects call traces of transactions, which are included into the report.
+    Call,
+    /// Halts transactions accessing more than the specified number of storage slots.
+    StorageInvocations { limit: usize },
+    /// Records reads and writes of the specified storage slots by transactions, which are included into the report.
+    StorageWatchpoints { keys: HashSet<StorageKey> },
+}
+
+/// Event produced by the VM or stored for a batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayedEvent {
+    pub miniblock: MiniblockNumber,
+    /// Hash of the transaction that produced the event; zero for events produced by the bootloader
+    /// in the block tip.
+    pub tx_hash: H256,
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+impl ReplayedEvent {
+    fn new(miniblock: MiniblockNumber, tx_hash: H256, event: &VmEvent) -> Self {
+        Self {
+            miniblock,
+            tx_hash,
+            address: event.address,
+            topics: event.indexed_topics.clone(),
+            data: event.value.clone().into(),
+        }
+    }
+}
+
+/// Result of re-executing a single transaction.
+#[derive(Debug, Serialize)]
+pub struct ReplayedTransaction {
+    pub hash: H256,
+    pub miniblock: MiniblockNumber,
+    /// Human-readable execution status (`success`, or a revert / halt reason).
+    pub status: String,
+    pub gas_used: u32,
+    pub refunded_gas: u32,
+    /// Revert or halt reason in the same format as saved by the state keeper; also included into `status`.
+    #[serde(skip)]
+    pub revert_reason: Option<String>,
+    /// Call trace of the transaction. Only present if the call tracer is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_trace: Option<Vec<Call>>,
+    /// Accesses to the watched storage slots. Only present if storage watchpoints are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_accesses: Option<Vec<StorageAccess>>,
+}
+
+/// Mismatch between a replayed and a stored event at the same position in the batch.
+#[derive(Debug, Serialize)]
+pub struct EventMismatch {
+    /// Zero-based index of the event in the batch.
+    pub index: usize,
+    pub replayed: Option<ReplayedEvent>,
+    pub stored: Option<ReplayedEvent>,
+}
+
+/// Mismatch between the replayed and stored values of a storage slot.
+#[derive(Debug, Serialize)]
+pub struct StorageWriteMismatch {
+    pub key: StorageKey,
+    /// Value written during replay; `None` if the slot wasn't written to.
+    pub replayed: Option<H256>,
+    /// Stored value; `None` if the slot wasn't written to in the stored batch.
+    pub stored: Option<H256>,
+}
+
+/// Report produced by [`BatchReplayer`].
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    /// VM version used for replay (may differ from the one corresponding to the protocol version).
+    pub vm_version: String,
+    pub transactions: Vec<ReplayedTransaction>,
+    pub event_mismatches: Vec<EventMismatch>,
+    pub storage_write_mismatches: Vec<StorageWriteMismatch>,
+}
+
+impl ReplayReport {
+    /// Checks whether the replayed batch matches the stored data.
+    pub fn is_consistent(&self) -> bool {
+        self.event_mismatches.is_empty() && self.storage_write_mismatches.is_empty()
+    }
+}
+
+/// Results of re-executing an L1 batch.
+#[derive(Debug, Default)]
+pub struct BatchExecutionOutput {
+    pub transactions: Vec<ReplayedTransaction>,
+    /// Events ordered by miniblock and event index.
+    pub events: Vec<ReplayedEvent>,
+    /// Final values of storage slots written to in the batch.
+    pub storage_writes: BTreeMap<StorageKey, H256>,
+}
+
+/// Re-executes sealed L1 batches and compares execution results with the stored ones.
+///
+/// Storage is always read from Postgres (at the end of the previous batch); thus, storage logs for the replayed batch
+/// must not be pruned.
+#[derive(Debug)]
+pub struct BatchReplayer {
+    pool: ConnectionPool,
+    vm_version: Option<VmVersion>,
+    tracers: Vec<ReplayTracer>,
+}
+
+impl BatchReplayer {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            vm_version: None,
+            tracers: vec![],
+        }
+    }
+
+    /// Overrides the VM version used for replay. By default, the version corresponding to the batch protocol version
+    /// is used.
+    pub fn with_vm_version(mut self, vm_version: VmVersion) -> Self {
+        self.vm_version = Some(vm_version);
+        self
+    }
+
+    pub fn with_tracer(mut self, tracer: ReplayTracer) -> Self {
+        self.tracers.push(tracer);
+        self
+    }
+
+    fn vm_version(&self, batch: &BatchData) -> VmVersion {
+        self.vm_version
+            .unwrap_or_else(|| batch.system_env.version.into())
+    }
+
+    /// Re-executes the provided batch without comparing results with the stored ones. The batch data may be modified
+    /// compared to the one returned by a [`BatchSource`], e.g. to execute the batch with other base system contracts.
+    pub async fn execute(&self, batch: BatchData) -> anyhow::Result<BatchExecutionOutput> {
+        let vm_version = self.vm_version(&batch);
+        tracing::info!(
+            "Replaying L1 batch #{} with {} miniblocks using VM {vm_version:?}",
+            batch.l1_batch_env.number,
+            batch.miniblocks.len()
+        );
+
+        let pool = self.pool.clone();
+        let tracers = self.tracers.clone();
+        tokio::task::spawn_blocking(move || {
+            execute_batch(Handle::current(), &pool, batch, vm_version, &tracers)
+        })
+        .await
+        .context("batch execution panicked")?
+    }
+
+    pub async fn replay(
+        &self,
+        source: &dyn BatchSource,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<ReplayReport> {
+        let batch = source
+            .load_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed loading L1 batch #{l1_batch_number}"))?;
+        let protocol_version = batch.system_env.version;
+        let vm_version = self.vm_version(&batch);
+        let output = self.execute(batch.clone()).await?;
+
+        let stored_events = source
+            .stored_events(&batch)
+            .await
+            .context("failed loading stored events")?;
+        let written_keys: Vec<_> = output.storage_writes.keys().copied().collect();
+        let stored_writes = source
+            .stored_storage_writes(&batch, &written_keys)
+            .await
+            .context("failed loading stored storage writes")?;
+
+        Ok(ReplayReport {
+            l1_batch_number,
+            protocol_version,
+            vm_version: format!("{vm_version:?}"),
+            event_mismatches: diff_events(output.events, stored_events),
+            storage_write_mismatches: diff_storage_writes(&output.storage_writes, &stored_writes),
+            transactions: output.transactions,
+        })
+    }
+}
+
+fn execute_batch(
+    rt_handle: Handle,
+    pool: &ConnectionPool,
+    batch: BatchData,
+    vm_version: VmVersion,
+    tracers: &[ReplayTracer],
+) -> anyhow::Result<BatchExecutionOutput> {
+    let connection = rt_handle.block_on(pool.access_storage())?;
+    let (first_miniblock, _) = batch.miniblock_range();
+    let pg_storage = PostgresStorage::new(rt_handle.clone(), connection, first_miniblock - 1, true);
+    let storage_view = StorageView::new(pg_storage).to_rc_ptr();
+    let mut vm = VmInstance::<_, HistoryEnabled>::new_with_specific_version(
+        batch.l1_batch_env,
+        batch.system_env,
+        storage_view,
+        vm_version,
+    );
+
+    let mut output = BatchExecutionOutput::default();
+    let miniblock_count = batch.miniblocks.len();
+    for (i, miniblock) in batch.miniblocks.iter().enumerate() {
+        if i > 0 {
+            vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(miniblock));
+        }
+        // Storage writes are deduplicated per miniblock, in the same way as in the state keeper.
+        let mut storage_writes = StorageWritesDeduplicator::new();
+        for tx in &miniblock.txs {
+            let (result, traces) = execute_tx(tx, &mut vm, tracers)
+                .with_context(|| format!("failed executing transaction {:?}", tx.hash()))?;
+            storage_writes.apply(filter_writes(&result.logs.storage_logs));
+            output.events.extend(
+                result
+                    .logs
+                    .events
+                    .iter()
+                    .map(|event| ReplayedEvent::new(miniblock.number, tx.hash(), event)),
+            );
+            output.transactions.push(ReplayedTransaction {
+                hash: tx.hash(),
+                miniblock: miniblock.number,
+                status: execution_status(&result.result),
+                gas_used: result.statistics.gas_used,
+                refunded_gas: result.refunds.gas_refunded,
+                revert_reason: revert_reason(&result.result),
+                call_trace: traces.call_trace,
+                storage_accesses: traces.storage_accesses,
+            });
+        }
+
+        if i + 1 == miniblock_count {
+            let block_tip = vm.finish_batch().block_tip_execution_result;
+            storage_writes.apply(filter_writes(&block_tip.logs.storage_logs));
+            output.events.extend(
+                block_tip
+                    .logs
+                    .events
+                    .iter()
+                    .map(|event| ReplayedEvent::new(miniblock.number, H256::zero(), event)),
+            );
+        }
+        let storage_writes = storage_writes.into_modified_key_values();
+        output.storage_writes.extend(
+            storage_writes
+                .into_iter()
+                .map(|(key, slot)| (key, u256_to_h256(slot.value))),
+        );
+    }
+    Ok(output)
+}
+
+fn filter_writes(logs: &[StorageLogQuery]) -> impl Iterator<Item = &StorageLogQuery> {
+    logs.iter().filter(|log| log.log_query.rw_flag)
+}
+
+fn execution_status(result: &ExecutionResult) -> String {
+    match result {
+        ExecutionResult::Success { .. } => "success".to_owned(),
+        ExecutionResult::Revert { output } => format!("reverted: {output}"),
+        ExecutionResult::Halt { reason } => format!("halted: {reason}"),
+    }
+}
+
+fn revert_reason(result: &ExecutionResult) -> Option<String> {
+    match result {
+        ExecutionResult::Success { .. } => None,
+        ExecutionResult::Revert { output } => Some(output.to_string()),
+        ExecutionResult::Halt { reason } => Some(reason.to_string()),
+    }
+}
+
+/// Shared cells populated by tracers during transaction execution.
+#[derive(Debug, Default)]
+struct TracerResults {
+    call_trace: Arc<OnceCell<Vec<Call>>>,
+    storage_accesses: Arc<OnceCell<Vec<StorageAccess>>>,
+}
+
+/// Outputs of tracers for a single transaction.
+#[derive(Debug)]
+struct TxTraces {
+    call_trace: Option<Vec<Call>>,
+    storage_accesses: Option<Vec<StorageAccess>>,
+}
+
+impl TracerResults {
+    fn into_traces(self, tracers: &[ReplayTracer]) -> TxTraces {
+        let has_call_tracer = tracers.contains(&ReplayTracer::Call);
+        let has_watchpoints = tracers
+            .iter()
+            .any(|tracer| matches!(tracer, ReplayTracer::StorageWatchpoints { .. }));
+        TxTraces {
+            call_trace: has_call_tracer.then(|| take_cell(self.call_trace)),
+            storage_accesses: has_watchpoints.then(|| take_cell(self.storage_accesses)),
+        }
+    }
+}
+
+fn take_cell<T: Default>(cell: Arc<OnceCell<T>>) -> T {
+    Arc::try_unwrap(cell)
+        .ok()
+        .and_then(OnceCell::into_inner)
+        .unwrap_or_default()
+}
+
+fn create_tracers<S: WriteStorage>(
+    tracers: &[ReplayTracer],
+    results: &TracerResults,
+) -> Vec<MultiVmTracerPointer<S, HistoryEnabled>> {
+    tracers
+        .iter()
+        .map(|tracer| match tracer {
+            ReplayTracer::Call => CallTracer::new(results.call_trace.clone()).into_tracer_pointer(),
+            ReplayTracer::StorageInvocations { limit } => {
+                StorageInvocations::new(*limit).into_tracer_pointer()
+            }
+            ReplayTracer::StorageWatchpoints { keys } => {
+                StorageWatchpointTracer::new(keys.clone(), results.storage_accesses.clone())
+                    .into_tracer_pointer()
+            }
+        })
+        .collect()
+}
+
+/// Executes a transaction in the same way as the state keeper, i.e., first with bytecode compression,
+/// and then without it if compression fails.
+fn execute_tx<S: WriteStorage>(
+    tx: &Transaction,
+    vm: &mut VmInstance<S, HistoryEnabled>,
+    tracers: &[ReplayTracer],
+) -> anyhow::Result<(VmExecutionResultAndLogs, TxTraces)> {
+    vm.make_snapshot();
+    let tracer_results = TracerResults::default();
+    let dispatcher = TracerDispatcher::from(create_tracers(tracers, &tracer_results));
+    if let Ok(result) =
+        vm.inspect_transaction_with_bytecode_compression(dispatcher, tx.clone(), true)
+    {
+        vm.pop_snapshot_no_rollback();
+        return Ok((result, tracer_results.into_traces(tracers)));
+    }
+
+    vm.rollback_to_the_latest_snapshot();
+    let tracer_results = TracerResults::default();
+    let dispatcher = TracerDispatcher::from(create_tracers(tracers, &tracer_results));
+    let result = vm
+        .inspect_transaction_with_bytecode_compression(dispatcher, tx.clone(), false)
+        .ok()
+        .context("compression can't fail if we don't apply it")?;
+    Ok((result, tracer_results.into_traces(tracers)))
+}
+
+pub(crate) fn diff_events(
+    replayed: Vec<ReplayedEvent>,
+    stored: Vec<ReplayedEvent>,
+) -> Vec<EventMismatch> {
+    replayed
+        .into_iter()
+        .zip_longest(stored)
+        .enumerate()
+        .filter_map(|(index, events)| {
+            let (replayed, stored) = match events {
+                EitherOrBoth::Both(replayed, stored) if replayed == stored => return None,
+                EitherOrBoth::Both(replayed, stored) => (Some(replayed), Some(stored)),
+                EitherOrBoth::Left(replayed) => (Some(replayed), None),
+                EitherOrBoth::Right(stored) => (None, Some(stored)),
+            };
+            Some(EventMismatch {
+                index,
+                replayed,
+                stored,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn diff_storage_writes(
+    replayed: &BTreeMap<StorageKey, H256>,
+    stored: &HashMap<StorageKey, H256>,
+) -> Vec<StorageWriteMismatch> {
+    let stored: BTreeMap<_, _> = stored.iter().map(|(key, value)| (*key, *value)).collect();
+    replayed
+        .iter()
+        .merge_join_by(&stored, |(replayed_key, _), (stored_key, _)| {
+            replayed_key.cmp(stored_key)
+        })
+        .filter_map(|entry| match entry {
+            EitherOrBoth::Both((_, replayed), (_, stored)) if replayed == stored => None,
+            EitherOrBoth::Both((key, replayed), (_, stored)) => Some(StorageWriteMismatch {
+                key: *key,
+                replayed: Some(*replayed),
+                stored: Some(*stored),
+            }),
+            EitherOrBoth::Left((key, replayed)) => Some(StorageWriteMismatch {
+                key: *key,
+                replayed: Some(*replayed),
+                stored: None,
+            }),
+            EitherOrBoth::Right((key, stored)) => Some(StorageWriteMismatch {
+                key: *key,
+                replayed: None,
+                stored: Some(*stored),
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::AccountTreeId;
+
+    use super::*;
+
+    fn event(miniblock: u32, data: u8) -> ReplayedEvent {
+        ReplayedEvent {
+            miniblock: MiniblockNumber(miniblock),
+            tx_hash: H256::repeat_byte(1),
+            address: Address::repeat_byte(2),
+            topics: vec![H256::repeat_byte(3)],
+            data: vec![data].into(),
+        }
+    }
+
+    #[test]
+    fn diffing_events() {
+        let events = vec![event(1, 0), event(1, 1), event(2, 2)];
+        assert!(diff_events(events.clone(), events.clone()).is_empty());
+
+        let mut stored = events.clone();
+        stored[1].data = vec![0xff].into();
+        stored.push(event(2, 3));
+        let mismatches = diff_events(events, stored);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].index, 1);
+        assert_eq!(mismatches[0].replayed, Some(event(1, 1)));
+        assert_eq!(
+            mismatches[0].stored.as_ref().unwrap().data,
+            vec![0xff].into()
+        );
+        assert_eq!(mismatches[1].index, 3);
+        assert_eq!(mismatches[1].replayed, None);
+        assert_eq!(mismatches[1].stored, Some(event(2, 3)));
+    }
+
+    #[test]
+    fn diffing_storage_writes() {
+        let key = |byte| {
+            StorageKey::new(
+                AccountTreeId::new(Address::repeat_byte(1)),
+                H256::repeat_byte(byte),
+            )
+        };
+        let replayed = BTreeMap::from([
+            (key(1), H256::repeat_byte(1)),
+            (key(2), H256::repeat_byte(2)),
+            (key(3), H256::repeat_byte(3)),
+        ]);
+        let stored = HashMap::from([
+            (key(1), H256::repeat_byte(1)),
+            (key(2), H256::repeat_byte(0xff)),
+            (key(4), H256::repeat_byte(4)),
+        ]);
+
+        let mismatches = diff_storage_writes(&replayed, &stored);
+        let mismatches: Vec<_> = mismatches
+            .iter()
+            .map(|mismatch| (mismatch.key, mismatch.replayed, mismatch.stored))
+            .collect();
+        assert_eq!(
+            mismatches,
+            [
+                (
+                    key(2),
+                    Some(H256::repeat_byte(2)),
+                    Some(H256::repeat_byte(0xff))
+                ),
+                (key(3), Some(H256::repeat_byte(3)), None),
+                (key(4), None, Some(H256::repeat_byte(4))),
+            ]
+        );
+    }
+}
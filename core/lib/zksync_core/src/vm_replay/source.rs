@@ -0,0 +1,290 @@
+//! Sources of L1 batch data for replaying.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::interface::{L1BatchEnv, SystemEnv};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber, GetLogsFilter, Log},
+    block::MiniblockExecutionData,
+    L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, H256, U64,
+};
+use zksync_utils::h256_to_u256;
+use zksync_web3_decl::{
+    jsonrpsee::http_client::HttpClient,
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    types::{Filter, FilterBuilder},
+};
+
+use super::ReplayedEvent;
+use crate::{
+    state_keeper::io::common::{l1_batch_params, load_l1_batch_params},
+    sync_layer::MainNodeClient,
+};
+
+/// In the state keeper, this value is used to reject execution. All replayed batches have already been executed
+/// by the state keeper, so we don't want to reject any execution.
+const VALIDATION_COMPUTATIONAL_GAS_LIMIT: u32 = u32::MAX;
+
+/// Data necessary to re-execute an L1 batch.
+#[derive(Debug, Clone)]
+pub struct BatchData {
+    pub system_env: SystemEnv,
+    pub l1_batch_env: L1BatchEnv,
+    /// Miniblocks in the batch, including the fictive one.
+    pub miniblocks: Vec<MiniblockExecutionData>,
+}
+
+impl BatchData {
+    pub(super) fn miniblock_range(&self) -> (MiniblockNumber, MiniblockNumber) {
+        let first = self.miniblocks.first().expect("batch without miniblocks");
+        let last = self.miniblocks.last().unwrap();
+        (first.number, last.number)
+    }
+}
+
+/// Source of the L1 batch data and of the execution results stored for the batch.
+#[async_trait]
+pub trait BatchSource: Send + Sync {
+    /// Loads data necessary to re-execute the specified L1 batch.
+    async fn load_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<BatchData>;
+
+    /// Loads events stored for the batch, ordered by miniblock and event index.
+    async fn stored_events(&self, batch: &BatchData) -> anyhow::Result<Vec<ReplayedEvent>>;
+
+    /// Loads the latest values of storage slots written to in the batch. The source may return values
+    /// only for the `written_keys` (i.e., keys written to during replay), or it may return values for all keys
+    /// written to in the stored batch.
+    async fn stored_storage_writes(
+        &self,
+        batch: &BatchData,
+        written_keys: &[StorageKey],
+    ) -> anyhow::Result<HashMap<StorageKey, H256>>;
+}
+
+fn convert_log(log: Log) -> anyhow::Result<ReplayedEvent> {
+    let miniblock = log.block_number.context("log without miniblock number")?;
+    Ok(ReplayedEvent {
+        miniblock: MiniblockNumber(miniblock.as_u32()),
+        tx_hash: log.transaction_hash.context("log without tx hash")?,
+        address: log.address,
+        topics: log.topics,
+        data: log.data,
+    })
+}
+
+/// Batch source reading data from the node Postgres.
+#[derive(Debug)]
+pub struct PostgresBatchSource {
+    pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+}
+
+impl PostgresBatchSource {
+    pub fn new(pool: ConnectionPool, l2_chain_id: L2ChainId) -> Self {
+        Self { pool, l2_chain_id }
+    }
+}
+
+#[async_trait]
+impl BatchSource for PostgresBatchSource {
+    async fn load_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<BatchData> {
+        let mut storage = self.pool.access_storage().await?;
+        let fee_account = storage
+            .blocks_dal()
+            .get_fee_address_for_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let (system_env, l1_batch_env) = load_l1_batch_params(
+            &mut storage,
+            l1_batch_number,
+            fee_account,
+            VALIDATION_COMPUTATIONAL_GAS_LIMIT,
+            self.l2_chain_id,
+        )
+        .await
+        .with_context(|| format!("miniblocks for L1 batch #{l1_batch_number} are not sealed"))?;
+        let miniblocks = storage
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?;
+        anyhow::ensure!(
+            !miniblocks.is_empty(),
+            "L1 batch #{l1_batch_number} has no miniblocks"
+        );
+        Ok(BatchData {
+            system_env,
+            l1_batch_env,
+            miniblocks,
+        })
+    }
+
+    async fn stored_events(&self, batch: &BatchData) -> anyhow::Result<Vec<ReplayedEvent>> {
+        let (from_block, to_block) = batch.miniblock_range();
+        let filter = GetLogsFilter {
+            from_block,
+            to_block,
+            addresses: vec![],
+            topics: vec![],
+        };
+        let mut storage = self.pool.access_storage().await?;
+        let logs = storage
+            .events_web3_dal()
+            .get_logs(filter, i32::MAX as usize)
+            .await
+            .context("get_logs()")?;
+        logs.into_iter().map(convert_log).collect()
+    }
+
+    async fn stored_storage_writes(
+        &self,
+        batch: &BatchData,
+        _written_keys: &[StorageKey],
+    ) -> anyhow::Result<HashMap<StorageKey, H256>> {
+        let mut storage = self.pool.access_storage().await?;
+        Ok(storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(batch.l1_batch_env.number)
+            .await)
+    }
+}
+
+/// Batch source reading data from the main node JSON-RPC API. Since the API doesn't expose all storage writes
+/// in a batch, only values of the keys written to during replay are compared.
+#[derive(Debug)]
+pub struct RpcBatchSource {
+    client: HttpClient,
+    l2_chain_id: L2ChainId,
+}
+
+impl RpcBatchSource {
+    pub fn new(client: HttpClient, l2_chain_id: L2ChainId) -> Self {
+        Self {
+            client,
+            l2_chain_id,
+        }
+    }
+}
+
+#[async_trait]
+impl BatchSource for RpcBatchSource {
+    async fn load_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<BatchData> {
+        let (first_miniblock, last_miniblock) = self
+            .client
+            .get_miniblock_range(l1_batch_number)
+            .await
+            .context("get_miniblock_range()")?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let prev_batch_hash = self
+            .client
+            .get_l1_batch_details(l1_batch_number - 1)
+            .await
+            .context("get_l1_batch_details()")?
+            .and_then(|details| details.base.root_hash)
+            .with_context(|| {
+                format!(
+                    "root hash for L1 batch #{} is not computed",
+                    l1_batch_number - 1
+                )
+            })?;
+
+        let first_miniblock = MiniblockNumber(first_miniblock.as_u32());
+        let mut prev_miniblock_hash = self
+            .client
+            .fetch_l2_block(first_miniblock - 1, false)
+            .await?
+            .and_then(|block| block.hash)
+            .with_context(|| format!("miniblock #{} is missing", first_miniblock - 1))?;
+
+        let mut blocks = vec![];
+        for number in first_miniblock.0..=last_miniblock.as_u32() {
+            let block = self
+                .client
+                .fetch_l2_block(MiniblockNumber(number), true)
+                .await?
+                .with_context(|| format!("miniblock #{number} is missing"))?;
+            blocks.push(block);
+        }
+        let first_block = &blocks[0];
+        let base_system_contracts = self
+            .client
+            .fetch_base_system_contracts(first_block.base_system_contracts_hashes)
+            .await
+            .context("failed fetching base system contracts")?;
+        let (system_env, l1_batch_env) = l1_batch_params(
+            l1_batch_number,
+            first_block.operator_address,
+            first_block.timestamp,
+            h256_to_u256(prev_batch_hash),
+            first_block.l1_gas_price,
+            first_block.l2_fair_gas_price,
+            first_miniblock,
+            prev_miniblock_hash,
+            base_system_contracts,
+            VALIDATION_COMPUTATIONAL_GAS_LIMIT,
+            first_block.protocol_version,
+            first_block.virtual_blocks.unwrap_or(0),
+            self.l2_chain_id,
+        );
+
+        let mut miniblocks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let hash = block
+                .hash
+                .with_context(|| format!("miniblock #{} has no hash", block.number))?;
+            miniblocks.push(MiniblockExecutionData {
+                number: block.number,
+                timestamp: block.timestamp,
+                prev_block_hash: prev_miniblock_hash,
+                virtual_blocks: block.virtual_blocks.unwrap_or(0),
+                txs: block.transactions.unwrap_or_default(),
+            });
+            prev_miniblock_hash = hash;
+        }
+        Ok(BatchData {
+            system_env,
+            l1_batch_env,
+            miniblocks,
+        })
+    }
+
+    async fn stored_events(&self, batch: &BatchData) -> anyhow::Result<Vec<ReplayedEvent>> {
+        // Request logs for each miniblock separately so that the response doesn't hit API limits.
+        let mut events = vec![];
+        for miniblock in &batch.miniblocks {
+            let number = BlockNumber::Number(miniblock.number.0.into());
+            let filter: Filter = FilterBuilder::default()
+                .set_from_block(number)
+                .set_to_block(number)
+                .build();
+            let logs = self.client.get_logs(filter).await.with_context(|| {
+                format!("failed getting logs for miniblock #{}", miniblock.number)
+            })?;
+            for log in logs {
+                events.push(convert_log(log)?);
+            }
+        }
+        Ok(events)
+    }
+
+    async fn stored_storage_writes(
+        &self,
+        batch: &BatchData,
+        written_keys: &[StorageKey],
+    ) -> anyhow::Result<HashMap<StorageKey, H256>> {
+        let (_, last_miniblock) = batch.miniblock_range();
+        let block = BlockIdVariant::BlockNumber(BlockNumber::Number(U64::from(last_miniblock.0)));
+        let mut values = HashMap::with_capacity(written_keys.len());
+        for key in written_keys {
+            let value = self
+                .client
+                .get_storage_at(*key.address(), h256_to_u256(*key.key()), Some(block))
+                .await
+                .with_context(|| format!("failed getting value for {key:?}"))?;
+            values.insert(*key, value);
+        }
+        Ok(values)
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     rng::{LoadtestRng, WeightedRandom},
 };
 
-static WEIGHTS: OnceCell<[(TxType, f32); 5]> = OnceCell::new();
+static WEIGHTS: OnceCell<[(TxType, f32); 7]> = OnceCell::new();
 
 /// Type of transaction. It doesn't copy the zkSync operation list, because
 /// it divides some transactions in subcategories (e.g. to new account / to existing account; to self / to other; etc)/
@@ -19,6 +19,7 @@ pub enum TxType {
     Deposit,
     WithdrawToSelf,
     WithdrawToOther,
+    Transfer,
     DeployContract,
     L1Execute,
     L2Execute,
@@ -36,6 +37,8 @@ impl TxType {
                     TxType::WithdrawToOther,
                     transaction_weights.withdrawal / 2.0,
                 ),
+                (TxType::Transfer, transaction_weights.transfer),
+                (TxType::DeployContract, transaction_weights.deploy_contract),
             ])
             .unwrap();
     }
@@ -59,6 +62,8 @@ impl TxType {
             Self::Deposit,
             Self::WithdrawToSelf,
             Self::WithdrawToOther,
+            Self::Transfer,
+            Self::DeployContract,
             Self::L1Execute,
             Self::L2Execute,
         ]
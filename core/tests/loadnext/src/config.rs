@@ -1,11 +1,14 @@
 use std::{path::PathBuf, time::Duration};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
 use zksync_contracts::test_contracts::LoadnextContractExecutionParams;
 use zksync_types::{network::Network, Address, L2ChainId, H160};
 
-use crate::fs_utils::read_tokens;
+use crate::{
+    fs_utils::read_tokens,
+    workload::{WorkloadMix, WorkloadProfile},
+};
 
 /// Configuration for the loadtest.
 ///
@@ -136,6 +139,37 @@ pub struct LoadtestConfig {
     /// in an eventual test failure anyway (e.g., a failure processing transactions).
     #[serde(default)]
     pub fail_fast: bool,
+
+    /// Profile of the generated workload. The `custom` profile (default) uses transaction weights and contract
+    /// execution params from the environment; other profiles ignore these variables.
+    #[serde(default)]
+    pub workload_profile: WorkloadProfile,
+
+    /// Path to the JSON file with the transaction mix replayed by the `replay` profile. If the file doesn't exist,
+    /// the mix is sampled from `replay_source_rpc_address` and saved to this path, so that subsequent runs
+    /// replay the same mix.
+    #[serde(default)]
+    pub replay_mix_path: Option<PathBuf>,
+
+    /// RPC address of the node (e.g., a mainnet node) to sample the replayed transaction mix from.
+    #[serde(default)]
+    pub replay_source_rpc_address: Option<String>,
+
+    /// Number of the latest miniblocks on the source node to sample the replayed transaction mix from.
+    #[serde(default = "default_replay_sampled_miniblocks")]
+    pub replay_sampled_miniblocks: u32,
+
+    /// URL of the Prometheus metrics endpoint of the tested node (e.g., `http://127.0.0.1:3312/metrics`).
+    /// If set, seal reasons and VM metrics accumulated by the node during the test are reported
+    /// once the test is finished.
+    #[serde(default)]
+    pub node_metrics_url: Option<String>,
+}
+
+fn default_replay_sampled_miniblocks() -> u32 {
+    let result = 1_000;
+    tracing::info!("Using default REPLAY_SAMPLED_MINIBLOCKS: {result}");
+    result
 }
 
 fn default_max_inflight_txs() -> usize {
@@ -283,6 +317,23 @@ pub struct ExecutionConfig {
 }
 
 impl ExecutionConfig {
+    /// Creates the execution config for the workload profile specified in the loadtest config.
+    pub async fn new(config: &LoadtestConfig) -> anyhow::Result<Self> {
+        let mix = match config.workload_profile {
+            WorkloadProfile::Custom => return Ok(Self::from_env()),
+            WorkloadProfile::Replay => WorkloadMix::for_replay(config).await?,
+            profile => profile.builtin_mix(),
+        };
+        tracing::info!(
+            "Using {:?} workload profile with mix: {mix:?}",
+            config.workload_profile
+        );
+        Ok(Self {
+            transaction_weights: mix.transaction_weights,
+            contract_execution_params: mix.contract_execution_params,
+        })
+    }
+
     pub fn from_env() -> Self {
         let transaction_weights =
             TransactionWeights::from_env().unwrap_or_else(default_transaction_weights);
@@ -295,12 +346,18 @@ impl ExecutionConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransactionWeights {
     pub deposit: f32,
     pub withdrawal: f32,
     pub l1_transactions: f32,
     pub l2_transactions: f32,
+    /// Weight of ERC-20 transfers between test accounts.
+    #[serde(default)]
+    pub transfer: f32,
+    /// Weight of deploying the test contract (in addition to the deployment performed by each account on start).
+    #[serde(default)]
+    pub deploy_contract: f32,
 }
 
 impl TransactionWeights {
@@ -316,6 +373,8 @@ impl Default for TransactionWeights {
             withdrawal: 0.5,
             l1_transactions: 0.05,
             l2_transactions: 1.0,
+            transfer: 0.0,
+            deploy_contract: 0.0,
         }
     }
 }
@@ -23,6 +23,7 @@ use crate::{
     account_pool::AccountPool,
     config::{ExecutionConfig, LoadtestConfig, RequestLimiters},
     constants::*,
+    node_metrics::NodeMetricsSnapshot,
     report::ReportBuilder,
     report_collector::{LoadtestResult, ReportCollector},
     utils::format_eth,
@@ -582,6 +583,7 @@ impl Executor {
             account_tasks.extend(new_account_futures);
         }
 
+        let node_metrics_before = self.scrape_node_metrics().await;
         report_sender
             .send(ReportBuilder::build_init_complete_report())
             .await
@@ -599,9 +601,24 @@ impl Executor {
         future::try_join_all(account_tasks).await?;
         tracing::info!("All the spawned tasks are completed");
 
+        if let Some(before) = node_metrics_before {
+            if let Some(after) = self.scrape_node_metrics().await {
+                after.report_since(&before).report();
+            }
+        }
         Ok(report_collector_future.await?)
     }
 
+    /// Scrapes metrics of the tested node if `node_metrics_url` is configured. Errors are logged, but don't
+    /// fail the test.
+    async fn scrape_node_metrics(&self) -> Option<NodeMetricsSnapshot> {
+        let url = self.config.node_metrics_url.as_deref()?;
+        NodeMetricsSnapshot::scrape(url)
+            .await
+            .map_err(|err| tracing::warn!("Failed scraping node metrics: {err:#}"))
+            .ok()
+    }
+
     /// Calculates amount of ETH to be distributed per account in order to make them
     /// able to perform priority operations.
     async fn eth_amount_to_distribute(&self) -> anyhow::Result<U256> {
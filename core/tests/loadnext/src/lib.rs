@@ -9,7 +9,9 @@ pub mod constants;
 pub mod corrupted_tx;
 pub mod executor;
 pub mod fs_utils;
+pub mod node_metrics;
 pub mod report;
 pub mod report_collector;
 pub mod rng;
 pub mod utils;
+pub mod workload;
@@ -36,7 +36,7 @@ async fn main() -> anyhow::Result<()> {
 
     let config = LoadtestConfig::from_env()
         .expect("Config parameters should be loaded from env or from default values");
-    let execution_config = ExecutionConfig::from_env();
+    let execution_config = ExecutionConfig::new(&config).await?;
     let prometheus_config: Option<PrometheusConfig> = envy::prefixed("PROMETHEUS_").from_env().ok();
 
     TxType::initialize_weights(&execution_config.transaction_weights);
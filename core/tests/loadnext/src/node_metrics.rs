@@ -0,0 +1,254 @@
+//! Metrics scraped from the tested node.
+//!
+//! The loadtest scrapes the Prometheus endpoint of the node before and after the test and reports the difference
+//! for metrics useful for analyzing performance regressions: seal criteria triggered by the state keeper
+//! and VM execution statistics.
+
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Context as _;
+
+/// Counter with reasons for sealing L1 batches (labeled by `criterion` and `seal_resolution`).
+const SEAL_REASONS_METRIC: &str = "server_tx_aggregation_reason_total";
+/// Histograms reported as VM metrics. Names are prefixes since the exported names may contain unit suffixes.
+const VM_HISTOGRAMS: &[&str] = &[
+    "server_state_keeper_tx_execution_time",
+    "state_keeper_batch_executor_command_response_time",
+    "state_keeper_computational_gas_per_nanosecond",
+    "server_state_keeper_l1_batch_transactions_in_l1_batch",
+    "server_state_keeper_miniblock_transactions_in_miniblock",
+];
+
+/// Key of a single time series: metric name and sorted `(label, value)` pairs.
+type SeriesKey = (String, Vec<(String, String)>);
+
+/// Snapshot of the node metrics parsed from the Prometheus text format.
+#[derive(Debug, Default)]
+pub struct NodeMetricsSnapshot {
+    series: BTreeMap<SeriesKey, f64>,
+}
+
+impl NodeMetricsSnapshot {
+    /// Scrapes metrics from the specified URL.
+    pub async fn scrape(url: &str) -> anyhow::Result<Self> {
+        let response = reqwest::get(url)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("failed scraping node metrics from {url}"))?;
+        let text = response
+            .text()
+            .await
+            .context("failed reading node metrics")?;
+        Ok(Self::parse(&text))
+    }
+
+    fn parse(text: &str) -> Self {
+        let series = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(parse_series)
+            .collect();
+        Self { series }
+    }
+
+    /// Computes the report for metrics accumulated since the `earlier` snapshot.
+    pub fn report_since(&self, earlier: &Self) -> NodeMetricsReport {
+        let delta =
+            |key: &SeriesKey, value: f64| value - earlier.series.get(key).copied().unwrap_or(0.0);
+
+        let mut seal_reasons = BTreeMap::new();
+        let mut histograms = BTreeMap::<_, HistogramDelta>::new();
+        for (key, &value) in &self.series {
+            let (name, labels) = key;
+            if name == SEAL_REASONS_METRIC {
+                let resolution = label_value(labels, "seal_resolution");
+                // `no_seal` is reported for every executed transaction and isn't a seal reason.
+                if resolution != Some("no_seal") {
+                    let criterion = label_value(labels, "criterion").unwrap_or("unknown");
+                    *seal_reasons.entry(criterion.to_owned()).or_insert(0.0) += delta(key, value);
+                }
+                continue;
+            }
+
+            let (base_name, is_sum) = if let Some(base_name) = name.strip_suffix("_sum") {
+                (base_name, true)
+            } else if let Some(base_name) = name.strip_suffix("_count") {
+                (base_name, false)
+            } else {
+                continue;
+            };
+            if !VM_HISTOGRAMS
+                .iter()
+                .any(|&prefix| base_name.starts_with(prefix))
+            {
+                continue;
+            }
+            let entry = histograms
+                .entry((base_name.to_owned(), labels.clone()))
+                .or_default();
+            if is_sum {
+                entry.sum = delta(key, value);
+            } else {
+                entry.count = delta(key, value);
+            }
+        }
+
+        seal_reasons.retain(|_, count| *count > 0.0);
+        histograms.retain(|_, histogram| histogram.count > 0.0);
+        NodeMetricsReport {
+            seal_reasons,
+            histograms,
+        }
+    }
+}
+
+fn parse_series(line: &str) -> Option<(SeriesKey, f64)> {
+    let (name, labels, rest) = if let Some((name, rest)) = line.split_once('{') {
+        let (labels, rest) = rest.split_once('}')?;
+        (name, parse_labels(labels), rest)
+    } else {
+        let (name, rest) = line.split_once(char::is_whitespace)?;
+        (name, vec![], rest)
+    };
+    // The value may be followed by a timestamp, which is ignored.
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some(((name.to_owned(), labels), value))
+}
+
+fn parse_labels(labels: &str) -> Vec<(String, String)> {
+    let mut labels: Vec<_> = labels
+        .split(',')
+        .filter_map(|label| {
+            let (name, value) = label.split_once('=')?;
+            let value = value.trim().trim_matches('"');
+            Some((name.trim().to_owned(), value.to_owned()))
+        })
+        .collect();
+    labels.sort_unstable();
+    labels
+}
+
+fn label_value<'a>(labels: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    labels
+        .iter()
+        .find_map(|(label, value)| (label == name).then_some(value.as_str()))
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct HistogramDelta {
+    sum: f64,
+    count: f64,
+}
+
+/// Node metrics accumulated during the loadtest.
+#[derive(Debug)]
+pub struct NodeMetricsReport {
+    /// Number of times each seal criterion was triggered.
+    seal_reasons: BTreeMap<String, f64>,
+    histograms: BTreeMap<SeriesKey, HistogramDelta>,
+}
+
+impl NodeMetricsReport {
+    pub fn report(&self) {
+        tracing::info!("Node metrics accumulated during the loadtest:\n{self}");
+    }
+}
+
+impl fmt::Display for NodeMetricsReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "Seal reasons:")?;
+        if self.seal_reasons.is_empty() {
+            writeln!(formatter, "  (none)")?;
+        }
+        for (criterion, count) in &self.seal_reasons {
+            writeln!(formatter, "  {criterion}: {count}")?;
+        }
+
+        writeln!(formatter, "VM metrics (count / mean):")?;
+        for ((name, labels), histogram) in &self.histograms {
+            let labels: Vec<_> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}={value}"))
+                .collect();
+            let mean = histogram.sum / histogram.count;
+            writeln!(
+                formatter,
+                "  {name}{{{}}}: {} / {mean:.6}",
+                labels.join(","),
+                histogram.count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = r#"
+# HELP server_tx_aggregation_reason Number of times a seal criterion was triggered.
+# TYPE server_tx_aggregation_reason counter
+server_tx_aggregation_reason_total{criterion="slots",seal_resolution="no_seal"} 10
+server_tx_aggregation_reason_total{criterion="slots",seal_resolution="include_and_seal"} 1
+server_tx_aggregation_reason_total{criterion="no_txs_timeout"} 2
+server_state_keeper_tx_execution_time_seconds_sum{stage="execution"} 1.5
+server_state_keeper_tx_execution_time_seconds_count{stage="execution"} 10
+"#;
+
+    const AFTER: &str = r#"
+server_tx_aggregation_reason_total{criterion="slots",seal_resolution="no_seal"} 100
+server_tx_aggregation_reason_total{criterion="slots",seal_resolution="include_and_seal"} 4
+server_tx_aggregation_reason_total{criterion="no_txs_timeout"} 2
+server_tx_aggregation_reason_total{criterion="gas",seal_resolution="exclude_and_seal"} 1
+server_state_keeper_tx_execution_time_seconds_sum{stage="execution"} 3.5
+server_state_keeper_tx_execution_time_seconds_count{stage="execution"} 20
+server_state_keeper_tx_execution_time_seconds_bucket{stage="execution",le="0.1"} 20
+state_keeper_computational_gas_per_nanosecond_sum 0 1700000000
+unrelated_metric_sum 100
+"#;
+
+    #[test]
+    fn parsing_metrics() {
+        let snapshot = NodeMetricsSnapshot::parse(AFTER);
+        let key = (
+            "server_tx_aggregation_reason_total".to_owned(),
+            vec![
+                ("criterion".to_owned(), "slots".to_owned()),
+                ("seal_resolution".to_owned(), "include_and_seal".to_owned()),
+            ],
+        );
+        assert_eq!(snapshot.series[&key], 4.0);
+        let key = (
+            "state_keeper_computational_gas_per_nanosecond_sum".to_owned(),
+            vec![],
+        );
+        assert_eq!(snapshot.series[&key], 0.0);
+    }
+
+    #[test]
+    fn computing_report() {
+        let before = NodeMetricsSnapshot::parse(BEFORE);
+        let after = NodeMetricsSnapshot::parse(AFTER);
+        let report = after.report_since(&before);
+
+        let seal_reasons: Vec<_> = report
+            .seal_reasons
+            .iter()
+            .map(|(criterion, &count)| (criterion.as_str(), count))
+            .collect();
+        assert_eq!(seal_reasons, [("gas", 1.0), ("slots", 3.0)]);
+
+        assert_eq!(report.histograms.len(), 1);
+        let (key, histogram) = report.histograms.iter().next().unwrap();
+        assert_eq!(key.0, "server_state_keeper_tx_execution_time_seconds");
+        assert_eq!(
+            *histogram,
+            HistogramDelta {
+                sum: 2.0,
+                count: 10.0
+            }
+        );
+    }
+}
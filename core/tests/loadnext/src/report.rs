@@ -120,6 +120,7 @@ impl ReportLabel {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TxActionType {
     Withdraw,
+    Transfer,
     Deposit,
     DeployContract,
     Execute(ExecutionType),
@@ -129,6 +130,7 @@ impl All for TxActionType {
     fn all() -> &'static [Self] {
         const ALL: &[TxActionType] = &[
             TxActionType::Withdraw,
+            TxActionType::Transfer,
             TxActionType::Deposit,
             TxActionType::DeployContract,
             TxActionType::Execute(ExecutionType::L2),
@@ -144,6 +146,7 @@ impl From<TxType> for TxActionType {
         match command {
             TxType::Deposit => Self::Deposit,
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
+            TxType::Transfer => Self::Transfer,
             TxType::L2Execute => Self::Execute(ExecutionType::L2),
             TxType::L1Execute => Self::Execute(ExecutionType::L1),
             TxType::DeployContract => Self::DeployContract,
@@ -0,0 +1,317 @@
+//! Workload profiles for the loadtest.
+//!
+//! A workload is defined by the [`WorkloadMix`], i.e., weights of the transaction types generated by test accounts
+//! and params of the test contract execution. Together with the seed, the mix fully determines the sequence
+//! of operations performed by each account, so a run can be reproduced by re-using the profile and the seed.
+
+use std::{fs, path::Path};
+
+use anyhow::Context as _;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use zksync::{EthNamespaceClient, HttpClientBuilder};
+use zksync_contracts::test_contracts::LoadnextContractExecutionParams;
+use zksync_system_constants::CONTRACT_DEPLOYER_ADDRESS;
+use zksync_types::{
+    api::{self, BlockNumber, TransactionVariant},
+    ethabi, PRIORITY_OPERATION_L2_TX_TYPE,
+};
+
+use crate::config::{LoadtestConfig, TransactionWeights};
+
+/// Selectors of functions used to withdraw funds from L2.
+static WITHDRAWAL_SELECTORS: Lazy<[[u8; 4]; 2]> = Lazy::new(|| {
+    [
+        // `L2EthToken.withdraw(address)`
+        ethabi::short_signature("withdraw", &[ethabi::ParamType::Address]),
+        // `L2ERC20Bridge.withdraw(address,address,uint256)`
+        ethabi::short_signature(
+            "withdraw",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Uint(256),
+            ],
+        ),
+    ]
+});
+
+/// Selector of the ERC-20 `transfer(address,uint256)` function.
+static TRANSFER_SELECTOR: Lazy<[u8; 4]> = Lazy::new(|| {
+    ethabi::short_signature(
+        "transfer",
+        &[ethabi::ParamType::Address, ethabi::ParamType::Uint(256)],
+    )
+});
+
+/// Profile of the workload generated by the loadtest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadProfile {
+    /// Transaction weights and contract execution params are read from the environment.
+    #[default]
+    Custom,
+    /// Mostly ERC-20 transfers between test accounts.
+    TransferHeavy,
+    /// Mostly contract deployments, both directly and from the test contract.
+    DeployHeavy,
+    /// Mostly transactions producing a lot of pubdata (storage writes and L2-to-L1 messages).
+    PubdataHeavy,
+    /// Replays a transaction mix sampled from another node (e.g., a mainnet one).
+    Replay,
+}
+
+impl WorkloadProfile {
+    /// Returns the mix for a built-in profile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called for [`Self::Custom`] or [`Self::Replay`] profiles, which are not built-in.
+    pub fn builtin_mix(self) -> WorkloadMix {
+        let no_op_execution = LoadnextContractExecutionParams::empty();
+        match self {
+            Self::TransferHeavy => WorkloadMix {
+                transaction_weights: TransactionWeights {
+                    deposit: 0.0,
+                    withdrawal: 0.05,
+                    l1_transactions: 0.0,
+                    l2_transactions: 0.1,
+                    transfer: 1.0,
+                    deploy_contract: 0.0,
+                },
+                contract_execution_params: no_op_execution,
+            },
+            Self::DeployHeavy => WorkloadMix {
+                transaction_weights: TransactionWeights {
+                    deposit: 0.0,
+                    withdrawal: 0.0,
+                    l1_transactions: 0.0,
+                    l2_transactions: 0.5,
+                    transfer: 0.1,
+                    deploy_contract: 1.0,
+                },
+                contract_execution_params: LoadnextContractExecutionParams {
+                    deploys: 5,
+                    ..no_op_execution
+                },
+            },
+            Self::PubdataHeavy => WorkloadMix {
+                transaction_weights: TransactionWeights {
+                    deposit: 0.0,
+                    withdrawal: 0.3,
+                    l1_transactions: 0.0,
+                    l2_transactions: 1.0,
+                    transfer: 0.1,
+                    deploy_contract: 0.0,
+                },
+                contract_execution_params: LoadnextContractExecutionParams {
+                    writes: 100,
+                    events: 10,
+                    ..no_op_execution
+                },
+            },
+            Self::Custom | Self::Replay => {
+                panic!("{self:?} workload profile is not built-in");
+            }
+        }
+    }
+}
+
+/// Weights of transaction types and test contract execution params defining the workload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadMix {
+    pub transaction_weights: TransactionWeights,
+    pub contract_execution_params: LoadnextContractExecutionParams,
+}
+
+impl WorkloadMix {
+    /// Loads the mix for the `replay` profile from `replay_mix_path`, sampling and persisting it first
+    /// if the file doesn't exist.
+    pub(crate) async fn for_replay(config: &LoadtestConfig) -> anyhow::Result<Self> {
+        let path = config
+            .replay_mix_path
+            .as_deref()
+            .context("`replay_mix_path` must be set for `replay` workload profile")?;
+        if path.exists() {
+            return Self::load(path);
+        }
+
+        let source_rpc_address = config.replay_source_rpc_address.as_deref().with_context(|| {
+            format!(
+                "replay mix at {} doesn't exist, and `replay_source_rpc_address` to sample it from is not set",
+                path.display()
+            )
+        })?;
+        let mix = Self::sample(source_rpc_address, config.replay_sampled_miniblocks).await?;
+        let serialized = serde_json::to_string_pretty(&mix)?;
+        fs::write(path, serialized)
+            .with_context(|| format!("failed saving replay mix to {}", path.display()))?;
+        tracing::info!("Saved sampled replay mix to {}", path.display());
+        Ok(mix)
+    }
+
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let serialized = fs::read_to_string(path)
+            .with_context(|| format!("failed reading replay mix from {}", path.display()))?;
+        serde_json::from_str(&serialized)
+            .with_context(|| format!("failed parsing replay mix from {}", path.display()))
+    }
+
+    /// Samples the transaction mix from the latest `miniblock_count` miniblocks on the node
+    /// at `rpc_address`.
+    async fn sample(rpc_address: &str, miniblock_count: u32) -> anyhow::Result<Self> {
+        let client = HttpClientBuilder::default()
+            .build(rpc_address)
+            .context("failed creating source node client")?;
+        let last_miniblock = client
+            .get_block_number()
+            .await
+            .context("failed getting latest miniblock from source node")?
+            .as_u32();
+        let first_miniblock = last_miniblock.saturating_sub(miniblock_count.saturating_sub(1));
+        tracing::info!(
+            "Sampling replay mix from miniblocks {first_miniblock}..={last_miniblock} at {rpc_address}"
+        );
+
+        let mut counts = TransactionWeights {
+            deposit: 0.0,
+            withdrawal: 0.0,
+            l1_transactions: 0.0,
+            l2_transactions: 0.0,
+            transfer: 0.0,
+            deploy_contract: 0.0,
+        };
+        for number in first_miniblock..=last_miniblock {
+            let block = client
+                .get_block_by_number(BlockNumber::Number(number.into()), true)
+                .await
+                .with_context(|| format!("failed getting miniblock #{number} from source node"))?
+                .with_context(|| format!("miniblock #{number} is missing on source node"))?;
+            for tx in &block.transactions {
+                if let TransactionVariant::Full(tx) = tx {
+                    classify_transaction(tx, &mut counts);
+                }
+            }
+        }
+
+        let total = counts.deposit
+            + counts.withdrawal
+            + counts.l1_transactions
+            + counts.l2_transactions
+            + counts.transfer
+            + counts.deploy_contract;
+        anyhow::ensure!(
+            total > 0.0,
+            "no transactions in sampled miniblocks {first_miniblock}..={last_miniblock}"
+        );
+        Ok(Self {
+            transaction_weights: counts,
+            contract_execution_params: mainnet_execution_params(),
+        })
+    }
+}
+
+/// Params of an average contract call on the mainnet.
+fn mainnet_execution_params() -> LoadnextContractExecutionParams {
+    LoadnextContractExecutionParams {
+        reads: 6,
+        writes: 2,
+        events: 2,
+        hashes: 10,
+        recursive_calls: 0,
+        deploys: 0,
+    }
+}
+
+/// Classifies a transaction from the source node and increments the corresponding counter.
+fn classify_transaction(tx: &api::Transaction, counts: &mut TransactionWeights) {
+    let is_priority_op = tx.transaction_type == Some(PRIORITY_OPERATION_L2_TX_TYPE.into());
+    let selector = tx.input.0.get(..4);
+
+    let counter = if is_priority_op {
+        if tx.input.0.is_empty() {
+            &mut counts.deposit
+        } else {
+            &mut counts.l1_transactions
+        }
+    } else if tx.to == Some(CONTRACT_DEPLOYER_ADDRESS) {
+        &mut counts.deploy_contract
+    } else if tx.input.0.is_empty() || selector == Some(TRANSFER_SELECTOR.as_slice()) {
+        &mut counts.transfer
+    } else if selector.map_or(false, |selector| {
+        WITHDRAWAL_SELECTORS.iter().any(|s| s == selector)
+    }) {
+        &mut counts.withdrawal
+    } else {
+        &mut counts.l2_transactions
+    };
+    *counter += 1.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{web3::types::Bytes, Address, U256};
+
+    use super::*;
+
+    fn create_transaction(to: Address, input: Vec<u8>, tx_type: Option<u8>) -> api::Transaction {
+        api::Transaction {
+            to: Some(to),
+            input: Bytes(input),
+            transaction_type: tx_type.map(Into::into),
+            ..api::Transaction::default()
+        }
+    }
+
+    #[test]
+    fn classifying_transactions() {
+        let mut counts = TransactionWeights {
+            deposit: 0.0,
+            withdrawal: 0.0,
+            l1_transactions: 0.0,
+            l2_transactions: 0.0,
+            transfer: 0.0,
+            deploy_contract: 0.0,
+        };
+        let token = Address::repeat_byte(1);
+        let transfer_calldata = ethabi::encode(&[
+            ethabi::Token::Address(Address::repeat_byte(2)),
+            ethabi::Token::Uint(U256::one()),
+        ]);
+        let transfer_calldata = [TRANSFER_SELECTOR.as_slice(), &transfer_calldata].concat();
+        let withdrawal_calldata = WITHDRAWAL_SELECTORS[0].to_vec();
+
+        let txs = [
+            create_transaction(token, transfer_calldata, Some(2)),
+            create_transaction(token, vec![], None),
+            create_transaction(token, withdrawal_calldata, Some(2)),
+            create_transaction(CONTRACT_DEPLOYER_ADDRESS, vec![1, 2, 3, 4], Some(0x71)),
+            create_transaction(token, vec![4, 3, 2, 1], Some(2)),
+            create_transaction(token, vec![], Some(PRIORITY_OPERATION_L2_TX_TYPE)),
+            create_transaction(token, vec![1, 2, 3, 4], Some(PRIORITY_OPERATION_L2_TX_TYPE)),
+        ];
+        for tx in &txs {
+            classify_transaction(tx, &mut counts);
+        }
+
+        assert_eq!(
+            counts,
+            TransactionWeights {
+                deposit: 1.0,
+                withdrawal: 1.0,
+                l1_transactions: 1.0,
+                l2_transactions: 1.0,
+                transfer: 2.0,
+                deploy_contract: 1.0,
+            }
+        );
+    }
+
+    #[test]
+    fn replay_mix_roundtrip() {
+        let mix = WorkloadProfile::PubdataHeavy.builtin_mix();
+        let serialized = serde_json::to_string(&mix).unwrap();
+        let restored: WorkloadMix = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(restored, mix);
+    }
+}
@@ -18,7 +18,7 @@ use zksync_prover_fri_types::{
 };
 use zksync_queued_job_processor::JobProcessor;
 use zksync_types::{
-    aggregated_operations::L1BatchProofForL1,
+    aggregated_operations::{FflonkL1BatchProofForL1, L1BatchProof, L1BatchProofForL1},
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::{
             ZkSyncCircuit, ZkSyncProof, ZkSyncVerificationKey,
@@ -26,17 +26,35 @@ use zksync_types::{
         bellman::{bn256::Bn256, plonk::better_better_cs::proof::Proof},
         witness::oracle::VmWitnessOracle,
     },
-    L1BatchNumber,
+    L1BatchNumber, U256,
 };
 use zksync_vk_setup_data_server_fri::{get_recursive_layer_vk_for_circuit_type, get_snark_vk};
 
-use crate::metrics::METRICS;
+use crate::{fflonk::FflonkWrapperClient, metrics::METRICS};
+
+/// Backend used to wrap the final FRI proof into a SNARK that can be verified on L1.
+#[derive(Debug, Clone)]
+pub enum CompressionBackend {
+    /// In-process PLONK wrapper.
+    Plonk {
+        compression_mode: u8,
+        verify_wrapper_proof: bool,
+    },
+    /// FFLONK wrapper provided by an external wrapping service.
+    Fflonk(FflonkWrapperClient),
+}
+
+/// Proof compressed with one of the [`CompressionBackend`]s.
+#[derive(Debug)]
+pub enum CompressedProof {
+    Plonk(Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>),
+    Fflonk(Vec<U256>),
+}
 
 pub struct ProofCompressor {
     blob_store: Box<dyn ObjectStore>,
     pool: ConnectionPool,
-    compression_mode: u8,
-    verify_wrapper_proof: bool,
+    backend: CompressionBackend,
     max_attempts: u32,
 }
 
@@ -44,20 +62,18 @@ impl ProofCompressor {
     pub fn new(
         blob_store: Box<dyn ObjectStore>,
         pool: ConnectionPool,
-        compression_mode: u8,
-        verify_wrapper_proof: bool,
+        backend: CompressionBackend,
         max_attempts: u32,
     ) -> Self {
         Self {
             blob_store,
             pool,
-            compression_mode,
-            verify_wrapper_proof,
+            backend,
             max_attempts,
         }
     }
 
-    pub fn compress_proof(
+    fn compress_proof_with_plonk(
         proof: ZkSyncRecursionLayerProof,
         compression_mode: u8,
         verify_wrapper_proof: bool,
@@ -89,6 +105,15 @@ impl ProofCompressor {
         Ok(proof)
     }
 
+    async fn compress_proof_with_fflonk(
+        proof: ZkSyncRecursionLayerProof,
+        client: &FflonkWrapperClient,
+    ) -> anyhow::Result<Vec<U256>> {
+        let serialized_proof =
+            bincode::serialize(&proof).context("Failed to serialize scheduler proof")?;
+        client.wrap_proof(serialized_proof).await
+    }
+
     fn aux_output_witness_to_array(
         aux_output_witness: BlockAuxilaryOutputWitness<GoldilocksField>,
     ) -> [[u8; 32]; 4] {
@@ -108,7 +133,7 @@ impl ProofCompressor {
 impl JobProcessor for ProofCompressor {
     type Job = ZkSyncRecursionLayerProof;
     type JobId = L1BatchNumber;
-    type JobArtifacts = Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>;
+    type JobArtifacts = CompressedProof;
     const SERVICE_NAME: &'static str = "ProofCompressor";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
@@ -161,18 +186,27 @@ impl JobProcessor for ProofCompressor {
         job: ZkSyncRecursionLayerProof,
         _started_at: Instant,
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
-        let compression_mode = self.compression_mode;
-        let verify_wrapper_proof = self.verify_wrapper_proof;
-        tokio::task::spawn_blocking(move || {
-            Self::compress_proof(job, compression_mode, verify_wrapper_proof)
-        })
+        match self.backend.clone() {
+            CompressionBackend::Plonk {
+                compression_mode,
+                verify_wrapper_proof,
+            } => tokio::task::spawn_blocking(move || {
+                Self::compress_proof_with_plonk(job, compression_mode, verify_wrapper_proof)
+                    .map(CompressedProof::Plonk)
+            }),
+            CompressionBackend::Fflonk(client) => tokio::spawn(async move {
+                Self::compress_proof_with_fflonk(job, &client)
+                    .await
+                    .map(CompressedProof::Fflonk)
+            }),
+        }
     }
 
     async fn save_result(
         &self,
         job_id: Self::JobId,
         started_at: Instant,
-        artifacts: Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
+        artifacts: CompressedProof,
     ) -> anyhow::Result<()> {
         METRICS.compression_time.observe(started_at.elapsed());
         tracing::info!(
@@ -187,14 +221,20 @@ impl JobProcessor for ProofCompressor {
             .context("Failed to get aggregation result coords from blob store")?;
         let aggregation_result_coords =
             Self::aux_output_witness_to_array(aux_output_witness_wrapper.0);
-        let l1_batch_proof = L1BatchProofForL1 {
-            aggregation_result_coords,
-            scheduler_proof: artifacts,
+        let l1_batch_proof = match artifacts {
+            CompressedProof::Plonk(scheduler_proof) => L1BatchProof::Plonk(L1BatchProofForL1 {
+                aggregation_result_coords,
+                scheduler_proof,
+            }),
+            CompressedProof::Fflonk(proof) => L1BatchProof::Fflonk(FflonkL1BatchProofForL1 {
+                aggregation_result_coords,
+                proof,
+            }),
         };
         let blob_save_started_at = Instant::now();
         let blob_url = self
             .blob_store
-            .put(job_id, &l1_batch_proof)
+            .put_l1_batch_proof(job_id, &l1_batch_proof)
             .await
             .context("Failed to save converted l1_batch_proof")?;
         METRICS
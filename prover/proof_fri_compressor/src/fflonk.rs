@@ -0,0 +1,98 @@
+//! Client for the external FFLONK wrapping service.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use zksync_types::U256;
+
+/// Client for the external FFLONK wrapping service. The service accepts a bincode-serialized
+/// scheduler proof and responds with the bincode-serialized FFLONK proof words.
+#[derive(Debug, Clone)]
+pub struct FflonkWrapperClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl FflonkWrapperClient {
+    /// Creates a client that fails wrapping requests not completed within `timeout`.
+    pub fn new(url: String, timeout: Duration) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("failed building HTTP client for FFLONK wrapping service")?;
+        Ok(Self { client, url })
+    }
+
+    pub async fn wrap_proof(&self, serialized_proof: Vec<u8>) -> anyhow::Result<Vec<U256>> {
+        let url = &self.url;
+        let response = self
+            .client
+            .post(url)
+            .body(serialized_proof)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("FFLONK wrapping request to {url} failed"))?;
+        let serialized_fflonk_proof = response
+            .bytes()
+            .await
+            .context("Failed to read FFLONK wrapping response")?;
+        bincode::deserialize(&serialized_fflonk_proof)
+            .context("Failed to deserialize FFLONK proof returned by wrapping service")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Spawns a mock wrapping service that responds to a single request with `response` (or doesn't respond
+    /// at all if `response` is `None`). Returns the service URL.
+    async fn spawn_mock_service(response: Option<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0_u8; 1_024];
+            let _bytes_read = stream.read(&mut buffer).await.unwrap();
+            let Some(body) = response else {
+                // Keep the connection open without responding.
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                return;
+            };
+            let header = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).await.unwrap();
+            stream.write_all(&body).await.unwrap();
+        });
+        format!("http://{local_addr}/")
+    }
+
+    #[tokio::test]
+    async fn wrapping_proof() {
+        let expected_proof = vec![U256::one(), U256::from(42)];
+        let response = bincode::serialize(&expected_proof).unwrap();
+        let url = spawn_mock_service(Some(response)).await;
+        let client = FflonkWrapperClient::new(url, Duration::from_secs(10)).unwrap();
+
+        let proof = client.wrap_proof(vec![1, 2, 3]).await.unwrap();
+        assert_eq!(proof, expected_proof);
+    }
+
+    #[tokio::test]
+    async fn wrapping_proof_times_out() {
+        let url = spawn_mock_service(None).await;
+        let client = FflonkWrapperClient::new(url, Duration::from_millis(100)).unwrap();
+
+        let err = client.wrap_proof(vec![1, 2, 3]).await.unwrap_err();
+        let err = err.downcast::<reqwest::Error>().unwrap();
+        assert!(err.is_timeout(), "{err:?}");
+    }
+}
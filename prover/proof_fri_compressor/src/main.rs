@@ -9,11 +9,16 @@ use zksync_dal::ConnectionPool;
 use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_queued_job_processor::JobProcessor;
+use zksync_types::basic_fri_types::ProofCompressionBackend;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::compressor::ProofCompressor;
+use crate::{
+    compressor::{CompressionBackend, ProofCompressor},
+    fflonk::FflonkWrapperClient,
+};
 
 mod compressor;
+mod fflonk;
 mod metrics;
 
 #[derive(Debug, StructOpt)]
@@ -57,13 +62,24 @@ async fn main() -> anyhow::Result<()> {
     let blob_store = ObjectStoreFactory::new(object_store_config.0)
         .create_store()
         .await;
-    let proof_compressor = ProofCompressor::new(
-        blob_store,
-        pool,
-        config.compression_mode,
-        config.verify_wrapper_proof,
-        config.max_attempts,
+    let backend = match config.compression_backend {
+        ProofCompressionBackend::Plonk => CompressionBackend::Plonk {
+            compression_mode: config.compression_mode,
+            verify_wrapper_proof: config.verify_wrapper_proof,
+        },
+        ProofCompressionBackend::Fflonk => {
+            let wrapper_url = config.fflonk_wrapper_url.clone().context(
+                "`fflonk_wrapper_url` must be set to use the FFLONK compression backend",
+            )?;
+            let client = FflonkWrapperClient::new(wrapper_url, config.fflonk_wrapper_timeout())?;
+            CompressionBackend::Fflonk(client)
+        }
+    };
+    tracing::info!(
+        "Using {:?} proof compression backend",
+        config.compression_backend
     );
+    let proof_compressor = ProofCompressor::new(blob_store, pool, backend, config.max_attempts);
 
     let (stop_sender, stop_receiver) = watch::channel(false);
 
@@ -1,13 +1,11 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{de::DeserializeOwned, Serialize};
 use tokio::{sync::watch, time::sleep};
-use zksync_dal::ConnectionPool;
-use zksync_object_store::ObjectStore;
 
-use crate::metrics::METRICS;
+use crate::{clusters::ProverClusters, metrics::METRICS};
 
 /// The path to the API endpoint that returns the next proof generation data.
 pub(crate) const PROOF_GENERATION_DATA_PATH: &str = "/proof_generation_data";
@@ -16,8 +14,7 @@ pub(crate) const PROOF_GENERATION_DATA_PATH: &str = "/proof_generation_data";
 pub(crate) const SUBMIT_PROOF_PATH: &str = "/submit_proof";
 
 pub(crate) struct PeriodicApiStruct {
-    pub(crate) blob_store: Box<dyn ObjectStore>,
-    pub(crate) pool: ConnectionPool,
+    pub(crate) clusters: Arc<ProverClusters>,
     pub(crate) api_url: String,
     pub(crate) poll_duration: Duration,
     pub(crate) client: Client,
@@ -65,16 +62,11 @@ impl PeriodicApiStruct {
                 return Ok(());
             }
 
-            if let Some((job_id, request)) = self.get_next_request().await {
-                match self.send_request(job_id, request).await {
-                    Ok(response) => {
-                        self.handle_response(job_id, response).await;
-                    }
-                    Err(err) => {
-                        METRICS.http_error[&Self::SERVICE_NAME].inc();
-                        tracing::error!("HTTP request failed due to error: {}", err);
-                    }
-                }
+            if let Err(err) = self.poll_once().await {
+                // Such errors are usually caused by an inaccessible prover DB or object store,
+                // so we retry on the next poll.
+                METRICS.internal_error[&Self::SERVICE_NAME].inc();
+                tracing::error!("{} iteration failed: {err:#}", Self::SERVICE_NAME);
             }
             tokio::select! {
                 _ = stop_receiver.changed() => {
@@ -85,6 +77,24 @@ impl PeriodicApiStruct {
             }
         }
     }
+
+    async fn poll_once<Req>(&self) -> anyhow::Result<()>
+    where
+        Req: Send,
+        Self: PeriodicApi<Req>,
+    {
+        let Some((job_id, request)) = self.get_next_request().await? else {
+            return Ok(());
+        };
+        match self.send_request(job_id, request).await {
+            Ok(response) => self.handle_response(job_id, response).await?,
+            Err(err) => {
+                METRICS.http_error[&Self::SERVICE_NAME].inc();
+                tracing::error!("HTTP request failed due to error: {}", err);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Trait for fetching data from an API periodically.
@@ -96,7 +106,7 @@ pub(crate) trait PeriodicApi<Req: Send>: Sync + Send {
     const SERVICE_NAME: &'static str;
 
     /// Returns the next request to be sent to the API and the endpoint to send it to.
-    async fn get_next_request(&self) -> Option<(Self::JobId, Req)>;
+    async fn get_next_request(&self) -> anyhow::Result<Option<(Self::JobId, Req)>>;
 
     /// Handles the response from the API.
     async fn send_request(
@@ -105,5 +115,9 @@ pub(crate) trait PeriodicApi<Req: Send>: Sync + Send {
         request: Req,
     ) -> reqwest::Result<Self::Response>;
 
-    async fn handle_response(&self, job_id: Self::JobId, response: Self::Response);
+    async fn handle_response(
+        &self,
+        job_id: Self::JobId,
+        response: Self::Response,
+    ) -> anyhow::Result<()>;
 }
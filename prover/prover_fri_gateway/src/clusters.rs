@@ -0,0 +1,215 @@
+//! Independent prover clusters (each with its own prover DB and object store) that the gateway
+//! distributes jobs to.
+
+use std::{sync::Mutex, time::Duration};
+
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStore;
+
+use crate::metrics::METRICS;
+
+#[derive(Debug)]
+pub(crate) struct ProverCluster {
+    pub(crate) name: String,
+    weight: u32,
+    pub(crate) pool: ConnectionPool,
+    pub(crate) blob_store: Box<dyn ObjectStore>,
+}
+
+impl ProverCluster {
+    pub(crate) fn new(
+        name: String,
+        weight: u32,
+        pool: ConnectionPool,
+        blob_store: Box<dyn ObjectStore>,
+    ) -> Self {
+        assert!(
+            weight > 0,
+            "Weight of prover cluster `{name}` must be positive"
+        );
+        Self {
+            name,
+            weight,
+            pool,
+            blob_store,
+        }
+    }
+
+    /// Checks whether the cluster picks up jobs. A cluster is unhealthy if its prover DB is inaccessible,
+    /// or if the oldest queued witness generation job is older than `stall_timeout`.
+    async fn is_healthy(&self, stall_timeout: Duration) -> bool {
+        let mut storage = match self.pool.access_storage().await {
+            Ok(storage) => storage,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed accessing DB of prover cluster `{}`: {err}",
+                    self.name
+                );
+                return false;
+            }
+        };
+        let oldest_job_age = storage
+            .fri_witness_generator_dal()
+            .get_oldest_queued_basic_witness_job_age()
+            .await;
+        match oldest_job_age {
+            Ok(Some(age)) if age > stall_timeout => {
+                tracing::warn!(
+                    "Prover cluster `{}` didn't pick up queued witness generation jobs for {age:?}",
+                    self.name
+                );
+                false
+            }
+            Ok(_) => true,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed getting queued jobs for prover cluster `{}`: {err}",
+                    self.name
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Prover clusters together with the state of distributing jobs among them.
+///
+/// New jobs are distributed among healthy clusters proportionally to cluster weights using smooth weighted
+/// round-robin. If a cluster stops picking up jobs, it doesn't receive new jobs until it recovers.
+#[derive(Debug)]
+pub(crate) struct ProverClusters {
+    clusters: Vec<ProverCluster>,
+    stall_timeout: Duration,
+    current_weights: Mutex<Vec<i64>>,
+}
+
+impl ProverClusters {
+    pub(crate) fn new(clusters: Vec<ProverCluster>, stall_timeout: Duration) -> Self {
+        assert!(
+            !clusters.is_empty(),
+            "At least one prover cluster is required"
+        );
+        let current_weights = Mutex::new(vec![0; clusters.len()]);
+        Self {
+            clusters,
+            stall_timeout,
+            current_weights,
+        }
+    }
+
+    pub(crate) fn get(&self, idx: usize) -> &ProverCluster {
+        &self.clusters[idx]
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &ProverCluster> + '_ {
+        self.clusters.iter()
+    }
+
+    /// Selects the cluster to assign a new job to. If all clusters are unhealthy, jobs are distributed
+    /// among all clusters, so that proving doesn't stop completely.
+    pub(crate) async fn select_cluster(&self) -> &ProverCluster {
+        let mut eligible = Vec::with_capacity(self.clusters.len());
+        for cluster in &self.clusters {
+            let is_healthy = cluster.is_healthy(self.stall_timeout).await;
+            METRICS.cluster_healthy[&cluster.name].set(is_healthy.into());
+            eligible.push(is_healthy);
+        }
+        if !eligible.contains(&true) {
+            tracing::warn!(
+                "All prover clusters are unhealthy; distributing jobs among all clusters"
+            );
+            eligible.fill(true);
+        }
+
+        let cluster = &self.clusters[self.next_cluster_index(&eligible)];
+        METRICS.cluster_assigned_jobs[&cluster.name].inc();
+        cluster
+    }
+
+    fn next_cluster_index(&self, eligible: &[bool]) -> usize {
+        let mut current_weights = self.current_weights.lock().unwrap();
+        let weights = self.clusters.iter().map(|cluster| cluster.weight);
+        select_weighted(weights, &mut current_weights, eligible)
+    }
+}
+
+/// Single step of smooth weighted round-robin among `eligible` items with the specified `weights`.
+fn select_weighted(
+    weights: impl Iterator<Item = u32>,
+    current_weights: &mut [i64],
+    eligible: &[bool],
+) -> usize {
+    let mut total_weight = 0;
+    let mut selected_idx = None;
+    for (idx, weight) in weights.enumerate() {
+        if !eligible[idx] {
+            continue;
+        }
+        let weight = i64::from(weight);
+        current_weights[idx] += weight;
+        total_weight += weight;
+        if selected_idx.map_or(true, |selected| {
+            current_weights[idx] > current_weights[selected]
+        }) {
+            selected_idx = Some(idx);
+        }
+    }
+    let selected_idx = selected_idx.expect("no eligible prover clusters");
+    current_weights[selected_idx] -= total_weight;
+    selected_idx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select_many(weights: &[u32], eligible: &[bool], count: usize) -> Vec<usize> {
+        let mut current_weights = vec![0; weights.len()];
+        (0..count)
+            .map(|_| select_weighted(weights.iter().copied(), &mut current_weights, eligible))
+            .collect()
+    }
+
+    #[test]
+    fn weighted_round_robin_is_smooth() {
+        let selected = select_many(&[5, 1, 1], &[true; 3], 7);
+        assert_eq!(selected, [0, 0, 1, 0, 2, 0, 0]);
+
+        let selected = select_many(&[5, 1, 1], &[true; 3], 70);
+        for idx in 0..3 {
+            let expected_count = if idx == 0 { 50 } else { 10 };
+            let count = selected.iter().filter(|&&selected| selected == idx).count();
+            assert_eq!(count, expected_count, "{selected:?}");
+        }
+    }
+
+    #[test]
+    fn weighted_round_robin_skips_ineligible_clusters() {
+        let selected = select_many(&[2, 1, 1], &[true, false, true], 6);
+        assert_eq!(selected, [0, 2, 0, 0, 2, 0]);
+
+        let selected = select_many(&[2, 1, 1], &[false, true, false], 5);
+        assert_eq!(selected, [1; 5]);
+    }
+
+    #[test]
+    fn weighted_round_robin_adapts_to_eligibility_changes() {
+        let weights = [1, 1];
+        let mut current_weights = vec![0; 2];
+        let select = |current_weights: &mut [i64], eligible: &[bool]| {
+            select_weighted(weights.iter().copied(), current_weights, eligible)
+        };
+
+        assert_eq!(select(&mut current_weights, &[true, true]), 0);
+        assert_eq!(select(&mut current_weights, &[true, false]), 0);
+        assert_eq!(select(&mut current_weights, &[true, true]), 1);
+        assert_eq!(select(&mut current_weights, &[true, true]), 0);
+        assert_eq!(select(&mut current_weights, &[true, true]), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no eligible prover clusters")]
+    fn weighted_round_robin_panics_without_eligible_clusters() {
+        select_many(&[1, 1], &[false, false], 1);
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
 use reqwest::Client;
@@ -9,9 +11,13 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_types::prover_server_api::{ProofGenerationDataRequest, SubmitProofRequest};
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH};
+use crate::{
+    api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH},
+    clusters::{ProverCluster, ProverClusters},
+};
 
 mod api_data_fetcher;
+mod clusters;
 mod metrics;
 mod proof_gen_data_fetcher;
 mod proof_submitter;
@@ -48,16 +54,53 @@ async fn main() -> anyhow::Result<()> {
         ProverObjectStoreConfig::from_env().context("ProverObjectStoreConfig::from_env()")?;
     let store_factory = ObjectStoreFactory::new(object_store_config.0);
 
+    let mut clusters = vec![ProverCluster::new(
+        "primary".to_owned(),
+        config.primary_cluster_weight(),
+        pool,
+        store_factory.create_store().await,
+    )];
+    for cluster_config in &config.additional_clusters {
+        let pool = ConnectionPool::builder(
+            &cluster_config.prover_url,
+            postgres_config.max_connections()?,
+        )
+        .build()
+        .await
+        .with_context(|| {
+            format!(
+                "failed to build a connection pool for prover cluster `{}`",
+                cluster_config.name
+            )
+        })?;
+        let store_factory = ObjectStoreFactory::new(cluster_config.object_store.clone());
+        clusters.push(ProverCluster::new(
+            cluster_config.name.clone(),
+            cluster_config.weight(),
+            pool,
+            store_factory.create_store().await,
+        ));
+    }
+    tracing::info!(
+        "Distributing jobs among prover clusters: {:?}",
+        clusters
+            .iter()
+            .map(|cluster| &cluster.name)
+            .collect::<Vec<_>>()
+    );
+    let clusters = Arc::new(ProverClusters::new(
+        clusters,
+        config.cluster_stall_timeout(),
+    ));
+
     let proof_submitter = PeriodicApiStruct {
-        blob_store: store_factory.create_store().await,
-        pool: pool.clone(),
+        clusters: clusters.clone(),
         api_url: format!("{}{SUBMIT_PROOF_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
     };
     let proof_gen_data_fetcher = PeriodicApiStruct {
-        blob_store: store_factory.create_store().await,
-        pool,
+        clusters,
         api_url: format!("{}{PROOF_GENERATION_DATA_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
@@ -1,10 +1,19 @@
-use vise::{Counter, LabeledFamily, Metrics};
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "prover_fri_prover_fri_gateway")]
 pub(crate) struct ProverFriGatewayMetrics {
     #[metrics(labels = ["service_name"])]
     pub http_error: LabeledFamily<&'static str, Counter>,
+    /// Number of failed iterations caused by non-HTTP errors (e.g., prover DB or object store errors).
+    #[metrics(labels = ["service_name"])]
+    pub internal_error: LabeledFamily<&'static str, Counter>,
+    /// Whether a prover cluster picks up jobs (1) or not (0).
+    #[metrics(labels = ["cluster"])]
+    pub cluster_healthy: LabeledFamily<String, Gauge>,
+    /// Number of jobs assigned to a prover cluster.
+    #[metrics(labels = ["cluster"])]
+    pub cluster_assigned_jobs: LabeledFamily<String, Counter>,
 }
 
 #[vise::register]
@@ -1,3 +1,4 @@
+use anyhow::Context as _;
 use async_trait::async_trait;
 use zksync_types::prover_server_api::{
     ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
@@ -6,13 +7,22 @@ use zksync_types::prover_server_api::{
 use crate::api_data_fetcher::{PeriodicApi, PeriodicApiStruct};
 
 impl PeriodicApiStruct {
-    async fn save_proof_gen_data(&self, data: ProofGenerationData) {
-        let store = &*self.blob_store;
-        let blob_url = store
+    async fn save_proof_gen_data(&self, data: ProofGenerationData) -> anyhow::Result<()> {
+        let cluster = self.clusters.select_cluster().await;
+        tracing::info!(
+            "Assigning proof generation for L1 batch {} to prover cluster `{}`",
+            data.l1_batch_number,
+            cluster.name
+        );
+        let blob_url = cluster
+            .blob_store
             .put(data.l1_batch_number, &data.data)
             .await
-            .expect("Failed to save proof generation data to GCS");
-        let mut connection = self.pool.access_storage().await.unwrap();
+            .context("failed to save proof generation data to blob store")?;
+        let mut connection =
+            cluster.pool.access_storage().await.with_context(|| {
+                format!("failed accessing storage for cluster `{}`", cluster.name)
+            })?;
         connection
             .fri_protocol_versions_dal()
             .save_prover_protocol_version(data.fri_protocol_version_id, data.l1_verifier_config)
@@ -23,8 +33,10 @@ impl PeriodicApiStruct {
                 data.l1_batch_number,
                 &blob_url,
                 data.fri_protocol_version_id,
+                data.is_high_priority,
             )
             .await;
+        Ok(())
     }
 }
 
@@ -35,8 +47,10 @@ impl PeriodicApi<ProofGenerationDataRequest> for PeriodicApiStruct {
 
     const SERVICE_NAME: &'static str = "ProofGenDataFetcher";
 
-    async fn get_next_request(&self) -> Option<(Self::JobId, ProofGenerationDataRequest)> {
-        Some(((), ProofGenerationDataRequest {}))
+    async fn get_next_request(
+        &self,
+    ) -> anyhow::Result<Option<(Self::JobId, ProofGenerationDataRequest)>> {
+        Ok(Some(((), ProofGenerationDataRequest {})))
     }
 
     async fn send_request(
@@ -47,18 +61,23 @@ impl PeriodicApi<ProofGenerationDataRequest> for PeriodicApiStruct {
         self.send_http_request(request, &self.api_url).await
     }
 
-    async fn handle_response(&self, _: (), response: Self::Response) {
+    async fn handle_response(&self, _: (), response: Self::Response) -> anyhow::Result<()> {
         match response {
             ProofGenerationDataResponse::Success(None) => {
                 tracing::info!("There are currently no pending batches to be proven");
             }
             ProofGenerationDataResponse::Success(Some(data)) => {
-                tracing::info!("Received proof gen data for: {:?}", data.l1_batch_number);
-                self.save_proof_gen_data(data).await;
+                tracing::info!(
+                    "Received proof gen data for: {:?} (high priority: {})",
+                    data.l1_batch_number,
+                    data.is_high_priority
+                );
+                self.save_proof_gen_data(data).await?;
             }
             ProofGenerationDataResponse::Error(err) => {
                 tracing::error!("Failed to get proof gen data: {:?}", err);
             }
         }
+        Ok(())
     }
 }
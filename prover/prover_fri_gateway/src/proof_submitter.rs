@@ -1,3 +1,4 @@
+use anyhow::Context as _;
 use async_trait::async_trait;
 use zksync_dal::fri_proof_compressor_dal::ProofCompressionJobStatus;
 use zksync_types::{
@@ -7,69 +8,107 @@ use zksync_types::{
 
 use crate::api_data_fetcher::{PeriodicApi, PeriodicApiStruct};
 
+/// Identifies a proof to be submitted: the index of the prover cluster that generated the proof
+/// and the L1 batch number.
+type ProofId = (usize, L1BatchNumber);
+
 impl PeriodicApiStruct {
-    async fn next_submit_proof_request(&self) -> Option<(L1BatchNumber, SubmitProofRequest)> {
-        let (l1_batch_number, status) = self
-            .pool
-            .access_storage()
-            .await
-            .unwrap()
-            .fri_proof_compressor_dal()
-            .get_least_proven_block_number_not_sent_to_server()
-            .await?;
+    async fn next_submit_proof_request(
+        &self,
+    ) -> anyhow::Result<Option<(ProofId, SubmitProofRequest)>> {
+        let mut next_proof = None;
+        for (cluster_idx, cluster) in self.clusters.iter().enumerate() {
+            let proof = cluster
+                .pool
+                .access_storage()
+                .await
+                .with_context(|| {
+                    format!("failed accessing storage for cluster `{}`", cluster.name)
+                })?
+                .fri_proof_compressor_dal()
+                .get_least_proven_block_number_not_sent_to_server()
+                .await;
+            let Some((l1_batch_number, status)) = proof else {
+                continue;
+            };
+            let is_least_batch = next_proof
+                .as_ref()
+                .map_or(true, |(_, _, number, _)| l1_batch_number < *number);
+            if is_least_batch {
+                next_proof = Some((cluster_idx, cluster, l1_batch_number, status));
+            }
+        }
+        let Some((cluster_idx, cluster, l1_batch_number, status)) = next_proof else {
+            return Ok(None);
+        };
 
         let request = match status {
             ProofCompressionJobStatus::Successful => {
-                let proof = self
+                let proof = cluster
                     .blob_store
-                    .get(l1_batch_number)
+                    .get_l1_batch_proof(l1_batch_number)
                     .await
-                    .expect("Failed to get compressed snark proof from blob store");
-                SubmitProofRequest::Proof(Box::new(proof))
+                    .context("failed to get compressed snark proof from blob store")?
+                    .with_context(|| {
+                        format!(
+                            "compressed snark proof for L1 batch #{l1_batch_number} is missing \
+                             in blob store"
+                        )
+                    })?;
+                proof.into()
             }
             ProofCompressionJobStatus::Skipped => SubmitProofRequest::SkippedProofGeneration,
-            _ => panic!(
-                "Trying to send proof that are not successful status: {:?}",
-                status
+            _ => anyhow::bail!(
+                "trying to send proof for L1 batch #{l1_batch_number} with unexpected status: \
+                 {status:?}"
             ),
         };
 
-        Some((l1_batch_number, request))
+        Ok(Some(((cluster_idx, l1_batch_number), request)))
     }
 
-    async fn save_successful_sent_proof(&self, l1_batch_number: L1BatchNumber) {
-        self.pool
+    async fn save_successful_sent_proof(
+        &self,
+        (cluster_idx, l1_batch_number): ProofId,
+    ) -> anyhow::Result<()> {
+        let cluster = self.clusters.get(cluster_idx);
+        cluster
+            .pool
             .access_storage()
             .await
-            .unwrap()
+            .with_context(|| format!("failed accessing storage for cluster `{}`", cluster.name))?
             .fri_proof_compressor_dal()
             .mark_proof_sent_to_server(l1_batch_number)
             .await;
+        Ok(())
     }
 }
 
 #[async_trait]
 impl PeriodicApi<SubmitProofRequest> for PeriodicApiStruct {
-    type JobId = L1BatchNumber;
+    type JobId = ProofId;
     type Response = SubmitProofResponse;
     const SERVICE_NAME: &'static str = "ProofSubmitter";
 
-    async fn get_next_request(&self) -> Option<(Self::JobId, SubmitProofRequest)> {
-        let (l1_batch_number, request) = self.next_submit_proof_request().await?;
-        Some((l1_batch_number, request))
+    async fn get_next_request(&self) -> anyhow::Result<Option<(Self::JobId, SubmitProofRequest)>> {
+        self.next_submit_proof_request().await
     }
 
     async fn send_request(
         &self,
-        job_id: Self::JobId,
+        (_, l1_batch_number): Self::JobId,
         request: SubmitProofRequest,
     ) -> reqwest::Result<Self::Response> {
-        let endpoint = format!("{}/{job_id}", self.api_url);
+        let endpoint = format!("{}/{l1_batch_number}", self.api_url);
         self.send_http_request(request, &endpoint).await
     }
 
-    async fn handle_response(&self, job_id: L1BatchNumber, response: Self::Response) {
+    async fn handle_response(
+        &self,
+        job_id: Self::JobId,
+        response: Self::Response,
+    ) -> anyhow::Result<()> {
         tracing::info!("Received response: {:?}", response);
-        self.save_successful_sent_proof(job_id).await;
+        self.save_successful_sent_proof(job_id).await
     }
 }
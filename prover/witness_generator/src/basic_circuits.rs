@@ -629,6 +629,9 @@ async fn generate_witness(
     }
 
     // The following part is CPU-heavy, so we move it to a separate thread.
+    // Note that `run_with_fixed_params()` materializes storage and memory queues for all basic circuits
+    // of the batch at once, so peak memory usage is proportional to the batch size. Only Merkle paths
+    // are consumed lazily (see `PrecalculatedMerklePathsProvider`).
     let rt_handle = tokio::runtime::Handle::current();
 
     let (
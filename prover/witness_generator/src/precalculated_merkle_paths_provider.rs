@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use serde::{Deserialize, Serialize};
 use zk_evm::blake2::Blake2s256;
 use zkevm_test_harness::witness::tree::{
@@ -9,8 +11,11 @@ use zksync_types::proofs::{PrepareBasicCircuitsJob, StorageLogMetadata};
 pub struct PrecalculatedMerklePathsProvider {
     // We keep the root hash of the last processed leaf, as it is needed by the the witness generator.
     pub root_hash: [u8; 32],
-    // The ordered list of expected leaves to be interacted with
-    pub pending_leaves: Vec<StorageLogMetadata>,
+    // The ordered queue of expected leaves to be interacted with. Merkle paths are kept compressed
+    // (see `PrepareBasicCircuitsJob`) and are decompressed one at a time, which bounds memory usage for large blocks.
+    pub pending_leaves: VecDeque<StorageLogMetadata>,
+    // Full Merkle path of the first leaf in the block used to decompress Merkle paths of pending leaves
+    pub first_merkle_path: Vec<[u8; 32]>,
     // The index that would be assigned to the next new leaf
     pub next_enumeration_index: u64,
     // For every Storage Write Log we expect two invocations: `get_leaf` and `insert_leaf`.
@@ -22,13 +27,23 @@ impl PrecalculatedMerklePathsProvider {
     pub fn new(input: PrepareBasicCircuitsJob, root_hash: [u8; 32]) -> Self {
         let next_enumeration_index = input.next_enumeration_index();
         tracing::debug!("Initializing PrecalculatedMerklePathsProvider. Initial root_hash: {:?}, initial next_enumeration_index: {:?}", root_hash, next_enumeration_index);
+        let (first_merkle_path, pending_leaves) = input.into_compressed_merkle_paths();
         Self {
             root_hash,
-            pending_leaves: input.into_merkle_paths().collect(),
+            pending_leaves: pending_leaves.into(),
+            first_merkle_path,
             next_enumeration_index,
             is_get_leaf_invoked: false,
         }
     }
+
+    fn decompressed_merkle_path(
+        first_merkle_path: &[[u8; 32]],
+        mut leaf: StorageLogMetadata,
+    ) -> Box<[[u8; 32]; 256]> {
+        PrepareBasicCircuitsJob::decompress_merkle_path(first_merkle_path, &mut leaf);
+        leaf.into_merkle_paths_array()
+    }
 }
 
 impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
@@ -63,7 +78,7 @@ impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
             !self.is_get_leaf_invoked,
             "`get_leaf()` invoked more than once or get_leaf is invoked when insert_leaf was expected"
         );
-        let next = self.pending_leaves.first().unwrap_or_else(|| {
+        let next = self.pending_leaves.front().unwrap_or_else(|| {
             panic!(
                 "invoked `get_leaf({:?})` with empty `pending_leaves`",
                 index
@@ -84,7 +99,7 @@ impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
             },
             first_write: next.first_write,
             index: *index,
-            merkle_path: next.clone().into_merkle_paths_array(),
+            merkle_path: Self::decompressed_merkle_path(&self.first_merkle_path, next.clone()),
         };
 
         if next.is_write {
@@ -95,7 +110,7 @@ impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
             }
         } else {
             // If it is a read, the next invocation will relate to the next `pending_leaf`
-            self.pending_leaves.remove(0);
+            self.pending_leaves.pop_front();
         };
 
         res
@@ -117,7 +132,12 @@ impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
             self.is_get_leaf_invoked,
             "`get_leaf()` is expected to be invoked before `insert_leaf()`"
         );
-        let next = self.pending_leaves.remove(0);
+        let next = self.pending_leaves.pop_front().unwrap_or_else(|| {
+            panic!(
+                "invoked `insert_leaf({:?})` with empty `pending_leaves`",
+                index
+            )
+        });
         self.root_hash = next.root_hash;
 
         assert!(
@@ -152,7 +172,7 @@ impl BinarySparseStorageTree<256, 32, 32, 8, 32, Blake2s256, ZkSyncStorageLeaf>
             },
             first_write: next.first_write,
             index: *index,
-            merkle_path: next.into_merkle_paths_array(),
+            merkle_path: Self::decompressed_merkle_path(&self.first_merkle_path, next),
         }
     }
 
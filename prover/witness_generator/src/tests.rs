@@ -221,3 +221,39 @@ fn initializing_provider_with_compacted_merkle_paths() {
         .all(|hash| *hash == [0; 32]));
     assert_ne!(query.merkle_path[255], [0; 32]);
 }
+
+#[test]
+fn provider_decompresses_merkle_paths_lazily() {
+    let mut job = PrepareBasicCircuitsJob::new(4);
+    for (mut log, merkle_path) in LOGS_AND_PATHS {
+        // All paths share the first 255 hashes, so all paths except for the first one are compressed
+        // to a single hash.
+        log.merkle_paths = iter::repeat([0; 32])
+            .take(255)
+            .chain([merkle_path])
+            .collect();
+        job.push_merkle_path(log);
+    }
+    let mut provider = PrecalculatedMerklePathsProvider::new(job, [0_u8; 32]);
+    assert_eq!(provider.first_merkle_path.len(), 256);
+    assert_eq!(provider.pending_leaves[0].merkle_paths.len(), 256);
+    assert_eq!(provider.pending_leaves[1].merkle_paths.len(), 1);
+    assert_eq!(provider.pending_leaves[2].merkle_paths.len(), 1);
+
+    for (_, merkle_path) in &LOGS_AND_PATHS[..2] {
+        let query = provider.get_leaf(&[0; 32]);
+        assert!(query.merkle_path[0..255]
+            .iter()
+            .all(|hash| *hash == [0; 32]));
+        assert_eq!(query.merkle_path[255], *merkle_path);
+    }
+    let query = provider.insert_leaf(
+        &[0; 32],
+        ZkSyncStorageLeaf {
+            index: 2,
+            value: [0; 32],
+        },
+    );
+    assert_eq!(query.merkle_path[255], LOGS_AND_PATHS[1].1);
+    assert_eq!(provider.pending_leaves.len(), 1);
+}